@@ -0,0 +1,145 @@
+use three_d::*;
+
+pub fn main() {
+    let window = Window::new(WindowSettings {
+        title: "Annotations!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl();
+
+    let mut camera = Camera::new_perspective(
+        window.viewport(),
+        vec3(4.0, 3.0, 8.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        1000.0,
+    );
+    let mut control = OrbitControl::new(camera.target(), 1.0, 100.0);
+    let mut gui = three_d::GUI::new(&context);
+
+    let mut sphere = Gm::new(
+        Mesh::new(&context, &CpuMesh::sphere(32)),
+        PhysicalMaterial::new_opaque(
+            &context,
+            &CpuMaterial {
+                albedo: Srgba::new(200, 180, 140, 255),
+                ..Default::default()
+            },
+        ),
+    );
+    sphere.set_transformation(Mat4::from_translation(vec3(-1.2, 0.0, 0.0)));
+    let mut cube = Gm::new(
+        Mesh::new(&context, &CpuMesh::cube()),
+        PhysicalMaterial::new_opaque(
+            &context,
+            &CpuMaterial {
+                albedo: Srgba::new(120, 160, 200, 255),
+                ..Default::default()
+            },
+        ),
+    );
+    cube.set_transformation(
+        Mat4::from_translation(vec3(1.2, 0.0, 0.0)) * Mat4::from_angle_y(degrees(30.0)),
+    );
+
+    let ambient = AmbientLight::new(&context, 0.4, Srgba::WHITE);
+    let directional = DirectionalLight::new(&context, 2.0, Srgba::WHITE, vec3(-1.0, -1.0, -1.0));
+
+    let mut annotations = Annotations::new();
+    let first = annotations.add(
+        vec3(-1.2, 1.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        "Right click on a surface to add a note, double click a note to edit it",
+        Srgba::RED,
+    );
+    annotations.select(Some(first));
+    let mut saved = annotations.to_json();
+
+    window.render_loop(move |mut frame_input| {
+        camera.set_viewport(frame_input.viewport);
+
+        annotations
+            .update(
+                &context,
+                &camera,
+                frame_input.accumulated_time,
+                sphere.into_iter().chain(&cube),
+            )
+            .unwrap();
+
+        let mut changed = false;
+        gui.update(
+            &mut frame_input.events,
+            frame_input.accumulated_time,
+            frame_input.viewport,
+            frame_input.device_pixel_ratio,
+            |gui_context| {
+                use three_d::egui::*;
+                changed |= annotations.ui(gui_context, &camera);
+                Window::new("Annotations").show(gui_context, |ui| {
+                    ui.label(format!("Annotations: {}", annotations.len()));
+                    ui.label(format!("Hovered: {:?}", annotations.hovered()));
+                    ui.label(format!("Selected: {:?}", annotations.selected()));
+                    ui.horizontal(|ui| {
+                        if ui.button("Save").clicked() {
+                            saved = annotations.to_json();
+                        }
+                        if ui.button("Load").clicked() {
+                            annotations = Annotations::from_json(&saved).unwrap();
+                        }
+                        if ui.button("Clear").clicked() {
+                            annotations.clear();
+                        }
+                    });
+                    ScrollArea::vertical().max_height(200.0).show(ui, |ui| {
+                        ui.add(Label::new(RichText::new(&saved).monospace()).wrap());
+                    });
+                });
+            },
+        );
+        if changed {
+            saved = annotations.to_json();
+        }
+
+        for event in frame_input.events.iter() {
+            if let Event::MousePress {
+                button: MouseButton::Right,
+                position,
+                handled: false,
+                ..
+            } = *event
+            {
+                if let Some((position, normal)) = Annotations::pick_anchor(
+                    &context,
+                    &camera,
+                    position,
+                    sphere.into_iter().chain(&cube),
+                )
+                .unwrap()
+                {
+                    let id = annotations.add(position, normal, "", Srgba::new(0, 120, 255, 255));
+                    annotations.edit(id);
+                }
+            }
+        }
+
+        control.handle_events(&mut camera, &mut frame_input.events);
+
+        frame_input
+            .screen()
+            .clear(ClearState::color_and_depth(0.8, 0.8, 0.8, 1.0, 1.0))
+            .render(
+                &camera,
+                sphere.into_iter().chain(&cube),
+                &[&ambient, &directional],
+            )
+            .write(|| gui.render())
+            .unwrap();
+
+        FrameOutput::default()
+    });
+}
@@ -0,0 +1,182 @@
+use three_d::*;
+
+#[derive(Clone, Copy, PartialEq)]
+enum AntiAliasing {
+    None,
+    Fxaa(FxaaQuality),
+    Taa,
+}
+
+pub fn main() {
+    let window = Window::new(WindowSettings {
+        title: "Anti-aliasing!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl();
+
+    let mut camera = Camera::new_perspective(
+        window.viewport(),
+        vec3(6.0, 3.0, 6.0),
+        vec3(0.0, 0.5, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        100.0,
+    );
+    let mut control = OrbitControl::new(camera.target(), 2.0, 50.0);
+    let mut gui = three_d::GUI::new(&context);
+
+    let material = PhysicalMaterial::new_opaque(
+        &context,
+        &CpuMaterial {
+            albedo: Srgba::new_opaque(220, 220, 220),
+            metallic: 0.6,
+            roughness: 0.2,
+            ..Default::default()
+        },
+    );
+    let mut cube = Gm::new(Mesh::new(&context, &CpuMesh::cube()), material.clone());
+
+    // Thin wires which are less than a pixel wide at a distance and therefore flicker without anti-aliasing
+    let wires = (0..20)
+        .map(|i| {
+            let mut wire = Gm::new(Mesh::new(&context, &CpuMesh::cylinder(8)), material.clone());
+            wire.set_transformation(
+                Mat4::from_translation(vec3(-5.0, 0.05, -5.0 + 0.5 * i as f32))
+                    * Mat4::from_nonuniform_scale(10.0, 0.01, 0.01),
+            );
+            wire
+        })
+        .collect::<Vec<_>>();
+
+    let mut ground = Gm::new(
+        Mesh::new(&context, &CpuMesh::square()),
+        PhysicalMaterial::new_opaque(
+            &context,
+            &CpuMaterial {
+                albedo: Srgba::new_opaque(40, 40, 50),
+                ..Default::default()
+            },
+        ),
+    );
+    ground.set_transformation(Mat4::from_angle_x(degrees(-90.0)) * Mat4::from_scale(10.0));
+
+    let ambient = AmbientLight::new(&context, 0.3, Srgba::WHITE);
+    let directional = DirectionalLight::new(&context, 2.0, Srgba::WHITE, vec3(-1.0, -1.0, -0.5));
+
+    let mut anti_aliasing = AntiAliasing::Fxaa(FxaaQuality::Medium);
+    let mut taa = TaaEffect::new(&context);
+
+    window.render_loop(move |mut frame_input| {
+        gui.update(
+            &mut frame_input.events,
+            frame_input.accumulated_time,
+            frame_input.viewport,
+            frame_input.device_pixel_ratio,
+            |gui_context| {
+                use three_d::egui::*;
+                Window::new("Anti-aliasing").show(gui_context, |ui| {
+                    ui.radio_value(&mut anti_aliasing, AntiAliasing::None, "None");
+                    ui.radio_value(
+                        &mut anti_aliasing,
+                        AntiAliasing::Fxaa(FxaaQuality::Low),
+                        "FXAA low",
+                    );
+                    ui.radio_value(
+                        &mut anti_aliasing,
+                        AntiAliasing::Fxaa(FxaaQuality::Medium),
+                        "FXAA medium",
+                    );
+                    ui.radio_value(
+                        &mut anti_aliasing,
+                        AntiAliasing::Fxaa(FxaaQuality::High),
+                        "FXAA high",
+                    );
+                    ui.radio_value(&mut anti_aliasing, AntiAliasing::Taa, "TAA");
+                    if anti_aliasing == AntiAliasing::Taa {
+                        ui.add(Slider::new(&mut taa.blend_factor, 0.02..=1.0).text("Blend factor"));
+                    }
+                });
+            },
+        );
+
+        camera.set_viewport(frame_input.viewport);
+        control.handle_events(&mut camera, &mut frame_input.events);
+        cube.set_transformation(
+            Mat4::from_translation(vec3(0.0, 1.0, 0.0))
+                * Mat4::from_angle_y(radians(0.0005 * frame_input.accumulated_time as f32)),
+        );
+        let (width, height) = (frame_input.viewport.width, frame_input.viewport.height);
+
+        if anti_aliasing == AntiAliasing::Taa {
+            taa.jitter_camera(&mut camera);
+        } else {
+            camera.set_jitter(vec2(0.0, 0.0));
+            taa.reset();
+        }
+
+        // Render the scene into a texture without multisampling and without tone and color mapping
+        let mut color_texture =
+            context.request_texture::<[f16; 4]>(width, height, Interpolation::Nearest);
+        let mut depth_texture = context.request_depth_texture::<f32>(width, height);
+        camera.disable_tone_and_color_mapping();
+        RenderTarget::new(
+            color_texture.as_color_target(None),
+            depth_texture.as_depth_target(),
+        )
+        .clear(ClearState::color_and_depth(0.6, 0.7, 0.9, 1.0, 1.0))
+        .render(
+            &camera,
+            cube.into_iter()
+                .chain(wires.iter().flatten())
+                .chain(&ground),
+            &[&ambient, &directional],
+        );
+        camera.set_default_tone_and_color_mapping();
+
+        let screen = frame_input.screen();
+        match anti_aliasing {
+            AntiAliasing::None => {
+                screen.apply_screen_effect(
+                    &ScreenEffect::default(),
+                    &camera,
+                    &[],
+                    Some(ColorTexture::Single(&color_texture)),
+                    None,
+                );
+            }
+            AntiAliasing::Fxaa(quality) => {
+                // FXAA finds the edges from the perceived brightness, so apply the tone and color mapping first
+                let mut mapped_texture =
+                    context.request_texture::<[u8; 4]>(width, height, Interpolation::Linear);
+                mapped_texture.as_color_target(None).apply_screen_effect(
+                    &ScreenEffect::default(),
+                    &camera,
+                    &[],
+                    Some(ColorTexture::Single(&color_texture)),
+                    None,
+                );
+                screen.apply_screen_effect(
+                    &FxaaEffect { quality },
+                    &camera,
+                    &[],
+                    Some(ColorTexture::Single(&mapped_texture)),
+                    None,
+                );
+            }
+            AntiAliasing::Taa => {
+                taa.update(
+                    &camera,
+                    ColorTexture::Single(&color_texture),
+                    Some(DepthTexture::Single(&depth_texture)),
+                );
+                screen.apply_screen_effect(&taa, &camera, &[], None, None);
+            }
+        }
+        screen.write(|| gui.render()).unwrap();
+
+        FrameOutput::default()
+    });
+}
@@ -0,0 +1,180 @@
+// Entry point for non-wasm
+#[cfg(not(target_arch = "wasm32"))]
+#[tokio::main]
+async fn main() {
+    run().await;
+}
+
+use three_d::*;
+
+pub async fn run() {
+    let window = Window::new(WindowSettings {
+        title: "Area light!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl();
+
+    let mut camera = Camera::new_perspective(
+        window.viewport(),
+        vec3(0.0, 4.0, 9.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        100.0,
+    );
+    let mut control = OrbitControl::new(camera.target(), 1.0, 100.0);
+    let mut gui = three_d::GUI::new(&context);
+
+    // A glossy floor and a few spheres with varying roughness
+    let mut plane = Gm::new(
+        Mesh::new(&context, &CpuMesh::square()),
+        PhysicalMaterial::new_opaque(
+            &context,
+            &CpuMaterial {
+                albedo: Srgba::new_opaque(60, 60, 70),
+                metallic: 0.0,
+                roughness: 0.2,
+                ..Default::default()
+            },
+        ),
+    );
+    plane.set_transformation(Mat4::from_angle_x(degrees(-90.0)) * Mat4::from_scale(6.0));
+    let mut spheres = (0..3)
+        .map(|i| {
+            let mut sphere = Gm::new(
+                Mesh::new(&context, &CpuMesh::sphere(32)),
+                PhysicalMaterial::new_opaque(
+                    &context,
+                    &CpuMaterial {
+                        albedo: Srgba::new_opaque(220, 200, 180),
+                        metallic: 0.8,
+                        roughness: 0.15 + 0.35 * i as f32,
+                        ..Default::default()
+                    },
+                ),
+            );
+            sphere.set_transformation(
+                Mat4::from_translation(vec3(-2.5 + 2.5 * i as f32, 0.7, 0.0))
+                    * Mat4::from_scale(0.7),
+            );
+            sphere
+        })
+        .collect::<Vec<_>>();
+
+    let ambient = AmbientLight::new(&context, 0.05, Srgba::WHITE);
+    let mut light = AreaLight::new(
+        &context,
+        3.0,
+        Srgba::new_opaque(255, 240, 220),
+        vec3(0.0, 2.0, -2.0),
+        vec3(0.0, -0.5, 1.0),
+        vec3(0.0, 1.0, 0.0),
+        3.0,
+        1.0,
+    );
+    // The visible surface of the light
+    let mut emitter = Gm::new(
+        Mesh::new(&context, &CpuMesh::square()),
+        ColorMaterial::default(),
+    );
+
+    let mut lighting_model = LightingModel::Cook(
+        NormalDistributionFunction::TrowbridgeReitzGGX,
+        GeometryFunction::SmithSchlickGGX,
+    );
+    let mut roughness = 0.2;
+    let mut width = 3.0;
+    let mut height = 1.0;
+    let mut intensity = 3.0;
+    let mut animate = true;
+    let mut time = 0.0;
+    window.render_loop(move |mut frame_input| {
+        let mut panel_width = 0.0;
+        gui.update(
+            &mut frame_input.events,
+            frame_input.accumulated_time,
+            frame_input.viewport,
+            frame_input.device_pixel_ratio,
+            |gui_context| {
+                use three_d::egui::*;
+                SidePanel::left("side_panel").show(gui_context, |ui| {
+                    ui.heading("Area light");
+                    ui.checkbox(&mut animate, "Animate");
+                    ui.add(Slider::new(&mut intensity, 0.0..=10.0).text("Intensity"));
+                    ui.add(Slider::new(&mut width, 0.1..=6.0).text("Width"));
+                    ui.add(Slider::new(&mut height, 0.1..=6.0).text("Height"));
+                    ui.add(Slider::new(&mut roughness, 0.0..=1.0).text("Floor roughness"));
+
+                    ui.label("Lighting model");
+                    ui.radio_value(&mut lighting_model, LightingModel::Phong, "Phong");
+                    ui.radio_value(&mut lighting_model, LightingModel::Blinn, "Blinn");
+                    ui.radio_value(
+                        &mut lighting_model,
+                        LightingModel::Cook(
+                            NormalDistributionFunction::Beckmann,
+                            GeometryFunction::SmithSchlickGGX,
+                        ),
+                        "Cook (Beckmann)",
+                    );
+                    ui.radio_value(
+                        &mut lighting_model,
+                        LightingModel::Cook(
+                            NormalDistributionFunction::TrowbridgeReitzGGX,
+                            GeometryFunction::SmithSchlickGGX,
+                        ),
+                        "Cook (Trowbridge-Reitz GGX)",
+                    );
+                });
+                panel_width = gui_context.used_rect().width();
+            },
+        );
+
+        // Move the light back and forth above the floor
+        if animate {
+            time += 0.001 * frame_input.elapsed_time;
+        }
+        light.set_position(vec3(3.0 * (0.5 * time).sin() as f32, 2.0, -2.0));
+        light.set_size(width, height);
+        light.set_intensity(intensity);
+
+        plane.material.roughness = roughness;
+        plane.material.lighting_model = lighting_model;
+        for sphere in spheres.iter_mut() {
+            sphere.material.lighting_model = lighting_model;
+        }
+
+        let right = light.normal().cross(light.up());
+        emitter.set_transformation(
+            Mat4::from_translation(light.position())
+                * Mat4::from_cols(
+                    (right * 0.5 * width).extend(0.0),
+                    (light.up() * 0.5 * height).extend(0.0),
+                    light.normal().extend(0.0),
+                    vec4(0.0, 0.0, 0.0, 1.0),
+                ),
+        );
+        emitter.material.color = light.color();
+
+        let viewport = frame_input.viewport_from_logical(
+            panel_width,
+            0.0,
+            frame_input.window_width as f32 - panel_width,
+            frame_input.window_height as f32,
+        );
+        camera.set_viewport(viewport);
+        control.handle_events(&mut camera, &mut frame_input.events);
+
+        frame_input
+            .screen()
+            .clear(ClearState::color_and_depth(0.02, 0.02, 0.03, 1.0, 1.0))
+            .render(&camera, spheres.iter().chain([&plane]), &[&ambient, &light])
+            .render(&camera, &emitter, &[])
+            .write(|| gui.render())
+            .unwrap();
+
+        FrameOutput::default()
+    });
+}
@@ -0,0 +1,147 @@
+use three_d::*;
+
+// A sphere which pulses with the current values of the signals
+struct PulseMaterial<'a> {
+    signals: &'a UniformSignals,
+}
+
+impl Material for PulseMaterial<'_> {
+    fn fragment_shader_source(&self, _lights: &[&dyn Light]) -> String {
+        include_str!("pulse.frag").to_string()
+    }
+
+    fn use_uniforms(&self, program: &Program, _viewer: &dyn Viewer, _lights: &[&dyn Light]) {
+        self.signals.use_uniforms(program);
+    }
+
+    fn render_states(&self) -> RenderStates {
+        RenderStates::default()
+    }
+
+    fn material_type(&self) -> MaterialType {
+        MaterialType::Opaque
+    }
+
+    fn id(&self) -> EffectMaterialId {
+        EffectMaterialId(0b1u16)
+    }
+}
+
+// A panel which draws the history of the signals as waveforms
+struct WaveformMaterial<'a> {
+    signals: &'a UniformSignals,
+}
+
+impl Material for WaveformMaterial<'_> {
+    fn fragment_shader_source(&self, _lights: &[&dyn Light]) -> String {
+        include_str!("waveform.frag").to_string()
+    }
+
+    fn use_uniforms(&self, program: &Program, _viewer: &dyn Viewer, _lights: &[&dyn Light]) {
+        self.signals.use_uniforms(program);
+    }
+
+    fn render_states(&self) -> RenderStates {
+        RenderStates::default()
+    }
+
+    fn material_type(&self) -> MaterialType {
+        MaterialType::Opaque
+    }
+
+    fn id(&self) -> EffectMaterialId {
+        EffectMaterialId(0b10u16)
+    }
+}
+
+pub fn main() {
+    let window = Window::new(WindowSettings {
+        title: "Audio signals!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl();
+
+    let mut camera = Camera::new_perspective(
+        window.viewport(),
+        vec3(0.0, 0.0, 9.0),
+        vec3(0.0, -0.8, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        100.0,
+    );
+    let mut control = OrbitControl::new(camera.target(), 4.0, 30.0);
+    let mut gui = three_d::GUI::new(&context);
+
+    // The signals are available in both shaders using `#include <signals>`
+    let names = ["bass", "mid", "treble"];
+    let mut signals = UniformSignals::new(&context, &names);
+    signals.register_snippet("signals");
+
+    let mut sphere = Mesh::new(&context, &CpuMesh::sphere(32));
+    let mut panel = Mesh::new(&context, &CpuMesh::square());
+    panel.set_transformation(
+        Mat4::from_translation(vec3(0.0, -2.6, 0.0)) * Mat4::from_nonuniform_scale(3.5, 0.8, 1.0),
+    );
+
+    let mut attack = 20.0;
+    let mut decay = 250.0;
+    let mut time = 0.0;
+    window.render_loop(move |mut frame_input| {
+        gui.update(
+            &mut frame_input.events,
+            frame_input.accumulated_time,
+            frame_input.viewport,
+            frame_input.device_pixel_ratio,
+            |gui_context| {
+                use three_d::egui::*;
+                Window::new("Envelope").show(gui_context, |ui| {
+                    ui.add(Slider::new(&mut attack, 0.0..=500.0).text("Attack (ms)"));
+                    ui.add(Slider::new(&mut decay, 0.0..=2000.0).text("Decay (ms)"));
+                });
+            },
+        );
+        camera.set_viewport(frame_input.viewport);
+        control.handle_events(&mut camera, &mut frame_input.events);
+
+        // Synthetic levels instead of an FFT of real audio: a kick drum at 120 beats per minute,
+        // a slowly varying mid range and hi-hats on some of the eighth notes
+        time += frame_input.elapsed_time;
+        let beat = (time % 500.0) / 500.0;
+        let eighth = (time / 250.0) as u32;
+        signals.set("bass", (-8.0 * beat as f32).exp());
+        signals.set(
+            "mid",
+            0.5 + 0.4 * (0.003 * time).sin() as f32 * (0.0011 * time).sin() as f32,
+        );
+        signals.set(
+            "treble",
+            if (eighth * 7 + 3) % 5 < 2 {
+                (-20.0 * ((time % 250.0) / 250.0) as f32).exp()
+            } else {
+                0.0
+            },
+        );
+        for name in names {
+            signals.set_envelope(name, SignalEnvelope { attack, decay });
+        }
+        signals.update(frame_input.elapsed_time);
+
+        // The smoothed values are also available on the CPU
+        sphere.set_transformation(Mat4::from_scale(1.0 + 0.3 * signals.value("bass")));
+
+        frame_input
+            .screen()
+            .clear(ClearState::color_and_depth(0.0, 0.0, 0.0, 1.0, 1.0))
+            .write(|| {
+                sphere.render_with_material(&PulseMaterial { signals: &signals }, &camera, &[]);
+                panel.render_with_material(&WaveformMaterial { signals: &signals }, &camera, &[]);
+                gui.render()
+            })
+            .unwrap();
+
+        FrameOutput::default()
+    });
+}
@@ -0,0 +1,15 @@
+#include <signals>
+
+in vec3 nor;
+
+layout (location = 0) out vec4 outColor;
+
+void main()
+{
+    vec3 normal = normalize(nor);
+    float diffuse = 0.35 + 0.65 * max(dot(normal, normalize(vec3(1.0, 1.0, 0.8))), 0.0);
+    vec3 color = vec3(0.1, 0.3, 1.0) * (0.3 + signal_mid)
+        + vec3(1.0, 0.15, 0.3) * signal_bass
+        + vec3(1.0, 1.0, 0.6) * signal_treble * pow(1.0 - abs(normal.y), 4.0);
+    outColor = vec4(diffuse * color, 1.0);
+}
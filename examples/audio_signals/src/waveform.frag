@@ -0,0 +1,23 @@
+#include <signals>
+
+in vec2 uvs;
+
+layout (location = 0) out vec4 outColor;
+
+// The coverage of a line at the given height in the panel, with a width of about two pixels
+float line(float height)
+{
+    float distance = uvs.y - height;
+    return 1.0 - smoothstep(1.0, 2.0, abs(distance) / max(fwidth(distance), 1e-5));
+}
+
+void main()
+{
+    // The newest values are to the right
+    float age = 1.0 - uvs.x;
+    vec3 color = vec3(0.05);
+    color = mix(color, vec3(1.0, 0.15, 0.3), line(signal_bass_history(age)));
+    color = mix(color, vec3(0.2, 0.5, 1.0), line(signal_mid_history(age)));
+    color = mix(color, vec3(1.0, 1.0, 0.6), line(signal_treble_history(age)));
+    outColor = vec4(color, 1.0);
+}
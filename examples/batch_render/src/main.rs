@@ -0,0 +1,143 @@
+use three_d::*;
+
+// The per frame overrides of the scene
+struct Overrides {
+    light_direction: Vec3,
+}
+
+struct Scene {
+    sphere: Gm<Mesh, PhysicalMaterial>,
+    cubes: Gm<InstancedMesh, PhysicalMaterial>,
+    ambient: AmbientLight,
+    directional: DirectionalLight,
+}
+
+impl BatchScene for Scene {
+    type Overrides = Overrides;
+
+    fn prepare(&mut self, _frame_index: usize, overrides: &Overrides, random: &mut BatchRandom) {
+        self.directional.direction = overrides.light_direction;
+        // Randomize the color of the sphere using the seeded random number generator
+        self.sphere.material.albedo = Srgba::new(
+            random.range(50.0, 255.0) as u8,
+            random.range(50.0, 255.0) as u8,
+            random.range(50.0, 255.0) as u8,
+            255,
+        );
+    }
+
+    fn objects(&self) -> Vec<&dyn Object> {
+        vec![&self.sphere, &self.cubes]
+    }
+
+    fn lights(&self) -> Vec<&dyn Light> {
+        vec![&self.ambient, &self.directional]
+    }
+}
+
+fn main() {
+    // Create a headless graphics context
+    let context = HeadlessContext::new().unwrap();
+
+    let mut scene = Scene {
+        sphere: Gm::new(
+            Mesh::new(&context, &CpuMesh::sphere(32)),
+            PhysicalMaterial::new_opaque(&context, &CpuMaterial::default()),
+        ),
+        cubes: Gm::new(
+            InstancedMesh::new(
+                &context,
+                &Instances {
+                    transformations: (0..8)
+                        .map(|i| {
+                            let angle = degrees(45.0 * i as f32);
+                            Mat4::from_angle_y(angle)
+                                * Mat4::from_translation(vec3(2.5, 0.0, 0.0))
+                                * Mat4::from_scale(0.4)
+                        })
+                        .collect(),
+                    ..Default::default()
+                },
+                &CpuMesh::cube(),
+            ),
+            PhysicalMaterial::new_opaque(
+                &context,
+                &CpuMaterial {
+                    albedo: Srgba::new(120, 160, 200, 255),
+                    ..Default::default()
+                },
+            ),
+        ),
+        ambient: AmbientLight::new(&context, 0.3, Srgba::WHITE),
+        directional: DirectionalLight::new(&context, 2.0, Srgba::WHITE, vec3(-1.0, -1.0, -1.0)),
+    };
+
+    // The projection used for all frames, the view is given by the camera pose of each frame
+    let camera = Camera::new_perspective(
+        Viewport::new_at_origo(1, 1),
+        vec3(0.0, 0.0, 1.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        100.0,
+    );
+
+    // A list of random camera poses and light directions
+    let frames = || {
+        let mut random = BatchRandom::new(1, 0);
+        (0..4)
+            .map(|_| {
+                let angle = random.range(0.0, std::f32::consts::TAU);
+                let height = random.range(1.0, 4.0);
+                BatchFrame {
+                    pose: CameraPose {
+                        position: vec3(7.0 * angle.cos(), height, 7.0 * angle.sin()),
+                        target: vec3(0.0, 0.0, 0.0),
+                        up: vec3(0.0, 1.0, 0.0),
+                    },
+                    overrides: Overrides {
+                        light_direction: vec3(
+                            random.range(-1.0, 1.0),
+                            -1.0,
+                            random.range(-1.0, 1.0),
+                        ),
+                    },
+                }
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let mut batch_renderer = BatchRenderer::new(
+        &context,
+        BatchSettings {
+            width: 640,
+            height: 480,
+            passes: BatchPasses::ALL,
+            seed: 42,
+            samples: 8,
+            dither: true,
+            clear_color: Srgba::new(200, 200, 200, 255),
+        },
+    );
+
+    // Render all frames, save them to disk and store the checksums
+    let mut checksums = Vec::new();
+    batch_renderer
+        .render(&mut scene, &camera, frames(), |output| {
+            checksums.push(output.checksum());
+            output.save(".")
+        })
+        .unwrap();
+
+    // Render the frames again and verify that the pixels are identical
+    let mut frame_index = 0;
+    batch_renderer
+        .render(&mut scene, &camera, frames(), |output| {
+            assert_eq!(output.checksum(), checksums[frame_index]);
+            frame_index += 1;
+            Ok(())
+        })
+        .unwrap();
+    println!("Rendered {} identical frames twice", checksums.len());
+}
@@ -0,0 +1,209 @@
+use three_d::*;
+
+pub fn main() {
+    let window = Window::new(WindowSettings {
+        title: "Bloom!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl();
+
+    let mut camera = Camera::new_perspective(
+        window.viewport(),
+        vec3(0.0, 3.0, 9.0),
+        vec3(0.0, 0.5, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        100.0,
+    );
+    let mut control = OrbitControl::new(camera.target(), 2.0, 50.0);
+    let mut gui = three_d::GUI::new(&context);
+
+    let mut ground = Gm::new(
+        Mesh::new(&context, &CpuMesh::square()),
+        PhysicalMaterial::new_opaque(
+            &context,
+            &CpuMaterial {
+                albedo: Srgba::new_opaque(80, 80, 90),
+                roughness: 0.4,
+                ..Default::default()
+            },
+        ),
+    );
+    ground.set_transformation(Mat4::from_angle_x(degrees(-90.0)) * Mat4::from_scale(10.0));
+
+    // Emissive spheres where the emissive color is multiplied with a high dynamic range texture, so the emitted light is brighter than 1
+    let spheres = [
+        (vec3(-2.5, 0.6, 0.0), [8.0, 1.5, 0.5]),
+        (vec3(0.0, 0.6, 0.0), [1.0, 6.0, 2.0]),
+        (vec3(2.5, 0.6, 0.0), [1.0, 2.0, 12.0]),
+        (vec3(0.0, 0.3, 2.0), [0.8, 0.8, 0.8]),
+    ]
+    .map(|(position, emissive)| {
+        let mut sphere = Gm::new(
+            Mesh::new(&context, &CpuMesh::sphere(32)),
+            PhysicalMaterial::new_opaque(
+                &context,
+                &CpuMaterial {
+                    albedo: Srgba::BLACK,
+                    emissive: Srgba::WHITE,
+                    emissive_texture: Some(CpuTexture {
+                        data: TextureData::RgbF32(vec![emissive]),
+                        width: 1,
+                        height: 1,
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                },
+            ),
+        );
+        sphere.set_transformation(Mat4::from_translation(position) * Mat4::from_scale(0.6));
+        sphere
+    });
+
+    let ambient = AmbientLight::new(&context, 0.3, Srgba::WHITE);
+    let directional = DirectionalLight::new(&context, 1.0, Srgba::WHITE, vec3(-1.0, -2.0, -0.5));
+
+    let mut bloom = BloomEffect::new(&context);
+    let lens_dirt = lens_dirt_texture(&context);
+    let starburst = starburst_texture(&context);
+    let mut use_lens_dirt = false;
+    let mut use_starburst = false;
+
+    window.render_loop(move |mut frame_input| {
+        gui.update(
+            &mut frame_input.events,
+            frame_input.accumulated_time,
+            frame_input.viewport,
+            frame_input.device_pixel_ratio,
+            |gui_context| {
+                use three_d::egui::*;
+                Window::new("Bloom").show(gui_context, |ui| {
+                    ui.add(Slider::new(&mut bloom.threshold, 0.0..=4.0).text("Threshold"));
+                    ui.add(Slider::new(&mut bloom.knee, 0.0..=1.0).text("Knee"));
+                    ui.add(Slider::new(&mut bloom.intensity, 0.0..=4.0).text("Intensity"));
+                    ui.add(Slider::new(&mut bloom.radius, 0.5..=3.0).text("Radius"));
+                    ui.checkbox(&mut use_lens_dirt, "Lens dirt");
+                    ui.add(
+                        Slider::new(&mut bloom.lens_dirt_intensity, 0.0..=10.0)
+                            .text("Lens dirt intensity"),
+                    );
+                    ui.checkbox(&mut use_starburst, "Starburst");
+                    ui.add(
+                        Slider::new(&mut bloom.starburst_intensity, 0.0..=10.0)
+                            .text("Starburst intensity"),
+                    );
+                });
+            },
+        );
+
+        bloom.lens_dirt = use_lens_dirt.then(|| lens_dirt.clone());
+        bloom.starburst = use_starburst.then(|| starburst.clone());
+        camera.set_viewport(frame_input.viewport);
+        control.handle_events(&mut camera, &mut frame_input.events);
+        let (width, height) = (frame_input.viewport.width, frame_input.viewport.height);
+
+        // Render the scene with high dynamic range colors and without tone and color mapping
+        let mut color_texture =
+            context.request_texture::<[f16; 4]>(width, height, Interpolation::Nearest);
+        let mut depth_texture = context.request_depth_texture::<f32>(width, height);
+        camera.disable_tone_and_color_mapping();
+        RenderTarget::new(
+            color_texture.as_color_target(None),
+            depth_texture.as_depth_target(),
+        )
+        .clear(ClearState::color_and_depth(0.02, 0.02, 0.04, 1.0, 1.0))
+        .render(
+            &camera,
+            ground.into_iter().chain(spheres.iter().flatten()),
+            &[&ambient, &directional],
+        );
+
+        // Add the bloom and apply tone and color mapping when writing to the screen
+        bloom.update(ColorTexture::Single(&color_texture));
+        camera.set_default_tone_and_color_mapping();
+        frame_input
+            .screen()
+            .apply_screen_effect(
+                &bloom,
+                &camera,
+                &[],
+                Some(ColorTexture::Single(&color_texture)),
+                None,
+            )
+            .write(|| gui.render())
+            .unwrap();
+
+        FrameOutput::default()
+    });
+}
+
+///
+/// Creates a lens dirt texture with soft smudges of different sizes at pseudo-random positions.
+///
+fn lens_dirt_texture(context: &Context) -> Texture2DRef {
+    const WIDTH: u32 = 320;
+    const HEIGHT: u32 = 180;
+    let hash = |i: u32| ((i as f32 * 12.9898).sin() * 43758.547).fract().abs();
+    let smudges = (0..40)
+        .map(|i| {
+            (
+                vec2(hash(3 * i) * WIDTH as f32, hash(3 * i + 1) * HEIGHT as f32),
+                4.0 + 20.0 * hash(3 * i + 2).powi(2),
+            )
+        })
+        .collect::<Vec<_>>();
+    let data = (0..WIDTH * HEIGHT)
+        .map(|i| {
+            let p = vec2((i % WIDTH) as f32, (i / WIDTH) as f32);
+            let value = smudges
+                .iter()
+                .map(|(center, radius)| (1.0 - p.distance(*center) / radius).max(0.0).sqrt())
+                .sum::<f32>()
+                .min(1.0);
+            [value * 0.8, value * 0.9, value]
+        })
+        .collect();
+    Texture2DRef::from_cpu_texture(
+        context,
+        &CpuTexture {
+            data: TextureData::RgbF32(data),
+            width: WIDTH,
+            height: HEIGHT,
+            wrap_s: Wrapping::ClampToEdge,
+            wrap_t: Wrapping::ClampToEdge,
+            ..Default::default()
+        },
+    )
+}
+
+///
+/// Creates a starburst texture with thin rays from the center.
+///
+fn starburst_texture(context: &Context) -> Texture2DRef {
+    const SIZE: u32 = 256;
+    let data = (0..SIZE * SIZE)
+        .map(|i| {
+            let x = 2.0 * ((i % SIZE) as f32 + 0.5) / SIZE as f32 - 1.0;
+            let y = 2.0 * ((i / SIZE) as f32 + 0.5) / SIZE as f32 - 1.0;
+            let angle = y.atan2(x);
+            let rays =
+                (6.0 * angle).cos().abs().powi(40) + 0.5 * (17.0 * angle).cos().abs().powi(60);
+            let value = rays * (1.0 - (x * x + y * y).sqrt()).max(0.0);
+            [value, value, value]
+        })
+        .collect();
+    Texture2DRef::from_cpu_texture(
+        context,
+        &CpuTexture {
+            data: TextureData::RgbF32(data),
+            width: SIZE,
+            height: SIZE,
+            wrap_s: Wrapping::ClampToEdge,
+            wrap_t: Wrapping::ClampToEdge,
+            ..Default::default()
+        },
+    )
+}
@@ -0,0 +1,172 @@
+// Entry point for non-wasm
+#[cfg(not(target_arch = "wasm32"))]
+#[tokio::main]
+async fn main() {
+    run().await;
+}
+
+use three_d::*;
+
+pub async fn run() {
+    let window = Window::new(WindowSettings {
+        title: "Cascaded shadows!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl();
+
+    let mut camera = Camera::new_perspective(
+        window.viewport(),
+        vec3(0.0, 2.0, -190.0),
+        vec3(0.0, 1.5, -180.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(60.0),
+        0.1,
+        400.0,
+    );
+    let mut control = FlyControl::new(0.05);
+
+    // A long road with a fence on each side
+    let length = 400.0;
+    let mut ground = Gm::new(
+        Mesh::new(&context, &CpuMesh::square()),
+        PhysicalMaterial::new_opaque(
+            &context,
+            &CpuMaterial {
+                albedo: Srgba::new_opaque(120, 150, 90),
+                ..Default::default()
+            },
+        ),
+    );
+    ground.set_transformation(
+        Mat4::from_angle_x(degrees(-90.0)) * Mat4::from_nonuniform_scale(20.0, 0.5 * length, 1.0),
+    );
+    let mut fence_parts = Vec::new();
+    for side in [-4.0, 4.0] {
+        let mut z = -0.5 * length;
+        while z <= 0.5 * length {
+            // Post
+            fence_parts.push(
+                Mat4::from_translation(vec3(side, 0.6, z))
+                    * Mat4::from_nonuniform_scale(0.05, 0.6, 0.05),
+            );
+            z += 2.0;
+        }
+        for height in [0.4, 0.9] {
+            // Rails
+            fence_parts.push(
+                Mat4::from_translation(vec3(side, height, 0.0))
+                    * Mat4::from_nonuniform_scale(0.02, 0.05, 0.5 * length),
+            );
+        }
+    }
+    let fences = Gm::new(
+        InstancedMesh::new(
+            &context,
+            &Instances {
+                transformations: fence_parts,
+                ..Default::default()
+            },
+            &CpuMesh::cube(),
+        ),
+        PhysicalMaterial::new_opaque(
+            &context,
+            &CpuMaterial {
+                albedo: Srgba::new_opaque(140, 100, 70),
+                ..Default::default()
+            },
+        ),
+    );
+
+    let ambient = AmbientLight::new(&context, 0.3, Srgba::WHITE);
+    let mut sun = DirectionalLight::new(
+        &context,
+        2.0,
+        Srgba::new_opaque(255, 240, 220),
+        vec3(1.0, -0.6, 0.4),
+    );
+
+    let mut gui = three_d::GUI::new(&context);
+    let mut cascaded = true;
+    let mut cascade_count = 4;
+    let mut shadow_distance = 150.0;
+    let mut texture_size = 1024;
+    let mut fly_through = true;
+    window.render_loop(move |mut frame_input| {
+        let mut panel_width = 0.0;
+        gui.update(
+            &mut frame_input.events,
+            frame_input.accumulated_time,
+            frame_input.viewport,
+            frame_input.device_pixel_ratio,
+            |gui_context| {
+                use three_d::egui::*;
+                SidePanel::left("side_panel").show(gui_context, |ui| {
+                    ui.heading("Cascaded shadows");
+                    ui.checkbox(&mut fly_through, "Fly through");
+                    ui.checkbox(&mut cascaded, "Cascaded");
+                    ui.add(
+                        Slider::new(&mut cascade_count, 1..=MAX_SHADOW_CASCADES).text("Cascades"),
+                    );
+                    ui.add(Slider::new(&mut shadow_distance, 10.0..=400.0).text("Shadow distance"));
+                    ui.label("Shadow map size");
+                    ui.radio_value(&mut texture_size, 512, "512");
+                    ui.radio_value(&mut texture_size, 1024, "1024");
+                    ui.radio_value(&mut texture_size, 2048, "2048");
+                });
+                panel_width = gui_context.used_rect().width();
+            },
+        );
+
+        let viewport = frame_input.viewport_from_logical(
+            panel_width,
+            0.0,
+            frame_input.window_width as f32 - panel_width,
+            frame_input.window_height as f32,
+        );
+        camera.set_viewport(viewport);
+        control.handle_events(&mut camera, &mut frame_input.events);
+        if fly_through {
+            // Move slowly along the road, back to the start at the end
+            let z = (camera.position().z + 0.005 * frame_input.elapsed_time as f32 + 0.5 * length)
+                % length
+                - 0.5 * length;
+            camera.set_view(
+                vec3(0.0, 2.0, z),
+                vec3(0.0, 1.5, z + 10.0),
+                vec3(0.0, 1.0, 0.0),
+            );
+        }
+
+        if cascaded {
+            // Split the shadow distance so each cascade covers a larger distance than the previous
+            let cascades = (1..=cascade_count)
+                .map(|i| shadow_distance * (i as f32 / cascade_count as f32).powi(2))
+                .collect::<Vec<_>>();
+            sun.generate_cascaded_shadow_maps(
+                &camera,
+                texture_size,
+                [&ground as &dyn Object, &fences],
+                &cascades,
+            )
+            .unwrap();
+        } else {
+            sun.generate_shadow_map(texture_size, [&ground as &dyn Object, &fences])
+                .unwrap();
+        }
+
+        frame_input
+            .screen()
+            .clear(ClearState::color_and_depth(0.6, 0.75, 0.95, 1.0, 1.0))
+            .render(
+                &camera,
+                [&ground as &dyn Object, &fences],
+                &[&ambient, &sun],
+            )
+            .write(|| gui.render())
+            .unwrap();
+
+        FrameOutput::default()
+    });
+}
@@ -0,0 +1,152 @@
+use three_d::*;
+
+pub fn main() {
+    let window = Window::new(WindowSettings {
+        title: "Caustics!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl();
+
+    let mut camera = Camera::new_perspective(
+        window.viewport(),
+        vec3(6.0, 3.0, 8.0),
+        vec3(0.0, -1.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        100.0,
+    );
+    let mut control = OrbitControl::new(camera.target(), 1.0, 50.0);
+    let mut gui = three_d::GUI::new(&context);
+
+    // The sea floor
+    let mut cpu_floor = CpuMesh::square();
+    cpu_floor
+        .transform(
+            Mat4::from_translation(vec3(0.0, -3.0, 0.0))
+                * Mat4::from_scale(20.0)
+                * Mat4::from_angle_x(degrees(-90.0)),
+        )
+        .unwrap();
+    let floor = Gm::new(
+        Mesh::new(&context, &cpu_floor),
+        PhysicalMaterial::new_opaque(
+            &context,
+            &CpuMaterial {
+                albedo: Srgba::new_opaque(220, 200, 150),
+                roughness: 0.9,
+                ..Default::default()
+            },
+        ),
+    );
+
+    // Pillars which are partly above and partly below the water
+    let mut pillars = Vec::new();
+    for (x, z) in [(-2.0, -1.0), (1.5, -2.0), (0.5, 2.0)] {
+        let mut pillar = Gm::new(
+            Mesh::new(&context, &CpuMesh::cube()),
+            PhysicalMaterial::new_opaque(
+                &context,
+                &CpuMaterial {
+                    albedo: Srgba::new_opaque(180, 180, 190),
+                    ..Default::default()
+                },
+            ),
+        );
+        pillar.set_transformation(
+            Mat4::from_translation(vec3(x, -1.0, z)) * Mat4::from_nonuniform_scale(0.5, 2.0, 0.5),
+        );
+        pillars.push(pillar);
+    }
+    let mut sphere = Gm::new(
+        Mesh::new(&context, &CpuMesh::sphere(32)),
+        PhysicalMaterial::new_opaque(
+            &context,
+            &CpuMaterial {
+                albedo: Srgba::new_opaque(200, 80, 60),
+                ..Default::default()
+            },
+        ),
+    );
+    sphere.set_transformation(Mat4::from_translation(vec3(-0.5, -2.0, 0.5)));
+
+    // The water surface
+    let mut cpu_water = CpuMesh::square();
+    cpu_water
+        .transform(Mat4::from_scale(20.0) * Mat4::from_angle_x(degrees(-90.0)))
+        .unwrap();
+    let mut water = Gm::new(
+        Mesh::new(&context, &cpu_water),
+        PhysicalMaterial::new_transparent(
+            &context,
+            &CpuMaterial {
+                albedo: Srgba::new(40, 120, 180, 80),
+                roughness: 0.1,
+                ..Default::default()
+            },
+        ),
+    );
+
+    let ambient = AmbientLight::new(&context, 0.3, Srgba::WHITE);
+    let mut sun = DirectionalLight::new(&context, 2.0, Srgba::WHITE, vec3(-0.3, -1.0, -0.2));
+    sun.caustics = Some(Caustics::new(&context, 256));
+
+    let mut show_water = true;
+    window.render_loop(move |mut frame_input| {
+        let caustics = sun.caustics.as_mut().unwrap();
+        let mut panel_width = 0.0;
+        gui.update(
+            &mut frame_input.events,
+            frame_input.accumulated_time,
+            frame_input.viewport,
+            frame_input.device_pixel_ratio,
+            |gui_context| {
+                use three_d::egui::*;
+                SidePanel::left("side_panel").show(gui_context, |ui| {
+                    ui.heading("Debug Panel");
+                    ui.add(Slider::new(&mut caustics.speed, 0.0..=5.0).text("Speed"));
+                    ui.add(Slider::new(&mut caustics.scale, 0.5..=20.0).text("Scale"));
+                    ui.add(Slider::new(&mut caustics.intensity, 0.0..=5.0).text("Intensity"));
+                    ui.add(
+                        Slider::new(&mut caustics.depth_attenuation, 0.0..=2.0)
+                            .text("Depth attenuation"),
+                    );
+                    ui.add(
+                        Slider::new(&mut caustics.chromatic_offset, 0.0..=0.05)
+                            .text("Chromatic offset"),
+                    );
+                    ui.add(Slider::new(&mut caustics.water_level, -3.0..=1.5).text("Water level"));
+                    ui.checkbox(&mut show_water, "Show water surface");
+                });
+                panel_width = gui_context.used_rect().width();
+            },
+        );
+        caustics.update(0.001 * frame_input.accumulated_time as f32);
+        water.set_transformation(Mat4::from_translation(vec3(0.0, caustics.water_level, 0.0)));
+
+        let viewport = frame_input.viewport_from_logical(
+            panel_width,
+            0.0,
+            frame_input.window_width as f32 - panel_width,
+            frame_input.window_height as f32,
+        );
+        camera.set_viewport(viewport);
+        control.handle_events(&mut camera, &mut frame_input.events);
+
+        let mut objects: Vec<&dyn Object> = vec![&floor, &sphere];
+        objects.extend(pillars.iter().map(|pillar| pillar as &dyn Object));
+        if show_water {
+            objects.push(&water);
+        }
+        frame_input
+            .screen()
+            .clear(ClearState::color_and_depth(0.6, 0.8, 1.0, 1.0, 1.0))
+            .render(&camera, objects, &[&ambient, &sun])
+            .write(|| gui.render())
+            .unwrap();
+
+        FrameOutput::default()
+    });
+}
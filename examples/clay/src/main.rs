@@ -0,0 +1,144 @@
+use three_d::*;
+
+pub fn main() {
+    let window = Window::new(WindowSettings {
+        title: "Clay!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl();
+
+    let mut camera = Camera::new_perspective(
+        window.viewport(),
+        vec3(0.0, 0.5, 4.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        100.0,
+    );
+    let mut control = OrbitControl::new(camera.target(), 2.0, 10.0);
+    let mut gui = three_d::GUI::new(&context);
+
+    // A sphere with bumps and grooves
+    let mut cpu_mesh = CpuMesh::sphere(96);
+    if let Positions::F32(ref mut positions) = cpu_mesh.positions {
+        for p in positions.iter_mut() {
+            let bumps = (7.0 * p.x).sin() * (5.0 * p.y).sin() * (6.0 * p.z).cos();
+            let grooves = (14.0 * (p.x + p.z)).sin().abs();
+            *p *= 1.0 + 0.12 * bumps - 0.04 * grooves;
+        }
+    }
+    cpu_mesh.compute_normals();
+
+    let settings = AmbientOcclusionSettings {
+        max_distance: 0.3,
+        ..Default::default()
+    };
+    let mut occlusion = compute_vertex_ambient_occlusion(&cpu_mesh, settings, |_| {});
+    let mut curvature = compute_vertex_curvature(&cpu_mesh);
+    let mut curvature_scale = 0.1;
+    cpu_mesh.colors = Some(ClayMaterial::vertex_colors(
+        &occlusion,
+        &curvature,
+        curvature_scale,
+    ));
+    let mut model = Gm::new(Mesh::new(&context, &cpu_mesh), ClayMaterial::default());
+
+    let ambient = AmbientLight::new(&context, 0.4, Srgba::WHITE);
+    let directional = DirectionalLight::new(&context, 2.0, Srgba::WHITE, vec3(-1.0, -1.0, -1.0));
+
+    window.render_loop(move |mut frame_input| {
+        let mut changed = false;
+        gui.update(
+            &mut frame_input.events,
+            frame_input.accumulated_time,
+            frame_input.viewport,
+            frame_input.device_pixel_ratio,
+            |gui_context| {
+                use three_d::egui::*;
+                Window::new("Clay").show(gui_context, |ui| {
+                    ui.add(
+                        Slider::new(&mut model.material.cavity_strength, 0.0..=1.0)
+                            .text("Cavity strength"),
+                    );
+                    ui.add(
+                        Slider::new(&mut model.material.edge_strength, 0.0..=1.0)
+                            .text("Edge strength"),
+                    );
+                    changed |= ui
+                        .add(Slider::new(&mut curvature_scale, 0.0..=0.5).text("Curvature scale"))
+                        .changed();
+                    ui.label("Ctrl + click to make a dent");
+                });
+            },
+        );
+
+        // Make a dent where the user clicks and only update the occlusion and curvature around the dent
+        for event in frame_input.events.iter_mut() {
+            if let Event::MousePress {
+                button: MouseButton::Left,
+                position,
+                modifiers,
+                handled,
+            } = event
+            {
+                if *handled || !modifiers.ctrl {
+                    continue;
+                }
+                if let Some(pick) =
+                    pick(&context, &camera, *position, &model.geometry, Cull::Back).unwrap()
+                {
+                    let radius = 0.2;
+                    let normals = cpu_mesh.normals.clone().unwrap();
+                    if let Positions::F32(ref mut positions) = cpu_mesh.positions {
+                        for (p, n) in positions.iter_mut().zip(normals) {
+                            let distance = p.distance(pick.position);
+                            if distance < radius {
+                                let falloff =
+                                    0.5 + 0.5 * (distance / radius * std::f32::consts::PI).cos();
+                                *p -= n * 0.05 * falloff;
+                            }
+                        }
+                    }
+                    cpu_mesh.compute_normals();
+                    let region = AxisAlignedBoundingBox::new_with_positions(&[
+                        pick.position - vec3(radius, radius, radius),
+                        pick.position + vec3(radius, radius, radius),
+                    ]);
+                    update_vertex_ambient_occlusion(
+                        &cpu_mesh,
+                        &mut occlusion,
+                        region,
+                        settings,
+                        |_| {},
+                    );
+                    update_vertex_curvature(&cpu_mesh, &mut curvature, region);
+                    changed = true;
+                    *handled = true;
+                }
+            }
+        }
+        if changed {
+            cpu_mesh.colors = Some(ClayMaterial::vertex_colors(
+                &occlusion,
+                &curvature,
+                curvature_scale,
+            ));
+            model.geometry = Mesh::new(&context, &cpu_mesh);
+        }
+
+        camera.set_viewport(frame_input.viewport);
+        control.handle_events(&mut camera, &mut frame_input.events);
+
+        frame_input
+            .screen()
+            .clear(ClearState::color_and_depth(0.2, 0.2, 0.2, 1.0, 1.0))
+            .render(&camera, &model, &[&ambient, &directional])
+            .write(|| gui.render())
+            .unwrap();
+
+        FrameOutput::default()
+    });
+}
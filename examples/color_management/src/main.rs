@@ -0,0 +1,112 @@
+use three_d::*;
+
+pub fn main() {
+    let window = Window::new(WindowSettings {
+        title: "Color management!".to_string(),
+        max_size: Some((1280, 720)),
+        surface_settings: SurfaceSettings {
+            // Only obtained if supported, see the surface settings of the window below
+            color_space: ColorSpace::DisplayP3,
+            ..Default::default()
+        },
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl();
+    let display_color_space = window.display_color_space();
+    let surface_color_space = window.surface_settings().color_space;
+
+    // A test pattern with a patch for each known color in the working space (linear sRGB), which is stretched to fill the screen.
+    // The rows are from top to bottom:
+    // 1. The sRGB primaries and secondaries followed by white and black, which should measure as the sRGB values.
+    // 2. A gray ramp with sRGB values 0, 32, 64, ..., 224, 255 which should measure as steps of equal perceived brightness.
+    // 3. The Display P3 primaries and secondaries, which are outside the sRGB gamut and therefore only different from the first row on a Display P3 surface.
+    // 4. The Rec. 2020 primaries and secondaries, which are clipped to Display P3 or sRGB.
+    let columns = 8;
+    let mut patches = Vec::new();
+    let saturated = [
+        [255, 0, 0],
+        [0, 255, 0],
+        [0, 0, 255],
+        [0, 255, 255],
+        [255, 0, 255],
+        [255, 255, 0],
+        [255, 255, 255],
+        [0, 0, 0],
+    ];
+    for [r, g, b] in saturated {
+        patches.push(Srgba::new_opaque(r, g, b).to_linear());
+    }
+    for i in 0..columns {
+        let value = if i == columns - 1 { 255 } else { i as u8 * 32 };
+        patches.push(Srgba::new_opaque(value, value, value).to_linear());
+    }
+    for color_space in [ColorSpace::DisplayP3, ColorSpace::Rec2020] {
+        for [r, g, b] in saturated {
+            patches.push(Srgba::new_opaque(r, g, b).to_linear_from(color_space));
+        }
+    }
+    let pattern = Texture2D::new(
+        &context,
+        &CpuTexture {
+            data: TextureData::RgbaF32(patches.into_iter().map(|c| c.into()).collect()),
+            width: columns,
+            height: 4,
+            min_filter: Interpolation::Nearest,
+            mag_filter: Interpolation::Nearest,
+            mipmap: None,
+            ..Default::default()
+        },
+    );
+
+    let mut camera = Camera::new_2d(window.viewport());
+    // The patches are given in the working space, so they must not be tone mapped
+    camera.tone_mapping = ToneMapping::None;
+    camera.color_mapping = ColorMapping::for_color_space(surface_color_space);
+
+    let mut gui = three_d::GUI::new(&context);
+    window.render_loop(move |mut frame_input| {
+        gui.update(
+            &mut frame_input.events,
+            frame_input.accumulated_time,
+            frame_input.viewport,
+            frame_input.device_pixel_ratio,
+            |gui_context| {
+                use three_d::egui::*;
+                Window::new("Color management")
+                    .anchor(Align2::RIGHT_BOTTOM, [-10.0, -10.0])
+                    .show(gui_context, |ui| {
+                        ui.label(format!("Display: {:?}", display_color_space));
+                        ui.label(format!("Surface: {:?}", surface_color_space));
+                        ui.label("Output transform:");
+                        ui.radio_value(
+                            &mut camera.color_mapping,
+                            ColorMapping::ComputeToSrgb,
+                            "sRGB",
+                        );
+                        ui.radio_value(
+                            &mut camera.color_mapping,
+                            ColorMapping::ComputeToDisplayP3,
+                            "Display P3",
+                        );
+                    });
+            },
+        );
+        camera.set_viewport(frame_input.viewport);
+
+        frame_input
+            .screen()
+            .clear(ClearState::color_and_depth(0.0, 0.0, 0.0, 1.0, 1.0))
+            .apply_screen_effect(
+                &ScreenEffect::default(),
+                &camera,
+                &[],
+                Some(ColorTexture::Single(&pattern)),
+                None,
+            )
+            .write(|| gui.render())
+            .unwrap();
+
+        FrameOutput::default()
+    });
+}
@@ -0,0 +1,97 @@
+use three_d::*;
+
+pub fn main() {
+    let window = Window::new(WindowSettings {
+        title: "Debug overlay!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl();
+
+    let mut camera = Camera::new_perspective(
+        window.viewport(),
+        vec3(6.0, 4.0, 10.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        30.0,
+    );
+    let mut control = OrbitControl::new(camera.target(), 1.0, 100.0);
+
+    let mut cube = Gm::new(
+        Mesh::new(&context, &CpuMesh::cube()),
+        PhysicalMaterial::new_opaque(
+            &context,
+            &CpuMaterial {
+                albedo: Srgba::new_opaque(200, 80, 60),
+                ..Default::default()
+            },
+        ),
+    );
+    cube.set_transformation(Mat4::from_translation(vec3(-2.0, 0.0, 0.0)));
+    let spheres = Gm::new(
+        InstancedMesh::new(
+            &context,
+            &Instances {
+                transformations: (0..5)
+                    .map(|i| {
+                        Mat4::from_translation(vec3(2.0, 0.0, 3.0 - 1.5 * i as f32))
+                            * Mat4::from_scale(0.6)
+                    })
+                    .collect(),
+                ..Default::default()
+            },
+            &CpuMesh::sphere(32),
+        ),
+        PhysicalMaterial::new_opaque(
+            &context,
+            &CpuMaterial {
+                albedo: Srgba::new_opaque(80, 160, 220),
+                ..Default::default()
+            },
+        ),
+    );
+
+    let ambient = AmbientLight::new(&context, 0.4, Srgba::WHITE);
+    let directional = DirectionalLight::new(&context, 2.0, Srgba::WHITE, vec3(-1.0, -1.0, -1.0));
+
+    let mut overlay = DebugOverlay::new(&context);
+    overlay.toggle_key = Some(Key::O);
+    overlay
+        .set_font(include_bytes!("../../text/src/font0.ttf"), 0)
+        .unwrap();
+
+    window.render_loop(move |mut frame_input| {
+        // Press F to freeze the pick ray and frustum and orbit around them
+        for event in frame_input.events.iter() {
+            if let Event::KeyPress { kind: Key::F, .. } = event {
+                overlay.set_frozen(!overlay.is_frozen());
+            }
+        }
+
+        camera.set_viewport(frame_input.viewport);
+        control.handle_events(&mut camera, &mut frame_input.events);
+        overlay
+            .update(
+                &mut frame_input.events,
+                frame_input.viewport,
+                frame_input.device_pixel_ratio,
+                &camera,
+                cube.into_iter().chain(&spheres),
+            )
+            .unwrap();
+
+        frame_input
+            .screen()
+            .clear(ClearState::color_and_depth(0.1, 0.1, 0.1, 1.0, 1.0))
+            .render(
+                &camera,
+                cube.into_iter().chain(&spheres).chain(&overlay),
+                &[&ambient, &directional],
+            );
+
+        FrameOutput::default()
+    });
+}
@@ -0,0 +1,166 @@
+// Entry point for non-wasm
+#[cfg(not(target_arch = "wasm32"))]
+#[tokio::main]
+async fn main() {
+    run().await;
+}
+
+use three_d::*;
+
+type Shape = Gm<Mesh, PhysicalMaterial>;
+
+pub async fn run() {
+    let window = Window::new(WindowSettings {
+        title: "Deferred creation!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl();
+
+    let mut camera = Camera::new_perspective(
+        window.viewport(),
+        vec3(30.0, 25.0, 30.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        1000.0,
+    );
+    let mut control = OrbitControl::new(camera.target(), 1.0, 200.0);
+    let mut gui = three_d::GUI::new(&context);
+
+    let ambient = AmbientLight::new(&context, 0.3, Srgba::WHITE);
+    let directional = DirectionalLight::new(&context, 2.0, Srgba::WHITE, vec3(-1.0, -1.0, -0.5));
+
+    let mut queue = DeferredCreationQueue::new();
+    let mut handles = load(queue.sender());
+    let mut pending = Vec::new();
+    let mut objects = Vec::new();
+
+    let mut budget = 2.0;
+    let mut max_elapsed: f64 = 0.0;
+    let mut frames_over_budget = 0;
+    window.render_loop(move |mut frame_input| {
+        let mut reload = false;
+        let mut panel_width = 0.0;
+        gui.update(
+            &mut frame_input.events,
+            frame_input.accumulated_time,
+            frame_input.viewport,
+            frame_input.device_pixel_ratio,
+            |gui_context| {
+                use three_d::egui::*;
+                SidePanel::left("side_panel").show(gui_context, |ui| {
+                    let stats = queue.stats();
+                    ui.heading("Deferred creation");
+                    ui.add(Slider::new(&mut budget, 0.1..=16.0).text("Budget (ms)"));
+                    reload = ui.button("Reload").clicked();
+                    ui.label(format!("Objects: {}", objects.len()));
+                    ui.label(format!("Pending: {}", stats.pending));
+                    ui.label(format!("Cancelled: {}", stats.cancelled));
+                    ui.label(format!("Created this frame: {}", stats.created));
+                    ui.label(format!("Time this frame: {:.2} ms", stats.elapsed_ms));
+                    ui.label(format!("Max time: {:.2} ms", max_elapsed));
+                    ui.label(format!("Frames over budget: {}", frames_over_budget));
+                });
+                panel_width = gui_context.used_rect().width();
+            },
+        );
+
+        pending.extend(handles.try_iter());
+        if reload {
+            // Cancel the objects that are not created yet and start over
+            for handle in pending.drain(..) {
+                handle.cancel();
+            }
+            objects.clear();
+            max_elapsed = 0.0;
+            frames_over_budget = 0;
+            handles = load(queue.sender());
+        }
+
+        queue.process(&context, budget);
+        pending.retain(|handle| match queue.take(handle) {
+            Some(object) => {
+                objects.push(object);
+                false
+            }
+            None => true,
+        });
+        let elapsed = queue.stats().elapsed_ms;
+        max_elapsed = max_elapsed.max(elapsed);
+        if elapsed > budget {
+            frames_over_budget += 1;
+        }
+
+        let viewport = frame_input.viewport_from_logical(
+            panel_width,
+            0.0,
+            frame_input.window_width as f32 - panel_width,
+            frame_input.window_height as f32,
+        );
+        camera.set_viewport(viewport);
+        control.handle_events(&mut camera, &mut frame_input.events);
+
+        frame_input
+            .screen()
+            .clear(ClearState::color_and_depth(0.8, 0.8, 0.8, 1.0, 1.0))
+            .render(&camera, &objects, &[&ambient, &directional])
+            .write(|| gui.render())
+            .unwrap();
+
+        FrameOutput::default()
+    });
+}
+
+///
+/// Generates the data for 500 objects on a background thread and adds them to the queue.
+/// The handles are sent back through the returned channel and the loading stops if the channel is dropped.
+///
+fn load(sender: DeferredCreationSender) -> std::sync::mpsc::Receiver<CreationHandle<Shape>> {
+    let (handle_sender, handles) = std::sync::mpsc::channel();
+    let generate = move || {
+        for i in 0..500 {
+            let x = (i % 25) as f32 - 12.0;
+            let z = (i / 25) as f32 - 10.0;
+            let height = 0.5 + ((i * 7919) % 13) as f32 * 0.3;
+            let mut cpu_mesh = if i % 2 == 0 {
+                CpuMesh::sphere(32)
+            } else {
+                CpuMesh::cylinder(32)
+            };
+            cpu_mesh
+                .transform(
+                    Mat4::from_translation(vec3(x * 1.5, height, z * 1.5))
+                        * Mat4::from_scale(0.5)
+                        * Mat4::from_nonuniform_scale(1.0, height, 1.0),
+                )
+                .unwrap();
+            let cpu_material = CpuMaterial {
+                albedo: Srgba::new_opaque(
+                    (i * 37 % 255) as u8,
+                    (i * 91 % 255) as u8,
+                    (i * 53 % 255) as u8,
+                ),
+                roughness: 0.5,
+                ..Default::default()
+            };
+            let handle = sender.create_with(move |context| {
+                Gm::new(
+                    Mesh::new(context, &cpu_mesh),
+                    PhysicalMaterial::new_opaque(context, &cpu_material),
+                )
+            });
+            if handle_sender.send(handle.clone()).is_err() {
+                handle.cancel();
+                return;
+            }
+        }
+    };
+    #[cfg(not(target_arch = "wasm32"))]
+    std::thread::spawn(generate);
+    #[cfg(target_arch = "wasm32")]
+    generate();
+    handles
+}
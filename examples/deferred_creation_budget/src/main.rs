@@ -0,0 +1,114 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use three_d::*;
+
+const OBJECT_COUNT: usize = 500;
+const CANCELLED_COUNT: usize = 50;
+const TIME_BUDGET_MS: f64 = 2.0;
+
+fn main() {
+    // Create a headless graphics context
+    let context = HeadlessContext::new().unwrap();
+
+    let mut queue = DeferredCreationQueue::new();
+    let sender = queue.sender();
+
+    // Generate the data on a background thread, using all the ways of adding an object to the queue
+    let callback_count = Arc::new(AtomicUsize::new(0));
+    let created_by_callback = callback_count.clone();
+    let (handles, cancelled_handles) = std::thread::spawn(move || {
+        let mut handles = Vec::new();
+        let mut cancelled_handles = Vec::new();
+        for i in 0..OBJECT_COUNT + CANCELLED_COUNT {
+            let mut cpu_mesh = if i % 2 == 0 {
+                CpuMesh::sphere(32)
+            } else {
+                CpuMesh::cylinder(32)
+            };
+            cpu_mesh
+                .transform(Mat4::from_translation(vec3(i as f32, 0.0, 0.0)))
+                .unwrap();
+            let cpu_material = CpuMaterial {
+                albedo: Srgba::new_opaque((i % 255) as u8, 128, 255 - (i % 255) as u8),
+                ..Default::default()
+            };
+            if i % 11 == 10 {
+                // Every eleventh object is cancelled before the queue is processed, so it must never be created
+                cancelled_handles.push(sender.create::<Mesh>(cpu_mesh));
+            } else if i % 3 == 0 {
+                handles.push(sender.create_with(move |context| {
+                    Gm::new(
+                        Mesh::new(context, &cpu_mesh),
+                        PhysicalMaterial::new_opaque(context, &cpu_material),
+                    )
+                }));
+            } else if i % 3 == 1 {
+                let callback_count = callback_count.clone();
+                sender.create_then::<Mesh>(cpu_mesh, move |_| {
+                    callback_count.fetch_add(1, Ordering::Relaxed);
+                });
+            } else {
+                let callback_count = callback_count.clone();
+                sender.create_then::<PhysicalMaterial>(cpu_material, move |_| {
+                    callback_count.fetch_add(1, Ordering::Relaxed);
+                });
+            }
+        }
+        (handles, cancelled_handles)
+    })
+    .join()
+    .unwrap();
+    for handle in cancelled_handles.iter() {
+        handle.cancel();
+    }
+
+    // Process the queue once each frame until there is nothing left to create
+    let mut created = Vec::new();
+    let mut frame_count = 0;
+    let mut max_elapsed_ms: f64 = 0.0;
+    let mut created_count = 0;
+    loop {
+        frame_count += 1;
+        assert!(
+            frame_count < 10 * OBJECT_COUNT,
+            "the objects are never created"
+        );
+        created_count += queue.process(&context, TIME_BUDGET_MS);
+        let stats = queue.stats();
+        println!(
+            "Frame {}: created {} objects in {:.3} ms, {} pending",
+            frame_count, stats.created, stats.elapsed_ms, stats.pending
+        );
+        // A single object is always created, even if it takes longer than the budget
+        assert!(
+            stats.created <= 1 || stats.elapsed_ms <= TIME_BUDGET_MS,
+            "frame {} created {} objects in {:.3} ms which exceeds the budget of {} ms",
+            frame_count,
+            stats.created,
+            stats.elapsed_ms,
+            TIME_BUDGET_MS
+        );
+        max_elapsed_ms = max_elapsed_ms.max(stats.elapsed_ms);
+        created.extend(handles.iter().filter_map(|handle| queue.take(handle)));
+        if stats.pending == 0 {
+            break;
+        }
+    }
+
+    let stats = queue.stats();
+    println!(
+        "Created {} objects over {} frames, where the slowest frame took {:.3} ms",
+        created_count, frame_count, max_elapsed_ms
+    );
+    assert!(frame_count > 1, "the objects are not spread across frames");
+    assert_eq!(created_count, OBJECT_COUNT);
+    assert_eq!(stats.cancelled, CANCELLED_COUNT);
+    assert_eq!(created.len(), handles.len());
+    assert_eq!(
+        created_by_callback.load(Ordering::Relaxed),
+        OBJECT_COUNT - handles.len()
+    );
+    assert!(cancelled_handles
+        .iter()
+        .all(|handle| queue.take(handle).is_none()));
+}
@@ -0,0 +1,153 @@
+use three_d::*;
+
+pub fn main() {
+    let window = Window::new(WindowSettings {
+        title: "Depth of field!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl();
+
+    let mut camera = Camera::new_perspective(
+        window.viewport(),
+        vec3(0.0, 1.5, 6.0),
+        vec3(0.0, 0.5, -4.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        100.0,
+    );
+    let mut control = OrbitControl::new(camera.target(), 2.0, 50.0);
+    let mut gui = three_d::GUI::new(&context);
+
+    let mut ground = Gm::new(
+        Mesh::new(&context, &CpuMesh::square()),
+        PhysicalMaterial::new_opaque(
+            &context,
+            &CpuMaterial {
+                albedo: Srgba::new_opaque(120, 120, 130),
+                roughness: 0.8,
+                ..Default::default()
+            },
+        ),
+    );
+    ground.set_transformation(Mat4::from_angle_x(degrees(-90.0)) * Mat4::from_scale(30.0));
+
+    // Two rows of objects at increasing distances from the camera
+    let objects = (0..12)
+        .map(|i| {
+            let color = [
+                Srgba::new_opaque(230, 80, 60),
+                Srgba::new_opaque(240, 190, 60),
+                Srgba::new_opaque(80, 200, 100),
+                Srgba::new_opaque(70, 140, 230),
+            ][i % 4];
+            let mesh = if i % 2 == 0 {
+                CpuMesh::sphere(32)
+            } else {
+                CpuMesh::cube()
+            };
+            let mut object = Gm::new(
+                Mesh::new(&context, &mesh),
+                PhysicalMaterial::new_opaque(
+                    &context,
+                    &CpuMaterial {
+                        albedo: color,
+                        metallic: 0.3,
+                        roughness: 0.3,
+                        ..Default::default()
+                    },
+                ),
+            );
+            let x = if i % 2 == 0 { -1.2 } else { 1.2 };
+            object.set_transformation(
+                Mat4::from_translation(vec3(x, 0.5, 4.0 - 2.0 * i as f32)) * Mat4::from_scale(0.5),
+            );
+            object
+        })
+        .collect::<Vec<_>>();
+
+    let ambient = AmbientLight::new(&context, 0.3, Srgba::WHITE);
+    let directional = DirectionalLight::new(&context, 2.0, Srgba::WHITE, vec3(-1.0, -2.0, -1.0));
+
+    let mut depth_of_field = DepthOfFieldEffect::new(&context, 6.0);
+
+    window.render_loop(move |mut frame_input| {
+        gui.update(
+            &mut frame_input.events,
+            frame_input.accumulated_time,
+            frame_input.viewport,
+            frame_input.device_pixel_ratio,
+            |gui_context| {
+                use three_d::egui::*;
+                Window::new("Depth of field").show(gui_context, |ui| {
+                    ui.label("Right click to focus on the object under the cursor");
+                    ui.add(
+                        Slider::new(&mut depth_of_field.focus_distance, 0.1..=50.0)
+                            .text("Focus distance"),
+                    );
+                    ui.add(Slider::new(&mut depth_of_field.aperture, 0.0..=0.5).text("Aperture"));
+                    ui.add(
+                        Slider::new(&mut depth_of_field.max_blur_radius, 0.0..=32.0)
+                            .text("Max blur radius"),
+                    );
+                });
+            },
+        );
+
+        camera.set_viewport(frame_input.viewport);
+        control.handle_events(&mut camera, &mut frame_input.events);
+        for event in frame_input.events.iter() {
+            if let Event::MousePress {
+                button,
+                position,
+                handled,
+                ..
+            } = *event
+            {
+                if !handled && button == MouseButton::Right {
+                    depth_of_field.set_focus_point(&camera, position);
+                }
+            }
+        }
+        let (width, height) = (frame_input.viewport.width, frame_input.viewport.height);
+
+        // Render the scene with high dynamic range colors and without tone and color mapping
+        let mut color_texture =
+            context.request_texture::<[f16; 4]>(width, height, Interpolation::Nearest);
+        let mut depth_texture = context.request_depth_texture::<f32>(width, height);
+        camera.disable_tone_and_color_mapping();
+        RenderTarget::new(
+            color_texture.as_color_target(None),
+            depth_texture.as_depth_target(),
+        )
+        .clear(ClearState::color_and_depth(0.5, 0.6, 0.8, 1.0, 1.0))
+        .render(
+            &camera,
+            ground.into_iter().chain(objects.iter().flatten()),
+            &[&ambient, &directional],
+        );
+
+        // Blur the scene depending on the depth and apply tone and color mapping when writing to the screen
+        depth_of_field.update(
+            &camera,
+            ColorTexture::Single(&color_texture),
+            DepthTexture::Single(&depth_texture),
+        );
+        camera.set_default_tone_and_color_mapping();
+        frame_input
+            .screen()
+            .apply_screen_effect(
+                &depth_of_field,
+                &camera,
+                &[],
+                Some(ColorTexture::Single(&color_texture)),
+                Some(DepthTexture::Single(&depth_texture)),
+            )
+            .write(|| gui.render())
+            .unwrap();
+
+        FrameOutput::default()
+    });
+}
@@ -0,0 +1,192 @@
+use three_d::*;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Mode {
+    Sorted,
+    WeightedBlended,
+    DepthPeeled,
+}
+
+pub fn main() {
+    let window = Window::new(WindowSettings {
+        title: "Depth peeling!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl();
+
+    let mut camera = Camera::new_perspective(
+        window.viewport(),
+        vec3(4.0, 3.0, 5.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        100.0,
+    );
+    let mut control = OrbitControl::new(camera.target(), 2.0, 50.0);
+    let mut gui = three_d::GUI::new(&context);
+
+    // Nested and intersecting glass parts with different colors, so the blending depends on the exact order of the layers
+    let glass = [
+        (
+            Mat4::from_nonuniform_scale(1.2, 0.8, 0.8),
+            Srgba::new(120, 180, 255, 90),
+        ),
+        (
+            Mat4::from_translation(vec3(0.6, 0.3, 0.0)) * Mat4::from_scale(0.5),
+            Srgba::new(255, 80, 80, 140),
+        ),
+        (
+            Mat4::from_translation(vec3(-0.5, -0.2, 0.3))
+                * Mat4::from_nonuniform_scale(0.3, 1.2, 0.3),
+            Srgba::new(80, 255, 120, 140),
+        ),
+    ]
+    .map(|(transformation, color)| {
+        let mut object = Gm::new(
+            Mesh::new(&context, &CpuMesh::cube()),
+            PhysicalMaterial::new_transparent(
+                &context,
+                &CpuMaterial {
+                    albedo: color,
+                    roughness: 0.2,
+                    ..Default::default()
+                },
+            ),
+        );
+        object.material.render_states.cull = Cull::None;
+        object.set_transformation(transformation);
+        object
+    });
+
+    // An opaque wireframe of the outer box which is partly hidden by the glass
+    let edge_material = PhysicalMaterial::new_opaque(
+        &context,
+        &CpuMaterial {
+            albedo: Srgba::new_opaque(30, 30, 30),
+            ..Default::default()
+        },
+    );
+    let corners = [
+        vec3(-1.2, -0.8, -0.8),
+        vec3(1.2, -0.8, -0.8),
+        vec3(1.2, 0.8, -0.8),
+        vec3(-1.2, 0.8, -0.8),
+        vec3(-1.2, -0.8, 0.8),
+        vec3(1.2, -0.8, 0.8),
+        vec3(1.2, 0.8, 0.8),
+        vec3(-1.2, 0.8, 0.8),
+    ];
+    let edges = [
+        (0, 1),
+        (1, 2),
+        (2, 3),
+        (3, 0),
+        (4, 5),
+        (5, 6),
+        (6, 7),
+        (7, 4),
+        (0, 4),
+        (1, 5),
+        (2, 6),
+        (3, 7),
+    ];
+    let wireframe = edges
+        .iter()
+        .map(|&(i, j)| {
+            let mut edge = Gm::new(
+                Mesh::new(&context, &CpuMesh::cylinder(8)),
+                edge_material.clone(),
+            );
+            let (p0, p1) = (corners[i], corners[j]);
+            let direction = p1 - p0;
+            edge.set_transformation(
+                Mat4::from_translation(p0)
+                    * Mat4::from(Quat::from_arc(
+                        vec3(1.0, 0.0, 0.0),
+                        direction.normalize(),
+                        None,
+                    ))
+                    * Mat4::from_nonuniform_scale(direction.magnitude(), 0.015, 0.015),
+            );
+            edge
+        })
+        .collect::<Vec<_>>();
+
+    let ambient = AmbientLight::new(&context, 0.4, Srgba::WHITE);
+    let directional = DirectionalLight::new(&context, 2.0, Srgba::WHITE, vec3(-1.0, -1.0, -0.5));
+
+    let mut mode = Mode::DepthPeeled;
+    let mut layers = 4;
+    window.render_loop(move |mut frame_input| {
+        gui.update(
+            &mut frame_input.events,
+            frame_input.accumulated_time,
+            frame_input.viewport,
+            frame_input.device_pixel_ratio,
+            |gui_context| {
+                use three_d::egui::*;
+                Window::new("Transparency").show(gui_context, |ui| {
+                    ui.radio_value(&mut mode, Mode::Sorted, "Sorted");
+                    ui.radio_value(&mut mode, Mode::WeightedBlended, "Weighted blended");
+                    ui.radio_value(&mut mode, Mode::DepthPeeled, "Depth peeled");
+                    ui.add_enabled(
+                        mode == Mode::DepthPeeled,
+                        Slider::new(&mut layers, 2..=6).text("Layers"),
+                    );
+                });
+            },
+        );
+
+        camera.set_viewport(frame_input.viewport);
+        control.handle_events(&mut camera, &mut frame_input.events);
+
+        let lights: [&dyn Light; 2] = [&ambient, &directional];
+        let screen = frame_input.screen();
+        if mode == Mode::Sorted {
+            screen
+                .clear(ClearState::color_and_depth(0.9, 0.9, 0.9, 1.0, 1.0))
+                .render(&camera, wireframe.iter().chain(&glass), &lights);
+        } else {
+            // Render the opaque objects into textures, so the depth can be used to hide the transparent objects behind them
+            let (width, height) = (frame_input.viewport.width, frame_input.viewport.height);
+            let mut color_texture =
+                context.request_texture::<[u8; 4]>(width, height, Interpolation::Nearest);
+            let mut depth_texture = context.request_depth_texture::<f32>(width, height);
+            RenderTarget::new(
+                color_texture.as_color_target(None),
+                depth_texture.as_depth_target(),
+            )
+            .clear(ClearState::color_and_depth(0.9, 0.9, 0.9, 1.0, 1.0))
+            .render(&camera, &wireframe, &lights);
+            screen.apply_screen_effect(
+                &CopyEffect::default(),
+                &camera,
+                &[],
+                Some(ColorTexture::Single(&color_texture)),
+                Some(DepthTexture::Single(&depth_texture)),
+            );
+            if mode == Mode::WeightedBlended {
+                screen.render_transparent_oit(
+                    &camera,
+                    &glass,
+                    &lights,
+                    Some(DepthTexture::Single(&depth_texture)),
+                );
+            } else {
+                screen.render_transparent_depth_peeled(
+                    &camera,
+                    &glass,
+                    &lights,
+                    layers,
+                    Some(DepthTexture::Single(&depth_texture)),
+                );
+            }
+        }
+        screen.write(|| gui.render()).unwrap();
+
+        FrameOutput::default()
+    });
+}
@@ -0,0 +1,152 @@
+use three_d::*;
+
+pub fn main() {
+    let window = Window::new(WindowSettings {
+        title: "Displacement!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl();
+
+    let mut camera = Camera::new_perspective(
+        window.viewport(),
+        vec3(18.0, 8.0, 18.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        1000.0,
+    );
+    let mut control = OrbitControl::new(camera.target(), 5.0, 80.0);
+    let mut gui = three_d::GUI::new(&context);
+
+    // A tileable swell made of waves with a whole number of periods across the texture
+    let size = 256;
+    let waves = [
+        (2.0, 1.0, 0.45, 0.0),
+        (3.0, -2.0, 0.3, 1.3),
+        (5.0, 4.0, 0.15, 2.1),
+        (-7.0, 9.0, 0.1, 0.4),
+    ];
+    let mut heights = Vec::with_capacity(size * size);
+    for y in 0..size {
+        for x in 0..size {
+            let (u, v) = (x as f32 / size as f32, y as f32 / size as f32);
+            let height = waves
+                .iter()
+                .map(|(fu, fv, amplitude, phase)| {
+                    amplitude * (2.0 * std::f32::consts::PI * (fu * u + fv * v) + phase).sin()
+                })
+                .sum::<f32>();
+            heights.push(f16::from_f32(0.5 + 0.5 * height));
+        }
+    }
+    let height_texture = Texture2DRef::from_cpu_texture(
+        &context,
+        &CpuTexture {
+            data: TextureData::RF16(heights),
+            width: size as u32,
+            height: size as u32,
+            ..Default::default()
+        },
+    );
+
+    // A subdivided plane with uv coordinates from 0 to 1
+    let resolution = 256;
+    let extent = 20.0;
+    let mut positions = Vec::new();
+    let mut uvs = Vec::new();
+    for j in 0..=resolution {
+        for i in 0..=resolution {
+            let (u, v) = (i as f32 / resolution as f32, j as f32 / resolution as f32);
+            positions.push(vec3(
+                extent * (2.0 * u - 1.0),
+                0.0,
+                extent * (2.0 * v - 1.0),
+            ));
+            uvs.push(vec2(u, v));
+        }
+    }
+    let mut indices = Vec::new();
+    for j in 0..resolution {
+        for i in 0..resolution {
+            let i0 = j * (resolution + 1) + i;
+            let i1 = i0 + resolution + 1;
+            indices.extend([i0, i1, i0 + 1, i0 + 1, i1, i1 + 1]);
+        }
+    }
+    let plane = CpuMesh {
+        positions: Positions::F32(positions),
+        indices: Indices::U32(indices),
+        uvs: Some(uvs),
+        ..Default::default()
+    };
+    let mut ocean = Gm::new(
+        DisplacedMesh::new(&context, &plane, height_texture, 1.5),
+        PhysicalMaterial::new_opaque(
+            &context,
+            &CpuMaterial {
+                albedo: Srgba::new_opaque(20, 70, 110),
+                roughness: 0.15,
+                ..Default::default()
+            },
+        ),
+    );
+    ocean.set_transformation(Mat4::from_translation(vec3(0.0, -1.0, 0.0)));
+
+    let ambient = AmbientLight::new(&context, 0.3, Srgba::new_opaque(180, 200, 255));
+    let mut directional = DirectionalLight::new(
+        &context,
+        2.5,
+        Srgba::new_opaque(255, 240, 220),
+        vec3(-1.0, -0.6, -0.3),
+    );
+
+    let mut scale = 1.5;
+    let mut speed = 1.0;
+    let mut normals_from_height = true;
+    let mut shadows = true;
+    let mut time = 0.0;
+    window.render_loop(move |mut frame_input| {
+        gui.update(
+            &mut frame_input.events,
+            frame_input.accumulated_time,
+            frame_input.viewport,
+            frame_input.device_pixel_ratio,
+            |gui_context| {
+                use three_d::egui::*;
+                Window::new("Swell").show(gui_context, |ui| {
+                    ui.add(Slider::new(&mut scale, 0.0..=4.0).text("Height"));
+                    ui.add(Slider::new(&mut speed, 0.0..=4.0).text("Speed"));
+                    ui.checkbox(&mut normals_from_height, "Normals from height");
+                    ui.checkbox(&mut shadows, "Shadows");
+                });
+            },
+        );
+        camera.set_viewport(frame_input.viewport);
+        control.handle_events(&mut camera, &mut frame_input.events);
+
+        // The swell moves by scrolling the height texture, which is repeated four times across the plane
+        time += 0.001 * speed * frame_input.elapsed_time as f32;
+        ocean.geometry.scale = scale;
+        ocean.geometry.normals_from_height = normals_from_height;
+        ocean.geometry.height_texture.transformation =
+            Mat3::from_translation(vec2(0.02 * time, 0.01 * time)) * Mat3::from_scale(4.0);
+
+        if shadows {
+            directional.generate_shadow_map(2048, &ocean).unwrap();
+        } else {
+            directional.clear_shadow_map();
+        }
+
+        frame_input
+            .screen()
+            .clear(ClearState::color_and_depth(0.7, 0.8, 0.9, 1.0, 1.0))
+            .render(&camera, &ocean, &[&ambient, &directional])
+            .write(|| gui.render())
+            .unwrap();
+
+        FrameOutput::default()
+    });
+}
@@ -0,0 +1,111 @@
+use three_d::*;
+
+pub fn main() {
+    let window = Window::new(WindowSettings {
+        title: "Dynamic resolution!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl();
+
+    let mut camera = Camera::new_perspective(
+        window.viewport(),
+        vec3(12.0, 8.0, 12.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        1000.0,
+    );
+    let mut control = OrbitControl::new(camera.target(), 1.0, 100.0);
+    let mut gui = three_d::GUI::new(&context);
+    let mut dynamic_resolution = DynamicResolution::new(&context);
+    let mut render_scale = dynamic_resolution.render_scale();
+    let mut automatic = false;
+    let mut target_frame_rate = 60.0;
+
+    let mesh = Mesh::new(&context, &CpuMesh::sphere(32));
+    let mut spheres = Vec::new();
+    for x in -5..=5 {
+        for z in -5..=5 {
+            let mut sphere = Gm::new(
+                mesh.clone(),
+                PhysicalMaterial::new_opaque(
+                    &context,
+                    &CpuMaterial {
+                        albedo: Srgba::new_opaque((128 + x * 12) as u8, 100, (128 + z * 12) as u8),
+                        metallic: 0.5,
+                        roughness: 0.3,
+                        ..Default::default()
+                    },
+                ),
+            );
+            sphere.set_transformation(
+                Mat4::from_translation(vec3(x as f32 * 1.2, 0.0, z as f32 * 1.2))
+                    * Mat4::from_scale(0.5),
+            );
+            spheres.push(sphere);
+        }
+    }
+    let ambient = AmbientLight::new(&context, 0.3, Srgba::WHITE);
+    let sun = DirectionalLight::new(&context, 2.0, Srgba::WHITE, vec3(-0.5, -1.0, -0.3));
+
+    window.render_loop(move |mut frame_input| {
+        dynamic_resolution.update(frame_input.elapsed_time);
+
+        gui.update(
+            &mut frame_input.events,
+            frame_input.accumulated_time,
+            frame_input.viewport,
+            frame_input.device_pixel_ratio,
+            |gui_context| {
+                use three_d::egui::*;
+                Window::new("Dynamic resolution").show(gui_context, |ui| {
+                    if ui
+                        .checkbox(&mut automatic, "Automatic render scale")
+                        .changed()
+                    {
+                        dynamic_resolution
+                            .set_target_frame_time(automatic.then_some(1000.0 / target_frame_rate));
+                    }
+                    if automatic {
+                        if ui
+                            .add(
+                                Slider::new(&mut target_frame_rate, 20.0..=144.0)
+                                    .text("Target frame rate"),
+                            )
+                            .changed()
+                        {
+                            dynamic_resolution
+                                .set_target_frame_time(Some(1000.0 / target_frame_rate));
+                        }
+                        render_scale = dynamic_resolution.render_scale();
+                        ui.label(format!("Render scale: {:.2}", render_scale));
+                    } else if ui
+                        .add(Slider::new(&mut render_scale, 0.1..=1.0).text("Render scale"))
+                        .changed()
+                    {
+                        dynamic_resolution.set_render_scale(render_scale);
+                    }
+                    ui.label(format!("Frame time: {:.1} ms", frame_input.elapsed_time));
+                });
+            },
+        );
+
+        camera.set_viewport(frame_input.viewport);
+        control.handle_events(&mut camera, &mut frame_input.events);
+
+        let screen = frame_input.screen();
+        dynamic_resolution.render(
+            &screen,
+            ClearState::color_and_depth(0.8, 0.8, 0.8, 1.0, 1.0),
+            &camera,
+            &spheres,
+            &[&ambient, &sun],
+        );
+        screen.write(|| gui.render()).unwrap();
+
+        FrameOutput::default()
+    });
+}
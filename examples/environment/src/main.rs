@@ -40,11 +40,12 @@ pub async fn run() {
         .expect("failed to download the necessary assets, to enable running this example offline, place the relevant assets in a folder called 'assets' next to the three-d source")
     };
 
-    let skybox = Skybox::new_from_equirectangular(
+    let mut skybox = Skybox::new_from_equirectangular(
         &context,
         &loaded.deserialize("chinese_garden_4k").unwrap(),
     );
-    let light = AmbientLight::new_with_environment(&context, 1.0, Srgba::WHITE, skybox.texture());
+    let mut light =
+        AmbientLight::new_with_environment(&context, 1.0, Srgba::WHITE, skybox.texture());
 
     let mut model = Gm::new(
         Mesh::new(&context, &CpuMesh::sphere(32)),
@@ -61,6 +62,8 @@ pub async fn run() {
 
     // main loop
     let mut color = [1.0; 4];
+    let mut rotation = 0.0;
+    let mut intensity = 1.0;
     window.render_loop(move |mut frame_input| {
         let mut panel_width = 0.0;
         gui.update(
@@ -75,19 +78,25 @@ pub async fn run() {
                     ui.add(Slider::new(&mut model.material.metallic, 0.0..=1.0).text("Metallic"));
                     ui.add(Slider::new(&mut model.material.roughness, 0.0..=1.0).text("Roughness"));
                     ui.color_edit_button_rgba_unmultiplied(&mut color);
+                    ui.add(Slider::new(&mut rotation, 0.0..=360.0).text("Environment rotation"));
+                    ui.add(Slider::new(&mut intensity, 0.0..=2.0).text("Environment intensity"));
                 });
                 panel_width = gui_context.used_rect().width();
             },
         );
         model.material.albedo = Srgba::from(color);
+        // Rotate the environment without recalculating the lighting from the environment
+        light.rotation = Quat::from_angle_y(degrees(rotation));
+        light.intensity = intensity;
+        skybox.set_rotation(light.rotation);
+        skybox.set_intensity(intensity);
 
-        let viewport = Viewport {
-            x: (panel_width * frame_input.device_pixel_ratio) as i32,
-            y: 0,
-            width: frame_input.viewport.width
-                - (panel_width * frame_input.device_pixel_ratio) as u32,
-            height: frame_input.viewport.height,
-        };
+        let viewport = frame_input.viewport_from_logical(
+            panel_width,
+            0.0,
+            frame_input.window_width as f32 - panel_width,
+            frame_input.window_height as f32,
+        );
         camera.set_viewport(viewport);
         control.handle_events(&mut camera, &mut frame_input.events);
 
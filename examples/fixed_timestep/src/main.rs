@@ -0,0 +1,140 @@
+use three_d::*;
+
+struct Ball {
+    previous_position: Vec3,
+    position: Vec3,
+    velocity: Vec3,
+}
+
+pub fn main() {
+    let window = Window::new(WindowSettings {
+        title: "Fixed timestep!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl();
+
+    let mut camera = Camera::new_perspective(
+        window.viewport(),
+        vec3(0.0, 6.0, 18.0),
+        vec3(0.0, 3.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        1000.0,
+    );
+    let mut control = OrbitControl::new(camera.target(), 1.0, 100.0);
+    let mut gui = three_d::GUI::new(&context);
+
+    let radius = 0.5;
+    let mut balls = (0..7)
+        .map(|i| {
+            let position = vec3(i as f32 * 1.5 - 4.5, 2.0 + i as f32, 0.0);
+            Ball {
+                previous_position: position,
+                position,
+                velocity: vec3(0.0, 0.0, 0.0),
+            }
+        })
+        .collect::<Vec<_>>();
+    let mut spheres = balls
+        .iter()
+        .enumerate()
+        .map(|(i, _)| {
+            Gm::new(
+                Mesh::new(&context, &CpuMesh::sphere(32)),
+                PhysicalMaterial::new_opaque(
+                    &context,
+                    &CpuMaterial {
+                        albedo: Srgba::new_opaque(255, 60 + i as u8 * 30, 50),
+                        roughness: 0.4,
+                        ..Default::default()
+                    },
+                ),
+            )
+        })
+        .collect::<Vec<_>>();
+    let mut ground = Gm::new(
+        Mesh::new(&context, &CpuMesh::square()),
+        PhysicalMaterial::new_opaque(
+            &context,
+            &CpuMaterial {
+                albedo: Srgba::new_opaque(200, 200, 200),
+                ..Default::default()
+            },
+        ),
+    );
+    ground.set_transformation(Mat4::from_angle_x(degrees(-90.0)) * Mat4::from_scale(8.0));
+
+    let ambient = AmbientLight::new(&context, 0.3, Srgba::WHITE);
+    let sun = DirectionalLight::new(&context, 2.0, Srgba::WHITE, vec3(-0.5, -1.0, -0.3));
+
+    let mut fixed_timestep = FixedTimestep::new(60.0);
+    let mut updates_per_second = 60.0;
+    let mut drop_frames = false;
+    let mut interpolate = true;
+
+    window.render_loop(move |mut frame_input| {
+        // Simulate long frames, for example when the window is dragged
+        let elapsed_time = frame_input.elapsed_time + if drop_frames { 200.0 } else { 0.0 };
+        fixed_timestep.update(elapsed_time, |dt| {
+            let dt = dt as f32 / 1000.0;
+            for ball in balls.iter_mut() {
+                ball.previous_position = ball.position;
+                ball.velocity.y -= 9.82 * dt;
+                ball.position += ball.velocity * dt;
+                if ball.position.y < radius {
+                    ball.position.y = radius;
+                    ball.velocity.y = ball.velocity.y.abs() * 0.9;
+                }
+            }
+        });
+
+        gui.update(
+            &mut frame_input.events,
+            frame_input.accumulated_time,
+            frame_input.viewport,
+            frame_input.device_pixel_ratio,
+            |gui_context| {
+                use three_d::egui::*;
+                Window::new("Fixed timestep").show(gui_context, |ui| {
+                    if ui
+                        .add(
+                            Slider::new(&mut updates_per_second, 5.0..=240.0)
+                                .text("Updates per second"),
+                        )
+                        .changed()
+                    {
+                        fixed_timestep.set_updates_per_second(updates_per_second);
+                    }
+                    ui.checkbox(&mut interpolate, "Interpolate");
+                    ui.checkbox(&mut drop_frames, "Drop frames");
+                    ui.label(format!("Fixed updates: {}", fixed_timestep.step_count()));
+                });
+            },
+        );
+
+        camera.set_viewport(frame_input.viewport);
+        control.handle_events(&mut camera, &mut frame_input.events);
+
+        let alpha = if interpolate {
+            fixed_timestep.alpha() as f32
+        } else {
+            1.0
+        };
+        for (ball, sphere) in balls.iter().zip(spheres.iter_mut()) {
+            let position = ball.previous_position.lerp(ball.position, alpha);
+            sphere.set_transformation(Mat4::from_translation(position) * Mat4::from_scale(radius));
+        }
+
+        frame_input
+            .screen()
+            .clear(ClearState::color_and_depth(0.8, 0.8, 0.8, 1.0, 1.0))
+            .render(&camera, spheres.iter().chain([&ground]), &[&ambient, &sun])
+            .write(|| gui.render())
+            .unwrap();
+
+        FrameOutput::default()
+    });
+}
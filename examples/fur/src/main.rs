@@ -0,0 +1,175 @@
+// Entry point for non-wasm
+#[cfg(not(target_arch = "wasm32"))]
+#[tokio::main]
+async fn main() {
+    run().await;
+}
+
+use three_d::*;
+
+pub async fn run() {
+    let window = Window::new(WindowSettings {
+        title: "Fur!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl();
+
+    let mut camera = Camera::new_perspective(
+        window.viewport(),
+        vec3(4.0, 3.0, 6.0),
+        vec3(0.0, 1.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        100.0,
+    );
+    let mut control = OrbitControl::new(camera.target(), 2.0, 30.0);
+
+    // A fuzzy ball
+    let mut ball_mesh = CpuMesh::sphere(64);
+    ball_mesh.uvs = Some(
+        ball_mesh
+            .positions
+            .to_f32()
+            .iter()
+            .map(|p| {
+                vec2(
+                    0.5 + p.z.atan2(p.x) / (2.0 * std::f32::consts::PI),
+                    p.y.clamp(-1.0, 1.0).acos() / std::f32::consts::PI,
+                )
+            })
+            .collect(),
+    );
+    let mut ball = Gm::new(
+        Shells::new(&context, &ball_mesh, 32, 0.25),
+        FurMaterial::new(
+            density_texture(&context, 128, 0.6, Mat3::from_nonuniform_scale(4.0, 2.0)),
+            Srgba::new_opaque(110, 70, 40),
+        ),
+    );
+    ball.set_transformation(Mat4::from_translation(vec3(0.0, 1.25, 0.0)));
+    ball.geometry.gravity = vec3(0.0, -0.6, 0.0);
+    ball.material.tip_color = Srgba::new_opaque(230, 200, 160);
+
+    // A patch of grass
+    let mut grass = Gm::new(
+        Shells::new(&context, &CpuMesh::square(), 32, 0.4),
+        FurMaterial::new(
+            density_texture(&context, 256, 0.3, Mat3::from_scale(4.0)),
+            Srgba::new_opaque(30, 70, 20),
+        ),
+    );
+    grass.set_transformation(Mat4::from_angle_x(degrees(-90.0)) * Mat4::from_scale(5.0));
+    grass.geometry.wind = vec3(0.3, 0.0, 0.1);
+    grass.material.tip_color = Srgba::new_opaque(170, 200, 80);
+    grass.material.thickness = 0.6;
+
+    let ambient = AmbientLight::new(&context, 0.4, Srgba::WHITE);
+    let mut sun = DirectionalLight::new(
+        &context,
+        2.0,
+        Srgba::new_opaque(255, 245, 230),
+        vec3(-1.0, -1.0, -0.6),
+    );
+
+    let mut gui = three_d::GUI::new(&context);
+    let mut shell_count = 32;
+    let mut ball_length = 0.25;
+    let mut grass_length = 0.4;
+    let mut wind = 0.3;
+    let mut shadows = true;
+    window.render_loop(move |mut frame_input| {
+        let mut panel_width = 0.0;
+        gui.update(
+            &mut frame_input.events,
+            frame_input.accumulated_time,
+            frame_input.viewport,
+            frame_input.device_pixel_ratio,
+            |gui_context| {
+                use three_d::egui::*;
+                SidePanel::left("side_panel").show(gui_context, |ui| {
+                    ui.heading("Fur");
+                    ui.add(Slider::new(&mut shell_count, 1..=64).text("Shells"));
+                    ui.add(Slider::new(&mut ball_length, 0.0..=0.5).text("Fur length"));
+                    ui.add(Slider::new(&mut grass_length, 0.0..=1.0).text("Grass length"));
+                    ui.add(Slider::new(&mut ball.material.thickness, 0.1..=1.0).text("Thickness"));
+                    ui.add(Slider::new(&mut ball.geometry.gravity.y, -1.0..=0.0).text("Gravity"));
+                    ui.add(Slider::new(&mut wind, 0.0..=1.0).text("Wind"));
+                    ui.checkbox(&mut shadows, "Shadows");
+                });
+                panel_width = gui_context.used_rect().width();
+            },
+        );
+
+        for shells in [&mut ball.geometry, &mut grass.geometry] {
+            shells.set_shell_count(shell_count);
+        }
+        ball.geometry.length = ball_length;
+        grass.geometry.length = grass_length;
+        grass.geometry.wind = vec3(wind, 0.0, 0.3 * wind);
+        ball.geometry.wind = 0.3 * vec3(wind, 0.0, 0.3 * wind);
+        ball.animate(frame_input.accumulated_time as f32);
+        grass.animate(frame_input.accumulated_time as f32);
+
+        let viewport = frame_input.viewport_from_logical(
+            panel_width,
+            0.0,
+            frame_input.window_width as f32 - panel_width,
+            frame_input.window_height as f32,
+        );
+        camera.set_viewport(viewport);
+        control.handle_events(&mut camera, &mut frame_input.events);
+
+        if shadows {
+            // Only the inner shells cast shadows
+            sun.generate_shadow_map(1024, [ball.geometry.base(), grass.geometry.base()])
+                .unwrap();
+        } else {
+            sun.clear_shadow_map();
+        }
+
+        frame_input
+            .screen()
+            .clear(ClearState::color_and_depth(0.6, 0.75, 0.95, 1.0, 1.0))
+            .render(&camera, [&ball as &dyn Object, &grass], &[&ambient, &sun])
+            .write(|| gui.render())
+            .unwrap();
+
+        FrameOutput::default()
+    });
+}
+
+///
+/// Creates a texture with a random strand length between the given minimum length and 1 in each texel, tiled using the given transformation.
+///
+fn density_texture(
+    context: &Context,
+    size: u32,
+    min_length: f32,
+    transformation: Mat3,
+) -> Texture2DRef {
+    let mut seed = 12345u32;
+    let data = (0..size * size)
+        .map(|_| {
+            seed = seed.wrapping_mul(1664525).wrapping_add(1013904223);
+            let random = (seed >> 8) as f32 / (1u32 << 24) as f32;
+            ((min_length + (1.0 - min_length) * random) * 255.0) as u8
+        })
+        .collect();
+    let mut texture = Texture2DRef::from_cpu_texture(
+        context,
+        &CpuTexture {
+            data: TextureData::RU8(data),
+            width: size,
+            height: size,
+            min_filter: Interpolation::Nearest,
+            mag_filter: Interpolation::Nearest,
+            mipmap: None,
+            ..Default::default()
+        },
+    );
+    texture.transformation = transformation;
+    texture
+}
@@ -49,11 +49,7 @@ pub async fn run() {
 
     let axes = Axes::new(&context, 0.01, 0.1);
     let c = -axes.aabb().center();
-    let mut axes_mesh = Gm {
-        // geometry: Mesh::new(axes.into(), &point_mesh),
-        geometry: axes,
-        material: ColorMaterial::default(),
-    };
+    let mut axes_mesh = Gm::new(axes, ColorMaterial::default());
 
     // find translation between view center and axes location
     axes_mesh.set_transformation(Mat4::from_translation(c));
@@ -64,58 +60,61 @@ pub async fn run() {
 
     // these axes don't move (maybe they'd be better in a different color)
     let static_axes = Axes::new(&context, 0.01, 0.1);
-    let mut static_axes_mesh = Gm {
-        geometry: static_axes,
-        material: ColorMaterial::default(),
-    };
+    let mut static_axes_mesh = Gm::new(static_axes, ColorMaterial::default());
     static_axes_mesh.set_transformation(Mat4::from_translation(c));
 
+    // dragging a slider should not rotate the camera and rotating the camera should not stop over the panel
+    let mut event_router = EventRouter::new();
+
     // main loop
     window.render_loop(move |mut frame_input| {
         const VPRT_BOUND: f32 = 90.0;
         let mut panel_width = 0.0;
-        gui.update(
-            &mut frame_input.events,
-            frame_input.accumulated_time,
-            frame_input.viewport,
-            frame_input.device_pixel_ratio,
-            |gui_context| {
-                use three_d::egui::*;
-                SidePanel::left("side_panel").show(gui_context, |ui| {
-                    ui.heading("Debug Panel");
-
-                    ui.add(
-                        Slider::new(&mut app.pitch_deg, -VPRT_BOUND..=VPRT_BOUND).text("Pitch (°)"),
-                    );
-                    ui.add(
-                        Slider::new(&mut app.roll_deg, -VPRT_BOUND..=VPRT_BOUND).text("Roll (°)"),
-                    );
-
-                    ui.label(format!("Camera View: {:?}", camera.position()));
-                    ui.end_row();
-
-                    // ui.label(format!("pitch={:?}", pitch_matrix));
-
-                    ui.separator();
-                });
-                panel_width = gui_context.used_rect().width();
-            },
-        );
+        event_router.handle_gui_events(&mut frame_input.events, |events| {
+            gui.update(
+                events,
+                frame_input.accumulated_time,
+                frame_input.viewport,
+                frame_input.device_pixel_ratio,
+                |gui_context| {
+                    use three_d::egui::*;
+                    SidePanel::left("side_panel").show(gui_context, |ui| {
+                        ui.heading("Debug Panel");
+
+                        ui.add(
+                            Slider::new(&mut app.pitch_deg, -VPRT_BOUND..=VPRT_BOUND)
+                                .text("Pitch (°)"),
+                        );
+                        ui.add(
+                            Slider::new(&mut app.roll_deg, -VPRT_BOUND..=VPRT_BOUND)
+                                .text("Roll (°)"),
+                        );
+
+                        ui.label(format!("Camera View: {:?}", camera.position()));
+                        ui.end_row();
+
+                        // ui.label(format!("pitch={:?}", pitch_matrix));
+
+                        ui.separator();
+                    });
+                    panel_width = gui_context.used_rect().width();
+                },
+            )
+        });
 
         // camera.position()
 
         // where camera goes
-        let viewport = Viewport {
-            x: (panel_width * frame_input.device_pixel_ratio) as i32,
-            y: 0,
-            width: frame_input.viewport.width
-                - (panel_width * frame_input.device_pixel_ratio) as u32,
-            height: frame_input.viewport.height,
-        };
+        let viewport = frame_input.viewport_from_logical(
+            panel_width,
+            0.0,
+            frame_input.window_width as f32 - panel_width,
+            frame_input.window_height as f32,
+        );
 
         camera.set_viewport(viewport);
 
-        // camera drag control
+        // camera drag control, only gets the events that are not used by the gui
         control.handle_events(&mut camera, &mut frame_input.events);
 
         let rotation = Euler {
@@ -137,11 +136,11 @@ pub async fn run() {
             .screen()
             .clear(ClearState::color_and_depth(1.0, 1.0, 1.0, 1.0, 1.0))
             .clear_partially(
-                viewport.into(),
+                viewport,
                 ClearState::color_and_depth(1.0, 1.0, 1.0, 1.0, 1.0),
             )
             .render_partially(
-                viewport.into(),
+                viewport,
                 &camera,
                 // &axes_mesh,
                 axes_mesh.into_iter().chain(&static_axes_mesh),
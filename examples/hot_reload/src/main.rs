@@ -0,0 +1,60 @@
+use three_d::*;
+
+// Reloads the model each time the file is saved, for example from Blender, while keeping the camera.
+// Run with the path to a glTF file as argument.
+pub fn main() {
+    let path = std::env::args()
+        .nth(1)
+        .unwrap_or("examples/assets/gltf/DamagedHelmet.glb".to_string());
+
+    let window = Window::new(WindowSettings {
+        title: "Hot reload!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl();
+
+    let mut camera = Camera::new_perspective(
+        window.viewport(),
+        vec3(3.0, 2.0, 4.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        1000.0,
+    );
+    let mut control = OrbitControl::new(camera.target(), 1.0, 100.0);
+
+    let mut watcher = FileWatcher::new(&path);
+    let mut model = Model::<PhysicalMaterial>::new(&context, &load(&path).unwrap()).unwrap();
+
+    let ambient = AmbientLight::new(&context, 0.4, Srgba::WHITE);
+    let directional = DirectionalLight::new(&context, 2.0, Srgba::WHITE, vec3(-1.0, -1.0, -1.0));
+
+    window.render_loop(move |mut frame_input| {
+        if watcher.changed() {
+            // A file that is only partly written or invalid is ignored until the next time it is saved
+            match load(&path).and_then(|cpu_model| Ok(model.reload(&context, &cpu_model)?)) {
+                Ok(log) => println!("Reloaded {}: {}", path, log),
+                Err(error) => println!("Failed to reload {}: {}", path, error),
+            }
+        }
+
+        camera.set_viewport(frame_input.viewport);
+        control.handle_events(&mut camera, &mut frame_input.events);
+        model.animate(0.001 * frame_input.accumulated_time as f32);
+
+        frame_input
+            .screen()
+            .clear(ClearState::color_and_depth(0.5, 0.5, 0.5, 1.0, 1.0))
+            .render(&camera, &model, &[&ambient, &directional]);
+
+        FrameOutput::default()
+    });
+}
+
+fn load(path: &str) -> Result<CpuModel, Box<dyn std::error::Error>> {
+    let mut loaded = three_d_asset::io::load(&[path])?;
+    Ok(loaded.deserialize(path)?)
+}
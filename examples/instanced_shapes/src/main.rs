@@ -42,6 +42,7 @@ pub fn main() {
             },
         ),
     );
+    instanced_mesh.set_instance_layout(InstanceLayout::PositionScaleRotation);
     instanced_mesh.set_animation(|time| Mat4::from_angle_x(Rad(time)));
 
     // Initial properties of the example, 2 cubes per side and non instanced.
@@ -75,13 +76,12 @@ pub fn main() {
                 panel_width = gui_context.used_rect().width();
             },
         );
-        let viewport = Viewport {
-            x: (panel_width * frame_input.device_pixel_ratio) as i32,
-            y: 0,
-            width: frame_input.viewport.width
-                - (panel_width * frame_input.device_pixel_ratio) as u32,
-            height: frame_input.viewport.height,
-        };
+        let viewport = frame_input.viewport_from_logical(
+            panel_width,
+            0.0,
+            frame_input.window_width as f32 - panel_width,
+            frame_input.window_height as f32,
+        );
         camera.set_viewport(viewport);
 
         // Camera control must be after the gui update.
@@ -119,18 +119,19 @@ pub fn main() {
         }
 
         if instanced_mesh.instance_count() != count as u32 {
+            // The instances are only translated, so only the positions are needed
             instanced_mesh.set_instances(&Instances {
-                transformations: (0..count)
-                    .map(|i| {
-                        let x = (i % side_count) as f32;
-                        let y =
-                            ((i as f32 / side_count as f32).floor() as usize % side_count) as f32;
-                        let z = (i as f32 / side_count.pow(2) as f32).floor();
-                        Mat4::from_translation(
-                            3.0 * vec3(x, y, z) - 1.5 * (side_count as f32) * vec3(1.0, 1.0, 1.0),
-                        )
-                    })
-                    .collect(),
+                positions: Some(
+                    (0..count)
+                        .map(|i| {
+                            let x = (i % side_count) as f32;
+                            let y = ((i as f32 / side_count as f32).floor() as usize % side_count)
+                                as f32;
+                            let z = (i as f32 / side_count.pow(2) as f32).floor();
+                            3.0 * vec3(x, y, z) - 1.5 * (side_count as f32) * vec3(1.0, 1.0, 1.0)
+                        })
+                        .collect(),
+                ),
                 ..Default::default()
             });
         }
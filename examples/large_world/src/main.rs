@@ -0,0 +1,103 @@
+use three_d::*;
+
+pub fn main() {
+    let window = Window::new(WindowSettings {
+        title: "Large world!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl();
+
+    // The model is placed ten million units from the origin
+    let model_position = DVec3::new(6_000_000.0, 0.0, 8_000_000.0);
+
+    let mut camera_relative = true;
+    context.set_world_origin(model_position);
+    let mut camera = Camera::new_perspective(
+        window.viewport(),
+        vec3(3.0, 2.0, 4.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        1000.0,
+    );
+    let mut control = OrbitControl::new(camera.target(), 1.0, 100.0);
+    let mut gui = three_d::GUI::new(&context);
+
+    let mut model = Gm::new(
+        Mesh::new(&context, &CpuMesh::cube()),
+        PhysicalMaterial::new_opaque(
+            &context,
+            &CpuMaterial {
+                albedo: Srgba::new_opaque(200, 180, 160),
+                ..Default::default()
+            },
+        ),
+    );
+    let ambient = AmbientLight::new(&context, 0.4, Srgba::WHITE);
+    let sun = DirectionalLight::new(&context, 2.0, Srgba::WHITE, vec3(-0.5, -1.0, -0.3));
+
+    window.render_loop(move |mut frame_input| {
+        let mut change_mode = false;
+        gui.update(
+            &mut frame_input.events,
+            frame_input.accumulated_time,
+            frame_input.viewport,
+            frame_input.device_pixel_ratio,
+            |gui_context| {
+                use three_d::egui::*;
+                Window::new("Large world").show(gui_context, |ui| {
+                    change_mode = ui
+                        .checkbox(&mut camera_relative, "Camera-relative rendering")
+                        .changed();
+                    let origin = context.world_origin();
+                    ui.label(format!(
+                        "World origin: ({:.1}, {:.1}, {:.1})",
+                        origin.x, origin.y, origin.z
+                    ));
+                });
+            },
+        );
+
+        if change_mode {
+            // Move the camera to the new render space, keeping it at the same position in world space
+            let position = context.to_world_space(camera.position());
+            let target = context.to_world_space(control.target);
+            context.set_world_origin(if camera_relative {
+                model_position
+            } else {
+                DVec3::new(0.0, 0.0, 0.0)
+            });
+            control.target = context.to_render_space(target);
+            let up = camera.up();
+            camera.set_view(context.to_render_space(position), control.target, up);
+        }
+
+        camera.set_viewport(frame_input.viewport);
+        control.handle_events(&mut camera, &mut frame_input.events);
+
+        if camera_relative {
+            // Keep the camera close to the origin of the render space
+            if camera.position().magnitude() > 100.0 {
+                control.target -= recenter_world_origin(&context, &mut camera);
+            }
+            model.set_transformation_f64(DMat4::from_translation(model_position));
+        } else {
+            // The single precision translation cannot represent the position of the model accurately
+            model.set_transformation(Mat4::from_translation(
+                model_position.cast::<f32>().unwrap(),
+            ));
+        }
+
+        frame_input
+            .screen()
+            .clear(ClearState::color_and_depth(0.8, 0.8, 0.8, 1.0, 1.0))
+            .render(&camera, &model, &[&ambient, &sun])
+            .write(|| gui.render())
+            .unwrap();
+
+        FrameOutput::default()
+    });
+}
@@ -0,0 +1,175 @@
+use three_d::*;
+
+pub fn main() {
+    let window = Window::new(WindowSettings {
+        title: "Light flares!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl();
+
+    let mut camera = Camera::new_perspective(
+        window.viewport(),
+        vec3(8.0, 3.0, 8.0),
+        vec3(0.0, 1.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        100.0,
+    );
+    let mut control = OrbitControl::new(camera.target(), 1.0, 50.0);
+    let mut gui = three_d::GUI::new(&context);
+
+    let mut cpu_floor = CpuMesh::square();
+    cpu_floor
+        .transform(Mat4::from_scale(10.0) * Mat4::from_angle_x(degrees(-90.0)))
+        .unwrap();
+    let mut objects = vec![Gm::new(
+        Mesh::new(&context, &cpu_floor),
+        PhysicalMaterial::new_opaque(
+            &context,
+            &CpuMaterial {
+                albedo: Srgba::new_opaque(120, 120, 130),
+                ..Default::default()
+            },
+        ),
+    )];
+    // Columns which the lights move behind
+    for i in 0..6 {
+        let angle = i as f32 * std::f32::consts::TAU / 6.0;
+        let mut column = Gm::new(
+            Mesh::new(&context, &CpuMesh::cylinder(16)),
+            PhysicalMaterial::new_opaque(
+                &context,
+                &CpuMaterial {
+                    albedo: Srgba::new_opaque(200, 190, 170),
+                    ..Default::default()
+                },
+            ),
+        );
+        column.set_transformation(
+            Mat4::from_translation(vec3(3.0 * angle.cos(), 0.0, 3.0 * angle.sin()))
+                * Mat4::from_angle_z(degrees(90.0))
+                * Mat4::from_nonuniform_scale(4.0, 0.3, 0.3),
+        );
+        objects.push(column);
+    }
+
+    let ambient = AmbientLight::new(&context, 0.1, Srgba::WHITE);
+    let mut lights = [
+        Srgba::new_opaque(255, 180, 100),
+        Srgba::new_opaque(100, 180, 255),
+        Srgba::new_opaque(255, 100, 180),
+    ]
+    .map(|color| {
+        PointLight::new(
+            &context,
+            2.0,
+            color,
+            vec3(0.0, 0.0, 0.0),
+            Attenuation {
+                constant: 0.0,
+                linear: 0.2,
+                quadratic: 0.1,
+            },
+        )
+    });
+    let mut flares = lights
+        .each_ref()
+        .map(|light| LightFlare::new_from_point_light(&context, light, FlareSize::World(0.5)));
+    let streak = LightFlare::streak_texture(&context);
+
+    let mut depth_texture = DepthTexture2D::new::<f32>(
+        &context,
+        camera.viewport().width,
+        camera.viewport().height,
+        Wrapping::ClampToEdge,
+        Wrapping::ClampToEdge,
+    );
+    let mut effect = LightFlareEffect::default();
+    let mut occlusion_fading = true;
+    let mut screen_size = false;
+    let mut size = 0.5;
+    let mut show_streak = false;
+    let mut streak_length = 4.0;
+    window.render_loop(move |mut frame_input| {
+        gui.update(
+            &mut frame_input.events,
+            frame_input.accumulated_time,
+            frame_input.viewport,
+            frame_input.device_pixel_ratio,
+            |gui_context| {
+                use three_d::egui::*;
+                Window::new("Light flares").show(gui_context, |ui| {
+                    ui.checkbox(&mut occlusion_fading, "Occlusion fading");
+                    ui.add(
+                        Slider::new(&mut effect.occlusion_radius, 1.0..=32.0)
+                            .text("Occlusion radius"),
+                    );
+                    ui.checkbox(&mut screen_size, "Constant screen size");
+                    ui.add(Slider::new(&mut size, 0.1..=2.0).text("Size"));
+                    ui.checkbox(&mut show_streak, "Anamorphic streak");
+                    ui.add(Slider::new(&mut streak_length, 1.0..=10.0).text("Streak length"));
+                });
+            },
+        );
+
+        if camera.set_viewport(frame_input.viewport) {
+            depth_texture = DepthTexture2D::new::<f32>(
+                &context,
+                frame_input.viewport.width,
+                frame_input.viewport.height,
+                Wrapping::ClampToEdge,
+                Wrapping::ClampToEdge,
+            );
+        }
+        control.handle_events(&mut camera, &mut frame_input.events);
+
+        let time = 0.0005 * frame_input.accumulated_time as f32;
+        for (i, (light, flare)) in lights.iter_mut().zip(flares.iter_mut()).enumerate() {
+            let angle = time * (1.0 + 0.3 * i as f32) + i as f32 * 2.0;
+            light.position = vec3(4.0 * angle.cos(), 1.0 + 0.5 * i as f32, 4.0 * angle.sin());
+            flare.track_point_light(light);
+            flare.size = if screen_size {
+                FlareSize::Screen(100.0 * size)
+            } else {
+                FlareSize::World(size)
+            };
+            flare.streak = show_streak.then(|| streak.clone());
+            flare.streak_length = streak_length;
+        }
+
+        if occlusion_fading {
+            depth_texture
+                .as_depth_target()
+                .clear(ClearState::depth(1.0))
+                .render(&camera, &objects, &[]);
+        }
+
+        let light_refs: Vec<&dyn Light> = vec![&ambient, &lights[0], &lights[1], &lights[2]];
+        frame_input
+            .screen()
+            .clear(ClearState::color_and_depth(0.02, 0.02, 0.05, 1.0, 1.0))
+            .render(&camera, &objects, &light_refs)
+            .write(|| {
+                for flare in flares.iter() {
+                    if occlusion_fading {
+                        flare.render_with_effect(
+                            &effect,
+                            &camera,
+                            &[],
+                            None,
+                            Some(DepthTexture::Single(&depth_texture)),
+                        );
+                    } else {
+                        flare.render(&camera, &[]);
+                    }
+                }
+                gui.render()
+            })
+            .unwrap();
+
+        FrameOutput::default()
+    });
+}
@@ -0,0 +1,170 @@
+use std::time::Instant;
+use three_d::*;
+
+const LIGHT_COUNT: u32 = 64;
+const ITERATIONS: u32 = 1000;
+
+fn main() {
+    // Create a headless graphics context
+    let context = HeadlessContext::new().unwrap();
+
+    // Create 64 point lights in a grid above the origin
+    let lights = (0..LIGHT_COUNT)
+        .map(|i| {
+            PointLight::new(
+                &context,
+                0.5,
+                Srgba::new((i * 4 % 256) as u8, (255 - i * 4 % 256) as u8, 128, 255),
+                vec3((i % 8) as f32 - 3.5, 2.0, (i / 8) as f32 - 3.5),
+                Attenuation {
+                    constant: 0.5,
+                    linear: 0.1,
+                    quadratic: 0.05,
+                },
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let vertex_shader = "
+        in vec3 position;
+        void main() {
+            gl_Position = vec4(position, 1.0);
+        }";
+    // All uniforms are used in the output, otherwise they are removed by the shader compiler
+    let fragment_shader = |declaration: &dyn Fn(u32) -> String, sum: &dyn Fn(u32) -> String| {
+        let mut source = String::new();
+        for i in 0..LIGHT_COUNT {
+            source.push_str(&declaration(i));
+        }
+        source.push_str(
+            "layout (location = 0) out vec4 outColor;\nvoid main() {\n    vec3 sum = vec3(0.0);\n",
+        );
+        for i in 0..LIGHT_COUNT {
+            source.push_str(&sum(i));
+        }
+        source.push_str("    outColor = vec4(sum, 1.0);\n}\n");
+        source
+    };
+
+    // The way the light uniforms were sent before, three uniforms per light each with a formatted name
+    let separate_program = Program::from_source(
+        &context,
+        vertex_shader,
+        &fragment_shader(
+            &|i| {
+                format!("uniform vec3 color{i};\nuniform vec3 attenuation{i};\nuniform vec3 position{i};\n")
+            },
+            &|i| format!("    sum += color{i} + attenuation{i} + position{i};\n"),
+        ),
+    )
+    .unwrap();
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        for (i, light) in lights.iter().enumerate() {
+            separate_program.use_uniform(
+                &format!("color{}", i),
+                light.color.to_linear_srgb().truncate() * light.intensity,
+            );
+            separate_program.use_uniform(
+                &format!("attenuation{}", i),
+                vec3(
+                    light.attenuation.constant,
+                    light.attenuation.linear,
+                    light.attenuation.quadratic,
+                ),
+            );
+            separate_program.use_uniform(&format!("position{}", i), light.position);
+        }
+    }
+    let separate = start.elapsed();
+
+    // The way the light uniforms are sent now, a single uniform array per light
+    let array_program = Program::from_source(
+        &context,
+        vertex_shader,
+        &fragment_shader(
+            &|i| format!("uniform vec4 lightParameters{i}[3];\n"),
+            &|i| {
+                format!("    sum += lightParameters{i}[0].xyz + lightParameters{i}[1].xyz + lightParameters{i}[2].xyz;\n")
+            },
+        ),
+    )
+    .unwrap();
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        for (i, light) in lights.iter().enumerate() {
+            light.use_uniforms(&array_program, i as u32);
+        }
+    }
+    let array = start.elapsed();
+
+    println!(
+        "Sending the uniforms of {} point lights, averaged over {} iterations:",
+        LIGHT_COUNT, ITERATIONS
+    );
+    println!(
+        "  One uniform at a time: {:.3} ms",
+        separate.as_secs_f64() * 1000.0 / ITERATIONS as f64
+    );
+    println!(
+        "  One uniform array per light: {:.3} ms",
+        array.as_secs_f64() * 1000.0 / ITERATIONS as f64
+    );
+
+    // Render a sphere lit by all of the lights to measure the time of a full frame
+    let viewport = Viewport::new_at_origo(512, 512);
+    let camera = Camera::new_perspective(
+        viewport,
+        vec3(0.0, 6.0, 8.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        100.0,
+    );
+    let sphere = Gm::new(
+        Mesh::new(&context, &CpuMesh::sphere(32)),
+        PhysicalMaterial::new_opaque(&context, &CpuMaterial::default()),
+    );
+    let lights = lights.iter().map(|l| l as &dyn Light).collect::<Vec<_>>();
+
+    let mut texture = Texture2D::new_empty::<[u8; 4]>(
+        &context,
+        viewport.width,
+        viewport.height,
+        Interpolation::Nearest,
+        Interpolation::Nearest,
+        None,
+        Wrapping::ClampToEdge,
+        Wrapping::ClampToEdge,
+    );
+    let mut depth_texture = DepthTexture2D::new::<f32>(
+        &context,
+        viewport.width,
+        viewport.height,
+        Wrapping::ClampToEdge,
+        Wrapping::ClampToEdge,
+    );
+    let render = |texture: &mut Texture2D, depth_texture: &mut DepthTexture2D| {
+        RenderTarget::new(
+            texture.as_color_target(None),
+            depth_texture.as_depth_target(),
+        )
+        .clear(ClearState::color_and_depth(0.0, 0.0, 0.0, 1.0, 1.0))
+        .render(&camera, &sphere, &lights)
+        // Reading the pixels waits for the rendering to finish
+        .read_color::<[u8; 4]>()
+    };
+
+    // The first frame compiles the shader
+    render(&mut texture, &mut depth_texture);
+    let start = Instant::now();
+    for _ in 0..ITERATIONS / 10 {
+        render(&mut texture, &mut depth_texture);
+    }
+    println!(
+        "Rendering a sphere lit by {} point lights: {:.3} ms per frame",
+        LIGHT_COUNT,
+        start.elapsed().as_secs_f64() * 1000.0 / (ITERATIONS / 10) as f64
+    );
+}
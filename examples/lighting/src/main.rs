@@ -96,6 +96,27 @@ pub async fn run() {
             quadratic: 0.0001,
         },
     );
+    // A window pane pattern projected by the spot light
+    let cookie = Texture2DRef::from_cpu_texture(
+        &context,
+        &CpuTexture {
+            data: TextureData::RgbaU8(
+                (0..64 * 64)
+                    .map(|i| {
+                        let (x, y) = (i % 64, i / 64);
+                        if x % 32 < 3 || y % 32 < 3 {
+                            [0, 0, 0, 255]
+                        } else {
+                            [255, 255, 255, 255]
+                        }
+                    })
+                    .collect(),
+            ),
+            width: 64,
+            height: 64,
+            ..Default::default()
+        },
+    );
     let mut point0 = PointLight::new(
         &context,
         1.0,
@@ -121,6 +142,11 @@ pub async fn run() {
 
     // main loop
     let mut shadows_enabled = true;
+    let mut shadow_softness = 0.0;
+    let mut shadow_samples = 16;
+    let mut shadow_bias = 0.0;
+    let mut angular_size = 0.0;
+    let mut cookie_enabled = false;
     let mut material_type = MaterialType::Forward;
 
     window.render_loop(move |mut frame_input| {
@@ -165,13 +191,20 @@ pub async fn run() {
                             .text("Directional 1 intensity"),
                     );
                     ui.add(Slider::new(&mut spot0.intensity, 0.0..=10.0).text("Spot intensity"));
+                    if ui.checkbox(&mut cookie_enabled, "Spot cookie").clicked() {
+                        spot0.cookie_texture = cookie_enabled.then(|| cookie.clone());
+                    }
                     ui.add(Slider::new(&mut point0.intensity, 0.0..=1.0).text("Point 0 intensity"));
                     ui.add(Slider::new(&mut point1.intensity, 0.0..=1.0).text("Point 1 intensity"));
+                    ui.add(Slider::new(&mut angular_size, 0.0..=10.0).text("Light angular size"));
                     if ui.checkbox(&mut shadows_enabled, "Shadows").clicked() && !shadows_enabled {
                         spot0.clear_shadow_map();
                         directional0.clear_shadow_map();
                         directional1.clear_shadow_map();
                     }
+                    ui.add(Slider::new(&mut shadow_softness, 0.0..=8.0).text("Shadow softness"));
+                    ui.add(Slider::new(&mut shadow_samples, 1..=64).text("Shadow samples"));
+                    ui.add(Slider::new(&mut shadow_bias, 0.0..=0.05).text("Shadow bias"));
 
                     ui.label("Lighting model");
                     ui.radio_value(
@@ -208,6 +241,26 @@ pub async fn run() {
                         ),
                         "Cook (Trowbridge-Reitz GGX)",
                     );
+                    ui.radio_value(
+                        &mut model.material.lighting_model,
+                        LightingModel::Cook(
+                            NormalDistributionFunction::TrowbridgeReitzGGX,
+                            GeometryFunction::SmithHeightCorrelatedGGX,
+                        ),
+                        "Cook (Trowbridge-Reitz GGX, height-correlated)",
+                    );
+
+                    ui.label("Diffuse model");
+                    ui.radio_value(
+                        &mut model.material.diffuse_model,
+                        DiffuseModel::Lambert,
+                        "Lambert",
+                    );
+                    ui.radio_value(
+                        &mut model.material.diffuse_model,
+                        DiffuseModel::OrenNayar,
+                        "Oren-Nayar",
+                    );
 
                     ui.label("Tone mapping");
                     ui.radio_value(&mut camera.tone_mapping, ToneMapping::None, "None");
@@ -229,13 +282,12 @@ pub async fn run() {
             },
         );
 
-        let viewport = Viewport {
-            x: (panel_width * frame_input.device_pixel_ratio) as i32,
-            y: 0,
-            width: frame_input.viewport.width
-                - (panel_width * frame_input.device_pixel_ratio) as u32,
-            height: frame_input.viewport.height,
-        };
+        let viewport = frame_input.viewport_from_logical(
+            panel_width,
+            0.0,
+            frame_input.window_width as f32 - panel_width,
+            frame_input.window_height as f32,
+        );
         camera.set_viewport(viewport);
         control.handle_events(&mut camera, &mut frame_input.events);
 
@@ -249,6 +301,17 @@ pub async fn run() {
         point0.position = vec3(-5.0 * c, 5.0, -5.0 * s);
         point1.position = vec3(5.0 * c, 5.0, 5.0 * s);
 
+        for light in [&mut directional0, &mut directional1] {
+            light.set_shadow_softness(shadow_softness);
+            light.set_shadow_samples(shadow_samples);
+            light.set_shadow_bias(shadow_bias);
+            light.set_angular_size(angular_size);
+        }
+        spot0.set_shadow_softness(shadow_softness);
+        spot0.set_shadow_samples(shadow_samples);
+        spot0.set_shadow_bias(shadow_bias);
+        spot0.set_angular_size(angular_size);
+
         // Draw
         if shadows_enabled {
             directional0.generate_shadow_map(1024, &model).unwrap();
@@ -0,0 +1,217 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+use three_d::*;
+
+pub fn main() {
+    let window = Window::new(WindowSettings {
+        title: "Lightmaps!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl();
+
+    let mut camera = Camera::new_perspective(
+        window.viewport(),
+        vec3(2.5, 2.3, 2.8),
+        vec3(-0.5, 0.8, -1.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(60.0),
+        0.1,
+        100.0,
+    );
+    let mut control = OrbitControl::new(camera.target(), 1.0, 10.0);
+
+    // A room with a window in the back wall, a red and a green wall and two boxes
+    let walls = quads_to_mesh(&[
+        quad(
+            vec3(-3.0, 0.0, -3.0),
+            vec3(0.0, 0.0, 6.0),
+            vec3(6.0, 0.0, 0.0),
+        ),
+        quad(
+            vec3(-3.0, 3.0, -3.0),
+            vec3(6.0, 0.0, 0.0),
+            vec3(0.0, 0.0, 6.0),
+        ),
+        quad(
+            vec3(-3.0, 0.0, 3.0),
+            vec3(0.0, 3.0, 0.0),
+            vec3(6.0, 0.0, 0.0),
+        ),
+        back_wall_piece(-3.0, -1.0, 0.0, 3.0),
+        back_wall_piece(1.0, 3.0, 0.0, 3.0),
+        back_wall_piece(-1.0, 1.0, 0.0, 1.0),
+        back_wall_piece(-1.0, 1.0, 2.0, 3.0),
+    ]);
+    let left_wall = quads_to_mesh(&[quad(
+        vec3(-3.0, 0.0, -3.0),
+        vec3(0.0, 3.0, 0.0),
+        vec3(0.0, 0.0, 6.0),
+    )]);
+    let right_wall = quads_to_mesh(&[quad(
+        vec3(3.0, 0.0, -3.0),
+        vec3(0.0, 0.0, 6.0),
+        vec3(0.0, 3.0, 0.0),
+    )]);
+    let boxes = quads_to_mesh(
+        &[
+            box_quads(vec3(1.2, 0.6, 0.8), vec3(0.6, 0.6, 0.6)),
+            box_quads(vec3(-1.2, 1.0, -0.8), vec3(0.5, 1.0, 0.5)),
+        ]
+        .concat(),
+    );
+
+    let scene = [
+        (walls, Srgba::new_opaque(220, 220, 220), 256),
+        (left_wall, Srgba::new_opaque(200, 40, 40), 64),
+        (right_wall, Srgba::new_opaque(40, 200, 40), 64),
+        (boxes, Srgba::new_opaque(220, 220, 220), 128),
+    ];
+
+    // The lights are only used for baking, they are not used when rendering
+    let sun = DirectionalLight::new(&context, 3.0, Srgba::WHITE, vec3(0.2, -0.5, 1.0));
+    let lamp = PointLight::new(
+        &context,
+        2.0,
+        Srgba::new_opaque(255, 230, 200),
+        vec3(0.0, 2.6, 0.0),
+        Attenuation {
+            constant: 1.0,
+            linear: 0.1,
+            quadratic: 0.1,
+        },
+    );
+
+    let reported = AtomicU32::new(0);
+    let lightmaps = bake_lightmaps(
+        &scene
+            .iter()
+            .map(|((mesh, lightmap_uvs), albedo, size)| LightmapObject {
+                mesh,
+                lightmap_uvs,
+                transformation: Mat4::identity(),
+                albedo: *albedo,
+                width: *size,
+                height: *size,
+            })
+            .collect::<Vec<_>>(),
+        &[&sun],
+        &[&lamp],
+        LightmapSettings::default(),
+        |progress| {
+            let percent = (progress * 10.0) as u32 * 10;
+            if reported.fetch_max(percent, Ordering::Relaxed) < percent {
+                println!("Baking lightmaps: {}%", percent);
+            }
+        },
+    )
+    .unwrap();
+
+    let objects = scene
+        .iter()
+        .zip(lightmaps)
+        .map(|(((cpu_mesh, lightmap_uvs), albedo, _), lightmap)| {
+            let mut mesh = Mesh::new(&context, cpu_mesh);
+            mesh.set_lightmap_uvs(lightmap_uvs);
+            Gm::new(
+                mesh,
+                PhysicalMaterial {
+                    albedo: *albedo,
+                    lightmap_texture: Some(Texture2DRef::from_cpu_texture(&context, &lightmap)),
+                    ..Default::default()
+                },
+            )
+        })
+        .collect::<Vec<_>>();
+
+    window.render_loop(move |mut frame_input| {
+        camera.set_viewport(frame_input.viewport);
+        control.handle_events(&mut camera, &mut frame_input.events);
+
+        frame_input
+            .screen()
+            .clear(ClearState::color_and_depth(0.1, 0.1, 0.1, 1.0, 1.0))
+            .render(&camera, &objects, &[]);
+
+        FrameOutput::default()
+    });
+}
+
+///
+/// Returns the corners of a quad where the normal is the cross product of the two edges.
+///
+fn quad(origin: Vec3, u: Vec3, v: Vec3) -> [Vec3; 4] {
+    [origin, origin + u, origin + u + v, origin + v]
+}
+
+fn back_wall_piece(x0: f32, x1: f32, y0: f32, y1: f32) -> [Vec3; 4] {
+    quad(
+        vec3(x0, y0, -3.0),
+        vec3(x1 - x0, 0.0, 0.0),
+        vec3(0.0, y1 - y0, 0.0),
+    )
+}
+
+///
+/// Returns the top and side quads of a box standing on the floor.
+///
+fn box_quads(center: Vec3, half_size: Vec3) -> Vec<[Vec3; 4]> {
+    let min = center - half_size;
+    let size = half_size * 2.0;
+    let (x, y, z) = (
+        vec3(size.x, 0.0, 0.0),
+        vec3(0.0, size.y, 0.0),
+        vec3(0.0, 0.0, size.z),
+    );
+    vec![
+        quad(min + y, z, x),
+        quad(min + x, y, z),
+        quad(min, z, y),
+        quad(min + z, x, y),
+        quad(min, y, x),
+    ]
+}
+
+///
+/// Creates a mesh from the given quads together with lightmap uv coordinates where each quad is placed in its own cell in a grid.
+///
+fn quads_to_mesh(quads: &[[Vec3; 4]]) -> (CpuMesh, Vec<Vec2>) {
+    let columns = (quads.len() as f32).sqrt().ceil() as usize;
+    let cell_size = 1.0 / columns as f32;
+    let padding = 0.05 * cell_size;
+    let mut positions = Vec::new();
+    let mut normals = Vec::new();
+    let mut lightmap_uvs = Vec::new();
+    let mut indices = Vec::new();
+    for (i, corners) in quads.iter().enumerate() {
+        let offset = positions.len() as u32;
+        let normal = (corners[1] - corners[0])
+            .cross(corners[3] - corners[0])
+            .normalize();
+        let min = vec2(
+            (i % columns) as f32 * cell_size + padding,
+            (i / columns) as f32 * cell_size + padding,
+        );
+        let max = min + vec2(cell_size, cell_size) - vec2(2.0 * padding, 2.0 * padding);
+        positions.extend_from_slice(corners);
+        normals.extend([normal; 4]);
+        lightmap_uvs.extend([min, vec2(max.x, min.y), max, vec2(min.x, max.y)]);
+        indices.extend([
+            offset,
+            offset + 1,
+            offset + 2,
+            offset,
+            offset + 2,
+            offset + 3,
+        ]);
+    }
+    (
+        CpuMesh {
+            positions: Positions::F32(positions),
+            normals: Some(normals),
+            indices: Indices::U32(indices),
+            ..Default::default()
+        },
+        lightmap_uvs,
+    )
+}
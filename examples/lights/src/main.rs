@@ -60,38 +60,47 @@ pub async fn run() {
     let mut quadratic = 0.5;
     let mut light_count = 20;
     let mut color = [1.0; 4];
+    // Makes sure that dragging a slider does not move the camera and that moving the camera does not stop when the mouse passes over the panel
+    let mut event_router = EventRouter::new();
     window.render_loop(move |mut frame_input| {
         let mut panel_width = 0.0;
-        gui.update(
-            &mut frame_input.events,
-            frame_input.accumulated_time,
-            frame_input.viewport,
-            frame_input.device_pixel_ratio,
-            |gui_context| {
-                use three_d::egui::*;
-                SidePanel::left("side_panel").show(gui_context, |ui| {
-                    ui.heading("Debug Panel");
-                    ui.add(Slider::new::<usize>(&mut light_count, 0..=50).text("Light count"));
-                    ui.add(Slider::new::<f32>(&mut intensity, 0.0..=10.0).text("Light intensity"));
-                    ui.add(
-                        Slider::new::<f32>(&mut constant, 0.0..=10.0).text("Attenuation constant"),
-                    );
-                    ui.add(Slider::new::<f32>(&mut linear, 0.01..=1.0).text("Attenuation linear"));
-                    ui.add(
-                        Slider::new::<f32>(&mut quadratic, 0.0001..=1.0)
-                            .text("Attenuation quadratic"),
-                    );
-                    ui.color_edit_button_rgba_unmultiplied(&mut color);
-
-                    ui.label("Tone mapping");
-                    ui.radio_value(&mut camera.tone_mapping, ToneMapping::None, "None");
-                    ui.radio_value(&mut camera.tone_mapping, ToneMapping::Reinhard, "Reinhard");
-                    ui.radio_value(&mut camera.tone_mapping, ToneMapping::Aces, "Aces");
-                    ui.radio_value(&mut camera.tone_mapping, ToneMapping::Filmic, "Filmic");
-                });
-                panel_width = gui_context.used_rect().width();
-            },
-        );
+        event_router.handle_gui_events(&mut frame_input.events, |events| {
+            gui.update(
+                events,
+                frame_input.accumulated_time,
+                frame_input.viewport,
+                frame_input.device_pixel_ratio,
+                |gui_context| {
+                    use three_d::egui::*;
+                    SidePanel::left("side_panel").show(gui_context, |ui| {
+                        ui.heading("Debug Panel");
+                        ui.add(Slider::new::<usize>(&mut light_count, 0..=50).text("Light count"));
+                        ui.add(
+                            Slider::new::<f32>(&mut intensity, 0.0..=10.0).text("Light intensity"),
+                        );
+                        ui.add(
+                            Slider::new::<f32>(&mut constant, 0.0..=10.0)
+                                .text("Attenuation constant"),
+                        );
+                        ui.add(
+                            Slider::new::<f32>(&mut linear, 0.01..=1.0).text("Attenuation linear"),
+                        );
+                        ui.add(
+                            Slider::new::<f32>(&mut quadratic, 0.0001..=1.0)
+                                .text("Attenuation quadratic"),
+                        );
+                        ui.color_edit_button_rgba_unmultiplied(&mut color);
+
+                        ui.label("Tone mapping");
+                        ui.radio_value(&mut camera.tone_mapping, ToneMapping::None, "None");
+                        ui.radio_value(&mut camera.tone_mapping, ToneMapping::Reinhard, "Reinhard");
+                        ui.radio_value(&mut camera.tone_mapping, ToneMapping::Aces, "Aces");
+                        ui.radio_value(&mut camera.tone_mapping, ToneMapping::Filmic, "Filmic");
+                    });
+                    panel_width = gui_context.used_rect().width();
+                },
+            )
+        });
         while lights.len() < light_count {
             lights.push(Glow::new(&context, light_box));
         }
@@ -111,15 +120,15 @@ pub async fn run() {
             );
             light.update(0.00005 * size.magnitude() * frame_input.elapsed_time as f32);
         }
-        let viewport = Viewport {
-            x: (panel_width * frame_input.device_pixel_ratio) as i32,
-            y: 0,
-            width: frame_input.viewport.width
-                - (panel_width * frame_input.device_pixel_ratio) as u32,
-            height: frame_input.viewport.height,
-        };
+        let viewport = frame_input.viewport_from_logical(
+            panel_width,
+            0.0,
+            frame_input.window_width as f32 - panel_width,
+            frame_input.window_height as f32,
+        );
         camera.set_viewport(viewport);
 
+        // The control only gets the events that are not used by the GUI
         control.handle_events(&mut camera, &mut frame_input.events);
 
         frame_input
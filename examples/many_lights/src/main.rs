@@ -0,0 +1,141 @@
+// Entry point for non-wasm
+#[cfg(not(target_arch = "wasm32"))]
+#[tokio::main]
+async fn main() {
+    run().await;
+}
+
+use three_d::*;
+
+pub async fn run() {
+    let window = Window::new(WindowSettings {
+        title: "Many lights!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl();
+
+    let mut camera = Camera::new_perspective(
+        window.viewport(),
+        vec3(0.0, 12.0, 18.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        100.0,
+    );
+    let mut control = OrbitControl::new(camera.target(), 2.0, 50.0);
+
+    // A floor with a field of cubes
+    let material = PhysicalMaterial::new_opaque(
+        &context,
+        &CpuMaterial {
+            albedo: Srgba::new_opaque(200, 200, 200),
+            roughness: 0.5,
+            ..Default::default()
+        },
+    );
+    let mut floor = Gm::new(Mesh::new(&context, &CpuMesh::square()), material.clone());
+    floor.set_transformation(Mat4::from_angle_x(degrees(-90.0)) * Mat4::from_scale(12.0));
+    let mut objects = vec![floor];
+    for x in -5..=5 {
+        for z in -5..=5 {
+            let mut cube = Gm::new(Mesh::new(&context, &CpuMesh::cube()), material.clone());
+            cube.set_transformation(
+                Mat4::from_translation(vec3(2.0 * x as f32, 0.3, 2.0 * z as f32))
+                    * Mat4::from_scale(0.3),
+            );
+            objects.push(cube);
+        }
+    }
+
+    // Lights with random colors and orbits
+    let mut seed = 12345u32;
+    let mut random = move || {
+        seed = seed.wrapping_mul(1664525).wrapping_add(1013904223);
+        (seed >> 8) as f32 / (1u32 << 24) as f32
+    };
+    let orbits = (0..MAX_BUFFERED_LIGHTS)
+        .map(|_| {
+            (
+                1.0 + 10.0 * random(),
+                2.0 * std::f32::consts::PI * random(),
+                random() - 0.5,
+            )
+        })
+        .collect::<Vec<_>>();
+    let mut lights = (0..MAX_BUFFERED_LIGHTS)
+        .map(|_| {
+            let mut channel = || (50.0 + 205.0 * random()) as u8;
+            let color = Srgba::new_opaque(channel(), channel(), channel());
+            PointLight::new(
+                &context,
+                1.0,
+                color,
+                vec3(0.0, 1.0, 0.0),
+                Attenuation {
+                    constant: 0.0,
+                    linear: 0.5,
+                    quadratic: 2.0,
+                },
+            )
+        })
+        .collect::<Vec<_>>();
+    let mut light_buffer = LightBuffer::new(&context, MAX_BUFFERED_LIGHTS);
+    let ambient = AmbientLight::new(&context, 0.05, Srgba::WHITE);
+
+    let mut gui = three_d::GUI::new(&context);
+    let mut light_count = 64;
+    window.render_loop(move |mut frame_input| {
+        let mut panel_width = 0.0;
+        gui.update(
+            &mut frame_input.events,
+            frame_input.accumulated_time,
+            frame_input.viewport,
+            frame_input.device_pixel_ratio,
+            |gui_context| {
+                use three_d::egui::*;
+                SidePanel::left("side_panel").show(gui_context, |ui| {
+                    ui.heading("Many lights");
+                    ui.add(Slider::new(&mut light_count, 0..=MAX_BUFFERED_LIGHTS).text("Lights"));
+                });
+                panel_width = gui_context.used_rect().width();
+            },
+        );
+
+        let viewport = frame_input.viewport_from_logical(
+            panel_width,
+            0.0,
+            frame_input.window_width as f32 - panel_width,
+            frame_input.window_height as f32,
+        );
+        camera.set_viewport(viewport);
+        control.handle_events(&mut camera, &mut frame_input.events);
+
+        let time = 0.001 * frame_input.accumulated_time as f32;
+        for (light, (radius, offset, speed)) in lights.iter_mut().zip(orbits.iter()) {
+            let angle = offset + speed * time;
+            light.position = vec3(radius * angle.cos(), 1.0, radius * angle.sin());
+        }
+
+        // Changing the number of lights does not require new shaders
+        light_buffer
+            .update(
+                lights
+                    .iter()
+                    .take(light_count as usize)
+                    .map(BufferedLight::from),
+            )
+            .unwrap();
+
+        frame_input
+            .screen()
+            .clear(ClearState::color_and_depth(0.0, 0.0, 0.0, 1.0, 1.0))
+            .render(&camera, &objects, &[&ambient, &light_buffer])
+            .write(|| gui.render())
+            .unwrap();
+
+        FrameOutput::default()
+    });
+}
@@ -0,0 +1,138 @@
+use std::time::{Duration, Instant};
+use three_d::*;
+
+// A grid of 317x317 vertices, which is a little more than 100k vertices
+const GRID_SIZE: u32 = 317;
+const FRAMES: u32 = 100;
+
+fn main() {
+    // Create a headless graphics context
+    let context = HeadlessContext::new().unwrap();
+
+    let mut indices = Vec::new();
+    for z in 0..GRID_SIZE - 1 {
+        for x in 0..GRID_SIZE - 1 {
+            let i = z * GRID_SIZE + x;
+            indices.extend_from_slice(&[
+                i,
+                i + GRID_SIZE,
+                i + 1,
+                i + 1,
+                i + GRID_SIZE,
+                i + GRID_SIZE + 1,
+            ]);
+        }
+    }
+
+    // The positions and normals of a wave moving over the grid at the given time, for example the result of a simulation
+    let deform = |time: f32| {
+        let mut positions = Vec::with_capacity((GRID_SIZE * GRID_SIZE) as usize);
+        let mut normals = Vec::with_capacity((GRID_SIZE * GRID_SIZE) as usize);
+        for z in 0..GRID_SIZE {
+            for x in 0..GRID_SIZE {
+                let (u, v) = (
+                    x as f32 / (GRID_SIZE - 1) as f32 * 2.0 - 1.0,
+                    z as f32 / (GRID_SIZE - 1) as f32 * 2.0 - 1.0,
+                );
+                let phase = 10.0 * (u + v) + time;
+                positions.push(vec3(u, 0.1 * phase.sin(), v));
+                normals.push(vec3(-phase.cos(), 1.0, -phase.cos()).normalize());
+            }
+        }
+        (positions, normals)
+    };
+
+    let viewport = Viewport::new_at_origo(256, 256);
+    let camera = Camera::new_perspective(
+        viewport,
+        vec3(0.0, 2.0, 2.5),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        10.0,
+    );
+    let material = NormalMaterial::default();
+    let mut texture = Texture2D::new_empty::<[u8; 4]>(
+        &context,
+        viewport.width,
+        viewport.height,
+        Interpolation::Nearest,
+        Interpolation::Nearest,
+        None,
+        Wrapping::ClampToEdge,
+        Wrapping::ClampToEdge,
+    );
+    let mut depth_texture = DepthTexture2D::new::<f32>(
+        &context,
+        viewport.width,
+        viewport.height,
+        Wrapping::ClampToEdge,
+        Wrapping::ClampToEdge,
+    );
+    let mut render = |mesh: &Mesh| {
+        RenderTarget::new(
+            texture.as_color_target(None),
+            depth_texture.as_depth_target(),
+        )
+        .clear(ClearState::color_and_depth(0.0, 0.0, 0.0, 1.0, 1.0))
+        .render_with_material(&material, &camera, mesh, &[])
+        // Reading the pixels waits for the upload and the rendering to finish
+        .read_color::<[u8; 4]>();
+    };
+
+    // Only the time spent on getting the new positions and normals to the GPU is measured, not the deformation itself
+    let (positions, normals) = deform(0.0);
+    let mut mesh = Mesh::new(
+        &context,
+        &CpuMesh {
+            positions: Positions::F32(positions),
+            indices: Indices::U32(indices.clone()),
+            normals: Some(normals),
+            ..Default::default()
+        },
+    );
+    render(&mesh);
+
+    // Create a new mesh each frame
+    let mut recreate = Duration::ZERO;
+    for frame in 0..FRAMES {
+        let (positions, normals) = deform(frame as f32 * 0.1);
+        let start = Instant::now();
+        mesh = Mesh::new(
+            &context,
+            &CpuMesh {
+                positions: Positions::F32(positions),
+                indices: Indices::U32(indices.clone()),
+                normals: Some(normals),
+                ..Default::default()
+            },
+        );
+        render(&mesh);
+        recreate += start.elapsed();
+    }
+
+    // Update the positions and normals of the existing mesh each frame
+    let mut update = Duration::ZERO;
+    for frame in 0..FRAMES {
+        let (positions, normals) = deform(frame as f32 * 0.1);
+        let start = Instant::now();
+        mesh.update_positions(&positions).unwrap();
+        mesh.update_normals(&normals).unwrap();
+        render(&mesh);
+        update += start.elapsed();
+    }
+
+    let per_frame = |duration: Duration| duration.as_secs_f64() * 1000.0 / FRAMES as f64;
+    println!(
+        "Deforming a mesh with {} vertices, averaged over {} frames:",
+        mesh.vertex_count(),
+        FRAMES
+    );
+    println!("  Creating a new mesh: {:.3} ms", per_frame(recreate));
+    println!("  Updating the existing mesh: {:.3} ms", per_frame(update));
+    println!(
+        "  Speedup: {:.1}x",
+        recreate.as_secs_f64() / update.as_secs_f64()
+    );
+}
@@ -0,0 +1,94 @@
+use three_d::*;
+
+pub fn main() {
+    let window = Window::new(WindowSettings {
+        title: "Navigation widgets!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl();
+
+    let mut camera = Camera::new_perspective(
+        window.viewport(),
+        vec3(5.0, 4.0, 7.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        1000.0,
+    );
+    let mut control = OrbitControl::new(camera.target(), 1.0, 100.0);
+    let mut widgets = NavigationWidgets::new(&context, &camera);
+    let mut gui = three_d::GUI::new(&context);
+
+    let axes = Axes::new(&context, 0.1, 2.0);
+    let mut model = Gm::new(
+        Mesh::new(&context, &CpuMesh::cube()),
+        PhysicalMaterial::new_opaque(
+            &context,
+            &CpuMaterial {
+                albedo: Srgba::new_opaque(200, 180, 160),
+                ..Default::default()
+            },
+        ),
+    );
+    model.set_transformation(Mat4::from_nonuniform_scale(1.5, 0.5, 1.0));
+    let ambient = AmbientLight::new(&context, 0.4, Srgba::WHITE);
+    let sun = DirectionalLight::new(&context, 2.0, Srgba::WHITE, vec3(-0.5, -1.0, -0.3));
+
+    window.render_loop(move |mut frame_input| {
+        gui.update(
+            &mut frame_input.events,
+            frame_input.accumulated_time,
+            frame_input.viewport,
+            frame_input.device_pixel_ratio,
+            |gui_context| {
+                use three_d::egui::*;
+                Window::new("Navigation widgets").show(gui_context, |ui| {
+                    ComboBox::from_label("Corner")
+                        .selected_text(format!("{:?}", widgets.corner))
+                        .show_ui(ui, |ui| {
+                            for corner in [
+                                WidgetCorner::TopLeft,
+                                WidgetCorner::TopRight,
+                                WidgetCorner::BottomLeft,
+                                WidgetCorner::BottomRight,
+                            ] {
+                                ui.selectable_value(
+                                    &mut widgets.corner,
+                                    corner,
+                                    format!("{:?}", corner),
+                                );
+                            }
+                        });
+                    ui.add(Slider::new(&mut widgets.scale, 0.5..=2.0).text("Scale"));
+                    ui.add(Slider::new(&mut widgets.margin, 0.0..=64.0).text("Margin"));
+                    ui.add(
+                        Slider::new(&mut widgets.animation_duration, 0.0..=2.0)
+                            .text("Animation duration"),
+                    );
+                });
+            },
+        );
+
+        camera.set_viewport(frame_input.viewport);
+        widgets.handle_events(
+            &mut camera,
+            &mut control,
+            &mut frame_input.events,
+            frame_input.elapsed_time,
+            frame_input.device_pixel_ratio,
+        );
+        control.handle_events(&mut camera, &mut frame_input.events);
+
+        let screen = frame_input.screen();
+        screen
+            .clear(ClearState::color_and_depth(0.8, 0.8, 0.8, 1.0, 1.0))
+            .render(&camera, axes.into_iter().chain(&model), &[&ambient, &sun]);
+        widgets.render(&screen, &camera);
+        screen.write(|| gui.render()).unwrap();
+
+        FrameOutput::default()
+    });
+}
@@ -0,0 +1,118 @@
+use three_d::*;
+
+pub fn main() {
+    let window = Window::new(WindowSettings {
+        title: "Object Collection!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl();
+
+    let mut camera = Camera::new_perspective(
+        window.viewport(),
+        vec3(6.0, 4.0, 8.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        100.0,
+    );
+    let mut control = OrbitControl::new(camera.target(), 1.0, 100.0);
+
+    // Objects of different types stored in the same vector
+    let scenery: Vec<Box<dyn Object>> = vec![
+        Box::new(Axes::new(&context, 0.05, 2.0)),
+        Box::new(Gm::new(
+            BoundingBox::new(
+                &context,
+                AxisAlignedBoundingBox::new_with_positions(&[
+                    vec3(-4.0, -1.0, -4.0),
+                    vec3(4.0, 3.0, 4.0),
+                ]),
+            ),
+            ColorMaterial {
+                color: Srgba::BLACK,
+                ..Default::default()
+            },
+        )),
+    ];
+
+    // Objects of different types which can be removed by clicking on them
+    let mut collection = ObjectCollection::new();
+    for i in 0..8 {
+        let angle = i as f32 * std::f32::consts::TAU / 8.0;
+        let transformation =
+            Mat4::from_translation(vec3(3.0 * angle.cos(), 0.0, 3.0 * angle.sin()))
+                * Mat4::from_scale(0.5);
+        if i % 2 == 0 {
+            let mut sphere = Gm::new(
+                Mesh::new(&context, &CpuMesh::sphere(16)),
+                PhysicalMaterial::new_opaque(
+                    &context,
+                    &CpuMaterial {
+                        albedo: Srgba::RED,
+                        ..Default::default()
+                    },
+                ),
+            );
+            sphere.set_transformation(transformation);
+            collection.insert(sphere);
+        } else {
+            let mut cube = Gm::new(
+                Mesh::new(&context, &CpuMesh::cube()),
+                ColorMaterial {
+                    color: Srgba::BLUE,
+                    ..Default::default()
+                },
+            );
+            cube.set_transformation(transformation);
+            cube.set_animation(|time| Mat4::from_angle_y(radians(time * 0.001)));
+            collection.insert(cube);
+        }
+    }
+
+    let ambient = AmbientLight::new(&context, 0.4, Srgba::WHITE);
+    let directional = DirectionalLight::new(&context, 2.0, Srgba::WHITE, vec3(-1.0, -1.0, -1.0));
+
+    window.render_loop(move |mut frame_input| {
+        camera.set_viewport(frame_input.viewport);
+
+        for event in frame_input.events.iter() {
+            if let Event::MousePress {
+                button: MouseButton::Left,
+                position,
+                handled: false,
+                ..
+            } = *event
+            {
+                if let Some(pick) =
+                    pick(&context, &camera, position, &collection, Cull::Back).unwrap()
+                {
+                    // The geometry id is the index of the object in the iteration order of the collection
+                    let handles = collection
+                        .iter()
+                        .map(|(handle, _)| handle)
+                        .collect::<Vec<_>>();
+                    collection.remove(handles[pick.geometry_id as usize]);
+                }
+            }
+        }
+        control.handle_events(&mut camera, &mut frame_input.events);
+        collection.animate(frame_input.accumulated_time as f32);
+
+        frame_input
+            .screen()
+            .clear(ClearState::color_and_depth(0.8, 0.8, 0.8, 1.0, 1.0))
+            .render(
+                &camera,
+                scenery
+                    .iter()
+                    .map(|object| object.as_ref())
+                    .chain(&collection),
+                &[&ambient, &directional],
+            );
+
+        FrameOutput::default()
+    });
+}
@@ -0,0 +1,158 @@
+// Entry point for non-wasm
+#[cfg(not(target_arch = "wasm32"))]
+#[tokio::main]
+async fn main() {
+    run().await;
+}
+
+use three_d::*;
+
+pub async fn run() {
+    let window = Window::new(WindowSettings {
+        title: "Occlusion culling!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl();
+
+    let mut camera = Camera::new_perspective(
+        window.viewport(),
+        vec3(1.0, 1.7, 1.0),
+        vec3(10.0, 1.7, 10.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(60.0),
+        0.1,
+        1000.0,
+    );
+    let mut control = FlyControl::new(0.05);
+
+    // A city with a building in each block and a few small objects around the building,
+    // most of them are inside the view frustum but hidden behind the buildings when standing in the street
+    let blocks = 24;
+    let block_size = 12.0;
+    let mut buildings = Vec::new();
+    let mut props = Vec::new();
+    for i in 0..blocks {
+        for j in 0..blocks {
+            let x = i as f32 * block_size;
+            let z = j as f32 * block_size;
+            let height = 8.0 + ((i * 7 + j * 13) % 10) as f32 * 3.0;
+            let mut building = Gm::new(
+                Mesh::new(&context, &CpuMesh::cube()),
+                PhysicalMaterial::new_opaque(
+                    &context,
+                    &CpuMaterial {
+                        albedo: Srgba::new_opaque(180, 170, 160),
+                        ..Default::default()
+                    },
+                ),
+            );
+            building.set_transformation(
+                Mat4::from_translation(vec3(x, 0.5 * height, z))
+                    * Mat4::from_nonuniform_scale(4.0, 0.5 * height, 4.0),
+            );
+            buildings.push(building);
+
+            for k in 0..4 {
+                let angle = degrees(90.0 * k as f32 + 45.0);
+                let mut prop = Gm::new(
+                    Mesh::new(&context, &CpuMesh::sphere(16)),
+                    PhysicalMaterial::new_opaque(
+                        &context,
+                        &CpuMaterial {
+                            albedo: Srgba::new_opaque(60, 120 + 30 * k as u8, 200),
+                            ..Default::default()
+                        },
+                    ),
+                );
+                prop.set_transformation(
+                    Mat4::from_translation(vec3(x + 5.0 * angle.cos(), 0.5, z + 5.0 * angle.sin()))
+                        * Mat4::from_scale(0.5),
+                );
+                props.push(prop);
+            }
+        }
+    }
+    let mut ground = Gm::new(
+        Mesh::new(&context, &CpuMesh::square()),
+        PhysicalMaterial::new_opaque(
+            &context,
+            &CpuMaterial {
+                albedo: Srgba::new_opaque(80, 80, 80),
+                ..Default::default()
+            },
+        ),
+    );
+    let city_size = blocks as f32 * block_size;
+    ground.set_transformation(
+        Mat4::from_translation(vec3(0.5 * city_size, 0.0, 0.5 * city_size))
+            * Mat4::from_angle_x(degrees(-90.0))
+            * Mat4::from_scale(0.5 * city_size + block_size),
+    );
+
+    let ambient = AmbientLight::new(&context, 0.4, Srgba::WHITE);
+    let directional = DirectionalLight::new(&context, 2.0, Srgba::WHITE, vec3(-1.0, -2.0, -0.5));
+
+    let mut occlusion_culling = OcclusionCulling::new(&context);
+    let mut enabled = true;
+    let mut gui = three_d::GUI::new(&context);
+    window.render_loop(move |mut frame_input| {
+        let mut panel_width = 0.0;
+        gui.update(
+            &mut frame_input.events,
+            frame_input.accumulated_time,
+            frame_input.viewport,
+            frame_input.device_pixel_ratio,
+            |gui_context| {
+                use three_d::egui::*;
+                SidePanel::left("side_panel").show(gui_context, |ui| {
+                    ui.heading("Occlusion culling");
+                    ui.checkbox(&mut enabled, "Enabled");
+                    ui.checkbox(&mut occlusion_culling.visualize, "Show culled objects");
+                    ui.add(
+                        Slider::new(&mut occlusion_culling.readback_size, 16..=512)
+                            .text("Readback size"),
+                    );
+                    let stats = occlusion_culling.stats();
+                    ui.label(format!("Tested: {}", stats.tested));
+                    ui.label(format!("Culled: {}", stats.culled));
+                    ui.label(format!("Rendered: {}", stats.tested - stats.culled));
+                });
+                panel_width = gui_context.used_rect().width();
+            },
+        );
+
+        let viewport = frame_input.viewport_from_logical(
+            panel_width,
+            0.0,
+            frame_input.window_width as f32 - panel_width,
+            frame_input.window_height as f32,
+        );
+        camera.set_viewport(viewport);
+        control.handle_events(&mut camera, &mut frame_input.events);
+
+        // The buildings are the occluders and all buildings and props are tested
+        let visible = if enabled {
+            occlusion_culling.render_depth_prepass(&camera, &buildings);
+            occlusion_culling.filter(buildings.iter().chain(&props))
+        } else {
+            buildings.iter().chain(&props).collect()
+        };
+
+        let screen = frame_input.screen();
+        screen
+            .clear(ClearState::color_and_depth(0.6, 0.7, 0.9, 1.0, 1.0))
+            .render(
+                &camera,
+                visible.into_iter().chain([&ground]),
+                &[&ambient, &directional],
+            );
+        if enabled && occlusion_culling.visualize {
+            screen.render(&camera, &occlusion_culling.debug_gizmo(&context, 0.05), &[]);
+        }
+        screen.write(|| gui.render()).unwrap();
+
+        FrameOutput::default()
+    });
+}
@@ -0,0 +1,148 @@
+use three_d::*;
+
+pub fn main() {
+    let window = Window::new(WindowSettings {
+        title: "Order-independent transparency!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl();
+
+    let mut camera = Camera::new_perspective(
+        window.viewport(),
+        vec3(5.0, 3.0, 5.0),
+        vec3(0.0, 0.8, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        100.0,
+    );
+    let mut control = OrbitControl::new(camera.target(), 2.0, 50.0);
+    let mut gui = three_d::GUI::new(&context);
+
+    // Transparent objects which intersect each other, so they cannot be sorted correctly
+    let transparent_objects = [
+        (vec3(0.0, 1.0, 0.0), Srgba::new(255, 60, 60, 120)),
+        (vec3(0.7, 1.0, 0.3), Srgba::new(60, 255, 60, 120)),
+        (vec3(0.3, 1.5, 0.7), Srgba::new(60, 60, 255, 120)),
+        // A transparent object which is fully opaque, which must not be darkened by the others
+        (vec3(-0.6, 1.2, 0.6), Srgba::new(255, 220, 60, 255)),
+    ]
+    .map(|(position, color)| {
+        let mut object = Gm::new(
+            Mesh::new(&context, &CpuMesh::sphere(32)),
+            PhysicalMaterial::new_transparent(
+                &context,
+                &CpuMaterial {
+                    albedo: color,
+                    roughness: 0.3,
+                    ..Default::default()
+                },
+            ),
+        );
+        object.set_transformation(Mat4::from_translation(position) * Mat4::from_scale(0.6));
+        object
+    });
+    let mut plane = Gm::new(
+        Mesh::new(&context, &CpuMesh::cube()),
+        PhysicalMaterial::new_transparent(
+            &context,
+            &CpuMaterial {
+                albedo: Srgba::new(255, 255, 255, 80),
+                ..Default::default()
+            },
+        ),
+    );
+    plane.set_transformation(
+        Mat4::from_translation(vec3(0.3, 1.1, 0.3)) * Mat4::from_nonuniform_scale(1.5, 0.02, 1.5),
+    );
+
+    // Opaque objects which hide the transparent objects behind them
+    let mut ground = Gm::new(
+        Mesh::new(&context, &CpuMesh::square()),
+        PhysicalMaterial::new_opaque(
+            &context,
+            &CpuMaterial {
+                albedo: Srgba::new_opaque(120, 120, 130),
+                ..Default::default()
+            },
+        ),
+    );
+    ground.set_transformation(Mat4::from_angle_x(degrees(-90.0)) * Mat4::from_scale(10.0));
+    let mut wall = Gm::new(
+        Mesh::new(&context, &CpuMesh::cube()),
+        PhysicalMaterial::new_opaque(
+            &context,
+            &CpuMaterial {
+                albedo: Srgba::new_opaque(180, 150, 120),
+                ..Default::default()
+            },
+        ),
+    );
+    wall.set_transformation(
+        Mat4::from_translation(vec3(-1.0, 1.0, -1.0)) * Mat4::from_nonuniform_scale(0.1, 1.0, 1.5),
+    );
+
+    let ambient = AmbientLight::new(&context, 0.4, Srgba::WHITE);
+    let directional = DirectionalLight::new(&context, 2.0, Srgba::WHITE, vec3(-1.0, -1.0, -0.5));
+
+    let mut order_independent = true;
+    window.render_loop(move |mut frame_input| {
+        gui.update(
+            &mut frame_input.events,
+            frame_input.accumulated_time,
+            frame_input.viewport,
+            frame_input.device_pixel_ratio,
+            |gui_context| {
+                use three_d::egui::*;
+                Window::new("Transparency").show(gui_context, |ui| {
+                    ui.radio_value(&mut order_independent, false, "Sorted");
+                    ui.radio_value(&mut order_independent, true, "Order-independent");
+                });
+            },
+        );
+
+        camera.set_viewport(frame_input.viewport);
+        control.handle_events(&mut camera, &mut frame_input.events);
+
+        let lights: [&dyn Light; 2] = [&ambient, &directional];
+        let screen = frame_input.screen();
+        let opaque_objects = ground.into_iter().chain(&wall);
+        let transparent_objects = transparent_objects.iter().flatten().chain(&plane);
+        if order_independent {
+            // Render the opaque objects into textures, so the depth can be used to hide the transparent objects behind them
+            let (width, height) = (frame_input.viewport.width, frame_input.viewport.height);
+            let mut color_texture =
+                context.request_texture::<[u8; 4]>(width, height, Interpolation::Nearest);
+            let mut depth_texture = context.request_depth_texture::<f32>(width, height);
+            RenderTarget::new(
+                color_texture.as_color_target(None),
+                depth_texture.as_depth_target(),
+            )
+            .clear(ClearState::color_and_depth(0.8, 0.8, 0.8, 1.0, 1.0))
+            .render(&camera, opaque_objects, &lights);
+            screen
+                .apply_screen_effect(
+                    &CopyEffect::default(),
+                    &camera,
+                    &[],
+                    Some(ColorTexture::Single(&color_texture)),
+                    Some(DepthTexture::Single(&depth_texture)),
+                )
+                .render_transparent_oit(
+                    &camera,
+                    transparent_objects,
+                    &lights,
+                    Some(DepthTexture::Single(&depth_texture)),
+                );
+        } else {
+            screen
+                .clear(ClearState::color_and_depth(0.8, 0.8, 0.8, 1.0, 1.0))
+                .render(&camera, opaque_objects.chain(transparent_objects), &lights);
+        }
+        screen.write(|| gui.render()).unwrap();
+
+        FrameOutput::default()
+    });
+}
@@ -0,0 +1,179 @@
+use three_d::*;
+
+pub fn main() {
+    let window = Window::new(WindowSettings {
+        title: "Overlay 2D!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl();
+
+    let mut camera = Camera::new_perspective(
+        window.viewport(),
+        vec3(6.0, 4.0, 8.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        100.0,
+    );
+    let mut control = OrbitControl::new(camera.target(), 2.0, 50.0);
+    let mut gui = three_d::GUI::new(&context);
+
+    let mut cubes = (0..5)
+        .map(|i| {
+            Gm::new(
+                Mesh::new(&context, &CpuMesh::cube()),
+                PhysicalMaterial::new_opaque(
+                    &context,
+                    &CpuMaterial {
+                        albedo: Srgba::new_opaque(60 + 40 * i as u8, 120, 220 - 40 * i as u8),
+                        ..Default::default()
+                    },
+                ),
+            )
+        })
+        .collect::<Vec<_>>();
+    let ambient = AmbientLight::new(&context, 0.4, Srgba::WHITE);
+    let directional = DirectionalLight::new(&context, 2.0, Srgba::WHITE, vec3(-1.0, -1.0, -1.0));
+
+    // A procedural icon drawn as a textured quad
+    let icon = Texture2DRef::from_cpu_texture(
+        &context,
+        &CpuTexture {
+            data: TextureData::RgbaU8(
+                (0..32 * 32)
+                    .map(|i| {
+                        let (x, y) = (i % 32, i / 32);
+                        if (x / 8 + y / 8) % 2 == 0 {
+                            [255, 200, 0, 255]
+                        } else {
+                            [40, 40, 40, 200]
+                        }
+                    })
+                    .collect(),
+            ),
+            width: 32,
+            height: 32,
+            ..Default::default()
+        },
+    );
+
+    let mut overlay = Overlay2D::new(&context);
+    let font = overlay
+        .add_font(include_bytes!("../../text/src/font0.ttf"), 0, 48.0)
+        .unwrap();
+
+    let mut show_overlay = true;
+    let mut draw_calls = 0;
+    window.render_loop(move |mut frame_input| {
+        gui.update(
+            &mut frame_input.events,
+            frame_input.accumulated_time,
+            frame_input.viewport,
+            frame_input.device_pixel_ratio,
+            |gui_context| {
+                use three_d::egui::*;
+                Window::new("Overlay").show(gui_context, |ui| {
+                    ui.checkbox(&mut show_overlay, "Show overlay");
+                    ui.label(format!("Draw calls: {}", draw_calls));
+                });
+            },
+        );
+
+        camera.set_viewport(frame_input.viewport);
+        control.handle_events(&mut camera, &mut frame_input.events);
+
+        let time = 0.001 * frame_input.accumulated_time as f32;
+        for (i, cube) in cubes.iter_mut().enumerate() {
+            let angle = time * 0.5 + i as f32 * std::f32::consts::TAU / 5.0;
+            cube.set_transformation(
+                Mat4::from_translation(3.0 * vec3(angle.cos(), 0.0, angle.sin()))
+                    * Mat4::from_angle_y(radians(time))
+                    * Mat4::from_scale(0.5),
+            );
+        }
+
+        let dpr = frame_input.device_pixel_ratio;
+        let width = frame_input.viewport.width as f32 / dpr;
+        let height = frame_input.viewport.height as f32 / dpr;
+        overlay.begin_frame(dpr);
+        if show_overlay {
+            // Crosshair in the center of the screen
+            let center = vec2(0.5 * width, 0.5 * height);
+            let white = Srgba::new(255, 255, 255, 200);
+            overlay.circle_stroke(center, 12.0, 1.5, white);
+            overlay.line(center - vec2(20.0, 0.0), center - vec2(6.0, 0.0), 1.5, white);
+            overlay.line(center + vec2(6.0, 0.0), center + vec2(20.0, 0.0), 1.5, white);
+            overlay.line(center - vec2(0.0, 20.0), center - vec2(0.0, 6.0), 1.5, white);
+            overlay.line(center + vec2(0.0, 6.0), center + vec2(0.0, 20.0), 1.5, white);
+
+            // A label next to each cube
+            for (i, cube) in cubes.iter().enumerate() {
+                let position = (cube.transformation() * vec4(0.0, 0.0, 0.0, 1.0)).truncate();
+                let pixel = LogicalPoint::from_physical(
+                    camera.pixel_at_position(position),
+                    dpr,
+                    frame_input.viewport.height,
+                );
+                let anchor = vec2(pixel.x, pixel.y);
+                overlay.circle_filled(anchor, 4.0, Srgba::RED);
+                overlay.line(anchor, anchor + vec2(30.0, -30.0), 1.0, Srgba::WHITE);
+                overlay.text(
+                    font,
+                    &format!("Cube {}", i),
+                    anchor + vec2(34.0, -34.0),
+                    16.0,
+                    Srgba::WHITE,
+                );
+            }
+
+            // A status panel where the scrolling text is clipped to the panel
+            let panel_min = vec2(width - 260.0, height - 110.0);
+            let panel_max = vec2(width - 10.0, height - 10.0);
+            overlay.rect_filled(panel_min, panel_max, Srgba::new(0, 0, 0, 160));
+            overlay.rect_stroke(panel_min, panel_max, 1.0, Srgba::new(255, 200, 0, 255));
+            overlay.textured_quad(
+                panel_min + vec2(10.0, 10.0),
+                panel_min + vec2(42.0, 42.0),
+                &icon,
+                Srgba::WHITE,
+            );
+            overlay.set_clip_rect(Some((panel_min, panel_max)));
+            let scroll = (time * 40.0) % 300.0;
+            overlay.text(
+                font,
+                "Status: all systems nominal\nCubes orbiting: 5\nOverlay batched into few draw calls",
+                panel_min + vec2(250.0 - scroll, 58.0),
+                14.0,
+                Srgba::new_opaque(180, 255, 180),
+            );
+            overlay.set_clip_rect(None);
+
+            // A health bar
+            let fraction = 0.5 + 0.5 * time.sin();
+            overlay.rect_filled(
+                vec2(10.0, height - 30.0),
+                vec2(10.0 + 200.0 * fraction, height - 14.0),
+                Srgba::new_opaque(220, 60, 60),
+            );
+            overlay.rect_stroke(
+                vec2(10.0, height - 30.0),
+                vec2(210.0, height - 14.0),
+                2.0,
+                Srgba::WHITE,
+            );
+        }
+        draw_calls = overlay.draw_call_count();
+
+        let screen = frame_input.screen();
+        screen
+            .clear(ClearState::color_and_depth(0.2, 0.2, 0.25, 1.0, 1.0))
+            .render(&camera, cubes.iter().flatten(), &[&ambient, &directional]);
+        overlay.render(&screen);
+        screen.write(|| gui.render()).unwrap();
+
+        FrameOutput::default()
+    });
+}
@@ -0,0 +1,126 @@
+use three_d::*;
+
+const SIZE: usize = 256;
+
+pub fn main() {
+    let window = Window::new(WindowSettings {
+        title: "Parallax occlusion mapping!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl();
+
+    let mut camera = Camera::new_perspective(
+        window.viewport(),
+        vec3(3.0, 1.5, 4.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        100.0,
+    );
+    let mut control = OrbitControl::new(camera.target(), 1.5, 20.0);
+    let mut gui = three_d::GUI::new(&context);
+
+    // Procedural bricks where the mortar is the deepest part and the edges of the bricks are beveled
+    let heights = (0..SIZE * SIZE)
+        .map(|i| {
+            let (x, y) = (i % SIZE, i / SIZE);
+            let row = y / 32;
+            let x = (x + if row % 2 == 0 { 0 } else { 32 }) % 64;
+            let y = y % 32;
+            let distance = x.min(63 - x).min(y).min(31 - y) as f32;
+            ((distance - 2.0) / 4.0).clamp(0.0, 1.0)
+        })
+        .collect::<Vec<_>>();
+    let height_at = |x: isize, y: isize| {
+        heights
+            [(y.rem_euclid(SIZE as isize) as usize) * SIZE + x.rem_euclid(SIZE as isize) as usize]
+    };
+    let mut normals = Vec::with_capacity(SIZE * SIZE);
+    let mut colors = Vec::with_capacity(SIZE * SIZE);
+    for y in 0..SIZE as isize {
+        for x in 0..SIZE as isize {
+            // The rows of the texture go from top to bottom, whereas the bitangent points upwards
+            let dx = height_at(x + 1, y) - height_at(x - 1, y);
+            let dy = height_at(x, y - 1) - height_at(x, y + 1);
+            let normal = vec3(-2.0 * dx, -2.0 * dy, 1.0).normalize();
+            normals.push(normal.map(|v| ((0.5 * v + 0.5) * 255.0) as u8).into());
+            let brick = ((x as usize + if (y / 32) % 2 == 0 { 0 } else { 32 }) / 64
+                + 7 * (y as usize / 32))
+                % 5;
+            let h = height_at(x, y);
+            colors.push(if h > 0.0 {
+                [150 + 15 * brick as u8, 60 + 5 * brick as u8, 40, 255]
+            } else {
+                [160, 160, 150, 255]
+            });
+        }
+    }
+    let texture = |data| {
+        Texture2DRef::from_cpu_texture(
+            &context,
+            &CpuTexture {
+                data,
+                width: SIZE as u32,
+                height: SIZE as u32,
+                ..Default::default()
+            },
+        )
+    };
+    let albedo_texture = texture(TextureData::RgbaU8(colors));
+    let normal_texture = texture(TextureData::RgbU8(normals));
+    let height_texture = texture(TextureData::RU8(
+        heights.iter().map(|h| (h * 255.0) as u8).collect(),
+    ));
+
+    let mut cpu_mesh = CpuMesh::cube();
+    cpu_mesh.compute_tangents();
+    let mut cube = Gm::new(
+        Mesh::new(&context, &cpu_mesh),
+        PhysicalMaterial {
+            albedo_texture: Some(albedo_texture),
+            normal_texture: Some(normal_texture),
+            roughness: 0.8,
+            parallax_clip: true,
+            ..Default::default()
+        },
+    );
+    let ambient = AmbientLight::new(&context, 0.3, Srgba::WHITE);
+    let directional = DirectionalLight::new(&context, 2.0, Srgba::WHITE, vec3(-1.0, -0.5, -1.0));
+
+    let mut enabled = true;
+    window.render_loop(move |mut frame_input| {
+        gui.update(
+            &mut frame_input.events,
+            frame_input.accumulated_time,
+            frame_input.viewport,
+            frame_input.device_pixel_ratio,
+            |gui_context| {
+                use three_d::egui::*;
+                Window::new("Parallax").show(gui_context, |ui| {
+                    ui.checkbox(&mut enabled, "Parallax occlusion mapping");
+                    ui.add(
+                        Slider::new(&mut cube.material.parallax_scale, 0.0..=0.15).text("Scale"),
+                    );
+                    ui.add(Slider::new(&mut cube.material.parallax_steps, 1..=64).text("Steps"));
+                    ui.checkbox(&mut cube.material.parallax_clip, "Clip silhouettes");
+                });
+            },
+        );
+        cube.material.height_texture = enabled.then(|| height_texture.clone());
+
+        camera.set_viewport(frame_input.viewport);
+        control.handle_events(&mut camera, &mut frame_input.events);
+
+        frame_input
+            .screen()
+            .clear(ClearState::color_and_depth(0.3, 0.3, 0.35, 1.0, 1.0))
+            .render(&camera, &cube, &[&ambient, &directional])
+            .write(|| gui.render())
+            .unwrap();
+
+        FrameOutput::default()
+    });
+}
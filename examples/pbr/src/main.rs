@@ -64,6 +64,10 @@ pub async fn run() {
     let mut metallic_roughness_enabled = true;
     let mut albedo_map_enabled = true;
     let mut emissive_map_enabled = true;
+    let mut clearcoat = 0.0;
+    let mut clearcoat_roughness = 0.1;
+    let mut anisotropy = 0.0;
+    let mut anisotropy_rotation = 0.0;
     window.render_loop(move |mut frame_input| {
         let mut panel_width = 0.0;
         gui.update(
@@ -80,18 +84,27 @@ pub async fn run() {
                     ui.checkbox(&mut normal_map_enabled, "Normal map");
                     ui.checkbox(&mut occlusion_map_enabled, "Occlusion map");
                     ui.checkbox(&mut emissive_map_enabled, "Emissive map");
+                    ui.add(Slider::new(&mut clearcoat, 0.0..=1.0).text("Clear coat"));
+                    ui.add(
+                        Slider::new(&mut clearcoat_roughness, 0.0..=1.0)
+                            .text("Clear coat roughness"),
+                    );
+                    ui.add(Slider::new(&mut anisotropy, -1.0..=1.0).text("Anisotropy"));
+                    ui.add(
+                        Slider::new(&mut anisotropy_rotation, 0.0..=std::f32::consts::PI)
+                            .text("Anisotropy rotation"),
+                    );
                 });
                 panel_width = gui_context.used_rect().width();
             },
         );
 
-        let viewport = Viewport {
-            x: (panel_width * frame_input.device_pixel_ratio) as i32,
-            y: 0,
-            width: frame_input.viewport.width
-                - (panel_width * frame_input.device_pixel_ratio) as u32,
-            height: frame_input.viewport.height,
-        };
+        let viewport = frame_input.viewport_from_logical(
+            panel_width,
+            0.0,
+            frame_input.window_width as f32 - panel_width,
+            frame_input.window_height as f32,
+        );
         camera.set_viewport(viewport);
         control.handle_events(&mut camera, &mut frame_input.events);
 
@@ -116,6 +129,10 @@ pub async fn run() {
                         None
                     },
                     normal_scale: model.material.normal_scale,
+                    height_texture: None,
+                    parallax_scale: model.material.parallax_scale,
+                    parallax_steps: model.material.parallax_steps,
+                    parallax_clip: false,
                     normal_texture: if normal_map_enabled {
                         model.material.normal_texture.clone()
                     } else {
@@ -143,6 +160,17 @@ pub async fn run() {
                         NormalDistributionFunction::TrowbridgeReitzGGX,
                         GeometryFunction::SmithSchlickGGX,
                     ),
+                    diffuse_model: model.material.diffuse_model,
+                    lightmap_texture: None,
+                    subsurface: None,
+                    clearcoat,
+                    clearcoat_roughness,
+                    clearcoat_texture: None,
+                    clearcoat_normal_texture: None,
+                    anisotropy,
+                    anisotropy_rotation,
+                    double_sided_lighting: model.material.double_sided_lighting,
+                    texture_mapping: model.material.texture_mapping,
                 };
                 model.render_with_material(&material, &camera, &[&light]);
                 gui.render()
@@ -89,6 +89,12 @@ pub async fn run() {
         ),
     );
 
+    // A glow around the picked object
+    let mut glow = DistanceGlow::new(&context, Srgba::new_opaque(255, 120, 0), 40.0);
+    // An outline around the instanced spheres, which is drawn through the other objects
+    let mut outline = Outline::new(&context, Srgba::new_opaque(0, 120, 255), 3.0);
+    let mut picked = None;
+
     // main loop
     window.render_loop(move |mut frame_input| {
         let mut change = frame_input.first_frame;
@@ -106,9 +112,11 @@ pub async fn run() {
                     monkey.material.albedo = original_color;
                     cone.material.albedo = Srgba::BLUE;
                     pick_mesh.set_transformation(Mat4::from_translation(vec3(0.0, 0.0, 0.0)));
+                    picked = None;
+                    change = true;
 
                     // Pick
-                    if let Some(pick) = pick(
+                    if let Some(pick) = pick_gpu(
                         &context,
                         &camera,
                         position,
@@ -120,7 +128,8 @@ pub async fn run() {
                         pick_mesh.set_transformation(
                             Mat4::from_translation(pick.position) * Mat4::from_scale(0.3),
                         );
-                        match pick.geometry_id {
+                        picked = Some(pick.geometry_index);
+                        match pick.geometry_index {
                             0 => {
                                 monkey.material.albedo = Srgba::RED;
                             }
@@ -128,7 +137,7 @@ pub async fn run() {
                                 cone.material.albedo = Srgba::RED;
                             }
                             2 => {
-                                instances.colors.as_mut().unwrap()[pick.instance_id as usize] =
+                                instances.colors.as_mut().unwrap()[pick.instance_index as usize] =
                                     Srgba::RED;
                                 instanced_mesh.set_instances(&instances);
                             }
@@ -136,7 +145,6 @@ pub async fn run() {
                                 unreachable!()
                             }
                         };
-                        change = true;
                     }
                 }
             }
@@ -146,6 +154,17 @@ pub async fn run() {
 
         // draw
         if change {
+            match picked {
+                Some(0) => glow.update(&camera, &monkey),
+                Some(1) => glow.update(&camera, &cone),
+                _ => glow.update(&camera, std::iter::empty::<&Mesh>()),
+            }
+            // The thickness is given in logical pixels, so it looks the same on high resolution displays
+            outline.thickness = 3.0 * frame_input.device_pixel_ratio;
+            match picked {
+                Some(2) => outline.update(&camera, &instanced_mesh, None),
+                _ => outline.update(&camera, std::iter::empty::<&Mesh>(), None),
+            }
             frame_input
                 .screen()
                 .clear(ClearState::color_and_depth(1.0, 1.0, 1.0, 1.0, 1.0))
@@ -157,7 +176,9 @@ pub async fn run() {
                         .chain(&cone)
                         .chain(&pick_mesh),
                     &[&ambient, &directional],
-                );
+                )
+                .apply_screen_material(&glow, &camera, &[])
+                .apply_screen_material(&outline, &camera, &[]);
         }
 
         FrameOutput {
@@ -36,10 +36,10 @@ pub async fn run() {
     let mut point_mesh = CpuMesh::sphere(4);
     point_mesh.transform(Mat4::from_scale(0.001)).unwrap();
 
-    let mut point_cloud = Gm {
-        geometry: InstancedMesh::new(&context, &cpu_point_cloud.into(), &point_mesh),
-        material: ColorMaterial::default(),
-    };
+    let mut point_cloud = Gm::new(
+        InstancedMesh::new(&context, &cpu_point_cloud.into(), &point_mesh),
+        ColorMaterial::default(),
+    );
     let c = -point_cloud.aabb().center();
     point_cloud.set_transformation(Mat4::from_translation(c));
 
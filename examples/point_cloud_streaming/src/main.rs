@@ -0,0 +1,267 @@
+use three_d::*;
+
+// Usage on native:
+//   point_cloud_streaming                                 Streams a generated point cloud
+//   point_cloud_streaming convert <input.las> <output>    Converts a LAS file into a point cloud octree
+//   point_cloud_streaming <file>                          Streams a point cloud octree from a file
+pub fn main() {
+    #[cfg(not(target_arch = "wasm32"))]
+    let args: Vec<String> = std::env::args().collect();
+    #[cfg(not(target_arch = "wasm32"))]
+    if args.len() == 4 && args[1] == "convert" {
+        if let Err(e) = convert(&args[2], &args[3]) {
+            eprintln!("Failed to convert {}: {}", args[2], e);
+        }
+        return;
+    }
+
+    let window = Window::new(WindowSettings {
+        title: "Point cloud streaming!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl();
+
+    #[cfg(not(target_arch = "wasm32"))]
+    let mut point_cloud = if args.len() == 2 {
+        StreamedPointCloud::open(&context, &args[1], 5_000_000).unwrap()
+    } else {
+        generate(&context)
+    };
+    #[cfg(target_arch = "wasm32")]
+    let mut point_cloud = generate(&context);
+
+    // Render relative to the origin of the point cloud, which is usually far from (0, 0, 0) for georeferenced point clouds
+    context.set_world_origin(point_cloud.octree().origin);
+    let bounds = point_cloud.octree().nodes[0].bounds;
+    let size = bounds.size().magnitude().max(1.0);
+    let mut camera = Camera::new_perspective(
+        window.viewport(),
+        bounds.center() + vec3(0.0, 0.3, 0.6) * size,
+        bounds.center(),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.001 * size,
+        10.0 * size,
+    );
+    let mut control = FlyControl::new(0.0005 * size);
+    let mut gui = three_d::GUI::new(&context);
+
+    let mut budget_millions = 5.0;
+    window.render_loop(move |mut frame_input| {
+        point_cloud.budget = (budget_millions * 1_000_000.0) as u64;
+        point_cloud.update(&camera);
+        let stats = point_cloud.stats();
+
+        gui.update(
+            &mut frame_input.events,
+            frame_input.accumulated_time,
+            frame_input.viewport,
+            frame_input.device_pixel_ratio,
+            |gui_context| {
+                use three_d::egui::*;
+                Window::new("Point cloud streaming").show(gui_context, |ui| {
+                    ui.add(
+                        Slider::new(&mut budget_millions, 0.1..=50.0)
+                            .text("Budget (million points)"),
+                    );
+                    ui.add(
+                        Slider::new(&mut point_cloud.max_screen_space_error, 0.5..=16.0)
+                            .text("Max screen-space error"),
+                    );
+                    ui.add(Slider::new(&mut point_cloud.point_size, 1.0..=8.0).text("Point size"));
+                    ui.label(format!(
+                        "Total: {:.2} million points in {} nodes",
+                        point_cloud.octree().point_count() as f64 / 1_000_000.0,
+                        stats.node_count
+                    ));
+                    ui.label(format!(
+                        "Resident: {:.2} million points in {} nodes",
+                        stats.resident_points as f64 / 1_000_000.0,
+                        stats.resident_nodes
+                    ));
+                    ui.label(format!(
+                        "Rendered: {:.2} million points in {} nodes",
+                        stats.rendered_points as f64 / 1_000_000.0,
+                        stats.rendered_nodes
+                    ));
+                    ui.label(format!("Limited by budget: {}", stats.budget_limited));
+                    ui.label(format!("Pending loads: {}", stats.pending_loads));
+                    ui.label(format!("Completed loads: {}", stats.completed_loads));
+                    ui.label(format!("Failed loads: {}", stats.failed_loads));
+                    ui.label(format!("Evictions: {}", stats.evictions));
+                });
+            },
+        );
+
+        camera.set_viewport(frame_input.viewport);
+        control.handle_events(&mut camera, &mut frame_input.events);
+
+        frame_input
+            .screen()
+            .clear(ClearState::color_and_depth(0.1, 0.1, 0.1, 1.0, 1.0))
+            .render_with_material(
+                &ColorMaterial::default(),
+                &camera,
+                point_cloud.geometries(),
+                &[],
+            )
+            .write(|| gui.render())
+            .unwrap();
+
+        FrameOutput::default()
+    });
+}
+
+///
+/// Generates a point cloud of a hilly terrain, builds the octree in memory and streams the nodes from memory.
+///
+fn generate(context: &Context) -> StreamedPointCloud {
+    // A georeferenced position far from (0, 0, 0), which cannot be represented accurately in single precision
+    let origin = DVec3::new(500000.0, 0.0, -6000000.0);
+    let mut builder = PointCloudOctreeBuilder::new(origin, true);
+    let mut seed = 1u32;
+    let mut random = move || {
+        seed = seed.wrapping_mul(1664525).wrapping_add(1013904223);
+        (seed >> 8) as f32 / (1 << 24) as f32
+    };
+    for _ in 0..2_000_000 {
+        let x = (random() - 0.5) * 2000.0;
+        let z = (random() - 0.5) * 2000.0;
+        let y = 40.0 * (x * 0.004).sin() * (z * 0.003).cos()
+            + 8.0 * (x * 0.02 + 1.0).sin() * (z * 0.025).sin()
+            + 0.5 * random();
+        let height = ((y + 50.0) / 100.0).clamp(0.0, 1.0);
+        let color = Srgba::new(
+            (60.0 + 160.0 * height) as u8,
+            (140.0 - 40.0 * height) as u8,
+            (60.0 + 100.0 * height) as u8,
+            255,
+        );
+        builder.add_point(
+            origin + DVec3::new(x as f64, y as f64, z as f64),
+            Some(color),
+        );
+    }
+    let mut bytes = Vec::new();
+    let octree = builder.write(&mut bytes).unwrap();
+    let index = octree.clone();
+    let bytes = std::sync::Arc::new(bytes);
+    StreamedPointCloud::new(
+        context,
+        octree,
+        move |node| {
+            // Simulate the latency of reading from a disk or network
+            #[cfg(not(target_arch = "wasm32"))]
+            std::thread::sleep(std::time::Duration::from_millis(30));
+            index
+                .read_chunk(&mut std::io::Cursor::new(bytes.as_slice()), node)
+                .unwrap()
+        },
+        5_000_000,
+    )
+}
+
+///
+/// Converts the LAS file at the input path into a point cloud octree at the output path.
+/// All points are kept in memory while building the octree, which requires 16 bytes per point.
+///
+#[cfg(not(target_arch = "wasm32"))]
+fn convert(input: &str, output: &str) -> Result<(), String> {
+    use std::io::{BufReader, Read, Seek, SeekFrom};
+    let mut reader = BufReader::new(std::fs::File::open(input).map_err(|e| e.to_string())?);
+    let mut header = [0u8; 375];
+    reader
+        .read_exact(&mut header[..227])
+        .map_err(|e| e.to_string())?;
+    if &header[0..4] != b"LASF" {
+        return Err("not a LAS file".to_string());
+    }
+    let u16_at = |h: &[u8], i: usize| u16::from_le_bytes([h[i], h[i + 1]]);
+    let u32_at = |h: &[u8], i: usize| u32::from_le_bytes(h[i..i + 4].try_into().unwrap());
+    let f64_at = |h: &[u8], i: usize| f64::from_le_bytes(h[i..i + 8].try_into().unwrap());
+    let header_size = u16_at(&header, 94) as usize;
+    let point_data_offset = u32_at(&header, 96) as u64;
+    let format = header[104];
+    let record_length = u16_at(&header, 105) as usize;
+    let mut point_count = u32_at(&header, 107) as u64;
+    if header_size >= 375 {
+        // LAS 1.4 stores the number of points in 64 bits
+        reader
+            .read_exact(&mut header[227..375])
+            .map_err(|e| e.to_string())?;
+        point_count = point_count.max(u64::from_le_bytes(header[247..255].try_into().unwrap()));
+    }
+    let scale = [
+        f64_at(&header, 131),
+        f64_at(&header, 139),
+        f64_at(&header, 147),
+    ];
+    let offset = [
+        f64_at(&header, 155),
+        f64_at(&header, 163),
+        f64_at(&header, 171),
+    ];
+    let (max, min) = (
+        [
+            f64_at(&header, 179),
+            f64_at(&header, 195),
+            f64_at(&header, 211),
+        ],
+        [
+            f64_at(&header, 187),
+            f64_at(&header, 203),
+            f64_at(&header, 219),
+        ],
+    );
+    let color_offset = match format {
+        0 | 1 | 4 | 6 | 9 => None,
+        2 => Some(20),
+        3 | 5 => Some(28),
+        7 | 8 | 10 => Some(30),
+        _ => return Err(format!("unsupported point data format {}", format)),
+    };
+
+    // LAS uses z as up, so the positions are converted to y as up
+    let to_y_up = |p: [f64; 3]| DVec3::new(p[0], p[2], -p[1]);
+    let center = to_y_up([
+        0.5 * (min[0] + max[0]),
+        0.5 * (min[1] + max[1]),
+        0.5 * (min[2] + max[2]),
+    ]);
+    let mut builder = PointCloudOctreeBuilder::new(center, color_offset.is_some());
+    reader
+        .seek(SeekFrom::Start(point_data_offset))
+        .map_err(|e| e.to_string())?;
+    let mut record = vec![0u8; record_length];
+    for i in 0..point_count {
+        reader.read_exact(&mut record).map_err(|e| e.to_string())?;
+        let coordinate =
+            |axis: usize| u32_at(&record, axis * 4) as i32 as f64 * scale[axis] + offset[axis];
+        let color = color_offset.map(|o| {
+            Srgba::new(
+                (u16_at(&record, o) >> 8) as u8,
+                (u16_at(&record, o + 2) >> 8) as u8,
+                (u16_at(&record, o + 4) >> 8) as u8,
+                255,
+            )
+        });
+        builder.add_point(
+            to_y_up([coordinate(0), coordinate(1), coordinate(2)]),
+            color,
+        );
+        if (i + 1) % 10_000_000 == 0 {
+            println!("Read {} of {} points", i + 1, point_count);
+        }
+    }
+    println!("Building the octree of {} points", point_count);
+    let octree = builder.save(output).map_err(|e| e.to_string())?;
+    println!(
+        "Wrote {} points in {} nodes to {}",
+        octree.point_count(),
+        octree.nodes.len(),
+        output
+    );
+    Ok(())
+}
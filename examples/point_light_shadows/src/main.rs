@@ -0,0 +1,161 @@
+// Entry point for non-wasm
+#[cfg(not(target_arch = "wasm32"))]
+#[tokio::main]
+async fn main() {
+    run().await;
+}
+
+use three_d::*;
+
+pub async fn run() {
+    let window = Window::new(WindowSettings {
+        title: "Point light shadows!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl();
+
+    let mut camera = Camera::new_perspective(
+        window.viewport(),
+        vec3(7.0, 6.0, 7.0),
+        vec3(0.0, 1.5, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(60.0),
+        0.1,
+        100.0,
+    );
+    let mut control = OrbitControl::new(camera.target(), 1.0, 10.0);
+
+    // A box seen from the inside with pillars standing on the floor
+    let mut room = Gm::new(
+        Mesh::new(&context, &CpuMesh::cube()),
+        PhysicalMaterial::new_opaque(
+            &context,
+            &CpuMaterial {
+                albedo: Srgba::new_opaque(220, 210, 190),
+                roughness: 0.8,
+                ..Default::default()
+            },
+        ),
+    );
+    room.set_transformation(
+        Mat4::from_translation(vec3(0.0, 4.0, 0.0)) * Mat4::from_nonuniform_scale(8.0, 4.0, 8.0),
+    );
+    room.material.render_states.cull = Cull::Front;
+    let mut pillars = Vec::new();
+    for i in 0..8 {
+        let angle = i as f32 * std::f32::consts::PI / 4.0;
+        let mut pillar = Gm::new(
+            Mesh::new(&context, &CpuMesh::cylinder(32)),
+            PhysicalMaterial::new_opaque(
+                &context,
+                &CpuMaterial {
+                    albedo: Srgba::new_opaque(150, 110, 90),
+                    roughness: 0.6,
+                    ..Default::default()
+                },
+            ),
+        );
+        pillar.set_transformation(
+            Mat4::from_translation(vec3(4.0 * angle.cos(), 0.0, 4.0 * angle.sin()))
+                * Mat4::from_angle_z(degrees(90.0))
+                * Mat4::from_nonuniform_scale(2.0 + (i % 3) as f32, 0.3, 0.3),
+        );
+        pillars.push(pillar);
+    }
+
+    let ambient = AmbientLight::new(&context, 0.05, Srgba::WHITE);
+    let mut light = PointLight::new(
+        &context,
+        3.0,
+        Srgba::new_opaque(255, 230, 200),
+        vec3(0.0, 2.0, 0.0),
+        Attenuation {
+            constant: 0.5,
+            linear: 0.05,
+            quadratic: 0.02,
+        },
+    );
+
+    let mut gui = three_d::GUI::new(&context);
+    let mut shadows = true;
+    let mut texture_size = 512;
+    let mut height = 2.0;
+    let mut radius = 1.5;
+    let mut animate = true;
+    let mut color_ramp = false;
+    window.render_loop(move |mut frame_input| {
+        let mut panel_width = 0.0;
+        gui.update(
+            &mut frame_input.events,
+            frame_input.accumulated_time,
+            frame_input.viewport,
+            frame_input.device_pixel_ratio,
+            |gui_context| {
+                use three_d::egui::*;
+                SidePanel::left("side_panel").show(gui_context, |ui| {
+                    ui.heading("Point light shadows");
+                    ui.checkbox(&mut shadows, "Shadows");
+                    ui.checkbox(&mut animate, "Move light");
+                    ui.checkbox(&mut color_ramp, "Warm color ramp");
+                    ui.add(Slider::new(&mut height, 0.5..=7.5).text("Light height"));
+                    ui.add(Slider::new(&mut radius, 0.0..=3.5).text("Light radius"));
+                    ui.label("Shadow map size");
+                    ui.radio_value(&mut texture_size, 256, "256");
+                    ui.radio_value(&mut texture_size, 512, "512");
+                    ui.radio_value(&mut texture_size, 1024, "1024");
+                });
+                panel_width = gui_context.used_rect().width();
+            },
+        );
+
+        let viewport = frame_input.viewport_from_logical(
+            panel_width,
+            0.0,
+            frame_input.window_width as f32 - panel_width,
+            frame_input.window_height as f32,
+        );
+        camera.set_viewport(viewport);
+        control.handle_events(&mut camera, &mut frame_input.events);
+
+        if animate {
+            let angle = 0.0005 * frame_input.accumulated_time as f32;
+            light.position = vec3(radius * angle.cos(), height, radius * angle.sin());
+        } else {
+            light.position.y = height;
+        }
+
+        if color_ramp && light.color_ramp().is_none() {
+            // From warm white near the light to deep orange at range
+            light.set_color_ramp(&[
+                (0.0, Srgba::new_opaque(255, 250, 240)),
+                (3.0, Srgba::new_opaque(255, 170, 60)),
+                (8.0, Srgba::new_opaque(200, 60, 10)),
+            ]);
+        } else if !color_ramp {
+            light.clear_color_ramp();
+        }
+
+        if shadows {
+            light
+                .generate_shadow_map(texture_size, pillars.iter().chain(std::iter::once(&room)))
+                .unwrap();
+        } else {
+            light.clear_shadow_map();
+        }
+
+        frame_input
+            .screen()
+            .clear(ClearState::color_and_depth(0.0, 0.0, 0.0, 1.0, 1.0))
+            .render(
+                &camera,
+                pillars.iter().chain(std::iter::once(&room)),
+                &[&ambient, &light],
+            )
+            .write(|| gui.render())
+            .unwrap();
+
+        FrameOutput::default()
+    });
+}
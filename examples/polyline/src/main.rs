@@ -0,0 +1,160 @@
+use three_d::*;
+
+// A helix which is colored from blue at the bottom to red at the top
+fn trajectory() -> (Vec<Vec3>, Vec<Srgba>) {
+    let count = 300;
+    (0..count)
+        .map(|i| {
+            let t = i as f32 / (count - 1) as f32;
+            let angle = 6.0 * std::f32::consts::PI * t;
+            (
+                vec3(2.0 * angle.cos(), 4.0 * t - 2.0, 2.0 * angle.sin()),
+                Srgba::new_opaque((255.0 * t) as u8, 80, (255.0 * (1.0 - t)) as u8),
+            )
+        })
+        .unzip()
+}
+
+// A star in the xz-plane with sharp corners to show the difference between the joins
+fn star() -> Vec<Vec3> {
+    (0..10)
+        .map(|i| {
+            let angle = std::f32::consts::PI * i as f32 / 5.0;
+            let radius = if i % 2 == 0 { 3.5 } else { 1.5 };
+            vec3(radius * angle.cos(), -2.5, radius * angle.sin())
+        })
+        .collect()
+}
+
+pub fn main() {
+    let window = Window::new(WindowSettings {
+        title: "Polyline!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl();
+
+    let mut camera = Camera::new_perspective(
+        window.viewport(),
+        vec3(6.0, 4.0, 8.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        100.0,
+    );
+    let mut control = OrbitControl::new(camera.target(), 2.0, 50.0);
+    let mut gui = three_d::GUI::new(&context);
+
+    let (points, colors) = trajectory();
+    let mut helix = Gm::new(
+        Polyline::new(
+            &context,
+            &points,
+            Some(&colors),
+            false,
+            LineWidth::Pixels(6.0),
+        ),
+        ColorMaterial::default(),
+    );
+    let mut polygon = Gm::new(
+        Polyline::new(&context, &star(), None, true, LineWidth::World(0.2)),
+        ColorMaterial {
+            color: Srgba::new_opaque(230, 200, 60),
+            ..Default::default()
+        },
+    );
+    let mut marker = Gm::new(
+        Polyline::new(
+            &context,
+            &[vec3(0.0, 0.0, 0.0)],
+            None,
+            false,
+            LineWidth::Pixels(16.0),
+        ),
+        ColorMaterial {
+            color: Srgba::WHITE,
+            ..Default::default()
+        },
+    );
+    let mut show_marker = false;
+
+    let mut pixel_width = 6.0;
+    let mut world_width = 0.2;
+    let mut join = LineJoin::Miter;
+    let mut cap = LineCap::Round;
+    let mut miter_limit = 4.0;
+    window.render_loop(move |mut frame_input| {
+        gui.update(
+            &mut frame_input.events,
+            frame_input.accumulated_time,
+            frame_input.viewport,
+            frame_input.device_pixel_ratio,
+            |gui_context| {
+                use three_d::egui::*;
+                Window::new("Polyline").show(gui_context, |ui| {
+                    ui.add(Slider::new(&mut pixel_width, 1.0..=30.0).text("Helix pixels"));
+                    ui.add(Slider::new(&mut world_width, 0.01..=1.0).text("Star width"));
+                    ui.label("Join");
+                    ui.radio_value(&mut join, LineJoin::Miter, "Miter");
+                    ui.radio_value(&mut join, LineJoin::Bevel, "Bevel");
+                    ui.radio_value(&mut join, LineJoin::Round, "Round");
+                    ui.add(Slider::new(&mut miter_limit, 1.0..=10.0).text("Miter limit"));
+                    ui.label("Cap");
+                    ui.radio_value(&mut cap, LineCap::Butt, "Butt");
+                    ui.radio_value(&mut cap, LineCap::Round, "Round");
+                    ui.radio_value(&mut cap, LineCap::Square, "Square");
+                    ui.label("Click on a line to mark the picked position");
+                });
+            },
+        );
+        camera.set_viewport(frame_input.viewport);
+        control.handle_events(&mut camera, &mut frame_input.events);
+
+        helix.geometry.width = LineWidth::Pixels(pixel_width);
+        polygon.geometry.width = LineWidth::World(world_width);
+        for line in [&mut helix.geometry, &mut polygon.geometry] {
+            line.join = join;
+            line.cap = cap;
+            line.miter_limit = miter_limit;
+        }
+
+        for event in frame_input.events.iter() {
+            if let Event::MousePress {
+                button,
+                position,
+                handled,
+                ..
+            } = *event
+            {
+                if button == MouseButton::Left && !handled {
+                    // A single point polyline with round caps is drawn as a dot
+                    if let Some(pick) = pick(
+                        &context,
+                        &camera,
+                        position,
+                        helix.into_iter().chain(&polygon),
+                        Cull::None,
+                    )
+                    .unwrap()
+                    {
+                        marker.set_points(&[pick.position], None, false);
+                        show_marker = true;
+                    }
+                }
+            }
+        }
+
+        let screen = frame_input.screen();
+        screen
+            .clear(ClearState::color_and_depth(0.1, 0.1, 0.12, 1.0, 1.0))
+            .render(&camera, helix.into_iter().chain(&polygon), &[]);
+        if show_marker {
+            screen.render(&camera, &marker, &[]);
+        }
+        screen.write(|| gui.render()).unwrap();
+
+        FrameOutput::default()
+    });
+}
@@ -0,0 +1,122 @@
+use three_d::*;
+
+pub fn main() {
+    let window = Window::new(WindowSettings {
+        title: "Reversed Z!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl();
+
+    // A depth range of 1e6 with a near plane close to the camera
+    let mut camera = Camera::new_perspective(
+        window.viewport(),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 0.0, -1.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(60.0),
+        0.1,
+        1_000_000.0,
+    );
+    let mut control = FlyControl::new(0.1);
+    let mut gui = three_d::GUI::new(&context);
+
+    // Pairs of overlapping squares at increasing distances where the squares in a pair are separated by a small fraction of the distance.
+    // With standard depth, the distant pairs z-fight.
+    let mut squares = Vec::new();
+    for i in 1..=5 {
+        let distance = 10.0f32.powi(i);
+        let x = (i as f32 - 3.0) * 0.35 * distance;
+        for (offset, color) in [
+            (0.0, Srgba::new_opaque(220, 60, 60)),
+            (0.0001 * distance, Srgba::new_opaque(60, 200, 80)),
+        ] {
+            let mut square = Gm::new(
+                Mesh::new(&context, &CpuMesh::square()),
+                ColorMaterial {
+                    color,
+                    ..Default::default()
+                },
+            );
+            // The squares are rotated differently, so both are visible where they do not overlap
+            let angle = if offset == 0.0 { 0.0 } else { 45.0 };
+            square.set_transformation(
+                Mat4::from_translation(vec3(x, 0.0, -distance + offset))
+                    * Mat4::from_scale(0.1 * distance)
+                    * Mat4::from_angle_z(degrees(angle)),
+            );
+            squares.push(square);
+        }
+    }
+
+    let mut reversed = true;
+    window.render_loop(move |mut frame_input| {
+        gui.update(
+            &mut frame_input.events,
+            frame_input.accumulated_time,
+            frame_input.viewport,
+            frame_input.device_pixel_ratio,
+            |gui_context| {
+                use three_d::egui::*;
+                Window::new("Depth").show(gui_context, |ui| {
+                    ui.radio_value(&mut reversed, false, "Standard");
+                    ui.radio_value(&mut reversed, true, "Reversed");
+                    ui.label(format!(
+                        "Reversed mode: {:?}",
+                        context.reversed_depth_mode()
+                    ));
+                });
+            },
+        );
+        camera.set_depth_mode(if reversed {
+            context.reversed_depth_mode()
+        } else {
+            DepthMode::Standard
+        });
+        camera.set_viewport(frame_input.viewport);
+        control.handle_events(&mut camera, &mut frame_input.events);
+
+        // Render to a 32 bit float depth texture, since the default depth buffer of the screen is usually a 24 bit fixed point buffer
+        let mut color_texture = Texture2D::new_empty::<[u8; 4]>(
+            &context,
+            frame_input.viewport.width,
+            frame_input.viewport.height,
+            Interpolation::Nearest,
+            Interpolation::Nearest,
+            None,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+        );
+        let mut depth_texture = DepthTexture2D::new::<f32>(
+            &context,
+            frame_input.viewport.width,
+            frame_input.viewport.height,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+        );
+        RenderTarget::new(
+            color_texture.as_color_target(None),
+            depth_texture.as_depth_target(),
+        )
+        .clear(
+            ClearState::color_and_depth(0.1, 0.1, 0.15, 1.0, 1.0)
+                .with_depth_mode(camera.depth_mode()),
+        )
+        .render(&camera, &squares, &[]);
+
+        frame_input
+            .screen()
+            .apply_screen_effect(
+                &CopyEffect::default(),
+                &camera,
+                &[],
+                Some(ColorTexture::Single(&color_texture)),
+                None,
+            )
+            .write(|| gui.render())
+            .unwrap();
+
+        FrameOutput::default()
+    });
+}
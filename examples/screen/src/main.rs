@@ -61,17 +61,16 @@ pub fn main() {
             },
         );
 
-        let viewport = Viewport {
-            x: (panel_width * frame_input.device_pixel_ratio) as i32,
-            y: 0,
-            width: frame_input.viewport.width
-                - (panel_width * frame_input.device_pixel_ratio) as u32,
-            height: frame_input.viewport.height,
-        };
+        let viewport = frame_input.viewport_from_logical(
+            panel_width,
+            0.0,
+            frame_input.window_width as f32 - panel_width,
+            frame_input.window_height as f32,
+        );
 
         // Main view
         let viewport_zoomed = zoom(viewport_zoom, viewport);
-        let scissor_box_zoomed = zoom(scissor_zoom, viewport).into();
+        let scissor_box_zoomed: ScissorBox = zoom(scissor_zoom, viewport).into();
 
         camera.set_viewport(viewport_zoomed);
         frame_input
@@ -108,10 +107,10 @@ pub fn main() {
         frame_input
             .screen()
             .clear_partially(
-                secondary_viewport.into(),
+                secondary_viewport,
                 ClearState::color_and_depth(0.3, 0.3, 0.3, 1.0, 1.0),
             )
-            .render_partially(secondary_viewport.into(), &camera, &model, &[]);
+            .render_partially(secondary_viewport, &camera, &model, &[]);
 
         // Returns default frame output to end the frame
         FrameOutput::default()
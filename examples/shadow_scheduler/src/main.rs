@@ -0,0 +1,199 @@
+// Entry point for non-wasm
+#[cfg(not(target_arch = "wasm32"))]
+#[tokio::main]
+async fn main() {
+    run().await;
+}
+
+use three_d::*;
+
+pub async fn run() {
+    let window = Window::new(WindowSettings {
+        title: "Shadow scheduler!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl();
+
+    let mut camera = Camera::new_perspective(
+        window.viewport(),
+        vec3(0.0, 12.0, 18.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        1000.0,
+    );
+    let mut control = OrbitControl::new(camera.target(), 1.0, 100.0);
+    let mut gui = three_d::GUI::new(&context);
+
+    // A floor and a few rotating boxes
+    let mut ground = Gm::new(
+        Mesh::new(&context, &CpuMesh::square()),
+        PhysicalMaterial::new_opaque(
+            &context,
+            &CpuMaterial {
+                albedo: Srgba::new_opaque(200, 200, 200),
+                ..Default::default()
+            },
+        ),
+    );
+    ground.set_transformation(Mat4::from_angle_x(degrees(-90.0)) * Mat4::from_scale(12.0));
+    let mut boxes = (0..5)
+        .map(|i| {
+            Gm::new(
+                Mesh::new(&context, &CpuMesh::cube()),
+                PhysicalMaterial::new_opaque(
+                    &context,
+                    &CpuMaterial {
+                        albedo: Srgba::new_opaque(100 + 30 * i as u8, 120, 220 - 30 * i as u8),
+                        ..Default::default()
+                    },
+                ),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let ambient = AmbientLight::new(&context, 0.1, Srgba::WHITE);
+    // An important light which moves and therefore updates its shadow every frame
+    let mut hero = SpotLight::new(
+        &context,
+        2.0,
+        Srgba::new_opaque(255, 240, 200),
+        vec3(0.0, 10.0, 0.0),
+        vec3(0.0, -1.0, 0.0),
+        degrees(35.0),
+        Attenuation::default(),
+    );
+    // Less important static lights with cheaper shadows
+    let colors = [
+        Srgba::RED,
+        Srgba::GREEN,
+        Srgba::BLUE,
+        Srgba::new_opaque(255, 255, 0),
+        Srgba::new_opaque(0, 255, 255),
+        Srgba::new_opaque(255, 0, 255),
+    ];
+    let mut fills = colors
+        .iter()
+        .enumerate()
+        .map(|(i, color)| {
+            let angle = degrees(60.0 * i as f32);
+            let position = vec3(9.0 * angle.cos(), 6.0, 9.0 * angle.sin());
+            SpotLight::new(
+                &context,
+                0.5,
+                *color,
+                position,
+                -position,
+                degrees(30.0),
+                Attenuation::default(),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let mut scheduler = ShadowScheduler::new();
+    let hero_shadow = scheduler.add(ShadowSettings::new(ShadowQuality::High));
+    let mut fill_settings = ShadowSettings {
+        update: ShadowUpdate::Interval(10),
+        ..ShadowSettings::new(ShadowQuality::Low)
+    };
+    let fill_shadows = fills
+        .iter()
+        .map(|_| scheduler.add(fill_settings))
+        .collect::<Vec<_>>();
+
+    let mut hero_quality = ShadowQuality::High;
+    let mut fill_interval = 10;
+    let mut budget = 4;
+    let mut time = 0.0;
+    window.render_loop(move |mut frame_input| {
+        let mut panel_width = 0.0;
+        gui.update(
+            &mut frame_input.events,
+            frame_input.accumulated_time,
+            frame_input.viewport,
+            frame_input.device_pixel_ratio,
+            |gui_context| {
+                use three_d::egui::*;
+                SidePanel::left("side_panel").show(gui_context, |ui| {
+                    ui.heading("Shadow scheduler");
+                    ui.label("Hero light quality");
+                    ui.radio_value(&mut hero_quality, ShadowQuality::Low, "Low");
+                    ui.radio_value(&mut hero_quality, ShadowQuality::Medium, "Medium");
+                    ui.radio_value(&mut hero_quality, ShadowQuality::High, "High");
+                    ui.add(Slider::new(&mut fill_interval, 1..=60).text("Fill light interval"));
+                    ui.add(Slider::new(&mut budget, 1..=32).text("Budget (512x512 maps)"));
+                    ui.label(format!(
+                        "Hero light staleness: {:?}",
+                        scheduler.staleness(hero_shadow)
+                    ));
+                    for (i, id) in fill_shadows.iter().enumerate() {
+                        ui.label(format!(
+                            "Fill light {} staleness: {:?}",
+                            i,
+                            scheduler.staleness(*id)
+                        ));
+                    }
+                });
+                panel_width = gui_context.used_rect().width();
+            },
+        );
+
+        let viewport = frame_input.viewport_from_logical(
+            panel_width,
+            0.0,
+            frame_input.window_width as f32 - panel_width,
+            frame_input.window_height as f32,
+        );
+        camera.set_viewport(viewport);
+        control.handle_events(&mut camera, &mut frame_input.events);
+
+        time += 0.001 * frame_input.elapsed_time as f32;
+        for (i, b) in boxes.iter_mut().enumerate() {
+            let angle = degrees(72.0 * i as f32);
+            b.set_transformation(
+                Mat4::from_translation(vec3(4.0 * angle.cos(), 1.0, 4.0 * angle.sin()))
+                    * Mat4::from_angle_y(radians(time + i as f32)),
+            );
+        }
+        hero.position = vec3(3.0 * time.cos(), 10.0, 3.0 * time.sin());
+
+        scheduler.set_settings(
+            hero_shadow,
+            ShadowSettings {
+                resolution: hero_quality.resolution(),
+                quality: hero_quality,
+                ..scheduler.settings(hero_shadow)
+            },
+        );
+        fill_settings.update = ShadowUpdate::Interval(fill_interval);
+        for id in fill_shadows.iter() {
+            scheduler.set_settings(*id, fill_settings);
+        }
+        scheduler.texel_budget = budget * 512 * 512;
+
+        let mut lights: Vec<(ShadowId, &mut dyn ShadowCaster)> = vec![(hero_shadow, &mut hero)];
+        for (id, fill) in fill_shadows.iter().zip(fills.iter_mut()) {
+            lights.push((*id, fill));
+        }
+        let casters = boxes
+            .iter()
+            .map(|b| &b.geometry as &dyn Geometry)
+            .chain([&ground.geometry as &dyn Geometry])
+            .collect::<Vec<_>>();
+        scheduler.update(&mut lights, &casters).unwrap();
+
+        let mut all_lights: Vec<&dyn Light> = vec![&ambient, &hero];
+        all_lights.extend(fills.iter().map(|l| l as &dyn Light));
+        frame_input
+            .screen()
+            .clear(ClearState::color_and_depth(0.1, 0.1, 0.1, 1.0, 1.0))
+            .render(&camera, boxes.iter().chain([&ground]), &all_lights)
+            .write(|| gui.render())
+            .unwrap();
+
+        FrameOutput::default()
+    });
+}
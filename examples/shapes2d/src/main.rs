@@ -8,28 +8,25 @@ pub fn main() {
     })
     .unwrap();
     let context = window.gl();
-    let scale_factor = window.device_pixel_ratio();
     let (width, height) = window.size();
 
+    // One unit is one logical pixel, scroll to zoom and drag with the middle mouse button to pan
+    let mut camera = Camera::new_2d_logical(window.viewport(), window.device_pixel_ratio());
+    let mut control = Control2D {
+        pan_button: MouseButton::Middle,
+        align_to_pixel_grid: true,
+        ..Control2D::new(0.5, 16.0)
+    };
+
     let mut rectangle = Gm::new(
-        Rectangle::new(
-            &context,
-            vec2(200.0, 200.0) * scale_factor,
-            degrees(45.0),
-            100.0 * scale_factor,
-            200.0 * scale_factor,
-        ),
+        Rectangle::new(&context, vec2(200.0, 200.0), degrees(45.0), 100.0, 200.0),
         ColorMaterial {
             color: Srgba::RED,
             ..Default::default()
         },
     );
     let mut circle = Gm::new(
-        Circle::new(
-            &context,
-            vec2(500.0, 500.0) * scale_factor,
-            200.0 * scale_factor,
-        ),
+        Circle::new(&context, vec2(500.0, 500.0), 200.0),
         ColorMaterial {
             color: Srgba::BLUE,
             ..Default::default()
@@ -38,9 +35,9 @@ pub fn main() {
     let mut line = Gm::new(
         Line::new(
             &context,
-            vec2(0.0, 0.0) * scale_factor,
-            vec2(width as f32, height as f32) * scale_factor,
-            5.0 * scale_factor,
+            vec2(0.0, 0.0),
+            vec2(width as f32, height as f32),
+            5.0,
         ),
         ColorMaterial {
             color: Srgba::GREEN,
@@ -48,7 +45,14 @@ pub fn main() {
         },
     );
 
-    window.render_loop(move |frame_input| {
+    window.render_loop(move |mut frame_input| {
+        camera.set_viewport_2d(frame_input.viewport, frame_input.device_pixel_ratio);
+        control.handle_events(
+            &mut camera,
+            &mut frame_input.events,
+            frame_input.device_pixel_ratio,
+        );
+
         for event in frame_input.events.iter() {
             if let Event::MousePress {
                 button,
@@ -57,6 +61,8 @@ pub fn main() {
                 ..
             } = *event
             {
+                // Place the shapes at the closest pixel corner so they stay crisp
+                let position = camera.snap_to_pixel(camera.position_at_pixel(position).truncate());
                 if button == MouseButton::Left && !modifiers.ctrl {
                     rectangle.set_center(position);
                 }
@@ -77,7 +83,7 @@ pub fn main() {
             .screen()
             .clear(ClearState::color_and_depth(0.8, 0.8, 0.8, 1.0, 1.0))
             .render(
-                Camera::new_2d(frame_input.viewport),
+                &camera,
                 line.into_iter().chain(&rectangle).chain(&circle),
                 &[],
             );
@@ -0,0 +1,172 @@
+use three_d::*;
+
+const LENGTH: f32 = 3.0;
+const ELBOW: f32 = 1.5;
+
+// An arm along the y-axis, where the lower part follows the first joint, the upper part follows the second joint and the vertices around the elbow are blended
+fn arm() -> (CpuMesh, Vec<[u16; 4]>, Vec<Vec4>) {
+    let rings = 30;
+    let segments = 16;
+    let mut positions = Vec::new();
+    let mut joint_indices = Vec::new();
+    let mut joint_weights = Vec::new();
+    for i in 0..=rings {
+        let y = LENGTH * i as f32 / rings as f32;
+        let t = ((y - ELBOW) / 0.4 + 0.5).clamp(0.0, 1.0);
+        for j in 0..segments {
+            let angle = 2.0 * std::f32::consts::PI * j as f32 / segments as f32;
+            positions.push(vec3(0.2 * angle.cos(), y, 0.2 * angle.sin()));
+            joint_indices.push([0, 1, 0, 0]);
+            joint_weights.push(vec4(1.0 - t, t, 0.0, 0.0));
+        }
+    }
+    let mut indices = Vec::new();
+    for i in 0..rings {
+        for j in 0..segments {
+            let i0 = i * segments + j;
+            let i1 = i * segments + (j + 1) % segments;
+            indices.extend([i0, i1 + segments, i1, i0, i0 + segments, i1 + segments]);
+        }
+    }
+    let mut mesh = CpuMesh {
+        positions: Positions::F32(positions),
+        indices: Indices::U32(indices),
+        ..Default::default()
+    };
+    mesh.compute_normals();
+    (mesh, joint_indices, joint_weights)
+}
+
+fn main() {
+    let viewport = Viewport::new_at_origo(512, 512);
+
+    // Create a headless graphics context
+    let context = HeadlessContext::new().unwrap();
+
+    // The arm is bent 90 degrees at the elbow
+    let (cpu_mesh, joint_indices, joint_weights) = arm();
+    let elbow = vec3(0.0, ELBOW, 0.0);
+    let joints = [
+        Mat4::identity(),
+        Mat4::from_translation(elbow)
+            * Mat4::from_angle_z(degrees(90.0))
+            * Mat4::from_translation(-elbow),
+    ];
+
+    // Skinned on the GPU
+    let mut gpu_skinned = SkinnedMesh::new(&context, &cpu_mesh, &joint_indices, &joint_weights);
+    gpu_skinned.set_joint_matrices(&joints);
+
+    // Skinned on the CPU, which is the reference
+    let cpu_skinned = SkinnedCpuMesh::new(
+        cpu_mesh.clone(),
+        joint_indices
+            .iter()
+            .map(|i| vec4(i[0] as f32, i[1] as f32, i[2] as f32, i[3] as f32))
+            .collect(),
+        joint_weights.clone(),
+    );
+    let cpu_skinned = Mesh::new(&context, &cpu_skinned.skinned_mesh(&joints));
+
+    // The arm in the bind pose, which should cast a different shadow
+    let bind_pose = Mesh::new(&context, &cpu_mesh);
+
+    // Only the ground is rendered, seen from above, so the image only contains the shadow of the arm
+    let mut ground = Gm::new(
+        Mesh::new(&context, &CpuMesh::square()),
+        PhysicalMaterial::new_opaque(&context, &CpuMaterial::default()),
+    );
+    ground.set_transformation(
+        Mat4::from_translation(vec3(0.0, -0.5, 0.0))
+            * Mat4::from_angle_x(degrees(-90.0))
+            * Mat4::from_scale(4.0),
+    );
+    let camera = Camera::new_orthographic(
+        viewport,
+        vec3(0.0, 10.0, 0.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 0.0, -1.0),
+        8.0,
+        0.1,
+        20.0,
+    );
+    let ambient = AmbientLight::new(&context, 0.2, Srgba::WHITE);
+    // The light comes from the side, so the bent arm and the arm in the bind pose cast clearly different shadows
+    let mut directional = DirectionalLight::new(&context, 2.0, Srgba::WHITE, vec3(0.3, -1.0, 0.6));
+
+    let mut texture = Texture2D::new_empty::<[u8; 4]>(
+        &context,
+        viewport.width,
+        viewport.height,
+        Interpolation::Nearest,
+        Interpolation::Nearest,
+        None,
+        Wrapping::ClampToEdge,
+        Wrapping::ClampToEdge,
+    );
+    let mut depth_texture = DepthTexture2D::new::<f32>(
+        &context,
+        viewport.width,
+        viewport.height,
+        Wrapping::ClampToEdge,
+        Wrapping::ClampToEdge,
+    );
+    let mut render_shadow = |caster: &dyn Geometry, name: &str| {
+        directional
+            .generate_shadow_map(1024, [caster, &ground.geometry])
+            .unwrap();
+        let pixels = RenderTarget::new(
+            texture.as_color_target(None),
+            depth_texture.as_depth_target(),
+        )
+        .clear(ClearState::color_and_depth(1.0, 1.0, 1.0, 1.0, 1.0))
+        .render(&camera, &ground, &[&ambient, &directional])
+        .read_color::<[u8; 4]>();
+
+        // Save the rendered image
+        use three_d_asset::io::Serialize;
+        three_d_asset::io::save(
+            &CpuTexture {
+                data: TextureData::RgbaU8(pixels.clone()),
+                width: viewport.width,
+                height: viewport.height,
+                ..Default::default()
+            }
+            .serialize(format!("skinned_shadow-{}.png", name))
+            .unwrap(),
+        )
+        .unwrap();
+
+        // The shadow is the pixels which are darker than the lit ground
+        let brightest = pixels.iter().map(|p| p[0]).max().unwrap();
+        pixels
+            .iter()
+            .map(|p| p[0] < brightest / 2)
+            .collect::<Vec<_>>()
+    };
+
+    let gpu = render_shadow(&gpu_skinned, "gpu");
+    let cpu = render_shadow(&cpu_skinned, "cpu");
+    let bind = render_shadow(&bind_pose, "bind_pose");
+
+    let count = |shadow: &[bool]| shadow.iter().filter(|s| **s).count();
+    let difference = |a: &[bool], b: &[bool]| a.iter().zip(b).filter(|(a, b)| a != b).count();
+    let shadow_pixels = count(&cpu);
+    assert!(shadow_pixels > 1000, "the arm does not cast a shadow");
+
+    // Allow a few pixels along the edge of the silhouette to differ due to rounding
+    let gpu_difference = difference(&gpu, &cpu);
+    let bind_difference = difference(&bind, &cpu);
+    println!(
+        "The shadow covers {} pixels, the GPU skinned shadow differs in {} pixels and the shadow in bind pose differs in {} pixels",
+        shadow_pixels, gpu_difference, bind_difference
+    );
+    assert!(
+        gpu_difference * 100 < shadow_pixels,
+        "the shadow of the GPU skinned arm does not match the CPU skinned reference"
+    );
+    assert!(
+        bind_difference * 10 > shadow_pixels,
+        "the shadow does not depend on the pose"
+    );
+}
@@ -0,0 +1,212 @@
+use three_d::*;
+
+const BONE_COUNT: usize = 5;
+const BONE_LENGTH: f32 = 1.0;
+
+// A chain of joints along the y-axis with a "sway" clip using linear interpolation and a "curl" clip using cubic spline interpolation,
+// which is constructed here but could as well be loaded from a glTF file
+fn tentacle_scene() -> CpuScene {
+    let mut node = CpuNode {
+        name: format!("joint{}", BONE_COUNT),
+        transformation: Mat4::from_translation(vec3(0.0, BONE_LENGTH, 0.0)),
+        ..Default::default()
+    };
+    for i in (0..BONE_COUNT).rev() {
+        let sway = KeyFrames {
+            interpolation: Interpolation::Linear,
+            times: vec![0.0, 0.5, 1.0, 1.5, 2.0],
+            rotations: Some(
+                [0.0, 0.3, 0.0, -0.3, 0.0]
+                    .iter()
+                    .map(|angle| Quat::from_angle_z(radians(*angle)))
+                    .collect(),
+            ),
+            ..Default::default()
+        };
+        // The values of a cubic spline are an in-tangent, a value and an out-tangent for each key frame
+        let zero = Quat::new(0.0, 0.0, 0.0, 0.0);
+        let curl = KeyFrames {
+            interpolation: Interpolation::CubicSpline,
+            times: vec![0.0, 1.0, 2.0],
+            rotations: Some(
+                [0.0, 0.7, 0.0]
+                    .iter()
+                    .flat_map(|angle| [zero, Quat::from_angle_x(radians(*angle)), zero])
+                    .collect(),
+            ),
+            ..Default::default()
+        };
+        node = CpuNode {
+            name: format!("joint{}", i),
+            transformation: if i == 0 {
+                Mat4::identity()
+            } else {
+                Mat4::from_translation(vec3(0.0, BONE_LENGTH, 0.0))
+            },
+            animations: vec![
+                (Some("sway".to_string()), sway),
+                (Some("curl".to_string()), curl),
+            ],
+            children: vec![node],
+            ..Default::default()
+        };
+    }
+    CpuScene {
+        children: vec![node],
+        ..Default::default()
+    }
+}
+
+// A tapered tube around the chain of joints, where each vertex is influenced by the two closest joints
+fn tentacle_mesh(player: &AnimationPlayer) -> (CpuMesh, Vec<[u16; 4]>, Vec<Vec4>) {
+    let rings = 60;
+    let segments = 24;
+    let height = BONE_COUNT as f32 * BONE_LENGTH;
+    let mut positions = Vec::new();
+    let mut joint_indices = Vec::new();
+    let mut joint_weights = Vec::new();
+    for i in 0..=rings {
+        let y = height * i as f32 / rings as f32;
+        let radius = 0.4 * (1.0 - 0.85 * y / height);
+        let bone = ((y / BONE_LENGTH) as usize).min(BONE_COUNT - 1);
+        let t = y / BONE_LENGTH - bone as f32;
+        let joint = |i: usize| player.joint_index(&format!("joint{}", i)).unwrap() as u16;
+        for j in 0..segments {
+            let angle = 2.0 * std::f32::consts::PI * j as f32 / segments as f32;
+            positions.push(vec3(radius * angle.cos(), y, radius * angle.sin()));
+            joint_indices.push([joint(bone), joint(bone + 1), 0, 0]);
+            joint_weights.push(vec4(1.0 - t, t, 0.0, 0.0));
+        }
+    }
+    let mut indices = Vec::new();
+    for i in 0..rings {
+        for j in 0..segments {
+            let i0 = i * segments + j;
+            let i1 = i * segments + (j + 1) % segments;
+            indices.extend([i0, i1 + segments, i1, i0, i0 + segments, i1 + segments]);
+        }
+    }
+    let mut mesh = CpuMesh {
+        positions: Positions::F32(positions),
+        indices: Indices::U32(indices),
+        ..Default::default()
+    };
+    mesh.compute_normals();
+    (mesh, joint_indices, joint_weights)
+}
+
+pub fn main() {
+    let window = Window::new(WindowSettings {
+        title: "Skinning!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl();
+
+    let mut camera = Camera::new_perspective(
+        window.viewport(),
+        vec3(6.0, 4.0, 8.0),
+        vec3(0.0, 2.5, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        100.0,
+    );
+    let mut control = OrbitControl::new(camera.target(), 3.0, 30.0);
+    let mut gui = three_d::GUI::new(&context);
+
+    let mut player = AnimationPlayer::from_scene(&tentacle_scene());
+    let sway = player.clip_index("sway").unwrap();
+    let curl = player.clip_index("curl").unwrap();
+
+    let (cpu_mesh, joint_indices, joint_weights) = tentacle_mesh(&player);
+    let mut tentacle = Gm::new(
+        SkinnedMesh::new(&context, &cpu_mesh, &joint_indices, &joint_weights),
+        PhysicalMaterial::new_opaque(
+            &context,
+            &CpuMaterial {
+                albedo: Srgba::new_opaque(200, 90, 120),
+                roughness: 0.4,
+                ..Default::default()
+            },
+        ),
+    );
+    let mut ground = Gm::new(
+        Mesh::new(&context, &CpuMesh::square()),
+        PhysicalMaterial::new_opaque(
+            &context,
+            &CpuMaterial {
+                albedo: Srgba::new_opaque(120, 130, 120),
+                ..Default::default()
+            },
+        ),
+    );
+    ground.set_transformation(Mat4::from_angle_x(degrees(-90.0)) * Mat4::from_scale(8.0));
+    let mut skeleton = SkeletonGizmo::new(
+        &context,
+        player.parents(),
+        &player.joint_transformations(sway, 0.0),
+    );
+
+    let ambient = AmbientLight::new(&context, 0.3, Srgba::WHITE);
+    let mut directional =
+        DirectionalLight::new(&context, 2.0, Srgba::WHITE, vec3(-1.0, -2.0, -0.5));
+
+    let mut blend = 0.0;
+    let mut speed = 1.0;
+    let mut clamp = false;
+    let mut show_skeleton = true;
+    let mut time = 0.0;
+    window.render_loop(move |mut frame_input| {
+        gui.update(
+            &mut frame_input.events,
+            frame_input.accumulated_time,
+            frame_input.viewport,
+            frame_input.device_pixel_ratio,
+            |gui_context| {
+                use three_d::egui::*;
+                Window::new("Animation").show(gui_context, |ui| {
+                    ui.add(Slider::new(&mut blend, 0.0..=1.0).text("Sway - curl"));
+                    ui.add(Slider::new(&mut speed, 0.0..=3.0).text("Speed"));
+                    ui.checkbox(&mut clamp, "Clamp instead of loop");
+                    if ui.button("Restart").clicked() {
+                        time = 0.0;
+                    }
+                    ui.checkbox(&mut show_skeleton, "Show skeleton");
+                });
+            },
+        );
+        camera.set_viewport(frame_input.viewport);
+        control.handle_events(&mut camera, &mut frame_input.events);
+
+        time += 0.001 * speed * frame_input.elapsed_time as f32;
+        player.wrap = if clamp {
+            AnimationWrap::Clamp
+        } else {
+            AnimationWrap::Loop
+        };
+        let transformations = player.blended_joint_transformations(sway, time, curl, time, blend);
+        tentacle
+            .geometry
+            .set_joint_matrices(&player.joint_matrices_from(&transformations));
+        skeleton.set_joint_transformations(&transformations);
+
+        directional.generate_shadow_map(1024, &tentacle).unwrap();
+
+        let screen = frame_input.screen();
+        screen
+            .clear(ClearState::color_and_depth(0.8, 0.8, 0.85, 1.0, 1.0))
+            .render(
+                &camera,
+                tentacle.into_iter().chain(&ground),
+                &[&ambient, &directional],
+            );
+        if show_skeleton {
+            screen.render(&camera, &skeleton, &[]);
+        }
+        screen.write(|| gui.render()).unwrap();
+
+        FrameOutput::default()
+    });
+}
@@ -71,6 +71,25 @@ pub async fn run() {
         Some(vec3(1.0, 1.0, 0.0).normalize()),
     );
 
+    // A sprite with a feathered edge which is blended using premultiplied alpha to avoid dark fringes against the bright background
+    let feathered_material = ColorMaterial::new_transparent(
+        &context,
+        &CpuMaterial {
+            albedo: Srgba::WHITE,
+            albedo_texture: Some(feathered_disc(64)),
+            ..Default::default()
+        },
+    );
+    let feathered_sprites = Sprites::new(
+        &context,
+        &[
+            vec3(-10.0, 0.0, 5.0),
+            vec3(0.0, 0.0, 5.0),
+            vec3(10.0, 0.0, 5.0),
+        ],
+        None,
+    );
+
     let ambient = AmbientLight::new(&context, 1.0, Srgba::WHITE);
 
     window.render_loop(move |mut frame_input| {
@@ -83,21 +102,33 @@ pub async fn run() {
             .render(
                 &camera,
                 axes.into_iter()
-                    .chain(&Gm {
-                        geometry: &billboards,
-                        material: &material,
-                    })
-                    .chain(&Gm {
-                        geometry: &sprites_up,
-                        material: &material,
-                    })
-                    .chain(&Gm {
-                        geometry: &sprites,
-                        material: &material,
-                    }),
+                    .chain(&Gm::new(&billboards, &material))
+                    .chain(&Gm::new(&sprites_up, &material))
+                    .chain(&Gm::new(&sprites, &material))
+                    .chain(&Gm::new(&feathered_sprites, &feathered_material)),
                 &[&ambient],
             );
 
         FrameOutput::default()
     });
 }
+
+///
+/// Returns an orange disc texture where the alpha value fades out towards the edge.
+///
+fn feathered_disc(size: u32) -> CpuTexture {
+    let data = (0..size * size)
+        .map(|i| {
+            let x = (i % size) as f32 / (size - 1) as f32 * 2.0 - 1.0;
+            let y = (i / size) as f32 / (size - 1) as f32 * 2.0 - 1.0;
+            let alpha = (1.0 - (x * x + y * y).sqrt()).clamp(0.0, 1.0);
+            [255, 160, 40, (alpha * 255.0) as u8]
+        })
+        .collect();
+    CpuTexture {
+        data: TextureData::RgbaU8(data),
+        width: size,
+        height: size,
+        ..Default::default()
+    }
+}
@@ -0,0 +1,171 @@
+use three_d::*;
+
+pub fn main() {
+    let window = Window::new(WindowSettings {
+        title: "Screen-space ambient occlusion!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl();
+
+    let mut camera = Camera::new_perspective(
+        window.viewport(),
+        vec3(6.0, 5.0, 8.0),
+        vec3(0.0, 0.5, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        100.0,
+    );
+    let mut control = OrbitControl::new(camera.target(), 2.0, 50.0);
+    let mut gui = three_d::GUI::new(&context);
+
+    // A ground plane with a cluster of boxes and spheres on it
+    let material = PhysicalMaterial::new_opaque(
+        &context,
+        &CpuMaterial {
+            albedo: Srgba::new_opaque(220, 220, 220),
+            roughness: 0.8,
+            ..Default::default()
+        },
+    );
+    let mut ground = Gm::new(Mesh::new(&context, &CpuMesh::square()), material.clone());
+    ground.set_transformation(Mat4::from_angle_x(degrees(-90.0)) * Mat4::from_scale(10.0));
+    let mut box_transformations = Vec::new();
+    let mut sphere_transformations = Vec::new();
+    for i in 0..5 {
+        for j in 0..5 {
+            let position = vec3(i as f32 - 2.0, 0.0, j as f32 - 2.0) * 1.3;
+            if (i + j) % 2 == 0 {
+                let height = 0.3 + 0.25 * ((i * 3 + j * 7) % 5) as f32;
+                box_transformations.push(
+                    Mat4::from_translation(position + vec3(0.0, height, 0.0))
+                        * Mat4::from_angle_y(degrees(15.0 * (i + j) as f32))
+                        * Mat4::from_nonuniform_scale(0.4, height, 0.4),
+                );
+            } else {
+                sphere_transformations.push(
+                    Mat4::from_translation(position + vec3(0.0, 0.45, 0.0))
+                        * Mat4::from_scale(0.45),
+                );
+            }
+        }
+    }
+    let boxes = Gm::new(
+        InstancedMesh::new(
+            &context,
+            &Instances {
+                transformations: box_transformations,
+                ..Default::default()
+            },
+            &CpuMesh::cube(),
+        ),
+        material.clone(),
+    );
+    let spheres = Gm::new(
+        InstancedMesh::new(
+            &context,
+            &Instances {
+                transformations: sphere_transformations,
+                ..Default::default()
+            },
+            &CpuMesh::sphere(32),
+        ),
+        material,
+    );
+
+    let ambient = AmbientLight::new(&context, 0.7, Srgba::WHITE);
+    let directional = DirectionalLight::new(&context, 1.5, Srgba::WHITE, vec3(-1.0, -2.0, -0.5));
+
+    let mut ssao = SsaoEffect::new(&context, 0.5);
+    let mut enabled = true;
+    let mut occlusion_only = false;
+    let mut use_normals = false;
+
+    window.render_loop(move |mut frame_input| {
+        gui.update(
+            &mut frame_input.events,
+            frame_input.accumulated_time,
+            frame_input.viewport,
+            frame_input.device_pixel_ratio,
+            |gui_context| {
+                use three_d::egui::*;
+                Window::new("Ambient occlusion").show(gui_context, |ui| {
+                    ui.checkbox(&mut enabled, "Enabled");
+                    ui.checkbox(&mut occlusion_only, "Show occlusion only");
+                    ui.checkbox(&mut use_normals, "Use rendered normals");
+                    ui.add(Slider::new(&mut ssao.radius, 0.05..=2.0).text("Radius"));
+                    ui.add(Slider::new(&mut ssao.bias, 0.0..=0.1).text("Bias"));
+                    ui.add(Slider::new(&mut ssao.intensity, 0.5..=4.0).text("Intensity"));
+                    ui.add(Slider::new(&mut ssao.samples, 1..=64).text("Samples"));
+                    ui.add(Slider::new(&mut ssao.blur_radius, 0..=8).text("Blur radius"));
+                });
+            },
+        );
+
+        camera.set_viewport(frame_input.viewport);
+        control.handle_events(&mut camera, &mut frame_input.events);
+        let objects = ground.into_iter().chain(&boxes).chain(&spheres);
+        let (width, height) = (frame_input.viewport.width, frame_input.viewport.height);
+
+        // Render the scene without tone and color mapping, so the ambient occlusion is applied to the linear colors
+        let mut color_texture =
+            context.request_texture::<[f16; 4]>(width, height, Interpolation::Nearest);
+        let mut depth_texture = context.request_depth_texture::<f32>(width, height);
+        camera.disable_tone_and_color_mapping();
+        RenderTarget::new(
+            color_texture.as_color_target(None),
+            depth_texture.as_depth_target(),
+        )
+        .clear(ClearState::color_and_depth(0.5, 0.6, 0.7, 1.0, 1.0))
+        .render(&camera, objects.clone(), &[&ambient, &directional]);
+
+        if enabled || occlusion_only {
+            let mut normal_texture =
+                context.request_texture::<[u8; 4]>(width, height, Interpolation::Nearest);
+            if use_normals {
+                let mut normal_depth_texture = context.request_depth_texture::<f32>(width, height);
+                RenderTarget::new(
+                    normal_texture.as_color_target(None),
+                    normal_depth_texture.as_depth_target(),
+                )
+                .clear(ClearState::color_and_depth(0.5, 0.5, 0.5, 1.0, 1.0))
+                .render_with_material(
+                    &NormalMaterial::default(),
+                    &camera,
+                    objects,
+                    &[],
+                );
+            }
+            ssao.update(
+                &camera,
+                &depth_texture,
+                use_normals.then_some(&*normal_texture),
+            );
+            if occlusion_only {
+                color_texture
+                    .as_color_target(None)
+                    .clear(ClearState::color(1.0, 1.0, 1.0, 1.0));
+            }
+            color_texture
+                .as_color_target(None)
+                .apply_screen_material(&ssao, &camera, &[]);
+        }
+
+        camera.set_default_tone_and_color_mapping();
+        frame_input
+            .screen()
+            .apply_screen_effect(
+                &ScreenEffect::default(),
+                &camera,
+                &[],
+                Some(ColorTexture::Single(&color_texture)),
+                None,
+            )
+            .write(|| gui.render())
+            .unwrap();
+
+        FrameOutput::default()
+    });
+}
@@ -147,13 +147,12 @@ pub async fn run() {
             },
         );
 
-        let viewport = Viewport {
-            x: (panel_width * frame_input.device_pixel_ratio) as i32,
-            y: 0,
-            width: frame_input.viewport.width
-                - (panel_width * frame_input.device_pixel_ratio) as u32,
-            height: frame_input.viewport.height,
-        };
+        let viewport = frame_input.viewport_from_logical(
+            panel_width,
+            0.0,
+            frame_input.window_width as f32 - panel_width,
+            frame_input.window_height as f32,
+        );
         primary_camera.set_viewport(viewport);
         secondary_camera.set_viewport(viewport);
         control.handle_events(&mut primary_camera, &mut frame_input.events);
@@ -0,0 +1,165 @@
+// Entry point for non-wasm
+#[cfg(not(target_arch = "wasm32"))]
+#[tokio::main]
+async fn main() {
+    run().await;
+}
+
+use three_d::*;
+
+pub async fn run() {
+    let window = Window::new(WindowSettings {
+        title: "Subsurface scattering!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl();
+
+    let mut camera = Camera::new_perspective(
+        window.viewport(),
+        vec3(0.0, 1.0, 7.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        100.0,
+    );
+    let mut control = OrbitControl::new(camera.target(), 1.0, 100.0);
+    let mut gui = three_d::GUI::new(&context);
+
+    let mut loaded = three_d_asset::io::load_async(&["examples/assets/suzanne.obj"])
+        .await
+        .unwrap();
+    let cpu_model: CpuModel = loaded.deserialize("suzanne.obj").unwrap();
+    let skin = CpuMaterial {
+        albedo: Srgba::new_opaque(230, 180, 160),
+        roughness: 0.6,
+        ..Default::default()
+    };
+    let wax = CpuMaterial {
+        albedo: Srgba::new_opaque(240, 225, 190),
+        roughness: 0.4,
+        ..Default::default()
+    };
+
+    // The left bust and candle are rendered without subsurface scattering and the right with
+    let mut busts = Vec::new();
+    let mut candles = Vec::new();
+    for x in [-1.5, 1.5] {
+        let mut bust: Gm<_, _> = Model::<PhysicalMaterial>::new(&context, &cpu_model)
+            .unwrap()
+            .remove(0)
+            .into();
+        bust.material = PhysicalMaterial::new_opaque(&context, &skin);
+        bust.set_transformation(Mat4::from_translation(vec3(x, 0.3, 0.0)));
+        busts.push(bust);
+
+        let mut candle = Gm::new(
+            Mesh::new(&context, &CpuMesh::cylinder(32)),
+            PhysicalMaterial::new_opaque(&context, &wax),
+        );
+        candle.set_transformation(
+            Mat4::from_translation(vec3(x, -2.5, 0.0))
+                * Mat4::from_angle_z(degrees(90.0))
+                * Mat4::from_nonuniform_scale(1.2, 0.25, 0.25),
+        );
+        candles.push(candle);
+    }
+    let mut skin_subsurface = SubsurfaceScattering {
+        color: Srgba::new_opaque(255, 90, 60),
+        radius: 0.15,
+        thickness: 0.3,
+        thickness_texture: None,
+        transmission: 2.0,
+    };
+    let mut wax_subsurface = SubsurfaceScattering {
+        color: Srgba::new_opaque(255, 200, 120),
+        radius: 0.3,
+        thickness: 0.2,
+        thickness_texture: None,
+        transmission: 2.0,
+    };
+
+    let ambient = AmbientLight::new(&context, 0.1, Srgba::WHITE);
+    let key = DirectionalLight::new(&context, 0.6, Srgba::WHITE, vec3(-1.0, -0.5, -1.0));
+    let mut back = PointLight::new(
+        &context,
+        3.0,
+        Srgba::new_opaque(255, 230, 200),
+        vec3(0.0, 0.0, -2.0),
+        Attenuation {
+            constant: 0.0,
+            linear: 0.1,
+            quadratic: 0.1,
+        },
+    );
+
+    let mut enabled = true;
+    let mut light_angle = 180.0f32;
+    window.render_loop(move |mut frame_input| {
+        let mut panel_width = 0.0;
+        gui.update(
+            &mut frame_input.events,
+            frame_input.accumulated_time,
+            frame_input.viewport,
+            frame_input.device_pixel_ratio,
+            |gui_context| {
+                use three_d::egui::*;
+                SidePanel::left("side_panel").show(gui_context, |ui| {
+                    ui.heading("Subsurface scattering");
+                    ui.label("Left: Off, Right: On");
+                    ui.checkbox(&mut enabled, "Enabled");
+                    ui.add(
+                        Slider::new(&mut light_angle, 0.0..=360.0)
+                            .text("Light angle (180 is behind)"),
+                    );
+                    ui.label("Skin");
+                    ui.add(Slider::new(&mut skin_subsurface.radius, 0.0..=1.0).text("Radius"));
+                    ui.add(
+                        Slider::new(&mut skin_subsurface.thickness, 0.0..=2.0).text("Thickness"),
+                    );
+                    ui.add(
+                        Slider::new(&mut skin_subsurface.transmission, 0.0..=5.0)
+                            .text("Transmission"),
+                    );
+                    ui.label("Wax");
+                    ui.add(Slider::new(&mut wax_subsurface.radius, 0.0..=1.0).text("Radius"));
+                    ui.add(Slider::new(&mut wax_subsurface.thickness, 0.0..=2.0).text("Thickness"));
+                    ui.add(
+                        Slider::new(&mut wax_subsurface.transmission, 0.0..=5.0)
+                            .text("Transmission"),
+                    );
+                });
+                panel_width = gui_context.used_rect().width();
+            },
+        );
+
+        busts[1].material.subsurface = enabled.then(|| skin_subsurface.clone());
+        candles[1].material.subsurface = enabled.then(|| wax_subsurface.clone());
+        let angle = degrees(light_angle);
+        back.position = vec3(3.0 * angle.sin(), 0.0, 3.0 * angle.cos());
+
+        let viewport = frame_input.viewport_from_logical(
+            panel_width,
+            0.0,
+            frame_input.window_width as f32 - panel_width,
+            frame_input.window_height as f32,
+        );
+        camera.set_viewport(viewport);
+        control.handle_events(&mut camera, &mut frame_input.events);
+
+        frame_input
+            .screen()
+            .clear(ClearState::color_and_depth(0.05, 0.05, 0.08, 1.0, 1.0))
+            .render(
+                &camera,
+                busts.iter().chain(&candles),
+                &[&ambient, &key, &back],
+            )
+            .write(|| gui.render())
+            .unwrap();
+
+        FrameOutput::default()
+    });
+}
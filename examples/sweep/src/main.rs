@@ -0,0 +1,136 @@
+use std::sync::Arc;
+use three_d::*;
+
+pub fn main() {
+    let window = Window::new(WindowSettings {
+        title: "Sweep!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl();
+
+    let mut camera = Camera::new_perspective(
+        window.viewport(),
+        vec3(0.0, 25.0, 45.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        1000.0,
+    );
+    let mut control = OrbitControl::new(camera.target(), 5.0, 200.0);
+    let mut gui = three_d::GUI::new(&context);
+
+    let height_map: Arc<dyn Fn(f32, f32) -> f32 + Send + Sync> =
+        Arc::new(|x, z| 2.0 * (0.15 * x).sin() * (0.1 * z).cos() + (0.05 * (x + z)).sin());
+    let terrain = Terrain::new(
+        &context,
+        PhysicalMaterial::new_opaque(
+            &context,
+            &CpuMaterial {
+                albedo: Srgba::new_opaque(90, 140, 70),
+                roughness: 1.0,
+                ..Default::default()
+            },
+        ),
+        height_map.clone(),
+        80.0,
+        0.5,
+        vec2(0.0, 0.0),
+    );
+
+    // A road which winds across the terrain
+    let road_path = [
+        vec3(-35.0, 0.0, 10.0),
+        vec3(-20.0, 0.0, -5.0),
+        vec3(-5.0, 0.0, 5.0),
+        vec3(10.0, 0.0, -10.0),
+        vec3(25.0, 0.0, 0.0),
+        vec3(35.0, 0.0, 15.0),
+    ];
+    let road = Gm::new(
+        Mesh::new(
+            &context,
+            &Sweep::road(3.0, height_map.clone(), 0.05, 1.0).along_spline(&Spline::new(&road_path)),
+        ),
+        PhysicalMaterial::new_opaque(
+            &context,
+            &CpuMaterial {
+                albedo: Srgba::new_opaque(60, 60, 60),
+                roughness: 0.8,
+                ..Default::default()
+            },
+        ),
+    );
+
+    // A pipe which loops above the terrain
+    let pipe_spline = Spline::new(&[
+        vec3(-20.0, 3.0, 15.0),
+        vec3(-10.0, 8.0, 10.0),
+        vec3(-5.0, 12.0, -5.0),
+        vec3(5.0, 6.0, -8.0),
+        vec3(5.0, 10.0, 5.0),
+        vec3(15.0, 14.0, 0.0),
+        vec3(20.0, 4.0, -15.0),
+    ]);
+    let pipe_material = PhysicalMaterial::new_opaque(
+        &context,
+        &CpuMaterial {
+            albedo: Srgba::new_opaque(200, 120, 50),
+            metallic: 0.8,
+            roughness: 0.3,
+            ..Default::default()
+        },
+    );
+    let mut pipe_radius = 0.5;
+    let mut max_angle = 5.0;
+    let mut pipe = Gm::new(Mesh::new(&context, &CpuMesh::default()), pipe_material);
+
+    let ambient = AmbientLight::new(&context, 0.4, Srgba::WHITE);
+    let directional = DirectionalLight::new(&context, 2.0, Srgba::WHITE, vec3(-1.0, -1.0, -1.0));
+
+    let mut changed = true;
+    window.render_loop(move |mut frame_input| {
+        gui.update(
+            &mut frame_input.events,
+            frame_input.accumulated_time,
+            frame_input.viewport,
+            frame_input.device_pixel_ratio,
+            |gui_context| {
+                use three_d::egui::*;
+                Window::new("Sweep").show(gui_context, |ui| {
+                    changed |= ui
+                        .add(Slider::new(&mut pipe_radius, 0.1..=2.0).text("Pipe radius"))
+                        .changed();
+                    changed |= ui
+                        .add(Slider::new(&mut max_angle, 1.0..=45.0).text("Max angle (degrees)"))
+                        .changed();
+                });
+            },
+        );
+
+        if changed {
+            changed = false;
+            let mut sweep = Sweep::tube(pipe_radius, 24);
+            sweep.max_angle = degrees(max_angle).into();
+            pipe.geometry = Mesh::new(&context, &sweep.along_spline(&pipe_spline));
+        }
+
+        camera.set_viewport(frame_input.viewport);
+        control.handle_events(&mut camera, &mut frame_input.events);
+
+        frame_input
+            .screen()
+            .clear(ClearState::color_and_depth(0.6, 0.8, 1.0, 1.0, 1.0))
+            .render(
+                &camera,
+                terrain.into_iter().chain(&road).chain(&pipe),
+                &[&ambient, &directional],
+            )
+            .write(|| gui.render())
+            .unwrap();
+
+        FrameOutput::default()
+    });
+}
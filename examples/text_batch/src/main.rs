@@ -0,0 +1,85 @@
+use three_d::*;
+
+// The number of counters and the number of glyphs in each counter, which gives 10.000 glyphs that are updated each frame
+const COUNTER_COUNT: u32 = 250;
+const COUNTER_LENGTH: usize = 40;
+
+pub fn main() {
+    let window = Window::new(WindowSettings {
+        title: "Text batch!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+
+    let context = window.gl();
+    let mut camera = Camera::new_2d(window.viewport());
+
+    let mut text_batch = TextBatch::new(
+        &context,
+        include_bytes!("../../text/src/font0.ttf"),
+        0,
+        32.0,
+    )
+    .unwrap();
+    let material = ColorMaterial::default();
+    let mut average_frame_time = 0.0;
+
+    // Render loop
+    window.render_loop(move |frame_input| {
+        camera.set_viewport(frame_input.viewport);
+        let width = frame_input.viewport.width as f32;
+        let height = frame_input.viewport.height as f32;
+
+        // Rewrite all the counters each frame
+        let columns = 5;
+        let rows = COUNTER_COUNT / columns;
+        for i in 0..COUNTER_COUNT {
+            let value = (frame_input.accumulated_time * (i + 1) as f64) as u64;
+            let mut text = format!("#{:03}: {}", i, value);
+            text.truncate(COUNTER_LENGTH);
+            text.extend(std::iter::repeat_n('*', COUNTER_LENGTH - text.len()));
+            text_batch.set_text(
+                i,
+                &text,
+                TextStyle {
+                    position: vec3(
+                        10.0 + (i % columns) as f32 * width / columns as f32,
+                        height - 60.0 - (i / columns) as f32 * (height - 70.0) / rows as f32,
+                        0.0,
+                    ),
+                    size: 10.0,
+                    color: Srgba::new_opaque(
+                        (i * 255 / COUNTER_COUNT) as u8,
+                        200,
+                        255 - (i * 255 / COUNTER_COUNT) as u8,
+                    ),
+                    ..Default::default()
+                },
+            );
+        }
+
+        // Show the average frame time
+        let frame_time = frame_input.elapsed_time;
+        average_frame_time = 0.95 * average_frame_time + 0.05 * frame_time;
+        text_batch.set_text(
+            COUNTER_COUNT,
+            &format!(
+                "{} glyphs, frame time: {:.1} ms",
+                COUNTER_COUNT as usize * COUNTER_LENGTH,
+                average_frame_time
+            ),
+            TextStyle {
+                position: vec3(10.0, height - 30.0, 0.0),
+                size: 24.0,
+                ..Default::default()
+            },
+        );
+
+        frame_input
+            .screen()
+            .clear(ClearState::color_and_depth(0.1, 0.1, 0.1, 1.0, 1.0))
+            .render_with_material(&material, &camera, &text_batch, &[]);
+        FrameOutput::default()
+    });
+}
@@ -0,0 +1,139 @@
+use std::collections::HashMap;
+use three_d::*;
+
+// The full resolution of each texture and the number of textures in each direction
+const TEXTURE_SIZE: u32 = 2048;
+const GRID_SIZE: i32 = 8;
+
+pub fn main() {
+    let window = Window::new(WindowSettings {
+        title: "Texture streaming!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl();
+
+    let mut camera = Camera::new_perspective(
+        window.viewport(),
+        vec3(0.0, 2.0, 12.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        1000.0,
+    );
+    let mut control = FlyControl::new(0.01);
+    let mut gui = three_d::GUI::new(&context);
+
+    // A grid of tiles, each with its own high resolution texture which would require more than 1 GB if all were resident at full resolution
+    let mut streamer = TextureStreamer::new(&context, 64 * 1024 * 1024);
+    let mut tiles = HashMap::new();
+    for x in -GRID_SIZE / 2..GRID_SIZE / 2 {
+        for z in -GRID_SIZE / 2..GRID_SIZE / 2 {
+            let mut tile = Gm::new(
+                Mesh::new(&context, &CpuMesh::square()),
+                ColorMaterial::default(),
+            );
+            tile.set_transformation(
+                Mat4::from_translation(vec3(x as f32 * 2.2, 0.0, z as f32 * 2.2))
+                    * Mat4::from_angle_x(degrees(-90.0)),
+            );
+            let seed = ((x + GRID_SIZE) * 31 + z + GRID_SIZE) as u32;
+            let id = streamer.register(TEXTURE_SIZE, TEXTURE_SIZE, tile.aabb(), move |level| {
+                generate_mip_level(seed, level)
+            });
+            tile.material.texture = Some(streamer.texture(id));
+            tiles.insert(id, tile);
+        }
+    }
+
+    let mut budget_mb = 64;
+    window.render_loop(move |mut frame_input| {
+        streamer.budget = budget_mb * 1024 * 1024;
+        for id in streamer.update(&camera) {
+            tiles.get_mut(&id).unwrap().material.texture = Some(streamer.texture(id));
+        }
+        let stats = streamer.stats();
+
+        gui.update(
+            &mut frame_input.events,
+            frame_input.accumulated_time,
+            frame_input.viewport,
+            frame_input.device_pixel_ratio,
+            |gui_context| {
+                use three_d::egui::*;
+                Window::new("Texture streaming").show(gui_context, |ui| {
+                    ui.add(Slider::new(&mut budget_mb, 8..=512).text("Budget (MB)"));
+                    ui.label(format!("Textures: {}", stats.texture_count));
+                    ui.label(format!(
+                        "Resident: {:.1} MB",
+                        stats.resident_bytes as f64 / (1024.0 * 1024.0)
+                    ));
+                    ui.label(format!(
+                        "Required: {:.1} MB",
+                        stats.required_bytes as f64 / (1024.0 * 1024.0)
+                    ));
+                    ui.label(format!("Limited by budget: {}", stats.budget_limited_count));
+                    ui.label(format!("Pending loads: {}", stats.pending_loads));
+                    ui.label(format!("Completed loads: {}", stats.completed_loads));
+                    ui.label(format!("Evictions: {}", stats.evictions));
+                });
+            },
+        );
+
+        camera.set_viewport(frame_input.viewport);
+        control.handle_events(&mut camera, &mut frame_input.events);
+
+        frame_input
+            .screen()
+            .clear(ClearState::color_and_depth(0.1, 0.1, 0.1, 1.0, 1.0))
+            .render(&camera, tiles.values(), &[])
+            .write(|| gui.render())
+            .unwrap();
+
+        FrameOutput::default()
+    });
+}
+
+///
+/// Generates the given mip level of a texture with a fine grid pattern, where the color of the lines shows the mip level,
+/// so it is easy to see when a higher resolution version of the texture is resident.
+///
+fn generate_mip_level(seed: u32, level: u32) -> CpuTexture {
+    let size = (TEXTURE_SIZE >> level).max(1);
+    let level_colors = [
+        [255, 255, 255],
+        [255, 80, 80],
+        [80, 255, 80],
+        [80, 80, 255],
+        [255, 255, 80],
+        [255, 80, 255],
+    ];
+    let line_color = level_colors[(level as usize).min(level_colors.len() - 1)];
+    let background = [
+        (seed * 37 % 128) as u8,
+        (seed * 71 % 128) as u8,
+        (seed * 13 % 128) as u8,
+    ];
+    // The lines are 4 texels wide at full resolution
+    let spacing = (64 >> level).max(2);
+    let width = (4 >> level).max(1);
+    let data = (0..size * size)
+        .map(|i| {
+            let (x, y) = (i % size, i / size);
+            let color = if x % spacing < width || y % spacing < width {
+                line_color
+            } else {
+                background
+            };
+            [color[0], color[1], color[2], 255]
+        })
+        .collect();
+    CpuTexture {
+        data: TextureData::RgbaU8(data),
+        width: size,
+        height: size,
+        ..Default::default()
+    }
+}
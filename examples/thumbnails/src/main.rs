@@ -0,0 +1,48 @@
+use three_d::*;
+
+fn main() {
+    // Create a headless graphics context
+    let context = HeadlessContext::new().unwrap();
+
+    // A few shapes, including a flat, an elongated and a transparent one, which are all framed automatically
+    let mut plate = CpuMesh::cylinder(32);
+    plate
+        .transform(Mat4::from_nonuniform_scale(0.05, 3.0, 3.0))
+        .unwrap();
+    let mut rod = CpuMesh::cylinder(16);
+    rod.transform(Mat4::from_nonuniform_scale(10.0, 0.2, 0.2))
+        .unwrap();
+    let shapes = [
+        (
+            "sphere",
+            CpuMesh::sphere(32),
+            Srgba::new_opaque(200, 80, 60),
+        ),
+        ("cube", CpuMesh::cube(), Srgba::new_opaque(80, 160, 220)),
+        ("plate", plate, Srgba::new_opaque(220, 200, 120)),
+        ("rod", rod, Srgba::new_opaque(120, 200, 120)),
+        ("glass", CpuMesh::sphere(32), Srgba::new(180, 220, 255, 100)),
+    ];
+
+    // The same renderer, render target and lights are used for all thumbnails
+    let mut renderer = ThumbnailRenderer::new(&context, 128);
+    for (name, cpu_mesh, color) in shapes {
+        let thumbnail = renderer.render_cpu_mesh(
+            &cpu_mesh,
+            &CpuMaterial {
+                albedo: color,
+                roughness: 0.5,
+                ..Default::default()
+            },
+        );
+
+        // Save the thumbnail
+        use three_d_asset::io::Serialize;
+        three_d_asset::io::save(
+            &thumbnail
+                .serialize(format!("thumbnail-{}.png", name))
+                .unwrap(),
+        )
+        .unwrap();
+    }
+}
@@ -0,0 +1,143 @@
+use three_d::*;
+
+pub fn main() {
+    let window = Window::new(WindowSettings {
+        title: "Toon!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl();
+
+    let mut camera = Camera::new_perspective(
+        window.viewport(),
+        vec3(5.0, 4.0, 6.0),
+        vec3(0.0, 0.8, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        100.0,
+    );
+    let mut control = OrbitControl::new(camera.target(), 2.0, 50.0);
+    let mut gui = three_d::GUI::new(&context);
+
+    let mut models = Vec::new();
+    for (i, (mesh, color)) in [
+        (CpuMesh::sphere(64), Srgba::new_opaque(255, 120, 60)),
+        (CpuMesh::cylinder(64), Srgba::new_opaque(80, 180, 255)),
+        (CpuMesh::cube(), Srgba::new_opaque(120, 220, 100)),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        let mut mesh = Mesh::new(&context, &mesh);
+        mesh.set_transformation(
+            Mat4::from_translation(vec3(-2.0 + 2.0 * i as f32, 0.8, 0.0))
+                * Mat4::from_angle_z(degrees(if i == 1 { 90.0 } else { 0.0 }))
+                * Mat4::from_scale(0.7),
+        );
+        models.push((mesh, color));
+    }
+    let mut ground = Mesh::new(&context, &CpuMesh::square());
+    ground.set_transformation(Mat4::from_angle_x(degrees(-90.0)) * Mat4::from_scale(6.0));
+
+    // A ramp with a dark blue shade, a mid tone and a bright highlight
+    let ramp = Texture2DRef::from_cpu_texture(
+        &context,
+        &CpuTexture {
+            data: TextureData::RgbaU8(vec![
+                [40, 40, 90, 255],
+                [150, 140, 170, 255],
+                [150, 140, 170, 255],
+                [255, 250, 230, 255],
+            ]),
+            width: 4,
+            height: 1,
+            min_filter: Interpolation::Nearest,
+            mag_filter: Interpolation::Nearest,
+            mipmap: None,
+            ..Default::default()
+        },
+    );
+
+    let ambient = AmbientLight::new(&context, 0.15, Srgba::WHITE);
+    let mut directional =
+        DirectionalLight::new(&context, 1.0, Srgba::WHITE, vec3(-1.0, -1.5, -0.5));
+    let point = PointLight::new(
+        &context,
+        1.0,
+        Srgba::new_opaque(255, 120, 200),
+        vec3(0.0, 2.5, 2.0),
+        Attenuation {
+            constant: 1.0,
+            linear: 0.3,
+            quadratic: 0.1,
+        },
+    );
+
+    let mut material = ToonMaterial {
+        outline_width: 0.03,
+        ..Default::default()
+    };
+    let mut use_ramp = false;
+    let mut shadows = true;
+    window.render_loop(move |mut frame_input| {
+        gui.update(
+            &mut frame_input.events,
+            frame_input.accumulated_time,
+            frame_input.viewport,
+            frame_input.device_pixel_ratio,
+            |gui_context| {
+                use three_d::egui::*;
+                Window::new("Toon").show(gui_context, |ui| {
+                    ui.add(Slider::new(&mut material.bands, 2..=6).text("Bands"));
+                    ui.checkbox(&mut use_ramp, "Ramp texture");
+                    ui.add(Slider::new(&mut material.shininess, 1.0..=128.0).text("Shininess"));
+                    ui.add(
+                        Slider::new(&mut material.specular_cutoff, 0.0..=1.0)
+                            .text("Specular cutoff"),
+                    );
+                    ui.add(
+                        Slider::new(&mut material.outline_width, 0.0..=0.1).text("Outline width"),
+                    );
+                    ui.checkbox(&mut shadows, "Shadows");
+                });
+            },
+        );
+        material.ramp = use_ramp.then(|| ramp.clone());
+
+        camera.set_viewport(frame_input.viewport);
+        control.handle_events(&mut camera, &mut frame_input.events);
+
+        if shadows {
+            directional
+                .generate_shadow_map(1024, models.iter().map(|(mesh, _)| mesh))
+                .unwrap();
+        } else {
+            directional.clear_shadow_map();
+        }
+
+        let lights: [&dyn Light; 3] = [&ambient, &directional, &point];
+        let screen = frame_input.screen();
+        screen.clear(ClearState::color_and_depth(0.9, 0.9, 0.85, 1.0, 1.0));
+        let ground_material = ToonMaterial {
+            color: Srgba::new_opaque(220, 210, 190),
+            ..material.clone()
+        };
+        screen.render_with_material(&ground_material, &camera, &ground, &lights);
+        for (mesh, color) in models.iter() {
+            let model_material = ToonMaterial {
+                color: *color,
+                ..material.clone()
+            };
+            screen.render_with_material(&model_material, &camera, mesh, &lights);
+            // The outline is rendered in a second pass
+            if let Some(outline) = model_material.outline_material() {
+                screen.render_with_material(&outline, &camera, mesh, &[]);
+            }
+        }
+        screen.write(|| gui.render()).unwrap();
+
+        FrameOutput::default()
+    });
+}
@@ -0,0 +1,206 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use three_d::*;
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Metadata, Subscriber};
+
+///
+/// A minimal subscriber which sums up the time spent in each span by name.
+/// Replace it with the layer from tracing-tracy to view each frame in Tracy.
+///
+#[derive(Default)]
+struct SpanTimer {
+    next_id: AtomicU64,
+    spans: Mutex<HashMap<u64, (&'static str, Option<Instant>)>>,
+    totals: Mutex<HashMap<&'static str, (Duration, u32)>>,
+}
+
+impl SpanTimer {
+    fn take_totals(&self) -> Vec<(&'static str, Duration, u32)> {
+        let mut totals = self
+            .totals
+            .lock()
+            .unwrap()
+            .drain()
+            .map(|(name, (duration, count))| (name, duration, count))
+            .collect::<Vec<_>>();
+        totals.sort_by_key(|total| std::cmp::Reverse(total.1));
+        totals
+    }
+}
+
+impl Subscriber for SpanTimer {
+    fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, span: &Attributes<'_>) -> Id {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+        self.spans
+            .lock()
+            .unwrap()
+            .insert(id, (span.metadata().name(), None));
+        Id::from_u64(id)
+    }
+
+    fn record(&self, _span: &Id, _values: &Record<'_>) {}
+
+    fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+    fn event(&self, _event: &Event<'_>) {}
+
+    fn enter(&self, span: &Id) {
+        if let Some((_, start)) = self.spans.lock().unwrap().get_mut(&span.into_u64()) {
+            *start = Some(Instant::now());
+        }
+    }
+
+    fn exit(&self, span: &Id) {
+        if let Some((name, Some(start))) = self.spans.lock().unwrap().get(&span.into_u64()) {
+            let mut totals = self.totals.lock().unwrap();
+            let total = totals.entry(name).or_default();
+            total.0 += start.elapsed();
+            total.1 += 1;
+        }
+    }
+
+    fn try_close(&self, span: Id) -> bool {
+        self.spans.lock().unwrap().remove(&span.into_u64());
+        true
+    }
+}
+
+pub fn main() {
+    let timer = std::sync::Arc::new(SpanTimer::default());
+    tracing::subscriber::set_global_default(timer.clone()).unwrap();
+
+    let window = Window::new(WindowSettings {
+        title: "Tracing!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl();
+
+    let mut camera = Camera::new_perspective(
+        window.viewport(),
+        vec3(6.0, 4.0, 6.0),
+        vec3(0.0, 0.5, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        100.0,
+    );
+    let mut control = OrbitControl::new(camera.target(), 2.0, 50.0);
+
+    let mut spheres = Vec::new();
+    for i in 0..5 {
+        let mut sphere = Gm::new(
+            Mesh::new(&context, &CpuMesh::sphere(32)),
+            DeferredPhysicalMaterial::new(
+                &context,
+                &CpuMaterial {
+                    albedo: Srgba::new_opaque(200, 80 + 30 * i, 60),
+                    roughness: 0.4,
+                    ..Default::default()
+                },
+            ),
+        );
+        sphere.set_transformation(
+            Mat4::from_translation(vec3(-3.0 + 1.5 * i as f32, 0.5, 0.0)) * Mat4::from_scale(0.5),
+        );
+        spheres.push(sphere);
+    }
+    let mut cube = Gm::new(
+        Mesh::new(&context, &CpuMesh::cube()),
+        PhysicalMaterial::new_transparent(
+            &context,
+            &CpuMaterial {
+                albedo: Srgba::new(100, 150, 220, 150),
+                ..Default::default()
+            },
+        ),
+    );
+    cube.set_transformation(Mat4::from_translation(vec3(0.0, 0.5, 2.0)) * Mat4::from_scale(0.5));
+    let mut ground = Gm::new(
+        Mesh::new(&context, &CpuMesh::square()),
+        PhysicalMaterial::new_opaque(
+            &context,
+            &CpuMaterial {
+                albedo: Srgba::new_opaque(120, 120, 120),
+                ..Default::default()
+            },
+        ),
+    );
+    ground.set_transformation(Mat4::from_angle_x(degrees(-90.0)) * Mat4::from_scale(10.0));
+
+    let ambient = AmbientLight::new(&context, 0.2, Srgba::WHITE);
+    let mut directional =
+        DirectionalLight::new(&context, 2.0, Srgba::WHITE, vec3(-1.0, -1.0, -0.5));
+    let mut spot = SpotLight::new(
+        &context,
+        3.0,
+        Srgba::new_opaque(255, 200, 150),
+        vec3(2.0, 4.0, 2.0),
+        vec3(-1.0, -2.0, -1.0),
+        degrees(30.0),
+        Attenuation::default(),
+    );
+
+    let mut frame_count = 0;
+    let mut last_print = Instant::now();
+    window.render_loop(move |mut frame_input| {
+        camera.set_viewport(frame_input.viewport);
+        control.handle_events(&mut camera, &mut frame_input.events);
+
+        // The shadows are generated every frame to show up in the trace
+        let shadow_casters = spheres.iter().flatten().chain(&cube);
+        directional
+            .generate_shadow_map(1024, shadow_casters.clone())
+            .unwrap();
+        spot.generate_shadow_map(512, shadow_casters).unwrap();
+
+        // Render the scene into a texture and apply FXAA to show a post-processing effect in the trace
+        let (width, height) = (frame_input.viewport.width, frame_input.viewport.height);
+        let mut color_texture =
+            context.request_texture::<[u8; 4]>(width, height, Interpolation::Linear);
+        let mut depth_texture = context.request_depth_texture::<f32>(width, height);
+        RenderTarget::new(
+            color_texture.as_color_target(None),
+            depth_texture.as_depth_target(),
+        )
+        .clear(ClearState::color_and_depth(0.6, 0.7, 0.9, 1.0, 1.0))
+        .render(
+            &camera,
+            spheres.iter().flatten().chain(&cube).chain(&ground),
+            &[&ambient, &directional, &spot],
+        );
+        frame_input.screen().apply_screen_effect(
+            &FxaaEffect::default(),
+            &camera,
+            &[],
+            Some(ColorTexture::Single(&color_texture)),
+            None,
+        );
+
+        // The spans measure the time spent on the CPU, which includes the time waiting for the GPU when the driver blocks
+        frame_count += 1;
+        if last_print.elapsed() > Duration::from_secs(1) {
+            println!("Average time per frame over {} frames:", frame_count);
+            for (name, duration, count) in timer.take_totals() {
+                println!(
+                    "  {:<32} {:>8.3} ms {:>6} calls",
+                    name,
+                    duration.as_secs_f64() * 1000.0 / frame_count as f64,
+                    count / frame_count
+                );
+            }
+            frame_count = 0;
+            last_print = Instant::now();
+        }
+
+        FrameOutput::default()
+    });
+}
@@ -0,0 +1,207 @@
+use three_d::*;
+
+pub fn main() {
+    let window = Window::new(WindowSettings {
+        title: "Triplanar mapping!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl();
+
+    let mut camera = Camera::new_perspective(
+        window.viewport(),
+        vec3(14.0, 10.0, 14.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        1000.0,
+    );
+    let mut control = OrbitControl::new(camera.target(), 5.0, 60.0);
+    let mut gui = three_d::GUI::new(&context);
+
+    // Stone tiles with bevelled edges, the albedo and normal map are computed from the same height field
+    let size = 256;
+    let tile_size = 64;
+    let tile = |x: usize, y: usize| {
+        (
+            x / tile_size,
+            (y + (x / tile_size % 2) * tile_size / 2) / tile_size,
+        )
+    };
+    let height = |x: usize, y: usize| {
+        let (x, y) = (x % size, y % size);
+        let offset = (x / tile_size % 2) * tile_size / 2;
+        let u = (x % tile_size) as f32;
+        let v = ((y + offset) % tile_size) as f32;
+        let edge = u
+            .min(v)
+            .min(tile_size as f32 - 1.0 - u)
+            .min(tile_size as f32 - 1.0 - v);
+        (edge / 6.0).min(1.0)
+    };
+    let mut albedo = Vec::with_capacity(size * size);
+    let mut normals = Vec::with_capacity(size * size);
+    for y in 0..size {
+        for x in 0..size {
+            let (tx, ty) = tile(x, y);
+            let shade = (((tx * 7 + ty * 13) % 5) as f32 * 0.08 + 0.6) * 255.0;
+            let h = height(x, y);
+            albedo.push(if h > 0.0 {
+                [shade as u8, (shade * 0.9) as u8, (shade * 0.8) as u8, 255]
+            } else {
+                [60, 55, 50, 255]
+            });
+            let dx = height(x + 1, y) - height(x + size - 1, y);
+            let dy = height(x, y + 1) - height(x, y + size - 1);
+            let n = vec3(-2.0 * dx, -2.0 * dy, 1.0).normalize();
+            normals.push([
+                ((0.5 * n.x + 0.5) * 255.0) as u8,
+                ((0.5 * n.y + 0.5) * 255.0) as u8,
+                ((0.5 * n.z + 0.5) * 255.0) as u8,
+            ]);
+        }
+    }
+    let cpu_material = CpuMaterial {
+        albedo: Srgba::WHITE,
+        roughness: 0.8,
+        albedo_texture: Some(CpuTexture {
+            data: TextureData::RgbaU8(albedo),
+            width: size as u32,
+            height: size as u32,
+            ..Default::default()
+        }),
+        normal_texture: Some(CpuTexture {
+            data: TextureData::RgbU8(normals),
+            width: size as u32,
+            height: size as u32,
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let mut material = PhysicalMaterial::new_opaque(&context, &cpu_material);
+    material.texture_mapping = TextureMapping::Triplanar {
+        scale: 0.5,
+        blend_sharpness: 4.0,
+    };
+
+    // A terrain with terraces and steep cliffs and without uv coordinates
+    let terrain_height = |x: f32, z: f32| {
+        let base = 2.0 * (0.3 * x).sin() * (0.25 * z).cos() + (0.15 * (x + z)).sin();
+        let terrace = base.floor() + (base - base.floor()).powi(6);
+        1.5 * terrace
+    };
+    let resolution = 160;
+    let extent = 12.0;
+    let mut positions = Vec::new();
+    for j in 0..=resolution {
+        for i in 0..=resolution {
+            let x = extent * (2.0 * i as f32 / resolution as f32 - 1.0);
+            let z = extent * (2.0 * j as f32 / resolution as f32 - 1.0);
+            positions.push(vec3(x, terrain_height(x, z), z));
+        }
+    }
+    let mut indices = Vec::new();
+    for j in 0..resolution {
+        for i in 0..resolution {
+            let i0 = j * (resolution + 1) + i;
+            let i1 = i0 + resolution + 1;
+            indices.extend([i0, i1, i0 + 1, i0 + 1, i1, i1 + 1]);
+        }
+    }
+    let mut terrain_mesh = CpuMesh {
+        positions: Positions::F32(positions),
+        indices: Indices::U32(indices),
+        ..Default::default()
+    };
+    terrain_mesh.compute_normals();
+    let mut terrain = Gm::new(Mesh::new(&context, &terrain_mesh), material.clone());
+
+    // Instanced boulders without uv coordinates, the texture stays fixed in the world while the boulders are rotated and scaled
+    let mut boulder_mesh = CpuMesh::sphere(32);
+    boulder_mesh.uvs = None;
+    boulder_mesh.tangents = None;
+    if let Positions::F32(ref mut positions) = boulder_mesh.positions {
+        for p in positions.iter_mut() {
+            let bumps = (3.0 * p.x).sin() * (4.0 * p.y).sin() * (5.0 * p.z).cos();
+            *p *= 1.0 + 0.25 * bumps;
+        }
+    }
+    boulder_mesh.compute_normals();
+    let transformations = (0..12)
+        .map(|i| {
+            let angle = i as f32 * 2.4;
+            let radius = 3.0 + 0.7 * i as f32;
+            let (x, z) = (radius * angle.cos(), radius * angle.sin());
+            let scale = 0.6 + 0.1 * (i % 4) as f32;
+            Mat4::from_translation(vec3(x, terrain_height(x, z) + 0.5 * scale, z))
+                * Mat4::from_angle_y(radians(angle))
+                * Mat4::from_nonuniform_scale(scale, 0.7 * scale, scale)
+        })
+        .collect();
+    let mut boulders = Gm::new(
+        InstancedMesh::new(
+            &context,
+            &Instances {
+                transformations,
+                ..Default::default()
+            },
+            &boulder_mesh,
+        ),
+        material,
+    );
+
+    let ambient = AmbientLight::new(&context, 0.3, Srgba::WHITE);
+    let directional = DirectionalLight::new(&context, 2.5, Srgba::WHITE, vec3(-1.0, -1.5, -0.5));
+
+    let mut triplanar = true;
+    let mut scale = 0.5;
+    let mut blend_sharpness = 4.0;
+    window.render_loop(move |mut frame_input| {
+        gui.update(
+            &mut frame_input.events,
+            frame_input.accumulated_time,
+            frame_input.viewport,
+            frame_input.device_pixel_ratio,
+            |gui_context| {
+                use three_d::egui::*;
+                Window::new("Texture mapping").show(gui_context, |ui| {
+                    ui.radio_value(&mut triplanar, false, "Uv (the meshes have no uvs)");
+                    ui.radio_value(&mut triplanar, true, "Triplanar");
+                    ui.add_enabled(triplanar, Slider::new(&mut scale, 0.1..=2.0).text("Scale"));
+                    ui.add_enabled(
+                        triplanar,
+                        Slider::new(&mut blend_sharpness, 1.0..=16.0).text("Blend sharpness"),
+                    );
+                });
+            },
+        );
+        let texture_mapping = if triplanar {
+            TextureMapping::Triplanar {
+                scale,
+                blend_sharpness,
+            }
+        } else {
+            TextureMapping::Uv
+        };
+        terrain.material.texture_mapping = texture_mapping;
+        boulders.material.texture_mapping = texture_mapping;
+
+        camera.set_viewport(frame_input.viewport);
+        control.handle_events(&mut camera, &mut frame_input.events);
+
+        frame_input
+            .screen()
+            .clear(ClearState::color_and_depth(0.6, 0.75, 0.9, 1.0, 1.0))
+            .render(
+                &camera,
+                terrain.into_iter().chain(&boulders),
+                &[&ambient, &directional],
+            )
+            .write(|| gui.render())
+            .unwrap();
+
+        FrameOutput::default()
+    });
+}
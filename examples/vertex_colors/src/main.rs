@@ -0,0 +1,126 @@
+use three_d::*;
+
+fn main() {
+    let viewport = Viewport::new_at_origo(256, 256);
+
+    // Create a headless graphics context
+    let context = HeadlessContext::new().unwrap();
+
+    let camera = Camera::new_perspective(
+        viewport,
+        vec3(0.0, 0.0, 3.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        0.1,
+        10.0,
+    );
+    let ambient = AmbientLight::new(&context, 0.3, Srgba::WHITE);
+    let directional = DirectionalLight::new(&context, 2.0, Srgba::WHITE, vec3(-1.0, -1.0, -1.0));
+
+    // The same color given as vertex colors and as the albedo, where the albedo of the vertex colored sphere is white
+    let color = Srgba::new(230, 120, 40, 255);
+    let sphere = CpuMesh::sphere(32);
+    let vertex_colored_sphere = Mesh::new(
+        &context,
+        &CpuMesh {
+            colors: Some(vec![color; sphere.positions.len()]),
+            ..sphere.clone()
+        },
+    );
+    let sphere = Mesh::new(&context, &sphere);
+    let colored = CpuMaterial {
+        albedo: color,
+        ..Default::default()
+    };
+
+    let mut texture = Texture2D::new_empty::<[u8; 4]>(
+        &context,
+        viewport.width,
+        viewport.height,
+        Interpolation::Nearest,
+        Interpolation::Nearest,
+        None,
+        Wrapping::ClampToEdge,
+        Wrapping::ClampToEdge,
+    );
+    let mut depth_texture = DepthTexture2D::new::<f32>(
+        &context,
+        viewport.width,
+        viewport.height,
+        Wrapping::ClampToEdge,
+        Wrapping::ClampToEdge,
+    );
+    let mut render = |geometry: &Mesh, material: &dyn Material, name: &str| {
+        let pixels = RenderTarget::new(
+            texture.as_color_target(None),
+            depth_texture.as_depth_target(),
+        )
+        .clear(ClearState::color_and_depth(0.8, 0.8, 0.8, 1.0, 1.0))
+        .render_with_material(material, &camera, geometry, &[&ambient, &directional])
+        .read_color::<[u8; 4]>();
+
+        // Save the rendered image
+        use three_d_asset::io::Serialize;
+        three_d_asset::io::save(
+            &CpuTexture {
+                data: TextureData::RgbaU8(pixels.clone()),
+                width: viewport.width,
+                height: viewport.height,
+                ..Default::default()
+            }
+            .serialize(format!("vertex_colors-{}.png", name))
+            .unwrap(),
+        )
+        .unwrap();
+        pixels
+    };
+
+    // Verify that the vertex colors and the albedo give the same result, both for a lit and an unlit material
+    let white = CpuMaterial::default();
+    for (name, vertex_colored, albedo) in [
+        (
+            "physical",
+            render(
+                &vertex_colored_sphere,
+                &PhysicalMaterial::new_opaque(&context, &white),
+                "physical-vertex",
+            ),
+            render(
+                &sphere,
+                &PhysicalMaterial::new_opaque(&context, &colored),
+                "physical-albedo",
+            ),
+        ),
+        (
+            "color",
+            render(
+                &vertex_colored_sphere,
+                &ColorMaterial::new_opaque(&context, &white),
+                "color-vertex",
+            ),
+            render(
+                &sphere,
+                &ColorMaterial::new_opaque(&context, &colored),
+                "color-albedo",
+            ),
+        ),
+    ] {
+        let max_difference = vertex_colored
+            .iter()
+            .zip(albedo.iter())
+            .flat_map(|(a, b)| (0..4).map(move |i| a[i].abs_diff(b[i])))
+            .max()
+            .unwrap();
+        assert!(
+            max_difference <= 1,
+            "The vertex colored sphere differs from the albedo colored sphere by {} using the {} material",
+            max_difference,
+            name
+        );
+        println!(
+            "The vertex colored and the albedo colored sphere are identical using the {} material",
+            name
+        );
+    }
+}
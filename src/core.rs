@@ -28,16 +28,42 @@ mod program;
 #[doc(inline)]
 pub use program::*;
 
+mod shader_include;
+#[doc(inline)]
+pub use shader_include::*;
+
 mod scissor_box;
 #[doc(inline)]
 pub use scissor_box::*;
 
+mod color;
+#[doc(inline)]
+pub use color::*;
+
+mod occlusion_query;
+#[doc(inline)]
+pub use occlusion_query::*;
+
+mod transforms;
+#[doc(inline)]
+pub use transforms::*;
+
 pub mod prelude {
 
     //!
     //! Basic types used throughout this crate, mostly basic math.
     //!
     pub use three_d_asset::prelude::*;
+
+    ///
+    /// A [Vector3] with f64 data type, for example a position in a large world, see [Context::set_world_origin](crate::core::Context::set_world_origin).
+    ///
+    pub type DVec3 = Vector3<f64>;
+
+    ///
+    /// A [Matrix4] with f64 data type, for example the transformation of an object in a large world, see [Context::set_world_origin](crate::core::Context::set_world_origin).
+    ///
+    pub type DMat4 = Matrix4<f64>;
 }
 pub use prelude::*;
 pub use three_d_asset::Viewport;
@@ -57,12 +83,16 @@ pub enum CoreError {
     ContextError(String),
     #[error("failed compiling {0} shader\n\nsource:\n{1}\n\nlog:\n{2}")]
     ShaderCompilation(String, String, String),
+    #[error("failed to resolve shader includes: {0}")]
+    ShaderInclude(String),
     #[error("failed to link shader program: {0}")]
     ShaderLink(String),
     #[error(
         "failed to compile a shader program, probably due to performance or memory constraints"
     )]
     ShaderCompilerError,
+    #[error("failed to read KTX2 data: {0}")]
+    Ktx2(String),
 }
 
 pub(crate) fn full_screen_draw(
@@ -98,10 +128,17 @@ pub(crate) fn full_screen_vertex_shader_source() -> &'static str {
 
 mod data_type;
 use data_type::DataType;
+pub(crate) use data_type::PrimitiveDataType;
 fn to_byte_slice<T: DataType>(data: &[T]) -> &[u8] {
     unsafe { std::slice::from_raw_parts(data.as_ptr() as *const _, std::mem::size_of_val(data)) }
 }
 
+fn to_mut_byte_slice<T: DataType>(data: &mut [T]) -> &mut [u8] {
+    unsafe {
+        std::slice::from_raw_parts_mut(data.as_mut_ptr() as *mut _, std::mem::size_of_val(data))
+    }
+}
+
 fn from_byte_slice<T: DataType>(data: &[u8]) -> &[T] {
     unsafe {
         let (_prefix, values, _suffix) = data.align_to::<T>();
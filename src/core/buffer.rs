@@ -68,6 +68,10 @@ impl<T: BufferDataType> Buffer<T> {
     }
 
     pub fn fill(&mut self, data: &[T]) {
+        trace_span!(
+            "three_d::upload_buffer",
+            bytes = std::mem::size_of_val(data)
+        );
         self.bind();
         unsafe {
             self.context.buffer_data_u8_slice(
@@ -85,16 +89,65 @@ impl<T: BufferDataType> Buffer<T> {
     }
 
     pub fn fill_subset(&mut self, offset: u32, data: &[T]) {
+        trace_span!(
+            "three_d::upload_buffer",
+            bytes = std::mem::size_of_val(data)
+        );
+        let element_size = std::mem::size_of::<T>() as i32;
+        let end = offset + data.len() as u32;
+        if end > self.attribute_count {
+            // Grow the buffer and keep the existing data, since sub data cannot be written outside the allocated buffer
+            unsafe {
+                let id = self
+                    .context
+                    .create_buffer()
+                    .expect("Failed creating buffer");
+                self.context
+                    .bind_buffer(crate::context::ARRAY_BUFFER, Some(id));
+                self.context.buffer_data_size(
+                    crate::context::ARRAY_BUFFER,
+                    end as i32 * element_size,
+                    crate::context::DYNAMIC_DRAW,
+                );
+                if self.attribute_count > 0 {
+                    self.context
+                        .bind_buffer(crate::context::COPY_READ_BUFFER, Some(self.id));
+                    self.context.copy_buffer_sub_data(
+                        crate::context::COPY_READ_BUFFER,
+                        crate::context::ARRAY_BUFFER,
+                        0,
+                        0,
+                        self.attribute_count as i32 * element_size,
+                    );
+                    self.context
+                        .bind_buffer(crate::context::COPY_READ_BUFFER, None);
+                }
+                self.context.delete_buffer(self.id);
+                self.id = id;
+            }
+            self.attribute_count = end;
+        }
         self.bind();
         unsafe {
             self.context.buffer_sub_data_u8_slice(
                 crate::context::ARRAY_BUFFER,
-                offset as i32,
+                offset as i32 * element_size,
                 to_byte_slice(data),
             );
             self.context.bind_buffer(crate::context::ARRAY_BUFFER, None);
         }
-        self.attribute_count = (offset + data.len() as u32).max(self.attribute_count);
+    }
+
+    pub fn copy(&self) -> Self {
+        let mut buffer = Self::new(&self.context);
+        let size = self.attribute_count as i32 * std::mem::size_of::<T>() as i32;
+        if size > 0 {
+            unsafe {
+                copy_buffer(&self.context, self.id, buffer.id, size);
+            }
+        }
+        buffer.attribute_count = self.attribute_count;
+        buffer
     }
 
     pub fn attribute_count(&self) -> u32 {
@@ -109,6 +162,33 @@ impl<T: BufferDataType> Buffer<T> {
     }
 }
 
+///
+/// Allocates the target buffer with the given size in bytes and copies the data of the source buffer into it on the GPU.
+///
+unsafe fn copy_buffer(
+    context: &Context,
+    source: crate::context::Buffer,
+    target: crate::context::Buffer,
+    size: i32,
+) {
+    context.bind_buffer(crate::context::COPY_WRITE_BUFFER, Some(target));
+    context.buffer_data_size(
+        crate::context::COPY_WRITE_BUFFER,
+        size,
+        crate::context::STATIC_DRAW,
+    );
+    context.bind_buffer(crate::context::COPY_READ_BUFFER, Some(source));
+    context.copy_buffer_sub_data(
+        crate::context::COPY_READ_BUFFER,
+        crate::context::COPY_WRITE_BUFFER,
+        0,
+        0,
+        size,
+    );
+    context.bind_buffer(crate::context::COPY_READ_BUFFER, None);
+    context.bind_buffer(crate::context::COPY_WRITE_BUFFER, None);
+}
+
 impl<T: BufferDataType> Drop for Buffer<T> {
     fn drop(&mut self) {
         unsafe {
@@ -67,6 +67,10 @@ impl<T: ElementBufferDataType> ElementBuffer<T> {
     /// This function will resize the buffer to have the same size as the indices array, if that is not desired, use [fill_subset](Self::fill_subset) instead.
     ///
     pub fn fill(&mut self, indices: &[T]) {
+        trace_span!(
+            "three_d::upload_buffer",
+            bytes = std::mem::size_of_val(indices)
+        );
         self.bind();
         unsafe {
             self.context.buffer_data_u8_slice(
@@ -85,6 +89,10 @@ impl<T: ElementBufferDataType> ElementBuffer<T> {
     /// This will increase the size of the buffer if there's not enough room. Otherwise, the size will remain unchanged.
     ///
     pub fn fill_subset(&mut self, offset: u32, indices: &[T]) {
+        trace_span!(
+            "three_d::upload_buffer",
+            bytes = std::mem::size_of_val(indices)
+        );
         self.bind();
         unsafe {
             self.context.buffer_sub_data_u8_slice(
@@ -112,6 +120,21 @@ impl<T: ElementBufferDataType> ElementBuffer<T> {
         self.count / 3
     }
 
+    ///
+    /// Returns a new element buffer containing a copy of the indices in this buffer, where the data is copied on the GPU.
+    ///
+    pub(crate) fn copy(&self) -> Self {
+        let mut buffer = Self::new(&self.context);
+        let size = self.count as i32 * std::mem::size_of::<T>() as i32;
+        if size > 0 {
+            unsafe {
+                super::copy_buffer(&self.context, self.id, buffer.id, size);
+            }
+        }
+        buffer.count = self.count;
+        buffer
+    }
+
     pub(crate) fn bind(&self) {
         unsafe {
             self.context
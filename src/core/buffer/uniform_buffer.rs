@@ -97,6 +97,10 @@ impl UniformBuffer {
     }
 
     fn send(&self) {
+        trace_span!(
+            "three_d::upload_buffer",
+            bytes = std::mem::size_of_val(&self.data[..])
+        );
         unsafe {
             self.context
                 .bind_buffer(crate::context::UNIFORM_BUFFER, Some(self.id));
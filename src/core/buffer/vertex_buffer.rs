@@ -60,6 +60,15 @@ impl<T: BufferDataType> VertexBuffer<T> {
         self.buffer.attribute_count()
     }
 
+    ///
+    /// Returns a new vertex buffer containing a copy of the data in this buffer, where the data is copied on the GPU.
+    ///
+    pub(crate) fn copy(&self) -> Self {
+        Self {
+            buffer: self.buffer.copy(),
+        }
+    }
+
     pub(in crate::core) fn bind(&self) {
         self.buffer.bind();
     }
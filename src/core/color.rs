@@ -0,0 +1,270 @@
+use crate::core::*;
+
+///
+/// Conversions between an [Srgba] color, which is stored in the sRGB color space, and linear sRGB, which is the color space used for lighting calculations.
+///
+/// The colors given to this crate as [Srgba], for example the vertex colors of a [CpuMesh](crate::CpuMesh), the instance colors or a material color,
+/// are converted to linear sRGB before they are used on the GPU, and the final color is converted back to sRGB by the [ColorMapping] when rendering to the screen.
+/// Use these conversions when working with colors on the GPU directly, for example when editing a vertex color buffer.
+///
+/// ```
+/// # use three_d::*;
+/// // Mid gray in sRGB is around 21% in linear sRGB
+/// let linear = Srgba::new(128, 128, 128, 128).to_linear();
+/// assert!((linear.x - 0.2158).abs() < 1e-3);
+/// assert!((linear.w - 128.0 / 255.0).abs() < 1e-6);
+///
+/// // Converting to linear sRGB and back gives the same color for all values
+/// for value in 0..=255 {
+///     let color = Srgba::new(value, 255 - value, value, 255 - value);
+///     assert_eq!(Srgba::from_linear(color.to_linear()), color);
+/// }
+///
+/// // Values outside the range [0, 1] are clamped
+/// assert_eq!(Srgba::from_linear(vec4(-1.0, 0.5, 2.0, 1.5)), Srgba::new(0, 188, 255, 255));
+/// ```
+///
+pub trait SrgbaExt {
+    ///
+    /// Returns the color in linear sRGB color space, where the alpha value is unchanged.
+    ///
+    fn to_linear(&self) -> Vec4;
+
+    ///
+    /// Creates a color from the given color in linear sRGB color space, where the alpha value is unchanged.
+    /// The values are clamped to the range `[0, 1]`.
+    ///
+    fn from_linear(linear: Vec4) -> Self;
+
+    ///
+    /// Returns the color in linear sRGB color space, where the red, green and blue values of this color are interpreted as being encoded in the given color space instead of sRGB,
+    /// for example a color picked in Display P3. The alpha value is unchanged.
+    /// The values are outside the range `[0, 1]` if the color is outside the sRGB gamut.
+    ///
+    fn to_linear_from(&self, color_space: ColorSpace) -> Vec4;
+
+    ///
+    /// Creates an sRGB color from the given color, where the red, green and blue values are encoded in the given color space, for example a color picked in Display P3.
+    /// Colors outside the sRGB gamut are clipped, use [SrgbaExt::to_linear_from] to keep them.
+    ///
+    fn from_color_space(color: [u8; 4], color_space: ColorSpace) -> Self;
+}
+
+impl SrgbaExt for Srgba {
+    fn to_linear(&self) -> Vec4 {
+        self.to_linear_srgb()
+    }
+
+    fn from_linear(linear: Vec4) -> Self {
+        let convert = |value: f32| {
+            let value = value.clamp(0.0, 1.0);
+            let value = if value < 0.0031308 {
+                value * 12.92
+            } else {
+                1.055 * value.powf(1.0 / 2.4) - 0.055
+            };
+            (value * 255.0).round() as u8
+        };
+        Self::new(
+            convert(linear.x),
+            convert(linear.y),
+            convert(linear.z),
+            (linear.w.clamp(0.0, 1.0) * 255.0).round() as u8,
+        )
+    }
+
+    fn to_linear_from(&self, color_space: ColorSpace) -> Vec4 {
+        let decode = |value: u8| color_space.decode(value as f32 / 255.0);
+        color_space
+            .linear_to_linear_srgb(vec3(decode(self.r), decode(self.g), decode(self.b)))
+            .extend(self.a as f32 / 255.0)
+    }
+
+    fn from_color_space(color: [u8; 4], color_space: ColorSpace) -> Self {
+        Self::from_linear(Srgba::from(color).to_linear_from(color_space))
+    }
+}
+
+///
+/// An RGB color space defined by its primaries, white point and transfer function, which colors and images can be converted from and to.
+///
+/// All lighting and blending is done in linear sRGB, which has the primaries and D65 white point of Rec. 709, and this is called the working space.
+/// All [Srgba] colors and 8-bit textures are assumed to be in sRGB and are converted to the working space before they are used on the GPU,
+/// so colors and images in other color spaces must be converted to sRGB or the working space first, see [SrgbaExt::to_linear_from] and [CpuTextureExt::convert_to_working_space].
+/// The final color is converted from the working space to the color space of the display by the [ColorMapping](crate::renderer::ColorMapping) of the viewer.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum ColorSpace {
+    /// sRGB, which has the primaries of Rec. 709.
+    #[default]
+    Srgb,
+    /// Display P3, which has a wider gamut than sRGB and is used by many displays, for example on Apple devices, and uses the sRGB transfer function.
+    DisplayP3,
+    /// Rec. 2020 (ITU-R BT.2020), which has a much wider gamut than sRGB and is used for ultra high definition video, with the BT.2020 transfer function.
+    Rec2020,
+}
+
+impl ColorSpace {
+    /// The color space which has the primaries of the working space, where the working space is the linear version of this color space.
+    pub const WORKING_SPACE: Self = Self::Srgb;
+
+    ///
+    /// Converts a linear color with the primaries of this color space to linear sRGB.
+    /// The values are outside the range `[0, 1]` if the color is outside the sRGB gamut.
+    ///
+    pub fn linear_to_linear_srgb(&self, color: Vec3) -> Vec3 {
+        match self {
+            Self::Srgb => color,
+            Self::DisplayP3 => transform(
+                [
+                    [1.2249401, -0.2249404, 0.0],
+                    [-0.0420569, 1.0420571, 0.0],
+                    [-0.0196376, -0.0786361, 1.0982735],
+                ],
+                color,
+            ),
+            Self::Rec2020 => transform(
+                [
+                    [1.660491, -0.5876411, -0.0728499],
+                    [-0.1245505, 1.1328999, -0.0083494],
+                    [-0.0181508, -0.1005789, 1.1187297],
+                ],
+                color,
+            ),
+        }
+    }
+
+    ///
+    /// Converts a linear sRGB color to a linear color with the primaries of this color space.
+    ///
+    pub fn linear_from_linear_srgb(&self, color: Vec3) -> Vec3 {
+        match self {
+            Self::Srgb => color,
+            Self::DisplayP3 => transform(
+                [
+                    [0.8224621, 0.177538, 0.0],
+                    [0.0331941, 0.9668058, 0.0],
+                    [0.0170827, 0.0723974, 0.9105199],
+                ],
+                color,
+            ),
+            Self::Rec2020 => transform(
+                [
+                    [0.627404, 0.329282, 0.0433136],
+                    [0.069097, 0.91954, 0.0113612],
+                    [0.0163916, 0.0880132, 0.895595],
+                ],
+                color,
+            ),
+        }
+    }
+
+    ///
+    /// Applies the inverse transfer function of this color space, ie. converts an encoded value in the range `[0, 1]`, for example from an 8-bit image, to a linear value.
+    ///
+    pub fn decode(&self, value: f32) -> f32 {
+        match self {
+            Self::Srgb | Self::DisplayP3 => {
+                if value <= 0.04045 {
+                    value / 12.92
+                } else {
+                    ((value + 0.055) / 1.055).powf(2.4)
+                }
+            }
+            Self::Rec2020 => {
+                if value < 4.5 * REC2020_BETA {
+                    value / 4.5
+                } else {
+                    ((value + REC2020_ALPHA - 1.0) / REC2020_ALPHA).powf(1.0 / 0.45)
+                }
+            }
+        }
+    }
+
+    ///
+    /// Applies the transfer function of this color space, ie. converts a linear value in the range `[0, 1]` to an encoded value.
+    ///
+    pub fn encode(&self, value: f32) -> f32 {
+        let value = value.max(0.0);
+        match self {
+            Self::Srgb | Self::DisplayP3 => {
+                if value < 0.0031308 {
+                    value * 12.92
+                } else {
+                    1.055 * value.powf(1.0 / 2.4) - 0.055
+                }
+            }
+            Self::Rec2020 => {
+                if value < REC2020_BETA {
+                    value * 4.5
+                } else {
+                    REC2020_ALPHA * value.powf(0.45) - (REC2020_ALPHA - 1.0)
+                }
+            }
+        }
+    }
+}
+
+const REC2020_ALPHA: f32 = 1.0992968;
+const REC2020_BETA: f32 = 0.01805397;
+
+fn transform(rows: [[f32; 3]; 3], color: Vec3) -> Vec3 {
+    vec3(
+        rows[0][0] * color.x + rows[0][1] * color.y + rows[0][2] * color.z,
+        rows[1][0] * color.x + rows[1][1] * color.y + rows[1][2] * color.z,
+        rows[2][0] * color.x + rows[2][1] * color.y + rows[2][2] * color.z,
+    )
+}
+
+///
+/// Conversions of the colors in a [CpuTexture] between color spaces.
+///
+pub trait CpuTextureExt {
+    ///
+    /// Converts the colors of this texture from the given color space to the color space assumed for textures, for example when loading an image tagged as Display P3 or Rec. 2020.
+    /// 8-bit data is assumed to be encoded with the transfer function of the given color space and is converted to sRGB, where colors outside the sRGB gamut are clipped.
+    /// Floating point data is assumed to be linear and is converted to linear sRGB, where colors outside the sRGB gamut are kept as values outside the range `[0, 1]`.
+    /// Data with less than three channels is unchanged.
+    ///
+    fn convert_to_working_space(&mut self, color_space: ColorSpace);
+}
+
+impl CpuTextureExt for CpuTexture {
+    fn convert_to_working_space(&mut self, color_space: ColorSpace) {
+        if color_space == ColorSpace::Srgb {
+            return;
+        }
+        let convert_u8 = |rgb: [u8; 3]| {
+            let c = Srgba::from_color_space([rgb[0], rgb[1], rgb[2], 255], color_space);
+            [c.r, c.g, c.b]
+        };
+        let convert_f32 =
+            |rgb: [f32; 3]| -> [f32; 3] { color_space.linear_to_linear_srgb(rgb.into()).into() };
+        let convert_f16 = |rgb: [f16; 3]| {
+            let c = convert_f32([rgb[0].to_f32(), rgb[1].to_f32(), rgb[2].to_f32()]);
+            [
+                f16::from_f32(c[0]),
+                f16::from_f32(c[1]),
+                f16::from_f32(c[2]),
+            ]
+        };
+        match &mut self.data {
+            TextureData::RgbU8(data) => data.iter_mut().for_each(|c| *c = convert_u8(*c)),
+            TextureData::RgbaU8(data) => data.iter_mut().for_each(|c| {
+                let [r, g, b] = convert_u8([c[0], c[1], c[2]]);
+                *c = [r, g, b, c[3]];
+            }),
+            TextureData::RgbF16(data) => data.iter_mut().for_each(|c| *c = convert_f16(*c)),
+            TextureData::RgbaF16(data) => data.iter_mut().for_each(|c| {
+                let [r, g, b] = convert_f16([c[0], c[1], c[2]]);
+                *c = [r, g, b, c[3]];
+            }),
+            TextureData::RgbF32(data) => data.iter_mut().for_each(|c| *c = convert_f32(*c)),
+            TextureData::RgbaF32(data) => data.iter_mut().for_each(|c| {
+                let [r, g, b] = convert_f32([c[0], c[1], c[2]]);
+                *c = [r, g, b, c[3]];
+            }),
+            _ => {}
+        }
+    }
+}
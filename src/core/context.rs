@@ -1,11 +1,39 @@
 use super::*;
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::sync::RwLock;
 
 #[doc(hidden)]
 pub use crate::context::HasContext;
 
+#[cfg(not(target_arch = "wasm32"))]
+type ClipControlFn = unsafe extern "system" fn(u32, u32);
+
+///
+/// The extension which gives access to the current color of the render target in a fragment shader, see [Context::framebuffer_fetch].
+/// This is mostly available on the tile based GPUs of mobile devices, where the color is read from the tile memory without an extra pass.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FramebufferFetch {
+    /// The `EXT_shader_framebuffer_fetch` extension, where the color output is declared as `inout` and contains the current color.
+    Ext,
+    /// The `ARM_shader_framebuffer_fetch` extension, where the current color is available in `gl_LastFragColorARM`.
+    Arm,
+}
+
+impl FramebufferFetch {
+    ///
+    /// Returns the glsl directive which enables this extension in a shader.
+    ///
+    pub fn extension_directive(&self) -> &'static str {
+        match self {
+            Self::Ext => "#extension GL_EXT_shader_framebuffer_fetch : require\n",
+            Self::Arm => "#extension GL_ARM_shader_framebuffer_fetch : require\n",
+        }
+    }
+}
+
 ///
 /// Contains the low-level OpenGL/WebGL graphics context as well as other "global" variables.
 /// Implements Deref with the low-level graphics context as target, so you can call low-level functionality
@@ -17,6 +45,18 @@ pub struct Context {
     pub(super) vao: crate::context::VertexArray,
     /// A cache of programs to avoid recompiling a [Program] every frame.
     pub programs: Arc<RwLock<HashMap<Vec<u8>, Program>>>,
+    /// Programs that are being compiled in parallel with the rendering, see [Context::set_parallel_shader_compilation].
+    /// They are moved to [Context::programs] when done, see [Context::poll_pending_programs].
+    pub pending_programs: Arc<RwLock<HashMap<Vec<u8>, ProgramCompilation>>>,
+    parallel_shader_compilation: Arc<AtomicBool>,
+    lazy_program_compilations: Arc<AtomicUsize>,
+    strict_uniforms: Arc<AtomicBool>,
+    #[cfg(not(target_arch = "wasm32"))]
+    clip_control: Option<ClipControlFn>,
+    #[cfg(not(target_arch = "wasm32"))]
+    zero_to_one_depth: Arc<AtomicBool>,
+    world_origin: Arc<RwLock<DVec3>>,
+    texture_pool: Arc<RwLock<TexturePool>>,
 }
 
 impl Context {
@@ -44,27 +84,326 @@ impl Context {
                 context,
                 vao,
                 programs: Arc::new(RwLock::new(HashMap::new())),
+                pending_programs: Arc::new(RwLock::new(HashMap::new())),
+                parallel_shader_compilation: Arc::new(AtomicBool::new(false)),
+                lazy_program_compilations: Arc::new(AtomicUsize::new(0)),
+                strict_uniforms: Arc::new(AtomicBool::new(false)),
+                #[cfg(not(target_arch = "wasm32"))]
+                clip_control: None,
+                #[cfg(not(target_arch = "wasm32"))]
+                zero_to_one_depth: Arc::new(AtomicBool::new(false)),
+                world_origin: Arc::new(RwLock::new(DVec3::zero())),
+                texture_pool: Arc::new(RwLock::new(TexturePool::default())),
             }
         };
         Ok(c)
     }
 
+    ///
+    /// Returns whether the `KHR_parallel_shader_compile` extension is supported, which allows for compiling shader programs without blocking the rendering.
+    ///
+    pub fn supports_parallel_shader_compile(&self) -> bool {
+        let extensions = self.supported_extensions();
+        extensions.contains("KHR_parallel_shader_compile")
+            || extensions.contains("GL_KHR_parallel_shader_compile")
+    }
+
+    ///
+    /// Enables or disables parallel shader compilation which is disabled by default.
+    /// When enabled and supported (see [Context::supports_parallel_shader_compile]), shader programs needed for rendering objects are compiled without blocking
+    /// and until a program is ready, the objects are rendered with a cheap fallback material (transparent objects are skipped).
+    /// This avoids stalls the first time a new combination of geometry, material and lights are rendered, which is especially noticeable on web.
+    ///
+    pub fn set_parallel_shader_compilation(&self, enabled: bool) {
+        self.parallel_shader_compilation
+            .store(enabled, Ordering::Relaxed);
+    }
+
+    ///
+    /// Returns whether parallel shader compilation is enabled and supported, see [Context::set_parallel_shader_compilation].
+    ///
+    pub fn parallel_shader_compilation(&self) -> bool {
+        self.parallel_shader_compilation.load(Ordering::Relaxed)
+            && self.supports_parallel_shader_compile()
+    }
+
+    ///
+    /// Returns the number of shader programs which have been compiled, or started compiling, the first time they were needed for rendering
+    /// instead of in advance, for example using [compile_all](crate::renderer::compile_all) or [warm_up](crate::renderer::warm_up).
+    /// Each of these compilations can cause a stutter, so this should stay constant after loading to avoid stutters.
+    ///
+    pub fn lazy_program_compilations(&self) -> usize {
+        self.lazy_program_compilations.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn count_lazy_program_compilation(&self) {
+        self.lazy_program_compilations
+            .fetch_add(1, Ordering::Relaxed);
+    }
+
+    ///
+    /// Enables or disables strict uniforms which is disabled by default.
+    /// When enabled, sending a uniform to a [Program] which does not use it, for example using [Program::use_uniform], panics.
+    /// When disabled, the uniform is ignored and a `three_d::missing_uniform` tracing event is emitted the first time if the `tracing` feature is enabled, see [trace](crate::trace).
+    /// Unused uniforms are often removed by the shader compiler, so disabling this avoids crashes for shader permutations where a uniform is optimized away.
+    ///
+    pub fn set_strict_uniforms(&self, enabled: bool) {
+        self.strict_uniforms.store(enabled, Ordering::Relaxed);
+    }
+
+    ///
+    /// Returns whether strict uniforms is enabled, see [Context::set_strict_uniforms].
+    ///
+    pub fn strict_uniforms(&self) -> bool {
+        self.strict_uniforms.load(Ordering::Relaxed)
+    }
+
+    ///
+    /// Requests a transient [Texture2D] with the given width, height, [TextureDataType] and interpolation from the texture pool of this context,
+    /// which is returned to the pool when the [PooledTexture] is dropped.
+    /// Use this for intermediate textures, for example in an effect, so that textures are reused across effects and frames instead of allocating new ones.
+    /// A texture is never given out twice at the same time, so requesting the same texture specification twice always gives two different textures.
+    /// The texture has clamp to edge wrapping and no mip maps and the content is undefined, so it must be cleared or overwritten before it is read.
+    ///
+    pub fn request_texture<T: TextureDataType>(
+        &self,
+        width: u32,
+        height: u32,
+        interpolation: Interpolation,
+    ) -> PooledTexture {
+        request_texture::<T>(self, &self.texture_pool, width, height, interpolation)
+    }
+
+    ///
+    /// Requests a transient [Texture2D] with the given [HdrFormat] from the texture pool of this context, see [Context::request_texture].
+    ///
+    pub fn request_hdr_texture(
+        &self,
+        width: u32,
+        height: u32,
+        format: HdrFormat,
+        interpolation: Interpolation,
+    ) -> PooledTexture {
+        match format {
+            HdrFormat::R11G11B10F => {
+                self.request_texture::<R11G11B10F>(width, height, interpolation)
+            }
+            HdrFormat::Rgba16F => self.request_texture::<[f16; 4]>(width, height, interpolation),
+            HdrFormat::Rgba32F => self.request_texture::<[f32; 4]>(width, height, interpolation),
+        }
+    }
+
+    ///
+    /// Requests a transient [Texture2DArray] with the given number of layers from the texture pool of this context, see [Context::request_texture].
+    ///
+    pub fn request_texture_array<T: TextureDataType>(
+        &self,
+        width: u32,
+        height: u32,
+        depth: u32,
+        interpolation: Interpolation,
+    ) -> PooledTexture<Texture2DArray> {
+        request_texture_array::<T>(
+            self,
+            &self.texture_pool,
+            width,
+            height,
+            depth,
+            interpolation,
+        )
+    }
+
+    ///
+    /// Requests a transient [DepthTexture2D] from the texture pool of this context, see [Context::request_texture].
+    ///
+    pub fn request_depth_texture<T: DepthTextureDataType>(
+        &self,
+        width: u32,
+        height: u32,
+    ) -> PooledTexture<DepthTexture2D> {
+        request_depth_texture::<T>(self, &self.texture_pool, width, height)
+    }
+
+    ///
+    /// Deletes the textures in the texture pool which are not in use and which have not been requested since the last call to this method,
+    /// for example textures with the size of the viewport before it was resized.
+    /// This is called after each frame when using the [window](crate::window) module, otherwise it should be called at the end of each frame.
+    ///
+    pub fn trim_texture_pool(&self) {
+        self.texture_pool.write().unwrap().trim();
+    }
+
+    ///
+    /// Deletes all textures in the texture pool which are not in use, see [Context::request_texture].
+    ///
+    pub fn clear_texture_pool(&self) {
+        self.texture_pool.write().unwrap().clear();
+    }
+
+    ///
+    /// Returns the memory used by the textures allocated by the texture pool, see [Context::request_texture].
+    ///
+    pub fn texture_pool_memory(&self) -> TexturePoolMemory {
+        self.texture_pool.read().unwrap().memory()
+    }
+
+    ///
+    /// Sets the world origin, which is the position in world space that corresponds to the origin of the render space.
+    /// The default is the origin of world space, in which case world space and render space are the same.
+    ///
+    /// Placing objects far from the origin, for example at geospatial coordinates, results in jitter since single precision floating point numbers,
+    /// which is used on the GPU, cannot represent these positions accurately.
+    /// To avoid that, set the transformations of the objects in double precision, for example using [Mesh::set_transformation_f64](crate::renderer::Mesh::set_transformation_f64),
+    /// and set the world origin close to the camera, for example using [recenter_world_origin](crate::renderer::recenter_world_origin).
+    /// The objects are then placed relative to the world origin in double precision on the CPU, so the GPU only sees positions close to the origin of the render space.
+    /// All other positions, for example the position of a [Camera](crate::renderer::Camera), lights and objects with a single precision transformation, are given in render space.
+    ///
+    pub fn set_world_origin(&self, origin: DVec3) {
+        *self.world_origin.write().unwrap() = origin;
+    }
+
+    ///
+    /// Returns the world origin, see [Context::set_world_origin].
+    ///
+    pub fn world_origin(&self) -> DVec3 {
+        *self.world_origin.read().unwrap()
+    }
+
+    ///
+    /// Converts the given position in render space to world space, see [Context::set_world_origin].
+    ///
+    pub fn to_world_space(&self, position: Vec3) -> DVec3 {
+        self.world_origin() + position.cast::<f64>().unwrap()
+    }
+
+    ///
+    /// Converts the given position in world space to render space, see [Context::set_world_origin].
+    ///
+    pub fn to_render_space(&self, position: DVec3) -> Vec3 {
+        (position - self.world_origin()).cast::<f32>().unwrap()
+    }
+
+    ///
+    /// Converts the given transformation from local space to world space into a transformation from local space to render space, see [Context::set_world_origin].
+    /// The translation to the world origin is applied in double precision before the transformation is converted to single precision.
+    ///
+    pub fn to_render_space_transformation(&self, transformation: DMat4) -> Mat4 {
+        (DMat4::from_translation(-self.world_origin()) * transformation)
+            .cast::<f32>()
+            .unwrap()
+    }
+
+    ///
+    /// Loads the `glClipControl` function, which is not exposed by the low-level graphics context, using the given loader function
+    /// that returns the address of an OpenGL function given its name.
+    /// This is done automatically when using the [window](crate::window) module, so it should only be called if you are creating the low-level context yourself
+    /// and want to use [DepthMode::ReversedZeroToOne].
+    /// Clip control requires OpenGL 4.5 or the `GL_ARB_clip_control` or `GL_EXT_clip_control` extension and is never available on web.
+    ///
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn load_clip_control(&mut self, mut loader: impl FnMut(&str) -> *const std::ffi::c_void) {
+        let version = self.version();
+        let extensions = self.supported_extensions();
+        let name = if !version.is_embedded
+            && (version.major > 4 || (version.major == 4 && version.minor >= 5))
+            || extensions.contains("GL_ARB_clip_control")
+        {
+            "glClipControl"
+        } else if extensions.contains("GL_EXT_clip_control") {
+            "glClipControlEXT"
+        } else {
+            return;
+        };
+        let address = loader(name);
+        if !address.is_null() {
+            self.clip_control = Some(unsafe {
+                std::mem::transmute::<*const std::ffi::c_void, ClipControlFn>(address)
+            });
+        }
+    }
+
+    ///
+    /// Returns whether clip control is supported, see [Context::load_clip_control], which is needed for [DepthMode::ReversedZeroToOne].
+    ///
+    pub fn supports_clip_control(&self) -> bool {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            self.clip_control.is_some()
+        }
+        #[cfg(target_arch = "wasm32")]
+        {
+            false
+        }
+    }
+
+    ///
+    /// Returns the reversed [DepthMode] with the best precision supported by this context,
+    /// ie. [DepthMode::ReversedZeroToOne] if clip control is supported and otherwise [DepthMode::Reversed].
+    ///
+    pub fn reversed_depth_mode(&self) -> DepthMode {
+        if self.supports_clip_control() {
+            DepthMode::ReversedZeroToOne
+        } else {
+            DepthMode::Reversed
+        }
+    }
+
+    ///
+    /// Sets whether the clip space depth range `[0, 1]` is mapped directly to the depth values (as needed for [DepthMode::ReversedZeroToOne])
+    /// instead of the standard clip space depth range `[-1, 1]`.
+    /// This is done automatically when rendering with a viewer using [DepthMode::ReversedZeroToOne] and does nothing if clip control is not supported.
+    ///
+    pub fn set_zero_to_one_depth(&self, enabled: bool) {
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(clip_control) = self.clip_control {
+            if self.zero_to_one_depth.swap(enabled, Ordering::Relaxed) != enabled {
+                unsafe {
+                    clip_control(
+                        crate::context::LOWER_LEFT,
+                        if enabled {
+                            crate::context::ZERO_TO_ONE
+                        } else {
+                            crate::context::NEGATIVE_ONE_TO_ONE
+                        },
+                    )
+                }
+            }
+        }
+        #[cfg(target_arch = "wasm32")]
+        let _ = enabled;
+    }
+
+    ///
+    /// Moves the programs in [Context::pending_programs] that are done compiling to [Context::programs].
+    /// Returns the number of programs that are still being compiled, which for example can be used to show a loading screen until all programs are ready.
+    ///
+    pub fn poll_pending_programs(&self) -> Result<usize, CoreError> {
+        let mut pending_programs = self.pending_programs.write().unwrap();
+        let completed = pending_programs
+            .iter()
+            .filter(|(_, compilation)| compilation.is_complete())
+            .map(|(id, _)| id.clone())
+            .collect::<Vec<_>>();
+        for id in completed {
+            let program = pending_programs.remove(&id).unwrap().finish()?;
+            self.programs.write().unwrap().insert(id, program);
+        }
+        Ok(pending_programs.len())
+    }
+
     ///
     /// Set the scissor test for this context (see [ScissorBox]).
+    /// If the scissor box is empty, the scissor test discards all pixels, ie. nothing is cleared or rendered.
     ///
     pub fn set_scissor(&self, scissor_box: ScissorBox) {
         unsafe {
-            if scissor_box.width > 0 && scissor_box.height > 0 {
-                self.enable(crate::context::SCISSOR_TEST);
-                self.scissor(
-                    scissor_box.x,
-                    scissor_box.y,
-                    scissor_box.width as i32,
-                    scissor_box.height as i32,
-                );
-            } else {
-                self.disable(crate::context::SCISSOR_TEST);
-            }
+            self.enable(crate::context::SCISSOR_TEST);
+            self.scissor(
+                scissor_box.x,
+                scissor_box.y,
+                scissor_box.width as i32,
+                scissor_box.height as i32,
+            );
         }
     }
 
@@ -107,6 +446,18 @@ impl Context {
         }
     }
 
+    ///
+    /// Set the winding order of the frontfacing triangles for this context (see [FrontFace]).
+    ///
+    pub fn set_front_face(&self, front_face: FrontFace) {
+        unsafe {
+            self.front_face(match front_face {
+                FrontFace::CounterClockwise => crate::context::CCW,
+                FrontFace::Clockwise => crate::context::CW,
+            });
+        }
+    }
+
     ///
     /// Set the write mask for this context (see [WriteMask]).
     ///
@@ -188,6 +539,103 @@ impl Context {
         }
     }
 
+    ///
+    /// Returns whether the blending can be set individually for each color output (draw buffer), see [RenderStates::draw_buffer_blend].
+    /// This requires OpenGL 4.0 or OpenGL ES 3.2 and is not supported on web.
+    ///
+    pub fn supports_draw_buffer_blend(&self) -> bool {
+        if cfg!(target_arch = "wasm32") {
+            return false;
+        }
+        let version = self.version();
+        if version.is_embedded {
+            (version.major, version.minor) >= (3, 2)
+        } else {
+            version.major >= 4
+        }
+    }
+
+    ///
+    /// Returns the extension which can be used for reading the current color of the render target in a fragment shader, see [FramebufferFetch],
+    /// or `None` if framebuffer fetch is not supported, which is always the case on web.
+    /// The `EXT_shader_framebuffer_fetch` extension is preferred since it also works with multiple color outputs.
+    ///
+    pub fn framebuffer_fetch(&self) -> Option<FramebufferFetch> {
+        if cfg!(target_arch = "wasm32") {
+            return None;
+        }
+        let extensions = self.supported_extensions();
+        let has =
+            |name: &str| extensions.contains(name) || extensions.contains(&format!("GL_{}", name));
+        if has("EXT_shader_framebuffer_fetch") {
+            Some(FramebufferFetch::Ext)
+        } else if has("ARM_shader_framebuffer_fetch") {
+            Some(FramebufferFetch::Arm)
+        } else {
+            None
+        }
+    }
+
+    ///
+    /// Returns whether a color texture with the given [HdrFormat] can be used as a color target.
+    /// This is always supported on desktop, but requires the `EXT_color_buffer_float` extension on OpenGL ES and web
+    /// or the `EXT_color_buffer_half_float` extension for [HdrFormat::Rgba16F]. See also [preferred_hdr_format].
+    ///
+    pub fn supports_hdr_format(&self, format: HdrFormat) -> bool {
+        if !cfg!(target_arch = "wasm32") && !self.version().is_embedded {
+            return true;
+        }
+        let extensions = self.supported_extensions();
+        let has =
+            |name: &str| extensions.contains(name) || extensions.contains(&format!("GL_{}", name));
+        has("EXT_color_buffer_float")
+            || (format == HdrFormat::Rgba16F && has("EXT_color_buffer_half_float"))
+    }
+
+    ///
+    /// Returns the maximum size in bytes of a uniform block, see [UniformBuffer], which is at least 16384 bytes.
+    ///
+    pub fn max_uniform_block_size(&self) -> u32 {
+        unsafe { self.get_parameter_i32(crate::context::MAX_UNIFORM_BLOCK_SIZE) as u32 }
+    }
+
+    ///
+    /// Set the blend state for the color output (draw buffer) with the given index, see [RenderStates::draw_buffer_blend].
+    /// Does nothing if not supported, see [Context::supports_draw_buffer_blend].
+    ///
+    pub fn set_draw_buffer_blend(&self, draw_buffer: u32, blend: Blend) {
+        if !self.supports_draw_buffer_blend() {
+            return;
+        }
+        unsafe {
+            if let Blend::Enabled {
+                source_rgb_multiplier,
+                source_alpha_multiplier,
+                destination_rgb_multiplier,
+                destination_alpha_multiplier,
+                rgb_equation,
+                alpha_equation,
+            } = blend
+            {
+                self.enable_draw_buffer(crate::context::BLEND, draw_buffer);
+                self.blend_func_separate_draw_buffer(
+                    draw_buffer,
+                    Self::blend_const_from_multiplier(source_rgb_multiplier),
+                    Self::blend_const_from_multiplier(destination_rgb_multiplier),
+                    Self::blend_const_from_multiplier(source_alpha_multiplier),
+                    Self::blend_const_from_multiplier(destination_alpha_multiplier),
+                );
+                self.blend_equation_separate_draw_buffer(
+                    draw_buffer,
+                    Self::blend_const_from_equation(rgb_equation),
+                    Self::blend_const_from_equation(alpha_equation),
+                );
+            } else {
+                self.disable_draw_buffer(crate::context::BLEND, draw_buffer);
+            }
+        }
+    }
+
     fn blend_const_from_multiplier(multiplier: BlendMultiplierType) -> u32 {
         match multiplier {
             BlendMultiplierType::Zero => crate::context::ZERO,
@@ -218,13 +666,20 @@ impl Context {
     ///
     pub fn set_render_states(&self, render_states: RenderStates) {
         self.set_cull(render_states.cull);
+        self.set_front_face(render_states.front_face);
         self.set_write_mask(render_states.write_mask);
         if !render_states.write_mask.depth && render_states.depth_test == DepthTest::Always {
             unsafe { self.disable(crate::context::DEPTH_TEST) }
         } else {
             self.set_depth_test(render_states.depth_test);
         }
+        // The blend state of all draw buffers is set first and then overridden for individual draw buffers
         self.set_blend(render_states.blend);
+        for (draw_buffer, blend) in render_states.draw_buffer_blend.iter().enumerate() {
+            if let Some(blend) = blend {
+                self.set_draw_buffer_blend(draw_buffer as u32, *blend);
+            }
+        }
     }
 
     ///
@@ -299,6 +754,10 @@ impl std::fmt::Debug for Context {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let mut d = f.debug_struct("Context");
         d.field("programs", &self.programs.read().unwrap().len());
+        d.field(
+            "pending_programs",
+            &self.pending_programs.read().unwrap().len(),
+        );
         d.finish()
     }
 }
@@ -534,6 +534,25 @@ impl<T: PrimitiveDataType> DataType for Quaternion<T> {
     }
 }
 
+impl DataType for R11G11B10F {
+    fn internal_format() -> u32 {
+        crate::context::R11F_G11F_B10F
+    }
+
+    fn data_type() -> u32 {
+        crate::context::UNSIGNED_INT_10F_11F_11F_REV
+    }
+
+    fn size() -> u32 {
+        3
+    }
+
+    fn send_uniform(context: &Context, location: &UniformLocation, data: &[Self]) {
+        let data = data.iter().flat_map(|v| v.to_f32()).collect::<Vec<_>>();
+        f32::send_uniform_with_type(context, location, &data, UniformType::Vec3)
+    }
+}
+
 impl<T: PrimitiveDataType> DataType for Matrix2<T> {
     fn internal_format() -> u32 {
         T::internal_format_with_size(Self::size())
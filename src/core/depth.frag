@@ -0,0 +1,8 @@
+
+// Reconstructs the world position from a value in a depth texture and the uv coordinates of the screen,
+// given the inverse of the view projection matrix, see view_projection_inverse
+vec3 world_pos_from_depth(mat4 viewProjectionInverse, float depth, vec2 uv) {
+    vec4 clipSpacePosition = vec4(uv * 2.0 - 1.0, depth * 2.0 - 1.0, 1.0);
+    vec4 position = viewProjectionInverse * clipSpacePosition;
+    return position.xyz / position.w;
+}
@@ -0,0 +1,61 @@
+use crate::core::*;
+
+///
+/// A query which finds out whether any fragments passed the depth test and were written while the query was active,
+/// for example to stop rendering passes which no longer change the result.
+///
+/// Reading the result with [OcclusionQuery::any_samples_passed] waits until the GPU has finished the draw calls issued while the query was active,
+/// so it stalls the CPU until then.
+///
+pub struct OcclusionQuery {
+    context: Context,
+    id: crate::context::Query,
+}
+
+impl OcclusionQuery {
+    ///
+    /// Creates a new occlusion query.
+    ///
+    pub fn new(context: &Context) -> Self {
+        let id = unsafe { context.create_query().expect("Failed creating query") };
+        Self {
+            context: context.clone(),
+            id,
+        }
+    }
+
+    ///
+    /// Counts the fragments written by the draw calls issued in the given callback, which replaces the result of any previous use of this query.
+    ///
+    pub fn measure(&self, callback: impl FnOnce()) -> &Self {
+        unsafe {
+            self.context
+                .begin_query(crate::context::ANY_SAMPLES_PASSED, self.id);
+        }
+        callback();
+        unsafe {
+            self.context.end_query(crate::context::ANY_SAMPLES_PASSED);
+        }
+        self
+    }
+
+    ///
+    /// Returns whether any fragments were written by the draw calls issued in the last call to [OcclusionQuery::measure].
+    /// This waits for the draw calls to finish.
+    ///
+    pub fn any_samples_passed(&self) -> bool {
+        unsafe {
+            self.context
+                .get_query_parameter_u32(self.id, crate::context::QUERY_RESULT)
+                != 0
+        }
+    }
+}
+
+impl Drop for OcclusionQuery {
+    fn drop(&mut self) {
+        unsafe {
+            self.context.delete_query(self.id);
+        }
+    }
+}
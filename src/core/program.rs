@@ -1,7 +1,37 @@
 use crate::core::*;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::RwLock;
 
+///
+/// Information about an active uniform variable in a [Program], see [Program::uniforms].
+///
+#[derive(Clone, Debug)]
+pub struct UniformInfo {
+    /// The name of the uniform. For arrays, the name is without the `[0]` suffix.
+    pub name: String,
+    /// The OpenGL type of the uniform, for example [FLOAT_VEC3](crate::context::FLOAT_VEC3) or [SAMPLER_2D](crate::context::SAMPLER_2D).
+    pub gl_type: u32,
+    /// The number of elements if the uniform is an array, otherwise 1.
+    pub array_len: u32,
+    /// The location of the uniform.
+    pub location: crate::context::UniformLocation,
+}
+
+///
+/// Information about an active vertex attribute in a [Program], see [Program::attributes].
+///
+#[derive(Clone, Debug)]
+pub struct AttributeInfo {
+    /// The name of the attribute.
+    pub name: String,
+    /// The OpenGL type of the attribute, for example [FLOAT_VEC3](crate::context::FLOAT_VEC3).
+    pub gl_type: u32,
+    /// The number of elements if the attribute is an array, otherwise 1.
+    pub array_len: u32,
+    /// The location of the attribute.
+    pub location: u32,
+}
+
 ///
 /// A shader program consisting of a programmable vertex shader followed by a programmable fragment shader.
 /// Functionality includes transferring per vertex data to the vertex shader (see the use_attribute functionality)
@@ -11,22 +41,60 @@ use std::sync::RwLock;
 pub struct Program {
     context: Context,
     id: crate::context::Program,
-    attributes: HashMap<String, u32>,
-    textures: RwLock<HashMap<String, u32>>,
-    uniforms: HashMap<String, crate::context::UniformLocation>,
+    attributes: HashMap<String, AttributeInfo>,
+    textures: RwLock<HashMap<String, (u32, u32)>>,
+    uniforms: HashMap<String, UniformInfo>,
     uniform_blocks: RwLock<HashMap<String, (u32, u32)>>,
+    missing_uniforms: RwLock<HashSet<String>>,
 }
 
 impl Program {
     ///
     /// Creates a new shader program from the given vertex and fragment glsl shader source.
+    /// The `#include <name>` directives in the sources are replaced by the registered shader snippets before compilation, see [resolve_shader_includes].
     ///
     pub fn from_source(
         context: &Context,
         vertex_shader_source: &str,
         fragment_shader_source: &str,
     ) -> Result<Self, CoreError> {
+        Self::start_compilation(context, vertex_shader_source, fragment_shader_source).finish()
+    }
+
+    ///
+    /// Starts compiling and linking a new shader program from the given vertex and fragment glsl shader source without waiting for the result.
+    /// Use [ProgramCompilation::is_complete] to check whether the compilation is done and [ProgramCompilation::finish] to get the resulting [Program].
+    /// The compilation only happens in parallel with the rendering if the `KHR_parallel_shader_compile` extension is supported (see [Context::supports_parallel_shader_compile]),
+    /// otherwise [ProgramCompilation::finish] blocks until the compilation is done.
+    ///
+    pub fn start_compilation(
+        context: &Context,
+        vertex_shader_source: &str,
+        fragment_shader_source: &str,
+    ) -> ProgramCompilation {
+        trace_span!("three_d::compile_program");
+        let sources = resolve_shader_includes_with_line_map(vertex_shader_source).and_then(
+            |vertex_shader_source| {
+                resolve_shader_includes_with_line_map(fragment_shader_source)
+                    .map(|fragment_shader_source| (vertex_shader_source, fragment_shader_source))
+            },
+        );
         unsafe {
+            let id = context.create_program().expect("Failed creating program");
+            let (
+                (vertex_shader_source, vertex_line_map),
+                (fragment_shader_source, fragment_line_map),
+            ) = match sources {
+                Ok(sources) => sources,
+                Err(error) => {
+                    return ProgramCompilation {
+                        context: context.clone(),
+                        id,
+                        shaders: None,
+                        error: Some(error),
+                    }
+                }
+            };
             let vert_shader = context
                 .create_shader(crate::context::VERTEX_SHADER)
                 .expect("Failed creating vertex shader");
@@ -50,85 +118,37 @@ impl Program {
             } else {
                 "#version 330 core\n"
             };
-            let vertex_shader_source = format!("{}{}", header, vertex_shader_source);
-            let fragment_shader_source = format!("{}{}", header, fragment_shader_source);
+            let (vertex_shader_source, vertex_header_lines) =
+                with_header(header, &vertex_shader_source);
+            let (fragment_shader_source, fragment_header_lines) =
+                with_header(header, &fragment_shader_source);
 
             context.shader_source(vert_shader, &vertex_shader_source);
             context.shader_source(frag_shader, &fragment_shader_source);
             context.compile_shader(vert_shader);
             context.compile_shader(frag_shader);
 
-            let id = context.create_program().expect("Failed creating program");
             context.attach_shader(id, vert_shader);
             context.attach_shader(id, frag_shader);
             context.link_program(id);
 
-            if !context.get_program_link_status(id) {
-                let log = context.get_shader_info_log(vert_shader);
-                if !log.is_empty() {
-                    Err(shader_compilation_error(
-                        "vertex",
-                        log,
+            ProgramCompilation {
+                context: context.clone(),
+                id,
+                shaders: Some([
+                    (
+                        vert_shader,
                         vertex_shader_source,
-                    ))?;
-                }
-                let log = context.get_shader_info_log(frag_shader);
-                if !log.is_empty() {
-                    Err(shader_compilation_error(
-                        "fragment",
-                        log,
+                        vertex_line_map.with_header_lines(vertex_header_lines),
+                    ),
+                    (
+                        frag_shader,
                         fragment_shader_source,
-                    ))?;
-                }
-                let log = context.get_program_info_log(id);
-                if !log.is_empty() {
-                    Err(CoreError::ShaderLink(log))?;
-                }
-                Err(CoreError::ShaderCompilerError)?;
+                        fragment_line_map.with_header_lines(fragment_header_lines),
+                    ),
+                ]),
+                error: None,
             }
-
-            context.detach_shader(id, vert_shader);
-            context.detach_shader(id, frag_shader);
-            context.delete_shader(vert_shader);
-            context.delete_shader(frag_shader);
-
-            // Init vertex attributes
-            let num_attribs = context.get_active_attributes(id);
-            let mut attributes = HashMap::new();
-            for i in 0..num_attribs {
-                if let Some(crate::context::ActiveAttribute { name, .. }) = context
-                    .get_active_attribute(id, i)
-                    .filter(|a| !a.name.starts_with("gl_"))
-                {
-                    if let Some(location) = context.get_attrib_location(id, &name) {
-                        attributes.insert(name, location);
-                    }
-                }
-            }
-
-            // Init uniforms
-            let num_uniforms = context.get_active_uniforms(id);
-            let mut uniforms = HashMap::new();
-            for i in 0..num_uniforms {
-                if let Some(crate::context::ActiveUniform { name, .. }) = context
-                    .get_active_uniform(id, i)
-                    .filter(|a| !a.name.starts_with("gl_"))
-                {
-                    if let Some(location) = context.get_uniform_location(id, &name) {
-                        let name = name.split('[').next().unwrap().to_string();
-                        uniforms.insert(name, location);
-                    }
-                }
-            }
-
-            Ok(Program {
-                context: context.clone(),
-                id,
-                attributes,
-                uniforms,
-                uniform_blocks: RwLock::new(HashMap::new()),
-                textures: RwLock::new(HashMap::new()),
-            })
         }
     }
 
@@ -137,14 +157,17 @@ impl Program {
     /// The glsl shader variable must be of type `uniform int` if the data is an integer, `uniform vec2` if it is of type [Vec2] etc.
     /// The uniform variable is uniformly available across all processing of vertices and fragments.
     ///
+    /// If the uniform is not defined or not used in the shader code, in which case the variable is removed by the shader compiler,
+    /// the data is ignored unless strict uniforms is enabled, see [Context::set_strict_uniforms].
+    ///
     /// # Panic
-    /// Will panic if the uniform is not defined or not used in the shader code.
-    /// In the latter case the variable is removed by the shader compiler.
+    /// Will panic if the uniform is not defined or not used in the shader code and strict uniforms is enabled.
     ///
     pub fn use_uniform<T: UniformDataType>(&self, name: &str, data: T) {
-        let location = self.get_uniform_location(name);
-        T::send_uniform(&self.context, location, &[data]);
-        self.unuse_program();
+        if let Some(location) = self.get_uniform_location(name) {
+            T::send_uniform(&self.context, location, &[data]);
+            self.unuse_program();
+        }
     }
 
     ///
@@ -160,34 +183,49 @@ impl Program {
     /// Send the given array of uniform data to this shader program and associate it with the given named variable.
     /// The glsl shader variable must be of same type and length as the data, so if the data is an array of three [Vec2], the variable must be `uniform vec2[3]`.
     /// The uniform variable is uniformly available across all processing of vertices and fragments.
+    /// The whole array is sent in one call, which is much faster than sending each element separately using [Program::use_uniform] with the element names (`name[0]`, `name[1]` etc.).
+    ///
+    /// If the uniform is not defined or not used in the shader code, the data is ignored unless strict uniforms is enabled, see [Program::use_uniform].
     ///
     /// # Panic
-    /// Will panic if the uniform is not defined in the shader code or not used.
-    /// In the latter case the variable is removed by the shader compiler.
+    /// Will panic if the uniform is not defined or not used in the shader code and strict uniforms is enabled.
     ///
     pub fn use_uniform_array<T: UniformDataType>(&self, name: &str, data: &[T]) {
-        let location = self.get_uniform_location(name);
-        T::send_uniform(&self.context, location, data);
-        self.unuse_program();
+        if let Some(location) = self.get_uniform_location(name) {
+            T::send_uniform(&self.context, location, data);
+            self.unuse_program();
+        }
     }
 
-    fn get_uniform_location(&self, name: &str) -> &crate::context::UniformLocation {
-        self.use_program();
-        self.uniforms.get(name).unwrap_or_else(|| {
+    fn get_uniform_location(&self, name: &str) -> Option<&crate::context::UniformLocation> {
+        if let Some(info) = self.uniforms.get(name) {
+            self.use_program();
+            return Some(&info.location);
+        }
+        if self.context.strict_uniforms() {
             panic!(
                 "the uniform {} is sent to the shader but not defined or never used",
                 name
             )
-        })
+        }
+        if !self.missing_uniforms.read().unwrap().contains(name) {
+            self.missing_uniforms
+                .write()
+                .unwrap()
+                .insert(name.to_owned());
+            trace_event!(WARN, "three_d::missing_uniform", uniform = name);
+        }
+        None
     }
 
     ///
     /// Use the given [Texture2D] in this shader program and associate it with the given named variable.
     /// The glsl shader variable must be of type `uniform sampler2D` and can only be accessed in the fragment shader.
     ///
+    /// If the texture is not defined or not used in the shader code, it is ignored unless strict uniforms is enabled, see [Program::use_uniform].
+    ///
     /// # Panic
-    /// Will panic if the texture is not defined in the shader code or not used.
-    /// In the latter case the variable is removed by the shader compiler.
+    /// Will panic if the texture is not defined or not used in the shader code and strict uniforms is enabled.
     ///
     pub fn use_texture(&self, name: &str, texture: &Texture2D) {
         self.use_texture_internal(name);
@@ -198,9 +236,10 @@ impl Program {
     /// Use the given [DepthTexture2D] in this shader program and associate it with the given named variable.
     /// The glsl shader variable must be of type `uniform sampler2D` and can only be accessed in the fragment shader.
     ///
+    /// If the texture is not defined or not used in the shader code, it is ignored unless strict uniforms is enabled, see [Program::use_uniform].
+    ///
     /// # Panic
-    /// Will panic if the texture is not defined in the shader code or not used.
-    /// In the latter case the variable is removed by the shader compiler.
+    /// Will panic if the texture is not defined or not used in the shader code and strict uniforms is enabled.
     ///
     pub fn use_depth_texture(&self, name: &str, texture: &DepthTexture2D) {
         self.use_texture_internal(name);
@@ -208,25 +247,50 @@ impl Program {
     }
 
     ///
-    /// Use the given texture array in this shader program and associate it with the given named variable.
-    /// The glsl shader variable must be of type `uniform sampler2DArray` and can only be accessed in the fragment shader.
+    /// Use the given texture array in this shader program and associate it with the given named variable, see [TextureArray].
+    /// If the texture array is a [Texture2DArray], the glsl shader variable must be of type `uniform sampler2DArray`.
+    /// If it is a list of [Texture2D]s, the textures are bound to consecutive texture units and the indices of the texture units are sent to the shader in one call,
+    /// so the glsl shader variable must be an array of type `uniform sampler2D` with the same length as the list, ie. `uniform sampler2D name[N];`.
+    /// Note that such an array can only be indexed by constant expressions in the shader code.
+    /// In both cases, the variable can only be accessed in the fragment shader.
+    ///
+    /// If the texture is not defined or not used in the shader code, it is ignored unless strict uniforms is enabled, see [Program::use_uniform].
     ///
     /// # Panic
-    /// Will panic if the texture is not defined in the shader code or not used.
-    /// In the latter case the variable is removed by the shader compiler.
+    /// Will panic if the texture is not defined or not used in the shader code and strict uniforms is enabled.
     ///
-    pub fn use_texture_array(&self, name: &str, texture: &Texture2DArray) {
-        self.use_texture_internal(name);
-        texture.bind();
+    pub fn use_texture_array<'a>(&self, name: &str, textures: impl Into<TextureArray<'a>>) {
+        match textures.into() {
+            TextureArray::Texture2DArray(texture) => {
+                self.use_texture_internal(name);
+                texture.bind();
+            }
+            TextureArray::Textures(textures) => {
+                let count = textures.len() as u32;
+                let first = self.texture_units(name, count);
+                self.use_uniform_array(
+                    name,
+                    &(first..first + count).map(|i| i as i32).collect::<Vec<_>>(),
+                );
+                for (i, texture) in textures.iter().enumerate() {
+                    unsafe {
+                        self.context
+                            .active_texture(crate::context::TEXTURE0 + first + i as u32);
+                    }
+                    texture.bind();
+                }
+            }
+        }
     }
 
     ///
     /// Use the given texture array in this shader program and associate it with the given named variable.
     /// The glsl shader variable must be of type `uniform sampler2DArray` and can only be accessed in the fragment shader.
     ///
+    /// If the texture is not defined or not used in the shader code, it is ignored unless strict uniforms is enabled, see [Program::use_uniform].
+    ///
     /// # Panic
-    /// Will panic if the texture is not defined in the shader code or not used.
-    /// In the latter case the variable is removed by the shader compiler.
+    /// Will panic if the texture is not defined or not used in the shader code and strict uniforms is enabled.
     ///
     pub fn use_depth_texture_array(&self, name: &str, texture: &DepthTexture2DArray) {
         self.use_texture_internal(name);
@@ -237,9 +301,10 @@ impl Program {
     /// Use the given texture cube map in this shader program and associate it with the given named variable.
     /// The glsl shader variable must be of type `uniform samplerCube` and can only be accessed in the fragment shader.
     ///
+    /// If the texture is not defined or not used in the shader code, it is ignored unless strict uniforms is enabled, see [Program::use_uniform].
+    ///
     /// # Panic
-    /// Will panic if the texture is not defined in the shader code or not used.
-    /// In the latter case the variable is removed by the shader compiler.
+    /// Will panic if the texture is not defined or not used in the shader code and strict uniforms is enabled.
     ///
     pub fn use_texture_cube(&self, name: &str, texture: &TextureCubeMap) {
         self.use_texture_internal(name);
@@ -250,9 +315,10 @@ impl Program {
     /// Use the given texture cube map in this shader program and associate it with the given named variable.
     /// The glsl shader variable must be of type `uniform samplerCube` and can only be accessed in the fragment shader.
     ///
+    /// If the texture is not defined or not used in the shader code, it is ignored unless strict uniforms is enabled, see [Program::use_uniform].
+    ///
     /// # Panic
-    /// Will panic if the texture is not defined in the shader code or not used.
-    /// In the latter case the variable is removed by the shader compiler.
+    /// Will panic if the texture is not defined or not used in the shader code and strict uniforms is enabled.
     ///
     pub fn use_depth_texture_cube(&self, name: &str, texture: &DepthTextureCubeMap) {
         self.use_texture_internal(name);
@@ -263,9 +329,10 @@ impl Program {
     /// Use the given 3D texture in this shader program and associate it with the given named variable.
     /// The glsl shader variable must be of type `uniform sampler3D` and can only be accessed in the fragment shader.
     ///
+    /// If the texture is not defined or not used in the shader code, it is ignored unless strict uniforms is enabled, see [Program::use_uniform].
+    ///
     /// # Panic
-    /// Will panic if the texture is not defined in the shader code or not used.
-    /// In the latter case the variable is removed by the shader compiler.
+    /// Will panic if the texture is not defined or not used in the shader code and strict uniforms is enabled.
     ///
     pub fn use_texture_3d(&self, name: &str, texture: &Texture3D) {
         self.use_texture_internal(name);
@@ -285,12 +352,7 @@ impl Program {
     }
 
     fn use_texture_internal(&self, name: &str) -> u32 {
-        if !self.textures.read().unwrap().contains_key(name) {
-            let mut map = self.textures.write().unwrap();
-            let index = map.len() as u32;
-            map.insert(name.to_owned(), index);
-        };
-        let index = *self.textures.read().unwrap().get(name).unwrap();
+        let index = self.texture_units(name, 1);
         self.use_uniform(name, index as i32);
         unsafe {
             self.context
@@ -299,6 +361,23 @@ impl Program {
         index
     }
 
+    // Returns the first of the given number of consecutive texture units reserved for the texture variable with the given name.
+    fn texture_units(&self, name: &str, count: u32) -> u32 {
+        if let Some((first, reserved)) = self.textures.read().unwrap().get(name) {
+            if *reserved >= count {
+                return *first;
+            }
+        }
+        let mut map = self.textures.write().unwrap();
+        let first = map
+            .values()
+            .map(|(first, reserved)| first + reserved)
+            .max()
+            .unwrap_or(0);
+        map.insert(name.to_owned(), (first, count));
+        first
+    }
+
     ///
     /// Use the given [UniformBuffer] in this shader program and associate it with the given named variable.
     ///
@@ -430,14 +509,30 @@ impl Program {
     /// If you want to use an [ElementBuffer], see [Program::draw_elements].
     ///
     pub fn draw_arrays(&self, render_states: RenderStates, viewport: Viewport, count: u32) {
+        self.draw_subset_of_arrays(render_states, viewport, 0, count)
+    }
+
+    ///
+    /// Draws `count` number of triangles, starting at vertex `first`, with the given render states and viewport using this shader program.
+    /// Requires that all attributes and uniforms have been defined using the use_attribute and use_uniform methods.
+    /// Assumes that the data for the three vertices in a triangle is defined contiguous in each vertex buffer.
+    /// If you want to use an [ElementBuffer], see [Program::draw_subset_of_elements].
+    ///
+    pub fn draw_subset_of_arrays(
+        &self,
+        render_states: RenderStates,
+        viewport: Viewport,
+        first: u32,
+        count: u32,
+    ) {
         self.context.set_viewport(viewport);
         self.context.set_render_states(render_states);
         self.use_program();
         unsafe {
             self.context
-                .draw_arrays(crate::context::TRIANGLES, 0, count as i32);
-            for location in self.attributes.values() {
-                self.context.disable_vertex_attrib_array(*location);
+                .draw_arrays(crate::context::TRIANGLES, first as i32, count as i32);
+            for attribute in self.attributes.values() {
+                self.context.disable_vertex_attrib_array(attribute.location);
             }
             self.context.bind_vertex_array(None);
         }
@@ -472,8 +567,8 @@ impl Program {
             );
             self.context
                 .bind_buffer(crate::context::ELEMENT_ARRAY_BUFFER, None);
-            for location in self.attributes.values() {
-                self.context.disable_vertex_attrib_array(*location);
+            for attribute in self.attributes.values() {
+                self.context.disable_vertex_attrib_array(attribute.location);
             }
             self.context.bind_vertex_array(None);
         }
@@ -507,6 +602,7 @@ impl Program {
 
     ///
     /// Draws a subset of the triangles defined by the given [ElementBuffer] with the given render states and viewport using this shader program.
+    /// The subset is given by the index of the first element and the number of elements to draw.
     /// Requires that all attributes and uniforms have been defined using the use_attribute and use_uniform methods.
     /// If you do not want to use an [ElementBuffer], see [Program::draw_arrays].
     ///
@@ -527,13 +623,13 @@ impl Program {
                 crate::context::TRIANGLES,
                 count as i32,
                 T::data_type(),
-                first as i32,
+                (first as usize * std::mem::size_of::<T>()) as i32,
             );
             self.context
                 .bind_buffer(crate::context::ELEMENT_ARRAY_BUFFER, None);
 
-            for location in self.attributes.values() {
-                self.context.disable_vertex_attrib_array(*location);
+            for attribute in self.attributes.values() {
+                self.context.disable_vertex_attrib_array(attribute.location);
             }
             self.context.bind_vertex_array(None);
         }
@@ -588,13 +684,13 @@ impl Program {
                 crate::context::TRIANGLES,
                 count as i32,
                 T::data_type(),
-                first as i32,
+                (first as usize * std::mem::size_of::<T>()) as i32,
                 instance_count as i32,
             );
             self.context
                 .bind_buffer(crate::context::ELEMENT_ARRAY_BUFFER, None);
-            for location in self.attributes.values() {
-                self.context.disable_vertex_attrib_array(*location);
+            for attribute in self.attributes.values() {
+                self.context.disable_vertex_attrib_array(attribute.location);
             }
             self.context.bind_vertex_array(None);
         }
@@ -608,26 +704,75 @@ impl Program {
 
     ///
     /// Returns true if this program uses the uniform with the given name.
+    /// Same as [Program::has_uniform].
     ///
     pub fn requires_uniform(&self, name: &str) -> bool {
-        self.uniforms.contains_key(name)
+        self.has_uniform(name)
+    }
+
+    ///
+    /// Returns true if this program uses the uniform block with the given name, ie. the block is defined and used in the shader code,
+    /// which is required for calling [Program::use_uniform_block].
+    ///
+    pub fn requires_uniform_block(&self, name: &str) -> bool {
+        self.uniform_blocks.read().unwrap().contains_key(name)
+            || unsafe { self.context.get_uniform_block_index(self.id, name) }.is_some()
     }
 
     ///
     /// Returns true if this program uses the attribute with the given name.
+    /// Same as [Program::has_attribute].
     ///
     pub fn requires_attribute(&self, name: &str) -> bool {
+        self.has_attribute(name)
+    }
+
+    ///
+    /// Returns true if this program has an active uniform with the given name, ie. the uniform is defined and used in the shader code.
+    ///
+    pub fn has_uniform(&self, name: &str) -> bool {
+        self.uniforms.contains_key(name)
+    }
+
+    ///
+    /// Returns true if this program has an active attribute with the given name, ie. the attribute is defined and used in the shader code.
+    ///
+    pub fn has_attribute(&self, name: &str) -> bool {
         self.attributes.contains_key(name)
     }
 
+    ///
+    /// Returns information about the active uniforms in this program sorted by name.
+    /// Uniforms that are defined but not used in the shader code are removed by the shader compiler and are therefore not included.
+    /// Uniforms in uniform blocks are not included.
+    ///
+    pub fn uniforms(&self) -> Vec<UniformInfo> {
+        let mut uniforms = self.uniforms.values().cloned().collect::<Vec<_>>();
+        uniforms.sort_by(|a, b| a.name.cmp(&b.name));
+        uniforms
+    }
+
+    ///
+    /// Returns information about the active vertex attributes in this program sorted by name.
+    /// Attributes that are defined but not used in the shader code are removed by the shader compiler and are therefore not included.
+    ///
+    pub fn attributes(&self) -> Vec<AttributeInfo> {
+        let mut attributes = self.attributes.values().cloned().collect::<Vec<_>>();
+        attributes.sort_by(|a, b| a.name.cmp(&b.name));
+        attributes
+    }
+
     fn location(&self, name: &str) -> u32 {
         self.use_program();
-        *self.attributes.get(name).unwrap_or_else(|| {
-            panic!(
-                "the attribute {} is sent to the shader but not defined or never used",
-                name
-            )
-        })
+        self.attributes
+            .get(name)
+            .unwrap_or_else(|| {
+                panic!(
+                    "the attribute {} is sent to the shader but not defined or never used",
+                    name
+                )
+            })
+            .location
     }
 
     fn use_program(&self) {
@@ -643,6 +788,152 @@ impl Program {
     }
 }
 
+///
+/// A shader program which is being compiled and linked, possibly in parallel with the rendering, see [Program::start_compilation].
+///
+pub struct ProgramCompilation {
+    context: Context,
+    id: crate::context::Program,
+    shaders: Option<[(crate::context::Shader, String, ShaderLineMap); 2]>,
+    error: Option<CoreError>,
+}
+
+impl ProgramCompilation {
+    ///
+    /// Returns true if the compilation and linking is done, ie. if [Self::finish] will return without blocking.
+    /// Always returns true if the `KHR_parallel_shader_compile` extension is not supported.
+    ///
+    pub fn is_complete(&self) -> bool {
+        self.error.is_some()
+            || !self.context.supports_parallel_shader_compile()
+            || unsafe { self.context.get_program_completion_status(self.id) }
+    }
+
+    ///
+    /// Returns the compiled and linked [Program] or an error if the compilation or linking failed.
+    /// Blocks until the compilation is done, use [Self::is_complete] to avoid blocking.
+    ///
+    pub fn finish(mut self) -> Result<Program, CoreError> {
+        trace_span!("three_d::link_program");
+        if let Some(error) = self.error.take() {
+            unsafe {
+                self.context.delete_program(self.id);
+            }
+            return Err(error);
+        }
+        let [(vert_shader, vertex_shader_source, vertex_line_map), (frag_shader, fragment_shader_source, fragment_line_map)] =
+            self.shaders.take().unwrap();
+        let context = &self.context;
+        let id = self.id;
+        unsafe {
+            if !context.get_program_link_status(id) {
+                let vertex_log = context.get_shader_info_log(vert_shader);
+                let fragment_log = context.get_shader_info_log(frag_shader);
+                let program_log = context.get_program_info_log(id);
+                context.delete_shader(vert_shader);
+                context.delete_shader(frag_shader);
+                context.delete_program(id);
+                if !vertex_log.is_empty() {
+                    Err(shader_compilation_error(
+                        "vertex",
+                        vertex_log,
+                        vertex_shader_source,
+                        &vertex_line_map,
+                    ))?;
+                }
+                if !fragment_log.is_empty() {
+                    Err(shader_compilation_error(
+                        "fragment",
+                        fragment_log,
+                        fragment_shader_source,
+                        &fragment_line_map,
+                    ))?;
+                }
+                if !program_log.is_empty() {
+                    Err(CoreError::ShaderLink(program_log))?;
+                }
+                Err(CoreError::ShaderCompilerError)?;
+            }
+
+            context.detach_shader(id, vert_shader);
+            context.detach_shader(id, frag_shader);
+            context.delete_shader(vert_shader);
+            context.delete_shader(frag_shader);
+
+            // Init vertex attributes
+            let num_attribs = context.get_active_attributes(id);
+            let mut attributes = HashMap::new();
+            for i in 0..num_attribs {
+                if let Some(crate::context::ActiveAttribute { name, size, atype }) = context
+                    .get_active_attribute(id, i)
+                    .filter(|a| !a.name.starts_with("gl_"))
+                {
+                    if let Some(location) = context.get_attrib_location(id, &name) {
+                        attributes.insert(
+                            name.clone(),
+                            AttributeInfo {
+                                name,
+                                gl_type: atype,
+                                array_len: size as u32,
+                                location,
+                            },
+                        );
+                    }
+                }
+            }
+
+            // Init uniforms
+            let num_uniforms = context.get_active_uniforms(id);
+            let mut uniforms = HashMap::new();
+            for i in 0..num_uniforms {
+                if let Some(crate::context::ActiveUniform { name, size, utype }) = context
+                    .get_active_uniform(id, i)
+                    .filter(|a| !a.name.starts_with("gl_"))
+                {
+                    if let Some(location) = context.get_uniform_location(id, &name) {
+                        let name = name.split('[').next().unwrap().to_string();
+                        uniforms.insert(
+                            name.clone(),
+                            UniformInfo {
+                                name,
+                                gl_type: utype,
+                                array_len: size as u32,
+                                location,
+                            },
+                        );
+                    }
+                }
+            }
+
+            Ok(Program {
+                context: context.clone(),
+                id,
+                attributes,
+                uniforms,
+                uniform_blocks: RwLock::new(HashMap::new()),
+                textures: RwLock::new(HashMap::new()),
+                missing_uniforms: RwLock::new(HashSet::new()),
+            })
+        }
+    }
+}
+
+impl Drop for ProgramCompilation {
+    fn drop(&mut self) {
+        if let Some([(vert_shader, _, _), (frag_shader, _, _)]) = self.shaders.take() {
+            unsafe {
+                self.context.delete_shader(vert_shader);
+                self.context.delete_shader(frag_shader);
+                self.context.delete_program(self.id);
+            }
+        } else if self.error.is_some() {
+            unsafe {
+                self.context.delete_program(self.id);
+            }
+        }
+    }
+}
+
 impl Drop for Program {
     fn drop(&mut self) {
         unsafe {
@@ -650,11 +941,77 @@ impl Drop for Program {
         }
     }
 }
-fn shader_compilation_error(typ: &str, log: String, source: String) -> CoreError {
+fn shader_compilation_error(
+    typ: &str,
+    log: String,
+    source: String,
+    line_map: &ShaderLineMap,
+) -> CoreError {
     let lines: Vec<String> = source
         .lines()
         .enumerate()
-        .map(|(index, l)| format!("{:0>3}: {}", index + 1, l))
+        .map(|(index, l)| match line_map.origin(index + 1) {
+            Some(origin) => format!("{:0>3} {}: {}", index + 1, origin, l),
+            None => format!("{:0>3}: {}", index + 1, l),
+        })
         .collect();
-    CoreError::ShaderCompilation(typ.to_string(), lines.join("\n"), log)
+    CoreError::ShaderCompilation(typ.to_string(), lines.join("\n"), line_map.remap_log(&log))
+}
+
+///
+/// Returns the given source with the given header in front and the number of lines in the header.
+/// The `#extension` directives in the source must come before any other statements, so they are moved to right after the `#version` directive in the header
+/// and replaced by empty lines in the source, which keeps the line numbers of the source.
+///
+fn with_header(header: &str, source: &str) -> (String, usize) {
+    let mut extensions = String::new();
+    let mut body = String::with_capacity(source.len());
+    for line in source.lines() {
+        if line.trim_start().starts_with("#extension") {
+            extensions.push_str(line.trim());
+            extensions.push('\n');
+        } else {
+            body.push_str(line);
+        }
+        body.push('\n');
+    }
+    let (version, rest) = header.split_once('\n').unwrap_or((header, ""));
+    let header = format!("{}\n{}{}", version, extensions, rest);
+    let header_lines = header.lines().count();
+    (format!("{}{}", header, body), header_lines)
+}
+
+///
+/// The textures used as a texture array in a shader program, see [Program::use_texture_array].
+///
+#[derive(Clone, Copy)]
+pub enum TextureArray<'a> {
+    /// A [Texture2DArray] used as a `uniform sampler2DArray` variable.
+    Texture2DArray(&'a Texture2DArray),
+    /// A list of [Texture2D]s used as an array of `uniform sampler2D` variables.
+    Textures(&'a [&'a Texture2D]),
+}
+
+impl<'a> From<&'a Texture2DArray> for TextureArray<'a> {
+    fn from(texture: &'a Texture2DArray) -> Self {
+        Self::Texture2DArray(texture)
+    }
+}
+
+impl<'a> From<&'a [&'a Texture2D]> for TextureArray<'a> {
+    fn from(textures: &'a [&'a Texture2D]) -> Self {
+        Self::Textures(textures)
+    }
+}
+
+impl<'a, const N: usize> From<&'a [&'a Texture2D; N]> for TextureArray<'a> {
+    fn from(textures: &'a [&'a Texture2D; N]) -> Self {
+        Self::Textures(textures)
+    }
+}
+
+impl<'a> From<&'a Vec<&'a Texture2D>> for TextureArray<'a> {
+    fn from(textures: &'a Vec<&'a Texture2D>) -> Self {
+        Self::Textures(textures)
+    }
 }
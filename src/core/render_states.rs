@@ -2,10 +2,15 @@
 //! Definitions of the input state needed for any draw call.
 //!
 
+///
+/// The maximum number of color outputs (draw buffers) that can have individual blending, see [RenderStates::draw_buffer_blend].
+///
+pub const MAX_DRAW_BUFFERS: usize = 8;
+
 ///
 /// A set of render specific states that has to be specified at each render call.
 ///
-#[derive(Debug, Copy, Clone, Default)]
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
 pub struct RenderStates {
     ///
     /// Defines which channels (red, green, blue, alpha and depth) to write to in a render call.
@@ -27,10 +32,25 @@ pub struct RenderStates {
     ///
     pub blend: Blend,
 
+    ///
+    /// Overrides the [RenderStates::blend] for individual color outputs (draw buffers) when rendering into multiple color textures,
+    /// where the index is the location of the output in the fragment shader.
+    /// For example, order independent transparency needs additive blending of the accumulated color and another blending of the revealage.
+    ///
+    /// Individual blending requires OpenGL 4.0 or OpenGL ES 3.2, see [Context::supports_draw_buffer_blend](crate::core::Context::supports_draw_buffer_blend).
+    /// If not supported, for example on web, the overrides are ignored and [RenderStates::blend] is used for all color outputs.
+    ///
+    pub draw_buffer_blend: [Option<Blend>; MAX_DRAW_BUFFERS],
+
     ///
     /// Defines whether the triangles that are backfacing, frontfacing or both should be skipped in a render call.
     ///
     pub cull: Cull,
+
+    ///
+    /// Defines the winding order of the frontfacing triangles, which is used for the [RenderStates::cull] and `gl_FrontFacing` in the fragment shader.
+    ///
+    pub front_face: FrontFace,
 }
 
 ///
@@ -54,6 +74,19 @@ impl Default for Cull {
     }
 }
 
+///
+/// Defines the winding order of the vertices of frontfacing triangles as seen on the screen.
+/// Meshes imported with the opposite winding order can use [FrontFace::Clockwise] instead of reversing the order of the indices.
+///
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum FrontFace {
+    /// Triangles with vertices in counter-clockwise order are frontfacing.
+    #[default]
+    CounterClockwise,
+    /// Triangles with vertices in clockwise order are frontfacing.
+    Clockwise,
+}
+
 ///
 /// Determines whether or not a fragment/pixel from the current render call should be discarded
 /// when comparing its depth with the depth of the current fragment/pixel.
@@ -79,6 +112,86 @@ impl Default for DepthTest {
     }
 }
 
+impl DepthTest {
+    ///
+    /// Returns the depth test which gives the same result when the depth values are reversed, see [DepthMode].
+    /// For example, [DepthTest::Less] becomes [DepthTest::Greater].
+    ///
+    pub fn reversed(self) -> Self {
+        match self {
+            Self::Less => Self::Greater,
+            Self::LessOrEqual => Self::GreaterOrEqual,
+            Self::Greater => Self::Less,
+            Self::GreaterOrEqual => Self::LessOrEqual,
+            _ => self,
+        }
+    }
+}
+
+///
+/// Defines how the distance from the viewer is mapped to the depth values stored in a depth texture.
+///
+/// The standard mapping stores 0 at the near plane and 1 at the far plane.
+/// Since the mapping of a perspective projection is non-linear, most of the precision is then used close to the near plane,
+/// which results in z-fighting far away from the viewer when the ratio between the far and near plane distances is large.
+/// The reversed mappings store 1 at the near plane and 0 at the far plane, which together with the floating point precision of a 32 bit float depth texture,
+/// distributes the precision almost evenly in log scale and therefore removes most z-fighting.
+///
+/// Use [Context::reversed_depth_mode] to get the best reversed mapping supported by the context.
+///
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum DepthMode {
+    /// Depth 0 at the near plane and 1 at the far plane.
+    #[default]
+    Standard,
+    /// Depth 1 at the near plane and 0 at the far plane, but the projection still maps to the standard clip space depth range `[-1, 1]`.
+    /// The depth test and clear values are reversed, but since the clip space depth is mapped to `[0, 1]` by computing `0.5 * z + 0.5`,
+    /// the precision of a 32 bit float depth texture is lost in the addition and the reduction of z-fighting is small.
+    /// This is the only reversed mapping available on web, since WebGL2 does not support clip control.
+    Reversed,
+    /// Depth 1 at the near plane and 0 at the far plane where the projection maps to the clip space depth range `[0, 1]` which is stored directly in the depth texture,
+    /// see [Context::supports_clip_control]. This gives the full precision improvement when rendering to a 32 bit float depth texture.
+    ReversedZeroToOne,
+}
+
+impl DepthMode {
+    ///
+    /// Returns whether or not the depth values are reversed, ie. the depth is 1 at the near plane and 0 at the far plane.
+    ///
+    pub fn is_reversed(&self) -> bool {
+        *self != Self::Standard
+    }
+
+    ///
+    /// Returns the depth value stored at the far plane, ie. the value to clear a depth texture to.
+    ///
+    pub fn far_depth(&self) -> f32 {
+        if self.is_reversed() {
+            0.0
+        } else {
+            1.0
+        }
+    }
+
+    ///
+    /// Returns the depth value stored at the near plane.
+    ///
+    pub fn near_depth(&self) -> f32 {
+        1.0 - self.far_depth()
+    }
+
+    ///
+    /// Returns the clip space depth, after division with w, at the near and far plane respectively.
+    ///
+    pub fn clip_space_range(&self) -> (f32, f32) {
+        match self {
+            Self::Standard => (-1.0, 1.0),
+            Self::Reversed => (1.0, -1.0),
+            Self::ReversedZeroToOne => (1.0, 0.0),
+        }
+    }
+}
+
 ///
 /// Defines which channels (red, green, blue, alpha and depth) to write to in a render call.
 ///
@@ -190,6 +303,19 @@ impl Blend {
         alpha_equation: BlendEquationType::Add,
     };
 
+    ///
+    /// Transparency blending parameters for colors where the color channels are already multiplied with the alpha value (premultiplied alpha),
+    /// for example when sampling a texture created by [Texture2D::new_premultiplied](crate::core::Texture2D::new_premultiplied). Like [Blend::TRANSPARENCY], it works the same way on desktop and web.
+    ///
+    pub const PREMULTIPLIED: Self = Self::Enabled {
+        source_rgb_multiplier: BlendMultiplierType::One,
+        source_alpha_multiplier: BlendMultiplierType::Zero,
+        destination_rgb_multiplier: BlendMultiplierType::OneMinusSrcAlpha,
+        destination_alpha_multiplier: BlendMultiplierType::One,
+        rgb_equation: BlendEquationType::Add,
+        alpha_equation: BlendEquationType::Add,
+    };
+
     ///
     /// Adds the color of the render target with the output color of the render call.
     ///
@@ -201,6 +327,58 @@ impl Blend {
         rgb_equation: BlendEquationType::Add,
         alpha_equation: BlendEquationType::Add,
     };
+
+    ///
+    /// Multiplies the color of the render target with the output color of the render call, which darkens the render target.
+    /// The alpha value of the render target is unchanged.
+    ///
+    pub const MULTIPLY: Self = Self::Enabled {
+        source_rgb_multiplier: BlendMultiplierType::DstColor,
+        source_alpha_multiplier: BlendMultiplierType::Zero,
+        destination_rgb_multiplier: BlendMultiplierType::Zero,
+        destination_alpha_multiplier: BlendMultiplierType::One,
+        rgb_equation: BlendEquationType::Add,
+        alpha_equation: BlendEquationType::Add,
+    };
+
+    ///
+    /// Screen blending which is the inverse of [Blend::MULTIPLY], ie. `1 - (1 - source) * (1 - destination)`, which lightens the render target.
+    /// The alpha value of the render target is unchanged.
+    ///
+    pub const SCREEN: Self = Self::Enabled {
+        source_rgb_multiplier: BlendMultiplierType::One,
+        source_alpha_multiplier: BlendMultiplierType::Zero,
+        destination_rgb_multiplier: BlendMultiplierType::OneMinusSrcColor,
+        destination_alpha_multiplier: BlendMultiplierType::One,
+        rgb_equation: BlendEquationType::Add,
+        alpha_equation: BlendEquationType::Add,
+    };
+
+    ///
+    /// Keeps the minimum of the color and alpha of the render target and the output of the render call for each channel.
+    /// The multipliers are ignored for the min blend equation.
+    ///
+    pub const MIN: Self = Self::Enabled {
+        source_rgb_multiplier: BlendMultiplierType::One,
+        source_alpha_multiplier: BlendMultiplierType::One,
+        destination_rgb_multiplier: BlendMultiplierType::One,
+        destination_alpha_multiplier: BlendMultiplierType::One,
+        rgb_equation: BlendEquationType::Min,
+        alpha_equation: BlendEquationType::Min,
+    };
+
+    ///
+    /// Keeps the maximum of the color and alpha of the render target and the output of the render call for each channel.
+    /// The multipliers are ignored for the max blend equation.
+    ///
+    pub const MAX: Self = Self::Enabled {
+        source_rgb_multiplier: BlendMultiplierType::One,
+        source_alpha_multiplier: BlendMultiplierType::One,
+        destination_rgb_multiplier: BlendMultiplierType::One,
+        destination_alpha_multiplier: BlendMultiplierType::One,
+        rgb_equation: BlendEquationType::Max,
+        alpha_equation: BlendEquationType::Max,
+    };
 }
 
 impl Default for Blend {
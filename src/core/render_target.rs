@@ -60,6 +60,21 @@ impl<'a> RenderTarget<'a> {
         }
     }
 
+    ///
+    /// Returns whether this render target stores the colors as floats, ie. not clamped to the range `[0, 1]`.
+    /// The screen and render targets constructed from a framebuffer are assumed to store the colors as fixed point values.
+    ///
+    pub(crate) fn is_float(&self) -> bool {
+        self.color.as_ref().is_some_and(|color| color.is_float())
+    }
+
+    ///
+    /// Returns whether this is the screen render target, see [RenderTarget::screen].
+    ///
+    pub(crate) fn is_screen(&self) -> bool {
+        self.id.is_none()
+    }
+
     ///
     /// Constructs a new render target that enables rendering into the given [ColorTarget] and [DepthTarget].
     ///
@@ -96,8 +111,12 @@ impl<'a> RenderTarget<'a> {
     ///
     /// Clears the color and depth of the part of this render target that is inside the given scissor box.
     ///
-    pub fn clear_partially(&self, scissor_box: ScissorBox, clear_state: ClearState) -> &Self {
-        self.context.set_scissor(scissor_box);
+    pub fn clear_partially(
+        &self,
+        scissor_box: impl Into<ScissorBox>,
+        clear_state: ClearState,
+    ) -> &Self {
+        self.context.set_scissor(scissor_box.into());
         self.bind(crate::context::DRAW_FRAMEBUFFER);
         clear_state.apply(&self.context);
         self
@@ -118,10 +137,10 @@ impl<'a> RenderTarget<'a> {
     ///
     pub fn write_partially<E: std::error::Error>(
         &self,
-        scissor_box: ScissorBox,
+        scissor_box: impl Into<ScissorBox>,
         render: impl FnOnce() -> Result<(), E>,
     ) -> Result<&Self, E> {
-        self.context.set_scissor(scissor_box);
+        self.context.set_scissor(scissor_box.into());
         self.bind(crate::context::DRAW_FRAMEBUFFER);
         render()?;
         if let Some(ref color) = self.color {
@@ -158,10 +177,14 @@ impl<'a> RenderTarget<'a> {
     /// - Unsigned byte RGBA (Specify `T` as either `Vec4<u8>` or `[u8; 4]`) which works with any render target using `u8` as its base type.
     /// - 32-bit float RGBA (Specify `T` as either `Vec4<f32>` or `[f32; 4]`) which works with any render target using `f16` or `f32` as its base type.
     ///
-    pub fn read_color_partially<T: TextureDataType>(&self, scissor_box: ScissorBox) -> Vec<T> {
+    pub fn read_color_partially<T: TextureDataType>(
+        &self,
+        scissor_box: impl Into<ScissorBox>,
+    ) -> Vec<T> {
         if self.id.is_some() && self.color.is_none() {
             panic!("Cannot read color from a render target without a color target");
         }
+        let scissor_box = scissor_box.into();
         let format = format_from_data_type::<T>();
         let data_type = T::data_type();
 
@@ -198,6 +221,68 @@ impl<'a> RenderTarget<'a> {
         pixels
     }
 
+    ///
+    /// Reads the colors of the pixels in this render target into the given slice instead of allocating a new vector, see [RenderTarget::read_color].
+    /// Use this to avoid allocations when reading the colors every frame.
+    ///
+    /// # Panic
+    /// Will panic if the length of the slice is not the width times the height of this render target.
+    ///
+    pub fn read_color_into<T: TextureDataType>(&self, pixels: &mut [T]) {
+        self.read_color_partially_into(self.scissor_box(), pixels)
+    }
+
+    ///
+    /// Reads the colors of the pixels in this render target inside the given scissor box into the given slice instead of allocating a new vector,
+    /// see [RenderTarget::read_color_partially].
+    ///
+    /// # Panic
+    /// Will panic if the length of the slice is not the width times the height of the scissor box.
+    ///
+    pub fn read_color_partially_into<T: TextureDataType>(
+        &self,
+        scissor_box: impl Into<ScissorBox>,
+        pixels: &mut [T],
+    ) {
+        if self.id.is_some() && self.color.is_none() {
+            panic!("Cannot read color from a render target without a color target");
+        }
+        let scissor_box = scissor_box.into();
+        assert_eq!(
+            pixels.len(),
+            scissor_box.width as usize * scissor_box.height as usize,
+            "the number of pixels must match the size of the scissor box"
+        );
+        let format = format_from_data_type::<T>();
+        let data_type = T::data_type();
+
+        #[cfg(target_arch = "wasm32")]
+        if format != crate::context::RGBA
+            || !(data_type == crate::context::UNSIGNED_BYTE || data_type == crate::context::FLOAT)
+        {
+            panic!("Only the texture data types `Vec4<T>` and `[T; 4]` where `T` is either `u8` or `f32` are supported when reading color from a render target on web.");
+        }
+
+        self.bind(crate::context::DRAW_FRAMEBUFFER);
+        self.bind(crate::context::READ_FRAMEBUFFER);
+        unsafe {
+            self.context.read_pixels(
+                scissor_box.x,
+                scissor_box.y,
+                scissor_box.width as i32,
+                scissor_box.height as i32,
+                format,
+                data_type,
+                crate::context::PixelPackData::Slice(Some(to_mut_byte_slice(pixels))),
+            );
+        }
+        flip_y(
+            pixels,
+            scissor_box.width as usize,
+            scissor_box.height as usize,
+        );
+    }
+
     ///
     /// Returns the depth values in this render target.
     ///
@@ -210,10 +295,11 @@ impl<'a> RenderTarget<'a> {
     /// Returns the depth values in this render target inside the given scissor box.
     ///
     #[cfg(not(target_arch = "wasm32"))]
-    pub fn read_depth_partially(&self, scissor_box: ScissorBox) -> Vec<f32> {
+    pub fn read_depth_partially(&self, scissor_box: impl Into<ScissorBox>) -> Vec<f32> {
         if self.id.is_some() && self.depth.is_none() {
             panic!("cannot read depth from a render target without a depth target");
         }
+        let scissor_box = scissor_box.into();
         self.bind(crate::context::DRAW_FRAMEBUFFER);
         self.bind(crate::context::READ_FRAMEBUFFER);
         let mut pixels = vec![0u8; scissor_box.width as usize * scissor_box.height as usize * 4];
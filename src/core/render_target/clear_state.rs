@@ -14,7 +14,8 @@ pub struct ClearState {
     pub blue: Option<f32>,
     /// Defines the clear value for the alpha channel.
     pub alpha: Option<f32>,
-    /// Defines the clear value for the depth channel. A value of 1 means a depth value equal to the far plane and 0 means a depth value equal to the near plane.
+    /// Defines the clear value for the depth channel. A value of 1 means a depth value equal to the far plane and 0 means a depth value equal to the near plane,
+    /// unless the depth is reversed, see [ClearState::with_depth_mode].
     pub depth: Option<f32>,
 }
 
@@ -71,6 +72,17 @@ impl ClearState {
         }
     }
 
+    ///
+    /// Returns this clear state where the depth clear value, given in the standard mapping where 1 is the far plane, is converted to the given [DepthMode].
+    /// For example, `ClearState::default().with_depth_mode(camera.depth_mode())` clears the depth to the far plane for both standard and reversed depth.
+    ///
+    pub fn with_depth_mode(mut self, depth_mode: DepthMode) -> Self {
+        if depth_mode.is_reversed() {
+            self.depth = self.depth.map(|depth| 1.0 - depth);
+        }
+        self
+    }
+
     pub(in crate::core) fn apply(&self, context: &Context) {
         context.set_write_mask(WriteMask {
             red: self.red.is_some(),
@@ -70,6 +70,18 @@ impl<'a> ColorTarget<'a> {
         }
     }
 
+    ///
+    /// Returns whether the colors are stored as floats, ie. not clamped to the range `[0, 1]`.
+    ///
+    pub(crate) fn is_float(&self) -> bool {
+        match &self.target {
+            Some(ColorTexture::Single(texture)) => texture.is_float(),
+            Some(ColorTexture::Array { texture, .. }) => texture.is_float(),
+            Some(ColorTexture::CubeMap { texture, .. }) => texture.is_float(),
+            None => false,
+        }
+    }
+
     ///
     /// Clears the color of this color target as defined by the given clear state.
     ///
@@ -80,7 +92,11 @@ impl<'a> ColorTarget<'a> {
     ///
     /// Clears the color of the part of this color target that is inside the given scissor box.
     ///
-    pub fn clear_partially(&self, scissor_box: ScissorBox, clear_state: ClearState) -> &Self {
+    pub fn clear_partially(
+        &self,
+        scissor_box: impl Into<ScissorBox>,
+        clear_state: ClearState,
+    ) -> &Self {
         self.as_render_target().clear_partially(
             scissor_box,
             ClearState {
@@ -106,7 +122,7 @@ impl<'a> ColorTarget<'a> {
     ///
     pub fn write_partially<E: std::error::Error>(
         &self,
-        scissor_box: ScissorBox,
+        scissor_box: impl Into<ScissorBox>,
         render: impl FnOnce() -> Result<(), E>,
     ) -> Result<&Self, E> {
         self.as_render_target()
@@ -142,7 +158,7 @@ impl<'a> ColorTarget<'a> {
     /// - Unsigned byte RGBA (Specify `T` as either `Vec4<u8>` or `[u8; 4]`) which works with any color target using `u8` as its base type.
     /// - 32-bit float RGBA (Specify `T` as either `Vec4<f32>` or `[f32; 4]`) which works with any color target using `f16` or `f32` as its base type.
     ///
-    pub fn read_partially<T: TextureDataType>(&self, scissor_box: ScissorBox) -> Vec<T> {
+    pub fn read_partially<T: TextureDataType>(&self, scissor_box: impl Into<ScissorBox>) -> Vec<T> {
         self.as_render_target().read_color_partially(scissor_box)
     }
 
@@ -42,7 +42,11 @@ impl<C: TextureDataType> ColorTargetMultisample<C> {
     ///
     /// Clears the color of the part of this target that is inside the given scissor box.
     ///
-    pub fn clear_partially(&self, scissor_box: ScissorBox, clear_state: ClearState) -> &Self {
+    pub fn clear_partially(
+        &self,
+        scissor_box: impl Into<ScissorBox>,
+        clear_state: ClearState,
+    ) -> &Self {
         self.as_render_target().clear_partially(
             scissor_box,
             ClearState {
@@ -68,7 +72,7 @@ impl<C: TextureDataType> ColorTargetMultisample<C> {
     ///
     pub fn write_partially<E: std::error::Error>(
         &self,
-        scissor_box: ScissorBox,
+        scissor_box: impl Into<ScissorBox>,
         render: impl FnOnce() -> Result<(), E>,
     ) -> Result<&Self, E> {
         self.as_render_target()
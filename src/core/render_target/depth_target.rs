@@ -67,7 +67,11 @@ impl<'a> DepthTarget<'a> {
     ///
     /// Clears the depth of the part of this depth target that is inside the given scissor box.
     ///
-    pub fn clear_partially(&self, scissor_box: ScissorBox, clear_state: ClearState) -> &Self {
+    pub fn clear_partially(
+        &self,
+        scissor_box: impl Into<ScissorBox>,
+        clear_state: ClearState,
+    ) -> &Self {
         self.as_render_target().clear_partially(
             scissor_box,
             ClearState {
@@ -93,7 +97,7 @@ impl<'a> DepthTarget<'a> {
     ///
     pub fn write_partially<E: std::error::Error>(
         &self,
-        scissor_box: ScissorBox,
+        scissor_box: impl Into<ScissorBox>,
         render: impl FnOnce() -> Result<(), E>,
     ) -> Result<&Self, E> {
         self.as_render_target()
@@ -113,7 +117,7 @@ impl<'a> DepthTarget<'a> {
     /// Returns the depth values in this depth target inside the given scissor box.
     ///
     #[cfg(not(target_arch = "wasm32"))]
-    pub fn read_partially(&self, scissor_box: ScissorBox) -> Vec<f32> {
+    pub fn read_partially(&self, scissor_box: impl Into<ScissorBox>) -> Vec<f32> {
         self.as_render_target().read_depth_partially(scissor_box)
     }
 
@@ -39,7 +39,11 @@ impl<D: DepthTextureDataType> DepthTargetMultisample<D> {
     ///
     /// Clears the color and depth of the part of this target that is inside the given scissor box.
     ///
-    pub fn clear_partially(&self, scissor_box: ScissorBox, clear_state: ClearState) -> &Self {
+    pub fn clear_partially(
+        &self,
+        scissor_box: impl Into<ScissorBox>,
+        clear_state: ClearState,
+    ) -> &Self {
         self.as_render_target().clear_partially(
             scissor_box,
             ClearState {
@@ -65,7 +69,7 @@ impl<D: DepthTextureDataType> DepthTargetMultisample<D> {
     ///
     pub fn write_partially<E: std::error::Error>(
         &self,
-        scissor_box: ScissorBox,
+        scissor_box: impl Into<ScissorBox>,
         render: impl FnOnce() -> Result<(), E>,
     ) -> Result<&Self, E> {
         self.as_render_target()
@@ -28,8 +28,34 @@ impl ScissorBox {
         }
     }
 
+    ///
+    /// Returns true if the scissor box contains no pixels, in which case nothing is cleared or rendered when using it.
+    ///
+    pub fn is_empty(&self) -> bool {
+        self.width == 0 || self.height == 0
+    }
+
     ///
     /// Returns the intersection between this and the other ScissorBox.
+    /// The intersection is empty if the two scissor boxes do not overlap.
+    ///
+    /// ```
+    /// # use three_d::*;
+    /// let scissor_box = ScissorBox { x: 10, y: 20, width: 100, height: 50 };
+    /// assert_eq!(
+    ///     scissor_box.intersection(ScissorBox { x: 50, y: 0, width: 100, height: 40 }),
+    ///     ScissorBox { x: 50, y: 20, width: 60, height: 20 }
+    /// );
+    ///
+    /// // A one pixel wide sliver along the right edge
+    /// let sliver = scissor_box.intersection(ScissorBox { x: 109, y: 0, width: 10, height: 100 });
+    /// assert_eq!(sliver, ScissorBox { x: 109, y: 20, width: 1, height: 50 });
+    /// assert!(!sliver.is_empty());
+    ///
+    /// // Boxes which only touch do not overlap
+    /// assert!(scissor_box.intersection(ScissorBox { x: 110, y: 20, width: 10, height: 10 }).is_empty());
+    /// assert!(scissor_box.intersection(ScissorBox { x: -50, y: 0, width: 10, height: 10 }).is_empty());
+    /// ```
     ///
     pub fn intersection(&self, other: impl Into<Self>) -> Self {
         let other = other.into();
@@ -50,6 +76,86 @@ impl ScissorBox {
     }
 }
 
+///
+/// A stack of nested scissor boxes, for example used when rendering into nested regions of a GUI, where each pushed region is clipped to the regions below it.
+/// The resulting region is given by [ScissorStack::current] and can be used directly in the `*_partially` functions, for example [RenderTarget::clear_partially] and [RenderTarget::render_partially].
+/// If the nested regions do not overlap, the resulting region is empty and nothing is cleared or rendered.
+///
+/// ```
+/// # use three_d::*;
+/// let mut stack = ScissorStack::new(Viewport::new_at_origo(800, 600));
+/// stack.push(ScissorBox { x: 100, y: 100, width: 200, height: 200 });
+/// assert_eq!(stack.push(ScissorBox { x: 299, y: 0, width: 100, height: 600 }), ScissorBox { x: 299, y: 100, width: 1, height: 200 });
+/// assert_eq!(stack.depth(), 2);
+///
+/// // A region outside the current region results in an empty region
+/// assert!(stack.push(ScissorBox { x: 0, y: 0, width: 50, height: 50 }).is_empty());
+/// stack.pop();
+/// assert_eq!(stack.current().width, 1);
+///
+/// // The root region is never popped
+/// stack.pop();
+/// stack.pop();
+/// assert_eq!(stack.pop(), None);
+/// assert_eq!(stack.current(), ScissorBox::new_at_origo(800, 600));
+/// ```
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScissorStack {
+    stack: Vec<ScissorBox>,
+}
+
+impl ScissorStack {
+    ///
+    /// Creates a new stack with the given region at the bottom, for example the viewport of the render target.
+    ///
+    pub fn new(root: impl Into<ScissorBox>) -> Self {
+        Self {
+            stack: vec![root.into()],
+        }
+    }
+
+    ///
+    /// Pushes the intersection between the given region and the current region onto the stack and returns it.
+    ///
+    pub fn push(&mut self, region: impl Into<ScissorBox>) -> ScissorBox {
+        let scissor_box = self.current().intersection(region);
+        self.stack.push(scissor_box);
+        scissor_box
+    }
+
+    ///
+    /// Pops the current region off the stack and returns it. The region given at construction is never popped, instead `None` is returned.
+    ///
+    pub fn pop(&mut self) -> Option<ScissorBox> {
+        if self.stack.len() > 1 {
+            self.stack.pop()
+        } else {
+            None
+        }
+    }
+
+    ///
+    /// Returns the current region, ie. the intersection of all the regions on the stack.
+    ///
+    pub fn current(&self) -> ScissorBox {
+        *self.stack.last().unwrap()
+    }
+
+    ///
+    /// Returns the number of regions pushed onto the stack, not counting the region given at construction.
+    ///
+    pub fn depth(&self) -> usize {
+        self.stack.len() - 1
+    }
+}
+
+impl From<&ScissorStack> for ScissorBox {
+    fn from(stack: &ScissorStack) -> Self {
+        stack.current()
+    }
+}
+
 impl From<crate::core::Viewport> for ScissorBox {
     fn from(viewport: crate::core::Viewport) -> Self {
         Self {
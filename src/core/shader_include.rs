@@ -0,0 +1,342 @@
+use crate::core::*;
+use std::collections::{HashMap, HashSet};
+use std::sync::{OnceLock, RwLock};
+
+///
+/// The name of the built-in shader snippet with common constants and functions, for example `PI`, `saturate` and `Hammersley`.
+/// Also includes the [DEPTH_SNIPPET].
+///
+pub const SHARED_SNIPPET: &str = "three-d/shared";
+
+///
+/// The name of the built-in shader snippet with the function `vec3 world_pos_from_depth(mat4 viewProjectionInverse, float depth, vec2 uv)`
+/// which reconstructs the world position from a value in a depth texture.
+///
+pub const DEPTH_SNIPPET: &str = "three-d/depth";
+
+///
+/// The name of the built-in shader snippet with the functions `vec3 srgb_from_linear_srgb(vec3 color)` and `vec3 linear_srgb_from_srgb(vec3 color)`
+/// which convert a color between the sRGB and linear sRGB color spaces.
+///
+pub const SRGB_SNIPPET: &str = "three-d/srgb";
+
+///
+/// The name of the built-in shader snippet with the function `vec3 tone_mapping(vec3 color)`, see [ToneMapping](crate::renderer::ToneMapping).
+///
+pub const TONE_MAPPING_SNIPPET: &str = "three-d/tone_mapping";
+
+///
+/// The name of the built-in shader snippet with the function `vec3 color_mapping(vec3 color)`, see [ColorMapping](crate::renderer::ColorMapping).
+///
+pub const COLOR_MAPPING_SNIPPET: &str = "three-d/color_mapping";
+
+///
+/// The name of the built-in shader snippet with the physically based lighting functions used by the lights,
+/// for example `calculate_light`, `fresnel_schlick_roughness` and `calculate_shadow`. Also includes the [SHARED_SNIPPET].
+/// Use [lights_shader_source](crate::renderer::lights_shader_source) to also get the lighting calculations for a specific set of lights.
+///
+pub const LIGHTING_SNIPPET: &str = "three-d/lighting";
+
+///
+/// The name of the built-in shader snippet with the function `calculate_area_light` which calculates the lighting from a rectangular light
+/// using linearly transformed cosines, see [AreaLight](crate::renderer::AreaLight). Also includes the [LIGHTING_SNIPPET].
+///
+pub const AREA_LIGHT_SNIPPET: &str = "three-d/area_light";
+
+const RESERVED_PREFIX: &str = "three-d/";
+
+fn snippets() -> &'static RwLock<HashMap<String, String>> {
+    static SNIPPETS: OnceLock<RwLock<HashMap<String, String>>> = OnceLock::new();
+    SNIPPETS.get_or_init(|| {
+        RwLock::new(HashMap::from(
+            [
+                (SHARED_SNIPPET, include_str!("shared.frag")),
+                (DEPTH_SNIPPET, include_str!("depth.frag")),
+                (SRGB_SNIPPET, include_str!("srgb.frag")),
+                (
+                    TONE_MAPPING_SNIPPET,
+                    include_str!("../renderer/viewer/shaders/tone_mapping.frag"),
+                ),
+                (
+                    COLOR_MAPPING_SNIPPET,
+                    include_str!("../renderer/viewer/shaders/color_mapping.frag"),
+                ),
+                (
+                    LIGHTING_SNIPPET,
+                    include_str!("../renderer/light/shaders/light_shared.frag"),
+                ),
+                (
+                    AREA_LIGHT_SNIPPET,
+                    include_str!("../renderer/light/shaders/area_light.frag"),
+                ),
+            ]
+            .map(|(name, source)| (name.to_string(), source.to_string())),
+        ))
+    })
+}
+
+///
+/// Registers a shader snippet with the given name, which can then be included in any shader source given to a [Program] using `#include <name>`.
+/// Registering a snippet with the same name as an already registered snippet replaces it,
+/// but programs that are already compiled are not affected, so register the snippets before they are used.
+///
+/// # Panic
+/// Will panic if the name starts with `three-d/`, since those names are reserved for the built-in snippets, for example [LIGHTING_SNIPPET].
+///
+pub fn register_shader_snippet(name: &str, source: impl Into<String>) {
+    assert!(
+        !name.starts_with(RESERVED_PREFIX),
+        "the shader snippet name {name} is reserved for the built-in snippets"
+    );
+    snippets()
+        .write()
+        .unwrap()
+        .insert(name.to_string(), source.into());
+}
+
+///
+/// Returns the source of the registered shader snippet with the given name, if any, see [register_shader_snippet].
+///
+pub fn shader_snippet(name: &str) -> Option<String> {
+    snippets().read().unwrap().get(name).cloned()
+}
+
+///
+/// Replaces each `#include <name>` directive in the given shader source with the source of the registered snippet with that name,
+/// see [register_shader_snippet] and the built-in snippets, for example [SHARED_SNIPPET].
+/// The directive must be on a line of its own and the name can also be given in quotes, ie. `#include "name"`.
+/// Snippets can include other snippets, and each snippet is only included once, the following includes of the same snippet are ignored.
+/// Note that the includes are resolved before the shader is compiled, so they are not affected by preprocessor conditions like `#ifdef`.
+///
+/// This is done automatically for the shader sources given to a [Program].
+/// Returns an error if a snippet is missing or if a snippet includes itself, directly or through other snippets.
+///
+/// ```
+/// # use three_d::*;
+/// register_shader_snippet("my/constants", "const float SCALE = 2.0;");
+/// register_shader_snippet("my/scale", "#include <my/constants>\nvec3 scale(vec3 v) { return SCALE * v; }");
+///
+/// // The included snippets are inserted in place and each snippet is only included once
+/// let source = resolve_shader_includes("#include <my/scale>\n#include \"my/constants\"\nvoid main() {}").unwrap();
+/// assert_eq!(source, "const float SCALE = 2.0;\nvec3 scale(vec3 v) { return SCALE * v; }\n\nvoid main() {}\n");
+///
+/// // A missing snippet is an error which tells where it was included
+/// let error = resolve_shader_includes("void main() {}\n#include <my/missing>").unwrap_err();
+/// assert_eq!(error.to_string(), "failed to resolve shader includes: missing shader snippet <my/missing> included at line 2");
+///
+/// // So is a snippet which includes itself through another snippet
+/// register_shader_snippet("my/a", "#include <my/b>");
+/// register_shader_snippet("my/b", "\n#include <my/a>");
+/// let error = resolve_shader_includes("#include <my/a>").unwrap_err();
+/// assert_eq!(
+///     error.to_string(),
+///     "failed to resolve shader includes: cyclic include of shader snippet <my/a> at line 2 of <my/b>: <my/a> -> <my/b> -> <my/a>"
+/// );
+/// ```
+///
+pub fn resolve_shader_includes(source: &str) -> Result<String, CoreError> {
+    resolve_shader_includes_with_line_map(source).map(|(source, _)| source)
+}
+
+#[derive(Clone, Debug)]
+struct SourceLine {
+    snippet: Option<String>,
+    line: usize,
+}
+
+///
+/// Maps the lines of a shader source, where the includes are resolved, back to the lines in the snippets and in the source before the includes were resolved,
+/// see [resolve_shader_includes_with_line_map].
+///
+#[derive(Clone, Debug)]
+pub struct ShaderLineMap {
+    header_lines: usize,
+    lines: Option<Vec<SourceLine>>,
+}
+
+impl ShaderLineMap {
+    pub(crate) fn with_header_lines(mut self, header_lines: usize) -> Self {
+        self.header_lines = header_lines;
+        self
+    }
+
+    ///
+    /// Returns where the given line number (starting at 1) in the resolved source came from, for example `<name>:12` for line 12 of the snippet `name` or `source:3`,
+    /// or `None` if it came from the header or if the source did not include any snippets.
+    ///
+    pub fn origin(&self, line_number: usize) -> Option<String> {
+        let lines = self.lines.as_ref()?;
+        let source_line = lines.get(line_number.checked_sub(self.header_lines + 1)?)?;
+        Some(match &source_line.snippet {
+            Some(name) => format!("<{}>:{}", name, source_line.line),
+            None => format!("source:{}", source_line.line),
+        })
+    }
+
+    ///
+    /// Appends the origin to each line in the given shader info log which refers to a line number, for example `ERROR: 0:45: ...` or `0(45) : error ...`.
+    ///
+    pub fn remap_log(&self, log: &str) -> String {
+        if self.lines.is_none() {
+            return log.to_string();
+        }
+        log.lines()
+            .map(
+                |line| match log_line_number(line).and_then(|n| self.origin(n)) {
+                    Some(origin) => format!("{} [{}]", line, origin),
+                    None => line.to_string(),
+                },
+            )
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+fn log_line_number(line: &str) -> Option<usize> {
+    fn digits(s: &str) -> Option<(usize, &str)> {
+        let end = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+        s[..end].parse::<usize>().ok().map(|n| (n, &s[end..]))
+    }
+    for (index, _) in line.match_indices("0:").chain(line.match_indices("0(")) {
+        if index > 0 && line[..index].ends_with(|c: char| c.is_ascii_digit()) {
+            continue;
+        }
+        if let Some((number, rest)) = digits(&line[index + 2..]) {
+            if rest.starts_with(':') || rest.starts_with(')') {
+                return Some(number);
+            }
+        }
+    }
+    None
+}
+
+///
+/// Resolves the includes in the given shader source as [resolve_shader_includes] and also returns a [ShaderLineMap],
+/// which maps the lines in the resolved source back to where they came from.
+/// This is useful when compiling the resolved source without a [Program], since a [Program] already adds the origin of the lines to the compilation errors.
+///
+/// ```
+/// # use three_d::*;
+/// register_shader_snippet("my/functions", "float twice(float v) {\n    return 2.0 * v;\n}");
+/// let (source, line_map) = resolve_shader_includes_with_line_map("out float value;\n#include <my/functions>\nvoid main() { value = twice(1.0) }").unwrap();
+/// assert_eq!(source.lines().count(), 5);
+/// assert_eq!(line_map.origin(1).unwrap(), "source:1");
+/// assert_eq!(line_map.origin(3).unwrap(), "<my/functions>:2");
+/// assert_eq!(line_map.origin(5).unwrap(), "source:3");
+/// assert_eq!(line_map.origin(6), None);
+///
+/// // The line numbers in the errors from the shader compiler refer to the resolved source
+/// let log = "ERROR: 0:5: '}' : syntax error\nERROR: 1 compilation errors.";
+/// assert_eq!(line_map.remap_log(log), "ERROR: 0:5: '}' : syntax error [source:3]\nERROR: 1 compilation errors.");
+/// ```
+///
+pub fn resolve_shader_includes_with_line_map(
+    source: &str,
+) -> Result<(String, ShaderLineMap), CoreError> {
+    if !source.contains("#include") {
+        return Ok((
+            source.to_string(),
+            ShaderLineMap {
+                header_lines: 0,
+                lines: None,
+            },
+        ));
+    }
+    let snippets = snippets().read().unwrap();
+    let mut output = String::with_capacity(source.len());
+    let mut lines = Vec::new();
+    expand(
+        source,
+        None,
+        &snippets,
+        &mut Vec::new(),
+        &mut HashSet::new(),
+        &mut output,
+        &mut lines,
+    )?;
+    Ok((
+        output,
+        ShaderLineMap {
+            header_lines: 0,
+            lines: Some(lines),
+        },
+    ))
+}
+
+fn expand<'a>(
+    source: &str,
+    snippet: Option<&'a str>,
+    snippets: &'a HashMap<String, String>,
+    stack: &mut Vec<&'a str>,
+    included: &mut HashSet<&'a str>,
+    output: &mut String,
+    lines: &mut Vec<SourceLine>,
+) -> Result<(), CoreError> {
+    let location = |index: usize| match snippet {
+        Some(name) => format!("line {} of <{}>", index + 1, name),
+        None => format!("line {}", index + 1),
+    };
+    for (index, line) in source.lines().enumerate() {
+        let source_line = SourceLine {
+            snippet: snippet.map(|name| name.to_string()),
+            line: index + 1,
+        };
+        let Some(directive) = line.trim_start().strip_prefix("#include") else {
+            output.push_str(line);
+            output.push('\n');
+            lines.push(source_line);
+            continue;
+        };
+        let directive = directive.trim();
+        let name = directive
+            .strip_prefix('<')
+            .and_then(|d| d.strip_suffix('>'))
+            .or_else(|| {
+                directive
+                    .strip_prefix('"')
+                    .and_then(|d| d.strip_suffix('"'))
+            })
+            .map(|name| name.trim())
+            .ok_or_else(|| {
+                CoreError::ShaderInclude(format!(
+                    "malformed include directive '{}' at {}",
+                    line.trim(),
+                    location(index)
+                ))
+            })?;
+        let (name, snippet_source) = snippets.get_key_value(name).ok_or_else(|| {
+            CoreError::ShaderInclude(format!(
+                "missing shader snippet <{}> included at {}",
+                name,
+                location(index)
+            ))
+        })?;
+        if stack.contains(&name.as_str()) {
+            let mut cycle = stack.iter().map(|n| format!("<{n}>")).collect::<Vec<_>>();
+            cycle.push(format!("<{name}>"));
+            return Err(CoreError::ShaderInclude(format!(
+                "cyclic include of shader snippet <{}> at {}: {}",
+                name,
+                location(index),
+                cycle.join(" -> ")
+            )));
+        }
+        if included.insert(name) {
+            stack.push(name);
+            expand(
+                snippet_source,
+                Some(name),
+                snippets,
+                stack,
+                included,
+                output,
+                lines,
+            )?;
+            stack.pop();
+        } else {
+            output.push('\n');
+            lines.push(source_line);
+        }
+    }
+    Ok(())
+}
@@ -1,3 +1,5 @@
+#include <three-d/depth>
+
 
 #define PI 3.1415926
 
@@ -7,12 +9,6 @@ float saturate(in float value)
     return clamp(value, 0.0, 1.0);
 }
 
-vec3 world_pos_from_depth(mat4 viewProjectionInverse, float depth, vec2 uv) {
-    vec4 clipSpacePosition = vec4(uv * 2.0 - 1.0, depth * 2.0 - 1.0, 1.0);
-    vec4 position = viewProjectionInverse * clipSpacePosition;
-    return position.xyz / position.w;
-}
-
 // http://holger.dammertz.org/stuff/notes_HammersleyOnHemisphere.html
 // efficient VanDerCorpus calculation.
 float RadicalInverse_VdC(uint bits) 
@@ -0,0 +1,20 @@
+
+// Converts a color from linear sRGB to sRGB color space
+vec3 srgb_from_linear_srgb(vec3 color) {
+    vec3 a = vec3(0.055, 0.055, 0.055);
+    vec3 ap1 = vec3(1.0, 1.0, 1.0) + a;
+    vec3 g = vec3(2.4, 2.4, 2.4);
+    vec3 ginv = 1.0 / g;
+    vec3 select = step(vec3(0.0031308, 0.0031308, 0.0031308), color);
+    vec3 lo = color * 12.92;
+    vec3 hi = ap1 * pow(color, ginv) - a;
+    return mix(lo, hi, select);
+}
+
+// Converts a color from sRGB to linear sRGB color space
+vec3 linear_srgb_from_srgb(vec3 color) {
+    vec3 select = step(vec3(0.04045, 0.04045, 0.04045), color);
+    vec3 lo = color / 12.92;
+    vec3 hi = pow((color + 0.055) / 1.055, vec3(2.4, 2.4, 2.4));
+    return mix(lo, hi, select);
+}
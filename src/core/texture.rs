@@ -37,6 +37,14 @@ mod depth_texture2d_multisample;
 #[doc(inline)]
 pub(in crate::core) use depth_texture2d_multisample::*;
 
+mod texture_pool;
+#[doc(inline)]
+pub use texture_pool::*;
+
+mod ktx2;
+#[doc(inline)]
+pub use ktx2::*;
+
 use data_type::*;
 pub use three_d_asset::texture::{
     Interpolation, Mipmap, Texture2D as CpuTexture, Texture3D as CpuTexture3D, TextureData,
@@ -57,6 +65,151 @@ impl<T: TextureDataType + PrimitiveDataType> TextureDataType for [T; 3] {}
 impl<T: TextureDataType + PrimitiveDataType> TextureDataType for [T; 4] {}
 
 impl TextureDataType for Quat {}
+impl TextureDataType for R11G11B10F {}
+
+///
+/// A packed RGB color with 11 bit unsigned floats for the red and green channels and a 10 bit unsigned float for the blue channel,
+/// which can be used as [TextureDataType]. It has the same range as [f16], but no sign, no alpha channel and a lower precision,
+/// which makes it the cheapest format for rendering colors with high dynamic range, see [HdrFormat].
+///
+/// ```
+/// # use three_d::*;
+/// let color = [0.25, 100.0, 3.14];
+/// let packed = R11G11B10F::from_f32(color);
+/// let unpacked = packed.to_f32();
+/// for (a, b) in color.iter().zip(unpacked) {
+///     assert!((a - b).abs() <= a * 1.0 / 32.0);
+/// }
+/// assert_eq!(R11G11B10F::from_f32([-1.0, f32::NAN, 0.0]).to_f32(), [0.0, 0.0, 0.0]);
+/// ```
+///
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub struct R11G11B10F(pub u32);
+
+impl R11G11B10F {
+    ///
+    /// Packs the given RGB color. Negative values and NaN are stored as zero and values larger than the largest finite value are clamped.
+    ///
+    pub fn from_f32(color: [f32; 3]) -> Self {
+        Self(
+            pack_unsigned_float(color[0], 6)
+                | (pack_unsigned_float(color[1], 6) << 11)
+                | (pack_unsigned_float(color[2], 5) << 22),
+        )
+    }
+
+    ///
+    /// Returns the unpacked RGB color.
+    ///
+    pub fn to_f32(self) -> [f32; 3] {
+        [
+            unpack_unsigned_float(self.0 & 0x7FF, 6),
+            unpack_unsigned_float((self.0 >> 11) & 0x7FF, 6),
+            unpack_unsigned_float(self.0 >> 22, 5),
+        ]
+    }
+}
+
+///
+/// Converts the value to an unsigned float with five exponent bits, like [f16], and the given number of mantissa bits, rounding to the nearest value.
+///
+fn pack_unsigned_float(value: f32, mantissa_bits: u32) -> u32 {
+    let shift = 10 - mantissa_bits;
+    // f32::max returns the other value when one is NaN, so NaN becomes zero
+    let bits = f16::from_f32(value.max(0.0).clamp(0.0, 65000.0)).to_bits() as u32;
+    let max_finite = (30 << mantissa_bits) | ((1 << mantissa_bits) - 1);
+    ((bits + (1 << (shift - 1))) >> shift).min(max_finite)
+}
+
+fn unpack_unsigned_float(bits: u32, mantissa_bits: u32) -> f32 {
+    f16::from_bits((bits << (10 - mantissa_bits)) as u16).to_f32()
+}
+
+///
+/// A floating point format for color textures which can contain colors with high dynamic range, ie. values outside the range `[0, 1]`.
+/// The formats are listed from the cheapest to the most expensive in memory and bandwidth, see [preferred_hdr_format].
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum HdrFormat {
+    /// RGB without alpha stored in 32 bits per pixel, see [R11G11B10F].
+    R11G11B10F,
+    /// RGBA with 16 bit floats, ie. 64 bits per pixel.
+    Rgba16F,
+    /// RGBA with 32 bit floats, ie. 128 bits per pixel.
+    Rgba32F,
+}
+
+impl HdrFormat {
+    ///
+    /// Returns whether the format has an alpha channel.
+    ///
+    pub fn has_alpha(&self) -> bool {
+        *self != HdrFormat::R11G11B10F
+    }
+
+    ///
+    /// Returns the number of bytes used for each pixel.
+    ///
+    pub fn bytes_per_pixel(&self) -> u32 {
+        match self {
+            HdrFormat::R11G11B10F => 4,
+            HdrFormat::Rgba16F => 8,
+            HdrFormat::Rgba32F => 16,
+        }
+    }
+
+    ///
+    /// Creates a new empty color texture with this format, which can be used as a color target.
+    /// Check that the format is supported for rendering first, see [Context::supports_hdr_format].
+    ///
+    pub fn new_texture(
+        &self,
+        context: &Context,
+        width: u32,
+        height: u32,
+        interpolation: Interpolation,
+    ) -> Texture2D {
+        let new = match self {
+            HdrFormat::R11G11B10F => Texture2D::new_empty::<R11G11B10F>,
+            HdrFormat::Rgba16F => Texture2D::new_empty::<[f16; 4]>,
+            HdrFormat::Rgba32F => Texture2D::new_empty::<[f32; 4]>,
+        };
+        new(
+            context,
+            width,
+            height,
+            interpolation,
+            interpolation,
+            None,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+        )
+    }
+}
+
+///
+/// Returns the cheapest [HdrFormat] that can be rendered to with the given context, or `None` if rendering to floating point textures is not supported.
+/// If the alpha channel is needed, for example for rendering transparent objects into a texture which is later blended onto something else,
+/// use [preferred_hdr_format_with_alpha] instead.
+///
+pub fn preferred_hdr_format(context: &Context) -> Option<HdrFormat> {
+    [
+        HdrFormat::R11G11B10F,
+        HdrFormat::Rgba16F,
+        HdrFormat::Rgba32F,
+    ]
+    .into_iter()
+    .find(|format| context.supports_hdr_format(*format))
+}
+
+///
+/// Returns the cheapest [HdrFormat] with an alpha channel that can be rendered to with the given context, or `None` if rendering to floating point textures is not supported.
+///
+pub fn preferred_hdr_format_with_alpha(context: &Context) -> Option<HdrFormat> {
+    [HdrFormat::Rgba16F, HdrFormat::Rgba32F]
+        .into_iter()
+        .find(|format| context.supports_hdr_format(*format))
+}
 
 /// The basic data type used for each pixel in a depth texture.
 pub trait DepthTextureDataType: DepthDataType {}
@@ -167,7 +320,7 @@ impl ColorTexture<'_> {
                     .enumerate()
                     .for_each(|(i, l)| la[i] = *l as i32);
                 program.use_uniform_array("colorLayers", &la);
-                program.use_texture_array("colorMap", texture);
+                program.use_texture_array("colorMap", *texture);
             }
             Self::CubeMap { .. } => todo!(),
         }
@@ -432,6 +585,33 @@ fn check_data_length<T: TextureDataType>(
     }
 }
 
+///
+/// Multiplies the color channels with the alpha channel if the data has an alpha channel, otherwise the data is unchanged.
+///
+fn premultiply_alpha(data: &mut TextureData) {
+    match data {
+        TextureData::RgbaU8(data) => data.iter_mut().for_each(|texel| {
+            let alpha = texel[3] as f32 / 255.0;
+            for channel in texel.iter_mut().take(3) {
+                *channel = (*channel as f32 * alpha).round() as u8;
+            }
+        }),
+        TextureData::RgbaF16(data) => data.iter_mut().for_each(|texel| {
+            let alpha = texel[3].to_f32();
+            for channel in texel.iter_mut().take(3) {
+                *channel = f16::from_f32(channel.to_f32() * alpha);
+            }
+        }),
+        TextureData::RgbaF32(data) => data.iter_mut().for_each(|texel| {
+            let alpha = texel[3];
+            for channel in texel.iter_mut().take(3) {
+                *channel *= alpha;
+            }
+        }),
+        _ => {}
+    }
+}
+
 fn ru8_data(t: &CpuTexture) -> &[u8] {
     if let TextureData::RU8(data) = &t.data {
         data
@@ -0,0 +1,428 @@
+use crate::core::texture::*;
+use crate::core::CoreError;
+
+const KTX2_IDENTIFIER: [u8; 12] = [
+    0xAB, 0x4B, 0x54, 0x58, 0x20, 0x32, 0x30, 0xBB, 0x0D, 0x0A, 0x1A, 0x0A,
+];
+
+///
+/// The format of the pixels stored in a [Ktx2Texture].
+/// All formats have four channels, which is the format returned when reading back a texture, see for example [Texture2D::read_rgba_f32].
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Ktx2Format {
+    /// RGBA with 8 bit unsigned normalized values, for example an ambient occlusion map. Values outside the range `[0, 1]` are clamped.
+    Rgba8Unorm,
+    /// RGBA with 8 bit unsigned normalized values, where the color channels are marked as sRGB encoded, for example a texture atlas of albedo colors.
+    /// The values are stored as they are, so the texture should already contain sRGB encoded colors.
+    Rgba8Srgb,
+    /// RGBA with 16 bit floats, for example a lightmap or a prefiltered environment map with high dynamic range.
+    Rgba16Float,
+    /// RGBA with 32 bit floats.
+    Rgba32Float,
+}
+
+impl Ktx2Format {
+    fn vk_format(self) -> u32 {
+        match self {
+            Self::Rgba8Unorm => 37,
+            Self::Rgba8Srgb => 43,
+            Self::Rgba16Float => 97,
+            Self::Rgba32Float => 109,
+        }
+    }
+
+    fn from_vk_format(vk_format: u32) -> Option<Self> {
+        match vk_format {
+            37 => Some(Self::Rgba8Unorm),
+            43 => Some(Self::Rgba8Srgb),
+            97 => Some(Self::Rgba16Float),
+            109 => Some(Self::Rgba32Float),
+            _ => None,
+        }
+    }
+
+    /// The size in bytes of each channel.
+    fn type_size(self) -> usize {
+        match self {
+            Self::Rgba8Unorm | Self::Rgba8Srgb => 1,
+            Self::Rgba16Float => 2,
+            Self::Rgba32Float => 4,
+        }
+    }
+
+    fn encode(self, pixel: [f32; 4], bytes: &mut Vec<u8>) {
+        for value in pixel {
+            match self {
+                Self::Rgba8Unorm | Self::Rgba8Srgb => {
+                    bytes.push((value.clamp(0.0, 1.0) * 255.0).round() as u8)
+                }
+                Self::Rgba16Float => bytes.extend(f16::from_f32(value).to_le_bytes()),
+                Self::Rgba32Float => bytes.extend(value.to_le_bytes()),
+            }
+        }
+    }
+
+    fn decode(self, bytes: &[u8]) -> [f32; 4] {
+        let size = self.type_size();
+        std::array::from_fn(|i| {
+            let b = &bytes[i * size..(i + 1) * size];
+            match self {
+                Self::Rgba8Unorm | Self::Rgba8Srgb => b[0] as f32 / 255.0,
+                Self::Rgba16Float => f16::from_le_bytes([b[0], b[1]]).to_f32(),
+                Self::Rgba32Float => f32::from_le_bytes([b[0], b[1], b[2], b[3]]),
+            }
+        })
+    }
+
+    ///
+    /// Returns the data format descriptor, which is a basic descriptor block with one sample per channel.
+    ///
+    fn data_format_descriptor(self) -> Vec<u8> {
+        let bits = 8 * self.type_size() as u32;
+        let block_size = 24 + 16 * 4u16;
+        let mut dfd = Vec::new();
+        dfd.extend((4 + block_size as u32).to_le_bytes());
+        // Vendor id and descriptor type are both zero for the basic block
+        dfd.extend(0u32.to_le_bytes());
+        dfd.extend(2u16.to_le_bytes());
+        dfd.extend(block_size.to_le_bytes());
+        // Color model RGBSDA, BT709 primaries, transfer function and straight alpha
+        let srgb = self == Self::Rgba8Srgb;
+        dfd.extend([1, 1, if srgb { 2 } else { 1 }, 0]);
+        // One texel per block
+        dfd.extend([0, 0, 0, 0]);
+        dfd.extend([(4 * self.type_size()) as u8, 0, 0, 0, 0, 0, 0, 0]);
+        for (i, channel) in [0u8, 1, 2, 15].into_iter().enumerate() {
+            let mut channel_type = channel;
+            if self == Self::Rgba16Float || self == Self::Rgba32Float {
+                // Float and signed
+                channel_type |= 0xC0;
+            } else if srgb && channel == 15 {
+                // The alpha channel is linear even if the color channels are sRGB encoded
+                channel_type |= 0x10;
+            }
+            dfd.extend((i as u16 * bits as u16).to_le_bytes());
+            dfd.extend([(bits - 1) as u8, channel_type]);
+            dfd.extend([0, 0, 0, 0]);
+            let (lower, upper) = if bits == 8 {
+                (0, 255)
+            } else {
+                ((-1.0f32).to_bits(), 1.0f32.to_bits())
+            };
+            dfd.extend(lower.to_le_bytes());
+            dfd.extend(upper.to_le_bytes());
+        }
+        dfd
+    }
+}
+
+///
+/// A texture in the [KTX2](https://registry.khronos.org/KTX/specs/2.0/ktxspec.v2.html) container format, which can store all mip levels of a 2D texture, a cube map or a 2D texture array
+/// in a format that can be transferred directly to the GPU.
+/// Use this to save textures that are computed on the GPU, for example a baked lightmap, an ambient occlusion map, a prefiltered environment map or a texture atlas,
+/// by reading them back with [Ktx2Texture::from_texture_2d], [Ktx2Texture::from_texture_cube_map] or [Ktx2Texture::from_texture_2d_array] and then writing the bytes returned from [Ktx2Texture::to_bytes] to a file.
+///
+/// The data is not supercompressed, since no Basis Universal encoder is available, and [Ktx2Texture::from_bytes] only reads files with the formats in [Ktx2Format] and no supercompression.
+///
+/// ```
+/// # use three_d::*;
+/// let texture = Ktx2Texture {
+///     format: Ktx2Format::Rgba16Float,
+///     width: 4,
+///     height: 2,
+///     layer_count: 0,
+///     face_count: 6,
+///     levels: vec![
+///         (0..6 * 8).map(|i| [i as f32 * 0.25, 1.5, -2.0, 1.0]).collect(),
+///         (0..6 * 2).map(|i| [i as f32, 0.0, 0.5, 1.0]).collect(),
+///         (0..6).map(|i| [0.1 * i as f32, 0.0, 0.0, 1.0]).collect(),
+///     ],
+/// };
+/// let loaded = Ktx2Texture::from_bytes(&texture.to_bytes()).unwrap();
+/// assert_eq!(loaded.format, texture.format);
+/// assert_eq!((loaded.width, loaded.height, loaded.face_count), (4, 2, 6));
+/// for (a, b) in texture.levels.iter().flatten().zip(loaded.levels.iter().flatten()) {
+///     for i in 0..4 {
+///         assert!((a[i] - b[i]).abs() <= 0.001 * a[i].abs());
+///     }
+/// }
+/// assert_eq!(loaded.image(2, 0, 5).width, 1);
+/// ```
+///
+/// Export of a prefiltered environment map, which is loaded again and compared to the original:
+///
+/// ```no_run
+/// # use three_d::*;
+/// # let context: Context = unimplemented!();
+/// # let environment_map: TextureCubeMap = unimplemented!();
+/// let prefiltered = prefilter_specular::<f16>(&context, &environment_map, 32, 5, 64);
+/// let exported = Ktx2Texture::from_texture_cube_map(&prefiltered, Ktx2Format::Rgba16Float);
+/// std::fs::write("prefiltered.ktx2", exported.to_bytes()).unwrap();
+///
+/// let loaded = Ktx2Texture::from_bytes(&std::fs::read("prefiltered.ktx2").unwrap()).unwrap();
+/// for (level, pixels) in loaded.levels.iter().enumerate() {
+///     let original = CubeMapSide::iter()
+///         .flat_map(|side| prefiltered.read_rgba_f32(side, level as u32))
+///         .collect::<Vec<_>>();
+///     for (a, b) in original.iter().zip(pixels) {
+///         assert!((0..4).all(|i| (a[i] - b[i]).abs() <= 0.001 * a[i].abs()));
+///     }
+/// }
+/// let faces = (0..6).map(|face| loaded.image(0, 0, face)).collect::<Vec<_>>();
+/// let reloaded = TextureCubeMap::new(&context, &faces[0], &faces[1], &faces[2], &faces[3], &faces[4], &faces[5]);
+/// ```
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct Ktx2Texture {
+    /// The format the pixels are stored in.
+    pub format: Ktx2Format,
+    /// The width of the first mip level.
+    pub width: u32,
+    /// The height of the first mip level.
+    pub height: u32,
+    /// The number of layers of an array texture, or zero if it is not an array texture.
+    pub layer_count: u32,
+    /// Six for a cube map and otherwise one.
+    pub face_count: u32,
+    /// The pixels of each mip level starting with the largest.
+    /// Each level contains the pixels of each layer, which each contains the pixels of each face, in the order of [CubeMapSide::iter], with the rows from top to bottom.
+    pub levels: Vec<Vec<[f32; 4]>>,
+}
+
+impl Ktx2Texture {
+    ///
+    /// Reads back all mip levels of the given texture.
+    ///
+    pub fn from_texture_2d(texture: &Texture2D, format: Ktx2Format) -> Self {
+        Self {
+            format,
+            width: texture.width(),
+            height: texture.height(),
+            layer_count: 0,
+            face_count: 1,
+            levels: (0..texture.number_of_mip_maps())
+                .map(|mip_level| texture.read_rgba_f32_mip_level(mip_level))
+                .collect(),
+        }
+    }
+
+    ///
+    /// Reads back all mip levels of all sides of the given cube map.
+    ///
+    pub fn from_texture_cube_map(texture: &TextureCubeMap, format: Ktx2Format) -> Self {
+        Self {
+            format,
+            width: texture.width(),
+            height: texture.height(),
+            layer_count: 0,
+            face_count: 6,
+            levels: (0..texture.number_of_mip_maps())
+                .map(|mip_level| {
+                    CubeMapSide::iter()
+                        .flat_map(|side| texture.read_rgba_f32(side, mip_level))
+                        .collect()
+                })
+                .collect(),
+        }
+    }
+
+    ///
+    /// Reads back all mip levels of all layers of the given texture array.
+    ///
+    pub fn from_texture_2d_array(texture: &Texture2DArray, format: Ktx2Format) -> Self {
+        Self {
+            format,
+            width: texture.width(),
+            height: texture.height(),
+            layer_count: texture.depth(),
+            face_count: 1,
+            levels: (0..texture.number_of_mip_maps())
+                .map(|mip_level| {
+                    (0..texture.depth())
+                        .flat_map(|layer| texture.read_rgba_f32_mip_level(layer, mip_level))
+                        .collect()
+                })
+                .collect(),
+        }
+    }
+
+    ///
+    /// Returns the width and height of the given mip level.
+    ///
+    pub fn level_size(&self, mip_level: u32) -> (u32, u32) {
+        (
+            (self.width >> mip_level).max(1),
+            (self.height >> mip_level).max(1),
+        )
+    }
+
+    ///
+    /// Returns the given mip level of the given layer and face as a [CpuTexture], which for example can be used to create a new texture.
+    /// The data is RGBA u8 for the 8 bit formats and otherwise RGBA f32.
+    ///
+    pub fn image(&self, mip_level: u32, layer: u32, face: u32) -> CpuTexture {
+        let (width, height) = self.level_size(mip_level);
+        let size = (width * height) as usize;
+        let start = (layer * self.face_count + face) as usize * size;
+        let pixels = &self.levels[mip_level as usize][start..start + size];
+        CpuTexture {
+            data: match self.format {
+                Ktx2Format::Rgba8Unorm | Ktx2Format::Rgba8Srgb => TextureData::RgbaU8(
+                    pixels
+                        .iter()
+                        .map(|p| p.map(|v| (v.clamp(0.0, 1.0) * 255.0).round() as u8))
+                        .collect(),
+                ),
+                _ => TextureData::RgbaF32(pixels.to_vec()),
+            },
+            width,
+            height,
+            ..Default::default()
+        }
+    }
+
+    ///
+    /// Returns the KTX2 file contents, including the data format descriptor for the format.
+    ///
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let level_count = self.levels.len();
+        let dfd = self.format.data_format_descriptor();
+        let kvd = key_value_data(&[
+            ("KTXorientation", "rd"),
+            ("KTXwriter", concat!("three-d ", env!("CARGO_PKG_VERSION"))),
+        ]);
+        let dfd_offset = 80 + 24 * level_count;
+        let kvd_offset = dfd_offset + dfd.len();
+
+        // The levels are stored from the smallest to the largest, each aligned to the size of a pixel
+        let pixel_size = 4 * self.format.type_size();
+        let alignment = pixel_size.max(4);
+        let mut data = Vec::new();
+        let mut level_index = vec![(0, 0); level_count];
+        for (mip_level, pixels) in self.levels.iter().enumerate().rev() {
+            let end = kvd_offset + kvd.len() + data.len();
+            data.resize(data.len() + (alignment - end % alignment) % alignment, 0);
+            let offset = kvd_offset + kvd.len() + data.len();
+            for pixel in pixels {
+                self.format.encode(*pixel, &mut data);
+            }
+            level_index[mip_level] = (offset, pixels.len() * pixel_size);
+        }
+
+        let mut bytes = Vec::with_capacity(kvd_offset + kvd.len() + data.len());
+        bytes.extend(KTX2_IDENTIFIER);
+        for value in [
+            self.format.vk_format(),
+            self.format.type_size() as u32,
+            self.width,
+            self.height,
+            0,
+            self.layer_count,
+            self.face_count,
+            level_count as u32,
+            0,
+            dfd_offset as u32,
+            dfd.len() as u32,
+            kvd_offset as u32,
+            kvd.len() as u32,
+        ] {
+            bytes.extend(value.to_le_bytes());
+        }
+        // No supercompression global data
+        bytes.extend([0u8; 16]);
+        for (offset, length) in level_index {
+            bytes.extend((offset as u64).to_le_bytes());
+            bytes.extend((length as u64).to_le_bytes());
+            bytes.extend((length as u64).to_le_bytes());
+        }
+        bytes.extend(dfd);
+        bytes.extend(kvd);
+        bytes.extend(data);
+        bytes
+    }
+
+    ///
+    /// Parses the given KTX2 file contents.
+    /// Returns an error if the file is not a 2D texture, cube map or 2D texture array with one of the formats in [Ktx2Format] and no supercompression.
+    ///
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, CoreError> {
+        let error = |message: &str| CoreError::Ktx2(message.to_string());
+        if bytes.get(..12) != Some(&KTX2_IDENTIFIER) {
+            return Err(error("missing KTX2 identifier"));
+        }
+        let read_u32 = |offset: usize| {
+            bytes
+                .get(offset..offset + 4)
+                .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+                .ok_or_else(|| error("unexpected end of data"))
+        };
+        let read_u64 = |offset: usize| -> Result<usize, CoreError> {
+            Ok(read_u32(offset)? as usize | ((read_u32(offset + 4)? as usize) << 32))
+        };
+
+        let format =
+            Ktx2Format::from_vk_format(read_u32(12)?).ok_or_else(|| error("unsupported format"))?;
+        let width = read_u32(20)?;
+        let height = read_u32(24)?;
+        let layer_count = read_u32(32)?;
+        let face_count = read_u32(36)?;
+        let level_count = read_u32(40)?.max(1);
+        if read_u32(28)? != 0 || height == 0 {
+            return Err(error("only 2D textures are supported"));
+        }
+        if face_count != 1 && face_count != 6 {
+            return Err(error("invalid face count"));
+        }
+        if read_u32(44)? != 0 {
+            return Err(error("supercompression is not supported"));
+        }
+
+        let pixel_size = 4 * format.type_size();
+        let mut texture = Self {
+            format,
+            width,
+            height,
+            layer_count,
+            face_count,
+            levels: Vec::new(),
+        };
+        for mip_level in 0..level_count {
+            let offset = read_u64(80 + 24 * mip_level as usize)?;
+            let length = read_u64(88 + 24 * mip_level as usize)?;
+            let (w, h) = texture.level_size(mip_level);
+            let expected = (w * h * layer_count.max(1) * face_count) as usize * pixel_size;
+            if length != expected {
+                return Err(error("invalid level size"));
+            }
+            let data = bytes
+                .get(offset..offset + length)
+                .ok_or_else(|| error("unexpected end of data"))?;
+            texture.levels.push(
+                data.chunks_exact(pixel_size)
+                    .map(|pixel| format.decode(pixel))
+                    .collect(),
+            );
+        }
+        Ok(texture)
+    }
+}
+
+///
+/// Returns the key/value data with the given pairs, which must be sorted by key.
+///
+fn key_value_data(pairs: &[(&str, &str)]) -> Vec<u8> {
+    let mut kvd = Vec::new();
+    for (key, value) in pairs {
+        let length = key.len() + value.len() + 2;
+        kvd.extend((length as u32).to_le_bytes());
+        kvd.extend(key.as_bytes());
+        kvd.push(0);
+        kvd.extend(value.as_bytes());
+        kvd.push(0);
+        while kvd.len() % 4 != 0 {
+            kvd.push(0);
+        }
+    }
+    kvd
+}
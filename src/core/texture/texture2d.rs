@@ -10,6 +10,7 @@ pub struct Texture2D {
     height: u32,
     number_of_mip_maps: u32,
     data_byte_size: usize,
+    data_type: u32,
 }
 
 impl Texture2D {
@@ -35,6 +36,19 @@ impl Texture2D {
         }
     }
 
+    ///
+    /// Constructs a new texture with the given data where the color channels are multiplied with the alpha channel (premultiplied alpha)
+    /// before the data is transferred to the GPU, and therefore also before the mip maps are generated.
+    /// This avoids dark fringes at semi-transparent edges when the texture is filtered, but the texture must then be rendered using [Blend::PREMULTIPLIED].
+    /// The multiplication is only correct for colors in linear color space, so sRGB colors should be converted first, for example using `to_linear_srgb`.
+    /// Data without an alpha channel is unchanged.
+    ///
+    pub fn new_premultiplied(context: &Context, cpu_texture: &CpuTexture) -> Self {
+        let mut cpu_texture = cpu_texture.clone();
+        premultiply_alpha(&mut cpu_texture.data);
+        Self::new(context, &cpu_texture)
+    }
+
     fn new_with_data<T: TextureDataType>(
         context: &Context,
         cpu_texture: &CpuTexture,
@@ -102,6 +116,13 @@ impl Texture2D {
     /// It is therefore necessary to create a new texture if the texture size or format has changed.
     ///
     pub fn fill<T: TextureDataType>(&mut self, data: &[T]) {
+        trace_span!(
+            "three_d::upload_texture",
+            width = self.width,
+            height = self.height,
+            depth = 1,
+            bytes = std::mem::size_of_val(data)
+        );
         check_data_length::<T>(self.width, self.height, 1, self.data_byte_size, data.len());
         self.bind();
         let mut data = data.to_owned();
@@ -134,6 +155,67 @@ impl Texture2D {
         ColorTarget::new_texture2d(&self.context, self, mip_level)
     }
 
+    ///
+    /// Returns the pixels of this texture in the same layout as the data given to [Texture2D::fill].
+    /// The number of channels per pixel and the data format for each channel returned from this function is specified by the generic parameter `T`.
+    ///
+    /// **Note:**
+    /// The base type of the generic parameter `T` must match the base type of the texture, for example if the textures base type is `u8`, the base type of `T` must also be `u8`.
+    ///
+    /// **Web:**
+    /// The generic parameter `T` is limited to:
+    /// - Unsigned byte RGBA (Specify `T` as either `Vec4<u8>` or `[u8; 4]`) which works with any texture using `u8` as its base type.
+    /// - 32-bit float RGBA (Specify `T` as either `Vec4<f32>` or `[f32; 4]`) which works with any texture using a floating point base type.
+    ///
+    pub fn read<T: TextureDataType>(&self) -> Vec<T> {
+        self.read_mip_level(0)
+    }
+
+    ///
+    /// Returns the pixels of the given mip level of this texture, see [Texture2D::read].
+    /// The size of the mip level is half the size of the previous level rounded down, but at least one.
+    ///
+    pub fn read_mip_level<T: TextureDataType>(&self, mip_level: u32) -> Vec<T> {
+        ColorTarget::new_texture2d(&self.context, self, Some(mip_level)).read()
+    }
+
+    ///
+    /// Returns the pixels of this texture as RGBA floats, independent of the base type of the texture.
+    /// Textures with `u8` as base type are converted to the range `[0, 1]`.
+    /// On desktop, [f16] and [R11G11B10F] textures are read in their own format and converted on the CPU, which transfers less data than reading 32-bit floats.
+    ///
+    pub fn read_rgba_f32(&self) -> Vec<[f32; 4]> {
+        self.read_rgba_f32_mip_level(0)
+    }
+
+    ///
+    /// Returns the pixels of the given mip level of this texture as RGBA floats, see [Texture2D::read_rgba_f32].
+    ///
+    pub fn read_rgba_f32_mip_level(&self, mip_level: u32) -> Vec<[f32; 4]> {
+        let native = !cfg!(target_arch = "wasm32") && !self.context.version().is_embedded;
+        match self.data_type {
+            crate::context::UNSIGNED_BYTE => self
+                .read_mip_level::<[u8; 4]>(mip_level)
+                .into_iter()
+                .map(|c| c.map(|v| v as f32 / 255.0))
+                .collect(),
+            crate::context::HALF_FLOAT if native => self
+                .read_mip_level::<[f16; 4]>(mip_level)
+                .into_iter()
+                .map(|c| c.map(|v| v.to_f32()))
+                .collect(),
+            crate::context::UNSIGNED_INT_10F_11F_11F_REV if native => self
+                .read_mip_level::<R11G11B10F>(mip_level)
+                .into_iter()
+                .map(|c| {
+                    let [r, g, b] = c.to_f32();
+                    [r, g, b, 1.0]
+                })
+                .collect(),
+            _ => self.read_mip_level::<[f32; 4]>(mip_level),
+        }
+    }
+
     /// The width of this texture.
     pub fn width(&self) -> u32 {
         self.width
@@ -144,11 +226,19 @@ impl Texture2D {
         self.height
     }
 
+    pub(crate) fn context(&self) -> &Context {
+        &self.context
+    }
+
     /// The number of mip maps of this texture.
     pub fn number_of_mip_maps(&self) -> u32 {
         self.number_of_mip_maps
     }
 
+    pub(in crate::core) fn is_float(&self) -> bool {
+        self.data_type == crate::context::FLOAT || self.data_type == crate::context::HALF_FLOAT
+    }
+
     pub(crate) fn generate_mip_maps(&self) {
         if self.number_of_mip_maps > 1 {
             self.bind();
@@ -206,6 +296,7 @@ impl Texture2D {
             height,
             number_of_mip_maps,
             data_byte_size: std::mem::size_of::<T>(),
+            data_type: T::data_type(),
         };
         texture.bind();
         set_parameters(
@@ -14,6 +14,7 @@ pub struct Texture2DArray {
     depth: u32,
     number_of_mip_maps: u32,
     data_byte_size: usize,
+    data_type: u32,
 }
 
 impl Texture2DArray {
@@ -206,6 +207,13 @@ impl Texture2DArray {
     /// It is therefore necessary to create a new texture if the texture size or format has changed.
     ///
     pub fn fill_layer<T: TextureDataType>(&mut self, layer: u32, data: &[T]) {
+        trace_span!(
+            "three_d::upload_texture",
+            width = self.width,
+            height = self.height,
+            depth = 1,
+            bytes = std::mem::size_of_val(data)
+        );
         if layer >= self.depth {
             panic!(
                 "cannot fill the layer {} with data, since there are only {} layers in the texture array",
@@ -250,6 +258,53 @@ impl Texture2DArray {
         ColorTarget::new_texture_2d_array(&self.context, self, layers, mip_level)
     }
 
+    ///
+    /// Returns the pixels of the given layer of this texture in the same layout as the data given to [Texture2DArray::fill_layer].
+    /// The number of channels per pixel and the data format for each channel returned from this function is specified by the generic parameter `T`.
+    ///
+    /// **Note:**
+    /// The base type of the generic parameter `T` must match the base type of the texture, for example if the textures base type is `u8`, the base type of `T` must also be `u8`.
+    ///
+    /// **Web:**
+    /// The generic parameter `T` is limited to:
+    /// - Unsigned byte RGBA (Specify `T` as either `Vec4<u8>` or `[u8; 4]`) which works with any texture using `u8` as its base type.
+    /// - 32-bit float RGBA (Specify `T` as either `Vec4<f32>` or `[f32; 4]`) which works with any texture using `f16` or `f32` as its base type.
+    ///
+    pub fn read<T: TextureDataType>(&self, layer: u32) -> Vec<T> {
+        self.read_mip_level(layer, 0)
+    }
+
+    ///
+    /// Returns the pixels of the given layer and mip level of this texture, see [Texture2DArray::read].
+    /// The size of the mip level is half the size of the previous level rounded down, but at least one.
+    ///
+    pub fn read_mip_level<T: TextureDataType>(&self, layer: u32, mip_level: u32) -> Vec<T> {
+        let layers = [layer];
+        ColorTarget::new_texture_2d_array(&self.context, self, &layers, Some(mip_level)).read()
+    }
+
+    ///
+    /// Returns the pixels of the given layer of this texture as RGBA floats, independent of the base type of the texture.
+    /// Textures with `u8` as base type are converted to the range `[0, 1]`.
+    ///
+    pub fn read_rgba_f32(&self, layer: u32) -> Vec<[f32; 4]> {
+        self.read_rgba_f32_mip_level(layer, 0)
+    }
+
+    ///
+    /// Returns the pixels of the given layer and mip level of this texture as RGBA floats, see [Texture2DArray::read_rgba_f32].
+    ///
+    pub fn read_rgba_f32_mip_level(&self, layer: u32, mip_level: u32) -> Vec<[f32; 4]> {
+        if self.data_type == crate::context::UNSIGNED_BYTE {
+            self.read_mip_level::<[u8; 4]>(layer, mip_level)
+                .into_iter()
+                .map(|c| c.map(|v| v as f32 / 255.0))
+                .collect()
+        } else {
+            self.read_mip_level::<[f32; 4]>(layer, mip_level)
+        }
+    }
+
     /// The width of this texture.
     pub fn width(&self) -> u32 {
         self.width
@@ -270,6 +325,10 @@ impl Texture2DArray {
         self.number_of_mip_maps
     }
 
+    pub(in crate::core) fn is_float(&self) -> bool {
+        self.data_type == crate::context::FLOAT || self.data_type == crate::context::HALF_FLOAT
+    }
+
     pub(in crate::core) fn generate_mip_maps(&self) {
         if self.number_of_mip_maps > 1 {
             self.bind();
@@ -331,6 +390,7 @@ impl Texture2DArray {
             depth,
             number_of_mip_maps,
             data_byte_size: std::mem::size_of::<T>(),
+            data_type: T::data_type(),
         };
         texture.bind();
         set_parameters(
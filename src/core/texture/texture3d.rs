@@ -107,6 +107,13 @@ impl Texture3D {
     /// It is therefore necessary to create a new texture if the texture size or format has changed.
     ///
     pub fn fill<T: TextureDataType>(&mut self, data: &[T]) {
+        trace_span!(
+            "three_d::upload_texture",
+            width = self.width,
+            height = self.height,
+            depth = self.depth,
+            bytes = std::mem::size_of_val(data)
+        );
         check_data_length::<T>(
             self.width,
             self.height,
@@ -111,6 +111,7 @@ pub struct TextureCubeMap {
     height: u32,
     number_of_mip_maps: u32,
     data_byte_size: usize,
+    data_type: u32,
 }
 
 impl TextureCubeMap {
@@ -354,6 +355,13 @@ impl TextureCubeMap {
         front_data: &[T],
         back_data: &[T],
     ) {
+        trace_span!(
+            "three_d::upload_texture",
+            width = self.width,
+            height = self.height,
+            depth = 6,
+            bytes = 6 * std::mem::size_of_val(right_data)
+        );
         check_data_length::<T>(
             self.width,
             self.height,
@@ -431,7 +439,24 @@ impl TextureCubeMap {
         context: &Context,
         cpu_texture: &CpuTexture,
     ) -> Self {
-        let texture_size = cpu_texture.width / 4;
+        Self::new_from_equirectangular_texture::<T>(
+            context,
+            &Texture2D::new(context, cpu_texture),
+            cpu_texture.width / 4,
+        )
+    }
+
+    ///
+    /// Creates a new cube texture with the given size of each side generated from the equirectangular texture given as input.
+    /// The data type of the cube texture is four channels of the generic parameter `T` independent of the data type of the equirectangular texture,
+    /// so for example `u8` can be used to convert a byte texture and `f16` or `f32` to keep the high dynamic range of a float texture.
+    ///
+    pub fn new_from_equirectangular_texture<T: PrimitiveDataType + TextureDataType>(
+        context: &Context,
+        equirectangular_texture: &Texture2D,
+        size: u32,
+    ) -> Self {
+        let texture_size = size.max(1);
         let mut texture = Self::new_empty::<[T; 4]>(
             context,
             texture_size,
@@ -445,7 +470,6 @@ impl TextureCubeMap {
         );
 
         {
-            let map = Texture2D::new(context, cpu_texture);
             let fragment_shader_source = "
             uniform sampler2D equirectangularMap;
             uniform vec3 direction;
@@ -476,7 +500,7 @@ impl TextureCubeMap {
                     .as_color_target(&[side], None)
                     .clear(ClearState::default())
                     .write::<CoreError>(|| {
-                        program.use_texture("equirectangularMap", &map);
+                        program.use_texture("equirectangularMap", equirectangular_texture);
                         program.use_uniform("direction", side.direction());
                         program.use_uniform("up", side.up());
                         full_screen_draw(context, &program, RenderStates::default(), viewport);
@@ -504,6 +528,83 @@ impl TextureCubeMap {
         ColorTarget::new_texture_cube_map(&self.context, self, sides, mip_level)
     }
 
+    ///
+    /// Returns the pixels of the given side and mip level of this texture in the same layout as the data given to [TextureCubeMap::fill].
+    /// The number of channels per pixel and the data format for each channel returned from this function is specified by the generic parameter `T`.
+    ///
+    /// **Note:**
+    /// The base type of the generic parameter `T` must match the base type of the texture, for example if the textures base type is `u8`, the base type of `T` must also be `u8`.
+    ///
+    /// **Web:**
+    /// The generic parameter `T` is limited to:
+    /// - Unsigned byte RGBA (Specify `T` as either `Vec4<u8>` or `[u8; 4]`) which works with any texture using `u8` as its base type.
+    /// - 32-bit float RGBA (Specify `T` as either `Vec4<f32>` or `[f32; 4]`) which works with any texture using `f16` or `f32` as its base type.
+    ///
+    pub fn read<T: TextureDataType>(&self, side: CubeMapSide, mip_level: u32) -> Vec<T> {
+        let sides = [side];
+        let target =
+            ColorTarget::new_texture_cube_map(&self.context, self, &sides, Some(mip_level));
+        let mut data = target.read::<T>();
+        // The cube map sides are stored with the first row at the top, whereas reading from a render target returns the first row at the bottom
+        flip_y(&mut data, target.width() as usize, target.height() as usize);
+        data
+    }
+
+    ///
+    /// Returns the pixels of the given side and mip level of this texture as RGBA floats, independent of the base type of the texture.
+    /// Textures with `u8` as base type are converted to the range `[0, 1]`.
+    ///
+    pub fn read_rgba_f32(&self, side: CubeMapSide, mip_level: u32) -> Vec<[f32; 4]> {
+        if self.data_type == crate::context::UNSIGNED_BYTE {
+            self.read::<[u8; 4]>(side, mip_level)
+                .into_iter()
+                .map(|c| c.map(|v| v as f32 / 255.0))
+                .collect()
+        } else {
+            self.read::<[f32; 4]>(side, mip_level)
+        }
+    }
+
+    ///
+    /// Returns the given mip level of this texture as a [CpuTexture] with the 6 sides laid out in a horizontal cross:
+    ///
+    /// ```text
+    ///         | top    |
+    /// | left  | front  | right | back |
+    ///         | bottom |
+    /// ```
+    ///
+    /// The returned texture has RGBA u8 data, so it can be saved directly as for example a PNG image using [three_d_asset::io::Serialize].
+    /// Float values are clamped to the range `[0, 1]` and the unused parts of the cross are fully transparent.
+    ///
+    pub fn to_cross_layout(&self, mip_level: u32) -> CpuTexture {
+        let size = (self.width / 2u32.pow(mip_level)).max(1) as usize;
+        let width = 4 * size;
+        let mut data = vec![[0u8; 4]; width * 3 * size];
+        for (side, column, row) in [
+            (CubeMapSide::Top, 1, 0),
+            (CubeMapSide::Left, 0, 1),
+            (CubeMapSide::Front, 1, 1),
+            (CubeMapSide::Right, 2, 1),
+            (CubeMapSide::Back, 3, 1),
+            (CubeMapSide::Bottom, 1, 2),
+        ] {
+            let pixels = self.read_rgba_f32(side, mip_level);
+            for y in 0..size {
+                for x in 0..size {
+                    data[(row * size + y) * width + column * size + x] =
+                        pixels[y * size + x].map(|v| (v.clamp(0.0, 1.0) * 255.0).round() as u8);
+                }
+            }
+        }
+        CpuTexture {
+            data: TextureData::RgbaU8(data),
+            width: width as u32,
+            height: 3 * size as u32,
+            ..Default::default()
+        }
+    }
+
     /// The width of this texture.
     pub fn width(&self) -> u32 {
         self.width
@@ -519,6 +620,10 @@ impl TextureCubeMap {
         self.number_of_mip_maps
     }
 
+    pub(in crate::core) fn is_float(&self) -> bool {
+        self.data_type == crate::context::FLOAT || self.data_type == crate::context::HALF_FLOAT
+    }
+
     pub(in crate::core) fn generate_mip_maps(&self) {
         if self.number_of_mip_maps > 1 {
             self.bind();
@@ -584,6 +689,7 @@ impl TextureCubeMap {
             height,
             number_of_mip_maps,
             data_byte_size: std::mem::size_of::<T>(),
+            data_type: T::data_type(),
         };
         texture.bind();
         set_parameters(
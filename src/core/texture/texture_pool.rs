@@ -0,0 +1,268 @@
+use crate::core::*;
+use std::marker::PhantomData;
+use std::sync::{Arc, RwLock};
+
+///
+/// The memory used by the transient texture pool, see [Context::request_texture].
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TexturePoolMemory {
+    /// The number of textures currently requested and not yet returned to the pool.
+    pub in_use_count: usize,
+    /// The size in bytes of the textures currently requested and not yet returned to the pool.
+    pub in_use_bytes: usize,
+    /// The number of textures in the pool that are ready to be reused.
+    pub available_count: usize,
+    /// The size in bytes of the textures in the pool that are ready to be reused.
+    pub available_bytes: usize,
+}
+
+impl TexturePoolMemory {
+    ///
+    /// Returns the total size in bytes of all textures allocated by the pool.
+    ///
+    pub fn total_bytes(&self) -> usize {
+        self.in_use_bytes + self.available_bytes
+    }
+}
+
+///
+/// A texture requested from the transient texture pool of a [Context], for example using [Context::request_texture].
+/// The texture is returned to the pool when this is dropped, so that it can be reused, for example by another effect or in the next frame.
+/// The content of the texture is undefined when requested, so it should always be cleared or completely overwritten before it is read.
+///
+/// Use [Deref](std::ops::Deref) to access the texture, ie. a `PooledTexture` can be used as a [Texture2D], a `PooledTexture<DepthTexture2D>` as a [DepthTexture2D]
+/// and a `PooledTexture<Texture2DArray>` as a [Texture2DArray].
+///
+pub struct PooledTexture<T = Texture2D> {
+    entry: Option<PoolEntry>,
+    pool: Arc<RwLock<TexturePool>>,
+    _texture: PhantomData<T>,
+}
+
+impl<T> Drop for PooledTexture<T> {
+    fn drop(&mut self) {
+        if let Some(entry) = self.entry.take() {
+            self.pool.write().unwrap().release(entry);
+        }
+    }
+}
+
+macro_rules! impl_pooled_texture_deref {
+    ($texture:ident) => {
+        impl std::ops::Deref for PooledTexture<$texture> {
+            type Target = $texture;
+            fn deref(&self) -> &Self::Target {
+                match &self.entry.as_ref().unwrap().texture {
+                    PoolTexture::$texture(texture) => texture,
+                    _ => unreachable!(),
+                }
+            }
+        }
+
+        impl std::ops::DerefMut for PooledTexture<$texture> {
+            fn deref_mut(&mut self) -> &mut Self::Target {
+                match &mut self.entry.as_mut().unwrap().texture {
+                    PoolTexture::$texture(texture) => texture,
+                    _ => unreachable!(),
+                }
+            }
+        }
+    };
+}
+
+impl_pooled_texture_deref!(Texture2D);
+impl_pooled_texture_deref!(DepthTexture2D);
+impl_pooled_texture_deref!(Texture2DArray);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum TextureKind {
+    Texture2D(Interpolation),
+    DepthTexture2D,
+    Texture2DArray(u32, Interpolation),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct TextureKey {
+    pub kind: TextureKind,
+    pub width: u32,
+    pub height: u32,
+    pub format: u32,
+}
+
+enum PoolTexture {
+    Texture2D(Texture2D),
+    DepthTexture2D(DepthTexture2D),
+    Texture2DArray(Texture2DArray),
+}
+
+struct PoolEntry {
+    key: TextureKey,
+    texture: PoolTexture,
+    byte_size: usize,
+    used: bool,
+}
+
+///
+/// The transient textures owned by a [Context], see [Context::request_texture].
+///
+#[derive(Default)]
+pub(crate) struct TexturePool {
+    available: Vec<PoolEntry>,
+    in_use_count: usize,
+    in_use_bytes: usize,
+}
+
+impl TexturePool {
+    ///
+    /// Returns a texture with the given key, either one from the pool or a new one created using the given closure.
+    /// A texture is never handed out twice before it is returned, so two requests with the same key always get two different textures.
+    ///
+    fn request<T>(
+        pool: &Arc<RwLock<Self>>,
+        key: TextureKey,
+        byte_size: usize,
+        create: impl FnOnce() -> PoolTexture,
+    ) -> PooledTexture<T> {
+        let mut data = pool.write().unwrap();
+        let entry = match data.available.iter().position(|e| e.key == key) {
+            Some(index) => {
+                let mut entry = data.available.swap_remove(index);
+                entry.used = true;
+                entry
+            }
+            None => PoolEntry {
+                key,
+                texture: create(),
+                byte_size,
+                used: true,
+            },
+        };
+        data.in_use_count += 1;
+        data.in_use_bytes += entry.byte_size;
+        PooledTexture {
+            entry: Some(entry),
+            pool: pool.clone(),
+            _texture: PhantomData,
+        }
+    }
+
+    fn release(&mut self, entry: PoolEntry) {
+        self.in_use_count -= 1;
+        self.in_use_bytes -= entry.byte_size;
+        self.available.push(entry);
+    }
+
+    ///
+    /// Deletes the available textures that have not been requested since the last call to this method.
+    ///
+    pub fn trim(&mut self) {
+        self.available.retain(|entry| entry.used);
+        for entry in self.available.iter_mut() {
+            entry.used = false;
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.available.clear();
+    }
+
+    pub fn memory(&self) -> TexturePoolMemory {
+        TexturePoolMemory {
+            in_use_count: self.in_use_count,
+            in_use_bytes: self.in_use_bytes,
+            available_count: self.available.len(),
+            available_bytes: self.available.iter().map(|e| e.byte_size).sum(),
+        }
+    }
+}
+
+pub(crate) fn request_texture<T: TextureDataType>(
+    context: &Context,
+    pool: &Arc<RwLock<TexturePool>>,
+    width: u32,
+    height: u32,
+    interpolation: Interpolation,
+) -> PooledTexture {
+    let key = TextureKey {
+        kind: TextureKind::Texture2D(interpolation),
+        width,
+        height,
+        format: T::internal_format(),
+    };
+    TexturePool::request(pool, key, byte_size::<T>(width, height, 1), || {
+        PoolTexture::Texture2D(Texture2D::new_empty::<T>(
+            context,
+            width,
+            height,
+            interpolation,
+            interpolation,
+            None,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+        ))
+    })
+}
+
+pub(crate) fn request_texture_array<T: TextureDataType>(
+    context: &Context,
+    pool: &Arc<RwLock<TexturePool>>,
+    width: u32,
+    height: u32,
+    depth: u32,
+    interpolation: Interpolation,
+) -> PooledTexture<Texture2DArray> {
+    let key = TextureKey {
+        kind: TextureKind::Texture2DArray(depth, interpolation),
+        width,
+        height,
+        format: T::internal_format(),
+    };
+    TexturePool::request(pool, key, byte_size::<T>(width, height, depth), || {
+        PoolTexture::Texture2DArray(Texture2DArray::new_empty::<T>(
+            context,
+            width,
+            height,
+            depth,
+            interpolation,
+            interpolation,
+            None,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+        ))
+    })
+}
+
+pub(crate) fn request_depth_texture<T: DepthTextureDataType>(
+    context: &Context,
+    pool: &Arc<RwLock<TexturePool>>,
+    width: u32,
+    height: u32,
+) -> PooledTexture<DepthTexture2D> {
+    let format = T::internal_format();
+    let key = TextureKey {
+        kind: TextureKind::DepthTexture2D,
+        width,
+        height,
+        format,
+    };
+    let bytes_per_texel = if format == crate::context::DEPTH_COMPONENT16 {
+        2
+    } else {
+        4
+    };
+    let byte_size = width as usize * height as usize * bytes_per_texel;
+    TexturePool::request(pool, key, byte_size, || {
+        PoolTexture::DepthTexture2D(DepthTexture2D::new::<T>(
+            context,
+            width,
+            height,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+        ))
+    })
+}
+
+fn byte_size<T: TextureDataType>(width: u32, height: u32, depth: u32) -> usize {
+    width as usize * height as usize * depth as usize * std::mem::size_of::<T>()
+}
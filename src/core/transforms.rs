@@ -0,0 +1,284 @@
+//!
+//! Helper functions for creating and decomposing transformations, for example euler angles in any order, decomposition of a transformation into translation, rotation and scale
+//! and conversion between the Y-up and Z-up conventions.
+//!
+
+use crate::core::*;
+
+///
+/// The order in which the rotations around the X, Y and Z axes are applied when converting euler angles to a rotation, see [euler_to_quat].
+///
+/// The rotations are extrinsic, ie. around the fixed axes of the world, and applied from left to right,
+/// so [RotationOrder::XYZ] first rotates around the X axis, then around the Y axis and finally around the Z axis.
+/// This is the same as intrinsic rotations, ie. around the axes of the rotated object, in the reverse order.
+/// Note that the `Euler` type in cgmath corresponds to [RotationOrder::ZYX].
+///
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum RotationOrder {
+    /// First around X, then Y and then Z, ie. the rotation matrix is `Rz * Ry * Rx`.
+    XYZ,
+    /// First around X, then Z and then Y, ie. the rotation matrix is `Ry * Rz * Rx`.
+    XZY,
+    /// First around Y, then X and then Z, ie. the rotation matrix is `Rz * Rx * Ry`.
+    YXZ,
+    /// First around Y, then Z and then X, ie. the rotation matrix is `Rx * Rz * Ry`.
+    YZX,
+    /// First around Z, then X and then Y, ie. the rotation matrix is `Ry * Rx * Rz`.
+    ZXY,
+    /// First around Z, then Y and then X, ie. the rotation matrix is `Rx * Ry * Rz`.
+    ZYX,
+}
+
+impl RotationOrder {
+    ///
+    /// Returns the indices of the axes in the order they are applied, where 0 is X, 1 is Y and 2 is Z.
+    ///
+    fn axes(self) -> [usize; 3] {
+        match self {
+            Self::XYZ => [0, 1, 2],
+            Self::XZY => [0, 2, 1],
+            Self::YXZ => [1, 0, 2],
+            Self::YZX => [1, 2, 0],
+            Self::ZXY => [2, 0, 1],
+            Self::ZYX => [2, 1, 0],
+        }
+    }
+
+    ///
+    /// Returns 1 if the order of the axes is a cyclic permutation of XYZ and -1 otherwise.
+    ///
+    fn parity(self) -> f32 {
+        match self {
+            Self::XYZ | Self::YZX | Self::ZXY => 1.0,
+            Self::XZY | Self::YXZ | Self::ZYX => -1.0,
+        }
+    }
+}
+
+///
+/// Converts the euler angles around the X, Y and Z axes to a rotation, where the rotations are applied in the given order, see [RotationOrder].
+///
+/// ```
+/// # use three_d::*;
+/// let rotation = euler_to_quat(RotationOrder::XYZ, degrees(90.0), degrees(90.0), degrees(0.0));
+/// // The X axis is first rotated to itself and then to -Z by the rotation around Y
+/// assert!((rotation.rotate_vector(vec3(1.0, 0.0, 0.0)) - vec3(0.0, 0.0, -1.0)).magnitude() < 1.0e-6);
+///
+/// // The Euler type in cgmath applies the rotation around Z first
+/// let (x, y, z) = (degrees(10.0), degrees(20.0), degrees(30.0));
+/// let rotation = euler_to_quat(RotationOrder::ZYX, x, y, z);
+/// let expected = Quat::from_angle_x(x) * Quat::from_angle_y(y) * Quat::from_angle_z(z);
+/// assert!((rotation - expected).magnitude() < 1.0e-6);
+/// ```
+///
+pub fn euler_to_quat(
+    order: RotationOrder,
+    x: impl Into<Radians>,
+    y: impl Into<Radians>,
+    z: impl Into<Radians>,
+) -> Quat {
+    let rotations = [
+        Quat::from_angle_x(x.into()),
+        Quat::from_angle_y(y.into()),
+        Quat::from_angle_z(z.into()),
+    ];
+    let [first, second, third] = order.axes();
+    rotations[third] * rotations[second] * rotations[first]
+}
+
+///
+/// Converts the rotation to euler angles around the X, Y and Z axes, which gives the same rotation when applied in the given order, see [euler_to_quat].
+/// The angle around the second axis is in the range `[-90, 90]` degrees and the other two angles are in the range `[-180, 180]` degrees.
+/// When the angle around the second axis is close to ±90 degrees, the first and third axes are aligned (gimbal lock) and the angle around the third axis is set to zero.
+///
+/// ```
+/// # use three_d::*;
+/// let orders = [RotationOrder::XYZ, RotationOrder::XZY, RotationOrder::YXZ, RotationOrder::YZX, RotationOrder::ZXY, RotationOrder::ZYX];
+/// for order in orders {
+///     for i in 0..50 {
+///         let t = i as f32;
+///         let angles = [degrees(170.0 * (t * 0.7).sin()), degrees(85.0 * (t * 1.3).cos()), degrees(170.0 * (t * 2.9).sin())];
+///         let rotation = euler_to_quat(order, angles[0], angles[1], angles[2]);
+///         let (x, y, z) = quat_to_euler(order, rotation);
+///         let result = euler_to_quat(order, x, y, z);
+///         // The rotation is the same, but the quaternion might have the opposite sign
+///         assert!(rotation.dot(result).abs() > 1.0 - 1.0e-5, "{:?} {:?}", order, angles);
+///     }
+/// }
+/// ```
+///
+pub fn quat_to_euler(order: RotationOrder, rotation: Quat) -> (Radians, Radians, Radians) {
+    let m = Mat3::from(rotation.normalize());
+    // The matrix is indexed by column first
+    let e = |row: usize, column: usize| m[column][row];
+    let [i, j, k] = order.axes();
+    let s = order.parity();
+
+    let sin_second = (-s * e(k, i)).clamp(-1.0, 1.0);
+    let second = sin_second.asin();
+    let (first, third) = if sin_second.abs() < 1.0 - 1.0e-6 {
+        ((s * e(k, j)).atan2(e(k, k)), (s * e(j, i)).atan2(e(i, i)))
+    } else {
+        ((-s * e(j, k)).atan2(e(j, j)), 0.0)
+    };
+
+    let mut angles = [0.0; 3];
+    angles[i] = first;
+    angles[j] = second;
+    angles[k] = third;
+    (radians(angles[0]), radians(angles[1]), radians(angles[2]))
+}
+
+///
+/// Returns the transformation of an object placed at `eye` where the negative Z axis of the object points towards `target`
+/// and the Y axis of the object is as close as possible to `up`.
+/// This is the inverse of the view matrix of a camera with the same position, target and up direction, see [Mat4::look_at_rh].
+///
+/// ```
+/// # use three_d::*;
+/// let eye = vec3(1.0, 2.0, 3.0);
+/// let target = vec3(-2.0, 0.5, 1.0);
+/// let transformation = look_at_matrix(eye, target, vec3(0.0, 1.0, 0.0));
+/// let forward = transformation.transform_vector(vec3(0.0, 0.0, -1.0));
+/// assert!((forward - (target - eye).normalize()).magnitude() < 1.0e-6);
+/// assert!((transformation.transform_point(Point3::new(0.0, 0.0, 0.0)) - Point3::from_vec(eye)).magnitude() < 1.0e-6);
+///
+/// let view = Mat4::look_at_rh(Point3::from_vec(eye), Point3::from_vec(target), vec3(0.0, 1.0, 0.0));
+/// let identity = view * transformation;
+/// assert!((0..4).all(|c| (identity[c] - Mat4::identity()[c]).magnitude() < 1.0e-5));
+/// ```
+///
+pub fn look_at_matrix(eye: Vec3, target: Vec3, up: Vec3) -> Mat4 {
+    let z = (eye - target).normalize();
+    let x = up.cross(z).normalize();
+    let y = z.cross(x);
+    Mat4::from_cols(x.extend(0.0), y.extend(0.0), z.extend(0.0), eye.extend(1.0))
+}
+
+///
+/// Composes a transformation which first scales, then rotates and finally translates, ie. `T * R * S`. This is the inverse of [decompose].
+///
+pub fn compose(translation: Vec3, rotation: Quat, scale: Vec3) -> Mat4 {
+    Mat4::from_translation(translation)
+        * Mat4::from(rotation)
+        * Mat4::from_nonuniform_scale(scale.x, scale.y, scale.z)
+}
+
+///
+/// Decomposes a transformation into a translation, a rotation and a scale, such that [compose] returns the transformation again.
+/// If the transformation mirrors the space (has a negative determinant), the scale along the X axis is negative.
+/// The transformation is assumed to not contain any shear or projection, in which case the result is an approximation.
+///
+/// ```
+/// # use three_d::*;
+/// for i in 0..50 {
+///     let t = i as f32;
+///     let translation = vec3(t.sin(), 10.0 * (t * 0.3).cos(), -t);
+///     let rotation = euler_to_quat(RotationOrder::YXZ, radians(t), radians(2.0 * t), radians(0.5 * t));
+///     let mirror = if i % 2 == 0 { 1.0 } else { -1.0 };
+///     let scale = vec3(mirror * (1.0 + (t * 0.1).sin().abs()), 0.5 + t * 0.05, 2.0);
+///     let transformation = compose(translation, rotation, scale);
+///
+///     let (t, r, s) = decompose(transformation);
+///     // The rotation is normalized
+///     assert!((r.magnitude() - 1.0).abs() < 1.0e-5);
+///     let result = compose(t, r, s);
+///     assert!((0..4).all(|c| (result[c] - transformation[c]).magnitude() < 1.0e-4));
+/// }
+/// ```
+///
+pub fn decompose(transformation: Mat4) -> (Vec3, Quat, Vec3) {
+    let x = transformation.x.truncate();
+    let y = transformation.y.truncate();
+    let z = transformation.z.truncate();
+    let mut scale = vec3(x.magnitude(), y.magnitude(), z.magnitude());
+    if x.cross(y).dot(z) < 0.0 {
+        scale.x = -scale.x;
+    }
+    let rotation = Mat3::from_cols(
+        x / non_zero(scale.x),
+        y / non_zero(scale.y),
+        z / non_zero(scale.z),
+    );
+    (
+        transformation.w.truncate(),
+        Quat::from(rotation).normalize(),
+        scale,
+    )
+}
+
+fn non_zero(value: f32) -> f32 {
+    if value.abs() < f32::EPSILON {
+        f32::EPSILON.copysign(value)
+    } else {
+        value
+    }
+}
+
+///
+/// Decomposes the rotation into a twist around the given axis and a swing around an axis perpendicular to the given axis,
+/// such that `rotation = swing * twist`, ie. the twist is applied first.
+/// This is for example useful to limit the twist of a joint in a skeleton independently of the swing.
+/// Returns the swing and the twist, in that order.
+///
+/// ```
+/// # use three_d::*;
+/// let axis = vec3(0.0, 1.0, 0.0);
+/// for i in 0..50 {
+///     let t = i as f32;
+///     let rotation = euler_to_quat(RotationOrder::XYZ, radians(t), radians(2.0 * t), radians(0.3 * t));
+///     let (swing, twist) = swing_twist_decomposition(rotation, axis);
+///     assert!(((swing * twist) - rotation).magnitude() < 1.0e-5);
+///     // The twist is a rotation around the axis
+///     assert!(twist.v.cross(axis).magnitude() < 1.0e-5);
+///     // The swing does not rotate around the axis
+///     assert!(swing.v.dot(axis).abs() < 1.0e-5);
+/// }
+/// ```
+///
+pub fn swing_twist_decomposition(rotation: Quat, axis: Vec3) -> (Quat, Quat) {
+    let axis = axis.normalize();
+    let projection = axis * rotation.v.dot(axis);
+    let twist = Quat::from_sv(rotation.s, projection);
+    let twist = if twist.magnitude2() < f32::EPSILON {
+        // The rotation is 180 degrees around an axis perpendicular to the given axis, so there is no twist
+        Quat::one()
+    } else {
+        twist.normalize()
+    };
+    (rotation * twist.conjugate(), twist)
+}
+
+///
+/// Returns the transformation from a right-handed Z-up coordinate system, for example used by Blender, to the right-handed Y-up coordinate system used by three-d and glTF.
+/// The Z axis is mapped to the Y axis and the Y axis is mapped to the negative Z axis, ie. a rotation of -90 degrees around the X axis.
+///
+/// ```
+/// # use three_d::*;
+/// let up = z_up_to_y_up().transform_vector(vec3(0.0, 0.0, 1.0));
+/// assert!((up - vec3(0.0, 1.0, 0.0)).magnitude() < 1.0e-6);
+/// let identity = y_up_to_z_up() * z_up_to_y_up();
+/// assert!((0..4).all(|c| (identity[c] - Mat4::identity()[c]).magnitude() < 1.0e-6));
+/// ```
+///
+pub fn z_up_to_y_up() -> Mat4 {
+    Mat4::from_cols(
+        vec4(1.0, 0.0, 0.0, 0.0),
+        vec4(0.0, 0.0, -1.0, 0.0),
+        vec4(0.0, 1.0, 0.0, 0.0),
+        vec4(0.0, 0.0, 0.0, 1.0),
+    )
+}
+
+///
+/// Returns the transformation from the right-handed Y-up coordinate system used by three-d and glTF to a right-handed Z-up coordinate system, for example used by Blender.
+/// This is the inverse of [z_up_to_y_up].
+///
+pub fn y_up_to_z_up() -> Mat4 {
+    Mat4::from_cols(
+        vec4(1.0, 0.0, 0.0, 0.0),
+        vec4(0.0, 0.0, 1.0, 0.0),
+        vec4(0.0, -1.0, 0.0, 0.0),
+        vec4(0.0, 0.0, 0.0, 1.0),
+    )
+}
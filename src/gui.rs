@@ -8,3 +8,10 @@ mod egui_gui;
 #[doc(inline)]
 #[cfg(feature = "egui-gui")]
 pub use egui_gui::*;
+
+#[cfg(feature = "egui-gui")]
+#[cfg_attr(docsrs, doc(cfg(feature = "egui-gui")))]
+mod annotations;
+#[doc(inline)]
+#[cfg(feature = "egui-gui")]
+pub use annotations::*;
@@ -0,0 +1,560 @@
+use crate::renderer::*;
+use std::collections::HashMap;
+
+///
+/// A note attached to a point on a surface, see [Annotations].
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct Annotation {
+    /// The unique id of the annotation within the [Annotations] it belongs to.
+    pub id: u32,
+    /// The anchor position in world space, ie. the point on the surface the annotation is attached to.
+    pub position: Vec3,
+    /// The surface normal at the anchor position which determines the direction of the leader line.
+    pub normal: Vec3,
+    /// The text of the annotation.
+    pub text: String,
+    /// The color of the leader line and the label frame.
+    pub color: Srgba,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct AnnotationState {
+    occluded: bool,
+    opacity: f32,
+}
+
+impl Default for AnnotationState {
+    fn default() -> Self {
+        Self {
+            occluded: false,
+            opacity: 1.0,
+        }
+    }
+}
+
+///
+/// A set of [Annotation]s, ie. notes attached to points on surfaces, which are shown as labels connected to the anchor point with a leader line.
+///
+/// The anchor and normal of a new annotation is typically found with [Annotations::pick_anchor] at the mouse position.
+/// Each frame, call [Annotations::update] to test whether the anchors are hidden behind the geometries and fade the hidden annotations,
+/// and [Annotations::ui] inside the callback of [GUI::update](crate::GUI::update) to show the labels.
+/// Hovering a label highlights it, clicking it selects it and double clicking it opens a small popup for editing the text.
+/// The annotations can be saved and loaded using [Annotations::to_json] and [Annotations::from_json].
+///
+#[derive(Clone, Debug)]
+pub struct Annotations {
+    /// The length of the leader line in logical points.
+    pub leader_length: f32,
+    /// The opacity of annotations where the anchor is hidden behind a geometry.
+    pub occluded_opacity: f32,
+    /// The time in milliseconds it takes to fade an annotation in or out when it becomes visible or hidden.
+    pub fade_time: f64,
+    annotations: Vec<Annotation>,
+    states: HashMap<u32, AnnotationState>,
+    next_id: u32,
+    hovered: Option<u32>,
+    selected: Option<u32>,
+    editing: Option<(u32, String)>,
+    last_occlusion_test: Option<(Mat4, Viewport)>,
+    last_time: Option<f64>,
+}
+
+impl Default for Annotations {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Annotations {
+    ///
+    /// Creates an empty set of annotations.
+    ///
+    pub fn new() -> Self {
+        Self {
+            leader_length: 40.0,
+            occluded_opacity: 0.25,
+            fade_time: 200.0,
+            annotations: Vec::new(),
+            states: HashMap::new(),
+            next_id: 0,
+            hovered: None,
+            selected: None,
+            editing: None,
+            last_occlusion_test: None,
+            last_time: None,
+        }
+    }
+
+    ///
+    /// Adds an annotation anchored at the given position with the given normal and returns its id.
+    ///
+    pub fn add(
+        &mut self,
+        position: Vec3,
+        normal: Vec3,
+        text: impl Into<String>,
+        color: Srgba,
+    ) -> u32 {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.annotations.push(Annotation {
+            id,
+            position,
+            normal: normal.normalize(),
+            text: text.into(),
+            color,
+        });
+        self.last_occlusion_test = None;
+        id
+    }
+
+    ///
+    /// Removes the annotation with the given id and returns it, if it exists.
+    ///
+    pub fn remove(&mut self, id: u32) -> Option<Annotation> {
+        let index = self.annotations.iter().position(|a| a.id == id)?;
+        self.states.remove(&id);
+        if self.hovered == Some(id) {
+            self.hovered = None;
+        }
+        if self.selected == Some(id) {
+            self.selected = None;
+        }
+        if self.editing.as_ref().is_some_and(|(i, _)| *i == id) {
+            self.editing = None;
+        }
+        Some(self.annotations.remove(index))
+    }
+
+    ///
+    /// Removes all annotations.
+    ///
+    pub fn clear(&mut self) {
+        self.annotations.clear();
+        self.states.clear();
+        self.hovered = None;
+        self.selected = None;
+        self.editing = None;
+    }
+
+    ///
+    /// Returns the annotation with the given id, if it exists.
+    ///
+    pub fn get(&self, id: u32) -> Option<&Annotation> {
+        self.annotations.iter().find(|a| a.id == id)
+    }
+
+    ///
+    /// Returns the annotation with the given id for modification, if it exists.
+    /// Call [Annotations::invalidate] if the anchor position is changed.
+    ///
+    pub fn get_mut(&mut self, id: u32) -> Option<&mut Annotation> {
+        self.annotations.iter_mut().find(|a| a.id == id)
+    }
+
+    ///
+    /// Returns an iterator over all annotations in the order they were added.
+    ///
+    pub fn iter(&self) -> impl Iterator<Item = &Annotation> {
+        self.annotations.iter()
+    }
+
+    ///
+    /// Returns the number of annotations.
+    ///
+    pub fn len(&self) -> usize {
+        self.annotations.len()
+    }
+
+    ///
+    /// Returns whether there are no annotations.
+    ///
+    pub fn is_empty(&self) -> bool {
+        self.annotations.is_empty()
+    }
+
+    ///
+    /// Returns the id of the annotation whose label is hovered by the mouse, if any.
+    ///
+    pub fn hovered(&self) -> Option<u32> {
+        self.hovered
+    }
+
+    ///
+    /// Returns the id of the selected annotation, if any.
+    ///
+    pub fn selected(&self) -> Option<u32> {
+        self.selected
+    }
+
+    ///
+    /// Selects the annotation with the given id or clears the selection if `None`.
+    ///
+    pub fn select(&mut self, id: Option<u32>) {
+        self.selected = id.filter(|id| self.get(*id).is_some());
+    }
+
+    ///
+    /// Returns the id of the annotation which text is currently being edited, if any.
+    ///
+    pub fn editing(&self) -> Option<u32> {
+        self.editing.as_ref().map(|(id, _)| *id)
+    }
+
+    ///
+    /// Opens the popup for editing the text of the annotation with the given id, for example right after adding it.
+    ///
+    pub fn edit(&mut self, id: u32) {
+        if let Some(annotation) = self.get(id) {
+            self.editing = Some((id, annotation.text.clone()));
+            self.selected = Some(id);
+        }
+    }
+
+    ///
+    /// Forces a new occlusion test the next time [Annotations::update] is called, for example because the geometries have moved.
+    ///
+    pub fn invalidate(&mut self) {
+        self.last_occlusion_test = None;
+    }
+
+    ///
+    /// Finds the position and normal of the closest point on the given geometries in the given pixel coordinate (see [pick]),
+    /// which can be used as the anchor of a new annotation.
+    /// The normal is estimated from the neighbouring pixels and faces the viewer.
+    ///
+    pub fn pick_anchor(
+        context: &Context,
+        camera: &Camera,
+        pixel: impl Into<PhysicalPoint> + Copy,
+        geometries: impl IntoIterator<Item = impl Geometry> + Clone,
+    ) -> Result<Option<(Vec3, Vec3)>, RendererError> {
+        let pixel: PhysicalPoint = pixel.into();
+        let Some(center) = pick(context, camera, pixel, geometries.clone(), Cull::Back)? else {
+            return Ok(None);
+        };
+        let towards_camera = -camera.view_direction_at_pixel(pixel);
+        let mut tangents = Vec::new();
+        for (dx, dy) in [(1.0, 0.0), (-1.0, 0.0), (0.0, 1.0), (0.0, -1.0)] {
+            let neighbour = PhysicalPoint {
+                x: pixel.x + dx,
+                y: pixel.y + dy,
+            };
+            if let Some(result) = pick(context, camera, neighbour, geometries.clone(), Cull::Back)?
+            {
+                if result.geometry_id == center.geometry_id {
+                    tangents.push((vec2(dx, dy), result.position - center.position));
+                }
+            }
+        }
+        let horizontal = tangents.iter().find(|(d, _)| d.y == 0.0);
+        let vertical = tangents.iter().find(|(d, _)| d.x == 0.0);
+        let normal = match (horizontal, vertical) {
+            (Some((dh, th)), Some((dv, tv))) => {
+                let n = (th * dh.x).cross(tv * dv.y);
+                if n.magnitude2() > 0.0 {
+                    n.normalize()
+                } else {
+                    towards_camera
+                }
+            }
+            _ => towards_camera,
+        };
+        let normal = if normal.dot(towards_camera) < 0.0 {
+            -normal
+        } else {
+            normal
+        };
+        Ok(Some((center.position, normal)))
+    }
+
+    ///
+    /// Tests whether the anchors are hidden behind the given geometries as seen from the given camera and fades the hidden annotations
+    /// towards [Annotations::occluded_opacity]. The time is the current time in milliseconds, for example [FrameInput::accumulated_time](crate::FrameInput::accumulated_time).
+    /// The occlusion test is only repeated if the camera moves or annotations are added, so call [Annotations::invalidate] if the geometries are changed.
+    /// Must be called each frame before [Annotations::ui].
+    ///
+    pub fn update(
+        &mut self,
+        context: &Context,
+        camera: &Camera,
+        time: f64,
+        geometries: impl IntoIterator<Item = impl Geometry> + Clone,
+    ) -> Result<(), RendererError> {
+        let key = (camera.projection() * camera.view(), camera.viewport());
+        if self.last_occlusion_test != Some(key) {
+            let view_direction = camera.view_direction();
+            for annotation in self.annotations.iter() {
+                let depth = (annotation.position - camera.position()).dot(view_direction);
+                let pixel = camera.pixel_at_position(annotation.position);
+                let occluded = depth > camera.z_near()
+                    && pick(context, camera, pixel, geometries.clone(), Cull::Back)?.is_some_and(
+                        |result| {
+                            (result.position - camera.position()).dot(view_direction) < depth * 0.99
+                        },
+                    );
+                self.states.entry(annotation.id).or_default().occluded = occluded;
+            }
+            self.last_occlusion_test = Some(key);
+        }
+
+        let elapsed = self.last_time.map(|t| (time - t).max(0.0)).unwrap_or(0.0);
+        self.last_time = Some(time);
+        let step = if self.fade_time > 0.0 {
+            (elapsed / self.fade_time) as f32
+        } else {
+            1.0
+        };
+        for state in self.states.values_mut() {
+            let target = if state.occluded {
+                self.occluded_opacity
+            } else {
+                1.0
+            };
+            state.opacity = if state.opacity < target {
+                (state.opacity + step).min(target)
+            } else {
+                (state.opacity - step).max(target)
+            };
+        }
+        Ok(())
+    }
+
+    ///
+    /// Shows the labels with leader lines and, if an annotation is being edited, the popup for editing the text.
+    /// Must be called in the callback given to [GUI::update](crate::GUI::update) with the same camera that is used for rendering the scene.
+    /// Returns whether any annotation was changed or removed by the user, for example to know when to save the annotations.
+    ///
+    pub fn ui(&mut self, gui_context: &egui::Context, camera: &Camera) -> bool {
+        let scale = gui_context.pixels_per_point();
+        let viewport = camera.viewport();
+        let to_gui = |p: PhysicalPoint| {
+            egui::pos2(
+                p.x / scale,
+                (viewport.y as f32 + viewport.height as f32 - p.y) / scale,
+            )
+        };
+        let painter = gui_context.layer_painter(egui::LayerId::background());
+        let view_direction = camera.view_direction();
+
+        let mut hovered = None;
+        let mut clicked = None;
+        let mut double_clicked = None;
+        let mut popup_position = None;
+        for annotation in self.annotations.iter() {
+            let depth = (annotation.position - camera.position()).dot(view_direction);
+            if depth <= camera.z_near() || depth >= camera.z_far() {
+                continue;
+            }
+            let opacity = self
+                .states
+                .get(&annotation.id)
+                .map(|s| s.opacity)
+                .unwrap_or(1.0);
+            let anchor = to_gui(camera.pixel_at_position(annotation.position));
+            let tip = to_gui(
+                camera.pixel_at_position(annotation.position + annotation.normal * depth * 0.01),
+            );
+            let direction = (tip - anchor).normalized();
+            let direction = if direction.x.is_finite() && direction.length_sq() > 0.5 {
+                direction
+            } else {
+                egui::vec2(0.0, -1.0)
+            };
+            let end = anchor + direction * self.leader_length;
+
+            let is_highlighted =
+                self.hovered == Some(annotation.id) || self.selected == Some(annotation.id);
+            let color = egui::Color32::from_rgba_unmultiplied(
+                annotation.color.r,
+                annotation.color.g,
+                annotation.color.b,
+                annotation.color.a,
+            )
+            .gamma_multiply(opacity);
+            let width = if is_highlighted { 2.0 } else { 1.0 };
+            painter.line_segment([anchor, end], egui::Stroke::new(width, color));
+            painter.circle_filled(anchor, 2.0 + width, color);
+
+            let pivot = egui::Align2([
+                if direction.x < -0.3 {
+                    egui::Align::Max
+                } else if direction.x > 0.3 {
+                    egui::Align::Min
+                } else {
+                    egui::Align::Center
+                },
+                if direction.y < -0.3 {
+                    egui::Align::Max
+                } else if direction.y > 0.3 {
+                    egui::Align::Min
+                } else {
+                    egui::Align::Center
+                },
+            ]);
+            let response = egui::Area::new(egui::Id::new(("three-d annotation", annotation.id)))
+                .fixed_pos(end)
+                .pivot(pivot)
+                .order(egui::Order::Middle)
+                .sense(egui::Sense::click())
+                .constrain(false)
+                .show(gui_context, |ui| {
+                    ui.multiply_opacity(opacity);
+                    egui::Frame::popup(ui.style())
+                        .stroke(egui::Stroke::new(width, color))
+                        .show(ui, |ui| {
+                            let text = if annotation.text.is_empty() {
+                                egui::RichText::new("…").weak()
+                            } else {
+                                egui::RichText::new(&annotation.text)
+                            };
+                            ui.add(egui::Label::new(text).selectable(false));
+                        });
+                })
+                .response;
+            if response.hovered() {
+                hovered = Some(annotation.id);
+            }
+            if response.double_clicked() {
+                double_clicked = Some(annotation.id);
+            } else if response.clicked() {
+                clicked = Some(annotation.id);
+            }
+            if self.editing() == Some(annotation.id) {
+                popup_position = Some(response.rect.right_top());
+            }
+        }
+        self.hovered = hovered;
+        if let Some(id) = clicked {
+            self.selected = Some(id);
+        }
+        if let Some(id) = double_clicked {
+            self.edit(id);
+        }
+
+        let mut changed = false;
+        let mut close = false;
+        let mut remove = None;
+        if let Some((id, text)) = self.editing.as_mut() {
+            let mut window = egui::Window::new("Annotation")
+                .id(egui::Id::new("three-d annotation editor"))
+                .collapsible(false)
+                .resizable(false);
+            if let Some(position) = popup_position {
+                window = window.default_pos(position + egui::vec2(8.0, 0.0));
+            }
+            window.show(gui_context, |ui| {
+                let response = ui.add(egui::TextEdit::multiline(text).desired_rows(3));
+                if !response.has_focus() && !response.lost_focus() {
+                    response.request_focus();
+                }
+                ui.horizontal(|ui| {
+                    if ui.button("Done").clicked() {
+                        if let Some(annotation) = self.annotations.iter_mut().find(|a| a.id == *id)
+                        {
+                            changed |= annotation.text != *text;
+                            annotation.text = text.clone();
+                        }
+                        close = true;
+                    }
+                    if ui.button("Cancel").clicked()
+                        || ui.input(|i| i.key_pressed(egui::Key::Escape))
+                    {
+                        close = true;
+                    }
+                    if ui.button("Delete").clicked() {
+                        remove = Some(*id);
+                    }
+                });
+            });
+        }
+        if close {
+            self.editing = None;
+        }
+        if let Some(id) = remove {
+            changed |= self.remove(id).is_some();
+        }
+        changed
+    }
+
+    ///
+    /// Serializes the annotations to a JSON string, which can be loaded again with [Annotations::from_json].
+    ///
+    pub fn to_json(&self) -> String {
+        let annotations = self
+            .annotations
+            .iter()
+            .map(|a| {
+                serde_json::json!({
+                    "id": a.id,
+                    "position": [a.position.x, a.position.y, a.position.z],
+                    "normal": [a.normal.x, a.normal.y, a.normal.z],
+                    "text": a.text,
+                    "color": [a.color.r, a.color.g, a.color.b, a.color.a],
+                })
+            })
+            .collect::<Vec<_>>();
+        serde_json::json!({ "annotations": annotations }).to_string()
+    }
+
+    ///
+    /// Deserializes annotations from a JSON string created by [Annotations::to_json].
+    /// New annotations added afterwards are given ids larger than all the loaded ids.
+    ///
+    pub fn from_json(json: &str) -> Result<Self, RendererError> {
+        trace_span!("three_d::deserialize", asset = "annotations");
+        use serde_json::Value;
+        let invalid = |message: &str| RendererError::InvalidAnnotations(message.to_string());
+        let value: Value = serde_json::from_str(json)
+            .map_err(|e| RendererError::InvalidAnnotations(e.to_string()))?;
+        let numbers =
+            |value: &Value, name: &str, count: usize| -> Result<Vec<f64>, RendererError> {
+                let numbers = value[name]
+                    .as_array()
+                    .filter(|a| a.len() == count)
+                    .ok_or_else(|| invalid(&format!("expected {count} numbers in '{name}'")))?;
+                numbers
+                    .iter()
+                    .map(|n| {
+                        n.as_f64()
+                            .ok_or_else(|| invalid(&format!("expected a number in '{name}'")))
+                    })
+                    .collect()
+            };
+
+        let mut annotations = Self::new();
+        for value in value["annotations"]
+            .as_array()
+            .ok_or_else(|| invalid("expected an 'annotations' array"))?
+        {
+            let id = value["id"]
+                .as_u64()
+                .and_then(|id| u32::try_from(id).ok())
+                .ok_or_else(|| invalid("expected an integer 'id'"))?;
+            if annotations.get(id).is_some() {
+                return Err(invalid(&format!("duplicate id {id}")));
+            }
+            let position = numbers(value, "position", 3)?;
+            let normal = numbers(value, "normal", 3)?;
+            let color = numbers(value, "color", 4)?;
+            let text = value["text"]
+                .as_str()
+                .ok_or_else(|| invalid("expected a string 'text'"))?;
+            annotations.annotations.push(Annotation {
+                id,
+                position: vec3(position[0] as f32, position[1] as f32, position[2] as f32),
+                normal: vec3(normal[0] as f32, normal[1] as f32, normal[2] as f32),
+                text: text.to_string(),
+                color: Srgba::new(
+                    color[0] as u8,
+                    color[1] as u8,
+                    color[2] as u8,
+                    color[3] as u8,
+                ),
+            });
+            annotations.next_id = annotations.next_id.max(id + 1);
+        }
+        Ok(annotations)
+    }
+}
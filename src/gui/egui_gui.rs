@@ -259,6 +259,42 @@ impl GUI {
         self.egui_context.wants_pointer_input() || self.egui_context.wants_keyboard_input()
     }
 
+    ///
+    /// Returns whether the GUI uses the pointer (mouse or touch), for example because the pointer is over a panel or a widget is being dragged.
+    /// In that case, the pointer events are marked as handled in [update](Self::update), so they are not used by the controls.
+    ///
+    pub fn is_pointer_captured(&self) -> bool {
+        self.egui_context.wants_pointer_input()
+    }
+
+    ///
+    /// Returns whether the GUI uses the keyboard, for example because a text field has focus.
+    /// In that case, the key events are marked as handled in [update](Self::update), so they are not used by the controls.
+    ///
+    pub fn is_keyboard_captured(&self) -> bool {
+        self.egui_context.wants_keyboard_input()
+    }
+
+    ///
+    /// Converts the given rectangle in egui logical points, for example [egui::Context::available_rect] or the rectangle of a widget,
+    /// into a [ScissorBox] in physical pixels which can be used to clear or render into that part of the render target,
+    /// for example using [RenderTarget::clear_partially] and [RenderTarget::render_partially].
+    /// The edges are rounded to the nearest pixel, so adjacent rectangles give adjacent scissor boxes without gaps or overlaps.
+    ///
+    pub fn scissor_box_from_rect(&self, rect: egui::Rect) -> ScissorBox {
+        let scale = self.egui_context.pixels_per_point();
+        let left = (rect.min.x * scale).round() as i32;
+        let right = (rect.max.x * scale).round() as i32;
+        let top = (rect.min.y * scale).round() as i32;
+        let bottom = (rect.max.y * scale).round() as i32;
+        ScissorBox {
+            x: left,
+            y: self.viewport.height as i32 - bottom,
+            width: (right - left).max(0) as u32,
+            height: (bottom - top).max(0) as u32,
+        }
+    }
+
     ///
     /// Render the GUI defined in the [update](Self::update) function.
     /// Must be called in the callback given as input to a [RenderTarget], [ColorTarget] or [DepthTarget] write method.
@@ -9,6 +9,8 @@
 //! the [examples](https://github.com/asny/three-d/tree/master/examples) for how to use it.
 //!
 
+pub mod trace;
+
 pub mod context;
 
 pub mod core;
@@ -32,6 +32,32 @@ pub enum RendererError {
     #[cfg(feature = "text")]
     #[error("Failed to find font with index {0} in the given font collection")]
     MissingFont(u32),
+    #[cfg(feature = "egui-gui")]
+    #[error("Failed to load annotations: {0}")]
+    InvalidAnnotations(String),
+    #[cfg(feature = "gltf")]
+    #[error("failed to read the lightmap uv coordinates from the glTF file: {0}")]
+    InvalidGltf(String),
+    #[error("the mesh must have uv coordinates to bake into a texture")]
+    MissingUvCoordinates,
+    #[error("failed to write the output of the batch renderer: {0}")]
+    BatchOutput(#[from] std::io::Error),
+    #[error("{0} shadow cascades are given but at most {1} are supported")]
+    TooManyShadowCascades(usize, usize),
+    #[error("{0} lights are given but the light buffer has room for at most {1}")]
+    TooManyBufferedLights(usize, usize),
+    #[error("{0} morph targets are given but at most {1} are supported")]
+    TooManyMorphTargets(usize, usize),
+    #[error("invalid event recording: {0}")]
+    InvalidEventRecording(String),
+    #[error("the event recording has version {0} but at most version {1} is supported")]
+    UnsupportedEventRecordingVersion(u16, u16),
+    #[error("failed to read or write the event recording: {0}")]
+    EventRecordingIo(std::io::Error),
+    #[error("invalid point cloud octree: {0}")]
+    InvalidPointCloudOctree(String),
+    #[error("failed to read or write the point cloud octree: {0}")]
+    PointCloudOctreeIo(std::io::Error),
     #[error("CoreError: {0}")]
     CoreError(#[from] CoreError),
 }
@@ -60,6 +86,74 @@ pub use object::*;
 pub mod control;
 pub use control::*;
 
+pub mod baking;
+pub use baking::*;
+
+pub mod collision;
+pub use collision::*;
+
+pub mod texture_streaming;
+pub use texture_streaming::*;
+
+pub mod point_cloud_streaming;
+pub use point_cloud_streaming::*;
+
+mod order_independent_transparency;
+use order_independent_transparency::*;
+
+mod depth_peeling;
+use depth_peeling::*;
+
+pub mod deferred_creation;
+pub use deferred_creation::*;
+
+pub mod dynamic_resolution;
+pub use dynamic_resolution::*;
+
+pub mod hdr_composer;
+pub use hdr_composer::*;
+
+pub mod portal_culling;
+pub use portal_culling::*;
+
+pub mod occlusion_culling;
+pub use occlusion_culling::*;
+
+pub mod distance_glow;
+pub use distance_glow::*;
+
+pub mod outline;
+pub use outline::*;
+
+pub mod program_cache;
+pub use program_cache::*;
+
+pub mod gpu_picking;
+pub use gpu_picking::*;
+
+pub mod fixed_timestep;
+pub use fixed_timestep::*;
+
+pub mod batch_renderer;
+pub use batch_renderer::*;
+
+pub mod overlay_2d;
+pub use overlay_2d::*;
+
+pub mod thumbnail;
+pub use thumbnail::*;
+
+pub mod uniform_signals;
+pub use uniform_signals::*;
+
+pub mod animation_player;
+pub use animation_player::*;
+
+#[cfg(not(target_arch = "wasm32"))]
+pub mod file_watcher;
+#[cfg(not(target_arch = "wasm32"))]
+pub use file_watcher::*;
+
 #[cfg(feature = "text")]
 mod text;
 #[cfg(feature = "text")]
@@ -88,11 +182,16 @@ macro_rules! impl_render_target_extensions_body {
         ///
         pub fn render_partially(
             &self,
-            scissor_box: ScissorBox,
+            scissor_box: impl Into<ScissorBox>,
             viewer: impl Viewer,
             objects: impl IntoIterator<Item = impl Object>,
             lights: &[&dyn Light],
         ) -> &Self {
+            trace_span!("three_d::render");
+            let scissor_box = scissor_box.into();
+            if scissor_box.is_empty() {
+                return self;
+            }
             let frustum = Frustum::new(viewer.projection() * viewer.view());
             let (mut deferred_objects, mut forward_objects): (Vec<_>, Vec<_>) = objects
                 .into_iter()
@@ -102,33 +201,25 @@ macro_rules! impl_render_target_extensions_body {
             // Deferred
             if deferred_objects.len() > 0 {
                 // Geometry pass
+                trace_span!("three_d::geometry_pass", objects = deferred_objects.len());
                 let geometry_pass_camera = GeometryPassCamera(&viewer);
                 let viewport = geometry_pass_camera.viewport();
                 deferred_objects.sort_by(|a, b| cmp_render_order(&geometry_pass_camera, a, b));
-                let mut geometry_pass_texture = Texture2DArray::new_empty::<[u8; 4]>(
-                    &self.context,
+                let mut geometry_pass_texture = self.context.request_texture_array::<[u8; 4]>(
                     viewport.width,
                     viewport.height,
                     3,
                     Interpolation::Nearest,
-                    Interpolation::Nearest,
-                    None,
-                    Wrapping::ClampToEdge,
-                    Wrapping::ClampToEdge,
-                );
-                let mut geometry_pass_depth_texture = DepthTexture2D::new::<f32>(
-                    &self.context,
-                    viewport.width,
-                    viewport.height,
-                    Wrapping::ClampToEdge,
-                    Wrapping::ClampToEdge,
                 );
+                let mut geometry_pass_depth_texture = self
+                    .context
+                    .request_depth_texture::<f32>(viewport.width, viewport.height);
                 let gbuffer_layers = [0, 1, 2];
                 RenderTarget::new(
                     geometry_pass_texture.as_color_target(&gbuffer_layers, None),
                     geometry_pass_depth_texture.as_depth_target(),
                 )
-                .clear(ClearState::default())
+                .clear(ClearState::default().with_depth_mode(viewer.depth_mode()))
                 .write::<RendererError>(|| {
                     for object in deferred_objects {
                         object.render(&geometry_pass_camera, lights);
@@ -152,6 +243,7 @@ macro_rules! impl_render_target_extensions_body {
             }
 
             // Forward
+            trace_span!("three_d::forward_pass", objects = forward_objects.len());
             forward_objects.sort_by(|a, b| cmp_render_order(&viewer, a, b));
             self.write_partially::<RendererError>(scissor_box, || {
                 for object in forward_objects {
@@ -163,6 +255,176 @@ macro_rules! impl_render_target_extensions_body {
             self
         }
 
+        ///
+        /// Render the transparent objects using weighted blended order-independent transparency, so the objects do not need to be sorted
+        /// and objects which intersect each other are blended correctly, at the cost of an approximation of the blending where the colors are weighted by the depth.
+        /// The objects are rendered into an accumulation and a revealage texture, which are then composited onto this render target,
+        /// so render the opaque objects into this render target before calling this function.
+        /// Objects which are not transparent (see [MaterialType::Transparent]) are rendered as usual using [Self::render] before the transparent objects.
+        ///
+        /// The render target does not need a depth texture, instead the transparent objects are hidden behind the opaque objects
+        /// by testing against the given depth texture, which should contain the depth of the opaque objects rendered with the same viewer.
+        /// If no depth texture is given, the transparent objects are not hidden by anything.
+        ///
+        /// Any object which exposes its material through [Object::with_material] is supported, for example a [Gm] with a [PhysicalMaterial] or [ColorMaterial] with transparency.
+        /// Other transparent objects are rendered as usual on top of the composited transparent objects.
+        ///
+        pub fn render_transparent_oit(
+            &self,
+            viewer: impl Viewer,
+            objects: impl IntoIterator<Item = impl Object>,
+            lights: &[&dyn Light],
+            depth_texture: Option<DepthTexture>,
+        ) -> &Self {
+            self.render_transparent_oit_partially(
+                self.scissor_box(),
+                viewer,
+                objects,
+                lights,
+                depth_texture,
+            )
+        }
+
+        ///
+        /// Render the transparent objects using weighted blended order-independent transparency into the part of this render target defined by the scissor box,
+        /// see [Self::render_transparent_oit].
+        ///
+        pub fn render_transparent_oit_partially(
+            &self,
+            scissor_box: impl Into<ScissorBox>,
+            viewer: impl Viewer,
+            objects: impl IntoIterator<Item = impl Object>,
+            lights: &[&dyn Light],
+            depth_texture: Option<DepthTexture>,
+        ) -> &Self {
+            trace_span!("three_d::render_transparent_oit");
+            let scissor_box = scissor_box.into();
+            if scissor_box.is_empty() {
+                return self;
+            }
+            let frustum = Frustum::new(viewer.projection() * viewer.view());
+            let (transparent_objects, other_objects): (Vec<_>, Vec<_>) = objects
+                .into_iter()
+                .filter(|o| frustum.contains(o.aabb()))
+                .partition(|o| o.material_type() == MaterialType::Transparent);
+            if !other_objects.is_empty() {
+                self.render_partially(scissor_box, &viewer, other_objects, lights);
+            }
+            if transparent_objects.is_empty() {
+                return self;
+            }
+
+            let (composite, unsupported_objects) = OitComposite::render(
+                &self.context,
+                &viewer,
+                &transparent_objects,
+                lights,
+                depth_texture,
+            );
+            self.apply_screen_effect_partially(scissor_box, &composite, &viewer, &[], None, None);
+            if !unsupported_objects.is_empty() {
+                self.render_partially(scissor_box, &viewer, unsupported_objects, lights);
+            }
+            self
+        }
+
+        ///
+        /// Render the transparent objects using depth peeling, so the objects do not need to be sorted and objects which intersect each other are blended exactly,
+        /// as opposed to [Self::render_transparent_oit] which approximates the blending, as long as there are no more than the given number of transparent layers at any pixel.
+        /// The number of layers is typically 2 to 6, any layers behind those are not rendered.
+        /// Each pass renders the nearest fragments behind the layer found in the previous pass into a separate texture,
+        /// and then the layers are blended onto this render target from back to front, so render the opaque objects into this render target before calling this function.
+        /// Objects which are not transparent (see [MaterialType::Transparent]) are rendered as usual using [Self::render] before the transparent objects.
+        ///
+        /// The cost is one pass for each layer where all the transparent objects are rendered, so the cost of rendering the transparent objects is multiplied by the number of layers,
+        /// and one full screen pass for each layer when blending. It also uses a half float color texture for each layer and two depth textures.
+        /// The passes stop early when a pass does not render any fragments, which is found using an [OcclusionQuery] and therefore waits for the GPU to finish each pass.
+        /// Fragments at exactly the same depth are treated as one layer, so only one of them is rendered.
+        ///
+        /// The render target does not need a depth texture, instead the transparent objects are hidden behind the opaque objects
+        /// by testing against the given depth texture, which should contain the depth of the opaque objects rendered with the same viewer.
+        /// If no depth texture is given, the transparent objects are not hidden by anything.
+        ///
+        /// Any object which exposes its material through [Object::with_material] is supported, for example a [Gm] with a [PhysicalMaterial] or [ColorMaterial] with transparency.
+        /// Other transparent objects are rendered as usual on top of the blended transparent objects.
+        ///
+        pub fn render_transparent_depth_peeled(
+            &self,
+            viewer: impl Viewer,
+            objects: impl IntoIterator<Item = impl Object>,
+            lights: &[&dyn Light],
+            layers: u32,
+            depth_texture: Option<DepthTexture>,
+        ) -> &Self {
+            self.render_transparent_depth_peeled_partially(
+                self.scissor_box(),
+                viewer,
+                objects,
+                lights,
+                layers,
+                depth_texture,
+            )
+        }
+
+        ///
+        /// Render the transparent objects using depth peeling into the part of this render target defined by the scissor box,
+        /// see [Self::render_transparent_depth_peeled].
+        ///
+        pub fn render_transparent_depth_peeled_partially(
+            &self,
+            scissor_box: impl Into<ScissorBox>,
+            viewer: impl Viewer,
+            objects: impl IntoIterator<Item = impl Object>,
+            lights: &[&dyn Light],
+            layers: u32,
+            depth_texture: Option<DepthTexture>,
+        ) -> &Self {
+            trace_span!("three_d::render_transparent_depth_peeled", layers = layers);
+            let scissor_box = scissor_box.into();
+            if scissor_box.is_empty() {
+                return self;
+            }
+            let frustum = Frustum::new(viewer.projection() * viewer.view());
+            let (transparent_objects, other_objects): (Vec<_>, Vec<_>) = objects
+                .into_iter()
+                .filter(|o| frustum.contains(o.aabb()))
+                .partition(|o| o.material_type() == MaterialType::Transparent);
+            if !other_objects.is_empty() {
+                self.render_partially(scissor_box, &viewer, other_objects, lights);
+            }
+            if transparent_objects.is_empty() {
+                return self;
+            }
+
+            let (peeling, unsupported_objects) = DepthPeeling::render(
+                &self.context,
+                &viewer,
+                &transparent_objects,
+                lights,
+                layers,
+                depth_texture,
+            );
+            // The layers are blended from back to front
+            let effect = CopyEffect {
+                write_mask: WriteMask::COLOR,
+                blend: Blend::PREMULTIPLIED,
+            };
+            for layer in peeling.layers.iter().rev() {
+                self.apply_screen_effect_partially(
+                    scissor_box,
+                    &effect,
+                    &viewer,
+                    &[],
+                    Some(ColorTexture::Single(layer)),
+                    None,
+                );
+            }
+            if !unsupported_objects.is_empty() {
+                self.render_partially(scissor_box, &viewer, unsupported_objects, lights);
+            }
+            self
+        }
+
         ///
         /// Render the geometries with the given [Material] using the given viewer and lights into this render target.
         /// Use an empty array for the `lights` argument, if the material does not require lights to be rendered.
@@ -189,12 +451,13 @@ macro_rules! impl_render_target_extensions_body {
         ///
         pub fn render_partially_with_material(
             &self,
-            scissor_box: ScissorBox,
+            scissor_box: impl Into<ScissorBox>,
             material: &dyn Material,
             viewer: impl Viewer,
             geometries: impl IntoIterator<Item = impl Geometry>,
             lights: &[&dyn Light],
         ) -> &Self {
+            trace_span!("three_d::render_with_material", id = material.id().0);
             let frustum = Frustum::new(viewer.projection() * viewer.view());
             if let Err(e) = self.write_partially::<RendererError>(scissor_box, || {
                 for geometry in geometries
@@ -240,7 +503,7 @@ macro_rules! impl_render_target_extensions_body {
         ///
         pub fn render_partially_with_effect(
             &self,
-            scissor_box: ScissorBox,
+            scissor_box: impl Into<ScissorBox>,
             effect: &dyn Effect,
             viewer: impl Viewer,
             geometries: impl IntoIterator<Item = impl Geometry>,
@@ -248,6 +511,10 @@ macro_rules! impl_render_target_extensions_body {
             color_texture: Option<ColorTexture>,
             depth_texture: Option<DepthTexture>,
         ) -> &Self {
+            trace_span!(
+                "three_d::render_with_effect",
+                id = effect.id(color_texture, depth_texture).0
+            );
             let frustum = Frustum::new(viewer.projection() * viewer.view());
             if let Err(e) = self.write_partially::<RendererError>(scissor_box, || {
                 for geometry in geometries
@@ -290,7 +557,7 @@ macro_rules! impl_render_target_extensions_body {
         ///
         pub fn apply_screen_material_partially(
             &self,
-            scissor_box: ScissorBox,
+            scissor_box: impl Into<ScissorBox>,
             material: &dyn Material,
             viewer: impl Viewer,
             lights: &[&dyn Light],
@@ -331,7 +598,7 @@ macro_rules! impl_render_target_extensions_body {
         ///
         pub fn apply_screen_effect_partially(
             &self,
-            scissor_box: ScissorBox,
+            scissor_box: impl Into<ScissorBox>,
             effect: &dyn Effect,
             viewer: impl Viewer,
             lights: &[&dyn Light],
@@ -410,30 +677,216 @@ fn combine_ids(
 /// Must be called in the callback given as input to a [RenderTarget], [ColorTarget] or [DepthTarget] write method.
 /// Use an empty array for the `lights` argument, if the material does not require lights to be rendered.
 ///
+/// If parallel shader compilation is enabled (see [Context::set_parallel_shader_compilation]) and the shader program is not ready yet,
+/// the geometry is instead rendered with a flat color or skipped if the material is transparent.
+/// Internal passes where the result is read as data, for example picking, masks and shadow maps, instead wait for the program to be compiled.
+///
 pub fn render_with_material(
     context: &Context,
     viewer: impl Viewer,
     geometry: impl Geometry,
     material: impl Material,
     lights: &[&dyn Light],
+) -> Result<(), RendererError> {
+    render_with_material_internal(
+        context,
+        viewer,
+        geometry,
+        material,
+        lights,
+        context.parallel_shader_compilation(),
+    )
+}
+
+fn render_with_material_internal(
+    context: &Context,
+    viewer: impl Viewer,
+    geometry: impl Geometry,
+    material: impl Material,
+    lights: &[&dyn Light],
+    parallel_shader_compilation: bool,
 ) -> Result<(), RendererError> {
     let id = combine_ids(geometry.id(), material.id(), lights.iter().map(|l| l.id()));
 
+    if parallel_shader_compilation
+        && !is_program_ready(
+            context,
+            &id,
+            || geometry.vertex_shader_source(),
+            || material.fragment_shader_source(lights),
+            true,
+        )?
+    {
+        if material.material_type() != MaterialType::Transparent {
+            let fallback_material = ColorMaterial {
+                color: Srgba::new_opaque(128, 128, 128),
+                render_states: material.render_states(),
+                ..Default::default()
+            };
+            render_with_material_internal(
+                context,
+                viewer,
+                geometry,
+                fallback_material,
+                &[],
+                false,
+            )?;
+        }
+        return Ok(());
+    }
+
     let mut programs = context.programs.write().unwrap();
     if !programs.contains_key(&id) {
-        programs.insert(
-            id.clone(),
-            Program::from_source(
-                context,
-                &geometry.vertex_shader_source(),
-                &material.fragment_shader_source(lights),
-            )?,
-        );
+        // Finish a compilation which is already started in parallel instead of compiling the program again
+        let pending_program = context.pending_programs.write().unwrap().remove(&id);
+        let program = match pending_program {
+            Some(compilation) => compilation.finish()?,
+            None => {
+                count_lazy_program_compilation(context, &id);
+                Program::from_source(
+                    context,
+                    &geometry.vertex_shader_source(),
+                    &material.fragment_shader_source(lights),
+                )?
+            }
+        };
+        programs.insert(id.clone(), program);
     }
     let program = programs.get(&id).unwrap();
 
     material.use_uniforms(program, &viewer, lights);
-    geometry.draw(&viewer, program, material.render_states());
+    draw_with_depth_mode(
+        context,
+        &viewer,
+        material.render_states(),
+        |render_states| geometry.draw(&viewer, program, render_states),
+    );
+    Ok(())
+}
+
+///
+/// Calls the given callback with parallel shader compilation disabled, so the geometries rendered in the callback wait for their shader programs to be compiled
+/// instead of being rendered with the flat color fallback (see [render_with_material]).
+/// Use this for internal passes where the result is read as data, for example picking, masks and shadow maps.
+///
+pub(crate) fn with_synchronous_shader_compilation<T>(
+    context: &Context,
+    callback: impl FnOnce() -> T,
+) -> T {
+    let parallel_shader_compilation = context.parallel_shader_compilation();
+    context.set_parallel_shader_compilation(false);
+    let result = callback();
+    context.set_parallel_shader_compilation(parallel_shader_compilation);
+    result
+}
+
+///
+/// Counts a program which is compiled the first time it is needed for rendering, see [Context::lazy_program_compilations],
+/// and emits the ids of the geometry, material and lights as a `three_d::lazy_program_compilation` tracing event to make it easy to find the missing program.
+///
+fn count_lazy_program_compilation(context: &Context, id: &[u8]) {
+    context.count_lazy_program_compilation();
+    trace_event!(
+        INFO,
+        "three_d::lazy_program_compilation",
+        geometry = u16::from_le_bytes([id[0], id[1]]),
+        material = u16::from_le_bytes([id[2], id[3]]),
+        lights = format!("{:02x?}", &id[4..]),
+    );
+}
+
+///
+/// Returns true if the program with the given id is compiled and ready to use.
+/// If not, the compilation is started in parallel with the rendering, if it is not already started.
+/// A compilation started at render time is counted as lazy, see [Context::lazy_program_compilations].
+///
+fn is_program_ready(
+    context: &Context,
+    id: &[u8],
+    vertex_shader_source: impl FnOnce() -> String,
+    fragment_shader_source: impl FnOnce() -> String,
+    at_render_time: bool,
+) -> Result<bool, RendererError> {
+    if context.programs.read().unwrap().contains_key(id) {
+        return Ok(true);
+    }
+    let mut pending_programs = context.pending_programs.write().unwrap();
+    match pending_programs.get(id) {
+        Some(compilation) if compilation.is_complete() => {
+            let program = pending_programs.remove(id).unwrap().finish()?;
+            context
+                .programs
+                .write()
+                .unwrap()
+                .insert(id.to_vec(), program);
+            Ok(true)
+        }
+        Some(_) => Ok(false),
+        None => {
+            if at_render_time {
+                count_lazy_program_compilation(context, id);
+            }
+            pending_programs.insert(
+                id.to_vec(),
+                Program::start_compilation(
+                    context,
+                    &vertex_shader_source(),
+                    &fragment_shader_source(),
+                ),
+            );
+            Ok(false)
+        }
+    }
+}
+
+///
+/// Prepares the shader programs needed for rendering each of the given geometries with each of the given materials and lights,
+/// for example while showing a loading screen, to avoid compiling them the first time they are rendered.
+/// If parallel shader compilation is enabled (see [Context::set_parallel_shader_compilation]), this only starts the compilations
+/// and [Context::poll_pending_programs] can be used to check when they are done, otherwise this blocks until all programs are compiled.
+///
+pub fn warm_up(
+    context: &Context,
+    geometries: impl IntoIterator<Item = impl Geometry>,
+    materials: &[&dyn Material],
+    lights: &[&dyn Light],
+) -> Result<(), RendererError> {
+    for geometry in geometries {
+        for material in materials {
+            prepare_program(context, &geometry, *material, lights)?;
+        }
+    }
+    Ok(())
+}
+
+///
+/// Compiles the program for rendering the given geometry with the given material and lights if it is not in the cache,
+/// or starts the compilation if parallel shader compilation is enabled.
+///
+fn prepare_program(
+    context: &Context,
+    geometry: &dyn Geometry,
+    material: &dyn Material,
+    lights: &[&dyn Light],
+) -> Result<(), RendererError> {
+    let id = combine_ids(geometry.id(), material.id(), lights.iter().map(|l| l.id()));
+    if context.parallel_shader_compilation() {
+        is_program_ready(
+            context,
+            &id,
+            || geometry.vertex_shader_source(),
+            || material.fragment_shader_source(lights),
+            false,
+        )?;
+    } else if let std::collections::hash_map::Entry::Vacant(entry) =
+        context.programs.write().unwrap().entry(id)
+    {
+        entry.insert(Program::from_source(
+            context,
+            &geometry.vertex_shader_source(),
+            &material.fragment_shader_source(lights),
+        )?);
+    }
     Ok(())
 }
 
@@ -459,6 +912,7 @@ pub fn render_with_effect(
 
     let mut programs = context.programs.write().unwrap();
     if !programs.contains_key(&id) {
+        count_lazy_program_compilation(context, &id);
         programs.insert(
             id.clone(),
             Program::from_source(
@@ -470,7 +924,9 @@ pub fn render_with_effect(
     }
     let program = programs.get(&id).unwrap();
     effect.use_uniforms(program, &viewer, lights, color_texture, depth_texture);
-    geometry.draw(&viewer, program, effect.render_states());
+    draw_with_depth_mode(context, &viewer, effect.render_states(), |render_states| {
+        geometry.draw(&viewer, program, render_states)
+    });
     Ok(())
 }
 
@@ -485,6 +941,7 @@ pub fn apply_screen_material(
     viewer: impl Viewer,
     lights: &[&dyn Light],
 ) {
+    trace_span!("three_d::apply_screen_material", id = material.id().0);
     let id = combine_ids(
         GeometryId::Screen,
         material.id(),
@@ -503,12 +960,11 @@ pub fn apply_screen_material(
         }
     });
     material.use_uniforms(program, &viewer, lights);
-    full_screen_draw(
-        context,
-        program,
-        material.render_states(),
-        viewer.viewport(),
-    );
+    let mut render_states = material.render_states();
+    if viewer.depth_mode().is_reversed() {
+        render_states.depth_test = render_states.depth_test.reversed();
+    }
+    full_screen_draw(context, program, render_states, viewer.viewport());
 }
 
 ///
@@ -524,6 +980,10 @@ pub fn apply_screen_effect(
     color_texture: Option<ColorTexture>,
     depth_texture: Option<DepthTexture>,
 ) {
+    trace_span!(
+        "three_d::apply_screen_effect",
+        id = effect.id(color_texture, depth_texture).0
+    );
     let id = combine_ids(
         GeometryId::Screen,
         effect.id(color_texture, depth_texture),
@@ -542,7 +1002,34 @@ pub fn apply_screen_effect(
         }
     });
     effect.use_uniforms(program, &viewer, lights, color_texture, depth_texture);
-    full_screen_draw(context, program, effect.render_states(), viewer.viewport());
+    let mut render_states = effect.render_states();
+    if viewer.depth_mode().is_reversed() {
+        render_states.depth_test = render_states.depth_test.reversed();
+    }
+    full_screen_draw(context, program, render_states, viewer.viewport());
+}
+
+///
+/// Reverses the depth test of the given render states if the viewer uses a reversed [DepthMode]
+/// and enables the zero to one clip space depth range while drawing if needed.
+///
+fn draw_with_depth_mode(
+    context: &Context,
+    viewer: &impl Viewer,
+    mut render_states: RenderStates,
+    draw: impl FnOnce(RenderStates),
+) {
+    let depth_mode = viewer.depth_mode();
+    if depth_mode.is_reversed() {
+        render_states.depth_test = render_states.depth_test.reversed();
+    }
+    if depth_mode == DepthMode::ReversedZeroToOne {
+        context.set_zero_to_one_depth(true);
+        draw(render_states);
+        context.set_zero_to_one_depth(false);
+    } else {
+        draw(render_states);
+    }
 }
 
 ///
@@ -577,25 +1064,24 @@ pub fn cmp_render_order(
 }
 
 ///
-/// Finds the closest intersection between a ray from the given camera in the given pixel coordinate and the given geometries.
+/// Finds the closest intersection between a ray from the given viewer, for example a [Camera] or a [MatrixViewer], in the given pixel coordinate and the given geometries.
 /// The pixel coordinate must be in physical pixels, where (viewport.x, viewport.y) indicate the bottom left corner of the viewport
 /// and (viewport.x + viewport.width, viewport.y + viewport.height) indicate the top right corner.
-/// Returns ```None``` if no geometry was hit between the near (`z_near`) and far (`z_far`) plane for this camera.
+/// Returns ```None``` if no geometry was hit between the near (`z_near`) and far (`z_far`) plane for this viewer.
 ///
 pub fn pick(
     context: &Context,
-    camera: &three_d_asset::Camera,
+    viewer: impl Viewer,
     pixel: impl Into<PhysicalPoint> + Copy,
     geometries: impl IntoIterator<Item = impl Geometry>,
     culling: Cull,
 ) -> Result<Option<IntersectionResult>, RendererError> {
-    let pos = camera.position_at_pixel(pixel);
-    let dir = camera.view_direction_at_pixel(pixel);
+    let (near, far) = unproject_pixel(&viewer, pixel.into());
     ray_intersect(
         context,
-        pos + dir * camera.z_near(),
-        dir,
-        camera.z_far() - camera.z_near(),
+        near,
+        (far - near).normalize(),
+        near.distance(far),
         geometries,
         culling,
     )
@@ -613,6 +1099,15 @@ pub struct IntersectionResult {
     pub instance_id: u32,
 }
 
+impl IntersectionResult {
+    ///
+    /// Returns the position of the intersection in world space in double precision, see [Context::set_world_origin].
+    ///
+    pub fn world_position(&self, context: &Context) -> DVec3 {
+        context.to_world_space(self.position)
+    }
+}
+
 ///
 /// Finds the closest intersection between a ray starting at the given position in the given direction and the given geometries.
 /// Returns ```None``` if no geometry was hit before the given maximum depth.
@@ -641,23 +1136,12 @@ pub fn ray_intersect(
         0.0,
         max_depth,
     );
-    let mut texture = Texture2D::new_empty::<[f32; 4]>(
-        context,
+    let mut texture = context.request_texture::<[f32; 4]>(
         viewport.width,
         viewport.height,
         Interpolation::Nearest,
-        Interpolation::Nearest,
-        None,
-        Wrapping::ClampToEdge,
-        Wrapping::ClampToEdge,
-    );
-    let mut depth_texture = DepthTexture2D::new::<f32>(
-        context,
-        viewport.width,
-        viewport.height,
-        Wrapping::ClampToEdge,
-        Wrapping::ClampToEdge,
     );
+    let mut depth_texture = context.request_depth_texture::<f32>(viewport.width, viewport.height);
     let mut material = IntersectionMaterial {
         ..Default::default()
     };
@@ -670,7 +1154,7 @@ pub fn ray_intersect(
     .write::<RendererError>(|| {
         for (id, geometry) in geometries.into_iter().enumerate() {
             material.geometry_id = id as u32;
-            render_with_material(context, &camera, &geometry, &material, &[])?;
+            render_with_material_internal(context, &camera, &geometry, &material, &[], false)?;
         }
         Ok(())
     })?
@@ -721,4 +1205,12 @@ impl<T: Viewer> Viewer for GeometryPassCamera<T> {
     fn tone_mapping(&self) -> ToneMapping {
         self.0.tone_mapping()
     }
+
+    fn depth_mode(&self) -> DepthMode {
+        self.0.depth_mode()
+    }
+
+    fn exposure(&self) -> f32 {
+        self.0.exposure()
+    }
 }
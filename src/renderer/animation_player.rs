@@ -0,0 +1,498 @@
+//!
+//! Sampling of the key frame animations in a scene into the transformations of the joints of a skeleton, see [AnimationPlayer].
+//!
+
+use crate::renderer::*;
+use std::ops::{Add, Mul};
+
+pub use three_d_asset::{Node as CpuNode, Scene as CpuScene};
+
+///
+/// Defines what happens when an animation clip is sampled at a time outside the duration of the clip, see [AnimationPlayer::wrap].
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum AnimationWrap {
+    /// The clip is repeated.
+    #[default]
+    Loop,
+    /// The clip holds the first pose before the start and the last pose after the end.
+    Clamp,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct JointPose {
+    translation: Vec3,
+    rotation: Quat,
+    scale: Vec3,
+}
+
+impl JointPose {
+    fn from_matrix(matrix: Mat4) -> Self {
+        let scale = vec3(
+            matrix.x.truncate().magnitude(),
+            matrix.y.truncate().magnitude(),
+            matrix.z.truncate().magnitude(),
+        );
+        let safe = |s: f32| if s != 0.0 { s } else { 1.0 };
+        Self {
+            translation: matrix.w.truncate(),
+            rotation: Quat::from(Mat3::from_cols(
+                matrix.x.truncate() / safe(scale.x),
+                matrix.y.truncate() / safe(scale.y),
+                matrix.z.truncate() / safe(scale.z),
+            ))
+            .normalize(),
+            scale,
+        }
+    }
+
+    fn matrix(&self) -> Mat4 {
+        Mat4::from_translation(self.translation)
+            * Mat4::from(self.rotation)
+            * Mat4::from_nonuniform_scale(self.scale.x, self.scale.y, self.scale.z)
+    }
+
+    fn blend(&self, other: &Self, weight: f32) -> Self {
+        Self {
+            translation: self.translation.lerp(other.translation, weight),
+            rotation: nlerp(self.rotation, other.rotation, weight),
+            scale: self.scale.lerp(other.scale, weight),
+        }
+    }
+}
+
+struct AnimationClip {
+    name: Option<String>,
+    duration: f32,
+    tracks: Vec<(usize, KeyFrames)>,
+}
+
+///
+/// Samples the key frame animations of a [CpuScene], for example loaded from a glTF file, into the transformation of each node at a given time,
+/// which can then be used to animate a [SkinnedMesh] or visualize the skeleton with a [SkeletonGizmo].
+///
+/// Each node in the scene is a joint and the joints are indexed in depth-first order, see [AnimationPlayer::joint_index] for looking up a joint by name.
+/// The animations are grouped into clips by their name, where each clip contains the translation, rotation and scale tracks for some of the joints,
+/// and the joints without a track keep the transformation of the node.
+/// The tracks are sampled using the interpolation of the key frames, ie. step, linear or cubic spline interpolation.
+/// The morph weights animated by a clip are sampled the same way, see [AnimationPlayer::morph_weights].
+///
+/// ```
+/// # use three_d::*;
+/// let scene = CpuScene {
+///     children: vec![CpuNode {
+///         name: "root".to_string(),
+///         animations: vec![(
+///             Some("lift".to_string()),
+///             KeyFrames {
+///                 times: vec![0.0, 1.0],
+///                 translations: Some(vec![vec3(0.0, 0.0, 0.0), vec3(0.0, 2.0, 0.0)]),
+///                 ..Default::default()
+///             },
+///         )],
+///         children: vec![CpuNode {
+///             name: "child".to_string(),
+///             transformation: Mat4::from_translation(vec3(1.0, 0.0, 0.0)),
+///             ..Default::default()
+///         }],
+///         ..Default::default()
+///     }],
+///     ..Default::default()
+/// };
+/// let mut player = AnimationPlayer::from_scene(&scene);
+/// let clip = player.clip_index("lift").unwrap();
+/// let child = player.joint_index("child").unwrap();
+///
+/// // Halfway through the clip, the child is moved along with the root
+/// let transformations = player.joint_transformations(clip, 0.5);
+/// assert_eq!(transformations[child].w.truncate(), vec3(1.0, 1.0, 0.0));
+///
+/// // The joint matrices are relative to the rest pose
+/// let joint_matrices = player.joint_matrices(clip, 0.5);
+/// assert_eq!(joint_matrices[child], Mat4::from_translation(vec3(0.0, 1.0, 0.0)));
+///
+/// // Clamping instead of looping holds the last pose
+/// player.wrap = AnimationWrap::Clamp;
+/// let transformations = player.joint_transformations(clip, 1.5);
+/// assert_eq!(transformations[child].w.truncate(), vec3(1.0, 2.0, 0.0));
+/// ```
+///
+pub struct AnimationPlayer {
+    names: Vec<String>,
+    parents: Vec<Option<usize>>,
+    rest_poses: Vec<JointPose>,
+    inverse_bind_matrices: Vec<Mat4>,
+    clips: Vec<AnimationClip>,
+    /// Defines what happens when a clip is sampled at a time outside the duration of the clip.
+    pub wrap: AnimationWrap,
+}
+
+impl AnimationPlayer {
+    ///
+    /// Creates a new animation player for the given scene, where each node is a joint.
+    /// The inverse bind matrices are the inverse of the transformation of each node from the joint space to the space of the scene,
+    /// ie. the skinned mesh is assumed to be bound to the skeleton in the pose given by the node transformations with the vertices in the space of the scene.
+    /// Use [AnimationPlayer::set_inverse_bind_matrices] if the skin of the mesh specifies other inverse bind matrices.
+    ///
+    pub fn from_scene(scene: &CpuScene) -> Self {
+        let mut player = Self {
+            names: Vec::new(),
+            parents: Vec::new(),
+            rest_poses: Vec::new(),
+            inverse_bind_matrices: Vec::new(),
+            clips: Vec::new(),
+            wrap: AnimationWrap::default(),
+        };
+        for node in scene.children.iter() {
+            player.add_joint(node, None);
+        }
+        let rest_poses = player.rest_poses.clone();
+        player.inverse_bind_matrices = player
+            .global_transformations(&rest_poses)
+            .into_iter()
+            .map(|m| m.invert().unwrap_or(Mat4::identity()))
+            .collect();
+        player
+    }
+
+    fn add_joint(&mut self, node: &CpuNode, parent: Option<usize>) {
+        let joint = self.names.len();
+        self.names.push(node.name.clone());
+        self.parents.push(parent);
+        self.rest_poses
+            .push(JointPose::from_matrix(node.transformation));
+        for (name, key_frames) in node.animations.iter() {
+            let clip = match self.clips.iter().position(|clip| &clip.name == name) {
+                Some(clip) => clip,
+                None => {
+                    self.clips.push(AnimationClip {
+                        name: name.clone(),
+                        duration: 0.0,
+                        tracks: Vec::new(),
+                    });
+                    self.clips.len() - 1
+                }
+            };
+            let clip = &mut self.clips[clip];
+            clip.duration = clip
+                .duration
+                .max(key_frames.times.last().copied().unwrap_or(0.0));
+            clip.tracks.push((joint, key_frames.clone()));
+        }
+        for child in node.children.iter() {
+            self.add_joint(child, Some(joint));
+        }
+    }
+
+    ///
+    /// Returns the number of joints, ie. the number of nodes in the scene.
+    ///
+    pub fn joint_count(&self) -> usize {
+        self.names.len()
+    }
+
+    ///
+    /// Returns the index of the first joint with the given name, which for example can be used to map the joint indices of a [SkinnedMesh] to the joints of this player.
+    ///
+    pub fn joint_index(&self, name: &str) -> Option<usize> {
+        self.names.iter().position(|n| n == name)
+    }
+
+    ///
+    /// Returns the index of the parent of each joint, or `None` if the joint is a root, for example for constructing a [SkeletonGizmo].
+    ///
+    pub fn parents(&self) -> &[Option<usize>] {
+        &self.parents
+    }
+
+    ///
+    /// Sets the inverse bind matrices, ie. the transformation from the space of the skinned mesh to the space of each joint in the bind pose.
+    ///
+    /// # Panic
+    /// Will panic if the number of matrices is not the same as the number of joints.
+    ///
+    pub fn set_inverse_bind_matrices(&mut self, inverse_bind_matrices: &[Mat4]) {
+        assert_eq!(
+            inverse_bind_matrices.len(),
+            self.joint_count(),
+            "the number of inverse bind matrices must be the same as the number of joints"
+        );
+        self.inverse_bind_matrices = inverse_bind_matrices.to_vec();
+    }
+
+    ///
+    /// Returns the name of each clip, where `None` is used for the animations without a name.
+    ///
+    pub fn clip_names(&self) -> impl Iterator<Item = Option<&str>> {
+        self.clips.iter().map(|clip| clip.name.as_deref())
+    }
+
+    ///
+    /// Returns the index of the clip with the given name.
+    ///
+    pub fn clip_index(&self, name: &str) -> Option<usize> {
+        self.clips
+            .iter()
+            .position(|clip| clip.name.as_deref() == Some(name))
+    }
+
+    ///
+    /// Returns the duration in seconds of the clip with the given index, ie. the time of the last key frame.
+    ///
+    /// # Panic
+    /// Will panic if there is no clip with the given index.
+    ///
+    pub fn duration(&self, clip: usize) -> f32 {
+        self.clips[clip].duration
+    }
+
+    ///
+    /// Returns the transformation of each joint from the joint space to the space of the scene at the given time in seconds of the clip with the given index.
+    ///
+    /// # Panic
+    /// Will panic if there is no clip with the given index.
+    ///
+    pub fn joint_transformations(&self, clip: usize, time: f32) -> Vec<Mat4> {
+        self.global_transformations(&self.sample(clip, time))
+    }
+
+    ///
+    /// Returns the transformation of each joint like [AnimationPlayer::joint_transformations], but blended between the pose of two clips,
+    /// where a weight of `0.0` gives the pose of the first clip and `1.0` gives the pose of the second clip.
+    /// The translations and scales are blended linearly and the rotations are blended along the shortest path.
+    ///
+    /// # Panic
+    /// Will panic if there is no clip with one of the given indices.
+    ///
+    pub fn blended_joint_transformations(
+        &self,
+        clip_a: usize,
+        time_a: f32,
+        clip_b: usize,
+        time_b: f32,
+        weight: f32,
+    ) -> Vec<Mat4> {
+        let poses = self
+            .sample(clip_a, time_a)
+            .iter()
+            .zip(self.sample(clip_b, time_b))
+            .map(|(a, b)| a.blend(&b, weight.clamp(0.0, 1.0)))
+            .collect::<Vec<_>>();
+        self.global_transformations(&poses)
+    }
+
+    ///
+    /// Returns the joint matrix of each joint at the given time in seconds of the clip with the given index,
+    /// ie. the transformation from the bind pose to the current pose which can be given to [SkinnedMesh::set_joint_matrices].
+    ///
+    /// # Panic
+    /// Will panic if there is no clip with the given index.
+    ///
+    pub fn joint_matrices(&self, clip: usize, time: f32) -> Vec<Mat4> {
+        self.joint_matrices_from(&self.joint_transformations(clip, time))
+    }
+
+    ///
+    /// Returns the joint matrix of each joint like [AnimationPlayer::joint_matrices], but blended between the pose of two clips,
+    /// see [AnimationPlayer::blended_joint_transformations].
+    ///
+    /// # Panic
+    /// Will panic if there is no clip with one of the given indices.
+    ///
+    pub fn blended_joint_matrices(
+        &self,
+        clip_a: usize,
+        time_a: f32,
+        clip_b: usize,
+        time_b: f32,
+        weight: f32,
+    ) -> Vec<Mat4> {
+        self.joint_matrices_from(
+            &self.blended_joint_transformations(clip_a, time_a, clip_b, time_b, weight),
+        )
+    }
+
+    ///
+    /// Returns the joint matrices from the given joint transformations, for example the result of [AnimationPlayer::joint_transformations]
+    /// after adjusting some of the joints, ie. each transformation multiplied by the inverse bind matrix of the joint.
+    ///
+    pub fn joint_matrices_from(&self, joint_transformations: &[Mat4]) -> Vec<Mat4> {
+        joint_transformations
+            .iter()
+            .zip(self.inverse_bind_matrices.iter())
+            .map(|(transformation, inverse_bind_matrix)| transformation * inverse_bind_matrix)
+            .collect()
+    }
+
+    ///
+    /// Returns the morph weights of the node with the given joint index at the given time in seconds of the clip with the given index,
+    /// which can be given to [Mesh::set_morph_weights] of the mesh of the node.
+    /// Returns `None` if the clip does not animate the morph weights of the node.
+    ///
+    /// ```
+    /// # use three_d::*;
+    /// let scene = CpuScene {
+    ///     children: vec![CpuNode {
+    ///         name: "face".to_string(),
+    ///         animations: vec![(
+    ///             Some("smile".to_string()),
+    ///             KeyFrames {
+    ///                 times: vec![0.0, 2.0],
+    ///                 weights: Some(vec![vec![0.0, 1.0], vec![1.0, 0.0]]),
+    ///                 ..Default::default()
+    ///             },
+    ///         )],
+    ///         ..Default::default()
+    ///     }],
+    ///     ..Default::default()
+    /// };
+    /// let player = AnimationPlayer::from_scene(&scene);
+    /// let clip = player.clip_index("smile").unwrap();
+    /// let face = player.joint_index("face").unwrap();
+    /// assert_eq!(player.morph_weights(clip, face, 0.5), Some(vec![0.25, 0.75]));
+    /// ```
+    ///
+    /// # Panic
+    /// Will panic if there is no clip with the given index.
+    ///
+    pub fn morph_weights(&self, clip: usize, joint: usize, time: f32) -> Option<Vec<f32>> {
+        let time = self.clip_time(clip, time);
+        let (_, key_frames) = self.clips[clip]
+            .tracks
+            .iter()
+            .find(|(j, key_frames)| *j == joint && key_frames.weights.is_some())?;
+        let values = key_frames.weights.as_ref()?;
+        // The weights of a cubic spline key frame are all the in-tangents, then all the values and then all the out-tangents
+        let cubic = key_frames.interpolation == Interpolation::CubicSpline;
+        let count = if cubic {
+            values.first()?.len() / 3
+        } else {
+            values.first()?.len()
+        };
+        let weight = |weights: &Vec<f32>, i: usize| weights.get(i).copied().unwrap_or(0.0);
+        (0..count)
+            .map(|i| {
+                let values = values
+                    .iter()
+                    .flat_map(|weights| {
+                        if cubic {
+                            vec![
+                                weight(weights, i),
+                                weight(weights, count + i),
+                                weight(weights, 2 * count + i),
+                            ]
+                        } else {
+                            vec![weight(weights, i)]
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                sample(
+                    &key_frames.times,
+                    &values,
+                    key_frames.interpolation,
+                    time,
+                    |a, b, t| a + (b - a) * t,
+                )
+            })
+            .collect()
+    }
+
+    fn clip_time(&self, clip: usize, time: f32) -> f32 {
+        let clip = &self.clips[clip];
+        if clip.duration <= 0.0 {
+            0.0
+        } else {
+            match self.wrap {
+                AnimationWrap::Loop => time.rem_euclid(clip.duration),
+                AnimationWrap::Clamp => time.clamp(0.0, clip.duration),
+            }
+        }
+    }
+
+    fn sample(&self, clip: usize, time: f32) -> Vec<JointPose> {
+        let time = self.clip_time(clip, time);
+        let clip = &self.clips[clip];
+        let mut poses = self.rest_poses.clone();
+        for (joint, key_frames) in clip.tracks.iter() {
+            let pose = &mut poses[*joint];
+            let times = &key_frames.times;
+            let interpolation = key_frames.interpolation;
+            if let Some(translation) = key_frames.translations.as_ref().and_then(|values| {
+                sample(times, values, interpolation, time, |a, b, t| a.lerp(b, t))
+            }) {
+                pose.translation = translation;
+            }
+            if let Some(rotation) = key_frames
+                .rotations
+                .as_ref()
+                .and_then(|values| sample(times, values, interpolation, time, nlerp))
+            {
+                pose.rotation = rotation.normalize();
+            }
+            if let Some(scale) = key_frames.scales.as_ref().and_then(|values| {
+                sample(times, values, interpolation, time, |a, b, t| a.lerp(b, t))
+            }) {
+                pose.scale = scale;
+            }
+        }
+        poses
+    }
+
+    fn global_transformations(&self, poses: &[JointPose]) -> Vec<Mat4> {
+        // The joints are in depth-first order, so the parent of a joint is always before the joint
+        let mut transformations: Vec<Mat4> = Vec::with_capacity(poses.len());
+        for (pose, parent) in poses.iter().zip(self.parents.iter()) {
+            let local = pose.matrix();
+            transformations.push(match parent {
+                Some(parent) => transformations[*parent] * local,
+                None => local,
+            });
+        }
+        transformations
+    }
+}
+
+fn nlerp(a: Quat, b: Quat, t: f32) -> Quat {
+    let b = if a.dot(b) < 0.0 { -b } else { b };
+    (a * (1.0 - t) + b * t).normalize()
+}
+
+// Samples the values at the given time, where the values of a cubic spline are given as an in-tangent, a value and an out-tangent for each key frame
+fn sample<T: Copy + Add<Output = T> + Mul<f32, Output = T>>(
+    times: &[f32],
+    values: &[T],
+    interpolation: Interpolation,
+    time: f32,
+    lerp: impl Fn(T, T, f32) -> T,
+) -> Option<T> {
+    let cubic = interpolation == Interpolation::CubicSpline && values.len() == 3 * times.len();
+    if times.is_empty() || (!cubic && values.len() < times.len()) {
+        return None;
+    }
+    let value = |i: usize| if cubic { values[3 * i + 1] } else { values[i] };
+    let next = times.partition_point(|t| *t <= time);
+    if next == 0 {
+        return Some(value(0));
+    }
+    if next == times.len() {
+        return Some(value(times.len() - 1));
+    }
+    let i = next - 1;
+    let dt = times[next] - times[i];
+    let t = if dt > 0.0 {
+        (time - times[i]) / dt
+    } else {
+        0.0
+    };
+    Some(match interpolation {
+        Interpolation::Nearest => value(i),
+        Interpolation::CubicSpline if cubic => {
+            let (t2, t3) = (t * t, t * t * t);
+            value(i) * (2.0 * t3 - 3.0 * t2 + 1.0)
+                + values[3 * i + 2] * ((t3 - 2.0 * t2 + t) * dt)
+                + value(next) * (3.0 * t2 - 2.0 * t3)
+                + values[3 * next] * ((t3 - t2) * dt)
+        }
+        _ => lerp(value(i), value(next), t),
+    })
+}
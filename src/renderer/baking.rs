@@ -0,0 +1,266 @@
+//!
+//! Utilities for precomputing (baking) expensive lighting effects on the CPU, for example ambient occlusion, for static geometry.
+//!
+
+use crate::renderer::*;
+
+mod bvh;
+#[doc(inline)]
+pub use bvh::*;
+
+mod ambient_occlusion;
+#[doc(inline)]
+pub use ambient_occlusion::*;
+
+mod lightmap;
+#[doc(inline)]
+pub use lightmap::*;
+
+mod curvature;
+#[doc(inline)]
+pub use curvature::*;
+
+mod uv_unwrap;
+#[doc(inline)]
+pub use uv_unwrap::*;
+
+///
+/// Calls the given function for each index in `0..count` distributed over the available threads and returns the results in order.
+/// The progress callback is called with the fraction of finished work, between 0 and 1, each time a chunk of work is done.
+///
+fn parallel_map<T: Send>(
+    count: usize,
+    progress: &(impl Fn(f32) + Sync),
+    function: impl Fn(usize) -> T + Sync,
+) -> Vec<T> {
+    const CHUNK_SIZE: usize = 64;
+    let chunk_count = count.div_ceil(CHUNK_SIZE);
+    let run_chunk = |chunk: usize| {
+        (chunk * CHUNK_SIZE..((chunk + 1) * CHUNK_SIZE).min(count))
+            .map(&function)
+            .collect::<Vec<_>>()
+    };
+
+    #[cfg(target_arch = "wasm32")]
+    let chunks = (0..chunk_count)
+        .map(|chunk| {
+            let result = run_chunk(chunk);
+            progress((chunk + 1) as f32 / chunk_count as f32);
+            result
+        })
+        .collect::<Vec<_>>();
+
+    #[cfg(not(target_arch = "wasm32"))]
+    let chunks = {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        let thread_count = std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1)
+            .min(chunk_count.max(1));
+        let next_chunk = AtomicUsize::new(0);
+        let finished_chunks = AtomicUsize::new(0);
+        let mut chunks = std::thread::scope(|scope| {
+            let handles = (0..thread_count)
+                .map(|_| {
+                    scope.spawn(|| {
+                        let mut results = Vec::new();
+                        loop {
+                            let chunk = next_chunk.fetch_add(1, Ordering::Relaxed);
+                            if chunk >= chunk_count {
+                                break;
+                            }
+                            results.push((chunk, run_chunk(chunk)));
+                            let finished = finished_chunks.fetch_add(1, Ordering::Relaxed) + 1;
+                            progress(finished as f32 / chunk_count as f32);
+                        }
+                        results
+                    })
+                })
+                .collect::<Vec<_>>();
+            handles
+                .into_iter()
+                .flat_map(|handle| handle.join().unwrap())
+                .collect::<Vec<_>>()
+        });
+        chunks.sort_by_key(|(chunk, _)| *chunk);
+        chunks
+            .into_iter()
+            .map(|(_, result)| result)
+            .collect::<Vec<_>>()
+    };
+
+    chunks.into_iter().flatten().collect()
+}
+
+///
+/// Returns a key which is the same for equal positions, including zero and negative zero, for example to find the vertices at the same position.
+///
+fn position_key(position: Vec3) -> [u32; 3] {
+    [position.x, position.y, position.z].map(|v| (v + 0.0).to_bits())
+}
+
+///
+/// Returns a point in the unit square from a low-discrepancy sequence (Hammersley), randomly offset by the given seed.
+///
+fn sample_2d(index: u32, count: u32, seed: u32) -> Vec2 {
+    let hash = |mut x: u32| {
+        x ^= x >> 16;
+        x = x.wrapping_mul(0x7feb352d);
+        x ^= x >> 15;
+        x = x.wrapping_mul(0x846ca68b);
+        x ^= x >> 16;
+        x as f32 / u32::MAX as f32
+    };
+    let u = (index as f32 + 0.5) / count as f32 + hash(seed);
+    let v = index.reverse_bits() as f32 / u32::MAX as f32 + hash(seed ^ 0x9e3779b9);
+    vec2(u.fract(), v.fract())
+}
+
+///
+/// Returns a cosine weighted direction in the hemisphere around the given normal.
+///
+fn cosine_weighted_direction(normal: Vec3, sample: Vec2) -> Vec3 {
+    let radius = sample.x.sqrt();
+    let angle = 2.0 * std::f32::consts::PI * sample.y;
+    let tangent = if normal.x.abs() > 0.9 {
+        vec3(0.0, 1.0, 0.0)
+    } else {
+        vec3(1.0, 0.0, 0.0)
+    }
+    .cross(normal)
+    .normalize();
+    let bitangent = normal.cross(tangent);
+    (tangent * radius * angle.cos()
+        + bitangent * radius * angle.sin()
+        + normal * (1.0 - sample.x).max(0.0).sqrt())
+    .normalize()
+}
+
+///
+/// Returns the vertex normals of the mesh, which are computed if the mesh does not have any.
+///
+fn vertex_normals(mesh: &CpuMesh) -> Vec<Vec3> {
+    if let Some(normals) = &mesh.normals {
+        normals.clone()
+    } else {
+        let mut mesh = mesh.clone();
+        mesh.compute_normals();
+        mesh.normals.unwrap()
+    }
+}
+
+///
+/// Returns the distance the ray origin is offset along the normal to avoid self intersections.
+///
+fn ray_bias(mesh: &CpuMesh) -> f32 {
+    1e-4 * mesh.compute_aabb().size().magnitude().max(f32::EPSILON)
+}
+
+///
+/// A sample point on the surface of a mesh corresponding to a texel in a texture.
+///
+struct TexelSample {
+    texel: usize,
+    position: Vec3,
+    normal: Vec3,
+}
+
+///
+/// Finds the surface position and normal for each texel in a texture with the given size which center is covered by one of the given triangles in uv space.
+///
+fn rasterize_uvs(
+    positions: &[Vec3],
+    normals: &[Vec3],
+    uvs: &[Vec2],
+    triangles: impl Iterator<Item = [u32; 3]>,
+    width: u32,
+    height: u32,
+) -> Vec<TexelSample> {
+    let mut samples = Vec::new();
+    for triangle in triangles {
+        let [i0, i1, i2] = triangle.map(|i| i as usize);
+        // Texel coordinates where the v axis is flipped, since the first row in a texture is the top row
+        let to_texel = |uv: Vec2| vec2(uv.x * width as f32, (1.0 - uv.y) * height as f32);
+        let (t0, t1, t2) = (to_texel(uvs[i0]), to_texel(uvs[i1]), to_texel(uvs[i2]));
+        let area = (t1 - t0).perp_dot(t2 - t0);
+        if area.abs() < 1e-12 {
+            continue;
+        }
+        let x0 = t0.x.min(t1.x).min(t2.x).floor().max(0.0) as u32;
+        let x1 = (t0.x.max(t1.x).max(t2.x).ceil() as u32).min(width);
+        let y0 = t0.y.min(t1.y).min(t2.y).floor().max(0.0) as u32;
+        let y1 = (t0.y.max(t1.y).max(t2.y).ceil() as u32).min(height);
+        for y in y0..y1 {
+            for x in x0..x1 {
+                let p = vec2(x as f32 + 0.5, y as f32 + 0.5);
+                let b0 = (t1 - p).perp_dot(t2 - p) / area;
+                let b1 = (t2 - p).perp_dot(t0 - p) / area;
+                let b2 = 1.0 - b0 - b1;
+                if b0 < 0.0 || b1 < 0.0 || b2 < 0.0 {
+                    continue;
+                }
+                samples.push(TexelSample {
+                    texel: (y * width + x) as usize,
+                    position: positions[i0] * b0 + positions[i1] * b1 + positions[i2] * b2,
+                    normal: (normals[i0] * b0 + normals[i1] * b1 + normals[i2] * b2).normalize(),
+                });
+            }
+        }
+    }
+    samples
+}
+
+///
+/// Fills the texels that are not covered with the average of the covered neighbouring texels, repeated the given number of times,
+/// to avoid seams when the texture is sampled with linear interpolation near the edge of an uv island.
+///
+fn dilate<T: Copy + Zero + std::ops::Mul<f32, Output = T>>(
+    data: &mut [T],
+    covered: &mut [bool],
+    width: u32,
+    height: u32,
+    iterations: u32,
+) {
+    let (width, height) = (width as i64, height as i64);
+    for _ in 0..iterations {
+        let mut new_texels = Vec::new();
+        for y in 0..height {
+            for x in 0..width {
+                if covered[(y * width + x) as usize] {
+                    continue;
+                }
+                let mut sum = T::zero();
+                let mut count = 0;
+                for (dx, dy) in [
+                    (-1, 0),
+                    (1, 0),
+                    (0, -1),
+                    (0, 1),
+                    (-1, -1),
+                    (1, -1),
+                    (-1, 1),
+                    (1, 1),
+                ] {
+                    let (nx, ny) = (x + dx, y + dy);
+                    if nx >= 0 && ny >= 0 && nx < width && ny < height {
+                        let neighbour = (ny * width + nx) as usize;
+                        if covered[neighbour] {
+                            sum = sum + data[neighbour];
+                            count += 1;
+                        }
+                    }
+                }
+                if count > 0 {
+                    new_texels.push(((y * width + x) as usize, sum * (1.0 / count as f32)));
+                }
+            }
+        }
+        if new_texels.is_empty() {
+            break;
+        }
+        for (texel, value) in new_texels {
+            data[texel] = value;
+            covered[texel] = true;
+        }
+    }
+}
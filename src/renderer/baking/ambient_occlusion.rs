@@ -0,0 +1,205 @@
+use super::*;
+
+///
+/// Settings for baking ambient occlusion, see [bake_ambient_occlusion_texture] and [bake_ambient_occlusion_vertex_colors].
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AmbientOcclusionSettings {
+    /// The number of rays traced in the hemisphere above each texel or vertex. Higher is less noisy but slower.
+    /// The default is 64.
+    pub ray_count: u32,
+    /// The maximum distance to an occluder, surfaces further away do not contribute to the occlusion.
+    /// The default is 1.
+    pub max_distance: f32,
+    /// The number of texels to extend the baked result beyond the edges of the uv islands to avoid seams.
+    /// Only used when baking to a texture. The default is 4.
+    pub dilation: u32,
+}
+
+impl Default for AmbientOcclusionSettings {
+    fn default() -> Self {
+        Self {
+            ray_count: 64,
+            max_distance: 1.0,
+            dilation: 4,
+        }
+    }
+}
+
+///
+/// Bakes the ambient occlusion of the given mesh into a single channel texture of the given size using the uv coordinates of the mesh,
+/// which should not contain overlapping triangles.
+/// The occlusion is found by tracing cosine weighted rays in the hemisphere above each texel and the result is 1 if nothing is occluded and 0 if everything is occluded.
+/// The returned texture can be used as the occlusion texture of for example a [PhysicalMaterial] (see [CpuMaterial::occlusion_texture]).
+///
+/// The work is distributed over the available threads and the progress callback is called with the fraction of the work done, between 0 and 1.
+///
+pub fn bake_ambient_occlusion_texture(
+    mesh: &CpuMesh,
+    width: u32,
+    height: u32,
+    settings: AmbientOcclusionSettings,
+    progress: impl Fn(f32) + Sync,
+) -> Result<CpuTexture, RendererError> {
+    let uvs = mesh
+        .uvs
+        .as_ref()
+        .ok_or(RendererError::MissingUvCoordinates)?;
+    let positions = mesh.positions.to_f32();
+    let normals = vertex_normals(mesh);
+    let bvh = Bvh::new(mesh);
+    let bias = ray_bias(mesh);
+
+    let samples = rasterize_uvs(
+        &positions,
+        &normals,
+        uvs,
+        (0..bvh.triangle_count()).map(|t| bvh.triangle_indices(t)),
+        width,
+        height,
+    );
+    let occlusion = parallel_map(samples.len(), &progress, |i| {
+        let sample = &samples[i];
+        ambient_occlusion(
+            &bvh,
+            sample.position,
+            sample.normal,
+            bias,
+            i as u32,
+            &settings,
+        )
+    });
+
+    let mut data = vec![1.0; (width * height) as usize];
+    let mut covered = vec![false; data.len()];
+    for (sample, occlusion) in samples.iter().zip(occlusion) {
+        data[sample.texel] = occlusion;
+        covered[sample.texel] = true;
+    }
+    dilate(&mut data, &mut covered, width, height, settings.dilation);
+
+    Ok(CpuTexture {
+        name: "ambient occlusion".to_string(),
+        data: TextureData::RU8(
+            data.into_iter()
+                .map(|v| (v.clamp(0.0, 1.0) * 255.0).round() as u8)
+                .collect(),
+        ),
+        width,
+        height,
+        ..Default::default()
+    })
+}
+
+///
+/// Bakes the ambient occlusion of the given mesh into the vertex colors of the mesh, replacing any existing colors.
+/// This does not require uv coordinates but the quality depends on the density of the vertices.
+/// The occlusion is found by tracing cosine weighted rays in the hemisphere above each vertex and the result is white if nothing is occluded and black if everything is occluded.
+///
+/// The work is distributed over the available threads and the progress callback is called with the fraction of the work done, between 0 and 1.
+///
+pub fn bake_ambient_occlusion_vertex_colors(
+    mesh: &mut CpuMesh,
+    settings: AmbientOcclusionSettings,
+    progress: impl Fn(f32) + Sync,
+) {
+    let occlusion = compute_vertex_ambient_occlusion(mesh, settings, progress);
+    mesh.colors = Some(
+        occlusion
+            .into_iter()
+            .map(|v| Srgba::from_linear(vec4(v, v, v, 1.0)))
+            .collect(),
+    );
+}
+
+///
+/// Computes the ambient occlusion at each vertex of the given mesh, where 1 is not occluded and 0 is fully occluded, see [bake_ambient_occlusion_vertex_colors].
+/// The rays traced from a vertex only depend on the position and normal of the vertex, so the result does not change if the vertices or triangles are reordered.
+///
+/// The work is distributed over the available threads and the progress callback is called with the fraction of the work done, between 0 and 1.
+///
+pub fn compute_vertex_ambient_occlusion(
+    mesh: &CpuMesh,
+    settings: AmbientOcclusionSettings,
+    progress: impl Fn(f32) + Sync,
+) -> Vec<f32> {
+    let mut occlusion = Vec::new();
+    update_vertex_ambient_occlusion(
+        mesh,
+        &mut occlusion,
+        AxisAlignedBoundingBox::INFINITE,
+        settings,
+        progress,
+    );
+    occlusion
+}
+
+///
+/// Updates the ambient occlusion at the vertices of the given mesh computed by [compute_vertex_ambient_occlusion] after the mesh has been edited within the given region,
+/// which must contain both the old and the new positions of the edited vertices.
+/// Only the occlusion of the vertices closer to the region than [AmbientOcclusionSettings::max_distance] is recomputed,
+/// unless the number of vertices has changed, in which case the occlusion of all vertices is recomputed.
+///
+/// The work is distributed over the available threads and the progress callback is called with the fraction of the work done, between 0 and 1.
+///
+pub fn update_vertex_ambient_occlusion(
+    mesh: &CpuMesh,
+    occlusion: &mut Vec<f32>,
+    region: AxisAlignedBoundingBox,
+    settings: AmbientOcclusionSettings,
+    progress: impl Fn(f32) + Sync,
+) {
+    let positions = mesh.positions.to_f32();
+    let vertices = if occlusion.len() == positions.len() {
+        (0..positions.len())
+            .filter(|i| region.distance(positions[*i]) <= settings.max_distance)
+            .collect::<Vec<_>>()
+    } else {
+        *occlusion = vec![1.0; positions.len()];
+        (0..positions.len()).collect()
+    };
+    if vertices.is_empty() {
+        progress(1.0);
+        return;
+    }
+    let normals = vertex_normals(mesh);
+    let bvh = Bvh::new(mesh);
+    let bias = ray_bias(mesh);
+
+    let result = parallel_map(vertices.len(), &progress, |i| {
+        let vertex = vertices[i];
+        let seed = position_key(positions[vertex])
+            .into_iter()
+            .fold(0u32, |seed, bits| seed.rotate_left(5) ^ bits);
+        ambient_occlusion(
+            &bvh,
+            positions[vertex],
+            normals[vertex],
+            bias,
+            seed,
+            &settings,
+        )
+    });
+    for (vertex, value) in vertices.into_iter().zip(result) {
+        occlusion[vertex] = value;
+    }
+}
+
+fn ambient_occlusion(
+    bvh: &Bvh,
+    position: Vec3,
+    normal: Vec3,
+    bias: f32,
+    seed: u32,
+    settings: &AmbientOcclusionSettings,
+) -> f32 {
+    let origin = position + normal * bias;
+    let unoccluded = (0..settings.ray_count)
+        .filter(|i| {
+            let direction =
+                cosine_weighted_direction(normal, sample_2d(*i, settings.ray_count, seed));
+            !bvh.is_occluded(origin, direction, settings.max_distance)
+        })
+        .count();
+    unoccluded as f32 / settings.ray_count.max(1) as f32
+}
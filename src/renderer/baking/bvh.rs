@@ -0,0 +1,299 @@
+use crate::renderer::*;
+
+///
+/// A bounding volume hierarchy over the triangles of a [CpuMesh], which makes it possible to find intersections between rays and the mesh on the CPU.
+/// Used when baking, for example by [bake_ambient_occlusion_texture].
+///
+pub struct Bvh {
+    positions: Vec<Vec3>,
+    triangles: Vec<[u32; 3]>,
+    order: Vec<u32>,
+    nodes: Vec<BvhNode>,
+}
+
+struct BvhNode {
+    min: Vec3,
+    max: Vec3,
+    // For a leaf, the index of the first triangle in the order, otherwise the index of the second child (the first child is the next node)
+    offset: u32,
+    // The number of triangles in a leaf, zero for an inner node
+    count: u32,
+}
+
+const MAX_TRIANGLES_PER_LEAF: usize = 4;
+
+// The tree is split at the median, so the traversal stack never gets close to this size
+const MAX_STACK_SIZE: usize = 64;
+
+impl Bvh {
+    ///
+    /// Builds a bounding volume hierarchy from the triangles of the given mesh.
+    ///
+    pub fn new(mesh: &CpuMesh) -> Self {
+        let positions = mesh.positions.to_f32();
+        let mut triangles = Vec::with_capacity(mesh.triangle_count());
+        mesh.for_each_triangle(|i0, i1, i2| triangles.push([i0 as u32, i1 as u32, i2 as u32]));
+        let centroids = triangles
+            .iter()
+            .map(|t| {
+                (positions[t[0] as usize] + positions[t[1] as usize] + positions[t[2] as usize])
+                    / 3.0
+            })
+            .collect::<Vec<_>>();
+        let mut order = (0..triangles.len() as u32).collect::<Vec<_>>();
+        let mut nodes = Vec::new();
+        if !order.is_empty() {
+            build_node(
+                &mut nodes, &positions, &triangles, &centroids, &mut order, 0,
+            );
+        }
+        Self {
+            positions,
+            triangles,
+            order,
+            nodes,
+        }
+    }
+
+    ///
+    /// Returns the number of triangles.
+    ///
+    pub fn triangle_count(&self) -> usize {
+        self.triangles.len()
+    }
+
+    ///
+    /// Returns the axis aligned bounding box of all the triangles.
+    ///
+    pub fn aabb(&self) -> AxisAlignedBoundingBox {
+        self.nodes
+            .first()
+            .map(|node| AxisAlignedBoundingBox::new_with_positions(&[node.min, node.max]))
+            .unwrap_or(AxisAlignedBoundingBox::EMPTY)
+    }
+
+    ///
+    /// Returns the positions of the three vertices of the triangle with the given index,
+    /// where the triangles are ordered the same way as in [CpuMesh::for_each_triangle].
+    ///
+    pub fn triangle(&self, triangle: usize) -> [Vec3; 3] {
+        let t = self.triangles[triangle];
+        [
+            self.positions[t[0] as usize],
+            self.positions[t[1] as usize],
+            self.positions[t[2] as usize],
+        ]
+    }
+
+    ///
+    /// Returns the vertex indices of the triangle with the given index,
+    /// where the triangles are ordered the same way as in [CpuMesh::for_each_triangle].
+    ///
+    pub fn triangle_indices(&self, triangle: usize) -> [u32; 3] {
+        self.triangles[triangle]
+    }
+
+    ///
+    /// Returns the distance along the ray to the closest intersection with the mesh and the index of the intersected triangle,
+    /// or `None` if the ray does not intersect the mesh within the given maximum distance.
+    /// The direction must be normalized.
+    ///
+    pub fn intersect(
+        &self,
+        origin: Vec3,
+        direction: Vec3,
+        max_distance: f32,
+    ) -> Option<(f32, usize)> {
+        let mut closest = None;
+        let mut max_distance = max_distance;
+        self.traverse(origin, direction, &mut max_distance, &mut |t, triangle| {
+            closest = Some((t, triangle));
+            false
+        });
+        closest
+    }
+
+    ///
+    /// Returns whether or not the ray intersects the mesh within the given maximum distance.
+    /// This is faster than [Bvh::intersect] since it stops at the first found intersection.
+    /// The direction must be normalized.
+    ///
+    pub fn is_occluded(&self, origin: Vec3, direction: Vec3, max_distance: f32) -> bool {
+        let mut occluded = false;
+        let mut max_distance = max_distance;
+        self.traverse(origin, direction, &mut max_distance, &mut |_, _| {
+            occluded = true;
+            true
+        });
+        occluded
+    }
+
+    // Calls the callback with each intersection closer than the current maximum distance, stops if the callback returns true.
+    fn traverse(
+        &self,
+        origin: Vec3,
+        direction: Vec3,
+        max_distance: &mut f32,
+        callback: &mut impl FnMut(f32, usize) -> bool,
+    ) {
+        let inverse_direction = vec3(1.0 / direction.x, 1.0 / direction.y, 1.0 / direction.z);
+        let max_distance = std::cell::Cell::new(*max_distance);
+        self.visit(
+            |min, max| intersects_box(origin, inverse_direction, min, max, max_distance.get()),
+            |triangle| {
+                let [p0, p1, p2] = self.triangle(triangle);
+                if let Some(t) = intersect_triangle(origin, direction, p0, p1, p2) {
+                    if t < max_distance.get() {
+                        max_distance.set(t);
+                        return callback(t, triangle);
+                    }
+                }
+                false
+            },
+        );
+    }
+
+    ///
+    /// Calls the triangle callback with the index of each triangle in the leaves whose bounds, given as minimum and maximum corner, passes the bounds test.
+    /// The triangles are visited in the same order each time and the traversal stops if the triangle callback returns true.
+    /// Does not allocate.
+    ///
+    pub(crate) fn visit(
+        &self,
+        mut bounds_test: impl FnMut(Vec3, Vec3) -> bool,
+        mut triangle_callback: impl FnMut(usize) -> bool,
+    ) {
+        if self.nodes.is_empty() {
+            return;
+        }
+        let mut stack = [0usize; MAX_STACK_SIZE];
+        let mut stack_size = 1;
+        while stack_size > 0 {
+            stack_size -= 1;
+            let index = stack[stack_size];
+            let node = &self.nodes[index];
+            if !bounds_test(node.min, node.max) {
+                continue;
+            }
+            if node.count == 0 {
+                stack[stack_size] = node.offset as usize;
+                stack[stack_size + 1] = index + 1;
+                stack_size += 2;
+            } else {
+                for i in node.offset as usize..(node.offset + node.count) as usize {
+                    if triangle_callback(self.order[i] as usize) {
+                        return;
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn build_node(
+    nodes: &mut Vec<BvhNode>,
+    positions: &[Vec3],
+    triangles: &[[u32; 3]],
+    centroids: &[Vec3],
+    order: &mut [u32],
+    start: usize,
+) {
+    let mut min = vec3(f32::MAX, f32::MAX, f32::MAX);
+    let mut max = vec3(f32::MIN, f32::MIN, f32::MIN);
+    let mut centroid_min = min;
+    let mut centroid_max = max;
+    for i in order.iter() {
+        for vertex in triangles[*i as usize] {
+            let p = positions[vertex as usize];
+            min = vec3(min.x.min(p.x), min.y.min(p.y), min.z.min(p.z));
+            max = vec3(max.x.max(p.x), max.y.max(p.y), max.z.max(p.z));
+        }
+        let c = centroids[*i as usize];
+        centroid_min = vec3(
+            centroid_min.x.min(c.x),
+            centroid_min.y.min(c.y),
+            centroid_min.z.min(c.z),
+        );
+        centroid_max = vec3(
+            centroid_max.x.max(c.x),
+            centroid_max.y.max(c.y),
+            centroid_max.z.max(c.z),
+        );
+    }
+    let node_index = nodes.len();
+    nodes.push(BvhNode {
+        min,
+        max,
+        offset: start as u32,
+        count: order.len() as u32,
+    });
+    if order.len() <= MAX_TRIANGLES_PER_LEAF {
+        return;
+    }
+
+    // Split at the median along the longest axis of the centroid bounds
+    let extent = centroid_max - centroid_min;
+    let axis = if extent.x >= extent.y && extent.x >= extent.z {
+        0
+    } else if extent.y >= extent.z {
+        1
+    } else {
+        2
+    };
+    let mid = order.len() / 2;
+    order.select_nth_unstable_by(mid, |a, b| {
+        centroids[*a as usize][axis].total_cmp(&centroids[*b as usize][axis])
+    });
+    let (left, right) = order.split_at_mut(mid);
+    build_node(nodes, positions, triangles, centroids, left, start);
+    let second_child = nodes.len() as u32;
+    build_node(nodes, positions, triangles, centroids, right, start + mid);
+    nodes[node_index].offset = second_child;
+    nodes[node_index].count = 0;
+}
+
+fn intersects_box(
+    origin: Vec3,
+    inverse_direction: Vec3,
+    min: Vec3,
+    max: Vec3,
+    max_distance: f32,
+) -> bool {
+    let mut t_min = 0.0f32;
+    let mut t_max = max_distance;
+    for axis in 0..3 {
+        let t0 = (min[axis] - origin[axis]) * inverse_direction[axis];
+        let t1 = (max[axis] - origin[axis]) * inverse_direction[axis];
+        t_min = t_min.max(t0.min(t1));
+        t_max = t_max.min(t0.max(t1));
+    }
+    t_min <= t_max
+}
+
+// Möller–Trumbore ray-triangle intersection
+fn intersect_triangle(origin: Vec3, direction: Vec3, p0: Vec3, p1: Vec3, p2: Vec3) -> Option<f32> {
+    let e1 = p1 - p0;
+    let e2 = p2 - p0;
+    let h = direction.cross(e2);
+    let a = e1.dot(h);
+    if a.abs() < 1e-12 {
+        return None;
+    }
+    let f = 1.0 / a;
+    let s = origin - p0;
+    let u = f * s.dot(h);
+    if !(0.0..=1.0).contains(&u) {
+        return None;
+    }
+    let q = s.cross(e1);
+    let v = f * direction.dot(q);
+    if v < 0.0 || u + v > 1.0 {
+        return None;
+    }
+    let t = f * e2.dot(q);
+    if t > 0.0 {
+        Some(t)
+    } else {
+        None
+    }
+}
@@ -0,0 +1,99 @@
+use super::*;
+use std::collections::HashMap;
+
+///
+/// Computes the mean curvature at each vertex of the given mesh, which is positive where the surface is convex, for example at edges and ridges,
+/// negative where the surface is concave, for example in cavities and creases, and zero where the surface is flat.
+/// The curvature is the inverse of the radius of the surface, so a sphere with radius 2 has a curvature of 0.5 everywhere.
+/// The front side of the triangles, given by the winding order, is assumed to be the outside of the surface.
+///
+/// Vertices at the same position are treated as one vertex, so seams in for example the normals or uv coordinates do not affect the curvature,
+/// and the result does not change if the vertices or triangles are reordered.
+///
+pub fn compute_vertex_curvature(mesh: &CpuMesh) -> Vec<f32> {
+    let mut curvature = Vec::new();
+    update_vertex_curvature(mesh, &mut curvature, AxisAlignedBoundingBox::INFINITE);
+    curvature
+}
+
+///
+/// Updates the mean curvature at the vertices of the given mesh computed by [compute_vertex_curvature] after the mesh has been edited within the given region,
+/// which must contain both the old and the new positions of the edited vertices.
+/// Only the curvature of the vertices in the region and their neighbours is recomputed,
+/// unless the number of vertices has changed, in which case the curvature of all vertices is recomputed.
+///
+pub fn update_vertex_curvature(
+    mesh: &CpuMesh,
+    curvature: &mut Vec<f32>,
+    mut region: AxisAlignedBoundingBox,
+) {
+    let positions = mesh.positions.to_f32();
+    if curvature.len() != positions.len() {
+        *curvature = vec![0.0; positions.len()];
+        region = AxisAlignedBoundingBox::INFINITE;
+    }
+
+    // Weld the vertices at the same position
+    let mut welded_indices = HashMap::new();
+    let welded = positions
+        .iter()
+        .map(|p| {
+            let next = welded_indices.len();
+            *welded_indices.entry(position_key(*p)).or_insert(next)
+        })
+        .collect::<Vec<_>>();
+    let count = welded_indices.len();
+    let mut triangles = Vec::with_capacity(mesh.triangle_count());
+    mesh.for_each_triangle(|i0, i1, i2| triangles.push([i0, i1, i2]));
+
+    // Find the vertices in the region and their neighbours
+    let mut inside = vec![false; count];
+    for (i, position) in positions.iter().enumerate() {
+        if region.is_inside(*position) {
+            inside[welded[i]] = true;
+        }
+    }
+    let mut dirty = inside.clone();
+    for triangle in triangles.iter() {
+        let w = triangle.map(|i| welded[i]);
+        if w.iter().any(|i| inside[*i]) {
+            w.iter().for_each(|i| dirty[*i] = true);
+        }
+    }
+
+    // Accumulate the cotangent weighted Laplacian, the area and the normal at each welded vertex
+    let mut laplacian = vec![vec3(0.0, 0.0, 0.0); count];
+    let mut area = vec![0.0; count];
+    let mut normal = vec![vec3(0.0, 0.0, 0.0); count];
+    for triangle in triangles.iter() {
+        let w = triangle.map(|i| welded[i]);
+        if !w.iter().any(|i| dirty[*i]) || w[0] == w[1] || w[1] == w[2] || w[2] == w[0] {
+            continue;
+        }
+        let p = triangle.map(|i| positions[i]);
+        let n = (p[1] - p[0]).cross(p[2] - p[0]);
+        let double_area = n.magnitude();
+        if double_area < f32::EPSILON {
+            continue;
+        }
+        for k in 0..3 {
+            let (i, j, o) = (k, (k + 1) % 3, (k + 2) % 3);
+            // The cotangent of the angle opposite to the edge from i to j
+            let cot = (p[i] - p[o]).dot(p[j] - p[o]) / double_area;
+            laplacian[w[i]] += cot * (p[j] - p[i]);
+            laplacian[w[j]] += cot * (p[i] - p[j]);
+            area[w[k]] += double_area / 6.0;
+            normal[w[k]] += n;
+        }
+    }
+
+    for (i, w) in welded.into_iter().enumerate() {
+        if dirty[w] {
+            curvature[i] = if area[w] > 0.0 && normal[w].magnitude2() > 0.0 {
+                -laplacian[w].dot(normal[w].normalize()) / (4.0 * area[w])
+            } else {
+                0.0
+            };
+        }
+    }
+}
@@ -0,0 +1,277 @@
+use super::*;
+
+///
+/// Settings for baking lightmaps, see [bake_lightmaps].
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LightmapSettings {
+    /// The number of rays traced in the hemisphere above each texel to find the indirect (bounced) light. Higher is less noisy but slower.
+    /// Set it to zero to only bake the direct light. The default is 64.
+    pub indirect_samples: u32,
+    /// The radius in texels of the blur applied to the indirect light to remove the noise. The direct light is not blurred.
+    /// The default is 2.
+    pub blur_radius: u32,
+    /// The number of texels to extend the baked result beyond the edges of the uv islands to avoid seams.
+    /// The default is 4.
+    pub dilation: u32,
+}
+
+impl Default for LightmapSettings {
+    fn default() -> Self {
+        Self {
+            indirect_samples: 64,
+            blur_radius: 2,
+            dilation: 4,
+        }
+    }
+}
+
+///
+/// A static object in the scene that is baked by [bake_lightmaps].
+/// All objects in the scene occlude the light and reflect light onto each other.
+///
+#[derive(Clone, Copy)]
+pub struct LightmapObject<'a> {
+    /// The triangle mesh of the object.
+    pub mesh: &'a CpuMesh,
+    /// The uv coordinates used for the lightmap, one for each vertex of the mesh, specified the same way as [CpuMesh::uvs].
+    /// The triangles must not overlap in uv space, which can be checked with [has_overlapping_uvs], and for meshes without such coordinates they can be generated using [unwrap_uvs].
+    /// Use the same uv coordinates when rendering, see [Mesh::set_lightmap_uvs].
+    pub lightmap_uvs: &'a [Vec2],
+    /// The local to world transformation of the mesh.
+    pub transformation: Mat4,
+    /// The diffuse color of the object, which determines how much light is reflected onto other objects.
+    pub albedo: Srgba,
+    /// The width of the lightmap texture.
+    pub width: u32,
+    /// The height of the lightmap texture.
+    pub height: u32,
+}
+
+///
+/// Bakes the diffuse lighting from the given lights into a lightmap texture for each of the given static objects.
+/// The direct light is found by tracing shadow rays towards the lights and one bounce of indirect light is found by path tracing over a [Bvh] of all the objects.
+/// The indirect light is blurred to remove noise, see [LightmapSettings].
+///
+/// The returned textures contain the light arriving at the surface in linear HDR color space, ready to be used as the [PhysicalMaterial::lightmap_texture]
+/// where it is multiplied with the albedo, so the lights can be left out when rendering the static objects.
+/// Since only diffuse lighting is baked, specular highlights are not included.
+///
+/// The work is distributed over the available threads and the progress callback is called with the fraction of the work done, between 0 and 1.
+///
+pub fn bake_lightmaps(
+    objects: &[LightmapObject],
+    directional_lights: &[&DirectionalLight],
+    point_lights: &[&PointLight],
+    settings: LightmapSettings,
+    progress: impl Fn(f32) + Sync,
+) -> Result<Vec<CpuTexture>, RendererError> {
+    // Combine all objects into one mesh in world space
+    let mut positions = Vec::new();
+    let mut normals = Vec::new();
+    let mut lightmap_uvs = Vec::new();
+    let mut indices = Vec::new();
+    let mut triangle_offsets = Vec::new();
+    let mut triangle_objects = Vec::new();
+    for (index, object) in objects.iter().enumerate() {
+        let vertex_count = object.mesh.vertex_count();
+        if object.lightmap_uvs.len() != vertex_count {
+            return Err(RendererError::InvalidBufferLength(
+                "lightmap uv".to_string(),
+                vertex_count,
+                object.lightmap_uvs.len(),
+            ));
+        }
+        let offset = positions.len() as u32;
+        let normal_transformation = object
+            .transformation
+            .invert()
+            .unwrap_or(Mat4::identity())
+            .transpose();
+        positions.extend(object.mesh.positions.to_f32().into_iter().map(|p| {
+            object
+                .transformation
+                .transform_point(Point3::from_vec(p))
+                .to_vec()
+        }));
+        normals.extend(
+            vertex_normals(object.mesh)
+                .into_iter()
+                .map(|n| normal_transformation.transform_vector(n).normalize()),
+        );
+        lightmap_uvs.extend_from_slice(object.lightmap_uvs);
+        triangle_offsets.push(triangle_objects.len());
+        object.mesh.for_each_triangle(|i0, i1, i2| {
+            indices.extend([i0 as u32 + offset, i1 as u32 + offset, i2 as u32 + offset]);
+            triangle_objects.push(index);
+        });
+    }
+    triangle_offsets.push(triangle_objects.len());
+    let scene = CpuMesh {
+        positions: Positions::F32(positions.clone()),
+        indices: Indices::U32(indices),
+        ..Default::default()
+    };
+    let bvh = Bvh::new(&scene);
+    let bias = ray_bias(&scene);
+    let albedos = objects
+        .iter()
+        .map(|object| object.albedo.to_linear_srgb().truncate())
+        .collect::<Vec<_>>();
+
+    let samples = objects
+        .iter()
+        .enumerate()
+        .map(|(index, object)| {
+            rasterize_uvs(
+                &positions,
+                &normals,
+                &lightmap_uvs,
+                (triangle_offsets[index]..triangle_offsets[index + 1])
+                    .map(|t| bvh.triangle_indices(t)),
+                object.width,
+                object.height,
+            )
+        })
+        .collect::<Vec<_>>();
+    let all_samples = samples.iter().flatten().collect::<Vec<_>>();
+
+    let direct_lighting = |position: Vec3, normal: Vec3| {
+        let origin = position + normal * bias;
+        let mut irradiance = Vec3::zero();
+        for light in directional_lights {
+            let direction = -light.direction.normalize();
+            let cos_angle = normal.dot(direction);
+            if cos_angle > 0.0 && !bvh.is_occluded(origin, direction, f32::MAX) {
+                irradiance += light.color.to_linear_srgb().truncate() * light.intensity * cos_angle;
+            }
+        }
+        for light in point_lights {
+            let to_light = light.position - origin;
+            let distance = to_light.magnitude();
+            let direction = to_light / distance;
+            let cos_angle = normal.dot(direction);
+            if cos_angle > 0.0 && !bvh.is_occluded(origin, direction, distance) {
+                let attenuation = light.attenuation.constant
+                    + light.attenuation.linear * distance
+                    + light.attenuation.quadratic * distance * distance;
+                irradiance += light.color.to_linear_srgb().truncate() * light.intensity * cos_angle
+                    / attenuation.max(1.0);
+            }
+        }
+        irradiance
+    };
+
+    let lighting = parallel_map(all_samples.len(), &progress, |i| {
+        let sample = all_samples[i];
+        let direct = direct_lighting(sample.position, sample.normal);
+        let origin = sample.position + sample.normal * bias;
+        let mut indirect = Vec3::zero();
+        for s in 0..settings.indirect_samples {
+            let direction = cosine_weighted_direction(
+                sample.normal,
+                sample_2d(s, settings.indirect_samples, i as u32),
+            );
+            if let Some((distance, triangle)) = bvh.intersect(origin, direction, f32::MAX) {
+                let [p0, p1, p2] = bvh.triangle(triangle);
+                let mut normal = (p1 - p0).cross(p2 - p0).normalize();
+                if normal.dot(direction) > 0.0 {
+                    normal = -normal;
+                }
+                let albedo = albedos[triangle_objects[triangle]];
+                let irradiance = direct_lighting(origin + direction * distance, normal);
+                indirect += vec3(
+                    irradiance.x * albedo.x,
+                    irradiance.y * albedo.y,
+                    irradiance.z * albedo.z,
+                );
+            }
+        }
+        // With cosine weighted sampling the irradiance is the average of the reflected radiance times pi,
+        // which cancels out the division by pi of the reflected diffuse radiance
+        indirect /= settings.indirect_samples.max(1) as f32;
+        (direct, indirect)
+    });
+
+    let mut lighting = lighting.into_iter();
+    Ok(objects
+        .iter()
+        .zip(samples.iter())
+        .map(|(object, samples)| {
+            let texel_count = (object.width * object.height) as usize;
+            let mut direct = vec![Vec3::zero(); texel_count];
+            let mut indirect = vec![Vec3::zero(); texel_count];
+            let mut covered = vec![false; texel_count];
+            for (sample, (d, i)) in samples.iter().zip(lighting.by_ref()) {
+                direct[sample.texel] = d;
+                indirect[sample.texel] = i;
+                covered[sample.texel] = true;
+            }
+            blur(
+                &mut indirect,
+                &covered,
+                object.width,
+                object.height,
+                settings.blur_radius,
+            );
+            // The lightmap is multiplied with the albedo in the shader, so the division by pi of the diffuse reflection is applied here
+            let mut data = direct
+                .into_iter()
+                .zip(indirect)
+                .map(|(d, i)| (d + i) / std::f32::consts::PI)
+                .collect::<Vec<_>>();
+            dilate(
+                &mut data,
+                &mut covered,
+                object.width,
+                object.height,
+                settings.dilation,
+            );
+            CpuTexture {
+                name: "lightmap".to_string(),
+                data: TextureData::RgbF16(
+                    data.into_iter()
+                        .map(|v| [f16::from_f32(v.x), f16::from_f32(v.y), f16::from_f32(v.z)])
+                        .collect(),
+                ),
+                width: object.width,
+                height: object.height,
+                mipmap: None,
+                wrap_s: Wrapping::ClampToEdge,
+                wrap_t: Wrapping::ClampToEdge,
+                ..Default::default()
+            }
+        })
+        .collect())
+}
+
+///
+/// Replaces each covered texel with the average of the covered texels within the given radius.
+///
+fn blur(data: &mut [Vec3], covered: &[bool], width: u32, height: u32, radius: u32) {
+    if radius == 0 {
+        return;
+    }
+    let (width, height, radius) = (width as i64, height as i64, radius as i64);
+    let source = data.to_vec();
+    for y in 0..height {
+        for x in 0..width {
+            let index = (y * width + x) as usize;
+            if !covered[index] {
+                continue;
+            }
+            let mut sum = Vec3::zero();
+            let mut count = 0;
+            for ny in (y - radius).max(0)..=(y + radius).min(height - 1) {
+                for nx in (x - radius).max(0)..=(x + radius).min(width - 1) {
+                    let neighbour = (ny * width + nx) as usize;
+                    if covered[neighbour] {
+                        sum += source[neighbour];
+                        count += 1;
+                    }
+                }
+            }
+            data[index] = sum / count as f32;
+        }
+    }
+}
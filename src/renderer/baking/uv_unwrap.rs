@@ -0,0 +1,651 @@
+use super::*;
+use std::collections::{HashMap, VecDeque};
+
+///
+/// Settings for automatically unwrapping the uv coordinates of a mesh, see [unwrap_uvs].
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct UvUnwrapSettings {
+    /// The number of texels per unit of length in the local space of the mesh, which determines the size of the atlas.
+    /// The default is 64.
+    pub texel_density: f32,
+    /// The number of texels between the charts and between the charts and the border of the atlas,
+    /// which avoids that the charts bleed into each other when the texture is filtered or dilated. The default is 4.
+    pub padding: u32,
+    /// The maximum angle between the normal of a triangle and the average normal of the chart it is added to.
+    /// A larger angle gives fewer charts, and therefore fewer seams, but more distortion. The default is 60 degrees.
+    pub max_chart_angle: Radians,
+}
+
+impl Default for UvUnwrapSettings {
+    fn default() -> Self {
+        Self {
+            texel_density: 64.0,
+            padding: 4,
+            max_chart_angle: degrees(60.0).into(),
+        }
+    }
+}
+
+///
+/// The result of unwrapping the uv coordinates of a mesh, see [unwrap_uvs].
+///
+#[derive(Clone, Debug)]
+pub struct UvAtlas {
+    /// The given mesh where the vertices are split along the chart boundaries, ie. a vertex used by several charts is duplicated once for each chart.
+    /// All attributes of the given mesh are preserved, including the uv coordinates if any, and the triangles are in the same order.
+    pub mesh: CpuMesh,
+    /// The unwrapped uv coordinates in the range `[0..1]`, one for each vertex of the [UvAtlas::mesh].
+    /// Use them as the uv coordinates of the mesh, for example for texture painting, or as the lightmap uv coordinates, see [LightmapObject::lightmap_uvs].
+    pub uvs: Vec<Vec2>,
+    /// The index of the vertex in the given mesh that each vertex of the [UvAtlas::mesh] is a copy of, for example to split other per vertex data the same way.
+    pub original_vertices: Vec<usize>,
+    /// The number of charts, ie. the number of pieces the surface is cut into.
+    pub chart_count: usize,
+    /// The fraction of the atlas covered by the triangles, between 0 and 1.
+    pub utilization: f32,
+    /// The width of the atlas in texels, given the [UvUnwrapSettings::texel_density].
+    pub width: u32,
+    /// The height of the atlas in texels, given the [UvUnwrapSettings::texel_density].
+    pub height: u32,
+}
+
+///
+/// Computes uv coordinates for the given mesh, for example a scanned mesh or the result of a boolean operation without uv coordinates,
+/// where the triangles do not overlap in uv space, which is needed for baking lightmaps (see [bake_lightmaps]) and other textures unique to the surface.
+///
+/// The surface is cut into charts of connected triangles facing roughly the same direction (see [UvUnwrapSettings::max_chart_angle]),
+/// each chart is flattened using least squares conformal maps (LSCM) which preserves the angles as well as possible,
+/// and the charts are packed into an atlas with the size given by the [UvUnwrapSettings::texel_density] and [UvUnwrapSettings::padding].
+/// A chart that folds over itself when flattened is split into smaller charts.
+///
+/// ```
+/// # use three_d::*;
+/// // Built-in primitives and a sphere with noise, which resembles a scanned mesh
+/// let mut scan = CpuMesh::sphere(24);
+/// if let Positions::F32(positions) = &mut scan.positions {
+///     for p in positions.iter_mut() {
+///         *p *= 1.0 + 0.1 * (7.0 * p.x).sin() * (5.0 * p.y).cos() * (3.0 * p.z + 1.0).sin();
+///     }
+/// }
+/// scan.normals = None;
+/// scan.uvs = None;
+/// for mesh in [CpuMesh::sphere(16), CpuMesh::cube(), CpuMesh::cylinder(16), CpuMesh::cone(16), scan] {
+///     let atlas = unwrap_uvs(&mesh, UvUnwrapSettings::default());
+///     assert!(!has_overlapping_uvs(&atlas.mesh, &atlas.uvs));
+///     assert_eq!(atlas.mesh.triangle_count(), mesh.triangle_count());
+///     assert!(atlas.chart_count >= 1 && atlas.utilization > 0.2 && atlas.utilization <= 1.0);
+/// }
+/// ```
+///
+pub fn unwrap_uvs(mesh: &CpuMesh, settings: UvUnwrapSettings) -> UvAtlas {
+    let positions = mesh.positions.to_f32();
+    let mut triangles = Vec::with_capacity(mesh.triangle_count());
+    mesh.for_each_triangle(|i0, i1, i2| triangles.push([i0, i1, i2]));
+
+    // Weld the vertices at the same position, so existing seams in for example the normals or uv coordinates do not cut the charts
+    let mut welded_indices = HashMap::new();
+    let welded = positions
+        .iter()
+        .map(|p| {
+            let next = welded_indices.len();
+            *welded_indices.entry(position_key(*p)).or_insert(next)
+        })
+        .collect::<Vec<_>>();
+    let welded_triangles = triangles
+        .iter()
+        .map(|t| t.map(|i| welded[i]))
+        .collect::<Vec<_>>();
+    let welded_positions = {
+        let mut welded_positions = vec![Vec3::zero(); welded_indices.len()];
+        for (i, w) in welded.iter().enumerate() {
+            welded_positions[*w] = positions[i];
+        }
+        welded_positions
+    };
+
+    // Triangles collapsed by the welding, like the triangles at the apex of a cone, have no surface and are added to a neighbouring chart afterwards
+    let collapsed = welded_triangles
+        .iter()
+        .map(|t| t[0] == t[1] || t[1] == t[2] || t[2] == t[0])
+        .collect::<Vec<_>>();
+
+    // Triangles are neighbours if they share an edge which is not shared by any other triangle and have the same orientation
+    let mut edges: HashMap<(usize, usize), Vec<usize>> = HashMap::new();
+    for (t, triangle) in welded_triangles.iter().enumerate() {
+        if collapsed[t] {
+            continue;
+        }
+        for k in 0..3 {
+            edges
+                .entry((triangle[k], triangle[(k + 1) % 3]))
+                .or_default()
+                .push(t);
+        }
+    }
+    let neighbours = welded_triangles
+        .iter()
+        .map(|triangle| {
+            let mut neighbours = Vec::with_capacity(3);
+            for k in 0..3 {
+                let (a, b) = (triangle[k], triangle[(k + 1) % 3]);
+                if let (Some(forward), Some(backward)) = (edges.get(&(a, b)), edges.get(&(b, a))) {
+                    if forward.len() == 1 && backward.len() == 1 {
+                        neighbours.push(backward[0]);
+                    }
+                }
+            }
+            neighbours
+        })
+        .collect::<Vec<_>>();
+    let normals = welded_triangles
+        .iter()
+        .map(|t| {
+            let p = t.map(|i| welded_positions[i]);
+            (p[1] - p[0]).cross(p[2] - p[0])
+        })
+        .collect::<Vec<_>>();
+
+    // Grow the charts from the largest triangles
+    let cos_max_angle = settings.max_chart_angle.0.cos();
+    let mut order = (0..triangles.len()).collect::<Vec<_>>();
+    order.sort_by(|a, b| {
+        normals[*b]
+            .magnitude2()
+            .total_cmp(&normals[*a].magnitude2())
+    });
+    let mut assigned = vec![false; triangles.len()];
+    let mut pending = Vec::new();
+    for seed in order {
+        if assigned[seed] || collapsed[seed] {
+            continue;
+        }
+        assigned[seed] = true;
+        let mut members = vec![seed];
+        let mut normal_sum = normals[seed];
+        let mut queue = VecDeque::from([seed]);
+        while let Some(t) = queue.pop_front() {
+            for n in neighbours[t].iter().copied() {
+                if assigned[n] {
+                    continue;
+                }
+                let normal = normals[n];
+                let accept = normal_sum.magnitude2() == 0.0
+                    || normal.magnitude2() == 0.0
+                    || normal.normalize().dot(normal_sum.normalize()) >= cos_max_angle;
+                if accept {
+                    assigned[n] = true;
+                    normal_sum += normal;
+                    members.push(n);
+                    queue.push_back(n);
+                }
+            }
+        }
+        pending.push(members);
+    }
+    let mut vertex_charts = HashMap::new();
+    for (chart, members) in pending.iter().enumerate() {
+        for t in members {
+            for v in welded_triangles[*t] {
+                vertex_charts.entry(v).or_insert(chart);
+            }
+        }
+    }
+    for t in (0..triangles.len()).filter(|t| collapsed[*t]) {
+        match welded_triangles[t]
+            .iter()
+            .find_map(|v| vertex_charts.get(v).copied())
+        {
+            Some(chart) => pending[chart].push(t),
+            None => pending.push(vec![t]),
+        }
+    }
+
+    // Flatten the charts and split the charts that fold over themselves
+    let mut charts = Vec::new();
+    while let Some(members) = pending.pop() {
+        match flatten_chart(&welded_positions, &welded_triangles, &members) {
+            Some(uvs) => charts.push((members, uvs)),
+            None => pending.extend(split_chart(
+                &welded_positions,
+                &welded_triangles,
+                &neighbours,
+                &members,
+            )),
+        }
+    }
+
+    // Rotate each chart to the smallest bounding rectangle and pack the rectangles into the atlas
+    let padding = settings.padding as f32;
+    let mut rectangles = charts
+        .iter_mut()
+        .enumerate()
+        .map(|(chart, (_, uvs))| {
+            for uv in uvs.values_mut() {
+                *uv *= settings.texel_density;
+            }
+            let (angle, _) = (0..6)
+                .map(|i| {
+                    let angle = i as f32 * std::f32::consts::FRAC_PI_2 / 6.0;
+                    let (min, max) = bounds(uvs.values().map(|uv| rotate(*uv, angle)));
+                    (angle, (max.x - min.x) * (max.y - min.y))
+                })
+                .min_by(|a, b| a.1.total_cmp(&b.1))
+                .unwrap();
+            for uv in uvs.values_mut() {
+                *uv = rotate(*uv, angle);
+            }
+            let (min, max) = bounds(uvs.values().copied());
+            for uv in uvs.values_mut() {
+                *uv -= min;
+            }
+            (chart, max - min)
+        })
+        .collect::<Vec<_>>();
+    rectangles.sort_by(|a, b| b.1.y.total_cmp(&a.1.y));
+    let total_area = rectangles
+        .iter()
+        .map(|(_, size)| (size.x + padding) * (size.y + padding))
+        .sum::<f32>();
+    let width = rectangles
+        .iter()
+        .map(|(_, size)| size.x.ceil() + 2.0 * padding)
+        .fold((1.15 * total_area).sqrt().ceil(), f32::max);
+    let mut offsets = vec![Vec2::zero(); charts.len()];
+    let (mut x, mut y, mut shelf_height) = (padding, padding, 0.0f32);
+    for (chart, size) in rectangles.iter() {
+        let (w, h) = (size.x.ceil(), size.y.ceil());
+        if x + w + padding > width && x > padding {
+            x = padding;
+            y += shelf_height + padding;
+            shelf_height = 0.0;
+        }
+        offsets[*chart] = vec2(x, y);
+        x += w + padding;
+        shelf_height = shelf_height.max(h);
+    }
+    let height = (y + shelf_height + padding).max(1.0);
+    let (width, height) = (width as u32, height.ceil() as u32);
+
+    // Split the vertices along the chart boundaries
+    let mut chart_of = vec![0; triangles.len()];
+    for (chart, (members, _)) in charts.iter().enumerate() {
+        for t in members {
+            chart_of[*t] = chart;
+        }
+    }
+    let mut vertex_map = HashMap::new();
+    let mut original_vertices = Vec::new();
+    let mut uvs = Vec::new();
+    let mut indices = Vec::with_capacity(3 * triangles.len());
+    let mut covered_area = 0.0;
+    for (t, triangle) in triangles.iter().enumerate() {
+        let chart = chart_of[t];
+        let chart_uvs = &charts[chart].1;
+        for i in triangle.iter().copied() {
+            let index = *vertex_map.entry((chart, i)).or_insert_with(|| {
+                original_vertices.push(i);
+                let uv = chart_uvs[&welded[i]] + offsets[chart];
+                uvs.push(vec2(uv.x / width as f32, uv.y / height as f32));
+                original_vertices.len() - 1
+            });
+            indices.push(index as u32);
+        }
+        let p = welded_triangles[t].map(|i| chart_uvs[&i]);
+        covered_area += 0.5 * (p[1] - p[0]).perp_dot(p[2] - p[0]).abs();
+    }
+
+    UvAtlas {
+        mesh: CpuMesh {
+            positions: match &mesh.positions {
+                Positions::F32(p) => Positions::F32(select(p, &original_vertices)),
+                Positions::F64(p) => Positions::F64(select(p, &original_vertices)),
+            },
+            indices: Indices::U32(indices),
+            normals: mesh.normals.as_ref().map(|v| select(v, &original_vertices)),
+            tangents: mesh
+                .tangents
+                .as_ref()
+                .map(|v| select(v, &original_vertices)),
+            uvs: mesh.uvs.as_ref().map(|v| select(v, &original_vertices)),
+            colors: mesh.colors.as_ref().map(|v| select(v, &original_vertices)),
+        },
+        uvs,
+        original_vertices,
+        chart_count: charts.len(),
+        utilization: covered_area / (width as f32 * height as f32),
+        width,
+        height,
+    }
+}
+
+///
+/// Returns true if any two triangles of the given mesh overlap when using the given uv coordinates, one for each vertex of the mesh.
+/// Triangles that only share an edge or a corner do not overlap. This can for example be used to check that the uv coordinates can be used for baking lightmaps,
+/// see [LightmapObject::lightmap_uvs].
+///
+/// # Panic
+/// Will panic if the number of uv coordinates is not the same as the number of vertices.
+///
+pub fn has_overlapping_uvs(mesh: &CpuMesh, uvs: &[Vec2]) -> bool {
+    assert_eq!(
+        uvs.len(),
+        mesh.vertex_count(),
+        "the number of uv coordinates must be the same as the number of vertices"
+    );
+    let mut triangles = Vec::with_capacity(mesh.triangle_count());
+    mesh.for_each_triangle(|i0, i1, i2| triangles.push([uvs[i0], uvs[i1], uvs[i2]]));
+    overlaps(&triangles)
+}
+
+// Flattens the chart using least squares conformal maps and returns the uv coordinates of each welded vertex in the chart,
+// scaled to the same area as the surface, or None if the flattened chart folds over itself
+fn flatten_chart(
+    positions: &[Vec3],
+    triangles: &[[usize; 3]],
+    members: &[usize],
+) -> Option<HashMap<usize, Vec2>> {
+    let mut local = HashMap::new();
+    let mut vertices = Vec::new();
+    let local_triangles = members
+        .iter()
+        .map(|t| {
+            triangles[*t].map(|i| {
+                *local.entry(i).or_insert_with(|| {
+                    vertices.push(i);
+                    vertices.len() - 1
+                })
+            })
+        })
+        .collect::<Vec<_>>();
+
+    // Project onto the plane orthogonal to the average normal, which is the starting point of the solver
+    let normal = members
+        .iter()
+        .map(|t| {
+            let p = triangles[*t].map(|i| positions[i]);
+            (p[1] - p[0]).cross(p[2] - p[0])
+        })
+        .sum::<Vec3>();
+    let normal = if normal.magnitude2() > 0.0 {
+        normal.normalize()
+    } else {
+        vec3(0.0, 0.0, 1.0)
+    };
+    let tangent = if normal.x.abs() < 0.9 {
+        vec3(1.0, 0.0, 0.0)
+    } else {
+        vec3(0.0, 1.0, 0.0)
+    };
+    let e1 = (tangent - normal * normal.dot(tangent)).normalize();
+    let e2 = normal.cross(e1);
+    let projection = vertices
+        .iter()
+        .map(|i| vec2(positions[*i].dot(e1), positions[*i].dot(e2)))
+        .collect::<Vec<_>>();
+
+    // The gradient of the linear basis function of each corner of each triangle in the plane of the triangle, weighted by the area
+    let mut surface_area = 0.0;
+    let gradients = members
+        .iter()
+        .map(|t| {
+            let p = triangles[*t].map(|i| positions[i]);
+            let n = (p[1] - p[0]).cross(p[2] - p[0]);
+            let double_area = n.magnitude();
+            surface_area += 0.5 * double_area;
+            let edge_length2 = (p[1] - p[0]).magnitude2().max((p[2] - p[0]).magnitude2());
+            if double_area <= 1e-6 * edge_length2 {
+                return ([Vec2::zero(); 3], 0.0);
+            }
+            let x = (p[1] - p[0]).normalize();
+            let y = (n / double_area).cross(x);
+            let q = p.map(|corner| vec2((corner - p[0]).dot(x), (corner - p[0]).dot(y)));
+            let gradient = |j: usize| {
+                let e = q[(j + 2) % 3] - q[(j + 1) % 3];
+                vec2(-e.y, e.x) / double_area
+            };
+            ([gradient(0), gradient(1), gradient(2)], 0.5 * double_area)
+        })
+        .collect::<Vec<_>>();
+
+    // Pin two vertices far apart to their projected position and solve for the rest using conjugate gradients
+    let mut uvs = projection.clone();
+    if vertices.len() > 3 {
+        let pin_a = (0..vertices.len())
+            .min_by(|a, b| projection[*a].x.total_cmp(&projection[*b].x))
+            .unwrap();
+        let pin_b = (0..vertices.len())
+            .max_by(|a, b| {
+                let distance = |i: usize| projection[i].distance2(projection[pin_a]);
+                distance(*a).total_cmp(&distance(*b))
+            })
+            .unwrap();
+        let free = |i: usize| i != pin_a && i != pin_b;
+        let apply = |x: &[Vec2]| {
+            let mut result = vec![Vec2::zero(); x.len()];
+            for (triangle, (g, area)) in local_triangles.iter().zip(gradients.iter()) {
+                if *area == 0.0 {
+                    continue;
+                }
+                let r = (0..3)
+                    .map(|j| g[j] * x[triangle[j]].y - perp(g[j]) * x[triangle[j]].x)
+                    .sum::<Vec2>();
+                for j in 0..3 {
+                    result[triangle[j]] += vec2(-area * perp(g[j]).dot(r), area * g[j].dot(r));
+                }
+            }
+            for (i, r) in result.iter_mut().enumerate() {
+                if !free(i) {
+                    *r = Vec2::zero();
+                }
+            }
+            result
+        };
+        let dot = |a: &[Vec2], b: &[Vec2]| a.iter().zip(b).map(|(a, b)| a.dot(*b)).sum::<f32>();
+        let mut r = apply(&uvs).into_iter().map(|r| -r).collect::<Vec<_>>();
+        let mut p = r.clone();
+        let mut rr = dot(&r, &r);
+        let tolerance = rr * 1e-10;
+        for _ in 0..(2 * vertices.len()).min(1000) {
+            if rr <= tolerance || rr == 0.0 {
+                break;
+            }
+            let ap = apply(&p);
+            let pap = dot(&p, &ap);
+            if pap <= 0.0 {
+                break;
+            }
+            let alpha = rr / pap;
+            for i in 0..uvs.len() {
+                uvs[i] += p[i] * alpha;
+                r[i] -= ap[i] * alpha;
+            }
+            let rr_new = dot(&r, &r);
+            for i in 0..p.len() {
+                p[i] = r[i] + p[i] * (rr_new / rr);
+            }
+            rr = rr_new;
+        }
+    }
+
+    // Use the solution if it is valid, otherwise the projection if that is valid
+    let valid = |uvs: &[Vec2]| {
+        uvs.iter().all(|uv| uv.x.is_finite() && uv.y.is_finite())
+            && local_triangles
+                .iter()
+                .zip(gradients.iter())
+                .all(|(t, (_, area))| {
+                    *area == 0.0 || (uvs[t[1]] - uvs[t[0]]).perp_dot(uvs[t[2]] - uvs[t[0]]) > 0.0
+                })
+            && !overlaps(
+                &local_triangles
+                    .iter()
+                    .map(|t| t.map(|i| uvs[i]))
+                    .collect::<Vec<_>>(),
+            )
+    };
+    let uvs = if valid(&uvs) {
+        uvs
+    } else if valid(&projection) {
+        projection
+    } else {
+        return None;
+    };
+
+    let uv_area = local_triangles
+        .iter()
+        .map(|t| 0.5 * (uvs[t[1]] - uvs[t[0]]).perp_dot(uvs[t[2]] - uvs[t[0]]))
+        .sum::<f32>();
+    let scale = if uv_area > 0.0 && surface_area > 0.0 {
+        (surface_area / uv_area).sqrt()
+    } else {
+        1.0
+    };
+    Some(
+        vertices
+            .into_iter()
+            .zip(uvs)
+            .map(|(i, uv)| (i, uv * scale))
+            .collect(),
+    )
+}
+
+// Splits the chart in two halves along the longest axis of its bounding box and returns the connected parts of each half
+fn split_chart(
+    positions: &[Vec3],
+    triangles: &[[usize; 3]],
+    neighbours: &[Vec<usize>],
+    members: &[usize],
+) -> Vec<Vec<usize>> {
+    if members.len() == 1 {
+        // A single triangle always flattens, unless it is degenerate in which case it is flattened as a point
+        return vec![members.to_vec()];
+    }
+    let centroid = |t: usize| triangles[t].iter().map(|i| positions[*i]).sum::<Vec3>() / 3.0;
+    let mut aabb = AxisAlignedBoundingBox::EMPTY;
+    aabb.expand(&members.iter().map(|t| centroid(*t)).collect::<Vec<_>>());
+    let size = aabb.size();
+    let axis = if size.x >= size.y && size.x >= size.z {
+        0
+    } else if size.y >= size.z {
+        1
+    } else {
+        2
+    };
+    let mut sorted = members.to_vec();
+    sorted.sort_by(|a, b| centroid(*a)[axis].total_cmp(&centroid(*b)[axis]));
+    let (first, second) = sorted.split_at(sorted.len() / 2);
+
+    let mut parts = Vec::new();
+    for half in [first, second] {
+        let mut remaining = half
+            .iter()
+            .copied()
+            .collect::<std::collections::HashSet<_>>();
+        while let Some(seed) = remaining.iter().next().copied() {
+            remaining.remove(&seed);
+            let mut part = vec![seed];
+            let mut queue = VecDeque::from([seed]);
+            while let Some(t) = queue.pop_front() {
+                for n in neighbours[t].iter() {
+                    if remaining.remove(n) {
+                        part.push(*n);
+                        queue.push_back(*n);
+                    }
+                }
+            }
+            parts.push(part);
+        }
+    }
+    parts
+}
+
+// Returns true if any two of the triangles overlap with more than a small tolerance, using a grid to only test triangles close to each other
+fn overlaps(triangles: &[[Vec2; 3]]) -> bool {
+    let triangles = triangles
+        .iter()
+        .filter(|t| (t[1] - t[0]).perp_dot(t[2] - t[0]).abs() > 0.0)
+        .collect::<Vec<_>>();
+    if triangles.len() < 2 {
+        return false;
+    }
+    let (min, max) = bounds(triangles.iter().flat_map(|t| t.iter().copied()));
+    let cells = (triangles.len() as f32).sqrt().ceil().clamp(1.0, 256.0);
+    let cell_size = vec2(
+        ((max.x - min.x) / cells).max(f32::MIN_POSITIVE),
+        ((max.y - min.y) / cells).max(f32::MIN_POSITIVE),
+    );
+    let cell = |p: Vec2| {
+        (
+            (((p.x - min.x) / cell_size.x) as i32).min(cells as i32 - 1),
+            (((p.y - min.y) / cell_size.y) as i32).min(cells as i32 - 1),
+        )
+    };
+    let mut grid: HashMap<(i32, i32), Vec<usize>> = HashMap::new();
+    for (index, triangle) in triangles.iter().enumerate() {
+        let (t_min, t_max) = bounds(triangle.iter().copied());
+        let (c_min, c_max) = (cell(t_min), cell(t_max));
+        for x in c_min.0..=c_max.0 {
+            for y in c_min.1..=c_max.1 {
+                let entry = grid.entry((x, y)).or_default();
+                if entry
+                    .iter()
+                    .any(|other| triangles_overlap(triangle, triangles[*other]))
+                {
+                    return true;
+                }
+                entry.push(index);
+            }
+        }
+    }
+    false
+}
+
+// Separating axis test for two triangles, where triangles touching along an edge or at a corner do not overlap
+fn triangles_overlap(a: &[Vec2; 3], b: &[Vec2; 3]) -> bool {
+    for triangle in [a, b] {
+        for k in 0..3 {
+            let edge = triangle[(k + 1) % 3] - triangle[k];
+            if edge.magnitude2() == 0.0 {
+                continue;
+            }
+            // Relative to the start of the edge and without normalizing the axis, so the corners on the edge project to exactly zero
+            let axis = perp(edge);
+            let project = |t: &[Vec2; 3]| {
+                let d = t.map(|p| (p - triangle[k]).dot(axis));
+                (d[0].min(d[1]).min(d[2]), d[0].max(d[1]).max(d[2]))
+            };
+            let ((a_min, a_max), (b_min, b_max)) = (project(a), project(b));
+            let tolerance = 1e-5 * (a_max - a_min).max(b_max - b_min);
+            if a_max.min(b_max) - a_min.max(b_min) <= tolerance {
+                return false;
+            }
+        }
+    }
+    true
+}
+
+fn select<T: Clone>(values: &[T], indices: &[usize]) -> Vec<T> {
+    indices.iter().map(|i| values[*i].clone()).collect()
+}
+
+fn perp(v: Vec2) -> Vec2 {
+    vec2(-v.y, v.x)
+}
+
+fn rotate(v: Vec2, angle: f32) -> Vec2 {
+    let (sin, cos) = angle.sin_cos();
+    vec2(cos * v.x - sin * v.y, sin * v.x + cos * v.y)
+}
+
+fn bounds(points: impl Iterator<Item = Vec2>) -> (Vec2, Vec2) {
+    points.fold(
+        (vec2(f32::MAX, f32::MAX), vec2(f32::MIN, f32::MIN)),
+        |(min, max), p| {
+            (
+                vec2(min.x.min(p.x), min.y.min(p.y)),
+                vec2(max.x.max(p.x), max.y.max(p.y)),
+            )
+        },
+    )
+}
@@ -0,0 +1,656 @@
+//!
+//! Deterministic offline rendering of many frames, for example to generate synthetic training data, see [BatchRenderer].
+//!
+
+use crate::renderer::*;
+use std::io::Write;
+use std::path::Path;
+
+///
+/// The passes rendered by a [BatchRenderer] for each frame. Only the color pass is enabled by default.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BatchPasses {
+    /// The color of the scene as seen by the camera, see [BatchOutput::color] and [BatchOutput::color_f32].
+    pub color: bool,
+    /// The distance from the camera to the closest surface, see [BatchOutput::depth].
+    pub depth: bool,
+    /// The normal of the closest surface, see [BatchOutput::normal].
+    pub normal: bool,
+    /// The index of the closest object and instance, see [BatchOutput::instance_id].
+    pub instance_id: bool,
+}
+
+impl Default for BatchPasses {
+    fn default() -> Self {
+        Self {
+            color: true,
+            depth: false,
+            normal: false,
+            instance_id: false,
+        }
+    }
+}
+
+impl BatchPasses {
+    ///
+    /// All passes enabled.
+    ///
+    pub const ALL: Self = Self {
+        color: true,
+        depth: true,
+        normal: true,
+        instance_id: true,
+    };
+}
+
+///
+/// Settings for a [BatchRenderer].
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BatchSettings {
+    /// The width of the rendered images in pixels.
+    pub width: u32,
+    /// The height of the rendered images in pixels.
+    pub height: u32,
+    /// The passes to render for each frame.
+    pub passes: BatchPasses,
+    /// The seed of all random elements, ie. the sub-pixel jitter, the dithering noise and the [BatchRandom] given to [BatchScene::prepare].
+    /// Two runs with the same seed, scene and frames produce identical pixels on the same hardware and driver.
+    pub seed: u32,
+    /// The number of jittered samples per pixel which are averaged to produce the color pass. A value of 1 disables the jitter.
+    /// The depth, normal and instance ID passes are always rendered without jitter.
+    pub samples: u32,
+    /// Whether to add noise before the color is quantized to 8 bits per channel, which avoids banding in smooth gradients.
+    pub dither: bool,
+    /// The color the color pass is cleared to before rendering the scene.
+    pub clear_color: Srgba,
+}
+
+impl Default for BatchSettings {
+    fn default() -> Self {
+        Self {
+            width: 512,
+            height: 512,
+            passes: BatchPasses::default(),
+            seed: 0,
+            samples: 1,
+            dither: false,
+            clear_color: Srgba::BLACK,
+        }
+    }
+}
+
+///
+/// The position, target and up direction of the camera in a frame rendered by a [BatchRenderer].
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CameraPose {
+    /// The position of the camera.
+    pub position: Vec3,
+    /// The position the camera looks at.
+    pub target: Vec3,
+    /// The up direction of the camera.
+    pub up: Vec3,
+}
+
+///
+/// A frame to render with [BatchRenderer::render], ie. a camera pose and the per frame overrides which are given to [BatchScene::prepare].
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct BatchFrame<T> {
+    /// The camera pose of this frame.
+    pub pose: CameraPose,
+    /// The overrides applied to the scene before this frame is rendered, for example the light intensities or object transformations.
+    pub overrides: T,
+}
+
+///
+/// A scene rendered by a [BatchRenderer].
+///
+pub trait BatchScene {
+    ///
+    /// The per frame overrides, see [BatchFrame::overrides].
+    ///
+    type Overrides;
+
+    ///
+    /// Applies the overrides of a frame to the scene before it is rendered.
+    /// The given random number generator is seeded by the [BatchSettings::seed] and the frame index,
+    /// so it can be used to randomize the scene without affecting the determinism or the other frames.
+    ///
+    fn prepare(
+        &mut self,
+        frame_index: usize,
+        overrides: &Self::Overrides,
+        random: &mut BatchRandom,
+    );
+
+    ///
+    /// Returns the objects in the scene. The index of an object in this list is the object index in the [BatchOutput::instance_id] pass.
+    ///
+    fn objects(&self) -> Vec<&dyn Object>;
+
+    ///
+    /// Returns the lights in the scene.
+    ///
+    fn lights(&self) -> Vec<&dyn Light>;
+}
+
+///
+/// A small, fast and deterministic pseudo random number generator (PCG32), used for the random elements of a [BatchRenderer].
+/// It is not suitable for cryptographic purposes.
+///
+/// ```
+/// # use three_d::*;
+/// let mut a = BatchRandom::new(42, 7);
+/// let mut b = BatchRandom::new(42, 7);
+/// for _ in 0..100 {
+///     assert_eq!(a.next_u32(), b.next_u32());
+/// }
+/// let value = a.range(-1.0, 1.0);
+/// assert!((-1.0..1.0).contains(&value));
+/// ```
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BatchRandom {
+    state: u64,
+    increment: u64,
+}
+
+impl BatchRandom {
+    ///
+    /// Creates a new random number generator from a seed and a stream, for example the frame index.
+    /// Different streams with the same seed give independent sequences.
+    ///
+    pub fn new(seed: u32, stream: u64) -> Self {
+        let mut random = Self {
+            state: 0,
+            increment: (stream << 1) | 1,
+        };
+        random.next_u32();
+        random.state = random.state.wrapping_add(seed as u64);
+        random.next_u32();
+        random
+    }
+
+    ///
+    /// Returns the next random number.
+    ///
+    pub fn next_u32(&mut self) -> u32 {
+        let state = self.state;
+        self.state = state
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(self.increment);
+        let xorshifted = (((state >> 18) ^ state) >> 27) as u32;
+        xorshifted.rotate_right((state >> 59) as u32)
+    }
+
+    ///
+    /// Returns the next random number in the range `[0, 1)`.
+    ///
+    pub fn next_f32(&mut self) -> f32 {
+        (self.next_u32() >> 8) as f32 / (1u32 << 24) as f32
+    }
+
+    ///
+    /// Returns the next random number in the range `[min, max)`.
+    ///
+    pub fn range(&mut self, min: f32, max: f32) -> f32 {
+        min + (max - min) * self.next_f32()
+    }
+}
+
+///
+/// The result of rendering a frame with a [BatchRenderer]. The pixels are stored row by row starting at the top left corner.
+/// The buffers are owned by the batch renderer and reused for the next frame, so copy the data if it is needed after the callback returns.
+///
+pub struct BatchOutput<'a> {
+    /// The index of the frame.
+    pub frame_index: usize,
+    /// The width of the images in pixels.
+    pub width: u32,
+    /// The height of the images in pixels.
+    pub height: u32,
+    /// The color pass in sRGB color space quantized to 8 bits per channel, if enabled.
+    pub color: Option<&'a [[u8; 4]]>,
+    /// The color pass in sRGB color space before it is quantized, ie. the average of the jittered samples, if enabled.
+    pub color_f32: Option<&'a [[f32; 4]]>,
+    /// The distance from the camera to the closest surface in world units, or infinity if nothing is hit, if enabled.
+    pub depth: Option<&'a [f32]>,
+    /// The world space normal of the closest surface facing the camera, or zero if nothing is hit, if enabled.
+    pub normal: Option<&'a [[f32; 3]]>,
+    /// The index of the closest object in [BatchScene::objects] and the index of the instance if the object is instanced (otherwise 0),
+    /// or `[u32::MAX, u32::MAX]` if nothing is hit, if enabled.
+    pub instance_id: Option<&'a [[u32; 2]]>,
+}
+
+impl BatchOutput<'_> {
+    ///
+    /// Returns a 64-bit FNV-1a hash of all enabled passes which can be used to verify that two runs produce identical pixels.
+    ///
+    pub fn checksum(&self) -> u64 {
+        let mut hash = 0xcbf29ce484222325u64;
+        let mut add = |bytes: &[u8]| {
+            for byte in bytes {
+                hash = (hash ^ *byte as u64).wrapping_mul(0x100000001b3);
+            }
+        };
+        if let Some(color) = self.color {
+            color.iter().for_each(|c| add(c));
+        }
+        if let Some(depth) = self.depth {
+            depth.iter().for_each(|d| add(&d.to_bits().to_le_bytes()));
+        }
+        if let Some(normal) = self.normal {
+            normal
+                .iter()
+                .flatten()
+                .for_each(|n| add(&n.to_bits().to_le_bytes()));
+        }
+        if let Some(instance_id) = self.instance_id {
+            instance_id
+                .iter()
+                .flatten()
+                .for_each(|id| add(&id.to_le_bytes()));
+        }
+        hash
+    }
+
+    ///
+    /// Saves the enabled passes as numbered files in the given directory, which must exist:
+    /// - `{frame_index:06}_color.ppm` - the 8-bit color pass without alpha (binary PPM).
+    /// - `{frame_index:06}_depth.pfm` - the depth pass (grayscale PFM).
+    /// - `{frame_index:06}_normal.pfm` - the normal pass (color PFM).
+    /// - `{frame_index:06}_instance_id.pgm` - the object index plus one, so 0 means that nothing is hit, as 16-bit values (binary PGM).
+    ///   Use [BatchOutput::instance_id] directly in a callback to also get the instance index.
+    ///
+    pub fn save(&self, directory: impl AsRef<Path>) -> Result<(), RendererError> {
+        let directory = directory.as_ref();
+        let (width, height) = (self.width as usize, self.height as usize);
+        let file = |name: &str| -> Result<_, RendererError> {
+            Ok(std::io::BufWriter::new(std::fs::File::create(
+                directory.join(format!("{:06}_{}", self.frame_index, name)),
+            )?))
+        };
+        if let Some(color) = self.color {
+            let mut writer = file("color.ppm")?;
+            write!(writer, "P6\n{} {}\n255\n", width, height)?;
+            for c in color {
+                writer.write_all(&c[..3])?;
+            }
+            writer.flush()?;
+        }
+        // The rows of a PFM file are stored from the bottom to the top
+        if let Some(depth) = self.depth {
+            let mut writer = file("depth.pfm")?;
+            write!(writer, "Pf\n{} {}\n-1.0\n", width, height)?;
+            for row in depth.chunks(width).rev() {
+                for d in row {
+                    writer.write_all(&d.to_le_bytes())?;
+                }
+            }
+            writer.flush()?;
+        }
+        if let Some(normal) = self.normal {
+            let mut writer = file("normal.pfm")?;
+            write!(writer, "PF\n{} {}\n-1.0\n", width, height)?;
+            for row in normal.chunks(width).rev() {
+                for n in row.iter().flatten() {
+                    writer.write_all(&n.to_le_bytes())?;
+                }
+            }
+            writer.flush()?;
+        }
+        if let Some(instance_id) = self.instance_id {
+            let mut writer = file("instance_id.pgm")?;
+            write!(writer, "P5\n{} {}\n65535\n", width, height)?;
+            for id in instance_id {
+                let value = id[0].wrapping_add(1).min(u16::MAX as u32) as u16;
+                writer.write_all(&value.to_be_bytes())?;
+            }
+            writer.flush()?;
+        }
+        Ok(())
+    }
+}
+
+///
+/// Renders many frames of a scene offline, for example using a [HeadlessContext](crate::HeadlessContext) to generate synthetic training data.
+/// For each frame, the camera pose and overrides are applied to the scene, the enabled passes (see [BatchPasses]) are rendered and read back
+/// and the result is given to a callback, which for example can save the frame using [BatchOutput::save].
+///
+/// The render targets and the pixel buffers are allocated once and reused for all frames.
+/// All random elements are seeded by [BatchSettings::seed] and the frame index, so rendering the same frames twice produce identical pixels,
+/// which can be verified using [BatchOutput::checksum].
+/// Parallel shader compilation (see [Context::set_parallel_shader_compilation]) is disabled while rendering,
+/// since the fallback material would otherwise make the result depend on the time it takes to compile the shaders.
+///
+pub struct BatchRenderer {
+    context: Context,
+    settings: BatchSettings,
+    color_texture: Texture2D,
+    depth_texture: DepthTexture2D,
+    pixels: Vec<[f32; 4]>,
+    color_f32: Vec<[f32; 4]>,
+    color: Vec<[u8; 4]>,
+    depth: Vec<f32>,
+    normal: Vec<[f32; 3]>,
+    instance_id: Vec<[u32; 2]>,
+}
+
+impl BatchRenderer {
+    ///
+    /// Creates a new batch renderer with the given settings and allocates the render targets and pixel buffers.
+    ///
+    pub fn new(context: &Context, settings: BatchSettings) -> Self {
+        let (width, height) = (settings.width.max(1), settings.height.max(1));
+        let settings = BatchSettings {
+            width,
+            height,
+            samples: settings.samples.max(1),
+            ..settings
+        };
+        let pixel_count = width as usize * height as usize;
+        let passes = settings.passes;
+        let buffer = |enabled: bool| if enabled { pixel_count } else { 0 };
+        Self {
+            context: context.clone(),
+            settings,
+            color_texture: Texture2D::new_empty::<[f32; 4]>(
+                context,
+                width,
+                height,
+                Interpolation::Nearest,
+                Interpolation::Nearest,
+                None,
+                Wrapping::ClampToEdge,
+                Wrapping::ClampToEdge,
+            ),
+            depth_texture: DepthTexture2D::new::<f32>(
+                context,
+                width,
+                height,
+                Wrapping::ClampToEdge,
+                Wrapping::ClampToEdge,
+            ),
+            pixels: vec![[0.0; 4]; pixel_count],
+            color_f32: vec![[0.0; 4]; buffer(passes.color)],
+            color: vec![[0; 4]; buffer(passes.color)],
+            depth: vec![0.0; buffer(passes.depth)],
+            normal: vec![[0.0; 3]; buffer(passes.normal)],
+            instance_id: vec![[0; 2]; buffer(passes.instance_id)],
+        }
+    }
+
+    ///
+    /// Returns the settings of this batch renderer.
+    ///
+    pub fn settings(&self) -> BatchSettings {
+        self.settings
+    }
+
+    ///
+    /// Renders the given frames of the scene in order and calls the callback with the result of each frame.
+    /// The projection of the given camera is used for all frames, and its view is replaced by the [CameraPose] of each frame.
+    /// The frame index given to [BatchScene::prepare] and in [BatchOutput::frame_index] is the index in the given frames.
+    /// Stops and returns the error if rendering a frame or the callback fails.
+    ///
+    pub fn render<S: BatchScene>(
+        &mut self,
+        scene: &mut S,
+        camera: &Camera,
+        frames: impl IntoIterator<Item = BatchFrame<S::Overrides>>,
+        mut callback: impl FnMut(&BatchOutput) -> Result<(), RendererError>,
+    ) -> Result<(), RendererError> {
+        let mut camera = camera.clone();
+        for (frame_index, frame) in frames.into_iter().enumerate() {
+            camera.set_view(frame.pose.position, frame.pose.target, frame.pose.up);
+            let mut random = BatchRandom::new(self.settings.seed, frame_index as u64);
+            scene.prepare(frame_index, &frame.overrides, &mut random);
+            let output =
+                self.render_frame(frame_index, &camera, &scene.objects(), &scene.lights())?;
+            callback(&output)?;
+        }
+        Ok(())
+    }
+
+    ///
+    /// Renders a single frame of the given objects and lights as seen by the given camera, where the viewport of the camera is replaced by the size of the images.
+    /// The frame index is used together with [BatchSettings::seed] to seed the sub-pixel jitter and dithering noise.
+    ///
+    pub fn render_frame(
+        &mut self,
+        frame_index: usize,
+        camera: &Camera,
+        objects: &[&dyn Object],
+        lights: &[&dyn Light],
+    ) -> Result<BatchOutput<'_>, RendererError> {
+        let context = self.context.clone();
+        with_synchronous_shader_compilation(&context, || {
+            self.render_passes(frame_index, camera, objects, lights)
+        })?;
+
+        let passes = self.settings.passes;
+        Ok(BatchOutput {
+            frame_index,
+            width: self.settings.width,
+            height: self.settings.height,
+            color: passes.color.then_some(&self.color[..]),
+            color_f32: passes.color.then_some(&self.color_f32[..]),
+            depth: passes.depth.then_some(&self.depth[..]),
+            normal: passes.normal.then_some(&self.normal[..]),
+            instance_id: passes.instance_id.then_some(&self.instance_id[..]),
+        })
+    }
+
+    fn render_passes(
+        &mut self,
+        frame_index: usize,
+        camera: &Camera,
+        objects: &[&dyn Object],
+        lights: &[&dyn Light],
+    ) -> Result<(), RendererError> {
+        let BatchSettings {
+            width,
+            height,
+            passes,
+            seed,
+            samples,
+            dither,
+            clear_color,
+        } = self.settings;
+        let mut camera = camera.clone();
+        camera.set_viewport(Viewport::new_at_origo(width, height));
+        let mut random = BatchRandom::new(seed, !(frame_index as u64));
+        let depth_mode = camera.depth_mode();
+
+        if passes.color {
+            self.color_f32.iter_mut().for_each(|c| *c = [0.0; 4]);
+            // A Cranley-Patterson rotation of the Halton sequence gives well distributed sample positions which are different for each frame
+            let rotation = vec2(random.next_f32(), random.next_f32());
+            for sample in 0..samples {
+                let jitter = if samples > 1 {
+                    vec2(
+                        (halton(sample + 1, 2) + rotation.x).fract() - 0.5,
+                        (halton(sample + 1, 3) + rotation.y).fract() - 0.5,
+                    )
+                } else {
+                    vec2(0.0, 0.0)
+                };
+                let viewer = JitteredViewer {
+                    viewer: &camera,
+                    offset: vec2(
+                        2.0 * jitter.x / width as f32,
+                        2.0 * jitter.y / height as f32,
+                    ),
+                };
+                RenderTarget::new(
+                    self.color_texture.as_color_target(None),
+                    self.depth_texture.as_depth_target(),
+                )
+                .clear(
+                    ClearState::color_and_depth(
+                        clear_color.r as f32 / 255.0,
+                        clear_color.g as f32 / 255.0,
+                        clear_color.b as f32 / 255.0,
+                        clear_color.a as f32 / 255.0,
+                        1.0,
+                    )
+                    .with_depth_mode(depth_mode),
+                )
+                .render(&viewer, objects, lights)
+                .read_color_into(&mut self.pixels);
+                for (sum, pixel) in self.color_f32.iter_mut().zip(&self.pixels) {
+                    for i in 0..4 {
+                        sum[i] += pixel[i];
+                    }
+                }
+            }
+            let scale = 1.0 / samples as f32;
+            for (color, sum) in self.color.iter_mut().zip(self.color_f32.iter_mut()) {
+                for i in 0..4 {
+                    sum[i] *= scale;
+                    let noise = if dither { random.next_f32() } else { 0.5 };
+                    color[i] = (sum[i].clamp(0.0, 1.0) * 255.0 + noise).min(255.0) as u8;
+                }
+            }
+        }
+
+        if passes.normal {
+            let target = RenderTarget::new(
+                self.color_texture.as_color_target(None),
+                self.depth_texture.as_depth_target(),
+            );
+            target
+                .clear(
+                    ClearState::color_and_depth(0.5, 0.5, 0.5, 0.0, 1.0)
+                        .with_depth_mode(depth_mode),
+                )
+                .write::<RendererError>(|| {
+                    for object in objects {
+                        render_with_material(
+                            &self.context,
+                            &camera,
+                            object,
+                            NormalMaterial::default(),
+                            &[],
+                        )?;
+                    }
+                    Ok(())
+                })?
+                .read_color_into(&mut self.pixels);
+            for (normal, pixel) in self.normal.iter_mut().zip(&self.pixels) {
+                *normal = [
+                    2.0 * pixel[0] - 1.0,
+                    2.0 * pixel[1] - 1.0,
+                    2.0 * pixel[2] - 1.0,
+                ];
+            }
+        }
+
+        if passes.depth || passes.instance_id {
+            let mut material = IntersectionMaterial {
+                min_distance: Some(0.0),
+                max_distance: Some(camera.z_far()),
+                ..Default::default()
+            };
+            let target = RenderTarget::new(
+                self.color_texture.as_color_target(None),
+                self.depth_texture.as_depth_target(),
+            );
+            target
+                .clear(
+                    ClearState::color_and_depth(1.0, 1.0, 1.0, 1.0, 1.0)
+                        .with_depth_mode(depth_mode),
+                )
+                .write::<RendererError>(|| {
+                    for (index, object) in objects.iter().enumerate() {
+                        material.geometry_id = index as u32;
+                        render_with_material(&self.context, &camera, object, &material, &[])?;
+                    }
+                    Ok(())
+                })?
+                .read_color_into(&mut self.pixels);
+            let z_far = camera.z_far();
+            for (i, pixel) in self.pixels.iter().enumerate() {
+                let hit = pixel[0] < 1.0;
+                if passes.depth {
+                    self.depth[i] = if hit { pixel[0] * z_far } else { f32::INFINITY };
+                }
+                if passes.instance_id {
+                    self.instance_id[i] = if hit {
+                        [pixel[1].to_bits(), pixel[2].to_bits()]
+                    } else {
+                        [u32::MAX, u32::MAX]
+                    };
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+///
+/// Returns the element with the given index in the Halton sequence with the given base.
+///
+pub(crate) fn halton(mut index: u32, base: u32) -> f32 {
+    let mut result = 0.0;
+    let mut fraction = 1.0;
+    while index > 0 {
+        fraction /= base as f32;
+        result += fraction * (index % base) as f32;
+        index /= base;
+    }
+    result
+}
+
+///
+/// A viewer which offsets the projection of another viewer by the given amount in normalized device coordinates, used for sub-pixel jitter.
+///
+struct JitteredViewer<'a> {
+    viewer: &'a dyn Viewer,
+    offset: Vec2,
+}
+
+impl Viewer for JitteredViewer<'_> {
+    fn position(&self) -> Vec3 {
+        self.viewer.position()
+    }
+
+    fn view(&self) -> Mat4 {
+        self.viewer.view()
+    }
+
+    fn projection(&self) -> Mat4 {
+        Mat4::from_translation(self.offset.extend(0.0)) * self.viewer.projection()
+    }
+
+    fn viewport(&self) -> Viewport {
+        self.viewer.viewport()
+    }
+
+    fn z_near(&self) -> f32 {
+        self.viewer.z_near()
+    }
+
+    fn z_far(&self) -> f32 {
+        self.viewer.z_far()
+    }
+
+    fn color_mapping(&self) -> ColorMapping {
+        self.viewer.color_mapping()
+    }
+
+    fn tone_mapping(&self) -> ToneMapping {
+        self.viewer.tone_mapping()
+    }
+
+    fn depth_mode(&self) -> DepthMode {
+        self.viewer.depth_mode()
+    }
+
+    fn exposure(&self) -> f32 {
+        self.viewer.exposure()
+    }
+}
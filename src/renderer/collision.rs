@@ -0,0 +1,830 @@
+//!
+//! Simple collision queries on the CPU against triangle meshes and analytic shapes, for example to stop the camera or a player from moving through walls,
+//! see [Collider], [capsule_cast] and [slide].
+//! The queries give the same result each time for the same input and do not allocate.
+//!
+
+use crate::renderer::*;
+
+const EPSILON: f32 = 1.0e-9;
+
+///
+/// A capsule, ie. all points within the radius of the line segment between the start and end point.
+/// If the start and end point are the same, the capsule is a sphere, see [Capsule::sphere].
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Capsule {
+    /// The start point of the line segment.
+    pub start: Vec3,
+    /// The end point of the line segment.
+    pub end: Vec3,
+    /// The radius.
+    pub radius: f32,
+}
+
+impl Capsule {
+    ///
+    /// Creates a new capsule around the line segment between the start and end point.
+    ///
+    pub fn new(start: Vec3, end: Vec3, radius: f32) -> Self {
+        Self { start, end, radius }
+    }
+
+    ///
+    /// Creates a new sphere with the given center and radius.
+    ///
+    pub fn sphere(center: Vec3, radius: f32) -> Self {
+        Self::new(center, center, radius)
+    }
+
+    ///
+    /// Returns this capsule moved by the given translation.
+    ///
+    pub fn translated(&self, translation: Vec3) -> Self {
+        Self::new(
+            self.start + translation,
+            self.end + translation,
+            self.radius,
+        )
+    }
+
+    ///
+    /// Returns the axis aligned bounding box of this capsule.
+    ///
+    pub fn aabb(&self) -> AxisAlignedBoundingBox {
+        let r = vec3(self.radius, self.radius, self.radius);
+        AxisAlignedBoundingBox::new_with_positions(&[
+            self.start - r,
+            self.start + r,
+            self.end - r,
+            self.end + r,
+        ])
+    }
+}
+
+///
+/// The result of a collision query, see [Collider::ray_cast] and [Collider::capsule_cast].
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CollisionHit {
+    /// For a ray cast, the distance along the ray to the hit.
+    /// For a sphere or capsule cast, the fraction of the motion, between 0 and 1, at which the shape hits the collider.
+    pub time: f32,
+    /// The position of the hit on the surface of the collider.
+    pub position: Vec3,
+    /// The normal of the collider surface at the hit, pointing towards the ray origin or the moving shape.
+    pub normal: Vec3,
+    /// The index of the hit collider when querying a list of colliders, otherwise 0.
+    pub collider: usize,
+}
+
+///
+/// A static shape which rays, spheres and capsules can collide with.
+/// Each query only depends on the given shapes, so colliders can be added, removed or moved between queries without any other updates.
+///
+pub enum Collider {
+    /// The triangles of a mesh, see [Collider::new_mesh].
+    /// Triangles are two-sided, so the mesh does not need to be closed.
+    Mesh(Bvh),
+    /// A capsule or sphere.
+    Capsule(Capsule),
+    /// An axis aligned box.
+    Box(AxisAlignedBoundingBox),
+}
+
+impl Collider {
+    ///
+    /// Creates a new collider from the triangles of the given mesh.
+    /// Use [CpuMesh::transform] first if the mesh is not already in world space.
+    ///
+    pub fn new_mesh(mesh: &CpuMesh) -> Self {
+        Self::Mesh(Bvh::new(mesh))
+    }
+
+    ///
+    /// Returns the axis aligned bounding box of this collider.
+    ///
+    pub fn aabb(&self) -> AxisAlignedBoundingBox {
+        match self {
+            Self::Mesh(bvh) => bvh.aabb(),
+            Self::Capsule(capsule) => capsule.aabb(),
+            Self::Box(aabb) => *aabb,
+        }
+    }
+
+    ///
+    /// Returns the closest hit between the ray and this collider within the given maximum distance, or `None` if the ray does not hit the collider.
+    /// The direction must be normalized.
+    /// Rays starting inside a capsule or box do not hit it.
+    ///
+    pub fn ray_cast(
+        &self,
+        origin: Vec3,
+        direction: Vec3,
+        max_distance: f32,
+    ) -> Option<CollisionHit> {
+        let (time, position, normal) = match self {
+            Self::Mesh(bvh) => {
+                let (t, triangle) = bvh.intersect(origin, direction, max_distance)?;
+                let [p0, p1, p2] = bvh.triangle(triangle);
+                let normal = (p1 - p0).cross(p2 - p0).normalize();
+                let normal = if normal.dot(direction) > 0.0 {
+                    -normal
+                } else {
+                    normal
+                };
+                (t, origin + direction * t, normal)
+            }
+            Self::Capsule(capsule) => {
+                let (t, axis_point) = point_capsule_cast(
+                    origin,
+                    direction,
+                    capsule.start,
+                    capsule.end,
+                    capsule.radius,
+                    max_distance,
+                )?;
+                let position = origin + direction * t;
+                (t, position, (position - axis_point).normalize())
+            }
+            Self::Box(aabb) => ray_box(origin, direction, aabb.min(), aabb.max(), max_distance)?,
+        };
+        Some(CollisionHit {
+            time,
+            position,
+            normal,
+            collider: 0,
+        })
+    }
+
+    ///
+    /// Returns the first hit between the given capsule, moved by the given motion, and this collider, or `None` if the capsule can move freely.
+    /// A capsule which already overlaps the collider hits it at time 0, unless it moves away from the collider.
+    ///
+    pub fn capsule_cast(&self, capsule: &Capsule, motion: Vec3) -> Option<CollisionHit> {
+        let (time, position, normal) = match self {
+            Self::Mesh(bvh) => {
+                let mut swept = capsule.aabb();
+                swept.expand_with_aabb(capsule.translated(motion).aabb());
+                let (swept_min, swept_max) = (swept.min(), swept.max());
+                let mut closest = None;
+                bvh.visit(
+                    |min, max| boxes_overlap(min, max, swept_min, swept_max),
+                    |triangle| {
+                        closest = earliest(
+                            closest,
+                            capsule_triangle_cast(capsule, motion, bvh.triangle(triangle)),
+                        );
+                        false
+                    },
+                );
+                closest?
+            }
+            Self::Capsule(other) => capsule_capsule_cast(capsule, motion, other)?,
+            Self::Box(aabb) => {
+                let mut closest = None;
+                for triangle in box_triangles(aabb.min(), aabb.max()) {
+                    closest = earliest(closest, capsule_triangle_cast(capsule, motion, triangle));
+                }
+                closest?
+            }
+        };
+        Some(CollisionHit {
+            time,
+            position,
+            normal,
+            collider: 0,
+        })
+    }
+
+    ///
+    /// Returns whether or not the given axis aligned bounding box overlaps this collider.
+    ///
+    pub fn overlaps_aabb(&self, aabb: &AxisAlignedBoundingBox) -> bool {
+        if aabb.is_empty() {
+            return false;
+        }
+        let (aabb_min, aabb_max) = (aabb.min(), aabb.max());
+        match self {
+            Self::Mesh(bvh) => {
+                let mut overlaps = false;
+                bvh.visit(
+                    |min, max| boxes_overlap(min, max, aabb_min, aabb_max),
+                    |triangle| {
+                        overlaps = triangle_box_overlap(bvh.triangle(triangle), aabb_min, aabb_max);
+                        overlaps
+                    },
+                );
+                overlaps
+            }
+            Self::Capsule(capsule) => {
+                segment_box_distance(capsule.start, capsule.end, aabb_min, aabb_max)
+                    <= capsule.radius
+            }
+            Self::Box(other) => {
+                !other.is_empty() && boxes_overlap(other.min(), other.max(), aabb_min, aabb_max)
+            }
+        }
+    }
+}
+
+///
+/// Returns the closest hit between the ray and the given colliders within the given maximum distance, see [Collider::ray_cast].
+///
+pub fn ray_cast(
+    colliders: &[Collider],
+    origin: Vec3,
+    direction: Vec3,
+    max_distance: f32,
+) -> Option<CollisionHit> {
+    let mut closest: Option<CollisionHit> = None;
+    for (index, collider) in colliders.iter().enumerate() {
+        let max_distance = closest.map(|hit| hit.time).unwrap_or(max_distance);
+        if let Some(hit) = collider.ray_cast(origin, direction, max_distance) {
+            if hit.time < max_distance {
+                closest = Some(CollisionHit {
+                    collider: index,
+                    ..hit
+                });
+            }
+        }
+    }
+    closest
+}
+
+///
+/// Returns the first hit between the sphere with the given center and radius, moved by the given motion, and the given colliders, see [Collider::capsule_cast].
+///
+pub fn sphere_cast(
+    colliders: &[Collider],
+    center: Vec3,
+    radius: f32,
+    motion: Vec3,
+) -> Option<CollisionHit> {
+    capsule_cast(colliders, &Capsule::sphere(center, radius), motion)
+}
+
+///
+/// Returns the first hit between the given capsule, moved by the given motion, and the given colliders, see [Collider::capsule_cast].
+///
+pub fn capsule_cast(
+    colliders: &[Collider],
+    capsule: &Capsule,
+    motion: Vec3,
+) -> Option<CollisionHit> {
+    let mut closest: Option<CollisionHit> = None;
+    for (index, collider) in colliders.iter().enumerate() {
+        if let Some(hit) = collider.capsule_cast(capsule, motion) {
+            if closest.map(|c| hit.time < c.time).unwrap_or(true) {
+                closest = Some(CollisionHit {
+                    collider: index,
+                    ..hit
+                });
+            }
+        }
+    }
+    closest
+}
+
+///
+/// Returns whether or not the given axis aligned bounding box overlaps any of the given colliders, see [Collider::overlaps_aabb].
+///
+pub fn overlaps_aabb(colliders: &[Collider], aabb: &AxisAlignedBoundingBox) -> bool {
+    colliders
+        .iter()
+        .any(|collider| collider.overlaps_aabb(aabb))
+}
+
+///
+/// Moves the given capsule (or sphere, see [Capsule::sphere]) by the given motion and returns the translation it can move without moving through any of the colliders.
+/// When the capsule hits a collider, the rest of the motion is projected onto the surface of the collider, so the capsule slides along walls and floors instead of stopping.
+/// This is repeated at most the given number of times, 4 is usually enough.
+///
+/// ```no_run
+/// # use three_d::*;
+/// # let walls: CpuMesh = unimplemented!();
+/// # let position: Vec3 = unimplemented!();
+/// # let velocity: Vec3 = unimplemented!();
+/// let colliders = [Collider::new_mesh(&walls)];
+/// let player = Capsule::new(position, position + vec3(0.0, 1.2, 0.0), 0.3);
+/// let position = position + slide(&colliders, &player, velocity, 4);
+/// ```
+///
+pub fn slide(colliders: &[Collider], capsule: &Capsule, motion: Vec3, max_iterations: u32) -> Vec3 {
+    // Stay a small distance away from the colliders to avoid starting the next query inside them
+    let skin = (0.01 * capsule.radius).max(1.0e-4);
+    let mut translation = Vec3::zero();
+    let mut remaining = motion;
+    for _ in 0..max_iterations {
+        let length = remaining.magnitude();
+        if length < EPSILON {
+            break;
+        }
+        match capsule_cast(colliders, &capsule.translated(translation), remaining) {
+            Some(hit) => {
+                let travel = (hit.time - skin / length).max(0.0);
+                translation += remaining * travel;
+                remaining *= 1.0 - travel;
+                remaining -= hit.normal * remaining.dot(hit.normal).min(0.0);
+            }
+            None => {
+                translation += remaining;
+                break;
+            }
+        }
+    }
+    translation
+}
+
+// The time, position on the collider and normal of a hit.
+type Contact = (f32, Vec3, Vec3);
+
+fn earliest(a: Option<Contact>, b: Option<Contact>) -> Option<Contact> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(if b.0 < a.0 { b } else { a }),
+        (a, None) => a,
+        (None, b) => b,
+    }
+}
+
+// The first time in [0, max_t] where the point `origin + t * motion` is on the sphere, if it starts outside the sphere.
+fn point_sphere_cast(
+    origin: Vec3,
+    motion: Vec3,
+    center: Vec3,
+    radius: f32,
+    max_t: f32,
+) -> Option<f32> {
+    let m = origin - center;
+    let a = motion.dot(motion);
+    let c = m.dot(m) - radius * radius;
+    if a < EPSILON || c <= 0.0 {
+        return None;
+    }
+    let b = m.dot(motion);
+    let discriminant = b * b - a * c;
+    if discriminant < 0.0 {
+        return None;
+    }
+    let t = (-b - discriminant.sqrt()) / a;
+    (0.0..=max_t).contains(&t).then_some(t)
+}
+
+// The first time in [0, max_t] where the point `origin + t * motion` is on the cylinder around the line segment, if it starts outside the cylinder,
+// together with the fraction along the line segment.
+fn point_cylinder_cast(
+    origin: Vec3,
+    motion: Vec3,
+    start: Vec3,
+    end: Vec3,
+    radius: f32,
+    max_t: f32,
+) -> Option<(f32, f32)> {
+    let e = end - start;
+    let m = origin - start;
+    let ee = e.dot(e);
+    if ee < EPSILON {
+        return None;
+    }
+    let de = motion.dot(e);
+    let me = m.dot(e);
+    let a = ee * motion.dot(motion) - de * de;
+    let c = ee * (m.dot(m) - radius * radius) - me * me;
+    if a < EPSILON * ee || c <= 0.0 {
+        return None;
+    }
+    let b = ee * m.dot(motion) - de * me;
+    let discriminant = b * b - a * c;
+    if discriminant < 0.0 {
+        return None;
+    }
+    let t = (-b - discriminant.sqrt()) / a;
+    let s = (me + t * de) / ee;
+    ((0.0..=max_t).contains(&t) && (0.0..=1.0).contains(&s)).then_some((t, s))
+}
+
+// The first time in [0, max_t] where the point `origin + t * motion` is on the capsule, together with the closest point on the capsule line segment.
+fn point_capsule_cast(
+    origin: Vec3,
+    motion: Vec3,
+    start: Vec3,
+    end: Vec3,
+    radius: f32,
+    max_t: f32,
+) -> Option<(f32, Vec3)> {
+    let mut closest = point_cylinder_cast(origin, motion, start, end, radius, max_t)
+        .map(|(t, s)| (t, start + (end - start) * s));
+    for p in [start, end] {
+        if let Some(t) = point_sphere_cast(origin, motion, p, radius, max_t) {
+            if closest.map(|c| t < c.0).unwrap_or(true) {
+                closest = Some((t, p));
+            }
+        }
+    }
+    closest
+}
+
+// The first time in [0, 1] where the interior of the moving line segment a, moved by the motion, is at the given distance from the interior of the static line segment b.
+// Returns the time, the closest point on b and the normal pointing from b towards a.
+fn segment_segment_cast(
+    a: (Vec3, Vec3),
+    motion: Vec3,
+    b: (Vec3, Vec3),
+    distance: f32,
+) -> Option<(f32, Vec3, Vec3)> {
+    let u = a.1 - a.0;
+    let v = b.1 - b.0;
+    let n = u.cross(v);
+    if n.magnitude2() < EPSILON * u.magnitude2() * v.magnitude2() + EPSILON {
+        return None;
+    }
+    let n = n.normalize();
+    let d0 = (a.0 - b.0).dot(n);
+    if d0.abs() <= distance {
+        return None;
+    }
+    let side = d0.signum();
+    let dn = motion.dot(n);
+    if side * dn >= 0.0 {
+        return None;
+    }
+    let t = (side * distance - d0) / dn;
+    if !(0.0..=1.0).contains(&t) {
+        return None;
+    }
+    let (s, r) = closest_line_parameters(a.0 + motion * t, u, b.0, v)?;
+    ((0.0..=1.0).contains(&s) && (0.0..=1.0).contains(&r)).then_some((t, b.0 + v * r, n * side))
+}
+
+// The parameters of the closest points on the lines `p + s * u` and `q + r * v`.
+fn closest_line_parameters(p: Vec3, u: Vec3, q: Vec3, v: Vec3) -> Option<(f32, f32)> {
+    let w = p - q;
+    let a = u.dot(u);
+    let b = u.dot(v);
+    let c = v.dot(v);
+    let d = u.dot(w);
+    let e = v.dot(w);
+    let denominator = a * c - b * b;
+    if denominator.abs() < EPSILON {
+        return None;
+    }
+    Some(((b * e - c * d) / denominator, (a * e - b * d) / denominator))
+}
+
+// The closest points between the line segments a and b.
+fn closest_points_segment_segment(a: (Vec3, Vec3), b: (Vec3, Vec3)) -> (Vec3, Vec3) {
+    let u = a.1 - a.0;
+    let v = b.1 - b.0;
+    let w = a.0 - b.0;
+    let aa = u.dot(u);
+    let cc = v.dot(v);
+    let f = v.dot(w);
+    let (s, t) = if aa < EPSILON && cc < EPSILON {
+        (0.0, 0.0)
+    } else if aa < EPSILON {
+        (0.0, (f / cc).clamp(0.0, 1.0))
+    } else {
+        let c = u.dot(w);
+        if cc < EPSILON {
+            ((-c / aa).clamp(0.0, 1.0), 0.0)
+        } else {
+            let bb = u.dot(v);
+            let denominator = aa * cc - bb * bb;
+            let mut s = if denominator > EPSILON {
+                ((bb * f - c * cc) / denominator).clamp(0.0, 1.0)
+            } else {
+                0.0
+            };
+            let mut t = (bb * s + f) / cc;
+            if t < 0.0 {
+                t = 0.0;
+                s = (-c / aa).clamp(0.0, 1.0);
+            } else if t > 1.0 {
+                t = 1.0;
+                s = ((bb - c) / aa).clamp(0.0, 1.0);
+            }
+            (s, t)
+        }
+    };
+    (a.0 + u * s, b.0 + v * t)
+}
+
+// The closest point on the triangle to the given point.
+fn closest_point_triangle(p: Vec3, [a, b, c]: [Vec3; 3]) -> Vec3 {
+    let ab = b - a;
+    let ac = c - a;
+    let ap = p - a;
+    let d1 = ab.dot(ap);
+    let d2 = ac.dot(ap);
+    if d1 <= 0.0 && d2 <= 0.0 {
+        return a;
+    }
+    let bp = p - b;
+    let d3 = ab.dot(bp);
+    let d4 = ac.dot(bp);
+    if d3 >= 0.0 && d4 <= d3 {
+        return b;
+    }
+    let vc = d1 * d4 - d3 * d2;
+    if vc <= 0.0 && d1 >= 0.0 && d3 <= 0.0 {
+        return a + ab * (d1 / (d1 - d3));
+    }
+    let cp = p - c;
+    let d5 = ab.dot(cp);
+    let d6 = ac.dot(cp);
+    if d6 >= 0.0 && d5 <= d6 {
+        return c;
+    }
+    let vb = d5 * d2 - d1 * d6;
+    if vb <= 0.0 && d2 >= 0.0 && d6 <= 0.0 {
+        return a + ac * (d2 / (d2 - d6));
+    }
+    let va = d3 * d6 - d5 * d4;
+    if va <= 0.0 && d4 - d3 >= 0.0 && d5 - d6 >= 0.0 {
+        return b + (c - b) * ((d4 - d3) / ((d4 - d3) + (d5 - d6)));
+    }
+    let denominator = 1.0 / (va + vb + vc);
+    a + ab * (vb * denominator) + ac * (vc * denominator)
+}
+
+// Whether the point in the plane of the triangle with the given normal is inside the triangle.
+fn is_inside_triangle(p: Vec3, [a, b, c]: [Vec3; 3], normal: Vec3) -> bool {
+    (b - a).cross(p - a).dot(normal) >= 0.0
+        && (c - b).cross(p - b).dot(normal) >= 0.0
+        && (a - c).cross(p - c).dot(normal) >= 0.0
+}
+
+// The closest points between the line segment and the triangle.
+fn closest_points_segment_triangle(
+    segment: (Vec3, Vec3),
+    triangle: [Vec3; 3],
+    normal: Vec3,
+) -> (Vec3, Vec3) {
+    // The segment crosses the triangle
+    let d0 = (segment.0 - triangle[0]).dot(normal);
+    let d1 = (segment.1 - triangle[0]).dot(normal);
+    if d0 * d1 < 0.0 {
+        let p = segment.0 + (segment.1 - segment.0) * (d0 / (d0 - d1));
+        if is_inside_triangle(p, triangle, normal) {
+            return (p, p);
+        }
+    }
+    let mut closest = (segment.0, closest_point_triangle(segment.0, triangle));
+    let mut candidates = [(segment.1, closest_point_triangle(segment.1, triangle)); 4];
+    for i in 0..3 {
+        candidates[i + 1] =
+            closest_points_segment_segment(segment, (triangle[i], triangle[(i + 1) % 3]));
+    }
+    for candidate in candidates {
+        if candidate.0.distance2(candidate.1) < closest.0.distance2(closest.1) {
+            closest = candidate;
+        }
+    }
+    closest
+}
+
+// The first hit between the capsule, moved by the motion, and the triangle.
+fn capsule_triangle_cast(capsule: &Capsule, motion: Vec3, triangle: [Vec3; 3]) -> Option<Contact> {
+    let [p0, p1, p2] = triangle;
+    let normal = (p1 - p0).cross(p2 - p0);
+    if normal.magnitude2() < EPSILON * EPSILON {
+        return None;
+    }
+    let normal = normal.normalize();
+    let radius = capsule.radius;
+    let segment = (capsule.start, capsule.end);
+    let edges = [(p0, p1), (p1, p2), (p2, p0)];
+
+    // Already overlapping
+    let (on_segment, on_triangle) = closest_points_segment_triangle(segment, triangle, normal);
+    let distance = on_segment.distance(on_triangle);
+    if distance < radius {
+        let n = if distance > EPSILON {
+            (on_segment - on_triangle) / distance
+        } else if motion.dot(normal) > 0.0 {
+            -normal
+        } else {
+            normal
+        };
+        return (motion.dot(n) < 0.0).then_some((0.0, on_triangle, n));
+    }
+
+    let mut closest = None;
+    let endpoints = if capsule.start == capsule.end {
+        &[capsule.start][..]
+    } else {
+        &[capsule.start, capsule.end][..]
+    };
+    for &p in endpoints {
+        // The sphere at the end point against the face, edges and vertices of the triangle
+        let (n, d) = {
+            let d = (p - p0).dot(normal);
+            if d < 0.0 {
+                (-normal, -d)
+            } else {
+                (normal, d)
+            }
+        };
+        let dn = motion.dot(n);
+        if dn < 0.0 {
+            let t = (d - radius) / -dn;
+            if (0.0..=1.0).contains(&t) {
+                let position = p + motion * t - n * radius;
+                if is_inside_triangle(position, triangle, normal) {
+                    closest = earliest(closest, Some((t, position, n)));
+                }
+            }
+        }
+        for (start, end) in edges {
+            if let Some((t, s)) = point_cylinder_cast(p, motion, start, end, radius, 1.0) {
+                let position = start + (end - start) * s;
+                closest = earliest(
+                    closest,
+                    Some((t, position, (p + motion * t - position) / radius)),
+                );
+            }
+        }
+        for vertex in triangle {
+            if let Some(t) = point_sphere_cast(p, motion, vertex, radius, 1.0) {
+                closest = earliest(
+                    closest,
+                    Some((t, vertex, (p + motion * t - vertex) / radius)),
+                );
+            }
+        }
+    }
+    // The vertices of the triangle against the side of the capsule
+    for vertex in triangle {
+        if let Some((t, s)) =
+            point_cylinder_cast(vertex, -motion, capsule.start, capsule.end, radius, 1.0)
+        {
+            let axis = capsule.start + (capsule.end - capsule.start) * s;
+            closest = earliest(
+                closest,
+                Some((t, vertex, (axis - (vertex - motion * t)) / radius)),
+            );
+        }
+    }
+    // The edges of the triangle against the side of the capsule
+    for edge in edges {
+        if let Some((t, position, n)) = segment_segment_cast(segment, motion, edge, radius) {
+            closest = earliest(closest, Some((t, position, n)));
+        }
+    }
+    closest
+}
+
+// The first hit between the capsule, moved by the motion, and the static capsule.
+fn capsule_capsule_cast(capsule: &Capsule, motion: Vec3, other: &Capsule) -> Option<Contact> {
+    let radius = capsule.radius + other.radius;
+    let a = (capsule.start, capsule.end);
+    let b = (other.start, other.end);
+
+    // Already overlapping
+    let (on_a, on_b) = closest_points_segment_segment(a, b);
+    let distance = on_a.distance(on_b);
+    if distance < radius {
+        if distance <= EPSILON {
+            return None;
+        }
+        let n = (on_a - on_b) / distance;
+        return (motion.dot(n) < 0.0).then_some((0.0, on_b + n * other.radius, n));
+    }
+
+    let mut closest = None;
+    // The end points of the moving capsule against the static capsule
+    for p in [a.0, a.1] {
+        if let Some((t, axis)) = point_capsule_cast(p, motion, b.0, b.1, radius, 1.0) {
+            let n = (p + motion * t - axis) / radius;
+            closest = earliest(closest, Some((t, axis + n * other.radius, n)));
+        }
+    }
+    // The end points of the static capsule against the moving capsule
+    for p in [b.0, b.1] {
+        if let Some((t, axis)) = point_capsule_cast(p, -motion, a.0, a.1, radius, 1.0) {
+            let n = (axis - (p - motion * t)) / radius;
+            closest = earliest(closest, Some((t, p + n * other.radius, n)));
+        }
+    }
+    // The sides against each other
+    if let Some((t, axis, n)) = segment_segment_cast(a, motion, b, radius) {
+        closest = earliest(closest, Some((t, axis + n * other.radius, n)));
+    }
+    closest
+}
+
+// The closest hit between the ray and the box, if the ray starts outside the box.
+fn ray_box(
+    origin: Vec3,
+    direction: Vec3,
+    min: Vec3,
+    max: Vec3,
+    max_distance: f32,
+) -> Option<Contact> {
+    let mut t_min = 0.0f32;
+    let mut t_max = max_distance;
+    let mut normal = None;
+    for axis in 0..3 {
+        if direction[axis].abs() < EPSILON {
+            if origin[axis] < min[axis] || origin[axis] > max[axis] {
+                return None;
+            }
+            continue;
+        }
+        let inverse = 1.0 / direction[axis];
+        let (t0, t1) = (
+            (min[axis] - origin[axis]) * inverse,
+            (max[axis] - origin[axis]) * inverse,
+        );
+        let (near, far) = if t0 < t1 { (t0, t1) } else { (t1, t0) };
+        if near > t_min {
+            t_min = near;
+            let mut n = Vec3::zero();
+            n[axis] = -direction[axis].signum();
+            normal = Some(n);
+        }
+        t_max = t_max.min(far);
+        if t_min > t_max {
+            return None;
+        }
+    }
+    normal.map(|n| (t_min, origin + direction * t_min, n))
+}
+
+// The 12 triangles of the box.
+fn box_triangles(min: Vec3, max: Vec3) -> [[Vec3; 3]; 12] {
+    let corner = |i: usize| {
+        vec3(
+            if i & 1 == 0 { min.x } else { max.x },
+            if i & 2 == 0 { min.y } else { max.y },
+            if i & 4 == 0 { min.z } else { max.z },
+        )
+    };
+    const FACES: [[usize; 4]; 6] = [
+        [0, 2, 6, 4],
+        [1, 5, 7, 3],
+        [0, 4, 5, 1],
+        [2, 3, 7, 6],
+        [0, 1, 3, 2],
+        [4, 6, 7, 5],
+    ];
+    let mut triangles = [[Vec3::zero(); 3]; 12];
+    for (i, [a, b, c, d]) in FACES.into_iter().enumerate() {
+        triangles[2 * i] = [corner(a), corner(b), corner(c)];
+        triangles[2 * i + 1] = [corner(a), corner(c), corner(d)];
+    }
+    triangles
+}
+
+fn boxes_overlap(min0: Vec3, max0: Vec3, min1: Vec3, max1: Vec3) -> bool {
+    (0..3).all(|axis| min0[axis] <= max1[axis] && min1[axis] <= max0[axis])
+}
+
+fn point_box_distance(p: Vec3, min: Vec3, max: Vec3) -> f32 {
+    let closest = vec3(
+        p.x.clamp(min.x, max.x),
+        p.y.clamp(min.y, max.y),
+        p.z.clamp(min.z, max.z),
+    );
+    p.distance(closest)
+}
+
+fn segment_box_distance(start: Vec3, end: Vec3, min: Vec3, max: Vec3) -> f32 {
+    // The distance to a convex shape is convex along the segment, so a ternary search finds the minimum
+    let (mut low, mut high) = (0.0f32, 1.0f32);
+    for _ in 0..32 {
+        let a = low + (high - low) / 3.0;
+        let b = high - (high - low) / 3.0;
+        if point_box_distance(start.lerp(end, a), min, max)
+            <= point_box_distance(start.lerp(end, b), min, max)
+        {
+            high = b;
+        } else {
+            low = a;
+        }
+    }
+    point_box_distance(start.lerp(end, 0.5 * (low + high)), min, max)
+}
+
+// Separating axis test between a triangle and a box.
+fn triangle_box_overlap(triangle: [Vec3; 3], min: Vec3, max: Vec3) -> bool {
+    let center = 0.5 * (min + max);
+    let half_size = 0.5 * (max - min);
+    let v = triangle.map(|p| p - center);
+    let edges = [v[1] - v[0], v[2] - v[1], v[0] - v[2]];
+    let is_separating = |axis: Vec3| {
+        let p = v.map(|p| p.dot(axis));
+        let r =
+            half_size.x * axis.x.abs() + half_size.y * axis.y.abs() + half_size.z * axis.z.abs();
+        p[0].min(p[1]).min(p[2]) > r || p[0].max(p[1]).max(p[2]) < -r
+    };
+    let box_axes = [Vec3::unit_x(), Vec3::unit_y(), Vec3::unit_z()];
+    if box_axes.into_iter().any(is_separating) || is_separating(edges[0].cross(edges[1])) {
+        return false;
+    }
+    !box_axes.into_iter().any(|axis| {
+        edges
+            .into_iter()
+            .any(|edge| is_separating(axis.cross(edge)))
+    })
+}
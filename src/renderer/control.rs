@@ -22,10 +22,126 @@ mod control2d;
 #[doc(inline)]
 pub use control2d::*;
 
+mod hover_tracker;
+#[doc(inline)]
+pub use hover_tracker::*;
+
+mod event_router;
+#[doc(inline)]
+pub use event_router::*;
+
+mod navigation_widgets;
+#[doc(inline)]
+pub use navigation_widgets::*;
+
+mod event_recording;
+#[doc(inline)]
+pub use event_recording::*;
+
 pub use three_d_asset::PixelPoint as PhysicalPoint;
 
+///
+/// A pixel coordinate in logical pixels, where `x` is on the horizontal axis with zero being at the left edge
+/// and `y` is on the vertical axis with zero being at top edge. This is the coordinate system used by the window system and the GUI.
+///
+/// Note that the positions in [Event]s, [Viewport](crate::core::Viewport)s and for example [pick](crate::renderer::pick) use physical pixels ([PhysicalPoint])
+/// where zero is at the bottom edge, so use [LogicalPoint::to_physical] and [LogicalPoint::from_physical] to convert between them,
+/// or use the functions on `FrameInput` which accept and return logical pixels, for example `FrameInput::pick` and `FrameInput::world_to_screen`.
+///
+/// ```
+/// # use three_d::*;
+/// // A window of 800x600 logical pixels with a device pixel ratio of 2 and a GUI side panel which is 200 logical pixels wide to the left
+/// let (device_pixel_ratio, window_height) = (2.0, 1200);
+/// let viewport = Viewport { x: 400, y: 0, width: 1200, height: 1200 };
+/// let camera = Camera::new_perspective(viewport, vec3(0.0, 0.0, 5.0), vec3(0.0, 0.0, 0.0), vec3(0.0, 1.0, 0.0), degrees(45.0), 0.1, 100.0);
+/// let sphere = Capsule::sphere(vec3(0.0, 0.0, 0.0), 0.5);
+///
+/// // The sphere is in the center of the area next to the panel
+/// let center = LogicalPoint::from_physical(world_to_screen(&camera, sphere.start), device_pixel_ratio, window_height);
+/// assert!((center.x - 500.0).abs() < 1e-3 && (center.y - 300.0).abs() < 1e-3);
+///
+/// // A click on the center of the sphere picks it
+/// let viewer = MatrixViewer::from_viewer(&camera);
+/// let cast = |pixel: PhysicalPoint| {
+///     ray_cast(&[Collider::Capsule(sphere)], viewer.position_at_pixel(pixel), viewer.view_direction_at_pixel(pixel), 100.0)
+/// };
+/// let click = LogicalPoint { x: 500.0, y: 300.0 };
+/// assert!(cast(click.to_physical(device_pixel_ratio, window_height)).is_some());
+///
+/// // Using the logical position directly as a physical position misses the sphere
+/// assert!(cast(PhysicalPoint { x: click.x, y: window_height as f32 - click.y }).is_none());
+/// ```
+///
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct LogicalPoint {
+    /// The horizontal pixel distance from the left edge.
+    pub x: f32,
+    /// The vertical pixel distance from the top edge.
+    pub y: f32,
+}
+
+impl LogicalPoint {
+    ///
+    /// Converts this point to physical pixels given the device pixel ratio and the height of the window in physical pixels.
+    ///
+    pub fn to_physical(self, device_pixel_ratio: f32, window_height: u32) -> PhysicalPoint {
+        PhysicalPoint {
+            x: self.x * device_pixel_ratio,
+            y: window_height as f32 - self.y * device_pixel_ratio,
+        }
+    }
+
+    ///
+    /// Converts the given point in physical pixels to logical pixels given the device pixel ratio and the height of the window in physical pixels.
+    ///
+    pub fn from_physical(
+        point: PhysicalPoint,
+        device_pixel_ratio: f32,
+        window_height: u32,
+    ) -> Self {
+        Self {
+            x: point.x / device_pixel_ratio,
+            y: (window_height as f32 - point.y) / device_pixel_ratio,
+        }
+    }
+}
+
 use three_d_asset::prelude::*;
 
+///
+/// The scene bounds used by a control to fit the near and far planes of the camera each time the events are handled,
+/// see for example [OrbitControl::depth_range_fit] and [Camera::auto_fit_depth_range](crate::renderer::Camera::auto_fit_depth_range).
+///
+#[derive(Debug, Copy, Clone)]
+pub struct DepthRangeFit {
+    /// The bounding box of the entire scene.
+    pub scene_aabb: AxisAlignedBoundingBox,
+    /// The distance in world space which is added in front of and behind the bounds.
+    pub margin: f32,
+    /// The minimum distance to the near plane of a perspective camera.
+    pub min_z_near: f32,
+}
+
+impl DepthRangeFit {
+    ///
+    /// Creates a new depth range fit to the given scene bounds with no margin and a minimum near plane distance of 0.01.
+    ///
+    pub fn new(scene_aabb: AxisAlignedBoundingBox) -> Self {
+        Self {
+            scene_aabb,
+            margin: 0.0,
+            min_z_near: crate::renderer::DEFAULT_MIN_Z_NEAR,
+        }
+    }
+
+    ///
+    /// Fits the near and far planes of the given camera to the scene bounds and returns whether or not they changed.
+    ///
+    pub fn apply(&self, camera: &mut three_d_asset::Camera) -> bool {
+        crate::renderer::auto_fit_depth_range(camera, self.scene_aabb, self.margin, self.min_z_near)
+    }
+}
+
 /// Type of mouse button.
 #[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd, Hash)]
 pub enum MouseButton {
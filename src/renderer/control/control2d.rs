@@ -2,6 +2,7 @@ use super::*;
 
 ///
 /// A control for 2D camera movements.
+/// Scrolling or pinching zooms about the cursor and dragging with the [Control2D::pan_button] pressed pans the camera.
 ///
 #[derive(Clone, Copy, Debug)]
 pub struct Control2D {
@@ -9,6 +10,11 @@ pub struct Control2D {
     pub min_zoom_factor: f32,
     /// The maximum zoom factor.
     pub max_zoom_factor: f32,
+    /// The mouse button used for panning by dragging. The default is [MouseButton::Right].
+    pub pan_button: MouseButton,
+    /// Whether or not to align the camera to the pixel grid after each change, see [Camera::align_to_pixel_grid](crate::Camera::align_to_pixel_grid).
+    /// This keeps the edges of 2D shapes at integer positions crisp when the number of pixels per unit is an integer. The default is `false`.
+    pub align_to_pixel_grid: bool,
 }
 
 impl Control2D {
@@ -17,6 +23,8 @@ impl Control2D {
         Self {
             min_zoom_factor,
             max_zoom_factor,
+            pan_button: MouseButton::Right,
+            align_to_pixel_grid: false,
         }
     }
 
@@ -64,7 +72,7 @@ impl Control2D {
                     handled,
                     ..
                 } => {
-                    if !*handled && Some(MouseButton::Right) == *button {
+                    if !*handled && Some(self.pan_button) == *button {
                         self.pan(camera, *delta, device_pixel_ratio);
                         *handled = true;
                         change = true;
@@ -73,6 +81,9 @@ impl Control2D {
                 _ => {}
             }
         }
+        if change && self.align_to_pixel_grid {
+            crate::renderer::align_to_pixel_grid(camera);
+        }
         change
     }
 
@@ -0,0 +1,630 @@
+use super::*;
+use crate::core::Viewport;
+use crate::renderer::RendererError;
+use std::ops::Range;
+
+///
+/// The input of a single frame in an [EventRecording], which is the same as the input to the render loop except for the graphics context.
+///
+#[derive(Clone, Debug)]
+pub struct RecordedFrame {
+    /// The [Event]s which occurred since the previous frame.
+    pub events: Vec<Event>,
+    /// Milliseconds since the previous frame.
+    pub elapsed_time: f64,
+    /// Milliseconds accumulated time since the start of the recording.
+    pub accumulated_time: f64,
+    /// Viewport of the window in physical pixels.
+    pub viewport: Viewport,
+    /// Width of the window in logical pixels.
+    pub window_width: u32,
+    /// Height of the window in logical pixels.
+    pub window_height: u32,
+    /// Number of physical pixels for each logical pixel.
+    pub device_pixel_ratio: f32,
+    /// Whether or not this is the first frame.
+    pub first_frame: bool,
+}
+
+///
+/// A recording of the input to the render loop each frame, for example to replay an interaction which caused a bug
+/// or to turn it into an automated test of a control.
+/// Use [Window::record_input](crate::window::Window::record_input) and [Window::play_input](crate::window::Window::play_input)
+/// to record and replay the input of the default window, or feed the [RecordedFrame]s directly to a control:
+///
+/// ```
+/// # use three_d::*;
+/// let mut recording = EventRecording::new();
+/// let viewport = Viewport::new_at_origo(800, 600);
+/// for i in 0..10 {
+///     recording.push(RecordedFrame {
+///         events: vec![Event::MouseMotion {
+///             button: Some(MouseButton::Left),
+///             delta: (10.0, 0.0),
+///             position: PhysicalPoint { x: 400.0 + 10.0 * i as f32, y: 300.0 },
+///             modifiers: Modifiers::default(),
+///             handled: false,
+///         }],
+///         elapsed_time: 16.0,
+///         accumulated_time: 16.0 * (i + 1) as f64,
+///         viewport,
+///         window_width: 800,
+///         window_height: 600,
+///         device_pixel_ratio: 1.0,
+///         first_frame: i == 0,
+///     });
+/// }
+/// let recording = EventRecording::from_bytes(&recording.to_bytes()).unwrap();
+///
+/// // Replay the recording and check that orbiting keeps the distance to the target
+/// let mut camera = Camera::new_perspective(
+///     viewport,
+///     vec3(0.0, 0.0, 5.0),
+///     vec3(0.0, 0.0, 0.0),
+///     vec3(0.0, 1.0, 0.0),
+///     degrees(45.0),
+///     0.1,
+///     100.0,
+/// );
+/// let mut control = OrbitControl::new(vec3(0.0, 0.0, 0.0), 1.0, 10.0);
+/// for frame in recording.frames() {
+///     let mut events = frame.events.clone();
+///     control.handle_events(&mut camera, &mut events);
+/// }
+/// assert!(camera.position().x.abs() > 0.1);
+/// assert!((camera.position().magnitude() - 5.0).abs() < 0.001);
+/// ```
+///
+#[derive(Clone, Debug, Default)]
+pub struct EventRecording {
+    frames: Vec<RecordedFrame>,
+}
+
+impl EventRecording {
+    /// The magic bytes at the start of a serialized recording.
+    const MAGIC: [u8; 4] = *b"3DIR";
+
+    ///
+    /// The version of the format written by [EventRecording::to_bytes].
+    /// Recordings with an older version can still be read, while recordings with a newer version are rejected.
+    ///
+    pub const VERSION: u16 = 1;
+
+    ///
+    /// Creates a new empty recording.
+    ///
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    ///
+    /// Adds the input of a frame to the end of the recording.
+    ///
+    pub fn push(&mut self, frame: RecordedFrame) {
+        self.frames.push(frame);
+    }
+
+    ///
+    /// Returns the recorded frames in the order they were recorded.
+    ///
+    pub fn frames(&self) -> &[RecordedFrame] {
+        &self.frames
+    }
+
+    ///
+    /// Returns the number of recorded frames.
+    ///
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    ///
+    /// Returns whether the recording does not contain any frames.
+    ///
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    ///
+    /// Returns a new recording containing the frames in the given range, for example to cut away the frames before and after an interaction which caused a bug.
+    /// The accumulated time is recomputed so it starts from zero and the first frame is marked as the first frame.
+    ///
+    pub fn trim(&self, range: Range<usize>) -> Self {
+        let end = range.end.min(self.frames.len());
+        let start = range.start.min(end);
+        let mut recording = Self {
+            frames: self.frames[start..end].to_vec(),
+        };
+        if let Some(frame) = recording.frames.first_mut() {
+            frame.first_frame = true;
+        }
+        recording.recompute_accumulated_time();
+        recording
+    }
+
+    ///
+    /// Inserts the frames of the other recording before the frame with the given index, or at the end if the index is larger than the number of frames.
+    /// The accumulated time is recomputed for all frames.
+    ///
+    pub fn splice(&mut self, index: usize, other: &EventRecording) {
+        let index = index.min(self.frames.len());
+        let frames = other.frames.iter().cloned().map(|mut frame| {
+            frame.first_frame = false;
+            frame
+        });
+        self.frames.splice(index..index, frames);
+        if let Some(frame) = self.frames.first_mut() {
+            frame.first_frame = true;
+        }
+        self.recompute_accumulated_time();
+    }
+
+    fn recompute_accumulated_time(&mut self) {
+        let mut accumulated_time = 0.0;
+        for frame in self.frames.iter_mut() {
+            accumulated_time += frame.elapsed_time;
+            frame.accumulated_time = accumulated_time;
+        }
+    }
+
+    ///
+    /// Serializes the recording into a compact binary format, see [EventRecording::from_bytes].
+    ///
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut writer = Writer(Vec::new());
+        writer.0.extend_from_slice(&Self::MAGIC);
+        writer.u16(Self::VERSION);
+        writer.u32(self.frames.len() as u32);
+        for frame in self.frames.iter() {
+            writer.f64(frame.elapsed_time);
+            writer.f64(frame.accumulated_time);
+            writer.i32(frame.viewport.x);
+            writer.i32(frame.viewport.y);
+            writer.u32(frame.viewport.width);
+            writer.u32(frame.viewport.height);
+            writer.u32(frame.window_width);
+            writer.u32(frame.window_height);
+            writer.f32(frame.device_pixel_ratio);
+            writer.bool(frame.first_frame);
+            writer.u32(frame.events.len() as u32);
+            for event in frame.events.iter() {
+                writer.event(event);
+            }
+        }
+        writer.0
+    }
+
+    ///
+    /// Deserializes a recording serialized using [EventRecording::to_bytes].
+    /// Returns an error if the data is not a recording or if it was written by a newer version of the format.
+    ///
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, RendererError> {
+        trace_span!("three_d::deserialize", asset = "event_recording");
+        let mut reader = Reader { bytes, offset: 0 };
+        if reader.take(4)? != Self::MAGIC {
+            Err(RendererError::InvalidEventRecording(
+                "the data is not an event recording".to_string(),
+            ))?;
+        }
+        let version = reader.u16()?;
+        if version > Self::VERSION {
+            Err(RendererError::UnsupportedEventRecordingVersion(
+                version,
+                Self::VERSION,
+            ))?;
+        }
+        let frame_count = reader.u32()?;
+        let mut frames = Vec::new();
+        for _ in 0..frame_count {
+            let elapsed_time = reader.f64()?;
+            let accumulated_time = reader.f64()?;
+            let viewport = Viewport {
+                x: reader.i32()?,
+                y: reader.i32()?,
+                width: reader.u32()?,
+                height: reader.u32()?,
+            };
+            let window_width = reader.u32()?;
+            let window_height = reader.u32()?;
+            let device_pixel_ratio = reader.f32()?;
+            let first_frame = reader.bool()?;
+            let event_count = reader.u32()?;
+            let mut events = Vec::new();
+            for _ in 0..event_count {
+                events.push(reader.event()?);
+            }
+            frames.push(RecordedFrame {
+                events,
+                elapsed_time,
+                accumulated_time,
+                viewport,
+                window_width,
+                window_height,
+                device_pixel_ratio,
+                first_frame,
+            });
+        }
+        if reader.offset != bytes.len() {
+            Err(RendererError::InvalidEventRecording(
+                "unexpected data after the last frame".to_string(),
+            ))?;
+        }
+        Ok(Self { frames })
+    }
+
+    ///
+    /// Saves the recording to the file at the given path, see [EventRecording::to_bytes].
+    ///
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn save(&self, path: impl AsRef<std::path::Path>) -> Result<(), RendererError> {
+        std::fs::write(path, self.to_bytes()).map_err(RendererError::EventRecordingIo)
+    }
+
+    ///
+    /// Loads a recording from the file at the given path, see [EventRecording::from_bytes].
+    ///
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn load(path: impl AsRef<std::path::Path>) -> Result<Self, RendererError> {
+        Self::from_bytes(&std::fs::read(path).map_err(RendererError::EventRecordingIo)?)
+    }
+}
+
+// The keys in the order of their serialized index, new keys must be added at the end
+const KEYS: [Key; 51] = [
+    Key::ArrowDown,
+    Key::ArrowLeft,
+    Key::ArrowRight,
+    Key::ArrowUp,
+    Key::Escape,
+    Key::Tab,
+    Key::Backspace,
+    Key::Enter,
+    Key::Space,
+    Key::Insert,
+    Key::Delete,
+    Key::Home,
+    Key::End,
+    Key::PageUp,
+    Key::PageDown,
+    Key::Num0,
+    Key::Num1,
+    Key::Num2,
+    Key::Num3,
+    Key::Num4,
+    Key::Num5,
+    Key::Num6,
+    Key::Num7,
+    Key::Num8,
+    Key::Num9,
+    Key::A,
+    Key::B,
+    Key::C,
+    Key::D,
+    Key::E,
+    Key::F,
+    Key::G,
+    Key::H,
+    Key::I,
+    Key::J,
+    Key::K,
+    Key::L,
+    Key::M,
+    Key::N,
+    Key::O,
+    Key::P,
+    Key::Q,
+    Key::R,
+    Key::S,
+    Key::T,
+    Key::U,
+    Key::V,
+    Key::W,
+    Key::X,
+    Key::Y,
+    Key::Z,
+];
+
+struct Writer(Vec<u8>);
+
+impl Writer {
+    fn u8(&mut self, value: u8) {
+        self.0.push(value);
+    }
+    fn bool(&mut self, value: bool) {
+        self.u8(value as u8);
+    }
+    fn u16(&mut self, value: u16) {
+        self.0.extend_from_slice(&value.to_le_bytes());
+    }
+    fn u32(&mut self, value: u32) {
+        self.0.extend_from_slice(&value.to_le_bytes());
+    }
+    fn i32(&mut self, value: i32) {
+        self.0.extend_from_slice(&value.to_le_bytes());
+    }
+    fn f32(&mut self, value: f32) {
+        self.0.extend_from_slice(&value.to_le_bytes());
+    }
+    fn f64(&mut self, value: f64) {
+        self.0.extend_from_slice(&value.to_le_bytes());
+    }
+    fn point(&mut self, point: PhysicalPoint) {
+        self.f32(point.x);
+        self.f32(point.y);
+    }
+    fn modifiers(&mut self, modifiers: Modifiers) {
+        self.u8(modifiers.alt as u8
+            | (modifiers.ctrl as u8) << 1
+            | (modifiers.shift as u8) << 2
+            | (modifiers.command as u8) << 3);
+    }
+    fn button(&mut self, button: Option<MouseButton>) {
+        self.u8(match button {
+            None => 0,
+            Some(MouseButton::Left) => 1,
+            Some(MouseButton::Right) => 2,
+            Some(MouseButton::Middle) => 3,
+        });
+    }
+    fn key(&mut self, key: Key) {
+        self.u8(KEYS.iter().position(|k| *k == key).unwrap() as u8);
+    }
+
+    fn event(&mut self, event: &Event) {
+        match event {
+            Event::MousePress {
+                button,
+                position,
+                modifiers,
+                handled,
+            } => {
+                self.u8(0);
+                self.button(Some(*button));
+                self.point(*position);
+                self.modifiers(*modifiers);
+                self.bool(*handled);
+            }
+            Event::MouseRelease {
+                button,
+                position,
+                modifiers,
+                handled,
+            } => {
+                self.u8(1);
+                self.button(Some(*button));
+                self.point(*position);
+                self.modifiers(*modifiers);
+                self.bool(*handled);
+            }
+            Event::MouseMotion {
+                button,
+                delta,
+                position,
+                modifiers,
+                handled,
+            } => {
+                self.u8(2);
+                self.button(*button);
+                self.f32(delta.0);
+                self.f32(delta.1);
+                self.point(*position);
+                self.modifiers(*modifiers);
+                self.bool(*handled);
+            }
+            Event::MouseWheel {
+                delta,
+                position,
+                modifiers,
+                handled,
+            } => {
+                self.u8(3);
+                self.f32(delta.0);
+                self.f32(delta.1);
+                self.point(*position);
+                self.modifiers(*modifiers);
+                self.bool(*handled);
+            }
+            Event::PinchGesture {
+                delta,
+                position,
+                modifiers,
+                handled,
+            } => {
+                self.u8(4);
+                self.f32(*delta);
+                self.point(*position);
+                self.modifiers(*modifiers);
+                self.bool(*handled);
+            }
+            Event::RotationGesture {
+                delta,
+                position,
+                modifiers,
+                handled,
+            } => {
+                self.u8(5);
+                self.f32(delta.0);
+                self.point(*position);
+                self.modifiers(*modifiers);
+                self.bool(*handled);
+            }
+            Event::MouseEnter => self.u8(6),
+            Event::MouseLeave => self.u8(7),
+            Event::KeyPress {
+                kind,
+                modifiers,
+                handled,
+            } => {
+                self.u8(8);
+                self.key(*kind);
+                self.modifiers(*modifiers);
+                self.bool(*handled);
+            }
+            Event::KeyRelease {
+                kind,
+                modifiers,
+                handled,
+            } => {
+                self.u8(9);
+                self.key(*kind);
+                self.modifiers(*modifiers);
+                self.bool(*handled);
+            }
+            Event::ModifiersChange { modifiers } => {
+                self.u8(10);
+                self.modifiers(*modifiers);
+            }
+            Event::Text(text) => {
+                self.u8(11);
+                self.u32(text.len() as u32);
+                self.0.extend_from_slice(text.as_bytes());
+            }
+        }
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl Reader<'_> {
+    fn take(&mut self, count: usize) -> Result<&[u8], RendererError> {
+        let bytes = self
+            .bytes
+            .get(self.offset..self.offset + count)
+            .ok_or_else(|| {
+                RendererError::InvalidEventRecording("unexpected end of data".to_string())
+            })?;
+        self.offset += count;
+        Ok(bytes)
+    }
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], RendererError> {
+        Ok(self.take(N)?.try_into().unwrap())
+    }
+    fn u8(&mut self) -> Result<u8, RendererError> {
+        Ok(self.take(1)?[0])
+    }
+    fn bool(&mut self) -> Result<bool, RendererError> {
+        Ok(self.u8()? != 0)
+    }
+    fn u16(&mut self) -> Result<u16, RendererError> {
+        Ok(u16::from_le_bytes(self.array()?))
+    }
+    fn u32(&mut self) -> Result<u32, RendererError> {
+        Ok(u32::from_le_bytes(self.array()?))
+    }
+    fn i32(&mut self) -> Result<i32, RendererError> {
+        Ok(i32::from_le_bytes(self.array()?))
+    }
+    fn f32(&mut self) -> Result<f32, RendererError> {
+        Ok(f32::from_le_bytes(self.array()?))
+    }
+    fn f64(&mut self) -> Result<f64, RendererError> {
+        Ok(f64::from_le_bytes(self.array()?))
+    }
+    fn point(&mut self) -> Result<PhysicalPoint, RendererError> {
+        Ok(PhysicalPoint {
+            x: self.f32()?,
+            y: self.f32()?,
+        })
+    }
+    fn modifiers(&mut self) -> Result<Modifiers, RendererError> {
+        let bits = self.u8()?;
+        Ok(Modifiers {
+            alt: bits & 1 != 0,
+            ctrl: bits & 2 != 0,
+            shift: bits & 4 != 0,
+            command: bits & 8 != 0,
+        })
+    }
+    fn button(&mut self) -> Result<Option<MouseButton>, RendererError> {
+        match self.u8()? {
+            0 => Ok(None),
+            1 => Ok(Some(MouseButton::Left)),
+            2 => Ok(Some(MouseButton::Right)),
+            3 => Ok(Some(MouseButton::Middle)),
+            value => Err(RendererError::InvalidEventRecording(format!(
+                "unknown mouse button {}",
+                value
+            ))),
+        }
+    }
+    fn pressed_button(&mut self) -> Result<MouseButton, RendererError> {
+        self.button()?
+            .ok_or_else(|| RendererError::InvalidEventRecording("missing mouse button".to_string()))
+    }
+    fn key(&mut self) -> Result<Key, RendererError> {
+        let index = self.u8()?;
+        KEYS.get(index as usize)
+            .copied()
+            .ok_or_else(|| RendererError::InvalidEventRecording(format!("unknown key {}", index)))
+    }
+
+    fn event(&mut self) -> Result<Event, RendererError> {
+        Ok(match self.u8()? {
+            0 => Event::MousePress {
+                button: self.pressed_button()?,
+                position: self.point()?,
+                modifiers: self.modifiers()?,
+                handled: self.bool()?,
+            },
+            1 => Event::MouseRelease {
+                button: self.pressed_button()?,
+                position: self.point()?,
+                modifiers: self.modifiers()?,
+                handled: self.bool()?,
+            },
+            2 => Event::MouseMotion {
+                button: self.button()?,
+                delta: (self.f32()?, self.f32()?),
+                position: self.point()?,
+                modifiers: self.modifiers()?,
+                handled: self.bool()?,
+            },
+            3 => Event::MouseWheel {
+                delta: (self.f32()?, self.f32()?),
+                position: self.point()?,
+                modifiers: self.modifiers()?,
+                handled: self.bool()?,
+            },
+            4 => Event::PinchGesture {
+                delta: self.f32()?,
+                position: self.point()?,
+                modifiers: self.modifiers()?,
+                handled: self.bool()?,
+            },
+            5 => Event::RotationGesture {
+                delta: radians(self.f32()?),
+                position: self.point()?,
+                modifiers: self.modifiers()?,
+                handled: self.bool()?,
+            },
+            6 => Event::MouseEnter,
+            7 => Event::MouseLeave,
+            8 => Event::KeyPress {
+                kind: self.key()?,
+                modifiers: self.modifiers()?,
+                handled: self.bool()?,
+            },
+            9 => Event::KeyRelease {
+                kind: self.key()?,
+                modifiers: self.modifiers()?,
+                handled: self.bool()?,
+            },
+            10 => Event::ModifiersChange {
+                modifiers: self.modifiers()?,
+            },
+            11 => {
+                let length = self.u32()? as usize;
+                Event::Text(String::from_utf8(self.take(length)?.to_vec()).map_err(|_| {
+                    RendererError::InvalidEventRecording("invalid text".to_string())
+                })?)
+            }
+            tag => Err(RendererError::InvalidEventRecording(format!(
+                "unknown event type {}",
+                tag
+            )))?,
+        })
+    }
+}
@@ -0,0 +1,149 @@
+use super::*;
+
+///
+/// Routes the [Event]s each frame between the GUI, the controls and the app specific event handling.
+/// The events should first be given to the GUI using [EventRouter::handle_gui_events], then to the controls, for example [OrbitControl::handle_events],
+/// and finally to the app specific event handling. Each step only uses the events that are not handled by an earlier step and marks the events it uses as handled.
+///
+/// Besides the order, the router keeps track of who owns the pointer during a drag:
+/// A drag that starts outside the GUI belongs to the controls and the app until the mouse button is released,
+/// so for example rotating the camera does not stop when the mouse passes over a GUI panel.
+/// A drag that starts in the GUI, for example on a slider, is handled by the GUI only, also when the mouse leaves the GUI.
+///
+#[derive(Clone, Debug, Default)]
+pub struct EventRouter {
+    pointer_captured: bool,
+}
+
+impl EventRouter {
+    ///
+    /// Creates a new event router.
+    ///
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    ///
+    /// Returns whether the controls and the app own the pointer, ie. a mouse button was pressed outside the GUI and has not been released yet.
+    ///
+    pub fn is_pointer_captured(&self) -> bool {
+        self.pointer_captured
+    }
+
+    ///
+    /// Calls the given GUI event handling, for example [GUI::update](crate::gui::GUI::update), with the events and returns the result.
+    /// The mouse events that belong to a drag which started outside the GUI are hidden from the GUI by temporarily marking them as handled.
+    /// Afterwards, the remaining unhandled events can be given to the controls and then the app specific event handling.
+    ///
+    /// ```
+    /// # use three_d::*;
+    /// // A GUI panel covering the left 100 pixels of the window, which uses the mouse events over the panel that it gets
+    /// fn gui(events: &mut [Event]) -> usize {
+    ///     let mut seen = 0;
+    ///     for event in events.iter_mut() {
+    ///         match event {
+    ///             Event::MousePress { position, handled, .. }
+    ///             | Event::MouseRelease { position, handled, .. }
+    ///             | Event::MouseMotion { position, handled, .. } if !*handled => {
+    ///                 seen += 1;
+    ///                 *handled = position.x < 100.0;
+    ///             }
+    ///             _ => {}
+    ///         }
+    ///     }
+    ///     seen
+    /// }
+    /// let press = |x: f32| Event::MousePress { button: MouseButton::Left, position: PhysicalPoint { x, y: 50.0 }, modifiers: Modifiers::default(), handled: false };
+    /// let release = |x: f32| Event::MouseRelease { button: MouseButton::Left, position: PhysicalPoint { x, y: 50.0 }, modifiers: Modifiers::default(), handled: false };
+    /// let drag = |x: f32| Event::MouseMotion { button: Some(MouseButton::Left), delta: (-10.0, 0.0), position: PhysicalPoint { x, y: 50.0 }, modifiers: Modifiers::default(), handled: false };
+    /// let is_handled = |event: &Event| match event {
+    ///     Event::MousePress { handled, .. } | Event::MouseRelease { handled, .. } | Event::MouseMotion { handled, .. } => *handled,
+    ///     _ => unreachable!(),
+    /// };
+    ///
+    /// // A drag that starts outside the GUI and moves over the GUI belongs to the controls until it is released
+    /// let mut router = EventRouter::new();
+    /// let mut events = vec![press(300.0)];
+    /// router.handle_gui_events(&mut events, gui);
+    /// assert!(router.is_pointer_captured());
+    /// assert!(!is_handled(&events[0]));
+    /// let mut events = vec![drag(50.0), release(50.0)];
+    /// assert_eq!(router.handle_gui_events(&mut events, gui), 0);
+    /// assert!(events.iter().all(|e| !is_handled(e)));
+    /// assert!(!router.is_pointer_captured());
+    ///
+    /// // A press and a release in the same frame does not capture the pointer, so the GUI gets the next drag over it
+    /// let mut events = vec![press(300.0), release(300.0)];
+    /// router.handle_gui_events(&mut events, gui);
+    /// assert!(!router.is_pointer_captured());
+    /// let mut events = vec![drag(50.0)];
+    /// assert_eq!(router.handle_gui_events(&mut events, gui), 1);
+    /// assert!(is_handled(&events[0]));
+    ///
+    /// // A press in the GUI after the release of a drag that started outside the GUI belongs to the GUI
+    /// let mut events = vec![press(300.0)];
+    /// router.handle_gui_events(&mut events, gui);
+    /// let mut events = vec![drag(200.0), release(200.0), press(50.0), drag(60.0)];
+    /// assert_eq!(router.handle_gui_events(&mut events, gui), 2);
+    /// assert!(!is_handled(&events[0]) && !is_handled(&events[1]));
+    /// assert!(is_handled(&events[2]) && is_handled(&events[3]));
+    /// assert!(!router.is_pointer_captured());
+    /// ```
+    ///
+    pub fn handle_gui_events<R>(
+        &mut self,
+        events: &mut [Event],
+        gui: impl FnOnce(&mut [Event]) -> R,
+    ) -> R {
+        // Hide the events from the GUI which belong to a drag that started outside the GUI in an earlier frame
+        let mut hidden = Vec::new();
+        if self.pointer_captured {
+            for (index, event) in events.iter_mut().enumerate() {
+                match event {
+                    Event::MouseMotion {
+                        button: Some(_),
+                        handled,
+                        ..
+                    } if !*handled => {
+                        *handled = true;
+                        hidden.push(index);
+                    }
+                    Event::MouseRelease { handled, .. } => {
+                        if !*handled {
+                            *handled = true;
+                            hidden.push(index);
+                        }
+                        break;
+                    }
+                    Event::MousePress { .. } => break,
+                    _ => {}
+                }
+            }
+        }
+
+        let result = gui(events);
+
+        for index in hidden {
+            if let Event::MouseMotion { handled, .. } | Event::MouseRelease { handled, .. } =
+                &mut events[index]
+            {
+                *handled = false;
+            }
+        }
+
+        // A press which is not used by the GUI starts a drag that belongs to the controls and the app until it is released
+        for event in events.iter() {
+            match event {
+                Event::MousePress { handled, .. } => {
+                    self.pointer_captured = !*handled;
+                }
+                Event::MouseRelease { .. } | Event::MouseLeave => {
+                    self.pointer_captured = false;
+                }
+                _ => {}
+            }
+        }
+
+        result
+    }
+}
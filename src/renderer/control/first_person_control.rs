@@ -3,6 +3,25 @@ use super::*;
 ///
 /// A control that makes the camera move like it is a person on the ground.
 ///
+/// The control does not stop the camera from moving through walls.
+/// For that, let the control move the camera and then use [slide] to limit the movement of a capsule around the player:
+///
+/// ```no_run
+/// # use three_d::*;
+/// # let mut camera: Camera = unimplemented!();
+/// # let mut events: Vec<Event> = unimplemented!();
+/// # let colliders: Vec<Collider> = unimplemented!();
+/// let mut control = FirstPersonControl::new(0.1);
+///
+/// // Each frame
+/// let position = camera.position();
+/// control.handle_events(&mut camera, &mut events);
+/// let motion = camera.position() - position;
+/// let player = Capsule::new(position - vec3(0.0, 1.2, 0.0), position, 0.3);
+/// let translation = slide(&colliders, &player, motion, 4);
+/// camera.translate(translation - motion);
+/// ```
+///
 #[derive(Clone, Copy, Debug)]
 pub struct FirstPersonControl {
     /// The speed of movements.
@@ -7,12 +7,17 @@ use super::*;
 pub struct FlyControl {
     /// The speed of movements.
     pub speed: f32,
+    /// If set, the near and far planes of the camera are fitted to the scene bounds each time the events are handled. `None` by default.
+    pub depth_range_fit: Option<DepthRangeFit>,
 }
 
 impl FlyControl {
     /// Creates a new fly control with the given speed of movements.
     pub fn new(speed: f32) -> Self {
-        Self { speed }
+        Self {
+            speed,
+            depth_range_fit: None,
+        }
     }
 
     /// Handles the events. Must be called each frame.
@@ -59,6 +64,9 @@ impl FlyControl {
                 _ => {}
             }
         }
+        if let Some(depth_range_fit) = self.depth_range_fit {
+            change |= depth_range_fit.apply(camera);
+        }
         change
     }
 }
@@ -0,0 +1,149 @@
+use crate::renderer::*;
+
+///
+/// A change in which object is hovered, as returned from [HoverTracker::update].
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HoverTransition {
+    /// The mouse started hovering the object with the given index.
+    Enter(u32),
+    /// The mouse stopped hovering the object with the given index.
+    Leave(u32),
+}
+
+///
+/// Keeps track of which object the mouse is hovering, for example to show a tooltip after the mouse has rested on an object for a short while.
+/// The hovered object is found by picking (see [pick]) at the mouse position once the mouse has been still for [HoverTracker::delay] milliseconds,
+/// where still means that it has not moved more than [HoverTracker::tolerance] pixels.
+/// Picking is only repeated if the mouse or the camera moves, so call [HoverTracker::invalidate] if the objects are changed.
+///
+/// Mouse motion events that are already handled are ignored and clears the hovered object,
+/// so if using a GUI, call [HoverTracker::update] after the GUI has handled the events to avoid picking through the GUI.
+///
+#[derive(Clone, Debug)]
+pub struct HoverTracker {
+    /// The time in milliseconds the mouse has to be still before the object under the mouse is hovered.
+    pub delay: f64,
+    /// The distance in physical pixels the mouse can move and still be considered still.
+    pub tolerance: f32,
+    mouse_position: Option<PhysicalPoint>,
+    anchor: Option<(PhysicalPoint, f64)>,
+    last_pick: Option<(PhysicalPoint, Mat4, Viewport)>,
+    hovered: Option<(u32, Vec3, PhysicalPoint)>,
+}
+
+impl HoverTracker {
+    ///
+    /// Creates a new hover tracker with the given delay in milliseconds and tolerance in physical pixels.
+    ///
+    pub fn new(delay: f64, tolerance: f32) -> Self {
+        Self {
+            delay,
+            tolerance,
+            mouse_position: None,
+            anchor: None,
+            last_pick: None,
+            hovered: None,
+        }
+    }
+
+    ///
+    /// Returns the index of the hovered object in the list of geometries given to [HoverTracker::update],
+    /// the world position of the hovered point on that object and the screen position in physical pixels, if any object is hovered.
+    ///
+    pub fn hovered(&self) -> Option<(u32, Vec3, PhysicalPoint)> {
+        self.hovered
+    }
+
+    ///
+    /// Forces a new pick the next time [HoverTracker::update] is called, for example because the objects have moved.
+    ///
+    pub fn invalidate(&mut self) {
+        self.last_pick = None;
+    }
+
+    ///
+    /// Updates the hovered object based on the mouse events, the current time in milliseconds (for example [FrameInput::accumulated_time](crate::FrameInput::accumulated_time))
+    /// and the given geometries. Must be called each frame.
+    /// Returns the transitions between hovered objects that happened in this update, ie. a [HoverTransition::Leave] is always returned before a [HoverTransition::Enter].
+    ///
+    pub fn update(
+        &mut self,
+        context: &Context,
+        camera: &Camera,
+        events: &[Event],
+        time: f64,
+        geometries: impl IntoIterator<Item = impl Geometry>,
+    ) -> Result<Vec<HoverTransition>, RendererError> {
+        for event in events.iter() {
+            match event {
+                Event::MouseMotion {
+                    position, handled, ..
+                } => {
+                    if *handled {
+                        self.mouse_position = None;
+                        self.anchor = None;
+                    } else {
+                        self.mouse_position = Some(*position);
+                        let is_still = self.anchor.is_some_and(|(anchor, _)| {
+                            (anchor.x - position.x).hypot(anchor.y - position.y) <= self.tolerance
+                        });
+                        if !is_still {
+                            self.anchor = Some((*position, time));
+                        }
+                    }
+                }
+                Event::MouseLeave => {
+                    self.mouse_position = None;
+                    self.anchor = None;
+                }
+                _ => {}
+            }
+        }
+
+        let mut transitions = Vec::new();
+        let position = match (self.mouse_position, self.anchor) {
+            (Some(position), Some((_, still_since))) if time - still_since >= self.delay => {
+                position
+            }
+            _ => {
+                self.last_pick = None;
+                if let Some((index, _, _)) = self.hovered.take() {
+                    transitions.push(HoverTransition::Leave(index));
+                }
+                return Ok(transitions);
+            }
+        };
+
+        let key = (
+            position,
+            camera.projection() * camera.view(),
+            camera.viewport(),
+        );
+        if self.last_pick == Some(key) {
+            return Ok(transitions);
+        }
+        self.last_pick = Some(key);
+
+        let hovered = pick(context, camera, position, geometries, Cull::Back)?
+            .map(|result| (result.geometry_id, result.position, position));
+        let previous = self.hovered.map(|(index, _, _)| index);
+        let current = hovered.map(|(index, _, _)| index);
+        if previous != current {
+            if let Some(index) = previous {
+                transitions.push(HoverTransition::Leave(index));
+            }
+            if let Some(index) = current {
+                transitions.push(HoverTransition::Enter(index));
+            }
+        }
+        self.hovered = hovered;
+        Ok(transitions)
+    }
+}
+
+impl Default for HoverTracker {
+    fn default() -> Self {
+        Self::new(200.0, 3.0)
+    }
+}
@@ -0,0 +1,675 @@
+use crate::renderer::*;
+
+/// The size of the view cube in logical pixels at scale 1.
+const CUBE_SIZE: f32 = 96.0;
+/// The radius of the buttons in logical pixels at scale 1.
+const BUTTON_RADIUS: f32 = 14.0;
+/// The distance between the view cube and the buttons in logical pixels at scale 1.
+const SPACING: f32 = 8.0;
+/// Half the height of the orthographic view of the view cube, which fits the cube with side length 2 when seen from a corner.
+const CUBE_VIEW_HALF_SIZE: f32 = 1.8;
+/// The coordinate on the cube where the faces end and the edges and corners begin.
+const EDGE_THRESHOLD: f32 = 0.6;
+/// The distance in logical pixels the pointer can move while pressing the view cube and still count as a click instead of a drag.
+const CLICK_DISTANCE: f32 = 4.0;
+/// The angle in radians a view along the up direction is tilted, since [OrbitControl] cannot rotate away from a view exactly along the up direction.
+const POLE_TILT: f32 = 0.05;
+
+///
+/// The corner of the viewport where the [NavigationWidgets] are placed.
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum WidgetCorner {
+    /// The top left corner.
+    TopLeft,
+    /// The top right corner.
+    #[default]
+    TopRight,
+    /// The bottom left corner.
+    BottomLeft,
+    /// The bottom right corner.
+    BottomRight,
+}
+
+///
+/// One of the parts of the [NavigationWidgets] which can be hovered and pressed.
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NavigationWidget {
+    /// A face, edge or corner of the view cube given by the direction from the center of the cube, where each component is -1, 0 or 1.
+    /// Clicking it moves the camera to look at the target from this direction.
+    ViewCube(Vec3),
+    /// The button that moves the camera closer to the target.
+    ZoomIn,
+    /// The button that moves the camera away from the target.
+    ZoomOut,
+    /// The button that moves the camera back to the home view, see [NavigationWidgets::set_home].
+    Home,
+}
+
+const BUTTONS: [NavigationWidget; 3] = [
+    NavigationWidget::ZoomIn,
+    NavigationWidget::ZoomOut,
+    NavigationWidget::Home,
+];
+
+///
+/// On-screen navigation widgets for touch devices and users without a mouse wheel, which work together with an [OrbitControl].
+/// The widgets consist of a view cube, which can be clicked on its faces, edges and corners to move the camera to look along the axes
+/// or dragged to orbit, and zoom in, zoom out and home buttons below the cube.
+/// The widgets are rendered by three-d using the 2D shapes, see [NavigationWidgets::render], and placed in a corner of the viewport of the camera, see [NavigationWidgets::corner].
+///
+/// The camera moves smoothly to the new view when clicking the widgets.
+/// The orbit target is shared with the [OrbitControl], so the home button also moves the target of the control.
+///
+pub struct NavigationWidgets {
+    context: Context,
+    /// The corner of the viewport where the widgets are placed. The default is [WidgetCorner::TopRight].
+    pub corner: WidgetCorner,
+    /// The distance in logical pixels between the widgets and the edges of the viewport. The default is 16.
+    pub margin: f32,
+    /// The scale of the widgets, where 1 corresponds to a view cube of 96 logical pixels. The default is 1.
+    pub scale: f32,
+    /// The duration in seconds of the camera animation when a widget is clicked. The default is 0.4.
+    pub animation_duration: f32,
+    /// The factor the distance to the target is divided by when zooming in and multiplied by when zooming out. The default is 1.5.
+    pub zoom_factor: f32,
+    home: CameraPose,
+    cube: Gm<Mesh, ColorMaterial>,
+    cube_highlight: Option<Vec3>,
+    buttons: Vec<Gm<Circle, ColorMaterial>>,
+    icons: Vec<(usize, Vec2, Vec2, Gm<Line, ColorMaterial>)>,
+    device_pixel_ratio: f32,
+    hovered: Option<NavigationWidget>,
+    pressed: Option<(NavigationWidget, f32)>,
+    animation: Option<CameraAnimation>,
+}
+
+impl NavigationWidgets {
+    ///
+    /// Creates new navigation widgets where the home view is the current view of the given camera.
+    ///
+    pub fn new(context: &Context, camera: &three_d_asset::Camera) -> Self {
+        let overlay_material = |color: Srgba| ColorMaterial {
+            color,
+            render_states: RenderStates {
+                depth_test: DepthTest::Always,
+                write_mask: WriteMask::COLOR,
+                blend: Blend::TRANSPARENCY,
+                ..Default::default()
+            },
+            is_transparent: true,
+            ..Default::default()
+        };
+        let buttons = BUTTONS
+            .iter()
+            .map(|_| {
+                Gm::new(
+                    Circle::new(context, vec2(0.0, 0.0), BUTTON_RADIUS),
+                    overlay_material(Srgba::new(0, 0, 0, 0)),
+                )
+            })
+            .collect();
+        // The icons are given in the local coordinates of the button with y pointing up and the radius of the button being 1
+        let icon_lines = [
+            (0, vec2(-0.45, 0.0), vec2(0.45, 0.0)),
+            (0, vec2(0.0, -0.45), vec2(0.0, 0.45)),
+            (1, vec2(-0.45, 0.0), vec2(0.45, 0.0)),
+            (2, vec2(-0.5, 0.0), vec2(0.0, 0.5)),
+            (2, vec2(0.0, 0.5), vec2(0.5, 0.0)),
+            (2, vec2(-0.35, 0.1), vec2(-0.35, -0.45)),
+            (2, vec2(0.35, 0.1), vec2(0.35, -0.45)),
+            (2, vec2(-0.35, -0.45), vec2(0.35, -0.45)),
+        ];
+        let icons = icon_lines
+            .into_iter()
+            .map(|(button, p0, p1)| {
+                let mut line = Line::new(context, vec2(0.0, 0.0), vec2(0.0, 0.0), 2.0);
+                line.set_cap(LineCap::Round);
+                (
+                    button,
+                    p0,
+                    p1,
+                    Gm::new(line, overlay_material(Srgba::WHITE)),
+                )
+            })
+            .collect();
+        Self {
+            context: context.clone(),
+            corner: WidgetCorner::default(),
+            margin: 16.0,
+            scale: 1.0,
+            animation_duration: 0.4,
+            zoom_factor: 1.5,
+            home: CameraPose::from_camera(camera),
+            cube: Gm::new(
+                Mesh::new(context, &cube_mesh(None)),
+                ColorMaterial {
+                    render_states: RenderStates {
+                        depth_test: DepthTest::Always,
+                        write_mask: WriteMask::COLOR,
+                        cull: Cull::Back,
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+            ),
+            cube_highlight: None,
+            buttons,
+            icons,
+            device_pixel_ratio: 1.0,
+            hovered: None,
+            pressed: None,
+            animation: None,
+        }
+    }
+
+    ///
+    /// Sets the home view which the camera moves to when the home button is clicked, given as the camera position, the target and the up direction.
+    /// The target is also the orbit target of the [OrbitControl] when the home view is reached.
+    ///
+    pub fn set_home(&mut self, position: Vec3, target: Vec3, up: Vec3) {
+        self.home = CameraPose {
+            position,
+            target,
+            up,
+        };
+    }
+
+    ///
+    /// Returns the widget below the pointer, if any.
+    ///
+    pub fn hovered(&self) -> Option<NavigationWidget> {
+        self.hovered
+    }
+
+    ///
+    /// Returns whether the camera is currently moving to a new view because a widget was clicked.
+    ///
+    pub fn is_animating(&self) -> bool {
+        self.animation.is_some()
+    }
+
+    ///
+    /// Handles the events and animates the camera. Must be called each frame before [OrbitControl::handle_events] with the same camera and orbit control,
+    /// so that pointer events on the widgets are marked as handled and are not also used by the orbit control.
+    /// The elapsed time is the time since the last frame in milliseconds, for example [FrameInput::elapsed_time](crate::window::FrameInput::elapsed_time).
+    /// Pressing somewhere else than on the widgets stops an ongoing camera animation so the user can take over.
+    /// Returns whether the camera has changed.
+    ///
+    pub fn handle_events(
+        &mut self,
+        camera: &mut three_d_asset::Camera,
+        control: &mut OrbitControl,
+        events: &mut [Event],
+        elapsed_time: f64,
+        device_pixel_ratio: f32,
+    ) -> bool {
+        self.device_pixel_ratio = device_pixel_ratio;
+        let viewport = camera.viewport();
+        let layout = self.layout(viewport);
+        let mut change = false;
+        for event in events.iter_mut() {
+            match event {
+                Event::MousePress {
+                    button,
+                    position,
+                    handled,
+                    ..
+                } => {
+                    if *handled || *button != MouseButton::Left {
+                        continue;
+                    }
+                    let point = self.to_logical(viewport, *position);
+                    if let Some(widget) = widget_at(&layout, camera, point) {
+                        self.pressed = Some((widget, 0.0));
+                        self.hovered = Some(widget);
+                        *handled = true;
+                    } else {
+                        self.animation = None;
+                    }
+                }
+                Event::MouseMotion {
+                    button,
+                    delta,
+                    position,
+                    handled,
+                    ..
+                } => {
+                    let point = self.to_logical(viewport, *position);
+                    if let Some((widget, moved)) = self.pressed.as_mut() {
+                        if button.is_none() {
+                            continue;
+                        }
+                        *moved += (delta.0 * delta.0 + delta.1 * delta.1).sqrt();
+                        if matches!(widget, NavigationWidget::ViewCube(_)) {
+                            if *moved > CLICK_DISTANCE {
+                                let speed = 0.01;
+                                camera.rotate_around_with_fixed_up(
+                                    control.target,
+                                    speed * delta.0,
+                                    speed * delta.1,
+                                );
+                                self.animation = None;
+                                self.hovered = None;
+                                change = true;
+                            }
+                        } else {
+                            self.hovered = widget_at(&layout, camera, point);
+                        }
+                        *handled = true;
+                    } else if !*handled {
+                        self.hovered = widget_at(&layout, camera, point);
+                    }
+                }
+                Event::MouseRelease {
+                    button,
+                    position,
+                    handled,
+                    ..
+                } => {
+                    if *button != MouseButton::Left {
+                        continue;
+                    }
+                    if let Some((widget, moved)) = self.pressed.take() {
+                        let point = self.to_logical(viewport, *position);
+                        let released_on = widget_at(&layout, camera, point);
+                        match widget {
+                            NavigationWidget::ViewCube(direction) if moved <= CLICK_DISTANCE => {
+                                self.snap_to(camera, control, direction);
+                            }
+                            NavigationWidget::ViewCube(_) => {}
+                            _ if released_on == Some(widget) => {
+                                self.click_button(camera, control, widget);
+                            }
+                            _ => {}
+                        }
+                        self.hovered = released_on;
+                        *handled = true;
+                    }
+                }
+                Event::MouseLeave => {
+                    self.hovered = None;
+                }
+                _ => {}
+            }
+        }
+
+        if let Some(mut animation) = self.animation.take() {
+            animation.time += 0.001 * elapsed_time as f32;
+            let t = if self.animation_duration > 0.0 {
+                (animation.time / self.animation_duration).min(1.0)
+            } else {
+                1.0
+            };
+            let pose = animation.pose_at(t * t * (3.0 - 2.0 * t));
+            camera.set_view(pose.position, pose.target, pose.up);
+            control.target = pose.target;
+            if t < 1.0 {
+                self.animation = Some(animation);
+            }
+            change = true;
+        }
+
+        self.update_shapes(&layout, viewport);
+        change
+    }
+
+    ///
+    /// Renders the widgets on top of the content of the given render target, so this should be called after rendering the scene.
+    /// The camera must be the camera given to [NavigationWidgets::handle_events], which is used to place the widgets and orient the view cube.
+    ///
+    pub fn render(&self, target: &RenderTarget, camera: &three_d_asset::Camera) {
+        let viewport = camera.viewport();
+        let layout = self.layout(viewport);
+        let cube_viewport = Viewport {
+            x: viewport.x + (layout.cube_origin.x * self.device_pixel_ratio).round() as i32,
+            y: viewport.y + viewport.height as i32
+                - ((layout.cube_origin.y + layout.cube_size) * self.device_pixel_ratio).round()
+                    as i32,
+            width: (layout.cube_size * self.device_pixel_ratio).round() as u32,
+            height: (layout.cube_size * self.device_pixel_ratio).round() as u32,
+        };
+        let cube_camera = Camera::new_orthographic(
+            cube_viewport,
+            -4.0 * camera.view_direction(),
+            vec3(0.0, 0.0, 0.0),
+            camera.up_orthogonal(),
+            2.0 * CUBE_VIEW_HALF_SIZE,
+            0.1,
+            10.0,
+        );
+        let camera_2d = Camera::new_2d_logical(viewport, self.device_pixel_ratio);
+        target
+            .render(&cube_camera, &self.cube, &[])
+            .render(&camera_2d, &self.buttons, &[])
+            .render(
+                &camera_2d,
+                self.icons.iter().map(|(_, _, _, icon)| icon),
+                &[],
+            );
+    }
+
+    fn click_button(
+        &mut self,
+        camera: &three_d_asset::Camera,
+        control: &OrbitControl,
+        widget: NavigationWidget,
+    ) {
+        let target = control.target;
+        let offset = camera.position() - target;
+        let distance = offset.magnitude();
+        let pose = match widget {
+            NavigationWidget::ZoomIn | NavigationWidget::ZoomOut => {
+                let factor = if widget == NavigationWidget::ZoomIn {
+                    1.0 / self.zoom_factor
+                } else {
+                    self.zoom_factor
+                };
+                let new_distance =
+                    (distance * factor).clamp(control.min_distance, control.max_distance);
+                CameraPose {
+                    position: target + offset * (new_distance / distance),
+                    target,
+                    up: camera.up(),
+                }
+            }
+            NavigationWidget::Home => self.home,
+            NavigationWidget::ViewCube(_) => unreachable!(),
+        };
+        self.animate_to(camera, pose);
+    }
+
+    fn snap_to(&mut self, camera: &three_d_asset::Camera, control: &OrbitControl, direction: Vec3) {
+        let up = camera.up();
+        let mut direction = direction.normalize();
+        if direction.dot(up).abs() > 0.999 {
+            let tilt = vec3(1.0, 0.0, 0.0).cross(up);
+            let tilt = if tilt.magnitude2() > 0.0001 {
+                tilt
+            } else {
+                vec3(0.0, 1.0, 0.0).cross(up)
+            };
+            direction = direction * POLE_TILT.cos() + tilt.normalize() * POLE_TILT.sin();
+        }
+        let target = control.target;
+        let distance = camera.position().distance(target);
+        self.animate_to(
+            camera,
+            CameraPose {
+                position: target + direction * distance,
+                target,
+                up,
+            },
+        );
+    }
+
+    fn animate_to(&mut self, camera: &three_d_asset::Camera, pose: CameraPose) {
+        self.animation = Some(CameraAnimation {
+            from: CameraPose::from_camera(camera),
+            to: pose,
+            time: 0.0,
+        });
+    }
+
+    fn layout(&self, viewport: Viewport) -> Layout {
+        let width = viewport.width as f32 / self.device_pixel_ratio;
+        let height = viewport.height as f32 / self.device_pixel_ratio;
+        let cube_size = CUBE_SIZE * self.scale;
+        let button_radius = BUTTON_RADIUS * self.scale;
+        let total_height = cube_size + SPACING * self.scale + 2.0 * button_radius;
+        let x = match self.corner {
+            WidgetCorner::TopLeft | WidgetCorner::BottomLeft => self.margin,
+            WidgetCorner::TopRight | WidgetCorner::BottomRight => width - self.margin - cube_size,
+        };
+        let y = match self.corner {
+            WidgetCorner::TopLeft | WidgetCorner::TopRight => self.margin,
+            WidgetCorner::BottomLeft | WidgetCorner::BottomRight => {
+                height - self.margin - total_height
+            }
+        };
+        let button_y = y + cube_size + SPACING * self.scale + button_radius;
+        Layout {
+            cube_origin: vec2(x, y),
+            cube_size,
+            button_centers: [
+                vec2(x + cube_size / 6.0, button_y),
+                vec2(x + cube_size * 0.5, button_y),
+                vec2(x + cube_size * 5.0 / 6.0, button_y),
+            ],
+            button_radius,
+        }
+    }
+
+    ///
+    /// Converts the given position in physical pixels to logical pixels relative to the top left corner of the viewport.
+    ///
+    fn to_logical(&self, viewport: Viewport, position: PhysicalPoint) -> Vec2 {
+        vec2(
+            (position.x - viewport.x as f32) / self.device_pixel_ratio,
+            (viewport.y as f32 + viewport.height as f32 - position.y) / self.device_pixel_ratio,
+        )
+    }
+
+    fn update_shapes(&mut self, layout: &Layout, viewport: Viewport) {
+        let height = viewport.height as f32 / self.device_pixel_ratio;
+        // The 2D camera has y pointing up from the bottom of the viewport
+        let to_2d = |point: Vec2| vec2(point.x, height - point.y);
+        let pressed = self.pressed.map(|(widget, _)| widget);
+        for (i, button) in self.buttons.iter_mut().enumerate() {
+            button.set_center(to_2d(layout.button_centers[i]));
+            button.set_radius(layout.button_radius);
+            button.material.color = if pressed == Some(BUTTONS[i]) {
+                Srgba::new(110, 110, 110, 220)
+            } else if self.hovered == Some(BUTTONS[i]) {
+                Srgba::new(80, 80, 80, 200)
+            } else {
+                Srgba::new(40, 40, 40, 160)
+            };
+        }
+        for (button, p0, p1, icon) in self.icons.iter_mut() {
+            let center = to_2d(layout.button_centers[*button]);
+            icon.set_endpoints(
+                center + *p0 * layout.button_radius,
+                center + *p1 * layout.button_radius,
+            );
+            icon.set_thickness(2.0 * self.scale);
+        }
+
+        let highlight = match self.hovered {
+            Some(NavigationWidget::ViewCube(direction)) => Some(direction),
+            _ => None,
+        };
+        if highlight != self.cube_highlight {
+            self.cube_highlight = highlight;
+            self.cube.geometry = Mesh::new(&self.context, &cube_mesh(highlight));
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct CameraPose {
+    position: Vec3,
+    target: Vec3,
+    up: Vec3,
+}
+
+impl CameraPose {
+    fn from_camera(camera: &three_d_asset::Camera) -> Self {
+        Self {
+            position: camera.position(),
+            target: camera.target(),
+            up: camera.up(),
+        }
+    }
+}
+
+///
+/// An animation of the camera which orbits around the target, so the distance to the target changes linearly
+/// and the direction from the target to the camera is rotated along the shortest arc.
+///
+struct CameraAnimation {
+    from: CameraPose,
+    to: CameraPose,
+    time: f32,
+}
+
+impl CameraAnimation {
+    fn pose_at(&self, t: f32) -> CameraPose {
+        let from_offset = self.from.position - self.from.target;
+        let to_offset = self.to.position - self.to.target;
+        let distance = from_offset.magnitude() * (1.0 - t) + to_offset.magnitude() * t;
+        let up = self.from.up.lerp(self.to.up, t);
+        let up = if up.magnitude2() > 0.0001 {
+            up.normalize()
+        } else {
+            self.to.up
+        };
+        let direction = slerp(from_offset.normalize(), to_offset.normalize(), t, up);
+        let target = self.from.target.lerp(self.to.target, t);
+        CameraPose {
+            position: target + direction * distance,
+            target,
+            up,
+        }
+    }
+}
+
+///
+/// Spherical interpolation between the two unit vectors, which rotates around the given axis when the vectors are opposite.
+///
+fn slerp(a: Vec3, b: Vec3, t: f32, axis: Vec3) -> Vec3 {
+    let cos_angle = a.dot(b).clamp(-1.0, 1.0);
+    let angle = cos_angle.acos();
+    if angle.sin() < 0.0001 {
+        if cos_angle > 0.0 {
+            return b;
+        }
+        return Mat3::from_axis_angle(axis, radians(std::f32::consts::PI * t)) * a;
+    }
+    (a * ((1.0 - t) * angle).sin() + b * (t * angle).sin()) / angle.sin()
+}
+
+///
+/// The position of the widgets in logical pixels relative to the top left corner of the viewport.
+///
+struct Layout {
+    cube_origin: Vec2,
+    cube_size: f32,
+    button_centers: [Vec2; 3],
+    button_radius: f32,
+}
+
+fn widget_at(
+    layout: &Layout,
+    camera: &three_d_asset::Camera,
+    point: Vec2,
+) -> Option<NavigationWidget> {
+    for (i, center) in layout.button_centers.iter().enumerate() {
+        if center.distance(point) <= layout.button_radius {
+            return Some(BUTTONS[i]);
+        }
+    }
+
+    // Intersect the ray through the point in the orthographic view of the cube with the cube
+    let u = 2.0 * (point.x - layout.cube_origin.x) / layout.cube_size - 1.0;
+    let v = 1.0 - 2.0 * (point.y - layout.cube_origin.y) / layout.cube_size;
+    if u.abs() > 1.0 || v.abs() > 1.0 {
+        return None;
+    }
+    let direction = camera.view_direction();
+    let origin = camera.right_direction() * u * CUBE_VIEW_HALF_SIZE
+        + camera.up_orthogonal() * v * CUBE_VIEW_HALF_SIZE
+        - direction * 4.0;
+    let mut t_min = f32::NEG_INFINITY;
+    let mut t_max = f32::INFINITY;
+    for i in 0..3 {
+        if direction[i].abs() < 0.00001 {
+            if origin[i].abs() > 1.0 {
+                return None;
+            }
+        } else {
+            let t0 = (-1.0 - origin[i]) / direction[i];
+            let t1 = (1.0 - origin[i]) / direction[i];
+            t_min = t_min.max(t0.min(t1));
+            t_max = t_max.min(t0.max(t1));
+        }
+    }
+    if t_min > t_max {
+        return None;
+    }
+    let hit = origin + direction * t_min;
+    let region = |c: f32| {
+        if c > EDGE_THRESHOLD {
+            1.0
+        } else if c < -EDGE_THRESHOLD {
+            -1.0
+        } else {
+            0.0
+        }
+    };
+    Some(NavigationWidget::ViewCube(vec3(
+        region(hit.x),
+        region(hit.y),
+        region(hit.z),
+    )))
+}
+
+///
+/// Creates the view cube with side length 2, where each face is divided into a 3 by 3 grid of cells which belong to either the face, an edge or a corner.
+/// The faces are tinted with the color of their axis (red for x, green for y and blue for z) and the cells of the given region are highlighted.
+///
+fn cube_mesh(highlight: Option<Vec3>) -> CpuMesh {
+    let bounds = [-1.0, -EDGE_THRESHOLD, EDGE_THRESHOLD, 1.0];
+    let light_direction = vec3(0.3, 0.8, 0.5).normalize();
+    let mut positions = Vec::new();
+    let mut colors = Vec::new();
+    for axis in 0..3 {
+        for sign in [-1.0, 1.0] {
+            let mut normal = Vec3::zero();
+            normal[axis] = sign;
+            let mut u = Vec3::zero();
+            u[(axis + 1) % 3] = 1.0;
+            let mut v = Vec3::zero();
+            v[(axis + 2) % 3] = 1.0;
+            // Keep the triangles counter clockwise when seen from outside the cube
+            let (u, v) = if sign > 0.0 { (u, v) } else { (v, u) };
+            let shade = 0.8 + 0.2 * normal.dot(light_direction);
+            for i in 0..3 {
+                for j in 0..3 {
+                    let region = normal + u * (i as f32 - 1.0) + v * (j as f32 - 1.0);
+                    let color = if highlight == Some(region) {
+                        vec3(1.0, 0.75, 0.3)
+                    } else if i == 1 && j == 1 {
+                        let mut tint = vec3(0.55, 0.55, 0.6);
+                        tint[axis] = if sign > 0.0 { 0.9 } else { 0.7 };
+                        tint * shade
+                    } else {
+                        vec3(0.75, 0.75, 0.78) * shade
+                    };
+                    let corners = [
+                        (bounds[i], bounds[j]),
+                        (bounds[i + 1], bounds[j]),
+                        (bounds[i + 1], bounds[j + 1]),
+                        (bounds[i], bounds[j + 1]),
+                    ]
+                    .map(|(a, b)| normal + u * a + v * b);
+                    positions.extend([
+                        corners[0], corners[1], corners[2], corners[0], corners[2], corners[3],
+                    ]);
+                    let color = Srgba::new_opaque(
+                        (color.x * 255.0) as u8,
+                        (color.y * 255.0) as u8,
+                        (color.z * 255.0) as u8,
+                    );
+                    colors.extend([color; 6]);
+                }
+            }
+        }
+    }
+    CpuMesh {
+        positions: Positions::F32(positions),
+        colors: Some(colors),
+        ..Default::default()
+    }
+}
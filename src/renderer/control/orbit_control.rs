@@ -11,6 +11,8 @@ pub struct OrbitControl {
     pub min_distance: f32,
     /// The maximum distance to the target point.
     pub max_distance: f32,
+    /// If set, the near and far planes of the camera are fitted to the scene bounds each time the events are handled. `None` by default.
+    pub depth_range_fit: Option<DepthRangeFit>,
 }
 
 impl OrbitControl {
@@ -20,6 +22,7 @@ impl OrbitControl {
             target,
             min_distance,
             max_distance,
+            depth_range_fit: None,
         }
     }
 
@@ -78,6 +81,9 @@ impl OrbitControl {
                 _ => {}
             }
         }
+        if let Some(depth_range_fit) = self.depth_range_fit {
+            change |= depth_range_fit.apply(camera);
+        }
         change
     }
 }
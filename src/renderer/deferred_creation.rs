@@ -0,0 +1,340 @@
+//!
+//! Creation of GPU objects from data prepared on other threads, spread across frames, see [DeferredCreationQueue].
+//!
+
+use crate::renderer::*;
+#[cfg(target_arch = "wasm32")]
+use instant::Instant;
+use std::any::{Any, TypeId};
+use std::collections::{HashMap, VecDeque};
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{mpsc, Arc};
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Instant;
+
+///
+/// An object which can be created on the GPU from data that can be prepared on another thread,
+/// so that it can be created using a [DeferredCreationQueue].
+///
+pub trait DeferredCreation: Sized + 'static {
+    /// The CPU data the object is created from.
+    type Source: Send + 'static;
+
+    /// Creates the object from the given data.
+    fn create(context: &Context, source: Self::Source) -> Self;
+}
+
+impl DeferredCreation for Mesh {
+    type Source = CpuMesh;
+    fn create(context: &Context, source: CpuMesh) -> Self {
+        Mesh::new(context, &source)
+    }
+}
+
+impl DeferredCreation for Texture2D {
+    type Source = CpuTexture;
+    fn create(context: &Context, source: CpuTexture) -> Self {
+        Texture2D::new(context, &source)
+    }
+}
+
+impl DeferredCreation for Texture2DRef {
+    type Source = CpuTexture;
+    fn create(context: &Context, source: CpuTexture) -> Self {
+        Texture2DRef::from_cpu_texture(context, &source)
+    }
+}
+
+impl DeferredCreation for PhysicalMaterial {
+    type Source = CpuMaterial;
+    fn create(context: &Context, source: CpuMaterial) -> Self {
+        PhysicalMaterial::new(context, &source)
+    }
+}
+
+///
+/// Refers to an object of type `T` which is created by a [DeferredCreationQueue].
+/// The handle can be sent between threads no matter the type of the object.
+/// Use [DeferredCreationQueue::take] to get the object when it has been created or [CreationHandle::cancel] if it is no longer needed.
+///
+pub struct CreationHandle<T> {
+    id: u64,
+    cancelled: Arc<AtomicBool>,
+    _type: PhantomData<fn() -> T>,
+}
+
+impl<T> Clone for CreationHandle<T> {
+    fn clone(&self) -> Self {
+        Self {
+            id: self.id,
+            cancelled: self.cancelled.clone(),
+            _type: PhantomData,
+        }
+    }
+}
+
+impl<T> std::fmt::Debug for CreationHandle<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CreationHandle")
+            .field("id", &self.id)
+            .field("cancelled", &self.is_cancelled())
+            .finish()
+    }
+}
+
+impl<T> CreationHandle<T> {
+    ///
+    /// Cancels the creation of the object. If the object has not been created yet, it is never created,
+    /// otherwise it is dropped at the next [DeferredCreationQueue::process] unless it has been taken.
+    /// Can be called from any thread.
+    ///
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    ///
+    /// Returns whether the creation has been cancelled, see [CreationHandle::cancel].
+    ///
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+// Returns the created object or `None` if it has been given to a callback
+type CreateFunction = Box<dyn FnOnce(&Context) -> Option<Box<dyn Any>> + Send>;
+
+struct QueuedCreation {
+    id: u64,
+    type_id: TypeId,
+    cancelled: Arc<AtomicBool>,
+    create: CreateFunction,
+}
+
+///
+/// Used to add objects to be created by a [DeferredCreationQueue], see [DeferredCreationQueue::sender].
+/// It can be cloned and sent to other threads, for example threads loading and processing assets.
+///
+#[derive(Clone)]
+pub struct DeferredCreationSender {
+    sender: mpsc::Sender<QueuedCreation>,
+    next_id: Arc<AtomicU64>,
+}
+
+impl DeferredCreationSender {
+    ///
+    /// Adds an object to be created from the given data, for example a [Mesh] from a [CpuMesh].
+    /// Use [DeferredCreationQueue::take] with the returned handle to get the object when it has been created.
+    ///
+    pub fn create<T: DeferredCreation>(&self, source: T::Source) -> CreationHandle<T> {
+        self.create_with(move |context| T::create(context, source))
+    }
+
+    ///
+    /// Adds an object to be created using the given function, for example to assemble a material from several textures.
+    /// Use [DeferredCreationQueue::take] with the returned handle to get the object when it has been created.
+    ///
+    pub fn create_with<T: 'static>(
+        &self,
+        create: impl FnOnce(&Context) -> T + Send + 'static,
+    ) -> CreationHandle<T> {
+        self.send(move |context| Some(Box::new(create(context)) as Box<dyn Any>))
+    }
+
+    ///
+    /// Adds an object to be created from the given data and given to the callback when it has been created.
+    /// The callback is called on the thread calling [DeferredCreationQueue::process], and not called if the creation is cancelled.
+    ///
+    pub fn create_then<T: DeferredCreation>(
+        &self,
+        source: T::Source,
+        callback: impl FnOnce(T) + Send + 'static,
+    ) -> CreationHandle<T> {
+        self.send(move |context| {
+            callback(T::create(context, source));
+            None
+        })
+    }
+
+    fn send<T: 'static>(
+        &self,
+        create: impl FnOnce(&Context) -> Option<Box<dyn Any>> + Send + 'static,
+    ) -> CreationHandle<T> {
+        let handle = CreationHandle {
+            id: self.next_id.fetch_add(1, Ordering::Relaxed),
+            cancelled: Arc::new(AtomicBool::new(false)),
+            _type: PhantomData,
+        };
+        // If the queue has been dropped, nothing is created
+        self.sender
+            .send(QueuedCreation {
+                id: handle.id,
+                type_id: TypeId::of::<T>(),
+                cancelled: handle.cancelled.clone(),
+                create: Box::new(create),
+            })
+            .ok();
+        handle
+    }
+}
+
+///
+/// Statistics from a [DeferredCreationQueue], for example to show in a debug overlay.
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct DeferredCreationStats {
+    /// The number of objects created in the last call to [DeferredCreationQueue::process].
+    pub created: usize,
+    /// The number of objects that are waiting to be created.
+    pub pending: usize,
+    /// The total number of objects that have been cancelled before they were created or taken.
+    pub cancelled: usize,
+    /// The time in milliseconds spent in the last call to [DeferredCreationQueue::process].
+    pub elapsed_ms: f64,
+}
+
+///
+/// Creates GPU objects, like meshes, textures and materials, from data prepared on other threads within a time budget each frame.
+/// GPU objects can only be created on the thread where the [Context] lives, so creating a lot of objects at once, for example when a scene has been loaded,
+/// makes that frame take a long time. Instead, the data is added using a [DeferredCreationSender], which can be sent to other threads,
+/// and the objects are created a few at a time each frame in [DeferredCreationQueue::process], in the order they were added.
+///
+/// ```no_run
+/// # use three_d::*;
+/// # let context: Context = unimplemented!();
+/// let mut queue = DeferredCreationQueue::new();
+/// let sender = queue.sender();
+/// let handle = std::thread::spawn(move || sender.create::<Mesh>(CpuMesh::sphere(64)))
+///     .join()
+///     .unwrap();
+///
+/// // Each frame
+/// queue.process(&context, 4.0);
+/// if let Some(mesh) = queue.take(&handle) {
+///     // Use the mesh
+/// }
+/// ```
+///
+pub struct DeferredCreationQueue {
+    sender: DeferredCreationSender,
+    receiver: mpsc::Receiver<QueuedCreation>,
+    pending: VecDeque<QueuedCreation>,
+    finished: HashMap<u64, (Arc<AtomicBool>, Box<dyn Any>)>,
+    estimates: HashMap<TypeId, f64>,
+    stats: DeferredCreationStats,
+}
+
+impl Default for DeferredCreationQueue {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DeferredCreationQueue {
+    ///
+    /// Creates a new empty queue.
+    ///
+    pub fn new() -> Self {
+        let (sender, receiver) = mpsc::channel();
+        Self {
+            sender: DeferredCreationSender {
+                sender,
+                next_id: Arc::new(AtomicU64::new(0)),
+            },
+            receiver,
+            pending: VecDeque::new(),
+            finished: HashMap::new(),
+            estimates: HashMap::new(),
+            stats: DeferredCreationStats::default(),
+        }
+    }
+
+    ///
+    /// Returns a sender which is used to add objects to be created by this queue.
+    ///
+    pub fn sender(&self) -> DeferredCreationSender {
+        self.sender.clone()
+    }
+
+    ///
+    /// Creates the objects that have been added in the order they were added, until the given time budget in milliseconds is spent.
+    /// The time each type of object takes to create is estimated from the previously created objects of the same type,
+    /// and an object is only created if it is expected to finish within the budget.
+    /// At least one object is created in each call, so an object which takes longer than the budget to create is created alone.
+    /// Call this once each frame. Returns the number of objects created.
+    ///
+    pub fn process(&mut self, context: &Context, time_budget_ms: f64) -> usize {
+        let start = Instant::now();
+        self.pending.extend(self.receiver.try_iter());
+        let finished_count = self.finished.len();
+        self.finished
+            .retain(|_, (cancelled, _)| !cancelled.load(Ordering::Relaxed));
+        self.stats.cancelled += finished_count - self.finished.len();
+
+        let mut created = 0;
+        while let Some(creation) = self.pending.pop_front() {
+            if creation.cancelled.load(Ordering::Relaxed) {
+                self.stats.cancelled += 1;
+                continue;
+            }
+            // Objects of an unknown type are expected to be as slow as the slowest known type
+            let estimate = self
+                .estimates
+                .get(&creation.type_id)
+                .copied()
+                .unwrap_or_else(|| self.estimates.values().copied().fold(0.0, f64::max));
+            if created > 0 && elapsed_ms(start) + estimate > time_budget_ms {
+                self.pending.push_front(creation);
+                break;
+            }
+
+            let creation_start = Instant::now();
+            if let Some(object) = (creation.create)(context) {
+                self.finished
+                    .insert(creation.id, (creation.cancelled, object));
+            }
+            let duration = elapsed_ms(creation_start);
+            // Decrease the estimate slowly to avoid exceeding the budget after a single fast creation
+            let estimate = self.estimates.entry(creation.type_id).or_insert(duration);
+            *estimate = duration.max(0.9 * *estimate + 0.1 * duration);
+            created += 1;
+        }
+
+        self.stats.created = created;
+        self.stats.pending = self.pending.len();
+        self.stats.elapsed_ms = elapsed_ms(start);
+        created
+    }
+
+    ///
+    /// Returns whether the object referred to by the given handle has been created and is ready to be taken, see [DeferredCreationQueue::take].
+    ///
+    pub fn is_ready<T>(&self, handle: &CreationHandle<T>) -> bool {
+        !handle.is_cancelled() && self.finished.contains_key(&handle.id)
+    }
+
+    ///
+    /// Returns the object referred to by the given handle if it has been created, otherwise `None`.
+    /// The object can only be taken once and is never returned if it was given to a callback or the creation has been cancelled.
+    ///
+    pub fn take<T: 'static>(&mut self, handle: &CreationHandle<T>) -> Option<T> {
+        if !self.is_ready(handle) {
+            return None;
+        }
+        self.finished
+            .remove(&handle.id)
+            .and_then(|(_, object)| object.downcast::<T>().ok())
+            .map(|object| *object)
+    }
+
+    ///
+    /// Returns the statistics of this queue.
+    ///
+    pub fn stats(&self) -> DeferredCreationStats {
+        self.stats
+    }
+}
+
+fn elapsed_ms(start: Instant) -> f64 {
+    start.elapsed().as_secs_f64() * 1000.0
+}
@@ -0,0 +1,178 @@
+use crate::renderer::*;
+
+///
+/// The layers of transparent fragments peeled from front to back, where each layer contains the nearest fragment behind the fragment in the previous layer
+/// with the color multiplied by the alpha, see [RenderTarget::render_transparent_depth_peeled].
+///
+pub(crate) struct DepthPeeling {
+    pub layers: Vec<PooledTexture>,
+}
+
+impl DepthPeeling {
+    ///
+    /// Peels at most the given number of layers of the given objects and returns the objects
+    /// which do not expose their material (see [Object::with_material]) and therefore are not rendered.
+    /// Stops when a pass does not render any fragments.
+    ///
+    pub fn render<'a, O: Object>(
+        context: &Context,
+        viewer: &dyn Viewer,
+        objects: &'a [O],
+        lights: &[&dyn Light],
+        layer_count: u32,
+        depth_texture: Option<DepthTexture>,
+    ) -> (Self, Vec<&'a O>) {
+        let viewport = viewer.viewport();
+        let viewer = GeometryPassCamera(viewer);
+        let query = OcclusionQuery::new(context);
+        let mut unsupported = Vec::new();
+        let mut layers = Vec::new();
+        let mut previous_depth: Option<PooledTexture<DepthTexture2D>> = None;
+        for layer_index in 0..layer_count.max(1) {
+            let mut color = context.request_texture::<[f16; 4]>(
+                viewport.width,
+                viewport.height,
+                Interpolation::Nearest,
+            );
+            let mut depth = context.request_depth_texture::<f32>(viewport.width, viewport.height);
+            query.measure(|| {
+                RenderTarget::new(color.as_color_target(None), depth.as_depth_target())
+                    .clear(
+                        ClearState::color_and_depth(0.0, 0.0, 0.0, 0.0, 1.0)
+                            .with_depth_mode(viewer.depth_mode()),
+                    )
+                    .write::<RendererError>(|| {
+                        for object in objects {
+                            let mut result = None;
+                            object.with_material(&mut |material| {
+                                result = Some(render_pass(
+                                    context,
+                                    &viewer,
+                                    object,
+                                    material,
+                                    lights,
+                                    previous_depth.as_deref(),
+                                    depth_texture,
+                                ));
+                            });
+                            match result {
+                                Some(result) => result?,
+                                None if layer_index == 0 => unsupported.push(object),
+                                None => {}
+                            }
+                        }
+                        Ok(())
+                    })
+                    .unwrap();
+            });
+            if !query.any_samples_passed() {
+                break;
+            }
+            layers.push(color);
+            previous_depth = Some(depth);
+        }
+        (Self { layers }, unsupported)
+    }
+}
+
+///
+/// Renders the geometry with the given material where fragments in front of or at the depth of the previous layer are discarded
+/// and the output of the material is multiplied by the alpha.
+/// The program is cached with an id that contains both the id of the material and the pass, since the id of the material cannot be changed.
+///
+fn render_pass(
+    context: &Context,
+    viewer: &dyn Viewer,
+    geometry: &dyn Geometry,
+    material: &dyn Material,
+    lights: &[&dyn Light],
+    previous_depth: Option<&DepthTexture2D>,
+    depth_texture: Option<DepthTexture>,
+) -> Result<(), RendererError> {
+    // The color is already multiplied by the alpha if the material is blended as premultiplied
+    let premultiplied = material.render_states().blend == Blend::PREMULTIPLIED;
+    let mut id = combine_ids(
+        geometry.id(),
+        EffectMaterialId::DepthPeelingMaterial,
+        lights.iter().map(|l| l.id()),
+    );
+    id.extend(material.id().0.to_le_bytes());
+    id.extend(
+        (previous_depth.is_some() as u16
+            | (premultiplied as u16) << 1
+            | depth_texture.map(|t| t.id() << 2).unwrap_or(0))
+        .to_le_bytes(),
+    );
+
+    let mut programs = context.programs.write().unwrap();
+    if !programs.contains_key(&id) {
+        let fragment_shader_source = format!(
+            "{}{}{}{}\n{}\n{}",
+            if previous_depth.is_some() {
+                "#define USE_PEEL_DEPTH\n"
+            } else {
+                ""
+            },
+            if premultiplied {
+                "#define PREMULTIPLIED_ALPHA\n"
+            } else {
+                ""
+            },
+            if depth_texture.is_some() {
+                "#define USE_DEPTH\n"
+            } else {
+                ""
+            },
+            // The main function of the material is called from the main function of the pass
+            material.fragment_shader_source(lights).replacen(
+                "void main()",
+                "void material_main()",
+                1
+            ),
+            depth_texture
+                .map(|t| t.fragment_shader_source())
+                .unwrap_or_default(),
+            include_str!("material/shaders/depth_peeling_pass.frag")
+        );
+        programs.insert(
+            id.clone(),
+            Program::from_source(
+                context,
+                &geometry.vertex_shader_source(),
+                &fragment_shader_source,
+            )?,
+        );
+    }
+    let program = programs.get(&id).unwrap();
+
+    material.use_uniforms(program, viewer, lights);
+    program.use_uniform_if_required(
+        "peelDepthDirection",
+        if viewer.depth_mode().is_reversed() {
+            -1.0f32
+        } else {
+            1.0
+        },
+    );
+    if let Some(previous_depth) = previous_depth {
+        program.use_depth_texture("peelDepthTexture", previous_depth);
+    }
+    if let Some(depth_texture) = depth_texture {
+        depth_texture.use_uniforms(program);
+        let viewport = viewer.viewport();
+        program.use_uniform(
+            "peelTexelSize",
+            vec2(1.0 / viewport.width as f32, 1.0 / viewport.height as f32),
+        );
+    }
+    let render_states = RenderStates {
+        depth_test: DepthTest::Less,
+        write_mask: WriteMask::COLOR_AND_DEPTH,
+        blend: Blend::Disabled,
+        ..material.render_states()
+    };
+    draw_with_depth_mode(context, &viewer, render_states, |render_states| {
+        geometry.draw(viewer, program, render_states)
+    });
+    Ok(())
+}
@@ -0,0 +1,347 @@
+//!
+//! Distance fields generated with the jump flooding algorithm and a glow around selected objects based on them, see [jump_flood_distance] and [DistanceGlow].
+//!
+
+use crate::renderer::*;
+
+const SEED_SHADER: &str = "
+uniform sampler2D maskTexture;
+
+layout (location = 0) out vec4 outColor;
+
+void main()
+{
+    ivec2 coords = ivec2(gl_FragCoord.xy);
+    float mask = texelFetch(maskTexture, coords, 0).r;
+    outColor = vec4(mask > 0.5 ? vec2(coords) : vec2(-1.0), 0.0, 1.0);
+}
+";
+
+const STEP_SHADER: &str = "
+uniform highp sampler2D seedTexture;
+uniform int stepSize;
+
+layout (location = 0) out vec4 outColor;
+
+void main()
+{
+    ivec2 coords = ivec2(gl_FragCoord.xy);
+    ivec2 size = textureSize(seedTexture, 0);
+    vec2 nearest = vec2(-1.0);
+    float nearest_distance = 1.0e30;
+    for (int y = -1; y <= 1; y++) {
+        for (int x = -1; x <= 1; x++) {
+            ivec2 sample_coords = coords + stepSize * ivec2(x, y);
+            if (any(lessThan(sample_coords, ivec2(0))) || any(greaterThanEqual(sample_coords, size))) {
+                continue;
+            }
+            vec2 seed = texelFetch(seedTexture, sample_coords, 0).xy;
+            if (seed.x < 0.0) {
+                continue;
+            }
+            vec2 offset = seed - vec2(coords);
+            float distance = dot(offset, offset);
+            if (distance < nearest_distance) {
+                nearest_distance = distance;
+                nearest = seed;
+            }
+        }
+    }
+    outColor = vec4(nearest, 0.0, 1.0);
+}
+";
+
+const DISTANCE_SHADER: &str = "
+uniform highp sampler2D seedTexture;
+
+layout (location = 0) out vec4 outColor;
+
+void main()
+{
+    ivec2 coords = ivec2(gl_FragCoord.xy);
+    vec2 seed = texelFetch(seedTexture, coords, 0).xy;
+    outColor = vec4(seed.x < 0.0 ? 1.0e30 : length(seed - vec2(coords)), 0.0, 0.0, 1.0);
+}
+";
+
+const GLOW_SHADER: &str = "
+#include <three-d/tone_mapping>
+#include <three-d/color_mapping>
+
+uniform highp sampler2D distanceTexture;
+uniform vec4 maskTransformation;
+uniform float pixelsPerTexel;
+uniform vec3 glowColor;
+uniform float radius;
+
+in vec2 uvs;
+
+layout (location = 0) out vec4 outColor;
+
+void main()
+{
+    float distance = pixelsPerTexel * texture(distanceTexture, maskTransformation.xy + uvs * maskTransformation.zw).r;
+    // No glow on top of the silhouette itself
+    if (distance <= 0.0 || distance >= radius) {
+        discard;
+    }
+    float falloff = 1.0 - distance / radius;
+    falloff *= falloff;
+    outColor = vec4(color_mapping(tone_mapping(glowColor)) * falloff, falloff);
+}
+";
+
+///
+/// The passes of the jump flooding algorithm, which are applied as screen materials to cache the programs.
+///
+enum JumpFloodPass<'a> {
+    Seed(&'a Texture2D),
+    Step(&'a Texture2D, i32),
+    Distance(&'a Texture2D),
+}
+
+impl Material for JumpFloodPass<'_> {
+    fn id(&self) -> EffectMaterialId {
+        match self {
+            Self::Seed(_) => EffectMaterialId::JumpFloodSeedMaterial,
+            Self::Step(..) => EffectMaterialId::JumpFloodStepMaterial,
+            Self::Distance(_) => EffectMaterialId::JumpFloodDistanceMaterial,
+        }
+    }
+
+    fn fragment_shader_source(&self, _lights: &[&dyn Light]) -> String {
+        match self {
+            Self::Seed(_) => SEED_SHADER,
+            Self::Step(..) => STEP_SHADER,
+            Self::Distance(_) => DISTANCE_SHADER,
+        }
+        .to_owned()
+    }
+
+    fn use_uniforms(&self, program: &Program, _viewer: &dyn Viewer, _lights: &[&dyn Light]) {
+        match self {
+            Self::Seed(texture) => program.use_texture("maskTexture", texture),
+            Self::Step(texture, step_size) => {
+                program.use_texture("seedTexture", texture);
+                program.use_uniform("stepSize", *step_size);
+            }
+            Self::Distance(texture) => program.use_texture("seedTexture", texture),
+        }
+    }
+
+    fn render_states(&self) -> RenderStates {
+        RenderStates {
+            depth_test: DepthTest::Always,
+            write_mask: WriteMask::COLOR,
+            ..Default::default()
+        }
+    }
+
+    fn material_type(&self) -> MaterialType {
+        MaterialType::Opaque
+    }
+}
+
+///
+/// Generates a distance field from the given mask texture using the jump flooding algorithm.
+/// The returned texture has the same size as the mask and contains, in the red channel, the distance in texels from each texel to the nearest texel
+/// where the red channel of the mask is larger than 0.5, ie. zero inside the mask.
+/// If the mask is empty, the distance is a very large value everywhere.
+///
+/// The distance field is generated in a number of full screen passes which is logarithmic in the size of the mask,
+/// so it is cheap to compute even for large distances. Besides the glow used by [DistanceGlow],
+/// it can be used for example for soft shadows of user interface elements or for generating signed distance fields for text.
+///
+pub fn jump_flood_distance(mask_texture: &Texture2D) -> Texture2D {
+    let context = mask_texture.context();
+    let (width, height) = (mask_texture.width(), mask_texture.height());
+    let viewport = Viewport::new_at_origo(width, height);
+    let camera = Camera::new_2d(viewport);
+    let mut source = context.request_texture::<[f32; 2]>(width, height, Interpolation::Nearest);
+    let mut target = context.request_texture::<[f32; 2]>(width, height, Interpolation::Nearest);
+    source.as_color_target(None).apply_screen_material(
+        &JumpFloodPass::Seed(mask_texture),
+        &camera,
+        &[],
+    );
+    let mut step_size = (width.max(height).next_power_of_two() / 2).max(1);
+    loop {
+        target.as_color_target(None).apply_screen_material(
+            &JumpFloodPass::Step(&source, step_size as i32),
+            &camera,
+            &[],
+        );
+        std::mem::swap(&mut source, &mut target);
+        if step_size == 1 {
+            break;
+        }
+        step_size /= 2;
+    }
+    let mut distance_texture = Texture2D::new_empty::<f32>(
+        context,
+        width,
+        height,
+        Interpolation::Nearest,
+        Interpolation::Nearest,
+        None,
+        Wrapping::ClampToEdge,
+        Wrapping::ClampToEdge,
+    );
+    distance_texture
+        .as_color_target(None)
+        .apply_screen_material(&JumpFloodPass::Distance(&source), &camera, &[]);
+    distance_texture
+}
+
+///
+/// A glow around the silhouette of selected objects, for example to highlight picked objects.
+/// The silhouettes are rendered into a mask with a lower resolution than the screen, see [DistanceGlow::resolution_scale],
+/// and a distance field is generated from the mask using [jump_flood_distance].
+/// The glow is blended over the scene and fades out with the distance to the silhouette, so very wide and soft halos are as cheap as thin ones.
+/// The mask covers a margin around the viewport given by the [DistanceGlow::radius], so objects partly outside the screen still glow at the edges.
+///
+/// Call [DistanceGlow::update] each frame the objects or the viewer changes and then apply the glow as a screen material,
+/// after the scene has been rendered, using for example [RenderTarget::apply_screen_material].
+///
+pub struct DistanceGlow {
+    context: Context,
+    distance_texture: Option<Texture2D>,
+    mask_transformation: Vec4,
+    pixels_per_texel: f32,
+    /// The color of the glow at the silhouette.
+    pub color: Srgba,
+    /// The intensity of the glow. This allows for higher intensity than 1, which is useful with tone mapping.
+    pub intensity: f32,
+    /// The distance in pixels from the silhouette at which the glow has faded out.
+    pub radius: f32,
+    /// The resolution of the mask relative to the viewport, for example 0.5 renders the mask in half resolution.
+    /// A lower resolution is faster, but gives a less precise glow close to the silhouette.
+    pub resolution_scale: f32,
+}
+
+impl DistanceGlow {
+    ///
+    /// Creates a new glow with the given color and radius in pixels.
+    ///
+    pub fn new(context: &Context, color: Srgba, radius: f32) -> Self {
+        Self {
+            context: context.clone(),
+            distance_texture: None,
+            mask_transformation: vec4(0.0, 0.0, 1.0, 1.0),
+            pixels_per_texel: 1.0,
+            color,
+            intensity: 1.0,
+            radius,
+            resolution_scale: 0.5,
+        }
+    }
+
+    ///
+    /// Renders the silhouettes of the given geometries, as seen by the given viewer, into the mask and generates the distance field used for the glow.
+    /// Giving no geometries removes the glow.
+    ///
+    pub fn update(
+        &mut self,
+        viewer: impl Viewer,
+        geometries: impl IntoIterator<Item = impl Geometry>,
+    ) {
+        let viewport = viewer.viewport();
+        let scale = self.resolution_scale.clamp(0.05, 1.0);
+        let width = ((viewport.width as f32 * scale).ceil() as u32).max(1);
+        let height = ((viewport.height as f32 * scale).ceil() as u32).max(1);
+        let margin = (self.radius.max(0.0) * scale).ceil() as u32;
+        let mask_width = width + 2 * margin;
+        let mask_height = height + 2 * margin;
+
+        // Widen the projection so the viewport covers the center of the mask
+        let mut mask_viewer = MatrixViewer::from_viewer(&viewer);
+        mask_viewer.set_projection(
+            Mat4::from_nonuniform_scale(
+                width as f32 / mask_width as f32,
+                height as f32 / mask_height as f32,
+                1.0,
+            ) * viewer.projection(),
+        );
+        mask_viewer.set_viewport(Viewport::new_at_origo(mask_width, mask_height));
+        mask_viewer.tone_mapping = ToneMapping::None;
+        mask_viewer.color_mapping = ColorMapping::None;
+
+        let mut mask =
+            self.context
+                .request_texture::<u8>(mask_width, mask_height, Interpolation::Nearest);
+        // The fallback for a shader which is not compiled yet would be part of the mask, so wait for the compilation
+        with_synchronous_shader_compilation(&self.context, || {
+            mask.as_color_target(None)
+                .clear(ClearState::color(0.0, 0.0, 0.0, 0.0))
+                .render_with_material(
+                    &ColorMaterial {
+                        color: Srgba::WHITE,
+                        ..Default::default()
+                    },
+                    &mask_viewer,
+                    geometries,
+                    &[],
+                );
+        });
+        self.distance_texture = Some(jump_flood_distance(&mask));
+        self.mask_transformation = vec4(
+            margin as f32 / mask_width as f32,
+            margin as f32 / mask_height as f32,
+            width as f32 / mask_width as f32,
+            height as f32 / mask_height as f32,
+        );
+        self.pixels_per_texel = viewport.width as f32 / width as f32;
+    }
+
+    ///
+    /// Returns the distance field generated by the last call to [DistanceGlow::update], see [jump_flood_distance].
+    ///
+    pub fn distance_texture(&self) -> Option<&Texture2D> {
+        self.distance_texture.as_ref()
+    }
+}
+
+impl Material for DistanceGlow {
+    fn id(&self) -> EffectMaterialId {
+        EffectMaterialId::DistanceGlowMaterial
+    }
+
+    fn fragment_shader_source(&self, _lights: &[&dyn Light]) -> String {
+        GLOW_SHADER.to_owned()
+    }
+
+    fn use_uniforms(&self, program: &Program, viewer: &dyn Viewer, _lights: &[&dyn Light]) {
+        viewer.tone_mapping().use_uniforms(program);
+        viewer.color_mapping().use_uniforms(program);
+        if let Some(ref texture) = self.distance_texture {
+            program.use_texture("distanceTexture", texture);
+        }
+        program.use_uniform("maskTransformation", self.mask_transformation);
+        program.use_uniform("pixelsPerTexel", self.pixels_per_texel);
+        program.use_uniform(
+            "glowColor",
+            self.color.to_linear_srgb().truncate() * self.intensity,
+        );
+        program.use_uniform(
+            "radius",
+            if self.distance_texture.is_some() {
+                self.radius
+            } else {
+                0.0
+            },
+        );
+    }
+
+    fn render_states(&self) -> RenderStates {
+        RenderStates {
+            depth_test: DepthTest::Always,
+            write_mask: WriteMask::COLOR,
+            blend: Blend::PREMULTIPLIED,
+            ..Default::default()
+        }
+    }
+
+    fn material_type(&self) -> MaterialType {
+        MaterialType::Transparent
+    }
+}
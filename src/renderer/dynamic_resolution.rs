@@ -0,0 +1,209 @@
+//!
+//! Rendering at a lower resolution than the screen, see [DynamicResolution].
+//!
+
+use crate::renderer::*;
+
+///
+/// Renders the scene into an internal color and depth texture with a resolution which is a fraction of the viewport, the render scale,
+/// and then upscales the result to the render target using [UpscaleEffect]. This trades image quality for performance on weak GPUs.
+/// The render scale can be set manually using [DynamicResolution::set_render_scale] or adjusted automatically once every second
+/// to reach a target frame time, see [DynamicResolution::set_target_frame_time].
+///
+/// Only what is rendered using [DynamicResolution::render] is affected, so a GUI rendered afterwards is still rendered at the native resolution.
+/// The viewer given to [DynamicResolution::render] is not changed, so picking and other screen space coordinates still refer to pixels in the native viewport.
+///
+pub struct DynamicResolution {
+    context: Context,
+    render_scale: f32,
+    target_frame_time: Option<f64>,
+    accumulated_time: f64,
+    frame_count: u32,
+    /// The lowest render scale used when the render scale is adjusted automatically.
+    pub min_render_scale: f32,
+    /// The highest render scale used when the render scale is adjusted automatically.
+    pub max_render_scale: f32,
+}
+
+impl DynamicResolution {
+    ///
+    /// Creates a new dynamic resolution with a render scale of 1, ie. rendering at native resolution, and no automatic adjustment.
+    ///
+    pub fn new(context: &Context) -> Self {
+        Self {
+            context: context.clone(),
+            render_scale: 1.0,
+            target_frame_time: None,
+            accumulated_time: 0.0,
+            frame_count: 0,
+            min_render_scale: 0.5,
+            max_render_scale: 1.0,
+        }
+    }
+
+    ///
+    /// Returns the current render scale, which is either set manually or the latest automatically adjusted value.
+    ///
+    pub fn render_scale(&self) -> f32 {
+        self.render_scale
+    }
+
+    ///
+    /// Sets the render scale, ie. the width and height of the internal textures relative to the viewport, and disables the automatic adjustment.
+    /// The render scale is clamped to the range `[0.1, 1]`.
+    ///
+    pub fn set_render_scale(&mut self, render_scale: f32) {
+        self.render_scale = render_scale.clamp(0.1, 1.0);
+        self.target_frame_time = None;
+    }
+
+    ///
+    /// Returns the target frame time in milliseconds if the render scale is adjusted automatically.
+    ///
+    pub fn target_frame_time(&self) -> Option<f64> {
+        self.target_frame_time
+    }
+
+    ///
+    /// Enables automatic adjustment of the render scale when given a target frame time in milliseconds, for example `1000.0 / 60.0`, or disables it when given `None`.
+    /// Once every second, the render scale is lowered if the average frame time measured using [DynamicResolution::update] is above the target
+    /// and raised if it is well below the target, within the range given by [DynamicResolution::min_render_scale] and [DynamicResolution::max_render_scale].
+    ///
+    pub fn set_target_frame_time(&mut self, target_frame_time: Option<f64>) {
+        self.target_frame_time = target_frame_time;
+        self.accumulated_time = 0.0;
+        self.frame_count = 0;
+    }
+
+    ///
+    /// Measures the frame time, which is the time in milliseconds since the last frame, for example [FrameInput::elapsed_time](crate::FrameInput::elapsed_time),
+    /// and adjusts the render scale if automatic adjustment is enabled. Returns whether the render scale changed. Should be called each frame.
+    ///
+    pub fn update(&mut self, elapsed_time: f64) -> bool {
+        let Some(target_frame_time) = self.target_frame_time else {
+            return false;
+        };
+        self.accumulated_time += elapsed_time;
+        self.frame_count += 1;
+        if self.accumulated_time < 1000.0 {
+            return false;
+        }
+        let frame_time = self.accumulated_time / self.frame_count as f64;
+        self.accumulated_time = 0.0;
+        self.frame_count = 0;
+
+        // The rendering cost is roughly proportional to the number of pixels, so the scale follows the square root of the ratio
+        let ratio = (target_frame_time / frame_time.max(f64::EPSILON)).sqrt() as f32;
+        let render_scale = if frame_time > target_frame_time * 1.05 {
+            self.render_scale * ratio
+        } else if frame_time < target_frame_time * 0.8 {
+            self.render_scale * ratio.min(1.1)
+        } else {
+            self.render_scale
+        };
+        // Round to steps of 5% to avoid reallocating the internal textures because of small changes
+        let render_scale = ((render_scale * 20.0).round() / 20.0).clamp(
+            self.min_render_scale.clamp(0.1, 1.0),
+            self.max_render_scale.clamp(0.1, 1.0),
+        );
+        let changed = render_scale != self.render_scale;
+        self.render_scale = render_scale;
+        changed
+    }
+
+    ///
+    /// Returns the viewport of the internal textures for the given native viewport, ie. the viewport scaled by the render scale.
+    ///
+    pub fn scaled_viewport(&self, viewport: Viewport) -> Viewport {
+        Viewport::new_at_origo(
+            ((viewport.width as f32 * self.render_scale).round() as u32).max(1),
+            ((viewport.height as f32 * self.render_scale).round() as u32).max(1),
+        )
+    }
+
+    ///
+    /// Clears the internal textures using the given clear state, renders the objects with the given viewer and lights into them
+    /// and upscales the result into the viewport of the viewer in the given render target, including the depth.
+    /// The internal textures are requested from the texture pool of the context, see [Context::request_texture].
+    ///
+    pub fn render(
+        &self,
+        target: &RenderTarget,
+        clear_state: ClearState,
+        viewer: impl Viewer,
+        objects: impl IntoIterator<Item = impl Object>,
+        lights: &[&dyn Light],
+    ) {
+        let viewport = self.scaled_viewport(viewer.viewport());
+        let mut color_texture = self.context.request_texture::<[u8; 4]>(
+            viewport.width,
+            viewport.height,
+            Interpolation::Linear,
+        );
+        let mut depth_texture = self
+            .context
+            .request_depth_texture::<f32>(viewport.width, viewport.height);
+        let scaled_viewer = ScaledViewer(&viewer, viewport);
+        RenderTarget::new(
+            color_texture.as_color_target(None),
+            depth_texture.as_depth_target(),
+        )
+        .clear(clear_state.with_depth_mode(viewer.depth_mode()))
+        .render(&scaled_viewer, objects, lights);
+        target.apply_screen_effect_partially(
+            viewer.viewport(),
+            &UpscaleEffect::default(),
+            &viewer,
+            &[],
+            Some(ColorTexture::Single(&color_texture)),
+            Some(DepthTexture::Single(&depth_texture)),
+        );
+    }
+}
+
+///
+/// A viewer which is the same as the given viewer, except for the viewport.
+///
+struct ScaledViewer<T>(T, Viewport);
+
+impl<T: Viewer> Viewer for ScaledViewer<T> {
+    fn position(&self) -> Vec3 {
+        self.0.position()
+    }
+
+    fn view(&self) -> Mat4 {
+        self.0.view()
+    }
+
+    fn projection(&self) -> Mat4 {
+        self.0.projection()
+    }
+
+    fn viewport(&self) -> Viewport {
+        self.1
+    }
+
+    fn z_near(&self) -> f32 {
+        self.0.z_near()
+    }
+
+    fn z_far(&self) -> f32 {
+        self.0.z_far()
+    }
+
+    fn color_mapping(&self) -> ColorMapping {
+        self.0.color_mapping()
+    }
+
+    fn tone_mapping(&self) -> ToneMapping {
+        self.0.tone_mapping()
+    }
+
+    fn depth_mode(&self) -> DepthMode {
+        self.0.depth_mode()
+    }
+
+    fn exposure(&self) -> f32 {
+        self.0.exposure()
+    }
+}
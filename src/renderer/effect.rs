@@ -56,10 +56,34 @@ mod fxaa;
 #[doc(inline)]
 pub use fxaa::*;
 
+mod upscale;
+#[doc(inline)]
+pub use upscale::*;
+
 mod water;
 #[doc(inline)]
 pub use water::*;
 
+mod light_flare;
+#[doc(inline)]
+pub use light_flare::*;
+
+mod ssao;
+#[doc(inline)]
+pub use ssao::*;
+
+mod bloom;
+#[doc(inline)]
+pub use bloom::*;
+
+mod taa;
+#[doc(inline)]
+pub use taa::*;
+
+mod depth_of_field;
+#[doc(inline)]
+pub use depth_of_field::*;
+
 pub(crate) mod lighting_pass;
 
 use crate::renderer::*;
@@ -118,15 +142,15 @@ impl<T: Effect + ?Sized> Effect for &mut T {
     impl_effect_body!(deref);
 }
 
-impl<T: Effect> Effect for Box<T> {
+impl<T: Effect + ?Sized> Effect for Box<T> {
     impl_effect_body!(as_ref);
 }
 
-impl<T: Effect> Effect for std::rc::Rc<T> {
+impl<T: Effect + ?Sized> Effect for std::rc::Rc<T> {
     impl_effect_body!(as_ref);
 }
 
-impl<T: Effect> Effect for std::sync::Arc<T> {
+impl<T: Effect + ?Sized> Effect for std::sync::Arc<T> {
     impl_effect_body!(as_ref);
 }
 
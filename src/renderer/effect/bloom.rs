@@ -0,0 +1,368 @@
+use crate::renderer::*;
+
+/// The maximum number of mip levels in the downsample and upsample chain.
+const MAX_MIP_LEVELS: u32 = 6;
+
+///
+/// The passes of the bloom which computes the mip levels.
+///
+enum BloomPass<'a> {
+    BrightPass { effect: &'a BloomEffect },
+    Downsample,
+    Upsample { radius: f32 },
+}
+
+impl BloomPass<'_> {
+    fn index(&self) -> u16 {
+        match self {
+            Self::BrightPass { .. } => 0,
+            Self::Downsample => 1,
+            Self::Upsample { .. } => 2,
+        }
+    }
+}
+
+impl Effect for BloomPass<'_> {
+    fn fragment_shader_source(
+        &self,
+        _lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        _depth_texture: Option<DepthTexture>,
+    ) -> String {
+        let color_texture = color_texture.unwrap();
+        match self {
+            Self::BrightPass { .. } => format!(
+                "#define BRIGHT_PASS\n{}\n{}",
+                color_texture.fragment_shader_source(),
+                include_str!("shaders/bloom_downsample.frag")
+            ),
+            Self::Downsample => format!(
+                "{}\n{}",
+                color_texture.fragment_shader_source(),
+                include_str!("shaders/bloom_downsample.frag")
+            ),
+            Self::Upsample { .. } => format!(
+                "{}\n{}",
+                color_texture.fragment_shader_source(),
+                include_str!("shaders/bloom_upsample.frag")
+            ),
+        }
+    }
+
+    fn id(
+        &self,
+        color_texture: Option<ColorTexture>,
+        _depth_texture: Option<DepthTexture>,
+    ) -> EffectMaterialId {
+        EffectMaterialId(
+            EffectMaterialId::BloomEffectBase.0 | self.index() << 7 | color_texture.unwrap().id(),
+        )
+    }
+
+    fn use_uniforms(
+        &self,
+        program: &Program,
+        _viewer: &dyn Viewer,
+        _lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        _depth_texture: Option<DepthTexture>,
+    ) {
+        let color_texture = color_texture.unwrap();
+        color_texture.use_uniforms(program);
+        program.use_uniform(
+            "texelSize",
+            vec2(
+                1.0 / color_texture.width() as f32,
+                1.0 / color_texture.height() as f32,
+            ),
+        );
+        match self {
+            Self::BrightPass { effect } => {
+                program.use_uniform("threshold", effect.threshold.max(0.0));
+                program.use_uniform(
+                    "knee",
+                    effect.threshold.max(0.0) * effect.knee.clamp(0.0, 1.0),
+                );
+            }
+            Self::Downsample => {}
+            Self::Upsample { radius } => {
+                program.use_uniform("radius", *radius);
+            }
+        }
+    }
+
+    fn render_states(&self) -> RenderStates {
+        RenderStates {
+            depth_test: DepthTest::Always,
+            write_mask: WriteMask::COLOR,
+            // Add the upsampled level to the level above it
+            blend: if let Self::Upsample { .. } = self {
+                Blend::ADD
+            } else {
+                Blend::Disabled
+            },
+            ..Default::default()
+        }
+    }
+}
+
+///
+/// A bloom effect, which makes bright parts of the image, like emissive materials and highlights from bright lights, glow into their surroundings.
+/// The color above the [BloomEffect::threshold] is extracted and progressively downsampled and blurred into a chain of up to six mip levels,
+/// which are then upsampled with a tent filter and added together, so the glow has both a bright core and a wide falloff.
+///
+/// Call [BloomEffect::update] with the rendered scene in a floating point color texture, for example a [Texture2D] with `[f16; 4]` data,
+/// and then apply this effect with the same color texture, for example using [RenderTarget::apply_screen_effect], which
+/// writes the color texture with the bloom added to the render target. Like [ScreenEffect], the tone and color mapping specified in the [Viewer] is applied,
+/// so render the scene into the color texture without tone and color mapping.
+/// If the [BloomEffect::intensity] is zero, the color texture is too small for a mip level or rendering to floating point textures is not supported,
+/// the color texture is copied without bloom.
+///
+/// The bloom can be modulated by a [BloomEffect::lens_dirt] texture, which makes smudges on the lens light up where the bloom is bright,
+/// and a [BloomEffect::starburst] texture, which adds a diffraction pattern around bright light sources that rotates when the camera turns.
+/// The lens dirt texture covers the entire viewport and the starburst texture is centered on the viewport, and both are cropped rather than stretched
+/// if the aspect ratio of the viewport differs from the texture.
+///
+pub struct BloomEffect {
+    context: Context,
+    mip_levels: Vec<PooledTexture>,
+    /// The brightness above which the color contributes to the bloom, where the brightness is the maximum of the red, green and blue channels in linear color space.
+    pub threshold: f32,
+    /// The width of the soft transition around the [BloomEffect::threshold] relative to the threshold, between 0 and 1, which avoids hard edges in the bloom.
+    pub knee: f32,
+    /// The strength of the bloom that is added to the color. Zero disables the bloom.
+    pub intensity: f32,
+    /// The radius of the tent filter used when upsampling the mip levels in texels of each mip level. Larger values gives a wider and smoother glow.
+    pub radius: f32,
+    /// An optional texture, typically mostly black with bright smudges, which scales the bloom added to each pixel.
+    pub lens_dirt: Option<Texture2DRef>,
+    /// The strength of the [BloomEffect::lens_dirt], where the bloom is scaled by one plus the intensity times the value of the lens dirt texture.
+    pub lens_dirt_intensity: f32,
+    /// An optional starburst or diffraction texture which scales the bloom like the [BloomEffect::lens_dirt] and is rotated with the yaw of the camera.
+    pub starburst: Option<Texture2DRef>,
+    /// The strength of the [BloomEffect::starburst], where the bloom is scaled by one plus the intensity times the value of the starburst texture.
+    pub starburst_intensity: f32,
+}
+
+impl BloomEffect {
+    ///
+    /// Creates a new bloom effect with default parameters.
+    ///
+    pub fn new(context: &Context) -> Self {
+        Self {
+            context: context.clone(),
+            mip_levels: Vec::new(),
+            threshold: 1.0,
+            knee: 0.5,
+            intensity: 1.0,
+            radius: 1.0,
+            lens_dirt: None,
+            lens_dirt_intensity: 1.0,
+            starburst: None,
+            starburst_intensity: 1.0,
+        }
+    }
+
+    ///
+    /// Computes the bloom from the given color texture, which must contain the rendered scene with floating point colors.
+    /// Call this each frame before applying the effect to the same color texture.
+    ///
+    pub fn update(&mut self, color_texture: ColorTexture) {
+        // Return the previous levels to the pool before requesting new ones
+        self.mip_levels.clear();
+        if self.intensity <= 0.0 {
+            return;
+        }
+        // The mip levels do not need an alpha channel, so use the cheapest format that can be rendered to
+        let Some(format) = preferred_hdr_format(&self.context) else {
+            return;
+        };
+        let (width, height) = (color_texture.width(), color_texture.height());
+        let level_count = width.min(height).max(1).ilog2().min(MAX_MIP_LEVELS);
+        for level in 1..=level_count {
+            let (level_width, level_height) = ((width >> level).max(1), (height >> level).max(1));
+            let mut texture = self.context.request_hdr_texture(
+                level_width,
+                level_height,
+                format,
+                Interpolation::Linear,
+            );
+            let camera = Camera::new_2d(Viewport::new_at_origo(level_width, level_height));
+            let target = texture.as_color_target(None);
+            if let Some(previous) = self.mip_levels.last() {
+                target.apply_screen_effect(
+                    &BloomPass::Downsample,
+                    &camera,
+                    &[],
+                    Some(ColorTexture::Single(previous)),
+                    None,
+                );
+            } else {
+                target.apply_screen_effect(
+                    &BloomPass::BrightPass { effect: self },
+                    &camera,
+                    &[],
+                    Some(color_texture),
+                    None,
+                );
+            }
+            self.mip_levels.push(texture);
+        }
+        for level in (1..self.mip_levels.len()).rev() {
+            let (larger, smaller) = self.mip_levels.split_at_mut(level);
+            let target = &mut larger[level - 1];
+            let camera = Camera::new_2d(Viewport::new_at_origo(target.width(), target.height()));
+            target.as_color_target(None).apply_screen_effect(
+                &BloomPass::Upsample {
+                    radius: self.radius,
+                },
+                &camera,
+                &[],
+                Some(ColorTexture::Single(&smaller[0])),
+                None,
+            );
+        }
+    }
+
+    fn use_bloom(&self) -> bool {
+        self.intensity > 0.0 && !self.mip_levels.is_empty()
+    }
+
+    fn use_lens_dirt(&self) -> bool {
+        self.use_bloom() && self.lens_dirt.is_some() && self.lens_dirt_intensity > 0.0
+    }
+
+    fn use_starburst(&self) -> bool {
+        self.use_bloom() && self.starburst.is_some() && self.starburst_intensity > 0.0
+    }
+}
+
+impl Effect for BloomEffect {
+    fn fragment_shader_source(
+        &self,
+        _lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        _depth_texture: Option<DepthTexture>,
+    ) -> String {
+        let color_texture =
+            color_texture.expect("Must supply a color texture to apply a bloom effect");
+        format!(
+            "{}{}{}{}\n{}",
+            if self.use_bloom() {
+                "#define USE_BLOOM\n"
+            } else {
+                ""
+            },
+            if self.use_lens_dirt() {
+                "#define USE_LENS_DIRT\n"
+            } else {
+                ""
+            },
+            if self.use_starburst() {
+                "#define USE_STARBURST\n"
+            } else {
+                ""
+            },
+            color_texture.fragment_shader_source(),
+            include_str!("shaders/bloom_effect.frag")
+        )
+    }
+
+    fn id(
+        &self,
+        color_texture: Option<ColorTexture>,
+        _depth_texture: Option<DepthTexture>,
+    ) -> EffectMaterialId {
+        let color_texture =
+            color_texture.expect("Must supply a color texture to apply a bloom effect");
+        let index = if self.use_bloom() {
+            4 + self.use_lens_dirt() as u16 + 2 * self.use_starburst() as u16
+        } else {
+            3
+        };
+        EffectMaterialId(EffectMaterialId::BloomEffectBase.0 | index << 7 | color_texture.id())
+    }
+
+    fn use_uniforms(
+        &self,
+        program: &Program,
+        viewer: &dyn Viewer,
+        _lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        _depth_texture: Option<DepthTexture>,
+    ) {
+        let color_texture =
+            color_texture.expect("Must supply a color texture to apply a bloom effect");
+        viewer.tone_mapping().use_uniforms(program);
+        viewer.color_mapping().use_uniforms(program);
+        color_texture.use_uniforms(program);
+        if self.use_bloom() {
+            let bloom_texture = &self.mip_levels[0];
+            program.use_texture("bloomTexture", bloom_texture);
+            program.use_uniform(
+                "bloomTexelSize",
+                vec2(
+                    1.0 / bloom_texture.width() as f32,
+                    1.0 / bloom_texture.height() as f32,
+                ),
+            );
+            program.use_uniform("radius", self.radius);
+            // The mip levels are added together, so the average is used to make the intensity independent of the number of levels
+            program.use_uniform("intensity", self.intensity / self.mip_levels.len() as f32);
+
+            let aspect = color_texture.width() as f32 / color_texture.height() as f32;
+            if self.use_lens_dirt() {
+                let lens_dirt = self.lens_dirt.as_ref().unwrap();
+                program.use_texture("lensDirtTexture", lens_dirt);
+                program.use_uniform(
+                    "lensDirtScale",
+                    cover_scale(aspect, lens_dirt.width(), lens_dirt.height()),
+                );
+                program.use_uniform("lensDirtIntensity", self.lens_dirt_intensity);
+            }
+            if self.use_starburst() {
+                let starburst = self.starburst.as_ref().unwrap();
+                // The yaw is the angle of the view direction around the world up direction
+                let view = viewer.view();
+                let direction = -vec3(view.x.z, view.y.z, view.z.z);
+                let yaw = direction.x.atan2(direction.z);
+                program.use_texture("starburstTexture", starburst);
+                program.use_uniform("starburstRotation", Mat2::from_angle(radians(yaw)));
+                // The texture is scaled to cover a circle around the viewport, so no edges are visible when it rotates
+                let diameter = (aspect * aspect + 1.0).sqrt();
+                let texture_aspect = starburst.width() as f32 / starburst.height() as f32;
+                program.use_uniform(
+                    "starburstScale",
+                    vec2(
+                        1.0 / (diameter * texture_aspect.max(1.0)),
+                        1.0 / (diameter / texture_aspect.min(1.0)),
+                    ),
+                );
+                program.use_uniform("aspect", aspect);
+                program.use_uniform("starburstIntensity", self.starburst_intensity);
+            }
+        }
+    }
+
+    fn render_states(&self) -> RenderStates {
+        RenderStates {
+            depth_test: DepthTest::Always,
+            write_mask: WriteMask::COLOR,
+            cull: Cull::Back,
+            ..Default::default()
+        }
+    }
+}
+
+///
+/// Returns the scale of the uv coordinates of the viewport with the given aspect ratio which makes a texture with the given size cover the viewport
+/// without being stretched.
+///
+fn cover_scale(aspect: f32, width: u32, height: u32) -> Vec2 {
+    let texture_aspect = width as f32 / height as f32;
+    if aspect > texture_aspect {
+        vec2(1.0, texture_aspect / aspect)
+    } else {
+        vec2(aspect / texture_aspect, 1.0)
+    }
+}
@@ -0,0 +1,373 @@
+use crate::renderer::*;
+
+/// The number of samples in the disc blur of each layer.
+const BLUR_SAMPLE_COUNT: u32 = 48;
+
+///
+/// The passes of the depth of field which computes the blurred near and far field layers and finds the focus distance.
+///
+enum DepthOfFieldPass<'a> {
+    Downsample {
+        effect: &'a DepthOfFieldEffect,
+        viewer: &'a dyn Viewer,
+        near_field: bool,
+    },
+    Blur {
+        max_radius: f32,
+        near_field: bool,
+    },
+    Focus {
+        effect: &'a DepthOfFieldEffect,
+        viewer: &'a dyn Viewer,
+        uv: Vec2,
+    },
+}
+
+impl DepthOfFieldPass<'_> {
+    fn index(&self) -> u16 {
+        match self {
+            Self::Downsample { near_field, .. } => *near_field as u16,
+            Self::Blur { near_field, .. } => 2 + *near_field as u16,
+            Self::Focus { .. } => 4,
+        }
+    }
+
+    fn near_field(&self) -> bool {
+        match self {
+            Self::Downsample { near_field, .. } | Self::Blur { near_field, .. } => *near_field,
+            Self::Focus { .. } => false,
+        }
+    }
+}
+
+impl Effect for DepthOfFieldPass<'_> {
+    fn fragment_shader_source(
+        &self,
+        _lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) -> String {
+        let defines = if self.near_field() {
+            "#define NEAR_FIELD\n"
+        } else {
+            ""
+        };
+        match self {
+            Self::Downsample { .. } => format!(
+                "{}{}\n{}\n{}\n{}",
+                defines,
+                color_texture.unwrap().fragment_shader_source(),
+                depth_texture.unwrap().fragment_shader_source(),
+                include_str!("shaders/depth_of_field_shared.frag"),
+                include_str!("shaders/depth_of_field_downsample.frag")
+            ),
+            Self::Blur { .. } => format!(
+                "{}#define SAMPLE_COUNT {}\n{}\n{}",
+                defines,
+                BLUR_SAMPLE_COUNT,
+                color_texture.unwrap().fragment_shader_source(),
+                include_str!("shaders/depth_of_field_blur.frag")
+            ),
+            Self::Focus { .. } => format!(
+                "{}\n{}\n{}",
+                depth_texture.unwrap().fragment_shader_source(),
+                include_str!("shaders/depth_of_field_shared.frag"),
+                include_str!("shaders/depth_of_field_focus.frag")
+            ),
+        }
+    }
+
+    fn id(
+        &self,
+        color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) -> EffectMaterialId {
+        EffectMaterialId(
+            EffectMaterialId::DepthOfFieldEffect(color_texture, depth_texture).0
+                | self.index() << 7,
+        )
+    }
+
+    fn use_uniforms(
+        &self,
+        program: &Program,
+        _viewer: &dyn Viewer,
+        _lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) {
+        if let Some(color_texture) = color_texture {
+            color_texture.use_uniforms(program);
+            program.use_uniform(
+                "texelSize",
+                vec2(
+                    1.0 / color_texture.width() as f32,
+                    1.0 / color_texture.height() as f32,
+                ),
+            );
+        }
+        if let Some(depth_texture) = depth_texture {
+            depth_texture.use_uniforms(program);
+        }
+        match self {
+            Self::Downsample { effect, viewer, .. } => effect.use_coc_uniforms(program, *viewer),
+            Self::Blur { max_radius, .. } => program.use_uniform("maxRadius", *max_radius),
+            Self::Focus { effect, viewer, uv } => {
+                effect.use_coc_uniforms(program, *viewer);
+                program.use_uniform("focusUv", *uv);
+            }
+        }
+    }
+
+    fn render_states(&self) -> RenderStates {
+        RenderStates {
+            depth_test: DepthTest::Always,
+            write_mask: WriteMask::COLOR,
+            ..Default::default()
+        }
+    }
+}
+
+///
+/// A depth of field effect, which blurs the parts of the scene in front of and behind the [DepthOfFieldEffect::focus_distance] like a camera lens with the given [DepthOfFieldEffect::aperture].
+/// The radius of the blur, the circle of confusion, is computed in each pixel from the depth texture and the scene is split into a near field in front of the focus distance and a far field behind it.
+/// Each field is blurred at half resolution with a disc blur where each pixel gathers the samples whose circle of confusion reaches the pixel,
+/// so the blurred foreground covers the in-focus parts behind it, whereas the blurred background does not blur over the in-focus parts in front of it.
+/// Where the depth is at the far plane, for example the skybox, the surface is treated as infinitely far away.
+///
+/// Call [DepthOfFieldEffect::update] with the rendered scene in a floating point color texture, for example a [Texture2D] with `[f16; 4]` data, and the depth texture,
+/// and then apply this effect with the same color and depth texture and the same viewer, for example using [RenderTarget::apply_screen_effect], which
+/// writes the color texture with the depth of field to the render target. Like [ScreenEffect], the tone and color mapping specified in the [Viewer] is applied,
+/// so render the scene into the color texture without tone and color mapping.
+/// The effect requires support for rendering to floating point textures, see [preferred_hdr_format_with_alpha].
+///
+pub struct DepthOfFieldEffect {
+    context: Context,
+    near_field: Option<PooledTexture>,
+    far_field: Option<PooledTexture>,
+    focus_point: Option<Vec2>,
+    /// The distance along the view direction from the viewer to the plane which is in focus.
+    pub focus_distance: f32,
+    /// The diameter of the lens aperture in world units. A larger aperture gives a more shallow depth of field, ie. more blur in front of and behind the focus distance.
+    pub aperture: f32,
+    /// The maximum radius in pixels of the blur.
+    pub max_blur_radius: f32,
+}
+
+impl DepthOfFieldEffect {
+    ///
+    /// Creates a new depth of field effect which is focused at the given distance.
+    ///
+    pub fn new(context: &Context, focus_distance: f32) -> Self {
+        Self {
+            context: context.clone(),
+            near_field: None,
+            far_field: None,
+            focus_point: None,
+            focus_distance,
+            aperture: 0.1,
+            max_blur_radius: 16.0,
+        }
+    }
+
+    ///
+    /// Focuses on the surface visible in the given pixel, ie. the [DepthOfFieldEffect::focus_distance] is set to the distance to that surface.
+    /// The pixel coordinate must be in physical pixels of the viewport of the given viewer.
+    /// The depth in the pixel is read from the depth texture given in the next call to [DepthOfFieldEffect::update].
+    /// If the pixel is at the far plane, for example the skybox, the focus distance is set to the far plane of the viewer.
+    ///
+    pub fn set_focus_point(&mut self, viewer: impl Viewer, pixel: impl Into<PhysicalPoint>) {
+        let pixel = pixel.into();
+        let viewport = viewer.viewport();
+        // The center of the pixel in uv coordinates
+        self.focus_point = Some(vec2(
+            (((pixel.x - viewport.x as f32).floor() + 0.5) / viewport.width as f32).clamp(0.0, 1.0),
+            (((pixel.y - viewport.y as f32).floor() + 0.5) / viewport.height as f32)
+                .clamp(0.0, 1.0),
+        ));
+    }
+
+    ///
+    /// Computes the blurred near and far field from the given color and depth texture, which must contain the scene rendered with the given viewer.
+    /// Call this each frame before applying the effect to the same color and depth texture.
+    ///
+    pub fn update(
+        &mut self,
+        viewer: impl Viewer,
+        color_texture: ColorTexture,
+        depth_texture: DepthTexture,
+    ) {
+        // The near and far field store the radius of the blur in the alpha channel
+        let format = preferred_hdr_format_with_alpha(&self.context).expect(
+            "The depth of field effect requires support for rendering to floating point textures",
+        );
+        if let Some(uv) = self.focus_point.take() {
+            // The distance is read back, so use 32 bit floats if possible
+            let focus_format = if self.context.supports_hdr_format(HdrFormat::Rgba32F) {
+                HdrFormat::Rgba32F
+            } else {
+                format
+            };
+            let mut texture =
+                self.context
+                    .request_hdr_texture(1, 1, focus_format, Interpolation::Nearest);
+            let camera = Camera::new_2d(Viewport::new_at_origo(1, 1));
+            let distance = texture
+                .as_color_target(None)
+                .apply_screen_effect(
+                    &DepthOfFieldPass::Focus {
+                        effect: self,
+                        viewer: &viewer,
+                        uv,
+                    },
+                    &camera,
+                    &[],
+                    None,
+                    Some(depth_texture),
+                )
+                .read::<[f32; 4]>()[0][0];
+            self.focus_distance = if distance < 0.0 {
+                viewer.z_far()
+            } else {
+                distance
+            };
+        }
+
+        // Return the previous layers to the pool before requesting new ones
+        self.near_field = None;
+        self.far_field = None;
+        let (width, height) = (
+            (color_texture.width() / 2).max(1),
+            (color_texture.height() / 2).max(1),
+        );
+        let camera = Camera::new_2d(Viewport::new_at_origo(width, height));
+        let layer = |near_field: bool| {
+            let mut downsampled =
+                self.context
+                    .request_hdr_texture(width, height, format, Interpolation::Linear);
+            downsampled.as_color_target(None).apply_screen_effect(
+                &DepthOfFieldPass::Downsample {
+                    effect: self,
+                    viewer: &viewer,
+                    near_field,
+                },
+                &camera,
+                &[],
+                Some(color_texture),
+                Some(depth_texture),
+            );
+            let mut blurred =
+                self.context
+                    .request_hdr_texture(width, height, format, Interpolation::Linear);
+            blurred.as_color_target(None).apply_screen_effect(
+                &DepthOfFieldPass::Blur {
+                    // The layers are half the resolution, so the radius is half the number of pixels
+                    max_radius: 0.5 * self.max_blur_radius.max(0.0),
+                    near_field,
+                },
+                &camera,
+                &[],
+                Some(ColorTexture::Single(&downsampled)),
+                None,
+            );
+            blurred
+        };
+        let near_field = layer(true);
+        let far_field = layer(false);
+        self.near_field = Some(near_field);
+        self.far_field = Some(far_field);
+    }
+
+    fn use_coc_uniforms(&self, program: &Program, viewer: &dyn Viewer) {
+        let focus_distance = self.focus_distance.max(0.0001);
+        // The number of pixels per world unit at the focus distance
+        let projection = viewer.projection();
+        let mut coc_scale = 0.5 * viewer.viewport().height as f32 * projection.y.y;
+        if projection.w.w == 0.0 {
+            coc_scale /= focus_distance;
+        }
+        program.use_uniform_if_required("viewProjectionInverse", view_projection_inverse(viewer));
+        program.use_uniform_if_required("eyePosition", viewer.position());
+        let view = viewer.view();
+        program.use_uniform_if_required("viewDirection", -vec3(view.x.z, view.y.z, view.z.z));
+        program.use_uniform_if_required("farDepth", viewer.depth_mode().far_depth());
+        program.use_uniform_if_required("focusDistance", focus_distance);
+        program.use_uniform_if_required("aperture", self.aperture.max(0.0));
+        program.use_uniform_if_required("cocScale", coc_scale);
+        program.use_uniform_if_required("maxBlurRadius", self.max_blur_radius.max(0.0));
+    }
+}
+
+impl Effect for DepthOfFieldEffect {
+    fn fragment_shader_source(
+        &self,
+        _lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) -> String {
+        format!(
+            "{}\n{}\n{}\n{}",
+            color_texture
+                .expect("Must supply a color texture to apply a depth of field effect")
+                .fragment_shader_source(),
+            depth_texture
+                .expect("Must supply a depth texture to apply a depth of field effect")
+                .fragment_shader_source(),
+            include_str!("shaders/depth_of_field_shared.frag"),
+            include_str!("shaders/depth_of_field_effect.frag")
+        )
+    }
+
+    fn id(
+        &self,
+        color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) -> EffectMaterialId {
+        EffectMaterialId(
+            EffectMaterialId::DepthOfFieldEffect(
+                Some(
+                    color_texture
+                        .expect("Must supply a color texture to apply a depth of field effect"),
+                ),
+                Some(
+                    depth_texture
+                        .expect("Must supply a depth texture to apply a depth of field effect"),
+                ),
+            )
+            .0 | 5 << 7,
+        )
+    }
+
+    fn use_uniforms(
+        &self,
+        program: &Program,
+        viewer: &dyn Viewer,
+        _lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) {
+        viewer.tone_mapping().use_uniforms(program);
+        viewer.color_mapping().use_uniforms(program);
+        color_texture
+            .expect("Must supply a color texture to apply a depth of field effect")
+            .use_uniforms(program);
+        depth_texture
+            .expect("Must supply a depth texture to apply a depth of field effect")
+            .use_uniforms(program);
+        self.use_coc_uniforms(program, viewer);
+        let (Some(near_field), Some(far_field)) = (&self.near_field, &self.far_field) else {
+            panic!("Must call DepthOfFieldEffect::update before applying a depth of field effect");
+        };
+        program.use_texture("nearTexture", near_field);
+        program.use_texture("farTexture", far_field);
+    }
+
+    fn render_states(&self) -> RenderStates {
+        RenderStates {
+            depth_test: DepthTest::Always,
+            write_mask: WriteMask::COLOR,
+            cull: Cull::Back,
+            ..Default::default()
+        }
+    }
+}
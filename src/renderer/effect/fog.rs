@@ -34,16 +34,13 @@ impl Effect for FogEffect {
         depth_texture: Option<DepthTexture>,
     ) -> String {
         format!(
-            "{}\n{}\n{}\n{}\n{}\n{}",
-            include_str!("../../core/shared.frag"),
+            "{}\n{}\n{}",
             color_texture
                 .expect("Must supply a depth texture to apply a fog effect")
                 .fragment_shader_source(),
             depth_texture
                 .expect("Must supply a depth texture to apply a fog effect")
                 .fragment_shader_source(),
-            ToneMapping::fragment_shader_source(),
-            ColorMapping::fragment_shader_source(),
             include_str!("shaders/fog_effect.frag")
         )
     }
@@ -75,15 +72,13 @@ impl Effect for FogEffect {
         depth_texture
             .expect("Must supply a depth texture to apply a fog effect")
             .use_uniforms(program);
-        program.use_uniform(
-            "viewProjectionInverse",
-            (viewer.projection() * viewer.view()).invert().unwrap(),
-        );
+        program.use_uniform("viewProjectionInverse", view_projection_inverse(viewer));
         program.use_uniform("fogColor", Vec4::from(self.color));
         program.use_uniform("fogDensity", self.density);
         program.use_uniform("animation", self.animation);
         program.use_uniform("time", 0.001 * self.time);
         program.use_uniform("eyePosition", viewer.position());
+        program.use_uniform("farDepth", viewer.depth_mode().far_depth());
     }
 
     fn render_states(&self) -> RenderStates {
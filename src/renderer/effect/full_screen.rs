@@ -20,7 +20,9 @@ impl Effect for ScreenEffect {
         depth_texture: Option<DepthTexture>,
     ) -> String {
         format!(
-            "{}{}{}{}
+            "{}{}
+            #include <{}>
+            #include <{}>
 
             in vec2 uvs;
             layout (location = 0) out vec4 outColor;
@@ -38,8 +40,8 @@ impl Effect for ScreenEffect {
             depth_texture
                 .map(|t| t.fragment_shader_source())
                 .unwrap_or("".to_string()),
-            ToneMapping::fragment_shader_source(),
-            ColorMapping::fragment_shader_source(),
+            TONE_MAPPING_SNIPPET,
+            COLOR_MAPPING_SNIPPET,
             color_texture
                 .map(|_| "
                     outColor = sample_color(uvs);
@@ -85,6 +87,7 @@ impl Effect for ScreenEffect {
             cull: Cull::Back,
             write_mask: self.write_mask,
             blend: self.blend,
+            ..Default::default()
         }
     }
 }
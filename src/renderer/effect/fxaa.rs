@@ -1,11 +1,56 @@
 use crate::renderer::*;
 
 ///
-/// A simple anti-aliasing approach which smooths otherwise jagged edges (for example lines) but also
-/// smooths the rest of the image.
+/// The quality preset of a [FxaaEffect], which trades the number of texture samples for a better detection of long edges and less blurring of details.
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FxaaQuality {
+    /// Searches 3 steps along each edge and only smooths edges with a high contrast (FXAA 3.11 quality preset 10).
+    Low,
+    /// Searches 5 steps along each edge (FXAA 3.11 quality preset 12).
+    #[default]
+    Medium,
+    /// Searches 12 steps along each edge and also smooths edges with a low contrast (FXAA 3.11 quality preset 39).
+    High,
+}
+
+impl FxaaQuality {
+    fn defines(&self) -> String {
+        let (step_sizes, edge_threshold, edge_threshold_min): (&[f32], f32, f32) = match self {
+            Self::Low => (&[1.5, 3.0, 12.0], 0.25, 0.0833),
+            Self::Medium => (&[1.0, 1.5, 2.0, 4.0, 12.0], 0.166, 0.0625),
+            Self::High => (
+                &[1.0, 1.0, 1.0, 1.0, 1.0, 1.5, 2.0, 2.0, 2.0, 2.0, 4.0, 8.0],
+                0.125,
+                0.0312,
+            ),
+        };
+        format!(
+            "#define SEARCH_STEPS {}\nconst float SEARCH_STEP_SIZES[{}] = float[]({});\n#define EDGE_THRESHOLD {:?}\n#define EDGE_THRESHOLD_MIN {:?}\n",
+            step_sizes.len(),
+            step_sizes.len(),
+            step_sizes
+                .iter()
+                .map(|s| format!("{:?}", s))
+                .collect::<Vec<_>>()
+                .join(", "),
+            edge_threshold,
+            edge_threshold_min
+        )
+    }
+}
+
+///
+/// An anti-aliasing effect which smooths jagged edges, for example when rendering into a texture without multisampling,
+/// using the quality version of FXAA 3.11 which detects edges from the contrast in luma and searches along each edge to find the blending amount.
+/// Apply it to the color texture after tone and color mapping for the best result, since the edges are found from the perceived brightness.
+/// It also slightly smooths details in the rest of the image, which can be reduced by choosing a higher [FxaaEffect::quality].
 ///
 #[derive(Clone, Default, Debug)]
-pub struct FxaaEffect {}
+pub struct FxaaEffect {
+    /// The quality preset.
+    pub quality: FxaaQuality,
+}
 
 impl Effect for FxaaEffect {
     fn fragment_shader_source(
@@ -17,8 +62,9 @@ impl Effect for FxaaEffect {
         let color_texture =
             color_texture.expect("Must supply a color texture to apply a fxaa effect");
         format!(
-            "{}\n{}",
+            "{}\n{}{}",
             color_texture.fragment_shader_source(),
+            self.quality.defines(),
             include_str!("shaders/fxaa_effect.frag")
         )
     }
@@ -28,8 +74,11 @@ impl Effect for FxaaEffect {
         color_texture: Option<ColorTexture>,
         _depth_texture: Option<DepthTexture>,
     ) -> EffectMaterialId {
-        EffectMaterialId::FxaaEffect(
-            color_texture.expect("Must supply a color texture to apply a fxaa effect"),
+        EffectMaterialId(
+            EffectMaterialId::FxaaEffect(
+                color_texture.expect("Must supply a color texture to apply a fxaa effect"),
+            )
+            .0 | (self.quality as u16) << 8,
         )
     }
 
@@ -0,0 +1,96 @@
+use crate::renderer::*;
+
+///
+/// An effect used for rendering [LightFlare]s which fades the flares smoothly when the light source is hidden behind geometry.
+/// The occlusion is found by comparing the depth of the light source with a grid of samples of the given depth texture around the center of the flare,
+/// so the depth texture should contain the depth of the scene, but not any geometry placed at the light source itself.
+///
+/// ```no_run
+/// # use three_d::*;
+/// # let context: Context = unimplemented!();
+/// # let camera: Camera = unimplemented!();
+/// # let flare: LightFlare = unimplemented!();
+/// # let depth_texture: DepthTexture2D = unimplemented!();
+/// flare.render_with_effect(
+///     &LightFlareEffect::default(),
+///     &camera,
+///     &[],
+///     None,
+///     Some(DepthTexture::Single(&depth_texture)),
+/// );
+/// ```
+///
+#[derive(Clone, Debug)]
+pub struct LightFlareEffect {
+    /// The radius in pixels of the area around the center of the flare which is sampled to find the occlusion.
+    /// A larger radius gives a smoother fade when the light source moves behind geometry.
+    pub occlusion_radius: f32,
+}
+
+impl Default for LightFlareEffect {
+    fn default() -> Self {
+        Self {
+            occlusion_radius: 8.0,
+        }
+    }
+}
+
+impl Effect for LightFlareEffect {
+    fn fragment_shader_source(
+        &self,
+        _lights: &[&dyn Light],
+        _color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) -> String {
+        format!(
+            "#define USE_OCCLUSION\n{}\n{}",
+            depth_texture
+                .expect("Must supply a depth texture to apply a light flare effect")
+                .fragment_shader_source(),
+            include_str!("../material/shaders/light_flare_material.frag")
+        )
+    }
+
+    fn id(
+        &self,
+        _color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) -> EffectMaterialId {
+        EffectMaterialId::LightFlareEffect(
+            depth_texture.expect("Must supply a depth texture to apply a light flare effect"),
+        )
+    }
+
+    fn use_uniforms(
+        &self,
+        program: &Program,
+        viewer: &dyn Viewer,
+        _lights: &[&dyn Light],
+        _color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) {
+        viewer.tone_mapping().use_uniforms(program);
+        viewer.color_mapping().use_uniforms(program);
+        depth_texture
+            .expect("Must supply a depth texture to apply a light flare effect")
+            .use_uniforms(program);
+        let viewport = viewer.viewport();
+        program.use_uniform(
+            "occlusionRadius",
+            vec2(
+                self.occlusion_radius / viewport.width.max(1) as f32,
+                self.occlusion_radius / viewport.height.max(1) as f32,
+            ),
+        );
+    }
+
+    fn render_states(&self) -> RenderStates {
+        RenderStates {
+            write_mask: WriteMask::COLOR,
+            depth_test: DepthTest::Always,
+            blend: Blend::ADD,
+            cull: Cull::None,
+            ..Default::default()
+        }
+    }
+}
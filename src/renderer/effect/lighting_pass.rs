@@ -12,8 +12,6 @@ impl Effect for LightingPassEffect {
         let mut fragment_shader = lights_shader_source(lights);
         fragment_shader.push_str(&color_texture.unwrap().fragment_shader_source());
         fragment_shader.push_str(&depth_texture.unwrap().fragment_shader_source());
-        fragment_shader.push_str(ToneMapping::fragment_shader_source());
-        fragment_shader.push_str(ColorMapping::fragment_shader_source());
         fragment_shader.push_str(include_str!("shaders/deferred_lighting.frag"));
         fragment_shader
     }
@@ -49,10 +47,9 @@ impl Effect for LightingPassEffect {
         for (i, light) in lights.iter().enumerate() {
             light.use_uniforms(program, i as u32);
         }
-        program.use_uniform_if_required(
-            "viewProjectionInverse",
-            (viewer.projection() * viewer.view()).invert().unwrap(),
-        );
+        program.use_uniform_if_required("logExposure", viewer.exposure().log2());
+        program.use_uniform_if_required("viewProjectionInverse", view_projection_inverse(viewer));
+        program.use_uniform("farDepth", viewer.depth_mode().far_depth());
         program.use_uniform("debug_type", DebugType::None as i32);
     }
 
@@ -0,0 +1,50 @@
+uniform vec2 texelSize;
+uniform float threshold;
+uniform float knee;
+
+in vec2 uvs;
+
+layout (location = 0) out vec4 outColor;
+
+// Downsampling with 13 bilinear samples, which avoids the pulsating and aliasing of a simple box filter, see "Next generation post processing in Call of Duty: Advanced Warfare" by Jimenez
+vec3 downsample(vec2 uv)
+{
+    vec3 a = sample_color(uv + texelSize * vec2(-2.0, 2.0)).rgb;
+    vec3 b = sample_color(uv + texelSize * vec2(0.0, 2.0)).rgb;
+    vec3 c = sample_color(uv + texelSize * vec2(2.0, 2.0)).rgb;
+    vec3 d = sample_color(uv + texelSize * vec2(-2.0, 0.0)).rgb;
+    vec3 e = sample_color(uv).rgb;
+    vec3 f = sample_color(uv + texelSize * vec2(2.0, 0.0)).rgb;
+    vec3 g = sample_color(uv + texelSize * vec2(-2.0, -2.0)).rgb;
+    vec3 h = sample_color(uv + texelSize * vec2(0.0, -2.0)).rgb;
+    vec3 i = sample_color(uv + texelSize * vec2(2.0, -2.0)).rgb;
+    vec3 j = sample_color(uv + texelSize * vec2(-1.0, 1.0)).rgb;
+    vec3 k = sample_color(uv + texelSize * vec2(1.0, 1.0)).rgb;
+    vec3 l = sample_color(uv + texelSize * vec2(-1.0, -1.0)).rgb;
+    vec3 m = sample_color(uv + texelSize * vec2(1.0, -1.0)).rgb;
+    return 0.125 * (e + j + k + l + m) + 0.03125 * (a + c + g + i) + 0.0625 * (b + d + f + h);
+}
+
+// Removes the color below the threshold with a quadratic transition of the given width around the threshold
+vec3 bright_pass(vec3 color)
+{
+    float brightness = max(color.r, max(color.g, color.b));
+    float soft = clamp(brightness - threshold + knee, 0.0, 2.0 * knee);
+    soft = soft * soft / (4.0 * knee + 0.00001);
+    float contribution = max(soft, brightness - threshold) / max(brightness, 0.00001);
+    return color * contribution;
+}
+
+void main()
+{
+    vec3 color = downsample(uvs);
+#ifdef BRIGHT_PASS
+    // Avoid that infinite or invalid values in the input spread over the entire image
+    color = clamp(color, vec3(0.0), vec3(60000.0));
+    if (any(isnan(color))) {
+        color = vec3(0.0);
+    }
+    color = bright_pass(color);
+#endif
+    outColor = vec4(color, 1.0);
+}
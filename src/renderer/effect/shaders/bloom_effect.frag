@@ -0,0 +1,52 @@
+#include <three-d/tone_mapping>
+#include <three-d/color_mapping>
+
+uniform sampler2D bloomTexture;
+uniform vec2 bloomTexelSize;
+uniform float radius;
+uniform float intensity;
+
+#ifdef USE_LENS_DIRT
+uniform sampler2D lensDirtTexture;
+uniform vec2 lensDirtScale;
+uniform float lensDirtIntensity;
+#endif
+
+#ifdef USE_STARBURST
+uniform sampler2D starburstTexture;
+uniform mat2 starburstRotation;
+uniform vec2 starburstScale;
+uniform float aspect;
+uniform float starburstIntensity;
+#endif
+
+in vec2 uvs;
+
+layout (location = 0) out vec4 outColor;
+
+void main()
+{
+    outColor = sample_color(uvs);
+#ifdef USE_BLOOM
+    // The same tent filter as when upsampling the mip levels
+    vec2 d = bloomTexelSize * radius;
+    vec3 bloom = 4.0 * texture(bloomTexture, uvs).rgb;
+    bloom += 2.0 * (texture(bloomTexture, uvs + vec2(d.x, 0.0)).rgb + texture(bloomTexture, uvs - vec2(d.x, 0.0)).rgb
+        + texture(bloomTexture, uvs + vec2(0.0, d.y)).rgb + texture(bloomTexture, uvs - vec2(0.0, d.y)).rgb);
+    bloom += texture(bloomTexture, uvs + d).rgb + texture(bloomTexture, uvs - d).rgb
+        + texture(bloomTexture, uvs + vec2(d.x, -d.y)).rgb + texture(bloomTexture, uvs + vec2(-d.x, d.y)).rgb;
+    bloom *= intensity / 16.0;
+    vec3 modulation = vec3(1.0);
+#ifdef USE_LENS_DIRT
+    modulation += lensDirtIntensity * texture(lensDirtTexture, (uvs - 0.5) * lensDirtScale + 0.5).rgb;
+#endif
+#ifdef USE_STARBURST
+    // Rotate around the center of the viewport in coordinates where the height of the viewport is one
+    vec2 p = starburstRotation * ((uvs - 0.5) * vec2(aspect, 1.0));
+    modulation += starburstIntensity * texture(starburstTexture, p * starburstScale + 0.5).rgb;
+#endif
+    outColor.rgb += modulation * bloom;
+#endif
+    outColor.rgb = tone_mapping(outColor.rgb);
+    outColor.rgb = color_mapping(outColor.rgb);
+}
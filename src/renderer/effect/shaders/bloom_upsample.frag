@@ -0,0 +1,23 @@
+uniform vec2 texelSize;
+uniform float radius;
+
+in vec2 uvs;
+
+layout (location = 0) out vec4 outColor;
+
+// Upsampling with a 3x3 tent filter scaled by the radius
+vec3 upsample(vec2 uv)
+{
+    vec2 d = texelSize * radius;
+    vec3 result = 4.0 * sample_color(uv).rgb;
+    result += 2.0 * (sample_color(uv + vec2(d.x, 0.0)).rgb + sample_color(uv - vec2(d.x, 0.0)).rgb
+        + sample_color(uv + vec2(0.0, d.y)).rgb + sample_color(uv - vec2(0.0, d.y)).rgb);
+    result += sample_color(uv + d).rgb + sample_color(uv - d).rgb
+        + sample_color(uv + vec2(d.x, -d.y)).rgb + sample_color(uv + vec2(-d.x, d.y)).rgb;
+    return result / 16.0;
+}
+
+void main()
+{
+    outColor = vec4(upsample(uvs), 1.0);
+}
@@ -1,7 +1,12 @@
+#include <three-d/depth>
+#include <three-d/tone_mapping>
+#include <three-d/color_mapping>
+
 
 uniform mat4 viewProjectionInverse;
 uniform float zNear;
 uniform float zFar;
+uniform float farDepth;
 uniform vec3 cameraPosition;
 uniform int debug_type;
 
@@ -12,7 +17,8 @@ layout (location = 0) out vec4 outColor;
 void main()
 {
     float depth = sample_depth(uvs);
-    if(depth > 0.99999)
+    // The background is at the far plane, which with reversed depth is exactly zero since distant geometry has depth values close to zero
+    if(farDepth > 0.5 ? depth > 0.99999 : depth <= 0.0)
     {
         discard;
     }
@@ -0,0 +1,43 @@
+
+uniform vec2 texelSize;
+uniform float maxRadius;
+
+in vec2 uvs;
+
+layout (location = 0) out vec4 outColor;
+
+const float GOLDEN_ANGLE = 2.39996323;
+
+void main()
+{
+    vec4 center = sample_color(uvs);
+#ifdef NEAR_FIELD
+    vec3 color = 0.0001 * center.rgb;
+    float weight = 0.0001;
+    float coverage = 0.0;
+#else
+    vec3 color = center.rgb;
+    float weight = 1.0;
+#endif
+    // Scatter as gather: each sample on a spiral within the maximum radius contributes if its circle of confusion reaches this pixel
+    for (int i = 0; i < SAMPLE_COUNT; i++) {
+        float r = maxRadius * sqrt((float(i) + 0.5) / float(SAMPLE_COUNT));
+        float a = float(i) * GOLDEN_ANGLE;
+        vec4 s = sample_color(uvs + r * vec2(cos(a), sin(a)) * texelSize);
+#ifdef NEAR_FIELD
+        // The foreground blurs over everything behind it
+        float w = clamp(s.a - r + 1.0, 0.0, 1.0);
+        coverage = max(coverage, w * clamp(s.a, 0.0, 1.0));
+#else
+        // In the background, the circle of confusion grows with the distance, so a sample with a larger circle of confusion than this pixel is behind it and cannot blur over it
+        float w = clamp(min(s.a, center.a) - r + 1.0, 0.0, 1.0);
+#endif
+        color += w * s.rgb;
+        weight += w;
+    }
+#ifdef NEAR_FIELD
+    outColor = vec4(color / weight, coverage);
+#else
+    outColor = vec4(color / weight, center.a);
+#endif
+}
@@ -0,0 +1,31 @@
+
+uniform vec2 texelSize;
+
+in vec2 uvs;
+
+layout (location = 0) out vec4 outColor;
+
+void main()
+{
+    vec3 color = vec3(0.0);
+    float weight = 0.0;
+    float coc = 0.0;
+    for (int i = 0; i < 4; i++) {
+        vec2 uv = uvs + texelSize * (vec2(i % 2, i / 2) - 0.5);
+        float c = circle_of_confusion(sample_depth(uv), uv);
+#ifdef NEAR_FIELD
+        // The largest circle of confusion, so the foreground covers the edges of the in-focus area behind it
+        c = max(-c, 0.0);
+        coc = max(coc, c);
+#else
+        c = max(c, 0.0);
+        coc += 0.25 * c;
+#endif
+        // Weight the colors by the circle of confusion, so the colors of in-focus pixels do not bleed into the blurred layer
+        float w = 0.001 + c;
+        color += w * sample_color(uv).rgb;
+        weight += w;
+    }
+    // The radius is stored in pixels of the half resolution layer
+    outColor = vec4(color / weight, 0.5 * coc);
+}
@@ -0,0 +1,21 @@
+#include <three-d/tone_mapping>
+#include <three-d/color_mapping>
+
+uniform sampler2D nearTexture;
+uniform sampler2D farTexture;
+
+in vec2 uvs;
+
+layout (location = 0) out vec4 outColor;
+
+void main()
+{
+    outColor = sample_color(uvs);
+    float coc = circle_of_confusion(sample_depth(uvs), uvs);
+    vec4 far = texture(farTexture, uvs);
+    outColor.rgb = mix(outColor.rgb, far.rgb, smoothstep(0.5, 1.5, coc));
+    vec4 near = texture(nearTexture, uvs);
+    outColor.rgb = mix(outColor.rgb, near.rgb, near.a);
+    outColor.rgb = tone_mapping(outColor.rgb);
+    outColor.rgb = color_mapping(outColor.rgb);
+}
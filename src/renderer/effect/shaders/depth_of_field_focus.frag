@@ -0,0 +1,9 @@
+
+uniform vec2 focusUv;
+
+layout (location = 0) out vec4 outColor;
+
+void main()
+{
+    outColor = vec4(view_distance(sample_depth(focusUv), focusUv), 0.0, 0.0, 1.0);
+}
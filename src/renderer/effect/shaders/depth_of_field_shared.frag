@@ -0,0 +1,29 @@
+#include <three-d/shared>
+
+uniform mat4 viewProjectionInverse;
+uniform vec3 eyePosition;
+uniform vec3 viewDirection;
+uniform float farDepth;
+uniform float focusDistance;
+uniform float aperture;
+uniform float cocScale;
+uniform float maxBlurRadius;
+
+// Returns the distance along the view direction to the surface with the given depth or a negative value if the surface is infinitely far away, for example the skybox
+float view_distance(float depth, vec2 uv)
+{
+    if (depth == farDepth) {
+        return -1.0;
+    }
+    vec3 position = world_pos_from_depth(viewProjectionInverse, depth, uv);
+    return max(dot(position - eyePosition, viewDirection), 0.00001);
+}
+
+// Returns the radius in pixels of the circle of confusion, which is negative in front of the focus distance and positive behind it
+float circle_of_confusion(float depth, vec2 uv)
+{
+    float d = view_distance(depth, uv);
+    // The size of the circle of confusion converges to the size of the aperture when the distance goes to infinity
+    float factor = d < 0.0 ? 1.0 : (d - focusDistance) / d;
+    return clamp(0.5 * aperture * factor * cocScale, -maxBlurRadius, maxBlurRadius);
+}
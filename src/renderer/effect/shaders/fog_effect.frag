@@ -1,3 +1,7 @@
+#include <three-d/shared>
+#include <three-d/tone_mapping>
+#include <three-d/color_mapping>
+
 
 uniform mat4 viewProjectionInverse;
 
@@ -6,6 +10,7 @@ uniform float fogDensity;
 uniform vec4 fogColor;
 uniform float animation;
 uniform vec3 eyePosition;
+uniform float farDepth;
 
 in vec2 uvs;
 
@@ -132,7 +137,8 @@ void main()
     vec3 pos = world_pos_from_depth(viewProjectionInverse, depth, uvs);
 
     // Distance
-    float dist = depth < 0.999f ? distance(pos, eyePosition) : 100.f;
+    bool background = farDepth > 0.5 ? depth >= 0.999f : depth <= 0.0;
+    float dist = background ? 100.f : distance(pos, eyePosition);
 
     float x = dist * fogDensity;
     float factor = 1. - 1. / exp(x * x);
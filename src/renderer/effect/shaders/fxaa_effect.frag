@@ -1,128 +1,160 @@
-
 uniform vec2 resolution;
 
 in vec2 uvs;
 
 layout (location = 0) out vec4 color;
 
-/**
-From:
-https://github.com/mattdesl/glsl-fxaa
-Basic FXAA implementation based on the code on geeks3d.com with the
-modification that the texture2DLod stuff was removed since it's
-unsupported by WebGL.
---
-From:
-https://github.com/mitsuhiko/webgl-meincraft
-Copyright (c) 2011 by Armin Ronacher.
-Some rights reserved.
-Redistribution and use in source and binary forms, with or without
-modification, are permitted provided that the following conditions are
-met:
-    * Redistributions of source code must retain the above copyright
-      notice, this list of conditions and the following disclaimer.
-    * Redistributions in binary form must reproduce the above
-      copyright notice, this list of conditions and the following
-      disclaimer in the documentation and/or other materials provided
-      with the distribution.
-    * The names of the contributors may not be used to endorse or
-      promote products derived from this software without specific
-      prior written permission.
-THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
-"AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
-LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
-A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT
-OWNER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
-SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT
-LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE,
-DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY
-THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT
-(INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
-OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
-*/
+// FXAA 3.11 quality by Timothy Lottes, where the quality preset defines SEARCH_STEPS, SEARCH_STEP_SIZES, EDGE_THRESHOLD and EDGE_THRESHOLD_MIN.
+// The amount of sub-pixel aliasing removal, where 1 is the softest and 0 turns it off.
+#define SUBPIX 0.75
 
-#ifndef FXAA_REDUCE_MIN
-    #define FXAA_REDUCE_MIN   (1.0/ 128.0)
-#endif
-#ifndef FXAA_REDUCE_MUL
-    #define FXAA_REDUCE_MUL   (1.0 / 8.0)
-#endif
-#ifndef FXAA_SPAN_MAX
-    #define FXAA_SPAN_MAX     8.0
-#endif
+float luma(vec4 c) {
+    return dot(c.rgb, vec3(0.299, 0.587, 0.114));
+}
 
-//optimized version for mobile, where dependent
-//texture reads can be a bottleneck
-vec4 fxaa(vec2 fragCoord, vec2 resolution,
-            vec2 v_rgbNW, vec2 v_rgbNE,
-            vec2 v_rgbSW, vec2 v_rgbSE,
-            vec2 v_rgbM) {
-    vec4 color;
-    mediump vec2 inverseVP = vec2(1.0 / resolution.x, 1.0 / resolution.y);
-    vec3 rgbNW = sample_color(v_rgbNW).xyz;
-    vec3 rgbNE = sample_color(v_rgbNE).xyz;
-    vec3 rgbSW = sample_color(v_rgbSW).xyz;
-    vec3 rgbSE = sample_color(v_rgbSE).xyz;
-    vec4 texColor = sample_color(v_rgbM);
-    vec3 rgbM  = texColor.xyz;
-    vec3 luma = vec3(0.299, 0.587, 0.114);
-    float lumaNW = dot(rgbNW, luma);
-    float lumaNE = dot(rgbNE, luma);
-    float lumaSW = dot(rgbSW, luma);
-    float lumaSE = dot(rgbSE, luma);
-    float lumaM  = dot(rgbM,  luma);
-    float lumaMin = min(lumaM, min(min(lumaNW, lumaNE), min(lumaSW, lumaSE)));
-    float lumaMax = max(lumaM, max(max(lumaNW, lumaNE), max(lumaSW, lumaSE)));
+float luma_at(vec2 uv) {
+    return luma(sample_color(uv));
+}
 
-    mediump vec2 dir;
-    dir.x = -((lumaNW + lumaNE) - (lumaSW + lumaSE));
-    dir.y =  ((lumaNW + lumaSW) - (lumaNE + lumaSE));
+vec4 fxaa(vec2 posM, vec2 rcpFrame) {
+    vec4 rgbyM = sample_color(posM);
+    float lumaM = luma(rgbyM);
+    float lumaS = luma_at(posM + vec2(0.0, 1.0) * rcpFrame);
+    float lumaE = luma_at(posM + vec2(1.0, 0.0) * rcpFrame);
+    float lumaN = luma_at(posM + vec2(0.0, -1.0) * rcpFrame);
+    float lumaW = luma_at(posM + vec2(-1.0, 0.0) * rcpFrame);
 
-    float dirReduce = max((lumaNW + lumaNE + lumaSW + lumaSE) *
-                          (0.25 * FXAA_REDUCE_MUL), FXAA_REDUCE_MIN);
+    float rangeMax = max(max(max(lumaN, lumaW), max(lumaS, lumaE)), lumaM);
+    float rangeMin = min(min(min(lumaN, lumaW), min(lumaS, lumaE)), lumaM);
+    float range = rangeMax - rangeMin;
+    // Early exit if the local contrast is too low to be an edge
+    if (range < max(EDGE_THRESHOLD_MIN, rangeMax * EDGE_THRESHOLD)) {
+        return rgbyM;
+    }
 
-    float rcpDirMin = 1.0 / (min(abs(dir.x), abs(dir.y)) + dirReduce);
-    dir = min(vec2(FXAA_SPAN_MAX, FXAA_SPAN_MAX),
-              max(vec2(-FXAA_SPAN_MAX, -FXAA_SPAN_MAX),
-              dir * rcpDirMin)) * inverseVP;
+    float lumaNW = luma_at(posM + vec2(-1.0, -1.0) * rcpFrame);
+    float lumaSE = luma_at(posM + vec2(1.0, 1.0) * rcpFrame);
+    float lumaNE = luma_at(posM + vec2(1.0, -1.0) * rcpFrame);
+    float lumaSW = luma_at(posM + vec2(-1.0, 1.0) * rcpFrame);
 
-    vec3 rgbA = 0.5 * (
-        sample_color(fragCoord * inverseVP + dir * (1.0 / 3.0 - 0.5)).xyz +
-        sample_color(fragCoord * inverseVP + dir * (2.0 / 3.0 - 0.5)).xyz);
-    vec3 rgbB = rgbA * 0.5 + 0.25 * (
-        sample_color(fragCoord * inverseVP + dir * -0.5).xyz +
-        sample_color(fragCoord * inverseVP + dir * 0.5).xyz);
+    // Find whether the edge is horizontal or vertical
+    float lumaNS = lumaN + lumaS;
+    float lumaWE = lumaW + lumaE;
+    float subpixRcpRange = 1.0 / range;
+    float subpixNSWE = lumaNS + lumaWE;
+    float edgeHorz1 = (-2.0 * lumaM) + lumaNS;
+    float edgeVert1 = (-2.0 * lumaM) + lumaWE;
+    float lumaNESE = lumaNE + lumaSE;
+    float lumaNWNE = lumaNW + lumaNE;
+    float edgeHorz2 = (-2.0 * lumaE) + lumaNESE;
+    float edgeVert2 = (-2.0 * lumaN) + lumaNWNE;
+    float lumaNWSW = lumaNW + lumaSW;
+    float lumaSWSE = lumaSW + lumaSE;
+    float edgeHorz4 = (abs(edgeHorz1) * 2.0) + abs(edgeHorz2);
+    float edgeVert4 = (abs(edgeVert1) * 2.0) + abs(edgeVert2);
+    float edgeHorz3 = (-2.0 * lumaW) + lumaNWSW;
+    float edgeVert3 = (-2.0 * lumaS) + lumaSWSE;
+    float edgeHorz = abs(edgeHorz3) + edgeHorz4;
+    float edgeVert = abs(edgeVert3) + edgeVert4;
 
-    float lumaB = dot(rgbB, luma);
-    if ((lumaB < lumaMin) || (lumaB > lumaMax))
-        color = vec4(rgbA, texColor.a);
-    else
-        color = vec4(rgbB, texColor.a);
-    return color;
-}
+    float subpixNWSWNESE = lumaNWSW + lumaNESE;
+    float lengthSign = rcpFrame.x;
+    bool horzSpan = edgeHorz >= edgeVert;
+    float subpixA = subpixNSWE * 2.0 + subpixNWSWNESE;
+    if (!horzSpan) {
+        lumaN = lumaW;
+        lumaS = lumaE;
+    } else {
+        lengthSign = rcpFrame.y;
+    }
+    float subpixB = (subpixA * (1.0 / 12.0)) - lumaM;
 
-void texcoords(vec2 fragCoord, vec2 resolution,
-			out vec2 v_rgbNW, out vec2 v_rgbNE,
-			out vec2 v_rgbSW, out vec2 v_rgbSE,
-			out vec2 v_rgbM) {
-	vec2 inverseVP = 1.0 / resolution.xy;
-	v_rgbNW = (fragCoord + vec2(-1.0, -1.0)) * inverseVP;
-	v_rgbNE = (fragCoord + vec2(1.0, -1.0)) * inverseVP;
-	v_rgbSW = (fragCoord + vec2(-1.0, 1.0)) * inverseVP;
-	v_rgbSE = (fragCoord + vec2(1.0, 1.0)) * inverseVP;
-	v_rgbM = vec2(fragCoord * inverseVP);
-}
+    // Choose the side of the edge with the largest gradient
+    float gradientN = lumaN - lumaM;
+    float gradientS = lumaS - lumaM;
+    float lumaNN = lumaN + lumaM;
+    float lumaSS = lumaS + lumaM;
+    bool pairN = abs(gradientN) >= abs(gradientS);
+    float gradient = max(abs(gradientN), abs(gradientS));
+    if (pairN) {
+        lengthSign = -lengthSign;
+    }
+    float subpixC = clamp(abs(subpixB) * subpixRcpRange, 0.0, 1.0);
 
-void main() {
-	mediump vec2 v_rgbNW;
-	mediump vec2 v_rgbNE;
-	mediump vec2 v_rgbSW;
-	mediump vec2 v_rgbSE;
-	mediump vec2 v_rgbM;
+    // Search along the edge in both directions for the end of the edge
+    vec2 posB = posM;
+    vec2 offNP = horzSpan ? vec2(rcpFrame.x, 0.0) : vec2(0.0, rcpFrame.y);
+    if (!horzSpan) {
+        posB.x += lengthSign * 0.5;
+    } else {
+        posB.y += lengthSign * 0.5;
+    }
+    vec2 posN = posB - offNP * SEARCH_STEP_SIZES[0];
+    vec2 posP = posB + offNP * SEARCH_STEP_SIZES[0];
+    float subpixD = ((-2.0) * subpixC) + 3.0;
+    float lumaEndN = luma_at(posN);
+    float subpixE = subpixC * subpixC;
+    float lumaEndP = luma_at(posP);
+    if (!pairN) {
+        lumaNN = lumaSS;
+    }
+    float gradientScaled = gradient * 1.0 / 4.0;
+    float lumaMM = lumaM - lumaNN * 0.5;
+    float subpixF = subpixD * subpixE;
+    bool lumaMLTZero = lumaMM < 0.0;
+    lumaEndN -= lumaNN * 0.5;
+    lumaEndP -= lumaNN * 0.5;
+    bool doneN = abs(lumaEndN) >= gradientScaled;
+    bool doneP = abs(lumaEndP) >= gradientScaled;
+    if (!doneN) {
+        posN -= offNP * SEARCH_STEP_SIZES[1];
+    }
+    if (!doneP) {
+        posP += offNP * SEARCH_STEP_SIZES[1];
+    }
+    for (int i = 2; i < SEARCH_STEPS; i++) {
+        if (doneN && doneP) {
+            break;
+        }
+        if (!doneN) {
+            lumaEndN = luma_at(posN) - lumaNN * 0.5;
+            doneN = abs(lumaEndN) >= gradientScaled;
+        }
+        if (!doneP) {
+            lumaEndP = luma_at(posP) - lumaNN * 0.5;
+            doneP = abs(lumaEndP) >= gradientScaled;
+        }
+        if (!doneN) {
+            posN -= offNP * SEARCH_STEP_SIZES[i];
+        }
+        if (!doneP) {
+            posP += offNP * SEARCH_STEP_SIZES[i];
+        }
+    }
 
-	//compute the texture coords
-	texcoords(uvs * resolution, resolution, v_rgbNW, v_rgbNE, v_rgbSW, v_rgbSE, v_rgbM);
+    // Offset the sample position towards the edge depending on the distance to the closest end of the edge
+    float dstN = horzSpan ? posM.x - posN.x : posM.y - posN.y;
+    float dstP = horzSpan ? posP.x - posM.x : posP.y - posM.y;
+    bool goodSpanN = (lumaEndN < 0.0) != lumaMLTZero;
+    float spanLength = dstP + dstN;
+    bool goodSpanP = (lumaEndP < 0.0) != lumaMLTZero;
+    float spanLengthRcp = 1.0 / spanLength;
+    bool directionN = dstN < dstP;
+    float dst = min(dstN, dstP);
+    bool goodSpan = directionN ? goodSpanN : goodSpanP;
+    float subpixG = subpixF * subpixF;
+    float pixelOffset = (dst * (-spanLengthRcp)) + 0.5;
+    float subpixH = subpixG * SUBPIX;
+    float pixelOffsetGood = goodSpan ? pixelOffset : 0.0;
+    float pixelOffsetSubpix = max(pixelOffsetGood, subpixH);
+    if (!horzSpan) {
+        posM.x += pixelOffsetSubpix * lengthSign;
+    } else {
+        posM.y += pixelOffsetSubpix * lengthSign;
+    }
+    return vec4(sample_color(posM).rgb, rgbyM.a);
+}
 
-	//compute FXAA
-	color = fxaa(uvs * resolution, resolution, v_rgbNW, v_rgbNE, v_rgbSW, v_rgbSE, v_rgbM);
+void main() {
+    color = fxaa(uvs, 1.0 / resolution);
 }
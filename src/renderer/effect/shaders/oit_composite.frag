@@ -0,0 +1,19 @@
+uniform sampler2D accumulationTexture;
+uniform sampler2D revealageTexture;
+
+in vec2 uvs;
+
+layout (location = 0) out vec4 outColor;
+
+void main()
+{
+    float revealage = texture(revealageTexture, uvs).r;
+    if (revealage >= 1.0) {
+        // No transparent fragments
+        discard;
+    }
+    vec4 accumulation = texture(accumulationTexture, uvs);
+    // The weighted average of the colors, which is blended with the opaque color using the product of one minus the alpha values
+    vec3 average = accumulation.rgb / max(accumulation.a, 1e-5);
+    outColor = vec4(average, 1.0 - revealage);
+}
@@ -0,0 +1,33 @@
+uniform highp sampler2D occlusionTexture;
+uniform vec2 direction;
+uniform int blurRadius;
+uniform float sharpness;
+
+layout (location = 0) out vec4 outColor;
+
+void main()
+{
+    ivec2 size = textureSize(occlusionTexture, 0);
+    ivec2 coords = ivec2(gl_FragCoord.xy);
+    vec2 center = texelFetch(occlusionTexture, coords, 0).rg;
+    if (center.g < 0.0) {
+        outColor = vec4(center, 0.0, 1.0);
+        return;
+    }
+
+    // A gaussian blur which ignores the background and decreases the weight of samples at a different depth, so occlusion does not bleed over edges
+    float sigma = 0.5 * float(blurRadius) + 0.5;
+    float sum = 0.0;
+    float weight_sum = 0.0;
+    for (int i = -blurRadius; i <= blurRadius; i++) {
+        ivec2 sample_coords = clamp(coords + i * ivec2(direction), ivec2(0), size - 1);
+        vec2 s = texelFetch(occlusionTexture, sample_coords, 0).rg;
+        if (s.g < 0.0) {
+            continue;
+        }
+        float weight = exp(-float(i * i) / (2.0 * sigma * sigma)) * exp(-sharpness * abs(s.g - center.g) / center.g);
+        sum += weight * s.r;
+        weight_sum += weight;
+    }
+    outColor = vec4(sum / weight_sum, center.g, 0.0, 1.0);
+}
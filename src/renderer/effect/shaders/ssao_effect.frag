@@ -0,0 +1,10 @@
+uniform sampler2D occlusionTexture;
+
+in vec2 uvs;
+
+layout (location = 0) out vec4 outColor;
+
+void main()
+{
+    outColor = vec4(vec3(texture(occlusionTexture, uvs).r), 1.0);
+}
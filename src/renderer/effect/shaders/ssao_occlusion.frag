@@ -0,0 +1,104 @@
+#include <three-d/shared>
+
+uniform sampler2D depthTexture;
+#ifdef USE_NORMAL_TEXTURE
+uniform sampler2D normalTexture;
+#endif
+uniform mat4 viewProjection;
+uniform mat4 viewProjectionInverse;
+uniform mat4 view;
+uniform vec3 eyePosition;
+uniform float radius;
+uniform float bias;
+uniform float intensity;
+uniform float farDepth;
+
+layout (location = 0) out vec4 outColor;
+
+bool is_background(ivec2 coords) {
+    return texelFetch(depthTexture, coords, 0).r == farDepth;
+}
+
+vec3 position_at(ivec2 coords) {
+    vec2 uv = (vec2(coords) + 0.5) / vec2(textureSize(depthTexture, 0));
+    return world_pos_from_depth(viewProjectionInverse, texelFetch(depthTexture, coords, 0).r, uv);
+}
+
+float view_depth(vec3 position) {
+    return -(view * vec4(position, 1.0)).z;
+}
+
+// Returns the difference in position to the neighbour in the given direction or the opposite direction,
+// whichever is closest in depth, to avoid reconstructing the normal across an edge
+vec3 position_difference(ivec2 coords, ivec2 offset, vec3 position, float depth) {
+    ivec2 size = textureSize(depthTexture, 0);
+    ivec2 c0 = coords - offset;
+    ivec2 c1 = coords + offset;
+    bool has0 = all(greaterThanEqual(c0, ivec2(0))) && !is_background(c0);
+    bool has1 = all(lessThan(c1, size)) && !is_background(c1);
+    vec3 d0 = has0 ? position - position_at(c0) : vec3(0.0);
+    vec3 d1 = has1 ? position_at(c1) - position : vec3(0.0);
+    if (!has0) {
+        return d1;
+    }
+    if (!has1) {
+        return d0;
+    }
+    return abs(view_depth(position - d0) - depth) < abs(view_depth(position + d1) - depth) ? d0 : d1;
+}
+
+void main()
+{
+    ivec2 size = textureSize(depthTexture, 0);
+    ivec2 coords = ivec2(gl_FragCoord.xy);
+    if (is_background(coords)) {
+        // The background is not occluded and is marked with a negative depth, so it is ignored by the blur
+        outColor = vec4(1.0, -1.0, 0.0, 1.0);
+        return;
+    }
+    vec3 position = position_at(coords);
+    float depth = view_depth(position);
+
+#ifdef USE_NORMAL_TEXTURE
+    vec3 normal = normalize(texelFetch(normalTexture, coords, 0).xyz * 2.0 - 1.0);
+#else
+    vec3 normal = cross(position_difference(coords, ivec2(1, 0), position, depth), position_difference(coords, ivec2(0, 1), position, depth));
+    normal = dot(normal, normal) > 0.0 ? normalize(normal) : normalize(eyePosition - position);
+#endif
+    if (dot(normal, eyePosition - position) < 0.0) {
+        normal = -normal;
+    }
+
+    // Rotate the samples by a different angle in each pixel, the noise is removed by the blur
+    float noise = fract(52.9829189 * fract(dot(gl_FragCoord.xy, vec2(0.06711056, 0.00583715))));
+    vec3 tangent = normalize(cross(abs(normal.x) > 0.9 ? vec3(0.0, 1.0, 0.0) : vec3(1.0, 0.0, 0.0), normal));
+    vec3 bitangent = cross(normal, tangent);
+    float angle = 2.0 * PI * noise;
+    tangent = cos(angle) * tangent + sin(angle) * bitangent;
+    bitangent = cross(normal, tangent);
+
+    float occlusion = 0.0;
+    for (int i = 0; i < SAMPLE_COUNT; i++) {
+        // Cosine weighted directions in the hemisphere around the normal distributed with the golden angle,
+        // where more samples are placed close to the surface point
+        float u = (float(i) + 0.5) / float(SAMPLE_COUNT);
+        float r = sqrt(u);
+        float phi = float(i) * 2.39996323;
+        vec3 direction = tangent * r * cos(phi) + bitangent * r * sin(phi) + normal * sqrt(1.0 - u);
+        float scale = (float(i) + noise) / float(SAMPLE_COUNT);
+        vec3 sample_position = position + direction * radius * mix(0.1, 1.0, scale * scale);
+
+        // Find the surface visible in the direction of the sample, clamped to the edges of the viewport
+        vec4 clip = viewProjection * vec4(sample_position, 1.0);
+        vec2 uv = 0.5 + 0.5 * clip.xy / clip.w;
+        ivec2 sample_coords = clamp(ivec2(floor(uv * vec2(size))), ivec2(0), size - 1);
+        if (is_background(sample_coords)) {
+            continue;
+        }
+        float surface_depth = view_depth(position_at(sample_coords));
+        float range = smoothstep(0.0, 1.0, radius / max(abs(depth - surface_depth), 1.0e-6));
+        occlusion += surface_depth <= view_depth(sample_position) - bias ? range : 0.0;
+    }
+    float visibility = pow(clamp(1.0 - occlusion / float(SAMPLE_COUNT), 0.0, 1.0), intensity);
+    outColor = vec4(visibility, depth, 0.0, 1.0);
+}
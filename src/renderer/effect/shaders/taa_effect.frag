@@ -0,0 +1,15 @@
+#include <three-d/tone_mapping>
+#include <three-d/color_mapping>
+
+uniform sampler2D historyTexture;
+
+in vec2 uvs;
+
+layout (location = 0) out vec4 outColor;
+
+void main()
+{
+    outColor = texture(historyTexture, uvs);
+    outColor.rgb = tone_mapping(outColor.rgb);
+    outColor.rgb = color_mapping(outColor.rgb);
+}
@@ -0,0 +1,47 @@
+#include <three-d/shared>
+
+uniform vec2 texelSize;
+#ifdef USE_HISTORY
+uniform sampler2D historyTexture;
+uniform float blendFactor;
+#endif
+#ifdef USE_DEPTH
+uniform mat4 viewProjectionInverse;
+uniform mat4 previousViewProjection;
+#endif
+
+in vec2 uvs;
+
+layout (location = 0) out vec4 outColor;
+
+void main()
+{
+    vec4 current = sample_color(uvs);
+#ifdef USE_HISTORY
+    // The range of colors in the 3x3 neighborhood of the current frame, which the history is clamped to, so history that is no longer visible does not leave ghosts
+    vec3 minColor = current.rgb;
+    vec3 maxColor = current.rgb;
+    for (int x = -1; x <= 1; x++) {
+        for (int y = -1; y <= 1; y++) {
+            vec3 c = sample_color(uvs + vec2(x, y) * texelSize).rgb;
+            minColor = min(minColor, c);
+            maxColor = max(maxColor, c);
+        }
+    }
+
+    vec2 historyUv = uvs;
+#ifdef USE_DEPTH
+    // Reproject the position seen in this pixel to where it was seen in the previous frame
+    vec3 position = world_pos_from_depth(viewProjectionInverse, sample_depth(uvs), uvs);
+    vec4 previous = previousViewProjection * vec4(position, 1.0);
+    if (previous.w > 0.0) {
+        historyUv = 0.5 * previous.xy / previous.w + 0.5;
+    }
+#endif
+    if (all(greaterThanEqual(historyUv, vec2(0.0))) && all(lessThanEqual(historyUv, vec2(1.0)))) {
+        vec3 history = clamp(texture(historyTexture, historyUv).rgb, minColor, maxColor);
+        current.rgb = mix(history, current.rgb, blendFactor);
+    }
+#endif
+    outColor = current;
+}
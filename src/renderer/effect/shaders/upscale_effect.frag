@@ -0,0 +1,49 @@
+uniform vec2 sourceResolution;
+
+in vec2 uvs;
+layout (location = 0) out vec4 outColor;
+
+// Catmull-Rom filtering using 9 bilinear samples instead of 16 point samples, see https://gist.github.com/TheRealMJP/c83b8c0f46b63f3a88a5986f4fa982b1
+vec4 sample_catmull_rom(vec2 uv)
+{
+    vec2 sample_position = uv * sourceResolution;
+    vec2 texel_position1 = floor(sample_position - 0.5) + 0.5;
+    vec2 f = sample_position - texel_position1;
+
+    vec2 w0 = f * (-0.5 + f * (1.0 - 0.5 * f));
+    vec2 w1 = 1.0 + f * f * (-2.5 + 1.5 * f);
+    vec2 w2 = f * (0.5 + f * (2.0 - 1.5 * f));
+    vec2 w3 = f * f * (-0.5 + 0.5 * f);
+
+    // The two middle samples are combined into one bilinear sample
+    vec2 w12 = w1 + w2;
+    vec2 offset12 = w2 / w12;
+
+    vec2 uv0 = (texel_position1 - 1.0) / sourceResolution;
+    vec2 uv3 = (texel_position1 + 2.0) / sourceResolution;
+    vec2 uv12 = (texel_position1 + offset12) / sourceResolution;
+
+    vec4 result = vec4(0.0);
+    result += sample_color(vec2(uv0.x, uv0.y)) * w0.x * w0.y;
+    result += sample_color(vec2(uv12.x, uv0.y)) * w12.x * w0.y;
+    result += sample_color(vec2(uv3.x, uv0.y)) * w3.x * w0.y;
+
+    result += sample_color(vec2(uv0.x, uv12.y)) * w0.x * w12.y;
+    result += sample_color(vec2(uv12.x, uv12.y)) * w12.x * w12.y;
+    result += sample_color(vec2(uv3.x, uv12.y)) * w3.x * w12.y;
+
+    result += sample_color(vec2(uv0.x, uv3.y)) * w0.x * w3.y;
+    result += sample_color(vec2(uv12.x, uv3.y)) * w12.x * w3.y;
+    result += sample_color(vec2(uv3.x, uv3.y)) * w3.x * w3.y;
+
+    // The negative lobes can give values outside the range of the neighbouring texels
+    return clamp(result, vec4(0.0), vec4(1.0));
+}
+
+void main()
+{
+    outColor = sample_catmull_rom(uvs);
+#ifdef USE_DEPTH
+    gl_FragDepth = sample_depth(uvs);
+#endif
+}
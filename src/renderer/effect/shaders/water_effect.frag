@@ -1,3 +1,7 @@
+#include <three-d/depth>
+#include <three-d/tone_mapping>
+#include <three-d/color_mapping>
+
 uniform mat4 viewProjection;
 uniform mat4 viewProjectionInverse;
 uniform vec3 cameraPosition;
@@ -0,0 +1,267 @@
+use crate::renderer::*;
+
+///
+/// The passes of the screen-space ambient occlusion, which are applied as screen materials to cache the programs.
+///
+enum SsaoPass<'a> {
+    Occlusion {
+        effect: &'a SsaoEffect,
+        viewer: &'a dyn Viewer,
+        depth_texture: &'a DepthTexture2D,
+        normal_texture: Option<&'a Texture2D>,
+    },
+    Blur {
+        texture: &'a Texture2D,
+        direction: Vec2,
+        radius: u32,
+    },
+}
+
+impl Material for SsaoPass<'_> {
+    fn id(&self) -> EffectMaterialId {
+        match self {
+            Self::Occlusion {
+                effect,
+                normal_texture,
+                ..
+            } => EffectMaterialId(
+                EffectMaterialId::SsaoOcclusionMaterialBase.0
+                    | ((effect.sample_count() - 1) << 1) as u16
+                    | normal_texture.is_some() as u16,
+            ),
+            Self::Blur { .. } => EffectMaterialId::SsaoBlurMaterial,
+        }
+    }
+
+    fn fragment_shader_source(&self, _lights: &[&dyn Light]) -> String {
+        match self {
+            Self::Occlusion {
+                effect,
+                normal_texture,
+                ..
+            } => format!(
+                "#define SAMPLE_COUNT {}\n{}{}",
+                effect.sample_count(),
+                if normal_texture.is_some() {
+                    "#define USE_NORMAL_TEXTURE\n"
+                } else {
+                    ""
+                },
+                include_str!("shaders/ssao_occlusion.frag")
+            ),
+            Self::Blur { .. } => include_str!("shaders/ssao_blur.frag").to_owned(),
+        }
+    }
+
+    fn use_uniforms(&self, program: &Program, _viewer: &dyn Viewer, _lights: &[&dyn Light]) {
+        match self {
+            Self::Occlusion {
+                effect,
+                viewer,
+                depth_texture,
+                normal_texture,
+            } => {
+                program.use_depth_texture("depthTexture", depth_texture);
+                if let Some(normal_texture) = normal_texture {
+                    program.use_texture("normalTexture", normal_texture);
+                }
+                program.use_uniform("viewProjection", viewer.projection() * viewer.view());
+                program.use_uniform("viewProjectionInverse", view_projection_inverse(*viewer));
+                program.use_uniform("view", viewer.view());
+                program.use_uniform("eyePosition", viewer.position());
+                program.use_uniform("radius", effect.radius);
+                program.use_uniform("bias", effect.bias);
+                program.use_uniform("intensity", effect.intensity);
+                program.use_uniform("farDepth", viewer.depth_mode().far_depth());
+            }
+            Self::Blur {
+                texture,
+                direction,
+                radius,
+            } => {
+                program.use_texture("occlusionTexture", texture);
+                program.use_uniform("direction", *direction);
+                program.use_uniform("blurRadius", *radius as i32);
+                program.use_uniform("sharpness", 20.0f32);
+            }
+        }
+    }
+
+    fn render_states(&self) -> RenderStates {
+        RenderStates {
+            depth_test: DepthTest::Always,
+            write_mask: WriteMask::COLOR,
+            ..Default::default()
+        }
+    }
+
+    fn material_type(&self) -> MaterialType {
+        MaterialType::Opaque
+    }
+}
+
+///
+/// Screen-space ambient occlusion, which darkens creases, cavities and the contact areas between objects based on the depth of the rendered scene.
+/// For each pixel, a number of samples in the hemisphere around the surface normal, within the [SsaoEffect::radius], are compared with the depth texture,
+/// and the result is blurred with a separable blur which does not blur across edges in the depth.
+/// The normals are either given as a texture or reconstructed from the depth.
+/// Samples outside the viewport are clamped to the edges of the viewport, and the background, ie. where the depth is at the far plane, is not occluded.
+///
+/// Call [SsaoEffect::update] each frame after the depth of the scene has been rendered. The result can then be
+/// - applied as a screen material after the scene has been rendered, using for example [RenderTarget::apply_screen_material], which multiplies the color of the scene with the ambient occlusion, or
+/// - used by a custom material as the `occlusion` input of `calculate_lighting`, see [SsaoEffect::occlusion_texture].
+///
+/// All parameters can be changed each frame, only changing the [SsaoEffect::samples] requires a new shader.
+///
+pub struct SsaoEffect {
+    context: Context,
+    occlusion_texture: Option<Texture2D>,
+    /// The radius in world units of the hemisphere in which the occlusion is sampled.
+    pub radius: f32,
+    /// The depth difference in world units that is required for a sample to be occluded, which avoids self occlusion on flat surfaces.
+    pub bias: f32,
+    /// The exponent applied to the ambient occlusion, where values larger than 1 gives a darker occlusion.
+    pub intensity: f32,
+    /// The number of samples per pixel, between 1 and 64. More samples gives less noise but is slower. Changing this requires a new shader.
+    pub samples: u32,
+    /// The radius in pixels of the blur applied to the ambient occlusion. Set to 0 to disable the blur.
+    pub blur_radius: u32,
+}
+
+impl SsaoEffect {
+    ///
+    /// Creates a new screen-space ambient occlusion effect with the given radius in world units.
+    ///
+    pub fn new(context: &Context, radius: f32) -> Self {
+        Self {
+            context: context.clone(),
+            occlusion_texture: None,
+            radius,
+            bias: 0.01 * radius,
+            intensity: 1.0,
+            samples: 16,
+            blur_radius: 4,
+        }
+    }
+
+    ///
+    /// Computes the ambient occlusion from the given depth texture which contains the depth of the scene as seen by the given viewer.
+    /// The depth texture must have the same size as the viewport of the viewer.
+    /// If available, give a texture with the same size containing the world space normals of the scene encoded as a [NormalMaterial] does,
+    /// otherwise the normals are reconstructed from the depth.
+    ///
+    pub fn update(
+        &mut self,
+        viewer: impl Viewer,
+        depth_texture: &DepthTexture2D,
+        normal_texture: Option<&Texture2D>,
+    ) {
+        let (width, height) = (depth_texture.width(), depth_texture.height());
+        if self
+            .occlusion_texture
+            .as_ref()
+            .is_none_or(|t| t.width() != width || t.height() != height)
+        {
+            self.occlusion_texture = Some(Texture2D::new_empty::<[f32; 2]>(
+                &self.context,
+                width,
+                height,
+                Interpolation::Nearest,
+                Interpolation::Nearest,
+                None,
+                Wrapping::ClampToEdge,
+                Wrapping::ClampToEdge,
+            ));
+        }
+        let mut occlusion_texture = self.occlusion_texture.take().unwrap();
+        let camera = Camera::new_2d(Viewport::new_at_origo(width, height));
+        occlusion_texture
+            .as_color_target(None)
+            .apply_screen_material(
+                &SsaoPass::Occlusion {
+                    effect: self,
+                    viewer: &viewer,
+                    depth_texture,
+                    normal_texture,
+                },
+                &camera,
+                &[],
+            );
+        if self.blur_radius > 0 {
+            let mut blurred =
+                self.context
+                    .request_texture::<[f32; 2]>(width, height, Interpolation::Nearest);
+            blurred.as_color_target(None).apply_screen_material(
+                &SsaoPass::Blur {
+                    texture: &occlusion_texture,
+                    direction: vec2(1.0, 0.0),
+                    radius: self.blur_radius,
+                },
+                &camera,
+                &[],
+            );
+            occlusion_texture
+                .as_color_target(None)
+                .apply_screen_material(
+                    &SsaoPass::Blur {
+                        texture: &blurred,
+                        direction: vec2(0.0, 1.0),
+                        radius: self.blur_radius,
+                    },
+                    &camera,
+                    &[],
+                );
+        }
+        self.occlusion_texture = Some(occlusion_texture);
+    }
+
+    ///
+    /// Returns the ambient occlusion computed by the last call to [SsaoEffect::update], if any.
+    /// The red channel contains the ambient occlusion, where 1 is no occlusion and 0 is fully occluded, and the green channel contains the distance to the viewer along the view direction.
+    /// A material can sample the red channel at `gl_FragCoord.xy` divided by the size of the viewport and use it as the `occlusion` input of `calculate_lighting`.
+    ///
+    pub fn occlusion_texture(&self) -> Option<ColorTexture<'_>> {
+        self.occlusion_texture.as_ref().map(ColorTexture::Single)
+    }
+
+    fn sample_count(&self) -> u32 {
+        self.samples.clamp(1, 64)
+    }
+}
+
+impl Material for SsaoEffect {
+    fn id(&self) -> EffectMaterialId {
+        EffectMaterialId::SsaoMaterial
+    }
+
+    fn fragment_shader_source(&self, _lights: &[&dyn Light]) -> String {
+        include_str!("shaders/ssao_effect.frag").to_owned()
+    }
+
+    fn use_uniforms(&self, program: &Program, _viewer: &dyn Viewer, _lights: &[&dyn Light]) {
+        if let Some(ref texture) = self.occlusion_texture {
+            program.use_texture("occlusionTexture", texture);
+        }
+    }
+
+    fn render_states(&self) -> RenderStates {
+        RenderStates {
+            depth_test: DepthTest::Always,
+            write_mask: WriteMask::COLOR,
+            // Multiply the color of the scene with the ambient occlusion
+            blend: Blend::Enabled {
+                source_rgb_multiplier: BlendMultiplierType::DstColor,
+                source_alpha_multiplier: BlendMultiplierType::Zero,
+                destination_rgb_multiplier: BlendMultiplierType::Zero,
+                destination_alpha_multiplier: BlendMultiplierType::One,
+                rgb_equation: BlendEquationType::Add,
+                alpha_equation: BlendEquationType::Add,
+            },
+            ..Default::default()
+        }
+    }
+
+    fn material_type(&self) -> MaterialType {
+        MaterialType::Transparent
+    }
+}
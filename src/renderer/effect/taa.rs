@@ -0,0 +1,252 @@
+use crate::renderer::*;
+
+///
+/// The pass of the temporal anti-aliasing which blends the current frame into the history.
+///
+struct TaaResolve<'a> {
+    effect: &'a TaaEffect,
+    view_projection_inverse: Mat4,
+}
+
+impl TaaResolve<'_> {
+    fn use_history(&self) -> bool {
+        self.effect.history.is_some()
+    }
+}
+
+impl Effect for TaaResolve<'_> {
+    fn fragment_shader_source(
+        &self,
+        _lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) -> String {
+        format!(
+            "{}{}{}\n{}\n{}",
+            if self.use_history() {
+                "#define USE_HISTORY\n"
+            } else {
+                ""
+            },
+            if depth_texture.is_some() {
+                "#define USE_DEPTH\n"
+            } else {
+                ""
+            },
+            color_texture.unwrap().fragment_shader_source(),
+            depth_texture
+                .map(|t| t.fragment_shader_source())
+                .unwrap_or_default(),
+            include_str!("shaders/taa_resolve.frag")
+        )
+    }
+
+    fn id(
+        &self,
+        color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) -> EffectMaterialId {
+        EffectMaterialId(
+            EffectMaterialId::TaaEffect(color_texture, depth_texture).0
+                | (self.use_history() as u16) << 7,
+        )
+    }
+
+    fn use_uniforms(
+        &self,
+        program: &Program,
+        _viewer: &dyn Viewer,
+        _lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) {
+        let color_texture = color_texture.unwrap();
+        color_texture.use_uniforms(program);
+        program.use_uniform(
+            "texelSize",
+            vec2(
+                1.0 / color_texture.width() as f32,
+                1.0 / color_texture.height() as f32,
+            ),
+        );
+        if let Some(history) = &self.effect.history {
+            program.use_texture("historyTexture", history);
+            program.use_uniform("blendFactor", self.effect.blend_factor.clamp(0.0, 1.0));
+        }
+        if let Some(depth_texture) = depth_texture {
+            depth_texture.use_uniforms(program);
+            program.use_uniform("viewProjectionInverse", self.view_projection_inverse);
+            program.use_uniform(
+                "previousViewProjection",
+                self.effect
+                    .previous_view_projection
+                    .unwrap_or(Mat4::identity()),
+            );
+        }
+    }
+
+    fn render_states(&self) -> RenderStates {
+        RenderStates {
+            depth_test: DepthTest::Always,
+            write_mask: WriteMask::COLOR,
+            ..Default::default()
+        }
+    }
+}
+
+///
+/// A temporal anti-aliasing effect, which smooths jagged edges by rendering the scene with a different sub-pixel offset each frame
+/// and blending each frame into a history of the previous frames.
+/// Compared to [FxaaEffect], it also smooths thin geometry and specular highlights, but it can blur the image slightly and leave faint trails behind moving objects.
+///
+/// Each frame, call [TaaEffect::jitter_camera] before rendering the scene into a floating point color texture, for example a [Texture2D] with `[f16; 4]` data,
+/// then call [TaaEffect::update] with the color texture and apply this effect without any color or depth texture, for example using [RenderTarget::apply_screen_effect],
+/// which writes the anti-aliased image to the render target. Like [ScreenEffect], the tone and color mapping specified in the [Viewer] is applied,
+/// so render the scene into the color texture without tone and color mapping.
+///
+/// The history is clamped to the colors around each pixel in the current frame, so changes in the scene only leave ghosts for a short time.
+/// If the depth texture is given to [TaaEffect::update], the history is also reprojected, so a moving camera does not blur the image.
+///
+pub struct TaaEffect {
+    context: Context,
+    history: Option<PooledTexture>,
+    previous_view_projection: Option<Mat4>,
+    frame_index: u32,
+    /// The weight of the current frame when blended with the history, between 0 and 1.
+    /// Lower values gives smoother edges, but more ghosting when the scene changes.
+    pub blend_factor: f32,
+    /// The number of different sub-pixel offsets that [TaaEffect::jitter_camera] cycles through.
+    pub sample_count: u32,
+}
+
+impl TaaEffect {
+    ///
+    /// Creates a new temporal anti-aliasing effect with default parameters.
+    ///
+    pub fn new(context: &Context) -> Self {
+        Self {
+            context: context.clone(),
+            history: None,
+            previous_view_projection: None,
+            frame_index: 0,
+            blend_factor: 0.1,
+            sample_count: 8,
+        }
+    }
+
+    ///
+    /// Sets the jitter of the camera to the next sub-pixel offset, see [Camera::set_jitter].
+    /// Call this each frame before rendering the scene.
+    ///
+    pub fn jitter_camera(&mut self, camera: &mut Camera) {
+        self.frame_index = (self.frame_index + 1) % self.sample_count.max(1);
+        camera.set_jitter(vec2(
+            halton(self.frame_index + 1, 2) - 0.5,
+            halton(self.frame_index + 1, 3) - 0.5,
+        ));
+    }
+
+    ///
+    /// Blends the given color texture, which must contain the scene rendered with the given camera, into the history.
+    /// The depth texture is optional, but without it the history is only correct if the camera does not move.
+    /// Call this each frame before applying the effect.
+    ///
+    pub fn update(
+        &mut self,
+        camera: &Camera,
+        color_texture: ColorTexture,
+        depth_texture: Option<DepthTexture>,
+    ) {
+        let (width, height) = (color_texture.width(), color_texture.height());
+        if self
+            .history
+            .as_ref()
+            .is_some_and(|history| history.width() != width || history.height() != height)
+        {
+            self.reset();
+        }
+
+        // The history keeps the alpha channel of the color texture
+        let mut texture = match preferred_hdr_format_with_alpha(&self.context) {
+            Some(format) => {
+                self.context
+                    .request_hdr_texture(width, height, format, Interpolation::Linear)
+            }
+            // Without support for rendering to floating point textures, the colors in the history are clamped to the range [0, 1]
+            None => self
+                .context
+                .request_texture::<[u8; 4]>(width, height, Interpolation::Linear),
+        };
+        let camera_2d = Camera::new_2d(Viewport::new_at_origo(width, height));
+        texture.as_color_target(None).apply_screen_effect(
+            &TaaResolve {
+                effect: self,
+                view_projection_inverse: view_projection_inverse(camera),
+            },
+            &camera_2d,
+            &[],
+            Some(color_texture),
+            depth_texture,
+        );
+        self.history = Some(texture);
+
+        // The history is stored without the jitter, so the next frame is reprojected to the view projection without the jitter
+        let mut camera = camera.clone();
+        camera.set_jitter(vec2(0.0, 0.0));
+        self.previous_view_projection = Some(camera.projection() * camera.view());
+    }
+
+    ///
+    /// Discards the history, for example when the camera jumps to a new position, so the next frame is not blended with the previous frames.
+    ///
+    pub fn reset(&mut self) {
+        self.history = None;
+        self.previous_view_projection = None;
+    }
+}
+
+impl Effect for TaaEffect {
+    fn fragment_shader_source(
+        &self,
+        _lights: &[&dyn Light],
+        _color_texture: Option<ColorTexture>,
+        _depth_texture: Option<DepthTexture>,
+    ) -> String {
+        include_str!("shaders/taa_effect.frag").to_string()
+    }
+
+    fn id(
+        &self,
+        _color_texture: Option<ColorTexture>,
+        _depth_texture: Option<DepthTexture>,
+    ) -> EffectMaterialId {
+        EffectMaterialId(EffectMaterialId::TaaEffectBase.0 | 1 << 8)
+    }
+
+    fn use_uniforms(
+        &self,
+        program: &Program,
+        viewer: &dyn Viewer,
+        _lights: &[&dyn Light],
+        _color_texture: Option<ColorTexture>,
+        _depth_texture: Option<DepthTexture>,
+    ) {
+        viewer.tone_mapping().use_uniforms(program);
+        viewer.color_mapping().use_uniforms(program);
+        program.use_texture(
+            "historyTexture",
+            self.history.as_ref().expect(
+                "Must call TaaEffect::update before applying a temporal anti-aliasing effect",
+            ),
+        );
+    }
+
+    fn render_states(&self) -> RenderStates {
+        RenderStates {
+            depth_test: DepthTest::Always,
+            write_mask: WriteMask::COLOR,
+            cull: Cull::Back,
+            ..Default::default()
+        }
+    }
+}
@@ -0,0 +1,72 @@
+use crate::renderer::*;
+
+///
+/// Copies the content of a color texture, and optionally a depth texture, with a different resolution than the render target,
+/// where the color is filtered with a Catmull-Rom filter which is sharper than bilinear filtering, see also [DynamicResolution].
+/// The color is clamped to the range `[0, 1]` and, like [CopyEffect], no tone and color mapping is applied.
+///
+#[derive(Clone, Debug, Default)]
+pub struct UpscaleEffect {
+    /// Defines which channels (red, green, blue, alpha and depth) to write.
+    pub write_mask: WriteMask,
+    /// Defines which type of blending to use when writing the upscaled color to the render target.
+    pub blend: Blend,
+}
+
+impl Effect for UpscaleEffect {
+    fn fragment_shader_source(
+        &self,
+        _lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) -> String {
+        let color_texture =
+            color_texture.expect("Must supply a color texture to apply an upscale effect");
+        format!(
+            "{}{}\n{}",
+            color_texture.fragment_shader_source(),
+            depth_texture
+                .map(|t| format!("#define USE_DEPTH\n{}", t.fragment_shader_source()))
+                .unwrap_or_default(),
+            include_str!("shaders/upscale_effect.frag")
+        )
+    }
+
+    fn id(
+        &self,
+        color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) -> EffectMaterialId {
+        EffectMaterialId::UpscaleEffect(color_texture, depth_texture)
+    }
+
+    fn use_uniforms(
+        &self,
+        program: &Program,
+        _viewer: &dyn Viewer,
+        _lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) {
+        let color_texture =
+            color_texture.expect("Must supply a color texture to apply an upscale effect");
+        color_texture.use_uniforms(program);
+        program.use_uniform(
+            "sourceResolution",
+            vec2(color_texture.width() as f32, color_texture.height() as f32),
+        );
+        if let Some(depth_texture) = depth_texture {
+            depth_texture.use_uniforms(program);
+        }
+    }
+
+    fn render_states(&self) -> RenderStates {
+        RenderStates {
+            depth_test: DepthTest::Always,
+            cull: Cull::Back,
+            write_mask: self.write_mask,
+            blend: self.blend,
+            ..Default::default()
+        }
+    }
+}
@@ -41,7 +41,7 @@ impl Effect for WaterEffect {
         depth_texture: Option<DepthTexture>,
     ) -> String {
         format!(
-            "{}\n{}\n{}\n{}\n{}\n{}\n{}",
+            "{}\n{}\n{}\n{}\n{}",
             match &self.background {
                 Background::Color(_) => "",
                 Background::Texture(_) => "#define USE_BACKGROUND_TEXTURE",
@@ -53,8 +53,6 @@ impl Effect for WaterEffect {
                 .expect("Must supply a depth texture to apply a water effect")
                 .fragment_shader_source(),
             lights_shader_source(lights),
-            ToneMapping::fragment_shader_source(),
-            ColorMapping::fragment_shader_source(),
             include_str!("shaders/water_effect.frag")
         )
     }
@@ -97,11 +95,9 @@ impl Effect for WaterEffect {
         for (i, light) in lights.iter().enumerate() {
             light.use_uniforms(program, i as u32);
         }
+        program.use_uniform_if_required("logExposure", viewer.exposure().log2());
         program.use_uniform("viewProjection", viewer.projection() * viewer.view());
-        program.use_uniform(
-            "viewProjectionInverse",
-            (viewer.projection() * viewer.view()).invert().unwrap(),
-        );
+        program.use_uniform("viewProjectionInverse", view_projection_inverse(viewer));
         program.use_uniform("cameraPosition", viewer.position());
         program.use_uniform(
             "screenSize",
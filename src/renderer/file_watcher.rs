@@ -0,0 +1,99 @@
+//!
+//! Watching a file for changes, for example to reload an asset while it is being edited, see [FileWatcher].
+//!
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+///
+/// Watches a file for changes by polling its modification time and size, for example to reload a glTF file using [Model::reload](crate::Model::reload)
+/// each time it is saved from a modelling tool.
+///
+/// A change is only reported when the modification time and size have been the same for two polls in a row,
+/// so a file that is in the process of being written is not reported until the writing is done.
+/// A file that does not exist, for example while it is being replaced, is not reported as changed until it exists again.
+///
+/// ```
+/// # use three_d::*;
+/// let path = std::env::temp_dir().join("three-d-file-watcher-doctest.txt");
+/// std::fs::write(&path, "first").unwrap();
+/// let mut watcher = FileWatcher::new(&path);
+/// watcher.poll_interval = std::time::Duration::ZERO;
+/// assert!(!watcher.changed());
+///
+/// std::fs::write(&path, "second version").unwrap();
+/// // The first poll sees the change and the second poll reports it, since the file is no longer being written
+/// assert!(!watcher.changed());
+/// assert!(watcher.changed());
+/// assert!(!watcher.changed());
+/// # std::fs::remove_file(&path).unwrap();
+/// ```
+///
+#[derive(Clone, Debug)]
+pub struct FileWatcher {
+    path: PathBuf,
+    reported: Option<(SystemTime, u64)>,
+    pending: Option<(SystemTime, u64)>,
+    last_poll: Option<Instant>,
+    /// The minimum time between two polls of the file. Calls to [FileWatcher::changed] within this time after the last poll return false without polling.
+    pub poll_interval: Duration,
+}
+
+impl FileWatcher {
+    ///
+    /// Starts watching the file at the given path. The current version of the file, if it exists, is not reported as a change.
+    ///
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        let path = path.as_ref().to_path_buf();
+        Self {
+            reported: stamp(&path),
+            path,
+            pending: None,
+            last_poll: None,
+            poll_interval: Duration::from_millis(250),
+        }
+    }
+
+    ///
+    /// Returns the path of the watched file.
+    ///
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    ///
+    /// Polls the file, if the [FileWatcher::poll_interval] has passed since the last poll, and returns whether the file has changed since the last time a change was reported.
+    /// Call this each frame and reload the file when it returns true.
+    ///
+    pub fn changed(&mut self) -> bool {
+        let now = Instant::now();
+        if self
+            .last_poll
+            .is_some_and(|last_poll| now.duration_since(last_poll) < self.poll_interval)
+        {
+            return false;
+        }
+        self.last_poll = Some(now);
+
+        let Some(current) = stamp(&self.path) else {
+            self.pending = None;
+            return false;
+        };
+        if Some(current) == self.reported {
+            self.pending = None;
+            false
+        } else if Some(current) == self.pending {
+            self.reported = Some(current);
+            self.pending = None;
+            true
+        } else {
+            self.pending = Some(current);
+            false
+        }
+    }
+}
+
+fn stamp(path: &Path) -> Option<(SystemTime, u64)> {
+    let metadata = std::fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
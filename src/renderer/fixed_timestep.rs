@@ -0,0 +1,108 @@
+//!
+//! Updating a simulation with a fixed time step independent of the frame rate, see [FixedTimestep].
+//!
+
+///
+/// Accumulates the time between frames and runs a fixed update, for example a physics simulation, zero or more times each frame
+/// with a fixed time step, so the simulation behaves the same independent of the frame rate and does not explode when a frame takes a long time.
+///
+/// Since the fixed updates do not happen at the same time as the frames, the state which is rendered should be interpolated
+/// between the state before and after the latest fixed update using [FixedTimestep::alpha] to avoid stuttering.
+/// When a frame takes so long that more than [FixedTimestep::max_steps] updates are needed to catch up, the remaining time is skipped,
+/// so the simulation slows down instead of spending more and more time each frame on catching up.
+///
+/// ```
+/// # use three_d::*;
+/// let mut fixed_timestep = FixedTimestep::new(60.0);
+/// let (mut previous_position, mut position, mut velocity) = (10.0, 10.0, 0.0);
+/// let mut steps = Vec::new();
+/// // Each frame, for example with the elapsed time from FrameInput::elapsed_time
+/// for elapsed_time in [16.0, 17.0, 250.0, 16.0] {
+///     steps.push(fixed_timestep.update(elapsed_time, |dt| {
+///         previous_position = position;
+///         velocity -= 9.82 * dt / 1000.0;
+///         position += velocity * dt / 1000.0;
+///     }));
+///     let rendered_position = previous_position + (position - previous_position) * fixed_timestep.alpha();
+/// }
+/// // The long frame needs 15 steps to catch up, but is clamped to the maximum number of steps
+/// assert_eq!(steps, [0, 1, fixed_timestep.max_steps, 1]);
+/// assert_eq!(fixed_timestep.max_steps, 5);
+/// assert_eq!(fixed_timestep.step_count(), 7);
+/// ```
+///
+#[derive(Clone, Debug)]
+pub struct FixedTimestep {
+    step_time: f64,
+    accumulated_time: f64,
+    step_count: u64,
+    /// The maximum number of fixed updates in one frame. The default is 5.
+    pub max_steps: u32,
+}
+
+impl FixedTimestep {
+    ///
+    /// Creates a new fixed time step with the given number of fixed updates per second.
+    ///
+    pub fn new(updates_per_second: f64) -> Self {
+        Self {
+            step_time: 1000.0 / updates_per_second.max(f64::EPSILON),
+            accumulated_time: 0.0,
+            step_count: 0,
+            max_steps: 5,
+        }
+    }
+
+    ///
+    /// Returns the fixed time step in milliseconds, which is the time step given to the fixed update.
+    ///
+    pub fn step_time(&self) -> f64 {
+        self.step_time
+    }
+
+    ///
+    /// Sets the number of fixed updates per second.
+    ///
+    pub fn set_updates_per_second(&mut self, updates_per_second: f64) {
+        let alpha = self.alpha();
+        self.step_time = 1000.0 / updates_per_second.max(f64::EPSILON);
+        self.accumulated_time = alpha * self.step_time;
+    }
+
+    ///
+    /// Adds the given time in milliseconds since the last frame, for example [FrameInput::elapsed_time](crate::FrameInput::elapsed_time),
+    /// and calls the fixed update with the fixed time step in milliseconds for each whole time step accumulated, at most [FixedTimestep::max_steps] times.
+    /// Returns the number of fixed updates. Should be called each frame before rendering.
+    ///
+    pub fn update(&mut self, elapsed_time: f64, mut fixed_update: impl FnMut(f64)) -> u32 {
+        self.accumulated_time += elapsed_time.max(0.0);
+        let mut steps = 0;
+        while self.accumulated_time >= self.step_time {
+            if steps >= self.max_steps {
+                // Skip the time that cannot be caught up
+                self.accumulated_time %= self.step_time;
+                break;
+            }
+            fixed_update(self.step_time);
+            self.accumulated_time -= self.step_time;
+            steps += 1;
+        }
+        self.step_count += steps as u64;
+        steps
+    }
+
+    ///
+    /// Returns how far the time has progressed from the latest fixed update towards the next fixed update, as a value in the range `[0, 1)`.
+    /// Use this to interpolate between the state before and after the latest fixed update when rendering.
+    ///
+    pub fn alpha(&self) -> f64 {
+        (self.accumulated_time / self.step_time).clamp(0.0, 1.0)
+    }
+
+    ///
+    /// Returns the total number of fixed updates since this was created.
+    ///
+    pub fn step_count(&self) -> u64 {
+        self.step_count
+    }
+}
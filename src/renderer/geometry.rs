@@ -43,6 +43,14 @@ macro_rules! impl_geometry_body {
         fn aabb(&self) -> AxisAlignedBoundingBox {
             self.$inner().aabb()
         }
+
+        fn alpha_cutout(&self) -> Option<AlphaCutout> {
+            self.$inner().alpha_cutout()
+        }
+
+        fn cull(&self) -> Option<Cull> {
+            self.$inner().cull()
+        }
     };
 }
 
@@ -54,14 +62,41 @@ mod instanced_mesh;
 #[doc(inline)]
 pub use instanced_mesh::*;
 
+mod skinned_cpu_mesh;
+#[doc(inline)]
+pub use skinned_cpu_mesh::*;
+
+mod mesh_batch;
+pub(in crate::renderer) use mesh_batch::*;
+
 mod sprites;
 #[doc(inline)]
 pub use sprites::*;
 
+mod point_cloud_geometry;
+#[doc(inline)]
+pub use point_cloud_geometry::*;
+
+mod surfels;
+#[doc(inline)]
+pub use surfels::*;
+
 mod particles;
 #[doc(inline)]
 pub use particles::*;
 
+mod shells;
+#[doc(inline)]
+pub use shells::*;
+
+mod displaced_mesh;
+#[doc(inline)]
+pub use displaced_mesh::*;
+
+mod skinned_mesh;
+#[doc(inline)]
+pub use skinned_mesh::*;
+
 mod bounding_box;
 #[doc(inline)]
 pub use bounding_box::*;
@@ -70,6 +105,10 @@ mod line;
 #[doc(inline)]
 pub use line::*;
 
+mod polyline;
+#[doc(inline)]
+pub use polyline::*;
+
 mod rectangle;
 #[doc(inline)]
 pub use rectangle::*;
@@ -78,6 +117,14 @@ mod circle;
 #[doc(inline)]
 pub use circle::*;
 
+mod shape2d;
+#[doc(inline)]
+pub use shape2d::*;
+
+mod sweep;
+#[doc(inline)]
+pub use sweep::*;
+
 use crate::core::*;
 use crate::renderer::*;
 
@@ -97,11 +144,19 @@ pub use three_d_asset::{
 /// - tangent: `out vec3 tang;`
 /// - bitangent: `out vec3 bitang;`
 /// - uv coordinates: `out vec2 uvs;` (must be flipped in v compared to standard uv coordinates, ie. do `uvs = vec2(uvs.x, 1.0 - uvs.y);` in the vertex shader or do the flip before constructing the uv coordinates vertex buffer)
-/// - color: `out vec4 col;`
+/// - color: `out vec4 col;` (must be in linear sRGB color space, see [SrgbaExt])
+/// - joint indices and weights: `out vec4 joints;` and `out vec4 weights;` (the indices and weights of the up to four joints influencing the vertex)
+/// - lightmap uv coordinates: `out vec2 lightmap_uvs;` (a second set of uv coordinates used for sampling a lightmap, flipped in v the same way as the uv coordinates)
 ///
 /// In addition, for the geometry to be pickable using the [pick] or [ray_intersect] methods (ie. combined with the [IntersectionMaterial]),
 /// it needs to support `flat out int instance_id;`. Simply set it to the built-in glsl variable: `gl_InstanceID`.
 ///
+/// Shadow maps are generated by rendering the geometry with a [DepthMaterial] using [Geometry::render_with_material],
+/// so the depth is rendered with the same vertex shader and the same [Geometry::draw] as when rendering with any other material.
+/// Therefore all vertex transformations, like skinning, morphing, displacement and instancing, must be done in the vertex shader source and in the draw call,
+/// and not depend on the material, otherwise the shadow does not match the rendered geometry.
+/// Materials with alpha cutout are supported in the shadow pass through [Geometry::alpha_cutout].
+///
 pub trait Geometry {
     ///
     /// Draw this geometry.
@@ -152,6 +207,25 @@ pub trait Geometry {
     ///
     fn aabb(&self) -> AxisAlignedBoundingBox;
 
+    ///
+    /// Returns the alpha cutout of the material this geometry is rendered with, if any, see [Material::alpha_cutout].
+    /// It is used to discard the same parts of the geometry when only the depth is rendered, for example into a shadow map.
+    /// The default implementation returns `None`, since a geometry does not have a material, whereas a [Gm] returns the alpha cutout of its material.
+    ///
+    fn alpha_cutout(&self) -> Option<AlphaCutout> {
+        None
+    }
+
+    ///
+    /// Returns the face culling of the material this geometry is rendered with, if any, relative to counter-clockwise frontfacing triangles,
+    /// see [RenderStates::cull] and [RenderStates::front_face].
+    /// It is used to cull the same triangles when only the depth is rendered, for example into a shadow map.
+    /// The default implementation returns `None`, in which case no triangles are culled, whereas a [Gm] returns the culling of its material.
+    ///
+    fn cull(&self) -> Option<Cull> {
+        None
+    }
+
     ///
     /// For updating the animation of this geometry if it is animated, if not, this method does nothing.
     /// The time parameter should be some continious time, for example the time since start.
@@ -172,15 +246,19 @@ impl<T: Geometry + ?Sized> Geometry for &mut T {
     }
 }
 
-impl<T: Geometry> Geometry for Box<T> {
+impl<T: Geometry + ?Sized> Geometry for Box<T> {
     impl_geometry_body!(as_ref);
+
+    fn animate(&mut self, time: f32) {
+        self.as_mut().animate(time)
+    }
 }
 
-impl<T: Geometry> Geometry for std::rc::Rc<T> {
+impl<T: Geometry + ?Sized> Geometry for std::rc::Rc<T> {
     impl_geometry_body!(as_ref);
 }
 
-impl<T: Geometry> Geometry for std::sync::Arc<T> {
+impl<T: Geometry + ?Sized> Geometry for std::sync::Arc<T> {
     impl_geometry_body!(as_ref);
 }
 
@@ -237,6 +315,14 @@ impl<T: Geometry> Geometry for std::sync::RwLock<T> {
         self.read().unwrap().aabb()
     }
 
+    fn alpha_cutout(&self) -> Option<AlphaCutout> {
+        self.read().unwrap().alpha_cutout()
+    }
+
+    fn cull(&self) -> Option<Cull> {
+        self.read().unwrap().cull()
+    }
+
     fn animate(&mut self, time: f32) {
         self.write().unwrap().animate(time)
     }
@@ -265,6 +351,36 @@ struct BaseMesh {
     tangents: Option<VertexBuffer<Vec4>>,
     uvs: Option<VertexBuffer<Vec2>>,
     colors: Option<VertexBuffer<Vec4>>,
+    joints: Option<VertexBuffer<Vec4>>,
+    weights: Option<VertexBuffer<Vec4>>,
+    lightmap_uvs: Option<VertexBuffer<Vec2>>,
+    // The morph targets are never edited, only replaced, so they are shared between copies of the mesh
+    morph_targets: Option<std::sync::Arc<mesh::MorphTargets>>,
+}
+
+impl Clone for BaseMesh {
+    ///
+    /// Returns a copy of the mesh with new vertex and index buffers, where the data is copied on the GPU.
+    ///
+    fn clone(&self) -> Self {
+        Self {
+            indices: match &self.indices {
+                IndexBuffer::U8(buffer) => IndexBuffer::U8(buffer.copy()),
+                IndexBuffer::U16(buffer) => IndexBuffer::U16(buffer.copy()),
+                IndexBuffer::U32(buffer) => IndexBuffer::U32(buffer.copy()),
+                IndexBuffer::None => IndexBuffer::None,
+            },
+            positions: self.positions.copy(),
+            normals: self.normals.as_ref().map(|buffer| buffer.copy()),
+            tangents: self.tangents.as_ref().map(|buffer| buffer.copy()),
+            uvs: self.uvs.as_ref().map(|buffer| buffer.copy()),
+            colors: self.colors.as_ref().map(|buffer| buffer.copy()),
+            joints: self.joints.as_ref().map(|buffer| buffer.copy()),
+            weights: self.weights.as_ref().map(|buffer| buffer.copy()),
+            lightmap_uvs: self.lightmap_uvs.as_ref().map(|buffer| buffer.copy()),
+            morph_targets: self.morph_targets.clone(),
+        }
+    }
 }
 
 impl BaseMesh {
@@ -303,27 +419,68 @@ impl BaseMesh {
                     &data.iter().map(|c| c.to_linear_srgb()).collect::<Vec<_>>(),
                 )
             }),
+            joints: None,
+            weights: None,
+            lightmap_uvs: None,
+            morph_targets: None,
         }
     }
 
     pub fn draw(&self, program: &Program, render_states: RenderStates, viewer: &dyn Viewer) {
+        self.draw_subset(program, render_states, viewer, 0..self.element_count());
+    }
+
+    ///
+    /// Draws the elements in the given range, ie. the indices if the mesh is indexed, otherwise the vertices.
+    ///
+    pub fn draw_subset(
+        &self,
+        program: &Program,
+        render_states: RenderStates,
+        viewer: &dyn Viewer,
+        range: std::ops::Range<u32>,
+    ) {
         self.use_attributes(program);
 
+        let first = range.start;
+        let count = range.end.saturating_sub(range.start);
         match &self.indices {
-            IndexBuffer::None => program.draw_arrays(
+            IndexBuffer::None => {
+                program.draw_subset_of_arrays(render_states, viewer.viewport(), first, count)
+            }
+            IndexBuffer::U8(element_buffer) => program.draw_subset_of_elements(
                 render_states,
                 viewer.viewport(),
-                self.positions.vertex_count(),
+                element_buffer,
+                first,
+                count,
+            ),
+            IndexBuffer::U16(element_buffer) => program.draw_subset_of_elements(
+                render_states,
+                viewer.viewport(),
+                element_buffer,
+                first,
+                count,
+            ),
+            IndexBuffer::U32(element_buffer) => program.draw_subset_of_elements(
+                render_states,
+                viewer.viewport(),
+                element_buffer,
+                first,
+                count,
             ),
-            IndexBuffer::U8(element_buffer) => {
-                program.draw_elements(render_states, viewer.viewport(), element_buffer)
-            }
-            IndexBuffer::U16(element_buffer) => {
-                program.draw_elements(render_states, viewer.viewport(), element_buffer)
-            }
-            IndexBuffer::U32(element_buffer) => {
-                program.draw_elements(render_states, viewer.viewport(), element_buffer)
-            }
+        }
+    }
+
+    ///
+    /// Returns the number of indices if the mesh is indexed, otherwise the number of vertices.
+    ///
+    pub fn element_count(&self) -> u32 {
+        match &self.indices {
+            IndexBuffer::None => self.positions.vertex_count(),
+            IndexBuffer::U8(element_buffer) => element_buffer.count(),
+            IndexBuffer::U16(element_buffer) => element_buffer.count(),
+            IndexBuffer::U32(element_buffer) => element_buffer.count(),
         }
     }
 
@@ -364,6 +521,52 @@ impl BaseMesh {
         }
     }
 
+    ///
+    /// Same as [BaseMesh::draw_instanced] except only the indices in the given range are drawn, so the mesh must be indexed.
+    ///
+    pub fn draw_subset_instanced(
+        &self,
+        program: &Program,
+        render_states: RenderStates,
+        viewer: &dyn Viewer,
+        range: std::ops::Range<u32>,
+        instance_count: u32,
+    ) {
+        self.use_attributes(program);
+
+        let first = range.start;
+        let count = range.end.saturating_sub(range.start);
+        match &self.indices {
+            IndexBuffer::None => {
+                unreachable!("a subset of a non-indexed mesh cannot be drawn instanced")
+            }
+            IndexBuffer::U8(element_buffer) => program.draw_subset_of_elements_instanced(
+                render_states,
+                viewer.viewport(),
+                element_buffer,
+                first,
+                count,
+                instance_count,
+            ),
+            IndexBuffer::U16(element_buffer) => program.draw_subset_of_elements_instanced(
+                render_states,
+                viewer.viewport(),
+                element_buffer,
+                first,
+                count,
+                instance_count,
+            ),
+            IndexBuffer::U32(element_buffer) => program.draw_subset_of_elements_instanced(
+                render_states,
+                viewer.viewport(),
+                element_buffer,
+                first,
+                count,
+                instance_count,
+            ),
+        }
+    }
+
     fn use_attributes(&self, program: &Program) {
         program.use_vertex_attribute("position", &self.positions);
 
@@ -390,11 +593,28 @@ impl BaseMesh {
                 program.use_vertex_attribute("color", colors);
             }
         }
+
+        if program.requires_attribute("joint_indices") {
+            if let Some((joints, weights)) = self.joints.as_ref().zip(self.weights.as_ref()) {
+                program.use_vertex_attribute("joint_indices", joints);
+                program.use_vertex_attribute("joint_weights", weights);
+            }
+        }
+
+        if program.requires_attribute("lightmap_uv_coordinates") {
+            if let Some(lightmap_uvs) = &self.lightmap_uvs {
+                program.use_vertex_attribute("lightmap_uv_coordinates", lightmap_uvs);
+            }
+        }
+    }
+
+    fn has_joint_weights(&self) -> bool {
+        self.joints.is_some() && self.weights.is_some()
     }
 
     fn vertex_shader_source(&self) -> String {
         format!(
-            "{}{}{}{}{}{}",
+            "{}{}{}{}{}{}{}{}",
             if self.normals.is_some() {
                 "#define USE_NORMALS\n"
             } else {
@@ -415,7 +635,23 @@ impl BaseMesh {
             } else {
                 ""
             },
-            include_str!("../core/shared.frag"),
+            if self.has_joint_weights() {
+                "#define USE_JOINT_WEIGHTS\n"
+            } else {
+                ""
+            },
+            if self.lightmap_uvs.is_some() {
+                "#define USE_LIGHTMAP_UVS\n"
+            } else {
+                ""
+            },
+            if self.morph_targets.is_some() {
+                format!(
+                    "#define USE_MORPH_TARGETS\n#define MAX_MORPH_TARGETS {MAX_MORPH_TARGETS}\n"
+                )
+            } else {
+                String::new()
+            },
             include_str!("geometry/shaders/mesh.vert"),
         )
     }
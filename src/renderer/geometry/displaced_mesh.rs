@@ -0,0 +1,199 @@
+use crate::core::*;
+use crate::renderer::*;
+
+///
+/// A triangle mesh where each vertex is offset along its normal by the height sampled from a texture in the vertex shader,
+/// for example a subdivided plane displaced into terrain or waves, which can be changed every frame without uploading new vertex data.
+///
+/// The height is sampled from the red channel of the [DisplacedMesh::height_texture] at the uv coordinates of the vertex,
+/// multiplied by the [DisplacedMesh::scale] and added along the normal before the transformation of the mesh is applied.
+/// The displaced position is used by any material, also when rendering shadow maps, since the displacement is done by the geometry.
+/// Only the vertices are displaced, so the mesh must be subdivided enough to show the details of the height texture.
+///
+pub struct DisplacedMesh {
+    context: Context,
+    mesh: Mesh,
+    aabb: AxisAlignedBoundingBox,
+    uv_scale: f32,
+    /// The texture with the height of each point on the surface in the red channel, which is sampled using the uv coordinates of the mesh.
+    /// The heights are expected to be in the range `[0..1]`, which is the range used to expand the bounding box of the mesh.
+    pub height_texture: Texture2DRef,
+    /// The displacement in local space of a height of 1.
+    pub scale: f32,
+    /// Whether to compute the normals from the gradient of the heights instead of using the normals of the mesh, which is needed for the lighting to follow the displacement.
+    /// The gradient is computed in the vertex shader using the tangents of the mesh and assumes that the distance in local space per unit of the uv coordinates is the same everywhere,
+    /// as in for example a subdivided plane.
+    pub normals_from_height: bool,
+}
+
+impl DisplacedMesh {
+    ///
+    /// Creates a new displaced mesh from the given [CpuMesh] which is displaced by the heights in the given texture multiplied by the given scale.
+    /// The normals and tangents are computed if the mesh does not have them.
+    ///
+    /// # Panic
+    /// Will panic if the mesh does not have uv coordinates.
+    ///
+    pub fn new(
+        context: &Context,
+        cpu_mesh: &CpuMesh,
+        height_texture: Texture2DRef,
+        scale: f32,
+    ) -> Self {
+        let uvs = cpu_mesh
+            .uvs
+            .as_ref()
+            .expect("a displaced mesh must have uv coordinates");
+        let mut cpu_mesh = cpu_mesh.clone();
+        if cpu_mesh.normals.is_none() {
+            cpu_mesh.compute_normals();
+        }
+        if cpu_mesh.tangents.is_none() {
+            cpu_mesh.compute_tangents();
+        }
+
+        // The average distance in local space per unit of the uv coordinates along the edges of the triangles
+        let positions = cpu_mesh.positions.to_f32();
+        let mut distance = 0.0;
+        let mut uv_distance = 0.0;
+        cpu_mesh.for_each_triangle(|i0, i1, i2| {
+            for (a, b) in [(i0, i1), (i1, i2), (i2, i0)] {
+                distance += positions[a].distance(positions[b]);
+                uv_distance += uvs[a].distance(uvs[b]);
+            }
+        });
+        let uv_scale = if uv_distance > 0.0 {
+            distance / uv_distance
+        } else {
+            1.0
+        };
+
+        Self {
+            context: context.clone(),
+            mesh: Mesh::new(context, &cpu_mesh),
+            aabb: cpu_mesh.compute_aabb(),
+            uv_scale,
+            height_texture,
+            scale,
+            normals_from_height: true,
+        }
+    }
+
+    ///
+    /// Returns the local to world transformation applied to this mesh.
+    ///
+    pub fn transformation(&self) -> Mat4 {
+        self.mesh.transformation()
+    }
+
+    ///
+    /// Set the local to world transformation applied to this mesh, which is applied after the displacement.
+    ///
+    pub fn set_transformation(&mut self, transformation: Mat4) {
+        self.mesh.set_transformation(transformation);
+    }
+
+    ///
+    /// Specifies a function which takes a time parameter as input and returns a transformation that should be applied to this mesh at the given time,
+    /// see [Mesh::set_animation].
+    ///
+    pub fn set_animation(&mut self, animation: impl Fn(f32) -> Mat4 + Send + Sync + 'static) {
+        self.mesh.set_animation(animation);
+    }
+}
+
+impl<'a> IntoIterator for &'a DisplacedMesh {
+    type Item = &'a dyn Geometry;
+    type IntoIter = std::iter::Once<&'a dyn Geometry>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        std::iter::once(self)
+    }
+}
+
+impl Geometry for DisplacedMesh {
+    fn id(&self) -> GeometryId {
+        let base_mesh = self.mesh.base_mesh();
+        GeometryId::DisplacedMesh(
+            base_mesh.colors.is_some(),
+            base_mesh.lightmap_uvs.is_some(),
+            self.normals_from_height,
+        )
+    }
+
+    fn vertex_shader_source(&self) -> String {
+        format!(
+            "#define DISPLACEMENT\n{}{}",
+            if self.normals_from_height {
+                "#define DISPLACEMENT_NORMALS\n"
+            } else {
+                ""
+            },
+            self.mesh.vertex_shader_source()
+        )
+    }
+
+    fn draw(&self, viewer: &dyn Viewer, program: &Program, render_states: RenderStates) {
+        program.use_texture("displacementTexture", &self.height_texture);
+        program.use_uniform(
+            "displacementTexTransform",
+            self.height_texture.transformation,
+        );
+        program.use_uniform("displacementScale", self.scale);
+        program.use_uniform_if_required(
+            "displacementTexelSize",
+            vec2(
+                1.0 / self.height_texture.width() as f32,
+                1.0 / self.height_texture.height() as f32,
+            ),
+        );
+        program.use_uniform_if_required("displacementUvScale", self.uv_scale);
+        self.mesh.draw(viewer, program, render_states);
+    }
+
+    fn aabb(&self) -> AxisAlignedBoundingBox {
+        // The vertices are at most offset the scale along the normal
+        let mut aabb = self.aabb;
+        if !aabb.is_empty() {
+            let reach = self.scale.abs() * vec3(1.0, 1.0, 1.0);
+            aabb.expand(&[aabb.min() - reach, aabb.max() + reach]);
+        }
+        aabb.transformed(self.mesh.local_to_world())
+    }
+
+    fn animate(&mut self, time: f32) {
+        self.mesh.animate(time);
+    }
+
+    fn render_with_material(
+        &self,
+        material: &dyn Material,
+        viewer: &dyn Viewer,
+        lights: &[&dyn Light],
+    ) {
+        if let Err(e) = render_with_material(&self.context, viewer, &self, material, lights) {
+            panic!("{}", e.to_string());
+        }
+    }
+
+    fn render_with_effect(
+        &self,
+        material: &dyn Effect,
+        viewer: &dyn Viewer,
+        lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) {
+        if let Err(e) = render_with_effect(
+            &self.context,
+            viewer,
+            self,
+            material,
+            lights,
+            color_texture,
+            depth_texture,
+        ) {
+            panic!("{}", e.to_string());
+        }
+    }
+}
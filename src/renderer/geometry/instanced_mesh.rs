@@ -4,17 +4,38 @@ use std::sync::RwLock;
 
 use super::BaseMesh;
 
+///
+/// Specifies how the transformation of each instance in an [InstancedMesh] is stored on the GPU.
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum InstanceLayout {
+    /// The full transformation of each instance is stored (48 bytes per instance), which supports any affine transformation.
+    #[default]
+    Transformation,
+    /// The position and uniform scale (16 bytes per instance) and, if the instances are rotated, the rotation (another 16 bytes per instance) of each instance is stored
+    /// and the transformation is reconstructed in the vertex shader.
+    /// If the instances are specified by [Instances::transformations], these are decomposed into position, uniform scale and rotation,
+    /// which is only correct if the transformations do not contain any non-uniform scaling or shearing.
+    PositionScaleRotation,
+}
+
+enum InstanceTransformBuffers {
+    Transformation(
+        InstanceBuffer<Vec4>,
+        InstanceBuffer<Vec4>,
+        InstanceBuffer<Vec4>,
+    ),
+    PositionScaleRotation(InstanceBuffer<Vec4>, Option<InstanceBuffer<Vec4>>),
+}
+
 ///
 /// Similar to [Mesh], except it is possible to render many instances of the same mesh efficiently.
 ///
 pub struct InstancedMesh {
     context: Context,
     base_mesh: BaseMesh,
-    transform: RwLock<(
-        InstanceBuffer<Vec4>,
-        InstanceBuffer<Vec4>,
-        InstanceBuffer<Vec4>,
-    )>,
+    instance_layout: InstanceLayout,
+    transform: RwLock<InstanceTransformBuffers>,
     indices: RwLock<Vec<usize>>,
     tex_transform: RwLock<Option<(InstanceBuffer<Vec3>, InstanceBuffer<Vec3>)>>,
     instance_color: RwLock<Option<InstanceBuffer<Vec4>>>,
@@ -31,6 +52,8 @@ impl InstancedMesh {
     /// Creates a new instanced 3D mesh from the given [CpuMesh].
     /// All data in the [CpuMesh] is transfered to the GPU, so make sure to remove all unnecessary data from the [CpuMesh] before calling this method.
     /// The model is rendered in as many instances as there are attributes in [Instances] given as input.
+    /// The [InstanceLayout::PositionScaleRotation] layout is used if the instances are specified by [Instances::positions], otherwise the [InstanceLayout::Transformation] layout is used,
+    /// see [Self::set_instance_layout].
     ///
     pub fn new(context: &Context, instances: &Instances, cpu_mesh: &CpuMesh) -> Self {
         #[cfg(debug_assertions)]
@@ -40,7 +63,12 @@ impl InstancedMesh {
         let instanced_mesh = Self {
             context: context.clone(),
             base_mesh: BaseMesh::new(context, cpu_mesh),
-            transform: RwLock::new((
+            instance_layout: if instances.positions.is_some() {
+                InstanceLayout::PositionScaleRotation
+            } else {
+                InstanceLayout::Transformation
+            },
+            transform: RwLock::new(InstanceTransformBuffers::Transformation(
                 InstanceBuffer::<Vec4>::new(context),
                 InstanceBuffer::<Vec4>::new(context),
                 InstanceBuffer::<Vec4>::new(context),
@@ -48,7 +76,7 @@ impl InstancedMesh {
             tex_transform: RwLock::new(None),
             instance_color: RwLock::new(None),
             last_camera_position: RwLock::new(None),
-            indices: RwLock::new((0..instances.transformations.len()).collect::<Vec<usize>>()),
+            indices: RwLock::new((0..instances.count() as usize).collect::<Vec<usize>>()),
             aabb,
             transformation: Mat4::identity(),
             animation_transformation: Mat4::identity(),
@@ -97,32 +125,106 @@ impl InstancedMesh {
         #[cfg(debug_assertions)]
         instances.validate().expect("invalid instances");
         self.instances = instances.clone();
-        *self.indices.write().unwrap() =
-            (0..instances.transformations.len()).collect::<Vec<usize>>();
+        *self.indices.write().unwrap() = (0..instances.count() as usize).collect::<Vec<usize>>();
         *self.last_camera_position.write().unwrap() = None;
 
         self.update_instance_buffers();
     }
 
     ///
-    /// This function updates the instance buffers, so the instances are rendered in the order given by the indices
+    /// Updates only the positions of the instances, which is cheaper than [Self::set_instances] when using the [InstanceLayout::PositionScaleRotation] layout
+    /// since only the positions and scales are transfered to the GPU.
+    /// If the instances are specified by [Instances::transformations], the translation part of the transformations is replaced.
+    /// The number of positions must be equal to the number of instances.
     ///
-    fn update_instance_buffers(&self) {
+    pub fn set_instance_positions(&mut self, positions: &[Vec3]) {
+        debug_assert_eq!(positions.len(), self.instance_count() as usize);
+        if let Some(instance_positions) = &mut self.instances.positions {
+            instance_positions.copy_from_slice(positions);
+        } else {
+            for (transformation, position) in
+                self.instances.transformations.iter_mut().zip(positions)
+            {
+                transformation.w = position.extend(1.0);
+            }
+        }
+        *self.last_camera_position.write().unwrap() = None;
+
         let indices = self.indices.read().unwrap();
-        let mut row1 = Vec::new();
-        let mut row2 = Vec::new();
-        let mut row3 = Vec::new();
-        for transformation in indices.iter().map(|i| self.instances.transformations[*i]) {
-            row1.push(transformation.row(0));
-            row2.push(transformation.row(1));
-            row3.push(transformation.row(2));
+        if let InstanceTransformBuffers::PositionScaleRotation(position_scale, _) =
+            &mut *self.transform.write().unwrap()
+        {
+            position_scale.fill(
+                &indices
+                    .iter()
+                    .map(|i| {
+                        let (position, scale, _) = self.instances.position_scale_rotation(*i);
+                        position.extend(scale)
+                    })
+                    .collect::<Vec<_>>(),
+            );
+            return;
+        }
+        drop(indices);
+        self.update_instance_buffers();
+    }
+
+    ///
+    /// Returns how the transformation of each instance is stored on the GPU.
+    ///
+    pub fn instance_layout(&self) -> InstanceLayout {
+        self.instance_layout
+    }
+
+    ///
+    /// Sets how the transformation of each instance is stored on the GPU, see [InstanceLayout].
+    ///
+    pub fn set_instance_layout(&mut self, instance_layout: InstanceLayout) {
+        if self.instance_layout != instance_layout {
+            self.instance_layout = instance_layout;
+            self.update_instance_buffers();
         }
+    }
 
-        *self.transform.write().unwrap() = (
-            InstanceBuffer::new_with_data(&self.context, &row1),
-            InstanceBuffer::new_with_data(&self.context, &row2),
-            InstanceBuffer::new_with_data(&self.context, &row3),
-        );
+    ///
+    /// This function updates the instance buffers, so the instances are rendered in the order given by the indices
+    ///
+    fn update_instance_buffers(&self) {
+        let indices = self.indices.read().unwrap();
+        *self.transform.write().unwrap() = match self.instance_layout {
+            InstanceLayout::Transformation => {
+                let mut row1 = Vec::new();
+                let mut row2 = Vec::new();
+                let mut row3 = Vec::new();
+                for transformation in indices.iter().map(|i| self.instances.transformation(*i)) {
+                    row1.push(transformation.row(0));
+                    row2.push(transformation.row(1));
+                    row3.push(transformation.row(2));
+                }
+                InstanceTransformBuffers::Transformation(
+                    InstanceBuffer::new_with_data(&self.context, &row1),
+                    InstanceBuffer::new_with_data(&self.context, &row2),
+                    InstanceBuffer::new_with_data(&self.context, &row3),
+                )
+            }
+            InstanceLayout::PositionScaleRotation => {
+                let mut position_scale = Vec::new();
+                let mut rotation = Vec::new();
+                for (position, scale, r) in indices
+                    .iter()
+                    .map(|i| self.instances.position_scale_rotation(*i))
+                {
+                    position_scale.push(position.extend(scale));
+                    rotation.push(r.v.extend(r.s));
+                }
+                let is_rotated =
+                    self.instances.positions.is_none() || self.instances.rotations.is_some();
+                InstanceTransformBuffers::PositionScaleRotation(
+                    InstanceBuffer::new_with_data(&self.context, &position_scale),
+                    is_rotated.then(|| InstanceBuffer::new_with_data(&self.context, &rotation)),
+                )
+            }
+        };
 
         *self.tex_transform.write().unwrap() =
             self.instances
@@ -180,12 +282,11 @@ impl Geometry for InstancedMesh {
                 .unwrap_or(true)
         {
             *self.last_camera_position.write().unwrap() = Some(viewer.position());
-            let distances = self
-                .instances
-                .transformations
-                .iter()
-                .map(|m| {
-                    (self.transformation * m * self.animation_transformation)
+            let distances = (0..self.instances.count() as usize)
+                .map(|i| {
+                    (self.transformation
+                        * self.instances.transformation(i)
+                        * self.animation_transformation)
                         .w
                         .truncate()
                         .distance2(viewer.position())
@@ -203,10 +304,19 @@ impl Geometry for InstancedMesh {
         program.use_uniform("animationTransform", self.animation_transformation);
         program.use_uniform("modelMatrix", self.transformation);
 
-        let (row1, row2, row3) = &*self.transform.read().unwrap();
-        program.use_instance_attribute("row1", row1);
-        program.use_instance_attribute("row2", row2);
-        program.use_instance_attribute("row3", row3);
+        match &*self.transform.read().unwrap() {
+            InstanceTransformBuffers::Transformation(row1, row2, row3) => {
+                program.use_instance_attribute("row1", row1);
+                program.use_instance_attribute("row2", row2);
+                program.use_instance_attribute("row3", row3);
+            }
+            InstanceTransformBuffers::PositionScaleRotation(position_scale, rotation) => {
+                program.use_instance_attribute("instance_position_scale", position_scale);
+                if let Some(rotation) = rotation {
+                    program.use_instance_attribute("instance_rotation", rotation);
+                }
+            }
+        }
 
         if program.requires_attribute("tex_transform_row1") {
             if let Some((row1, row2)) = &*self.tex_transform.read().unwrap() {
@@ -227,7 +337,16 @@ impl Geometry for InstancedMesh {
 
     fn vertex_shader_source(&self) -> String {
         format!(
-            "#define USE_INSTANCE_TRANSFORMS\n{}{}{}",
+            "#define USE_INSTANCE_TRANSFORMS\n{}{}{}{}",
+            match &*self.transform.read().unwrap() {
+                InstanceTransformBuffers::Transformation(..) => "",
+                InstanceTransformBuffers::PositionScaleRotation(_, None) => {
+                    "#define USE_INSTANCE_POSITION_SCALE\n"
+                }
+                InstanceTransformBuffers::PositionScaleRotation(_, Some(_)) => {
+                    "#define USE_INSTANCE_POSITION_SCALE\n#define USE_INSTANCE_ROTATIONS\n"
+                }
+            },
             if self.instance_color.read().unwrap().is_some() {
                 "#define USE_INSTANCE_COLORS\n"
             } else {
@@ -243,6 +362,12 @@ impl Geometry for InstancedMesh {
     }
 
     fn id(&self) -> GeometryId {
+        let (position_scale, rotations) = match &*self.transform.read().unwrap() {
+            InstanceTransformBuffers::Transformation(..) => (false, false),
+            InstanceTransformBuffers::PositionScaleRotation(_, rotation) => {
+                (true, rotation.is_some())
+            }
+        };
         GeometryId::InstancedMesh(
             self.base_mesh.normals.is_some(),
             self.base_mesh.tangents.is_some(),
@@ -250,14 +375,18 @@ impl Geometry for InstancedMesh {
             self.base_mesh.colors.is_some(),
             self.instance_color.read().unwrap().is_some(),
             self.tex_transform.read().unwrap().is_some(),
+            position_scale,
+            rotations,
         )
     }
 
     fn aabb(&self) -> AxisAlignedBoundingBox {
         let mut aabb = AxisAlignedBoundingBox::EMPTY;
-        for instance_transformation in &self.instances.transformations {
+        for i in 0..self.instances.count() as usize {
             aabb.expand_with_aabb(self.aabb.transformed(
-                self.transformation * instance_transformation * self.animation_transformation,
+                self.transformation
+                    * self.instances.transformation(i)
+                    * self.animation_transformation,
             ));
         }
         aabb
@@ -310,14 +439,26 @@ impl Geometry for InstancedMesh {
 /// The attributes are applied to each instance before they are rendered.
 /// The [Instances::transformations] are applied after the transformation applied to all instances (see [InstancedMesh::set_transformation]).
 ///
+/// Alternatively, the transformation of each instance can be specified by a position and optionally a uniform scale and a rotation,
+/// which requires less memory and is uploaded more compactly to the GPU (see [InstanceLayout::PositionScaleRotation]).
+///
 #[derive(Clone, Debug, Default)]
 pub struct Instances {
     /// The transformations applied to each instance.
     /// This transformation is applied in between the animation transformation defined by [InstancedMesh::set_animation] and the transformation defined in [InstancedMesh::set_transformation].
+    /// Ignored if [Self::positions] is specified.
     pub transformations: Vec<Mat4>,
+    /// The positions of each instance. If specified, the transformation of each instance is the translation to this position
+    /// applied after the [Self::rotations] and [Self::scales] and the [Self::transformations] are ignored.
+    pub positions: Option<Vec<Vec3>>,
+    /// The uniform scale of each instance. Only used if [Self::positions] is specified.
+    pub scales: Option<Vec<f32>>,
+    /// The rotation of each instance. Only used if [Self::positions] is specified.
+    pub rotations: Option<Vec<Quat>>,
     /// The texture transform applied to the uv coordinates of each instance.
     pub texture_transformations: Option<Vec<Mat3>>,
     /// Colors multiplied onto the base color of each instance.
+    /// The colors are converted from sRGB to linear sRGB before they are transfered to the GPU, like all other [Srgba] colors, see [SrgbaExt].
     pub colors: Option<Vec<Srgba>>,
 }
 
@@ -344,7 +485,11 @@ impl Instances {
             self.texture_transformations.as_ref().map(|b| b.len()),
             "texture transformations",
         )?;
-        buffer_check(Some(self.transformations.len()), "transformations")?;
+        if self.positions.is_none() {
+            buffer_check(Some(self.transformations.len()), "transformations")?;
+        }
+        buffer_check(self.scales.as_ref().map(|b| b.len()), "scales")?;
+        buffer_check(self.rotations.as_ref().map(|b| b.len()), "rotations")?;
         buffer_check(self.colors.as_ref().map(|b| b.len()), "colors")?;
 
         Ok(())
@@ -352,7 +497,44 @@ impl Instances {
 
     /// Returns the number of instances.
     pub fn count(&self) -> u32 {
-        self.transformations.len() as u32
+        self.positions
+            .as_ref()
+            .map(|positions| positions.len())
+            .unwrap_or(self.transformations.len()) as u32
+    }
+
+    ///
+    /// Returns the transformation of the instance with the given index,
+    /// either from the [Self::transformations] or constructed from the [Self::positions], [Self::scales] and [Self::rotations].
+    ///
+    pub fn transformation(&self, index: usize) -> Mat4 {
+        if self.positions.is_some() {
+            let (position, scale, rotation) = self.position_scale_rotation(index);
+            Mat4::from_translation(position) * Mat4::from(rotation) * Mat4::from_scale(scale)
+        } else {
+            self.transformations[index]
+        }
+    }
+
+    ///
+    /// Returns the position, uniform scale and rotation of the instance with the given index,
+    /// which are decomposed from the transformation if the instances are specified by [Self::transformations].
+    ///
+    fn position_scale_rotation(&self, index: usize) -> (Vec3, f32, Quat) {
+        if let Some(positions) = &self.positions {
+            (
+                positions[index],
+                self.scales.as_ref().map(|s| s[index]).unwrap_or(1.0),
+                self.rotations
+                    .as_ref()
+                    .map(|r| r[index])
+                    .unwrap_or(Quat::one()),
+            )
+        } else {
+            let (position, rotation, scale) = decompose(self.transformations[index]);
+            // A mirrored transformation has a negative scale along x, which would flip the instance along all axes when used as a uniform scale
+            (position, scale.x.abs(), rotation)
+        }
     }
 }
 
@@ -1,18 +1,23 @@
+use super::shape2d::{render_shape, ShapeParameters};
 use crate::renderer::*;
 
 ///
-/// A rectangle 2D geometry which can be rendered using a camera created by [Camera::new_2d].
+/// A line 2D geometry which can be rendered using a camera created by [Camera::new_2d] or [Camera::new_2d_logical].
 ///
 pub struct Line {
+    context: Context,
     mesh: Mesh,
     pixel0: PhysicalPoint,
     pixel1: PhysicalPoint,
     thickness: f32,
+    cap: LineCap,
+    dash_pattern: Option<(f32, f32)>,
+    device_pixel_ratio: f32,
 }
 
 impl Line {
     ///
-    /// Constructs a new line geometry.
+    /// Constructs a new line geometry with the given thickness in logical pixels.
     ///
     pub fn new(
         context: &Context,
@@ -21,13 +26,16 @@ impl Line {
         thickness: f32,
     ) -> Self {
         let mut mesh = CpuMesh::square();
-        mesh.transform(Mat4::from_scale(0.5) * Mat4::from_translation(vec3(1.0, 0.0, 0.0)))
-            .unwrap();
+        mesh.transform(Mat4::from_scale(0.5)).unwrap();
         let mut line = Self {
+            context: context.clone(),
             mesh: Mesh::new(context, &mesh),
             pixel0: pixel0.into(),
             pixel1: pixel1.into(),
             thickness,
+            cap: LineCap::Butt,
+            dash_pattern: None,
+            device_pixel_ratio: 1.0,
         };
         line.update();
         line
@@ -58,24 +66,83 @@ impl Line {
         self.update();
     }
 
-    /// Set the line thickness.
+    /// Set the line thickness in logical pixels.
     pub fn set_thickness(&mut self, thickness: f32) {
         self.thickness = thickness;
         self.update();
     }
 
-    fn update(&mut self) {
+    /// Get the line thickness in logical pixels.
+    pub fn thickness(&self) -> f32 {
+        self.thickness
+    }
+
+    /// Set the shape of the end points of the line.
+    pub fn set_cap(&mut self, cap: LineCap) {
+        self.cap = cap;
+        self.update();
+    }
+
+    /// Get the shape of the end points of the line.
+    pub fn cap(&self) -> LineCap {
+        self.cap
+    }
+
+    ///
+    /// Set the dash pattern of the line given as the length of the dashes and the length of the gaps between the dashes in logical pixels.
+    /// Use `None` for a solid line.
+    ///
+    pub fn set_dash_pattern(&mut self, dash_pattern: Option<(f32, f32)>) {
+        self.dash_pattern = dash_pattern;
+    }
+
+    /// Get the dash pattern of the line.
+    pub fn dash_pattern(&self) -> Option<(f32, f32)> {
+        self.dash_pattern
+    }
+
+    ///
+    /// Set the device pixel ratio which is used to convert the thickness and dash pattern from logical to physical pixels.
+    /// The default is 1.
+    ///
+    pub fn set_device_pixel_ratio(&mut self, device_pixel_ratio: f32) {
+        self.device_pixel_ratio = device_pixel_ratio;
+        self.update();
+    }
+
+    fn parameters(&self) -> ShapeParameters {
         let dx = self.pixel1.x - self.pixel0.x;
         let dy = self.pixel1.y - self.pixel0.y;
         let length = (dx * dx + dy * dy).sqrt();
-        let c = dx / length;
-        let s = dy / length;
+        let (c, s) = if length > 0.0 {
+            (dx / length, dy / length)
+        } else {
+            (1.0, 0.0)
+        };
         let rot = Mat3::new(c, s, 0.0, -s, c, 0.0, 0.0, 0.0, 1.0);
-        self.mesh.set_transformation_2d(
-            Mat3::from_translation(self.pixel0.into())
-                * rot
-                * Mat3::from_nonuniform_scale(length, self.thickness),
+        let center = vec2(
+            0.5 * (self.pixel0.x + self.pixel1.x),
+            0.5 * (self.pixel0.y + self.pixel1.y),
         );
+        ShapeParameters {
+            shape_type: 2,
+            line_cap: self.cap,
+            half_size: vec2(0.5 * length, 0.5 * self.thickness * self.device_pixel_ratio),
+            radius: 0.0,
+            stroke_width: 0.0,
+            dash_pattern: self.dash_pattern.map(|(dash, gap)| {
+                (
+                    dash * self.device_pixel_ratio,
+                    gap * self.device_pixel_ratio,
+                )
+            }),
+            transformation: Mat3::from_translation(center) * rot,
+        }
+    }
+
+    fn update(&mut self) {
+        let transformation = self.parameters().quad_transformation();
+        self.mesh.set_transformation_2d(transformation);
     }
 }
 
@@ -103,7 +170,49 @@ impl std::ops::DerefMut for Line {
 }
 
 impl Geometry for Line {
-    impl_geometry_body!(deref);
+    fn draw(&self, viewer: &dyn Viewer, program: &Program, render_states: RenderStates) {
+        self.mesh.draw(viewer, program, render_states)
+    }
+
+    fn vertex_shader_source(&self) -> String {
+        self.mesh.vertex_shader_source()
+    }
+
+    fn id(&self) -> GeometryId {
+        GeometryId::Shape2D(self.mesh.id())
+    }
+
+    fn render_with_material(
+        &self,
+        material: &dyn Material,
+        viewer: &dyn Viewer,
+        lights: &[&dyn Light],
+    ) {
+        render_shape(
+            &self.context,
+            self,
+            self.parameters(),
+            material,
+            viewer,
+            lights,
+        )
+    }
+
+    fn render_with_effect(
+        &self,
+        material: &dyn Effect,
+        viewer: &dyn Viewer,
+        lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) {
+        self.mesh
+            .render_with_effect(material, viewer, lights, color_texture, depth_texture)
+    }
+
+    fn aabb(&self) -> AxisAlignedBoundingBox {
+        self.mesh.aabb()
+    }
 
     fn animate(&mut self, time: f32) {
         self.mesh.animate(time)
@@ -2,17 +2,148 @@ use crate::core::*;
 use crate::renderer::*;
 
 use super::BaseMesh;
+use std::ops::Range;
+use std::sync::Arc;
+
+///
+/// The maximum number of morph targets of a [Mesh], see [Mesh::set_morph_targets].
+///
+pub const MAX_MORPH_TARGETS: usize = 16;
+
+///
+/// A morph target, also called a blend shape, of a [Mesh], for example a facial expression or a corrective shape.
+/// The target is given as the difference to the vertex positions and normals of the mesh, see [Mesh::set_morph_targets].
+///
+#[derive(Clone, Debug, Default)]
+pub struct MorphTarget {
+    /// The difference to the position of each vertex of the mesh.
+    pub position_deltas: Vec<Vec3>,
+    /// The difference to the normal of each vertex of the mesh. Only used if the mesh has normals.
+    pub normal_deltas: Option<Vec<Vec3>>,
+}
+
+///
+/// The morph targets of a mesh on the GPU, stored in a texture array with one texel per vertex
+/// and one layer with the position deltas of each target followed by one layer with the normal deltas of each target if any of the targets have normal deltas.
+///
+pub(super) struct MorphTargets {
+    texture: Texture2DArray,
+    count: usize,
+    has_normals: bool,
+    // The smallest and largest position delta of each target
+    delta_bounds: Vec<(Vec3, Vec3)>,
+}
+
+impl MorphTargets {
+    fn new(
+        context: &Context,
+        targets: &[MorphTarget],
+        vertex_count: usize,
+        has_normals: bool,
+    ) -> Self {
+        let width = vertex_count.clamp(1, 4096);
+        let height = vertex_count.div_ceil(width).max(1);
+        let has_normals = has_normals && targets.iter().any(|t| t.normal_deltas.is_some());
+        let layer = |deltas: Option<&Vec<Vec3>>| {
+            let mut data = vec![[0.0f32; 4]; width * height];
+            if let Some(deltas) = deltas {
+                for (texel, delta) in data.iter_mut().zip(deltas) {
+                    *texel = [delta.x, delta.y, delta.z, 0.0];
+                }
+            }
+            data
+        };
+        let mut layers = targets
+            .iter()
+            .map(|t| layer(Some(&t.position_deltas)))
+            .collect::<Vec<_>>();
+        if has_normals {
+            layers.extend(targets.iter().map(|t| layer(t.normal_deltas.as_ref())));
+        }
+        let mut texture = Texture2DArray::new_empty::<[f32; 4]>(
+            context,
+            width as u32,
+            height as u32,
+            layers.len() as u32,
+            Interpolation::Nearest,
+            Interpolation::Nearest,
+            None,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+        );
+        texture.fill(&layers.iter().map(|l| l.as_slice()).collect::<Vec<_>>());
+        Self {
+            texture,
+            count: targets.len(),
+            has_normals,
+            delta_bounds: targets
+                .iter()
+                .map(|t| {
+                    t.position_deltas
+                        .iter()
+                        .fold((Vec3::zero(), Vec3::zero()), |(min, max), d| {
+                            (
+                                vec3(min.x.min(d.x), min.y.min(d.y), min.z.min(d.z)),
+                                vec3(max.x.max(d.x), max.y.max(d.y), max.z.max(d.z)),
+                            )
+                        })
+                })
+                .collect(),
+        }
+    }
+
+    fn use_uniforms(&self, program: &Program, weights: &[f32]) {
+        let mut morph_weights = [0.0; MAX_MORPH_TARGETS];
+        for (w, weight) in morph_weights.iter_mut().zip(weights) {
+            *w = *weight;
+        }
+        program.use_texture_array("morphTargets", &self.texture);
+        program.use_uniform_array("morphWeights", &morph_weights);
+        program.use_uniform("morphTargetCount", self.count as i32);
+        program.use_uniform_if_required("morphNormals", self.has_normals as i32);
+    }
+
+    // Each morphed position is the position plus the weighted sum of the deltas, so it is inside the bounding box expanded by the weighted bounds of the deltas
+    fn morphed_aabb(
+        &self,
+        aabb: AxisAlignedBoundingBox,
+        weights: &[f32],
+    ) -> AxisAlignedBoundingBox {
+        if aabb.is_empty() {
+            return aabb;
+        }
+        let (mut min, mut max) = (aabb.min(), aabb.max());
+        for ((low, high), weight) in self.delta_bounds.iter().zip(weights) {
+            let (a, b) = (low * *weight, high * *weight);
+            min += vec3(a.x.min(b.x), a.y.min(b.y), a.z.min(b.z));
+            max += vec3(a.x.max(b.x), a.y.max(b.y), a.z.max(b.z));
+        }
+        AxisAlignedBoundingBox::new_with_positions(&[min, max])
+    }
+}
 
 ///
 /// A triangle mesh [Geometry].
 ///
+/// Several meshes can share the same vertex and index buffers on the GPU and each draw a different range of the triangles, see [Mesh::new_with_ranges].
+/// Cloning a mesh is cheap since the clone also shares the vertex and index buffers with the original mesh,
+/// which makes it possible to render many copies with one instanced draw call, see [InstanceBatches].
+/// When the vertex data of a mesh that shares its buffers with other meshes is edited, for example using the `*_mut` methods,
+/// the buffers are first copied on the GPU so the other meshes are not affected.
+///
+/// A mesh can be deformed by morph targets, see [Mesh::set_morph_targets], where each clone has its own morph weights.
+///
+#[derive(Clone)]
 pub struct Mesh {
-    base_mesh: BaseMesh,
+    base_mesh: Arc<BaseMesh>,
+    range: Option<Range<u32>>,
     context: Context,
     aabb: AxisAlignedBoundingBox,
     transformation: Mat4,
+    transformation_f64: Option<DMat4>,
     animation_transformation: Mat4,
-    animation: Option<Box<dyn Fn(f32) -> Mat4 + Send + Sync>>,
+    animation: Option<Arc<dyn Fn(f32) -> Mat4 + Send + Sync>>,
+    morph_weights: Vec<f32>,
 }
 
 impl Mesh {
@@ -24,14 +155,116 @@ impl Mesh {
         let aabb = cpu_mesh.compute_aabb();
         Self {
             context: context.clone(),
-            base_mesh: BaseMesh::new(context, cpu_mesh),
+            base_mesh: Arc::new(BaseMesh::new(context, cpu_mesh)),
+            range: None,
             aabb,
             transformation: Mat4::identity(),
+            transformation_f64: None,
             animation_transformation: Mat4::identity(),
             animation: None,
+            morph_weights: Vec::new(),
         }
     }
 
+    ///
+    /// Creates one triangle mesh for each of the given ranges, which all share the vertex and index buffers constructed from the given [CpuMesh].
+    /// Each mesh only draws the triangles in its range, where a range is given in indices if the [CpuMesh] is indexed, otherwise in vertices.
+    /// This is for example useful for a mesh where different ranges of the triangles use different materials, see [MultiMaterialMesh],
+    /// since the vertex data is only transfered to the GPU once.
+    ///
+    /// **Note:** Editing the vertex data of one of the meshes, for example using the `*_mut` methods, first copies the buffers on the GPU,
+    /// so the edited mesh no longer shares its buffers with the other meshes.
+    ///
+    pub fn new_with_ranges(
+        context: &Context,
+        cpu_mesh: &CpuMesh,
+        ranges: impl IntoIterator<Item = Range<u32>>,
+    ) -> Vec<Self> {
+        Self::new_with_ranges_and_lightmap_uvs(context, cpu_mesh, None, ranges)
+    }
+
+    ///
+    /// Same as [Self::new_with_ranges], but the shared buffers also contain the given lightmap uv coordinates, see [Self::set_lightmap_uvs].
+    ///
+    pub(crate) fn new_with_ranges_and_lightmap_uvs(
+        context: &Context,
+        cpu_mesh: &CpuMesh,
+        lightmap_uvs: Option<&[Vec2]>,
+        ranges: impl IntoIterator<Item = Range<u32>>,
+    ) -> Vec<Self> {
+        let mut base_mesh = BaseMesh::new(context, cpu_mesh);
+        base_mesh.lightmap_uvs = lightmap_uvs.map(|uvs| lightmap_uv_buffer(context, uvs));
+        let base_mesh = Arc::new(base_mesh);
+        let positions = cpu_mesh.positions.to_f32();
+        let indices = cpu_mesh.indices.to_u32();
+        let element_count = base_mesh.element_count();
+        ranges
+            .into_iter()
+            .map(|range| {
+                let range = range.start.min(element_count)..range.end.min(element_count);
+                let mut aabb = AxisAlignedBoundingBox::EMPTY;
+                aabb.expand(
+                    &range
+                        .clone()
+                        .map(|i| match &indices {
+                            Some(indices) => positions[indices[i as usize] as usize],
+                            None => positions[i as usize],
+                        })
+                        .collect::<Vec<_>>(),
+                );
+                Self {
+                    context: context.clone(),
+                    base_mesh: base_mesh.clone(),
+                    range: Some(range),
+                    aabb,
+                    transformation: Mat4::identity(),
+                    transformation_f64: None,
+                    animation_transformation: Mat4::identity(),
+                    animation: None,
+                    morph_weights: Vec::new(),
+                }
+            })
+            .collect()
+    }
+
+    ///
+    /// Returns the range of the triangles drawn by this mesh if it was constructed using [Mesh::new_with_ranges], otherwise `None` in which case all triangles are drawn.
+    /// The range is given in indices if the mesh is indexed, otherwise in vertices.
+    ///
+    pub fn draw_range(&self) -> Option<Range<u32>> {
+        self.range.clone()
+    }
+
+    pub(super) fn base_mesh(&self) -> &Arc<BaseMesh> {
+        &self.base_mesh
+    }
+
+    ///
+    /// Returns the transformation from local to render space including the animation transformation, see [Context::set_world_origin].
+    ///
+    pub(in crate::renderer) fn local_to_world(&self) -> Mat4 {
+        let transformation = match self.transformation_f64 {
+            Some(transformation) => self.context.to_render_space_transformation(transformation),
+            None => self.transformation,
+        };
+        transformation * self.animation_transformation
+    }
+
+    ///
+    /// Returns the buffers of this mesh for editing, where the buffers are first copied if they are shared with other meshes,
+    /// so editing a mesh never changes the other meshes.
+    ///
+    fn base_mesh_mut(&mut self) -> &mut BaseMesh {
+        Arc::make_mut(&mut self.base_mesh)
+    }
+
+    ///
+    /// Returns whether this mesh shares its vertex and index buffers with other meshes.
+    ///
+    pub(in crate::renderer) fn is_shared(&self) -> bool {
+        Arc::strong_count(&self.base_mesh) > 1
+    }
+
     pub(in crate::renderer) fn set_transformation_2d(&mut self, transformation: Mat3) {
         self.set_transformation(Mat4::new(
             transformation.x.x,
@@ -57,7 +290,10 @@ impl Mesh {
     /// Returns the local to world transformation applied to this mesh.
     ///
     pub fn transformation(&self) -> Mat4 {
-        self.transformation
+        match self.transformation_f64 {
+            Some(transformation) => self.context.to_render_space_transformation(transformation),
+            None => self.transformation,
+        }
     }
 
     ///
@@ -66,6 +302,26 @@ impl Mesh {
     ///
     pub fn set_transformation(&mut self, transformation: Mat4) {
         self.transformation = transformation;
+        self.transformation_f64 = None;
+    }
+
+    ///
+    /// Returns the local to world transformation applied to this mesh in double precision, see [Self::set_transformation_f64].
+    ///
+    pub fn transformation_f64(&self) -> DMat4 {
+        self.transformation_f64
+            .unwrap_or_else(|| self.transformation.cast::<f64>().unwrap())
+    }
+
+    ///
+    /// Set the local to world transformation applied to this mesh in double precision, which avoids jitter when the mesh is placed far from the origin.
+    /// The transformation is relative to the origin of world space and is converted to render space, ie. relative to the world origin set by [Context::set_world_origin],
+    /// in double precision before it is sent to the GPU. After calling this method, [Self::transformation] returns the transformation to render space.
+    /// If any animation method is set using [Self::set_animation], the transformation from that method is applied before this transformation.
+    ///
+    pub fn set_transformation_f64(&mut self, transformation: DMat4) {
+        self.transformation_f64 = Some(transformation);
+        self.transformation = transformation.cast::<f32>().unwrap();
     }
 
     ///
@@ -74,7 +330,7 @@ impl Mesh {
     /// This transformation is applied first, then the local to world transformation defined by [Self::set_transformation].
     ///
     pub fn set_animation(&mut self, animation: impl Fn(f32) -> Mat4 + Send + Sync + 'static) {
-        self.animation = Some(Box::new(animation));
+        self.animation = Some(Arc::new(animation));
         self.animate(0.0);
     }
 
@@ -85,12 +341,174 @@ impl Mesh {
         self.base_mesh.positions.vertex_count()
     }
 
+    ///
+    /// Updates the vertex positions by writing into the existing vertex buffer, which is much faster than creating a new mesh,
+    /// for example when deforming the mesh on the CPU every frame.
+    /// The number of positions must be equal to the number of vertices in this mesh.
+    /// The bounding box is not updated, so call [Self::recompute_aabb_from] if the positions move outside the current bounding box.
+    ///
+    pub fn update_positions(&mut self, positions: &[Vec3]) -> Result<(), RendererError> {
+        self.update_positions_partially(0..self.vertex_count(), positions)
+    }
+
+    ///
+    /// Updates the vertex positions as [Self::update_positions], but only the positions in the given range of vertices are written to the vertex buffer.
+    /// The given positions must still contain all the vertices of this mesh, so the range is only a hint of which positions have changed.
+    ///
+    pub fn update_positions_partially(
+        &mut self,
+        range: Range<u32>,
+        positions: &[Vec3],
+    ) -> Result<(), RendererError> {
+        let range = self.validate_update("positions", &range, positions.len())?;
+        self.positions_mut()
+            .fill_subset(range.start as u32, &positions[range]);
+        Ok(())
+    }
+
+    ///
+    /// Updates the vertex normals by writing into the existing vertex buffer, which is much faster than creating a new mesh,
+    /// for example when deforming the mesh on the CPU every frame. If this mesh has no normals, a new normal buffer is created.
+    /// The number of normals must be equal to the number of vertices in this mesh.
+    ///
+    pub fn update_normals(&mut self, normals: &[Vec3]) -> Result<(), RendererError> {
+        self.update_normals_partially(0..self.vertex_count(), normals)
+    }
+
+    ///
+    /// Updates the vertex normals as [Self::update_normals], but only the normals in the given range of vertices are written to the vertex buffer.
+    /// The given normals must still contain all the vertices of this mesh, so the range is only a hint of which normals have changed.
+    ///
+    pub fn update_normals_partially(
+        &mut self,
+        range: Range<u32>,
+        normals: &[Vec3],
+    ) -> Result<(), RendererError> {
+        let range = self.validate_update("normals", &range, normals.len())?;
+        let context = self.context.clone();
+        match self.normals_mut() {
+            Some(buffer) => buffer.fill_subset(range.start as u32, &normals[range]),
+            buffer => *buffer = Some(VertexBuffer::new_with_data(&context, normals)),
+        }
+        Ok(())
+    }
+
+    ///
+    /// Sets the bounding box in local space to the bounding box of the given positions, for example after the positions have been updated using [Self::update_positions].
+    /// If the bounds are already known, it is faster to set the bounding box directly using [Self::set_aabb].
+    ///
+    pub fn recompute_aabb_from(&mut self, positions: &[Vec3]) {
+        self.aabb = AxisAlignedBoundingBox::new_with_positions(positions);
+    }
+
+    ///
+    /// Sets the bounding box in local space, for example after the positions have been updated using [Self::update_positions].
+    ///
+    pub fn set_aabb(&mut self, aabb: AxisAlignedBoundingBox) {
+        self.aabb = aabb;
+    }
+
+    fn validate_update(
+        &self,
+        name: &str,
+        range: &Range<u32>,
+        count: usize,
+    ) -> Result<Range<usize>, RendererError> {
+        let vertex_count = self.vertex_count() as usize;
+        if count != vertex_count {
+            return Err(RendererError::InvalidBufferLength(
+                name.to_string(),
+                vertex_count,
+                count,
+            ));
+        }
+        let start = (range.start as usize).min(vertex_count);
+        let end = (range.end as usize).clamp(start, vertex_count);
+        Ok(start..end)
+    }
+
+    ///
+    /// Sets the morph targets of this mesh, each given as the difference to the vertex positions and optionally normals, for example the blend shapes of a face.
+    /// The morphed position of a vertex is the position plus the sum of the position deltas of each target multiplied by the weight of the target, see [Self::set_morph_weights],
+    /// and similarly for the normal which is normalized after the deltas are added.
+    /// The morph weights are reset to zero and the bounding box is expanded by the largest weighted deltas when the weights are set.
+    /// Use an empty slice to remove the morph targets.
+    ///
+    /// # Errors
+    /// Will return an error if there are more than [MAX_MORPH_TARGETS] targets or if the number of deltas of a target is not the same as the number of vertices.
+    ///
+    pub fn set_morph_targets(&mut self, targets: &[MorphTarget]) -> Result<(), RendererError> {
+        if targets.len() > MAX_MORPH_TARGETS {
+            return Err(RendererError::TooManyMorphTargets(
+                targets.len(),
+                MAX_MORPH_TARGETS,
+            ));
+        }
+        let vertex_count = self.vertex_count() as usize;
+        for target in targets {
+            if target.position_deltas.len() != vertex_count {
+                return Err(RendererError::InvalidBufferLength(
+                    "morph target position deltas".to_string(),
+                    vertex_count,
+                    target.position_deltas.len(),
+                ));
+            }
+            if let Some(normal_deltas) = &target.normal_deltas {
+                if normal_deltas.len() != vertex_count {
+                    return Err(RendererError::InvalidBufferLength(
+                        "morph target normal deltas".to_string(),
+                        vertex_count,
+                        normal_deltas.len(),
+                    ));
+                }
+            }
+        }
+        let context = self.context.clone();
+        let base_mesh = self.base_mesh_mut();
+        base_mesh.morph_targets = (!targets.is_empty()).then(|| {
+            Arc::new(MorphTargets::new(
+                &context,
+                targets,
+                vertex_count,
+                base_mesh.normals.is_some(),
+            ))
+        });
+        self.morph_weights = vec![0.0; targets.len()];
+        Ok(())
+    }
+
+    ///
+    /// Returns the number of morph targets of this mesh, see [Self::set_morph_targets].
+    ///
+    pub fn morph_target_count(&self) -> usize {
+        self.base_mesh
+            .morph_targets
+            .as_ref()
+            .map_or(0, |morph_targets| morph_targets.count)
+    }
+
+    ///
+    /// Sets the weight of each morph target, see [Self::set_morph_targets], for example sampled from an animation using [AnimationPlayer::morph_weights].
+    /// Weights beyond the number of morph targets are ignored and the targets without a weight get the weight zero.
+    ///
+    pub fn set_morph_weights(&mut self, weights: &[f32]) {
+        self.morph_weights = weights.to_vec();
+        self.morph_weights.resize(self.morph_target_count(), 0.0);
+    }
+
+    ///
+    /// Returns the weight of each morph target, see [Self::set_morph_weights].
+    ///
+    pub fn morph_weights(&self) -> &[f32] {
+        &self.morph_weights
+    }
+
     ///
     /// Used for editing the triangle indices.
     /// Note: Changing this will possibly ruin the mesh.
     ///
     pub fn indices_mut(&mut self) -> &mut IndexBuffer {
-        &mut self.base_mesh.indices
+        &mut self.base_mesh_mut().indices
     }
 
     ///
@@ -98,7 +516,7 @@ impl Mesh {
     /// Note: Changing this will possibly ruin the mesh.
     ///
     pub fn positions_mut(&mut self) -> &mut VertexBuffer<Vec3> {
-        &mut self.base_mesh.positions
+        &mut self.base_mesh_mut().positions
     }
 
     ///
@@ -106,7 +524,7 @@ impl Mesh {
     /// Note: Changing this will possibly ruin the mesh.
     ///
     pub fn normals_mut(&mut self) -> &mut Option<VertexBuffer<Vec3>> {
-        &mut self.base_mesh.normals
+        &mut self.base_mesh_mut().normals
     }
 
     ///
@@ -114,7 +532,7 @@ impl Mesh {
     /// Note: Changing this will possibly ruin the mesh.
     ///
     pub fn uvs_mut(&mut self) -> &mut Option<VertexBuffer<Vec2>> {
-        &mut self.base_mesh.uvs
+        &mut self.base_mesh_mut().uvs
     }
 
     ///
@@ -122,18 +540,65 @@ impl Mesh {
     /// Note: Changing this will possibly ruin the mesh.
     ///
     pub fn tangents_mut(&mut self) -> &mut Option<VertexBuffer<Vec4>> {
-        &mut self.base_mesh.tangents
+        &mut self.base_mesh_mut().tangents
     }
 
     ///
     /// Used for editing the vertex colors.
+    /// The colors in the buffer are in linear sRGB color space, since the [CpuMesh::colors] are converted from sRGB when the mesh is constructed,
+    /// so use [SrgbaExt::to_linear] to convert an [Srgba] color before writing it to the buffer.
     /// Note: Changing this will possibly ruin the mesh.
     ///
     pub fn colors_mut(&mut self) -> &mut Option<VertexBuffer<Vec4>> {
-        &mut self.base_mesh.colors
+        &mut self.base_mesh_mut().colors
+    }
+
+    ///
+    /// Used for editing the indices of the (up to four) joints influencing each vertex.
+    /// The joint weights are only available to the material if both these and the [Self::joint_weights_mut] are specified.
+    /// Note: Changing this will possibly ruin the mesh.
+    ///
+    pub fn joint_indices_mut(&mut self) -> &mut Option<VertexBuffer<Vec4>> {
+        &mut self.base_mesh_mut().joints
+    }
+
+    ///
+    /// Used for editing the weights of the (up to four) joints influencing each vertex, see [Self::joint_indices_mut].
+    /// Note: Changing this will possibly ruin the mesh.
+    ///
+    pub fn joint_weights_mut(&mut self) -> &mut Option<VertexBuffer<Vec4>> {
+        &mut self.base_mesh_mut().weights
+    }
+
+    ///
+    /// Sets a second set of uv coordinates, one for each vertex, which is used for sampling a lightmap, see [PhysicalMaterial::lightmap_texture].
+    /// The lightmap uv coordinates are specified the same way as [CpuMesh::uvs], ie. they are flipped in v before they are transfered to the GPU,
+    /// and are usually the same as the ones used for baking the lightmap, see [bake_lightmaps].
+    ///
+    pub fn set_lightmap_uvs(&mut self, lightmap_uvs: &[Vec2]) {
+        let buffer = lightmap_uv_buffer(&self.context, lightmap_uvs);
+        self.base_mesh_mut().lightmap_uvs = Some(buffer);
+    }
+
+    ///
+    /// Used for editing the lightmap uvs, see [Self::set_lightmap_uvs].
+    /// Note: Changing this will possibly ruin the mesh.
+    ///
+    pub fn lightmap_uvs_mut(&mut self) -> &mut Option<VertexBuffer<Vec2>> {
+        &mut self.base_mesh_mut().lightmap_uvs
     }
 }
 
+fn lightmap_uv_buffer(context: &Context, lightmap_uvs: &[Vec2]) -> VertexBuffer<Vec2> {
+    VertexBuffer::new_with_data(
+        context,
+        &lightmap_uvs
+            .iter()
+            .map(|uv| vec2(uv.x, 1.0 - uv.y))
+            .collect::<Vec<_>>(),
+    )
+}
+
 impl<'a> IntoIterator for &'a Mesh {
     type Item = &'a dyn Geometry;
     type IntoIter = std::iter::Once<&'a dyn Geometry>;
@@ -145,8 +610,11 @@ impl<'a> IntoIterator for &'a Mesh {
 
 impl Geometry for Mesh {
     fn aabb(&self) -> AxisAlignedBoundingBox {
-        self.aabb
-            .transformed(self.transformation * self.animation_transformation)
+        let aabb = match &self.base_mesh.morph_targets {
+            Some(morph_targets) => morph_targets.morphed_aabb(self.aabb, &self.morph_weights),
+            None => self.aabb,
+        };
+        aabb.transformed(self.local_to_world())
     }
 
     fn animate(&mut self, time: f32) {
@@ -156,7 +624,7 @@ impl Geometry for Mesh {
     }
 
     fn draw(&self, viewer: &dyn Viewer, program: &Program, render_states: RenderStates) {
-        let local2world = self.transformation * self.animation_transformation;
+        let local2world = self.local_to_world();
         if let Some(inverse) = local2world.invert() {
             program.use_uniform_if_required("normalMatrix", inverse.transpose());
         } else {
@@ -166,8 +634,17 @@ impl Geometry for Mesh {
 
         program.use_uniform("viewProjection", viewer.projection() * viewer.view());
         program.use_uniform("modelMatrix", local2world);
+        if let Some(morph_targets) = &self.base_mesh.morph_targets {
+            morph_targets.use_uniforms(program, &self.morph_weights);
+        }
 
-        self.base_mesh.draw(program, render_states, viewer);
+        match &self.range {
+            Some(range) => {
+                self.base_mesh
+                    .draw_subset(program, render_states, viewer, range.clone())
+            }
+            None => self.base_mesh.draw(program, render_states, viewer),
+        }
     }
 
     fn vertex_shader_source(&self) -> String {
@@ -180,6 +657,9 @@ impl Geometry for Mesh {
             self.base_mesh.tangents.is_some(),
             self.base_mesh.uvs.is_some(),
             self.base_mesh.colors.is_some(),
+            self.base_mesh.has_joint_weights(),
+            self.base_mesh.lightmap_uvs.is_some(),
+            self.base_mesh.morph_targets.is_some(),
         )
     }
 
@@ -0,0 +1,153 @@
+use crate::core::*;
+use crate::renderer::*;
+use std::ops::Range;
+use std::sync::Arc;
+
+use super::BaseMesh;
+
+///
+/// A transient geometry which draws several meshes that share the same vertex and index buffers with one instanced draw call,
+/// where the local to world transformation of each mesh is sent to the GPU as an instance attribute, see [InstanceBatches].
+///
+pub(in crate::renderer) struct MeshBatch {
+    context: Context,
+    base_mesh: Arc<BaseMesh>,
+    range: Option<Range<u32>>,
+    row1: InstanceBuffer<Vec4>,
+    row2: InstanceBuffer<Vec4>,
+    row3: InstanceBuffer<Vec4>,
+    instance_count: u32,
+    aabb: AxisAlignedBoundingBox,
+}
+
+impl MeshBatch {
+    ///
+    /// Returns whether the mesh can be drawn as part of a batch.
+    /// Skinned meshes, meshes with lightmap uvs and meshes with morph targets are not supported by the instanced shader
+    /// and a range of a non-indexed mesh cannot be drawn instanced.
+    ///
+    pub fn is_supported(mesh: &Mesh) -> bool {
+        let base_mesh = mesh.base_mesh();
+        !base_mesh.has_joint_weights()
+            && base_mesh.lightmap_uvs.is_none()
+            && base_mesh.morph_targets.is_none()
+            && (mesh.draw_range().is_none() || !matches!(base_mesh.indices, IndexBuffer::None))
+    }
+
+    ///
+    /// Returns a key which is equal for meshes that share the same vertex and index buffers and draw the same range of the triangles.
+    ///
+    pub fn key(mesh: &Mesh) -> (usize, Option<(u32, u32)>) {
+        (
+            Arc::as_ptr(mesh.base_mesh()) as usize,
+            mesh.draw_range().map(|range| (range.start, range.end)),
+        )
+    }
+
+    ///
+    /// Creates a batch of the given meshes, which must all have the same [MeshBatch::key], in the given order.
+    ///
+    pub fn new(context: &Context, meshes: &[&Mesh]) -> Self {
+        let mut row1 = Vec::with_capacity(meshes.len());
+        let mut row2 = Vec::with_capacity(meshes.len());
+        let mut row3 = Vec::with_capacity(meshes.len());
+        let mut aabb = AxisAlignedBoundingBox::EMPTY;
+        for mesh in meshes {
+            let transformation = mesh.local_to_world();
+            row1.push(transformation.row(0));
+            row2.push(transformation.row(1));
+            row3.push(transformation.row(2));
+            aabb.expand_with_aabb(mesh.aabb());
+        }
+        Self {
+            context: context.clone(),
+            base_mesh: meshes[0].base_mesh().clone(),
+            range: meshes[0].draw_range(),
+            row1: InstanceBuffer::new_with_data(context, &row1),
+            row2: InstanceBuffer::new_with_data(context, &row2),
+            row3: InstanceBuffer::new_with_data(context, &row3),
+            instance_count: meshes.len() as u32,
+            aabb,
+        }
+    }
+}
+
+impl Geometry for MeshBatch {
+    fn draw(&self, viewer: &dyn Viewer, program: &Program, render_states: RenderStates) {
+        program.use_uniform("viewProjection", viewer.projection() * viewer.view());
+        program.use_uniform("animationTransform", Mat4::identity());
+        program.use_uniform("modelMatrix", Mat4::identity());
+        program.use_instance_attribute("row1", &self.row1);
+        program.use_instance_attribute("row2", &self.row2);
+        program.use_instance_attribute("row3", &self.row3);
+        match &self.range {
+            Some(range) => self.base_mesh.draw_subset_instanced(
+                program,
+                render_states,
+                viewer,
+                range.clone(),
+                self.instance_count,
+            ),
+            None => {
+                self.base_mesh
+                    .draw_instanced(program, render_states, viewer, self.instance_count)
+            }
+        }
+    }
+
+    fn vertex_shader_source(&self) -> String {
+        format!(
+            "#define USE_INSTANCE_TRANSFORMS\n{}",
+            self.base_mesh.vertex_shader_source()
+        )
+    }
+
+    fn id(&self) -> GeometryId {
+        GeometryId::InstancedMesh(
+            self.base_mesh.normals.is_some(),
+            self.base_mesh.tangents.is_some(),
+            self.base_mesh.uvs.is_some(),
+            self.base_mesh.colors.is_some(),
+            false,
+            false,
+            false,
+            false,
+        )
+    }
+
+    fn render_with_material(
+        &self,
+        material: &dyn Material,
+        viewer: &dyn Viewer,
+        lights: &[&dyn Light],
+    ) {
+        if let Err(e) = render_with_material(&self.context, viewer, self, material, lights) {
+            panic!("{}", e.to_string());
+        }
+    }
+
+    fn render_with_effect(
+        &self,
+        material: &dyn Effect,
+        viewer: &dyn Viewer,
+        lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) {
+        if let Err(e) = render_with_effect(
+            &self.context,
+            viewer,
+            self,
+            material,
+            lights,
+            color_texture,
+            depth_texture,
+        ) {
+            panic!("{}", e.to_string());
+        }
+    }
+
+    fn aabb(&self) -> AxisAlignedBoundingBox {
+        self.aabb
+    }
+}
@@ -16,6 +16,7 @@ pub struct Particles {
     /// The texture transform applied to the uv coordinates of each particle.
     pub texture_transforms: Option<Vec<Mat3>>,
     /// A custom color for each particle.
+    /// The colors are converted from sRGB to linear sRGB before they are transfered to the GPU, like all other [Srgba] colors, see [SrgbaExt].
     pub colors: Option<Vec<Srgba>>,
 }
 
@@ -0,0 +1,219 @@
+use crate::core::*;
+use crate::renderer::*;
+
+///
+/// A point cloud [Geometry] where each point is rendered as a square facing the camera with a constant size in pixels, see [PointCloudGeometry::point_size].
+/// All points are rendered with one instanced draw call, so it can render millions of points.
+/// The instance ID given to the material is the index of the point, see [PointCloudGeometry::pick] for selecting individual points.
+///
+pub struct PointCloudGeometry {
+    context: Context,
+    position_buffer: VertexBuffer<Vec3>,
+    center_buffer: InstanceBuffer<Vec3>,
+    color_buffer: Option<InstanceBuffer<Vec4>>,
+    aabb: AxisAlignedBoundingBox,
+    transformation: Mat4,
+    /// The width and height of each point in physical pixels.
+    pub point_size: f32,
+}
+
+impl PointCloudGeometry {
+    ///
+    /// Creates a new point cloud geometry from the given [PointCloud], where each point is rendered as a square with the given size in physical pixels.
+    /// The colors of the points, if specified, are available to the material as vertex colors.
+    ///
+    pub fn new(context: &Context, point_cloud: &PointCloud, point_size: f32) -> Self {
+        let centers = point_cloud.positions.to_f32();
+        let mut aabb = AxisAlignedBoundingBox::EMPTY;
+        aabb.expand(&centers);
+        Self {
+            context: context.clone(),
+            position_buffer: VertexBuffer::new_with_data(
+                context,
+                &[
+                    vec3(-1.0, -1.0, 0.0),
+                    vec3(1.0, -1.0, 0.0),
+                    vec3(1.0, 1.0, 0.0),
+                    vec3(1.0, 1.0, 0.0),
+                    vec3(-1.0, 1.0, 0.0),
+                    vec3(-1.0, -1.0, 0.0),
+                ],
+            ),
+            center_buffer: InstanceBuffer::new_with_data(context, &centers),
+            color_buffer: point_cloud.colors.as_ref().map(|colors| {
+                InstanceBuffer::new_with_data(
+                    context,
+                    &colors
+                        .iter()
+                        .map(|c| c.to_linear_srgb())
+                        .collect::<Vec<_>>(),
+                )
+            }),
+            aabb,
+            transformation: Mat4::identity(),
+            point_size,
+        }
+    }
+
+    ///
+    /// Returns the number of points.
+    ///
+    pub fn point_count(&self) -> u32 {
+        self.center_buffer.instance_count()
+    }
+
+    ///
+    /// Returns the local to world transformation applied to all points.
+    ///
+    pub fn transformation(&self) -> Mat4 {
+        self.transformation
+    }
+
+    ///
+    /// Set the local to world transformation applied to all points.
+    ///
+    pub fn set_transformation(&mut self, transformation: Mat4) {
+        self.transformation = transformation;
+    }
+
+    ///
+    /// Returns the index of the point closest to the viewer among the points covering the pixels within the given radius, in physical pixels, of the given pixel,
+    /// or `None` if no point covers any of those pixels. If several points are at the same distance, the one closest to the given pixel is returned.
+    /// The pixel coordinate must be in physical pixels, where (viewport.x, viewport.y) indicate the bottom left corner of the viewport, see [pick].
+    ///
+    /// The point indices are rendered into a small offscreen target only covering the pixels around the given pixel, which is then read back,
+    /// so this is fast enough to call every time the mouse moves, for example for highlighting the point under the cursor.
+    ///
+    pub fn pick(
+        &self,
+        viewer: impl Viewer,
+        pixel: impl Into<PhysicalPoint>,
+        radius: u32,
+    ) -> Option<usize> {
+        let size = 2 * radius + 1;
+        let pick_viewer = PickViewer::new(&viewer, pixel.into(), radius);
+
+        let mut texture =
+            self.context
+                .request_texture::<[f32; 4]>(size, size, Interpolation::Nearest);
+        let mut depth_texture = self.context.request_depth_texture::<f32>(size, size);
+        let material = IntersectionMaterial::default();
+        let result = RenderTarget::new(
+            texture.as_color_target(None),
+            depth_texture.as_depth_target(),
+        )
+        .clear(
+            ClearState::color_and_depth(1.0, 1.0, 1.0, 1.0, 1.0)
+                .with_depth_mode(viewer.depth_mode()),
+        )
+        .write::<RendererError>(|| {
+            // The fallback for a shader which is not compiled yet would be read as point indices, so wait for the compilation
+            render_with_material_internal(&self.context, &pick_viewer, self, &material, &[], false)
+        })
+        .unwrap_or_else(|e| panic!("{}", e.to_string()))
+        .read_color::<[f32; 4]>();
+
+        let radius2 = (radius * radius) as i32;
+        result
+            .iter()
+            .enumerate()
+            .filter_map(|(i, value)| {
+                let dx = (i as u32 % size) as i32 - radius as i32;
+                let dy = (i as u32 / size) as i32 - radius as i32;
+                let distance2 = dx * dx + dy * dy;
+                (value[0] < 1.0 && distance2 <= radius2).then_some((
+                    value[0],
+                    distance2,
+                    value[2].to_bits() as usize,
+                ))
+            })
+            .min_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)))
+            .map(|(_, _, index)| index)
+    }
+
+    fn draw(&self, program: &Program, render_states: RenderStates, viewer: &dyn Viewer) {
+        let viewport = viewer.viewport();
+        program.use_uniform("viewProjection", viewer.projection() * viewer.view());
+        program.use_uniform("modelMatrix", self.transformation);
+        program.use_uniform(
+            "pointSize",
+            vec2(
+                self.point_size / viewport.width as f32,
+                self.point_size / viewport.height as f32,
+            ),
+        );
+        program.use_vertex_attribute("position", &self.position_buffer);
+        program.use_instance_attribute("center", &self.center_buffer);
+        if let Some(color_buffer) = &self.color_buffer {
+            program.use_instance_attribute("instance_color", color_buffer);
+        }
+        program.draw_arrays_instanced(render_states, viewport, 6, self.point_count())
+    }
+}
+
+impl<'a> IntoIterator for &'a PointCloudGeometry {
+    type Item = &'a dyn Geometry;
+    type IntoIter = std::iter::Once<&'a dyn Geometry>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        std::iter::once(self)
+    }
+}
+
+impl Geometry for PointCloudGeometry {
+    fn draw(&self, viewer: &dyn Viewer, program: &Program, render_states: RenderStates) {
+        self.draw(program, render_states, viewer);
+    }
+
+    fn vertex_shader_source(&self) -> String {
+        format!(
+            "{}{}",
+            if self.color_buffer.is_some() {
+                "#define USE_VERTEX_COLORS\n"
+            } else {
+                ""
+            },
+            include_str!("shaders/point_cloud.vert")
+        )
+    }
+
+    fn id(&self) -> GeometryId {
+        GeometryId::PointCloud(self.color_buffer.is_some())
+    }
+
+    fn render_with_material(
+        &self,
+        material: &dyn Material,
+        viewer: &dyn Viewer,
+        lights: &[&dyn Light],
+    ) {
+        if let Err(e) = render_with_material(&self.context, viewer, &self, material, lights) {
+            panic!("{}", e.to_string());
+        }
+    }
+
+    fn render_with_effect(
+        &self,
+        material: &dyn Effect,
+        viewer: &dyn Viewer,
+        lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) {
+        if let Err(e) = render_with_effect(
+            &self.context,
+            viewer,
+            self,
+            material,
+            lights,
+            color_texture,
+            depth_texture,
+        ) {
+            panic!("{}", e.to_string());
+        }
+    }
+
+    fn aabb(&self) -> AxisAlignedBoundingBox {
+        self.aabb.transformed(self.transformation)
+    }
+}
@@ -0,0 +1,333 @@
+use crate::core::*;
+use crate::renderer::*;
+use std::f32::consts::FRAC_PI_2;
+
+// The number of triangles in each half disk used for round caps and joins
+const ROUND_SEGMENTS: usize = 8;
+
+///
+/// The width of a [Polyline].
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LineWidth {
+    /// The width in world units, so the line gets thinner further away from the viewer.
+    World(f32),
+    /// The width in physical pixels, so the line has the same width on the screen regardless of the distance to the viewer.
+    Pixels(f32),
+}
+
+impl Default for LineWidth {
+    fn default() -> Self {
+        Self::Pixels(2.0)
+    }
+}
+
+///
+/// Defines how two connected segments of a [Polyline] are joined.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum LineJoin {
+    /// The outer edges of the segments are extended until they meet in a sharp corner,
+    /// unless the corner is further away than the [Polyline::miter_limit] in which case the segments are joined with a bevel.
+    #[default]
+    Miter,
+    /// The outer corners of the segments are connected with a straight edge.
+    Bevel,
+    /// The segments are joined with a circular arc.
+    Round,
+}
+
+///
+/// A line through an ordered list of points, for example a trajectory, a graph or a wireframe overlay, with a width in world units or pixels, see [LineWidth].
+/// Each segment is expanded into triangles facing the camera in the vertex shader and the segments are connected with the given [LineJoin] and ends with the given [LineCap].
+/// All segments are rendered with one instanced draw call and the instance ID given to the material is the index of the segment.
+///
+/// The polyline is rendered with depth test and write like any other geometry, so it can be combined with for example a [ColorMaterial] and picked using [pick].
+/// The winding of the triangles depends on the direction of each segment on the screen, so use [Cull::None] when rendering and picking.
+/// A polyline with a single point is rendered as a dot if the caps are round and coincident points do not produce any artifacts.
+///
+pub struct Polyline {
+    context: Context,
+    template: VertexBuffer<Vec3>,
+    start_buffer: InstanceBuffer<Vec3>,
+    end_buffer: InstanceBuffer<Vec3>,
+    next_buffer: InstanceBuffer<Vec3>,
+    neighbour_buffer: InstanceBuffer<Vec2>,
+    color_buffers: Option<(InstanceBuffer<Vec4>, InstanceBuffer<Vec4>)>,
+    segment_count: u32,
+    aabb: AxisAlignedBoundingBox,
+    transformation: Mat4,
+    /// The width of the line.
+    pub width: LineWidth,
+    /// Defines how the segments are joined.
+    pub join: LineJoin,
+    /// Defines how the ends of the line are drawn if the line is not closed. The default is [LineCap::Round].
+    pub cap: LineCap,
+    /// The longest distance from a point to the corner of a [LineJoin::Miter] relative to half the width of the line, longer miters are beveled. The default is 4.
+    pub miter_limit: f32,
+}
+
+impl Polyline {
+    ///
+    /// Creates a new polyline through the given points, where the last point is connected to the first point if `closed` is true.
+    /// The colors of the points, if specified, are interpolated along each segment and available to the material as vertex colors.
+    ///
+    /// # Panic
+    /// Will panic if colors are given and the number of colors is not the same as the number of points.
+    ///
+    pub fn new(
+        context: &Context,
+        points: &[Vec3],
+        colors: Option<&[Srgba]>,
+        closed: bool,
+        width: LineWidth,
+    ) -> Self {
+        let mut template = vec![
+            vec3(0.0, -1.0, 0.0),
+            vec3(1.0, -1.0, 0.0),
+            vec3(1.0, 1.0, 0.0),
+            vec3(1.0, 1.0, 0.0),
+            vec3(0.0, 1.0, 0.0),
+            vec3(0.0, -1.0, 0.0),
+        ];
+        for end in [0.0, 1.0] {
+            for i in 0..ROUND_SEGMENTS {
+                let angle =
+                    |i: usize| -FRAC_PI_2 + i as f32 / ROUND_SEGMENTS as f32 * 2.0 * FRAC_PI_2;
+                template.push(vec3(end, 0.0, 1.0));
+                template.push(vec3(end, angle(i), 2.0));
+                template.push(vec3(end, angle(i + 1), 2.0));
+            }
+        }
+        template.extend([
+            vec3(1.0, 0.0, 3.0),
+            vec3(1.0, 1.0, 3.0),
+            vec3(1.0, 2.0, 3.0),
+            vec3(1.0, 0.0, 3.0),
+            vec3(1.0, 2.0, 3.0),
+            vec3(1.0, 3.0, 3.0),
+        ]);
+
+        let mut polyline = Self {
+            context: context.clone(),
+            template: VertexBuffer::new_with_data(context, &template),
+            start_buffer: InstanceBuffer::new(context),
+            end_buffer: InstanceBuffer::new(context),
+            next_buffer: InstanceBuffer::new(context),
+            neighbour_buffer: InstanceBuffer::new(context),
+            color_buffers: None,
+            segment_count: 0,
+            aabb: AxisAlignedBoundingBox::EMPTY,
+            transformation: Mat4::identity(),
+            width,
+            join: LineJoin::default(),
+            cap: LineCap::Round,
+            miter_limit: 4.0,
+        };
+        polyline.set_points(points, colors, closed);
+        polyline
+    }
+
+    ///
+    /// Replaces the points of this polyline, for example when a trajectory is extended, see [Polyline::new].
+    ///
+    /// # Panic
+    /// Will panic if colors are given and the number of colors is not the same as the number of points.
+    ///
+    pub fn set_points(&mut self, points: &[Vec3], colors: Option<&[Srgba]>, closed: bool) {
+        if let Some(colors) = colors {
+            assert_eq!(
+                colors.len(),
+                points.len(),
+                "the number of colors must be the same as the number of points"
+            );
+        }
+        let n = points.len();
+        // A single point is a segment from the point to itself, so it is drawn as a dot with round caps
+        let segments = match n {
+            0 => Vec::new(),
+            1 => vec![(0, 0)],
+            _ if closed => (0..n).map(|i| (i, (i + 1) % n)).collect(),
+            _ => (0..n - 1).map(|i| (i, i + 1)).collect::<Vec<_>>(),
+        };
+        let segment_count = segments.len();
+        let has_next = |s: usize| n > 1 && (closed || s + 1 < segment_count);
+        let has_previous = |s: usize| n > 1 && (closed || s > 0);
+
+        self.start_buffer
+            .fill(&segments.iter().map(|(a, _)| points[*a]).collect::<Vec<_>>());
+        self.end_buffer
+            .fill(&segments.iter().map(|(_, b)| points[*b]).collect::<Vec<_>>());
+        self.next_buffer.fill(
+            &(0..segment_count)
+                .map(|s| {
+                    if has_next(s) {
+                        points[segments[(s + 1) % segment_count].1]
+                    } else {
+                        points[segments[s].1]
+                    }
+                })
+                .collect::<Vec<_>>(),
+        );
+        self.neighbour_buffer.fill(
+            &(0..segment_count)
+                .map(|s| vec2(has_previous(s) as u8 as f32, has_next(s) as u8 as f32))
+                .collect::<Vec<_>>(),
+        );
+        self.color_buffers = colors.map(|colors| {
+            let colors = colors
+                .iter()
+                .map(|c| c.to_linear_srgb())
+                .collect::<Vec<_>>();
+            (
+                InstanceBuffer::new_with_data(
+                    &self.context,
+                    &segments.iter().map(|(a, _)| colors[*a]).collect::<Vec<_>>(),
+                ),
+                InstanceBuffer::new_with_data(
+                    &self.context,
+                    &segments.iter().map(|(_, b)| colors[*b]).collect::<Vec<_>>(),
+                ),
+            )
+        });
+        self.segment_count = segment_count as u32;
+        self.aabb = AxisAlignedBoundingBox::new_with_positions(points);
+    }
+
+    ///
+    /// Returns the number of segments, which is the index of the last segment plus one, see [Polyline].
+    ///
+    pub fn segment_count(&self) -> u32 {
+        self.segment_count
+    }
+
+    ///
+    /// Returns the local to world transformation applied to all points.
+    ///
+    pub fn transformation(&self) -> Mat4 {
+        self.transformation
+    }
+
+    ///
+    /// Set the local to world transformation applied to all points. The width of the line is not affected by the transformation.
+    ///
+    pub fn set_transformation(&mut self, transformation: Mat4) {
+        self.transformation = transformation;
+    }
+}
+
+impl<'a> IntoIterator for &'a Polyline {
+    type Item = &'a dyn Geometry;
+    type IntoIter = std::iter::Once<&'a dyn Geometry>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        std::iter::once(self)
+    }
+}
+
+impl Geometry for Polyline {
+    fn draw(&self, viewer: &dyn Viewer, program: &Program, render_states: RenderStates) {
+        if self.segment_count == 0 {
+            return;
+        }
+        let viewport = viewer.viewport();
+        let (width, in_pixels) = match self.width {
+            LineWidth::World(width) => (width, false),
+            LineWidth::Pixels(width) => (width, true),
+        };
+        program.use_uniform("viewProjection", viewer.projection() * viewer.view());
+        program.use_uniform("modelMatrix", self.transformation);
+        program.use_uniform(
+            "viewportSize",
+            vec2(viewport.width as f32, viewport.height as f32),
+        );
+        program.use_uniform("lineWidth", width);
+        program.use_uniform("lineWidthInPixels", in_pixels as i32);
+        // The number of pixels per world unit at a distance of one, which is divided by the distance in the shader
+        program.use_uniform(
+            "pixelsPerUnit",
+            0.5 * viewport.height as f32 * viewer.projection().y.y.abs(),
+        );
+        program.use_uniform("lineJoin", self.join as i32);
+        program.use_uniform("lineCap", self.cap as i32);
+        program.use_uniform("miterLimit", self.miter_limit);
+        program.use_vertex_attribute("position", &self.template);
+        program.use_instance_attribute("point_start", &self.start_buffer);
+        program.use_instance_attribute("point_end", &self.end_buffer);
+        program.use_instance_attribute("point_next", &self.next_buffer);
+        program.use_instance_attribute("neighbours", &self.neighbour_buffer);
+        if let Some((start_colors, end_colors)) = &self.color_buffers {
+            program.use_instance_attribute("color_start", start_colors);
+            program.use_instance_attribute("color_end", end_colors);
+        }
+        program.draw_arrays_instanced(
+            render_states,
+            viewport,
+            self.template.vertex_count(),
+            self.segment_count,
+        )
+    }
+
+    fn vertex_shader_source(&self) -> String {
+        format!(
+            "{}{}",
+            if self.color_buffers.is_some() {
+                "#define USE_VERTEX_COLORS\n"
+            } else {
+                ""
+            },
+            include_str!("shaders/polyline.vert")
+        )
+    }
+
+    fn id(&self) -> GeometryId {
+        GeometryId::Polyline(self.color_buffers.is_some())
+    }
+
+    fn render_with_material(
+        &self,
+        material: &dyn Material,
+        viewer: &dyn Viewer,
+        lights: &[&dyn Light],
+    ) {
+        if let Err(e) = render_with_material(&self.context, viewer, &self, material, lights) {
+            panic!("{}", e.to_string());
+        }
+    }
+
+    fn render_with_effect(
+        &self,
+        material: &dyn Effect,
+        viewer: &dyn Viewer,
+        lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) {
+        if let Err(e) = render_with_effect(
+            &self.context,
+            viewer,
+            self,
+            material,
+            lights,
+            color_texture,
+            depth_texture,
+        ) {
+            panic!("{}", e.to_string());
+        }
+    }
+
+    fn aabb(&self) -> AxisAlignedBoundingBox {
+        let aabb = self.aabb.transformed(self.transformation);
+        match self.width {
+            LineWidth::World(width) if !aabb.is_empty() => {
+                let half_width = 0.5 * width.abs();
+                let half_width = vec3(half_width, half_width, half_width);
+                AxisAlignedBoundingBox::new_with_positions(&[
+                    aabb.min() - half_width,
+                    aabb.max() + half_width,
+                ])
+            }
+            _ => aabb,
+        }
+    }
+}
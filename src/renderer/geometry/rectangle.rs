@@ -1,19 +1,25 @@
+use super::shape2d::{render_shape, ShapeParameters};
 use crate::renderer::*;
 
 ///
-/// A rectangle 2D geometry which can be rendered using a camera created by [Camera::new_2d].
+/// A rectangle 2D geometry which can be rendered using a camera created by [Camera::new_2d] or [Camera::new_2d_logical].
 ///
 pub struct Rectangle {
+    context: Context,
     mesh: Mesh,
     width: f32,
     height: f32,
     center: PhysicalPoint,
     rotation: Radians,
+    corner_radius: f32,
+    mode: ShapeMode,
+    dash_pattern: Option<(f32, f32)>,
+    device_pixel_ratio: f32,
 }
 
 impl Rectangle {
     ///
-    /// Constructs a new rectangle geometry.
+    /// Constructs a new filled rectangle geometry.
     ///
     pub fn new(
         context: &Context,
@@ -25,11 +31,16 @@ impl Rectangle {
         let mut mesh = CpuMesh::square();
         mesh.transform(Mat4::from_scale(0.5)).unwrap();
         let mut rectangle = Self {
+            context: context.clone(),
             mesh: Mesh::new(context, &mesh),
             width,
             height,
             center: center.into(),
             rotation: rotation.into(),
+            corner_radius: 0.0,
+            mode: ShapeMode::Fill,
+            dash_pattern: None,
+            device_pixel_ratio: 1.0,
         };
         rectangle.update();
         rectangle
@@ -69,12 +80,77 @@ impl Rectangle {
         self.rotation
     }
 
+    /// Set the radius of the rounded corners of the rectangle in physical pixels.
+    pub fn set_corner_radius(&mut self, corner_radius: f32) {
+        self.corner_radius = corner_radius;
+    }
+
+    /// Get the radius of the rounded corners of the rectangle in physical pixels.
+    pub fn corner_radius(&self) -> f32 {
+        self.corner_radius
+    }
+
+    /// Set whether the rectangle is filled or only the outline is drawn.
+    pub fn set_mode(&mut self, mode: ShapeMode) {
+        self.mode = mode;
+        self.update();
+    }
+
+    /// Get whether the rectangle is filled or only the outline is drawn.
+    pub fn mode(&self) -> ShapeMode {
+        self.mode
+    }
+
+    ///
+    /// Set the dash pattern of the outline given as the length of the dashes and the length of the gaps between the dashes in logical pixels.
+    /// Use `None` for a solid outline. Only used when the mode is [ShapeMode::Stroke].
+    ///
+    pub fn set_dash_pattern(&mut self, dash_pattern: Option<(f32, f32)>) {
+        self.dash_pattern = dash_pattern;
+    }
+
+    /// Get the dash pattern of the outline.
+    pub fn dash_pattern(&self) -> Option<(f32, f32)> {
+        self.dash_pattern
+    }
+
+    ///
+    /// Set the device pixel ratio which is used to convert the stroke width and dash pattern from logical to physical pixels.
+    /// The default is 1.
+    ///
+    pub fn set_device_pixel_ratio(&mut self, device_pixel_ratio: f32) {
+        self.device_pixel_ratio = device_pixel_ratio;
+        self.update();
+    }
+
+    fn parameters(&self) -> ShapeParameters {
+        let stroke_width = match self.mode {
+            ShapeMode::Fill => 0.0,
+            ShapeMode::Stroke(width) => width * self.device_pixel_ratio,
+        };
+        ShapeParameters {
+            shape_type: 0,
+            line_cap: LineCap::Butt,
+            half_size: vec2(0.5 * self.width, 0.5 * self.height),
+            radius: self.corner_radius,
+            stroke_width,
+            dash_pattern: self
+                .dash_pattern
+                .filter(|_| stroke_width > 0.0)
+                .map(|(dash, gap)| {
+                    (
+                        dash * self.device_pixel_ratio,
+                        gap * self.device_pixel_ratio,
+                    )
+                }),
+            transformation: Mat3::from_translation(self.center.into())
+                * Mat3::from_angle_z(self.rotation),
+        }
+    }
+
     fn update(&mut self) {
-        self.mesh.set_transformation_2d(
-            Mat3::from_translation(self.center.into())
-                * Mat3::from_angle_z(self.rotation)
-                * Mat3::from_nonuniform_scale(self.width, self.height),
-        );
+        let transformation = self.parameters().quad_transformation();
+        self.mesh.set_transformation_2d(transformation);
     }
 }
 
@@ -102,7 +178,49 @@ impl std::ops::DerefMut for Rectangle {
 }
 
 impl Geometry for Rectangle {
-    impl_geometry_body!(deref);
+    fn draw(&self, viewer: &dyn Viewer, program: &Program, render_states: RenderStates) {
+        self.mesh.draw(viewer, program, render_states)
+    }
+
+    fn vertex_shader_source(&self) -> String {
+        self.mesh.vertex_shader_source()
+    }
+
+    fn id(&self) -> GeometryId {
+        GeometryId::Shape2D(self.mesh.id())
+    }
+
+    fn render_with_material(
+        &self,
+        material: &dyn Material,
+        viewer: &dyn Viewer,
+        lights: &[&dyn Light],
+    ) {
+        render_shape(
+            &self.context,
+            self,
+            self.parameters(),
+            material,
+            viewer,
+            lights,
+        )
+    }
+
+    fn render_with_effect(
+        &self,
+        material: &dyn Effect,
+        viewer: &dyn Viewer,
+        lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) {
+        self.mesh
+            .render_with_effect(material, viewer, lights, color_texture, depth_texture)
+    }
+
+    fn aabb(&self) -> AxisAlignedBoundingBox {
+        self.mesh.aabb()
+    }
 
     fn animate(&mut self, time: f32) {
         self.mesh.animate(time)
@@ -1,3 +1,5 @@
+#include <three-d/shared>
+
 
 uniform mat4 viewProjection;
 uniform mat4 modelMatrix;
@@ -10,25 +12,44 @@ uniform vec3 acceleration;
 uniform float time;
 #endif
 
+#ifdef SHELLS
+uniform float shellCount;
+uniform float shellLength;
+uniform vec3 shellGravity;
+uniform vec3 shellWind;
+uniform float shellTime;
+out float shell_height;
+#endif
+
 #ifdef USE_INSTANCE_TRANSFORMS
 uniform mat4 animationTransform;
+#ifdef USE_INSTANCE_POSITION_SCALE
+in vec4 instance_position_scale;
+#ifdef USE_INSTANCE_ROTATIONS
+in vec4 instance_rotation;
+#endif
+#else
 in vec4 row1;
 in vec4 row2;
 in vec4 row3;
 #endif
+#endif
 
 out vec3 pos;
 
 #ifdef USE_NORMALS 
 uniform mat4 normalMatrix;
+// The distance the surface is moved along the normal, which is zero unless set by the material, for example the outline of a toon material
+uniform float normalExtrusion;
 in vec3 normal;
 out vec3 nor;
 
 #ifdef USE_TANGENTS 
 in vec4 tangent;
+#endif
+// Zero when the geometry has no tangents, so the material can detect that and fall back to tangents derived from screen space derivatives
 out vec3 tang;
 out vec3 bitang;
-#endif
 
 #endif
 
@@ -39,8 +60,9 @@ in vec3 tex_transform_row1;
 in vec3 tex_transform_row2;
 #endif
 in vec2 uv_coordinates;
-out vec2 uvs;
 #endif
+// Zero when the geometry has no uv coordinates
+out vec2 uvs;
 
 #ifdef USE_VERTEX_COLORS 
 in vec4 color;
@@ -49,20 +71,143 @@ in vec4 color;
 in vec4 instance_color;
 #endif
 
+#ifdef USE_JOINT_WEIGHTS
+in vec4 joint_indices;
+in vec4 joint_weights;
+out vec4 joints;
+out vec4 weights;
+#endif
+
+#ifdef USE_LIGHTMAP_UVS
+in vec2 lightmap_uv_coordinates;
+out vec2 lightmap_uvs;
+#endif
+
 out vec4 col;
 flat out int instance_id;
 
+#ifdef SKINNING
+// Four texels per joint with the columns of the joint matrix, one row per joint
+uniform highp sampler2D jointMatrices;
+
+mat4 joint_matrix(float index)
+{
+    int row = int(index);
+    return mat4(
+        texelFetch(jointMatrices, ivec2(0, row), 0),
+        texelFetch(jointMatrices, ivec2(1, row), 0),
+        texelFetch(jointMatrices, ivec2(2, row), 0),
+        texelFetch(jointMatrices, ivec2(3, row), 0));
+}
+#endif
+
+#ifdef USE_MORPH_TARGETS
+// One layer with the position deltas of each target followed by one layer with the normal deltas of each target if morphNormals is set, one texel per vertex
+uniform highp sampler2DArray morphTargets;
+uniform float morphWeights[MAX_MORPH_TARGETS];
+uniform int morphTargetCount;
+uniform int morphNormals;
+
+vec3 morph_delta(int layer)
+{
+    int width = textureSize(morphTargets, 0).x;
+    return texelFetch(morphTargets, ivec3(gl_VertexID % width, gl_VertexID / width, layer), 0).xyz;
+}
+#endif
+
+#ifdef DISPLACEMENT
+uniform sampler2D displacementTexture;
+uniform mat3 displacementTexTransform;
+uniform float displacementScale;
+uniform vec2 displacementTexelSize;
+// The distance in local space per unit of the uv coordinates
+uniform float displacementUvScale;
+
+float displacement_height(vec2 uv)
+{
+    return textureLod(displacementTexture, (displacementTexTransform * vec3(uv, 1.0)).xy, 0.0).r;
+}
+#endif
+
 void main()
 {
+    vec3 local_position = position;
+#ifdef USE_NORMALS
+    vec3 local_normal = normal;
+#endif
+#ifdef USE_TANGENTS
+    vec3 local_tangent = tangent.xyz;
+#endif
+
+    // *** MORPH TARGETS ***
+#ifdef USE_MORPH_TARGETS
+    for (int i = 0; i < morphTargetCount; i++) {
+        local_position += morphWeights[i] * morph_delta(i);
+#ifdef USE_NORMALS
+        if (morphNormals != 0) {
+            local_normal += morphWeights[i] * morph_delta(morphTargetCount + i);
+        }
+#endif
+    }
+#ifdef USE_NORMALS
+    local_normal = normalize(local_normal);
+#endif
+#endif
+
+    // *** DISPLACEMENT ***
+#ifdef DISPLACEMENT
+    local_position += displacementScale * displacement_height(uv_coordinates) * normal;
+#ifdef DISPLACEMENT_NORMALS
+    // The normal is tilted by the gradient of the height field, where the tangent and bitangent point along the u and v coordinates
+    vec2 du = vec2(displacementTexelSize.x, 0.0);
+    vec2 dv = vec2(0.0, displacementTexelSize.y);
+    float dh_du = (displacement_height(uv_coordinates + du) - displacement_height(uv_coordinates - du)) / (2.0 * du.x);
+    float dh_dv = (displacement_height(uv_coordinates + dv) - displacement_height(uv_coordinates - dv)) / (2.0 * dv.y);
+    vec3 local_bitangent = cross(normal, tangent.xyz) * tangent.w;
+    local_normal = normalize(normal - displacementScale / displacementUvScale * (dh_du * tangent.xyz + dh_dv * local_bitangent));
+    local_tangent = tangent.xyz - local_normal * dot(local_normal, tangent.xyz);
+#endif
+#endif
+
+    // *** SKINNING ***
+#ifdef SKINNING
+    // Linear blend skinning with up to four joints, vertices without any weights are not moved
+    float total_weight = dot(joint_weights, vec4(1.0));
+    mat4 skin = total_weight > 0.0 ? (joint_weights.x * joint_matrix(joint_indices.x)
+        + joint_weights.y * joint_matrix(joint_indices.y)
+        + joint_weights.z * joint_matrix(joint_indices.z)
+        + joint_weights.w * joint_matrix(joint_indices.w)) / total_weight : mat4(1.0);
+    local_position = (skin * vec4(local_position, 1.0)).xyz;
+#ifdef USE_NORMALS
+    local_normal = mat3(skin) * local_normal;
+#endif
+#ifdef USE_TANGENTS
+    local_tangent = mat3(skin) * local_tangent;
+#endif
+#endif
+
     // *** POSITION ***
     mat4 local2World = modelMatrix;
     
 #ifdef USE_INSTANCE_TRANSFORMS
     mat4 transform;
+#ifdef USE_INSTANCE_POSITION_SCALE
+    mat3 rotation = mat3(1.0);
+#ifdef USE_INSTANCE_ROTATIONS
+    vec4 q = instance_rotation;
+    rotation = mat3(
+        1.0 - 2.0 * (q.y * q.y + q.z * q.z), 2.0 * (q.x * q.y + q.w * q.z), 2.0 * (q.x * q.z - q.w * q.y),
+        2.0 * (q.x * q.y - q.w * q.z), 1.0 - 2.0 * (q.x * q.x + q.z * q.z), 2.0 * (q.y * q.z + q.w * q.x),
+        2.0 * (q.x * q.z + q.w * q.y), 2.0 * (q.y * q.z - q.w * q.x), 1.0 - 2.0 * (q.x * q.x + q.y * q.y));
+#endif
+    transform = mat4(rotation * instance_position_scale.w);
+    transform[3] = vec4(instance_position_scale.xyz, 1.0);
+#else
     transform[0] = vec4(row1.x, row2.x, row3.x, 0.0);
     transform[1] = vec4(row1.y, row2.y, row3.y, 0.0);
     transform[2] = vec4(row1.z, row2.z, row3.z, 0.0);
     transform[3] = vec4(row1.w, row2.w, row3.w, 1.0);
+#endif
     local2World = local2World * transform * animationTransform;
 #endif
 
@@ -72,7 +217,24 @@ void main()
     local2World = local2World * animationTransform;
 #endif
 
-    vec4 worldPosition = local2World * vec4(position, 1.);
+    vec4 worldPosition = local2World * vec4(local_position, 1.);
+#ifdef SHELLS
+    // Each instance is a shell offset along the normal, the outer shells bend more than the inner
+    shell_height = shellCount > 1.0 ? float(gl_InstanceID) / (shellCount - 1.0) : 0.0;
+    vec3 shell_normal = normalize(mat3(normalMatrix) * normal);
+    float sway = sin(shellTime + dot(worldPosition.xyz, vec3(0.73, 0.31, 0.57)));
+    vec3 bend = shellGravity + shellWind * sway;
+    worldPosition.xyz += shellLength * (shell_height * shell_normal + shell_height * shell_height * bend);
+#endif
+#ifdef USE_NORMALS
+    if (normalExtrusion != 0.0) {
+#ifdef USE_INSTANCE_TRANSFORMS
+        worldPosition.xyz += normalExtrusion * normalize(mat3(transpose(inverse(local2World))) * local_normal);
+#else
+        worldPosition.xyz += normalExtrusion * normalize(mat3(normalMatrix) * local_normal);
+#endif
+    }
+#endif
     gl_Position = viewProjection * worldPosition;
     pos = worldPosition.xyz / worldPosition.w;
 
@@ -83,11 +245,14 @@ void main()
 #else
     mat3 normalMat = mat3(normalMatrix);
 #endif
-    nor = normalize(normalMat * normal);
+    nor = normalize(normalMat * local_normal);
 
 #ifdef USE_TANGENTS 
-    tang = normalize(normalMat * tangent.xyz);
+    tang = normalize(normalMat * local_tangent);
     bitang = normalize(cross(nor, tang) * tangent.w);
+#else
+    tang = vec3(0.0);
+    bitang = vec3(0.0);
 #endif
 
 #endif
@@ -103,6 +268,8 @@ void main()
 #else
     uvs = uv_coordinates;
 #endif
+#else
+    uvs = vec2(0.0);
 #endif
 
     // *** COLOR ***
@@ -113,5 +280,16 @@ void main()
 #ifdef USE_INSTANCE_COLORS
     col *= instance_color;
 #endif
+
+    // *** JOINT WEIGHTS ***
+#ifdef USE_JOINT_WEIGHTS
+    joints = joint_indices;
+    weights = joint_weights;
+#endif
+
+    // *** LIGHTMAP UV ***
+#ifdef USE_LIGHTMAP_UVS
+    lightmap_uvs = lightmap_uv_coordinates;
+#endif
     instance_id = gl_InstanceID;
 }
\ No newline at end of file
@@ -0,0 +1,31 @@
+uniform mat4 viewProjection;
+uniform mat4 modelMatrix;
+uniform vec2 pointSize;
+
+in vec3 position;
+in vec3 center;
+
+#ifdef USE_VERTEX_COLORS
+in vec4 instance_color;
+#endif
+
+out vec3 pos;
+out vec2 uvs;
+out vec4 col;
+flat out int instance_id;
+
+void main()
+{
+    vec4 world_pos = modelMatrix * vec4(center, 1.0);
+    pos = world_pos.xyz / world_pos.w;
+    uvs = 0.5 * position.xy + 0.5;
+#ifdef USE_VERTEX_COLORS
+    col = instance_color;
+#else
+    col = vec4(1.0);
+#endif
+    gl_Position = viewProjection * world_pos;
+    // The point is a square with a constant size in pixels, so the corner offset is given in normalized device coordinates
+    gl_Position.xy += position.xy * pointSize * gl_Position.w;
+    instance_id = gl_InstanceID;
+}
@@ -0,0 +1,134 @@
+uniform mat4 viewProjection;
+uniform mat4 modelMatrix;
+uniform vec2 viewportSize;
+// The width in pixels if lineWidthInPixels is set, otherwise in world units which are converted to pixels using pixelsPerUnit at a distance of one
+uniform float lineWidth;
+uniform int lineWidthInPixels;
+uniform float pixelsPerUnit;
+// 0: miter, 1: bevel, 2: round
+uniform int lineJoin;
+// 0: butt, 1: round, 2: square
+uniform int lineCap;
+uniform float miterLimit;
+
+// The x coordinate is 0 at the start and 1 at the end of the segment and the z coordinate is the type of the vertex:
+// 0: the quad along the segment, where y is the side,
+// 1: the center of a half disk at the end of the segment and 2: a vertex on the rim of the half disk, where y is the angle,
+// 3: the join with the next segment, where y is the corner
+in vec3 position;
+in vec3 point_start;
+in vec3 point_end;
+in vec3 point_next;
+// Whether the segment has a previous and a next segment
+in vec2 neighbours;
+
+#ifdef USE_VERTEX_COLORS
+in vec4 color_start;
+in vec4 color_end;
+#endif
+
+out vec3 pos;
+out vec2 uvs;
+out vec4 col;
+flat out int instance_id;
+
+// Moves the point behind the near plane towards the other point until it is on the near plane
+void clip_near(inout vec4 a, inout vec4 b)
+{
+    float da = a.z + a.w;
+    float db = b.z + b.w;
+    if (da < 0.0 && db >= 0.0) {
+        a = mix(a, b, da / (da - db));
+    } else if (db < 0.0 && da >= 0.0) {
+        b = mix(b, a, db / (db - da));
+    }
+}
+
+vec2 to_screen(vec4 clip_position)
+{
+    return 0.5 * viewportSize * clip_position.xy / max(clip_position.w, 1e-6);
+}
+
+vec2 safe_normalize(vec2 v, vec2 fallback)
+{
+    float l = length(v);
+    return l > 1e-6 ? v / l : fallback;
+}
+
+void main()
+{
+    bool at_end = position.x > 0.5;
+    vec4 world_start = modelMatrix * vec4(point_start, 1.0);
+    vec4 world_end = modelMatrix * vec4(point_end, 1.0);
+    vec4 world_pos = at_end ? world_end : world_start;
+    pos = world_pos.xyz / world_pos.w;
+    instance_id = gl_InstanceID;
+#ifdef USE_VERTEX_COLORS
+    col = at_end ? color_end : color_start;
+#else
+    col = vec4(1.0);
+#endif
+    uvs = vec2(position.x, position.z < 0.5 ? 0.5 * position.y + 0.5 : 0.5);
+
+    vec4 clip_start = viewProjection * world_start;
+    vec4 clip_end = viewProjection * world_end;
+    vec4 unclipped_end = clip_end;
+    if (clip_start.z + clip_start.w < 0.0 && clip_end.z + clip_end.w < 0.0) {
+        // The segment is behind the viewer, so all vertices are placed at the same point which is clipped
+        gl_Position = vec4(0.0, 0.0, -2.0, 1.0);
+        return;
+    }
+    clip_near(clip_start, clip_end);
+    vec4 clip = at_end ? clip_end : clip_start;
+
+    vec2 screen_start = to_screen(clip_start);
+    vec2 screen_end = to_screen(clip_end);
+    bool degenerate = length(screen_end - screen_start) < 1e-6;
+    vec2 dir = safe_normalize(screen_end - screen_start, vec2(1.0, 0.0));
+    vec2 normal = vec2(-dir.y, dir.x);
+    float half_width = lineWidthInPixels != 0 ? 0.5 * lineWidth : 0.5 * lineWidth * pixelsPerUnit / max(clip.w, 1e-6);
+
+    vec2 offset = vec2(0.0);
+    if (position.z < 0.5) {
+        offset = position.y * half_width * normal;
+    } else if (position.z < 2.5) {
+        // A cap if there is no neighbour at this end, otherwise a round join which covers the gap on the outer side of the turn
+        bool has_neighbour = at_end ? neighbours.y > 0.5 : neighbours.x > 0.5;
+        bool rounded = has_neighbour ? at_end && lineJoin == 2 : lineCap == 1;
+        bool square = !has_neighbour && lineCap == 2;
+        if ((rounded || square) && position.z > 1.5) {
+            vec2 outward = at_end ? dir : -dir;
+            vec2 rim = vec2(cos(position.y), sin(position.y));
+            // The rim of a square cap is the rim of the half disk projected onto a half square
+            if (square) {
+                rim /= max(abs(rim.x), abs(rim.y));
+            }
+            offset = half_width * (rim.x * outward + rim.y * normal);
+        }
+    } else if (neighbours.y > 0.5 && lineJoin != 2 && !degenerate) {
+        vec4 clip_next = viewProjection * modelMatrix * vec4(point_next, 1.0);
+        if (unclipped_end.z + unclipped_end.w >= 0.0 && clip_next.z + clip_next.w >= 0.0) {
+            vec2 next_dir = safe_normalize(to_screen(clip_next) - screen_end, dir);
+            // The corners of the two segments on the outer side of the turn
+            float side = dir.x * next_dir.y - dir.y * next_dir.x > 0.0 ? -1.0 : 1.0;
+            vec2 a = side * normal;
+            vec2 b = side * vec2(-next_dir.y, next_dir.x);
+            if (position.y > 2.5) {
+                offset = half_width * b;
+            } else if (position.y > 1.5) {
+                // The miter corner, or the middle of the bevel if the miter is too long
+                vec2 m = 0.5 * (a + b);
+                float cos_half_angle = length(m);
+                if (lineJoin == 0 && cos_half_angle > 1e-3 && 1.0 / cos_half_angle <= miterLimit) {
+                    m /= cos_half_angle * cos_half_angle;
+                }
+                offset = half_width * m;
+            } else if (position.y > 0.5) {
+                offset = half_width * a;
+            }
+        }
+    }
+
+    gl_Position = clip;
+    gl_Position.xy += offset / (0.5 * viewportSize) * clip.w;
+}
@@ -0,0 +1,76 @@
+
+uniform mat3 shapeInverseTransformation;
+uniform int shapeType;
+uniform int lineCap;
+uniform vec2 shapeHalfSize;
+uniform float shapeRadius;
+uniform float strokeWidth;
+uniform vec2 dashPattern;
+
+// Signed distance to the shape in local coordinates, negative inside
+float shape_distance(vec2 p)
+{
+    vec2 hs = shapeHalfSize;
+    if (shapeType == 0) {
+        // Rectangle with rounded corners
+        float r = min(shapeRadius, min(hs.x, hs.y));
+        vec2 q = abs(p) - hs + r;
+        return length(max(q, 0.0)) + min(max(q.x, q.y), 0.0) - r;
+    } else if (shapeType == 1) {
+        // Circle
+        return length(p) - shapeRadius;
+    } else {
+        // Line from (-hs.x, 0) to (hs.x, 0) with a thickness of 2 * hs.y
+        if (lineCap == 1) {
+            return length(vec2(p.x - clamp(p.x, -hs.x, hs.x), p.y)) - hs.y;
+        }
+        vec2 h = lineCap == 2 ? hs + vec2(hs.y, 0.0) : hs;
+        vec2 q = abs(p) - h;
+        return length(max(q, 0.0)) + min(max(q.x, q.y), 0.0);
+    }
+}
+
+// The distance along the outline of the shape, used for dashing
+float shape_perimeter_position(vec2 p)
+{
+    vec2 hs = shapeHalfSize;
+    if (shapeType == 0) {
+        vec2 q = abs(p) - hs;
+        if (q.y > q.x) {
+            return p.y < 0.0 ? p.x + hs.x : 3.0 * hs.x + 2.0 * hs.y - p.x;
+        }
+        return p.x > 0.0 ? 2.0 * hs.x + hs.y + p.y : 4.0 * hs.x + 3.0 * hs.y - p.y;
+    } else if (shapeType == 1) {
+        return (atan(p.y, p.x) + 3.14159265) * shapeRadius;
+    } else {
+        return p.x + hs.x;
+    }
+}
+
+void main()
+{
+    material_main();
+
+    vec2 p = (shapeInverseTransformation * vec3(pos.xy, 1.0)).xy;
+    float d = shape_distance(p);
+    if (strokeWidth > 0.0) {
+        d = abs(d) - 0.5 * strokeWidth;
+    }
+    if (dashPattern.x > 0.0 && dashPattern.y > 0.0) {
+        float period = dashPattern.x + dashPattern.y;
+        float m = mod(shape_perimeter_position(p), period);
+        float dash_distance = m < dashPattern.x ? -min(m, dashPattern.x - m) : min(m - dashPattern.x, period - m);
+        d = max(d, dash_distance);
+    }
+
+    // Anti-aliasing over one pixel
+    float coverage = clamp(0.5 - d / max(fwidth(d), 0.0001), 0.0, 1.0);
+    if (coverage <= 0.0) {
+        discard;
+    }
+    #ifdef PREMULTIPLIED_ALPHA
+    outColor *= coverage;
+    #else
+    outColor.a *= coverage;
+    #endif
+}
@@ -0,0 +1,11 @@
+
+in vec2 surfel_coords;
+
+void main()
+{
+    // Cut the disc out of the quad
+    if (dot(surfel_coords, surfel_coords) > 1.0) {
+        discard;
+    }
+    material_main();
+}
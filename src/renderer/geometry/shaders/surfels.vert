@@ -0,0 +1,46 @@
+uniform mat4 viewProjection;
+uniform mat4 modelMatrix;
+uniform mat4 normalMatrix;
+uniform vec3 eye;
+
+in vec3 position;
+in vec3 center;
+in vec3 instance_normal;
+in float radius;
+
+#ifdef USE_VERTEX_COLORS
+in vec4 instance_color;
+#endif
+
+out vec3 pos;
+out vec3 nor;
+out vec2 uvs;
+out vec4 col;
+out vec2 surfel_coords;
+flat out int instance_id;
+
+void main()
+{
+    // Span the quad in the plane orthogonal to the normal
+    vec3 n = normalize(instance_normal);
+    vec3 t = normalize(cross(n, abs(n.y) < 0.99 ? vec3(0.0, 1.0, 0.0) : vec3(1.0, 0.0, 0.0)));
+    vec3 b = cross(n, t);
+    vec4 world_pos = modelMatrix * vec4(center + radius * (position.x * t + position.y * b), 1.0);
+    pos = world_pos.xyz / world_pos.w;
+
+    // Shade the side of the disc facing the camera
+    nor = normalize(mat3(normalMatrix) * n);
+    if (dot(nor, eye - pos) < 0.0) {
+        nor = -nor;
+    }
+
+    surfel_coords = position.xy;
+    uvs = 0.5 * position.xy + 0.5;
+#ifdef USE_VERTEX_COLORS
+    col = instance_color;
+#else
+    col = vec4(1.0);
+#endif
+    gl_Position = viewProjection * world_pos;
+    instance_id = gl_InstanceID;
+}
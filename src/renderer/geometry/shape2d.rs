@@ -0,0 +1,164 @@
+use crate::renderer::*;
+
+///
+/// Specifies whether the inside of a 2D shape, for example a [Circle] or [Rectangle], is filled or only the outline is drawn.
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ShapeMode {
+    /// The inside of the shape is filled.
+    #[default]
+    Fill,
+    /// Only the outline of the shape is drawn with the given stroke width in logical pixels.
+    Stroke(f32),
+}
+
+///
+/// The shape of the end points of a [Line].
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LineCap {
+    /// The line ends exactly at the end points.
+    #[default]
+    Butt,
+    /// The line ends in a half circle centered at the end points.
+    Round,
+    /// The line ends in a half square centered at the end points.
+    Square,
+}
+
+///
+/// The number of physical pixels added around a 2D shape to make room for the anti-aliased edge.
+///
+const ANTI_ALIASING_MARGIN: f32 = 2.0;
+
+///
+/// The parameters describing a 2D shape in its local coordinate system, which are used in the fragment shader to compute the signed distance to the shape.
+///
+#[derive(Clone, Copy)]
+pub(super) struct ShapeParameters {
+    pub shape_type: i32,
+    pub line_cap: LineCap,
+    pub half_size: Vec2,
+    pub radius: f32,
+    pub stroke_width: f32,
+    pub dash_pattern: Option<(f32, f32)>,
+    pub transformation: Mat3,
+}
+
+impl ShapeParameters {
+    ///
+    /// Returns the transformation of a unit square centered at origo to a quad covering the shape including the anti-aliased edge.
+    ///
+    pub fn quad_transformation(&self) -> Mat3 {
+        let extent = match self.shape_type {
+            1 => vec2(self.radius, self.radius),
+            2 if self.line_cap != LineCap::Butt => {
+                vec2(self.half_size.x + self.half_size.y, self.half_size.y)
+            }
+            _ => self.half_size,
+        } + vec2(1.0, 1.0) * (0.5 * self.stroke_width + ANTI_ALIASING_MARGIN);
+        self.transformation * Mat3::from_nonuniform_scale(2.0 * extent.x, 2.0 * extent.y)
+    }
+}
+
+///
+/// Renders the shape geometry with the given material, where the fragment shader of the material is extended to cut out the shape with anti-aliased edges.
+/// Deferred materials are rendered without cutting out the shape since the shape coverage cannot be blended.
+///
+pub(super) fn render_shape(
+    context: &Context,
+    geometry: &dyn Geometry,
+    parameters: ShapeParameters,
+    material: &dyn Material,
+    viewer: &dyn Viewer,
+    lights: &[&dyn Light],
+) {
+    let result = if material.material_type() == MaterialType::Deferred {
+        render_with_material(context, viewer, geometry, material, lights)
+    } else {
+        render_with_material(
+            context,
+            viewer,
+            geometry,
+            ShapeMaterial {
+                material,
+                parameters,
+            },
+            lights,
+        )
+    };
+    if let Err(e) = result {
+        panic!("{}", e.to_string());
+    }
+}
+
+struct ShapeMaterial<'a> {
+    material: &'a dyn Material,
+    parameters: ShapeParameters,
+}
+
+impl Material for ShapeMaterial<'_> {
+    fn fragment_shader_source(&self, lights: &[&dyn Light]) -> String {
+        let source = self.material.fragment_shader_source(lights).replacen(
+            "void main(",
+            "void material_main(",
+            1,
+        );
+        let position = if source.contains("in vec3 pos;") {
+            ""
+        } else {
+            "in vec3 pos;\n"
+        };
+        format!(
+            "{}\n{}{}",
+            source,
+            position,
+            include_str!("shaders/shape2d.frag")
+        )
+    }
+
+    fn id(&self) -> EffectMaterialId {
+        // The geometry ID of the shapes ensures that the extended shader is cached separately from the original material shader
+        self.material.id()
+    }
+
+    fn use_uniforms(&self, program: &Program, viewer: &dyn Viewer, lights: &[&dyn Light]) {
+        self.material.use_uniforms(program, viewer, lights);
+        let parameters = &self.parameters;
+        program.use_uniform(
+            "shapeInverseTransformation",
+            parameters.transformation.invert().unwrap(),
+        );
+        program.use_uniform_if_required("shapeType", parameters.shape_type);
+        program.use_uniform_if_required(
+            "lineCap",
+            match parameters.line_cap {
+                LineCap::Butt => 0,
+                LineCap::Round => 1,
+                LineCap::Square => 2,
+            },
+        );
+        program.use_uniform_if_required("shapeHalfSize", parameters.half_size);
+        program.use_uniform_if_required("shapeRadius", parameters.radius);
+        program.use_uniform_if_required("strokeWidth", parameters.stroke_width);
+        program.use_uniform_if_required(
+            "dashPattern",
+            parameters
+                .dash_pattern
+                .map(|(dash, gap)| vec2(dash, gap))
+                .unwrap_or(vec2(0.0, 0.0)),
+        );
+    }
+
+    fn render_states(&self) -> RenderStates {
+        let mut render_states = self.material.render_states();
+        if render_states.blend == Blend::Disabled {
+            render_states.blend = Blend::TRANSPARENCY;
+        }
+        render_states
+    }
+
+    fn material_type(&self) -> MaterialType {
+        self.material.material_type()
+    }
+}
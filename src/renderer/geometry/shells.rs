@@ -0,0 +1,271 @@
+use super::BaseMesh;
+use crate::core::*;
+use crate::renderer::*;
+
+///
+/// Renders a mesh a number of times, called shells, each offset a bit further along the normal than the previous,
+/// which together with a [FurMaterial] creates the appearance of fur, hair or grass.
+/// The shells are rendered in a single instanced draw call and the outer shells are bent by [Shells::gravity] and [Shells::wind].
+///
+/// The offset of each vertex in a shell at height `h`, which is `0` for the inner shell and `1` for the outer shell, is
+///
+/// ```no_rust
+/// offset = length * (h * normal + h * h * (gravity + wind * sin(wind_frequency * time + phase)))
+/// ```
+///
+/// where the phase depends on the position, so the wind moves across the surface.
+/// The shells only move if the [Shells::animate] is called every frame.
+///
+/// When generating shadow maps, use [Shells::base] instead of the shells, since the shells would otherwise cast a solid shadow
+/// of the size of the outer shell.
+///
+pub struct Shells {
+    context: Context,
+    base_mesh: BaseMesh,
+    aabb: AxisAlignedBoundingBox,
+    transformation: Mat4,
+    shell_count: u32,
+    /// The distance between the inner and the outer shell.
+    pub length: f32,
+    /// The bend applied to the outer shell relative to the [Shells::length] and defined in the world coordinate system,
+    /// for example `vec3(0.0, -0.5, 0.0)` to make long fur hang down.
+    pub gravity: Vec3,
+    /// The maximum bend caused by wind applied to the outer shell relative to the [Shells::length] and defined in the world coordinate system.
+    pub wind: Vec3,
+    /// How fast the wind sways the shells back and forth in radians per millisecond.
+    pub wind_frequency: f32,
+    time: f32,
+}
+
+impl Shells {
+    ///
+    /// Creates the given number of shells from the given mesh, with the outer shell offset the given length along the normals.
+    /// The normals are computed if the mesh does not have normals.
+    ///
+    pub fn new(context: &Context, cpu_mesh: &CpuMesh, shell_count: u32, length: f32) -> Self {
+        #[cfg(debug_assertions)]
+        cpu_mesh.validate().expect("invalid cpu mesh");
+
+        let base_mesh = if cpu_mesh.normals.is_some() {
+            BaseMesh::new(context, cpu_mesh)
+        } else {
+            let mut cpu_mesh = cpu_mesh.clone();
+            cpu_mesh.compute_normals();
+            BaseMesh::new(context, &cpu_mesh)
+        };
+        Self {
+            context: context.clone(),
+            base_mesh,
+            aabb: cpu_mesh.compute_aabb(),
+            transformation: Mat4::identity(),
+            shell_count: shell_count.max(1),
+            length,
+            gravity: Vec3::zero(),
+            wind: Vec3::zero(),
+            wind_frequency: 0.002,
+            time: 0.0,
+        }
+    }
+
+    ///
+    /// Returns the local to world transformation applied to the mesh before the shells are offset.
+    ///
+    pub fn transformation(&self) -> Mat4 {
+        self.transformation
+    }
+
+    ///
+    /// Set the local to world transformation applied to the mesh before the shells are offset.
+    ///
+    pub fn set_transformation(&mut self, transformation: Mat4) {
+        self.transformation = transformation;
+    }
+
+    ///
+    /// Returns the number of shells including the inner shell, which is the mesh itself.
+    ///
+    pub fn shell_count(&self) -> u32 {
+        self.shell_count
+    }
+
+    ///
+    /// Sets the number of shells including the inner shell, which is the mesh itself.
+    /// More shells gives more detailed strands but costs more to render.
+    ///
+    pub fn set_shell_count(&mut self, shell_count: u32) {
+        self.shell_count = shell_count.max(1);
+    }
+
+    ///
+    /// Returns a geometry which only renders the inner shell, that is the mesh itself,
+    /// which is useful as a shadow caster, for example in [DirectionalLight::generate_shadow_map].
+    ///
+    pub fn base(&self) -> ShellsBase<'_> {
+        ShellsBase(self)
+    }
+
+    fn draw_shells(
+        &self,
+        viewer: &dyn Viewer,
+        program: &Program,
+        render_states: RenderStates,
+        instance_count: u32,
+    ) {
+        if let Some(inverse) = self.transformation.invert() {
+            program.use_uniform("normalMatrix", inverse.transpose());
+        } else {
+            // determinant is float zero
+            return;
+        }
+        program.use_uniform("viewProjection", viewer.projection() * viewer.view());
+        program.use_uniform("modelMatrix", self.transformation);
+        program.use_uniform("shellCount", self.shell_count as f32);
+        program.use_uniform_if_required("shellLength", self.length);
+        program.use_uniform_if_required("shellGravity", self.gravity);
+        program.use_uniform_if_required("shellWind", self.wind);
+        program.use_uniform_if_required("shellTime", self.wind_frequency * self.time);
+
+        self.base_mesh
+            .draw_instanced(program, render_states, viewer, instance_count);
+    }
+}
+
+impl<'a> IntoIterator for &'a Shells {
+    type Item = &'a dyn Geometry;
+    type IntoIter = std::iter::Once<&'a dyn Geometry>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        std::iter::once(self)
+    }
+}
+
+impl Geometry for Shells {
+    fn id(&self) -> GeometryId {
+        GeometryId::Shells(
+            self.base_mesh.tangents.is_some(),
+            self.base_mesh.uvs.is_some(),
+            self.base_mesh.colors.is_some(),
+        )
+    }
+
+    fn vertex_shader_source(&self) -> String {
+        format!("#define SHELLS\n{}", self.base_mesh.vertex_shader_source())
+    }
+
+    fn draw(&self, viewer: &dyn Viewer, program: &Program, render_states: RenderStates) {
+        self.draw_shells(viewer, program, render_states, self.shell_count);
+    }
+
+    fn aabb(&self) -> AxisAlignedBoundingBox {
+        // The shells can at most be offset the length along the normal plus the bend
+        let reach = self.length
+            * (1.0 + self.gravity.magnitude() + self.wind.magnitude())
+            * vec3(1.0, 1.0, 1.0);
+        let mut aabb = self.aabb.transformed(self.transformation);
+        if !aabb.is_empty() {
+            aabb.expand(&[aabb.min() - reach, aabb.max() + reach]);
+        }
+        aabb
+    }
+
+    fn render_with_material(
+        &self,
+        material: &dyn Material,
+        viewer: &dyn Viewer,
+        lights: &[&dyn Light],
+    ) {
+        if let Err(e) = render_with_material(&self.context, viewer, &self, material, lights) {
+            panic!("{}", e.to_string());
+        }
+    }
+
+    fn render_with_effect(
+        &self,
+        material: &dyn Effect,
+        viewer: &dyn Viewer,
+        lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) {
+        if let Err(e) = render_with_effect(
+            &self.context,
+            viewer,
+            self,
+            material,
+            lights,
+            color_texture,
+            depth_texture,
+        ) {
+            panic!("{}", e.to_string());
+        }
+    }
+
+    fn animate(&mut self, time: f32) {
+        self.time = time;
+    }
+}
+
+///
+/// The inner shell of [Shells], see [Shells::base].
+///
+#[derive(Clone, Copy)]
+pub struct ShellsBase<'a>(&'a Shells);
+
+impl<'a> IntoIterator for &'a ShellsBase<'_> {
+    type Item = &'a dyn Geometry;
+    type IntoIter = std::iter::Once<&'a dyn Geometry>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        std::iter::once(self)
+    }
+}
+
+impl Geometry for ShellsBase<'_> {
+    fn id(&self) -> GeometryId {
+        self.0.id()
+    }
+
+    fn vertex_shader_source(&self) -> String {
+        self.0.vertex_shader_source()
+    }
+
+    fn draw(&self, viewer: &dyn Viewer, program: &Program, render_states: RenderStates) {
+        self.0.draw_shells(viewer, program, render_states, 1);
+    }
+
+    fn aabb(&self) -> AxisAlignedBoundingBox {
+        self.0.aabb.transformed(self.0.transformation)
+    }
+
+    fn render_with_material(
+        &self,
+        material: &dyn Material,
+        viewer: &dyn Viewer,
+        lights: &[&dyn Light],
+    ) {
+        if let Err(e) = render_with_material(&self.0.context, viewer, self, material, lights) {
+            panic!("{}", e.to_string());
+        }
+    }
+
+    fn render_with_effect(
+        &self,
+        material: &dyn Effect,
+        viewer: &dyn Viewer,
+        lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) {
+        if let Err(e) = render_with_effect(
+            &self.0.context,
+            viewer,
+            self,
+            material,
+            lights,
+            color_texture,
+            depth_texture,
+        ) {
+            panic!("{}", e.to_string());
+        }
+    }
+}
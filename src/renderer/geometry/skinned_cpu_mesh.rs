@@ -0,0 +1,183 @@
+use crate::core::*;
+use crate::renderer::*;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, RwLock};
+
+///
+/// A [CpuMesh] in bind pose together with the indices and weights of the (up to four) joints influencing each vertex,
+/// which makes it possible to evaluate the skinning on the CPU for a given pose.
+///
+/// A pose is given as a palette of joint matrices, where each matrix is the transformation of a joint from the bind pose to the current pose,
+/// ie. the global transformation of the joint multiplied by its inverse bind matrix.
+/// Use [SkinnedCpuMesh::intersect] to find intersections between rays and the mesh in the current pose, for example for picking,
+/// and [SkinnedCpuMesh::update_mesh] to skin a [Mesh] on the CPU on platforms where skinning on the GPU is not available.
+///
+/// ```
+/// # use three_d::*;
+/// // An arm hanging down from the shoulder, which is influenced by one joint
+/// let shoulder = vec3(0.0, 2.0, 0.0);
+/// let mut arm = CpuMesh::cube();
+/// arm.transform(Mat4::from_translation(vec3(0.0, 1.5, 0.0)) * Mat4::from_nonuniform_scale(0.1, 0.5, 0.1))
+///     .unwrap();
+/// let vertex_count = arm.vertex_count();
+/// let arm = SkinnedCpuMesh::new(arm, vec![vec4(0.0, 0.0, 0.0, 0.0); vertex_count], vec![vec4(1.0, 0.0, 0.0, 0.0); vertex_count]);
+///
+/// // The arm is raised over five frames and the ray above the shoulder only hits the arm when it is fully raised
+/// for frame in 0..=4 {
+///     let joint = Mat4::from_translation(shoulder)
+///         * Mat4::from_angle_z(degrees(45.0 * frame as f32))
+///         * Mat4::from_translation(-shoulder);
+///     let hit = arm.intersect(&[joint], vec3(0.0, 2.5, -5.0), vec3(0.0, 0.0, 1.0), 10.0);
+///     assert_eq!(hit.is_some(), frame == 4);
+/// }
+/// ```
+///
+pub struct SkinnedCpuMesh {
+    /// The mesh in bind pose.
+    pub mesh: CpuMesh,
+    /// The indices of the (up to four) joints influencing each vertex.
+    pub joint_indices: Vec<Vec4>,
+    /// The weights of the (up to four) joints influencing each vertex, which should sum to one.
+    pub joint_weights: Vec<Vec4>,
+    bvh_cache: RwLock<Option<(u64, Arc<Bvh>)>>,
+}
+
+impl SkinnedCpuMesh {
+    ///
+    /// Creates a new skinned mesh from a mesh in bind pose and the indices and weights of the joints influencing each vertex.
+    ///
+    pub fn new(mesh: CpuMesh, joint_indices: Vec<Vec4>, joint_weights: Vec<Vec4>) -> Self {
+        Self {
+            mesh,
+            joint_indices,
+            joint_weights,
+            bvh_cache: RwLock::new(None),
+        }
+    }
+
+    ///
+    /// Returns the vertex positions in the pose given by the joint matrices.
+    /// Vertices without joint weights and joint indices outside the palette are not transformed.
+    ///
+    pub fn skinned_positions(&self, joints: &[Mat4]) -> Vec<Vec3> {
+        self.mesh
+            .positions
+            .to_f32()
+            .into_iter()
+            .enumerate()
+            .map(|(i, position)| {
+                (self.skinning_matrix(i, joints) * position.extend(1.0)).truncate()
+            })
+            .collect()
+    }
+
+    ///
+    /// Returns the vertex normals in the pose given by the joint matrices, or `None` if the mesh does not have normals.
+    ///
+    pub fn skinned_normals(&self, joints: &[Mat4]) -> Option<Vec<Vec3>> {
+        self.mesh.normals.as_ref().map(|normals| {
+            normals
+                .iter()
+                .enumerate()
+                .map(|(i, normal)| {
+                    (self.skinning_matrix(i, joints) * normal.extend(0.0))
+                        .truncate()
+                        .normalize()
+                })
+                .collect()
+        })
+    }
+
+    ///
+    /// Returns the mesh in the pose given by the joint matrices.
+    ///
+    pub fn skinned_mesh(&self, joints: &[Mat4]) -> CpuMesh {
+        CpuMesh {
+            positions: Positions::F32(self.skinned_positions(joints)),
+            normals: self.skinned_normals(joints),
+            ..self.mesh.clone()
+        }
+    }
+
+    ///
+    /// Returns a bounding volume hierarchy over the triangles of the mesh in the pose given by the joint matrices.
+    /// The latest hierarchy is cached and reused as long as the joint matrices are the same, so it is not rebuilt for each ray.
+    ///
+    pub fn bvh(&self, joints: &[Mat4]) -> Arc<Bvh> {
+        let key = palette_hash(joints);
+        if let Some((cached_key, bvh)) = self.bvh_cache.read().unwrap().as_ref() {
+            if *cached_key == key {
+                return bvh.clone();
+            }
+        }
+        let bvh = Arc::new(Bvh::new(&self.skinned_mesh(joints)));
+        *self.bvh_cache.write().unwrap() = Some((key, bvh.clone()));
+        bvh
+    }
+
+    ///
+    /// Returns the distance along the ray to the closest intersection with the mesh in the pose given by the joint matrices and the index of the intersected triangle,
+    /// or `None` if the ray does not intersect the mesh within the given maximum distance, see [Bvh::intersect].
+    /// The ray is given in the space of the mesh and the direction must be normalized.
+    ///
+    pub fn intersect(
+        &self,
+        joints: &[Mat4],
+        origin: Vec3,
+        direction: Vec3,
+        max_distance: f32,
+    ) -> Option<(f32, usize)> {
+        self.bvh(joints).intersect(origin, direction, max_distance)
+    }
+
+    ///
+    /// Skins the given mesh on the CPU by updating its vertex positions, normals and bounding box to the pose given by the joint matrices.
+    /// The mesh must have been created from [SkinnedCpuMesh::mesh] and must not share its buffers with other meshes.
+    ///
+    pub fn update_mesh(&self, mesh: &mut Mesh, joints: &[Mat4]) {
+        let positions = self.skinned_positions(joints);
+        let mut aabb = AxisAlignedBoundingBox::EMPTY;
+        aabb.expand(&positions);
+        mesh.positions_mut().fill(&positions);
+        if let (Some(normals), Some(buffer)) = (self.skinned_normals(joints), mesh.normals_mut()) {
+            buffer.fill(&normals);
+        }
+        mesh.set_aabb(aabb);
+    }
+
+    fn skinning_matrix(&self, vertex: usize, joints: &[Mat4]) -> Mat4 {
+        let (Some(indices), Some(weights)) = (
+            self.joint_indices.get(vertex),
+            self.joint_weights.get(vertex),
+        ) else {
+            return Mat4::identity();
+        };
+        let mut matrix = Mat4::zero();
+        let mut total_weight = 0.0;
+        for j in 0..4 {
+            if let Some(joint) = joints.get(indices[j] as usize) {
+                if weights[j] > 0.0 {
+                    matrix += joint * weights[j];
+                    total_weight += weights[j];
+                }
+            }
+        }
+        if total_weight > 0.0 {
+            matrix / total_weight
+        } else {
+            Mat4::identity()
+        }
+    }
+}
+
+fn palette_hash(joints: &[Mat4]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    for joint in joints {
+        let values: &[f32; 16] = joint.as_ref();
+        for value in values {
+            value.to_bits().hash(&mut hasher);
+        }
+    }
+    hasher.finish()
+}
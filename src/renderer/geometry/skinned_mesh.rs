@@ -0,0 +1,256 @@
+use crate::core::*;
+use crate::renderer::*;
+
+///
+/// A triangle mesh which is deformed by a skeleton using linear blend skinning in the vertex shader,
+/// for example a rigged character animated by an [AnimationPlayer].
+///
+/// Each vertex is influenced by up to four joints given by the joint indices and weights at construction.
+/// The joint matrices, which transform from the bind pose to the current pose of each joint in the local space of the mesh,
+/// are given each frame using [SkinnedMesh::set_joint_matrices] and the skinned position is blended from the positions transformed by each of the joint matrices.
+/// The transformation of the mesh is applied after the skinning.
+///
+/// The bounding box, which is used for culling, is by default derived from the joint matrices so it contains the animated mesh,
+/// but can also be set using [SkinnedMesh::set_aabb], for example to a box around the whole range of motion of an animation.
+///
+pub struct SkinnedMesh {
+    context: Context,
+    mesh: Mesh,
+    bind_aabb: AxisAlignedBoundingBox,
+    influencing_joints: Vec<bool>,
+    has_unskinned_vertices: bool,
+    joint_matrices: Vec<Mat4>,
+    joint_texture: Texture2D,
+    aabb: Option<AxisAlignedBoundingBox>,
+}
+
+impl SkinnedMesh {
+    ///
+    /// Creates a new skinned mesh from the given [CpuMesh] in its bind pose and the indices and weights of the (up to four) joints influencing each vertex.
+    /// The joint matrices are initialized to the identity, ie. the mesh is rendered in the bind pose until [SkinnedMesh::set_joint_matrices] is called.
+    ///
+    /// # Panic
+    /// Will panic if the number of joint indices or joint weights is not the same as the number of vertices.
+    ///
+    pub fn new(
+        context: &Context,
+        cpu_mesh: &CpuMesh,
+        joint_indices: &[[u16; 4]],
+        joint_weights: &[Vec4],
+    ) -> Self {
+        let vertex_count = cpu_mesh.vertex_count();
+        assert_eq!(
+            joint_indices.len(),
+            vertex_count,
+            "the number of joint indices must be the same as the number of vertices"
+        );
+        assert_eq!(
+            joint_weights.len(),
+            vertex_count,
+            "the number of joint weights must be the same as the number of vertices"
+        );
+
+        let joint_count = joint_indices
+            .iter()
+            .flatten()
+            .map(|i| *i as usize + 1)
+            .max()
+            .unwrap_or(1);
+        let mut influencing_joints = vec![false; joint_count];
+        let mut has_unskinned_vertices = false;
+        for (indices, weights) in joint_indices.iter().zip(joint_weights) {
+            has_unskinned_vertices |= weights.x + weights.y + weights.z + weights.w <= 0.0;
+            for (i, weight) in indices
+                .iter()
+                .zip([weights.x, weights.y, weights.z, weights.w])
+            {
+                if weight != 0.0 {
+                    influencing_joints[*i as usize] = true;
+                }
+            }
+        }
+
+        let mut mesh = Mesh::new(context, cpu_mesh);
+        *mesh.joint_indices_mut() = Some(VertexBuffer::new_with_data(
+            context,
+            &joint_indices
+                .iter()
+                .map(|i| vec4(i[0] as f32, i[1] as f32, i[2] as f32, i[3] as f32))
+                .collect::<Vec<_>>(),
+        ));
+        *mesh.joint_weights_mut() = Some(VertexBuffer::new_with_data(context, joint_weights));
+
+        let mut skinned_mesh = Self {
+            context: context.clone(),
+            mesh,
+            bind_aabb: cpu_mesh.compute_aabb(),
+            influencing_joints,
+            has_unskinned_vertices,
+            joint_matrices: Vec::new(),
+            joint_texture: new_joint_texture(context, joint_count),
+            aabb: None,
+        };
+        skinned_mesh.set_joint_matrices(&vec![Mat4::identity(); joint_count]);
+        skinned_mesh
+    }
+
+    ///
+    /// Sets the joint matrices, ie. the transformation from the bind pose to the current pose of each joint in the local space of the mesh,
+    /// for example the result of [AnimationPlayer::joint_matrices].
+    /// The joint indices given at construction index into these matrices and joints without a matrix are not moved.
+    ///
+    pub fn set_joint_matrices(&mut self, joint_matrices: &[Mat4]) {
+        let joint_count = joint_matrices.len().max(self.influencing_joints.len());
+        if self.joint_texture.height() as usize != joint_count {
+            self.joint_texture = new_joint_texture(&self.context, joint_count);
+        }
+        self.joint_matrices = joint_matrices.to_vec();
+        self.joint_matrices.resize(joint_count, Mat4::identity());
+        let data = self
+            .joint_matrices
+            .iter()
+            .flat_map(|m| [m.x, m.y, m.z, m.w].map(|c| [c.x, c.y, c.z, c.w]))
+            .collect::<Vec<_>>();
+        self.joint_texture.fill(&data);
+    }
+
+    ///
+    /// Returns the joint matrices, see [SkinnedMesh::set_joint_matrices].
+    ///
+    pub fn joint_matrices(&self) -> &[Mat4] {
+        &self.joint_matrices
+    }
+
+    ///
+    /// Sets the bounding box in the local space of the mesh, which is used instead of the bounding box derived from the joint matrices.
+    /// Use `None` to go back to deriving the bounding box from the joint matrices.
+    ///
+    pub fn set_aabb(&mut self, aabb: Option<AxisAlignedBoundingBox>) {
+        self.aabb = aabb;
+    }
+
+    ///
+    /// Returns the local to world transformation applied to this mesh.
+    ///
+    pub fn transformation(&self) -> Mat4 {
+        self.mesh.transformation()
+    }
+
+    ///
+    /// Set the local to world transformation applied to this mesh, which is applied after the skinning.
+    ///
+    pub fn set_transformation(&mut self, transformation: Mat4) {
+        self.mesh.set_transformation(transformation);
+    }
+
+    ///
+    /// Specifies a function which takes a time parameter as input and returns a transformation that should be applied to this mesh at the given time,
+    /// see [Mesh::set_animation].
+    ///
+    pub fn set_animation(&mut self, animation: impl Fn(f32) -> Mat4 + Send + Sync + 'static) {
+        self.mesh.set_animation(animation);
+    }
+}
+
+fn new_joint_texture(context: &Context, joint_count: usize) -> Texture2D {
+    Texture2D::new_empty::<[f32; 4]>(
+        context,
+        4,
+        joint_count as u32,
+        Interpolation::Nearest,
+        Interpolation::Nearest,
+        None,
+        Wrapping::ClampToEdge,
+        Wrapping::ClampToEdge,
+    )
+}
+
+impl<'a> IntoIterator for &'a SkinnedMesh {
+    type Item = &'a dyn Geometry;
+    type IntoIter = std::iter::Once<&'a dyn Geometry>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        std::iter::once(self)
+    }
+}
+
+impl Geometry for SkinnedMesh {
+    fn id(&self) -> GeometryId {
+        let base_mesh = self.mesh.base_mesh();
+        GeometryId::SkinnedMesh(
+            base_mesh.normals.is_some(),
+            base_mesh.tangents.is_some(),
+            base_mesh.uvs.is_some(),
+            base_mesh.colors.is_some(),
+            base_mesh.lightmap_uvs.is_some(),
+        )
+    }
+
+    fn vertex_shader_source(&self) -> String {
+        format!("#define SKINNING\n{}", self.mesh.vertex_shader_source())
+    }
+
+    fn draw(&self, viewer: &dyn Viewer, program: &Program, render_states: RenderStates) {
+        program.use_texture("jointMatrices", &self.joint_texture);
+        self.mesh.draw(viewer, program, render_states);
+    }
+
+    fn aabb(&self) -> AxisAlignedBoundingBox {
+        // The skinned positions are weighted averages of the positions transformed by each joint matrix,
+        // so they are inside the union of the bind pose bounding box transformed by each of the influencing joint matrices.
+        // Vertices without any weights are not moved.
+        let aabb = self.aabb.unwrap_or_else(|| {
+            let mut aabb = if self.has_unskinned_vertices {
+                self.bind_aabb
+            } else {
+                AxisAlignedBoundingBox::EMPTY
+            };
+            for (joint_matrix, _) in self
+                .joint_matrices
+                .iter()
+                .zip(&self.influencing_joints)
+                .filter(|(_, influencing)| **influencing)
+            {
+                aabb.expand_with_aabb(self.bind_aabb.transformed(*joint_matrix));
+            }
+            aabb
+        });
+        aabb.transformed(self.mesh.local_to_world())
+    }
+
+    fn animate(&mut self, time: f32) {
+        self.mesh.animate(time);
+    }
+
+    fn render_with_material(
+        &self,
+        material: &dyn Material,
+        viewer: &dyn Viewer,
+        lights: &[&dyn Light],
+    ) {
+        if let Err(e) = render_with_material(&self.context, viewer, &self, material, lights) {
+            panic!("{}", e.to_string());
+        }
+    }
+
+    fn render_with_effect(
+        &self,
+        material: &dyn Effect,
+        viewer: &dyn Viewer,
+        lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) {
+        if let Err(e) = render_with_effect(
+            &self.context,
+            viewer,
+            self,
+            material,
+            lights,
+            color_texture,
+            depth_texture,
+        ) {
+            panic!("{}", e.to_string());
+        }
+    }
+}
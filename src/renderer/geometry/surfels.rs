@@ -0,0 +1,417 @@
+use crate::core::*;
+use crate::renderer::*;
+use std::collections::BinaryHeap;
+
+///
+/// Defines the attributes of a set of surfels (surface elements), ie. oriented discs approximating a scanned surface, see [SurfelGeometry].
+/// Each list of attributes must contain the same number of elements as the number of positions.
+///
+#[derive(Clone, Debug, Default)]
+pub struct Surfels {
+    /// The center of each surfel.
+    pub positions: Vec<Vec3>,
+    /// The normal of each surfel, which defines the orientation of the disc and is used for shading. Does not need to be normalized.
+    pub normals: Vec<Vec3>,
+    /// The radius of each surfel, see [estimate_surfel_radii] for computing the radii from the density of the positions.
+    pub radii: Vec<f32>,
+    /// The color of each surfel.
+    /// The colors are converted from sRGB to linear sRGB before they are transfered to the GPU, like all other [Srgba] colors, see [SrgbaExt].
+    pub colors: Option<Vec<Srgba>>,
+}
+
+impl Surfels {
+    ///
+    /// Creates surfels from the positions and colors of the given point cloud and the given normals, one for each point.
+    /// The radii are estimated from the distance to the six nearest neighbours of each point, see [estimate_surfel_radii].
+    ///
+    pub fn from_point_cloud(point_cloud: &PointCloud, normals: Vec<Vec3>) -> Self {
+        let positions = point_cloud.positions.to_f32();
+        Self {
+            radii: estimate_surfel_radii(&positions, 6),
+            positions,
+            normals,
+            colors: point_cloud.colors.clone(),
+        }
+    }
+
+    ///
+    /// Returns the number of surfels.
+    ///
+    pub fn count(&self) -> u32 {
+        self.positions.len() as u32
+    }
+
+    ///
+    /// Returns an error if the surfels are not valid.
+    ///
+    pub fn validate(&self) -> Result<(), RendererError> {
+        let count = self.positions.len();
+        let buffer_check = |length: Option<usize>, name: &str| -> Result<(), RendererError> {
+            if let Some(length) = length {
+                if length < count {
+                    Err(RendererError::InvalidBufferLength(
+                        name.to_string(),
+                        count,
+                        length,
+                    ))?;
+                }
+            }
+            Ok(())
+        };
+        buffer_check(Some(self.normals.len()), "normals")?;
+        buffer_check(Some(self.radii.len()), "radii")?;
+        buffer_check(self.colors.as_ref().map(|b| b.len()), "colors")?;
+        Ok(())
+    }
+}
+
+///
+/// Estimates a radius for each of the given positions from the local density of the positions,
+/// which is the distance to the given number of nearest neighbours, for example 6.
+/// This makes the surfels overlap their neighbours, so there are no holes between them, while surfels in dense areas stay small.
+/// The nearest neighbours are found using a k-d tree, so this is fast enough for millions of points.
+///
+/// ```
+/// # use three_d::*;
+/// let positions = (0..100)
+///     .map(|i| vec3((i % 10) as f32, (i / 10) as f32, 0.0))
+///     .collect::<Vec<_>>();
+/// let radii = estimate_surfel_radii(&positions, 4);
+/// // Points inside the grid have four neighbours at distance one, while the corner points also need the neighbours two steps away
+/// assert_eq!(radii[55], 1.0);
+/// assert_eq!(radii[0], 2.0);
+/// ```
+///
+pub fn estimate_surfel_radii(positions: &[Vec3], neighbours: usize) -> Vec<f32> {
+    let tree = KdTree::new(positions);
+    let mut heap = BinaryHeap::with_capacity(neighbours + 1);
+    (0..positions.len())
+        .map(|index| {
+            heap.clear();
+            tree.nearest(positions[index], index, neighbours.max(1), &mut heap);
+            heap.peek().map(|d| d.0.sqrt()).unwrap_or(0.0)
+        })
+        .collect()
+}
+
+///
+/// A geometry consisting of surfels (surface elements), ie. discs oriented by a normal, which is used for splat rendering of scanned surfaces.
+/// All surfels are rendered with one instanced draw call where a quad is expanded for each surfel in the vertex shader
+/// and the fragment shader of the material is extended to cut the disc out of the quad.
+/// The normal of each surfel is given to the material, flipped to face the camera, so lit materials like [PhysicalMaterial] shade the surfels using the lights.
+///
+pub struct SurfelGeometry {
+    context: Context,
+    position_buffer: VertexBuffer<Vec3>,
+    center_buffer: InstanceBuffer<Vec3>,
+    normal_buffer: InstanceBuffer<Vec3>,
+    radius_buffer: InstanceBuffer<f32>,
+    color_buffer: Option<InstanceBuffer<Vec4>>,
+    aabb: AxisAlignedBoundingBox,
+    transformation: Mat4,
+}
+
+impl SurfelGeometry {
+    ///
+    /// Creates a new surfel geometry from the given [Surfels].
+    ///
+    pub fn new(context: &Context, surfels: &Surfels) -> Self {
+        #[cfg(debug_assertions)]
+        surfels.validate().expect("invalid surfels");
+
+        let mut aabb = AxisAlignedBoundingBox::EMPTY;
+        for (position, radius) in surfels.positions.iter().zip(surfels.radii.iter()) {
+            let extent = vec3(*radius, *radius, *radius);
+            aabb.expand(&[position - extent, position + extent]);
+        }
+        Self {
+            context: context.clone(),
+            position_buffer: VertexBuffer::new_with_data(
+                context,
+                &[
+                    vec3(-1.0, -1.0, 0.0),
+                    vec3(1.0, -1.0, 0.0),
+                    vec3(1.0, 1.0, 0.0),
+                    vec3(1.0, 1.0, 0.0),
+                    vec3(-1.0, 1.0, 0.0),
+                    vec3(-1.0, -1.0, 0.0),
+                ],
+            ),
+            center_buffer: InstanceBuffer::new_with_data(context, &surfels.positions),
+            normal_buffer: InstanceBuffer::new_with_data(context, &surfels.normals),
+            radius_buffer: InstanceBuffer::new_with_data(context, &surfels.radii),
+            color_buffer: surfels.colors.as_ref().map(|colors| {
+                InstanceBuffer::new_with_data(
+                    context,
+                    &colors
+                        .iter()
+                        .map(|c| c.to_linear_srgb())
+                        .collect::<Vec<_>>(),
+                )
+            }),
+            aabb,
+            transformation: Mat4::identity(),
+        }
+    }
+
+    ///
+    /// Returns the number of surfels.
+    ///
+    pub fn surfel_count(&self) -> u32 {
+        self.center_buffer.instance_count()
+    }
+
+    ///
+    /// Returns the local to world transformation applied to all surfels.
+    ///
+    pub fn transformation(&self) -> Mat4 {
+        self.transformation
+    }
+
+    ///
+    /// Set the local to world transformation applied to all surfels.
+    ///
+    pub fn set_transformation(&mut self, transformation: Mat4) {
+        self.transformation = transformation;
+    }
+
+    fn draw(&self, program: &Program, render_states: RenderStates, viewer: &dyn Viewer) {
+        if let Some(inverse) = self.transformation.invert() {
+            program.use_uniform_if_required("normalMatrix", inverse.transpose());
+        } else {
+            // determinant is float zero
+            return;
+        }
+        program.use_uniform("viewProjection", viewer.projection() * viewer.view());
+        program.use_uniform("modelMatrix", self.transformation);
+        program.use_uniform_if_required("eye", viewer.position());
+        program.use_vertex_attribute("position", &self.position_buffer);
+        program.use_instance_attribute("center", &self.center_buffer);
+        program.use_instance_attribute("radius", &self.radius_buffer);
+        program.use_instance_attribute("instance_normal", &self.normal_buffer);
+        if let Some(color_buffer) = &self.color_buffer {
+            program.use_instance_attribute("instance_color", color_buffer);
+        }
+        program.draw_arrays_instanced(render_states, viewer.viewport(), 6, self.surfel_count())
+    }
+}
+
+impl<'a> IntoIterator for &'a SurfelGeometry {
+    type Item = &'a dyn Geometry;
+    type IntoIter = std::iter::Once<&'a dyn Geometry>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        std::iter::once(self)
+    }
+}
+
+impl Geometry for SurfelGeometry {
+    fn draw(&self, viewer: &dyn Viewer, program: &Program, render_states: RenderStates) {
+        self.draw(program, render_states, viewer);
+    }
+
+    fn vertex_shader_source(&self) -> String {
+        format!(
+            "{}{}",
+            if self.color_buffer.is_some() {
+                "#define USE_VERTEX_COLORS\n"
+            } else {
+                ""
+            },
+            include_str!("shaders/surfels.vert")
+        )
+    }
+
+    fn id(&self) -> GeometryId {
+        GeometryId::Surfels(self.color_buffer.is_some())
+    }
+
+    fn render_with_material(
+        &self,
+        material: &dyn Material,
+        viewer: &dyn Viewer,
+        lights: &[&dyn Light],
+    ) {
+        if let Err(e) = render_with_material(
+            &self.context,
+            viewer,
+            &self,
+            SurfelMaterial { material },
+            lights,
+        ) {
+            panic!("{}", e.to_string());
+        }
+    }
+
+    fn render_with_effect(
+        &self,
+        material: &dyn Effect,
+        viewer: &dyn Viewer,
+        lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) {
+        if let Err(e) = render_with_effect(
+            &self.context,
+            viewer,
+            self,
+            material,
+            lights,
+            color_texture,
+            depth_texture,
+        ) {
+            panic!("{}", e.to_string());
+        }
+    }
+
+    fn aabb(&self) -> AxisAlignedBoundingBox {
+        self.aabb.transformed(self.transformation)
+    }
+}
+
+///
+/// Extends the fragment shader of the given material to cut the disc of each surfel out of its quad.
+///
+struct SurfelMaterial<'a> {
+    material: &'a dyn Material,
+}
+
+impl Material for SurfelMaterial<'_> {
+    fn fragment_shader_source(&self, lights: &[&dyn Light]) -> String {
+        let source = self.material.fragment_shader_source(lights).replacen(
+            "void main(",
+            "void material_main(",
+            1,
+        );
+        format!("{}\n{}", source, include_str!("shaders/surfels.frag"))
+    }
+
+    fn id(&self) -> EffectMaterialId {
+        // The geometry ID of the surfels ensures that the extended shader is cached separately from the original material shader
+        self.material.id()
+    }
+
+    fn use_uniforms(&self, program: &Program, viewer: &dyn Viewer, lights: &[&dyn Light]) {
+        self.material.use_uniforms(program, viewer, lights);
+    }
+
+    fn render_states(&self) -> RenderStates {
+        self.material.render_states()
+    }
+
+    fn material_type(&self) -> MaterialType {
+        self.material.material_type()
+    }
+}
+
+///
+/// A squared distance to a neighbour, ordered so the farthest neighbour is on top of a [BinaryHeap].
+///
+#[derive(PartialEq)]
+struct Neighbour(f32);
+
+impl Eq for Neighbour {}
+
+impl PartialOrd for Neighbour {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Neighbour {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+///
+/// A balanced k-d tree stored implicitly in a list of indices, where the median of each range is the node splitting the range.
+///
+struct KdTree<'a> {
+    positions: &'a [Vec3],
+    indices: Vec<usize>,
+    axes: Vec<usize>,
+}
+
+impl<'a> KdTree<'a> {
+    fn new(positions: &'a [Vec3]) -> Self {
+        let mut tree = Self {
+            positions,
+            indices: (0..positions.len()).collect(),
+            axes: vec![0; positions.len()],
+        };
+        tree.build(0, positions.len());
+        tree
+    }
+
+    fn build(&mut self, start: usize, end: usize) {
+        if end - start <= 1 {
+            return;
+        }
+        // Split along the axis with the largest extent
+        let mut aabb = AxisAlignedBoundingBox::EMPTY;
+        aabb.expand(
+            &self.indices[start..end]
+                .iter()
+                .map(|i| self.positions[*i])
+                .collect::<Vec<_>>(),
+        );
+        let size = aabb.size();
+        let axis = if size.x >= size.y && size.x >= size.z {
+            0
+        } else if size.y >= size.z {
+            1
+        } else {
+            2
+        };
+        let mid = (start + end) / 2;
+        let positions = self.positions;
+        self.indices[start..end].select_nth_unstable_by(mid - start, |a, b| {
+            positions[*a][axis].total_cmp(&positions[*b][axis])
+        });
+        self.axes[mid] = axis;
+        self.build(start, mid);
+        self.build(mid + 1, end);
+    }
+
+    fn nearest(&self, point: Vec3, exclude: usize, count: usize, heap: &mut BinaryHeap<Neighbour>) {
+        self.search(0, self.indices.len(), point, exclude, count, heap);
+    }
+
+    fn search(
+        &self,
+        start: usize,
+        end: usize,
+        point: Vec3,
+        exclude: usize,
+        count: usize,
+        heap: &mut BinaryHeap<Neighbour>,
+    ) {
+        if start >= end {
+            return;
+        }
+        let mid = (start + end) / 2;
+        let index = self.indices[mid];
+        let position = self.positions[index];
+        if index != exclude {
+            let distance2 = position.distance2(point);
+            if heap.len() < count {
+                heap.push(Neighbour(distance2));
+            } else if heap.peek().is_some_and(|d| distance2 < d.0) {
+                heap.pop();
+                heap.push(Neighbour(distance2));
+            }
+        }
+        let axis = self.axes[mid];
+        let difference = point[axis] - position[axis];
+        let (near, far) = if difference < 0.0 {
+            ((start, mid), (mid + 1, end))
+        } else {
+            ((mid + 1, end), (start, mid))
+        };
+        self.search(near.0, near.1, point, exclude, count, heap);
+        if heap.len() < count || heap.peek().is_some_and(|d| difference * difference < d.0) {
+            self.search(far.0, far.1, point, exclude, count, heap);
+        }
+    }
+}
@@ -0,0 +1,487 @@
+use crate::renderer::*;
+use std::sync::Arc;
+
+///
+/// A smooth curve through a list of control points, more specifically a centripetal Catmull-Rom spline,
+/// which does not overshoot or form loops between control points that are close together.
+/// Consecutive control points at the same position are ignored.
+/// Used for example as the path of a [Sweep].
+///
+#[derive(Clone, Debug)]
+pub struct Spline {
+    points: Vec<Vec3>,
+}
+
+impl Spline {
+    ///
+    /// Creates a new spline through the given control points.
+    ///
+    pub fn new(control_points: &[Vec3]) -> Self {
+        let mut points: Vec<Vec3> = Vec::with_capacity(control_points.len());
+        for p in control_points {
+            if points
+                .last()
+                .is_none_or(|last| !is_same_position(*last, *p))
+            {
+                points.push(*p);
+            }
+        }
+        Self { points }
+    }
+
+    ///
+    /// Returns the control points, without consecutive duplicates.
+    ///
+    pub fn control_points(&self) -> &[Vec3] {
+        &self.points
+    }
+
+    ///
+    /// Returns the number of segments of the spline, ie. one less than the number of control points or zero if there are no control points.
+    ///
+    pub fn segment_count(&self) -> usize {
+        self.points.len().saturating_sub(1)
+    }
+
+    ///
+    /// Returns the position on the spline at the given parameter, where the integer part is the index of the segment and the fractional part
+    /// is the position within that segment, ie. the spline passes through control point `i` at parameter `i`.
+    /// The parameter is clamped to the start and end of the spline.
+    ///
+    pub fn position(&self, parameter: f32) -> Vec3 {
+        match self.points.len() {
+            0 => Vec3::zero(),
+            1 => self.points[0],
+            n => {
+                let parameter = parameter.clamp(0.0, (n - 1) as f32);
+                let segment = (parameter.floor() as usize).min(n - 2);
+                self.segment_position(segment, parameter - segment as f32)
+            }
+        }
+    }
+
+    ///
+    /// Returns points along the spline, including the control points, where each segment is subdivided until the angle between consecutive
+    /// line pieces is at most the given maximum angle and each line piece is at most the given maximum length,
+    /// so more points are placed where the spline is curved.
+    ///
+    pub fn sample(&self, max_angle: impl Into<Radians>, max_length: f32) -> Vec<Vec3> {
+        let max_angle = max_angle.into().0.max(0.001);
+        let max_length = if max_length > 0.0 {
+            max_length
+        } else {
+            f32::INFINITY
+        };
+        let mut points = self.points.first().copied().into_iter().collect::<Vec<_>>();
+        for segment in 0..self.segment_count() {
+            self.subdivide(
+                segment,
+                (0.0, self.points[segment]),
+                (1.0, self.points[segment + 1]),
+                max_angle,
+                max_length,
+                0,
+                &mut points,
+            );
+        }
+        points
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn subdivide(
+        &self,
+        segment: usize,
+        (t0, p0): (f32, Vec3),
+        (t1, p1): (f32, Vec3),
+        max_angle: f32,
+        max_length: f32,
+        depth: u32,
+        points: &mut Vec<Vec3>,
+    ) {
+        let t = 0.5 * (t0 + t1);
+        let p = self.segment_position(segment, t);
+        // Always split a segment once, since the midpoint of an S-shaped segment is on the line between its end points
+        let is_flat = depth > 0 && angle_between(p - p0, p1 - p) <= max_angle;
+        if depth >= 12 || (is_flat && p0.distance(p1) <= max_length) {
+            points.push(p1);
+        } else {
+            self.subdivide(
+                segment,
+                (t0, p0),
+                (t, p),
+                max_angle,
+                max_length,
+                depth + 1,
+                points,
+            );
+            self.subdivide(
+                segment,
+                (t, p),
+                (t1, p1),
+                max_angle,
+                max_length,
+                depth + 1,
+                points,
+            );
+        }
+    }
+
+    fn segment_position(&self, segment: usize, t: f32) -> Vec3 {
+        let n = self.points.len();
+        let p1 = self.points[segment];
+        let p2 = self.points[segment + 1];
+        // Mirror the neighbouring control points at the ends
+        let p0 = if segment > 0 {
+            self.points[segment - 1]
+        } else {
+            2.0 * p1 - p2
+        };
+        let p3 = if segment + 2 < n {
+            self.points[segment + 2]
+        } else {
+            2.0 * p2 - p1
+        };
+
+        // Barry and Goldman's pyramidal formulation with centripetal knot intervals
+        let interval = |a: Vec3, b: Vec3| a.distance(b).sqrt().max(1.0e-4);
+        let k1 = interval(p0, p1);
+        let k2 = k1 + interval(p1, p2);
+        let k3 = k2 + interval(p2, p3);
+        let u = k1 + t * (k2 - k1);
+        let lerp = |a: Vec3, b: Vec3, ka: f32, kb: f32| a + (b - a) * ((u - ka) / (kb - ka));
+        let a1 = lerp(p0, p1, 0.0, k1);
+        let a2 = lerp(p1, p2, k1, k2);
+        let a3 = lerp(p2, p3, k2, k3);
+        let b1 = lerp(a1, a2, 0.0, k2);
+        let b2 = lerp(a2, a3, k1, k3);
+        lerp(b1, b2, k1, k2)
+    }
+}
+
+///
+/// Generates a mesh by sweeping a 2D profile along a path, for example a tube along a curve for pipes and cables (see [Sweep::tube])
+/// or a flat ribbon following a path on a terrain for roads and rivers (see [Sweep::road]).
+///
+/// The profile is oriented along the path using rotation minimizing frames, so the mesh does not twist around the path,
+/// unless a [ground](Sweep::ground) is given, in which case the profile is kept level.
+/// The x-axis of the profile is to the side of the path and the y-axis is up, ie. towards the [up](Sweep::up) direction at the start of the path.
+/// The front side of the mesh is on the right side of the profile when walking along the profile, so a closed profile must be counterclockwise to face outwards.
+/// The normals are smoothed at the profile points, so duplicate a profile point to get a sharp edge.
+///
+/// The uv coordinates are the relative distance along the profile in the u coordinate and the distance along the path in world units in the v coordinate,
+/// so a texture repeats once per unit along the path.
+///
+#[derive(Clone)]
+pub struct Sweep {
+    /// The 2D points of the profile.
+    pub profile: Vec<Vec2>,
+    /// Whether the profile is closed, ie. the last point is connected to the first point.
+    pub closed: bool,
+    /// Whether to close the ends of the mesh with caps. Only applies to closed profiles and assumes that the profile is star-shaped around the average of its points.
+    pub caps: bool,
+    /// The up direction used for orienting the profile at the start of the path.
+    pub up: Vec3,
+    /// The maximum angle between consecutive path pieces when sweeping along a [Spline]. A smaller angle gives more segments where the path is curved.
+    pub max_angle: Radians,
+    /// The maximum length of the path pieces, which is useful for following a [ground](Sweep::ground). Zero means no maximum.
+    pub max_segment_length: f32,
+    /// A height function of the (x, z) coordinates, for example the height map of a [Terrain].
+    /// If given, the profile is kept level and the height of each vertex is set to the height at the vertex plus the [ground offset](Sweep::ground_offset) plus the height of the profile point.
+    pub ground: Option<Arc<dyn Fn(f32, f32) -> f32 + Send + Sync>>,
+    /// The height above the [ground](Sweep::ground), which avoids z-fighting with the ground.
+    pub ground_offset: f32,
+}
+
+impl Sweep {
+    ///
+    /// Creates a sweep of a circle with the given radius and number of points, with caps at the ends.
+    ///
+    pub fn tube(radius: f32, points: u32) -> Self {
+        let points = points.max(3);
+        Self {
+            profile: (0..points)
+                .map(|i| {
+                    let angle = 2.0 * std::f32::consts::PI * i as f32 / points as f32;
+                    radius * vec2(angle.cos(), angle.sin())
+                })
+                .collect(),
+            closed: true,
+            caps: true,
+            up: vec3(0.0, 1.0, 0.0),
+            max_angle: degrees(5.0).into(),
+            max_segment_length: 0.0,
+            ground: None,
+            ground_offset: 0.0,
+        }
+    }
+
+    ///
+    /// Creates a sweep of a flat ribbon with the given width, which follows the given height function, for example the height map of a [Terrain], at the given offset above it.
+    /// The path is split into pieces of at most the given length, so the ribbon follows the ground between the path points.
+    ///
+    pub fn road(
+        width: f32,
+        height_map: Arc<dyn Fn(f32, f32) -> f32 + Send + Sync>,
+        offset: f32,
+        max_segment_length: f32,
+    ) -> Self {
+        Self {
+            profile: vec![vec2(0.5 * width, 0.0), vec2(-0.5 * width, 0.0)],
+            closed: false,
+            caps: false,
+            up: vec3(0.0, 1.0, 0.0),
+            max_angle: degrees(5.0).into(),
+            max_segment_length,
+            ground: Some(height_map),
+            ground_offset: offset,
+        }
+    }
+
+    ///
+    /// Generates the mesh swept along the given spline, which is subdivided according to [Sweep::max_angle] and [Sweep::max_segment_length].
+    ///
+    pub fn along_spline(&self, spline: &Spline) -> CpuMesh {
+        self.along_path(&spline.sample(self.max_angle, self.max_segment_length))
+    }
+
+    ///
+    /// Generates the mesh swept along the given polyline, where the pieces longer than [Sweep::max_segment_length] are subdivided.
+    /// The profile is not scaled at sharp corners, so use [Sweep::along_spline] for a smooth mesh.
+    ///
+    pub fn along_polyline(&self, points: &[Vec3]) -> CpuMesh {
+        let mut path: Vec<Vec3> = Vec::with_capacity(points.len());
+        for p in points {
+            if let Some(last) = path.last().copied() {
+                if is_same_position(last, *p) {
+                    continue;
+                }
+                if self.max_segment_length > 0.0 {
+                    let pieces = (last.distance(*p) / self.max_segment_length).ceil() as u32;
+                    for i in 1..pieces {
+                        path.push(last.lerp(*p, i as f32 / pieces as f32));
+                    }
+                }
+            }
+            path.push(*p);
+        }
+        self.along_path(&path)
+    }
+
+    fn along_path(&self, path: &[Vec3]) -> CpuMesh {
+        if path.len() < 2 || self.profile.len() < 2 {
+            return CpuMesh::default();
+        }
+        let frames = if self.ground.is_some() {
+            level_frames(path, self.up)
+        } else {
+            rotation_minimizing_frames(path, self.up)
+        };
+
+        // The profile points, where the first point is repeated at the end of a closed profile to get continuous uv coordinates
+        let count = self.profile.len();
+        let ring = if self.closed { count + 1 } else { count };
+        let profile_point = |j: usize| self.profile[j % count];
+        let mut profile_distances = vec![0.0; ring];
+        for j in 1..ring {
+            profile_distances[j] =
+                profile_distances[j - 1] + profile_point(j).distance(profile_point(j - 1));
+        }
+        let profile_length = profile_distances[ring - 1].max(f32::EPSILON);
+        let profile_normals = (0..ring)
+            .map(|j| {
+                let edge_normal = |a: Vec2, b: Vec2| {
+                    let d = b - a;
+                    vec2(d.y, -d.x).normalize()
+                };
+                let previous = (j > 0 || self.closed)
+                    .then(|| edge_normal(profile_point(j + count - 1), profile_point(j)));
+                let next = (j + 1 < count || self.closed)
+                    .then(|| edge_normal(profile_point(j), profile_point(j + 1)));
+                let n = previous.unwrap_or(Vec2::zero()) + next.unwrap_or(Vec2::zero());
+                if n.magnitude2() > 1.0e-12 && n.x.is_finite() && n.y.is_finite() {
+                    n.normalize()
+                } else {
+                    vec2(0.0, 1.0)
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let mut positions = Vec::new();
+        let mut normals = Vec::new();
+        let mut uvs = Vec::new();
+        let mut indices = Vec::new();
+        let mut path_distance = 0.0;
+        for (i, (p, (tangent, up))) in path.iter().zip(frames.iter()).enumerate() {
+            if i > 0 {
+                path_distance += p.distance(path[i - 1]);
+            }
+            let side = tangent.cross(*up);
+            for j in 0..ring {
+                let q = profile_point(j);
+                let mut position = p + side * q.x + up * q.y;
+                if let Some(height_map) = self.ground.as_ref() {
+                    position.y = height_map(position.x, position.z) + self.ground_offset + q.y;
+                }
+                positions.push(position);
+                let n = profile_normals[j];
+                normals.push(side * n.x + up * n.y);
+                uvs.push(vec2(profile_distances[j] / profile_length, path_distance));
+            }
+            if i > 0 {
+                let r0 = ((i - 1) * ring) as u32;
+                let r1 = (i * ring) as u32;
+                for j in 0..ring as u32 - 1 {
+                    indices.extend([r0 + j, r1 + j, r0 + j + 1]);
+                    indices.extend([r0 + j + 1, r1 + j, r1 + j + 1]);
+                }
+            }
+        }
+
+        if self.caps && self.closed && count >= 3 {
+            let center = self.profile.iter().sum::<Vec2>() / count as f32;
+            for (end, reversed) in [(0, false), (path.len() - 1, true)] {
+                let (tangent, up) = frames[end];
+                let side = tangent.cross(up);
+                let normal = if reversed { tangent } else { -tangent };
+                let start = positions.len() as u32;
+                for q in std::iter::once(center).chain(self.profile.iter().copied()) {
+                    positions.push(path[end] + side * q.x + up * q.y);
+                    normals.push(normal);
+                    uvs.push(q);
+                }
+                for j in 0..count as u32 {
+                    let (a, b) = (start + 1 + j, start + 1 + (j + 1) % count as u32);
+                    if reversed {
+                        indices.extend([start, b, a]);
+                    } else {
+                        indices.extend([start, a, b]);
+                    }
+                }
+            }
+        }
+
+        let mut mesh = CpuMesh {
+            positions: Positions::F32(positions),
+            indices: Indices::U32(indices),
+            normals: Some(normals),
+            uvs: Some(uvs),
+            ..Default::default()
+        };
+        if self.ground.is_some() {
+            mesh.compute_normals();
+        }
+        mesh
+    }
+}
+
+///
+/// Returns the tangent and up direction at each point of the path using the double reflection method by Wang et al.
+/// for computing rotation minimizing frames, which avoids twisting of the profile around the path.
+///
+fn rotation_minimizing_frames(path: &[Vec3], up: Vec3) -> Vec<(Vec3, Vec3)> {
+    let tangents = path_tangents(path);
+    let mut frames = Vec::with_capacity(path.len());
+    let mut r = perpendicular(tangents[0], up);
+    frames.push((tangents[0], r));
+    for i in 0..path.len() - 1 {
+        let v1 = path[i + 1] - path[i];
+        let c1 = v1.dot(v1);
+        let r_l = r - v1 * (2.0 / c1 * v1.dot(r));
+        let t_l = tangents[i] - v1 * (2.0 / c1 * v1.dot(tangents[i]));
+        let v2 = tangents[i + 1] - t_l;
+        let c2 = v2.dot(v2);
+        r = if c2 > 1.0e-12 {
+            r_l - v2 * (2.0 / c2 * v2.dot(r_l))
+        } else {
+            r_l
+        };
+        r = perpendicular(tangents[i + 1], r);
+        frames.push((tangents[i + 1], r));
+    }
+    frames
+}
+
+///
+/// Returns the tangent and an up direction which is as close as possible to the given up direction at each point of the path,
+/// so the profile is kept level.
+///
+fn level_frames(path: &[Vec3], up: Vec3) -> Vec<(Vec3, Vec3)> {
+    let mut previous = up;
+    path_tangents(path)
+        .into_iter()
+        .map(|tangent| {
+            previous = perpendicular(
+                tangent,
+                if tangent.cross(up).magnitude2() > 1.0e-6 {
+                    up
+                } else {
+                    previous
+                },
+            );
+            (tangent, previous)
+        })
+        .collect()
+}
+
+///
+/// Returns the tangent at each point of the path, which is the average of the directions of the neighbouring pieces.
+/// The path must have at least two points and no consecutive points at the same position.
+///
+fn path_tangents(path: &[Vec3]) -> Vec<Vec3> {
+    let n = path.len();
+    (0..n)
+        .map(|i| {
+            let before = normalize_or_zero(path[i] - path[i.saturating_sub(1)]);
+            let after = normalize_or_zero(path[(i + 1).min(n - 1)] - path[i]);
+            let tangent = before + after;
+            if tangent.magnitude2() > 1.0e-6 {
+                tangent.normalize()
+            } else {
+                // The path turns around, so use the direction of one of the pieces
+                if after.magnitude2() > 0.0 {
+                    after
+                } else {
+                    before
+                }
+            }
+        })
+        .collect()
+}
+
+///
+/// Returns the unit vector perpendicular to the given unit direction which is closest to the given vector,
+/// or an arbitrary perpendicular unit vector if the vector is parallel to the direction.
+///
+fn perpendicular(direction: Vec3, v: Vec3) -> Vec3 {
+    let p = v - direction * direction.dot(v);
+    if p.magnitude2() > 1.0e-12 {
+        p.normalize()
+    } else {
+        let axis = if direction.x.abs() < 0.9 {
+            vec3(1.0, 0.0, 0.0)
+        } else {
+            vec3(0.0, 1.0, 0.0)
+        };
+        direction.cross(axis).normalize()
+    }
+}
+
+fn angle_between(a: Vec3, b: Vec3) -> f32 {
+    if a.magnitude2() < 1.0e-12 || b.magnitude2() < 1.0e-12 {
+        0.0
+    } else {
+        a.normalize().dot(b.normalize()).clamp(-1.0, 1.0).acos()
+    }
+}
+
+fn is_same_position(a: Vec3, b: Vec3) -> bool {
+    a.distance2(b) <= 1.0e-12 * a.magnitude2().max(1.0)
+}
+
+fn normalize_or_zero(v: Vec3) -> Vec3 {
+    if v.magnitude2() > 0.0 {
+        v.normalize()
+    } else {
+        Vec3::zero()
+    }
+}
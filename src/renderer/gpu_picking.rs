@@ -0,0 +1,187 @@
+//!
+//! Picking of the geometry and instance in a pixel by rendering the geometries into a small offscreen target, see [pick_gpu].
+//!
+
+use crate::core::*;
+use crate::renderer::*;
+
+///
+/// The result of [pick_gpu].
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PickResult {
+    /// The index of the picked geometry in the list of geometries.
+    pub geometry_index: u32,
+    /// The index of the picked instance in the list of instances, ie. [gl_InstanceID](https://registry.khronos.org/OpenGL-Refpages/gl4/html/gl_InstanceID.xhtml).
+    /// This is 0 if the picked geometry is not instanced.
+    pub instance_index: u32,
+    /// The depth of the picked surface as stored in a depth texture, which depends on the [DepthMode] of the viewer.
+    pub depth: f32,
+    /// The position of the picked surface, reconstructed from the depth.
+    pub position: Vec3,
+}
+
+impl PickResult {
+    ///
+    /// Returns the position of the picked surface in world space in double precision, see [Context::set_world_origin].
+    ///
+    pub fn world_position(&self, context: &Context) -> DVec3 {
+        context.to_world_space(self.position)
+    }
+}
+
+///
+/// Finds the geometry and instance visible in the given pixel when the given geometries are rendered from the given viewer, for example a [Camera].
+/// The pixel coordinate must be in physical pixels, where (viewport.x, viewport.y) indicate the bottom left corner of the viewport
+/// and (viewport.x + viewport.width, viewport.y + viewport.height) indicate the top right corner.
+/// Returns ```None``` if no geometry covers the pixel.
+///
+/// Unlike [pick], which renders the geometries along a ray, the geometries are rendered with the projection of the viewer zoomed in on the pixel
+/// into a 1x1 target with an [IntersectionMaterial], so the result is exactly what is visible in the pixel, also for geometry which is displaced or expanded on the GPU,
+/// for example sprites and point clouds. Only one pixel is rendered and read back, so this is fast enough to call every time the mouse moves.
+///
+pub fn pick_gpu(
+    context: &Context,
+    viewer: impl Viewer,
+    pixel: impl Into<PhysicalPoint>,
+    geometries: impl IntoIterator<Item = impl Geometry>,
+    culling: Cull,
+) -> Result<Option<PickResult>, RendererError> {
+    let pixel = pixel.into();
+    let pick_viewer = PickViewer::new(&viewer, pixel, 0);
+    let mut texture = context.request_texture::<[f32; 4]>(1, 1, Interpolation::Nearest);
+    let mut depth_texture = context.request_depth_texture::<f32>(1, 1);
+    let mut material = IntersectionMaterial::default();
+    material.render_states.cull = culling;
+    let result = RenderTarget::new(
+        texture.as_color_target(None),
+        depth_texture.as_depth_target(),
+    )
+    .clear(
+        ClearState::color_and_depth(-1.0, 0.0, 0.0, 0.0, 1.0).with_depth_mode(viewer.depth_mode()),
+    )
+    .write::<RendererError>(|| {
+        for (index, geometry) in geometries.into_iter().enumerate() {
+            material.geometry_id = index as u32;
+            // The fallback for a shader which is not compiled yet would be read as a pick result, so wait for the compilation
+            render_with_material_internal(context, &pick_viewer, &geometry, &material, &[], false)?;
+        }
+        Ok(())
+    })?
+    .read_color::<[f32; 4]>()[0];
+    // The distance in the red channel is never negative where a geometry is rendered
+    if result[0] < 0.0 {
+        return Ok(None);
+    }
+
+    // Unproject the center of the pixel at the depth of the picked surface
+    let depth = result[3];
+    let viewport = viewer.viewport();
+    let x = 2.0 * ((pixel.x - viewport.x as f32).floor() + 0.5) / viewport.width as f32 - 1.0;
+    let y = 2.0 * ((pixel.y - viewport.y as f32).floor() + 0.5) / viewport.height as f32 - 1.0;
+    let z = if viewer.depth_mode() == DepthMode::ReversedZeroToOne {
+        depth
+    } else {
+        2.0 * depth - 1.0
+    };
+    let p = (viewer.projection() * viewer.view())
+        .invert()
+        .unwrap_or(Mat4::identity())
+        * vec4(x, y, z, 1.0);
+    Ok(Some(PickResult {
+        geometry_index: result[1].to_bits(),
+        instance_index: result[2].to_bits(),
+        depth,
+        position: p.truncate() / p.w,
+    }))
+}
+
+///
+/// A viewer which is the same as the given viewer, except that the projection is zoomed in on the pixels within a radius of a pixel
+/// and the viewport only covers those pixels, so one pixel in the viewport of the given viewer is one pixel in a small pick target.
+///
+pub(crate) struct PickViewer<T> {
+    viewer: T,
+    projection: Mat4,
+    viewport: Viewport,
+}
+
+impl<T: Viewer> PickViewer<T> {
+    pub(crate) fn new(viewer: T, pixel: PhysicalPoint, radius: u32) -> Self {
+        let viewport = viewer.viewport();
+        let size = 2 * radius + 1;
+        let center_x = (pixel.x - viewport.x as f32).floor();
+        let center_y = (pixel.y - viewport.y as f32).floor();
+
+        let x0 = 2.0 * (center_x - radius as f32) / viewport.width as f32 - 1.0;
+        let x1 = 2.0 * (center_x + radius as f32 + 1.0) / viewport.width as f32 - 1.0;
+        let y0 = 2.0 * (center_y - radius as f32) / viewport.height as f32 - 1.0;
+        let y1 = 2.0 * (center_y + radius as f32 + 1.0) / viewport.height as f32 - 1.0;
+        let (scale_x, scale_y) = (2.0 / (x1 - x0), 2.0 / (y1 - y0));
+        let zoom = Mat4::new(
+            scale_x,
+            0.0,
+            0.0,
+            0.0,
+            0.0,
+            scale_y,
+            0.0,
+            0.0,
+            0.0,
+            0.0,
+            1.0,
+            0.0,
+            -scale_x * 0.5 * (x0 + x1),
+            -scale_y * 0.5 * (y0 + y1),
+            0.0,
+            1.0,
+        );
+        Self {
+            projection: zoom * viewer.projection(),
+            viewer,
+            viewport: Viewport::new_at_origo(size, size),
+        }
+    }
+}
+
+impl<T: Viewer> Viewer for PickViewer<T> {
+    fn position(&self) -> Vec3 {
+        self.viewer.position()
+    }
+
+    fn view(&self) -> Mat4 {
+        self.viewer.view()
+    }
+
+    fn projection(&self) -> Mat4 {
+        self.projection
+    }
+
+    fn viewport(&self) -> Viewport {
+        self.viewport
+    }
+
+    fn z_near(&self) -> f32 {
+        self.viewer.z_near()
+    }
+
+    fn z_far(&self) -> f32 {
+        self.viewer.z_far()
+    }
+
+    fn color_mapping(&self) -> ColorMapping {
+        self.viewer.color_mapping()
+    }
+
+    fn tone_mapping(&self) -> ToneMapping {
+        self.viewer.tone_mapping()
+    }
+
+    fn depth_mode(&self) -> DepthMode {
+        self.viewer.depth_mode()
+    }
+
+    fn exposure(&self) -> f32 {
+        self.viewer.exposure()
+    }
+}
@@ -0,0 +1,243 @@
+//!
+//! Rendering in high dynamic range followed by tone and color mapping, see [HdrComposer].
+//!
+
+use crate::renderer::*;
+
+///
+/// Defines how the tone and color mapping is applied by a [HdrComposer].
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ToneMappingPath {
+    /// The objects are rendered into an intermediate HDR texture, which is then tone and color mapped into the render target using [ScreenEffect].
+    SeparatePass,
+    /// The objects are rendered directly into the render target and the last draw reads the color of each pixel using framebuffer fetch,
+    /// see [Context::framebuffer_fetch], and replaces it with the tone and color mapped color.
+    /// On tile based GPUs, the color is read from the tile memory, which avoids the intermediate texture and the extra pass.
+    FramebufferFetch(FramebufferFetch),
+}
+
+///
+/// Renders objects in high dynamic range, ie. without the tone and color mapping specified in the [Viewer], and afterwards applies the tone and color mapping.
+/// This is needed when the colors of several objects are blended before they are mapped, for example for transparent objects or particles in physical light units.
+///
+/// The tone and color mapping is applied using the fastest path available for the render target, see [HdrComposer::path]:
+/// When framebuffer fetch is supported and the render target stores the colors as floats, the mapping is applied in place as the last draw into the render target,
+/// otherwise the objects are rendered into an intermediate HDR texture which is then mapped into the render target in a separate pass.
+/// Both paths use the same tone and color mapping, so they give the same result except for the precision of the stored colors.
+///
+pub struct HdrComposer {
+    context: Context,
+    /// Whether to use framebuffer fetch when it is supported. Set to `false` to always use the separate pass, for example to compare the output of the two paths.
+    pub use_framebuffer_fetch: bool,
+    /// Whether the screen stores the colors as floats, for example when the surface of the window is created with a half float format,
+    /// in which case framebuffer fetch can also be used when rendering to the screen. The default is `false`.
+    pub float_screen: bool,
+}
+
+impl HdrComposer {
+    ///
+    /// Creates a new composer which uses framebuffer fetch when it is supported by the context and the render target.
+    ///
+    pub fn new(context: &Context) -> Self {
+        Self {
+            context: context.clone(),
+            use_framebuffer_fetch: true,
+            float_screen: false,
+        }
+    }
+
+    ///
+    /// Returns the path used for applying the tone and color mapping when rendering into the given render target,
+    /// which is [ToneMappingPath::FramebufferFetch] if framebuffer fetch is supported and enabled and the render target stores the colors as floats,
+    /// otherwise [ToneMappingPath::SeparatePass].
+    ///
+    pub fn path(&self, target: &RenderTarget) -> ToneMappingPath {
+        let float_target = target.is_float() || (self.float_screen && target.is_screen());
+        match self.context.framebuffer_fetch() {
+            Some(framebuffer_fetch) if self.use_framebuffer_fetch && float_target => {
+                ToneMappingPath::FramebufferFetch(framebuffer_fetch)
+            }
+            _ => ToneMappingPath::SeparatePass,
+        }
+    }
+
+    ///
+    /// Clears the render target, or the intermediate textures, using the given clear state, renders the objects with the given viewer and lights
+    /// without tone and color mapping and then applies the tone and color mapping of the viewer, see [HdrComposer::path].
+    /// The intermediate textures are requested from the texture pool of the context, see [Context::request_texture].
+    ///
+    pub fn render(
+        &self,
+        target: &RenderTarget,
+        clear_state: ClearState,
+        viewer: impl Viewer,
+        objects: impl IntoIterator<Item = impl Object>,
+        lights: &[&dyn Light],
+    ) {
+        let viewport = viewer.viewport();
+        match self.path(target) {
+            ToneMappingPath::FramebufferFetch(framebuffer_fetch) => {
+                target
+                    .clear_partially(viewport, clear_state.with_depth_mode(viewer.depth_mode()))
+                    .render(HdrViewer(&viewer, viewport), objects, lights)
+                    .apply_screen_effect_partially(
+                        viewport,
+                        &FramebufferFetchToneMapping(framebuffer_fetch),
+                        &viewer,
+                        &[],
+                        None,
+                        None,
+                    );
+            }
+            ToneMappingPath::SeparatePass => {
+                let mut color_texture = self.context.request_texture::<[f16; 4]>(
+                    viewport.width,
+                    viewport.height,
+                    Interpolation::Nearest,
+                );
+                let mut depth_texture = self
+                    .context
+                    .request_depth_texture::<f32>(viewport.width, viewport.height);
+                RenderTarget::new(
+                    color_texture.as_color_target(None),
+                    depth_texture.as_depth_target(),
+                )
+                .clear(clear_state.with_depth_mode(viewer.depth_mode()))
+                .render(
+                    HdrViewer(
+                        &viewer,
+                        Viewport::new_at_origo(viewport.width, viewport.height),
+                    ),
+                    objects,
+                    lights,
+                );
+                target.apply_screen_effect_partially(
+                    viewport,
+                    &ScreenEffect::default(),
+                    &viewer,
+                    &[],
+                    Some(ColorTexture::Single(&color_texture)),
+                    Some(DepthTexture::Single(&depth_texture)),
+                );
+            }
+        }
+    }
+}
+
+///
+/// Replaces the color of each pixel in the render target with the tone and color mapped color, which is read using framebuffer fetch.
+///
+struct FramebufferFetchToneMapping(FramebufferFetch);
+
+impl Effect for FramebufferFetchToneMapping {
+    fn fragment_shader_source(
+        &self,
+        _lights: &[&dyn Light],
+        _color_texture: Option<ColorTexture>,
+        _depth_texture: Option<DepthTexture>,
+    ) -> String {
+        let main = match self.0 {
+            FramebufferFetch::Ext => {
+                "layout (location = 0) inout vec4 outColor;
+
+                void main()
+                {
+                    outColor.rgb = color_mapping(tone_mapping(outColor.rgb));
+                }"
+            }
+            FramebufferFetch::Arm => {
+                "layout (location = 0) out vec4 outColor;
+
+                void main()
+                {
+                    vec4 color = gl_LastFragColorARM;
+                    outColor = vec4(color_mapping(tone_mapping(color.rgb)), color.a);
+                }"
+            }
+        };
+        format!(
+            "{}#include <{}>\n#include <{}>\n\n{}\n",
+            self.0.extension_directive(),
+            TONE_MAPPING_SNIPPET,
+            COLOR_MAPPING_SNIPPET,
+            main
+        )
+    }
+
+    fn id(
+        &self,
+        _color_texture: Option<ColorTexture>,
+        _depth_texture: Option<DepthTexture>,
+    ) -> EffectMaterialId {
+        EffectMaterialId::FramebufferFetchToneMappingEffect(self.0 == FramebufferFetch::Arm)
+    }
+
+    fn use_uniforms(
+        &self,
+        program: &Program,
+        viewer: &dyn Viewer,
+        _lights: &[&dyn Light],
+        _color_texture: Option<ColorTexture>,
+        _depth_texture: Option<DepthTexture>,
+    ) {
+        viewer.tone_mapping().use_uniforms(program);
+        viewer.color_mapping().use_uniforms(program);
+    }
+
+    fn render_states(&self) -> RenderStates {
+        RenderStates {
+            depth_test: DepthTest::Always,
+            cull: Cull::Back,
+            write_mask: WriteMask::COLOR,
+            ..Default::default()
+        }
+    }
+}
+
+///
+/// A viewer which is the same as the given viewer, except for the viewport and that it does not apply any tone and color mapping.
+///
+struct HdrViewer<T>(T, Viewport);
+
+impl<T: Viewer> Viewer for HdrViewer<T> {
+    fn position(&self) -> Vec3 {
+        self.0.position()
+    }
+
+    fn view(&self) -> Mat4 {
+        self.0.view()
+    }
+
+    fn projection(&self) -> Mat4 {
+        self.0.projection()
+    }
+
+    fn viewport(&self) -> Viewport {
+        self.1
+    }
+
+    fn z_near(&self) -> f32 {
+        self.0.z_near()
+    }
+
+    fn z_far(&self) -> f32 {
+        self.0.z_far()
+    }
+
+    fn color_mapping(&self) -> ColorMapping {
+        ColorMapping::None
+    }
+
+    fn tone_mapping(&self) -> ToneMapping {
+        ToneMapping::None
+    }
+
+    fn depth_mode(&self) -> DepthMode {
+        self.0.depth_mode()
+    }
+
+    fn exposure(&self) -> f32 {
+        self.0.exposure()
+    }
+}
@@ -28,10 +28,22 @@ mod spot_light;
 #[doc(inline)]
 pub use spot_light::*;
 
+mod shadow_atlas;
+#[doc(inline)]
+pub use shadow_atlas::*;
+
+mod shadow_scheduler;
+#[doc(inline)]
+pub use shadow_scheduler::*;
+
 mod point_light;
 #[doc(inline)]
 pub use point_light::*;
 
+mod area_light;
+#[doc(inline)]
+pub use area_light::*;
+
 mod ambient_light;
 #[doc(inline)]
 pub use ambient_light::*;
@@ -40,9 +52,22 @@ mod environment;
 #[doc(inline)]
 pub use environment::*;
 
+mod caustics;
+#[doc(inline)]
+pub use caustics::*;
+
+mod light_animator;
+#[doc(inline)]
+pub use light_animator::*;
+
+mod light_buffer;
+#[doc(inline)]
+pub use light_buffer::*;
+
 use crate::core::*;
 use crate::renderer::viewer::*;
 use crate::renderer::LightId;
+use crate::renderer::{render_with_material_internal, DepthMaterial, Geometry, RendererError};
 
 ///
 /// Specifies how the intensity of a light fades over distance.
@@ -68,6 +93,89 @@ impl Default for Attenuation {
     }
 }
 
+///
+/// A gradient multiplied with the color of a light as a function of the distance to the light, see for example [PointLight::set_color_ramp].
+/// The gradient is baked into a texture which is sampled by the distance relative to the distance of the last stop.
+///
+struct ColorRamp {
+    stops: Vec<(f32, Srgba)>,
+    texture: Texture2D,
+    max_distance: f32,
+}
+
+impl ColorRamp {
+    const SIZE: u32 = 64;
+
+    ///
+    /// Creates a color ramp from the given distances and colors, or returns `None` if no stops are given.
+    /// The stops are sorted by distance and the color is linearly interpolated between stops and clamped before the first and after the last stop.
+    ///
+    fn new(context: &Context, stops: &[(f32, Srgba)]) -> Option<Self> {
+        if stops.is_empty() {
+            return None;
+        }
+        let mut stops = stops.to_vec();
+        stops.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let max_distance = stops.last().unwrap().0;
+        // A single stop, or stops at distance zero, is a constant color
+        let max_distance = if max_distance > 0.0 {
+            max_distance
+        } else {
+            1.0
+        };
+        let data = (0..Self::SIZE)
+            .map(|k| {
+                let distance = max_distance * k as f32 / (Self::SIZE - 1) as f32;
+                let color = match stops.iter().position(|(d, _)| *d >= distance) {
+                    Some(0) => stops[0].1.to_linear_srgb(),
+                    Some(j) => {
+                        let (d0, c0) = stops[j - 1];
+                        let (d1, c1) = stops[j];
+                        let t = (distance - d0) / (d1 - d0).max(f32::EPSILON);
+                        c0.to_linear_srgb().lerp(c1.to_linear_srgb(), t)
+                    }
+                    None => stops.last().unwrap().1.to_linear_srgb(),
+                };
+                [
+                    f16::from_f32(color.x),
+                    f16::from_f32(color.y),
+                    f16::from_f32(color.z),
+                    f16::ONE,
+                ]
+            })
+            .collect::<Vec<_>>();
+        let mut texture = Texture2D::new_empty::<[f16; 4]>(
+            context,
+            Self::SIZE,
+            1,
+            Interpolation::Linear,
+            Interpolation::Linear,
+            None,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+        );
+        texture.fill(&data);
+        Some(Self {
+            stops,
+            texture,
+            max_distance,
+        })
+    }
+
+    fn shader_source(i: u32) -> String {
+        format!("uniform sampler2D colorRamp{i};\nuniform float colorRampDistance{i};\n")
+    }
+
+    fn sample_source(i: u32) -> String {
+        format!("light_color *= sample_color_ramp(colorRamp{i}, colorRampDistance{i}, distance);")
+    }
+
+    fn use_uniforms(&self, program: &Program, i: u32) {
+        program.use_texture(&format!("colorRamp{}", i), &self.texture);
+        program.use_uniform(&format!("colorRampDistance{}", i), self.max_distance);
+    }
+}
+
 /// Represents a light source.
 pub trait Light {
     /// The fragment shader source for calculating this lights contribution to the color in a fragment.
@@ -97,15 +205,15 @@ impl<T: Light + ?Sized> Light for &mut T {
     impl_light_body!(deref);
 }
 
-impl<T: Light> Light for Box<T> {
+impl<T: Light + ?Sized> Light for Box<T> {
     impl_light_body!(as_ref);
 }
 
-impl<T: Light> Light for std::rc::Rc<T> {
+impl<T: Light + ?Sized> Light for std::rc::Rc<T> {
     impl_light_body!(as_ref);
 }
 
-impl<T: Light> Light for std::sync::Arc<T> {
+impl<T: Light + ?Sized> Light for std::sync::Arc<T> {
     impl_light_body!(as_ref);
 }
 
@@ -128,15 +236,16 @@ impl<T: Light> Light for std::sync::Arc<std::sync::RwLock<T>> {
 ///
 /// Returns shader source code with the function `calculate_lighting` which calculate the lighting contribution for the given lights and the given [LightingModel].
 /// Use this if you want to implement a custom [Material](crate::renderer::Material) but use the default lighting calculations.
+/// The returned source includes the [LIGHTING_SNIPPET] using an `#include` directive which is resolved when the [Program] is created.
 ///
 /// The shader function has the following signature:
 /// ```no_rust
 /// vec3 calculate_lighting(vec3 camera_position, vec3 surface_color, vec3 position, vec3 normal, float metallic, float roughness, float occlusion)
 /// ```
+/// To support the exposure of the viewer (see [Viewer::exposure]), set the `logExposure` uniform to the base 2 logarithm of the exposure.
 ///
 pub fn lights_shader_source(lights: &[&dyn Light]) -> String {
-    let mut shader_source = include_str!("../core/shared.frag").to_string();
-    shader_source.push_str(include_str!("light/shaders/light_shared.frag"));
+    let mut shader_source = format!("#include <{}>\n", LIGHTING_SNIPPET);
     let mut dir_fun = String::new();
     for (i, light) in lights.iter().enumerate() {
         shader_source.push_str(&light.shader_source(i as u32));
@@ -144,12 +253,15 @@ pub fn lights_shader_source(lights: &[&dyn Light]) -> String {
     }
     shader_source.push_str(&format!(
         "
+            // Zero, ie. no exposure, if not set by the material
+            uniform float logExposure;
+
             vec3 calculate_lighting(vec3 camera_position, vec3 surface_color, vec3 position, vec3 normal, float metallic, float roughness, float occlusion)
             {{
                 vec3 color = vec3(0.0, 0.0, 0.0);
                 vec3 view_direction = normalize(camera_position - position);
                 {}
-                return color;
+                return exp2(logExposure) * color;
             }}
             ",
         &dir_fun
@@ -164,6 +276,40 @@ fn shadow_matrix(camera: &Camera) -> Mat4 {
     bias_matrix * camera.projection() * camera.view()
 }
 
+///
+/// Renders the depth of the geometry into a shadow map with the given depth material,
+/// which is replaced by a depth material with the same settings and the alpha cutout and face culling of the geometry, if it has any.
+/// The shadow map is never rendered with the fallback for a shader which is not compiled yet, see [render_with_material](crate::renderer::render_with_material).
+///
+fn render_shadow_caster(
+    context: &Context,
+    shadow_camera: &Camera,
+    geometry: &impl Geometry,
+    depth_material: &DepthMaterial,
+) -> Result<(), RendererError> {
+    let alpha_cutout = geometry.alpha_cutout();
+    let cull = geometry.cull();
+    if alpha_cutout.is_some() || cull.is_some() {
+        render_with_material_internal(
+            context,
+            shadow_camera,
+            geometry,
+            &DepthMaterial {
+                alpha_cutout: alpha_cutout.or_else(|| depth_material.alpha_cutout.clone()),
+                render_states: RenderStates {
+                    cull: cull.unwrap_or(depth_material.render_states.cull),
+                    ..depth_material.render_states
+                },
+                ..depth_material.clone()
+            },
+            &[],
+            false,
+        )
+    } else {
+        render_with_material_internal(context, shadow_camera, geometry, depth_material, &[], false)
+    }
+}
+
 fn compute_up_direction(direction: Vec3) -> Vec3 {
     if vec3(1.0, 0.0, 0.0).dot(direction).abs() > 0.9 {
         (vec3(0.0, 1.0, 0.0).cross(direction)).normalize()
@@ -172,7 +318,7 @@ fn compute_up_direction(direction: Vec3) -> Vec3 {
     }
 }
 
-use crate::renderer::{GeometryFunction, LightingModel, NormalDistributionFunction};
+use crate::renderer::{DiffuseModel, GeometryFunction, LightingModel, NormalDistributionFunction};
 pub(crate) fn lighting_model_to_id(model: LightingModel) -> u32 {
     match model {
         LightingModel::Phong => 1,
@@ -189,5 +335,25 @@ pub(crate) fn lighting_model_to_id(model: LightingModel) -> u32 {
             NormalDistributionFunction::TrowbridgeReitzGGX,
             GeometryFunction::SmithSchlickGGX,
         ) => 5,
+        LightingModel::Cook(
+            NormalDistributionFunction::Blinn,
+            GeometryFunction::SmithHeightCorrelatedGGX,
+        ) => 6,
+        LightingModel::Cook(
+            NormalDistributionFunction::Beckmann,
+            GeometryFunction::SmithHeightCorrelatedGGX,
+        ) => 7,
+        LightingModel::Cook(
+            NormalDistributionFunction::TrowbridgeReitzGGX,
+            GeometryFunction::SmithHeightCorrelatedGGX,
+        ) => 8,
+    }
+}
+
+// Zero is the default value of the uniform, so materials which do not set it use the Lambertian model
+pub(crate) fn diffuse_model_to_id(model: DiffuseModel) -> u32 {
+    match model {
+        DiffuseModel::Lambert => 0,
+        DiffuseModel::OrenNayar => 1,
     }
 }
@@ -12,6 +12,10 @@ pub struct AmbientLight {
     pub color: Srgba,
     /// The light shining from the environment. This is calculated based on an environment map.
     pub environment: Option<Environment>,
+    /// The rotation of the [environment](AmbientLight::environment) applied when sampling the precalculated maps, so the environment can be rotated each frame without recalculating the maps.
+    /// Use the same rotation for a [Skybox] showing the same environment map, see [Skybox::set_rotation], so the reflections match the background.
+    /// Has no effect if the light is uniform.
+    pub rotation: Quat,
 }
 
 impl AmbientLight {
@@ -21,6 +25,7 @@ impl AmbientLight {
             intensity,
             color,
             environment: None,
+            rotation: Quat::one(),
         }
     }
 
@@ -35,6 +40,7 @@ impl AmbientLight {
             intensity,
             color,
             environment: Some(Environment::new(context, environment_map)),
+            rotation: Quat::one(),
         }
     }
 }
@@ -48,12 +54,16 @@ impl Light for AmbientLight {
                 uniform samplerCube prefilterMap;
                 uniform sampler2D brdfLUT;
                 uniform vec3 ambientColor;
+                uniform mat3 environmentRotation;
     
                 vec3 calculate_lighting{}(vec3 surface_color, vec3 position, vec3 normal, vec3 view_direction, float metallic, float roughness, float occlusion)
                 {{
                     vec3 N = normal;
                     vec3 V = view_direction;
                     vec3 R = reflect(-V, N); 
+                    #ifdef USE_ANISOTROPY
+                    R = reflect(-V, anisotropic_bent_normal(V, N, roughness));
+                    #endif
                     float NdV = max(0.001, dot(N, V));
                     
                     // calculate reflectance at normal incidence; if dia-electric (like plastic) use F0 
@@ -63,16 +73,25 @@ impl Light for AmbientLight {
                     vec3 diffuse_fresnel = 1.0 - specular_fresnel;
 
                     // Diffuse
-                    vec3 irradiance = texture(irradianceMap, N).rgb;
+                    vec3 irradiance = texture(irradianceMap, environmentRotation * N).rgb;
                     vec3 diffuse = diffuse_fresnel * mix(surface_color, vec3(0.0), metallic) * irradiance;
                     
                     // sample both the pre-filter map and the BRDF lut and combine them together as per the Split-Sum approximation to get the IBL specular part.
                     const float MAX_REFLECTION_LOD = 4.0;
-                    vec3 prefilteredColor = textureLod(prefilterMap, R,  roughness * MAX_REFLECTION_LOD).rgb;    
+                    vec3 prefilteredColor = textureLod(prefilterMap, environmentRotation * R,  roughness * MAX_REFLECTION_LOD).rgb;    
                     vec2 brdf  = texture(brdfLUT, vec2(NdV, roughness)).rg;
                     vec3 specular = prefilteredColor * (specular_fresnel * brdf.x + brdf.y);
+                    vec3 result = diffuse + specular;
+                    #ifdef USE_CLEARCOAT
+                    // The clear coat reflects the environment in the direction of its own normal with its own roughness and dims the base layer
+                    vec3 Rc = reflect(-V, clearcoat_normal);
+                    float NdVc = max(0.001, dot(clearcoat_normal, V));
+                    vec3 coatColor = textureLod(prefilterMap, environmentRotation * Rc, clearcoat_roughness * MAX_REFLECTION_LOD).rgb;
+                    vec2 coatBrdf = texture(brdfLUT, vec2(NdVc, clearcoat_roughness)).rg;
+                    result = result * (1.0 - clearcoat_fresnel(V)) + clearcoat_factor * coatColor * (0.04 * coatBrdf.x + coatBrdf.y);
+                    #endif
     
-                    return (diffuse + specular) * occlusion * ambientColor;
+                    return result * occlusion * ambientColor;
                 }}
             
             ", i)
@@ -93,6 +112,11 @@ impl Light for AmbientLight {
             program.use_texture_cube("irradianceMap", &environment.irradiance_map);
             program.use_texture_cube("prefilterMap", &environment.prefilter_map);
             program.use_texture("brdfLUT", &environment.brdf_map);
+            // The maps are sampled in the direction before the rotation
+            program.use_uniform(
+                "environmentRotation",
+                Mat3::from(self.rotation.normalize().conjugate()),
+            );
         }
         program.use_uniform(
             "ambientColor",
@@ -111,6 +135,7 @@ impl Default for AmbientLight {
             color: Srgba::WHITE,
             intensity: 1.0,
             environment: None,
+            rotation: Quat::one(),
         }
     }
 }
@@ -0,0 +1,198 @@
+use crate::core::*;
+use crate::renderer::light::*;
+use crate::renderer::*;
+use std::sync::Arc;
+
+// The size of the lookup tables for the linearly transformed cosines.
+const LTC_LUT_SIZE: u32 = 64;
+
+// The lookup tables for the linearly transformed cosines approximating the GGX BRDF, fitted using the method described in
+// "Real-Time Polygonal-Light Shading with Linearly Transformed Cosines" by Heitz et al. 2016.
+// Contains the four non-trivial elements of the inverse matrices (RGBA) followed by the magnitude and Fresnel terms (RG)
+// for each roughness and view angle, stored as little endian 16-bit floats.
+const LTC_LUT: &[u8] = include_bytes!("ltc_ggx.bin");
+
+///
+/// A rectangular light which emits light from one side of the rectangle, which results in soft lighting and shadows
+/// as well as realistic reflections on glossy surfaces compared to a [PointLight].
+/// The lighting is calculated using linearly transformed cosines for the [LightingModel::Cook] lighting model with the
+/// [NormalDistributionFunction::TrowbridgeReitzGGX] normal distribution function and approximated using the point on the rectangle closest to the reflection direction for the other lighting models.
+///
+/// **Note:** The light does not cast shadows.
+///
+pub struct AreaLight {
+    intensity: f32,
+    color: Srgba,
+    position: Vec3,
+    normal: Vec3,
+    up: Vec3,
+    width: f32,
+    height: f32,
+    ltc_matrix_texture: Arc<Texture2D>,
+    ltc_magnitude_texture: Arc<Texture2D>,
+}
+
+impl AreaLight {
+    ///
+    /// Constructs a new rectangular area light with the center at the given position, emitting light in the direction of the given normal.
+    /// The width of the light is measured perpendicular to the up direction and the height along the up direction.
+    /// The intensity is the emitted light per area, so the total amount of light increases with the size of the light.
+    ///
+    pub fn new(
+        context: &Context,
+        intensity: f32,
+        color: Srgba,
+        position: Vec3,
+        normal: Vec3,
+        up: Vec3,
+        width: f32,
+        height: f32,
+    ) -> AreaLight {
+        let f = |i: usize| f16::from_bits(u16::from_le_bytes([LTC_LUT[2 * i], LTC_LUT[2 * i + 1]]));
+        let count = (LTC_LUT_SIZE * LTC_LUT_SIZE) as usize;
+        let lut = |data| CpuTexture {
+            data,
+            width: LTC_LUT_SIZE,
+            height: LTC_LUT_SIZE,
+            min_filter: Interpolation::Linear,
+            mag_filter: Interpolation::Linear,
+            mipmap: None,
+            wrap_s: Wrapping::ClampToEdge,
+            wrap_t: Wrapping::ClampToEdge,
+            ..Default::default()
+        };
+        let ltc_matrix_texture = Texture2D::new(
+            context,
+            &lut(TextureData::RgbaF16(
+                (0..count)
+                    .map(|i| [f(4 * i), f(4 * i + 1), f(4 * i + 2), f(4 * i + 3)])
+                    .collect(),
+            )),
+        );
+        let ltc_magnitude_texture = Texture2D::new(
+            context,
+            &lut(TextureData::RgF16(
+                (0..count)
+                    .map(|i| [f(4 * count + 2 * i), f(4 * count + 2 * i + 1)])
+                    .collect(),
+            )),
+        );
+        let mut light = AreaLight {
+            intensity,
+            color,
+            position,
+            normal: vec3(0.0, 0.0, 1.0),
+            up: vec3(0.0, 1.0, 0.0),
+            width,
+            height,
+            ltc_matrix_texture: Arc::new(ltc_matrix_texture),
+            ltc_magnitude_texture: Arc::new(ltc_magnitude_texture),
+        };
+        light.set_orientation(normal, up);
+        light
+    }
+
+    /// Returns the intensity of the light.
+    pub fn intensity(&self) -> f32 {
+        self.intensity
+    }
+
+    /// Sets the intensity of the light. This allows for higher intensity than 1 which can be used to simulate high intensity light sources.
+    pub fn set_intensity(&mut self, intensity: f32) {
+        self.intensity = intensity;
+    }
+
+    /// Returns the base color of the light.
+    pub fn color(&self) -> Srgba {
+        self.color
+    }
+
+    /// Sets the base color of the light.
+    pub fn set_color(&mut self, color: Srgba) {
+        self.color = color;
+    }
+
+    /// Returns the position of the center of the light.
+    pub fn position(&self) -> Vec3 {
+        self.position
+    }
+
+    /// Sets the position of the center of the light.
+    pub fn set_position(&mut self, position: Vec3) {
+        self.position = position;
+    }
+
+    /// Returns the direction in which the light is emitted.
+    pub fn normal(&self) -> Vec3 {
+        self.normal
+    }
+
+    /// Returns the up direction of the light, which is perpendicular to the normal.
+    pub fn up(&self) -> Vec3 {
+        self.up
+    }
+
+    ///
+    /// Sets the direction in which the light is emitted and the up direction of the light.
+    /// The up direction is made perpendicular to the normal, if it is parallel to the normal, another up direction is chosen.
+    ///
+    pub fn set_orientation(&mut self, normal: Vec3, up: Vec3) {
+        self.normal = normal.normalize();
+        let up = up - self.normal * self.normal.dot(up);
+        self.up = if up.magnitude2() > 0.0001 {
+            up.normalize()
+        } else {
+            compute_up_direction(self.normal)
+        };
+    }
+
+    /// Returns the width and height of the light.
+    pub fn size(&self) -> (f32, f32) {
+        (self.width, self.height)
+    }
+
+    /// Sets the width and height of the light.
+    pub fn set_size(&mut self, width: f32, height: f32) {
+        self.width = width;
+        self.height = height;
+    }
+
+    fn right(&self) -> Vec3 {
+        self.normal.cross(self.up)
+    }
+}
+
+impl Light for AreaLight {
+    fn shader_source(&self, i: u32) -> String {
+        format!(
+        "
+            #include <{}>
+            uniform vec4 lightParameters{}[4];
+
+            vec3 calculate_lighting{}(vec3 surface_color, vec3 position, vec3 normal, vec3 view_direction, float metallic, float roughness, float occlusion)
+            {{
+                return calculate_area_light(lightParameters{}[0].xyz, lightParameters{}[1].xyz, lightParameters{}[2].xyz, lightParameters{}[3].xyz,
+                    surface_color, position, view_direction, normal, metallic, roughness);
+            }}
+
+        ", AREA_LIGHT_SNIPPET, i, i, i, i, i, i)
+    }
+
+    fn use_uniforms(&self, program: &Program, i: u32) {
+        program.use_texture("ltcMatrixTexture", &self.ltc_matrix_texture);
+        program.use_texture("ltcMagnitudeTexture", &self.ltc_magnitude_texture);
+        program.use_uniform_array(
+            &format!("lightParameters{}", i),
+            &[
+                (self.color.to_linear_srgb().truncate() * self.intensity).extend(0.0),
+                self.position.extend(0.0),
+                (self.right() * 0.5 * self.width).extend(0.0),
+                (self.up * 0.5 * self.height).extend(0.0),
+            ],
+        );
+    }
+
+    fn id(&self) -> LightId {
+        LightId::AreaLight
+    }
+}
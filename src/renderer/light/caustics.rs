@@ -0,0 +1,89 @@
+use crate::core::*;
+
+///
+/// Animated water caustics, ie. the bright moving pattern of light focused by the water surface onto underwater geometry.
+/// The pattern is generated procedurally into a small tiling texture each time [Caustics::update] is called and is projected by a [DirectionalLight](crate::DirectionalLight)
+/// onto the geometry below the water plane when assigned to [DirectionalLight::caustics](crate::DirectionalLight::caustics).
+/// Geometry above the water plane is unaffected.
+///
+pub struct Caustics {
+    context: Context,
+    texture: Texture2D,
+    program: Program,
+    /// The height (y-coordinate) of the water plane in world space. Only geometry below this height receives caustics.
+    pub water_level: f32,
+    /// The size in world space units of one tile of the caustics pattern.
+    pub scale: f32,
+    /// The animation speed of the pattern, where 1 is a moderate speed.
+    pub speed: f32,
+    /// The intensity of the caustics, where 0 means no caustics.
+    pub intensity: f32,
+    /// How fast the caustics fade with the distance below the water plane. The caustics are scaled by `exp(-depth_attenuation * depth)`.
+    pub depth_attenuation: f32,
+    /// The distance in world space units per unit of depth that the red and blue components of the pattern are shifted to simulate dispersion.
+    pub chromatic_offset: f32,
+}
+
+impl Caustics {
+    ///
+    /// Creates new caustics where the pattern is generated into a texture with the given width and height in pixels.
+    /// The pattern is smooth, so a small texture, for example 256x256 pixels, is usually enough.
+    ///
+    pub fn new(context: &Context, resolution: u32) -> Self {
+        let program = Program::from_source(
+            context,
+            crate::core::full_screen_vertex_shader_source(),
+            include_str!("shaders/caustics.frag"),
+        )
+        .expect("Failed compiling shader");
+        let mut caustics = Self {
+            context: context.clone(),
+            texture: Texture2D::new_empty::<u8>(
+                context,
+                resolution,
+                resolution,
+                Interpolation::Linear,
+                Interpolation::Linear,
+                Some(Mipmap::default()),
+                Wrapping::Repeat,
+                Wrapping::Repeat,
+            ),
+            program,
+            water_level: 0.0,
+            scale: 4.0,
+            speed: 1.0,
+            intensity: 1.5,
+            depth_attenuation: 0.2,
+            chromatic_offset: 0.005,
+        };
+        caustics.update(0.0);
+        caustics
+    }
+
+    ///
+    /// Generates the caustics pattern at the given time in seconds, for example `0.001 * frame_input.accumulated_time as f32`.
+    /// Call this each frame to animate the caustics.
+    ///
+    pub fn update(&mut self, time: f32) {
+        let viewport = Viewport::new_at_origo(self.texture.width(), self.texture.height());
+        let program = &self.program;
+        let time = time * self.speed;
+        let context = &self.context;
+        self.texture
+            .as_color_target(None)
+            .clear(ClearState::default())
+            .write::<CoreError>(|| {
+                program.use_uniform("time", time);
+                crate::core::full_screen_draw(context, program, RenderStates::default(), viewport);
+                Ok(())
+            })
+            .unwrap();
+    }
+
+    ///
+    /// Returns the tiling texture containing the caustics pattern.
+    ///
+    pub fn texture(&self) -> &Texture2D {
+        &self.texture
+    }
+}
@@ -2,13 +2,30 @@ use crate::core::*;
 use crate::renderer::light::*;
 use crate::renderer::*;
 
+///
+/// The maximum number of cascades in [DirectionalLight::generate_cascaded_shadow_maps].
+///
+pub const MAX_SHADOW_CASCADES: usize = 4;
+
+enum DirectionalLightShadow {
+    Texture(DepthTexture2D),
+    Cascades {
+        texture: DepthTexture2DArray,
+        matrices: Vec<Mat4>,
+        splits: Vec<f32>,
+        camera_position: Vec3,
+        camera_direction: Vec3,
+    },
+}
+
 ///
 /// A light which shines in the given direction.
-/// The light will cast shadows if you [generate a shadow map](DirectionalLight::generate_shadow_map).
+/// The light will cast shadows if you [generate a shadow map](DirectionalLight::generate_shadow_map)
+/// or [cascaded shadow maps](DirectionalLight::generate_cascaded_shadow_maps), which give a higher shadow resolution close to the camera in large scenes.
 ///
 pub struct DirectionalLight {
     context: Context,
-    shadow_texture: Option<DepthTexture2D>,
+    shadow_texture: Option<DirectionalLightShadow>,
     shadow_matrix: Mat4,
     /// The intensity of the light. This allows for higher intensity than 1 which can be used to simulate high intensity light sources like the sun.
     pub intensity: f32,
@@ -16,6 +33,15 @@ pub struct DirectionalLight {
     pub color: Srgba,
     /// The direction the light shines.
     pub direction: Vec3,
+    /// Optional water [Caustics] projected along the light direction onto the geometry below the water plane.
+    pub caustics: Option<Caustics>,
+    /// The quality of the shadow, which controls the filtering of the shadow edges. Only used if the light casts shadows
+    /// and the shadow softness and bias are zero, see [DirectionalLight::set_shadow_softness] and [DirectionalLight::set_shadow_bias].
+    pub shadow_quality: ShadowQuality,
+    shadow_softness: f32,
+    shadow_samples: u32,
+    shadow_bias: f32,
+    angular_size: f32,
 }
 
 impl DirectionalLight {
@@ -33,11 +59,31 @@ impl DirectionalLight {
             intensity,
             color,
             direction,
+            caustics: None,
+            shadow_quality: ShadowQuality::default(),
+            shadow_softness: 0.0,
+            shadow_samples: 16,
+            shadow_bias: 0.0,
+            angular_size: 0.0,
         }
     }
 
     ///
-    /// Clear the shadow map, effectively disable the shadow.
+    /// Creates a new directional light with the given illuminance in lux, ie. lumen per square meter, on surfaces facing the light.
+    /// For example, direct sunlight is around 100000 lux and an overcast sky gives around 1000 lux.
+    /// Use a camera with an exposure, see [Camera::set_exposure], to render physical light units.
+    ///
+    pub fn new_physical(
+        context: &Context,
+        illuminance: f32,
+        color: Srgba,
+        direction: Vec3,
+    ) -> DirectionalLight {
+        Self::new(context, illuminance, color, direction)
+    }
+
+    ///
+    /// Clear the shadow map or the cascaded shadow maps, effectively disable the shadow.
     /// Only necessary if you want to disable the shadow, if you want to update the shadow, just use [DirectionalLight::generate_shadow_map].
     ///
     pub fn clear_shadow_map(&mut self) {
@@ -56,6 +102,11 @@ impl DirectionalLight {
         texture_size: u32,
         geometries: impl IntoIterator<Item = impl Geometry> + Clone,
     ) -> Result<(), RendererError> {
+        trace_span!(
+            "three_d::generate_shadow_map",
+            light = "directional",
+            size = texture_size
+        );
         let up = compute_up_direction(self.direction);
 
         let viewport = Viewport::new_at_origo(texture_size, texture_size);
@@ -71,7 +122,7 @@ impl DirectionalLight {
         let z_far = aabb.distance_max(position);
         let z_near = -z_far;
         let frustum_height = aabb.max().distance(aabb.min()); // TODO: more tight fit
-        let shadow_camera = Camera::new_orthographic(
+        let mut shadow_camera = Camera::new_orthographic(
             viewport,
             position,
             target,
@@ -80,6 +131,7 @@ impl DirectionalLight {
             z_near,
             z_far,
         );
+        shadow_camera.auto_fit_depth_range(aabb, 0.001 * frustum_height);
         let mut shadow_texture = DepthTexture2D::new::<f32>(
             &self.context,
             texture_size,
@@ -103,74 +155,425 @@ impl DirectionalLight {
                     .into_iter()
                     .filter(|g| frustum.contains(g.aabb()))
                 {
-                    render_with_material(
+                    render_shadow_caster(
                         &self.context,
                         &shadow_camera,
                         &geometry,
                         &depth_material,
-                        &[],
                     )?;
                 }
                 Ok(())
             })?;
-        self.shadow_texture = Some(shadow_texture);
+        self.shadow_texture = Some(DirectionalLightShadow::Texture(shadow_texture));
         self.shadow_matrix = shadow_matrix(&shadow_camera);
         Ok(())
     }
 
     ///
-    /// Returns a reference to the shadow map if it has been generated.
+    /// Generate cascaded shadow maps which are used to simulate shadows from the directional light onto the geometries given as input.
+    /// The view frustum of the given camera is split at the given distances from the camera along the view direction,
+    /// so the first cascade covers from the near plane to the first distance, the second from the first to the second distance and so on,
+    /// and a shadow map with the given width and height in texels is rendered for each cascade.
+    /// Close to the camera the shadow maps cover a small area, which gives a much higher resolution than a single shadow map covering the entire scene.
+    /// The cascades are blended in the last tenth of each cascade to hide the seams between them and there is no shadow beyond the last distance.
+    ///
+    /// The shadow maps are fitted to a sphere around each cascade and moved in whole texels,
+    /// so the shadows do not shimmer when the camera moves or rotates. Call this each frame the camera or the scene moves.
+    /// At most [MAX_SHADOW_CASCADES] cascades are supported.
+    ///
+    pub fn generate_cascaded_shadow_maps(
+        &mut self,
+        camera: &Camera,
+        texture_size: u32,
+        geometries: impl IntoIterator<Item = impl Geometry> + Clone,
+        cascades: &[f32],
+    ) -> Result<(), RendererError> {
+        trace_span!(
+            "three_d::generate_shadow_map",
+            light = "directional",
+            size = texture_size
+        );
+        if cascades.len() > MAX_SHADOW_CASCADES {
+            return Err(RendererError::TooManyShadowCascades(
+                cascades.len(),
+                MAX_SHADOW_CASCADES,
+            ));
+        }
+        let mut aabb = AxisAlignedBoundingBox::EMPTY;
+        for geometry in geometries.clone() {
+            aabb.expand_with_aabb(geometry.aabb());
+        }
+        if aabb.is_empty() || cascades.is_empty() {
+            return Ok(());
+        }
+
+        let direction = self.direction.normalize();
+        let up = compute_up_direction(self.direction);
+        let light_view = Mat4::look_to_rh(Point3::origin(), direction, up);
+        let inverse_light_view = light_view.invert().unwrap();
+        let viewport = Viewport::new_at_origo(texture_size, texture_size);
+        let mut texture = DepthTexture2DArray::new::<f32>(
+            &self.context,
+            texture_size,
+            texture_size,
+            cascades.len() as u32,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+        );
+        let depth_material = DepthMaterial {
+            render_states: RenderStates {
+                write_mask: WriteMask::DEPTH,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut matrices = Vec::new();
+        let mut near = camera.z_near();
+        for (layer, far) in cascades.iter().enumerate() {
+            // A sphere around the part of the view frustum has the same size no matter the rotation of the camera
+            let corners = frustum_slice_corners(camera, near, *far);
+            let center = corners.iter().fold(Vec3::zero(), |sum, c| sum + c) / 8.0;
+            let radius = corners
+                .iter()
+                .map(|c| c.distance(center))
+                .fold(0.0, f32::max);
+            let radius = (radius * 16.0).ceil() / 16.0;
+
+            // Snap the center to whole texels in the light space to avoid shimmering when the camera moves
+            let texel_size = 2.0 * radius / texture_size as f32;
+            let center = light_view.transform_point(Point3::from_vec(center));
+            let center = Point3::new(
+                (center.x / texel_size).floor() * texel_size,
+                (center.y / texel_size).floor() * texel_size,
+                center.z,
+            );
+            let position = inverse_light_view.transform_point(center).to_vec();
+
+            let mut shadow_camera = Camera::new_orthographic(
+                viewport,
+                position,
+                position + direction,
+                up,
+                2.0 * radius,
+                0.0,
+                1.0,
+            );
+            shadow_camera.auto_fit_depth_range(aabb, 0.001 * aabb.size().magnitude());
+            let frustum = shadow_camera.frustum();
+            texture
+                .as_depth_target(layer as u32)
+                .clear(ClearState::default())
+                .write::<RendererError>(|| {
+                    for geometry in geometries
+                        .clone()
+                        .into_iter()
+                        .filter(|g| frustum.contains(g.aabb()))
+                    {
+                        render_shadow_caster(
+                            &self.context,
+                            &shadow_camera,
+                            &geometry,
+                            &depth_material,
+                        )?;
+                    }
+                    Ok(())
+                })?;
+            matrices.push(shadow_matrix(&shadow_camera));
+            near = *far;
+        }
+        self.shadow_texture = Some(DirectionalLightShadow::Cascades {
+            texture,
+            matrices,
+            splits: cascades.to_vec(),
+            camera_position: camera.position(),
+            camera_direction: camera.view_direction(),
+        });
+        self.shadow_matrix = Mat4::identity();
+        Ok(())
+    }
+
+    ///
+    /// Returns a reference to the shadow map if it has been generated using [DirectionalLight::generate_shadow_map].
     ///
     pub fn shadow_map(&self) -> Option<&DepthTexture2D> {
-        self.shadow_texture.as_ref()
+        match &self.shadow_texture {
+            Some(DirectionalLightShadow::Texture(texture)) => Some(texture),
+            _ => None,
+        }
+    }
+
+    ///
+    /// Returns a reference to the cascaded shadow maps, one layer per cascade, if they have been generated using [DirectionalLight::generate_cascaded_shadow_maps].
+    ///
+    pub fn cascaded_shadow_maps(&self) -> Option<&DepthTexture2DArray> {
+        match &self.shadow_texture {
+            Some(DirectionalLightShadow::Cascades { texture, .. }) => Some(texture),
+            _ => None,
+        }
+    }
+
+    ///
+    /// Returns the radius in texels of the percentage closer filtering of the shadow, see [DirectionalLight::set_shadow_softness].
+    ///
+    pub fn shadow_softness(&self) -> f32 {
+        self.shadow_softness
+    }
+
+    ///
+    /// Sets the radius in texels of the shadow map within which the shadow is sampled to soften the shadow edges using percentage closer filtering.
+    /// The number of samples is set using [DirectionalLight::set_shadow_samples].
+    /// A radius of zero, which is the default, means that the filtering is instead determined by the [DirectionalLight::shadow_quality].
+    ///
+    pub fn set_shadow_softness(&mut self, radius_in_texels: f32) {
+        self.shadow_softness = radius_in_texels.max(0.0);
+    }
+
+    ///
+    /// Returns the number of samples used to soften the shadow edges, see [DirectionalLight::set_shadow_samples].
+    ///
+    pub fn shadow_samples(&self) -> u32 {
+        self.shadow_samples
+    }
+
+    ///
+    /// Sets the number of samples, between 1 and 64, used to soften the shadow edges when the shadow softness is larger than zero, see [DirectionalLight::set_shadow_softness].
+    /// More samples give smoother shadow edges at a higher cost. Default is 16.
+    ///
+    pub fn set_shadow_samples(&mut self, samples: u32) {
+        self.shadow_samples = samples.clamp(1, 64);
+    }
+
+    ///
+    /// Returns the additional depth bias of the shadow, see [DirectionalLight::set_shadow_bias].
+    ///
+    pub fn shadow_bias(&self) -> f32 {
+        self.shadow_bias
+    }
+
+    ///
+    /// Sets an additional depth bias which is added to the bias computed from the angle between the surface and the light.
+    /// Increase the bias if a surface incorrectly shadows itself (shadow acne), which is more visible with soft shadows,
+    /// and decrease it if the shadows are detached from the objects casting them. Default is zero.
+    ///
+    pub fn set_shadow_bias(&mut self, bias: f32) {
+        self.shadow_bias = bias;
+    }
+
+    ///
+    /// Returns the angular diameter in degrees of the light source as seen from the lit surfaces, see [DirectionalLight::set_angular_size].
+    ///
+    pub fn angular_size(&self) -> f32 {
+        self.angular_size
+    }
+
+    ///
+    /// Sets the angular diameter in degrees of the light source as seen from the lit surfaces, for example the sun is about 0.5 degrees and a large soft box is several degrees.
+    /// A larger size gives broader and less intense specular highlights, which matches the highlights from an [Environment] better,
+    /// while the diffuse lighting is unaffected. Default is zero, ie. an ideal light source with pinpoint highlights.
+    ///
+    pub fn set_angular_size(&mut self, degrees: f32) {
+        self.angular_size = degrees.clamp(0.0, 90.0);
+    }
+
+    fn has_shadow_filter(&self) -> bool {
+        self.shadow_texture.is_some() && (self.shadow_softness > 0.0 || self.shadow_bias != 0.0)
     }
 }
 
+///
+/// Returns the corners of the part of the view frustum of the given camera between the given distances along the view direction.
+///
+fn frustum_slice_corners(camera: &Camera, near: f32, far: f32) -> [Vec3; 8] {
+    let position = camera.position();
+    let direction = camera.view_direction();
+    let right = camera.right_direction().normalize();
+    let up = right.cross(direction);
+    let aspect = camera.viewport().aspect();
+    let mut corners = [Vec3::zero(); 8];
+    for (i, distance) in [near, far].into_iter().enumerate() {
+        let half_height = match camera.projection_type() {
+            three_d_asset::ProjectionType::Perspective { field_of_view_y } => {
+                distance * (0.5 * field_of_view_y.0).tan()
+            }
+            three_d_asset::ProjectionType::Orthographic { height } => 0.5 * height,
+        };
+        let half_width = half_height * aspect;
+        let center = position + direction * distance;
+        corners[4 * i] = center - right * half_width - up * half_height;
+        corners[4 * i + 1] = center + right * half_width - up * half_height;
+        corners[4 * i + 2] = center + right * half_width + up * half_height;
+        corners[4 * i + 3] = center - right * half_width + up * half_height;
+    }
+    corners
+}
+
 impl Light for DirectionalLight {
     fn shader_source(&self, i: u32) -> String {
+        let mut uniforms = String::new();
+        match self.shadow_texture {
+            Some(DirectionalLightShadow::Texture(_)) => uniforms.push_str(&format!(
+                "uniform sampler2D shadowMap{i};\nuniform mat4 shadowMVP{i};\n"
+            )),
+            Some(DirectionalLightShadow::Cascades { .. }) => uniforms.push_str(&format!(
+                "uniform sampler2DArray shadowMaps{i};\nuniform mat4 shadowMVPs{i}[{MAX_SHADOW_CASCADES}];\nuniform vec4 shadowSplits{i};\nuniform int shadowCascadeCount{i};\nuniform vec3 shadowCameraPosition{i};\nuniform vec3 shadowCameraDirection{i};\n"
+            )),
+            None => {}
+        }
         if self.shadow_texture.is_some() {
-            format!(
+            if self.has_shadow_filter() {
+                uniforms.push_str(&format!(
+                    "uniform vec2 shadowFilter{i};\nuniform int shadowSamples{i};\n"
+                ));
+            } else {
+                uniforms.push_str(&format!("uniform int shadowKernel{i};\n"));
+            }
+        }
+        if self.caustics.is_some() {
+            uniforms.push_str(&format!(
+                "uniform sampler2D causticsTexture{i};\nuniform vec4 causticsParameters{i};\nuniform float causticsChromaticOffset{i};\n"
+            ));
+        }
+        let shadow = match self.shadow_texture {
+            Some(DirectionalLightShadow::Texture(_)) if self.has_shadow_filter() => format!(
                 "
-                    uniform sampler2D shadowMap{};
-                    uniform mat4 shadowMVP{};
-
-                    uniform vec3 color{};
-                    uniform vec3 direction{};
-
-                    vec3 calculate_lighting{}(vec3 surface_color, vec3 position, vec3 normal, vec3 view_direction, float metallic, float roughness, float occlusion)
-                    {{
-                        return calculate_light(color{}, -direction{}, surface_color, view_direction, normal, metallic, roughness)
-                            * calculate_shadow(-direction{}, normal, shadowMap{}, shadowMVP{}, position);
-                    }}
-
-                ", i, i, i, i, i, i, i, i, i, i)
-        } else {
+                        result *= calculate_soft_shadow(-direction{i}, normal, shadowMap{i}, shadowMVP{i}, position, shadowFilter{i}.x, shadowSamples{i}, shadowFilter{i}.y);"
+            ),
+            Some(DirectionalLightShadow::Texture(_)) => format!(
+                "
+                        result *= calculate_shadow_with_kernel(-direction{i}, normal, shadowMap{i}, shadowMVP{i}, position, shadowKernel{i});"
+            ),
+            Some(DirectionalLightShadow::Cascades { .. }) => {
+                let filter = if self.has_shadow_filter() {
+                    format!("1, shadowFilter{i}.x, shadowSamples{i}, shadowFilter{i}.y")
+                } else {
+                    format!("shadowKernel{i}, 0.0, 1, 0.0")
+                };
+                format!(
+                    "
+                        float shadow_depth = dot(position - shadowCameraPosition{i}, shadowCameraDirection{i});
+                        result *= calculate_cascaded_shadow(-direction{i}, normal, shadowMaps{i}, shadowMVPs{i}, shadowSplits{i}, shadowCascadeCount{i}, shadow_depth, position, {filter});"
+                )
+            }
+            None => String::new(),
+        };
+        let caustics = if self.caustics.is_some() {
             format!(
                 "
-                    uniform vec3 color{};
-                    uniform vec3 direction{};
+                        float water_depth = causticsParameters{i}.x - position.y;
+                        if (water_depth > 0.0 && direction{i}.y < 0.0) {{
+                            // Project the position along the light direction onto the water plane
+                            vec2 caustics_uv = (position.xz - direction{i}.xz * water_depth / -direction{i}.y) * causticsParameters{i}.y;
+                            vec2 dispersion = normalize(direction{i}.xz + vec2(0.0001)) * causticsChromaticOffset{i} * water_depth * causticsParameters{i}.y;
+                            vec3 caustics = vec3(
+                                texture(causticsTexture{i}, caustics_uv + dispersion).r,
+                                texture(causticsTexture{i}, caustics_uv).r,
+                                texture(causticsTexture{i}, caustics_uv - dispersion).r
+                            );
+                            float fade = exp(-causticsParameters{i}.w * water_depth) * smoothstep(0.0, 0.05, water_depth);
+                            result *= 1.0 + causticsParameters{i}.z * fade * caustics;
+                        }}"
+            )
+        } else {
+            String::new()
+        };
+        format!(
+            "
+                    {uniforms}
+                    uniform vec4 lightParameters{i}[2];
 
-                    vec3 calculate_lighting{}(vec3 surface_color, vec3 position, vec3 normal, vec3 view_direction, float metallic, float roughness, float occlusion)
+                    vec3 calculate_lighting{i}(vec3 surface_color, vec3 position, vec3 normal, vec3 view_direction, float metallic, float roughness, float occlusion)
                     {{
-                        return calculate_light(color{}, -direction{}, surface_color, view_direction, normal, metallic, roughness);
+                        vec3 color{i} = lightParameters{i}[0].xyz;
+                        float angularRadius{i} = lightParameters{i}[0].w;
+                        vec3 direction{i} = lightParameters{i}[1].xyz;
+                        vec3 result = calculate_light_with_angular_radius(color{i}, -direction{i}, surface_color, view_direction, normal, metallic, roughness, angularRadius{i});{caustics}{shadow}
+                        return result;
                     }}
 
-                ", i, i, i, i, i)
-        }
+                "
+        )
     }
     fn use_uniforms(&self, program: &Program, i: u32) {
-        if let Some(ref tex) = self.shadow_texture {
-            program.use_depth_texture(&format!("shadowMap{}", i), tex);
-            program.use_uniform(&format!("shadowMVP{}", i), self.shadow_matrix);
+        match &self.shadow_texture {
+            Some(DirectionalLightShadow::Texture(tex)) => {
+                program.use_depth_texture(&format!("shadowMap{}", i), tex);
+                program.use_uniform(&format!("shadowMVP{}", i), self.shadow_matrix);
+            }
+            Some(DirectionalLightShadow::Cascades {
+                texture,
+                matrices,
+                splits,
+                camera_position,
+                camera_direction,
+            }) => {
+                let mut mvps = [Mat4::identity(); MAX_SHADOW_CASCADES];
+                mvps[..matrices.len()].copy_from_slice(matrices);
+                let mut far = [0.0; MAX_SHADOW_CASCADES];
+                far[..splits.len()].copy_from_slice(splits);
+                program.use_depth_texture_array(&format!("shadowMaps{}", i), texture);
+                program.use_uniform_array(&format!("shadowMVPs{}", i), &mvps);
+                program.use_uniform(
+                    &format!("shadowSplits{}", i),
+                    vec4(far[0], far[1], far[2], far[3]),
+                );
+                program.use_uniform(&format!("shadowCascadeCount{}", i), splits.len() as i32);
+                program.use_uniform(&format!("shadowCameraPosition{}", i), *camera_position);
+                program.use_uniform(&format!("shadowCameraDirection{}", i), *camera_direction);
+            }
+            None => {}
+        }
+        if self.shadow_texture.is_some() {
+            if self.has_shadow_filter() {
+                program.use_uniform(
+                    &format!("shadowFilter{}", i),
+                    vec2(self.shadow_softness, self.shadow_bias),
+                );
+                // Only bias the shadow, so a single sample is enough
+                let samples = if self.shadow_softness > 0.0 {
+                    self.shadow_samples
+                } else {
+                    1
+                };
+                program.use_uniform(&format!("shadowSamples{}", i), samples as i32);
+            } else {
+                program.use_uniform(&format!("shadowKernel{}", i), self.shadow_quality.kernel());
+            }
+        }
+        if let Some(ref caustics) = self.caustics {
+            program.use_texture(&format!("causticsTexture{}", i), caustics.texture());
+            program.use_uniform(
+                &format!("causticsParameters{}", i),
+                vec4(
+                    caustics.water_level,
+                    1.0 / caustics.scale,
+                    caustics.intensity,
+                    caustics.depth_attenuation,
+                ),
+            );
+            program.use_uniform(
+                &format!("causticsChromaticOffset{}", i),
+                caustics.chromatic_offset,
+            );
         }
-        program.use_uniform(
-            &format!("color{}", i),
-            self.color.to_linear_srgb().truncate() * self.intensity,
+        program.use_uniform_array(
+            &format!("lightParameters{}", i),
+            &[
+                (self.color.to_linear_srgb().truncate() * self.intensity)
+                    .extend(0.5 * self.angular_size.to_radians()),
+                self.direction.normalize().extend(0.0),
+            ],
         );
-        program.use_uniform(&format!("direction{}", i), self.direction.normalize());
     }
 
     fn id(&self) -> LightId {
-        LightId::DirectionalLight(self.shadow_texture.is_some())
+        LightId::DirectionalLight(
+            self.shadow_texture.is_some(),
+            self.caustics.is_some(),
+            self.has_shadow_filter(),
+            matches!(
+                self.shadow_texture,
+                Some(DirectionalLightShadow::Cascades { .. })
+            ),
+        )
     }
 }
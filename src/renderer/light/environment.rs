@@ -39,73 +39,15 @@ impl Environment {
         environment_map: &TextureCubeMap,
         lighting_model: LightingModel,
     ) -> Self {
-        // Diffuse
-        let irradiance_size = 32;
-        let mut irradiance_map = TextureCubeMap::new_empty::<[f16; 4]>(
+        let irradiance_map = irradiance_from::<f16>(context, environment_map, 32);
+        let prefilter_map = prefilter_specular_with_lighting_model::<f16>(
             context,
-            irradiance_size,
-            irradiance_size,
-            Interpolation::Linear,
-            Interpolation::Linear,
-            Some(Mipmap::default()),
-            Wrapping::ClampToEdge,
-            Wrapping::ClampToEdge,
-            Wrapping::ClampToEdge,
-        );
-        {
-            let viewport = Viewport::new_at_origo(irradiance_size, irradiance_size);
-            for side in CubeMapSide::iter() {
-                irradiance_map
-                    .as_color_target(&[side], None)
-                    .clear(ClearState::default())
-                    .apply_screen_material(
-                        &IrradianceMaterial {
-                            environment_map,
-                            side,
-                        },
-                        Camera::new_2d(viewport),
-                        &[],
-                    );
-            }
-        }
-
-        // Prefilter
-        let prefilter_size = 128;
-        let mut prefilter_map = TextureCubeMap::new_empty::<[f16; 4]>(
-            context,
-            prefilter_size,
-            prefilter_size,
-            Interpolation::Linear,
-            Interpolation::Linear,
-            Some(Mipmap::default()),
-            Wrapping::ClampToEdge,
-            Wrapping::ClampToEdge,
-            Wrapping::ClampToEdge,
+            environment_map,
+            128,
+            5,
+            1024,
+            lighting_model,
         );
-        {
-            let max_mip_levels = 5;
-            for mip in 0..max_mip_levels {
-                for side in CubeMapSide::iter() {
-                    let sides = [side];
-                    let color_target = prefilter_map.as_color_target(&sides, Some(mip));
-                    let viewport =
-                        Viewport::new_at_origo(color_target.width(), color_target.height());
-                    color_target
-                        .clear(ClearState::default())
-                        .apply_screen_material(
-                            &PrefilterMaterial {
-                                lighting_model,
-                                environment_map,
-                                side,
-                                mip,
-                                max_mip_levels,
-                            },
-                            Camera::new_2d(viewport),
-                            &[],
-                        );
-                }
-            }
-        }
 
         // BRDF
         let mut brdf_map = Texture2D::new_empty::<[f32; 2]>(
@@ -136,22 +78,149 @@ impl Environment {
     }
 }
 
+///
+/// Computes the irradiance from the given environment map, ie. the diffuse light arriving at a surface facing each direction,
+/// and returns it as a new cube map with the given size of each side. The irradiance varies slowly, so a small size like 32 is usually enough.
+///
+/// The environment map can have any data type, while the returned cube map has four channels of the generic parameter `T`,
+/// for example `f16` to keep the high dynamic range or `u8` for a compact result.
+/// Use [TextureCubeMap::read] or [TextureCubeMap::to_cross_layout] to read back the result, for example to save it.
+///
+pub fn irradiance_from<T: PrimitiveDataType + TextureDataType>(
+    context: &Context,
+    environment_map: &TextureCubeMap,
+    size: u32,
+) -> TextureCubeMap {
+    let size = size.max(1);
+    let mut irradiance_map = TextureCubeMap::new_empty::<[T; 4]>(
+        context,
+        size,
+        size,
+        Interpolation::Linear,
+        Interpolation::Linear,
+        Some(Mipmap::default()),
+        Wrapping::ClampToEdge,
+        Wrapping::ClampToEdge,
+        Wrapping::ClampToEdge,
+    );
+    let viewport = Viewport::new_at_origo(size, size);
+    for side in CubeMapSide::iter() {
+        irradiance_map
+            .as_color_target(&[side], None)
+            .clear(ClearState::default())
+            .apply_screen_material(
+                &IrradianceMaterial {
+                    environment_map,
+                    side,
+                },
+                Camera::new_2d(viewport),
+                &[],
+            );
+    }
+    irradiance_map
+}
+
+///
+/// Computes the prefiltered specular reflections of the given environment map using a Cook-Torrance lighting model
+/// and returns them as a new cube map with the given size of each side.
+/// Each of the first `mip_levels` mip levels contains the reflections for a surface roughness of `mip_level / (mip_levels - 1)`,
+/// so the first level is a perfect mirror and the last is fully rough.
+/// Each texel is computed from the given number of importance samples of the environment map, where more samples reduce noise at the cost of computation time.
+///
+/// The environment map can have any data type, but should have mip maps to avoid bright spots, while the returned cube map has four channels of the generic parameter `T`,
+/// for example `f16` to keep the high dynamic range or `u8` for a compact result.
+/// Use [TextureCubeMap::read] or [TextureCubeMap::to_cross_layout] to read back each mip level, for example to save it.
+///
+pub fn prefilter_specular<T: PrimitiveDataType + TextureDataType>(
+    context: &Context,
+    environment_map: &TextureCubeMap,
+    size: u32,
+    mip_levels: u32,
+    sample_count: u32,
+) -> TextureCubeMap {
+    prefilter_specular_with_lighting_model::<T>(
+        context,
+        environment_map,
+        size,
+        mip_levels,
+        sample_count,
+        LightingModel::Cook(
+            NormalDistributionFunction::TrowbridgeReitzGGX,
+            GeometryFunction::SmithSchlickGGX,
+        ),
+    )
+}
+
+fn prefilter_specular_with_lighting_model<T: PrimitiveDataType + TextureDataType>(
+    context: &Context,
+    environment_map: &TextureCubeMap,
+    size: u32,
+    mip_levels: u32,
+    sample_count: u32,
+    lighting_model: LightingModel,
+) -> TextureCubeMap {
+    let size = size.max(1);
+    let mut prefilter_map = TextureCubeMap::new_empty::<[T; 4]>(
+        context,
+        size,
+        size,
+        Interpolation::Linear,
+        Interpolation::Linear,
+        Some(Mipmap::default()),
+        Wrapping::ClampToEdge,
+        Wrapping::ClampToEdge,
+        Wrapping::ClampToEdge,
+    );
+    let mip_levels = mip_levels.clamp(1, prefilter_map.number_of_mip_maps().max(1));
+    for mip in 0..mip_levels {
+        for side in CubeMapSide::iter() {
+            let sides = [side];
+            let color_target = prefilter_map.as_color_target(&sides, Some(mip));
+            let viewport = Viewport::new_at_origo(color_target.width(), color_target.height());
+            color_target
+                .clear(ClearState::default())
+                .apply_screen_material(
+                    &PrefilterMaterial {
+                        lighting_model,
+                        environment_map,
+                        side,
+                        mip,
+                        max_mip_levels: mip_levels,
+                        sample_count: sample_count.max(1),
+                    },
+                    Camera::new_2d(viewport),
+                    &[],
+                );
+        }
+    }
+    prefilter_map
+}
+
+///
+/// Creates a new cube map with the given size of each side from the given equirectangular (panorama) texture,
+/// for example to use a user supplied panorama as input to [irradiance_from] and [prefilter_specular].
+/// See [TextureCubeMap::new_from_equirectangular_texture] for details on the data type.
+///
+pub fn equirectangular_to_cubemap<T: PrimitiveDataType + TextureDataType>(
+    context: &Context,
+    equirectangular_texture: &Texture2D,
+    size: u32,
+) -> TextureCubeMap {
+    TextureCubeMap::new_from_equirectangular_texture::<T>(context, equirectangular_texture, size)
+}
+
 struct PrefilterMaterial<'a> {
     lighting_model: LightingModel,
     environment_map: &'a TextureCubeMap,
     side: CubeMapSide,
     mip: u32,
     max_mip_levels: u32,
+    sample_count: u32,
 }
 
 impl Material for PrefilterMaterial<'_> {
     fn fragment_shader_source(&self, _lights: &[&dyn Light]) -> String {
-        format!(
-            "{}{}{}",
-            include_str!("../../core/shared.frag"),
-            include_str!("shaders/light_shared.frag"),
-            include_str!("shaders/prefilter.frag")
-        )
+        include_str!("shaders/prefilter.frag").to_string()
     }
 
     fn id(&self) -> EffectMaterialId {
@@ -163,8 +232,13 @@ impl Material for PrefilterMaterial<'_> {
         program.use_texture_cube("environmentMap", self.environment_map);
         program.use_uniform(
             "roughness",
-            self.mip as f32 / (self.max_mip_levels as f32 - 1.0),
+            if self.max_mip_levels > 1 {
+                self.mip as f32 / (self.max_mip_levels as f32 - 1.0)
+            } else {
+                0.0
+            },
         );
+        program.use_uniform("sampleCount", self.sample_count as i32);
         program.use_uniform("resolution", self.environment_map.width() as f32);
         program.use_uniform("direction", self.side.direction());
         program.use_uniform("up", self.side.up());
@@ -185,12 +259,7 @@ struct BrdfMaterial {
 
 impl Material for BrdfMaterial {
     fn fragment_shader_source(&self, _lights: &[&dyn Light]) -> String {
-        format!(
-            "{}{}{}",
-            include_str!("../../core/shared.frag"),
-            include_str!("shaders/light_shared.frag"),
-            include_str!("shaders/brdf.frag")
-        )
+        include_str!("shaders/brdf.frag").to_string()
     }
 
     fn id(&self) -> EffectMaterialId {
@@ -217,11 +286,7 @@ struct IrradianceMaterial<'a> {
 
 impl Material for IrradianceMaterial<'_> {
     fn fragment_shader_source(&self, _lights: &[&dyn Light]) -> String {
-        format!(
-            "{}{}",
-            include_str!("../../core/shared.frag"),
-            include_str!("shaders/irradiance.frag")
-        )
+        include_str!("shaders/irradiance.frag").to_string()
     }
 
     fn id(&self) -> EffectMaterialId {
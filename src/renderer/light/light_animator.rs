@@ -0,0 +1,290 @@
+use crate::core::*;
+use crate::renderer::light::*;
+
+///
+/// A function of time used by a [LightAnimator] to animate a property of a light.
+/// The modulators can be composed using [Modulator::Sum] and [Modulator::Product], for example a flicker multiplied with a strobe pattern.
+/// All modulators are deterministic, ie. they always return the same value given the same time.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub enum Modulator {
+    /// A constant value.
+    Constant(f32),
+    /// A sine wave between the given minimum and maximum value.
+    Sine {
+        /// The number of periods per second.
+        frequency: f32,
+        /// The offset of the wave as a fraction of a period.
+        phase: f32,
+        /// The minimum value.
+        min: f32,
+        /// The maximum value.
+        max: f32,
+    },
+    /// Smooth random changes between the given minimum and maximum value using one dimensional Perlin noise.
+    /// Different seeds give different, but reproducible, noise.
+    Flicker {
+        /// The average number of changes per second.
+        frequency: f32,
+        /// The seed of the noise.
+        seed: u32,
+        /// The minimum value.
+        min: f32,
+        /// The maximum value.
+        max: f32,
+    },
+    /// A repeating on/off pattern where each step in the pattern lasts the given duration.
+    Strobe {
+        /// The pattern where `true` is on and `false` is off.
+        pattern: Vec<bool>,
+        /// The duration in seconds of each step in the pattern.
+        step_duration: f32,
+        /// The value when off.
+        off: f32,
+        /// The value when on.
+        on: f32,
+    },
+    /// A curve which is linearly interpolated between the given key frames, each given as a time in seconds and a value.
+    /// The key frames must be sorted by time. Before the first and after the last key frame, the value of the first and last key frame is used,
+    /// unless the curve is looping in which case the curve repeats with a period equal to the time of the last key frame.
+    Curve {
+        /// The key frames given as time in seconds and value.
+        key_frames: Vec<(f32, f32)>,
+        /// Whether the curve repeats.
+        looping: bool,
+    },
+    /// The sum of the values of the given modulators.
+    Sum(Vec<Modulator>),
+    /// The product of the values of the given modulators.
+    Product(Vec<Modulator>),
+}
+
+impl Modulator {
+    ///
+    /// Returns the value at the given time in seconds.
+    ///
+    pub fn value(&self, time: f32) -> f32 {
+        match self {
+            Self::Constant(value) => *value,
+            Self::Sine {
+                frequency,
+                phase,
+                min,
+                max,
+            } => {
+                let s = (2.0 * std::f32::consts::PI * (frequency * time + phase)).sin();
+                min + (max - min) * (0.5 + 0.5 * s)
+            }
+            Self::Flicker {
+                frequency,
+                seed,
+                min,
+                max,
+            } => min + (max - min) * (0.5 + 0.5 * perlin_noise(frequency * time, *seed)),
+            Self::Strobe {
+                pattern,
+                step_duration,
+                off,
+                on,
+            } => {
+                if pattern.is_empty() || *step_duration <= 0.0 {
+                    return *off;
+                }
+                let step = (time / step_duration).floor() as i64;
+                if pattern[step.rem_euclid(pattern.len() as i64) as usize] {
+                    *on
+                } else {
+                    *off
+                }
+            }
+            Self::Curve {
+                key_frames,
+                looping,
+            } => {
+                let (Some(first), Some(last)) = (key_frames.first(), key_frames.last()) else {
+                    return 0.0;
+                };
+                let time = if *looping && last.0 > 0.0 {
+                    time.rem_euclid(last.0)
+                } else {
+                    time
+                };
+                if time <= first.0 {
+                    return first.1;
+                }
+                key_frames
+                    .windows(2)
+                    .find(|w| time < w[1].0)
+                    .map(|w| {
+                        let t = (time - w[0].0) / (w[1].0 - w[0].0);
+                        w[0].1 + t * (w[1].1 - w[0].1)
+                    })
+                    .unwrap_or(last.1)
+            }
+            Self::Sum(modulators) => modulators.iter().map(|m| m.value(time)).sum(),
+            Self::Product(modulators) => modulators.iter().map(|m| m.value(time)).product(),
+        }
+    }
+}
+
+///
+/// Returns one dimensional Perlin noise in the range `[-1, 1]` at the given position.
+///
+fn perlin_noise(x: f32, seed: u32) -> f32 {
+    let gradient = |i: i64| {
+        let mut h = (i as u32) ^ seed.wrapping_mul(0x9e3779b9);
+        h ^= h >> 16;
+        h = h.wrapping_mul(0x7feb352d);
+        h ^= h >> 15;
+        h = h.wrapping_mul(0x846ca68b);
+        h ^= h >> 16;
+        2.0 * (h as f32 / u32::MAX as f32) - 1.0
+    };
+    let i = x.floor();
+    let f = x - i;
+    let i = i as i64;
+    let fade = f * f * f * (f * (f * 6.0 - 15.0) + 10.0);
+    // The maximum absolute value of one dimensional Perlin noise with gradients in [-1, 1] is 0.5
+    (2.0 * (gradient(i) * f + fade * (gradient(i + 1) * (f - 1.0) - gradient(i) * f)))
+        .clamp(-1.0, 1.0)
+}
+
+///
+/// A light which properties can be animated by a [LightAnimator].
+///
+pub trait AnimatedLight {
+    ///
+    /// Sets the intensity of the light.
+    ///
+    fn set_intensity(&mut self, intensity: f32);
+
+    ///
+    /// Sets the color of the light.
+    ///
+    fn set_color(&mut self, color: Srgba);
+
+    ///
+    /// Sets the position of the light. Does nothing for lights without a position.
+    ///
+    fn set_position(&mut self, _position: Vec3) {}
+}
+
+impl AnimatedLight for PointLight {
+    fn set_intensity(&mut self, intensity: f32) {
+        self.intensity = intensity;
+    }
+    fn set_color(&mut self, color: Srgba) {
+        self.color = color;
+    }
+    fn set_position(&mut self, position: Vec3) {
+        self.position = position;
+    }
+}
+
+impl AnimatedLight for SpotLight {
+    fn set_intensity(&mut self, intensity: f32) {
+        self.intensity = intensity;
+    }
+    fn set_color(&mut self, color: Srgba) {
+        self.color = color;
+    }
+    fn set_position(&mut self, position: Vec3) {
+        self.position = position;
+    }
+}
+
+impl AnimatedLight for DirectionalLight {
+    fn set_intensity(&mut self, intensity: f32) {
+        self.intensity = intensity;
+    }
+    fn set_color(&mut self, color: Srgba) {
+        self.color = color;
+    }
+}
+
+impl AnimatedLight for AmbientLight {
+    fn set_intensity(&mut self, intensity: f32) {
+        self.intensity = intensity;
+    }
+    fn set_color(&mut self, color: Srgba) {
+        self.color = color;
+    }
+}
+
+impl<T: AnimatedLight + ?Sized> AnimatedLight for &mut T {
+    fn set_intensity(&mut self, intensity: f32) {
+        (*self).set_intensity(intensity)
+    }
+    fn set_color(&mut self, color: Srgba) {
+        (*self).set_color(color)
+    }
+    fn set_position(&mut self, position: Vec3) {
+        (*self).set_position(position)
+    }
+}
+
+impl<T: AnimatedLight> AnimatedLight for std::sync::Arc<std::sync::RwLock<T>> {
+    fn set_intensity(&mut self, intensity: f32) {
+        self.write().unwrap().set_intensity(intensity)
+    }
+    fn set_color(&mut self, color: Srgba) {
+        self.write().unwrap().set_color(color)
+    }
+    fn set_position(&mut self, position: Vec3) {
+        self.write().unwrap().set_position(position)
+    }
+}
+
+///
+/// Animates the intensity, color and position of a light, for example a [PointLight] or [SpotLight], using [Modulator]s.
+/// The properties without a modulator are left unchanged.
+///
+/// Call [LightAnimator::animate] each frame with the time in seconds, for example `0.001 * frame_input.accumulated_time as f32`.
+/// Since the modulators are deterministic, the light is always the same given the same time, which makes recordings reproducible.
+///
+/// ```
+/// # use three_d::*;
+/// // A torch that flickers and sways slightly around its position
+/// let animator = LightAnimator {
+///     intensity: Some(Modulator::Sum(vec![
+///         Modulator::Flicker { frequency: 8.0, seed: 1, min: 0.6, max: 1.0 },
+///         Modulator::Flicker { frequency: 23.0, seed: 2, min: -0.1, max: 0.1 },
+///     ])),
+///     position: Some([
+///         Modulator::Flicker { frequency: 2.0, seed: 3, min: -0.05, max: 0.05 },
+///         Modulator::Constant(2.0),
+///         Modulator::Flicker { frequency: 2.0, seed: 4, min: -0.05, max: 0.05 },
+///     ]),
+///     ..Default::default()
+/// };
+/// ```
+///
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LightAnimator {
+    /// The modulator of the intensity of the light.
+    pub intensity: Option<Modulator>,
+    /// Mixes the color of the light between the two given colors, where a modulator value of 0 gives the first color and a value of 1 gives the second color.
+    /// The value is clamped to `[0, 1]`.
+    pub color: Option<(Srgba, Srgba, Modulator)>,
+    /// The modulators of the x, y and z coordinate of the position of the light.
+    pub position: Option<[Modulator; 3]>,
+}
+
+impl LightAnimator {
+    ///
+    /// Sets the animated properties of the given light to the values at the given time in seconds.
+    ///
+    pub fn animate(&self, light: &mut impl AnimatedLight, time: f32) {
+        if let Some(intensity) = &self.intensity {
+            light.set_intensity(intensity.value(time));
+        }
+        if let Some((from, to, modulator)) = &self.color {
+            let t = modulator.value(time).clamp(0.0, 1.0);
+            let color = Vec4::from(*from) * (1.0 - t) + Vec4::from(*to) * t;
+            light.set_color(color.into());
+        }
+        if let Some([x, y, z]) = &self.position {
+            light.set_position(vec3(x.value(time), y.value(time), z.value(time)));
+        }
+    }
+}
@@ -0,0 +1,232 @@
+use crate::core::*;
+use crate::renderer::*;
+
+///
+/// The maximum number of lights in a [LightBuffer].
+/// Each light takes up 64 bytes, so the lights exactly fill the minimum uniform block size of 16384 bytes required by OpenGL ES 3.0 and WebGL 2.
+///
+pub const MAX_BUFFERED_LIGHTS: u32 = 256;
+
+///
+/// A light which can be added to a [LightBuffer].
+///
+#[derive(Clone, Copy)]
+pub enum BufferedLight<'a> {
+    /// A [PointLight].
+    Point(&'a PointLight),
+    /// A [SpotLight].
+    Spot(&'a SpotLight),
+    /// A [DirectionalLight].
+    Directional(&'a DirectionalLight),
+}
+
+impl<'a> From<&'a PointLight> for BufferedLight<'a> {
+    fn from(light: &'a PointLight) -> Self {
+        Self::Point(light)
+    }
+}
+
+impl<'a> From<&'a SpotLight> for BufferedLight<'a> {
+    fn from(light: &'a SpotLight) -> Self {
+        Self::Spot(light)
+    }
+}
+
+impl<'a> From<&'a DirectionalLight> for BufferedLight<'a> {
+    fn from(light: &'a DirectionalLight) -> Self {
+        Self::Directional(light)
+    }
+}
+
+///
+/// A collection of point, spot and directional lights which are packed into a uniform buffer and evaluated in a single loop in the shader.
+/// A light buffer is used as a single [Light] and the shader only depends on the capacity of the buffer, not the number or types of lights,
+/// so lights can be added and removed each frame, using [LightBuffer::update], without compiling new shaders as long as the capacity is not exceeded.
+/// This is useful for scenes with many short lived lights, for example muzzle flashes or glowing particles,
+/// while lights with shadows, cookies, falloff textures and color ramps should be given as separate lights, since these features are not supported by the buffer.
+///
+/// ```no_run
+/// # use three_d::*;
+/// # let context: Context = unimplemented!();
+/// # let camera: Camera = unimplemented!();
+/// # let objects: Vec<Gm<Mesh, PhysicalMaterial>> = unimplemented!();
+/// # let point_lights: Vec<PointLight> = unimplemented!();
+/// let mut light_buffer = LightBuffer::new(&context, 64);
+///
+/// // Each frame
+/// light_buffer
+///     .update(point_lights.iter().map(BufferedLight::from))
+///     .unwrap();
+/// RenderTarget::screen(&context, 0, 0).render(&camera, &objects, &[&light_buffer]);
+/// ```
+///
+pub struct LightBuffer {
+    buffer: UniformBuffer,
+    capacity: u32,
+    count: u32,
+}
+
+impl LightBuffer {
+    ///
+    /// Creates a new empty light buffer with room for at least the given number of lights.
+    /// The capacity is rounded up to a power of two between 8 and [MAX_BUFFERED_LIGHTS], since each capacity requires a separate shader,
+    /// and is limited to the number of lights that fit in the maximum uniform block size supported by the device.
+    ///
+    pub fn new(context: &Context, max_lights: u32) -> Self {
+        let max_capacity = (context.max_uniform_block_size() / 64).clamp(8, MAX_BUFFERED_LIGHTS);
+        // Round down to a power of two so the capacity never exceeds the block size
+        let max_capacity = 1 << (31 - max_capacity.leading_zeros());
+        let capacity = max_lights.clamp(8, max_capacity).next_power_of_two();
+        Self {
+            // The lights, uploaded as a single variable, while the light count is a separate uniform
+            buffer: UniformBuffer::new(context, &[16 * capacity]),
+            capacity,
+            count: 0,
+        }
+    }
+
+    ///
+    /// Returns the maximum number of lights in this buffer.
+    ///
+    pub fn capacity(&self) -> u32 {
+        self.capacity
+    }
+
+    ///
+    /// Returns the number of lights in this buffer.
+    ///
+    pub fn len(&self) -> u32 {
+        self.count
+    }
+
+    ///
+    /// Returns whether this buffer contains no lights.
+    ///
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    ///
+    /// Replaces the lights in this buffer with the given lights and uploads them to the GPU.
+    /// Call this once each frame the lights change, before rendering.
+    /// Shadows, cookie textures, falloff textures and color ramps of the lights are ignored.
+    /// Returns an error and leaves the buffer unchanged if there are more lights than the capacity of the buffer.
+    ///
+    pub fn update<'a>(
+        &mut self,
+        lights: impl IntoIterator<Item = BufferedLight<'a>>,
+    ) -> Result<(), RendererError> {
+        let mut data = Vec::with_capacity(16 * self.capacity as usize);
+        let mut count = 0;
+        for light in lights {
+            count += 1;
+            if count > self.capacity {
+                continue;
+            }
+            // Each light is four vec4s: color and type, position and cutoff, direction and attenuation
+            let (color, kind, position, cutoff, direction, attenuation) = match light {
+                BufferedLight::Point(light) => (
+                    light.color.to_linear_srgb().truncate() * light.intensity,
+                    0.0,
+                    light.position,
+                    0.0,
+                    Vec3::zero(),
+                    light.attenuation,
+                ),
+                BufferedLight::Spot(light) => (
+                    light.color.to_linear_srgb().truncate() * light.intensity,
+                    1.0,
+                    light.position,
+                    light.cutoff.0,
+                    light.direction.normalize(),
+                    light.attenuation,
+                ),
+                BufferedLight::Directional(light) => (
+                    light.color.to_linear_srgb().truncate() * light.intensity,
+                    2.0,
+                    Vec3::zero(),
+                    0.0,
+                    light.direction.normalize(),
+                    Attenuation::default(),
+                ),
+            };
+            data.extend_from_slice(&[color.x, color.y, color.z, kind]);
+            data.extend_from_slice(&[position.x, position.y, position.z, cutoff]);
+            data.extend_from_slice(&[direction.x, direction.y, direction.z, 0.0]);
+            data.extend_from_slice(&[
+                attenuation.constant,
+                attenuation.linear,
+                attenuation.quadratic,
+                0.0,
+            ]);
+        }
+        if count > self.capacity {
+            return Err(RendererError::TooManyBufferedLights(
+                count as usize,
+                self.capacity as usize,
+            ));
+        }
+        data.resize(16 * self.capacity as usize, 0.0);
+        self.buffer.update(0, &data);
+        self.count = count;
+        Ok(())
+    }
+}
+
+impl Light for LightBuffer {
+    fn shader_source(&self, i: u32) -> String {
+        let capacity = self.capacity;
+        let size = 4 * capacity;
+        format!(
+            "
+                uniform int lightCount{i};
+                layout (std140) uniform LightBlock{i}
+                {{
+                    vec4 lightData{i}[{size}];
+                }};
+
+                vec3 calculate_lighting{i}(vec3 surface_color, vec3 position, vec3 normal, vec3 view_direction, float metallic, float roughness, float occlusion)
+                {{
+                    vec3 color = vec3(0.0);
+                    int count = lightCount{i};
+                    for (int l = 0; l < {capacity}; l++)
+                    {{
+                        if (l >= count) {{
+                            break;
+                        }}
+                        vec4 color_type = lightData{i}[4 * l];
+                        vec4 position_cutoff = lightData{i}[4 * l + 1];
+                        vec3 direction = lightData{i}[4 * l + 2].xyz;
+                        vec3 attenuation = lightData{i}[4 * l + 3].xyz;
+
+                        vec3 light_color = color_type.rgb;
+                        vec3 light_direction = -direction;
+                        if (color_type.w < 1.5) {{
+                            light_direction = position_cutoff.xyz - position;
+                            float distance = length(light_direction);
+                            light_direction = light_direction / distance;
+                            light_color = attenuate(light_color, attenuation, distance);
+                            if (color_type.w > 0.5) {{
+                                float angle = acos(dot(-light_direction, direction));
+                                float cutoff = position_cutoff.w;
+                                light_color *= angle < cutoff ? 1.0 - smoothstep(0.75 * cutoff, cutoff, angle) : 0.0;
+                            }}
+                        }}
+                        color += calculate_light(light_color, light_direction, surface_color, view_direction, normal, metallic, roughness);
+                    }}
+                    return color;
+                }}
+
+            "
+        )
+    }
+
+    fn use_uniforms(&self, program: &Program, i: u32) {
+        program.use_uniform(&format!("lightCount{}", i), self.count as i32);
+        program.use_uniform_block(&format!("LightBlock{}", i), &self.buffer);
+    }
+
+    fn id(&self) -> LightId {
+        LightId::LightBuffer((self.capacity.trailing_zeros() - 3) as u8)
+    }
+}
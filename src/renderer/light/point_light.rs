@@ -1,10 +1,16 @@
 use crate::core::*;
+use crate::renderer::light::*;
 use crate::renderer::*;
 
 ///
 /// A light which shines from the given position in all directions.
+/// The light will cast shadows if you [generate a shadow map](PointLight::generate_shadow_map).
 ///
 pub struct PointLight {
+    context: Context,
+    shadow_texture: Option<DepthTextureCubeMap>,
+    shadow_near_far: Vec2,
+    color_ramp: Option<ColorRamp>,
     /// The intensity of the light. This allows for higher intensity than 1 which can be used to simulate high intensity light sources like the sun.
     pub intensity: f32,
     /// The base color of the light.
@@ -18,58 +24,255 @@ pub struct PointLight {
 impl PointLight {
     /// Constructs a new point light.
     pub fn new(
-        _context: &Context,
+        context: &Context,
         intensity: f32,
         color: Srgba,
         position: Vec3,
         attenuation: Attenuation,
     ) -> PointLight {
         PointLight {
+            context: context.clone(),
+            shadow_texture: None,
+            shadow_near_far: vec2(0.0, 0.0),
+            color_ramp: None,
             intensity,
             color,
             position,
             attenuation,
         }
     }
+
+    ///
+    /// Constructs a new point light with the given luminous flux in lumen, ie. the total amount of light emitted in all directions,
+    /// and physically correct inverse square attenuation.
+    /// For example, a 60 watt incandescent light bulb emits around 800 lumen.
+    /// Use a camera with an exposure, see [Camera::set_exposure], to render physical light units.
+    ///
+    pub fn new_physical(
+        context: &Context,
+        luminous_flux: f32,
+        color: Srgba,
+        position: Vec3,
+    ) -> PointLight {
+        // The luminous intensity in candela
+        let intensity = luminous_flux / (4.0 * std::f32::consts::PI);
+        Self::new(
+            context,
+            intensity,
+            color,
+            position,
+            Attenuation {
+                constant: 0.0,
+                linear: 0.0,
+                quadratic: 1.0,
+            },
+        )
+    }
+
+    ///
+    /// Clear the shadow map, effectively disable the shadow.
+    /// Only necessary if you want to disable the shadow, if you want to update the shadow, just use [PointLight::generate_shadow_map].
+    ///
+    pub fn clear_shadow_map(&mut self) {
+        self.shadow_texture = None;
+        self.shadow_near_far = vec2(0.0, 0.0);
+    }
+
+    ///
+    /// Generate a shadow map which is used to simulate shadows from the point light onto the geometries given as input.
+    /// The depth of the geometries is rendered into the six sides of a cube map around the light position,
+    /// each with the given width and height in texels. It is recomended that the texture size is power of 2.
+    /// The far plane is set to the distance to the farthest corner of the bounding box of the geometries and surfaces beyond the far plane are not shadowed.
+    /// The shadow must be generated again when the light or the geometries move.
+    ///
+    pub fn generate_shadow_map(
+        &mut self,
+        texture_size: u32,
+        geometries: impl IntoIterator<Item = impl Geometry> + Clone,
+    ) -> Result<(), RendererError> {
+        trace_span!(
+            "three_d::generate_shadow_map",
+            light = "point",
+            size = texture_size
+        );
+        let mut aabb = AxisAlignedBoundingBox::EMPTY;
+        for geometry in geometries.clone() {
+            aabb.expand_with_aabb(geometry.aabb());
+        }
+        if aabb.is_empty() {
+            return Ok(());
+        }
+        let z_far = aabb.distance_max(self.position);
+        let z_near = 0.01 * z_far;
+
+        let viewport = Viewport::new_at_origo(texture_size, texture_size);
+        let mut shadow_texture = DepthTextureCubeMap::new::<f32>(
+            &self.context,
+            texture_size,
+            texture_size,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+        );
+        let depth_material = DepthMaterial {
+            render_states: RenderStates {
+                write_mask: WriteMask::DEPTH,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        for side in CubeMapSide::iter() {
+            let shadow_camera = Camera::new_perspective(
+                viewport,
+                self.position,
+                self.position + side.direction(),
+                side.up(),
+                degrees(90.0),
+                z_near,
+                z_far,
+            );
+            let frustum = shadow_camera.frustum();
+            shadow_texture
+                .as_depth_target(side)
+                .clear(ClearState::default())
+                .write::<RendererError>(|| {
+                    for geometry in geometries
+                        .clone()
+                        .into_iter()
+                        .filter(|g| frustum.contains(g.aabb()))
+                    {
+                        render_shadow_caster(
+                            &self.context,
+                            &shadow_camera,
+                            &geometry,
+                            &depth_material,
+                        )?;
+                    }
+                    Ok(())
+                })?;
+        }
+        self.shadow_texture = Some(shadow_texture);
+        self.shadow_near_far = vec2(z_near, z_far);
+        Ok(())
+    }
+
+    ///
+    /// Returns a reference to the shadow map if it has been generated.
+    ///
+    pub fn shadow_map(&self) -> Option<&DepthTextureCubeMap> {
+        self.shadow_texture.as_ref()
+    }
+
+    ///
+    /// Returns the near and far plane used when the shadow map was generated, see [PointLight::generate_shadow_map].
+    ///
+    pub fn shadow_near_far(&self) -> Vec2 {
+        self.shadow_near_far
+    }
+
+    ///
+    /// Sets a gradient, given as pairs of a distance to the light and a color, which is multiplied with the light color depending on the distance to the light.
+    /// This makes it possible for the light to change color with the distance, for example from warm white near a fire to deep orange further away.
+    /// The color is linearly interpolated between the stops and the color of the first and last stop is used before and after these stops,
+    /// so a single stop is a constant color. The gradient is applied in addition to the [PointLight::attenuation].
+    /// Giving no stops removes the gradient, see also [PointLight::clear_color_ramp].
+    ///
+    pub fn set_color_ramp(&mut self, stops: &[(f32, Srgba)]) {
+        self.color_ramp = ColorRamp::new(&self.context, stops);
+    }
+
+    ///
+    /// Removes the gradient set by [PointLight::set_color_ramp].
+    ///
+    pub fn clear_color_ramp(&mut self) {
+        self.color_ramp = None;
+    }
+
+    ///
+    /// Returns the stops of the gradient set by [PointLight::set_color_ramp] sorted by distance.
+    ///
+    pub fn color_ramp(&self) -> Option<&[(f32, Srgba)]> {
+        self.color_ramp.as_ref().map(|ramp| ramp.stops.as_slice())
+    }
 }
 
 impl Light for PointLight {
     fn shader_source(&self, i: u32) -> String {
-        format!(
-        "
-            uniform vec3 color{};
-            uniform vec3 attenuation{};
-            uniform vec3 position{};
-
-            vec3 calculate_lighting{}(vec3 surface_color, vec3 position, vec3 normal, vec3 view_direction, float metallic, float roughness, float occlusion)
-            {{
-                vec3 light_direction = position{} - position;
-                float distance = length(light_direction);
-                light_direction = light_direction / distance;
-
-                vec3 light_color = attenuate(color{}, attenuation{}, distance);
-                return calculate_light(light_color, light_direction, surface_color, view_direction, normal, metallic, roughness);
-            }}
-        
-        ", i, i, i, i, i, i, i)
+        let (ramp_uniforms, ramp) = if self.color_ramp.is_some() {
+            (
+                ColorRamp::shader_source(i),
+                format!("\n                    {}", ColorRamp::sample_source(i)),
+            )
+        } else {
+            (String::new(), String::new())
+        };
+        if self.shadow_texture.is_some() {
+            format!(
+            "
+                {ramp_uniforms}uniform samplerCube shadowMap{i};
+                uniform vec2 shadowNearFar{i};
+                uniform vec4 lightParameters{i}[3];
+
+                vec3 calculate_lighting{i}(vec3 surface_color, vec3 position, vec3 normal, vec3 view_direction, float metallic, float roughness, float occlusion)
+                {{
+                    vec3 color{i} = lightParameters{i}[0].xyz;
+                    vec3 attenuation{i} = lightParameters{i}[1].xyz;
+                    vec3 position{i} = lightParameters{i}[2].xyz;
+                    vec3 light_direction = position{i} - position;
+                    float distance = length(light_direction);
+                    light_direction = light_direction / distance;
+
+                    vec3 light_color = attenuate(color{i}, attenuation{i}, distance);{ramp}
+                    light_color *= calculate_cube_shadow(light_direction, normal, shadowMap{i}, shadowNearFar{i}, position - position{i});
+                    return calculate_light(light_color, light_direction, surface_color, view_direction, normal, metallic, roughness);
+                }}
+
+            ")
+        } else {
+            format!(
+            "
+                {ramp_uniforms}uniform vec4 lightParameters{i}[3];
+
+                vec3 calculate_lighting{i}(vec3 surface_color, vec3 position, vec3 normal, vec3 view_direction, float metallic, float roughness, float occlusion)
+                {{
+                    vec3 color{i} = lightParameters{i}[0].xyz;
+                    vec3 attenuation{i} = lightParameters{i}[1].xyz;
+                    vec3 position{i} = lightParameters{i}[2].xyz;
+                    vec3 light_direction = position{i} - position;
+                    float distance = length(light_direction);
+                    light_direction = light_direction / distance;
+
+                    vec3 light_color = attenuate(color{i}, attenuation{i}, distance);{ramp}
+                    return calculate_light(light_color, light_direction, surface_color, view_direction, normal, metallic, roughness);
+                }}
+
+            ")
+        }
     }
     fn use_uniforms(&self, program: &Program, i: u32) {
-        program.use_uniform(
-            &format!("color{}", i),
-            self.color.to_linear_srgb().truncate() * self.intensity,
-        );
-        program.use_uniform(
-            &format!("attenuation{}", i),
-            vec3(
-                self.attenuation.constant,
-                self.attenuation.linear,
-                self.attenuation.quadratic,
-            ),
+        program.use_uniform_array(
+            &format!("lightParameters{}", i),
+            &[
+                (self.color.to_linear_srgb().truncate() * self.intensity).extend(0.0),
+                vec4(
+                    self.attenuation.constant,
+                    self.attenuation.linear,
+                    self.attenuation.quadratic,
+                    0.0,
+                ),
+                self.position.extend(0.0),
+            ],
         );
-        program.use_uniform(&format!("position{}", i), self.position);
+        if let Some(ref tex) = self.shadow_texture {
+            program.use_depth_texture_cube(&format!("shadowMap{}", i), tex);
+            program.use_uniform(&format!("shadowNearFar{}", i), self.shadow_near_far);
+        }
+        if let Some(ref ramp) = self.color_ramp {
+            ramp.use_uniforms(program, i);
+        }
     }
 
     fn id(&self) -> LightId {
-        LightId::PointLight
+        LightId::PointLight(self.shadow_texture.is_some(), self.color_ramp.is_some())
     }
 }
@@ -0,0 +1,137 @@
+#include <three-d/lighting>
+
+// Lookup tables for the linearly transformed cosines (LTC) approximating the GGX BRDF, indexed by roughness and sqrt(1 - NdV)
+// The matrix table contains the four non-trivial elements of the inverse transformation and the magnitude table the
+// integral of the BRDF with a Fresnel factor of one (x) and the Schlick Fresnel term (y)
+uniform sampler2D ltcMatrixTexture;
+uniform sampler2D ltcMagnitudeTexture;
+
+const float LTC_LUT_SIZE = 64.0;
+
+// Integrates the cosine distribution over the edge between two points on the unit sphere.
+// The fitted rational approximation of theta / sin(theta) includes the normalization with 1 / (2 * PI).
+vec3 ltc_integrate_edge(vec3 v1, vec3 v2)
+{
+    float x = dot(v1, v2);
+    float y = abs(x);
+    float a = 0.8543985 + (0.4965155 + 0.0145206 * y) * y;
+    float b = 3.4175940 + (4.1616724 + y) * y;
+    float v = a / b;
+    float theta_sintheta = x > 0.0 ? v : 0.5 * inversesqrt(max(1.0 - x * x, 1e-7)) - v;
+    return cross(v1, v2) * theta_sintheta;
+}
+
+// Integrates the cosine distribution transformed by the inverse matrix Minv over the rectangle with the given corners
+// as seen from the position P with normal N and view direction V.
+// The integral is zero when the rectangle is behind the surface or when the back of the rectangle is facing the surface.
+float ltc_evaluate(vec3 N, vec3 V, vec3 P, mat3 Minv, vec3 corners[4])
+{
+    // Orthonormal basis around the normal with the view direction in the tangent plane
+    vec3 T1 = V - N * dot(V, N);
+    if(dot(T1, T1) < 1e-8) {
+        T1 = abs(N.z) < 0.999 ? cross(vec3(0.0, 0.0, 1.0), N) : vec3(1.0, 0.0, 0.0);
+    }
+    T1 = normalize(T1);
+    vec3 T2 = cross(N, T1);
+    Minv = Minv * transpose(mat3(T1, T2, N));
+
+    // Transform the rectangle and clip it to the upper hemisphere, which results in up to five corners
+    vec3 L[5];
+    int n = 0;
+    for(int i = 0; i < 4; i++) {
+        vec3 a = Minv * (corners[i] - P);
+        vec3 b = Minv * (corners[(i + 1) % 4] - P);
+        if(a.z > 0.0) {
+            L[n] = normalize(a);
+            n++;
+        }
+        if((a.z > 0.0) != (b.z > 0.0)) {
+            L[n] = normalize(mix(a, b, a.z / (a.z - b.z)));
+            n++;
+        }
+    }
+    if(n < 3) {
+        return 0.0;
+    }
+
+    float sum = 0.0;
+    for(int i = 0; i < n; i++) {
+        sum += ltc_integrate_edge(L[i], L[(i + 1) % n]).z;
+    }
+    return max(0.0, sum);
+}
+
+// Calculates the light reflected towards the view direction V from a rectangular light with the given center, half width vector and half height vector,
+// which emits light with the given color in the direction of cross(light_up, light_right).
+vec3 calculate_area_light(vec3 light_color, vec3 light_center, vec3 light_right, vec3 light_up, vec3 surface_color, vec3 position, vec3 V, vec3 N, float metallic, float roughness)
+{
+    vec3 corners[4] = vec3[](
+        light_center - light_right - light_up,
+        light_center + light_right - light_up,
+        light_center + light_right + light_up,
+        light_center - light_right + light_up
+    );
+    float NdV = max(0.001, dot(N, V));
+    vec3 F0 = mix(vec3(0.04), surface_color, metallic);
+
+    // The integral of the clamped cosine over the rectangle divided by PI
+    float form_factor = ltc_evaluate(N, V, position, mat3(1.0), corners);
+
+    vec3 specular;
+    vec3 specular_fresnel;
+    if(lightingModel == 5u || lightingModel == 8u) {
+        // GGX specular reflectance using the linearly transformed cosines
+        vec2 uv = vec2(roughness, sqrt(1.0 - NdV)) * (LTC_LUT_SIZE - 1.0) / LTC_LUT_SIZE + 0.5 / LTC_LUT_SIZE;
+        vec4 t1 = texture(ltcMatrixTexture, uv);
+        vec2 t2 = texture(ltcMagnitudeTexture, uv).xy;
+        mat3 Minv = mat3(
+            vec3(t1.x, 0.0, t1.y),
+            vec3(0.0, 1.0, 0.0),
+            vec3(t1.z, 0.0, t1.w)
+        );
+        specular = (F0 * t2.x + (1.0 - F0) * t2.y) * ltc_evaluate(N, V, position, Minv, corners);
+        specular_fresnel = fresnel_schlick_roughness(F0, NdV, roughness);
+    }
+    else {
+        // Specular reflectance of the remaining lighting models evaluated at the point on the rectangle closest to the reflection ray
+        vec3 R = reflect(-V, N);
+        vec3 light_normal = normalize(cross(light_up, light_right));
+        float RdN = dot(R, light_normal);
+        vec3 hit = light_center;
+        if(abs(RdN) > 0.0001) {
+            float t = dot(light_center - position, light_normal) / RdN;
+            if(t > 0.0) {
+                hit = position + t * R;
+            }
+        }
+        vec3 d = hit - light_center;
+        vec3 closest = light_center
+            + clamp(dot(d, light_right) / dot(light_right, light_right), -1.0, 1.0) * light_right
+            + clamp(dot(d, light_up) / dot(light_up, light_up), -1.0, 1.0) * light_up;
+        vec3 L = normalize(closest - position);
+
+        float NdL = max(0.001, dot(N, L));
+        if(lightingModel == 1u) {
+            specular_fresnel = fresnel_schlick_roughness(F0, NdV, roughness);
+            specular = phong_specular(V, L, N, specular_fresnel, roughness);
+        }
+        else {
+            vec3 H = normalize(L + V);
+            float NdH = max(0.001, dot(N, H));
+            float HdV = max(0.001, dot(H, V));
+            specular_fresnel = fresnel_schlick_roughness(F0, HdV, roughness);
+            if(lightingModel == 2u) {
+                specular = blinn_specular(NdH, specular_fresnel, roughness);
+            }
+            else {
+                specular = cooktorrance_specular(NdL, NdV, NdH, specular_fresnel, roughness);
+            }
+        }
+        // Scale by the cosine weighted solid angle of the rectangle instead of the cosine of a single direction
+        specular *= PI * form_factor;
+    }
+
+    // The 1 / PI of the Lambertian BRDF cancels out with the PI of the form factor
+    vec3 diffuse = (1.0 - specular_fresnel) * mix(surface_color, vec3(0.0), metallic) * diffuse_factor(normalize(light_center - position), V, N, roughness);
+    return (diffuse * form_factor + specular) * light_color;
+}
@@ -1,3 +1,5 @@
+#include <three-d/lighting>
+
 in vec2 uvs;
 
 out vec2 FragColor;
@@ -31,7 +33,7 @@ vec2 IntegrateBRDF(float NdotV, float roughness)
             float VdH = max(dot(V, H), 0.0);
             float NdV = max(dot(N, V), 0.0);
 
-            float G = G_schlick(roughness, NdV, NdL);
+            float G = calculate_G(roughness, NdV, NdL);
             float G_Vis = (G * VdH) / (NdH * NdV);
             float Fc = pow(1.0 - VdH, 5.0);
 
@@ -0,0 +1,44 @@
+uniform float time;
+
+in vec2 uvs;
+
+layout (location = 0) out vec4 outColor;
+
+vec2 hash(vec2 cell)
+{
+    return fract(sin(vec2(dot(cell, vec2(127.1, 311.7)), dot(cell, vec2(269.5, 183.3)))) * 43758.5453);
+}
+
+// Returns the difference between the distance to the second closest and the closest cell point
+// of an animated Voronoi pattern which tiles the unit square with the given number of cells in each direction.
+float voronoi_edge(vec2 uv, float cells, float t)
+{
+    vec2 p = uv * cells;
+    vec2 cell = floor(p);
+    float f1 = 10.0;
+    float f2 = 10.0;
+    for (int y = -1; y <= 1; y++) {
+        for (int x = -1; x <= 1; x++) {
+            vec2 neighbour = cell + vec2(float(x), float(y));
+            // Wrap the cell index so the pattern tiles
+            vec2 h = hash(mod(neighbour, cells));
+            vec2 point = neighbour + 0.5 + 0.4 * sin(t + 6.2831 * h);
+            float d = length(point - p);
+            if (d < f1) {
+                f2 = f1;
+                f1 = d;
+            } else if (d < f2) {
+                f2 = d;
+            }
+        }
+    }
+    return f2 - f1;
+}
+
+void main()
+{
+    float c0 = 1.0 - smoothstep(0.0, 0.2, voronoi_edge(uvs, 5.0, time));
+    float c1 = 1.0 - smoothstep(0.0, 0.25, voronoi_edge(uvs + vec2(0.37, 0.61), 8.0, 1.3 * time + 2.0));
+    float caustics = pow(c0, 3.0) + 0.5 * pow(c1, 3.0);
+    outColor = vec4(clamp(caustics, 0.0, 1.0), 0.0, 0.0, 1.0);
+}
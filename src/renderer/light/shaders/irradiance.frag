@@ -1,3 +1,5 @@
+#include <three-d/shared>
+
 
 
 uniform samplerCube environmentMap;
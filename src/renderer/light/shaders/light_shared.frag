@@ -1,4 +1,41 @@
+#include <three-d/shared>
+
 uniform uint lightingModel;
+uniform uint diffuseModel;
+
+#ifdef USE_SUBSURFACE
+// Set by the material before the lighting is calculated
+// How far the diffuse lighting wraps around the shadow terminator for each color channel
+vec3 subsurface_wrap;
+// The amount of light transmitted through the object for each color channel
+vec3 subsurface_transmittance;
+#endif
+
+#ifdef USE_CLEARCOAT
+// Set by the material before the lighting is calculated
+// The strength, roughness and normal of the clear coat on top of the base layer
+float clearcoat_factor;
+float clearcoat_roughness;
+vec3 clearcoat_normal;
+#endif
+
+#ifdef USE_ANISOTROPY
+// Set by the material before the lighting is calculated
+// The anisotropy in the range [-1..1] which stretches the specular highlight along the tangent when positive and along the bitangent when negative
+float anisotropy_factor;
+// The tangent frame which is orthogonal to the shading normal and rotated by the anisotropy rotation
+vec3 anisotropy_tangent;
+vec3 anisotropy_bitangent;
+#endif
+
+#ifdef USE_TOON
+// Set by the material before the lighting is calculated
+// The exponent of the specular highlight
+float toon_shininess;
+// Set by each light before the shadow is applied, so the material can find the visibility of the light
+vec3 toon_irradiance;
+float toon_specular;
+#endif
 
 struct BaseLight
 {
@@ -49,11 +86,11 @@ float D_GGX(in float roughness, in float NdH)
 
 float calculate_D(float roughness, float NdH) {
     float D = 0.0;
-    if(lightingModel == 3u) {
+    if(lightingModel == 3u || lightingModel == 6u) {
         D = D_blinn(roughness, NdH);
-    } else if(lightingModel == 4u) {
+    } else if(lightingModel == 4u || lightingModel == 7u) {
         D = D_beckmann(roughness, NdH);
-    } else if(lightingModel == 5u) {
+    } else if(lightingModel == 5u || lightingModel == 8u) {
         D = D_GGX(roughness, NdH);
     }
     return D;
@@ -69,6 +106,40 @@ float G_schlick(in float roughness, in float NdV, in float NdL)
     return NdV * NdL / (V * L);
 }
 
+// Height-correlated Smith GGX geometry function, see "Understanding the Masking-Shadowing Function in Microfacet-Based BRDFs" by Eric Heitz
+float G_smith_height_correlated(in float roughness, in float NdV, in float NdL)
+{
+    float alpha = roughness * roughness;
+    float alpha2 = alpha * alpha;
+    float lambda_V = NdL * sqrt(NdV * NdV * (1.0 - alpha2) + alpha2);
+    float lambda_L = NdV * sqrt(NdL * NdL * (1.0 - alpha2) + alpha2);
+    return 2.0 * NdV * NdL / (lambda_V + lambda_L);
+}
+
+float calculate_G(float roughness, float NdV, float NdL) {
+    if(lightingModel > 5u) {
+        return G_smith_height_correlated(roughness, NdV, NdL);
+    }
+    return G_schlick(roughness, NdV, NdL);
+}
+
+// The Oren-Nayar diffuse reflectance relative to the Lambertian reflectance, using the roughness as the standard deviation of the microfacet angles in radians,
+// see "A tiny improvement of Oren-Nayar reflectance model" by Yasuhiro Fujii
+float diffuse_factor(in vec3 L, in vec3 V, in vec3 N, in float roughness)
+{
+    if(diffuseModel == 0u) {
+        return 1.0;
+    }
+    float sigma2 = roughness * roughness;
+    float A = 1.0 - 0.5 * sigma2 / (sigma2 + 0.33);
+    float B = 0.45 * sigma2 / (sigma2 + 0.09);
+    float NdL = saturate(dot(N, L));
+    float NdV = saturate(dot(N, V));
+    float s = dot(L, V) - NdL * NdV;
+    float t = s > 0.0 ? max(max(NdL, NdV), 0.001) : 1.0;
+    return A + B * s / t;
+}
+
 // simple phong specular calculation with normalization
 vec3 phong_specular(in vec3 V, in vec3 L, in vec3 N, in vec3 specular_fresnel, in float roughness)
 {
@@ -92,12 +163,84 @@ vec3 blinn_specular(in float NdH, in vec3 specular_fresnel, in float roughness)
 vec3 cooktorrance_specular(in float NdL, in float NdV, in float NdH, in vec3 specular_fresnel, in float roughness)
 {
     float D = calculate_D(roughness, NdH);
-    float G = G_schlick(roughness, NdV, NdL);
+    float G = calculate_G(roughness, NdV, NdL);
     return specular_fresnel * G * D / (4.0 * NdV * NdL);
 }
 
-vec3 calculate_light(vec3 light_color, vec3 L, vec3 surface_color, vec3 V, vec3 N, float metallic, float roughness)
+#ifdef USE_ANISOTROPY
+// Anisotropic Trowbridge-Reitz GGX normal distribution function and height-correlated Smith visibility function, which includes the 1 / (4 * NdV * NdL) term,
+// see "Physically-Based Shading at Disney" by Brent Burley and "Understanding the Masking-Shadowing Function in Microfacet-Based BRDFs" by Eric Heitz
+vec3 anisotropic_specular(in vec3 L, in vec3 V, in vec3 N, in vec3 H, in vec3 specular_fresnel, in float roughness)
+{
+    vec3 T = anisotropy_tangent;
+    vec3 B = anisotropy_bitangent;
+    float alpha = roughness * roughness;
+    float at = max(alpha * (1.0 + anisotropy_factor), 0.001);
+    float ab = max(alpha * (1.0 - anisotropy_factor), 0.001);
+    float NdL = max(0.001, dot(N, L));
+    float NdV = max(0.001, dot(N, V));
+    float NdH = max(0.001, dot(N, H));
+
+    float a2 = at * ab;
+    vec3 d = vec3(ab * dot(T, H), at * dot(B, H), a2 * NdH);
+    float b2 = a2 / dot(d, d);
+    float D = a2 * b2 * b2 / PI;
+
+    float lambda_V = NdL * length(vec3(at * dot(T, V), ab * dot(B, V), NdV));
+    float lambda_L = NdV * length(vec3(at * dot(T, L), ab * dot(B, L), NdL));
+    float visibility = 0.5 / (lambda_V + lambda_L);
+    return specular_fresnel * D * visibility;
+}
+
+// The normal used for looking up the prefiltered environment, which is bent towards the direction of the stretched highlight,
+// see "Rendering the World of Far Cry 4" by Stephen McAuley
+vec3 anisotropic_bent_normal(in vec3 V, in vec3 N, in float roughness)
+{
+    vec3 direction = anisotropy_factor >= 0.0 ? anisotropy_bitangent : anisotropy_tangent;
+    vec3 anisotropic_normal = cross(cross(direction, V), direction);
+    float bend = abs(anisotropy_factor) * saturate(5.0 * roughness);
+    return normalize(mix(N, anisotropic_normal, bend));
+}
+#endif
+
+#ifdef USE_CLEARCOAT
+// The fraction of the light reflected by the clear coat when seen from the given direction, which is the light that does not reach the base layer
+float clearcoat_fresnel(vec3 V)
 {
+    return clearcoat_factor * fresnel_schlick(vec3(0.04), max(0.001, dot(clearcoat_normal, V))).x;
+}
+
+// Adds the specular reflection of the clear coat, which is a dielectric layer evaluated with a GGX lobe, to the light reflected by the base layer,
+// which is dimmed by the light reflected by the coat, see the KHR_materials_clearcoat glTF extension
+vec3 apply_clearcoat(vec3 base, vec3 light_color, vec3 L, vec3 V)
+{
+    vec3 N = clearcoat_normal;
+    float NdL = dot(N, L);
+    float fresnel = clearcoat_fresnel(V);
+    if(NdL <= 0.0) {
+        return base * (1.0 - fresnel);
+    }
+    float NdV = max(0.001, dot(N, V));
+    float NdH = max(0.001, dot(N, normalize(L + V)));
+    float roughness = max(clearcoat_roughness, 0.05);
+    float specular = D_GGX(roughness, NdH) * G_smith_height_correlated(roughness, NdV, NdL) / (4.0 * NdV * NdL);
+    return base * (1.0 - fresnel) + fresnel * specular * NdL * light_color;
+}
+#endif
+
+// The light is a disk with the given angular radius in radians seen from the surface, for example the sun, where zero is an ideal direction.
+// The size only affects the specular highlight, which is evaluated at the direction within the disk closest to the reflection direction
+// and normalized to approximately conserve energy, see "Real Shading in Unreal Engine 4" by Brian Karis.
+vec3 calculate_light_with_angular_radius(vec3 light_color, vec3 L, vec3 surface_color, vec3 V, vec3 N, float metallic, float roughness, float angular_radius)
+{
+#ifdef USE_TOON
+    // The toon material quantizes the light itself, so the irradiance is returned instead of the reflected light
+    float toon_NdL = saturate(dot(N, L));
+    toon_irradiance = light_color * toon_NdL;
+    toon_specular = toon_NdL > 0.0 ? pow(saturate(dot(N, normalize(L + V))), toon_shininess) : 0.0;
+    return toon_irradiance;
+#endif
+
     // compute material reflectance
     float NdL = max(0.001, dot(N, L));
     float NdV = max(0.001, dot(N, V));
@@ -106,15 +249,30 @@ vec3 calculate_light(vec3 light_color, vec3 L, vec3 surface_color, vec3 V, vec3
     // constant base specular factor of 0.04 grey is used
     vec3 F0 = mix(vec3(0.04), surface_color, metallic);
 
+    vec3 specular_L = L;
+    float specular_normalization = 1.0;
+    if(angular_radius > 0.0) {
+        vec3 R = reflect(-V, N);
+        float RdL = dot(R, L);
+        if(RdL >= cos(angular_radius)) {
+            specular_L = R;
+        } else if(RdL > -0.9999) {
+            specular_L = normalize(cos(angular_radius) * L + sin(angular_radius) * normalize(R - RdL * L));
+        }
+        float alpha = max(roughness * roughness, 0.001);
+        float widened_alpha = saturate(alpha + 0.5 * tan(angular_radius));
+        specular_normalization = (alpha / widened_alpha) * (alpha / widened_alpha);
+    }
+
     vec3 specular;
     vec3 specular_fresnel;
     if(lightingModel == 1u) {
         // specular reflectance with PHONG
         specular_fresnel = fresnel_schlick_roughness(F0, NdV, roughness);
-        specular = phong_specular(V, L, N, specular_fresnel, roughness);
+        specular = phong_specular(V, specular_L, N, specular_fresnel, roughness);
     }
     else {
-        vec3 H = normalize(L + V);
+        vec3 H = normalize(specular_L + V);
         float NdH = max(0.001, dot(N, H));
         float HdV = max(0.001, dot(H, V));
         specular_fresnel = fresnel_schlick_roughness(F0, HdV, roughness);
@@ -125,16 +283,43 @@ vec3 calculate_light(vec3 light_color, vec3 L, vec3 surface_color, vec3 V, vec3
         }
         else if(lightingModel > 2u) {
             // specular reflectance with COOK-TORRANCE
-            specular = cooktorrance_specular(NdL, NdV, NdH, specular_fresnel, roughness);
+#ifdef USE_ANISOTROPY
+            specular = anisotropic_specular(specular_L, V, N, H, specular_fresnel, roughness);
+#else
+            specular = cooktorrance_specular(max(0.001, dot(N, specular_L)), NdV, NdH, specular_fresnel, roughness);
+#endif
         }
     }
+    specular *= specular_normalization;
+    if(angular_radius > 0.0 && lightingModel != 1u) {
+        // The diffuse fresnel is evaluated for the center of the light
+        specular_fresnel = fresnel_schlick_roughness(F0, max(0.001, dot(normalize(L + V), V)), roughness);
+    }
 
     // diffuse is common for any model
     vec3 diffuse_fresnel = 1.0 - specular_fresnel;
-    vec3 diffuse = diffuse_fresnel * mix(surface_color, vec3(0.0), metallic) / PI;
+    vec3 diffuse = diffuse_fresnel * mix(surface_color, vec3(0.0), metallic) * diffuse_factor(L, V, N, roughness) / PI;
 
+#ifdef USE_SUBSURFACE
+    // Light scattered beneath the surface wraps the diffuse lighting around the shadow terminator (normalized to conserve energy)
+    // and light hitting the back of the object is transmitted through it
+    vec3 wrapped = clamp((dot(N, L) + subsurface_wrap) / (1.0 + subsurface_wrap), 0.0, 1.0) / (1.0 + subsurface_wrap);
+    float back_lighting = pow(saturate(dot(V, -normalize(L + 0.3 * N))), 4.0);
+    vec3 transmission = subsurface_transmittance * back_lighting * mix(surface_color, vec3(0.0), metallic) / PI;
+    vec3 result = (diffuse * wrapped + specular * NdL + transmission) * light_color;
+#else
     // final result
-    return (diffuse + specular) * light_color * NdL;
+    vec3 result = (diffuse + specular) * light_color * NdL;
+#endif
+#ifdef USE_CLEARCOAT
+    result = apply_clearcoat(result, light_color, L, V);
+#endif
+    return result;
+}
+
+vec3 calculate_light(vec3 light_color, vec3 L, vec3 surface_color, vec3 V, vec3 N, float metallic, float roughness)
+{
+    return calculate_light_with_angular_radius(light_color, L, surface_color, V, N, metallic, roughness, 0.0);
 }
 
 vec3 attenuate(vec3 light_color, vec3 attenuation, float distance)
@@ -146,37 +331,189 @@ vec3 attenuate(vec3 light_color, vec3 attenuation, float distance)
     return light_color / max(1.0, att);
 }
 
-float is_visible(vec3 lightDirection, vec3 normal, sampler2D shadowMap, vec4 shadow_coord, vec2 offset)
+vec3 sample_color_ramp(sampler2D ramp, float max_distance, float distance)
+{
+    // The first and last texel centers correspond to zero and the maximum distance
+    float size = float(textureSize(ramp, 0).x);
+    float u = clamp(distance / max_distance, 0.0, 1.0);
+    return texture(ramp, vec2((0.5 + u * (size - 1.0)) / size, 0.5)).rgb;
+}
+
+float is_visible_in_tile_with_bias(vec3 lightDirection, vec3 normal, sampler2D shadowMap, vec4 tile, vec4 shadow_coord, vec2 offset, float extra_bias)
 {
     vec2 uv = (shadow_coord.xy + offset)/shadow_coord.w;
     if(uv.x < 0.0 || uv.x > 1.0 || uv.y < 0.0 || uv.y > 1.0) {
         return 1.0;
     }
-    float shadow_cast_distance = texture(shadowMap, uv).x;
+    float shadow_cast_distance = texture(shadowMap, tile.xy + uv * tile.zw).x;
     if(shadow_cast_distance > 0.999) {
         return 1.0;
     }
     // Adjust shadow bias based on surface normal and light direction
-    float bias = max(0.05 * (1.0 - dot(normal, lightDirection)), 0.005);
+    float bias = max(0.05 * (1.0 - dot(normal, lightDirection)), 0.005) + extra_bias;
     float true_distance = (shadow_coord.z - bias)/shadow_coord.w;
     return shadow_cast_distance > true_distance ? 1.0 : 0.0;
 }
 
+float is_visible_in_tile(vec3 lightDirection, vec3 normal, sampler2D shadowMap, vec4 tile, vec4 shadow_coord, vec2 offset)
+{
+    return is_visible_in_tile_with_bias(lightDirection, normal, shadowMap, tile, shadow_coord, offset, 0.0);
+}
+
+float is_visible(vec3 lightDirection, vec3 normal, sampler2D shadowMap, vec4 shadow_coord, vec2 offset)
+{
+    return is_visible_in_tile(lightDirection, normal, shadowMap, vec4(0.0, 0.0, 1.0, 1.0), shadow_coord, offset);
+}
+
+// The tile is the offset (xy) and size (zw) in uv coordinates of the part of the shadow map, for example a tile in a shadow atlas, that contains the shadow
+// The kernel is the percentage closer filtering kernel, 0 is a single sample, 1 is four samples in a Poisson disk and 2 is a 4x4 grid of samples one texel apart
+float calculate_shadow_in_tile_with_kernel(vec3 lightDirection, vec3 normal, sampler2D shadowMap, mat4 shadowMVP, vec4 tile, vec3 position, int kernel)
+{
+    vec4 shadow_coord = shadowMVP * vec4(position, 1.);
+    if (kernel == 0) {
+        return is_visible_in_tile(lightDirection, normal, shadowMap, tile, shadow_coord, vec2(0.0));
+    }
+    float visibility = 0.0;
+    if (kernel == 1) {
+        vec2 poissonDisk[4] = vec2[](
+                                     vec2( -0.94201624, -0.39906216 ),
+                                     vec2( 0.94558609, -0.76890725 ),
+                                     vec2( -0.094184101, -0.92938870 ),
+                                     vec2( 0.34495938, 0.29387760 )
+                                     );
+        for (int i=0;i<4;i++)
+        {
+            visibility += is_visible_in_tile(lightDirection, normal, shadowMap, tile, shadow_coord, poissonDisk[i] * 0.001f);
+        }
+        return visibility * 0.25;
+    }
+    // The offset is added before the perspective division, so it is scaled by w to be one texel in the tile
+    vec2 texel_size = shadow_coord.w / (vec2(textureSize(shadowMap, 0)) * tile.zw);
+    for (int y=0;y<4;y++)
+    {
+        for (int x=0;x<4;x++)
+        {
+            visibility += is_visible_in_tile(lightDirection, normal, shadowMap, tile, shadow_coord, (vec2(x, y) - 1.5) * texel_size);
+        }
+    }
+    return visibility * 0.0625;
+}
+
+float calculate_shadow_in_tile(vec3 lightDirection, vec3 normal, sampler2D shadowMap, mat4 shadowMVP, vec4 tile, vec3 position)
+{
+    return calculate_shadow_in_tile_with_kernel(lightDirection, normal, shadowMap, shadowMVP, tile, position, 1);
+}
+
+float calculate_shadow_with_kernel(vec3 lightDirection, vec3 normal, sampler2D shadowMap, mat4 shadowMVP, vec3 position, int kernel)
+{
+    return calculate_shadow_in_tile_with_kernel(lightDirection, normal, shadowMap, shadowMVP, vec4(0.0, 0.0, 1.0, 1.0), position, kernel);
+}
+
 float calculate_shadow(vec3 lightDirection, vec3 normal, sampler2D shadowMap, mat4 shadowMVP, vec3 position)
+{
+    return calculate_shadow_with_kernel(lightDirection, normal, shadowMap, shadowMVP, position, 1);
+}
+
+// Percentage closer filtering with the given number of samples, at most 64, spread evenly in a disk with the given radius in texels (a Vogel disk).
+// The bias is added to the depth bias which is computed from the angle between the surface normal and the light direction.
+float calculate_soft_shadow_in_tile(vec3 lightDirection, vec3 normal, sampler2D shadowMap, mat4 shadowMVP, vec4 tile, vec3 position, float radius, int samples, float bias)
 {
     vec4 shadow_coord = shadowMVP * vec4(position, 1.);
+    vec2 texel_size = shadow_coord.w / (vec2(textureSize(shadowMap, 0)) * tile.zw);
+    int sample_count = clamp(samples, 1, 64);
     float visibility = 0.0;
+    for (int i = 0; i < 64; i++)
+    {
+        if (i >= sample_count) {
+            break;
+        }
+        float r = radius * sqrt((float(i) + 0.5) / float(sample_count));
+        float theta = float(i) * 2.39996323; // The golden angle
+        visibility += is_visible_in_tile_with_bias(lightDirection, normal, shadowMap, tile, shadow_coord, r * vec2(cos(theta), sin(theta)) * texel_size, bias);
+    }
+    return visibility / float(sample_count);
+}
+
+float calculate_soft_shadow(vec3 lightDirection, vec3 normal, sampler2D shadowMap, mat4 shadowMVP, vec3 position, float radius, int samples, float bias)
+{
+    return calculate_soft_shadow_in_tile(lightDirection, normal, shadowMap, shadowMVP, vec4(0.0, 0.0, 1.0, 1.0), position, radius, samples, bias);
+}
+
+float is_visible_in_layer(vec3 lightDirection, vec3 normal, sampler2DArray shadowMaps, float layer, vec4 shadow_coord, vec2 offset, float extra_bias)
+{
+    vec2 uv = (shadow_coord.xy + offset)/shadow_coord.w;
+    if(uv.x < 0.0 || uv.x > 1.0 || uv.y < 0.0 || uv.y > 1.0) {
+        return 1.0;
+    }
+    float shadow_cast_distance = texture(shadowMaps, vec3(uv, layer)).x;
+    if(shadow_cast_distance > 0.999) {
+        return 1.0;
+    }
+    float bias = max(0.05 * (1.0 - dot(normal, lightDirection)), 0.005) + extra_bias;
+    float true_distance = (shadow_coord.z - bias)/shadow_coord.w;
+    return shadow_cast_distance > true_distance ? 1.0 : 0.0;
+}
+
+// The shadow in one layer of a shadow map array, filtered using the given kernel (see calculate_shadow_in_tile_with_kernel)
+// or, if the radius is larger than zero, using the given number of samples in a disk (see calculate_soft_shadow_in_tile)
+float calculate_shadow_in_layer(vec3 lightDirection, vec3 normal, sampler2DArray shadowMaps, mat4 shadowMVP, int layer, vec3 position, int kernel, float radius, int samples, float bias)
+{
+    vec4 shadow_coord = shadowMVP * vec4(position, 1.);
+    vec2 texel_size = shadow_coord.w / vec2(textureSize(shadowMaps, 0).xy);
     vec2 poissonDisk[4] = vec2[](
-                                 vec2( -0.94201624, -0.39906216 ),
-                                 vec2( 0.94558609, -0.76890725 ),
-                                 vec2( -0.094184101, -0.92938870 ),
-                                 vec2( 0.34495938, 0.29387760 )
-                                 );
-    for (int i=0;i<4;i++)
+        vec2( -0.94201624, -0.39906216 ),
+        vec2( 0.94558609, -0.76890725 ),
+        vec2( -0.094184101, -0.92938870 ),
+        vec2( 0.34495938, 0.29387760 )
+    );
+    int sample_count = radius > 0.0 ? clamp(samples, 1, 64) : (kernel == 0 ? 1 : (kernel == 1 ? 4 : 16));
+    float visibility = 0.0;
+    for (int i = 0; i < 64; i++)
     {
-        visibility += is_visible(lightDirection, normal, shadowMap, shadow_coord, poissonDisk[i] * 0.001f);
+        if (i >= sample_count) {
+            break;
+        }
+        vec2 offset = vec2(0.0);
+        if (radius > 0.0) {
+            float r = radius * sqrt((float(i) + 0.5) / float(sample_count));
+            float theta = float(i) * 2.39996323; // The golden angle
+            offset = r * vec2(cos(theta), sin(theta)) * texel_size;
+        } else if (kernel == 1) {
+            offset = poissonDisk[i] * 0.001;
+        } else if (kernel == 2) {
+            offset = (vec2(i % 4, i / 4) - 1.5) * texel_size;
+        }
+        visibility += is_visible_in_layer(lightDirection, normal, shadowMaps, float(layer), shadow_coord, offset, bias);
     }
-    return visibility * 0.25;
+    return visibility / float(sample_count);
+}
+
+// The shadow from cascaded shadow maps, where the cascade is chosen from the given depth of the fragment in view space of the camera.
+// The splits are the far distances of the cascades, and the shadow is blended with the next cascade in the last tenth of a cascade to hide the seams.
+// Fragments beyond the last cascade are not shadowed.
+float calculate_cascaded_shadow(vec3 lightDirection, vec3 normal, sampler2DArray shadowMaps, mat4 shadowMVPs[4], vec4 splits, int cascade_count, float depth, vec3 position, int kernel, float radius, int samples, float bias)
+{
+    float start = 0.0;
+    for (int cascade = 0; cascade < 4; cascade++)
+    {
+        if (cascade >= cascade_count) {
+            break;
+        }
+        float end = splits[cascade];
+        if (depth < end) {
+            float visibility = calculate_shadow_in_layer(lightDirection, normal, shadowMaps, shadowMVPs[cascade], cascade, position, kernel, radius, samples, bias);
+            float band = 0.1 * (end - start);
+            if (depth > end - band) {
+                float next_visibility = cascade + 1 < cascade_count
+                    ? calculate_shadow_in_layer(lightDirection, normal, shadowMaps, shadowMVPs[cascade + 1], cascade + 1, position, kernel, radius, samples, bias)
+                    : 1.0;
+                visibility = mix(visibility, next_visibility, (depth - end + band) / band);
+            }
+            return visibility;
+        }
+        start = end;
+    }
+    return 1.0;
 }
 
 vec3 ImportanceSampleGGX(vec2 Xi, vec3 N, float roughness)
@@ -201,3 +538,29 @@ vec3 ImportanceSampleGGX(vec2 Xi, vec3 N, float roughness)
 	vec3 sampleVec = tangent * H.x + bitangent * H.y + N * H.z;
 	return normalize(sampleVec);
 }
+
+// The shadow from a depth cube map rendered from the light position with the given near and far planes.
+// The stored depth is compared with the distance along the major axis of the vector from the light to the position, which is the depth of the position in the cube map side.
+// Positions beyond the far plane are not shadowed.
+float calculate_cube_shadow(vec3 lightDirection, vec3 normal, samplerCube shadowMap, vec2 nearFar, vec3 lightToPosition)
+{
+    vec3 a = abs(lightToPosition);
+    float depth = max(a.x, max(a.y, a.z));
+    if (depth > nearFar.y) {
+        return 1.0;
+    }
+    float n = nearFar.x;
+    float f = nearFar.y;
+    float texel_size = 2.0 * depth / float(textureSize(shadowMap, 0).x);
+    float bias = texel_size * (1.5 + 3.0 * (1.0 - clamp(dot(normal, lightDirection), 0.0, 1.0)));
+    float visibility = 0.0;
+    for (int i = 0; i < 8; i++)
+    {
+        // The corners of a cube around the position
+        vec3 offset = 2.0 * vec3(float(i & 1), float((i >> 1) & 1), float((i >> 2) & 1)) - 1.0;
+        float d = 2.0 * texture(shadowMap, lightToPosition + texel_size * offset).r - 1.0;
+        float shadow_depth = 2.0 * f * n / (f + n - d * (f - n));
+        visibility += depth - bias > shadow_depth ? 0.0 : 1.0;
+    }
+    return visibility * 0.125;
+}
@@ -1,3 +1,5 @@
+#include <three-d/lighting>
+
 out vec4 FragColor;
 in vec2 uvs;
 
@@ -6,6 +8,7 @@ uniform float roughness;
 uniform float resolution; // resolution of source cubemap (per face)
 uniform vec3 direction;
 uniform vec3 up;
+uniform int sampleCount;
 
 void main()
 {
@@ -16,7 +19,7 @@ void main()
     vec3 R = N;
     vec3 V = R;
 
-    const uint SAMPLE_COUNT = 1024u;
+    uint SAMPLE_COUNT = uint(sampleCount);
     vec3 prefilteredColor = vec3(0.0);
     float totalWeight = 0.0;
     
@@ -0,0 +1,238 @@
+use crate::core::*;
+use crate::renderer::light::*;
+use crate::renderer::*;
+use std::sync::{Arc, RwLock};
+
+///
+/// A shadow atlas is one large depth texture which is divided into square tiles, where each tile contains the shadow map of one [SpotLight].
+/// Compared to [SpotLight::generate_shadow_map], where each light has its own depth texture, all lights sample the same texture,
+/// so many shadow casting lights only use one texture unit, which is important on WebGL2 where the number of texture units is limited.
+///
+/// The size of the tile given to a light depends on how much of the screen is covered by the geometries the light shines onto,
+/// so lights close to the camera get more detailed shadows than lights far away or outside the view.
+/// When there is not enough space in the atlas, the least important lights get smaller tiles or no tile, in which case they are rendered without shadows.
+/// All shadow casting lights that are used together when rendering must get their tiles from the same atlas.
+///
+pub struct ShadowAtlas {
+    context: Context,
+    texture: Arc<RwLock<DepthTexture2D>>,
+    size: u32,
+    /// The size in texels of the tile given to a light which covers the entire screen. It is rounded down to a power of two and clamped to the size of the atlas.
+    pub max_tile_size: u32,
+    /// The size in texels of the smallest tile. It is rounded down to a power of two. Lights which do not get a tile of at least this size are rendered without shadows.
+    pub min_tile_size: u32,
+}
+
+impl ShadowAtlas {
+    ///
+    /// Creates a new shadow atlas with a depth texture of the given width and height, which is rounded up to a power of two.
+    ///
+    pub fn new(context: &Context, size: u32) -> Self {
+        let size = size.max(1).next_power_of_two();
+        Self {
+            context: context.clone(),
+            texture: Arc::new(RwLock::new(DepthTexture2D::new::<f32>(
+                context,
+                size,
+                size,
+                Wrapping::ClampToEdge,
+                Wrapping::ClampToEdge,
+            ))),
+            size,
+            max_tile_size: (size / 2).max(1),
+            min_tile_size: (size / 32).max(1),
+        }
+    }
+
+    ///
+    /// Returns the width and height of the atlas texture.
+    ///
+    pub fn size(&self) -> u32 {
+        self.size
+    }
+
+    ///
+    /// Returns the depth texture containing the shadow maps of all the lights with a tile in this atlas.
+    ///
+    pub fn texture(&self) -> std::sync::RwLockReadGuard<'_, DepthTexture2D> {
+        self.texture.read().unwrap()
+    }
+
+    ///
+    /// Divides the atlas into tiles for the given lights based on how much of the screen of the given viewer is covered by the geometries they shine onto,
+    /// and renders the shadows of the geometries into the tiles.
+    /// Lights that do not get a tile have their shadow cleared, see [SpotLight::clear_shadow_map], and lights that do get a tile replace any previous shadow map.
+    /// Call this whenever the lights, the geometries or the viewer changes, for example every frame.
+    ///
+    pub fn generate_shadow_maps(
+        &mut self,
+        viewer: impl Viewer,
+        lights: &mut [&mut SpotLight],
+        geometries: impl IntoIterator<Item = impl Geometry> + Clone,
+    ) -> Result<(), RendererError> {
+        trace_span!(
+            "three_d::generate_shadow_map",
+            light = "atlas",
+            size = self.size
+        );
+        let max_tile_size = previous_power_of_two(self.max_tile_size.clamp(1, self.size));
+        let min_tile_size = previous_power_of_two(self.min_tile_size.clamp(1, max_tile_size));
+
+        // Find the tile size each light would like based on its importance
+        let view_projection = viewer.projection() * viewer.view();
+        let mut requests = lights
+            .iter()
+            .enumerate()
+            .filter_map(|(index, light)| {
+                let camera = light.shadow_camera(Viewport::new_at_origo(1, 1), geometries.clone());
+                let frustum = camera.frustum();
+                let mut aabb = AxisAlignedBoundingBox::EMPTY;
+                for geometry in geometries.clone() {
+                    let geometry_aabb = geometry.aabb();
+                    if frustum.contains(geometry_aabb) {
+                        aabb.expand_with_aabb(geometry_aabb);
+                    }
+                }
+                let coverage = screen_coverage(view_projection, aabb);
+                (coverage > 0.0).then(|| {
+                    let size = (max_tile_size as f32 * coverage.sqrt()) as u32;
+                    (
+                        index,
+                        coverage,
+                        previous_power_of_two(size.clamp(min_tile_size, max_tile_size)),
+                    )
+                })
+            })
+            .collect::<Vec<_>>();
+        requests.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+        // Shrink the largest tiles until the tiles fit in the atlas or cannot be shrunk any further
+        let area = |requests: &[(usize, f32, u32)]| {
+            requests
+                .iter()
+                .map(|(_, _, size)| *size as u64 * *size as u64)
+                .sum::<u64>()
+        };
+        while area(&requests) > self.size as u64 * self.size as u64 {
+            let largest = requests.iter().map(|r| r.2).max().unwrap_or(min_tile_size);
+            if largest <= min_tile_size {
+                break;
+            }
+            for request in requests.iter_mut().filter(|r| r.2 == largest) {
+                request.2 /= 2;
+            }
+        }
+
+        // Allocate the tiles in order of importance, so the least important lights lose their tile if there is no space left
+        let mut tiles: Vec<Option<Viewport>> = vec![None; lights.len()];
+        let mut free = vec![Viewport::new_at_origo(self.size, self.size)];
+        for (index, _, size) in requests {
+            let mut size = size;
+            while tiles[index].is_none() && size >= min_tile_size {
+                tiles[index] = allocate_tile(&mut free, size);
+                size /= 2;
+            }
+        }
+
+        let depth_material = DepthMaterial {
+            render_states: RenderStates {
+                write_mask: WriteMask::DEPTH,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut texture = self.texture.write().unwrap();
+        let target = texture.as_depth_target();
+        target.clear(ClearState::default());
+        for (light, tile) in lights.iter_mut().zip(tiles) {
+            let Some(tile) = tile else {
+                light.clear_shadow_map();
+                continue;
+            };
+            let shadow_camera = light.shadow_camera(tile, geometries.clone());
+            let frustum = shadow_camera.frustum();
+            target.write_partially::<RendererError>(tile, || {
+                for geometry in geometries
+                    .clone()
+                    .into_iter()
+                    .filter(|g| frustum.contains(g.aabb()))
+                {
+                    render_shadow_caster(
+                        &self.context,
+                        &shadow_camera,
+                        &geometry,
+                        &depth_material,
+                    )?;
+                }
+                Ok(())
+            })?;
+            light.set_shadow_atlas_tile(self.texture.clone(), self.size, tile, &shadow_camera);
+        }
+        Ok(())
+    }
+}
+
+///
+/// Returns the fraction of the screen covered by the given bounding box projected with the given view projection matrix.
+/// A bounding box which intersects the plane of the viewer covers the entire screen.
+///
+fn screen_coverage(view_projection: Mat4, aabb: AxisAlignedBoundingBox) -> f32 {
+    if aabb.is_empty() {
+        return 0.0;
+    }
+    let (min, max) = (aabb.min(), aabb.max());
+    let mut screen_min = vec2(f32::MAX, f32::MAX);
+    let mut screen_max = vec2(f32::MIN, f32::MIN);
+    for i in 0..8 {
+        let corner = vec3(
+            if i & 1 == 0 { min.x } else { max.x },
+            if i & 2 == 0 { min.y } else { max.y },
+            if i & 4 == 0 { min.z } else { max.z },
+        );
+        let position = view_projection * corner.extend(1.0);
+        if position.w <= 0.0 {
+            return 1.0;
+        }
+        let ndc = position.truncate().truncate() / position.w;
+        screen_min = vec2(screen_min.x.min(ndc.x), screen_min.y.min(ndc.y));
+        screen_max = vec2(screen_max.x.max(ndc.x), screen_max.y.max(ndc.y));
+    }
+    let width = screen_max.x.min(1.0) - screen_min.x.max(-1.0);
+    let height = screen_max.y.min(1.0) - screen_min.y.max(-1.0);
+    (width.max(0.0) * height.max(0.0) / 4.0).min(1.0)
+}
+
+///
+/// Takes the smallest free square that is large enough and splits it into quadrants until it has the given size.
+///
+fn allocate_tile(free: &mut Vec<Viewport>, size: u32) -> Option<Viewport> {
+    let index = free
+        .iter()
+        .enumerate()
+        .filter(|(_, tile)| tile.width >= size)
+        .min_by_key(|(_, tile)| tile.width)
+        .map(|(index, _)| index)?;
+    let mut tile = free.swap_remove(index);
+    while tile.width > size {
+        let half = tile.width / 2;
+        for (x, y) in [(half, 0), (0, half), (half, half)] {
+            free.push(Viewport {
+                x: tile.x + x as i32,
+                y: tile.y + y as i32,
+                width: half,
+                height: half,
+            });
+        }
+        tile.width = half;
+        tile.height = half;
+    }
+    Some(tile)
+}
+
+fn previous_power_of_two(value: u32) -> u32 {
+    if value.is_power_of_two() {
+        value
+    } else {
+        value.next_power_of_two() / 2
+    }
+}
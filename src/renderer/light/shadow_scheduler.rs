@@ -0,0 +1,340 @@
+use crate::core::*;
+use crate::renderer::light::*;
+use crate::renderer::*;
+
+///
+/// The quality of a shadow, which determines the default resolution of the shadow map, see [ShadowQuality::resolution],
+/// and the percentage closer filtering (PCF) kernel used to soften the shadow edges.
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum ShadowQuality {
+    /// A single sample per fragment, which gives hard and aliased edges but is the fastest.
+    Low,
+    /// Four samples per fragment in a Poisson disk.
+    #[default]
+    Medium,
+    /// Sixteen samples per fragment in a 4x4 grid of texels, which gives the softest edges.
+    High,
+}
+
+impl ShadowQuality {
+    ///
+    /// Returns the default resolution of the shadow map for this quality.
+    ///
+    pub fn resolution(&self) -> u32 {
+        match self {
+            Self::Low => 512,
+            Self::Medium => 1024,
+            Self::High => 2048,
+        }
+    }
+
+    pub(crate) fn kernel(&self) -> i32 {
+        match self {
+            Self::Low => 0,
+            Self::Medium => 1,
+            Self::High => 2,
+        }
+    }
+}
+
+///
+/// Specifies when the shadow map of a light is regenerated by a [ShadowScheduler].
+/// The shadow map is always regenerated if the light has moved or changed in a way that changes the shadow, see [ShadowCaster::shadow_transformation].
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ShadowUpdate {
+    /// Regenerate the shadow map every frame.
+    EveryFrame,
+    /// Regenerate the shadow map when at least the given number of frames has passed since the last update.
+    Interval(u32),
+    /// Only regenerate the shadow map when it is marked as dirty, see [ShadowScheduler::mark_dirty].
+    OnDemand,
+}
+
+///
+/// The shadow settings of a light in a [ShadowScheduler].
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ShadowSettings {
+    /// The width and height of the shadow map in texels.
+    pub resolution: u32,
+    /// When the shadow map is regenerated.
+    pub update: ShadowUpdate,
+    /// The quality of the shadow, which controls the filtering of the shadow edges.
+    pub quality: ShadowQuality,
+}
+
+impl ShadowSettings {
+    ///
+    /// Creates settings with the given quality, the default resolution of the quality and an update every frame.
+    ///
+    pub fn new(quality: ShadowQuality) -> Self {
+        Self {
+            resolution: quality.resolution(),
+            update: ShadowUpdate::EveryFrame,
+            quality,
+        }
+    }
+}
+
+impl Default for ShadowSettings {
+    fn default() -> Self {
+        Self::new(ShadowQuality::default())
+    }
+}
+
+///
+/// A light which can cast shadows using a shadow map that is updated by a [ShadowScheduler].
+///
+pub trait ShadowCaster {
+    ///
+    /// Generates the shadow map with the given width and height in texels from the given geometries.
+    ///
+    fn update_shadow_map(
+        &mut self,
+        texture_size: u32,
+        geometries: &[&dyn Geometry],
+    ) -> Result<(), RendererError>;
+
+    ///
+    /// Returns a transformation which changes whenever the light changes in a way that requires the shadow map to be regenerated,
+    /// for example when the light moves.
+    ///
+    fn shadow_transformation(&self) -> Mat4;
+
+    ///
+    /// Sets the quality of the shadow, which controls the filtering of the shadow edges.
+    ///
+    fn set_shadow_quality(&mut self, quality: ShadowQuality);
+}
+
+impl ShadowCaster for SpotLight {
+    fn update_shadow_map(
+        &mut self,
+        texture_size: u32,
+        geometries: &[&dyn Geometry],
+    ) -> Result<(), RendererError> {
+        self.generate_shadow_map(texture_size, geometries.iter())
+    }
+
+    fn shadow_transformation(&self) -> Mat4 {
+        Mat4::from_scale(self.cutoff.0)
+            * Mat4::look_to_rh(
+                Point3::from_vec(self.position),
+                self.direction.normalize(),
+                compute_up_direction(self.direction),
+            )
+    }
+
+    fn set_shadow_quality(&mut self, quality: ShadowQuality) {
+        self.shadow_quality = quality;
+    }
+}
+
+impl ShadowCaster for DirectionalLight {
+    fn update_shadow_map(
+        &mut self,
+        texture_size: u32,
+        geometries: &[&dyn Geometry],
+    ) -> Result<(), RendererError> {
+        self.generate_shadow_map(texture_size, geometries.iter())
+    }
+
+    fn shadow_transformation(&self) -> Mat4 {
+        Mat4::look_to_rh(
+            Point3::new(0.0, 0.0, 0.0),
+            self.direction.normalize(),
+            compute_up_direction(self.direction),
+        )
+    }
+
+    fn set_shadow_quality(&mut self, quality: ShadowQuality) {
+        self.shadow_quality = quality;
+    }
+}
+
+///
+/// Identifies a light in a [ShadowScheduler].
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ShadowId(usize);
+
+struct ShadowEntry {
+    settings: ShadowSettings,
+    last_update: Option<u64>,
+    transformation: Option<Mat4>,
+    dirty: bool,
+}
+
+///
+/// Spreads the regeneration of the shadow maps of several lights across frames to bound the cost per frame,
+/// for example to update the shadow of an important light every frame while the shadows of less important lights are updated less often and with a lower resolution.
+///
+/// Each light is added with its [ShadowSettings] and each frame [ShadowScheduler::update] regenerates the shadow maps that are due:
+/// - Lights that have moved or changed in a way that changes the shadow, see [ShadowCaster::shadow_transformation], and lights that have never been updated are always updated.
+/// - Other lights are updated according to their [ShadowUpdate] setting, the least recently updated first, as long as the total number of texels
+///   in the shadow maps updated in the frame is within the [ShadowScheduler::texel_budget]. At least one light is updated each frame if any is due.
+///
+/// ```no_run
+/// # use three_d::*;
+/// # let context: Context = unimplemented!();
+/// # let mut hero: SpotLight = unimplemented!();
+/// # let mut fill: SpotLight = unimplemented!();
+/// # let model: Gm<Mesh, PhysicalMaterial> = unimplemented!();
+/// let mut scheduler = ShadowScheduler::new();
+/// let hero_shadow = scheduler.add(ShadowSettings::new(ShadowQuality::High));
+/// let fill_shadow = scheduler.add(ShadowSettings {
+///     update: ShadowUpdate::Interval(10),
+///     ..ShadowSettings::new(ShadowQuality::Low)
+/// });
+///
+/// // Each frame
+/// scheduler.update(&mut [(hero_shadow, &mut hero), (fill_shadow, &mut fill)], &[&model]).unwrap();
+/// println!("The fill light shadow is {:?} frames old", scheduler.staleness(fill_shadow));
+/// ```
+///
+pub struct ShadowScheduler {
+    entries: Vec<ShadowEntry>,
+    frame: u64,
+    /// The maximum number of texels, ie. the width times the height of the shadow maps, regenerated per frame,
+    /// not counting the shadow maps that must be regenerated because the light has changed. Default is 2048x2048.
+    pub texel_budget: u64,
+}
+
+impl Default for ShadowScheduler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ShadowScheduler {
+    ///
+    /// Creates a new shadow scheduler without any lights.
+    ///
+    pub fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+            frame: 0,
+            texel_budget: 2048 * 2048,
+        }
+    }
+
+    ///
+    /// Adds a light with the given settings. The returned id is used to refer to the light in the other methods.
+    ///
+    pub fn add(&mut self, settings: ShadowSettings) -> ShadowId {
+        self.entries.push(ShadowEntry {
+            settings,
+            last_update: None,
+            transformation: None,
+            dirty: true,
+        });
+        ShadowId(self.entries.len() - 1)
+    }
+
+    ///
+    /// Returns the settings of the given light.
+    ///
+    pub fn settings(&self, id: ShadowId) -> ShadowSettings {
+        self.entries[id.0].settings
+    }
+
+    ///
+    /// Sets the settings of the given light. The shadow map is regenerated at the next update if the resolution has changed.
+    ///
+    pub fn set_settings(&mut self, id: ShadowId, settings: ShadowSettings) {
+        let entry = &mut self.entries[id.0];
+        entry.dirty |= entry.settings.resolution != settings.resolution;
+        entry.settings = settings;
+    }
+
+    ///
+    /// Marks the shadow map of the given light as dirty, so it is regenerated at the next update no matter the [ShadowUpdate] setting,
+    /// for example when the geometries casting the shadow have moved.
+    ///
+    pub fn mark_dirty(&mut self, id: ShadowId) {
+        self.entries[id.0].dirty = true;
+    }
+
+    ///
+    /// Marks the shadow maps of all lights as dirty, see [ShadowScheduler::mark_dirty].
+    ///
+    pub fn mark_all_dirty(&mut self) {
+        self.entries.iter_mut().for_each(|entry| entry.dirty = true);
+    }
+
+    ///
+    /// Returns the number of frames since the shadow map of the given light was regenerated, where 0 means it was regenerated in the last update,
+    /// or `None` if it has never been generated.
+    ///
+    pub fn staleness(&self, id: ShadowId) -> Option<u64> {
+        self.entries[id.0]
+            .last_update
+            .map(|frame| self.frame - frame)
+    }
+
+    ///
+    /// Returns whether the shadow map of the given light is due for an update, ie. whether it would be regenerated by [ShadowScheduler::update] if the budget allows it.
+    /// Does not take into account whether the light has changed.
+    ///
+    pub fn is_due(&self, id: ShadowId) -> bool {
+        let entry = &self.entries[id.0];
+        entry.dirty
+            || match (entry.settings.update, entry.last_update) {
+                (_, None) | (ShadowUpdate::EveryFrame, _) => true,
+                (ShadowUpdate::Interval(interval), Some(frame)) => {
+                    self.frame + 1 - frame >= interval.max(1) as u64
+                }
+                (ShadowUpdate::OnDemand, _) => false,
+            }
+    }
+
+    ///
+    /// Advances one frame and regenerates the shadow maps of the given lights which are due, using the given geometries as shadow casters.
+    /// Also sets the [ShadowQuality] of each light. Returns the number of shadow maps that were regenerated.
+    ///
+    pub fn update(
+        &mut self,
+        lights: &mut [(ShadowId, &mut dyn ShadowCaster)],
+        geometries: &[&dyn Geometry],
+    ) -> Result<usize, RendererError> {
+        // Lights that have changed are always updated, the rest in order of the least recently updated
+        let mut forced = Vec::new();
+        let mut due = Vec::new();
+        for (index, (id, light)) in lights.iter_mut().enumerate() {
+            let entry = &self.entries[id.0];
+            light.set_shadow_quality(entry.settings.quality);
+            if entry.transformation != Some(light.shadow_transformation()) {
+                forced.push(index);
+            } else if self.is_due(*id) {
+                due.push(index);
+            }
+        }
+        due.sort_by_key(|index| self.entries[lights[*index].0 .0].last_update);
+
+        self.frame += 1;
+        let mut texels = 0;
+        let mut updated = 0;
+        for (index, is_forced) in forced
+            .into_iter()
+            .map(|i| (i, true))
+            .chain(due.into_iter().map(|i| (i, false)))
+        {
+            let (id, light) = &mut lights[index];
+            let entry = &mut self.entries[id.0];
+            let size = entry.settings.resolution.max(1);
+            let cost = size as u64 * size as u64;
+            if !is_forced && updated > 0 && texels + cost > self.texel_budget {
+                continue;
+            }
+            light.update_shadow_map(size, geometries)?;
+            entry.transformation = Some(light.shadow_transformation());
+            entry.last_update = Some(self.frame);
+            entry.dirty = false;
+            texels += cost;
+            updated += 1;
+        }
+        Ok(updated)
+    }
+}
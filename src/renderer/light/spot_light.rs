@@ -1,14 +1,25 @@
 use crate::core::*;
 use crate::renderer::light::*;
 use crate::renderer::*;
+use std::sync::{Arc, RwLock};
+
+enum SpotLightShadow {
+    Texture(DepthTexture2D),
+    Atlas {
+        texture: Arc<RwLock<DepthTexture2D>>,
+        tile: Viewport,
+        tile_transformation: Vec4,
+    },
+}
 
 ///
 /// A light which shines from the given position and in the given direction.
-/// The light will cast shadows if you [generate a shadow map](SpotLight::generate_shadow_map).
+/// The light will cast shadows if you [generate a shadow map](SpotLight::generate_shadow_map)
+/// or give it a tile in a [ShadowAtlas] which is shared with other lights.
 ///
 pub struct SpotLight {
     context: Context,
-    shadow_texture: Option<DepthTexture2D>,
+    shadow_texture: Option<SpotLightShadow>,
     shadow_matrix: Mat4,
     /// The intensity of the light. This allows for higher intensity than 1 which can be used to simulate high intensity light sources like the sun.
     pub intensity: f32,
@@ -22,6 +33,30 @@ pub struct SpotLight {
     pub cutoff: Radians,
     /// The [Attenuation] of the light.
     pub attenuation: Attenuation,
+    ///
+    /// An optional texture projected by the light, also known as a cookie or gobo, which is multiplied with the light color.
+    /// The texture is projected so that it covers the cone of the light, ie. the circle inscribed in the texture corresponds to the cutoff angle.
+    /// The transformation of the texture reference is applied to the projected uv coordinates, which can be used to tile or rotate the texture,
+    /// in which case the wrapping of the texture determines what is projected outside the unit square.
+    /// There is no light outside the cone no matter the texture.
+    ///
+    pub cookie_texture: Option<Texture2DRef>,
+    ///
+    /// An optional texture describing the intensity of the light as a function of the angle to the light direction, for example measured for a real fixture.
+    /// It replaces the default smooth falloff towards the cutoff angle and is sampled horizontally in the middle row,
+    /// where the left edge corresponds to the light direction and the right edge corresponds to the cutoff angle.
+    /// The color of the texture is multiplied with the light color, so use a grayscale texture to only change the intensity.
+    /// The transformation of the texture reference is not used.
+    ///
+    pub falloff_texture: Option<Texture2DRef>,
+    /// The quality of the shadow, which controls the filtering of the shadow edges. Only used if the light casts shadows
+    /// and the shadow softness and bias are zero, see [SpotLight::set_shadow_softness] and [SpotLight::set_shadow_bias].
+    pub shadow_quality: ShadowQuality,
+    shadow_softness: f32,
+    shadow_samples: u32,
+    shadow_bias: f32,
+    angular_size: f32,
+    color_ramp: Option<ColorRamp>,
 }
 
 impl SpotLight {
@@ -45,11 +80,52 @@ impl SpotLight {
             cutoff: cutoff.into(),
             attenuation,
             shadow_matrix: Mat4::identity(),
+            cookie_texture: None,
+            falloff_texture: None,
+            shadow_quality: ShadowQuality::default(),
+            shadow_softness: 0.0,
+            shadow_samples: 16,
+            shadow_bias: 0.0,
+            angular_size: 0.0,
+            color_ramp: None,
         }
     }
 
     ///
-    /// Clear the shadow map, effectively disable the shadow.
+    /// Constructs a new spot light with the given luminous flux in lumen, ie. the total amount of light emitted within the cone given by the cutoff angle,
+    /// and physically correct inverse square attenuation.
+    /// The luminous flux is spread over the cone, so a narrow cone is brighter than a wide cone with the same luminous flux.
+    /// Use a camera with an exposure, see [Camera::set_exposure], to render physical light units.
+    ///
+    pub fn new_physical(
+        context: &Context,
+        luminous_flux: f32,
+        color: Srgba,
+        position: Vec3,
+        direction: Vec3,
+        cutoff: impl Into<Radians>,
+    ) -> SpotLight {
+        let cutoff = cutoff.into();
+        // The luminous intensity in candela, ie. the luminous flux divided by the solid angle of the cone
+        let solid_angle = 2.0 * std::f32::consts::PI * (1.0 - cutoff.0.cos());
+        let intensity = luminous_flux / solid_angle.max(1.0e-6);
+        Self::new(
+            context,
+            intensity,
+            color,
+            position,
+            direction,
+            cutoff,
+            Attenuation {
+                constant: 0.0,
+                linear: 0.0,
+                quadratic: 1.0,
+            },
+        )
+    }
+
+    ///
+    /// Clear the shadow map or the tile in a [ShadowAtlas], effectively disable the shadow.
     /// Only necessary if you want to disable the shadow, if you want to update the shadow, just use [SpotLight::generate_shadow_map].
     ///
     pub fn clear_shadow_map(&mut self) {
@@ -67,30 +143,14 @@ impl SpotLight {
         texture_size: u32,
         geometries: impl IntoIterator<Item = impl Geometry> + Clone,
     ) -> Result<(), RendererError> {
-        let position = self.position;
-        let target = position + self.direction.normalize();
-        let up = compute_up_direction(self.direction);
-
-        let viewport = Viewport::new_at_origo(texture_size, texture_size);
-
-        let mut z_far = 0.0f32;
-        let mut z_near = f32::MAX;
-        for geometry in geometries.clone() {
-            let aabb = geometry.aabb();
-            if !aabb.is_empty() {
-                z_far = z_far.max(aabb.distance_max(self.position));
-                z_near = z_near.min(aabb.distance(self.position));
-            }
-        }
-
-        let shadow_camera = Camera::new_perspective(
-            viewport,
-            position,
-            target,
-            up,
-            self.cutoff,
-            z_near.max(0.01),
-            z_far,
+        trace_span!(
+            "three_d::generate_shadow_map",
+            light = "spot",
+            size = texture_size
+        );
+        let shadow_camera = self.shadow_camera(
+            Viewport::new_at_origo(texture_size, texture_size),
+            geometries.clone(),
         );
         self.shadow_matrix = shadow_matrix(&shadow_camera);
 
@@ -117,113 +177,390 @@ impl SpotLight {
                     .into_iter()
                     .filter(|g| frustum.contains(g.aabb()))
                 {
-                    render_with_material(
+                    render_shadow_caster(
                         &self.context,
                         &shadow_camera,
                         &geometry,
                         &depth_material,
-                        &[],
                     )?;
                 }
                 Ok(())
             })?;
-        self.shadow_texture = Some(shadow_texture);
+        self.shadow_texture = Some(SpotLightShadow::Texture(shadow_texture));
         Ok(())
     }
 
     ///
-    /// Returns a reference to the shadow map if it has been generated.
+    /// Returns a reference to the shadow map if it has been generated using [SpotLight::generate_shadow_map].
+    /// Returns `None` if the shadow is rendered into a tile of a [ShadowAtlas], see [SpotLight::shadow_atlas_tile].
     ///
     pub fn shadow_map(&self) -> Option<&DepthTexture2D> {
-        self.shadow_texture.as_ref()
+        match &self.shadow_texture {
+            Some(SpotLightShadow::Texture(texture)) => Some(texture),
+            _ => None,
+        }
+    }
+
+    ///
+    /// Returns the tile in the texture of a [ShadowAtlas] that this light renders its shadow into,
+    /// or `None` if the light has not been given a tile by [ShadowAtlas::generate_shadow_maps].
+    ///
+    pub fn shadow_atlas_tile(&self) -> Option<Viewport> {
+        match &self.shadow_texture {
+            Some(SpotLightShadow::Atlas { tile, .. }) => Some(*tile),
+            _ => None,
+        }
+    }
+
+    ///
+    /// Returns the radius in texels of the percentage closer filtering of the shadow, see [SpotLight::set_shadow_softness].
+    ///
+    pub fn shadow_softness(&self) -> f32 {
+        self.shadow_softness
+    }
+
+    ///
+    /// Sets the radius in texels of the shadow map within which the shadow is sampled to soften the shadow edges using percentage closer filtering.
+    /// The number of samples is set using [SpotLight::set_shadow_samples].
+    /// A radius of zero, which is the default, means that the filtering is instead determined by the [SpotLight::shadow_quality].
+    ///
+    pub fn set_shadow_softness(&mut self, radius_in_texels: f32) {
+        self.shadow_softness = radius_in_texels.max(0.0);
+    }
+
+    ///
+    /// Returns the number of samples used to soften the shadow edges, see [SpotLight::set_shadow_samples].
+    ///
+    pub fn shadow_samples(&self) -> u32 {
+        self.shadow_samples
+    }
+
+    ///
+    /// Sets the number of samples, between 1 and 64, used to soften the shadow edges when the shadow softness is larger than zero, see [SpotLight::set_shadow_softness].
+    /// More samples give smoother shadow edges at a higher cost. Default is 16.
+    ///
+    pub fn set_shadow_samples(&mut self, samples: u32) {
+        self.shadow_samples = samples.clamp(1, 64);
+    }
+
+    ///
+    /// Returns the additional depth bias of the shadow, see [SpotLight::set_shadow_bias].
+    ///
+    pub fn shadow_bias(&self) -> f32 {
+        self.shadow_bias
+    }
+
+    ///
+    /// Sets an additional depth bias which is added to the bias computed from the angle between the surface and the light.
+    /// Increase the bias if a surface incorrectly shadows itself (shadow acne), which is more visible with soft shadows,
+    /// and decrease it if the shadows are detached from the objects casting them. Default is zero.
+    ///
+    pub fn set_shadow_bias(&mut self, bias: f32) {
+        self.shadow_bias = bias;
+    }
+
+    ///
+    /// Sets a gradient, given as pairs of a distance to the light and a color, which is multiplied with the light color depending on the distance to the light.
+    /// This makes it possible for the light to change color with the distance, for example from white near a sodium lamp to orange further away.
+    /// The color is linearly interpolated between the stops and the color of the first and last stop is used before and after these stops,
+    /// so a single stop is a constant color. The gradient is applied in addition to the [SpotLight::attenuation].
+    /// Giving no stops removes the gradient, see also [SpotLight::clear_color_ramp].
+    ///
+    pub fn set_color_ramp(&mut self, stops: &[(f32, Srgba)]) {
+        self.color_ramp = ColorRamp::new(&self.context, stops);
+    }
+
+    ///
+    /// Removes the gradient set by [SpotLight::set_color_ramp].
+    ///
+    pub fn clear_color_ramp(&mut self) {
+        self.color_ramp = None;
+    }
+
+    ///
+    /// Returns the stops of the gradient set by [SpotLight::set_color_ramp] sorted by distance.
+    ///
+    pub fn color_ramp(&self) -> Option<&[(f32, Srgba)]> {
+        self.color_ramp.as_ref().map(|ramp| ramp.stops.as_slice())
+    }
+
+    ///
+    /// Returns the angular diameter in degrees of the light source as seen from the lit surfaces, see [SpotLight::set_angular_size].
+    ///
+    pub fn angular_size(&self) -> f32 {
+        self.angular_size
+    }
+
+    ///
+    /// Sets the angular diameter in degrees of the light source as seen from the lit surfaces, for example a small bulb is a fraction of a degree and a large soft box is several degrees.
+    /// A larger size gives broader and less intense specular highlights, which matches the highlights from an [Environment] better,
+    /// while the diffuse lighting is unaffected. Default is zero, ie. an ideal light source with pinpoint highlights.
+    ///
+    pub fn set_angular_size(&mut self, degrees: f32) {
+        self.angular_size = degrees.clamp(0.0, 90.0);
+    }
+
+    fn has_shadow_filter(&self) -> bool {
+        self.shadow_texture.is_some() && (self.shadow_softness > 0.0 || self.shadow_bias != 0.0)
+    }
+
+    ///
+    /// Returns a camera looking in the direction of the light with the given viewport and the near and far planes fitted to the given geometries.
+    ///
+    pub(super) fn shadow_camera(
+        &self,
+        viewport: Viewport,
+        geometries: impl IntoIterator<Item = impl Geometry>,
+    ) -> Camera {
+        let mut aabb = AxisAlignedBoundingBox::EMPTY;
+        for geometry in geometries {
+            aabb.expand_with_aabb(geometry.aabb());
+        }
+        let mut camera = Camera::new_perspective(
+            viewport,
+            self.position,
+            self.position + self.direction.normalize(),
+            compute_up_direction(self.direction),
+            self.cutoff,
+            0.01,
+            1.0,
+        );
+        camera.auto_fit_depth_range(aabb, 0.001 * aabb.size().magnitude());
+        camera
+    }
+
+    ///
+    /// Uses the given tile in the given shadow atlas texture as the shadow map of this light, where the shadow has been rendered with the given camera.
+    ///
+    pub(super) fn set_shadow_atlas_tile(
+        &mut self,
+        texture: Arc<RwLock<DepthTexture2D>>,
+        atlas_size: u32,
+        tile: Viewport,
+        shadow_camera: &Camera,
+    ) {
+        let size = atlas_size as f32;
+        self.shadow_matrix = shadow_matrix(shadow_camera);
+        self.shadow_texture = Some(SpotLightShadow::Atlas {
+            texture,
+            tile,
+            tile_transformation: vec4(
+                tile.x as f32 / size,
+                tile.y as f32 / size,
+                tile.width as f32 / size,
+                tile.height as f32 / size,
+            ),
+        });
+    }
+
+    ///
+    /// Returns the matrix projecting a position in world space to the uv coordinates of the cookie texture, see [SpotLight::cookie_texture].
+    /// The projection is the same as for the shadow map, except that the field of view covers the entire cone.
+    ///
+    fn cookie_matrix(&self) -> Mat4 {
+        let camera = Camera::new_perspective(
+            Viewport::new_at_origo(1, 1),
+            self.position,
+            self.position + self.direction.normalize(),
+            compute_up_direction(self.direction),
+            radians((2.0 * self.cutoff.0).min(179f32.to_radians())),
+            0.01,
+            1.0,
+        );
+        shadow_matrix(&camera)
     }
 }
 
 impl Light for SpotLight {
     fn shader_source(&self, i: u32) -> String {
+        let mut uniforms = String::new();
+        match self.shadow_texture {
+            Some(SpotLightShadow::Texture(_)) => uniforms.push_str(&format!(
+                "uniform sampler2D shadowMap{i};\nuniform mat4 shadowMVP{i};\n"
+            )),
+            Some(SpotLightShadow::Atlas { .. }) => uniforms.push_str(&format!(
+                "#ifndef SHADOW_ATLAS\n#define SHADOW_ATLAS\nuniform sampler2D shadowAtlas;\n#endif\nuniform mat4 shadowMVP{i};\nuniform vec4 shadowTile{i};\n"
+            )),
+            None => {}
+        }
         if self.shadow_texture.is_some() {
+            if self.has_shadow_filter() {
+                uniforms.push_str(&format!(
+                    "uniform vec2 shadowFilter{i};\nuniform int shadowSamples{i};\n"
+                ));
+            } else {
+                uniforms.push_str(&format!("uniform int shadowKernel{i};\n"));
+            }
+        }
+        if self.cookie_texture.is_some() {
+            uniforms.push_str(&format!(
+                "uniform sampler2D cookieTexture{i};\nuniform mat4 cookieMVP{i};\nuniform mat3 cookieTransformation{i};\n"
+            ));
+        }
+        if self.falloff_texture.is_some() {
+            uniforms.push_str(&format!("uniform sampler2D falloffTexture{i};\n"));
+        }
+        let ramp = if self.color_ramp.is_some() {
+            uniforms.push_str(&ColorRamp::shader_source(i));
             format!(
-                "
-                    uniform sampler2D shadowMap{};
-                    uniform mat4 shadowMVP{};
-
-                    uniform vec3 color{};
-                    uniform vec3 attenuation{};
-                    uniform vec3 position{};
-                    uniform float cutoff{};
-                    uniform vec3 direction{};
-                    vec3 calculate_lighting{}(vec3 surface_color, vec3 position, vec3 normal, vec3 view_direction, float metallic, float roughness, float occlusion)
-                    {{
-                        vec3 light_direction = position{} - position;
-                        float distance = length(light_direction);
-                        light_direction = light_direction / distance;
-
-                        float angle = acos(dot(-light_direction, normalize(direction{})));
-                        float cutoff = cutoff{};
-
-                        vec3 result = vec3(0.0);
-                        if (angle < cutoff) {{
-                            vec3 light_color = attenuate(color{}, attenuation{}, distance);
-                            result = calculate_light(light_color, light_direction, surface_color, view_direction, normal,
-                                metallic, roughness) * (1.0 - smoothstep(0.75 * cutoff, cutoff, angle));
-                            result *= calculate_shadow(light_direction, normal, shadowMap{}, shadowMVP{}, position);
-                        }}
-                        return result;
-                    }}
-
-                ", i, i, i, i, i, i, i, i, i, i, i, i, i, i, i)
+                "\n                            {}",
+                ColorRamp::sample_source(i)
+            )
         } else {
+            String::new()
+        };
+        let falloff = if self.falloff_texture.is_some() {
+            format!("texture(falloffTexture{i}, vec2(angle / cutoff, 0.5)).rgb")
+        } else {
+            "vec3(1.0 - smoothstep(0.75 * cutoff, cutoff, angle))".to_owned()
+        };
+        let cookie = if self.cookie_texture.is_some() {
             format!(
                 "
-                    uniform vec3 color{};
-                    uniform vec3 attenuation{};
-                    uniform vec3 position{};
-                    uniform float cutoff{};
-                    uniform vec3 direction{};
-                    vec3 calculate_lighting{}(vec3 surface_color, vec3 position, vec3 normal, vec3 view_direction, float metallic, float roughness, float occlusion)
+                            vec4 cookie_position = cookieMVP{i} * vec4(position, 1.0);
+                            vec2 cookie_uv = (cookieTransformation{i} * vec3(cookie_position.xy / cookie_position.w, 1.0)).xy;
+                            result *= cookie_position.w > 0.0 ? texture(cookieTexture{i}, cookie_uv).rgb : vec3(0.0);"
+            )
+        } else {
+            String::new()
+        };
+        let shadow = match self.shadow_texture {
+            Some(SpotLightShadow::Texture(_)) if self.has_shadow_filter() => format!(
+                "
+                            result *= calculate_soft_shadow(light_direction, normal, shadowMap{i}, shadowMVP{i}, position, shadowFilter{i}.x, shadowSamples{i}, shadowFilter{i}.y);"
+            ),
+            Some(SpotLightShadow::Atlas { .. }) if self.has_shadow_filter() => format!(
+                "
+                            result *= calculate_soft_shadow_in_tile(light_direction, normal, shadowAtlas, shadowMVP{i}, shadowTile{i}, position, shadowFilter{i}.x, shadowSamples{i}, shadowFilter{i}.y);"
+            ),
+            Some(SpotLightShadow::Texture(_)) => format!(
+                "
+                            result *= calculate_shadow_with_kernel(light_direction, normal, shadowMap{i}, shadowMVP{i}, position, shadowKernel{i});"
+            ),
+            Some(SpotLightShadow::Atlas { .. }) => format!(
+                "
+                            result *= calculate_shadow_in_tile_with_kernel(light_direction, normal, shadowAtlas, shadowMVP{i}, shadowTile{i}, position, shadowKernel{i});"
+            ),
+            None => String::new(),
+        };
+        format!(
+            "
+                    {uniforms}
+                    uniform vec4 lightParameters{i}[4];
+                    vec3 calculate_lighting{i}(vec3 surface_color, vec3 position, vec3 normal, vec3 view_direction, float metallic, float roughness, float occlusion)
                     {{
-                        vec3 light_direction = position{} - position;
+                        vec3 color{i} = lightParameters{i}[0].xyz;
+                        float cutoff{i} = lightParameters{i}[0].w;
+                        vec3 attenuation{i} = lightParameters{i}[1].xyz;
+                        float angularRadius{i} = lightParameters{i}[1].w;
+                        vec3 position{i} = lightParameters{i}[2].xyz;
+                        vec3 direction{i} = lightParameters{i}[3].xyz;
+                        vec3 light_direction = position{i} - position;
                         float distance = length(light_direction);
                         light_direction = light_direction / distance;
 
-                        float angle = acos(dot(-light_direction, normalize(direction{})));
-                        float cutoff = cutoff{};
+                        float angle = acos(dot(-light_direction, normalize(direction{i})));
+                        float cutoff = cutoff{i};
 
                         vec3 result = vec3(0.0);
                         if (angle < cutoff) {{
-                            vec3 light_color = attenuate(color{}, attenuation{}, distance);
-                            result = calculate_light(light_color, light_direction, surface_color, view_direction, normal,
-                                metallic, roughness) * (1.0 - smoothstep(0.75 * cutoff, cutoff, angle));
+                            vec3 light_color = attenuate(color{i}, attenuation{i}, distance);{ramp}
+                            result = calculate_light_with_angular_radius(light_color, light_direction, surface_color, view_direction, normal,
+                                metallic, roughness, angularRadius{i}) * {falloff};{cookie}{shadow}
                         }}
                         return result;
                     }}
 
-                ", i, i, i, i, i, i, i, i, i, i, i)
-        }
+                "
+        )
     }
     fn use_uniforms(&self, program: &Program, i: u32) {
-        if let Some(ref tex) = self.shadow_texture {
-            program.use_depth_texture(&format!("shadowMap{}", i), tex);
-            program.use_uniform(&format!("shadowMVP{}", i), self.shadow_matrix);
+        match &self.shadow_texture {
+            Some(SpotLightShadow::Texture(tex)) => {
+                program.use_depth_texture(&format!("shadowMap{}", i), tex);
+                program.use_uniform(&format!("shadowMVP{}", i), self.shadow_matrix);
+            }
+            Some(SpotLightShadow::Atlas {
+                texture,
+                tile_transformation,
+                ..
+            }) => {
+                program.use_depth_texture("shadowAtlas", &texture.read().unwrap());
+                program.use_uniform(&format!("shadowMVP{}", i), self.shadow_matrix);
+                program.use_uniform(&format!("shadowTile{}", i), *tile_transformation);
+            }
+            None => {}
         }
-        program.use_uniform(
-            &format!("color{}", i),
-            self.color.to_linear_srgb().truncate() * self.intensity,
-        );
-        program.use_uniform(
-            &format!("attenuation{}", i),
-            vec3(
-                self.attenuation.constant,
-                self.attenuation.linear,
-                self.attenuation.quadratic,
-            ),
+        if self.shadow_texture.is_some() {
+            if self.has_shadow_filter() {
+                program.use_uniform(
+                    &format!("shadowFilter{}", i),
+                    vec2(self.shadow_softness, self.shadow_bias),
+                );
+                // Only bias the shadow, so a single sample is enough
+                let samples = if self.shadow_softness > 0.0 {
+                    self.shadow_samples
+                } else {
+                    1
+                };
+                program.use_uniform(&format!("shadowSamples{}", i), samples as i32);
+            } else {
+                program.use_uniform(&format!("shadowKernel{}", i), self.shadow_quality.kernel());
+            }
+        }
+        if let Some(ref tex) = self.cookie_texture {
+            program.use_texture(&format!("cookieTexture{}", i), tex);
+            program.use_uniform(&format!("cookieMVP{}", i), self.cookie_matrix());
+            program.use_uniform(&format!("cookieTransformation{}", i), tex.transformation);
+        }
+        if let Some(ref tex) = self.falloff_texture {
+            program.use_texture(&format!("falloffTexture{}", i), tex);
+        }
+        if let Some(ref ramp) = self.color_ramp {
+            ramp.use_uniforms(program, i);
+        }
+        program.use_uniform_array(
+            &format!("lightParameters{}", i),
+            &[
+                (self.color.to_linear_srgb().truncate() * self.intensity).extend(self.cutoff.0),
+                vec4(
+                    self.attenuation.constant,
+                    self.attenuation.linear,
+                    self.attenuation.quadratic,
+                    0.5 * self.angular_size.to_radians(),
+                ),
+                self.position.extend(0.0),
+                self.direction.normalize().extend(0.0),
+            ],
         );
-        program.use_uniform(&format!("position{}", i), self.position);
-        program.use_uniform(&format!("direction{}", i), self.direction.normalize());
-        program.use_uniform(&format!("cutoff{}", i), self.cutoff.0);
     }
 
     fn id(&self) -> LightId {
-        LightId::SpotLight(self.shadow_texture.is_some())
+        match self.shadow_texture {
+            Some(SpotLightShadow::Atlas { .. }) if self.color_ramp.is_some() => {
+                LightId::SpotLightWithShadowAtlasAndColorRamp(
+                    self.cookie_texture.is_some(),
+                    self.falloff_texture.is_some(),
+                    self.has_shadow_filter(),
+                )
+            }
+            Some(SpotLightShadow::Atlas { .. }) => LightId::SpotLightWithShadowAtlas(
+                self.cookie_texture.is_some(),
+                self.falloff_texture.is_some(),
+                self.has_shadow_filter(),
+            ),
+            _ if self.color_ramp.is_some() => LightId::SpotLightWithColorRamp(
+                self.shadow_texture.is_some(),
+                self.cookie_texture.is_some(),
+                self.falloff_texture.is_some(),
+                self.has_shadow_filter(),
+            ),
+            _ => LightId::SpotLight(
+                self.shadow_texture.is_some(),
+                self.cookie_texture.is_some(),
+                self.falloff_texture.is_some(),
+                self.has_shadow_filter(),
+            ),
+        }
     }
 }
@@ -22,14 +22,23 @@ macro_rules! impl_material_body {
         fn id(&self) -> EffectMaterialId {
             self.$inner().id()
         }
+        fn alpha_cutout(&self) -> Option<AlphaCutout> {
+            self.$inner().alpha_cutout()
+        }
     };
 }
 
 use crate::renderer::*;
 
-pub use three_d_asset::material::{
-    GeometryFunction, LightingModel, NormalDistributionFunction, PbrMaterial as CpuMaterial,
-};
+pub use three_d_asset::material::PbrMaterial as CpuMaterial;
+
+mod lighting_model;
+#[doc(inline)]
+pub use lighting_model::*;
+
+mod texture_mapping;
+#[doc(inline)]
+pub use texture_mapping::*;
 
 mod color_material;
 #[doc(inline)]
@@ -59,6 +68,10 @@ mod uv_material;
 #[doc(inline)]
 pub use uv_material::*;
 
+mod joint_weight_material;
+#[doc(inline)]
+pub use joint_weight_material::*;
+
 mod physical_material;
 #[doc(inline)]
 pub use physical_material::*;
@@ -71,14 +84,31 @@ mod skybox_material;
 #[doc(inline)]
 pub(in crate::renderer) use skybox_material::*;
 
+mod light_flare_material;
+#[doc(inline)]
+pub(in crate::renderer) use light_flare_material::*;
+
 mod isosurface_material;
 #[doc(inline)]
 pub use isosurface_material::*;
 
+mod fur_material;
+#[doc(inline)]
+pub use fur_material::*;
+
+mod clay_material;
+#[doc(inline)]
+pub use clay_material::*;
+
+mod toon_material;
+#[doc(inline)]
+pub use toon_material::*;
+
 use std::{ops::Deref, sync::Arc};
 
 ///
 /// A reference to a 2D texture and a texture transformation.
+/// Two references are equal if they refer to the same texture with the same transformation.
 ///
 #[derive(Clone)]
 pub struct Texture2DRef {
@@ -98,6 +128,14 @@ impl Texture2DRef {
         }
     }
 
+    /// Creates a new [Texture2DRef] with an identity transformation from a [CpuTexture] where the colors are premultiplied with alpha, see [Texture2D::new_premultiplied].
+    pub fn from_cpu_texture_premultiplied(context: &Context, cpu_texture: &CpuTexture) -> Self {
+        Self {
+            texture: Arc::new(Texture2D::new_premultiplied(context, cpu_texture)),
+            transformation: Mat3::identity(),
+        }
+    }
+
     /// Creates a new [Texture2DRef] with an identity transformation from a [Texture2D].
     pub fn from_texture(texture: Texture2D) -> Self {
         Self {
@@ -107,6 +145,12 @@ impl Texture2DRef {
     }
 }
 
+impl PartialEq for Texture2DRef {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.texture, &other.texture) && self.transformation == other.transformation
+    }
+}
+
 impl std::ops::Deref for Texture2DRef {
     type Target = Texture2D;
     fn deref(&self) -> &Self::Target {
@@ -155,7 +199,8 @@ pub enum MaterialType {
 /// - tangent: `in vec3 tang;`
 /// - bitangent: `in vec3 bitang;`
 /// - uv coordinates: `in vec2 uvs;` (flipped in v compared to standard uv coordinates)
-/// - color: `in vec4 col;`
+/// - color: `in vec4 col;` (in linear sRGB color space, see [SrgbaExt])
+/// - joint indices and weights: `in vec4 joints;` and `in vec4 weights;`
 ///
 pub trait Material {
     ///
@@ -185,6 +230,15 @@ pub trait Material {
     /// Returns the type of material.
     ///
     fn material_type(&self) -> MaterialType;
+
+    ///
+    /// Returns the alpha cutout of this material, if the parts of the geometry where the alpha value is below a threshold are discarded.
+    /// The alpha cutout is used when only the depth of the geometry is rendered, for example into a shadow map (see [Geometry::alpha_cutout]),
+    /// so the discarded parts do not cast shadows. The default implementation returns `None`.
+    ///
+    fn alpha_cutout(&self) -> Option<AlphaCutout> {
+        None
+    }
 }
 
 ///
@@ -215,15 +269,15 @@ impl<T: Material + ?Sized> Material for &mut T {
     impl_material_body!(deref);
 }
 
-impl<T: Material> Material for Box<T> {
+impl<T: Material + ?Sized> Material for Box<T> {
     impl_material_body!(as_ref);
 }
 
-impl<T: Material> Material for std::rc::Rc<T> {
+impl<T: Material + ?Sized> Material for std::rc::Rc<T> {
     impl_material_body!(as_ref);
 }
 
-impl<T: Material> Material for std::sync::Arc<T> {
+impl<T: Material + ?Sized> Material for std::sync::Arc<T> {
     impl_material_body!(as_ref);
 }
 
@@ -247,6 +301,9 @@ impl<T: Material> Material for std::sync::RwLock<T> {
     fn id(&self) -> EffectMaterialId {
         self.read().unwrap().id()
     }
+    fn alpha_cutout(&self) -> Option<AlphaCutout> {
+        self.read().unwrap().alpha_cutout()
+    }
 }
 
 fn is_transparent(cpu_material: &CpuMaterial) -> bool {
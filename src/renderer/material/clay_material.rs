@@ -0,0 +1,109 @@
+use crate::core::*;
+use crate::renderer::*;
+
+///
+/// A material with a single color which is darkened in cavities and brightened at edges, which gives a look similar to the viewport of a sculpting application
+/// and shows the shape of a mesh without any textures.
+///
+/// The ambient occlusion and curvature are read from the vertex colors of the mesh, which can be computed using [ClayMaterial::vertex_colors]
+/// from the results of [compute_vertex_ambient_occlusion] and [compute_vertex_curvature].
+/// The red channel contains the ambient occlusion, the green channel the convex curvature and the blue channel the concave curvature,
+/// where 1 is no occlusion and no curvature, so a mesh without vertex colors is rendered without darkening and brightening.
+/// The mesh must have normals.
+///
+/// ```no_run
+/// # use three_d::*;
+/// # let context: Context = unimplemented!();
+/// let mut cpu_mesh = CpuMesh::sphere(32);
+/// let occlusion = compute_vertex_ambient_occlusion(&cpu_mesh, AmbientOcclusionSettings::default(), |_| {});
+/// let curvature = compute_vertex_curvature(&cpu_mesh);
+/// cpu_mesh.colors = Some(ClayMaterial::vertex_colors(&occlusion, &curvature, 0.5));
+/// let model = Gm::new(Mesh::new(&context, &cpu_mesh), ClayMaterial::default());
+/// ```
+///
+#[derive(Clone)]
+pub struct ClayMaterial {
+    /// The color of the clay.
+    pub color: Srgba,
+    /// A value in the range `[0..1]` specifying how rough the surface is.
+    pub roughness: f32,
+    /// A value in the range `[0..1]` specifying how much the ambient occlusion darkens the cavities.
+    pub cavity_strength: f32,
+    /// A value in the range `[0..1]` specifying how much the curvature brightens the convex edges and darkens the concave creases.
+    pub edge_strength: f32,
+    /// The lighting model used when rendering this material.
+    pub lighting_model: LightingModel,
+    /// Render states.
+    pub render_states: RenderStates,
+}
+
+impl ClayMaterial {
+    ///
+    /// Returns the vertex colors used by this material given the ambient occlusion and curvature of each vertex, see [compute_vertex_ambient_occlusion] and [compute_vertex_curvature].
+    /// The curvature is multiplied by the curvature scale and clamped to `[-1..1]`, so the curvature scale should be close to the radius of the smallest details that should be highlighted.
+    ///
+    pub fn vertex_colors(occlusion: &[f32], curvature: &[f32], curvature_scale: f32) -> Vec<Srgba> {
+        occlusion
+            .iter()
+            .zip(curvature)
+            .map(|(occlusion, curvature)| {
+                let curvature = (curvature * curvature_scale).clamp(-1.0, 1.0);
+                // The colors are converted to linear sRGB when the mesh is created
+                Srgba::from_linear(vec4(
+                    occlusion.clamp(0.0, 1.0),
+                    1.0 - curvature.max(0.0),
+                    1.0 + curvature.min(0.0),
+                    1.0,
+                ))
+            })
+            .collect()
+    }
+}
+
+impl Material for ClayMaterial {
+    fn id(&self) -> EffectMaterialId {
+        EffectMaterialId::ClayMaterial
+    }
+
+    fn fragment_shader_source(&self, lights: &[&dyn Light]) -> String {
+        let mut source = lights_shader_source(lights);
+        source.push_str(include_str!("shaders/clay_material.frag"));
+        source
+    }
+
+    fn use_uniforms(&self, program: &Program, viewer: &dyn Viewer, lights: &[&dyn Light]) {
+        program.use_uniform_if_required("lightingModel", lighting_model_to_id(self.lighting_model));
+        viewer.tone_mapping().use_uniforms(program);
+        viewer.color_mapping().use_uniforms(program);
+        for (i, light) in lights.iter().enumerate() {
+            light.use_uniforms(program, i as u32);
+        }
+        program.use_uniform_if_required("logExposure", viewer.exposure().log2());
+        program.use_uniform("cameraPosition", viewer.position());
+        program.use_uniform("surfaceColor", self.color.to_linear_srgb());
+        program.use_uniform_if_required("roughness", self.roughness);
+        program.use_uniform("cavityStrength", self.cavity_strength);
+        program.use_uniform("edgeStrength", self.edge_strength);
+    }
+
+    fn render_states(&self) -> RenderStates {
+        self.render_states
+    }
+
+    fn material_type(&self) -> MaterialType {
+        MaterialType::Opaque
+    }
+}
+
+impl Default for ClayMaterial {
+    fn default() -> Self {
+        Self {
+            color: Srgba::new(200, 190, 180, 255),
+            roughness: 0.7,
+            cavity_strength: 1.0,
+            edge_strength: 0.5,
+            lighting_model: LightingModel::Blinn,
+            render_states: RenderStates::default(),
+        }
+    }
+}
@@ -5,17 +5,22 @@ use crate::renderer::*;
 /// A material that renders a [Geometry] in a color defined by multiplying a color with an optional texture and optional per vertex colors.
 /// This material is not affected by lights.
 ///
-#[derive(Clone, Default)]
+#[derive(Clone, Default, PartialEq)]
 pub struct ColorMaterial {
     /// Base surface color.
     pub color: Srgba,
-    /// An optional texture which is samples using uv coordinates (requires that the [Geometry] supports uv coordinates).
+    /// An optional texture which is sampled as specified by the [Self::texture_mapping], by default using uv coordinates (requires that the [Geometry] supports uv coordinates).
     /// The colors are assumed to be in linear sRGB (`RgbU8`), linear sRGB with an alpha channel (`RgbaU8`) or HDR color space.
     pub texture: Option<Texture2DRef>,
     /// Render states.
     pub render_states: RenderStates,
     /// Whether this material should be treated as a transparent material (An object needs to be rendered differently depending on whether it is transparent or opaque).
     pub is_transparent: bool,
+    /// Whether the colors of the texture are premultiplied with alpha, for example if it is created using [Texture2DRef::from_cpu_texture_premultiplied].
+    /// If true, the output color is also premultiplied with alpha and should be blended using [Blend::PREMULTIPLIED].
+    pub premultiplied_alpha: bool,
+    /// How the [Self::texture] is mapped onto the surface, for example [TextureMapping::Triplanar] for geometry without uv coordinates.
+    pub texture_mapping: TextureMapping,
 }
 
 impl ColorMaterial {
@@ -51,32 +56,35 @@ impl ColorMaterial {
             texture,
             is_transparent: false,
             render_states: RenderStates::default(),
+            premultiplied_alpha: false,
+            texture_mapping: TextureMapping::default(),
         }
     }
 
+    ///
     /// Constructs a new transparent color material from a [CpuMaterial].
+    /// The colors of the albedo texture are premultiplied with alpha in linear color space before the mip maps are generated,
+    /// which avoids dark fringes at semi-transparent edges, see [ColorMaterial::premultiplied_alpha].
+    ///
     pub fn new_transparent(context: &Context, cpu_material: &CpuMaterial) -> Self {
-        let texture =
-            cpu_material
-                .albedo_texture
-                .as_ref()
-                .map(|cpu_texture| match &cpu_texture.data {
-                    TextureData::RgbU8(_) | TextureData::RgbaU8(_) => {
-                        let mut cpu_texture = cpu_texture.clone();
-                        cpu_texture.data.to_linear_srgb();
-                        Texture2DRef::from_cpu_texture(context, &cpu_texture)
-                    }
-                    _ => Texture2DRef::from_cpu_texture(context, cpu_texture),
-                });
+        let texture = cpu_material.albedo_texture.as_ref().map(|cpu_texture| {
+            let mut cpu_texture = cpu_texture.clone();
+            if let TextureData::RgbU8(_) | TextureData::RgbaU8(_) = cpu_texture.data {
+                cpu_texture.data.to_linear_srgb();
+            }
+            Texture2DRef::from_cpu_texture_premultiplied(context, &cpu_texture)
+        });
         Self {
             color: cpu_material.albedo,
             texture,
             is_transparent: true,
             render_states: RenderStates {
                 write_mask: WriteMask::COLOR,
-                blend: Blend::TRANSPARENCY,
+                blend: Blend::PREMULTIPLIED,
                 ..Default::default()
             },
+            premultiplied_alpha: true,
+            texture_mapping: TextureMapping::default(),
         }
     }
 
@@ -87,6 +95,8 @@ impl ColorMaterial {
             texture: physical_material.albedo_texture.clone(),
             render_states: physical_material.render_states,
             is_transparent: physical_material.is_transparent,
+            premultiplied_alpha: false,
+            texture_mapping: physical_material.texture_mapping,
         }
     }
 }
@@ -99,16 +109,28 @@ impl FromCpuMaterial for ColorMaterial {
 
 impl Material for ColorMaterial {
     fn id(&self) -> EffectMaterialId {
-        EffectMaterialId::ColorMaterial(self.texture.is_some())
+        EffectMaterialId::ColorMaterial(
+            self.texture.is_some(),
+            self.premultiplied_alpha,
+            self.texture.is_some() && self.texture_mapping.is_triplanar(),
+        )
     }
 
     fn fragment_shader_source(&self, _lights: &[&dyn Light]) -> String {
         let mut shader = String::new();
+        if self.premultiplied_alpha {
+            shader.push_str("#define PREMULTIPLIED_ALPHA\n");
+        }
         if self.texture.is_some() {
-            shader.push_str("#define USE_TEXTURE\nin vec2 uvs;\n");
+            if self.texture_mapping.is_triplanar() {
+                shader.push_str(
+                    "#define USE_TEXTURE\n#define USE_TRIPLANAR\nin vec3 pos;\nin vec3 nor;\n",
+                );
+                shader.push_str(include_str!("shaders/triplanar.frag"));
+            } else {
+                shader.push_str("#define USE_TEXTURE\nin vec2 uvs;\n");
+            }
         }
-        shader.push_str(include_str!("../../core/shared.frag"));
-        shader.push_str(ColorMapping::fragment_shader_source());
         shader.push_str(include_str!("shaders/color_material.frag"));
         shader
     }
@@ -116,9 +138,12 @@ impl Material for ColorMaterial {
     fn use_uniforms(&self, program: &Program, viewer: &dyn Viewer, _lights: &[&dyn Light]) {
         viewer.color_mapping().use_uniforms(program);
         program.use_uniform("surfaceColor", self.color.to_linear_srgb());
+        program.use_uniform_if_required("colorOverride", Vec4::zero());
+        program.use_uniform_if_required("emissiveOverride", Vec4::zero());
         if let Some(ref tex) = self.texture {
             program.use_uniform("textureTransformation", tex.transformation);
             program.use_texture("tex", tex);
+            self.texture_mapping.use_uniforms(program);
         }
     }
     fn render_states(&self) -> RenderStates {
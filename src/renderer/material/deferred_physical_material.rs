@@ -11,7 +11,7 @@ use crate::renderer::*;
 /// Instead render the object into a [RenderTarget] consisting of a [Texture2DArray] with three RGBA u8 layers as color target and a [DepthTexture2D] as depth target.
 /// Then call the [DeferredPhysicalMaterial::lighting_pass] method with these textures to render to the screen.
 ///
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
 pub struct DeferredPhysicalMaterial {
     /// Name.
     pub name: String,
@@ -190,7 +190,7 @@ impl Material for DeferredPhysicalMaterial {
     }
 
     fn fragment_shader_source(&self, _lights: &[&dyn Light]) -> String {
-        let mut output = include_str!("../../core/shared.frag").to_string();
+        let mut output = String::new();
         if self.albedo_texture.is_some()
             || self.metallic_roughness_texture.is_some()
             || self.normal_texture.is_some()
@@ -263,6 +263,15 @@ impl Material for DeferredPhysicalMaterial {
         self.render_states
     }
 
+    fn alpha_cutout(&self) -> Option<AlphaCutout> {
+        // The alpha value in the albedo texture is compared to the threshold, the same as in the fragment shader
+        Some(AlphaCutout {
+            threshold: self.alpha_cutout?,
+            alpha: 1.0,
+            texture: Some(self.albedo_texture.clone()?),
+        })
+    }
+
     fn material_type(&self) -> MaterialType {
         MaterialType::Deferred
     }
@@ -1,6 +1,19 @@
 use crate::core::*;
 use crate::renderer::*;
 
+///
+/// Defines which parts of a geometry are discarded by a material with alpha cutout, see [Material::alpha_cutout].
+///
+#[derive(Clone)]
+pub struct AlphaCutout {
+    /// The threshold below which the alpha value is discarded.
+    pub threshold: f32,
+    /// The alpha value of the material, which is multiplied with the alpha value read from the [AlphaCutout::texture].
+    pub alpha: f32,
+    /// A texture with the alpha values in the alpha channel, usually the albedo texture of the material.
+    pub texture: Option<Texture2DRef>,
+}
+
 ///
 /// Used for rendering the distance from the camera to the object with this material in each pixel.
 /// Can be used for debug purposes but is also used to create shadow maps from light sources.
@@ -11,23 +24,49 @@ pub struct DepthMaterial {
     pub min_distance: Option<f32>,
     /// The maximum distance from the camera to any object. If None, then the far plane of the camera is used.
     pub max_distance: Option<f32>,
+    /// Discards the parts of the geometry where the alpha value is below the threshold, for example to render the shadow of a material with alpha cutout.
+    pub alpha_cutout: Option<AlphaCutout>,
     /// Render states.
     pub render_states: RenderStates,
 }
 
 impl FromCpuMaterial for DepthMaterial {
-    fn from_cpu_material(_context: &Context, _cpu_material: &CpuMaterial) -> Self {
-        Self::default()
+    fn from_cpu_material(context: &Context, cpu_material: &CpuMaterial) -> Self {
+        Self {
+            alpha_cutout: cpu_material.alpha_cutout.map(|threshold| AlphaCutout {
+                threshold,
+                alpha: cpu_material.albedo.a as f32 / 255.0,
+                texture: cpu_material
+                    .albedo_texture
+                    .as_ref()
+                    .map(|t| Texture2DRef::from_cpu_texture(context, t)),
+            }),
+            ..Default::default()
+        }
     }
 }
 
 impl Material for DepthMaterial {
     fn id(&self) -> EffectMaterialId {
-        EffectMaterialId::DepthMaterial
+        match &self.alpha_cutout {
+            Some(alpha_cutout) => EffectMaterialId(
+                EffectMaterialId::AlphaCutoutDepthMaterialBase.0
+                    | alpha_cutout.texture.is_some() as u16,
+            ),
+            None => EffectMaterialId::DepthMaterial,
+        }
     }
 
     fn fragment_shader_source(&self, _lights: &[&dyn Light]) -> String {
-        include_str!("shaders/depth_material.frag").to_string()
+        let mut source = String::new();
+        if let Some(alpha_cutout) = &self.alpha_cutout {
+            source.push_str("#define USE_ALPHA_CUTOUT\n");
+            if alpha_cutout.texture.is_some() {
+                source.push_str("#define USE_TEXTURE\nin vec2 uvs;\n");
+            }
+        }
+        source.push_str(include_str!("shaders/depth_material.frag"));
+        source
     }
 
     fn use_uniforms(&self, program: &Program, viewer: &dyn Viewer, _lights: &[&dyn Light]) {
@@ -40,12 +79,24 @@ impl Material for DepthMaterial {
             self.max_distance.unwrap_or_else(|| viewer.z_far()),
         );
         program.use_uniform("eye", viewer.position());
+        if let Some(alpha_cutout) = &self.alpha_cutout {
+            program.use_uniform("alphaCutout", alpha_cutout.threshold);
+            program.use_uniform("alpha", alpha_cutout.alpha);
+            if let Some(texture) = &alpha_cutout.texture {
+                program.use_texture("alphaTexture", texture);
+                program.use_uniform("alphaTexTransform", texture.transformation);
+            }
+        }
     }
 
     fn render_states(&self) -> RenderStates {
         self.render_states
     }
 
+    fn alpha_cutout(&self) -> Option<AlphaCutout> {
+        self.alpha_cutout.clone()
+    }
+
     fn material_type(&self) -> MaterialType {
         MaterialType::Opaque
     }
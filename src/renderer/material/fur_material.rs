@@ -0,0 +1,96 @@
+use crate::core::*;
+use crate::renderer::*;
+
+///
+/// A material that renders [Shells] as strands of fur, hair or grass.
+/// Each texel in the [FurMaterial::density_texture] contains a strand and the red channel defines the length of the strand relative to [Shells::length].
+/// A shell is discarded where the strands are shorter than the height of the shell and outside of the strand cross-section,
+/// which becomes thinner towards the tip of the strand.
+/// This material is affected by lights.
+///
+/// The geometry must be [Shells] with uv coordinates, since the height of each shell is needed to evaluate the strands.
+///
+#[derive(Clone)]
+pub struct FurMaterial {
+    /// The strand lengths in the range `[0..1]` sampled from the red channel.
+    /// Use nearest filtering and a texture transformation to control the number of strands, for example `Mat3::from_scale(8.0)` to repeat the texture 8 times.
+    pub density_texture: Texture2DRef,
+    /// The color at the root of the strands and of the skin.
+    pub root_color: Srgba,
+    /// The color at the tip of the strands.
+    pub tip_color: Srgba,
+    /// The radius of a strand at the root relative to the size of a texel in the [FurMaterial::density_texture].
+    pub thickness: f32,
+    /// A value in the range `[0..1]` specifying how rough the strands are.
+    pub roughness: f32,
+    /// A value in the range `[0..1]` specifying how much less light reaches the roots than the tips of the strands.
+    pub occlusion_strength: f32,
+    /// The lighting model used when rendering this material
+    pub lighting_model: LightingModel,
+    /// Render states.
+    pub render_states: RenderStates,
+}
+
+impl FurMaterial {
+    ///
+    /// Creates a new fur material with the given strand lengths and color.
+    ///
+    pub fn new(density_texture: Texture2DRef, color: Srgba) -> Self {
+        Self {
+            density_texture,
+            root_color: color,
+            tip_color: color,
+            thickness: 0.8,
+            roughness: 0.8,
+            occlusion_strength: 0.6,
+            lighting_model: LightingModel::Blinn,
+            render_states: RenderStates {
+                // The shells are seen from both sides
+                cull: Cull::None,
+                ..Default::default()
+            },
+        }
+    }
+}
+
+impl Material for FurMaterial {
+    fn id(&self) -> EffectMaterialId {
+        EffectMaterialId::FurMaterial
+    }
+
+    fn fragment_shader_source(&self, lights: &[&dyn Light]) -> String {
+        let mut source = lights_shader_source(lights);
+        source.push_str(include_str!("shaders/fur_material.frag"));
+        source
+    }
+
+    fn use_uniforms(&self, program: &Program, viewer: &dyn Viewer, lights: &[&dyn Light]) {
+        program.use_uniform_if_required("lightingModel", lighting_model_to_id(self.lighting_model));
+        viewer.tone_mapping().use_uniforms(program);
+        viewer.color_mapping().use_uniforms(program);
+        for (i, light) in lights.iter().enumerate() {
+            light.use_uniforms(program, i as u32);
+        }
+        program.use_uniform_if_required("logExposure", viewer.exposure().log2());
+        program.use_uniform_if_required("cameraPosition", viewer.position());
+        program.use_uniform_if_required("roughness", self.roughness);
+        program.use_uniform("rootColor", self.root_color.to_linear_srgb());
+        program.use_uniform_if_required("tipColor", self.tip_color.to_linear_srgb());
+        program.use_uniform_if_required("occlusionStrength", self.occlusion_strength);
+        program.use_uniform_if_required("colorOverride", Vec4::zero());
+        program.use_uniform_if_required("emissiveOverride", Vec4::zero());
+        if program.requires_uniform("densityTexture") {
+            program.use_uniform("thickness", self.thickness);
+            program.use_uniform("densityTexTransform", self.density_texture.transformation);
+            program.use_texture("densityTexture", &self.density_texture);
+        }
+    }
+
+    fn render_states(&self) -> RenderStates {
+        self.render_states
+    }
+
+    fn material_type(&self) -> MaterialType {
+        MaterialType::Opaque
+    }
+}
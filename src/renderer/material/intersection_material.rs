@@ -2,11 +2,12 @@ use crate::core::*;
 use crate::renderer::*;
 
 ///
-/// Used for intersection tests, see [pick] and [ray_intersect].
+/// Used for intersection tests, see [pick], [ray_intersect] and [pick_gpu].
 /// When rendering with this material, the output in each pixel is:
 /// - Red channel: The depth (same as [DepthMaterial]).
 /// - Green channel: The [IntersectionMaterial::geometry_id].
 /// - Blue channel: The [gl_InstanceID](https://registry.khronos.org/OpenGL-Refpages/gl4/html/gl_InstanceID.xhtml).
+/// - Alpha channel: The depth in the depth buffer ([gl_FragCoord.z](https://registry.khronos.org/OpenGL-Refpages/gl4/html/gl_FragCoord.xhtml)).
 ///
 /// Note: The geometry needs to pass the instance ID to the fragment shader, see [Geometry] for more information.
 ///
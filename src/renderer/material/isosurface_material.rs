@@ -31,8 +31,6 @@ impl Material for IsosurfaceMaterial {
 
     fn fragment_shader_source(&self, lights: &[&dyn Light]) -> String {
         let mut source = lights_shader_source(lights);
-        source.push_str(ToneMapping::fragment_shader_source());
-        source.push_str(ColorMapping::fragment_shader_source());
         source.push_str(include_str!("shaders/isosurface_material.frag"));
         source
     }
@@ -44,6 +42,7 @@ impl Material for IsosurfaceMaterial {
         for (i, light) in lights.iter().enumerate() {
             light.use_uniforms(program, i as u32);
         }
+        program.use_uniform_if_required("logExposure", viewer.exposure().log2());
         program.use_uniform("cameraPosition", viewer.position());
         program.use_uniform("surfaceColor", self.color.to_linear_srgb());
         program.use_uniform("metallic", self.metallic);
@@ -0,0 +1,48 @@
+use crate::core::*;
+use crate::renderer::*;
+
+///
+/// Render the object with colors that reflect how much the joint with the given index influences each vertex which primarily is used for debugging skinning weights.
+/// Vertices that are not influenced by the joint are rendered blue, the color then goes through green to red which is used for vertices that are fully influenced by the joint.
+/// Requires that the [Geometry] provides joint indices and weights, for example a [Mesh] with [Mesh::joint_indices_mut] and [Mesh::joint_weights_mut] specified.
+///
+#[derive(Clone, Default)]
+pub struct JointWeightMaterial {
+    /// The index of the joint for which the influence is visualized.
+    pub joint_index: u32,
+    /// Render states.
+    pub render_states: RenderStates,
+}
+
+impl JointWeightMaterial {
+    ///
+    /// Constructs a new material that visualizes the influence of the joint with the given index.
+    ///
+    pub fn new(joint_index: u32) -> Self {
+        Self {
+            joint_index,
+            ..Default::default()
+        }
+    }
+}
+
+impl Material for JointWeightMaterial {
+    fn id(&self) -> EffectMaterialId {
+        EffectMaterialId::JointWeightMaterial
+    }
+
+    fn fragment_shader_source(&self, _lights: &[&dyn Light]) -> String {
+        include_str!("shaders/joint_weight_material.frag").to_string()
+    }
+
+    fn use_uniforms(&self, program: &Program, viewer: &dyn Viewer, _lights: &[&dyn Light]) {
+        viewer.color_mapping().use_uniforms(program);
+        program.use_uniform("jointIndex", self.joint_index as f32);
+    }
+    fn render_states(&self) -> RenderStates {
+        self.render_states
+    }
+    fn material_type(&self) -> MaterialType {
+        MaterialType::Opaque
+    }
+}
@@ -0,0 +1,36 @@
+use crate::core::*;
+use crate::renderer::*;
+
+///
+/// The material used when rendering a [LightFlare] directly, ie. without the occlusion fading applied by the [LightFlareEffect].
+/// The flare texture, streak texture and color are given by the [LightFlare] geometry.
+///
+pub struct LightFlareMaterial;
+
+impl Material for LightFlareMaterial {
+    fn id(&self) -> EffectMaterialId {
+        EffectMaterialId::LightFlareMaterial
+    }
+
+    fn fragment_shader_source(&self, _lights: &[&dyn Light]) -> String {
+        include_str!("shaders/light_flare_material.frag").to_string()
+    }
+
+    fn use_uniforms(&self, program: &Program, viewer: &dyn Viewer, _lights: &[&dyn Light]) {
+        viewer.tone_mapping().use_uniforms(program);
+        viewer.color_mapping().use_uniforms(program);
+    }
+
+    fn render_states(&self) -> RenderStates {
+        RenderStates {
+            write_mask: WriteMask::COLOR,
+            blend: Blend::ADD,
+            cull: Cull::None,
+            ..Default::default()
+        }
+    }
+
+    fn material_type(&self) -> MaterialType {
+        MaterialType::Transparent
+    }
+}
@@ -0,0 +1,88 @@
+///
+/// Lighting models which specify how the lighting is computed when rendering a material.
+/// This is a trade-off between how fast the computations are versus how physically correct they look.
+///
+/// Can be converted from the lighting model of a [CpuMaterial](crate::renderer::CpuMaterial) using [From].
+///
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum LightingModel {
+    /// Phong lighting model.
+    /// The fastest lighting model to calculate.
+    Phong,
+    /// Blinn lighting model.
+    /// Almost as fast as Phong and has less artifacts.
+    Blinn,
+    /// Cook-Torrance lighting model with the given normal distribution and geometry functions.
+    /// The most physically correct lighting model but also the most expensive.
+    Cook(NormalDistributionFunction, GeometryFunction),
+}
+
+///
+/// The geometry function, which models the shadowing and masking of the microfacets, used in a Cook-Torrance lighting model.
+///
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum GeometryFunction {
+    /// The Schlick approximation of the separable Smith geometry function with GGX.
+    SmithSchlickGGX,
+    /// The height-correlated Smith geometry function with GGX, which takes into account that the shadowing and masking are correlated by the height of the microfacets.
+    /// This is more accurate than [GeometryFunction::SmithSchlickGGX], especially for rough surfaces at grazing angles, and is the one used in most modern renderers.
+    SmithHeightCorrelatedGGX,
+}
+
+///
+/// The normal distribution function, which models the distribution of the microfacet normals, used in a Cook-Torrance lighting model.
+///
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[allow(missing_docs)]
+pub enum NormalDistributionFunction {
+    Blinn,
+    Beckmann,
+    TrowbridgeReitzGGX,
+}
+
+///
+/// The model used for the diffuse part of the lighting, which is used together with a [LightingModel].
+///
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub enum DiffuseModel {
+    /// The Lambertian model, where the diffuse light is scattered equally in all directions.
+    #[default]
+    Lambert,
+    /// The Oren-Nayar model, where the roughness of the material makes the diffuse light scatter more towards the light,
+    /// which makes rough dielectric surfaces, for example concrete, clay and unfinished wood, look less flat than with the Lambertian model.
+    /// Smooth surfaces look the same as with the Lambertian model.
+    /// The ambient light always uses the Lambertian model.
+    OrenNayar,
+}
+
+impl From<three_d_asset::material::LightingModel> for LightingModel {
+    fn from(model: three_d_asset::material::LightingModel) -> Self {
+        match model {
+            three_d_asset::material::LightingModel::Phong => Self::Phong,
+            three_d_asset::material::LightingModel::Blinn => Self::Blinn,
+            three_d_asset::material::LightingModel::Cook(normal_distribution, geometry) => {
+                Self::Cook(normal_distribution.into(), geometry.into())
+            }
+        }
+    }
+}
+
+impl From<three_d_asset::material::GeometryFunction> for GeometryFunction {
+    fn from(function: three_d_asset::material::GeometryFunction) -> Self {
+        match function {
+            three_d_asset::material::GeometryFunction::SmithSchlickGGX => Self::SmithSchlickGGX,
+        }
+    }
+}
+
+impl From<three_d_asset::material::NormalDistributionFunction> for NormalDistributionFunction {
+    fn from(function: three_d_asset::material::NormalDistributionFunction) -> Self {
+        match function {
+            three_d_asset::material::NormalDistributionFunction::Blinn => Self::Blinn,
+            three_d_asset::material::NormalDistributionFunction::Beckmann => Self::Beckmann,
+            three_d_asset::material::NormalDistributionFunction::TrowbridgeReitzGGX => {
+                Self::TrowbridgeReitzGGX
+            }
+        }
+    }
+}
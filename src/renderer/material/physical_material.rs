@@ -5,7 +5,7 @@ use crate::renderer::*;
 /// A physically-based material that renders a [Geometry] in an approximate correct physical manner based on Physically Based Rendering (PBR).
 /// This material is affected by lights.
 ///
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
 pub struct PhysicalMaterial {
     /// Name.
     pub name: String,
@@ -30,6 +30,19 @@ pub struct PhysicalMaterial {
     pub normal_scale: f32,
     /// A tangent space normal map, also known as bump map.
     pub normal_texture: Option<Texture2DRef>,
+    /// A height map used for parallax occlusion mapping, where the height is sampled from the red channel and 1 is the surface and 0 is the deepest point.
+    /// The height field is ray marched in tangent space to find the texture coordinates used for all other textures, which gives the illusion of depth, for example of bricks and cobblestones seen at glancing angles.
+    /// Like the [Self::normal_texture], this requires the geometry to have tangents, see [CpuMesh::compute_tangents].
+    /// The shader code is compiled out when this is `None`.
+    pub height_texture: Option<Texture2DRef>,
+    /// The depth of the [Self::height_texture] in texture coordinates, ie. the maximum offset of the texture coordinates when looking at the surface at a 45 degree angle.
+    pub parallax_scale: f32,
+    /// The number of steps used when ray marching the [Self::height_texture] at glancing angles, half as many are used when looking straight at the surface.
+    /// More steps reduce the visible layering at the cost of performance.
+    pub parallax_steps: u32,
+    /// Whether to discard fragments where the offset texture coordinates are outside the range `[0..1]`, which cuts away the bumps at the silhouette of for example a cube
+    /// where each side has texture coordinates in that range. Should be disabled when the texture coordinates are repeating.
+    pub parallax_clip: bool,
     /// Render states.
     pub render_states: RenderStates,
     /// Whether this material should be treated as a transparent material (An object needs to be rendered differently depending on whether it is transparent or opaque).
@@ -41,6 +54,77 @@ pub struct PhysicalMaterial {
     pub emissive_texture: Option<Texture2DRef>,
     /// The lighting model used when rendering this material
     pub lighting_model: LightingModel,
+    /// The model used for the diffuse part of the lighting when rendering this material, which uses the [Self::roughness] of the material.
+    pub diffuse_model: DiffuseModel,
+    /// A lightmap containing precomputed diffuse lighting, for example baked using [bake_lightmaps], which is multiplied with the albedo and added to the lighting from the lights.
+    /// This makes it possible to render static geometry without any runtime lights.
+    /// The lightmap is sampled using the lightmap uv coordinates of the geometry, so the geometry must support that, see [Mesh::set_lightmap_uvs].
+    pub lightmap_texture: Option<Texture2DRef>,
+    /// An approximation of the light that scatters beneath the surface, which softens the transition from lit to unlit areas and
+    /// makes thin parts glow when lit from behind, for example skin, wax, marble and leaves.
+    /// The scattering is applied to the light from all lights except the ambient light and the shader code is compiled out when this is `None`.
+    pub subsurface: Option<SubsurfaceScattering>,
+    /// The strength in the range `[0..1]` of a clear coat, which is a thin transparent layer on top of the surface with its own specular reflection, for example car paint or lacquered wood.
+    /// The light reflected by the coat does not reach the surface below, so the surface is dimmed accordingly.
+    /// The coat reflects the light from all lights except area lights, including the environment of an ambient light, and the shader code is compiled out when this is zero.
+    pub clearcoat: f32,
+    /// A value in the range `[0..1]` specifying how rough the clear coat is.
+    pub clearcoat_roughness: f32,
+    /// Texture with the strength of the clear coat, which is multiplied with the [Self::clearcoat] value. The strength is sampled from the red channel.
+    pub clearcoat_texture: Option<Texture2DRef>,
+    /// A tangent space normal map of the clear coat, otherwise the coat uses the normal of the geometry.
+    /// Like the [Self::normal_texture], this requires the geometry to have tangents.
+    pub clearcoat_normal_texture: Option<Texture2DRef>,
+    /// A value in the range `[-1..1]` specifying how much the specular highlight is stretched along the tangent (positive) or the bitangent (negative), for example brushed metal or hair.
+    /// This only affects the [LightingModel::Cook] lighting model, which then always uses the anisotropic GGX normal distribution and the height-correlated Smith geometry function,
+    /// and the reflection of the environment of an ambient light. The shader code is compiled out when this is zero.
+    /// The tangents of the geometry are used if present, see [CpuMesh::compute_tangents], otherwise the tangents are derived from the uv coordinates.
+    pub anisotropy: f32,
+    /// The rotation in radians of the direction of the [Self::anisotropy] counter-clockwise from the tangent in tangent space.
+    pub anisotropy_rotation: f32,
+    /// Whether the backfacing triangles are lit as the back side of the surface, ie. with the normal flipped, which is needed for thin two-sided surfaces like leaves and paper.
+    /// Otherwise the backfacing triangles are lit as the front side, which is correct if they are hidden or culled, see [RenderStates::cull].
+    /// Use [PhysicalMaterial::set_double_sided] to set both at once as specified by the `doubleSided` property of a glTF material.
+    pub double_sided_lighting: bool,
+    /// How the textures are mapped onto the surface, for example [TextureMapping::Triplanar] for geometry without uv coordinates.
+    /// The lightmap is always sampled using the lightmap uv coordinates and the [Self::height_texture] is ignored when using triplanar mapping.
+    pub texture_mapping: TextureMapping,
+}
+
+///
+/// Parameters for the approximation of subsurface scattering in a [PhysicalMaterial], see [PhysicalMaterial::subsurface].
+///
+/// The diffuse lighting is wrapped around the shadow terminator depending on the curvature of the surface and the scattering radius,
+/// which approximates pre-integrated skin shading without a lookup texture.
+/// Furthermore, light hitting the back of the object is transmitted through it depending on the thickness.
+/// Note that shadows also block the transmitted light, so disable shadows or make sure the object does not cast a shadow on itself to get the full effect.
+///
+#[derive(Clone, PartialEq)]
+pub struct SubsurfaceScattering {
+    /// The color of the light that is scattered beneath the surface, for example a red color for skin or a warm yellow for wax.
+    /// Color channels with higher values scatter further and are transmitted through thicker parts.
+    pub color: Srgba,
+    /// The distance in world units that the light travels beneath the surface.
+    /// Curves with a radius below this distance are softened the most.
+    pub radius: f32,
+    /// The thickness of the object in world units, which is multiplied by the value in the [Self::thickness_texture] if any.
+    pub thickness: f32,
+    /// A texture with the thickness of the object relative to [Self::thickness], sampled from the red channel, for example baked as the inverted ambient occlusion of the inverted normals.
+    pub thickness_texture: Option<Texture2DRef>,
+    /// The strength of the light transmitted through the object when it is lit from behind. A value of 0 disables the transmission.
+    pub transmission: f32,
+}
+
+impl Default for SubsurfaceScattering {
+    fn default() -> Self {
+        Self {
+            color: Srgba::new_opaque(255, 100, 70),
+            radius: 0.1,
+            thickness: 0.1,
+            thickness_texture: None,
+            transmission: 1.0,
+        }
+    }
 }
 
 impl PhysicalMaterial {
@@ -124,6 +208,10 @@ impl PhysicalMaterial {
             metallic_roughness_texture,
             normal_texture,
             normal_scale: cpu_material.normal_scale,
+            height_texture: None,
+            parallax_scale: 0.05,
+            parallax_steps: 16,
+            parallax_clip: false,
             occlusion_texture,
             occlusion_strength: cpu_material.occlusion_strength,
             render_states: if is_transparent {
@@ -138,9 +226,30 @@ impl PhysicalMaterial {
             is_transparent,
             emissive: cpu_material.emissive,
             emissive_texture,
-            lighting_model: cpu_material.lighting_model,
+            lighting_model: cpu_material.lighting_model.into(),
+            diffuse_model: DiffuseModel::default(),
+            lightmap_texture: None,
+            subsurface: None,
+            clearcoat: 0.0,
+            clearcoat_roughness: 0.0,
+            clearcoat_texture: None,
+            clearcoat_normal_texture: None,
+            anisotropy: 0.0,
+            anisotropy_rotation: 0.0,
+            double_sided_lighting: true,
+            texture_mapping: TextureMapping::default(),
         }
     }
+
+    ///
+    /// Sets whether this material is double sided as specified by the `doubleSided` property of a glTF material.
+    /// A double sided material renders and lights both sides of the surface, otherwise the backfacing triangles are culled,
+    /// which also applies when rendering shadow maps.
+    ///
+    pub fn set_double_sided(&mut self, double_sided: bool) {
+        self.render_states.cull = if double_sided { Cull::None } else { Cull::Back };
+        self.double_sided_lighting = double_sided;
+    }
 }
 
 impl FromCpuMaterial for PhysicalMaterial {
@@ -157,18 +266,58 @@ impl Material for PhysicalMaterial {
             self.occlusion_texture.is_some(),
             self.normal_texture.is_some(),
             self.emissive_texture.is_some(),
+            self.lightmap_texture.is_some(),
+            self.subsurface.is_some(),
+            self.subsurface
+                .as_ref()
+                .is_some_and(|s| s.thickness_texture.is_some()),
+            self.height_texture.is_some() && !self.texture_mapping.is_triplanar(),
+            self.clearcoat > 0.0,
+            self.clearcoat > 0.0 && self.clearcoat_texture.is_some(),
+            self.clearcoat > 0.0 && self.clearcoat_normal_texture.is_some(),
+            self.anisotropy != 0.0,
+            self.texture_mapping.is_triplanar(),
         )
     }
 
     fn fragment_shader_source(&self, lights: &[&dyn Light]) -> String {
-        let mut output = lights_shader_source(lights);
+        let mut output = String::new();
+        let thickness_texture = self
+            .subsurface
+            .as_ref()
+            .is_some_and(|s| s.thickness_texture.is_some());
+        let clearcoat = self.clearcoat > 0.0;
+        let clearcoat_texture = clearcoat && self.clearcoat_texture.is_some();
+        let clearcoat_normal_texture = clearcoat && self.clearcoat_normal_texture.is_some();
+        let anisotropy = self.anisotropy != 0.0;
+        let triplanar = self.texture_mapping.is_triplanar();
+        if self.subsurface.is_some() {
+            // Must be defined before the lighting code which uses it
+            output.push_str("#define USE_SUBSURFACE;\n");
+        }
+        if clearcoat {
+            output.push_str("#define USE_CLEARCOAT;\n");
+        }
+        if anisotropy {
+            output.push_str("#define USE_ANISOTROPY;\n");
+        }
+        output.push_str(&lights_shader_source(lights));
         if self.albedo_texture.is_some()
             || self.metallic_roughness_texture.is_some()
             || self.normal_texture.is_some()
             || self.occlusion_texture.is_some()
             || self.emissive_texture.is_some()
+            || self.height_texture.is_some()
+            || thickness_texture
+            || clearcoat_texture
+            || clearcoat_normal_texture
+            || anisotropy
         {
-            output.push_str("in vec2 uvs;\n");
+            output.push_str("#define USE_UVS;\nin vec2 uvs;\n");
+            if triplanar {
+                output.push_str("#define USE_TRIPLANAR;\n");
+                output.push_str(include_str!("shaders/triplanar.frag"));
+            }
             if self.albedo_texture.is_some() {
                 output.push_str("#define USE_ALBEDO_TEXTURE;\n");
             }
@@ -179,28 +328,62 @@ impl Material for PhysicalMaterial {
                 output.push_str("#define USE_OCCLUSION_TEXTURE;\n");
             }
             if self.normal_texture.is_some() {
-                output.push_str("#define USE_NORMAL_TEXTURE;\nin vec3 tang;\nin vec3 bitang;\n");
+                output.push_str("#define USE_NORMAL_TEXTURE;\n");
+            }
+            // Parallax occlusion mapping requires uv coordinates and tangents
+            if self.height_texture.is_some() && !triplanar {
+                output.push_str("#define USE_HEIGHT_TEXTURE;\n");
+            }
+            if clearcoat_texture {
+                output.push_str("#define USE_CLEARCOAT_TEXTURE;\n");
+            }
+            if clearcoat_normal_texture {
+                output.push_str("#define USE_CLEARCOAT_NORMAL_TEXTURE;\n");
+            }
+            // The normal maps are reoriented for each projection when using triplanar mapping, so the tangents are not needed
+            if (!triplanar
+                && (self.normal_texture.is_some()
+                    || self.height_texture.is_some()
+                    || clearcoat_normal_texture))
+                || anisotropy
+            {
+                output.push_str("in vec3 tang;\nin vec3 bitang;\n");
             }
             if self.emissive_texture.is_some() {
                 output.push_str("#define USE_EMISSIVE_TEXTURE;\n");
             }
+            if thickness_texture {
+                output.push_str("#define USE_THICKNESS_TEXTURE;\n");
+            }
+        }
+        if self.lightmap_texture.is_some() {
+            output.push_str("#define USE_LIGHTMAP_TEXTURE;\nin vec2 lightmap_uvs;\n");
         }
-        output.push_str(ToneMapping::fragment_shader_source());
-        output.push_str(ColorMapping::fragment_shader_source());
         output.push_str(include_str!("shaders/physical_material.frag"));
         output
     }
 
     fn use_uniforms(&self, program: &Program, viewer: &dyn Viewer, lights: &[&dyn Light]) {
         program.use_uniform_if_required("lightingModel", lighting_model_to_id(self.lighting_model));
+        program.use_uniform_if_required("diffuseModel", diffuse_model_to_id(self.diffuse_model));
         viewer.tone_mapping().use_uniforms(program);
         viewer.color_mapping().use_uniforms(program);
         program.use_uniform_if_required("cameraPosition", viewer.position());
+        program.use_uniform_if_required(
+            "doubleSidedLighting",
+            if self.double_sided_lighting {
+                1.0f32
+            } else {
+                0.0
+            },
+        );
         for (i, light) in lights.iter().enumerate() {
             light.use_uniforms(program, i as u32);
         }
+        program.use_uniform_if_required("logExposure", viewer.exposure().log2());
         program.use_uniform_if_required("metallic", self.metallic);
         program.use_uniform_if_required("roughness", self.roughness);
+        self.texture_mapping.use_uniforms(program);
         if program.requires_uniform("albedoTexture") {
             if let Some(ref texture) = self.albedo_texture {
                 program.use_uniform("albedoTexTransform", texture.transformation);
@@ -227,15 +410,70 @@ impl Material for PhysicalMaterial {
                 program.use_texture("normalTexture", texture);
             }
         }
+        if program.requires_uniform("heightTexture") {
+            if let Some(ref texture) = self.height_texture {
+                program.use_uniform("heightTexTransform", texture.transformation);
+                program.use_uniform("parallaxScale", self.parallax_scale);
+                program.use_uniform("parallaxSteps", self.parallax_steps.max(1) as f32);
+                program.use_uniform_if_required(
+                    "parallaxClip",
+                    if self.parallax_clip { 1.0f32 } else { 0.0 },
+                );
+                program.use_texture("heightTexture", texture);
+            }
+        }
 
         program.use_uniform("albedo", self.albedo.to_linear_srgb());
         program.use_uniform("emissive", self.emissive.to_linear_srgb());
+        program.use_uniform_if_required("colorOverride", Vec4::zero());
+        program.use_uniform_if_required("emissiveOverride", Vec4::zero());
         if program.requires_uniform("emissiveTexture") {
             if let Some(ref texture) = self.emissive_texture {
                 program.use_uniform("emissiveTexTransform", texture.transformation);
                 program.use_texture("emissiveTexture", texture);
             }
         }
+        if program.requires_uniform("lightmapTexture") {
+            if let Some(ref texture) = self.lightmap_texture {
+                program.use_uniform("lightmapTexTransform", texture.transformation);
+                program.use_texture("lightmapTexture", texture);
+            }
+        }
+        if self.clearcoat > 0.0 {
+            program.use_uniform_if_required("clearcoat", self.clearcoat);
+            program.use_uniform_if_required("clearcoatRoughness", self.clearcoat_roughness);
+            if program.requires_uniform("clearcoatTexture") {
+                if let Some(ref texture) = self.clearcoat_texture {
+                    program.use_uniform("clearcoatTexTransform", texture.transformation);
+                    program.use_texture("clearcoatTexture", texture);
+                }
+            }
+            if program.requires_uniform("clearcoatNormalTexture") {
+                if let Some(ref texture) = self.clearcoat_normal_texture {
+                    program.use_uniform("clearcoatNormalTexTransform", texture.transformation);
+                    program.use_texture("clearcoatNormalTexture", texture);
+                }
+            }
+        }
+        if self.anisotropy != 0.0 {
+            program.use_uniform_if_required("anisotropy", self.anisotropy.clamp(-1.0, 1.0));
+            program.use_uniform_if_required("anisotropyRotation", self.anisotropy_rotation);
+        }
+        if let Some(ref subsurface) = self.subsurface {
+            program.use_uniform_if_required(
+                "subsurfaceColor",
+                subsurface.color.to_linear_srgb().truncate(),
+            );
+            program.use_uniform_if_required("subsurfaceRadius", subsurface.radius);
+            program.use_uniform_if_required("subsurfaceThickness", subsurface.thickness);
+            program.use_uniform_if_required("subsurfaceTransmission", subsurface.transmission);
+            if program.requires_uniform("thicknessTexture") {
+                if let Some(ref texture) = subsurface.thickness_texture {
+                    program.use_uniform("thicknessTexTransform", texture.transformation);
+                    program.use_texture("thicknessTexture", texture);
+                }
+            }
+        }
     }
 
     fn render_states(&self) -> RenderStates {
@@ -261,6 +499,10 @@ impl Default for PhysicalMaterial {
             metallic_roughness_texture: None,
             normal_texture: None,
             normal_scale: 1.0,
+            height_texture: None,
+            parallax_scale: 0.05,
+            parallax_steps: 16,
+            parallax_clip: false,
             occlusion_texture: None,
             occlusion_strength: 1.0,
             render_states: RenderStates::default(),
@@ -268,6 +510,17 @@ impl Default for PhysicalMaterial {
             emissive: Srgba::BLACK,
             emissive_texture: None,
             lighting_model: LightingModel::Blinn,
+            diffuse_model: DiffuseModel::default(),
+            lightmap_texture: None,
+            subsurface: None,
+            clearcoat: 0.0,
+            clearcoat_roughness: 0.0,
+            clearcoat_texture: None,
+            clearcoat_normal_texture: None,
+            anisotropy: 0.0,
+            anisotropy_rotation: 0.0,
+            double_sided_lighting: true,
+            texture_mapping: TextureMapping::default(),
         }
     }
 }
@@ -0,0 +1,29 @@
+#include <three-d/tone_mapping>
+#include <three-d/color_mapping>
+
+uniform vec3 cameraPosition;
+uniform vec4 surfaceColor;
+uniform float roughness;
+uniform float cavityStrength;
+uniform float edgeStrength;
+
+in vec3 pos;
+in vec3 nor;
+in vec4 col;
+
+layout (location = 0) out vec4 outColor;
+
+void main()
+{
+    vec3 normal = normalize(gl_FrontFacing ? nor : -nor);
+
+    // The red channel is the ambient occlusion, the green and blue channels are the convex and concave curvature
+    float cavity = mix(1.0, col.r, cavityStrength);
+    float edge = edgeStrength * (col.b - col.g);
+    vec3 color = surfaceColor.rgb * cavity * clamp(1.0 + edge, 0.0, 2.0);
+
+    outColor.rgb = calculate_lighting(cameraPosition, color, pos, normal, 0.0, roughness, 1.0);
+    outColor.rgb = tone_mapping(outColor.rgb);
+    outColor.rgb = color_mapping(outColor.rgb);
+    outColor.a = surfaceColor.a;
+}
@@ -1,4 +1,9 @@
+#include <three-d/shared>
+#include <three-d/color_mapping>
+
 uniform vec4 surfaceColor;
+uniform vec4 colorOverride;
+uniform vec4 emissiveOverride;
 
 #ifdef USE_TEXTURE
 uniform sampler2D tex;
@@ -12,10 +17,31 @@ layout (location = 0) out vec4 outColor;
 void main()
 {
     outColor = surfaceColor * col;
+
+    #ifdef PREMULTIPLIED_ALPHA
+    outColor.rgb *= outColor.a;
+    #endif
     
     #ifdef USE_TEXTURE
+    #ifdef USE_TRIPLANAR
+    outColor *= triplanar_texture(tex, textureTransformation, pos, normalize(nor));
+    #else
     outColor *= texture(tex, (textureTransformation * vec3(uvs, 1.0)).xy);
     #endif
+    #endif
 
-    outColor.rgb = color_mapping(outColor.rgb);
+    #ifdef PREMULTIPLIED_ALPHA
+    // The overrides and the color mapping are applied to the straight color before it is premultiplied again
+    vec3 color = outColor.a > 0.0 ? outColor.rgb / outColor.a : vec3(0.0);
+    #else
+    vec3 color = outColor.rgb;
+    #endif
+
+    color = mix(color, colorOverride.rgb, colorOverride.a) + emissiveOverride.a * emissiveOverride.rgb;
+
+    #ifdef PREMULTIPLIED_ALPHA
+    outColor.rgb = color_mapping(color) * outColor.a;
+    #else
+    outColor.rgb = color_mapping(color);
+    #endif
 }
\ No newline at end of file
@@ -1,3 +1,5 @@
+#include <three-d/shared>
+
 
 uniform float metallic;
 uniform float roughness;
@@ -3,12 +3,28 @@ uniform vec3 eye;
 uniform float minDistance;
 uniform float maxDistance;
 
+#ifdef USE_ALPHA_CUTOUT
+uniform float alphaCutout;
+uniform float alpha;
+#ifdef USE_TEXTURE
+uniform sampler2D alphaTexture;
+uniform mat3 alphaTexTransform;
+#endif
+#endif
+
 in vec3 pos;
 
 layout (location = 0) out vec4 outColor;
 
 void main()
 {
+#ifdef USE_ALPHA_CUTOUT
+    float a = alpha;
+#ifdef USE_TEXTURE
+    a *= texture(alphaTexture, (alphaTexTransform * vec3(uvs, 1.0)).xy).a;
+#endif
+    if (a < alphaCutout) discard;
+#endif
     float dist = (distance(pos, eye) - minDistance) / (maxDistance - minDistance);
     outColor = vec4(dist, dist, dist, 1.0);
-}
\ No newline at end of file
+}
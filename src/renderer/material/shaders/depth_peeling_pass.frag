@@ -0,0 +1,29 @@
+uniform float peelDepthDirection;
+#ifdef USE_PEEL_DEPTH
+uniform sampler2D peelDepthTexture;
+#endif
+#ifdef USE_DEPTH
+uniform vec2 peelTexelSize;
+#endif
+
+void main()
+{
+#ifdef USE_DEPTH
+    if ((gl_FragCoord.z - sample_depth(gl_FragCoord.xy * peelTexelSize)) * peelDepthDirection > 0.0) {
+        discard;
+    }
+#endif
+#ifdef USE_PEEL_DEPTH
+    // Only the fragments behind the layer peeled in the previous pass remain
+    if ((gl_FragCoord.z - texelFetch(peelDepthTexture, ivec2(gl_FragCoord.xy), 0).x) * peelDepthDirection <= 0.0) {
+        discard;
+    }
+#endif
+    material_main();
+    float alpha = clamp(outColor.a, 0.0, 1.0);
+#ifdef PREMULTIPLIED_ALPHA
+    outColor = vec4(outColor.rgb, alpha);
+#else
+    outColor = vec4(outColor.rgb * alpha, alpha);
+#endif
+}
@@ -0,0 +1,48 @@
+#include <three-d/tone_mapping>
+#include <three-d/color_mapping>
+
+uniform float roughness;
+uniform vec3 cameraPosition;
+uniform vec4 rootColor;
+uniform vec4 tipColor;
+uniform float thickness;
+uniform float occlusionStrength;
+uniform sampler2D densityTexture;
+uniform mat3 densityTexTransform;
+uniform vec4 colorOverride;
+uniform vec4 emissiveOverride;
+
+in vec3 pos;
+in vec3 nor;
+in vec2 uvs;
+in vec4 col;
+in float shell_height;
+
+layout (location = 0) out vec4 outColor;
+
+void main()
+{
+    // The inner shell is the skin and is never discarded
+    if (shell_height > 0.0) {
+        vec2 tex_uvs = (densityTexTransform * vec3(uvs, 1.0)).xy;
+        float strand_length = texture(densityTexture, tex_uvs).r;
+        if (strand_length < shell_height) discard;
+
+        // Each texel contains a strand which becomes thinner towards the tip
+        vec2 cell = 2.0 * fract(tex_uvs * vec2(textureSize(densityTexture, 0))) - 1.0;
+        float radius = thickness * (1.0 - shell_height / strand_length);
+        if (length(cell) > radius) discard;
+    }
+
+    vec4 surface_color = mix(rootColor, tipColor, shell_height) * col;
+    surface_color.rgb = mix(surface_color.rgb, colorOverride.rgb, colorOverride.a);
+
+    // Less light reaches the roots of the strands
+    float occlusion = mix(1.0 - occlusionStrength, 1.0, shell_height);
+    vec3 normal = normalize(gl_FrontFacing ? nor : -nor);
+
+    outColor.rgb = emissiveOverride.a * emissiveOverride.rgb + calculate_lighting(cameraPosition, surface_color.rgb, pos, normal, 0.0, roughness, occlusion);
+    outColor.rgb = tone_mapping(outColor.rgb);
+    outColor.rgb = color_mapping(outColor.rgb);
+    outColor.a = surface_color.a;
+}
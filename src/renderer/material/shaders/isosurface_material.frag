@@ -1,3 +1,6 @@
+#include <three-d/tone_mapping>
+#include <three-d/color_mapping>
+
 uniform vec3 cameraPosition;
 uniform vec4 surfaceColor;
 uniform float metallic;
@@ -0,0 +1,26 @@
+#include <three-d/color_mapping>
+
+
+uniform float jointIndex;
+
+in vec4 joints;
+in vec4 weights;
+
+layout (location = 0) out vec4 outColor;
+
+vec3 heat_map(float value)
+{
+    return clamp(vec3(2.0 * value - 0.5, 1.5 - abs(2.0 * value - 1.0) * 2.0, 1.5 - 2.0 * value), 0.0, 1.0);
+}
+
+void main()
+{
+    float influence = 0.0;
+    for (int i = 0; i < 4; i++) {
+        if (abs(joints[i] - jointIndex) < 0.5) {
+            influence += weights[i];
+        }
+    }
+    outColor = vec4(heat_map(clamp(influence, 0.0, 1.0)), 1.0);
+    outColor.rgb = color_mapping(outColor.rgb);
+}
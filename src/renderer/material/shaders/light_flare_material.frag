@@ -0,0 +1,63 @@
+#include <three-d/tone_mapping>
+#include <three-d/color_mapping>
+
+uniform sampler2D flareTexture;
+uniform mat3 flareTextureTransformation;
+uniform sampler2D streakTexture;
+uniform mat3 streakTextureTransformation;
+uniform float streakLength;
+
+in vec2 uvs;
+in vec4 col;
+
+#ifdef USE_OCCLUSION
+// The center of the flare in screen space and the depth value of the light source, which is outside [0, 1] when the light source is behind the camera
+uniform vec3 flareCenter;
+// 1 for standard depth and -1 for reversed depth
+uniform float depthDirection;
+uniform vec2 occlusionRadius;
+
+// The fraction of a grid of depth samples around the center of the flare which are not in front of the light source
+float flare_visibility()
+{
+    if (flareCenter.z < 0.0 || flareCenter.z > 1.0) {
+        return 0.0;
+    }
+    float visible = 0.0;
+    for (int y = -2; y <= 2; y++) {
+        for (int x = -2; x <= 2; x++) {
+            vec2 uv = flareCenter.xy + 0.5 * vec2(float(x), float(y)) * occlusionRadius;
+            if (uv.x >= 0.0 && uv.x <= 1.0 && uv.y >= 0.0 && uv.y <= 1.0 && depthDirection * (flareCenter.z - sample_depth(uv)) <= 0.0) {
+                visible += 1.0;
+            }
+        }
+    }
+    return visible / 25.0;
+}
+#endif
+
+layout (location = 0) out vec4 outColor;
+
+void main()
+{
+    // The quad is stretched horizontally by the streak length, while the flare texture keeps its aspect ratio in the center
+    float aspect = max(streakLength, 1.0);
+    vec2 flareUvs = vec2((uvs.x - 0.5) * aspect + 0.5, uvs.y);
+    vec4 flare = vec4(0.0);
+    if (flareUvs.x >= 0.0 && flareUvs.x <= 1.0) {
+        flare = texture(flareTexture, (flareTextureTransformation * vec3(flareUvs, 1.0)).xy);
+    }
+    vec3 color = flare.rgb * flare.a;
+    if (streakLength > 0.0) {
+        vec4 streak = texture(streakTexture, (streakTextureTransformation * vec3(uvs, 1.0)).xy);
+        color += streak.rgb * streak.a;
+    }
+    color *= col.rgb;
+
+#ifdef USE_OCCLUSION
+    color *= flare_visibility();
+#endif
+
+    // The alpha is zero so the additive blending leaves the alpha of the render target unchanged
+    outColor = vec4(color_mapping(tone_mapping(color)), 0.0);
+}
@@ -0,0 +1,28 @@
+#ifdef USE_DEPTH
+uniform vec2 oitTexelSize;
+uniform float oitDepthDirection;
+#endif
+
+void main()
+{
+#ifdef USE_DEPTH
+    if ((gl_FragCoord.z - sample_depth(gl_FragCoord.xy * oitTexelSize)) * oitDepthDirection > 0.0) {
+        discard;
+    }
+#endif
+    material_main();
+    float alpha = clamp(outColor.a, 0.0, 1.0);
+#ifdef ACCUMULATION
+    // The weight function from McGuire and Bavoil (2013), equation 10, scaled down to avoid overflowing the half float target with many layers.
+    // Closer fragments get a larger weight, so the color of the front most layers dominates.
+    float z = abs(1.0 / gl_FragCoord.w);
+    float weight = clamp(0.003 / (1e-5 + pow(z / 200.0, 4.0)), 1e-3, 300.0);
+#ifdef PREMULTIPLIED_ALPHA
+    outColor = vec4(outColor.rgb, alpha) * weight;
+#else
+    outColor = vec4(outColor.rgb * alpha, alpha) * weight;
+#endif
+#else
+    outColor = vec4(alpha);
+#endif
+}
@@ -1,7 +1,11 @@
+#include <three-d/tone_mapping>
+#include <three-d/color_mapping>
+
 
 uniform float metallic;
 uniform float roughness;
 uniform vec3 cameraPosition;
+uniform float doubleSidedLighting;
 
 uniform vec4 albedo;
 #ifdef USE_ALBEDO_TEXTURE
@@ -10,6 +14,8 @@ uniform mat3 albedoTexTransform;
 #endif
 
 uniform vec4 emissive;
+uniform vec4 colorOverride;
+uniform vec4 emissiveOverride;
 #ifdef USE_EMISSIVE_TEXTURE
 uniform sampler2D emissiveTexture;
 uniform mat3 emissiveTexTransform;
@@ -32,50 +38,227 @@ uniform mat3 normalTexTransform;
 uniform float normalScale;
 #endif
 
+#ifdef USE_HEIGHT_TEXTURE
+uniform sampler2D heightTexture;
+uniform mat3 heightTexTransform;
+uniform float parallaxScale;
+uniform float parallaxSteps;
+uniform float parallaxClip;
+#endif
+
+#ifdef USE_CLEARCOAT
+uniform float clearcoat;
+uniform float clearcoatRoughness;
+#ifdef USE_CLEARCOAT_TEXTURE
+uniform sampler2D clearcoatTexture;
+uniform mat3 clearcoatTexTransform;
+#endif
+#ifdef USE_CLEARCOAT_NORMAL_TEXTURE
+uniform sampler2D clearcoatNormalTexture;
+uniform mat3 clearcoatNormalTexTransform;
+#endif
+#endif
+
+#ifdef USE_ANISOTROPY
+uniform float anisotropy;
+uniform float anisotropyRotation;
+#endif
+
+#ifdef USE_LIGHTMAP_TEXTURE
+uniform sampler2D lightmapTexture;
+uniform mat3 lightmapTexTransform;
+#endif
+
+#ifdef USE_SUBSURFACE
+uniform vec3 subsurfaceColor;
+uniform float subsurfaceRadius;
+uniform float subsurfaceThickness;
+uniform float subsurfaceTransmission;
+#ifdef USE_THICKNESS_TEXTURE
+uniform sampler2D thicknessTexture;
+uniform mat3 thicknessTexTransform;
+#endif
+#endif
+
 in vec3 pos;
 in vec3 nor;
 in vec4 col;
 
 layout (location = 0) out vec4 outColor;
 
+#ifdef USE_UVS
+vec4 sample_texture(sampler2D tex, mat3 transform, vec2 uv)
+{
+#ifdef USE_TRIPLANAR
+    // The texture is projected using the normal of the geometry, so both sides of the surface look the same
+    return triplanar_texture(tex, transform, pos, normalize(nor));
+#else
+    return texture(tex, (transform * vec3(uv, 1.0)).xy);
+#endif
+}
+#endif
+
+#ifdef USE_HEIGHT_TEXTURE
+float parallax_depth(vec2 uv, vec2 dx, vec2 dy)
+{
+    // The gradients of the original texture coordinates are used since the derivatives are undefined inside the non-uniform loops
+    return 1.0 - textureGrad(heightTexture, (heightTexTransform * vec3(uv, 1.0)).xy, dx, dy).r;
+}
+
+// Ray marches the height field from the top of the surface along the view direction given in tangent space and returns the texture coordinates where the ray hits the height field
+vec2 parallax_occlusion_mapping(vec2 uv, vec3 view_direction)
+{
+    vec2 dx = dFdx((heightTexTransform * vec3(uv, 1.0)).xy);
+    vec2 dy = dFdy((heightTexTransform * vec3(uv, 1.0)).xy);
+    float layer_count = max(floor(mix(parallaxSteps, 0.5 * parallaxSteps, abs(view_direction.z))), 1.0);
+    float layer_depth = 1.0 / layer_count;
+    vec2 delta = parallaxScale * view_direction.xy / max(view_direction.z, 0.05) / layer_count;
+
+    // Step through the layers until the ray is below the height field
+    vec2 current_uv = uv;
+    float current_layer = 0.0;
+    float current_depth = parallax_depth(current_uv, dx, dy);
+    for (int i = 0; i < int(layer_count) && current_layer < current_depth; i++) {
+        current_uv -= delta;
+        current_layer += layer_depth;
+        current_depth = parallax_depth(current_uv, dx, dy);
+    }
+
+    // Refine the intersection between the last two layers using a binary search
+    vec2 previous_uv = current_uv + delta;
+    float previous_layer = current_layer - layer_depth;
+    for (int i = 0; i < 5; i++) {
+        vec2 middle_uv = 0.5 * (previous_uv + current_uv);
+        float middle_layer = 0.5 * (previous_layer + current_layer);
+        if (middle_layer < parallax_depth(middle_uv, dx, dy)) {
+            previous_uv = middle_uv;
+            previous_layer = middle_layer;
+        } else {
+            current_uv = middle_uv;
+            current_layer = middle_layer;
+        }
+    }
+    return current_uv;
+}
+#endif
+
 void main()
 {
+    // The normal is flipped on backfacing triangles unless only the front side is lit
+    bool front_facing = gl_FrontFacing || doubleSidedLighting < 0.5;
+#ifdef USE_UVS
+    vec2 tex_uvs = uvs;
+#endif
+#ifdef USE_HEIGHT_TEXTURE
+    vec3 view_direction = normalize(cameraPosition - pos);
+    vec3 view_tangent = normalize(front_facing ? tang : -tang);
+    vec3 view_bitangent = normalize(front_facing ? bitang : -bitang);
+    vec3 view_normal = normalize(front_facing ? nor : -nor);
+    tex_uvs = parallax_occlusion_mapping(uvs, vec3(dot(view_direction, view_tangent), dot(view_direction, view_bitangent), dot(view_direction, view_normal)));
+    if (parallaxClip > 0.5 && (any(lessThan(tex_uvs, vec2(0.0))) || any(greaterThan(tex_uvs, vec2(1.0))))) {
+        discard;
+    }
+#endif
+
     vec4 surface_color = albedo * col;
 #ifdef USE_ALBEDO_TEXTURE
-    vec4 c = texture(albedoTexture, (albedoTexTransform * vec3(uvs, 1.0)).xy);
+    vec4 c = sample_texture(albedoTexture, albedoTexTransform, tex_uvs);
     #ifdef ALPHACUT
         if (c.a < acut) discard;
     #endif
     surface_color *= c;
 #endif
+    surface_color.rgb = mix(surface_color.rgb, colorOverride.rgb, colorOverride.a);
 
     float metallic_factor = metallic;
     float roughness_factor = roughness;
 #ifdef USE_METALLIC_ROUGHNESS_TEXTURE
-    vec2 t = texture(metallicRoughnessTexture, (metallicRoughnessTexTransform * vec3(uvs, 1.0)).xy).gb;
+    vec2 t = sample_texture(metallicRoughnessTexture, metallicRoughnessTexTransform, tex_uvs).gb;
     roughness_factor *= t.x;
     metallic_factor *= t.y;
 #endif
 
     float occlusion = 1.0;
 #ifdef USE_OCCLUSION_TEXTURE
-    occlusion = mix(1.0, texture(occlusionTexture, (occlusionTexTransform * vec3(uvs, 1.0)).xy).r, occlusionStrength);
+    occlusion = mix(1.0, sample_texture(occlusionTexture, occlusionTexTransform, tex_uvs).r, occlusionStrength);
 #endif
 
-    vec3 normal = normalize(gl_FrontFacing ? nor : -nor);
+    vec3 normal = normalize(front_facing ? nor : -nor);
 #ifdef USE_NORMAL_TEXTURE
-    vec3 tangent = normalize(gl_FrontFacing ? tang : -tang);
-    vec3 bitangent = normalize(gl_FrontFacing ? bitang : -bitang);
+#ifdef USE_TRIPLANAR
+    normal = triplanar_normal(normalTexture, normalTexTransform, normalScale, pos, normalize(nor));
+    normal = front_facing ? normal : -normal;
+#else
+    vec3 tangent = normalize(front_facing ? tang : -tang);
+    vec3 bitangent = normalize(front_facing ? bitang : -bitang);
     mat3 tbn = mat3(tangent, bitangent, normal);
-    normal = tbn * ((2.0 * texture(normalTexture, (normalTexTransform * vec3(uvs, 1.0)).xy).xyz - 1.0) * vec3(normalScale, normalScale, 1.0));
+    normal = tbn * ((2.0 * texture(normalTexture, (normalTexTransform * vec3(tex_uvs, 1.0)).xy).xyz - 1.0) * vec3(normalScale, normalScale, 1.0));
+#endif
+#endif
+
+#ifdef USE_ANISOTROPY
+    // The derivatives are computed outside of the branch since they are undefined inside non-uniform control flow
+    vec3 dp_dx = dFdx(pos);
+    vec3 dp_dy = dFdy(pos);
+    vec2 duv_dx = dFdx(uvs);
+    vec2 duv_dy = dFdy(uvs);
+    vec3 anisotropy_direction = front_facing ? tang : -tang;
+    if (dot(anisotropy_direction, anisotropy_direction) < 0.25) {
+        // The geometry has no tangents, so the tangent is derived from the screen space derivatives of the position and uv coordinates
+        float det = duv_dx.x * duv_dy.y - duv_dy.x * duv_dx.y;
+        anisotropy_direction = (dp_dx * duv_dy.y - dp_dy * duv_dx.y) * sign(det);
+        if (dot(anisotropy_direction, anisotropy_direction) < 1e-12) {
+            // Neither tangents nor uv coordinates, so any direction orthogonal to the normal is used
+            anisotropy_direction = abs(normal.y) < 0.99 ? cross(vec3(0.0, 1.0, 0.0), normal) : vec3(1.0, 0.0, 0.0);
+        }
+    }
+    vec3 anisotropy_t = normalize(anisotropy_direction - normal * dot(normal, anisotropy_direction));
+    vec3 anisotropy_b = cross(normal, anisotropy_t);
+    anisotropy_tangent = cos(anisotropyRotation) * anisotropy_t + sin(anisotropyRotation) * anisotropy_b;
+    anisotropy_bitangent = cross(normal, anisotropy_tangent);
+    anisotropy_factor = anisotropy;
+#endif
+
+#ifdef USE_CLEARCOAT
+    clearcoat_factor = clearcoat;
+    #ifdef USE_CLEARCOAT_TEXTURE
+    clearcoat_factor *= sample_texture(clearcoatTexture, clearcoatTexTransform, tex_uvs).r;
+    #endif
+    clearcoat_roughness = clearcoatRoughness;
+    clearcoat_normal = normalize(front_facing ? nor : -nor);
+    #ifdef USE_CLEARCOAT_NORMAL_TEXTURE
+    #ifdef USE_TRIPLANAR
+    clearcoat_normal = triplanar_normal(clearcoatNormalTexture, clearcoatNormalTexTransform, 1.0, pos, normalize(nor));
+    clearcoat_normal = front_facing ? clearcoat_normal : -clearcoat_normal;
+    #else
+    mat3 clearcoat_tbn = mat3(normalize(front_facing ? tang : -tang), normalize(front_facing ? bitang : -bitang), clearcoat_normal);
+    clearcoat_normal = normalize(clearcoat_tbn * (2.0 * texture(clearcoatNormalTexture, (clearcoatNormalTexTransform * vec3(tex_uvs, 1.0)).xy).xyz - 1.0));
+    #endif
+    #endif
+#endif
+
+#ifdef USE_SUBSURFACE
+    // The curvature is estimated from the screen space derivatives of the interpolated normal and position
+    float curvature = length(fwidth(nor)) / max(length(fwidth(pos)), 0.0001);
+    subsurface_wrap = subsurfaceColor * saturate(subsurfaceRadius * curvature);
+    float thickness = subsurfaceThickness;
+    #ifdef USE_THICKNESS_TEXTURE
+    thickness *= sample_texture(thicknessTexture, thicknessTexTransform, tex_uvs).r;
+    #endif
+    subsurface_transmittance = subsurfaceTransmission * exp(-thickness / max(subsurfaceRadius * subsurfaceColor, vec3(0.0001)));
 #endif
 
     vec3 total_emissive = emissive.rgb;
 #ifdef USE_EMISSIVE_TEXTURE
-    total_emissive *= texture(emissiveTexture, (emissiveTexTransform * vec3(uvs, 1.0)).xy).rgb;
+    total_emissive *= sample_texture(emissiveTexture, emissiveTexTransform, tex_uvs).rgb;
 #endif
+    total_emissive = mix(total_emissive, emissiveOverride.rgb, emissiveOverride.a);
 
     outColor.rgb = total_emissive + calculate_lighting(cameraPosition, surface_color.rgb, pos, normal, metallic_factor, roughness_factor, occlusion);
+#ifdef USE_LIGHTMAP_TEXTURE
+    vec3 baked_lighting = texture(lightmapTexture, (lightmapTexTransform * vec3(lightmap_uvs, 1.0)).xy).rgb;
+    outColor.rgb += mix(surface_color.rgb, vec3(0.0), metallic_factor) * baked_lighting;
+#endif
     outColor.rgb = tone_mapping(outColor.rgb);
     outColor.rgb = color_mapping(outColor.rgb);
     outColor.a = surface_color.a;
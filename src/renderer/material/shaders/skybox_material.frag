@@ -1,12 +1,18 @@
+#include <three-d/shared>
+#include <three-d/tone_mapping>
+#include <three-d/color_mapping>
+
 uniform samplerCube texture0;
 uniform int isHDR;
+uniform mat3 rotation;
+uniform float intensity;
 
 in vec3 coords;
 
 layout (location = 0) out vec4 outColor;
 
 void main() {
-    outColor = vec4(texture(texture0, coords).rgb, 1.0);
+    outColor = vec4(intensity * texture(texture0, rotation * coords).rgb, 1.0);
     outColor.rgb = tone_mapping(outColor.rgb);
     outColor.rgb = color_mapping(outColor.rgb);
 }
@@ -0,0 +1,58 @@
+#include <three-d/tone_mapping>
+#include <three-d/color_mapping>
+
+uniform vec3 cameraPosition;
+uniform vec4 surfaceColor;
+uniform float bands;
+uniform vec4 specularColor;
+uniform float shininess;
+uniform float specularCutoff;
+#ifdef USE_RAMP
+uniform sampler2D rampTexture;
+#endif
+
+in vec3 pos;
+in vec3 nor;
+in vec4 col;
+
+layout (location = 0) out vec4 outColor;
+
+// Quantizes the light returned by one light, which is the irradiance since USE_TOON is defined
+vec3 toon_light(vec3 light, vec3 surface_color)
+{
+    if (toon_irradiance.r < 0.0) {
+        // The light does not use calculate_light, for example an ambient light, so it is not quantized
+        return light * surface_color;
+    }
+    float strength = max(max(light.r, light.g), light.b);
+    if (strength <= 0.0) {
+        return vec3(0.0);
+    }
+    vec3 hue = light / strength;
+
+    // The shadow is applied after calculate_light, so the ratio to the irradiance from calculate_light is the visibility of the light
+    float unshadowed = max(max(toon_irradiance.r, toon_irradiance.g), toon_irradiance.b);
+    float visibility = unshadowed > 0.0 ? saturate(strength / unshadowed) : 0.0;
+
+#ifdef USE_RAMP
+    vec3 diffuse = texture(rampTexture, vec2(saturate(strength), 0.5)).rgb;
+#else
+    vec3 diffuse = vec3(min(floor(strength * bands), bands - 1.0) / max(bands - 1.0, 1.0));
+#endif
+    float specular = toon_specular * visibility > specularCutoff ? 1.0 : 0.0;
+    return hue * (diffuse * surface_color + specular * specularColor.rgb);
+}
+
+vec3 calculate_toon_lighting(vec3 surface_color, vec3 position, vec3 normal, vec3 view_direction);
+
+void main()
+{
+    vec3 normal = normalize(gl_FrontFacing ? nor : -nor);
+    vec3 color = surfaceColor.rgb * col.rgb;
+    toon_shininess = shininess;
+
+    outColor.rgb = calculate_toon_lighting(color, pos, normal, normalize(cameraPosition - pos));
+    outColor.rgb = tone_mapping(outColor.rgb);
+    outColor.rgb = color_mapping(outColor.rgb);
+    outColor.a = surfaceColor.a * col.a;
+}
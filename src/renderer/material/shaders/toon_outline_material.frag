@@ -0,0 +1,10 @@
+#include <three-d/color_mapping>
+
+uniform vec4 outlineColor;
+
+layout (location = 0) out vec4 outColor;
+
+void main()
+{
+    outColor = vec4(color_mapping(outlineColor.rgb), outlineColor.a);
+}
@@ -0,0 +1,51 @@
+uniform float triplanarScale;
+uniform float triplanarSharpness;
+
+// The weight of the projections along the x, y and z axes
+vec3 triplanar_weights(vec3 normal)
+{
+    vec3 weights = pow(abs(normal), vec3(triplanarSharpness));
+    return weights / max(weights.x + weights.y + weights.z, 0.0001);
+}
+
+vec3 triplanar_signs(vec3 normal)
+{
+    return vec3(normal.x < 0.0 ? -1.0 : 1.0, normal.y < 0.0 ? -1.0 : 1.0, normal.z < 0.0 ? -1.0 : 1.0);
+}
+
+// The texture coordinates of the projection of the position onto the plane spanned by u and v
+vec2 triplanar_uv(mat3 transform, vec3 position, vec3 u, vec3 v)
+{
+    return (transform * vec3(triplanarScale * vec2(dot(position, u), dot(position, v)), 1.0)).xy;
+}
+
+// Samples the texture projected along each of the world axes, where the u and v directions of each projection are chosen such that u x v points away from the surface,
+// so the texture is not mirrored on the opposite sides
+vec4 triplanar_texture(sampler2D tex, mat3 transform, vec3 position, vec3 normal)
+{
+    vec3 weights = triplanar_weights(normal);
+    vec3 signs = triplanar_signs(normal);
+    return weights.x * texture(tex, triplanar_uv(transform, position, vec3(0.0, 0.0, -signs.x), vec3(0.0, 1.0, 0.0)))
+        + weights.y * texture(tex, triplanar_uv(transform, position, vec3(signs.y, 0.0, 0.0), vec3(0.0, 0.0, -1.0)))
+        + weights.z * texture(tex, triplanar_uv(transform, position, vec3(signs.z, 0.0, 0.0), vec3(0.0, 1.0, 0.0)));
+}
+
+// Reorients the tangent space normal from the projection onto the plane spanned by u and v to the surface using a whiteout blend
+vec3 triplanar_plane_normal(sampler2D tex, mat3 transform, float normal_scale, vec3 position, vec3 normal, vec3 u, vec3 v)
+{
+    vec3 w = cross(u, v);
+    vec3 tangent_normal = (2.0 * texture(tex, triplanar_uv(transform, position, u, v)).xyz - 1.0) * vec3(normal_scale, normal_scale, 1.0);
+    vec3 surface_normal = vec3(dot(normal, u), dot(normal, v), abs(dot(normal, w)));
+    tangent_normal = vec3(tangent_normal.xy + surface_normal.xy, tangent_normal.z * surface_normal.z);
+    return tangent_normal.x * u + tangent_normal.y * v + tangent_normal.z * w;
+}
+
+// Samples the tangent space normal map projected along each of the world axes and returns the blended world space normal
+vec3 triplanar_normal(sampler2D tex, mat3 transform, float normal_scale, vec3 position, vec3 normal)
+{
+    vec3 weights = triplanar_weights(normal);
+    vec3 signs = triplanar_signs(normal);
+    return normalize(weights.x * triplanar_plane_normal(tex, transform, normal_scale, position, normal, vec3(0.0, 0.0, -signs.x), vec3(0.0, 1.0, 0.0))
+        + weights.y * triplanar_plane_normal(tex, transform, normal_scale, position, normal, vec3(signs.y, 0.0, 0.0), vec3(0.0, 0.0, -1.0))
+        + weights.z * triplanar_plane_normal(tex, transform, normal_scale, position, normal, vec3(signs.z, 0.0, 0.0), vec3(0.0, 1.0, 0.0)));
+}
@@ -4,6 +4,8 @@ use std::sync::Arc;
 
 pub struct SkyboxMaterial {
     pub texture: Arc<TextureCubeMap>,
+    pub rotation: Quat,
+    pub intensity: f32,
 }
 
 impl Material for SkyboxMaterial {
@@ -12,19 +14,18 @@ impl Material for SkyboxMaterial {
     }
 
     fn fragment_shader_source(&self, _lights: &[&dyn Light]) -> String {
-        format!(
-            "{}{}{}{}",
-            include_str!("../../core/shared.frag"),
-            ToneMapping::fragment_shader_source(),
-            ColorMapping::fragment_shader_source(),
-            include_str!("shaders/skybox_material.frag")
-        )
+        include_str!("shaders/skybox_material.frag").to_string()
     }
 
     fn use_uniforms(&self, program: &Program, viewer: &dyn Viewer, _lights: &[&dyn Light]) {
         viewer.tone_mapping().use_uniforms(program);
         viewer.color_mapping().use_uniforms(program);
         program.use_texture_cube("texture0", &self.texture);
+        program.use_uniform(
+            "rotation",
+            Mat3::from(self.rotation.normalize().conjugate()),
+        );
+        program.use_uniform("intensity", self.intensity);
     }
 
     fn render_states(&self) -> RenderStates {
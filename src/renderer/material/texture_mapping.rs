@@ -0,0 +1,40 @@
+use crate::renderer::*;
+
+///
+/// How the textures of a material, for example [PhysicalMaterial] and [ColorMaterial], are mapped onto the surface of a [Geometry].
+///
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub enum TextureMapping {
+    /// The textures are sampled using the uv coordinates of the geometry.
+    #[default]
+    Uv,
+    /// The textures are projected onto the surface along the three world axes and blended depending on the world normal,
+    /// which makes it possible to texture geometry without uv coordinates, for example procedurally generated meshes and terrain.
+    /// Tangent space normal maps are reoriented for each projection, so the geometry does not need tangents either, but it must have normals.
+    /// The world position includes the transformation of the geometry, also the instance transformations of an [InstancedMesh],
+    /// so the texture is fixed in the world and does not move with the geometry.
+    /// The texture transformation is applied to the projected coordinates, except the instance texture transformations of an [InstancedMesh].
+    Triplanar {
+        /// The number of times the textures are repeated per world unit.
+        scale: f32,
+        /// How sharp the transition between the projections is, where higher values make the transition shorter and reduce the blurring where the projections are blended.
+        blend_sharpness: f32,
+    },
+}
+
+impl TextureMapping {
+    pub(super) fn is_triplanar(&self) -> bool {
+        matches!(self, Self::Triplanar { .. })
+    }
+
+    pub(super) fn use_uniforms(&self, program: &Program) {
+        if let Self::Triplanar {
+            scale,
+            blend_sharpness,
+        } = *self
+        {
+            program.use_uniform_if_required("triplanarScale", scale);
+            program.use_uniform_if_required("triplanarSharpness", blend_sharpness.max(1.0));
+        }
+    }
+}
@@ -0,0 +1,184 @@
+use crate::core::*;
+use crate::renderer::*;
+
+///
+/// A cel shaded material where the light from each light is quantized into a few bands of constant brightness, or mapped through a ramp texture,
+/// and the specular highlight is a single band with a hard edge, which gives a cartoon look.
+///
+/// The directional, spot and point lights are quantized individually, including their shadows, so a shadowed region falls into the darkest band instead of being darkened smoothly.
+/// The ambient and area lights are not quantized and are added to the quantized lights, so an ambient light can be used to brighten the darkest band.
+///
+/// The ink outline is rendered in a second pass by rendering the same geometries with the [ToonOutlineMaterial] returned from [ToonMaterial::outline_material],
+/// which renders the back faces of the geometry moved outwards along the normals. The geometry must have normals.
+///
+/// ```no_run
+/// # use three_d::*;
+/// # let context: Context = unimplemented!();
+/// # let camera: Camera = unimplemented!();
+/// # let light: DirectionalLight = unimplemented!();
+/// let material = ToonMaterial {
+///     color: Srgba::new_opaque(255, 120, 60),
+///     outline_width: 0.02,
+///     ..Default::default()
+/// };
+/// let mesh = Mesh::new(&context, &CpuMesh::sphere(32));
+/// let target = RenderTarget::screen(&context, 800, 600);
+/// target.render_with_material(&material, &camera, &mesh, &[&light]);
+/// if let Some(outline) = material.outline_material() {
+///     target.render_with_material(&outline, &camera, &mesh, &[]);
+/// }
+/// ```
+///
+#[derive(Clone)]
+pub struct ToonMaterial {
+    /// The color of the surface, which is multiplied with the vertex colors of the geometry, if any.
+    pub color: Srgba,
+    /// The number of bands the light of each light is quantized into, where the darkest band is no light. Ignored if a ramp texture is given.
+    pub bands: u32,
+    /// A texture which maps the light of each light, where the horizontal uv coordinate zero is no light and one is full light, to the light reflected by the surface.
+    /// Only the first row of the texture is used and the texture should use nearest interpolation to get hard edges between the bands.
+    pub ramp: Option<Texture2DRef>,
+    /// The color of the specular highlight. Black disables the highlight.
+    pub specular_color: Srgba,
+    /// The exponent of the specular term, where a higher value gives a smaller highlight.
+    pub shininess: f32,
+    /// The value in the range `[0..1]` of the specular term above which the surface is inside the highlight.
+    pub specular_cutoff: f32,
+    /// The width of the ink outline in world space units, see [ToonMaterial::outline_material]. Zero disables the outline.
+    pub outline_width: f32,
+    /// The color of the ink outline.
+    pub outline_color: Srgba,
+    /// Render states.
+    pub render_states: RenderStates,
+}
+
+impl ToonMaterial {
+    ///
+    /// Returns the material used for rendering the ink outline in a second pass, or `None` if the [ToonMaterial::outline_width] is zero.
+    ///
+    pub fn outline_material(&self) -> Option<ToonOutlineMaterial> {
+        (self.outline_width > 0.0).then_some(ToonOutlineMaterial {
+            color: self.outline_color,
+            width: self.outline_width,
+        })
+    }
+}
+
+impl Material for ToonMaterial {
+    fn id(&self) -> EffectMaterialId {
+        EffectMaterialId(EffectMaterialId::ToonMaterialBase.0 | self.ramp.is_some() as u16)
+    }
+
+    fn fragment_shader_source(&self, lights: &[&dyn Light]) -> String {
+        // Must be defined before the lighting code which uses it
+        let mut source = String::from("#define USE_TOON\n");
+        if self.ramp.is_some() {
+            source.push_str("#define USE_RAMP\n");
+        }
+        source.push_str(&lights_shader_source(lights));
+        source.push_str(include_str!("shaders/toon_material.frag"));
+
+        // Each light is quantized separately, where a negative irradiance means that the light did not set it
+        let mut calls = String::new();
+        for i in 0..lights.len() {
+            calls.push_str(&format!(
+                "toon_irradiance = vec3(-1.0);\ncolor += toon_light(calculate_lighting{i}(vec3(1.0), position, normal, view_direction, 0.0, 1.0, 1.0), surface_color);\n"
+            ));
+        }
+        source.push_str(&format!(
+            "
+            vec3 calculate_toon_lighting(vec3 surface_color, vec3 position, vec3 normal, vec3 view_direction)
+            {{
+                vec3 color = vec3(0.0);
+                {calls}
+                return exp2(logExposure) * color;
+            }}
+            "
+        ));
+        source
+    }
+
+    fn use_uniforms(&self, program: &Program, viewer: &dyn Viewer, lights: &[&dyn Light]) {
+        program
+            .use_uniform_if_required("lightingModel", lighting_model_to_id(LightingModel::Blinn));
+        viewer.tone_mapping().use_uniforms(program);
+        viewer.color_mapping().use_uniforms(program);
+        for (i, light) in lights.iter().enumerate() {
+            light.use_uniforms(program, i as u32);
+        }
+        program.use_uniform_if_required("logExposure", viewer.exposure().log2());
+        program.use_uniform_if_required("cameraPosition", viewer.position());
+        program.use_uniform("surfaceColor", self.color.to_linear_srgb());
+        program.use_uniform_if_required("bands", self.bands.max(1) as f32);
+        program.use_uniform_if_required("specularColor", self.specular_color.to_linear_srgb());
+        program.use_uniform_if_required("shininess", self.shininess);
+        program.use_uniform_if_required("specularCutoff", self.specular_cutoff);
+        if let Some(ref ramp) = self.ramp {
+            program.use_texture("rampTexture", ramp);
+        }
+    }
+
+    fn render_states(&self) -> RenderStates {
+        self.render_states
+    }
+
+    fn material_type(&self) -> MaterialType {
+        MaterialType::Opaque
+    }
+}
+
+impl Default for ToonMaterial {
+    fn default() -> Self {
+        Self {
+            color: Srgba::WHITE,
+            bands: 3,
+            ramp: None,
+            specular_color: Srgba::new_opaque(255, 255, 255),
+            shininess: 32.0,
+            specular_cutoff: 0.5,
+            outline_width: 0.0,
+            outline_color: Srgba::BLACK,
+            render_states: RenderStates::default(),
+        }
+    }
+}
+
+///
+/// A material which renders the ink outline of a [ToonMaterial], see [ToonMaterial::outline_material].
+/// The back faces of the geometry are moved outwards along the normals and rendered with a single color, so only the part outside the silhouette of the geometry,
+/// and at creases where the back faces are in front of the surface, is visible. The geometry must have normals.
+///
+#[derive(Clone, Copy, Debug)]
+pub struct ToonOutlineMaterial {
+    /// The color of the outline.
+    pub color: Srgba,
+    /// The width of the outline in world space units.
+    pub width: f32,
+}
+
+impl Material for ToonOutlineMaterial {
+    fn id(&self) -> EffectMaterialId {
+        EffectMaterialId::ToonOutlineMaterial
+    }
+
+    fn fragment_shader_source(&self, _lights: &[&dyn Light]) -> String {
+        include_str!("shaders/toon_outline_material.frag").to_owned()
+    }
+
+    fn use_uniforms(&self, program: &Program, viewer: &dyn Viewer, _lights: &[&dyn Light]) {
+        viewer.color_mapping().use_uniforms(program);
+        program.use_uniform("outlineColor", self.color.to_linear_srgb());
+        program.use_uniform_if_required("normalExtrusion", self.width);
+    }
+
+    fn render_states(&self) -> RenderStates {
+        RenderStates {
+            cull: Cull::Front,
+            ..Default::default()
+        }
+    }
+
+    fn material_type(&self) -> MaterialType {
+        MaterialType::Opaque
+    }
+}
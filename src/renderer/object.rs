@@ -15,6 +15,10 @@ macro_rules! impl_object_body {
         fn material_type(&self) -> MaterialType {
             self.$inner().material_type()
         }
+
+        fn with_material(&self, callback: &mut dyn FnMut(&dyn Material)) {
+            self.$inner().with_material(callback)
+        }
     };
 }
 
@@ -26,6 +30,18 @@ mod model;
 #[doc(inline)]
 pub use model::*;
 
+mod lightmap_uvs;
+#[doc(inline)]
+pub use lightmap_uvs::*;
+
+mod multi_material_mesh;
+#[doc(inline)]
+pub use multi_material_mesh::*;
+
+mod instance_batches;
+#[doc(inline)]
+pub use instance_batches::*;
+
 mod instanced_model;
 #[doc(inline)]
 pub use instanced_model::*;
@@ -50,10 +66,26 @@ mod water;
 #[doc(inline)]
 pub use water::*;
 
+mod light_flare;
+#[doc(inline)]
+pub use light_flare::*;
+
 mod axes;
 #[doc(inline)]
 pub use axes::*;
 
+mod skeleton_gizmo;
+#[doc(inline)]
+pub use skeleton_gizmo::*;
+
+mod debug_overlay;
+#[doc(inline)]
+pub use debug_overlay::*;
+
+mod object_collection;
+#[doc(inline)]
+pub use object_collection::*;
+
 use crate::core::*;
 use crate::renderer::*;
 
@@ -72,6 +104,14 @@ pub trait Object: Geometry {
     /// Returns the type of material applied to this object.
     ///
     fn material_type(&self) -> MaterialType;
+
+    ///
+    /// Calls the given function with the material of this object, which enables render paths that render the object with a modified version of its material,
+    /// for example [RenderTarget::render_transparent_oit].
+    /// The default implementation does not call the function, which means that the object does not have a single material which can be modified,
+    /// and the object is then rendered with [Object::render] instead.
+    ///
+    fn with_material(&self, _callback: &mut dyn FnMut(&dyn Material)) {}
 }
 
 use std::ops::Deref;
@@ -83,15 +123,15 @@ impl<T: Object + ?Sized> Object for &mut T {
     impl_object_body!(deref);
 }
 
-impl<T: Object> Object for Box<T> {
+impl<T: Object + ?Sized> Object for Box<T> {
     impl_object_body!(as_ref);
 }
 
-impl<T: Object> Object for std::rc::Rc<T> {
+impl<T: Object + ?Sized> Object for std::rc::Rc<T> {
     impl_object_body!(as_ref);
 }
 
-impl<T: Object> Object for std::sync::Arc<T> {
+impl<T: Object + ?Sized> Object for std::sync::Arc<T> {
     impl_object_body!(as_ref);
 }
 
@@ -107,4 +147,8 @@ impl<T: Object> Object for std::sync::RwLock<T> {
     fn material_type(&self) -> MaterialType {
         self.read().unwrap().material_type()
     }
+
+    fn with_material(&self, callback: &mut dyn FnMut(&dyn Material)) {
+        self.read().unwrap().with_material(callback)
+    }
 }
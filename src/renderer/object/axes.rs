@@ -26,8 +26,8 @@ impl Axes {
                         Mat4::from_angle_z(degrees(90.0)),
                         Mat4::from_angle_y(degrees(-90.0)),
                     ],
-                    texture_transformations: None,
                     colors: Some(vec![Srgba::RED, Srgba::GREEN, Srgba::BLUE]),
+                    ..Default::default()
                 },
                 &cpu_mesh,
             ),
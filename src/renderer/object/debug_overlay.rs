@@ -0,0 +1,416 @@
+use crate::renderer::*;
+
+const RAY_COLOR: Srgba = Srgba::new_opaque(255, 220, 0);
+const FRUSTUM_COLOR: Srgba = Srgba::new_opaque(0, 200, 255);
+const HIT_COLOR: Srgba = Srgba::new_opaque(255, 60, 60);
+const VIEWPORT_COLOR: Srgba = Srgba::new_opaque(255, 0, 255);
+
+///
+/// An overlay for debugging picking and camera problems, which shows
+/// - the pick ray through the cursor as a yellow line and the picked point as a red cross,
+/// - the frustum of the camera as cyan lines,
+/// - the outline of the viewport of the camera as a magenta rectangle and
+/// - a text block with the camera position, target and field of view, the cursor position in logical and physical pixels,
+///   and the index, instance and depth of the geometry under the cursor, see [DebugOverlay::info].
+///
+/// The pick ray and frustum coincide with the cursor and the edges of the screen when seen from the camera, so [freeze](DebugOverlay::set_frozen)
+/// the overlay and move the camera to inspect them from another point of view.
+/// The text block is only rendered if a font is given with [DebugOverlay::set_font], which requires the `text` feature.
+///
+/// Update the overlay each frame with [DebugOverlay::update] and add it to the render call after the rest of the scene,
+/// it is rendered on top of everything else. When the overlay is hidden, updating and rendering it does nothing.
+///
+pub struct DebugOverlay {
+    context: Context,
+    lines: Gm<InstancedMesh, ColorMaterial>,
+    outline: Gm<InstancedMesh, ColorMaterial>,
+    #[cfg(feature = "text")]
+    text: Option<Gm<TextBatch<'static>, ColorMaterial>>,
+    camera_2d: Camera,
+    /// The key that toggles whether the overlay is visible, if any.
+    pub toggle_key: Option<Key>,
+    visible: bool,
+    frozen: bool,
+    cursor: Option<PhysicalPoint>,
+    ray: Option<(Vec3, Vec3)>,
+    frustum: Option<[Vec3; 8]>,
+    hovered: Option<PickResult>,
+    info: String,
+}
+
+impl DebugOverlay {
+    ///
+    /// Creates a new visible debug overlay without a toggle key.
+    ///
+    pub fn new(context: &Context) -> Self {
+        let mut material = ColorMaterial::default();
+        material.render_states.depth_test = DepthTest::Always;
+        let instances = Instances {
+            transformations: Vec::new(),
+            colors: Some(Vec::new()),
+            ..Default::default()
+        };
+        let mut square = CpuMesh::square();
+        square.transform(Mat4::from_scale(0.5)).unwrap();
+        Self {
+            context: context.clone(),
+            lines: Gm::new(
+                InstancedMesh::new(context, &instances, &CpuMesh::cylinder(8)),
+                material.clone(),
+            ),
+            outline: Gm::new(InstancedMesh::new(context, &instances, &square), material),
+            #[cfg(feature = "text")]
+            text: None,
+            camera_2d: Camera::new_2d(Viewport::new_at_origo(1, 1)),
+            toggle_key: None,
+            visible: true,
+            frozen: false,
+            cursor: None,
+            ray: None,
+            frustum: None,
+            hovered: None,
+            info: String::new(),
+        }
+    }
+
+    ///
+    /// Sets the font used for rendering the text block, see [DebugOverlay::info].
+    /// The index indicates the specific font in a font collection. Set to 0 if unsure.
+    ///
+    #[cfg(feature = "text")]
+    pub fn set_font(
+        &mut self,
+        font_bytes: &'static [u8],
+        font_index: u32,
+    ) -> Result<(), RendererError> {
+        let mut material = ColorMaterial::default();
+        material.render_states.depth_test = DepthTest::Always;
+        self.text = Some(Gm::new(
+            TextBatch::new(&self.context, font_bytes, font_index, 32.0)?,
+            material,
+        ));
+        Ok(())
+    }
+
+    ///
+    /// Shows or hides the overlay.
+    ///
+    pub fn set_visible(&mut self, visible: bool) {
+        self.visible = visible;
+    }
+
+    ///
+    /// Returns whether the overlay is visible.
+    ///
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    ///
+    /// Freezes the pick ray and the frustum, so they are not updated when the camera or cursor moves.
+    /// Use this to inspect the pick ray and the frustum from another point of view.
+    ///
+    pub fn set_frozen(&mut self, frozen: bool) {
+        self.frozen = frozen;
+    }
+
+    ///
+    /// Returns whether the pick ray and the frustum are frozen, see [DebugOverlay::set_frozen].
+    ///
+    pub fn is_frozen(&self) -> bool {
+        self.frozen
+    }
+
+    ///
+    /// Returns the result of picking at the cursor position in the last update, if the cursor is over a geometry.
+    ///
+    pub fn hovered(&self) -> Option<PickResult> {
+        self.hovered
+    }
+
+    ///
+    /// Returns the text block describing the camera, the cursor and the hovered geometry in the last update,
+    /// for example to show it in a GUI instead of or in addition to rendering it with a font.
+    ///
+    pub fn info(&self) -> &str {
+        &self.info
+    }
+
+    ///
+    /// Updates the overlay based on the events, the viewport and device pixel ratio of the frame (for example [FrameInput::viewport](crate::FrameInput::viewport)
+    /// and [FrameInput::device_pixel_ratio](crate::FrameInput::device_pixel_ratio)), the camera and the geometries that can be hovered.
+    /// The hovered geometry is found with [pick_gpu], so the geometry index refers to the order of the given geometries. Must be called each frame.
+    ///
+    /// Key press events with the [toggle key](DebugOverlay::toggle_key) are marked as handled.
+    ///
+    pub fn update(
+        &mut self,
+        events: &mut [Event],
+        viewport: Viewport,
+        device_pixel_ratio: f32,
+        camera: &Camera,
+        geometries: impl IntoIterator<Item = impl Geometry>,
+    ) -> Result<(), RendererError> {
+        for event in events.iter_mut() {
+            match event {
+                Event::KeyPress { kind, handled, .. }
+                    if !*handled && Some(*kind) == self.toggle_key =>
+                {
+                    self.visible = !self.visible;
+                    *handled = true;
+                }
+                Event::MouseMotion { position, .. } => {
+                    self.cursor = Some(*position);
+                }
+                Event::MouseLeave => {
+                    self.cursor = None;
+                }
+                _ => {}
+            }
+        }
+        if !self.visible {
+            return Ok(());
+        }
+
+        self.hovered = match self.cursor {
+            Some(cursor) => pick_gpu(&self.context, camera, cursor, geometries, Cull::Back)?,
+            None => None,
+        };
+        if !self.frozen {
+            let v = camera.viewport();
+            let (x0, y0) = (v.x as f32, v.y as f32);
+            let (x1, y1) = (x0 + v.width as f32, y0 + v.height as f32);
+            let corners = [(x0, y0), (x1, y0), (x1, y1), (x0, y1)]
+                .map(|(x, y)| unproject_pixel(&camera, PhysicalPoint { x, y }));
+            self.frustum = Some([
+                corners[0].0,
+                corners[1].0,
+                corners[2].0,
+                corners[3].0,
+                corners[0].1,
+                corners[1].1,
+                corners[2].1,
+                corners[3].1,
+            ]);
+            self.ray = self.cursor.map(|cursor| unproject_pixel(&camera, cursor));
+        }
+        self.update_lines(camera);
+        self.update_outline(camera.viewport(), viewport);
+        self.update_info(camera, device_pixel_ratio);
+        Ok(())
+    }
+
+    fn update_lines(&mut self, camera: &Camera) {
+        let eye = camera.position();
+        let mut lines = Vec::new();
+        if let Some(f) = self.frustum {
+            for i in 0..4 {
+                lines.push((f[i], f[(i + 1) % 4], FRUSTUM_COLOR));
+                lines.push((f[i + 4], f[(i + 1) % 4 + 4], FRUSTUM_COLOR));
+                lines.push((f[i], f[i + 4], FRUSTUM_COLOR));
+            }
+        }
+        if let Some((p0, p1)) = self.ray {
+            lines.push((p0, p1, RAY_COLOR));
+        }
+        if let Some(hovered) = self.hovered {
+            let size = 0.02 * eye.distance(hovered.position);
+            for axis in [Vec3::unit_x(), Vec3::unit_y(), Vec3::unit_z()] {
+                lines.push((
+                    hovered.position - size * axis,
+                    hovered.position + size * axis,
+                    HIT_COLOR,
+                ));
+            }
+        }
+
+        let mut transformations = Vec::new();
+        let mut colors = Vec::new();
+        for (p0, p1, color) in lines {
+            let length = p0.distance(p1);
+            if length <= 0.0 {
+                continue;
+            }
+            // Keep the lines at roughly the same thickness on the screen by scaling with the distance to the camera
+            let direction = (p1 - p0) / length;
+            let closest = p0 + direction * (eye - p0).dot(direction).clamp(0.0, length);
+            let thickness = 0.002 * eye.distance(closest).max(camera.z_near());
+            transformations.push(
+                Mat4::from_translation(p0)
+                    * rotation_matrix_from_dir_to_dir(vec3(1.0, 0.0, 0.0), direction)
+                    * Mat4::from_nonuniform_scale(length, thickness, thickness),
+            );
+            colors.push(color);
+        }
+        self.lines.set_instances(&Instances {
+            transformations,
+            colors: Some(colors),
+            ..Default::default()
+        });
+    }
+
+    fn update_outline(&mut self, camera_viewport: Viewport, viewport: Viewport) {
+        self.camera_2d.set_viewport(viewport);
+        self.camera_2d.set_view(
+            vec3(
+                0.5 * viewport.width as f32,
+                0.5 * viewport.height as f32,
+                1.0,
+            ),
+            vec3(
+                0.5 * viewport.width as f32,
+                0.5 * viewport.height as f32,
+                0.0,
+            ),
+            vec3(0.0, 1.0, 0.0),
+        );
+        self.camera_2d
+            .set_orthographic_projection(viewport.height as f32, 0.0, 10.0);
+
+        let x0 = camera_viewport.x as f32 - viewport.x as f32;
+        let y0 = camera_viewport.y as f32 - viewport.y as f32;
+        let (width, height) = (camera_viewport.width as f32, camera_viewport.height as f32);
+        let thickness = 2.0;
+        let rectangle = |x: f32, y: f32, w: f32, h: f32| {
+            Mat4::from_translation(vec3(x + 0.5 * w, y + 0.5 * h, 0.0))
+                * Mat4::from_nonuniform_scale(w, h, 1.0)
+        };
+        self.outline.set_instances(&Instances {
+            transformations: vec![
+                rectangle(x0, y0, width, thickness),
+                rectangle(x0, y0 + height - thickness, width, thickness),
+                rectangle(x0, y0, thickness, height),
+                rectangle(x0 + width - thickness, y0, thickness, height),
+            ],
+            colors: Some(vec![VIEWPORT_COLOR; 4]),
+            ..Default::default()
+        });
+    }
+
+    fn update_info(&mut self, camera: &Camera, device_pixel_ratio: f32) {
+        let p = camera.position();
+        let t = camera.target();
+        let v = camera.viewport();
+        let mut info = format!(
+            "Camera position: ({:.3}, {:.3}, {:.3})\nCamera target: ({:.3}, {:.3}, {:.3})\n",
+            p.x, p.y, p.z, t.x, t.y, t.z
+        );
+        info += &match camera.projection_type() {
+            three_d_asset::ProjectionType::Perspective { field_of_view_y } => {
+                format!("Field of view: {:.1}°\n", Deg::from(*field_of_view_y).0)
+            }
+            three_d_asset::ProjectionType::Orthographic { height } => {
+                format!("Orthographic height: {:.3}\n", height)
+            }
+        };
+        info += &format!("Viewport: {}x{} at ({}, {})\n", v.width, v.height, v.x, v.y);
+        info += &match self.cursor {
+            Some(c) => format!(
+                "Cursor: ({:.1}, {:.1}) logical, ({:.1}, {:.1}) physical\n",
+                c.x / device_pixel_ratio,
+                c.y / device_pixel_ratio,
+                c.x,
+                c.y
+            ),
+            None => "Cursor: outside\n".to_string(),
+        };
+        info += &match self.hovered {
+            Some(h) => format!(
+                "Hovered: geometry {}, instance {}, depth {:.6}\nHovered position: ({:.3}, {:.3}, {:.3})",
+                h.geometry_index, h.instance_index, h.depth, h.position.x, h.position.y, h.position.z
+            ),
+            None => "Hovered: none".to_string(),
+        };
+        if self.frozen {
+            info += "\nFrozen";
+        }
+        self.info = info;
+
+        #[cfg(feature = "text")]
+        if let Some(text) = self.text.as_mut() {
+            let size = 14.0 * device_pixel_ratio;
+            let height = self.camera_2d.viewport().height as f32;
+            text.set_text(
+                0,
+                &self.info,
+                TextStyle {
+                    position: vec3(10.0 * device_pixel_ratio, height - 1.5 * size, 0.0),
+                    size,
+                    color: Srgba::WHITE,
+                    ..Default::default()
+                },
+            );
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a DebugOverlay {
+    type Item = &'a dyn Object;
+    type IntoIter = std::iter::Once<&'a dyn Object>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        std::iter::once(self)
+    }
+}
+
+impl Geometry for DebugOverlay {
+    fn draw(&self, viewer: &dyn Viewer, program: &Program, render_states: RenderStates) {
+        self.lines.draw(viewer, program, render_states)
+    }
+
+    fn vertex_shader_source(&self) -> String {
+        self.lines.vertex_shader_source()
+    }
+
+    fn id(&self) -> GeometryId {
+        self.lines.id()
+    }
+
+    fn render_with_material(
+        &self,
+        material: &dyn Material,
+        viewer: &dyn Viewer,
+        lights: &[&dyn Light],
+    ) {
+        if self.visible {
+            self.lines.render_with_material(material, viewer, lights)
+        }
+    }
+
+    fn render_with_effect(
+        &self,
+        material: &dyn Effect,
+        viewer: &dyn Viewer,
+        lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) {
+        if self.visible {
+            self.lines
+                .render_with_effect(material, viewer, lights, color_texture, depth_texture)
+        }
+    }
+
+    fn aabb(&self) -> AxisAlignedBoundingBox {
+        AxisAlignedBoundingBox::INFINITE
+    }
+}
+
+impl Object for DebugOverlay {
+    fn render(&self, viewer: &dyn Viewer, lights: &[&dyn Light]) {
+        if !self.visible {
+            return;
+        }
+        self.lines.render(viewer, lights);
+        self.outline.render(&self.camera_2d, lights);
+        #[cfg(feature = "text")]
+        if let Some(text) = self.text.as_ref() {
+            text.render(&self.camera_2d, lights);
+        }
+    }
+
+    fn material_type(&self) -> MaterialType {
+        // Rendered after the opaque objects, so it is on top of them
+        MaterialType::Transparent
+    }
+}
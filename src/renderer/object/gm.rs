@@ -10,6 +10,8 @@ pub struct Gm<G: Geometry, M: Material> {
     pub geometry: G,
     /// The material applied to the geometry
     pub material: M,
+    color_override: Option<Srgba>,
+    emissive_override: Option<Srgba>,
 }
 
 impl<G: Geometry, M: Material> Gm<G, M> {
@@ -17,7 +19,52 @@ impl<G: Geometry, M: Material> Gm<G, M> {
     /// Creates a new [Gm] from a geometry and material.
     ///
     pub fn new(geometry: G, material: M) -> Self {
-        Self { geometry, material }
+        Self {
+            geometry,
+            material,
+            color_override: None,
+            emissive_override: None,
+        }
+    }
+
+    ///
+    /// Overrides the surface color of the material of this object, for example to highlight it, without changing or duplicating the material.
+    /// The alpha value of the given color specifies how much of the surface color is replaced, so [Srgba::RED] replaces it completely with red
+    /// while a color with alpha 128 tints the surface color halfway towards red.
+    /// The transparency of the material is not changed.
+    /// The override is sent as a uniform when rendering, so it is cheap to change every frame.
+    ///
+    /// The override is supported by [ColorMaterial] and [PhysicalMaterial] and is ignored by other materials.
+    ///
+    pub fn set_color_override(&mut self, color: Option<Srgba>) {
+        self.color_override = color;
+    }
+
+    ///
+    /// Returns the color override, see [Gm::set_color_override].
+    ///
+    pub fn color_override(&self) -> Option<Srgba> {
+        self.color_override
+    }
+
+    ///
+    /// Overrides the emissive color of the material of this object without changing or duplicating the material.
+    /// For a [PhysicalMaterial], the emissive color and texture are replaced by the given color,
+    /// and for a [ColorMaterial], the given color is added to the output color.
+    /// The alpha value of the given color is ignored.
+    /// The override is sent as a uniform when rendering, so it is cheap to change every frame.
+    ///
+    /// The override is supported by [ColorMaterial] and [PhysicalMaterial] and is ignored by other materials.
+    ///
+    pub fn set_emissive_override(&mut self, color: Option<Srgba>) {
+        self.emissive_override = color;
+    }
+
+    ///
+    /// Returns the emissive override, see [Gm::set_emissive_override].
+    ///
+    pub fn emissive_override(&self) -> Option<Srgba> {
+        self.emissive_override
     }
 }
 
@@ -35,11 +82,12 @@ impl<G: Geometry + Clone, M: Material + Clone> Clone for Gm<G, M> {
         Self {
             geometry: self.geometry.clone(),
             material: self.material.clone(),
+            color_override: self.color_override,
+            emissive_override: self.emissive_override,
         }
     }
 }
 
-use std::ops::Deref;
 impl<G: Geometry, M: Material> std::ops::Deref for Gm<G, M> {
     type Target = G;
     fn deref(&self) -> &Self::Target {
@@ -54,7 +102,55 @@ impl<G: Geometry, M: Material> std::ops::DerefMut for Gm<G, M> {
 }
 
 impl<G: Geometry, M: Material> Geometry for Gm<G, M> {
-    impl_geometry_body!(deref);
+    fn draw(&self, viewer: &dyn Viewer, program: &Program, render_states: RenderStates) {
+        self.geometry.draw(viewer, program, render_states)
+    }
+
+    fn vertex_shader_source(&self) -> String {
+        self.geometry.vertex_shader_source()
+    }
+
+    fn id(&self) -> GeometryId {
+        self.geometry.id()
+    }
+
+    fn render_with_material(
+        &self,
+        material: &dyn Material,
+        viewer: &dyn Viewer,
+        lights: &[&dyn Light],
+    ) {
+        self.geometry.render_with_material(material, viewer, lights)
+    }
+
+    fn render_with_effect(
+        &self,
+        material: &dyn Effect,
+        viewer: &dyn Viewer,
+        lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) {
+        self.geometry
+            .render_with_effect(material, viewer, lights, color_texture, depth_texture)
+    }
+
+    fn aabb(&self) -> AxisAlignedBoundingBox {
+        self.geometry.aabb()
+    }
+
+    fn alpha_cutout(&self) -> Option<AlphaCutout> {
+        self.material.alpha_cutout()
+    }
+
+    fn cull(&self) -> Option<Cull> {
+        let render_states = self.material.render_states();
+        Some(match (render_states.front_face, render_states.cull) {
+            (FrontFace::Clockwise, Cull::Back) => Cull::Front,
+            (FrontFace::Clockwise, Cull::Front) => Cull::Back,
+            (_, cull) => cull,
+        })
+    }
 
     fn animate(&mut self, time: f32) {
         self.geometry.animate(time)
@@ -63,7 +159,76 @@ impl<G: Geometry, M: Material> Geometry for Gm<G, M> {
 
 impl<G: Geometry, M: Material> Object for Gm<G, M> {
     fn render(&self, viewer: &dyn Viewer, lights: &[&dyn Light]) {
-        self.render_with_material(&self.material, viewer, lights)
+        if self.color_override.is_none() && self.emissive_override.is_none() {
+            self.render_with_material(&self.material, viewer, lights)
+        } else {
+            self.render_with_material(
+                &OverriddenMaterial {
+                    material: &self.material,
+                    color_override: self.color_override,
+                    emissive_override: self.emissive_override,
+                },
+                viewer,
+                lights,
+            )
+        }
+    }
+
+    fn material_type(&self) -> MaterialType {
+        self.material.material_type()
+    }
+
+    fn with_material(&self, callback: &mut dyn FnMut(&dyn Material)) {
+        if self.color_override.is_none() && self.emissive_override.is_none() {
+            callback(&self.material)
+        } else {
+            callback(&OverriddenMaterial {
+                material: &self.material,
+                color_override: self.color_override,
+                emissive_override: self.emissive_override,
+            })
+        }
+    }
+}
+
+///
+/// Wraps a material and sends the color and emissive overrides of a [Gm] after the uniforms of the material.
+/// The shader source and id are the same as the wrapped material, so no new program is compiled.
+///
+struct OverriddenMaterial<'a, M: Material> {
+    material: &'a M,
+    color_override: Option<Srgba>,
+    emissive_override: Option<Srgba>,
+}
+
+impl<M: Material> Material for OverriddenMaterial<'_, M> {
+    fn fragment_shader_source(&self, lights: &[&dyn Light]) -> String {
+        self.material.fragment_shader_source(lights)
+    }
+
+    fn id(&self) -> EffectMaterialId {
+        self.material.id()
+    }
+
+    fn alpha_cutout(&self) -> Option<AlphaCutout> {
+        self.material.alpha_cutout()
+    }
+
+    fn use_uniforms(&self, program: &Program, viewer: &dyn Viewer, lights: &[&dyn Light]) {
+        self.material.use_uniforms(program, viewer, lights);
+        if let Some(color) = self.color_override {
+            program.use_uniform_if_required("colorOverride", color.to_linear_srgb());
+        }
+        if let Some(color) = self.emissive_override {
+            program.use_uniform_if_required(
+                "emissiveOverride",
+                color.to_linear_srgb().truncate().extend(1.0),
+            );
+        }
+    }
+
+    fn render_states(&self) -> RenderStates {
+        self.material.render_states()
     }
 
     fn material_type(&self) -> MaterialType {
@@ -187,13 +187,9 @@ impl ImpostersMaterial {
                 Wrapping::ClampToEdge,
                 Wrapping::ClampToEdge,
             );
-            let mut depth_texture = DepthTexture2D::new::<f32>(
-                &self.context,
-                texture_width,
-                texture_height,
-                Wrapping::ClampToEdge,
-                Wrapping::ClampToEdge,
-            );
+            let mut depth_texture = self
+                .context
+                .request_depth_texture::<f32>(texture_width, texture_height);
             for i in 0..NO_VIEW_ANGLES {
                 let layers = [i];
                 let angle = i as f32 * 2.0 * PI / NO_VIEW_ANGLES as f32;
@@ -219,13 +215,7 @@ impl Material for ImpostersMaterial {
     }
 
     fn fragment_shader_source(&self, _lights: &[&dyn Light]) -> String {
-        format!(
-            "{}{}{}{}",
-            ToneMapping::fragment_shader_source(),
-            ColorMapping::fragment_shader_source(),
-            include_str!("../../core/shared.frag"),
-            include_str!("shaders/imposter.frag")
-        )
+        include_str!("shaders/imposter.frag").to_string()
     }
 
     fn use_uniforms(&self, program: &Program, viewer: &dyn Viewer, _lights: &[&dyn Light]) {
@@ -0,0 +1,168 @@
+use crate::renderer::*;
+use std::collections::HashMap;
+
+///
+/// Statistics about how a list of objects is rendered, for example after batching them using [InstanceBatches].
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RenderStats {
+    /// The number of objects given as input.
+    pub object_count: usize,
+    /// The number of objects which are not rendered because they are outside the view frustum or have a transformation that cannot be inverted.
+    pub culled_object_count: usize,
+    /// The number of draw calls needed to render the objects, ie. one for each batch and one for each object that is not part of a batch.
+    pub draw_calls: usize,
+    /// The number of batches, each rendering several objects with one instanced draw call.
+    pub batch_count: usize,
+    /// The number of objects rendered as part of a batch.
+    pub batched_object_count: usize,
+    /// The total number of shader programs compiled at render time by the context when these statistics were gathered, see [Context::lazy_program_compilations].
+    pub lazy_program_compilations: usize,
+}
+
+impl RenderStats {
+    ///
+    /// Returns whether any of the objects are rendered as part of a batch.
+    ///
+    pub fn is_batched(&self) -> bool {
+        self.batch_count > 0
+    }
+}
+
+///
+/// Automatic instancing of objects that share the same mesh data and have equal materials.
+/// The objects which share the same vertex and index buffers on the GPU, ie. the meshes are clones of the same [Mesh] and draw the same range of triangles,
+/// and which have equal materials are gathered into a batch which is rendered with one instanced draw call instead of one draw call for each object.
+/// All other objects are rendered individually as usual, which includes the objects with a color or emissive override (see [Gm::set_color_override] and [Gm::set_emissive_override]),
+/// since the overrides are set per object.
+///
+/// The batches are transient, since the transformations of the objects are copied to the GPU when the batches are created,
+/// so create new batches each frame, or whenever the objects change, and render them like any other objects, for example using [RenderTarget::render].
+/// Objects outside the frustum of the given viewer are removed before batching, so the same objects are visible as when rendering the objects directly,
+/// and for transparent materials, the objects in a batch are sorted back to front.
+/// Use the original objects for anything else than rendering, for example when using [pick] or [ray_intersect], since the result then refers to the individual objects.
+///
+/// ```no_run
+/// # use three_d::*;
+/// # let context: Context = unimplemented!();
+/// # let camera: Camera = unimplemented!();
+/// let mesh = Mesh::new(&context, &CpuMesh::cube());
+/// let material = PhysicalMaterial::new_opaque(&context, &CpuMaterial::default());
+/// let objects = (0..1000)
+///     .map(|i| {
+///         let mut object = Gm::new(mesh.clone(), material.clone());
+///         object.set_transformation(Mat4::from_translation(vec3(3.0 * i as f32, 0.0, 0.0)));
+///         object
+///     })
+///     .collect::<Vec<_>>();
+///
+/// // Each frame
+/// let batches = InstanceBatches::new(&context, &camera, &objects);
+/// assert!(batches.stats().is_batched());
+/// RenderTarget::screen(&context, 1280, 720).render(&camera, &batches, &[]);
+/// ```
+///
+pub struct InstanceBatches<'a, M: Material> {
+    batches: Vec<Gm<MeshBatch, &'a M>>,
+    objects: Vec<&'a Gm<Mesh, M>>,
+    stats: RenderStats,
+}
+
+impl<'a, M: Material + PartialEq> InstanceBatches<'a, M> {
+    ///
+    /// Gathers the given objects into batches of objects that share the same mesh data and have equal materials.
+    /// The viewer is used to remove the objects outside the view frustum and to sort the objects in the batches with a transparent material.
+    ///
+    pub fn new(
+        context: &Context,
+        viewer: impl Viewer,
+        objects: impl IntoIterator<Item = &'a Gm<Mesh, M>>,
+    ) -> Self {
+        let frustum = Frustum::new(viewer.projection() * viewer.view());
+        let mut stats = RenderStats::default();
+        let mut groups: Vec<Vec<&'a Gm<Mesh, M>>> = Vec::new();
+        let mut groups_with_key = HashMap::<_, Vec<usize>>::new();
+        let mut objects_without_batch = Vec::new();
+        for object in objects {
+            stats.object_count += 1;
+            if !frustum.contains(object.aabb()) || object.local_to_world().invert().is_none() {
+                stats.culled_object_count += 1;
+                continue;
+            }
+            if !MeshBatch::is_supported(&object.geometry)
+                || object.color_override().is_some()
+                || object.emissive_override().is_some()
+            {
+                objects_without_batch.push(object);
+                continue;
+            }
+            let group_indices = groups_with_key
+                .entry(MeshBatch::key(&object.geometry))
+                .or_default();
+            match group_indices
+                .iter()
+                .find(|i| groups[**i][0].material == object.material)
+            {
+                Some(i) => groups[*i].push(object),
+                None => {
+                    group_indices.push(groups.len());
+                    groups.push(vec![object]);
+                }
+            }
+        }
+
+        let mut batches = Vec::new();
+        for mut group in groups {
+            if group.len() == 1 {
+                objects_without_batch.push(group[0]);
+                continue;
+            }
+            if group[0].material.material_type() == MaterialType::Transparent {
+                let position = viewer.position();
+                group.sort_by(|a, b| {
+                    b.aabb()
+                        .center()
+                        .distance2(position)
+                        .total_cmp(&a.aabb().center().distance2(position))
+                });
+            }
+            stats.batched_object_count += group.len();
+            let meshes = group.iter().map(|o| &o.geometry).collect::<Vec<_>>();
+            batches.push(Gm::new(
+                MeshBatch::new(context, &meshes),
+                &group[0].material,
+            ));
+        }
+        stats.batch_count = batches.len();
+        stats.draw_calls = batches.len() + objects_without_batch.len();
+        stats.lazy_program_compilations = context.lazy_program_compilations();
+        Self {
+            batches,
+            objects: objects_without_batch,
+            stats,
+        }
+    }
+}
+
+impl<M: Material> InstanceBatches<'_, M> {
+    ///
+    /// Returns statistics about the batching, for example whether any batching happened, see [RenderStats::is_batched].
+    ///
+    pub fn stats(&self) -> RenderStats {
+        self.stats
+    }
+}
+
+impl<'a, M: Material> IntoIterator for &'a InstanceBatches<'_, M> {
+    type Item = &'a dyn Object;
+    type IntoIter = std::vec::IntoIter<&'a dyn Object>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.batches
+            .iter()
+            .map(|batch| batch as &dyn Object)
+            .chain(self.objects.iter().map(|object| *object as &dyn Object))
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+}
@@ -0,0 +1,321 @@
+use crate::core::*;
+use crate::renderer::*;
+
+///
+/// Defines how the size of a [LightFlare] is specified.
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FlareSize {
+    /// The radius of the flare in world units, so the flare gets smaller when the light source is further away from the camera.
+    World(f32),
+    /// The radius of the flare in pixels, so the flare has the same size on the screen independent of the distance to the light source.
+    Screen(f32),
+}
+
+///
+/// A glow sprite rendered at the position of a light source, for example a [PointLight], which always faces the camera.
+/// The flare is rendered with additive blending and optionally with a horizontal anamorphic streak, see [LightFlare::streak].
+///
+/// Rendering the flare as an [Object] uses the regular depth test, so the flare is cut off by geometry in front of it.
+/// To fade the flare smoothly when the light source moves behind geometry, render it with a [LightFlareEffect] and the depth texture of the scene instead.
+///
+pub struct LightFlare {
+    context: Context,
+    position_buffer: VertexBuffer<Vec3>,
+    material: LightFlareMaterial,
+    /// The position of the light source.
+    pub position: Vec3,
+    /// The color of the flare.
+    pub color: Srgba,
+    /// The intensity of the flare which is multiplied with the color.
+    pub intensity: f32,
+    /// The size of the flare.
+    pub size: FlareSize,
+    /// The texture of the flare where the color is multiplied with the alpha value. Defaults to a radial glow, see [LightFlare::glow_texture].
+    pub texture: Texture2DRef,
+    /// An optional texture which is stretched horizontally across the flare, see [LightFlare::streak_texture].
+    pub streak: Option<Texture2DRef>,
+    /// The length of the streak relative to the size of the flare.
+    pub streak_length: f32,
+}
+
+impl LightFlare {
+    ///
+    /// Creates a new light flare at the given position with a radial glow texture and no streak.
+    ///
+    pub fn new(
+        context: &Context,
+        position: Vec3,
+        color: Srgba,
+        intensity: f32,
+        size: FlareSize,
+    ) -> Self {
+        let position_buffer = VertexBuffer::new_with_data(
+            context,
+            &[
+                vec3(-1.0, -1.0, 0.0),
+                vec3(1.0, -1.0, 0.0),
+                vec3(1.0, 1.0, 0.0),
+                vec3(1.0, 1.0, 0.0),
+                vec3(-1.0, 1.0, 0.0),
+                vec3(-1.0, -1.0, 0.0),
+            ],
+        );
+        Self {
+            context: context.clone(),
+            position_buffer,
+            material: LightFlareMaterial,
+            position,
+            color,
+            intensity,
+            size,
+            texture: Self::glow_texture(context),
+            streak: None,
+            streak_length: 4.0,
+        }
+    }
+
+    ///
+    /// Creates a new light flare with the position, color and intensity of the given [PointLight].
+    ///
+    pub fn new_from_point_light(context: &Context, light: &PointLight, size: FlareSize) -> Self {
+        Self::new(context, light.position, light.color, light.intensity, size)
+    }
+
+    ///
+    /// Updates the position, color and intensity of the flare to match the given [PointLight].
+    /// Call this each frame to make the flare follow an animated light.
+    ///
+    pub fn track_point_light(&mut self, light: &PointLight) {
+        self.position = light.position;
+        self.color = light.color;
+        self.intensity = light.intensity;
+    }
+
+    ///
+    /// Updates the position, color and intensity of the flare to match the given [SpotLight].
+    /// Call this each frame to make the flare follow an animated light.
+    ///
+    pub fn track_spot_light(&mut self, light: &SpotLight) {
+        self.position = light.position;
+        self.color = light.color;
+        self.intensity = light.intensity;
+    }
+
+    ///
+    /// Creates a radial glow texture which is the default texture of a [LightFlare].
+    ///
+    pub fn glow_texture(context: &Context) -> Texture2DRef {
+        const SIZE: u32 = 64;
+        let data = (0..SIZE * SIZE)
+            .map(|i| {
+                let x = 2.0 * ((i % SIZE) as f32 + 0.5) / SIZE as f32 - 1.0;
+                let y = 2.0 * ((i / SIZE) as f32 + 0.5) / SIZE as f32 - 1.0;
+                let falloff = (1.0 - (x * x + y * y).sqrt()).max(0.0);
+                // A bright core and a wide halo
+                let alpha = (0.6 * falloff.powi(8) + 0.4 * falloff.powi(2)).min(1.0);
+                [255, 255, 255, (alpha * 255.0) as u8]
+            })
+            .collect();
+        Texture2DRef::from_cpu_texture(
+            context,
+            &CpuTexture {
+                data: TextureData::RgbaU8(data),
+                width: SIZE,
+                height: SIZE,
+                wrap_s: Wrapping::ClampToEdge,
+                wrap_t: Wrapping::ClampToEdge,
+                ..Default::default()
+            },
+        )
+    }
+
+    ///
+    /// Creates a thin horizontal streak texture with a blue tint, which imitates the streaks seen when filming with anamorphic lenses.
+    /// Assign it to [LightFlare::streak] to enable the streak.
+    ///
+    pub fn streak_texture(context: &Context) -> Texture2DRef {
+        const WIDTH: u32 = 256;
+        const HEIGHT: u32 = 16;
+        let data = (0..WIDTH * HEIGHT)
+            .map(|i| {
+                let x = 2.0 * ((i % WIDTH) as f32 + 0.5) / WIDTH as f32 - 1.0;
+                let y = 2.0 * ((i / WIDTH) as f32 + 0.5) / HEIGHT as f32 - 1.0;
+                let alpha = (1.0 - x.abs()).powi(3) * (-16.0 * y * y).exp();
+                [160, 200, 255, (alpha * 255.0) as u8]
+            })
+            .collect();
+        Texture2DRef::from_cpu_texture(
+            context,
+            &CpuTexture {
+                data: TextureData::RgbaU8(data),
+                width: WIDTH,
+                height: HEIGHT,
+                wrap_s: Wrapping::ClampToEdge,
+                wrap_t: Wrapping::ClampToEdge,
+                ..Default::default()
+            },
+        )
+    }
+
+    fn half_size(&self) -> Vec2 {
+        let radius = match self.size {
+            FlareSize::World(radius) | FlareSize::Screen(radius) => radius,
+        };
+        if self.streak.is_some() {
+            vec2(radius * self.streak_length.max(1.0), radius)
+        } else {
+            vec2(radius, radius)
+        }
+    }
+
+    fn draw(&self, program: &Program, render_states: RenderStates, viewer: &dyn Viewer) {
+        program.use_uniform_if_required("view", viewer.view());
+        program.use_uniform("viewProjection", viewer.projection() * viewer.view());
+        program.use_uniform("flarePosition", self.position);
+        program.use_uniform("flareHalfSize", self.half_size());
+        program.use_uniform_if_required(
+            "viewportSize",
+            vec2(
+                viewer.viewport().width as f32,
+                viewer.viewport().height as f32,
+            ),
+        );
+        if program.requires_uniform("flareCenter") {
+            let center = viewer.projection() * viewer.view() * self.position.extend(1.0);
+            let depth_mode = viewer.depth_mode();
+            let flare_center = if center.w > 0.0 {
+                let ndc = center.truncate() / center.w;
+                let depth = if depth_mode == DepthMode::ReversedZeroToOne {
+                    ndc.z
+                } else {
+                    0.5 * ndc.z + 0.5
+                };
+                vec3(0.5 * ndc.x + 0.5, 0.5 * ndc.y + 0.5, depth)
+            } else {
+                vec3(-1.0, -1.0, -1.0)
+            };
+            program.use_uniform("flareCenter", flare_center);
+            program.use_uniform(
+                "depthDirection",
+                if depth_mode.is_reversed() {
+                    -1.0f32
+                } else {
+                    1.0
+                },
+            );
+        }
+        let mut color = self.color.to_linear_srgb();
+        color.x *= self.intensity;
+        color.y *= self.intensity;
+        color.z *= self.intensity;
+        program.use_uniform("flareColor", color);
+        if program.requires_uniform("flareTexture") {
+            program.use_texture("flareTexture", &self.texture);
+            program.use_uniform("flareTextureTransformation", self.texture.transformation);
+            // The flare texture is bound as the streak texture when there is no streak, but the streak is then not sampled
+            let streak = self.streak.as_ref().unwrap_or(&self.texture);
+            program.use_texture("streakTexture", streak);
+            program.use_uniform("streakTextureTransformation", streak.transformation);
+            program.use_uniform(
+                "streakLength",
+                if self.streak.is_some() {
+                    self.streak_length.max(1.0)
+                } else {
+                    0.0
+                },
+            );
+        }
+        program.use_vertex_attribute("position", &self.position_buffer);
+        program.draw_arrays(render_states, viewer.viewport(), 6)
+    }
+}
+
+impl<'a> IntoIterator for &'a LightFlare {
+    type Item = &'a dyn Object;
+    type IntoIter = std::iter::Once<&'a dyn Object>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        std::iter::once(self)
+    }
+}
+
+impl Geometry for LightFlare {
+    fn draw(&self, viewer: &dyn Viewer, program: &Program, render_states: RenderStates) {
+        self.draw(program, render_states, viewer);
+    }
+
+    fn vertex_shader_source(&self) -> String {
+        format!(
+            "{}{}",
+            if matches!(self.size, FlareSize::Screen(_)) {
+                "#define SCREEN_SIZE\n"
+            } else {
+                ""
+            },
+            include_str!("shaders/light_flare.vert")
+        )
+    }
+
+    fn id(&self) -> GeometryId {
+        GeometryId::LightFlare(matches!(self.size, FlareSize::Screen(_)))
+    }
+
+    fn render_with_material(
+        &self,
+        material: &dyn Material,
+        viewer: &dyn Viewer,
+        lights: &[&dyn Light],
+    ) {
+        if let Err(e) = render_with_material(&self.context, viewer, &self, material, lights) {
+            panic!("{}", e.to_string());
+        }
+    }
+
+    fn render_with_effect(
+        &self,
+        material: &dyn Effect,
+        viewer: &dyn Viewer,
+        lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) {
+        if let Err(e) = render_with_effect(
+            &self.context,
+            viewer,
+            self,
+            material,
+            lights,
+            color_texture,
+            depth_texture,
+        ) {
+            panic!("{}", e.to_string());
+        }
+    }
+
+    fn aabb(&self) -> AxisAlignedBoundingBox {
+        match self.size {
+            FlareSize::World(_) => {
+                let half_size = self.half_size();
+                let extent = vec3(half_size.x, half_size.x, half_size.x);
+                AxisAlignedBoundingBox::new_with_positions(&[
+                    self.position - extent,
+                    self.position + extent,
+                ])
+            }
+            FlareSize::Screen(_) => AxisAlignedBoundingBox::INFINITE,
+        }
+    }
+}
+
+impl Object for LightFlare {
+    fn render(&self, viewer: &dyn Viewer, lights: &[&dyn Light]) {
+        if let Err(e) = render_with_material(&self.context, viewer, self, &self.material, lights) {
+            panic!("{}", e.to_string());
+        }
+    }
+
+    fn material_type(&self) -> MaterialType {
+        MaterialType::Transparent
+    }
+}
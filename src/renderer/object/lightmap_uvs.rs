@@ -0,0 +1,176 @@
+use crate::renderer::*;
+
+///
+/// The lightmap uv coordinates of the triangle meshes in a [CpuModel], one for each vertex, which are used for sampling a lightmap (see [Mesh::set_lightmap_uvs]).
+/// Use [Model::new_with_lightmap_uvs] to construct a [Model] where the meshes get these lightmap uv coordinates.
+///
+/// A [CpuMesh] only has one set of uv coordinates, so the second set of uv coordinates in for example a glTF file (`TEXCOORD_1`), which is usually the one used for lightmaps,
+/// is not part of the [CpuModel] and is read separately, see [LightmapUvs::from_gltf] which requires the `gltf` feature.
+/// The lightmap uv coordinates are matched to a [CpuMesh] by its vertex positions.
+///
+/// ```
+/// # use three_d::*;
+/// let cpu_mesh = CpuMesh::square();
+/// let mut lightmap_uvs = LightmapUvs::default();
+/// lightmap_uvs
+///     .insert(&cpu_mesh, vec![vec2(0.0, 0.0), vec2(0.5, 0.0), vec2(0.5, 0.5), vec2(0.0, 0.5)])
+///     .unwrap();
+/// assert_eq!(lightmap_uvs.get(&cpu_mesh).unwrap()[2], vec2(0.5, 0.5));
+/// assert!(lightmap_uvs.get(&CpuMesh::cube()).is_none());
+///
+/// // There must be one lightmap uv coordinate for each vertex
+/// assert!(lightmap_uvs.insert(&CpuMesh::cube(), vec![vec2(0.0, 0.0)]).is_err());
+/// ```
+///
+#[derive(Clone, Debug, Default)]
+pub struct LightmapUvs {
+    meshes: Vec<(Vec<Vec3>, Vec<Vec2>)>,
+}
+
+impl LightmapUvs {
+    ///
+    /// Adds the lightmap uv coordinates for the given [CpuMesh], one for each vertex.
+    ///
+    pub fn insert(
+        &mut self,
+        cpu_mesh: &CpuMesh,
+        lightmap_uvs: Vec<Vec2>,
+    ) -> Result<(), RendererError> {
+        self.insert_positions(cpu_mesh.positions.to_f32(), lightmap_uvs)
+    }
+
+    ///
+    /// Returns the lightmap uv coordinates for the given [CpuMesh], ie. the lightmap uv coordinates which were added for a mesh with the same vertex positions, if any.
+    ///
+    pub fn get(&self, cpu_mesh: &CpuMesh) -> Option<&[Vec2]> {
+        if self.meshes.is_empty() {
+            return None;
+        }
+        let positions = cpu_mesh.positions.to_f32();
+        self.meshes
+            .iter()
+            .find(|(p, _)| *p == positions)
+            .map(|(_, lightmap_uvs)| lightmap_uvs.as_slice())
+    }
+
+    ///
+    /// Reads the second set of uv coordinates (`TEXCOORD_1`) of all primitives in the glTF file at the given path in the [RawAssets](three_d_asset::io::RawAssets)
+    /// and the buffers it depends on. The glTF file can be both a `.gltf` and a `.glb` file.
+    /// Primitives without a second set of uv coordinates are skipped.
+    ///
+    /// This must be called before the [CpuModel] is deserialized, since deserializing removes the glTF file and its buffers from the [RawAssets](three_d_asset::io::RawAssets).
+    ///
+    /// ```
+    /// # use three_d::*;
+    /// # // A glb file containing a single triangle with two sets of uv coordinates
+    /// # let floats: [f32; 21] = [
+    /// #     0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0,
+    /// #     0.0, 0.0, 1.0, 0.0, 0.0, 1.0,
+    /// #     0.5, 0.5, 0.75, 0.5, 0.5, 0.75,
+    /// # ];
+    /// # let bin = floats.iter().flat_map(|f| f.to_le_bytes()).collect::<Vec<u8>>();
+    /// # let mut json = r#"{"asset":{"version":"2.0"},"buffers":[{"byteLength":84}],
+    /// #     "bufferViews":[{"buffer":0,"byteLength":36},{"buffer":0,"byteOffset":36,"byteLength":24},{"buffer":0,"byteOffset":60,"byteLength":24}],
+    /// #     "accessors":[{"bufferView":0,"componentType":5126,"count":3,"type":"VEC3","min":[0,0,0],"max":[1,1,0]},
+    /// #         {"bufferView":1,"componentType":5126,"count":3,"type":"VEC2"},{"bufferView":2,"componentType":5126,"count":3,"type":"VEC2"}],
+    /// #     "meshes":[{"primitives":[{"attributes":{"POSITION":0,"TEXCOORD_0":1,"TEXCOORD_1":2}}]}],
+    /// #     "nodes":[{"mesh":0}],"scenes":[{"nodes":[0]}],"scene":0}"#.as_bytes().to_vec();
+    /// # while json.len() % 4 != 0 {
+    /// #     json.push(b' ');
+    /// # }
+    /// # let mut glb = Vec::new();
+    /// # glb.extend(b"glTF");
+    /// # glb.extend(2u32.to_le_bytes());
+    /// # glb.extend((12 + 8 + json.len() as u32 + 8 + bin.len() as u32).to_le_bytes());
+    /// # glb.extend((json.len() as u32).to_le_bytes());
+    /// # glb.extend(b"JSON");
+    /// # glb.extend(json);
+    /// # glb.extend((bin.len() as u32).to_le_bytes());
+    /// # glb.extend(b"BIN\0");
+    /// # glb.extend(bin);
+    /// let mut raw_assets = three_d_asset::io::RawAssets::new();
+    /// raw_assets.insert("triangle.glb", glb);
+    ///
+    /// // Read the lightmap uv coordinates before deserializing the model
+    /// let lightmap_uvs = LightmapUvs::from_gltf(&raw_assets, "triangle.glb").unwrap();
+    /// let cpu_model: CpuModel = raw_assets.deserialize("triangle.glb").unwrap();
+    ///
+    /// let CpuGeometry::Triangles(cpu_mesh) = &cpu_model.geometries[0].geometry else {
+    ///     unreachable!()
+    /// };
+    /// assert_eq!(
+    ///     lightmap_uvs.get(cpu_mesh).unwrap(),
+    ///     &[vec2(0.5, 0.5), vec2(0.75, 0.5), vec2(0.5, 0.75)]
+    /// );
+    /// // The meshes of a model constructed with `Model::new_with_lightmap_uvs(&context, &cpu_model, &lightmap_uvs)` get these lightmap uv coordinates
+    /// ```
+    ///
+    #[cfg(feature = "gltf")]
+    pub fn from_gltf(
+        raw_assets: &three_d_asset::io::RawAssets,
+        path: impl AsRef<std::path::Path>,
+    ) -> Result<Self, RendererError> {
+        let path = path.as_ref();
+        let error = |e: &dyn std::fmt::Display| RendererError::InvalidGltf(e.to_string());
+        let ::gltf::Gltf { document, blob } =
+            ::gltf::Gltf::from_slice(raw_assets.get(path).map_err(|e| error(&e))?)
+                .map_err(|e| error(&e))?;
+        let base_path = path.parent().unwrap_or(std::path::Path::new(""));
+
+        let mut buffers = Vec::new();
+        for buffer in document.buffers() {
+            let data = match buffer.source() {
+                ::gltf::buffer::Source::Uri(uri) => if uri.starts_with("data:") {
+                    raw_assets.get(uri)
+                } else {
+                    raw_assets.get(base_path.join(uri))
+                }
+                .map_err(|e| error(&e))?,
+                ::gltf::buffer::Source::Bin => blob
+                    .as_deref()
+                    .ok_or_else(|| error(&"the binary chunk is missing"))?,
+            };
+            if data.len() < buffer.length() {
+                Err(error(&format!(
+                    "buffer {} is {} bytes but must be at least {} bytes",
+                    buffer.index(),
+                    data.len(),
+                    buffer.length()
+                )))?;
+            }
+            buffers.push(data);
+        }
+
+        let mut lightmap_uvs = Self::default();
+        for mesh in document.meshes() {
+            for primitive in mesh.primitives() {
+                let reader = primitive.reader(|buffer| buffers.get(buffer.index()).copied());
+                if let (Some(positions), Some(uvs)) =
+                    (reader.read_positions(), reader.read_tex_coords(1))
+                {
+                    lightmap_uvs.insert_positions(
+                        positions.map(|p| p.into()).collect(),
+                        uvs.into_f32().map(|uv| uv.into()).collect(),
+                    )?;
+                }
+            }
+        }
+        Ok(lightmap_uvs)
+    }
+
+    fn insert_positions(
+        &mut self,
+        positions: Vec<Vec3>,
+        lightmap_uvs: Vec<Vec2>,
+    ) -> Result<(), RendererError> {
+        if positions.len() != lightmap_uvs.len() {
+            Err(RendererError::InvalidBufferLength(
+                "lightmap uv".to_string(),
+                positions.len(),
+                lightmap_uvs.len(),
+            ))?;
+        }
+        self.meshes.push((positions, lightmap_uvs));
+        Ok(())
+    }
+}
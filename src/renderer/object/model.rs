@@ -1,4 +1,7 @@
 use crate::renderer::*;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
 pub use three_d_asset::Model as CpuModel;
 
 ///
@@ -8,9 +11,70 @@ pub use three_d_asset::Model as CpuModel;
 pub struct ModelPart<M: Material> {
     gm: Gm<Mesh, M>,
     animations: Vec<KeyFrameAnimation>,
+    chosen_animation: Option<Option<String>>,
+    name: String,
+    material_name: Option<String>,
+    transformation: Mat4,
+    fingerprint: MeshFingerprint,
 }
 
 impl<M: Material> ModelPart<M> {
+    fn new(
+        primitive: &three_d_asset::Primitive,
+        cpu_mesh: &CpuMesh,
+        mesh: Mesh,
+        material: M,
+        material_name: Option<String>,
+    ) -> Self {
+        let mut gm = Gm::new(mesh, material);
+        gm.set_transformation(primitive.transformation);
+        Self {
+            gm,
+            animations: primitive.animations.clone(),
+            chosen_animation: None,
+            name: primitive.name.clone(),
+            material_name,
+            transformation: primitive.transformation,
+            fingerprint: MeshFingerprint::new(cpu_mesh),
+        }
+    }
+
+    ///
+    /// Writes the vertex positions and normals of the given [CpuMesh] into the existing vertex buffers, if only those have changed.
+    /// Returns false if a new [Mesh] is needed.
+    ///
+    fn update_vertices(&mut self, cpu_mesh: &CpuMesh, fingerprint: &MeshFingerprint) -> bool {
+        if self.fingerprint.layout != fingerprint.layout || self.gm.geometry.is_shared() {
+            return false;
+        }
+        let positions = cpu_mesh.positions.to_f32();
+        let mesh = &mut self.gm.geometry;
+        if mesh.update_positions(&positions).is_err() {
+            return false;
+        }
+        mesh.recompute_aabb_from(&positions);
+        if let Some(normals) = cpu_mesh.normals.as_ref() {
+            if mesh.update_normals(normals).is_err() {
+                return false;
+            }
+        }
+        true
+    }
+
+    ///
+    /// Returns the name of this model part, for example the name of the glTF node. The name might not be unique within a [Model].
+    ///
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    ///
+    /// Returns the name of the material of this model part, if any.
+    ///
+    pub fn material_name(&self) -> Option<&str> {
+        self.material_name.as_deref()
+    }
+
     ///
     /// Returns a list of unique names for the animations for this model part. Use these names as input to [Self::choose_animation].
     ///
@@ -32,6 +96,7 @@ impl<M: Material> ModelPart<M> {
             .cloned()
         {
             self.set_animation(move |time| animation.transformation(time));
+            self.chosen_animation = Some(animation_name.map(|name| name.to_owned()));
         }
     }
 }
@@ -80,7 +145,10 @@ impl<'a, M: Material> IntoIterator for &'a ModelPart<M> {
 ///
 /// A 3D model consisting of a set of [ModelPart]s.
 ///
-pub struct Model<M: Material>(Vec<ModelPart<M>>);
+pub struct Model<M: Material> {
+    parts: Vec<ModelPart<M>>,
+    materials: HashMap<String, u64>,
+}
 
 impl<'a, M: Material> IntoIterator for &'a Model<M> {
     type Item = &'a dyn Object;
@@ -99,40 +167,74 @@ impl<M: Material + FromCpuMaterial + Clone + Default> Model<M> {
     /// Constructs a [Model] from a [CpuModel], ie. constructs a list of [Gm]s with a [Mesh] as geometry (constructed from the [CpuMesh]es in the [CpuModel]) and
     /// a [material] type specified by the generic parameter which implement [FromCpuMaterial] (constructed from the [CpuMaterial]s in the [CpuModel]).
     ///
+    /// Primitives with identical vertex data, for example the primitives of a glTF mesh with multiple materials, share the vertex and index buffers on the GPU
+    /// (see [Mesh::new_with_ranges]). Editing the vertex data of one of those model parts first copies its buffers, so the other parts are not affected.
+    ///
     pub fn new(context: &Context, cpu_model: &CpuModel) -> Result<Self, RendererError> {
+        Self::new_with_lightmap_uvs(context, cpu_model, &LightmapUvs::default())
+    }
+
+    ///
+    /// Constructs a [Model] from a [CpuModel] in the same way as [Self::new], but the meshes also get the lightmap uv coordinates
+    /// which match their [CpuMesh] in the given [LightmapUvs], for example the `TEXCOORD_1` coordinates read from the same glTF file (see [LightmapUvs::from_gltf]).
+    /// Use these together with a lightmap, see [PhysicalMaterial::lightmap_texture].
+    ///
+    /// **Note:** Model parts which get a new [Mesh] when calling [Self::reload] do not get lightmap uv coordinates.
+    ///
+    pub fn new_with_lightmap_uvs(
+        context: &Context,
+        cpu_model: &CpuModel,
+        lightmap_uvs: &LightmapUvs,
+    ) -> Result<Self, RendererError> {
         let materials = cpu_model
             .materials
             .iter()
             .map(|m| M::from_cpu_material(context, m))
             .collect::<Vec<_>>();
+        let primitives = cpu_model
+            .geometries
+            .iter()
+            .filter_map(|primitive| match &primitive.geometry {
+                CpuGeometry::Triangles(geometry) => Some((primitive, geometry)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        let mut meshes = shared_meshes(
+            context,
+            &primitives
+                .iter()
+                .map(|(_, geometry)| *geometry)
+                .collect::<Vec<_>>(),
+            lightmap_uvs,
+        )
+        .into_iter();
         let mut gms = Vec::new();
-        for primitive in cpu_model.geometries.iter() {
-            if let CpuGeometry::Triangles(geometry) = &primitive.geometry {
-                let material = if let Some(material_index) = primitive.material_index {
-                    materials
-                        .get(material_index)
-                        .ok_or_else(|| {
-                            RendererError::MissingMaterial(
-                                material_index.to_string(),
-                                primitive.name.clone(),
-                            )
-                        })?
-                        .clone()
-                } else {
-                    M::default()
-                };
-                let mut gm = Gm {
-                    geometry: Mesh::new(context, geometry),
-                    material,
-                };
-                gm.set_transformation(primitive.transformation);
-                gms.push(ModelPart {
-                    gm,
-                    animations: primitive.animations.clone(),
-                });
-            }
+        for (primitive, geometry) in primitives {
+            let material = if let Some(material_index) = primitive.material_index {
+                materials
+                    .get(material_index)
+                    .ok_or_else(|| {
+                        RendererError::MissingMaterial(
+                            material_index.to_string(),
+                            primitive.name.clone(),
+                        )
+                    })?
+                    .clone()
+            } else {
+                M::default()
+            };
+            gms.push(ModelPart::new(
+                primitive,
+                geometry,
+                meshes.next().unwrap(),
+                material,
+                material_name(cpu_model, primitive)?,
+            ));
         }
-        let mut model = Self(gms);
+        let mut model = Self {
+            parts: gms,
+            materials: material_fingerprints(cpu_model),
+        };
         if let Some(animation_name) = model.animations().first().cloned() {
             model.choose_animation(animation_name.as_deref());
         }
@@ -144,7 +246,7 @@ impl<M: Material + FromCpuMaterial + Clone + Default> Model<M> {
     ///
     pub fn animations(&self) -> Vec<Option<String>> {
         let mut set = std::collections::HashSet::new();
-        for model_part in self.0.iter() {
+        for model_part in self.parts.iter() {
             set.extend(model_part.animations());
         }
         set.into_iter().collect()
@@ -154,7 +256,7 @@ impl<M: Material + FromCpuMaterial + Clone + Default> Model<M> {
     /// Specifies the animation to use when [Geometry::animate] is called. Use the [Self::animations] method to get a list of possible animations.
     ///
     pub fn choose_animation(&mut self, animation_name: Option<&str>) {
-        for part in self.0.iter_mut() {
+        for part in self.parts.iter_mut() {
             part.choose_animation(animation_name);
         }
     }
@@ -165,17 +267,434 @@ impl<M: Material + FromCpuMaterial + Clone + Default> Model<M> {
     pub fn animate(&mut self, time: f32) {
         self.iter_mut().for_each(|m| m.animate(time));
     }
+
+    ///
+    /// Updates this model in place to match the given [CpuModel], for example a new version of the glTF file this model was constructed from,
+    /// and returns a log of what changed. This is useful for live authoring, for example together with a [FileWatcher].
+    ///
+    /// The model parts are matched by name, and by order among parts with the same name, and the materials are matched by name, which means that
+    /// - unchanged parts keep their [Mesh] and material including any changes made to them after construction, for example render states,
+    /// - parts where the vertex positions and normals changed are updated by writing into the existing vertex buffers, unless the vertex count or other vertex data changed
+    ///   or the buffers are shared with other parts, in which case a new [Mesh] is constructed,
+    /// - parts using a material that changed get a new material constructed from the [CpuMaterial], while unchanged materials and their textures are kept,
+    /// - new parts are added and parts that no longer exist are removed.
+    ///
+    /// The parts are ordered as in the given [CpuModel] and each part keeps the animation chosen using [Self::choose_animation].
+    ///
+    pub fn reload(
+        &mut self,
+        context: &Context,
+        cpu_model: &CpuModel,
+    ) -> Result<ModelReloadLog, RendererError> {
+        // Validate the material references before changing anything
+        let material_names = cpu_model
+            .geometries
+            .iter()
+            .map(|primitive| material_name(cpu_model, primitive))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut log = ModelReloadLog::default();
+        let materials = material_fingerprints(cpu_model);
+        let mut changed_materials = HashMap::new();
+        for cpu_material in cpu_model.materials.iter() {
+            if self.materials.get(&cpu_material.name) != materials.get(&cpu_material.name) {
+                changed_materials
+                    .entry(cpu_material.name.clone())
+                    .or_insert_with(|| M::from_cpu_material(context, cpu_material));
+            }
+        }
+        log.changed_materials = changed_materials.keys().cloned().collect();
+        log.changed_materials.sort();
+
+        let mut old_parts = HashMap::new();
+        let mut occurrences = HashMap::new();
+        for part in self.parts.drain(..) {
+            let occurrence = occurrences.entry(part.name.clone()).or_insert(0);
+            old_parts.insert((part.name.clone(), *occurrence), part);
+            *occurrence += 1;
+        }
+
+        let mut occurrences = HashMap::new();
+        let mut parts = Vec::new();
+        let mut new_meshes = Vec::new();
+        for (primitive, material_name) in cpu_model.geometries.iter().zip(material_names) {
+            let CpuGeometry::Triangles(geometry) = &primitive.geometry else {
+                continue;
+            };
+            let occurrence = occurrences.entry(primitive.name.clone()).or_insert(0);
+            let key = (primitive.name.clone(), *occurrence);
+            *occurrence += 1;
+            let is_material_changed = material_name
+                .as_ref()
+                .is_some_and(|name| changed_materials.contains_key(name));
+            let mut part = match old_parts.remove(&key) {
+                Some(mut part) => {
+                    let mut is_changed = is_material_changed;
+                    let fingerprint = MeshFingerprint::new(geometry);
+                    if part.fingerprint != fingerprint {
+                        is_changed = true;
+                        log.changed_geometries.push(primitive.name.clone());
+                        if !part.update_vertices(geometry, &fingerprint) {
+                            new_meshes.push((parts.len(), geometry));
+                        }
+                        part.fingerprint = fingerprint;
+                    }
+                    if part.transformation != primitive.transformation {
+                        is_changed = true;
+                        log.changed_transformations.push(primitive.name.clone());
+                        part.transformation = primitive.transformation;
+                    }
+                    if !is_changed && part.material_name == material_name {
+                        log.unchanged_parts += 1;
+                    }
+                    part.animations = primitive.animations.clone();
+                    part
+                }
+                None => {
+                    log.added_parts.push(primitive.name.clone());
+                    new_meshes.push((parts.len(), geometry));
+                    // The mesh is constructed below, together with the other new meshes, so the vertex buffers can be shared
+                    ModelPart::new(
+                        primitive,
+                        geometry,
+                        Mesh::new(context, &CpuMesh::default()),
+                        M::default(),
+                        material_name.clone(),
+                    )
+                }
+            };
+            if is_material_changed || part.material_name != material_name {
+                part.gm.material = match material_name.as_ref() {
+                    Some(name) => match changed_materials.get(name).or_else(|| {
+                        old_parts
+                            .values()
+                            .chain(parts.iter())
+                            .find(|p: &&ModelPart<M>| p.material_name.as_ref() == Some(name))
+                            .map(|p| &p.gm.material)
+                    }) {
+                        Some(material) => material.clone(),
+                        None => M::from_cpu_material(
+                            context,
+                            &cpu_model.materials[primitive.material_index.unwrap()],
+                        ),
+                    },
+                    None => M::default(),
+                };
+                part.material_name = material_name;
+            }
+            parts.push(part);
+        }
+        log.removed_parts = old_parts.into_keys().map(|(name, _)| name).collect();
+        log.removed_parts.sort();
+
+        let meshes = shared_meshes(
+            context,
+            &new_meshes
+                .iter()
+                .map(|(_, geometry)| *geometry)
+                .collect::<Vec<_>>(),
+            &LightmapUvs::default(),
+        );
+        for ((index, _), mesh) in new_meshes.into_iter().zip(meshes) {
+            parts[index].gm.geometry = mesh;
+        }
+
+        self.parts = parts;
+        self.materials = materials;
+        let first_animation = self.animations().first().cloned();
+        for part in self.parts.iter_mut() {
+            part.gm.set_transformation(part.transformation);
+            if let Some(animation_name) = part.chosen_animation.clone().or(first_animation.clone())
+            {
+                part.choose_animation(animation_name.as_deref());
+            }
+        }
+        Ok(log)
+    }
+}
+
+///
+/// Constructs a [Mesh] for each of the given [CpuMesh]es, in the same order, where the indexed meshes with identical vertex data,
+/// for example glTF primitives using different materials but the same vertex buffers, share the vertex and index buffers on the GPU.
+/// The meshes get the lightmap uv coordinates matching their [CpuMesh] in the given [LightmapUvs], if any.
+///
+fn shared_meshes(
+    context: &Context,
+    cpu_meshes: &[&CpuMesh],
+    lightmap_uvs: &LightmapUvs,
+) -> Vec<Mesh> {
+    let is_indexed = |cpu_mesh: &CpuMesh| !matches!(cpu_mesh.indices, Indices::None);
+    let mut groups: Vec<Vec<usize>> = Vec::new();
+    for (i, cpu_mesh) in cpu_meshes.iter().enumerate() {
+        let group = groups.iter_mut().find(|group| {
+            let other = cpu_meshes[group[0]];
+            is_indexed(cpu_mesh) && is_indexed(other) && has_same_vertex_data(cpu_mesh, other)
+        });
+        match group {
+            Some(group) => group.push(i),
+            None => groups.push(vec![i]),
+        }
+    }
+
+    let mut meshes = (0..cpu_meshes.len()).map(|_| None).collect::<Vec<_>>();
+    for group in groups {
+        if group.len() == 1 {
+            let cpu_mesh = cpu_meshes[group[0]];
+            let mut mesh = Mesh::new(context, cpu_mesh);
+            if let Some(lightmap_uvs) = lightmap_uvs.get(cpu_mesh) {
+                mesh.set_lightmap_uvs(lightmap_uvs);
+            }
+            meshes[group[0]] = Some(mesh);
+            continue;
+        }
+        let mut indices = Vec::new();
+        let mut ranges = Vec::new();
+        for &i in group.iter() {
+            let start = indices.len() as u32;
+            indices.extend(cpu_meshes[i].indices.to_u32().unwrap());
+            ranges.push(start..indices.len() as u32);
+        }
+        let first = cpu_meshes[group[0]];
+        let cpu_mesh = CpuMesh {
+            positions: first.positions.clone(),
+            indices: if first.positions.len() <= u16::MAX as usize + 1 {
+                Indices::U16(indices.into_iter().map(|i| i as u16).collect())
+            } else {
+                Indices::U32(indices)
+            },
+            normals: first.normals.clone(),
+            tangents: first.tangents.clone(),
+            uvs: first.uvs.clone(),
+            colors: first.colors.clone(),
+        };
+        // The lightmap uv coordinates are set before the buffers are shared, since setting them afterwards copies the buffers
+        for (&i, mesh) in group.iter().zip(Mesh::new_with_ranges_and_lightmap_uvs(
+            context,
+            &cpu_mesh,
+            lightmap_uvs.get(first),
+            ranges,
+        )) {
+            meshes[i] = Some(mesh);
+        }
+    }
+    meshes.into_iter().map(|mesh| mesh.unwrap()).collect()
+}
+
+fn has_same_vertex_data(a: &CpuMesh, b: &CpuMesh) -> bool {
+    let same_positions = match (&a.positions, &b.positions) {
+        (Positions::F32(a), Positions::F32(b)) => a == b,
+        (Positions::F64(a), Positions::F64(b)) => a == b,
+        _ => false,
+    };
+    same_positions
+        && a.normals == b.normals
+        && a.tangents == b.tangents
+        && a.uvs == b.uvs
+        && a.colors == b.colors
 }
 
 impl<M: Material> std::ops::Deref for Model<M> {
     type Target = Vec<ModelPart<M>>;
     fn deref(&self) -> &Self::Target {
-        &self.0
+        &self.parts
     }
 }
 
 impl<M: Material> std::ops::DerefMut for Model<M> {
     fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.0
+        &mut self.parts
+    }
+}
+
+///
+/// A log of the changes applied to a [Model] by [Model::reload], which implements [std::fmt::Display] for printing.
+///
+#[derive(Clone, Debug, Default)]
+pub struct ModelReloadLog {
+    /// The names of the parts that were added.
+    pub added_parts: Vec<String>,
+    /// The names of the parts that were removed.
+    pub removed_parts: Vec<String>,
+    /// The names of the parts where the vertex data changed.
+    pub changed_geometries: Vec<String>,
+    /// The names of the parts where the transformation changed.
+    pub changed_transformations: Vec<String>,
+    /// The names of the materials that changed or were added.
+    pub changed_materials: Vec<String>,
+    /// The number of parts that did not change.
+    pub unchanged_parts: usize,
+}
+
+impl ModelReloadLog {
+    ///
+    /// Returns whether nothing changed.
+    ///
+    pub fn is_empty(&self) -> bool {
+        self.added_parts.is_empty()
+            && self.removed_parts.is_empty()
+            && self.changed_geometries.is_empty()
+            && self.changed_transformations.is_empty()
+            && self.changed_materials.is_empty()
+    }
+}
+
+impl std::fmt::Display for ModelReloadLog {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_empty() {
+            return write!(f, "no changes");
+        }
+        let mut separator = "";
+        for (description, names) in [
+            ("added parts", &self.added_parts),
+            ("removed parts", &self.removed_parts),
+            ("changed geometries", &self.changed_geometries),
+            ("changed transformations", &self.changed_transformations),
+            ("changed materials", &self.changed_materials),
+        ] {
+            if !names.is_empty() {
+                write!(f, "{}{}: {}", separator, description, names.join(", "))?;
+                separator = "; ";
+            }
+        }
+        write!(f, "{}{} unchanged parts", separator, self.unchanged_parts)
+    }
+}
+
+fn material_name(
+    cpu_model: &CpuModel,
+    primitive: &three_d_asset::Primitive,
+) -> Result<Option<String>, RendererError> {
+    primitive
+        .material_index
+        .map(|index| {
+            cpu_model
+                .materials
+                .get(index)
+                .map(|material| material.name.clone())
+                .ok_or_else(|| {
+                    RendererError::MissingMaterial(index.to_string(), primitive.name.clone())
+                })
+        })
+        .transpose()
+}
+
+///
+/// Hashes of the vertex data of a [CpuMesh], where the vertex positions and normals are separate from the rest,
+/// since those can be updated without constructing a new [Mesh].
+///
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct MeshFingerprint {
+    layout: u64,
+    vertices: u64,
+}
+
+impl MeshFingerprint {
+    fn new(cpu_mesh: &CpuMesh) -> Self {
+        let mut hasher = DefaultHasher::new();
+        cpu_mesh.vertex_count().hash(&mut hasher);
+        cpu_mesh.normals.is_some().hash(&mut hasher);
+        cpu_mesh.indices.to_u32().hash(&mut hasher);
+        hash_floats(
+            &mut hasher,
+            cpu_mesh.uvs.iter().flatten().flat_map(|uv| [uv.x, uv.y]),
+        );
+        hash_floats(
+            &mut hasher,
+            cpu_mesh
+                .tangents
+                .iter()
+                .flatten()
+                .flat_map(|t| [t.x, t.y, t.z, t.w]),
+        );
+        cpu_mesh.uvs.is_some().hash(&mut hasher);
+        cpu_mesh.tangents.is_some().hash(&mut hasher);
+        cpu_mesh.colors.hash(&mut hasher);
+        let layout = hasher.finish();
+
+        let mut hasher = DefaultHasher::new();
+        match &cpu_mesh.positions {
+            Positions::F32(positions) => {
+                hash_floats(&mut hasher, positions.iter().flat_map(|p| [p.x, p.y, p.z]))
+            }
+            Positions::F64(positions) => positions
+                .iter()
+                .flat_map(|p| [p.x, p.y, p.z])
+                .for_each(|v| v.to_bits().hash(&mut hasher)),
+        }
+        hash_floats(
+            &mut hasher,
+            cpu_mesh
+                .normals
+                .iter()
+                .flatten()
+                .flat_map(|n| [n.x, n.y, n.z]),
+        );
+        Self {
+            layout,
+            vertices: hasher.finish(),
+        }
+    }
+}
+
+///
+/// Returns a hash of each material in the [CpuModel], including the texture data, by name.
+///
+fn material_fingerprints(cpu_model: &CpuModel) -> HashMap<String, u64> {
+    cpu_model
+        .materials
+        .iter()
+        .map(|material| {
+            let mut hasher = DefaultHasher::new();
+            // The debug output contains all parameters, but only the size of the texture data
+            use std::fmt::Write;
+            write!(HashWriter(&mut hasher), "{:?}", material).unwrap();
+            for texture in [
+                &material.albedo_texture,
+                &material.occlusion_metallic_roughness_texture,
+                &material.metallic_roughness_texture,
+                &material.occlusion_texture,
+                &material.normal_texture,
+                &material.emissive_texture,
+                &material.transmission_texture,
+            ]
+            .into_iter()
+            .flatten()
+            {
+                hash_texture_data(&mut hasher, &texture.data);
+            }
+            (material.name.clone(), hasher.finish())
+        })
+        .collect()
+}
+
+struct HashWriter<'a>(&'a mut DefaultHasher);
+
+impl std::fmt::Write for HashWriter<'_> {
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+        self.0.write(s.as_bytes());
+        Ok(())
+    }
+}
+
+fn hash_floats(hasher: &mut DefaultHasher, values: impl Iterator<Item = f32>) {
+    values.for_each(|v| v.to_bits().hash(hasher));
+}
+
+fn hash_texture_data(hasher: &mut DefaultHasher, data: &TextureData) {
+    let hash_f16 = |hasher: &mut DefaultHasher, values: &mut dyn Iterator<Item = &f16>| {
+        values.for_each(|v| v.to_bits().hash(hasher))
+    };
+    match data {
+        TextureData::RU8(data) => data.hash(hasher),
+        TextureData::RgU8(data) => data.hash(hasher),
+        TextureData::RgbU8(data) => data.hash(hasher),
+        TextureData::RgbaU8(data) => data.hash(hasher),
+        TextureData::RF16(data) => hash_f16(hasher, &mut data.iter()),
+        TextureData::RgF16(data) => hash_f16(hasher, &mut data.iter().flatten()),
+        TextureData::RgbF16(data) => hash_f16(hasher, &mut data.iter().flatten()),
+        TextureData::RgbaF16(data) => hash_f16(hasher, &mut data.iter().flatten()),
+        TextureData::RF32(data) => hash_floats(hasher, data.iter().copied()),
+        TextureData::RgF32(data) => hash_floats(hasher, data.iter().flatten().copied()),
+        TextureData::RgbF32(data) => hash_floats(hasher, data.iter().flatten().copied()),
+        TextureData::RgbaF32(data) => hash_floats(hasher, data.iter().flatten().copied()),
     }
 }
@@ -0,0 +1,101 @@
+use crate::renderer::*;
+use std::ops::Range;
+
+///
+/// A triangle mesh where different ranges of the triangles are rendered with different materials.
+/// The vertex and index data is only transfered to the GPU once and shared between all the ranges, see [Mesh::new_with_ranges],
+/// and rendering issues one draw call for each range with the corresponding material.
+///
+/// Each range is a separate [Object] when iterating, so when using this in [pick] or [ray_intersect],
+/// the [IntersectionResult::geometry_id] is the index of the range that was hit (offset by the number of geometries before this mesh in the list).
+///
+pub struct MultiMaterialMesh<M: Material> {
+    parts: Vec<Gm<Mesh, M>>,
+}
+
+impl<M: Material> MultiMaterialMesh<M> {
+    ///
+    /// Creates a new mesh from the given [CpuMesh] where each of the given ranges is rendered with the corresponding material.
+    /// A range is given in indices if the [CpuMesh] is indexed, otherwise in vertices, and should be a multiple of three.
+    ///
+    pub fn new(
+        context: &Context,
+        cpu_mesh: &CpuMesh,
+        parts: impl IntoIterator<Item = (Range<u32>, M)>,
+    ) -> Self {
+        let (ranges, materials): (Vec<_>, Vec<_>) = parts.into_iter().unzip();
+        Self {
+            parts: Mesh::new_with_ranges(context, cpu_mesh, ranges)
+                .into_iter()
+                .zip(materials)
+                .map(|(mesh, material)| Gm::new(mesh, material))
+                .collect(),
+        }
+    }
+
+    ///
+    /// Returns the parts of this mesh, one for each range given at construction, which can be used to access the range and material of each part.
+    ///
+    pub fn parts(&self) -> &[Gm<Mesh, M>] {
+        &self.parts
+    }
+
+    ///
+    /// Returns the parts of this mesh, which can be used to change the material of a part.
+    ///
+    pub fn parts_mut(&mut self) -> &mut [Gm<Mesh, M>] {
+        &mut self.parts
+    }
+
+    ///
+    /// Returns the local to world transformation applied to this mesh.
+    ///
+    pub fn transformation(&self) -> Mat4 {
+        self.parts
+            .first()
+            .map(|part| part.transformation())
+            .unwrap_or(Mat4::identity())
+    }
+
+    ///
+    /// Set the local to world transformation applied to all parts of this mesh.
+    ///
+    pub fn set_transformation(&mut self, transformation: Mat4) {
+        for part in self.parts.iter_mut() {
+            part.set_transformation(transformation);
+        }
+    }
+
+    ///
+    /// For updating the animation of all parts of this mesh, see [Mesh::set_animation].
+    ///
+    pub fn animate(&mut self, time: f32) {
+        for part in self.parts.iter_mut() {
+            part.animate(time);
+        }
+    }
+
+    ///
+    /// Returns the axis aligned bounding box of all parts of this mesh.
+    ///
+    pub fn aabb(&self) -> AxisAlignedBoundingBox {
+        let mut aabb = AxisAlignedBoundingBox::EMPTY;
+        for part in self.parts.iter() {
+            aabb.expand_with_aabb(part.aabb());
+        }
+        aabb
+    }
+}
+
+impl<'a, M: Material> IntoIterator for &'a MultiMaterialMesh<M> {
+    type Item = &'a dyn Object;
+    type IntoIter = std::vec::IntoIter<&'a dyn Object>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.parts
+            .iter()
+            .map(|part| part as &dyn Object)
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+}
@@ -0,0 +1,172 @@
+use crate::renderer::*;
+
+///
+/// A handle to an object in an [ObjectCollection].
+/// The handle stays valid until the object is removed, also when other objects are inserted or removed.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ObjectHandle {
+    index: u32,
+    generation: u32,
+}
+
+struct Slot {
+    generation: u32,
+    object: Option<Box<dyn Object>>,
+}
+
+///
+/// A collection of objects which can be of different types, for example [Gm]s with different geometry and material types.
+/// Each inserted object is identified by an [ObjectHandle] which can be used to access or remove the object later.
+///
+/// A reference to the collection can be used directly in a render call, for example [RenderTarget::render],
+/// and in any other function that takes an iterator of [Object]s or [Geometry]s, for example [pick].
+///
+#[derive(Default)]
+pub struct ObjectCollection {
+    slots: Vec<Slot>,
+    free: Vec<u32>,
+    count: usize,
+}
+
+impl ObjectCollection {
+    ///
+    /// Creates a new empty collection.
+    ///
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    ///
+    /// Inserts the object into the collection and returns a handle to it.
+    ///
+    pub fn insert(&mut self, object: impl Object + 'static) -> ObjectHandle {
+        self.insert_boxed(Box::new(object))
+    }
+
+    ///
+    /// Inserts the boxed object into the collection and returns a handle to it.
+    ///
+    pub fn insert_boxed(&mut self, object: Box<dyn Object>) -> ObjectHandle {
+        self.count += 1;
+        if let Some(index) = self.free.pop() {
+            let slot = &mut self.slots[index as usize];
+            slot.object = Some(object);
+            ObjectHandle {
+                index,
+                generation: slot.generation,
+            }
+        } else {
+            self.slots.push(Slot {
+                generation: 0,
+                object: Some(object),
+            });
+            ObjectHandle {
+                index: self.slots.len() as u32 - 1,
+                generation: 0,
+            }
+        }
+    }
+
+    ///
+    /// Removes the object with the given handle from the collection and returns it.
+    /// Returns `None` if the object has already been removed.
+    ///
+    pub fn remove(&mut self, handle: ObjectHandle) -> Option<Box<dyn Object>> {
+        let slot = self.slots.get_mut(handle.index as usize)?;
+        if slot.generation != handle.generation {
+            return None;
+        }
+        let object = slot.object.take()?;
+        slot.generation = slot.generation.wrapping_add(1);
+        self.free.push(handle.index);
+        self.count -= 1;
+        Some(object)
+    }
+
+    ///
+    /// Returns the object with the given handle or `None` if it has been removed.
+    ///
+    pub fn get(&self, handle: ObjectHandle) -> Option<&dyn Object> {
+        self.slots
+            .get(handle.index as usize)
+            .filter(|slot| slot.generation == handle.generation)
+            .and_then(|slot| slot.object.as_deref())
+    }
+
+    ///
+    /// Returns the object with the given handle mutably or `None` if it has been removed.
+    ///
+    pub fn get_mut(&mut self, handle: ObjectHandle) -> Option<&mut dyn Object> {
+        match self.slots.get_mut(handle.index as usize) {
+            Some(slot) if slot.generation == handle.generation => slot
+                .object
+                .as_deref_mut()
+                .map(|object| object as &mut dyn Object),
+            _ => None,
+        }
+    }
+
+    ///
+    /// Returns whether the object with the given handle is in the collection.
+    ///
+    pub fn contains(&self, handle: ObjectHandle) -> bool {
+        self.get(handle).is_some()
+    }
+
+    ///
+    /// Returns the number of objects in the collection.
+    ///
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    ///
+    /// Returns whether the collection is empty.
+    ///
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    ///
+    /// Returns an iterator over the handles and objects in the collection.
+    ///
+    pub fn iter(&self) -> impl Iterator<Item = (ObjectHandle, &dyn Object)> {
+        self.slots.iter().enumerate().filter_map(|(index, slot)| {
+            slot.object.as_deref().map(|object| {
+                (
+                    ObjectHandle {
+                        index: index as u32,
+                        generation: slot.generation,
+                    },
+                    object,
+                )
+            })
+        })
+    }
+
+    ///
+    /// Updates the animation of all the objects in the collection, see [Geometry::animate].
+    ///
+    pub fn animate(&mut self, time: f32) {
+        for object in self
+            .slots
+            .iter_mut()
+            .filter_map(|slot| slot.object.as_mut())
+        {
+            object.animate(time);
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a ObjectCollection {
+    type Item = &'a dyn Object;
+    type IntoIter = std::vec::IntoIter<&'a dyn Object>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+            .map(|(_, object)| object)
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+}
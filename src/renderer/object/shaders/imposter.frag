@@ -1,3 +1,7 @@
+#include <three-d/shared>
+#include <three-d/tone_mapping>
+#include <three-d/color_mapping>
+
 uniform sampler2DArray tex;
 
 uniform mat4 view;
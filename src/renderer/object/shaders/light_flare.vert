@@ -0,0 +1,33 @@
+uniform mat4 viewProjection;
+uniform vec3 flarePosition;
+uniform vec2 flareHalfSize;
+uniform vec4 flareColor;
+
+#ifdef SCREEN_SIZE
+uniform vec2 viewportSize;
+#else
+uniform mat4 view;
+#endif
+
+in vec3 position;
+
+out vec2 uvs;
+out vec4 col;
+out vec3 pos;
+
+void main()
+{
+    uvs = 0.5 * position.xy + 0.5;
+    col = flareColor;
+
+#ifdef SCREEN_SIZE
+    vec4 center = viewProjection * vec4(flarePosition, 1.0);
+    pos = flarePosition;
+    gl_Position = center + vec4(2.0 * position.xy * flareHalfSize / viewportSize * center.w, 0.0, 0.0);
+#else
+    vec3 right = vec3(view[0][0], view[1][0], view[2][0]);
+    vec3 up = vec3(view[0][1], view[1][1], view[2][1]);
+    pos = flarePosition + position.x * flareHalfSize.x * right + position.y * flareHalfSize.y * up;
+    gl_Position = viewProjection * vec4(pos, 1.0);
+#endif
+}
@@ -1,6 +1,7 @@
 
 uniform mat4 view;
 uniform mat4 projection;
+uniform float farClipDepth;
 
 in vec3 position;
 
@@ -9,5 +10,7 @@ out vec3 coords;
 void main()
 {
     coords = position;
-    gl_Position = (projection * mat4(mat3(view)) * vec4(position, 1.)).xyww;
+    // Place the skybox at the far plane
+    vec4 p = projection * mat4(mat3(view)) * vec4(position, 1.);
+    gl_Position = vec4(p.xy, farClipDepth * p.w, p.w);
 }
\ No newline at end of file
@@ -0,0 +1,204 @@
+use crate::renderer::*;
+
+///
+/// A debug visualization of a skeleton, ie. a hierarchy of joints, consisting of an octahedral bone between each joint and its parent and a small sphere at each joint.
+/// The size of the bones and joints are scaled according to the size of the skeleton.
+/// Each bone is identified by the index of the joint at the end of the bone, ie. the child joint.
+/// Used for debugging skinned models, see also [JointWeightMaterial].
+///
+/// By default, the skeleton is rendered on top of everything else rendered before it, so render it in a separate render call after the rest of the scene.
+///
+pub struct SkeletonGizmo {
+    bones: Gm<InstancedMesh, ColorMaterial>,
+    joints: Gm<InstancedMesh, ColorMaterial>,
+    parents: Vec<Option<usize>>,
+    joint_transformations: Vec<Mat4>,
+    bone_colors: Vec<Srgba>,
+    highlight_color: Srgba,
+    selected_bone: Option<usize>,
+}
+
+impl SkeletonGizmo {
+    ///
+    /// Creates a new skeleton gizmo from a joint hierarchy, given as the index of the parent of each joint (`None` if the joint is a root),
+    /// and the current global transformation of each joint, ie. the transformation from the joint space to world space.
+    ///
+    pub fn new(
+        context: &Context,
+        parents: &[Option<usize>],
+        joint_transformations: &[Mat4],
+    ) -> Self {
+        let instances = Instances {
+            transformations: Vec::new(),
+            colors: Some(Vec::new()),
+            ..Default::default()
+        };
+        let mut material = ColorMaterial {
+            color: Srgba::WHITE,
+            ..Default::default()
+        };
+        material.render_states.depth_test = DepthTest::Always;
+        let mut gizmo = Self {
+            bones: Gm::new(
+                InstancedMesh::new(context, &instances, &octahedron()),
+                material.clone(),
+            ),
+            joints: Gm::new(
+                InstancedMesh::new(context, &instances, &CpuMesh::sphere(8)),
+                material,
+            ),
+            parents: parents.to_vec(),
+            joint_transformations: Vec::new(),
+            bone_colors: vec![Srgba::new_opaque(200, 200, 200); parents.len()],
+            highlight_color: Srgba::new_opaque(255, 150, 0),
+            selected_bone: None,
+        };
+        gizmo.set_joint_transformations(joint_transformations);
+        gizmo
+    }
+
+    ///
+    /// Updates the global transformation of each joint, ie. the transformation from the joint space to world space.
+    /// Call this each frame the skeleton is animated.
+    ///
+    pub fn set_joint_transformations(&mut self, joint_transformations: &[Mat4]) {
+        self.joint_transformations = joint_transformations.to_vec();
+        self.update();
+    }
+
+    ///
+    /// Sets the color of each bone, where the bone is identified by the index of its child joint.
+    ///
+    pub fn set_bone_colors(&mut self, colors: &[Srgba]) {
+        self.bone_colors = colors.to_vec();
+        self.update();
+    }
+
+    ///
+    /// Selects the bone with the given child joint index, which is then rendered in the highlight color (see [Self::set_highlight_color]).
+    /// Use `None` to clear the selection.
+    ///
+    pub fn set_selected_bone(&mut self, joint_index: Option<usize>) {
+        self.selected_bone = joint_index;
+        self.update();
+    }
+
+    ///
+    /// Returns the child joint index of the selected bone, if any.
+    ///
+    pub fn selected_bone(&self) -> Option<usize> {
+        self.selected_bone
+    }
+
+    ///
+    /// Sets the color used for rendering the selected bone.
+    ///
+    pub fn set_highlight_color(&mut self, color: Srgba) {
+        self.highlight_color = color;
+        self.update();
+    }
+
+    ///
+    /// Specifies whether the skeleton is rendered on top of everything else (the default) or is depth tested against the rest of the scene.
+    ///
+    pub fn set_on_top(&mut self, on_top: bool) {
+        let depth_test = if on_top {
+            DepthTest::Always
+        } else {
+            DepthTest::Less
+        };
+        self.bones.material.render_states.depth_test = depth_test;
+        self.joints.material.render_states.depth_test = depth_test;
+    }
+
+    fn update(&mut self) {
+        let positions = self
+            .joint_transformations
+            .iter()
+            .map(|t| t.w.truncate())
+            .collect::<Vec<_>>();
+        let size = AxisAlignedBoundingBox::new_with_positions(&positions)
+            .size()
+            .magnitude();
+        let joint_radius = if size > 0.0 { 0.015 * size } else { 0.05 };
+
+        let mut bone_transformations = Vec::new();
+        let mut bone_colors = Vec::new();
+        for (joint, parent) in self.parents.iter().enumerate() {
+            if let Some((p0, p1)) = parent
+                .and_then(|parent| positions.get(parent))
+                .zip(positions.get(joint))
+            {
+                let length = p0.distance(*p1);
+                if length > 0.0 {
+                    bone_transformations.push(
+                        Mat4::from_translation(*p0)
+                            * rotation_matrix_from_dir_to_dir(vec3(1.0, 0.0, 0.0), *p1 - *p0)
+                            * Mat4::from_scale(length),
+                    );
+                    bone_colors.push(if self.selected_bone == Some(joint) {
+                        self.highlight_color
+                    } else {
+                        self.bone_colors.get(joint).copied().unwrap_or(Srgba::WHITE)
+                    });
+                }
+            }
+        }
+        self.bones.set_instances(&Instances {
+            transformations: bone_transformations,
+            colors: Some(bone_colors),
+            ..Default::default()
+        });
+        self.joints.set_instances(&Instances {
+            transformations: positions
+                .iter()
+                .map(|p| Mat4::from_translation(*p) * Mat4::from_scale(joint_radius))
+                .collect(),
+            colors: Some(
+                (0..positions.len())
+                    .map(|joint| {
+                        if self.selected_bone == Some(joint) {
+                            self.highlight_color
+                        } else {
+                            Srgba::WHITE
+                        }
+                    })
+                    .collect(),
+            ),
+            ..Default::default()
+        });
+    }
+}
+
+///
+/// An octahedral bone shape from (0, 0, 0) to (1, 0, 0).
+///
+fn octahedron() -> CpuMesh {
+    let head = vec3(0.0, 0.0, 0.0);
+    let tail = vec3(1.0, 0.0, 0.0);
+    let ring = [
+        vec3(0.1, 0.1, 0.0),
+        vec3(0.1, 0.0, 0.1),
+        vec3(0.1, -0.1, 0.0),
+        vec3(0.1, 0.0, -0.1),
+    ];
+    let mut positions = Vec::new();
+    for i in 0..4 {
+        let r0 = ring[i];
+        let r1 = ring[(i + 1) % 4];
+        positions.extend([head, r1, r0, tail, r0, r1]);
+    }
+    CpuMesh {
+        positions: Positions::F32(positions),
+        ..Default::default()
+    }
+}
+
+impl<'a> IntoIterator for &'a SkeletonGizmo {
+    type Item = &'a dyn Object;
+    type IntoIter = std::array::IntoIter<&'a dyn Object, 2>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        [&self.bones as &dyn Object, &self.joints as &dyn Object].into_iter()
+    }
+}
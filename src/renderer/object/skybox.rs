@@ -127,7 +127,11 @@ impl Skybox {
         Skybox {
             context: context.clone(),
             vertex_buffer,
-            material: SkyboxMaterial { texture },
+            material: SkyboxMaterial {
+                texture,
+                rotation: Quat::one(),
+                intensity: 1.0,
+            },
         }
     }
 
@@ -137,6 +141,35 @@ impl Skybox {
     pub fn texture(&self) -> &Arc<TextureCubeMap> {
         &self.material.texture
     }
+
+    ///
+    /// Sets the rotation of the sky, which is applied when sampling the cube map texture, so the sky can be rotated each frame.
+    /// Use the same rotation for an [AmbientLight] with an environment calculated from the same texture, see [AmbientLight::rotation], so the reflections match the background.
+    ///
+    pub fn set_rotation(&mut self, rotation: Quat) {
+        self.material.rotation = rotation;
+    }
+
+    ///
+    /// Returns the rotation of the sky, see [Skybox::set_rotation].
+    ///
+    pub fn rotation(&self) -> Quat {
+        self.material.rotation
+    }
+
+    ///
+    /// Sets the multiplier of the colors of the sky, for example to dim the sky together with the [intensity](AmbientLight::intensity) of an ambient light. Defaults to 1.
+    ///
+    pub fn set_intensity(&mut self, intensity: f32) {
+        self.material.intensity = intensity;
+    }
+
+    ///
+    /// Returns the multiplier of the colors of the sky, see [Skybox::set_intensity].
+    ///
+    pub fn intensity(&self) -> f32 {
+        self.material.intensity
+    }
 }
 
 impl<'a> IntoIterator for &'a Skybox {
@@ -152,6 +185,7 @@ impl Geometry for Skybox {
     fn draw(&self, viewer: &dyn Viewer, program: &Program, render_states: RenderStates) {
         program.use_uniform("view", viewer.view());
         program.use_uniform("projection", viewer.projection());
+        program.use_uniform("farClipDepth", viewer.depth_mode().clip_space_range().1);
         program.use_vertex_attribute("position", &self.vertex_buffer);
         program.draw_arrays(render_states, viewer.viewport(), 36);
     }
@@ -0,0 +1,489 @@
+//!
+//! Culling of objects hidden behind other objects using a hierarchical depth buffer, see [OcclusionCulling].
+//!
+
+use crate::renderer::*;
+
+const REDUCE_SHADER: &str = "
+uniform highp sampler2D sourceTexture;
+uniform ivec2 sourceSize;
+uniform ivec2 targetSize;
+
+layout (location = 0) out vec4 outColor;
+
+void main()
+{
+    ivec2 coords = ivec2(gl_FragCoord.xy);
+    // The last row and column also cover the remaining source texels when the source size is odd
+    ivec2 footprint = 2 + ivec2(equal(coords, targetSize - 1)) * (sourceSize - 2 * targetSize);
+    vec2 result = vec2(1.0e30, -1.0e30);
+    for (int y = 0; y < footprint.y; y++) {
+        for (int x = 0; x < footprint.x; x++) {
+            ivec2 source_coords = min(2 * coords + ivec2(x, y), sourceSize - 1);
+#ifdef FROM_DEPTH
+            vec2 depth = texelFetch(sourceTexture, source_coords, 0).xx;
+#else
+            vec2 depth = texelFetch(sourceTexture, source_coords, 0).xy;
+#endif
+            result = vec2(min(result.x, depth.x), max(result.y, depth.y));
+        }
+    }
+    outColor = vec4(result, 0.0, 1.0);
+}
+";
+
+///
+/// The number of objects tested and culled by the last call to [OcclusionCulling::filter].
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct OcclusionStats {
+    /// The number of objects tested.
+    pub tested: usize,
+    /// The number of objects which are hidden behind the occluders and therefore removed.
+    pub culled: usize,
+}
+
+struct Level {
+    width: u32,
+    height: u32,
+    // The size of a texel in this level measured in pixels of the depth texture
+    texel_size: u32,
+    // The minimum and maximum depth of the pixels covered by each texel
+    depths: Vec<[f32; 2]>,
+}
+
+impl Level {
+    fn reduce(&self) -> Self {
+        let width = (self.width / 2).max(1);
+        let height = (self.height / 2).max(1);
+        let mut depths = vec![[f32::MAX, f32::MIN]; (width * height) as usize];
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let source = self.depths[(y * self.width + x) as usize];
+                let target = &mut depths
+                    [((y / 2).min(height - 1) * width + (x / 2).min(width - 1)) as usize];
+                target[0] = target[0].min(source[0]);
+                target[1] = target[1].max(source[1]);
+            }
+        }
+        Self {
+            width,
+            height,
+            texel_size: 2 * self.texel_size,
+            depths,
+        }
+    }
+}
+
+///
+/// Hierarchical Z (Hi-Z) occlusion culling which removes objects that are hidden behind other objects, for example buildings in a city,
+/// which is useful when most objects are inside the view frustum but hidden.
+///
+/// Each frame, the depth of a set of occluders is rendered in a depth prepass using [OcclusionCulling::render_depth_prepass],
+/// or an existing depth texture is given to [OcclusionCulling::update].
+/// A pyramid of the minimum and maximum depth is then built on the GPU from the depth texture until the size is at most [OcclusionCulling::readback_size],
+/// which is read back to the CPU where the rest of the pyramid is built.
+/// Finally, the objects are tested against the pyramid using [OcclusionCulling::filter], where the screen space bounding rectangle of an object
+/// is compared to the depth in the pyramid level where the rectangle covers at most 2x2 texels.
+///
+/// The test is conservative, so an object is only culled if it is hidden:
+/// - Transparent objects are not used as occluders in the prepass and objects with alpha testing or displacement in the vertex shader
+///   are rendered with their own material, so the prepass depth is the same as the depth when rendering.
+/// - The bounding box of each tested object is enlarged by the [OcclusionCulling::margin] which can be used for geometry displaced outside its bounding box.
+/// - Objects intersecting the near plane or outside the view are never culled by the occlusion test, use frustum culling for those.
+///
+/// ```no_run
+/// # use three_d::*;
+/// # let context: Context = unimplemented!();
+/// # let camera: Camera = unimplemented!();
+/// # let buildings: Vec<Gm<Mesh, PhysicalMaterial>> = unimplemented!();
+/// # let objects: Vec<Gm<Mesh, PhysicalMaterial>> = unimplemented!();
+/// let mut occlusion_culling = OcclusionCulling::new(&context);
+///
+/// // Each frame
+/// occlusion_culling.render_depth_prepass(&camera, &buildings);
+/// let visible_objects = occlusion_culling.filter(&objects);
+/// println!("Culled {} objects", occlusion_culling.stats().culled);
+/// RenderTarget::screen(&context, 1280, 720).render(&camera, buildings.iter().chain(visible_objects), &[]);
+/// ```
+///
+pub struct OcclusionCulling {
+    context: Context,
+    depth_texture: Option<DepthTexture2D>,
+    gpu_levels: Vec<Texture2D>,
+    from_depth_program: Program,
+    reduce_program: Program,
+    levels: Vec<Level>,
+    size: (u32, u32),
+    view_projection: Mat4,
+    depth_mode: DepthMode,
+    stats: OcclusionStats,
+    culled: Vec<AxisAlignedBoundingBox>,
+    /// The maximum width and height of the part of the pyramid which is read back to the CPU.
+    /// A larger size gives a more precise test at the cost of a larger readback. Default is 128.
+    pub readback_size: u32,
+    /// The distance in world space that the bounding box of each tested object is enlarged with before testing.
+    /// Use this for geometry that is displaced outside its bounding box. Default is 0.
+    pub margin: f32,
+    /// Whether the bounding boxes of the culled objects are stored, so they can be visualized using [OcclusionCulling::debug_gizmo]. Default is false.
+    pub visualize: bool,
+}
+
+impl OcclusionCulling {
+    ///
+    /// Creates a new occlusion culling. All objects are visible until the depth pyramid is built,
+    /// see [OcclusionCulling::render_depth_prepass] and [OcclusionCulling::update].
+    ///
+    pub fn new(context: &Context) -> Self {
+        let program = |defines: &str| {
+            Program::from_source(
+                context,
+                crate::core::full_screen_vertex_shader_source(),
+                &format!("{}{}", defines, REDUCE_SHADER),
+            )
+            .expect("Failed compiling shader")
+        };
+        Self {
+            context: context.clone(),
+            depth_texture: None,
+            gpu_levels: Vec::new(),
+            from_depth_program: program("#define FROM_DEPTH\n"),
+            reduce_program: program(""),
+            levels: Vec::new(),
+            size: (0, 0),
+            view_projection: Mat4::identity(),
+            depth_mode: DepthMode::Standard,
+            stats: OcclusionStats::default(),
+            culled: Vec::new(),
+            readback_size: 128,
+            margin: 0.0,
+            visualize: false,
+        }
+    }
+
+    ///
+    /// Renders the depth of the given occluders as seen from the given viewer and builds the depth pyramid used for testing the objects.
+    /// Transparent occluders are ignored. The occluders are rendered with their own material, so alpha tested fragments are discarded as usual.
+    /// Typically, the occluders are large objects close to the viewer, for example walls and buildings.
+    ///
+    pub fn render_depth_prepass(
+        &mut self,
+        viewer: impl Viewer,
+        occluders: impl IntoIterator<Item = impl Object>,
+    ) {
+        let viewport = viewer.viewport();
+        let mut depth_texture = match self.depth_texture.take() {
+            Some(texture)
+                if texture.width() == viewport.width && texture.height() == viewport.height =>
+            {
+                texture
+            }
+            _ => DepthTexture2D::new::<f32>(
+                &self.context,
+                viewport.width,
+                viewport.height,
+                Wrapping::ClampToEdge,
+                Wrapping::ClampToEdge,
+            ),
+        };
+        let frustum = Frustum::new(viewer.projection() * viewer.view());
+        let prepass_viewer = GeometryPassCamera(&viewer);
+        depth_texture
+            .as_depth_target()
+            .clear(ClearState::default().with_depth_mode(viewer.depth_mode()))
+            .write::<RendererError>(|| {
+                for occluder in occluders.into_iter().filter(|o| {
+                    o.material_type() != MaterialType::Transparent && frustum.contains(o.aabb())
+                }) {
+                    occluder.render(&prepass_viewer, &[]);
+                }
+                Ok(())
+            })
+            .unwrap();
+        self.update(&viewer, &depth_texture);
+        self.depth_texture = Some(depth_texture);
+    }
+
+    ///
+    /// Builds the depth pyramid used for testing the objects from the given depth texture,
+    /// which must contain the depth of the occluders as seen from the given viewer, for example from a previous render pass.
+    ///
+    pub fn update(&mut self, viewer: impl Viewer, depth_texture: &DepthTexture2D) {
+        self.view_projection = viewer.projection() * viewer.view();
+        self.depth_mode = viewer.depth_mode();
+        self.size = (depth_texture.width(), depth_texture.height());
+        self.culled.clear();
+
+        // Reduce on the GPU until the size is small enough to be read back
+        let (mut width, mut height) = (depth_texture.width(), depth_texture.height());
+        let mut sizes = Vec::new();
+        loop {
+            let (w, h) = ((width / 2).max(1), (height / 2).max(1));
+            sizes.push((w, h));
+            if (w.max(h) <= self.readback_size.max(1)) || (w == 1 && h == 1) {
+                break;
+            }
+            (width, height) = (w, h);
+        }
+        if self.gpu_levels.len() != sizes.len()
+            || self
+                .gpu_levels
+                .iter()
+                .zip(sizes.iter())
+                .any(|(t, s)| (t.width(), t.height()) != *s)
+        {
+            self.gpu_levels = sizes
+                .iter()
+                .map(|(w, h)| {
+                    Texture2D::new_empty::<[f32; 2]>(
+                        &self.context,
+                        *w,
+                        *h,
+                        Interpolation::Nearest,
+                        Interpolation::Nearest,
+                        None,
+                        Wrapping::ClampToEdge,
+                        Wrapping::ClampToEdge,
+                    )
+                })
+                .collect();
+        }
+        let mut source_size = (depth_texture.width(), depth_texture.height());
+        for i in 0..self.gpu_levels.len() {
+            let (previous, rest) = self.gpu_levels.split_at_mut(i);
+            let target = &mut rest[0];
+            let target_size = (target.width(), target.height());
+            let (program, context) = if i == 0 {
+                (&self.from_depth_program, &self.context)
+            } else {
+                (&self.reduce_program, &self.context)
+            };
+            target
+                .as_color_target(None)
+                .write::<CoreError>(|| {
+                    if i == 0 {
+                        program.use_depth_texture("sourceTexture", depth_texture);
+                    } else {
+                        program.use_texture("sourceTexture", &previous[i - 1]);
+                    }
+                    program.use_uniform(
+                        "sourceSize",
+                        vec2(source_size.0 as i32, source_size.1 as i32),
+                    );
+                    program.use_uniform(
+                        "targetSize",
+                        vec2(target_size.0 as i32, target_size.1 as i32),
+                    );
+                    crate::core::full_screen_draw(
+                        context,
+                        program,
+                        RenderStates {
+                            depth_test: DepthTest::Always,
+                            write_mask: WriteMask::COLOR,
+                            ..Default::default()
+                        },
+                        Viewport::new_at_origo(target_size.0, target_size.1),
+                    );
+                    Ok(())
+                })
+                .unwrap();
+            source_size = target_size;
+        }
+
+        // Read back and build the rest of the pyramid on the CPU
+        let last = self.gpu_levels.last().unwrap();
+        let mut level = Level {
+            width: last.width(),
+            height: last.height(),
+            texel_size: 1 << self.gpu_levels.len(),
+            depths: last.read(),
+        };
+        self.levels.clear();
+        while level.width > 1 || level.height > 1 {
+            let next = level.reduce();
+            self.levels.push(level);
+            level = next;
+        }
+        self.levels.push(level);
+    }
+
+    ///
+    /// Returns whether an object with the given bounding box is hidden behind the occluders, ie. the depth of the closest point of the bounding box
+    /// is further away than the furthest depth in the screen space rectangle covered by the bounding box.
+    /// Returns false if the depth pyramid has not been built.
+    ///
+    pub fn is_occluded(&self, aabb: AxisAlignedBoundingBox) -> bool {
+        if self.levels.is_empty() || aabb.is_empty() || aabb.is_infinite() {
+            return false;
+        }
+        let (min, max) = (
+            aabb.min() - vec3(self.margin, self.margin, self.margin),
+            aabb.max() + vec3(self.margin, self.margin, self.margin),
+        );
+        let reversed = self.depth_mode.is_reversed();
+        let (mut min_x, mut min_y, mut max_x, mut max_y) = (f32::MAX, f32::MAX, f32::MIN, f32::MIN);
+        let mut nearest_depth = self.depth_mode.far_depth();
+        for i in 0..8 {
+            let corner = vec3(
+                if i & 1 == 0 { min.x } else { max.x },
+                if i & 2 == 0 { min.y } else { max.y },
+                if i & 4 == 0 { min.z } else { max.z },
+            );
+            let clip = self.view_projection * corner.extend(1.0);
+            if clip.w <= 0.0001 {
+                // Behind the viewer
+                return false;
+            }
+            let ndc = clip.truncate() / clip.w;
+            let depth = if self.depth_mode == DepthMode::ReversedZeroToOne {
+                ndc.z
+            } else {
+                0.5 * ndc.z + 0.5
+            };
+            if !(0.0..=1.0).contains(&depth) {
+                // Intersecting the near or far plane
+                return false;
+            }
+            nearest_depth = if reversed {
+                nearest_depth.max(depth)
+            } else {
+                nearest_depth.min(depth)
+            };
+            min_x = min_x.min(ndc.x);
+            min_y = min_y.min(ndc.y);
+            max_x = max_x.max(ndc.x);
+            max_y = max_y.max(ndc.y);
+        }
+        if max_x < -1.0 || min_x > 1.0 || max_y < -1.0 || min_y > 1.0 {
+            return false;
+        }
+
+        // The pixel rectangle in the depth texture covered by the bounding box
+        let (width, height) = self.size;
+        let pixel = |ndc: f32, size: u32| {
+            (((0.5 * ndc.clamp(-1.0, 1.0) + 0.5) * size as f32) as u32).min(size - 1)
+        };
+        let (x0, y0) = (pixel(min_x, width), pixel(min_y, height));
+        let (x1, y1) = (pixel(max_x, width), pixel(max_y, height));
+
+        // The finest level where the rectangle covers at most 2x2 texels
+        let level = self
+            .levels
+            .iter()
+            .find(|level| {
+                x1 / level.texel_size - x0 / level.texel_size <= 1
+                    && y1 / level.texel_size - y0 / level.texel_size <= 1
+            })
+            .unwrap_or(self.levels.last().unwrap());
+        let texel = |p: u32, size: u32| (p / level.texel_size).min(size - 1);
+        for y in texel(y0, level.height)..=texel(y1, level.height) {
+            for x in texel(x0, level.width)..=texel(x1, level.width) {
+                let [min_depth, max_depth] = level.depths[(y * level.width + x) as usize];
+                let visible = if reversed {
+                    nearest_depth >= min_depth - 0.00001
+                } else {
+                    nearest_depth <= max_depth + 0.00001
+                };
+                if visible {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
+    ///
+    /// Returns the objects that are not hidden behind the occluders, see [OcclusionCulling::is_occluded], and updates the [OcclusionCulling::stats].
+    /// If [OcclusionCulling::visualize] is enabled, the bounding boxes of the removed objects are stored so they can be shown using [OcclusionCulling::debug_gizmo].
+    ///
+    pub fn filter<'a, T: Object + 'a>(
+        &mut self,
+        objects: impl IntoIterator<Item = &'a T>,
+    ) -> Vec<&'a T> {
+        self.stats = OcclusionStats::default();
+        self.culled.clear();
+        let mut visible = Vec::new();
+        for object in objects {
+            self.stats.tested += 1;
+            let aabb = object.aabb();
+            if self.is_occluded(aabb) {
+                self.stats.culled += 1;
+                if self.visualize {
+                    self.culled.push(aabb);
+                }
+            } else {
+                visible.push(object);
+            }
+        }
+        visible
+    }
+
+    ///
+    /// Returns the number of objects tested and culled by the last call to [OcclusionCulling::filter].
+    ///
+    pub fn stats(&self) -> OcclusionStats {
+        self.stats
+    }
+
+    ///
+    /// Returns a debug visualization of the objects removed by the last call to [OcclusionCulling::filter], where the bounding box of each removed object is shown as red lines.
+    /// Requires that [OcclusionCulling::visualize] is enabled.
+    /// The lines have the given thickness and are rendered on top of everything else rendered before it, so render it in a separate render call after the rest of the scene.
+    ///
+    pub fn debug_gizmo(
+        &self,
+        context: &Context,
+        thickness: f32,
+    ) -> Gm<InstancedMesh, ColorMaterial> {
+        let mut transformations = Vec::new();
+        for aabb in self.culled.iter() {
+            let (min, max) = (aabb.min(), aabb.max());
+            let corner = |i: usize| {
+                vec3(
+                    if i & 1 == 0 { min.x } else { max.x },
+                    if i & 2 == 0 { min.y } else { max.y },
+                    if i & 4 == 0 { min.z } else { max.z },
+                )
+            };
+            for i in 0..8 {
+                for bit in [1, 2, 4] {
+                    if i & bit == 0 {
+                        let (a, b) = (corner(i), corner(i | bit));
+                        let direction = b - a;
+                        if direction.magnitude2() > 0.0 {
+                            transformations.push(
+                                Mat4::from_translation(a)
+                                    * Mat4::from(Quat::from_arc(
+                                        vec3(1.0, 0.0, 0.0),
+                                        direction.normalize(),
+                                        Some(vec3(0.0, 0.0, 1.0)),
+                                    ))
+                                    * Mat4::from_nonuniform_scale(
+                                        direction.magnitude(),
+                                        thickness,
+                                        thickness,
+                                    ),
+                            );
+                        }
+                    }
+                }
+            }
+        }
+        let mut material = ColorMaterial {
+            color: Srgba::RED,
+            ..Default::default()
+        };
+        material.render_states.depth_test = DepthTest::Always;
+        Gm::new(
+            InstancedMesh::new(
+                context,
+                &Instances {
+                    transformations,
+                    ..Default::default()
+                },
+                &CpuMesh::cylinder(8),
+            ),
+            material,
+        )
+    }
+}
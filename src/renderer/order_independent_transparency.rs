@@ -0,0 +1,250 @@
+use crate::renderer::*;
+
+///
+/// The passes of the weighted blended order-independent transparency, which are rendered separately
+/// since the two targets need different blending and blending per target is not supported on web.
+///
+#[derive(Clone, Copy, PartialEq)]
+enum OitPass {
+    Accumulation,
+    Revealage,
+}
+
+///
+/// Renders the transparent objects into an accumulation and a revealage texture which are composited onto the render target when applied as an effect,
+/// see [RenderTarget::render_transparent_oit].
+///
+pub(crate) struct OitComposite {
+    accumulation: PooledTexture,
+    revealage: PooledTexture,
+}
+
+impl OitComposite {
+    ///
+    /// Renders the given objects into the accumulation and revealage textures and returns the objects
+    /// which do not expose their material (see [Object::with_material]) and therefore are not rendered.
+    ///
+    pub fn render<'a, O: Object>(
+        context: &Context,
+        viewer: &dyn Viewer,
+        objects: &'a [O],
+        lights: &[&dyn Light],
+        depth_texture: Option<DepthTexture>,
+    ) -> (Self, Vec<&'a O>) {
+        let viewport = viewer.viewport();
+        let mut accumulation = context.request_texture::<[f16; 4]>(
+            viewport.width,
+            viewport.height,
+            Interpolation::Nearest,
+        );
+        let mut revealage =
+            context.request_texture::<u8>(viewport.width, viewport.height, Interpolation::Nearest);
+
+        let viewer = GeometryPassCamera(viewer);
+        let mut unsupported = Vec::new();
+        accumulation
+            .as_color_target(None)
+            .clear(ClearState::color(0.0, 0.0, 0.0, 0.0))
+            .write::<RendererError>(|| {
+                for object in objects {
+                    let mut result = None;
+                    object.with_material(&mut |material| {
+                        result = Some(render_pass(
+                            context,
+                            &viewer,
+                            object,
+                            material,
+                            lights,
+                            OitPass::Accumulation,
+                            depth_texture,
+                        ));
+                    });
+                    match result {
+                        Some(result) => result?,
+                        None => unsupported.push(object),
+                    }
+                }
+                Ok(())
+            })
+            .unwrap();
+        revealage
+            .as_color_target(None)
+            .clear(ClearState::color(1.0, 1.0, 1.0, 1.0))
+            .write::<RendererError>(|| {
+                for object in objects {
+                    let mut result = Ok(());
+                    object.with_material(&mut |material| {
+                        result = render_pass(
+                            context,
+                            &viewer,
+                            object,
+                            material,
+                            lights,
+                            OitPass::Revealage,
+                            depth_texture,
+                        );
+                    });
+                    result?;
+                }
+                Ok(())
+            })
+            .unwrap();
+        (
+            Self {
+                accumulation,
+                revealage,
+            },
+            unsupported,
+        )
+    }
+}
+
+impl Effect for OitComposite {
+    fn fragment_shader_source(
+        &self,
+        _lights: &[&dyn Light],
+        _color_texture: Option<ColorTexture>,
+        _depth_texture: Option<DepthTexture>,
+    ) -> String {
+        include_str!("effect/shaders/oit_composite.frag").to_string()
+    }
+
+    fn id(
+        &self,
+        _color_texture: Option<ColorTexture>,
+        _depth_texture: Option<DepthTexture>,
+    ) -> EffectMaterialId {
+        EffectMaterialId::OitCompositeEffect
+    }
+
+    fn use_uniforms(
+        &self,
+        program: &Program,
+        _viewer: &dyn Viewer,
+        _lights: &[&dyn Light],
+        _color_texture: Option<ColorTexture>,
+        _depth_texture: Option<DepthTexture>,
+    ) {
+        program.use_texture("accumulationTexture", &self.accumulation);
+        program.use_texture("revealageTexture", &self.revealage);
+    }
+
+    fn render_states(&self) -> RenderStates {
+        RenderStates {
+            depth_test: DepthTest::Always,
+            write_mask: WriteMask::COLOR,
+            blend: Blend::TRANSPARENCY,
+            ..Default::default()
+        }
+    }
+}
+
+///
+/// Renders the geometry with the given material where the output of the material is changed to the output of the given pass.
+/// The program is cached with an id that contains both the id of the material and the pass, since the id of the material cannot be changed.
+///
+fn render_pass(
+    context: &Context,
+    viewer: &dyn Viewer,
+    geometry: &dyn Geometry,
+    material: &dyn Material,
+    lights: &[&dyn Light],
+    pass: OitPass,
+    depth_texture: Option<DepthTexture>,
+) -> Result<(), RendererError> {
+    // The color is already multiplied by the alpha if the material is blended as premultiplied
+    let premultiplied = material.render_states().blend == Blend::PREMULTIPLIED;
+    let mut id = combine_ids(
+        geometry.id(),
+        EffectMaterialId::OitMaterial,
+        lights.iter().map(|l| l.id()),
+    );
+    id.extend(material.id().0.to_le_bytes());
+    id.extend(
+        (pass as u16
+            | (premultiplied as u16) << 1
+            | depth_texture.map(|t| t.id() << 2).unwrap_or(0))
+        .to_le_bytes(),
+    );
+
+    let mut programs = context.programs.write().unwrap();
+    if !programs.contains_key(&id) {
+        let fragment_shader_source = format!(
+            "{}{}{}{}\n{}\n{}",
+            if pass == OitPass::Accumulation {
+                "#define ACCUMULATION\n"
+            } else {
+                ""
+            },
+            if premultiplied {
+                "#define PREMULTIPLIED_ALPHA\n"
+            } else {
+                ""
+            },
+            if depth_texture.is_some() {
+                "#define USE_DEPTH\n"
+            } else {
+                ""
+            },
+            // The main function of the material is called from the main function of the pass
+            material.fragment_shader_source(lights).replacen(
+                "void main()",
+                "void material_main()",
+                1
+            ),
+            depth_texture
+                .map(|t| t.fragment_shader_source())
+                .unwrap_or_default(),
+            include_str!("material/shaders/oit_pass.frag")
+        );
+        programs.insert(
+            id.clone(),
+            Program::from_source(
+                context,
+                &geometry.vertex_shader_source(),
+                &fragment_shader_source,
+            )?,
+        );
+    }
+    let program = programs.get(&id).unwrap();
+
+    material.use_uniforms(program, viewer, lights);
+    if let Some(depth_texture) = depth_texture {
+        depth_texture.use_uniforms(program);
+        let viewport = viewer.viewport();
+        program.use_uniform(
+            "oitTexelSize",
+            vec2(1.0 / viewport.width as f32, 1.0 / viewport.height as f32),
+        );
+        program.use_uniform(
+            "oitDepthDirection",
+            if viewer.depth_mode().is_reversed() {
+                -1.0f32
+            } else {
+                1.0
+            },
+        );
+    }
+    let render_states = RenderStates {
+        // The depth test is done in the shader, since the depth of the opaque objects is not attached to the targets
+        depth_test: DepthTest::Always,
+        write_mask: WriteMask::COLOR,
+        blend: match pass {
+            OitPass::Accumulation => Blend::ADD,
+            // Multiplies the revealage by one minus the alpha
+            OitPass::Revealage => Blend::Enabled {
+                source_rgb_multiplier: BlendMultiplierType::Zero,
+                source_alpha_multiplier: BlendMultiplierType::Zero,
+                destination_rgb_multiplier: BlendMultiplierType::OneMinusSrcColor,
+                destination_alpha_multiplier: BlendMultiplierType::OneMinusSrcAlpha,
+                rgb_equation: BlendEquationType::Add,
+                alpha_equation: BlendEquationType::Add,
+            },
+        },
+        ..material.render_states()
+    };
+    draw_with_depth_mode(context, &viewer, render_states, |render_states| {
+        geometry.draw(viewer, program, render_states)
+    });
+    Ok(())
+}
@@ -0,0 +1,260 @@
+//!
+//! A constant width outline around the silhouette of selected objects, see [Outline].
+//!
+
+use crate::renderer::*;
+
+const MASK_SHADER: &str = "
+#ifdef USE_DEPTH
+uniform vec4 depthTransformation;
+uniform float depthDirection;
+#endif
+
+layout (location = 0) out vec4 outColor;
+
+void main()
+{
+#ifdef USE_DEPTH
+    vec2 uv = depthTransformation.xy + gl_FragCoord.xy * depthTransformation.zw;
+    if (all(greaterThanEqual(uv, vec2(0.0))) && all(lessThanEqual(uv, vec2(1.0)))) {
+        // The bias allows for the small differences in depth when the visible surfaces are rendered again
+        float bias = 2.0 * max(abs(dFdx(gl_FragCoord.z)), abs(dFdy(gl_FragCoord.z))) + 1.0e-6;
+        if ((gl_FragCoord.z - sample_depth(uv)) * depthDirection > bias) {
+            discard;
+        }
+    }
+#endif
+    outColor = vec4(1.0);
+}
+";
+
+const OUTLINE_SHADER: &str = "
+#include <three-d/tone_mapping>
+#include <three-d/color_mapping>
+
+uniform highp sampler2D distanceTexture;
+uniform vec4 maskTransformation;
+uniform vec4 outlineColor;
+uniform float thickness;
+
+in vec2 uvs;
+
+layout (location = 0) out vec4 outColor;
+
+void main()
+{
+    float distance = texture(distanceTexture, maskTransformation.xy + uvs * maskTransformation.zw).r;
+    // No outline on top of the silhouette itself
+    if (distance <= 0.0) {
+        discard;
+    }
+    // Fade out over one pixel to smooth the outer edge of the outline
+    float coverage = clamp(thickness + 1.0 - distance, 0.0, 1.0) * outlineColor.a;
+    if (coverage <= 0.0) {
+        discard;
+    }
+    outColor = vec4(color_mapping(tone_mapping(outlineColor.rgb)) * coverage, coverage);
+}
+";
+
+///
+/// Renders the visible parts of the selected geometries into the mask of an [Outline].
+///
+struct OutlineMask<'a> {
+    depth_texture: Option<DepthTexture<'a>>,
+    depth_transformation: Vec4,
+}
+
+impl Material for OutlineMask<'_> {
+    fn id(&self) -> EffectMaterialId {
+        EffectMaterialId(
+            EffectMaterialId::OutlineMaterialBase.0
+                | self.depth_texture.map(|t| t.id()).unwrap_or(0),
+        )
+    }
+
+    fn fragment_shader_source(&self, _lights: &[&dyn Light]) -> String {
+        if let Some(depth_texture) = self.depth_texture {
+            format!(
+                "#define USE_DEPTH\n{}\n{}",
+                depth_texture.fragment_shader_source(),
+                MASK_SHADER
+            )
+        } else {
+            MASK_SHADER.to_owned()
+        }
+    }
+
+    fn use_uniforms(&self, program: &Program, viewer: &dyn Viewer, _lights: &[&dyn Light]) {
+        if let Some(depth_texture) = self.depth_texture {
+            depth_texture.use_uniforms(program);
+            program.use_uniform("depthTransformation", self.depth_transformation);
+            program.use_uniform(
+                "depthDirection",
+                if viewer.depth_mode().is_reversed() {
+                    -1.0f32
+                } else {
+                    1.0
+                },
+            );
+        }
+    }
+
+    fn render_states(&self) -> RenderStates {
+        RenderStates {
+            depth_test: DepthTest::Always,
+            write_mask: WriteMask::COLOR,
+            cull: Cull::None,
+            ..Default::default()
+        }
+    }
+
+    fn material_type(&self) -> MaterialType {
+        MaterialType::Opaque
+    }
+}
+
+///
+/// A constant width outline around the silhouette of selected objects, for example to highlight picked objects in an editor.
+/// The silhouettes of all the objects are rendered into one mask, so overlapping objects get a single outline without any edges between them,
+/// and the mask is dilated using the distance field generated by [jump_flood_distance], so the width of the outline is the same at any distance to the objects.
+/// Any geometry can be outlined, including instanced meshes where the outline is around all the instances.
+///
+/// Call [Outline::update] each frame the objects or the viewer changes and then apply the outline as a screen material,
+/// after the scene has been rendered, using for example [RenderTarget::apply_screen_material].
+///
+pub struct Outline {
+    context: Context,
+    distance_texture: Option<Texture2D>,
+    mask_transformation: Vec4,
+    /// The color of the outline. The alpha value specifies the opacity of the outline.
+    pub color: Srgba,
+    /// The width of the outline in physical pixels, ie. the pixels of the viewport.
+    /// To get the same width on displays with different pixel densities, multiply a width in logical pixels by the device pixel ratio, see [FrameInput::device_pixel_ratio](crate::FrameInput::device_pixel_ratio).
+    pub thickness: f32,
+}
+
+impl Outline {
+    ///
+    /// Creates a new outline with the given color and thickness in physical pixels.
+    ///
+    pub fn new(context: &Context, color: Srgba, thickness: f32) -> Self {
+        Self {
+            context: context.clone(),
+            distance_texture: None,
+            mask_transformation: vec4(0.0, 0.0, 1.0, 1.0),
+            color,
+            thickness,
+        }
+    }
+
+    ///
+    /// Renders the silhouettes of the given geometries, as seen by the given viewer, into the mask and generates the distance field used for the outline.
+    /// If a depth texture containing the depth of the scene rendered with the same viewer is given, only the visible parts of the geometries are outlined,
+    /// otherwise the outline is drawn around the entire geometries, also when they are behind other objects.
+    /// Giving no geometries removes the outline.
+    ///
+    pub fn update(
+        &mut self,
+        viewer: impl Viewer,
+        geometries: impl IntoIterator<Item = impl Geometry>,
+        depth_texture: Option<DepthTexture>,
+    ) {
+        let viewport = viewer.viewport();
+        let (width, height) = (viewport.width.max(1), viewport.height.max(1));
+        // The mask covers a margin around the viewport, so objects partly outside the screen are still outlined at the edges
+        let margin = (self.thickness.max(0.0).ceil() as u32) + 1;
+        let mask_width = width + 2 * margin;
+        let mask_height = height + 2 * margin;
+
+        // Widen the projection so the viewport covers the center of the mask
+        let mut mask_viewer = MatrixViewer::from_viewer(&viewer);
+        mask_viewer.set_projection(
+            Mat4::from_nonuniform_scale(
+                width as f32 / mask_width as f32,
+                height as f32 / mask_height as f32,
+                1.0,
+            ) * viewer.projection(),
+        );
+        mask_viewer.set_viewport(Viewport::new_at_origo(mask_width, mask_height));
+
+        let mut mask =
+            self.context
+                .request_texture::<u8>(mask_width, mask_height, Interpolation::Nearest);
+        // The fallback for a shader which is not compiled yet would be part of the mask, so wait for the compilation
+        with_synchronous_shader_compilation(&self.context, || {
+            mask.as_color_target(None)
+                .clear(ClearState::color(0.0, 0.0, 0.0, 0.0))
+                .render_with_material(
+                    &OutlineMask {
+                        depth_texture,
+                        depth_transformation: vec4(
+                            -(margin as f32) / width as f32,
+                            -(margin as f32) / height as f32,
+                            1.0 / width as f32,
+                            1.0 / height as f32,
+                        ),
+                    },
+                    &mask_viewer,
+                    geometries,
+                    &[],
+                );
+        });
+        self.distance_texture = Some(jump_flood_distance(&mask));
+        self.mask_transformation = vec4(
+            margin as f32 / mask_width as f32,
+            margin as f32 / mask_height as f32,
+            width as f32 / mask_width as f32,
+            height as f32 / mask_height as f32,
+        );
+    }
+
+    ///
+    /// Returns the distance field generated by the last call to [Outline::update], see [jump_flood_distance].
+    ///
+    pub fn distance_texture(&self) -> Option<&Texture2D> {
+        self.distance_texture.as_ref()
+    }
+}
+
+impl Material for Outline {
+    fn id(&self) -> EffectMaterialId {
+        EffectMaterialId(EffectMaterialId::OutlineMaterialBase.0 | 0x10)
+    }
+
+    fn fragment_shader_source(&self, _lights: &[&dyn Light]) -> String {
+        OUTLINE_SHADER.to_owned()
+    }
+
+    fn use_uniforms(&self, program: &Program, viewer: &dyn Viewer, _lights: &[&dyn Light]) {
+        viewer.tone_mapping().use_uniforms(program);
+        viewer.color_mapping().use_uniforms(program);
+        if let Some(ref texture) = self.distance_texture {
+            program.use_texture("distanceTexture", texture);
+        }
+        program.use_uniform("maskTransformation", self.mask_transformation);
+        program.use_uniform("outlineColor", self.color.to_linear_srgb());
+        program.use_uniform(
+            "thickness",
+            if self.distance_texture.is_some() {
+                self.thickness.max(0.0)
+            } else {
+                // Without a distance field, the coverage is zero everywhere
+                -1.0
+            },
+        );
+    }
+
+    fn render_states(&self) -> RenderStates {
+        RenderStates {
+            depth_test: DepthTest::Always,
+            write_mask: WriteMask::COLOR,
+            blend: Blend::PREMULTIPLIED,
+            ..Default::default()
+        }
+    }
+
+    fn material_type(&self) -> MaterialType {
+        MaterialType::Transparent
+    }
+}
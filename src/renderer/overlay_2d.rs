@@ -0,0 +1,620 @@
+//!
+//! An immediate-mode painter for 2D shapes and text drawn on top of the 3D view, see [Overlay2D].
+//!
+
+use crate::renderer::*;
+use std::sync::Arc;
+
+#[cfg(feature = "text")]
+use super::text::GlyphAtlas;
+#[cfg(feature = "text")]
+use swash::{shape::ShapeContext, FontRef};
+
+const VERTEX_SHADER: &str = "
+uniform vec2 screenSize;
+
+in vec2 position;
+in vec2 uv_coordinates;
+in vec4 color;
+
+out vec2 uvs;
+out vec4 col;
+
+void main()
+{
+    uvs = uv_coordinates;
+    col = color;
+    // The positions are in logical pixels with the origin at the top left corner of the screen
+    gl_Position = vec4(2.0 * position.x / screenSize.x - 1.0, 1.0 - 2.0 * position.y / screenSize.y, 0.0, 1.0);
+}
+";
+
+const FRAGMENT_SHADER: &str = "
+#include <three-d/srgb>
+
+uniform sampler2D overlayTexture;
+uniform int textureMode;
+
+in vec2 uvs;
+in vec4 col;
+
+layout (location = 0) out vec4 outColor;
+
+void main()
+{
+    // The vertex color is linear and premultiplied by the alpha
+    vec4 color = col;
+    if (textureMode == 1) {
+        vec4 texel = texture(overlayTexture, uvs);
+        color *= vec4(texel.rgb * texel.a, texel.a);
+    } else if (textureMode == 2) {
+        color *= texture(overlayTexture, uvs).r;
+    }
+    if (color.a <= 0.0) {
+        discard;
+    }
+    outColor = vec4(srgb_from_linear_srgb(color.rgb / color.a) * color.a, color.a);
+}
+";
+
+///
+/// The texture sampled by the shapes in a [Batch].
+///
+#[derive(Clone)]
+enum BatchTexture {
+    None,
+    Image(Arc<Texture2D>),
+    #[cfg(feature = "text")]
+    Glyphs {
+        font: usize,
+        page: usize,
+    },
+}
+
+impl PartialEq for BatchTexture {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::None, Self::None) => true,
+            (Self::Image(a), Self::Image(b)) => Arc::ptr_eq(a, b),
+            #[cfg(feature = "text")]
+            (
+                Self::Glyphs { font, page },
+                Self::Glyphs {
+                    font: other_font,
+                    page: other_page,
+                },
+            ) => font == other_font && page == other_page,
+            _ => false,
+        }
+    }
+}
+
+///
+/// A range of consecutive vertices which are drawn in one draw call.
+///
+struct Batch {
+    texture: BatchTexture,
+    clip_rect: Option<(Vec2, Vec2)>,
+    first: u32,
+    count: u32,
+}
+
+///
+/// A font added to an [Overlay2D] with [Overlay2D::add_font].
+///
+#[cfg(feature = "text")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct OverlayFontId(usize);
+
+#[cfg(feature = "text")]
+struct OverlayFont {
+    font: FontRef<'static>,
+    atlas: GlyphAtlas<'static>,
+}
+
+///
+/// An immediate-mode painter for lines, rectangles, circles, textured quads and text drawn on top of the 3D view, for example a HUD.
+/// Instead of one draw call per shape like [Line], [Rectangle] and [Circle], all the shapes are batched into as few draw calls as possible:
+/// consecutive shapes with the same texture and clip rectangle are drawn in one draw call, and the shapes are drawn in the order they are added.
+///
+/// Each frame, call [Overlay2D::begin_frame], add the shapes and then call [Overlay2D::render] after the scene has been rendered.
+/// The shapes are specified in logical pixels where the origin is the top left corner of the render target and the y-axis points down,
+/// the same as [LogicalPoint] and the GUI, and the edges are anti-aliased with a feather of one physical pixel.
+/// The overlay can be used together with the GUI, for example for lightweight overlays which do not need the interactivity of the GUI.
+/// The buffers are cleared, but not deallocated, at the beginning of each frame, so adding the same amount of shapes each frame does not allocate.
+///
+pub struct Overlay2D {
+    #[cfg(feature = "text")]
+    context: Context,
+    program: Program,
+    position_buffer: VertexBuffer<Vec2>,
+    uv_buffer: VertexBuffer<Vec2>,
+    color_buffer: VertexBuffer<Vec4>,
+    positions: Vec<Vec2>,
+    uvs: Vec<Vec2>,
+    colors: Vec<Vec4>,
+    batches: Vec<Batch>,
+    clip_rect: Option<(Vec2, Vec2)>,
+    device_pixel_ratio: f32,
+    #[cfg(feature = "text")]
+    fonts: Vec<OverlayFont>,
+    #[cfg(feature = "text")]
+    shape_context: ShapeContext,
+}
+
+impl Overlay2D {
+    ///
+    /// Creates a new empty overlay.
+    ///
+    pub fn new(context: &Context) -> Self {
+        Self {
+            #[cfg(feature = "text")]
+            context: context.clone(),
+            program: Program::from_source(context, VERTEX_SHADER, FRAGMENT_SHADER)
+                .expect("Failed compiling shader"),
+            position_buffer: VertexBuffer::new(context),
+            uv_buffer: VertexBuffer::new(context),
+            color_buffer: VertexBuffer::new(context),
+            positions: Vec::new(),
+            uvs: Vec::new(),
+            colors: Vec::new(),
+            batches: Vec::new(),
+            clip_rect: None,
+            device_pixel_ratio: 1.0,
+            #[cfg(feature = "text")]
+            fonts: Vec::new(),
+            #[cfg(feature = "text")]
+            shape_context: ShapeContext::new(),
+        }
+    }
+
+    ///
+    /// Adds a font which can be used to draw text with [Overlay2D::text].
+    /// The glyphs are rasterized with the given size in physical pixels per em and scaled to the size of each text,
+    /// so use the largest size that the texts are drawn with times the device pixel ratio.
+    /// The index indicates the specific font in a font collection. Set to 0 if unsure.
+    ///
+    #[cfg(feature = "text")]
+    pub fn add_font(
+        &mut self,
+        font_bytes: &'static [u8],
+        font_index: u32,
+        size: f32,
+    ) -> Result<OverlayFontId, RendererError> {
+        let font = FontRef::from_index(font_bytes, font_index as usize)
+            .ok_or(RendererError::MissingFont(font_index))?;
+        self.fonts.push(OverlayFont {
+            font,
+            atlas: GlyphAtlas::new(&self.context, font, size),
+        });
+        Ok(OverlayFontId(self.fonts.len() - 1))
+    }
+
+    ///
+    /// Removes all the shapes added in the previous frame, without deallocating the buffers, and sets the device pixel ratio used to convert the logical pixels
+    /// to physical pixels, see [FrameInput::device_pixel_ratio](crate::FrameInput::device_pixel_ratio). Also removes the clip rectangle.
+    ///
+    pub fn begin_frame(&mut self, device_pixel_ratio: f32) {
+        self.positions.clear();
+        self.uvs.clear();
+        self.colors.clear();
+        self.batches.clear();
+        self.clip_rect = None;
+        self.device_pixel_ratio = device_pixel_ratio.max(f32::EPSILON);
+    }
+
+    ///
+    /// Sets the rectangle, given by the top left and bottom right corner in logical pixels, that the shapes added after this call are clipped to.
+    /// `None` removes the clipping.
+    ///
+    pub fn set_clip_rect(&mut self, clip_rect: Option<(Vec2, Vec2)>) {
+        self.clip_rect = clip_rect;
+    }
+
+    ///
+    /// Adds a line between the two points with the given width in logical pixels.
+    ///
+    pub fn line(&mut self, start: Vec2, end: Vec2, width: f32, color: Srgba) {
+        let direction = end - start;
+        if direction.magnitude2() == 0.0 {
+            return;
+        }
+        let (width, color) = self.thin(width, color);
+        let normal = vec2(-direction.y, direction.x).normalize() * (0.5 * width);
+        self.fill_convex(
+            &[start + normal, end + normal, end - normal, start - normal],
+            color,
+        );
+    }
+
+    ///
+    /// Adds a filled rectangle given by the top left and bottom right corner in logical pixels.
+    ///
+    pub fn rect_filled(&mut self, min: Vec2, max: Vec2, color: Srgba) {
+        self.fill_convex(
+            &[min, vec2(max.x, min.y), max, vec2(min.x, max.y)],
+            premultiplied(color),
+        );
+    }
+
+    ///
+    /// Adds the outline of the rectangle given by the top left and bottom right corner in logical pixels.
+    /// The stroke is centered on the edges of the rectangle and has the given width in logical pixels.
+    ///
+    pub fn rect_stroke(&mut self, min: Vec2, max: Vec2, width: f32, color: Srgba) {
+        self.stroke_closed(
+            &[min, vec2(max.x, min.y), max, vec2(min.x, max.y)],
+            width,
+            color,
+        );
+    }
+
+    ///
+    /// Adds a filled circle with the given center and radius in logical pixels.
+    ///
+    pub fn circle_filled(&mut self, center: Vec2, radius: f32, color: Srgba) {
+        let points = self.circle_points(center, radius);
+        self.fill_convex(&points, premultiplied(color));
+    }
+
+    ///
+    /// Adds the outline of the circle with the given center and radius in logical pixels.
+    /// The stroke is centered on the circle and has the given width in logical pixels.
+    ///
+    pub fn circle_stroke(&mut self, center: Vec2, radius: f32, width: f32, color: Srgba) {
+        let points = self.circle_points(center, radius);
+        self.stroke_closed(&points, width, color);
+    }
+
+    ///
+    /// Adds a quad, given by the top left and bottom right corner in logical pixels, which is textured with the given texture multiplied by the given tint.
+    /// The [Texture2DRef::transformation] is applied to the uv coordinates, so a part of a texture atlas can be drawn.
+    ///
+    pub fn textured_quad(&mut self, min: Vec2, max: Vec2, texture: &Texture2DRef, tint: Srgba) {
+        let uv = |u: f32, v: f32| (texture.transformation * vec3(u, v, 1.0)).truncate();
+        self.quad(
+            BatchTexture::Image(texture.texture.clone()),
+            [min, vec2(max.x, min.y), max, vec2(min.x, max.y)],
+            [uv(0.0, 1.0), uv(1.0, 1.0), uv(1.0, 0.0), uv(0.0, 0.0)],
+            premultiplied(tint),
+        );
+    }
+
+    ///
+    /// Adds a text using the given font, where the position is the start of the baseline of the first line in logical pixels
+    /// and the size is the font size in logical pixels per em. Each new line in the text moves the baseline down by 1.2 times the line height of the font.
+    ///
+    #[cfg(feature = "text")]
+    pub fn text(
+        &mut self,
+        font: OverlayFontId,
+        text: &str,
+        position: Vec2,
+        size: f32,
+        color: Srgba,
+    ) {
+        let color = premultiplied(color);
+        let OverlayFont {
+            font: font_ref,
+            atlas,
+        } = &mut self.fonts[font.0];
+        let metrics = font_ref.metrics(&[]).scale(size);
+        let line_height = (metrics.ascent + metrics.descent) * 1.2;
+        let scale = size / atlas.size();
+        let mut quads = Vec::new();
+        let mut pen = position;
+
+        let mut shaper = self.shape_context.builder(*font_ref).size(size).build();
+        shaper.add_str(text);
+        shaper.shape_with(|cluster| {
+            if matches!(text.get(cluster.source.to_range()), Some("\n")) {
+                pen = vec2(position.x, pen.y + line_height);
+            }
+            for glyph in cluster.glyphs {
+                if let Some(atlas_glyph) = atlas.glyph(glyph.id) {
+                    // The glyph offset is in a coordinate system where the y-axis points up
+                    let bottom_left = vec2(
+                        pen.x + glyph.x + atlas_glyph.offset.x * scale,
+                        pen.y - glyph.y - atlas_glyph.offset.y * scale,
+                    );
+                    quads.push((atlas_glyph, bottom_left, atlas_glyph.size * scale));
+                }
+            }
+            pen.x += cluster.advance();
+        });
+
+        for (atlas_glyph, bottom_left, glyph_size) in quads {
+            let uv = atlas_glyph.uv_rect;
+            self.quad(
+                BatchTexture::Glyphs {
+                    font: font.0,
+                    page: atlas_glyph.page,
+                },
+                [
+                    bottom_left - vec2(0.0, glyph_size.y),
+                    bottom_left + vec2(glyph_size.x, -glyph_size.y),
+                    bottom_left + vec2(glyph_size.x, 0.0),
+                    bottom_left,
+                ],
+                [
+                    vec2(uv.x, uv.w),
+                    vec2(uv.z, uv.w),
+                    vec2(uv.z, uv.y),
+                    vec2(uv.x, uv.y),
+                ],
+                color,
+            );
+        }
+    }
+
+    ///
+    /// Returns the number of draw calls needed to render the shapes added since [Overlay2D::begin_frame].
+    ///
+    pub fn draw_call_count(&self) -> usize {
+        self.batches.len()
+    }
+
+    ///
+    /// Renders the shapes added since [Overlay2D::begin_frame] on top of the content of the given render target, for example the screen.
+    ///
+    pub fn render(&mut self, target: &RenderTarget) {
+        if self.batches.is_empty() {
+            return;
+        }
+        #[cfg(feature = "text")]
+        for font in self.fonts.iter_mut() {
+            font.atlas.upload();
+        }
+        // Writing a subset does not reallocate the buffers if they are already large enough
+        self.position_buffer.fill_subset(0, &self.positions);
+        self.uv_buffer.fill_subset(0, &self.uvs);
+        self.color_buffer.fill_subset(0, &self.colors);
+
+        let viewport = target.viewport();
+        let dpr = self.device_pixel_ratio;
+        let render_states = RenderStates {
+            depth_test: DepthTest::Always,
+            write_mask: WriteMask::COLOR,
+            blend: Blend::PREMULTIPLIED,
+            cull: Cull::None,
+            ..Default::default()
+        };
+        let program = &self.program;
+        program.use_uniform(
+            "screenSize",
+            vec2(viewport.width as f32 / dpr, viewport.height as f32 / dpr),
+        );
+        for batch in self.batches.iter() {
+            let scissor_box = match batch.clip_rect {
+                Some((min, max)) => {
+                    let left = (min.x * dpr).round().max(0.0) as i32;
+                    let right = (max.x * dpr).round().max(0.0) as i32;
+                    let top = (min.y * dpr).round().max(0.0) as i32;
+                    let bottom = (max.y * dpr).round().max(0.0) as i32;
+                    ScissorBox {
+                        x: left,
+                        y: viewport.height as i32 - bottom,
+                        width: (right - left).max(0) as u32,
+                        height: (bottom - top).max(0) as u32,
+                    }
+                }
+                None => target.scissor_box(),
+            };
+            let texture_mode = match &batch.texture {
+                BatchTexture::None => 0,
+                BatchTexture::Image(texture) => {
+                    program.use_texture("overlayTexture", texture);
+                    1
+                }
+                #[cfg(feature = "text")]
+                BatchTexture::Glyphs { font, page } => {
+                    program.use_texture("overlayTexture", self.fonts[*font].atlas.texture(*page));
+                    2
+                }
+            };
+            program.use_uniform("textureMode", texture_mode);
+            target
+                .write_partially::<RendererError>(scissor_box, || {
+                    // The attributes are disabled after each draw call
+                    program.use_vertex_attribute("position", &self.position_buffer);
+                    program.use_vertex_attribute("uv_coordinates", &self.uv_buffer);
+                    program.use_vertex_attribute("color", &self.color_buffer);
+                    program.draw_subset_of_arrays(
+                        render_states,
+                        viewport,
+                        batch.first,
+                        batch.count,
+                    );
+                    Ok(())
+                })
+                .unwrap();
+        }
+    }
+
+    ///
+    /// Returns a line width of at least one physical pixel and the premultiplied color, where thinner lines are faded instead.
+    ///
+    fn thin(&self, width: f32, color: Srgba) -> (f32, Vec4) {
+        let feather = 1.0 / self.device_pixel_ratio;
+        let color = premultiplied(color);
+        if width < feather {
+            (feather, color * (width.max(0.0) / feather))
+        } else {
+            (width, color)
+        }
+    }
+
+    fn circle_points(&self, center: Vec2, radius: f32) -> Vec<Vec2> {
+        // More segments for larger circles, so the segments are never longer than a few physical pixels
+        let segments =
+            (4.0 * (radius * self.device_pixel_ratio).max(0.0).sqrt()).clamp(8.0, 256.0) as u32;
+        (0..segments)
+            .map(|i| {
+                let angle = i as f32 * std::f32::consts::TAU / segments as f32;
+                center + radius * vec2(angle.cos(), angle.sin())
+            })
+            .collect()
+    }
+
+    ///
+    /// Fills the convex polygon and fades the edges over one physical pixel.
+    ///
+    fn fill_convex(&mut self, points: &[Vec2], color: Vec4) {
+        if points.len() < 3 {
+            return;
+        }
+        let half_feather = 0.5 / self.device_pixel_ratio;
+        let normals = vertex_normals(points);
+        let inner = points
+            .iter()
+            .zip(&normals)
+            .map(|(p, n)| p - n * half_feather)
+            .collect::<Vec<_>>();
+        let outer = points
+            .iter()
+            .zip(&normals)
+            .map(|(p, n)| p + n * half_feather)
+            .collect::<Vec<_>>();
+
+        self.begin_batch(BatchTexture::None);
+        for i in 1..inner.len() - 1 {
+            self.push_triangle([inner[0], inner[i], inner[i + 1]], [color; 3]);
+        }
+        self.push_band(&inner, &outer, color, Vec4::zero());
+    }
+
+    ///
+    /// Strokes the closed path with the given width and fades the edges of the stroke over one physical pixel.
+    ///
+    fn stroke_closed(&mut self, points: &[Vec2], width: f32, color: Srgba) {
+        if points.len() < 2 {
+            return;
+        }
+        let (width, color) = self.thin(width, color);
+        let feather = 1.0 / self.device_pixel_ratio;
+        let core = 0.5 * (width - feather);
+        let normals = vertex_normals(points);
+        let offset = |distance: f32| {
+            points
+                .iter()
+                .zip(&normals)
+                .map(|(p, n)| p + n * distance)
+                .collect::<Vec<_>>()
+        };
+        let (outer_edge, outer, inner, inner_edge) = (
+            offset(core + feather),
+            offset(core),
+            offset(-core),
+            offset(-core - feather),
+        );
+
+        self.begin_batch(BatchTexture::None);
+        self.push_band(&outer, &outer_edge, color, Vec4::zero());
+        self.push_band(&inner, &outer, color, color);
+        self.push_band(&inner, &inner_edge, color, Vec4::zero());
+    }
+
+    ///
+    /// Adds the quads between two closed loops of points with the given colors.
+    ///
+    fn push_band(&mut self, from: &[Vec2], to: &[Vec2], from_color: Vec4, to_color: Vec4) {
+        for i in 0..from.len() {
+            let j = (i + 1) % from.len();
+            self.push_triangle(
+                [from[i], from[j], to[j]],
+                [from_color, from_color, to_color],
+            );
+            self.push_triangle([from[i], to[j], to[i]], [from_color, to_color, to_color]);
+        }
+    }
+
+    fn quad(&mut self, texture: BatchTexture, corners: [Vec2; 4], uvs: [Vec2; 4], color: Vec4) {
+        self.begin_batch(texture);
+        for i in [0, 1, 2, 2, 3, 0] {
+            self.push_vertex(corners[i], uvs[i], color);
+        }
+    }
+
+    fn push_triangle(&mut self, positions: [Vec2; 3], colors: [Vec4; 3]) {
+        for i in 0..3 {
+            self.push_vertex(positions[i], Vec2::zero(), colors[i]);
+        }
+    }
+
+    fn push_vertex(&mut self, position: Vec2, uv: Vec2, color: Vec4) {
+        self.positions.push(position);
+        self.uvs.push(uv);
+        self.colors.push(color);
+        if let Some(batch) = self.batches.last_mut() {
+            batch.count += 1;
+        }
+    }
+
+    ///
+    /// Continues the last batch if it has the same texture and clip rectangle, otherwise starts a new batch.
+    ///
+    fn begin_batch(&mut self, texture: BatchTexture) {
+        let continues = self
+            .batches
+            .last()
+            .map(|batch| batch.texture == texture && batch.clip_rect == self.clip_rect)
+            .unwrap_or(false);
+        if !continues {
+            self.batches.push(Batch {
+                texture,
+                clip_rect: self.clip_rect,
+                first: self.positions.len() as u32,
+                count: 0,
+            });
+        }
+    }
+}
+
+///
+/// Returns the color in linear sRGB premultiplied by the alpha.
+///
+fn premultiplied(color: Srgba) -> Vec4 {
+    let color = color.to_linear_srgb();
+    vec4(
+        color.x * color.w,
+        color.y * color.w,
+        color.z * color.w,
+        color.w,
+    )
+}
+
+///
+/// Returns the outward normals at the points of the closed polygon, scaled so offsetting the points along the normals
+/// moves the edges the same distance.
+///
+fn vertex_normals(points: &[Vec2]) -> Vec<Vec2> {
+    let n = points.len();
+    let area = (0..n)
+        .map(|i| {
+            let (a, b) = (points[i], points[(i + 1) % n]);
+            a.x * b.y - b.x * a.y
+        })
+        .sum::<f32>();
+    let sign = if area < 0.0 { -1.0 } else { 1.0 };
+    let edge_normals = (0..n)
+        .map(|i| {
+            let direction = points[(i + 1) % n] - points[i];
+            let normal = vec2(direction.y, -direction.x) * sign;
+            if normal.magnitude2() > 0.0 {
+                normal.normalize()
+            } else {
+                Vec2::zero()
+            }
+        })
+        .collect::<Vec<_>>();
+    (0..n)
+        .map(|i| {
+            let previous = edge_normals[(i + n - 1) % n];
+            let next = edge_normals[i];
+            let sum = previous + next;
+            // Limits the offset at very sharp corners
+            let cos = sum.dot(next).max(0.1);
+            sum / cos
+        })
+        .collect()
+}
@@ -0,0 +1,865 @@
+//!
+//! Out-of-core rendering of point clouds that are too large to be resident, see [PointCloudOctreeBuilder] and [StreamedPointCloud].
+//!
+
+use crate::renderer::*;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::ops::Range;
+use std::sync::{mpsc, Arc};
+
+/// The magic bytes at the start of a point cloud octree file.
+const MAGIC: [u8; 4] = *b"3DPO";
+
+/// The size in bytes of the header before the node table.
+const HEADER_SIZE: u64 = 4 + 2 + 1 + 3 * 8 + 4;
+
+/// The size in bytes of a node in the node table.
+const NODE_SIZE: u64 = 6 * 4 + 4 + 4 + 4 + 1 + 8;
+
+/// The maximum depth of the octree, which stops the subdivision of many points at the same position.
+const MAX_DEPTH: u32 = 24;
+
+///
+/// A node in a [PointCloudOctree].
+/// The refinement is additive, so the points of a node are not repeated in its children and
+/// rendering a node at full detail requires rendering all of its ancestors.
+///
+#[derive(Clone, Debug)]
+pub struct PointCloudOctreeNode {
+    /// The bounds of the points in this node and all of its descendants, relative to the [origin](PointCloudOctree::origin).
+    pub bounds: AxisAlignedBoundingBox,
+    /// The approximate distance between the points in this node, which is the error of rendering this node without its children.
+    pub spacing: f32,
+    /// The number of points in this node.
+    pub point_count: u32,
+    /// The indices of the children of this node.
+    pub children: Range<usize>,
+    data_offset: u64,
+}
+
+///
+/// The index of a point cloud which is spatially partitioned into an octree of chunks, see [PointCloudOctreeBuilder].
+/// The index only contains the bounds and the number of points of each chunk, the points are read using [PointCloudOctree::read_chunk].
+///
+/// The binary layout, where all values are little-endian, is a header with the magic bytes `3DPO`, the version (u16), flags (u8, where bit 0 specifies whether the points have colors),
+/// the origin (3 x f64) and the number of nodes (u32), followed by the node table and then the points of each node.
+/// Each node in the node table consists of the minimum and maximum bounds (6 x f32), the spacing (f32), the number of points (u32),
+/// the index of the first child (u32), the number of children (u8) and the offset of the points from the start of the file (u64).
+/// The nodes are stored in breadth-first order, so the root is the first node and the children of a node are stored next to each other.
+/// The points of a node are stored as positions relative to the origin (3 x f32 per point) followed by the colors (4 x u8 per point) if the points have colors.
+///
+#[derive(Clone, Debug)]
+pub struct PointCloudOctree {
+    /// The position all points are relative to, which is stored in double precision so the point cloud can be georeferenced.
+    pub origin: DVec3,
+    /// Whether the points have colors.
+    pub has_colors: bool,
+    /// The nodes in breadth-first order, where the first node is the root.
+    pub nodes: Vec<PointCloudOctreeNode>,
+}
+
+impl PointCloudOctree {
+    ///
+    /// The version of the format written by [PointCloudOctreeBuilder::write].
+    /// Files with an older version can still be read, while files with a newer version are rejected.
+    ///
+    pub const VERSION: u16 = 1;
+
+    ///
+    /// Reads the index of a point cloud octree written by [PointCloudOctreeBuilder::write].
+    ///
+    pub fn read_index(reader: &mut impl Read) -> Result<Self, RendererError> {
+        trace_span!("three_d::deserialize", asset = "point_cloud");
+        let mut magic = [0; 4];
+        reader
+            .read_exact(&mut magic)
+            .map_err(RendererError::PointCloudOctreeIo)?;
+        if magic != MAGIC {
+            Err(RendererError::InvalidPointCloudOctree(
+                "the data is not a point cloud octree".to_string(),
+            ))?;
+        }
+        let version = u16::from_le_bytes(read_bytes(reader)?);
+        if version > Self::VERSION {
+            Err(RendererError::InvalidPointCloudOctree(format!(
+                "the version is {} but at most version {} is supported",
+                version,
+                Self::VERSION
+            )))?;
+        }
+        let flags = u8::from_le_bytes(read_bytes(reader)?);
+        let origin = DVec3::new(read_f64(reader)?, read_f64(reader)?, read_f64(reader)?);
+        let node_count = read_u32(reader)? as usize;
+        let mut nodes = Vec::new();
+        for index in 0..node_count {
+            let min = vec3(read_f32(reader)?, read_f32(reader)?, read_f32(reader)?);
+            let max = vec3(read_f32(reader)?, read_f32(reader)?, read_f32(reader)?);
+            let spacing = read_f32(reader)?;
+            let point_count = read_u32(reader)?;
+            let first_child = read_u32(reader)? as usize;
+            let child_count = u8::from_le_bytes(read_bytes(reader)?) as usize;
+            let data_offset = u64::from_le_bytes(read_bytes(reader)?);
+            if child_count > 8
+                || (child_count > 0 && first_child <= index)
+                || first_child + child_count > node_count
+            {
+                Err(RendererError::InvalidPointCloudOctree(format!(
+                    "the node {} has invalid children",
+                    index
+                )))?;
+            }
+            nodes.push(PointCloudOctreeNode {
+                bounds: AxisAlignedBoundingBox::new_with_positions(&[min, max]),
+                spacing,
+                point_count,
+                children: first_child..first_child + child_count,
+                data_offset,
+            });
+        }
+        if nodes.is_empty() {
+            Err(RendererError::InvalidPointCloudOctree(
+                "the octree has no nodes".to_string(),
+            ))?;
+        }
+        Ok(Self {
+            origin,
+            has_colors: flags & 1 != 0,
+            nodes,
+        })
+    }
+
+    ///
+    /// Reads the points of the node with the given index from the given reader, which must read from the start of the same data as the index was read from.
+    /// The positions are relative to the [origin](PointCloudOctree::origin).
+    ///
+    pub fn read_chunk(
+        &self,
+        reader: &mut (impl Read + Seek),
+        index: usize,
+    ) -> Result<PointCloud, RendererError> {
+        trace_span!("three_d::deserialize", asset = "point_cloud_chunk");
+        let node = &self.nodes[index];
+        let count = node.point_count as usize;
+        reader
+            .seek(SeekFrom::Start(node.data_offset))
+            .map_err(RendererError::PointCloudOctreeIo)?;
+        let mut bytes = vec![0; count * if self.has_colors { 16 } else { 12 }];
+        reader
+            .read_exact(&mut bytes)
+            .map_err(RendererError::PointCloudOctreeIo)?;
+        let f32_at = |i: usize| f32::from_le_bytes(bytes[i * 4..i * 4 + 4].try_into().unwrap());
+        let positions = (0..count)
+            .map(|i| vec3(f32_at(3 * i), f32_at(3 * i + 1), f32_at(3 * i + 2)))
+            .collect();
+        let colors = self.has_colors.then(|| {
+            bytes[count * 12..]
+                .chunks_exact(4)
+                .map(|c| Srgba::new(c[0], c[1], c[2], c[3]))
+                .collect()
+        });
+        Ok(PointCloud {
+            positions: Positions::F32(positions),
+            colors,
+        })
+    }
+
+    ///
+    /// Returns the total number of points in all nodes.
+    ///
+    pub fn point_count(&self) -> u64 {
+        self.nodes.iter().map(|node| node.point_count as u64).sum()
+    }
+
+    ///
+    /// Loads the index of the point cloud octree in the file at the given path, see [PointCloudOctree::read_index].
+    ///
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn load(path: impl AsRef<std::path::Path>) -> Result<Self, RendererError> {
+        let file = std::fs::File::open(path).map_err(RendererError::PointCloudOctreeIo)?;
+        Self::read_index(&mut std::io::BufReader::new(file))
+    }
+}
+
+#[derive(Clone, Copy)]
+struct BuildPoint {
+    position: Vec3,
+    color: [u8; 4],
+}
+
+struct BuildNode {
+    bounds: AxisAlignedBoundingBox,
+    spacing: f32,
+    points: Range<usize>,
+    children: Vec<BuildNode>,
+}
+
+///
+/// Builds a [PointCloudOctree] from a set of points, either offline, for example in a command-line tool, or at load time.
+/// The points are spatially partitioned into an octree where each node contains an evenly distributed subset of at most [PointCloudOctreeBuilder::max_points_per_chunk] points
+/// of the points within its bounds, which is not contained in any of its children.
+/// All points are kept in memory while building, which requires 16 bytes per point.
+///
+/// ```
+/// # use three_d::*;
+/// let mut builder = PointCloudOctreeBuilder::new(DVec3::new(1000000.0, 0.0, 0.0), false);
+/// builder.max_points_per_chunk = 100;
+/// for i in 0..1000 {
+///     builder.add_point(DVec3::new(1000000.0 + i as f64 * 0.01, (i % 10) as f64, 0.0), None);
+/// }
+/// let mut bytes = Vec::new();
+/// let octree = builder.write(&mut bytes).unwrap();
+/// assert_eq!(octree.point_count(), 1000);
+/// assert!(octree.nodes.len() > 1);
+///
+/// let mut reader = std::io::Cursor::new(bytes);
+/// let index = PointCloudOctree::read_index(&mut reader).unwrap();
+/// assert_eq!(index.nodes.len(), octree.nodes.len());
+/// let root = octree.read_chunk(&mut reader, 0).unwrap();
+/// assert_eq!(root.positions.len(), octree.nodes[0].point_count as usize);
+/// ```
+///
+pub struct PointCloudOctreeBuilder {
+    origin: DVec3,
+    has_colors: bool,
+    points: Vec<BuildPoint>,
+    /// The maximum number of points in each node. Defaults to 50000.
+    pub max_points_per_chunk: usize,
+}
+
+impl PointCloudOctreeBuilder {
+    ///
+    /// Creates a new builder where all positions are stored relative to the given origin, which should be close to the points,
+    /// and where the points have colors if `has_colors` is true.
+    ///
+    pub fn new(origin: DVec3, has_colors: bool) -> Self {
+        Self {
+            origin,
+            has_colors,
+            points: Vec::new(),
+            max_points_per_chunk: 50000,
+        }
+    }
+
+    ///
+    /// Adds a point at the given position. The color is ignored if the builder was created without colors and defaults to white.
+    ///
+    pub fn add_point(&mut self, position: DVec3, color: Option<Srgba>) {
+        let color = color.unwrap_or(Srgba::WHITE);
+        self.points.push(BuildPoint {
+            position: (position - self.origin).cast::<f32>().unwrap(),
+            color: [color.r, color.g, color.b, color.a],
+        });
+    }
+
+    ///
+    /// Adds all points of the given point cloud, where the positions are given relative to the given origin.
+    ///
+    pub fn add_point_cloud(&mut self, point_cloud: &PointCloud, origin: DVec3) {
+        for (i, position) in point_cloud.positions.to_f64().into_iter().enumerate() {
+            let color = point_cloud.colors.as_ref().map(|colors| colors[i]);
+            self.add_point(origin + position, color);
+        }
+    }
+
+    ///
+    /// Returns the number of points added so far.
+    ///
+    pub fn point_count(&self) -> usize {
+        self.points.len()
+    }
+
+    ///
+    /// Builds the octree and writes it to the given writer in the binary layout described in [PointCloudOctree].
+    /// Returns the index of the written octree.
+    ///
+    pub fn write(mut self, writer: &mut impl Write) -> Result<PointCloudOctree, RendererError> {
+        let max_points = self.max_points_per_chunk.max(1);
+        let mut size = 0.0f32;
+        let mut min = vec3(0.0, 0.0, 0.0);
+        if !self.points.is_empty() {
+            let aabb = AxisAlignedBoundingBox::new_with_positions(
+                &self.points.iter().map(|p| p.position).collect::<Vec<_>>(),
+            );
+            min = aabb.min();
+            let s = aabb.size();
+            size = s.x.max(s.y).max(s.z).max(f32::EPSILON);
+        }
+        let root = build_node(&mut self.points, 0, min, size, 0, max_points);
+
+        // Flatten the nodes in breadth-first order
+        let mut flattened = vec![&root];
+        let mut first_children = Vec::new();
+        let mut i = 0;
+        while i < flattened.len() {
+            first_children.push(flattened.len());
+            let children = &flattened[i].children;
+            flattened.extend(children.iter());
+            i += 1;
+        }
+        let point_size = if self.has_colors { 16 } else { 12 };
+        let mut data_offset = HEADER_SIZE + NODE_SIZE * flattened.len() as u64;
+        let mut nodes = Vec::new();
+        for (node, first_child) in flattened.iter().zip(first_children) {
+            nodes.push(PointCloudOctreeNode {
+                bounds: node.bounds,
+                spacing: node.spacing,
+                point_count: node.points.len() as u32,
+                children: first_child..first_child + node.children.len(),
+                data_offset,
+            });
+            data_offset += node.points.len() as u64 * point_size;
+        }
+
+        let io = RendererError::PointCloudOctreeIo;
+        writer.write_all(&MAGIC).map_err(io)?;
+        writer
+            .write_all(&PointCloudOctree::VERSION.to_le_bytes())
+            .map_err(io)?;
+        writer.write_all(&[self.has_colors as u8]).map_err(io)?;
+        for value in [self.origin.x, self.origin.y, self.origin.z] {
+            writer.write_all(&value.to_le_bytes()).map_err(io)?;
+        }
+        writer
+            .write_all(&(nodes.len() as u32).to_le_bytes())
+            .map_err(io)?;
+        for node in nodes.iter() {
+            let (min, max) = (node.bounds.min(), node.bounds.max());
+            for value in [min.x, min.y, min.z, max.x, max.y, max.z, node.spacing] {
+                writer.write_all(&value.to_le_bytes()).map_err(io)?;
+            }
+            writer
+                .write_all(&node.point_count.to_le_bytes())
+                .map_err(io)?;
+            writer
+                .write_all(&(node.children.start as u32).to_le_bytes())
+                .map_err(io)?;
+            writer.write_all(&[node.children.len() as u8]).map_err(io)?;
+            writer
+                .write_all(&node.data_offset.to_le_bytes())
+                .map_err(io)?;
+        }
+        for node in flattened.iter() {
+            let points = &self.points[node.points.clone()];
+            let mut bytes = Vec::with_capacity(points.len() * point_size as usize);
+            for point in points {
+                for value in [point.position.x, point.position.y, point.position.z] {
+                    bytes.extend_from_slice(&value.to_le_bytes());
+                }
+            }
+            if self.has_colors {
+                for point in points {
+                    bytes.extend_from_slice(&point.color);
+                }
+            }
+            writer.write_all(&bytes).map_err(io)?;
+        }
+        Ok(PointCloudOctree {
+            origin: self.origin,
+            has_colors: self.has_colors,
+            nodes,
+        })
+    }
+
+    ///
+    /// Builds the octree and saves it to the file at the given path, see [PointCloudOctreeBuilder::write].
+    ///
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn save(
+        self,
+        path: impl AsRef<std::path::Path>,
+    ) -> Result<PointCloudOctree, RendererError> {
+        let file = std::fs::File::create(path).map_err(RendererError::PointCloudOctreeIo)?;
+        let mut writer = std::io::BufWriter::new(file);
+        let octree = self.write(&mut writer)?;
+        writer.flush().map_err(RendererError::PointCloudOctreeIo)?;
+        Ok(octree)
+    }
+}
+
+///
+/// Builds the node for the given points, which are within the cube with the given minimum corner and size.
+/// The points are reordered so the points of the node are first, followed by the points of each of the children.
+///
+fn build_node(
+    points: &mut [BuildPoint],
+    offset: usize,
+    min: Vec3,
+    size: f32,
+    depth: u32,
+    max_points: usize,
+) -> BuildNode {
+    let bounds = if points.is_empty() {
+        AxisAlignedBoundingBox::EMPTY
+    } else {
+        AxisAlignedBoundingBox::new_with_positions(
+            &points.iter().map(|p| p.position).collect::<Vec<_>>(),
+        )
+    };
+    // Scanned point clouds are mostly surfaces, so a grid with this size fills a node with points
+    let grid_size = (max_points as f32).sqrt().ceil().max(1.0) as usize;
+    if points.len() <= max_points || depth >= MAX_DEPTH {
+        return BuildNode {
+            bounds,
+            spacing: size / grid_size as f32,
+            points: offset..offset + points.len(),
+            children: Vec::new(),
+        };
+    }
+
+    // Keep the first point in each cell of a regular grid, which gives an evenly distributed subset of the points
+    let mut occupied = std::collections::HashSet::new();
+    let cell = |v: f32, m: f32| (((v - m) / size * grid_size as f32) as usize).min(grid_size - 1);
+    let mut count = 0;
+    for i in 0..points.len() {
+        if count == max_points {
+            break;
+        }
+        let p = points[i].position;
+        let index =
+            cell(p.x, min.x) + grid_size * (cell(p.y, min.y) + grid_size * cell(p.z, min.z));
+        if occupied.insert(index) {
+            points.swap(i, count);
+            count += 1;
+        }
+    }
+
+    // Distribute the remaining points between the octants
+    let half = 0.5 * size;
+    let center = min + vec3(half, half, half);
+    let (_, rest) = points.split_at_mut(count);
+    let mut octants = vec![(count, rest)];
+    for axis in 0..3 {
+        octants = octants
+            .into_iter()
+            .flat_map(|(start, slice)| {
+                let split = partition(slice, |p| p.position[axis] < center[axis]);
+                let (low, high) = slice.split_at_mut(split);
+                [(start, low), (start + split, high)]
+            })
+            .collect();
+    }
+    let mut children = Vec::new();
+    for (octant, (start, slice)) in octants.into_iter().enumerate() {
+        if !slice.is_empty() {
+            let child_min = vec3(
+                if octant & 4 != 0 { center.x } else { min.x },
+                if octant & 2 != 0 { center.y } else { min.y },
+                if octant & 1 != 0 { center.z } else { min.z },
+            );
+            children.push(build_node(
+                slice,
+                offset + start,
+                child_min,
+                half,
+                depth + 1,
+                max_points,
+            ));
+        }
+    }
+    BuildNode {
+        bounds,
+        spacing: size / grid_size as f32,
+        points: offset..offset + count,
+        children,
+    }
+}
+
+///
+/// Reorders the points so the points where the predicate is true are first and returns the number of those points.
+///
+fn partition(points: &mut [BuildPoint], predicate: impl Fn(&BuildPoint) -> bool) -> usize {
+    let mut split = 0;
+    for i in 0..points.len() {
+        if predicate(&points[i]) {
+            points.swap(i, split);
+            split += 1;
+        }
+    }
+    split
+}
+
+fn read_bytes<const N: usize>(reader: &mut impl Read) -> Result<[u8; N], RendererError> {
+    let mut bytes = [0; N];
+    reader
+        .read_exact(&mut bytes)
+        .map_err(RendererError::PointCloudOctreeIo)?;
+    Ok(bytes)
+}
+
+fn read_u32(reader: &mut impl Read) -> Result<u32, RendererError> {
+    Ok(u32::from_le_bytes(read_bytes(reader)?))
+}
+
+fn read_f32(reader: &mut impl Read) -> Result<f32, RendererError> {
+    Ok(f32::from_le_bytes(read_bytes(reader)?))
+}
+
+fn read_f64(reader: &mut impl Read) -> Result<f64, RendererError> {
+    Ok(f64::from_le_bytes(read_bytes(reader)?))
+}
+
+///
+/// A function which returns the points of the node with the given index in a [PointCloudOctree], for example using [PointCloudOctree::read_chunk].
+/// On native, it is called on a background thread, so it can for example read from a file or download the points.
+/// A node that fails to load is rendered without points and the error is available from [StreamedPointCloud::take_load_errors].
+///
+pub type PointChunkLoader = Arc<dyn Fn(usize) -> Result<PointCloud, RendererError> + Send + Sync>;
+
+///
+/// Statistics from a [StreamedPointCloud], for example to show in a debug overlay.
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PointCloudStreamingStats {
+    /// The number of nodes in the octree.
+    pub node_count: usize,
+    /// The number of nodes whose points are resident on the GPU.
+    pub resident_nodes: usize,
+    /// The number of points resident on the GPU.
+    pub resident_points: u64,
+    /// The number of nodes that are rendered.
+    pub rendered_nodes: usize,
+    /// The number of points that are rendered.
+    pub rendered_points: u64,
+    /// The budget, see [StreamedPointCloud::budget].
+    pub budget_points: u64,
+    /// Whether the budget prevented refining the point cloud to the required level of detail, see [StreamedPointCloud::max_screen_space_error].
+    pub budget_limited: bool,
+    /// The number of nodes currently being loaded.
+    pub pending_loads: usize,
+    /// The total number of nodes that has been loaded and made resident.
+    pub completed_loads: usize,
+    /// The total number of nodes that has been evicted to stay within the budget.
+    pub evictions: usize,
+    /// The total number of nodes that failed to load, see [StreamedPointCloud::take_load_errors].
+    pub failed_loads: usize,
+}
+
+#[derive(Default)]
+struct StreamedNode {
+    geometry: Option<PointCloudGeometry>,
+    pending: bool,
+    wanted: bool,
+    rendered: bool,
+    last_used: u64,
+}
+
+///
+/// Renders a point cloud which is too large to be resident on the GPU by only keeping the nodes of a [PointCloudOctree] resident
+/// that are needed given the current view and the [budget](StreamedPointCloud::budget).
+///
+/// Call [StreamedPointCloud::update] each frame to select the nodes to render, prioritized by their screen-space error which is the projected [spacing](PointCloudOctreeNode::spacing) of the points in pixels,
+/// until the error is below [StreamedPointCloud::max_screen_space_error] or the budget is reached.
+/// The missing nodes are loaded in the background and nodes that are no longer needed are kept resident until the budget requires the least recently used of them to be evicted.
+/// Until the children of a node arrive, the coarser node is rendered alone.
+///
+/// ```no_run
+/// # use three_d::*;
+/// # let context: Context = unimplemented!();
+/// # let camera: Camera = unimplemented!();
+/// # let target: RenderTarget = unimplemented!();
+/// let mut point_cloud = StreamedPointCloud::open(&context, "points.3dpo", 20_000_000).unwrap();
+///
+/// // Each frame
+/// point_cloud.update(&camera);
+/// target.render_with_material(&ColorMaterial::default(), &camera, point_cloud.geometries(), &[]);
+/// ```
+///
+pub struct StreamedPointCloud {
+    context: Context,
+    octree: PointCloudOctree,
+    parents: Vec<Option<usize>>,
+    nodes: Vec<StreamedNode>,
+    loader: PointChunkLoader,
+    sender: mpsc::Sender<(usize, Result<PointCloud, RendererError>)>,
+    receiver: mpsc::Receiver<(usize, Result<PointCloud, RendererError>)>,
+    frame: u64,
+    stats: PointCloudStreamingStats,
+    load_errors: Vec<(usize, RendererError)>,
+    /// The maximum number of points resident on the GPU. The points of the root node are always resident.
+    pub budget: u64,
+    /// The maximum projected distance between the points in physical pixels before a node is refined. Defaults to 2.
+    pub max_screen_space_error: f32,
+    /// The maximum number of nodes that are loaded at the same time. Defaults to 4.
+    pub max_pending_loads: usize,
+    /// The width and height of each point in physical pixels. Defaults to 2.
+    pub point_size: f32,
+}
+
+impl StreamedPointCloud {
+    ///
+    /// Creates a new streamed point cloud from the index of an octree and a loader that returns the points of a node, with the given budget in number of points.
+    /// The root node is loaded immediately using the given loader, the rest of the nodes are loaded when needed.
+    ///
+    pub fn new(
+        context: &Context,
+        octree: PointCloudOctree,
+        loader: impl Fn(usize) -> PointCloud + Send + Sync + 'static,
+        budget: u64,
+    ) -> Self {
+        let root = loader(0);
+        Self::new_with_loader(
+            context,
+            octree,
+            root,
+            Arc::new(move |node| Ok(loader(node))),
+            budget,
+        )
+    }
+
+    fn new_with_loader(
+        context: &Context,
+        octree: PointCloudOctree,
+        root: PointCloud,
+        loader: PointChunkLoader,
+        budget: u64,
+    ) -> Self {
+        let mut parents = vec![None; octree.nodes.len()];
+        for (index, node) in octree.nodes.iter().enumerate() {
+            for child in node.children.clone() {
+                parents[child] = Some(index);
+            }
+        }
+        let mut nodes: Vec<StreamedNode> = octree
+            .nodes
+            .iter()
+            .map(|_| StreamedNode::default())
+            .collect();
+        nodes[0].geometry = Some(PointCloudGeometry::new(context, &root, 2.0));
+        let (sender, receiver) = mpsc::channel();
+        Self {
+            context: context.clone(),
+            octree,
+            parents,
+            nodes,
+            loader,
+            sender,
+            receiver,
+            frame: 0,
+            stats: PointCloudStreamingStats::default(),
+            load_errors: Vec::new(),
+            budget,
+            max_screen_space_error: 2.0,
+            max_pending_loads: 4,
+            point_size: 2.0,
+        }
+    }
+
+    ///
+    /// Opens the point cloud octree in the file at the given path, see [PointCloudOctreeBuilder::save], and streams the nodes from the file.
+    /// Returns an error if the root node fails to load, while other nodes that fail to load are rendered without points, see [StreamedPointCloud::take_load_errors].
+    ///
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn open(
+        context: &Context,
+        path: impl AsRef<std::path::Path>,
+        budget: u64,
+    ) -> Result<Self, RendererError> {
+        let path = path.as_ref().to_path_buf();
+        let octree = PointCloudOctree::load(&path)?;
+        let index = octree.clone();
+        let loader: PointChunkLoader = Arc::new(move |node: usize| {
+            std::fs::File::open(&path)
+                .map_err(RendererError::PointCloudOctreeIo)
+                .and_then(|file| index.read_chunk(&mut std::io::BufReader::new(file), node))
+        });
+        let root = loader(0)?;
+        Ok(Self::new_with_loader(context, octree, root, loader, budget))
+    }
+
+    ///
+    /// Returns the index of the streamed octree.
+    ///
+    pub fn octree(&self) -> &PointCloudOctree {
+        &self.octree
+    }
+
+    ///
+    /// Returns the geometries of the nodes selected for rendering by the last call to [StreamedPointCloud::update].
+    ///
+    pub fn geometries(&self) -> impl Iterator<Item = &PointCloudGeometry> + Clone {
+        self.nodes
+            .iter()
+            .filter(|node| node.rendered)
+            .filter_map(|node| node.geometry.as_ref())
+    }
+
+    ///
+    /// Returns the errors, together with the index of the node, of the nodes that failed to load since the last call to this method.
+    /// The nodes that failed to load are rendered without points until they are evicted and loaded again.
+    ///
+    pub fn take_load_errors(&mut self) -> Vec<(usize, RendererError)> {
+        std::mem::take(&mut self.load_errors)
+    }
+
+    ///
+    /// Returns the statistics from the last call to [StreamedPointCloud::update].
+    ///
+    pub fn stats(&self) -> PointCloudStreamingStats {
+        self.stats
+    }
+
+    ///
+    /// Updates the resident and rendered nodes given the viewer.
+    ///
+    pub fn update(&mut self, viewer: &dyn Viewer) {
+        self.frame += 1;
+        let transformation = self
+            .context
+            .to_render_space_transformation(DMat4::from_translation(self.octree.origin));
+
+        // Make the loaded nodes resident
+        while let Ok((index, result)) = self.receiver.try_recv() {
+            let node = &mut self.nodes[index];
+            if node.pending {
+                node.pending = false;
+                let point_cloud = result.unwrap_or_else(|e| {
+                    self.stats.failed_loads += 1;
+                    self.load_errors.push((index, e));
+                    PointCloud::default()
+                });
+                node.geometry = Some(PointCloudGeometry::new(
+                    &self.context,
+                    &point_cloud,
+                    self.point_size,
+                ));
+                node.last_used = self.frame;
+                self.stats.completed_loads += 1;
+            }
+        }
+
+        // Select the nodes in order of their screen-space error until the error is small enough or the budget is reached
+        let frustum = Frustum::new(viewer.projection() * viewer.view());
+        let mut order = Vec::new();
+        let mut queue = BinaryHeap::new();
+        let mut wanted_points = 0;
+        let mut budget_limited = false;
+        for node in self.nodes.iter_mut() {
+            node.wanted = false;
+        }
+        queue.push((0, Reverse(0)));
+        while let Some((_, Reverse(index))) = queue.pop() {
+            let node = &self.octree.nodes[index];
+            let bounds = node.bounds.transformed(transformation);
+            if !frustum.contains(bounds) {
+                continue;
+            }
+            if index != 0 && wanted_points + node.point_count as u64 > self.budget {
+                budget_limited = true;
+                continue;
+            }
+            wanted_points += node.point_count as u64;
+            self.nodes[index].wanted = true;
+            order.push(index);
+            let error = projected_length(viewer, node.spacing, bounds);
+            if error > self.max_screen_space_error {
+                for child in node.children.clone() {
+                    let child_bounds = self.octree.nodes[child].bounds.transformed(transformation);
+                    let child_error =
+                        projected_length(viewer, self.octree.nodes[child].spacing, child_bounds);
+                    // The bits of a non-negative float are ordered like the float
+                    queue.push((child_error.max(0.0).to_bits(), Reverse(child)));
+                }
+            }
+        }
+
+        // Evict the least recently used nodes which are not selected until the resident points are within the budget
+        let mut resident_points = self.resident_points();
+        if resident_points > self.budget {
+            let mut candidates: Vec<_> = (1..self.nodes.len())
+                .filter(|i| self.nodes[*i].geometry.is_some() && !self.nodes[*i].wanted)
+                .collect();
+            candidates.sort_by_key(|i| (self.nodes[*i].last_used, Reverse(*i)));
+            for index in candidates {
+                if resident_points <= self.budget {
+                    break;
+                }
+                resident_points -= self.octree.nodes[index].point_count as u64;
+                self.nodes[index].geometry = None;
+                self.stats.evictions += 1;
+            }
+        }
+
+        // Start loading the missing nodes in order of priority when their parent is resident
+        let mut pending_loads = self.nodes.iter().filter(|node| node.pending).count();
+        for index in order.iter().copied() {
+            if pending_loads >= self.max_pending_loads {
+                break;
+            }
+            let resident_parent =
+                self.parents[index].is_none_or(|parent| self.nodes[parent].geometry.is_some());
+            let node = &mut self.nodes[index];
+            if node.geometry.is_none() && !node.pending && resident_parent {
+                node.pending = true;
+                pending_loads += 1;
+                let loader = self.loader.clone();
+                let sender = self.sender.clone();
+                let load = move || {
+                    // The receiver is only dropped together with the point cloud, in which case the result is not needed
+                    let _ = sender.send((index, loader(index)));
+                };
+                #[cfg(not(target_arch = "wasm32"))]
+                std::thread::spawn(load);
+                #[cfg(target_arch = "wasm32")]
+                load();
+            }
+        }
+
+        // Render the selected resident nodes where all ancestors are rendered, the parents are before the children in breadth-first order
+        let mut rendered_nodes = 0;
+        let mut rendered_points = 0;
+        for index in 0..self.nodes.len() {
+            let rendered_parent = self.parents[index].is_none_or(|p| self.nodes[p].rendered);
+            let node = &mut self.nodes[index];
+            node.rendered = node.wanted && rendered_parent && node.geometry.is_some();
+            if node.rendered {
+                let geometry = node.geometry.as_mut().unwrap();
+                geometry.set_transformation(transformation);
+                geometry.point_size = self.point_size;
+                node.last_used = self.frame;
+                rendered_nodes += 1;
+                rendered_points += geometry.point_count() as u64;
+            }
+        }
+
+        self.stats = PointCloudStreamingStats {
+            node_count: self.nodes.len(),
+            resident_nodes: self
+                .nodes
+                .iter()
+                .filter(|node| node.geometry.is_some())
+                .count(),
+            resident_points,
+            rendered_nodes,
+            rendered_points,
+            budget_points: self.budget,
+            budget_limited,
+            pending_loads,
+            ..self.stats
+        };
+    }
+
+    fn resident_points(&self) -> u64 {
+        self.nodes
+            .iter()
+            .zip(self.octree.nodes.iter())
+            .filter(|(node, _)| node.geometry.is_some())
+            .map(|(_, node)| node.point_count as u64)
+            .sum()
+    }
+}
+
+///
+/// Returns a conservative estimate of the length in pixels of the given length within the given bounds when seen from the given viewer.
+///
+fn projected_length(viewer: &dyn Viewer, length: f32, bounds: AxisAlignedBoundingBox) -> f32 {
+    let projection = viewer.projection();
+    let size = length * projection.y.y * 0.5 * viewer.viewport().height as f32;
+    if projection.w.w == 1.0 {
+        // Orthographic projection
+        size
+    } else {
+        size / bounds.distance(viewer.position()).max(viewer.z_near())
+    }
+}
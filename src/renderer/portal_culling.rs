@@ -0,0 +1,550 @@
+//!
+//! Culling of objects hidden behind walls using cells and portals, see [PortalCulling].
+//!
+
+use crate::renderer::*;
+
+///
+/// Identifies a cell in a [PortalCulling].
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct CellId(usize);
+
+///
+/// Identifies a portal in a [PortalCulling].
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct PortalId(usize);
+
+///
+/// A plane where the positive side is inside.
+///
+#[derive(Clone, Copy, Debug)]
+struct Plane {
+    normal: Vec3,
+    distance: f32,
+}
+
+impl Plane {
+    fn new(point: Vec3, normal: Vec3) -> Self {
+        Self {
+            normal,
+            distance: -normal.dot(point),
+        }
+    }
+
+    fn signed_distance(&self, point: Vec3) -> f32 {
+        self.normal.dot(point) + self.distance
+    }
+
+    fn is_outside(&self, aabb: &AxisAlignedBoundingBox) -> bool {
+        let (min, max) = (aabb.min(), aabb.max());
+        let corner = vec3(
+            if self.normal.x >= 0.0 { max.x } else { min.x },
+            if self.normal.y >= 0.0 { max.y } else { min.y },
+            if self.normal.z >= 0.0 { max.z } else { min.z },
+        );
+        self.signed_distance(corner) < 0.0
+    }
+}
+
+struct Cell {
+    planes: Vec<Plane>,
+    aabb: Option<AxisAlignedBoundingBox>,
+    portals: Vec<usize>,
+}
+
+struct Portal {
+    polygon: Vec<Vec3>,
+    cells: [usize; 2],
+}
+
+///
+/// A simple cells-and-portals system for culling objects that are hidden behind walls, for example in an indoor scene with many rooms.
+/// The scene is divided into convex cells, for example rooms, which are connected by portals, for example doors and windows, given as convex polygons.
+///
+/// Each frame, [PortalCulling::visible_cells] finds the cell containing the viewer and traverses the portals that are visible,
+/// narrowing the view through each portal, to find which cells can be seen and through which part of the view.
+/// Objects in cells that cannot be seen, or outside the part of the view through the portals, are then removed using [VisibleCells::filter] or [VisibleCells::filter_assigned].
+/// The remaining objects are still culled against the view frustum when rendered, for example using [RenderTarget::render].
+/// When the viewer is not inside any cell, all cells are visible.
+///
+/// ```no_run
+/// # use three_d::*;
+/// # let context: Context = unimplemented!();
+/// # let camera: Camera = unimplemented!();
+/// # let objects: Vec<Gm<Mesh, PhysicalMaterial>> = unimplemented!();
+/// // Two rooms next to each other connected by a door
+/// let mut portals = PortalCulling::new();
+/// let room0 = portals.add_cell(AxisAlignedBoundingBox::new_with_positions(&[vec3(0.0, 0.0, 0.0), vec3(10.0, 3.0, 10.0)]));
+/// let room1 = portals.add_cell(AxisAlignedBoundingBox::new_with_positions(&[vec3(10.0, 0.0, 0.0), vec3(20.0, 3.0, 10.0)]));
+/// portals.add_portal(&[vec3(10.0, 0.0, 4.0), vec3(10.0, 0.0, 6.0), vec3(10.0, 2.0, 6.0), vec3(10.0, 2.0, 4.0)], room0, room1);
+///
+/// // Each frame
+/// let visible_cells = portals.visible_cells(&camera);
+/// let visible_objects = visible_cells.filter(&portals, &objects);
+/// RenderTarget::screen(&context, 1280, 720).render(&camera, visible_objects, &[]);
+/// ```
+///
+pub struct PortalCulling {
+    cells: Vec<Cell>,
+    portals: Vec<Portal>,
+    /// The maximum number of portals traversed from the cell containing the viewer. Cells further away are not visible.
+    pub max_depth: usize,
+}
+
+impl Default for PortalCulling {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PortalCulling {
+    ///
+    /// Creates a new portal culling without any cells.
+    ///
+    pub fn new() -> Self {
+        Self {
+            cells: Vec::new(),
+            portals: Vec::new(),
+            max_depth: 16,
+        }
+    }
+
+    ///
+    /// Adds a cell with the volume of the given axis aligned bounding box.
+    ///
+    pub fn add_cell(&mut self, aabb: AxisAlignedBoundingBox) -> CellId {
+        let (min, max) = (aabb.min(), aabb.max());
+        let planes = vec![
+            Plane::new(min, vec3(1.0, 0.0, 0.0)),
+            Plane::new(min, vec3(0.0, 1.0, 0.0)),
+            Plane::new(min, vec3(0.0, 0.0, 1.0)),
+            Plane::new(max, vec3(-1.0, 0.0, 0.0)),
+            Plane::new(max, vec3(0.0, -1.0, 0.0)),
+            Plane::new(max, vec3(0.0, 0.0, -1.0)),
+        ];
+        self.cells.push(Cell {
+            planes,
+            aabb: Some(aabb),
+            portals: Vec::new(),
+        });
+        CellId(self.cells.len() - 1)
+    }
+
+    ///
+    /// Adds a convex cell with the volume bounded by the given planes, each given as a point on the plane and the normal of the plane pointing out of the cell.
+    /// Only cells added using [PortalCulling::add_cell] are shown by [PortalCulling::debug_gizmo].
+    ///
+    pub fn add_convex_cell(&mut self, planes: &[(Vec3, Vec3)]) -> CellId {
+        self.cells.push(Cell {
+            planes: planes
+                .iter()
+                .map(|(point, normal)| Plane::new(*point, -normal.normalize()))
+                .collect(),
+            aabb: None,
+            portals: Vec::new(),
+        });
+        CellId(self.cells.len() - 1)
+    }
+
+    ///
+    /// Adds a portal between the two given cells, for example a door, given as the corners of a convex polygon in order around the polygon.
+    /// The portal can be seen through from both cells.
+    ///
+    pub fn add_portal(&mut self, polygon: &[Vec3], cell0: CellId, cell1: CellId) -> PortalId {
+        let index = self.portals.len();
+        self.portals.push(Portal {
+            polygon: polygon.to_vec(),
+            cells: [cell0.0, cell1.0],
+        });
+        self.cells[cell0.0].portals.push(index);
+        if cell1 != cell0 {
+            self.cells[cell1.0].portals.push(index);
+        }
+        PortalId(index)
+    }
+
+    ///
+    /// Returns the number of cells.
+    ///
+    pub fn cell_count(&self) -> usize {
+        self.cells.len()
+    }
+
+    ///
+    /// Returns the number of portals.
+    ///
+    pub fn portal_count(&self) -> usize {
+        self.portals.len()
+    }
+
+    ///
+    /// Returns the first cell containing the given position or `None` if the position is outside all cells.
+    ///
+    pub fn cell_at(&self, position: Vec3) -> Option<CellId> {
+        self.cells
+            .iter()
+            .position(|cell| {
+                cell.planes
+                    .iter()
+                    .all(|plane| plane.signed_distance(position) >= -0.0001)
+            })
+            .map(CellId)
+    }
+
+    ///
+    /// Returns the first cell containing the center of the given bounding box, which can be used to assign an object to a cell,
+    /// or `None` if the center is outside all cells.
+    ///
+    pub fn cell_of(&self, aabb: AxisAlignedBoundingBox) -> Option<CellId> {
+        self.cell_at(aabb.center())
+    }
+
+    ///
+    /// Finds the cells that can be seen by the given viewer by traversing the portals visible from the cell containing the viewer.
+    ///
+    /// ```
+    /// # use three_d::*;
+    /// let mut portals = PortalCulling::new();
+    /// let room0 = portals.add_cell(AxisAlignedBoundingBox::new_with_positions(&[vec3(0.0, 0.0, 0.0), vec3(10.0, 3.0, 10.0)]));
+    /// let room1 = portals.add_cell(AxisAlignedBoundingBox::new_with_positions(&[vec3(10.0, 0.0, 0.0), vec3(20.0, 3.0, 10.0)]));
+    /// let room2 = portals.add_cell(AxisAlignedBoundingBox::new_with_positions(&[vec3(20.0, 0.0, 0.0), vec3(30.0, 3.0, 10.0)]));
+    /// let door = |x: f32, z: f32| [vec3(x, 0.0, z), vec3(x, 0.0, z + 1.0), vec3(x, 2.0, z + 1.0), vec3(x, 2.0, z)];
+    /// portals.add_portal(&door(10.0, 4.5), room0, room1);
+    /// portals.add_portal(&door(20.0, 0.5), room1, room2);
+    ///
+    /// // Looking through the door into the next room, but the door to the third room is not in line of sight
+    /// let viewport = Viewport::new_at_origo(100, 100);
+    /// let camera = Camera::new_perspective(viewport, vec3(2.0, 1.0, 5.0), vec3(12.0, 1.0, 5.0), vec3(0.0, 1.0, 0.0), degrees(60.0), 0.1, 100.0);
+    /// let visible = portals.visible_cells(&camera);
+    /// assert!(visible.is_cell_visible(room0) && visible.is_cell_visible(room1) && !visible.is_cell_visible(room2));
+    /// // Objects in the next room are only visible through the door
+    /// assert!(visible.is_visible_in_cell(room1, AxisAlignedBoundingBox::new_with_positions(&[vec3(15.0, 0.5, 4.5), vec3(16.0, 1.5, 5.5)])));
+    /// assert!(!visible.is_visible_in_cell(room1, AxisAlignedBoundingBox::new_with_positions(&[vec3(15.0, 0.5, 0.5), vec3(16.0, 1.5, 1.5)])));
+    ///
+    /// // Looking away from the door
+    /// let camera = Camera::new_perspective(viewport, vec3(2.0, 1.0, 5.0), vec3(0.0, 1.0, 5.0), vec3(0.0, 1.0, 0.0), degrees(60.0), 0.1, 100.0);
+    /// assert_eq!(portals.visible_cells(&camera).visible_cell_count(), 1);
+    /// ```
+    ///
+    pub fn visible_cells(&self, viewer: impl Viewer) -> VisibleCells {
+        let mut visible = VisibleCells {
+            regions: vec![Vec::new(); self.cells.len()],
+            traversed_portals: vec![false; self.portals.len()],
+            all_visible: false,
+        };
+        let Some(start) = self.cell_at(viewer.position()) else {
+            visible.all_visible = true;
+            return visible;
+        };
+
+        // The side planes of the view frustum, the near and far planes are handled by the frustum culling when rendering
+        let m = viewer.projection() * viewer.view();
+        let row = |i: usize| vec4(m.x[i], m.y[i], m.z[i], m.w[i]);
+        let region = [
+            row(3) + row(0),
+            row(3) - row(0),
+            row(3) + row(1),
+            row(3) - row(1),
+        ]
+        .into_iter()
+        .filter_map(|p| {
+            let length = p.truncate().magnitude();
+            (length > 0.0).then(|| Plane {
+                normal: p.truncate() / length,
+                distance: p.w / length,
+            })
+        })
+        .collect::<Vec<_>>();
+
+        // The apex of the view is the viewer position for perspective projections and infinitely far behind the viewer for orthographic projections
+        let apex = if viewer.projection().z.w == 0.0 {
+            let backward = viewer
+                .view()
+                .invert()
+                .map(|m| (m * vec4(0.0, 0.0, 1.0, 0.0)).truncate())
+                .unwrap_or(vec3(0.0, 0.0, 1.0));
+            Apex::Direction(backward.normalize())
+        } else {
+            Apex::Point(viewer.position())
+        };
+        let mut path = vec![start.0];
+        self.traverse(start.0, region, apex, &mut path, &mut visible);
+        visible
+    }
+
+    fn traverse(
+        &self,
+        cell: usize,
+        region: Vec<Plane>,
+        apex: Apex,
+        path: &mut Vec<usize>,
+        visible: &mut VisibleCells,
+    ) {
+        if path.len() <= self.max_depth {
+            for &portal_index in self.cells[cell].portals.iter() {
+                let portal = &self.portals[portal_index];
+                let next = if portal.cells[0] == cell {
+                    portal.cells[1]
+                } else {
+                    portal.cells[0]
+                };
+                if path.contains(&next) {
+                    continue;
+                }
+                let polygon = region
+                    .iter()
+                    .fold(portal.polygon.clone(), |polygon, plane| {
+                        clip_polygon(&polygon, plane)
+                    });
+                if polygon.len() < 3 {
+                    continue;
+                }
+                visible.traversed_portals[portal_index] = true;
+                let next_region = portal_region(&polygon, apex).unwrap_or_else(|| region.clone());
+                path.push(next);
+                self.traverse(next, next_region, apex, path, visible);
+                path.pop();
+            }
+        }
+        visible.regions[cell].push(region);
+    }
+
+    ///
+    /// Returns a debug visualization of the cells and portals, where cells added using [PortalCulling::add_cell] are shown as boxes
+    /// which are green if visible and gray otherwise, and portals are shown as outlines which are yellow if traversed and red otherwise.
+    /// The lines have the given thickness and are rendered on top of everything else rendered before it, so render it in a separate render call after the rest of the scene.
+    ///
+    pub fn debug_gizmo(
+        &self,
+        context: &Context,
+        visible: &VisibleCells,
+        thickness: f32,
+    ) -> Gm<InstancedMesh, ColorMaterial> {
+        let mut lines = Vec::new();
+        for (index, cell) in self.cells.iter().enumerate() {
+            if let Some(aabb) = cell.aabb {
+                let color = if visible.is_cell_visible(CellId(index)) {
+                    Srgba::GREEN
+                } else {
+                    Srgba::new_opaque(128, 128, 128)
+                };
+                let (min, max) = (aabb.min(), aabb.max());
+                let corner = |i: usize| {
+                    vec3(
+                        if i & 1 == 0 { min.x } else { max.x },
+                        if i & 2 == 0 { min.y } else { max.y },
+                        if i & 4 == 0 { min.z } else { max.z },
+                    )
+                };
+                for i in 0..8 {
+                    for bit in [1, 2, 4] {
+                        if i & bit == 0 {
+                            lines.push((corner(i), corner(i | bit), color));
+                        }
+                    }
+                }
+            }
+        }
+        for (index, portal) in self.portals.iter().enumerate() {
+            let color = if visible.is_portal_traversed(PortalId(index)) {
+                Srgba::new_opaque(255, 255, 0)
+            } else {
+                Srgba::RED
+            };
+            for i in 0..portal.polygon.len() {
+                let j = (i + 1) % portal.polygon.len();
+                lines.push((portal.polygon[i], portal.polygon[j], color));
+            }
+        }
+
+        let instances = Instances {
+            transformations: lines
+                .iter()
+                .map(|(a, b, _)| {
+                    let direction = b - a;
+                    Mat4::from_translation(*a)
+                        * Mat4::from(Quat::from_arc(
+                            vec3(1.0, 0.0, 0.0),
+                            direction.normalize(),
+                            Some(vec3(0.0, 0.0, 1.0)),
+                        ))
+                        * Mat4::from_nonuniform_scale(direction.magnitude(), thickness, thickness)
+                })
+                .collect(),
+            colors: Some(lines.iter().map(|(_, _, color)| *color).collect()),
+            ..Default::default()
+        };
+        let mut material = ColorMaterial {
+            color: Srgba::WHITE,
+            ..Default::default()
+        };
+        material.render_states.depth_test = DepthTest::Always;
+        Gm::new(
+            InstancedMesh::new(context, &instances, &CpuMesh::cylinder(8)),
+            material,
+        )
+    }
+}
+
+///
+/// The result of traversing the cells and portals of a [PortalCulling] from a viewer, see [PortalCulling::visible_cells].
+///
+pub struct VisibleCells {
+    regions: Vec<Vec<Vec<Plane>>>,
+    traversed_portals: Vec<bool>,
+    all_visible: bool,
+}
+
+impl VisibleCells {
+    ///
+    /// Returns whether any part of the given cell can be seen.
+    ///
+    pub fn is_cell_visible(&self, cell: CellId) -> bool {
+        self.all_visible || !self.regions[cell.0].is_empty()
+    }
+
+    ///
+    /// Returns the number of cells that can be seen.
+    ///
+    pub fn visible_cell_count(&self) -> usize {
+        if self.all_visible {
+            self.regions.len()
+        } else {
+            self.regions.iter().filter(|r| !r.is_empty()).count()
+        }
+    }
+
+    ///
+    /// Returns whether the viewer could see through the given portal when traversing the cells.
+    ///
+    pub fn is_portal_traversed(&self, portal: PortalId) -> bool {
+        self.all_visible || self.traversed_portals[portal.0]
+    }
+
+    ///
+    /// Returns whether an object with the given bounding box which is inside the given cell can be seen,
+    /// ie. the cell is visible and the bounding box is inside the part of the view that sees the cell through the portals.
+    ///
+    pub fn is_visible_in_cell(&self, cell: CellId, aabb: AxisAlignedBoundingBox) -> bool {
+        self.all_visible
+            || self.regions[cell.0]
+                .iter()
+                .any(|region| region.iter().all(|plane| !plane.is_outside(&aabb)))
+    }
+
+    ///
+    /// Returns whether an object with the given bounding box can be seen in any of the cells it overlaps.
+    /// Objects which do not overlap any cell are always visible.
+    ///
+    pub fn is_visible(&self, culling: &PortalCulling, aabb: AxisAlignedBoundingBox) -> bool {
+        if self.all_visible {
+            return true;
+        }
+        let mut overlaps_cell = false;
+        for (index, cell) in culling.cells.iter().enumerate() {
+            if cell.planes.iter().all(|plane| !plane.is_outside(&aabb)) {
+                overlaps_cell = true;
+                if self.is_visible_in_cell(CellId(index), aabb) {
+                    return true;
+                }
+            }
+        }
+        !overlaps_cell
+    }
+
+    ///
+    /// Returns the objects that can be seen, where each object is automatically assigned to the cells its bounding box overlaps, see [VisibleCells::is_visible].
+    ///
+    pub fn filter<'a, T: Object + 'a>(
+        &self,
+        culling: &PortalCulling,
+        objects: impl IntoIterator<Item = &'a T>,
+    ) -> Vec<&'a T> {
+        objects
+            .into_iter()
+            .filter(|object| self.is_visible(culling, object.aabb()))
+            .collect()
+    }
+
+    ///
+    /// Returns the objects that can be seen, where each object is explicitly assigned to a cell, see [VisibleCells::is_visible_in_cell].
+    ///
+    pub fn filter_assigned<'a, T: Object + 'a>(
+        &self,
+        objects: impl IntoIterator<Item = (CellId, &'a T)>,
+    ) -> Vec<&'a T> {
+        objects
+            .into_iter()
+            .filter(|(cell, object)| self.is_visible_in_cell(*cell, object.aabb()))
+            .map(|(_, object)| object)
+            .collect()
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Apex {
+    Point(Vec3),
+    Direction(Vec3),
+}
+
+///
+/// Returns the part of the polygon on the inside of the plane.
+///
+fn clip_polygon(polygon: &[Vec3], plane: &Plane) -> Vec<Vec3> {
+    let mut result = Vec::with_capacity(polygon.len() + 1);
+    for i in 0..polygon.len() {
+        let (a, b) = (polygon[i], polygon[(i + 1) % polygon.len()]);
+        let (da, db) = (plane.signed_distance(a), plane.signed_distance(b));
+        if da >= 0.0 {
+            result.push(a);
+        }
+        if (da >= 0.0) != (db >= 0.0) {
+            result.push(a + (b - a) * (da / (da - db)));
+        }
+    }
+    result
+}
+
+///
+/// Returns the planes bounding the part of the view seen through the given portal polygon,
+/// or `None` if the apex of the view is (almost) in the plane of the portal, in which case the view is not narrowed.
+///
+fn portal_region(polygon: &[Vec3], apex: Apex) -> Option<Vec<Plane>> {
+    let center = polygon.iter().sum::<Vec3>() / polygon.len() as f32;
+    let mut normal = Vec3::zero();
+    for i in 0..polygon.len() {
+        normal += polygon[i].cross(polygon[(i + 1) % polygon.len()]);
+    }
+    let normal = normal.normalize();
+    let apex_side = match apex {
+        Apex::Point(point) => normal.dot(point - center),
+        Apex::Direction(direction) => normal.dot(direction),
+    };
+    if apex_side.abs() < 0.0001 || apex_side.is_nan() {
+        return None;
+    }
+
+    // The plane of the portal, so only what is behind the portal is visible, and a plane through the apex and each edge of the portal
+    let mut planes = vec![Plane::new(center, -normal * apex_side.signum())];
+    for i in 0..polygon.len() {
+        let (a, b) = (polygon[i], polygon[(i + 1) % polygon.len()]);
+        let to_apex = match apex {
+            Apex::Point(point) => point - a,
+            Apex::Direction(direction) => direction,
+        };
+        let edge_normal = (b - a).cross(to_apex);
+        if edge_normal.magnitude2() < 1e-12 {
+            continue;
+        }
+        let edge_normal = edge_normal.normalize();
+        let edge_normal = if edge_normal.dot(center - a) < 0.0 {
+            -edge_normal
+        } else {
+            edge_normal
+        };
+        planes.push(Plane::new(a, edge_normal));
+    }
+    Some(planes)
+}
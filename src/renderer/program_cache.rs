@@ -0,0 +1,185 @@
+//!
+//! Introspection and pre-compilation of the shader programs cached in a [Context], see [program_report] and [compile_all].
+//!
+
+use crate::renderer::*;
+
+///
+/// A combination of a geometry, a material and a set of lights which needs a shader program to be rendered.
+/// The program only depends on the shader ids of the geometry, material and lights, see [Geometry::id], [Material::id] and [Light::id],
+/// so one combination covers all geometries, materials and lights with the same ids.
+///
+#[derive(Clone, Copy)]
+pub struct ProgramCombination<'a> {
+    /// The geometry.
+    pub geometry: &'a dyn Geometry,
+    /// The material.
+    pub material: &'a dyn Material,
+    /// The lights in the order they are given when rendering.
+    pub lights: &'a [&'a dyn Light],
+}
+
+impl<'a> ProgramCombination<'a> {
+    ///
+    /// Returns all combinations of the given geometries, materials and sets of lights.
+    ///
+    pub fn all(
+        geometries: &[&'a dyn Geometry],
+        materials: &[&'a dyn Material],
+        light_sets: &[&'a [&'a dyn Light]],
+    ) -> Vec<Self> {
+        let mut combinations = Vec::new();
+        for geometry in geometries {
+            for material in materials {
+                for lights in light_sets {
+                    combinations.push(Self {
+                        geometry: *geometry,
+                        material: *material,
+                        lights,
+                    });
+                }
+            }
+        }
+        combinations
+    }
+
+    fn id(&self) -> Vec<u8> {
+        combine_ids(
+            self.geometry.id(),
+            self.material.id(),
+            self.lights.iter().map(|l| l.id()),
+        )
+    }
+}
+
+///
+/// The state of a shader program in the cache of a [Context].
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProgramStatus {
+    /// The program is compiled and ready to use.
+    Compiled,
+    /// The program is being compiled in parallel with the rendering, see [Context::set_parallel_shader_compilation].
+    Compiling,
+    /// The program is not compiled, so it will be compiled the first time it is needed for rendering.
+    NotCompiled,
+}
+
+///
+/// A shader program permutation identified by the shader ids of the geometry, material and lights, see [ProgramReport].
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProgramPermutation {
+    /// The shader id of the geometry.
+    pub geometry_id: GeometryId,
+    /// The shader id of the material.
+    pub material_id: EffectMaterialId,
+    /// The shader ids of the lights.
+    pub light_ids: Vec<LightId>,
+    /// Whether the program is compiled.
+    pub status: ProgramStatus,
+}
+
+///
+/// A report of which shader program permutations are in the cache of a [Context], see [program_report].
+///
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ProgramReport {
+    /// The distinct permutations needed by the combinations given to [program_report].
+    pub permutations: Vec<ProgramPermutation>,
+}
+
+impl ProgramReport {
+    ///
+    /// Returns the number of permutations with the given status.
+    ///
+    pub fn count(&self, status: ProgramStatus) -> usize {
+        self.permutations
+            .iter()
+            .filter(|p| p.status == status)
+            .count()
+    }
+
+    ///
+    /// Returns whether all permutations are compiled, ie. rendering the combinations does not cause any compilation.
+    ///
+    pub fn is_complete(&self) -> bool {
+        self.permutations
+            .iter()
+            .all(|p| p.status == ProgramStatus::Compiled)
+    }
+
+    ///
+    /// Returns the permutations which are not compiled yet, including the ones being compiled.
+    ///
+    pub fn requires_compilation(&self) -> impl Iterator<Item = &ProgramPermutation> {
+        self.permutations
+            .iter()
+            .filter(|p| p.status != ProgramStatus::Compiled)
+    }
+}
+
+///
+/// Reports which of the shader program permutations needed for rendering the given combinations exist in the cache of the context
+/// and which would require compilation the first time they are rendered.
+/// Combinations with the same shader ids are only reported once.
+///
+pub fn program_report(context: &Context, combinations: &[ProgramCombination]) -> ProgramReport {
+    let programs = context.programs.read().unwrap();
+    let pending_programs = context.pending_programs.read().unwrap();
+    let mut ids = std::collections::HashSet::new();
+    let mut permutations = Vec::new();
+    for combination in combinations {
+        let id = combination.id();
+        let status = if programs.contains_key(&id) {
+            ProgramStatus::Compiled
+        } else if pending_programs.contains_key(&id) {
+            ProgramStatus::Compiling
+        } else {
+            ProgramStatus::NotCompiled
+        };
+        if ids.insert(id) {
+            permutations.push(ProgramPermutation {
+                geometry_id: combination.geometry.id(),
+                material_id: combination.material.id(),
+                light_ids: combination.lights.iter().map(|l| l.id()).collect(),
+                status,
+            });
+        }
+    }
+    ProgramReport { permutations }
+}
+
+///
+/// Compiles the shader programs needed for rendering the given combinations which are not already in the cache of the context,
+/// for example while showing a loading screen, to avoid stutters the first time each combination is rendered.
+/// If parallel shader compilation is enabled (see [Context::set_parallel_shader_compilation]), this only starts the compilations
+/// and [Context::poll_pending_programs] or [program_report] can be used to check when they are done, otherwise this blocks until all programs are compiled.
+/// Use [Context::lazy_program_compilations] to detect programs which are still compiled at render time.
+///
+/// ```no_run
+/// # use three_d::*;
+/// # let context: Context = unimplemented!();
+/// # let mesh: Mesh = unimplemented!();
+/// # let material: PhysicalMaterial = unimplemented!();
+/// # let ambient: AmbientLight = unimplemented!();
+/// # let sun: DirectionalLight = unimplemented!();
+/// let combinations = ProgramCombination::all(&[&mesh], &[&material], &[&[&ambient], &[&ambient, &sun]]);
+/// compile_all(&context, &combinations).unwrap();
+/// assert!(program_report(&context, &combinations).is_complete());
+/// ```
+///
+pub fn compile_all(
+    context: &Context,
+    combinations: &[ProgramCombination],
+) -> Result<(), RendererError> {
+    for combination in combinations {
+        prepare_program(
+            context,
+            combination.geometry,
+            combination.material,
+            combination.lights,
+        )?;
+    }
+    Ok(())
+}
@@ -90,28 +90,80 @@ macro_rules! enum_effectfield {
 ///
 #[allow(missing_docs)]
 #[open_enum]
+#[derive(Clone, Copy, Debug)]
 #[repr(u16)]
 pub enum GeometryId {
     Screen = 0x8000,
     Skybox = 0x8001,
     TerrainPatch = 0x8002,
+    TextBatch = 0x8003,
     Sprites = 0x8004,
     WaterPatch = 0x8005,
-    MeshBase = 0x8010,           // To 0x801F
+    LightFlareBase = 0x8006,     // To 0x8007
+    PointCloudBase = 0x8008,     // To 0x8009
+    SurfelsBase = 0x800A,        // To 0x800B
+    PolylineBase = 0x800C,       // To 0x800D
+    ShellsBase = 0x8010,         // To 0x8017
+    DisplacedMeshBase = 0x8018,  // To 0x801F
+    SkinnedMeshBase = 0x8020,    // To 0x803F
     ParticleSystemBase = 0x8040, // To 0x807F
-    InstancedMeshBase = 0x8080,  // To 0x80FF
+    MeshBase = 0x8080,           // To 0x80FF
+    Shape2DBase = 0x8100,        // To 0x817F
+    InstancedMeshBase = 0x8200,  // To 0x82FF
 }
 
 impl GeometryId {
-    enum_bitfield!(MeshBase, Mesh(normal, tangents, uv, color));
+    enum_bitfield!(LightFlareBase, LightFlare(screen_size));
+    enum_bitfield!(PointCloudBase, PointCloud(color));
+    enum_bitfield!(SurfelsBase, Surfels(color));
+    enum_bitfield!(PolylineBase, Polyline(color));
+    enum_bitfield!(ShellsBase, Shells(tangents, uv, color));
+    enum_bitfield!(
+        DisplacedMeshBase,
+        DisplacedMesh(color, lightmap_uv, normals_from_height)
+    );
+    enum_bitfield!(
+        SkinnedMeshBase,
+        SkinnedMesh(normal, tangents, uv, color, lightmap_uv)
+    );
+    enum_bitfield!(
+        MeshBase,
+        Mesh(
+            normal,
+            tangents,
+            uv,
+            color,
+            joint_weights,
+            lightmap_uv,
+            morph_targets
+        )
+    );
     enum_bitfield!(
         ParticleSystemBase,
         ParticleSystem(normal, tangents, uv, color, instance_color, instance_uv)
     );
     enum_bitfield!(
         InstancedMeshBase,
-        InstancedMesh(normal, tangents, uv, color, instance_color, instance_uv)
+        InstancedMesh(
+            normal,
+            tangents,
+            uv,
+            color,
+            instance_color,
+            instance_uv,
+            instance_position_scale,
+            instance_rotation
+        )
     );
+
+    ///
+    /// The ID of a 2D shape rendered using a mesh with the given ID, see [GeometryId::Mesh].
+    ///
+    #[allow(non_snake_case)]
+    #[inline]
+    pub(crate) fn Shape2D(mesh_id: GeometryId) -> Self {
+        Self(Self::Shape2DBase.0 | (mesh_id.0 - Self::MeshBase.0))
+    }
 }
 
 ///
@@ -120,30 +172,55 @@ impl GeometryId {
 ///
 #[allow(missing_docs)]
 #[open_enum]
+#[derive(Clone, Copy, Debug)]
 #[repr(u16)]
 pub enum EffectMaterialId {
     LightingPassEffectBase = 0x5000, // To 0x503F
+    LightFlareEffectBase = 0x5400,   // To 0x543F
     WaterEffectBase = 0x5800,        // To 0x583F
     CopyEffectBase = 0x6000,         // To 0x603F
+    UpscaleEffectBase = 0x6080,      // To 0x60FF
+    BloomEffectBase = 0x6400,        // To 0x67FF
     ScreenEffectBase = 0x6800,       // To 0x683F
+    TaaEffectBase = 0x6C00,          // To 0x6DFF (has holes)
     FogEffectBase = 0x7000,          // To 0x703F
-    FxaaEffectBase = 0x7800,         // To 0x7838 (has holes)
+    DepthOfFieldEffectBase = 0x7400, // To 0x76FF (has holes)
+    FxaaEffectBase = 0x7800,         // To 0x7A5F (has holes)
+    OitCompositeEffect = 0x7C00,
+    FramebufferFetchToneMappingEffectBase = 0x7C02, // To 0x7C03
 
-    ColorMaterialBase = 0x8000, // To 0x8001
     DepthMaterial = 0x8002,
     PositionMaterial = 0x8003,
     SkyboxMaterial = 0x8004,
     UVMaterial = 0x8005,
     NormalMaterialBase = 0x8006, // To 0x8007
+    LightFlareMaterial = 0x8008,
     IntersectionMaterial = 0x800B,
     IsosurfaceMaterial = 0x800C,
     ImpostersMaterial = 0x800D,
     BrdfMaterial = 0x800E,
     IrradianceMaterial = 0x800F,
     ORMMaterialBase = 0x8010,              // To 0x8013
-    PhysicalMaterialBase = 0x8020,         // To 0x803F
+    ColorMaterialBase = 0x8020,            // To 0x8027
     DeferredPhysicalMaterialBase = 0x8040, // To 0x807F
     PrefilterMaterial = 0x8080,
+    JointWeightMaterial = 0x8081,
+    FurMaterial = 0x8082,
+    JumpFloodSeedMaterial = 0x8083,
+    JumpFloodStepMaterial = 0x8084,
+    JumpFloodDistanceMaterial = 0x8085,
+    DistanceGlowMaterial = 0x8086,
+    ClayMaterial = 0x8087,
+    SsaoBlurMaterial = 0x8088,
+    SsaoMaterial = 0x8089,
+    AlphaCutoutDepthMaterialBase = 0x808A, // To 0x808B
+    OitMaterial = 0x808C,
+    ToonOutlineMaterial = 0x808D,
+    ToonMaterialBase = 0x808E, // To 0x808F
+    DepthPeelingMaterial = 0x8090,
+    SsaoOcclusionMaterialBase = 0x8200, // To 0x827F
+    OutlineMaterialBase = 0x8280,       // To 0x829F (has holes)
+    PhysicalMaterialBase = 0xC000,      // To 0xFFFF
 }
 
 impl EffectMaterialId {
@@ -153,8 +230,25 @@ impl EffectMaterialId {
     enum_effectfield!(ScreenEffectBase, ScreenEffect(Option<...Default>));
     enum_effectfield!(FogEffectBase, FogEffect(...Default));
     enum_effectfield!(FxaaEffectBase, FxaaEffect(color_texture: ColorTexture));
+    enum_effectfield!(UpscaleEffectBase, UpscaleEffect(Option<...Default>));
+    enum_effectfield!(TaaEffectBase, TaaEffect(Option<...Default>));
+    enum_effectfield!(
+        DepthOfFieldEffectBase,
+        DepthOfFieldEffect(Option<...Default>)
+    );
+    enum_effectfield!(
+        LightFlareEffectBase,
+        LightFlareEffect(depth_texture: DepthTexture)
+    );
 
-    enum_bitfield!(ColorMaterialBase, ColorMaterial(texture));
+    enum_bitfield!(
+        ColorMaterialBase,
+        ColorMaterial(texture, premultiplied_alpha, triplanar)
+    );
+    enum_bitfield!(
+        FramebufferFetchToneMappingEffectBase,
+        FramebufferFetchToneMappingEffect(arm)
+    );
     enum_bitfield!(NormalMaterialBase, NormalMaterial(normal_texture));
     enum_bitfield!(
         ORMMaterialBase,
@@ -168,6 +262,15 @@ impl EffectMaterialId {
             occlusion_texture,
             normal_texture,
             emissive_texture,
+            lightmap_texture,
+            subsurface,
+            thickness_texture,
+            height_texture,
+            clearcoat,
+            clearcoat_texture,
+            clearcoat_normal_texture,
+            anisotropy,
+            triplanar,
         )
     );
     enum_bitfield!(
@@ -189,16 +292,57 @@ impl EffectMaterialId {
 ///
 #[allow(missing_docs)]
 #[open_enum]
+#[derive(Clone, Copy, Debug)]
 #[repr(u8)]
 pub enum LightId {
-    AmbientLightBase = 0x80,     // To 0x81
-    DirectionalLightBase = 0x82, // To 0x83
-    PointLight = 0x84,
-    SpotLightBase = 0x86, // To 0x87
+    AmbientLightBase = 0x80, // To 0x81
+    PointLightBase = 0x84,   // To 0x87
+    AreaLight = 0x88,
+    DirectionalLightBase = 0x90,                     // To 0x9F
+    SpotLightBase = 0xA0,                            // To 0xAF
+    SpotLightWithShadowAtlasBase = 0xB0,             // To 0xB7
+    LightBufferBase = 0xB8,                          // To 0xBD
+    SpotLightWithColorRampBase = 0xC0,               // To 0xCF
+    SpotLightWithShadowAtlasAndColorRampBase = 0xD0, // To 0xD7
 }
 
 impl LightId {
     enum_bitfield!(AmbientLightBase, AmbientLight(environment));
-    enum_bitfield!(DirectionalLightBase, DirectionalLight(shadow_texture));
-    enum_bitfield!(SpotLightBase, SpotLight(shadow_texture));
+    enum_bitfield!(PointLightBase, PointLight(shadow_texture, color_ramp));
+    enum_bitfield!(
+        DirectionalLightBase,
+        DirectionalLight(shadow_texture, caustics, shadow_filter, cascaded_shadow)
+    );
+    enum_bitfield!(
+        SpotLightBase,
+        SpotLight(
+            shadow_texture,
+            cookie_texture,
+            falloff_texture,
+            shadow_filter
+        )
+    );
+    enum_bitfield!(
+        SpotLightWithShadowAtlasBase,
+        SpotLightWithShadowAtlas(cookie_texture, falloff_texture, shadow_filter)
+    );
+    enum_bitfield!(
+        SpotLightWithColorRampBase,
+        SpotLightWithColorRamp(
+            shadow_texture,
+            cookie_texture,
+            falloff_texture,
+            shadow_filter
+        )
+    );
+    enum_bitfield!(
+        SpotLightWithShadowAtlasAndColorRampBase,
+        SpotLightWithShadowAtlasAndColorRamp(cookie_texture, falloff_texture, shadow_filter)
+    );
+
+    #[allow(non_snake_case)]
+    #[inline]
+    pub(crate) fn LightBuffer(capacity_index: u8) -> Self {
+        Self(Self::LightBufferBase.0 | capacity_index)
+    }
 }
@@ -6,6 +6,13 @@ use std::collections::HashMap;
 use swash::zeno::{Command, PathData};
 use swash::{scale::ScaleContext, shape::ShapeContext, FontRef, GlyphId};
 
+mod glyph_atlas;
+pub(crate) use glyph_atlas::GlyphAtlas;
+
+mod text_batch;
+#[doc(inline)]
+pub use text_batch::*;
+
 ///
 /// Options for text layout.
 ///
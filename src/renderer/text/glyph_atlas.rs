@@ -0,0 +1,188 @@
+use crate::*;
+use std::collections::HashMap;
+use swash::scale::{Render, ScaleContext, Source};
+use swash::zeno::Format;
+use swash::{FontRef, GlyphId};
+
+///
+/// The width and height in pixels of each page in a [GlyphAtlas].
+///
+const PAGE_SIZE: u32 = 1024;
+
+///
+/// The number of empty pixels between the glyphs in a [GlyphAtlas] to avoid bleeding when sampling with linear interpolation.
+///
+const PADDING: u32 = 1;
+
+///
+/// The placement of a rasterized glyph in a [GlyphAtlas].
+///
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct AtlasGlyph {
+    /// The index of the atlas page containing the glyph.
+    pub page: usize,
+    /// The uv coordinates of the bottom left (xy) and top right (zw) corner of the glyph in the atlas page.
+    pub uv_rect: Vec4,
+    /// The offset from the pen position to the bottom left corner of the glyph in pixels at the atlas font size.
+    pub offset: Vec2,
+    /// The size of the glyph in pixels at the atlas font size.
+    pub size: Vec2,
+}
+
+struct AtlasPage {
+    data: Vec<u8>,
+    texture: Texture2D,
+    cursor: (u32, u32),
+    row_height: u32,
+    dirty: bool,
+}
+
+///
+/// Rasterized glyph coverage packed into one or more texture pages, where the glyphs are rasterized on demand the first time they are used.
+///
+pub(crate) struct GlyphAtlas<'a> {
+    context: Context,
+    font: FontRef<'a>,
+    size: f32,
+    scale_context: ScaleContext,
+    glyphs: HashMap<GlyphId, Option<AtlasGlyph>>,
+    pages: Vec<AtlasPage>,
+}
+
+impl<'a> GlyphAtlas<'a> {
+    ///
+    /// Creates a new empty atlas where the glyphs of the given font are rasterized with the given size in pixels per em.
+    ///
+    pub fn new(context: &Context, font: FontRef<'a>, size: f32) -> Self {
+        Self {
+            context: context.clone(),
+            font,
+            size,
+            scale_context: ScaleContext::new(),
+            glyphs: HashMap::new(),
+            pages: Vec::new(),
+        }
+    }
+
+    ///
+    /// The size in pixels per em the glyphs are rasterized with.
+    ///
+    pub fn size(&self) -> f32 {
+        self.size
+    }
+
+    ///
+    /// The number of pages in the atlas.
+    ///
+    pub fn page_count(&self) -> usize {
+        self.pages.len()
+    }
+
+    ///
+    /// The coverage texture of the given page.
+    ///
+    pub fn texture(&self, page: usize) -> &Texture2D {
+        &self.pages[page].texture
+    }
+
+    ///
+    /// Returns the placement of the given glyph in the atlas, which is rasterized if it is not already in the atlas.
+    /// Returns `None` if the glyph has no visible pixels, for example a space.
+    ///
+    pub fn glyph(&mut self, id: GlyphId) -> Option<AtlasGlyph> {
+        if let Some(glyph) = self.glyphs.get(&id) {
+            return *glyph;
+        }
+        let glyph = self.rasterize(id);
+        self.glyphs.insert(id, glyph);
+        glyph
+    }
+
+    ///
+    /// Uploads the pages with new glyphs to the GPU.
+    ///
+    pub fn upload(&mut self) {
+        for page in self.pages.iter_mut().filter(|page| page.dirty) {
+            page.texture.fill(&page.data);
+            page.dirty = false;
+        }
+    }
+
+    fn rasterize(&mut self, id: GlyphId) -> Option<AtlasGlyph> {
+        let mut scaler = self
+            .scale_context
+            .builder(self.font)
+            .size(self.size)
+            .build();
+        let image = Render::new(&[Source::Outline])
+            .format(Format::Alpha)
+            .render(&mut scaler, id)?;
+        let (width, height) = (image.placement.width, image.placement.height);
+        if width == 0 || height == 0 || width + 2 * PADDING > PAGE_SIZE {
+            return None;
+        }
+        let height = height.min(PAGE_SIZE - 2 * PADDING);
+
+        // Find room for the glyph in the last page using a simple shelf packing
+        let fits = |page: &AtlasPage| {
+            let (x, y) = page.cursor;
+            if x + width + PADDING <= PAGE_SIZE {
+                y + height + PADDING <= PAGE_SIZE
+            } else {
+                y + page.row_height + PADDING + height + PADDING <= PAGE_SIZE
+            }
+        };
+        if !self.pages.last().map(fits).unwrap_or(false) {
+            self.pages.push(AtlasPage {
+                data: vec![0; (PAGE_SIZE * PAGE_SIZE) as usize],
+                texture: Texture2D::new_empty::<u8>(
+                    &self.context,
+                    PAGE_SIZE,
+                    PAGE_SIZE,
+                    Interpolation::Linear,
+                    Interpolation::Linear,
+                    None,
+                    Wrapping::ClampToEdge,
+                    Wrapping::ClampToEdge,
+                ),
+                cursor: (PADDING, PADDING),
+                row_height: 0,
+                dirty: true,
+            });
+        }
+        let page_index = self.pages.len() - 1;
+        let page = &mut self.pages[page_index];
+        if page.cursor.0 + width + PADDING > PAGE_SIZE {
+            page.cursor = (PADDING, page.cursor.1 + page.row_height + PADDING);
+            page.row_height = 0;
+        }
+        let (x, y) = page.cursor;
+        page.cursor.0 += width + PADDING;
+        page.row_height = page.row_height.max(height);
+        page.dirty = true;
+
+        // The first row of the page data is the top row of the texture
+        for row in 0..height {
+            let source = (row * width) as usize;
+            let target = ((y + row) * PAGE_SIZE + x) as usize;
+            page.data[target..target + width as usize]
+                .copy_from_slice(&image.data[source..source + width as usize]);
+        }
+
+        let page_size = PAGE_SIZE as f32;
+        Some(AtlasGlyph {
+            page: page_index,
+            uv_rect: vec4(
+                x as f32 / page_size,
+                1.0 - (y + height) as f32 / page_size,
+                (x + width) as f32 / page_size,
+                1.0 - y as f32 / page_size,
+            ),
+            offset: vec2(
+                image.placement.left as f32,
+                image.placement.top as f32 - height as f32,
+            ),
+            size: vec2(width as f32, height as f32),
+        })
+    }
+}
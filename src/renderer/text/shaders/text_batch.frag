@@ -0,0 +1,19 @@
+
+uniform sampler2D glyphAtlas;
+
+in vec2 glyph_uvs;
+
+void main()
+{
+    material_main();
+
+    float coverage = texture(glyphAtlas, glyph_uvs).r;
+    if (coverage <= 0.0) {
+        discard;
+    }
+    #ifdef PREMULTIPLIED_ALPHA
+    outColor *= coverage;
+    #else
+    outColor.a *= coverage;
+    #endif
+}
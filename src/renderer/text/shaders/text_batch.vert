@@ -0,0 +1,27 @@
+uniform mat4 viewProjection;
+uniform mat4 modelMatrix;
+
+in vec3 position;
+
+in vec3 glyph_position;
+in vec2 glyph_size;
+in vec4 glyph_uv_rect;
+in vec4 glyph_color;
+
+out vec3 pos;
+out vec2 uvs;
+out vec4 col;
+out vec2 glyph_uvs;
+flat out int instance_id;
+
+void main()
+{
+    uvs = position.xy;
+    glyph_uvs = mix(glyph_uv_rect.xy, glyph_uv_rect.zw, position.xy);
+    col = glyph_color;
+
+    vec4 world_pos = modelMatrix * vec4(glyph_position + vec3(position.xy * glyph_size, 0.0), 1.0);
+    pos = world_pos.xyz / world_pos.w;
+    gl_Position = viewProjection * world_pos;
+    instance_id = gl_InstanceID;
+}
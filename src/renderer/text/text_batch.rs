@@ -0,0 +1,447 @@
+use super::glyph_atlas::*;
+use crate::renderer::*;
+use std::collections::HashMap;
+use std::ops::Range;
+use swash::{shape::ShapeContext, FontRef};
+
+///
+/// The style of a text in a [TextBatch].
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TextStyle {
+    /// The start of the baseline of the first line of the text in the local coordinate system of the [TextBatch].
+    pub position: Vec3,
+    /// The font size in units per em, which is pixels when the text is rendered with a camera created with [Camera::new_2d].
+    pub size: f32,
+    /// The color of the text.
+    pub color: Srgba,
+    /// The line height multiplier where 1.0 corresponds to the ascent plus the descent of the font.
+    pub line_height: f32,
+}
+
+impl Default for TextStyle {
+    fn default() -> Self {
+        Self {
+            position: Vec3::zero(),
+            size: 16.0,
+            color: Srgba::WHITE,
+            line_height: 1.2,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+struct GlyphInstance {
+    position: Vec3,
+    size: Vec2,
+    uv_rect: Vec4,
+    color: Vec4,
+}
+
+impl GlyphInstance {
+    /// An instance with zero size which is not visible, used for the unused instances.
+    const HIDDEN: Self = Self {
+        position: vec3(0.0, 0.0, 0.0),
+        size: vec2(0.0, 0.0),
+        uv_rect: vec4(0.0, 0.0, 0.0, 0.0),
+        color: vec4(0.0, 0.0, 0.0, 0.0),
+    };
+}
+
+///
+/// The instances of all the glyphs in one atlas page, which are drawn in one draw call.
+///
+struct PageInstances {
+    position_buffer: InstanceBuffer<Vec3>,
+    size_buffer: InstanceBuffer<Vec2>,
+    uv_rect_buffer: InstanceBuffer<Vec4>,
+    color_buffer: InstanceBuffer<Vec4>,
+    count: u32,
+    capacity: u32,
+    free: Vec<Range<u32>>,
+}
+
+impl PageInstances {
+    fn new(context: &Context) -> Self {
+        Self {
+            position_buffer: InstanceBuffer::new(context),
+            size_buffer: InstanceBuffer::new(context),
+            uv_rect_buffer: InstanceBuffer::new(context),
+            color_buffer: InstanceBuffer::new(context),
+            count: 0,
+            capacity: 0,
+            free: Vec::new(),
+        }
+    }
+
+    ///
+    /// Returns a range of unused instances with the given length.
+    ///
+    fn allocate(&mut self, length: u32) -> Range<u32> {
+        if let Some(index) = self.free.iter().position(|r| r.len() as u32 >= length) {
+            let range = self.free.swap_remove(index);
+            if range.len() as u32 > length {
+                self.free.push(range.start + length..range.end);
+            }
+            range.start..range.start + length
+        } else {
+            self.count += length;
+            if self.count > self.capacity {
+                // Grow the buffers to at least twice the size to avoid growing them each time a text is added
+                let capacity = self.count.max(2 * self.capacity).max(64);
+                self.write(
+                    self.capacity,
+                    &vec![GlyphInstance::HIDDEN; (capacity - self.capacity) as usize],
+                );
+                self.capacity = capacity;
+            }
+            self.count - length..self.count
+        }
+    }
+
+    ///
+    /// Hides the instances in the given range and makes them available for other texts.
+    ///
+    fn release(&mut self, range: Range<u32>) {
+        self.write(range.start, &vec![GlyphInstance::HIDDEN; range.len()]);
+        self.free.push(range);
+    }
+
+    ///
+    /// Writes the given instances to the buffers starting at the given instance, without touching the rest of the instances.
+    ///
+    fn write(&mut self, start: u32, instances: &[GlyphInstance]) {
+        if instances.is_empty() {
+            return;
+        }
+        self.position_buffer.fill_subset(
+            start,
+            &instances.iter().map(|i| i.position).collect::<Vec<_>>(),
+        );
+        self.size_buffer
+            .fill_subset(start, &instances.iter().map(|i| i.size).collect::<Vec<_>>());
+        self.uv_rect_buffer.fill_subset(
+            start,
+            &instances.iter().map(|i| i.uv_rect).collect::<Vec<_>>(),
+        );
+        self.color_buffer.fill_subset(
+            start,
+            &instances.iter().map(|i| i.color).collect::<Vec<_>>(),
+        );
+    }
+}
+
+///
+/// A retained set of texts rendered as instanced quads, one per glyph, with the glyphs rasterized into a glyph atlas.
+/// All the glyphs in the same atlas page are rendered in a single draw call, which makes it possible to render a large amount of
+/// dynamic text, for example a HUD with many counters, efficiently.
+///
+/// Each text is identified by an id and is added or changed with [TextBatch::set_text] which only rewrites the glyph instances of that text.
+/// The batch is a [Geometry] which is rendered with a material, for example a [ColorMaterial] where the color is multiplied with the color of the text.
+/// Use a camera created with [Camera::new_2d] to position the texts in pixels.
+///
+pub struct TextBatch<'a> {
+    context: Context,
+    font: FontRef<'a>,
+    atlas: GlyphAtlas<'a>,
+    shape_context: ShapeContext,
+    quad_buffer: VertexBuffer<Vec3>,
+    pages: Vec<PageInstances>,
+    texts: HashMap<u32, Vec<(usize, Range<u32>)>>,
+    transformation: Mat4,
+}
+
+impl<'a> TextBatch<'a> {
+    ///
+    /// Creates a new empty text batch using the given font.
+    /// The glyphs are rasterized with the given size in pixels per em and scaled to the size of each text, so use the largest size that the texts are rendered with.
+    /// The index indicates the specific font in a font collection. Set to 0 if unsure.
+    ///
+    pub fn new(
+        context: &Context,
+        font_bytes: &'a [u8],
+        font_index: u32,
+        size: f32,
+    ) -> Result<Self, RendererError> {
+        let font = FontRef::from_index(font_bytes, font_index as usize)
+            .ok_or(RendererError::MissingFont(font_index))?;
+        Ok(Self {
+            context: context.clone(),
+            font,
+            atlas: GlyphAtlas::new(context, font, size),
+            shape_context: ShapeContext::new(),
+            quad_buffer: VertexBuffer::new_with_data(
+                context,
+                &[
+                    vec3(0.0, 0.0, 0.0),
+                    vec3(1.0, 0.0, 0.0),
+                    vec3(1.0, 1.0, 0.0),
+                    vec3(1.0, 1.0, 0.0),
+                    vec3(0.0, 1.0, 0.0),
+                    vec3(0.0, 0.0, 0.0),
+                ],
+            ),
+            pages: Vec::new(),
+            texts: HashMap::new(),
+            transformation: Mat4::identity(),
+        })
+    }
+
+    ///
+    /// Adds a text with the given id or replaces the text with the given id if it already exists.
+    /// Only the glyph instances belonging to this text are updated.
+    ///
+    pub fn set_text(&mut self, id: u32, text: &str, style: TextStyle) {
+        let instances = self.layout(text, style);
+        while self.pages.len() < self.atlas.page_count() {
+            self.pages.push(PageInstances::new(&self.context));
+        }
+
+        let mut old_ranges = self.texts.remove(&id).unwrap_or_default();
+        let mut ranges = Vec::new();
+        for (page, page_instances) in instances.iter().enumerate() {
+            let old_range = old_ranges
+                .iter()
+                .position(|(p, _)| *p == page)
+                .map(|i| old_ranges.swap_remove(i).1);
+            let length = page_instances.len() as u32;
+            let instances = &mut self.pages[page];
+            let range = match old_range {
+                Some(range) if range.len() as u32 >= length && length > 0 => range,
+                Some(range) => {
+                    instances.release(range);
+                    if length == 0 {
+                        continue;
+                    }
+                    instances.allocate(length)
+                }
+                None if length == 0 => continue,
+                None => instances.allocate(length),
+            };
+            // Unused instances at the end of the range are hidden, so the range can be reused if the text grows again
+            let mut data = page_instances.clone();
+            data.resize(range.len(), GlyphInstance::HIDDEN);
+            instances.write(range.start, &data);
+            ranges.push((page, range));
+        }
+        for (page, range) in old_ranges {
+            self.pages[page].release(range);
+        }
+        self.texts.insert(id, ranges);
+    }
+
+    ///
+    /// Removes the text with the given id. Returns whether the text existed.
+    ///
+    pub fn remove_text(&mut self, id: u32) -> bool {
+        if let Some(ranges) = self.texts.remove(&id) {
+            for (page, range) in ranges {
+                self.pages[page].release(range);
+            }
+            true
+        } else {
+            false
+        }
+    }
+
+    ///
+    /// Returns whether a text with the given id exists.
+    ///
+    pub fn contains_text(&self, id: u32) -> bool {
+        self.texts.contains_key(&id)
+    }
+
+    ///
+    /// Removes all texts. The rasterized glyphs are kept in the glyph atlas.
+    ///
+    pub fn clear(&mut self) {
+        self.texts.clear();
+        self.pages.clear();
+    }
+
+    ///
+    /// Returns the number of glyph instances, including the unused instances which are not visible.
+    ///
+    pub fn instance_count(&self) -> u32 {
+        self.pages.iter().map(|page| page.count).sum()
+    }
+
+    ///
+    /// Returns the local to world transformation applied to all texts.
+    ///
+    pub fn transformation(&self) -> Mat4 {
+        self.transformation
+    }
+
+    ///
+    /// Set the local to world transformation applied to all texts.
+    ///
+    pub fn set_transformation(&mut self, transformation: Mat4) {
+        self.transformation = transformation;
+    }
+
+    ///
+    /// Shapes the text and returns the glyph instances for each atlas page.
+    /// New glyphs are rasterized into the atlas and uploaded to the GPU.
+    ///
+    fn layout(&mut self, text: &str, style: TextStyle) -> Vec<Vec<GlyphInstance>> {
+        let metrics = self.font.metrics(&[]).scale(style.size);
+        let line_height = (metrics.ascent + metrics.descent) * style.line_height;
+        let scale = style.size / self.atlas.size();
+        let color = style.color.to_linear_srgb();
+        let atlas = &mut self.atlas;
+        let mut instances: Vec<Vec<GlyphInstance>> = Vec::new();
+        let mut pen = vec2(0.0, 0.0);
+
+        let mut shaper = self
+            .shape_context
+            .builder(self.font)
+            .size(style.size)
+            .build();
+        shaper.add_str(text);
+        shaper.shape_with(|cluster| {
+            if matches!(text.get(cluster.source.to_range()), Some("\n")) {
+                pen = vec2(0.0, pen.y - line_height);
+            }
+            for glyph in cluster.glyphs {
+                if let Some(atlas_glyph) = atlas.glyph(glyph.id) {
+                    if instances.len() <= atlas_glyph.page {
+                        instances.resize(atlas_glyph.page + 1, Vec::new());
+                    }
+                    let corner = pen + vec2(glyph.x, glyph.y) + atlas_glyph.offset * scale;
+                    instances[atlas_glyph.page].push(GlyphInstance {
+                        position: style.position + corner.extend(0.0),
+                        size: atlas_glyph.size * scale,
+                        uv_rect: atlas_glyph.uv_rect,
+                        color,
+                    });
+                }
+            }
+            pen.x += cluster.advance();
+        });
+        self.atlas.upload();
+        instances.resize(self.atlas.page_count(), Vec::new());
+        instances
+    }
+
+    fn draw(&self, program: &Program, render_states: RenderStates, viewer: &dyn Viewer) {
+        program.use_uniform("viewProjection", viewer.projection() * viewer.view());
+        program.use_uniform("modelMatrix", self.transformation);
+        program.use_vertex_attribute("position", &self.quad_buffer);
+        for (page, instances) in self.pages.iter().enumerate() {
+            if instances.count == 0 {
+                continue;
+            }
+            program.use_texture("glyphAtlas", self.atlas.texture(page));
+            program.use_instance_attribute("glyph_position", &instances.position_buffer);
+            program.use_instance_attribute("glyph_size", &instances.size_buffer);
+            program.use_instance_attribute("glyph_uv_rect", &instances.uv_rect_buffer);
+            program.use_instance_attribute("glyph_color", &instances.color_buffer);
+            program.draw_arrays_instanced(render_states, viewer.viewport(), 6, instances.count);
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a TextBatch<'_> {
+    type Item = &'a dyn Geometry;
+    type IntoIter = std::iter::Once<&'a dyn Geometry>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        std::iter::once(self)
+    }
+}
+
+impl Geometry for TextBatch<'_> {
+    fn draw(&self, viewer: &dyn Viewer, program: &Program, render_states: RenderStates) {
+        self.draw(program, render_states, viewer);
+    }
+
+    fn vertex_shader_source(&self) -> String {
+        include_str!("shaders/text_batch.vert").to_owned()
+    }
+
+    fn id(&self) -> GeometryId {
+        GeometryId::TextBatch
+    }
+
+    fn render_with_material(
+        &self,
+        material: &dyn Material,
+        viewer: &dyn Viewer,
+        lights: &[&dyn Light],
+    ) {
+        if let Err(e) = render_with_material(
+            &self.context,
+            viewer,
+            self,
+            GlyphMaterial { material },
+            lights,
+        ) {
+            panic!("{}", e.to_string());
+        }
+    }
+
+    fn render_with_effect(
+        &self,
+        material: &dyn Effect,
+        viewer: &dyn Viewer,
+        lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) {
+        if let Err(e) = render_with_effect(
+            &self.context,
+            viewer,
+            self,
+            material,
+            lights,
+            color_texture,
+            depth_texture,
+        ) {
+            panic!("{}", e.to_string());
+        }
+    }
+
+    fn aabb(&self) -> AxisAlignedBoundingBox {
+        AxisAlignedBoundingBox::INFINITE
+    }
+}
+
+///
+/// Extends the fragment shader of a material to multiply the alpha with the glyph coverage from the glyph atlas.
+///
+struct GlyphMaterial<'a> {
+    material: &'a dyn Material,
+}
+
+impl Material for GlyphMaterial<'_> {
+    fn fragment_shader_source(&self, lights: &[&dyn Light]) -> String {
+        let source = self.material.fragment_shader_source(lights).replacen(
+            "void main(",
+            "void material_main(",
+            1,
+        );
+        format!("{}\n{}", source, include_str!("shaders/text_batch.frag"))
+    }
+
+    fn id(&self) -> EffectMaterialId {
+        // The geometry ID of the text batch ensures that the extended shader is cached separately from the original material shader
+        self.material.id()
+    }
+
+    fn use_uniforms(&self, program: &Program, viewer: &dyn Viewer, lights: &[&dyn Light]) {
+        self.material.use_uniforms(program, viewer, lights);
+    }
+
+    fn render_states(&self) -> RenderStates {
+        let mut render_states = self.material.render_states();
+        if render_states.blend == Blend::Disabled {
+            render_states.blend = Blend::TRANSPARENCY;
+        }
+        render_states
+    }
+
+    fn material_type(&self) -> MaterialType {
+        self.material.material_type()
+    }
+}
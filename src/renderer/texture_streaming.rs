@@ -0,0 +1,392 @@
+//!
+//! Streaming of high resolution textures, see [TextureStreamer].
+//!
+
+use crate::renderer::*;
+use std::collections::HashMap;
+use std::sync::{mpsc, Arc};
+
+///
+/// A function which returns the given mip level of a streamed texture, where level 0 is the full resolution and
+/// each following level halves the width and height (rounded down, but at least one pixel).
+/// On native, it is called on a background thread, so it can for example load and decode an image file.
+///
+pub type MipLevelLoader = Arc<dyn Fn(u32) -> CpuTexture + Send + Sync>;
+
+///
+/// Identifies a texture registered in a [TextureStreamer].
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct StreamedTextureId(u32);
+
+///
+/// Statistics from a [TextureStreamer], for example to show in a debug overlay.
+/// All sizes are estimates in bytes including the mip chain below the resident level.
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct TextureStreamingStats {
+    /// The number of registered textures.
+    pub texture_count: usize,
+    /// The size of the resident textures.
+    pub resident_bytes: u64,
+    /// The size of the resident textures if all textures were at their required mip level, ie. the budget needed to show all textures sharp.
+    pub required_bytes: u64,
+    /// The budget, see [TextureStreamer::budget].
+    pub budget_bytes: u64,
+    /// The number of textures that are not at their required mip level because of the budget.
+    pub budget_limited_count: usize,
+    /// The number of mip levels currently being loaded.
+    pub pending_loads: usize,
+    /// The total number of mip levels that has been loaded and made resident.
+    pub completed_loads: usize,
+    /// The total number of times a texture has been reduced to a lower resolution to stay within the budget.
+    pub evictions: usize,
+}
+
+struct StreamedTexture {
+    loader: MipLevelLoader,
+    width: u32,
+    height: u32,
+    bytes_per_texel: u64,
+    bounds: AxisAlignedBoundingBox,
+    priority: f32,
+    lowest_level: u32,
+    lowest_level_data: CpuTexture,
+    texture: Texture2DRef,
+    resident_level: u32,
+    pending_level: Option<u32>,
+    required_level: u32,
+    target_level: u32,
+}
+
+impl StreamedTexture {
+    fn bytes(&self, level: u32) -> u64 {
+        let width = (self.width >> level).max(1) as u64;
+        let height = (self.height >> level).max(1) as u64;
+        // The mip chain below the level adds one third
+        width * height * self.bytes_per_texel * 4 / 3
+    }
+}
+
+///
+/// Keeps the total size of a set of high resolution textures within a budget by only keeping the mip levels resident on the GPU
+/// that are needed given the current view.
+///
+/// Each texture is registered with its full resolution, the bounds of the geometry it is applied to and a [MipLevelLoader].
+/// Initially, only a low resolution version of each texture is resident, see [TextureStreamer::min_resident_size].
+/// Call [TextureStreamer::update] each frame to estimate the required mip level of each texture from the projected screen size of its bounds,
+/// start loading the missing mip levels in the background and reduce the resolution of the textures that are no longer needed,
+/// prioritized by the projected screen size so that the total size never exceeds the [budget](TextureStreamer::budget).
+/// When a texture has changed, the new texture must be assigned to the materials using it, for example:
+///
+/// ```no_run
+/// # use three_d::*;
+/// # let context: Context = unimplemented!();
+/// # let camera: Camera = unimplemented!();
+/// # let mut streamer = TextureStreamer::new(&context, 256 * 1024 * 1024);
+/// # let mut materials: std::collections::HashMap<StreamedTextureId, PhysicalMaterial> = unimplemented!();
+/// for id in streamer.update(&camera) {
+///     materials.get_mut(&id).unwrap().albedo_texture = Some(streamer.texture(id));
+/// }
+/// ```
+///
+/// **Note:** The GPU memory of a texture that has been replaced is not freed until all references to it are dropped.
+///
+pub struct TextureStreamer {
+    context: Context,
+    textures: HashMap<StreamedTextureId, StreamedTexture>,
+    next_id: u32,
+    sender: mpsc::Sender<(StreamedTextureId, u32, CpuTexture)>,
+    receiver: mpsc::Receiver<(StreamedTextureId, u32, CpuTexture)>,
+    stats: TextureStreamingStats,
+    /// The maximum size in bytes of the resident textures. The lowest resident mip levels are always resident, so they should fit within the budget.
+    pub budget: u64,
+    /// The largest width or height of the mip level of a texture that is always resident. Defaults to 64.
+    /// Only used when a texture is registered.
+    pub min_resident_size: u32,
+    /// The maximum number of mip levels that are loaded at the same time. Defaults to 4.
+    pub max_pending_loads: usize,
+    /// Added to the required mip level of all textures, so a positive bias gives lower resolution textures and a negative bias gives higher resolution textures.
+    pub mip_bias: f32,
+}
+
+impl TextureStreamer {
+    ///
+    /// Creates a new texture streamer with the given budget in bytes.
+    ///
+    pub fn new(context: &Context, budget: u64) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        Self {
+            context: context.clone(),
+            textures: HashMap::new(),
+            next_id: 0,
+            sender,
+            receiver,
+            stats: TextureStreamingStats::default(),
+            budget,
+            min_resident_size: 64,
+            max_pending_loads: 4,
+            mip_bias: 0.0,
+        }
+    }
+
+    ///
+    /// Registers a texture with the given full resolution width and height, which is applied to geometry within the given bounds.
+    /// The lowest resident mip level is loaded immediately using the given loader, the rest of the mip levels are loaded when needed.
+    ///
+    pub fn register(
+        &mut self,
+        width: u32,
+        height: u32,
+        bounds: AxisAlignedBoundingBox,
+        loader: impl Fn(u32) -> CpuTexture + Send + Sync + 'static,
+    ) -> StreamedTextureId {
+        let loader: MipLevelLoader = Arc::new(loader);
+        let mut lowest_level = 0;
+        while (width.max(height) >> lowest_level) > self.min_resident_size.max(1) {
+            lowest_level += 1;
+        }
+        let lowest_level_data = with_mipmap(loader(lowest_level));
+        let id = StreamedTextureId(self.next_id);
+        self.next_id += 1;
+        self.textures.insert(
+            id,
+            StreamedTexture {
+                width,
+                height,
+                bytes_per_texel: bytes_per_texel(&lowest_level_data.data),
+                bounds,
+                priority: 1.0,
+                lowest_level,
+                texture: Texture2DRef::from_cpu_texture(&self.context, &lowest_level_data),
+                lowest_level_data,
+                loader,
+                resident_level: lowest_level,
+                pending_level: None,
+                required_level: lowest_level,
+                target_level: lowest_level,
+            },
+        );
+        id
+    }
+
+    ///
+    /// Removes the texture with the given id.
+    ///
+    pub fn remove(&mut self, id: StreamedTextureId) {
+        self.textures.remove(&id);
+    }
+
+    ///
+    /// Sets the bounds of the geometry the texture is applied to, for example if the geometry has moved.
+    ///
+    pub fn set_bounds(&mut self, id: StreamedTextureId, bounds: AxisAlignedBoundingBox) {
+        if let Some(texture) = self.textures.get_mut(&id) {
+            texture.bounds = bounds;
+        }
+    }
+
+    ///
+    /// Sets the priority of the texture, which is multiplied with the projected screen size when deciding which textures get the budget. Defaults to 1.
+    ///
+    pub fn set_priority(&mut self, id: StreamedTextureId, priority: f32) {
+        if let Some(texture) = self.textures.get_mut(&id) {
+            texture.priority = priority;
+        }
+    }
+
+    ///
+    /// Returns the currently resident version of the texture with the given id.
+    ///
+    /// # Panic
+    /// Will panic if the texture has been removed.
+    ///
+    pub fn texture(&self, id: StreamedTextureId) -> Texture2DRef {
+        self.textures[&id].texture.clone()
+    }
+
+    ///
+    /// Returns the currently resident mip level of the texture with the given id, where 0 is the full resolution.
+    ///
+    pub fn resident_level(&self, id: StreamedTextureId) -> Option<u32> {
+        self.textures.get(&id).map(|texture| texture.resident_level)
+    }
+
+    ///
+    /// Returns the statistics from the last call to [TextureStreamer::update].
+    ///
+    pub fn stats(&self) -> TextureStreamingStats {
+        self.stats
+    }
+
+    ///
+    /// Updates the resident mip levels of the textures given the viewer and returns the ids of the textures that have changed since the last update.
+    /// The changed textures must be assigned to the materials using them, see [TextureStreamer::texture].
+    ///
+    pub fn update(&mut self, viewer: &dyn Viewer) -> Vec<StreamedTextureId> {
+        let mut changed = Vec::new();
+
+        // Compute the required mip level and the priority of each texture
+        let frustum = Frustum::new(viewer.projection() * viewer.view());
+        let mut order = Vec::new();
+        for (id, texture) in self.textures.iter_mut() {
+            let pixels = projected_size(viewer, texture.bounds);
+            let texels = texture.width.max(texture.height) as f32;
+            let level = ((texels / pixels.max(1.0)).log2() + self.mip_bias).floor();
+            texture.required_level = (level.max(0.0) as u32).min(texture.lowest_level);
+            // Textures outside the view keep their required mip level, but are the first to be reduced
+            let visible = frustum.contains(texture.bounds);
+            order.push((*id, visible, pixels * texture.priority));
+        }
+        order.sort_by(|a, b| b.1.cmp(&a.1).then(b.2.total_cmp(&a.2)));
+
+        // Distribute the budget in order of priority
+        let mut remaining = self.budget.saturating_sub(
+            self.textures
+                .values()
+                .map(|texture| texture.bytes(texture.lowest_level))
+                .sum(),
+        );
+        let mut budget_limited_count = 0;
+        for (id, _, _) in order.iter() {
+            let texture = self.textures.get_mut(id).unwrap();
+            let lowest_bytes = texture.bytes(texture.lowest_level);
+            let mut target = texture.required_level;
+            while target < texture.lowest_level && texture.bytes(target) - lowest_bytes > remaining
+            {
+                target += 1;
+            }
+            if target > texture.required_level {
+                budget_limited_count += 1;
+            }
+            remaining -= texture.bytes(target) - lowest_bytes;
+            texture.target_level = target;
+        }
+
+        // Reduce the resolution of the textures that use more than their share of the budget
+        for (id, texture) in self.textures.iter_mut() {
+            if texture
+                .pending_level
+                .is_some_and(|level| level < texture.target_level)
+            {
+                texture.pending_level = None;
+            }
+            if texture.resident_level < texture.target_level {
+                texture.texture =
+                    Texture2DRef::from_cpu_texture(&self.context, &texture.lowest_level_data);
+                texture.resident_level = texture.lowest_level;
+                self.stats.evictions += 1;
+                changed.push(*id);
+            }
+        }
+
+        // Make the loaded mip levels resident if they are still within the budget
+        while let Ok((id, level, cpu_texture)) = self.receiver.try_recv() {
+            if let Some(texture) = self.textures.get_mut(&id) {
+                if texture.pending_level == Some(level) {
+                    texture.pending_level = None;
+                    if level >= texture.target_level && level < texture.resident_level {
+                        texture.texture = Texture2DRef::from_cpu_texture(
+                            &self.context,
+                            &with_mipmap(cpu_texture),
+                        );
+                        texture.resident_level = level;
+                        self.stats.completed_loads += 1;
+                        changed.push(id);
+                    }
+                }
+            }
+        }
+
+        // Start loading the missing mip levels in order of priority
+        let mut pending_loads = self
+            .textures
+            .values()
+            .filter(|texture| texture.pending_level.is_some())
+            .count();
+        for (id, _, _) in order.iter() {
+            if pending_loads >= self.max_pending_loads {
+                break;
+            }
+            let texture = self.textures.get_mut(id).unwrap();
+            if texture.target_level < texture.resident_level && texture.pending_level.is_none() {
+                let level = texture.target_level;
+                texture.pending_level = Some(level);
+                pending_loads += 1;
+                let loader = texture.loader.clone();
+                let sender = self.sender.clone();
+                let id = *id;
+                let load = move || {
+                    // The receiver is only dropped together with the streamer, in which case the result is not needed
+                    let _ = sender.send((id, level, loader(level)));
+                };
+                #[cfg(not(target_arch = "wasm32"))]
+                std::thread::spawn(load);
+                #[cfg(target_arch = "wasm32")]
+                load();
+            }
+        }
+
+        changed.sort();
+        changed.dedup();
+        self.stats = TextureStreamingStats {
+            texture_count: self.textures.len(),
+            resident_bytes: self
+                .textures
+                .values()
+                .map(|texture| texture.bytes(texture.resident_level))
+                .sum(),
+            required_bytes: self
+                .textures
+                .values()
+                .map(|texture| texture.bytes(texture.required_level))
+                .sum(),
+            budget_bytes: self.budget,
+            budget_limited_count,
+            pending_loads,
+            ..self.stats
+        };
+        changed
+    }
+}
+
+///
+/// Returns a conservative estimate of the height in pixels of the given bounds when seen from the given viewer.
+///
+fn projected_size(viewer: &dyn Viewer, bounds: AxisAlignedBoundingBox) -> f32 {
+    if bounds.is_empty() {
+        return 0.0;
+    }
+    let projection = viewer.projection();
+    let size = bounds.size().magnitude() * projection.y.y * 0.5 * viewer.viewport().height as f32;
+    if projection.w.w == 1.0 {
+        // Orthographic projection
+        size
+    } else {
+        size / bounds.distance(viewer.position()).max(viewer.z_near())
+    }
+}
+
+fn with_mipmap(mut cpu_texture: CpuTexture) -> CpuTexture {
+    if cpu_texture.mipmap.is_none() {
+        cpu_texture.mipmap = Some(Mipmap::default());
+    }
+    cpu_texture
+}
+
+fn bytes_per_texel(data: &TextureData) -> u64 {
+    match data {
+        TextureData::RU8(_) => 1,
+        TextureData::RgU8(_) => 2,
+        TextureData::RgbU8(_) => 3,
+        TextureData::RgbaU8(_) => 4,
+        TextureData::RF16(_) => 2,
+        TextureData::RgF16(_) => 4,
+        TextureData::RgbF16(_) => 6,
+        TextureData::RgbaF16(_) => 8,
+        TextureData::RF32(_) => 4,
+        TextureData::RgF32(_) => 8,
+        TextureData::RgbF32(_) => 12,
+        TextureData::RgbaF32(_) => 16,
+    }
+}
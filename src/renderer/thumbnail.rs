@@ -0,0 +1,189 @@
+//!
+//! Rendering of small preview images of objects, see [ThumbnailRenderer].
+//!
+
+use crate::renderer::*;
+
+///
+/// Renders thumbnails, ie. small preview images, of objects, for example for an asset browser.
+/// Each object is framed automatically, so that its bounding box fills the thumbnail seen from [ThumbnailRenderer::camera_direction],
+/// and lit by a three-point light rig which follows the camera.
+///
+/// The render target, the textures and the lights are created once and reused for each thumbnail,
+/// so it is cheap to render many thumbnails with the same renderer, also with a [HeadlessContext] for generating thumbnails from the command line.
+///
+/// ```no_run
+/// # use three_d::*;
+/// # let context: Context = unimplemented!();
+/// # let cpu_models: Vec<CpuModel> = unimplemented!();
+/// let mut renderer = ThumbnailRenderer::new(&context, 128);
+/// for cpu_model in cpu_models.iter() {
+///     let model = Model::<PhysicalMaterial>::new(&context, cpu_model).unwrap();
+///     let thumbnail: CpuTexture = renderer.render(model.iter());
+/// }
+/// ```
+///
+pub struct ThumbnailRenderer {
+    context: Context,
+    size: u32,
+    target: RenderTargetMultisample<[u8; 4], f32>,
+    texture: Texture2D,
+    ambient: AmbientLight,
+    key: DirectionalLight,
+    fill: DirectionalLight,
+    rim: DirectionalLight,
+    /// The direction from the object towards the camera. The default is a three-quarter view from the front right and slightly above.
+    pub camera_direction: Vec3,
+    /// The vertical and horizontal field of view of the camera.
+    pub field_of_view: Degrees,
+    /// The fraction of the thumbnail on each side that is left empty around the object.
+    pub margin: f32,
+    /// The background color. Transparent materials are not blended correctly with a transparent background,
+    /// since the transparent materials does not write to the alpha channel.
+    pub background: Srgba,
+}
+
+impl ThumbnailRenderer {
+    ///
+    /// Creates a new thumbnail renderer which renders thumbnails with the given width and height in pixels.
+    ///
+    pub fn new(context: &Context, size: u32) -> Self {
+        Self {
+            context: context.clone(),
+            size,
+            target: RenderTargetMultisample::new(context, size, size, 4),
+            texture: Texture2D::new_empty::<[u8; 4]>(
+                context,
+                size,
+                size,
+                Interpolation::Nearest,
+                Interpolation::Nearest,
+                None,
+                Wrapping::ClampToEdge,
+                Wrapping::ClampToEdge,
+            ),
+            ambient: AmbientLight::new(context, 0.25, Srgba::WHITE),
+            key: DirectionalLight::new(context, 2.0, Srgba::WHITE, vec3(0.0, -1.0, 0.0)),
+            fill: DirectionalLight::new(context, 0.6, Srgba::WHITE, vec3(0.0, -1.0, 0.0)),
+            rim: DirectionalLight::new(context, 1.2, Srgba::WHITE, vec3(0.0, -1.0, 0.0)),
+            camera_direction: vec3(1.0, 0.6, 1.6).normalize(),
+            field_of_view: degrees(30.0),
+            margin: 0.05,
+            background: Srgba::new_opaque(64, 64, 70),
+        }
+    }
+
+    /// The width and height of the thumbnails in pixels.
+    pub fn size(&self) -> u32 {
+        self.size
+    }
+
+    ///
+    /// Renders a thumbnail of the given objects, for example the parts of a [Model], framed by the union of their bounding boxes.
+    /// The returned texture has RGBA u8 data with the first row at the top, so it can be saved directly as for example a PNG image using [three_d_asset::io::Serialize].
+    ///
+    pub fn render(&mut self, objects: impl IntoIterator<Item = impl Object>) -> CpuTexture {
+        let objects = objects.into_iter().collect::<Vec<_>>();
+        let mut aabb = AxisAlignedBoundingBox::EMPTY;
+        for object in objects.iter() {
+            aabb.expand_with_aabb(object.aabb());
+        }
+        let camera = self.frame(aabb);
+
+        // The lights are placed relative to the camera, the key light from the upper left, the fill light from the right and the rim light from behind
+        let forward = camera.view_direction();
+        let right = camera.right_direction();
+        let up = camera.up_orthogonal();
+        self.key.direction = (forward + 0.8 * right - up).normalize();
+        self.fill.direction = (forward - right - 0.3 * up).normalize();
+        self.rim.direction = (-forward - 0.5 * up).normalize();
+
+        let [r, g, b, a] = self.background.to_linear_srgb().into();
+        self.target
+            .clear(ClearState::color_and_depth(r, g, b, a, 1.0))
+            .render(
+                &camera,
+                &objects,
+                &[&self.ambient, &self.key, &self.fill, &self.rim],
+            );
+        self.target
+            .resolve_color_to(&self.texture.as_color_target(None));
+        CpuTexture {
+            data: TextureData::RgbaU8(self.texture.read()),
+            width: self.size,
+            height: self.size,
+            ..Default::default()
+        }
+    }
+
+    ///
+    /// Renders a thumbnail of the given mesh with a [PhysicalMaterial] created from the given material, see [ThumbnailRenderer::render].
+    /// The mesh and material are transferred to the GPU for each call, so use [ThumbnailRenderer::render] for objects that are already on the GPU.
+    ///
+    pub fn render_cpu_mesh(
+        &mut self,
+        cpu_mesh: &CpuMesh,
+        cpu_material: &CpuMaterial,
+    ) -> CpuTexture {
+        let object = Gm::new(
+            Mesh::new(&self.context, cpu_mesh),
+            PhysicalMaterial::new(&self.context, cpu_material),
+        );
+        self.render(&object)
+    }
+
+    ///
+    /// Returns a camera looking at the center of the bounding box from the camera direction, at the distance where all the corners of the box are inside the view.
+    /// Each corner is tested, so flat and elongated objects fill the thumbnail in the widest direction, no matter how they are oriented.
+    ///
+    fn frame(&self, aabb: AxisAlignedBoundingBox) -> Camera {
+        let viewport = Viewport::new_at_origo(self.size, self.size);
+        let direction = self.camera_direction.normalize();
+        let world_up = if direction.y.abs() > 0.99 {
+            vec3(0.0, 0.0, -1.0)
+        } else {
+            vec3(0.0, 1.0, 0.0)
+        };
+        if aabb.is_empty() || aabb.is_infinite() {
+            return Camera::new_perspective(
+                viewport,
+                direction,
+                Vec3::zero(),
+                world_up,
+                self.field_of_view,
+                0.01,
+                10.0,
+            );
+        }
+        let center = aabb.center();
+        let forward = -direction;
+        let right = forward.cross(world_up).normalize();
+        let up = right.cross(forward);
+
+        // The corners must fit inside the frustum, ie. |x| <= t * (distance + z) and |y| <= t * (distance + z) where z is the depth relative to the center
+        let t =
+            (0.5 * Radians::from(self.field_of_view).0).tan() * (1.0 - 2.0 * self.margin).max(0.1);
+        let (min, max) = (aabb.min(), aabb.max());
+        let mut distance = 0.0f32;
+        for i in 0..8 {
+            let corner = vec3(
+                if i & 1 == 0 { min.x } else { max.x },
+                if i & 2 == 0 { min.y } else { max.y },
+                if i & 4 == 0 { min.z } else { max.z },
+            ) - center;
+            let extent = corner.dot(right).abs().max(corner.dot(up).abs());
+            distance = distance.max(extent / t - corner.dot(forward));
+        }
+        let radius = 0.5 * aabb.size().magnitude();
+        let distance = distance.max(radius * 0.01).max(0.0001);
+        Camera::new_perspective(
+            viewport,
+            center + direction * distance,
+            center,
+            world_up,
+            self.field_of_view,
+            (distance - radius).max(distance * 0.001),
+            distance + radius * 1.01 + 0.0001,
+        )
+    }
+}
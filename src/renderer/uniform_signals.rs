@@ -0,0 +1,289 @@
+//!
+//! Named float signals which are updated each frame by the application and exposed to shaders, see [UniformSignals].
+//!
+
+use crate::renderer::*;
+use std::collections::VecDeque;
+
+///
+/// The maximum number of signals in a [UniformSignals].
+///
+pub const MAX_UNIFORM_SIGNALS: usize = 16;
+
+///
+/// The number of values of each signal stored in the history texture of a [UniformSignals].
+///
+pub const UNIFORM_SIGNAL_HISTORY_LENGTH: usize = 512;
+
+const SIGNAL_BLOCK: &str = "SignalBlock";
+const SIGNAL_HISTORY: &str = "signalHistory";
+
+///
+/// An attack-decay envelope which smooths the changes of a signal.
+/// The signal moves towards a higher value with the attack time constant and towards a lower value with the decay time constant,
+/// so for example a short attack and a long decay makes the signal react immediately to a beat and then slowly fall off.
+///
+/// ```
+/// # use three_d::*;
+/// let envelope = SignalEnvelope {
+///     attack: 0.0,
+///     decay: 100.0,
+/// };
+/// // No attack time, so the signal jumps to a higher value immediately
+/// assert_eq!(envelope.apply(0.0, 1.0, 16.0), 1.0);
+/// // After one decay time constant, the signal has moved about 63% of the way towards the lower value
+/// assert!((envelope.apply(1.0, 0.0, 100.0) - (-1.0f32).exp()).abs() < 1e-6);
+/// ```
+///
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub struct SignalEnvelope {
+    /// The time constant in milliseconds used when the signal increases. A value of zero means that the signal increases immediately.
+    pub attack: f32,
+    /// The time constant in milliseconds used when the signal decreases. A value of zero means that the signal decreases immediately.
+    pub decay: f32,
+}
+
+impl SignalEnvelope {
+    ///
+    /// Returns the value after moving the given current value towards the given target value for the given elapsed time in milliseconds.
+    ///
+    pub fn apply(&self, current: f32, target: f32, elapsed_time: f32) -> f32 {
+        let time_constant = if target > current {
+            self.attack
+        } else {
+            self.decay
+        };
+        if time_constant <= 0.0 {
+            target
+        } else {
+            current + (target - current) * (1.0 - (-elapsed_time.max(0.0) / time_constant).exp())
+        }
+    }
+}
+
+struct Signal {
+    name: String,
+    target: f32,
+    value: f32,
+    envelope: SignalEnvelope,
+    history: VecDeque<f32>,
+}
+
+///
+/// A set of named float signals, for example the bass, mid and treble levels of an audio spectrum computed elsewhere,
+/// which are set by the application each frame and exposed to shaders without any bespoke uniform plumbing.
+///
+/// Each signal is smoothed by a [SignalEnvelope] when calling [UniformSignals::update], which also records the smoothed value
+/// in a history of the last [UNIFORM_SIGNAL_HISTORY_LENGTH] values for shaders that want to draw waveforms.
+/// In a shader, the signals are available after including the source from [UniformSignals::shader_source],
+/// either directly or by registering it as a shader snippet with [UniformSignals::register_snippet] and using `#include <name>`.
+/// The included source defines
+/// - a `float signal_<name>` uniform for each signal with the current smoothed value,
+/// - a `float signal_<name>_history(float age)` function for each signal which returns the value `age` of the way back through the history,
+///   so `0.0` is the current value and `1.0` is the oldest value.
+///
+/// Then call [UniformSignals::use_uniforms] in the `use_uniforms` method of the custom [Material] or [Effect] or before drawing with a [Program].
+/// The values are also available on the CPU using [UniformSignals::value], for example to drive the [DisplacedMesh::scale]
+/// or the parameters of a [ParticleSystem] each frame.
+///
+pub struct UniformSignals {
+    signals: Vec<Signal>,
+    buffer: UniformBuffer,
+    history_texture: Texture2D,
+}
+
+impl UniformSignals {
+    ///
+    /// Creates a new set of signals with the given names, which all start at zero without any smoothing.
+    ///
+    /// # Panic
+    /// Will panic if there are no names or more than [MAX_UNIFORM_SIGNALS] names, if a name is used twice
+    /// or if a name is not a valid glsl identifier, ie. only ascii letters, digits and underscores and not starting with a digit.
+    ///
+    pub fn new(context: &Context, names: &[&str]) -> Self {
+        assert!(
+            !names.is_empty() && names.len() <= MAX_UNIFORM_SIGNALS,
+            "the number of uniform signals must be between 1 and {MAX_UNIFORM_SIGNALS}"
+        );
+        for (i, name) in names.iter().enumerate() {
+            assert!(
+                !name.is_empty()
+                    && !name.starts_with(|c: char| c.is_ascii_digit())
+                    && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'),
+                "the uniform signal name {name} is not a valid glsl identifier"
+            );
+            assert!(
+                !names[..i].contains(name),
+                "the uniform signal name {name} is used twice"
+            );
+        }
+        let signals = names
+            .iter()
+            .map(|name| Signal {
+                name: name.to_string(),
+                target: 0.0,
+                value: 0.0,
+                envelope: SignalEnvelope::default(),
+                history: VecDeque::from(vec![0.0; UNIFORM_SIGNAL_HISTORY_LENGTH]),
+            })
+            .collect::<Vec<_>>();
+        let mut uniform_signals = Self {
+            buffer: UniformBuffer::new(context, &[signals.len().div_ceil(4) as u32 * 4]),
+            history_texture: Texture2D::new_empty::<f16>(
+                context,
+                UNIFORM_SIGNAL_HISTORY_LENGTH as u32,
+                signals.len() as u32,
+                Interpolation::Linear,
+                Interpolation::Linear,
+                None,
+                Wrapping::ClampToEdge,
+                Wrapping::ClampToEdge,
+            ),
+            signals,
+        };
+        uniform_signals.upload();
+        uniform_signals
+    }
+
+    ///
+    /// Sets the raw value of the signal with the given name, for example the level of a frequency band from an FFT done elsewhere.
+    /// The smoothed value moves towards this value when calling [UniformSignals::update].
+    ///
+    /// # Panic
+    /// Will panic if there is no signal with the given name.
+    ///
+    pub fn set(&mut self, name: &str, value: f32) {
+        self.signal_mut(name).target = value;
+    }
+
+    ///
+    /// Sets the envelope used to smooth the signal with the given name.
+    ///
+    /// # Panic
+    /// Will panic if there is no signal with the given name.
+    ///
+    pub fn set_envelope(&mut self, name: &str, envelope: SignalEnvelope) {
+        self.signal_mut(name).envelope = envelope;
+    }
+
+    ///
+    /// Returns the current smoothed value of the signal with the given name.
+    ///
+    /// # Panic
+    /// Will panic if there is no signal with the given name.
+    ///
+    pub fn value(&self, name: &str) -> f32 {
+        self.signal(name).value
+    }
+
+    ///
+    /// Returns the history of smoothed values of the signal with the given name, starting with the current value.
+    ///
+    /// # Panic
+    /// Will panic if there is no signal with the given name.
+    ///
+    pub fn history(&self, name: &str) -> impl Iterator<Item = f32> + '_ {
+        self.signal(name).history.iter().copied()
+    }
+
+    ///
+    /// Returns the names of the signals in the order given at construction.
+    ///
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.signals.iter().map(|signal| signal.name.as_str())
+    }
+
+    ///
+    /// Moves the smoothed value of each signal towards the latest value given to [UniformSignals::set] using the envelope of the signal,
+    /// records the smoothed values in the history and sends the values to the GPU.
+    /// Should be called once each frame with the elapsed time in milliseconds since the last frame, for example [FrameInput::elapsed_time](crate::FrameInput::elapsed_time).
+    ///
+    pub fn update(&mut self, elapsed_time: f64) {
+        for signal in self.signals.iter_mut() {
+            signal.value = signal
+                .envelope
+                .apply(signal.value, signal.target, elapsed_time as f32);
+            signal.history.pop_back();
+            signal.history.push_front(signal.value);
+        }
+        self.upload();
+    }
+
+    ///
+    /// Returns the glsl source which defines the signal uniforms and history functions, see [UniformSignals].
+    ///
+    pub fn shader_source(&self) -> String {
+        let count = self.signals.len();
+        let mut source = format!("layout (std140) uniform {SIGNAL_BLOCK}\n{{\n");
+        for signal in self.signals.iter() {
+            source.push_str(&format!("    float signal_{};\n", signal.name));
+        }
+        source.push_str(&format!(
+            "}};\n\nuniform sampler2D {SIGNAL_HISTORY};\n\nfloat signal_history(int signal, float age)\n{{\n    \
+            float u = (clamp(age, 0.0, 1.0) * {}.0 + 0.5) / {UNIFORM_SIGNAL_HISTORY_LENGTH}.0;\n    \
+            return textureLod({SIGNAL_HISTORY}, vec2(u, (float(signal) + 0.5) / {count}.0), 0.0).r;\n}}\n",
+            UNIFORM_SIGNAL_HISTORY_LENGTH - 1,
+        ));
+        for (i, signal) in self.signals.iter().enumerate() {
+            source.push_str(&format!(
+                "\nfloat signal_{}_history(float age)\n{{\n    return signal_history({i}, age);\n}}\n",
+                signal.name
+            ));
+        }
+        source
+    }
+
+    ///
+    /// Registers the [UniformSignals::shader_source] as a shader snippet with the given name, so the signals can be used in any shader with `#include <name>`,
+    /// see [register_shader_snippet].
+    ///
+    /// # Panic
+    /// Will panic if the name starts with `three-d/`.
+    ///
+    pub fn register_snippet(&self, name: &str) {
+        register_shader_snippet(name, self.shader_source());
+    }
+
+    ///
+    /// Sends the signal uniforms and the history texture to the given program.
+    /// Nothing is sent if the program does not use them, so it is safe to call for any program.
+    ///
+    pub fn use_uniforms(&self, program: &Program) {
+        if program.requires_uniform_block(SIGNAL_BLOCK) {
+            program.use_uniform_block(SIGNAL_BLOCK, &self.buffer);
+        }
+        if program.requires_uniform(SIGNAL_HISTORY) {
+            program.use_texture(SIGNAL_HISTORY, &self.history_texture);
+        }
+    }
+
+    fn signal(&self, name: &str) -> &Signal {
+        self.signals
+            .iter()
+            .find(|signal| signal.name == name)
+            .unwrap_or_else(|| panic!("there is no uniform signal with the name {name}"))
+    }
+
+    fn signal_mut(&mut self, name: &str) -> &mut Signal {
+        self.signals
+            .iter_mut()
+            .find(|signal| signal.name == name)
+            .unwrap_or_else(|| panic!("there is no uniform signal with the name {name}"))
+    }
+
+    fn upload(&mut self) {
+        let mut values = self
+            .signals
+            .iter()
+            .map(|signal| signal.value)
+            .collect::<Vec<_>>();
+        values.resize(values.len().div_ceil(4) * 4, 0.0);
+        self.buffer.update(0, &values);
+        let history = self
+            .signals
+            .iter()
+            .flat_map(|signal| signal.history.iter().map(|value| f16::from_f32(*value)))
+            .collect::<Vec<_>>();
+        self.history_texture.fill(&history);
+    }
+}
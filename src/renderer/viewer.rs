@@ -7,6 +7,9 @@ pub use color_space::*;
 mod camera;
 pub use camera::*;
 
+mod matrix_viewer;
+pub use matrix_viewer::*;
+
 use crate::*;
 
 pub use three_d_asset::Frustum;
@@ -44,6 +47,14 @@ macro_rules! impl_viewer_body {
         fn tone_mapping(&self) -> ToneMapping {
             self.$inner().tone_mapping()
         }
+
+        fn depth_mode(&self) -> DepthMode {
+            self.$inner().depth_mode()
+        }
+
+        fn exposure(&self) -> f32 {
+            self.$inner().exposure()
+        }
     };
 }
 
@@ -75,6 +86,52 @@ pub trait Viewer {
 
     /// Defines the [ToneMapping] applied to the final rendered image.
     fn tone_mapping(&self) -> ToneMapping;
+
+    /// Defines how the depth is stored in the depth texture, see [DepthMode], which must match the [Viewer::projection] matrix.
+    /// When reversed, the depth tests of materials and effects are reversed when rendering with this viewer.
+    fn depth_mode(&self) -> DepthMode {
+        DepthMode::Standard
+    }
+
+    /// The factor which the light reflected by surfaces is multiplied with before tone mapping, see [Camera::set_exposure].
+    /// The default is 1, which means that the light intensities are used without any units.
+    fn exposure(&self) -> f32 {
+        1.0
+    }
+}
+
+///
+/// Returns the pixel coordinate in physical pixels of the given world position projected by the given viewer,
+/// where (viewport.x, viewport.y) is the bottom left corner of the viewport of the viewer, ie. the same coordinate system as used by [pick].
+/// Use [LogicalPoint::from_physical](crate::renderer::control::LogicalPoint::from_physical) to convert the result to logical pixels, for example to place a GUI label.
+///
+pub fn world_to_screen(
+    viewer: &impl Viewer,
+    position: Vec3,
+) -> crate::renderer::control::PhysicalPoint {
+    let viewport = viewer.viewport();
+    let p = viewer.projection() * viewer.view() * position.extend(1.0);
+    crate::renderer::control::PhysicalPoint {
+        x: viewport.x as f32 + 0.5 * (p.x / p.w.abs() + 1.0) * viewport.width as f32,
+        y: viewport.y as f32 + 0.5 * (p.y / p.w.abs() + 1.0) * viewport.height as f32,
+    }
+}
+
+///
+/// Returns the inverse of the view projection matrix of the given viewer, which transforms the position `(uv * 2 - 1, depth * 2 - 1)`
+/// where `depth` is the value stored in a depth texture, to world space (see `world_pos_from_depth` in the shaders) independent of the [DepthMode] of the viewer.
+///
+pub(crate) fn view_projection_inverse(viewer: &dyn Viewer) -> Mat4 {
+    let inverse = (viewer.projection() * viewer.view()).invert().unwrap();
+    if viewer.depth_mode() == DepthMode::ReversedZeroToOne {
+        // The depth texture stores the clip space depth directly, so map the depth back from [-1, 1] to [0, 1]
+        inverse
+            * Mat4::new(
+                1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.5, 0.0, 0.0, 0.0, 0.5, 1.0,
+            )
+    } else {
+        inverse
+    }
 }
 
 use std::ops::Deref;
@@ -86,15 +143,15 @@ impl<T: Viewer + ?Sized> Viewer for &mut T {
     impl_viewer_body!(deref);
 }
 
-impl<T: Viewer> Viewer for Box<T> {
+impl<T: Viewer + ?Sized> Viewer for Box<T> {
     impl_viewer_body!(as_ref);
 }
 
-impl<T: Viewer> Viewer for std::rc::Rc<T> {
+impl<T: Viewer + ?Sized> Viewer for std::rc::Rc<T> {
     impl_viewer_body!(as_ref);
 }
 
-impl<T: Viewer> Viewer for std::sync::Arc<T> {
+impl<T: Viewer + ?Sized> Viewer for std::sync::Arc<T> {
     impl_viewer_body!(as_ref);
 }
 
@@ -134,4 +191,8 @@ impl<T: Viewer> Viewer for std::sync::RwLock<T> {
     fn tone_mapping(&self) -> ToneMapping {
         self.read().unwrap().tone_mapping()
     }
+
+    fn depth_mode(&self) -> DepthMode {
+        self.read().unwrap().depth_mode()
+    }
 }
@@ -10,6 +10,10 @@ pub struct Camera {
     pub tone_mapping: ToneMapping,
     /// This color mapping is applied to the final color of renders using this camera.
     pub color_mapping: ColorMapping,
+    depth_mode: DepthMode,
+    min_z_near: f32,
+    exposure_value: Option<f32>,
+    jitter: Vec2,
 }
 
 impl Viewer for Camera {
@@ -22,7 +26,28 @@ impl Viewer for Camera {
     }
 
     fn projection(&self) -> Mat4 {
-        self.camera.projection()
+        let mut projection = self.camera.projection();
+        if self.depth_mode.is_reversed() {
+            // Replace the depth row of the projection instead of transforming the standard projection to avoid losing precision
+            let (n, f) = (self.camera.z_near(), self.camera.z_far());
+            let perspective = projection.w.w == 0.0;
+            let (z, w) = match (self.depth_mode, perspective) {
+                (DepthMode::ReversedZeroToOne, true) => (n / (f - n), f * n / (f - n)),
+                (DepthMode::ReversedZeroToOne, false) => (1.0 / (f - n), f / (f - n)),
+                _ => (-projection.z.z, -projection.w.z),
+            };
+            projection.z.z = z;
+            projection.w.z = w;
+        }
+        if self.jitter != vec2(0.0, 0.0) {
+            let viewport = self.camera.viewport();
+            projection = Mat4::from_translation(vec3(
+                2.0 * self.jitter.x / viewport.width as f32,
+                2.0 * self.jitter.y / viewport.height as f32,
+                0.0,
+            )) * projection;
+        }
+        projection
     }
 
     fn viewport(&self) -> Viewport {
@@ -44,6 +69,16 @@ impl Viewer for Camera {
     fn tone_mapping(&self) -> ToneMapping {
         self.tone_mapping
     }
+
+    fn depth_mode(&self) -> DepthMode {
+        self.depth_mode
+    }
+
+    fn exposure(&self) -> f32 {
+        self.exposure_value
+            .map(|ev100| 1.0 / (1.2 * 2.0f32.powf(ev100)))
+            .unwrap_or(1.0)
+    }
 }
 
 impl Camera {
@@ -65,6 +100,10 @@ impl Camera {
             ),
             tone_mapping: ToneMapping::default(),
             color_mapping: ColorMapping::default(),
+            depth_mode: DepthMode::Standard,
+            min_z_near: DEFAULT_MIN_Z_NEAR,
+            exposure_value: None,
+            jitter: vec2(0.0, 0.0),
         }
     }
 
@@ -92,6 +131,10 @@ impl Camera {
             ),
             tone_mapping: ToneMapping::default(),
             color_mapping: ColorMapping::default(),
+            depth_mode: DepthMode::Standard,
+            min_z_near: DEFAULT_MIN_Z_NEAR,
+            exposure_value: None,
+            jitter: vec2(0.0, 0.0),
         }
     }
 
@@ -121,6 +164,251 @@ impl Camera {
         )
     }
 
+    ///
+    /// Returns an orthographic camera for viewing 2D content where one unit in world space is one logical pixel,
+    /// ie. the physical pixels of the viewport divided by the given device pixel ratio.
+    /// The (0, 0) position is at the bottom left corner and the
+    /// (`viewport.width / device_pixel_ratio`, `viewport.height / device_pixel_ratio`) position is at the top right corner.
+    /// Use [Camera::set_viewport_2d] to keep the mapping when the viewport changes.
+    ///
+    pub fn new_2d_logical(viewport: Viewport, device_pixel_ratio: f32) -> Self {
+        let width = viewport.width as f32 / device_pixel_ratio;
+        let height = viewport.height as f32 / device_pixel_ratio;
+        Self::new_orthographic(
+            viewport,
+            vec3(width * 0.5, height * 0.5, 1.0),
+            vec3(width * 0.5, height * 0.5, 0.0),
+            vec3(0.0, 1.0, 0.0),
+            height,
+            0.0,
+            10.0,
+        )
+    }
+
+    ///
+    /// Sets the viewport of a 2D camera, for example created by [Camera::new_2d_logical], while keeping the current zoom factor
+    /// so that one unit in world space is still one logical pixel at zoom factor 1.
+    /// The world position at the bottom left corner of the viewport is kept fixed, so the content does not move when the window is resized.
+    /// Returns whether or not the viewport actually changed.
+    ///
+    pub fn set_viewport_2d(&mut self, viewport: Viewport, device_pixel_ratio: f32) -> bool {
+        let height = viewport.height as f32 / device_pixel_ratio;
+        let unchanged = self.viewport() == viewport
+            && matches!(self.projection_type(), three_d_asset::ProjectionType::Orthographic { height: h } if *h == height);
+        if unchanged {
+            return false;
+        }
+        let old_viewport = self.viewport();
+        let bottom_left =
+            self.position_at_pixel(vec2(old_viewport.x as f32, old_viewport.y as f32));
+        self.camera.set_viewport(viewport);
+        let (z_near, z_far) = (self.z_near(), self.z_far());
+        self.set_orthographic_projection(height, z_near, z_far);
+        let new_bottom_left = self.position_at_pixel(vec2(viewport.x as f32, viewport.y as f32));
+        self.translate(bottom_left - new_bottom_left);
+        true
+    }
+
+    ///
+    /// Returns the number of physical pixels per unit in world space at the current zoom.
+    /// For a perspective camera, this is measured in the plane through the camera target orthogonal to the view direction.
+    ///
+    pub fn pixels_per_unit(&self) -> f32 {
+        pixels_per_unit(self)
+    }
+
+    ///
+    /// Converts the given distance in physical pixels to a distance in world space at the current zoom, see [Camera::pixels_per_unit].
+    ///
+    pub fn pixels_to_units(&self, pixels: f32) -> f32 {
+        pixels / pixels_per_unit(self)
+    }
+
+    ///
+    /// Converts the given distance in world space to a distance in physical pixels at the current zoom, see [Camera::pixels_per_unit].
+    ///
+    pub fn units_to_pixels(&self, units: f32) -> f32 {
+        units * pixels_per_unit(self)
+    }
+
+    ///
+    /// Returns the given 2D world position moved to the closest corner between physical pixels.
+    /// Edges of 2D shapes placed at such positions are aligned with the pixels and are therefore rendered crisp.
+    /// Only valid for orthographic cameras.
+    ///
+    pub fn snap_to_pixel(&self, position: Vec2) -> Vec2 {
+        let pixel = self.pixel_at_position(position.extend(0.0));
+        self.position_at_pixel(vec2(pixel.x.round(), pixel.y.round()))
+            .truncate()
+    }
+
+    ///
+    /// Returns the given 2D world position moved to the closest center of a physical pixel.
+    /// Lines with an odd width in pixels placed at such positions are rendered crisp.
+    /// Only valid for orthographic cameras.
+    ///
+    pub fn snap_to_pixel_center(&self, position: Vec2) -> Vec2 {
+        let pixel = self.pixel_at_position(position.extend(0.0));
+        self.position_at_pixel(vec2(
+            (pixel.x - 0.5).round() + 0.5,
+            (pixel.y - 0.5).round() + 0.5,
+        ))
+        .truncate()
+    }
+
+    ///
+    /// Moves the camera less than a pixel so that the world space origin is placed at a corner between physical pixels.
+    /// When the number of pixels per unit is an integer, for example at integer zoom factors using a camera created by [Camera::new_2d_logical]
+    /// on a display with an integer device pixel ratio, all integer world positions are then placed at pixel corners.
+    /// Only valid for orthographic cameras.
+    ///
+    pub fn align_to_pixel_grid(&mut self) {
+        align_to_pixel_grid(self);
+    }
+
+    ///
+    /// Sets how the depth is stored in the depth texture when rendering with this camera, see [DepthMode], which is [DepthMode::Standard] by default.
+    /// The [Viewer::projection] matrix of this camera is adjusted to the given mode and when reversed, the depth tests of materials and effects are reversed.
+    /// Remember to clear the depth to the far plane of the given mode, for example using [ClearState::with_depth_mode],
+    /// and render to a 32 bit float depth texture (for example a [DepthTexture2D] created with `f32` as data type) to get the full precision improvement.
+    ///
+    pub fn set_depth_mode(&mut self, depth_mode: DepthMode) {
+        self.depth_mode = depth_mode;
+    }
+
+    ///
+    /// Sets the exposure of this camera as an exposure value at ISO 100 (EV100), which makes it possible to use physical light units,
+    /// ie. lights created with for example [DirectionalLight::new_physical], [PointLight::new_physical] or [SpotLight::new_physical].
+    /// The reflected light is scaled so that a luminance of `1.2 * 2^ev100` candela per square meter is 1 before tone mapping, see [Viewer::exposure].
+    /// Typical values are 15 for a sunny day, 12 for an overcast day, 7 for a bright interior and 4 for a dim interior.
+    ///
+    /// Note that emissive materials are not affected by the exposure.
+    ///
+    pub fn set_exposure(&mut self, ev100: f32) {
+        self.exposure_value = Some(ev100);
+    }
+
+    ///
+    /// Sets the exposure of this camera from the settings of a physical camera,
+    /// ie. the aperture as an f-number, the shutter time in seconds and the sensitivity as an ISO value, see [Camera::set_exposure].
+    ///
+    /// ```
+    /// # use three_d::*;
+    /// # let viewport = Viewport::new_at_origo(1, 1);
+    /// let mut camera = Camera::new_perspective(viewport, vec3(0.0, 0.0, 1.0), vec3(0.0, 0.0, 0.0), vec3(0.0, 1.0, 0.0), degrees(45.0), 0.1, 10.0);
+    ///
+    /// // The luminance of an 18% gray card lit by 1000 lux
+    /// let luminance = 0.18 * 1000.0 / std::f32::consts::PI;
+    ///
+    /// camera.set_exposure_from_settings(4.0, 1.0 / 60.0, 100.0);
+    /// let output = luminance * camera.exposure();
+    ///
+    /// // Doubling the ISO doubles the output
+    /// camera.set_exposure_from_settings(4.0, 1.0 / 60.0, 200.0);
+    /// assert!((luminance * camera.exposure() - 2.0 * output).abs() < 1e-5 * output);
+    /// ```
+    ///
+    pub fn set_exposure_from_settings(&mut self, aperture: f32, shutter_time: f32, iso: f32) {
+        self.set_exposure((aperture * aperture / shutter_time * 100.0 / iso).log2());
+    }
+
+    ///
+    /// Returns the exposure value at ISO 100 set by [Camera::set_exposure], or `None` if the light intensities are used without any units, which is the default.
+    ///
+    pub fn exposure_value(&self) -> Option<f32> {
+        self.exposure_value
+    }
+
+    ///
+    /// Removes the exposure set by [Camera::set_exposure] so the light intensities are used without any units.
+    ///
+    pub fn clear_exposure(&mut self) {
+        self.exposure_value = None;
+    }
+
+    ///
+    /// Sets the near and far planes as tight as possible around the given scene bounds, as seen from the current camera pose,
+    /// to get the best possible depth precision. Call this each frame the camera or the scene moves.
+    /// The margin is a distance in world space which is added in front of and behind the bounds,
+    /// for example to avoid clipping geometry lying exactly on the bounds.
+    /// For a perspective camera, the near plane is clamped to the minimum near plane distance, see [Camera::set_min_z_near],
+    /// which limits the precision lost when the camera is inside the scene bounds.
+    /// Returns whether or not the near or far plane changed.
+    ///
+    /// The controls can do this automatically, see for example [OrbitControl::fit_depth_range](crate::OrbitControl::fit_depth_range).
+    ///
+    /// ```
+    /// # use three_d::*;
+    /// // A 10 km terrain seen from 8 km beyond its edge
+    /// let scene_aabb = AxisAlignedBoundingBox::new_with_positions(&[vec3(-5000.0, 0.0, -5000.0), vec3(5000.0, 100.0, 5000.0)]);
+    /// let mut camera = Camera::new_perspective(
+    ///     Viewport::new_at_origo(1920, 1080),
+    ///     vec3(0.0, 2000.0, 13000.0),
+    ///     vec3(0.0, 0.0, 0.0),
+    ///     vec3(0.0, 1.0, 0.0),
+    ///     degrees(45.0),
+    ///     0.01,
+    ///     100000.0,
+    /// );
+    ///
+    /// // Two quads 1 meter apart in the middle of the terrain
+    /// let depth = |camera: &Camera, position: Vec3| {
+    ///     let clip = camera.projection() * camera.view() * position.extend(1.0);
+    ///     clip.z / clip.w
+    /// };
+    /// let (a, b) = (vec3(0.0, 50.0, 0.0), vec3(0.0, 50.0, -1.0));
+    /// // With the default near and far plane, the quads get the same depth and fight
+    /// assert_eq!(depth(&camera, a), depth(&camera, b));
+    ///
+    /// assert!(camera.auto_fit_depth_range(scene_aabb, 1.0));
+    /// // The near and far planes are tight around the terrain
+    /// let distance = |p: Vec3| (p - camera.position()).dot(camera.view_direction());
+    /// let closest = distance(vec3(0.0, 100.0, 5000.0));
+    /// let farthest = distance(vec3(5000.0, 0.0, -5000.0));
+    /// assert!((camera.z_near() - (closest - 1.0)).abs() < 1.0);
+    /// assert!((camera.z_far() - (farthest + 1.0)).abs() < 1.0);
+    /// // and the quads can now be distinguished in the depth buffer
+    /// assert!(depth(&camera, b) - depth(&camera, a) > 1e-5);
+    /// ```
+    ///
+    pub fn auto_fit_depth_range(
+        &mut self,
+        scene_aabb: AxisAlignedBoundingBox,
+        margin: f32,
+    ) -> bool {
+        auto_fit_depth_range(&mut self.camera, scene_aabb, margin, self.min_z_near)
+    }
+
+    ///
+    /// Returns the minimum distance to the near plane used by [Camera::auto_fit_depth_range]. The default is 0.01.
+    ///
+    pub fn min_z_near(&self) -> f32 {
+        self.min_z_near
+    }
+
+    ///
+    /// Sets the minimum distance to the near plane used by [Camera::auto_fit_depth_range].
+    ///
+    pub fn set_min_z_near(&mut self, min_z_near: f32) {
+        self.min_z_near = min_z_near;
+    }
+
+    ///
+    /// Offsets the projection of this camera by the given amount in pixels, which moves the rendered image by a fraction of a pixel,
+    /// for example to sample different positions within each pixel in each frame for temporal anti-aliasing, see [TaaEffect].
+    /// The jitter is applied to the [Viewer::projection] matrix, so it applies to all rendering with this camera. Set it to zero to disable the jitter.
+    ///
+    pub fn set_jitter(&mut self, jitter: Vec2) {
+        self.jitter = jitter;
+    }
+
+    ///
+    /// Returns the offset of the projection in pixels set by [Camera::set_jitter].
+    ///
+    pub fn jitter(&self) -> Vec2 {
+        self.jitter
+    }
+
     ///
     /// Disables the tone and color mapping so as to be ready for rendering into an intermediate render target with this camera.
     ///
@@ -138,6 +426,101 @@ impl Camera {
     }
 }
 
+///
+/// Returns the given 2D position moved to the closest point on a grid with the given spacing and the grid lines going through the origin.
+///
+pub fn snap_to_grid(position: Vec2, spacing: f32) -> Vec2 {
+    if spacing > 0.0 {
+        vec2(
+            (position.x / spacing).round() * spacing,
+            (position.y / spacing).round() * spacing,
+        )
+    } else {
+        position
+    }
+}
+
+///
+/// Moves the world origin of the given context to the position of the given camera and moves the camera the opposite way,
+/// so the camera is at the origin of the render space while it stays at the same position in world space, see [Context::set_world_origin].
+/// Call this whenever the camera has moved far away from the world origin, to keep the positions sent to the GPU small.
+/// Returns the offset that the render space has moved, which must be subtracted from any other position given in render space,
+/// for example the target of an [OrbitControl](crate::renderer::OrbitControl), lights and objects with a single precision transformation.
+///
+pub fn recenter_world_origin(context: &Context, camera: &mut three_d_asset::Camera) -> Vec3 {
+    let offset = camera.position();
+    context.set_world_origin(context.world_origin() + offset.cast::<f64>().unwrap());
+    camera.translate(-offset);
+    offset
+}
+
+pub(crate) const DEFAULT_MIN_Z_NEAR: f32 = 0.01;
+
+pub(crate) fn auto_fit_depth_range(
+    camera: &mut three_d_asset::Camera,
+    scene_aabb: AxisAlignedBoundingBox,
+    margin: f32,
+    min_z_near: f32,
+) -> bool {
+    if scene_aabb.is_empty() || scene_aabb.is_infinite() {
+        return false;
+    }
+    let (min, max) = (scene_aabb.min(), scene_aabb.max());
+    let view = camera.view();
+    let mut z_near = f32::MAX;
+    let mut z_far = f32::MIN;
+    for i in 0..8 {
+        let corner = vec3(
+            if i & 1 == 0 { min.x } else { max.x },
+            if i & 2 == 0 { min.y } else { max.y },
+            if i & 4 == 0 { min.z } else { max.z },
+        );
+        let depth = -(view * corner.extend(1.0)).z;
+        z_near = z_near.min(depth);
+        z_far = z_far.max(depth);
+    }
+    z_near -= margin;
+    z_far += margin;
+    let projection_type = camera.projection_type().clone();
+    if let three_d_asset::ProjectionType::Perspective { .. } = projection_type {
+        z_near = z_near.max(min_z_near);
+    }
+    // Avoid an empty depth range, for example if the scene is flat or behind the camera
+    z_far = z_far.max(z_near + z_near.abs().max(min_z_near) * 0.001);
+    if z_near == camera.z_near() && z_far == camera.z_far() {
+        return false;
+    }
+    match projection_type {
+        three_d_asset::ProjectionType::Perspective { field_of_view_y } => {
+            camera.set_perspective_projection(field_of_view_y, z_near, z_far)
+        }
+        three_d_asset::ProjectionType::Orthographic { height } => {
+            camera.set_orthographic_projection(height, z_near, z_far)
+        }
+    }
+    true
+}
+
+pub(crate) fn pixels_per_unit(camera: &three_d_asset::Camera) -> f32 {
+    let distance = camera.position().distance(camera.target());
+    let visible_height = match camera.projection_type() {
+        three_d_asset::ProjectionType::Orthographic { height } => height * distance,
+        three_d_asset::ProjectionType::Perspective { field_of_view_y } => {
+            2.0 * distance * (*field_of_view_y * 0.5).tan()
+        }
+    };
+    camera.viewport().height as f32 / visible_height.max(f32::EPSILON)
+}
+
+pub(crate) fn align_to_pixel_grid(camera: &mut three_d_asset::Camera) {
+    let pixel = camera.pixel_at_position(Vec3::zero());
+    let offset =
+        vec2(pixel.x.round() - pixel.x, pixel.y.round() - pixel.y) / pixels_per_unit(camera);
+    let right = camera.right_direction();
+    let up = right.cross(camera.view_direction());
+    camera.translate(-right * offset.x - up * offset.y);
+}
+
 impl std::ops::Deref for Camera {
     type Target = three_d_asset::Camera;
     fn deref(&self) -> &Self::Target {
@@ -10,31 +10,30 @@ pub enum ColorMapping {
     /// Maps from compute color space (HDR or linear sRGB) to sRGB color space. Use this if this is the final render pass, ie. you write to the screen or want to save it as an image.
     #[default]
     ComputeToSrgb = 1,
+    /// Maps from compute color space (HDR or linear sRGB) to Display P3 color space. Use this instead of [ColorMapping::ComputeToSrgb] in the final render pass
+    /// when the surface is in Display P3, otherwise the colors are shown oversaturated, see [ColorMapping::for_color_space].
+    /// The colors are the same, but colors outside the sRGB gamut, which are represented by values outside the range `[0, 1]` in the compute color space, can be shown.
+    ComputeToDisplayP3 = 2,
 }
 
 impl ColorMapping {
+    ///
+    /// Returns the color mapping used in the final render pass into a surface with the given color space, see [ColorSpace].
+    /// Rec. 2020 output is not supported, so Display P3 is used instead, which is the closest supported color space.
+    ///
+    pub fn for_color_space(color_space: ColorSpace) -> Self {
+        match color_space {
+            ColorSpace::Srgb => Self::ComputeToSrgb,
+            ColorSpace::DisplayP3 | ColorSpace::Rec2020 => Self::ComputeToDisplayP3,
+        }
+    }
+
     ///
     /// Returns the fragment shader source for mapping to the specified color space in a shader.
+    /// The same source is registered as the shader snippet [COLOR_MAPPING_SNIPPET], so it can also be added to a shader with `#include <three-d/color_mapping>`.
     ///
     pub fn fragment_shader_source() -> &'static str {
-        "
-        uniform uint ColorMappingType;
-
-        vec3 color_mapping(vec3 color) {
-            if (ColorMappingType == 1u) {
-                vec3 a = vec3(0.055, 0.055, 0.055);
-                vec3 ap1 = vec3(1.0, 1.0, 1.0) + a;
-                vec3 g = vec3(2.4, 2.4, 2.4);
-                vec3 ginv = 1.0 / g;
-                vec3 select = step(vec3(0.0031308, 0.0031308, 0.0031308), color);
-                vec3 lo = color * 12.92;
-                vec3 hi = ap1 * pow(color, ginv) - a;
-                color = mix(lo, hi, select);
-            } 
-
-            return color;
-        }
-        "
+        include_str!("shaders/color_mapping.frag")
     }
 
     ///
@@ -0,0 +1,231 @@
+use crate::*;
+
+///
+/// A viewer defined directly by a view and a projection matrix, for example received from an external tracking system.
+/// It can be used instead of a [Camera] in all render calls, including effects that reconstruct positions from the depth, and in [pick](crate::renderer::pick).
+///
+/// The position, the near and far plane distances and the frustum are derived from the matrices.
+/// The projection matrix must be an OpenGL perspective or orthographic projection matrix which maps the view space to the clip space depth range of the [DepthMode] of the viewer,
+/// ie. `[-1, 1]` for the default [DepthMode::Standard], see [MatrixViewer::set_depth_mode].
+///
+/// ```
+/// # use three_d::*;
+/// let viewport = Viewport::new_at_origo(800, 600);
+/// let (position, target, up) = (vec3(1.0, 2.0, 5.0), vec3(0.0, 0.5, 0.0), vec3(0.0, 1.0, 0.0));
+/// for camera in [
+///     Camera::new_perspective(viewport, position, target, up, degrees(45.0), 0.1, 100.0),
+///     Camera::new_orthographic(viewport, position, target, up, 4.0, 0.1, 100.0),
+/// ] {
+///     let viewer = MatrixViewer::new(camera.view(), camera.projection(), camera.viewport());
+///     assert!((viewer.position() - camera.position()).magnitude() < 1e-4);
+///     assert!((viewer.z_near() - camera.z_near()).abs() < 1e-4);
+///     assert!((viewer.z_far() - camera.z_far()).abs() < 1e-1);
+///
+///     // The viewer returns positions on the near plane, so check that they are on the pixel rays of the camera
+///     for pixel in [(400.0, 300.0), (10.0, 20.0), (790.0, 590.0)] {
+///         let direction = camera.view_direction_at_pixel(pixel);
+///         assert!((viewer.view_direction_at_pixel(pixel) - direction).magnitude() < 1e-4);
+///         let offset = viewer.position_at_pixel(pixel) - camera.position_at_pixel(pixel);
+///         assert!(offset.cross(direction).magnitude() < 1e-4);
+///     }
+///
+///     let inside = AxisAlignedBoundingBox::new_with_positions(&[target, target + vec3(0.1, 0.1, 0.1)]);
+///     let behind = AxisAlignedBoundingBox::new_with_positions(&[position + 2.0 * (position - target), position + 3.0 * (position - target)]);
+///     assert!(viewer.frustum().contains(inside) && camera.frustum().contains(inside));
+///     assert!(!viewer.frustum().contains(behind) && !camera.frustum().contains(behind));
+/// }
+/// ```
+///
+#[derive(Clone, Debug)]
+pub struct MatrixViewer {
+    view: Mat4,
+    projection: Mat4,
+    viewport: Viewport,
+    position: Vec3,
+    z_near: f32,
+    z_far: f32,
+    depth_mode: DepthMode,
+    /// This tone mapping is applied to the final color of renders using this viewer.
+    pub tone_mapping: ToneMapping,
+    /// This color mapping is applied to the final color of renders using this viewer.
+    pub color_mapping: ColorMapping,
+    /// The factor which the reflected light is multiplied with before tone mapping, see [Viewer::exposure].
+    pub exposure: f32,
+}
+
+impl MatrixViewer {
+    ///
+    /// Creates a new viewer from the given view matrix, which transforms from world space to view space,
+    /// and projection matrix, which transforms from view space to clip space.
+    ///
+    pub fn new(view: Mat4, projection: Mat4, viewport: Viewport) -> Self {
+        let mut viewer = Self {
+            view,
+            projection,
+            viewport,
+            position: Vec3::zero(),
+            z_near: 0.0,
+            z_far: 0.0,
+            depth_mode: DepthMode::Standard,
+            tone_mapping: ToneMapping::default(),
+            color_mapping: ColorMapping::default(),
+            exposure: 1.0,
+        };
+        viewer.set_view(view);
+        viewer.set_projection(projection);
+        viewer
+    }
+
+    ///
+    /// Creates a new viewer with the same view, projection, viewport, depth mode, tone mapping, color mapping and exposure as the given viewer.
+    ///
+    pub fn from_viewer(viewer: &impl Viewer) -> Self {
+        let mut matrix_viewer = Self::new(viewer.view(), viewer.projection(), viewer.viewport());
+        matrix_viewer.set_depth_mode(viewer.depth_mode());
+        matrix_viewer.tone_mapping = viewer.tone_mapping();
+        matrix_viewer.color_mapping = viewer.color_mapping();
+        matrix_viewer.exposure = viewer.exposure();
+        matrix_viewer
+    }
+
+    ///
+    /// Sets the view matrix which transforms from world space to view space.
+    ///
+    pub fn set_view(&mut self, view: Mat4) {
+        self.view = view;
+        self.position = view
+            .invert()
+            .map(|inverse| inverse.w.truncate())
+            .unwrap_or(Vec3::zero());
+    }
+
+    ///
+    /// Sets the projection matrix which transforms from view space to clip space.
+    ///
+    pub fn set_projection(&mut self, projection: Mat4) {
+        self.projection = projection;
+        let (a, b) = (projection.z.z, projection.w.z);
+        let perspective = projection.w.w == 0.0;
+        // The distance to the plane which is projected to the given clip space depth
+        let distance = |depth: f32| {
+            if perspective {
+                b / (a + depth)
+            } else {
+                (b - depth) / a
+            }
+        };
+        let (near, far) = self.depth_mode.clip_space_range();
+        (self.z_near, self.z_far) = (distance(near), distance(far));
+    }
+
+    ///
+    /// Sets how the depth is stored in the depth texture when rendering with this viewer, see [DepthMode], which is [DepthMode::Standard] by default.
+    /// The projection matrix must map to the clip space depth range of the given mode, see [DepthMode::clip_space_range],
+    /// and when reversed, the depth tests of materials and effects are reversed.
+    ///
+    /// ```
+    /// # use three_d::*;
+    /// # let viewport = Viewport::new_at_origo(1, 1);
+    /// let mut camera = Camera::new_perspective(viewport, vec3(0.0, 0.0, 5.0), vec3(0.0, 0.0, 0.0), vec3(0.0, 1.0, 0.0), degrees(45.0), 0.1, 100.0);
+    /// camera.set_depth_mode(DepthMode::ReversedZeroToOne);
+    ///
+    /// let viewer = MatrixViewer::from_viewer(&camera);
+    /// assert_eq!(viewer.depth_mode(), DepthMode::ReversedZeroToOne);
+    /// assert!((viewer.z_near() - 0.1).abs() < 1e-5);
+    /// assert!((viewer.z_far() - 100.0).abs() < 1e-2);
+    /// ```
+    ///
+    pub fn set_depth_mode(&mut self, depth_mode: DepthMode) {
+        self.depth_mode = depth_mode;
+        self.set_projection(self.projection);
+    }
+
+    ///
+    /// Sets the viewport.
+    ///
+    pub fn set_viewport(&mut self, viewport: Viewport) {
+        self.viewport = viewport;
+    }
+
+    ///
+    /// Returns the frustum of this viewer which can be used for culling.
+    ///
+    pub fn frustum(&self) -> Frustum {
+        Frustum::new(self.projection * self.view)
+    }
+
+    ///
+    /// Returns the 3D position on the near plane at the given pixel coordinate in physical pixels.
+    ///
+    pub fn position_at_pixel(&self, pixel: impl Into<PhysicalPoint>) -> Vec3 {
+        unproject_pixel(self, pixel.into()).0
+    }
+
+    ///
+    /// Returns the normalized view direction at the given pixel coordinate in physical pixels.
+    ///
+    pub fn view_direction_at_pixel(&self, pixel: impl Into<PhysicalPoint>) -> Vec3 {
+        let (near, far) = unproject_pixel(self, pixel.into());
+        (far - near).normalize()
+    }
+}
+
+impl Viewer for MatrixViewer {
+    fn position(&self) -> Vec3 {
+        self.position
+    }
+
+    fn view(&self) -> Mat4 {
+        self.view
+    }
+
+    fn projection(&self) -> Mat4 {
+        self.projection
+    }
+
+    fn viewport(&self) -> Viewport {
+        self.viewport
+    }
+
+    fn z_near(&self) -> f32 {
+        self.z_near
+    }
+
+    fn z_far(&self) -> f32 {
+        self.z_far
+    }
+
+    fn color_mapping(&self) -> ColorMapping {
+        self.color_mapping
+    }
+
+    fn tone_mapping(&self) -> ToneMapping {
+        self.tone_mapping
+    }
+
+    fn depth_mode(&self) -> DepthMode {
+        self.depth_mode
+    }
+
+    fn exposure(&self) -> f32 {
+        self.exposure
+    }
+}
+
+///
+/// Returns the world space positions on the near and far plane of the given viewer at the given pixel coordinate in physical pixels.
+///
+pub(crate) fn unproject_pixel(viewer: &impl Viewer, pixel: PhysicalPoint) -> (Vec3, Vec3) {
+    let viewport = viewer.viewport();
+    let x = 2.0 * (pixel.x - viewport.x as f32) / viewport.width as f32 - 1.0;
+    let y = 2.0 * (pixel.y - viewport.y as f32) / viewport.height as f32 - 1.0;
+    let inverse = (viewer.projection() * viewer.view())
+        .invert()
+        .unwrap_or(Mat4::identity());
+    let unproject = |z: f32| {
+        let p = inverse * vec4(x, y, z, 1.0);
+        p.truncate() / p.w
+    };
+    let (near, far) = viewer.depth_mode().clip_space_range();
+    (unproject(near), unproject(far))
+}
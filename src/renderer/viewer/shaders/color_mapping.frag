@@ -0,0 +1,17 @@
+#include <three-d/srgb>
+
+uniform uint ColorMappingType;
+
+vec3 color_mapping(vec3 color) {
+    if (ColorMappingType == 1u) {
+        color = srgb_from_linear_srgb(color);
+    } else if (ColorMappingType == 2u) {
+        // Display P3 has other primaries than sRGB, but the same transfer function
+        const mat3 linear_display_p3_from_linear_srgb = mat3(
+            0.8224621, 0.0331941, 0.0170827,
+            0.1775380, 0.9668058, 0.0723974,
+            0.0, 0.0, 0.9105199);
+        color = srgb_from_linear_srgb(max(linear_display_p3_from_linear_srgb * color, vec3(0.0)));
+    }
+    return color;
+}
@@ -0,0 +1,59 @@
+#![macro_use]
+//!
+//! Tracing spans around the internal operations of three-d, which are only available with the `tracing` feature enabled.
+//!
+//! With the feature enabled, three-d emits [tracing](https://docs.rs/tracing) spans at the `INFO` level, so the internal operations show up in the
+//! spans of the application, for example in a flamegraph or in [Tracy](https://github.com/wolfpld/tracy) using [tracing-tracy](https://docs.rs/tracing-tracy).
+//! Without the feature, the spans are not compiled and there is no overhead.
+//!
+//! The span names and fields below are stable, so they can be relied on, for example to aggregate the time spent in each operation.
+//!
+//! | Name | Fields | Description |
+//! |------|--------|-------------|
+//! | `three_d::compile_program` | | Compiling the shaders of a [Program](crate::core::Program), see [Program::start_compilation](crate::core::Program::start_compilation). |
+//! | `three_d::link_program` | | Waiting for the compilation and linking of a [Program](crate::core::Program) to finish, see [ProgramCompilation::finish](crate::core::ProgramCompilation::finish). |
+//! | `three_d::upload_buffer` | `bytes` | Uploading data to a vertex, instance, element or uniform buffer. |
+//! | `three_d::upload_texture` | `width`, `height`, `depth`, `bytes` | Uploading data to a texture, where `depth` is the number of layers or 1 for a 2D texture. |
+//! | `three_d::generate_shadow_map` | `light`, `size` | Rendering the shadow map of a light, where `light` is `"directional"`, `"spot"`, `"point"` or `"atlas"` and `size` is the width and height of the shadow map. |
+//! | `three_d::render` | | Rendering objects into a render target, see [RenderTarget::render](crate::core::RenderTarget::render). |
+//! | `three_d::geometry_pass` | `objects` | Rendering the objects with a deferred material into the geometry buffer. |
+//! | `three_d::forward_pass` | `objects` | Rendering the objects with a forward material. |
+//! | `three_d::render_transparent_oit` | | Rendering transparent objects with order-independent transparency, see [RenderTarget::render_transparent_oit](crate::core::RenderTarget::render_transparent_oit). |
+//! | `three_d::render_transparent_depth_peeled` | `layers` | Rendering transparent objects with depth peeling, where `layers` is the maximum number of layers, see [RenderTarget::render_transparent_depth_peeled](crate::core::RenderTarget::render_transparent_depth_peeled). |
+//! | `three_d::render_with_material` | `id` | Rendering geometries with a [Material](crate::renderer::Material), where `id` is the [EffectMaterialId](crate::renderer::EffectMaterialId) of the material. |
+//! | `three_d::render_with_effect` | `id` | Rendering geometries with an [Effect](crate::renderer::Effect), where `id` is the [EffectMaterialId](crate::renderer::EffectMaterialId) of the effect. |
+//! | `three_d::apply_screen_material` | `id` | Applying a [Material](crate::renderer::Material) to the entire screen, for example a pass of a multipass effect. |
+//! | `three_d::apply_screen_effect` | `id` | Applying an [Effect](crate::renderer::Effect) to the entire screen, for example the lighting pass of deferred rendering or a post-processing effect. |
+//! | `three_d::deserialize` | `asset` | Deserializing an asset, where `asset` is `"event_recording"`, `"annotations"`, `"point_cloud"` for the index of a point cloud octree or `"point_cloud_chunk"` for a chunk of points. |
+//!
+//! Diagnostics are emitted as [tracing](https://docs.rs/tracing) events with the names and fields below, which are also stable.
+//!
+//! | Name | Level | Fields | Description |
+//! |------|-------|--------|-------------|
+//! | `three_d::missing_uniform` | `WARN` | `uniform` | A uniform is sent to a [Program](crate::core::Program) but not defined or never used in the shader code, which is only emitted the first time for each uniform, see [Program::use_uniform](crate::core::Program::use_uniform). |
+//! | `three_d::lazy_program_compilation` | `INFO` | `geometry`, `material`, `lights` | A shader program is compiled at render time, where the fields are the ids of the geometry, material and lights of the program, see [Context::lazy_program_compilations](crate::core::Context::lazy_program_compilations) and [compile_all](crate::renderer::compile_all). |
+//!
+
+///
+/// Enters a span with the given name and fields until the end of the current scope if the `tracing` feature is enabled, otherwise it does nothing.
+///
+macro_rules! trace_span {
+    ($name:literal $(, $field:ident = $value:expr)* $(,)?) => {
+        #[cfg(feature = "tracing")]
+        let _span = ::tracing::info_span!($name $(, $field = $value)*).entered();
+    };
+}
+
+///
+/// Emits an event with the given level, name and fields if the `tracing` feature is enabled, otherwise it does nothing.
+///
+macro_rules! trace_event {
+    ($level:ident, $name:literal $(, $field:ident = $value:expr)* $(,)?) => {
+        #[cfg(feature = "tracing")]
+        ::tracing::event!(name: $name, ::tracing::Level::$level $(, $field = $value)*);
+        #[cfg(not(feature = "tracing"))]
+        {
+            $(let _ = &$value;)*
+        }
+    };
+}
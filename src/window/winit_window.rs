@@ -1,5 +1,6 @@
 #![allow(unsafe_code)]
-use crate::core::{Context, CoreError, Viewport};
+use crate::control::{EventRecording, RecordedFrame};
+use crate::core::{ColorSpace, Context, CoreError, Viewport};
 use winit::event::{Event, WindowEvent};
 use winit::event_loop::{ControlFlow, EventLoop};
 use winit::window::WindowBuilder;
@@ -33,6 +34,10 @@ pub enum WindowError {
     ThreeDError(#[from] CoreError),
     #[error("the number of MSAA samples must be a power of two")]
     InvalidNumberOfMSAASamples,
+    #[error("the number of depth bits must be at most 32")]
+    InvalidNumberOfDepthBits,
+    #[error("the number of stencil bits must be at most 8")]
+    InvalidNumberOfStencilBits,
     #[error("it's not possible to create a graphics context/surface with the given settings")]
     SurfaceCreationError,
 }
@@ -77,6 +82,17 @@ pub struct Window {
     gl: WindowedContext,
     #[allow(dead_code)]
     maximized: bool,
+    input: InputMode,
+}
+
+enum InputMode {
+    Live,
+    #[cfg(not(target_arch = "wasm32"))]
+    Record {
+        path: std::path::PathBuf,
+        recording: EventRecording,
+    },
+    Playback(std::vec::IntoIter<RecordedFrame>),
 }
 
 impl Window {
@@ -213,17 +229,47 @@ impl Window {
             #[cfg(target_arch = "wasm32")]
             closure,
             maximized,
+            input: InputMode::Live,
         })
     }
 
+    ///
+    /// Records the input of each frame in the render loop and saves it as an [EventRecording] to the file at the given path when the window is closed
+    /// or the render loop is stopped using [FrameOutput::exit].
+    /// The recording can be replayed using [Window::play_input], for example to reproduce a bug.
+    ///
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn record_input(&mut self, path: impl Into<std::path::PathBuf>) {
+        self.input = InputMode::Record {
+            path: path.into(),
+            recording: EventRecording::new(),
+        };
+    }
+
+    ///
+    /// Replays the given recording in the render loop, so the input of each frame, including the events, the elapsed time and the viewport,
+    /// is taken from the recording instead of the window. The events from the window are ignored, except that the window can still be resized and closed.
+    /// The render loop is stopped after the last recorded frame.
+    ///
+    pub fn play_input(&mut self, recording: EventRecording) {
+        self.input = InputMode::Playback(recording.frames().to_vec().into_iter());
+    }
+
     ///
     /// Start the main render loop which calls the `callback` closure each frame.
     ///
-    pub fn render_loop<F: 'static + FnMut(FrameInput) -> FrameOutput>(self, mut callback: F) {
+    pub fn render_loop<F: 'static + FnMut(FrameInput) -> FrameOutput>(mut self, mut callback: F) {
         let mut frame_input_generator = FrameInputGenerator::from_winit_window(&self.window);
+        let mut input = std::mem::replace(&mut self.input, InputMode::Live);
         self.event_loop
             .run(move |event, _, control_flow| match event {
                 Event::LoopDestroyed => {
+                    #[cfg(not(target_arch = "wasm32"))]
+                    if let InputMode::Record { path, recording } = &input {
+                        recording
+                            .save(path)
+                            .expect("failed to save the event recording");
+                    }
                     #[cfg(target_arch = "wasm32")]
                     {
                         use wasm_bindgen::JsCast;
@@ -258,8 +304,22 @@ impl Window {
                         });
                     }
 
-                    let frame_input = frame_input_generator.generate(&self.gl);
+                    let frame_input = match &mut input {
+                        InputMode::Playback(frames) => match frames.next() {
+                            Some(frame) => FrameInput::from_recorded_frame(frame, &self.gl),
+                            None => {
+                                *control_flow = ControlFlow::Exit;
+                                return;
+                            }
+                        },
+                        _ => frame_input_generator.generate(&self.gl),
+                    };
+                    #[cfg(not(target_arch = "wasm32"))]
+                    if let InputMode::Record { recording, .. } = &mut input {
+                        recording.push(frame_input.to_recorded_frame());
+                    }
                     let frame_output = callback(frame_input);
+                    self.gl.trim_texture_pool();
                     if frame_output.exit {
                         *control_flow = ControlFlow::Exit;
                     } else {
@@ -276,7 +336,9 @@ impl Window {
                     }
                 }
                 Event::WindowEvent { ref event, .. } => {
-                    frame_input_generator.handle_winit_window_event(event);
+                    if !matches!(input, InputMode::Playback(_)) {
+                        frame_input_generator.handle_winit_window_event(event);
+                    }
                     match event {
                         WindowEvent::Resized(physical_size) => {
                             self.gl.resize(*physical_size);
@@ -323,4 +385,34 @@ impl Window {
     pub fn gl(&self) -> Context {
         (*self.gl).clone()
     }
+
+    ///
+    /// Returns the surface settings that were actually obtained for this window, for example the number of depth bits and MSAA samples,
+    /// which might differ from the requested settings if those were not supported.
+    ///
+    pub fn surface_settings(&self) -> SurfaceSettings {
+        self.gl.surface_settings()
+    }
+
+    ///
+    /// Returns the widest color space supported by the display that the window is shown on, which can be requested using [SurfaceSettings::color_space].
+    /// This is only detected on web, using the `color-gamut` media query, on native this always returns [ColorSpace::Srgb].
+    ///
+    pub fn display_color_space(&self) -> ColorSpace {
+        #[cfg(target_arch = "wasm32")]
+        {
+            let matches = |query: &str| {
+                web_sys::window()
+                    .and_then(|w| w.match_media(query).ok().flatten())
+                    .is_some_and(|m| m.matches())
+            };
+            if matches("(color-gamut: rec2020)") {
+                return ColorSpace::Rec2020;
+            }
+            if matches("(color-gamut: p3)") {
+                return ColorSpace::DisplayP3;
+            }
+        }
+        ColorSpace::Srgb
+    }
 }
@@ -55,6 +55,10 @@ impl FrameInputGenerator {
         }
     }
 
+    fn to_physical(&self, point: LogicalPoint) -> PhysicalPoint {
+        point.to_physical(self.device_pixel_ratio as f32, self.viewport.height)
+    }
+
     ///
     /// Creates a new frame input generator from a [winit](https://crates.io/crates/winit) window.
     ///
@@ -194,7 +198,7 @@ impl FrameInputGenerator {
                             let line_height = 24.0; // TODO
                             self.events.push(crate::Event::MouseWheel {
                                 delta: (*x * line_height, *y * line_height),
-                                position: position.into(),
+                                position: self.to_physical(position),
                                 modifiers: self.modifiers,
                                 handled: false,
                             });
@@ -203,7 +207,7 @@ impl FrameInputGenerator {
                             let d = delta.to_logical(self.device_pixel_ratio);
                             self.events.push(crate::Event::MouseWheel {
                                 delta: (d.x, d.y),
-                                position: position.into(),
+                                position: self.to_physical(position),
                                 modifiers: self.modifiers,
                                 handled: false,
                             });
@@ -217,7 +221,7 @@ impl FrameInputGenerator {
                     let d = *delta as f32;
                     self.events.push(crate::Event::PinchGesture {
                         delta: d,
-                        position: position.into(),
+                        position: self.to_physical(position),
                         modifiers: self.modifiers,
                         handled: false,
                     });
@@ -229,7 +233,7 @@ impl FrameInputGenerator {
                     let d = radians(*delta);
                     self.events.push(crate::Event::RotationGesture {
                         delta: d,
-                        position: position.into(),
+                        position: self.to_physical(position),
                         modifiers: self.modifiers,
                         handled: false,
                     });
@@ -249,7 +253,7 @@ impl FrameInputGenerator {
                                 self.mouse_pressed = Some(b);
                                 crate::Event::MousePress {
                                     button: b,
-                                    position: position.into(),
+                                    position: self.to_physical(position),
                                     modifiers: self.modifiers,
                                     handled: false,
                                 }
@@ -257,7 +261,7 @@ impl FrameInputGenerator {
                                 self.mouse_pressed = None;
                                 crate::Event::MouseRelease {
                                     button: b,
-                                    position: position.into(),
+                                    position: self.to_physical(position),
                                     modifiers: self.modifiers,
                                     handled: false,
                                 }
@@ -272,16 +276,11 @@ impl FrameInputGenerator {
                 } else {
                     (0.0, 0.0)
                 };
-                let position = LogicalPoint {
-                    x: p.x,
-                    y: p.y,
-                    device_pixel_ratio: self.device_pixel_ratio as f32,
-                    height: self.viewport.height as f32,
-                };
+                let position = LogicalPoint { x: p.x, y: p.y };
                 self.events.push(crate::Event::MouseMotion {
                     button: self.mouse_pressed,
                     delta,
-                    position: position.into(),
+                    position: self.to_physical(position),
                     modifiers: self.modifiers,
                     handled: false,
                 });
@@ -304,15 +303,13 @@ impl FrameInputGenerator {
                 let position = LogicalPoint {
                     x: position.x,
                     y: position.y,
-                    device_pixel_ratio: self.device_pixel_ratio as f32,
-                    height: self.viewport.height as f32,
                 };
                 match touch.phase {
                     TouchPhase::Started => {
                         if self.finger_id.is_none() {
                             self.events.push(crate::Event::MousePress {
                                 button: MouseButton::Left,
-                                position: position.into(),
+                                position: self.to_physical(position),
                                 modifiers: self.modifiers,
                                 handled: false,
                             });
@@ -327,7 +324,7 @@ impl FrameInputGenerator {
                         if self.finger_id.map(|id| id == touch.id).unwrap_or(false) {
                             self.events.push(crate::Event::MouseRelease {
                                 button: MouseButton::Left,
-                                position: position.into(),
+                                position: self.to_physical(position),
                                 modifiers: self.modifiers,
                                 handled: false,
                             });
@@ -347,7 +344,7 @@ impl FrameInputGenerator {
                             let last_pos = self.cursor_pos.unwrap();
                             if let Some(p) = self.secondary_cursor_pos {
                                 self.events.push(crate::Event::MouseWheel {
-                                    position: position.into(),
+                                    position: self.to_physical(position),
                                     modifiers: self.modifiers,
                                     handled: false,
                                     delta: (
@@ -358,7 +355,7 @@ impl FrameInputGenerator {
                             } else {
                                 self.events.push(crate::Event::MouseMotion {
                                     button: Some(MouseButton::Left),
-                                    position: position.into(),
+                                    position: self.to_physical(position),
                                     modifiers: self.modifiers,
                                     handled: false,
                                     delta: (position.x - last_pos.x, position.y - last_pos.y),
@@ -373,7 +370,7 @@ impl FrameInputGenerator {
                             let last_pos = self.secondary_cursor_pos.unwrap();
                             if let Some(p) = self.cursor_pos {
                                 self.events.push(crate::Event::MouseWheel {
-                                    position: p.into(),
+                                    position: self.to_physical(p),
                                     modifiers: self.modifiers,
                                     handled: false,
                                     delta: (
@@ -465,26 +462,3 @@ fn translate_virtual_key_code(key: winit::event::VirtualKeyCode) -> Option<crate
         }
     })
 }
-
-///
-/// A pixel coordinate in logical pixels, where `x` is on the horizontal axis with zero being at the left edge
-/// and `y` is on the vertical axis with zero being at top edge.
-///
-#[derive(Debug, Copy, Clone, PartialEq)]
-struct LogicalPoint {
-    /// The horizontal pixel distance from the left edge.
-    x: f32,
-    /// The vertical pixel distance from the top edge.
-    y: f32,
-    device_pixel_ratio: f32,
-    height: f32,
-}
-
-impl From<LogicalPoint> for PhysicalPoint {
-    fn from(value: LogicalPoint) -> Self {
-        Self {
-            x: value.x * value.device_pixel_ratio,
-            y: value.height - value.y * value.device_pixel_ratio,
-        }
-    }
-}
@@ -1,5 +1,8 @@
-use crate::control::Event;
-use crate::core::{Context, RenderTarget, Viewport};
+use crate::control::{Event, LogicalPoint, PhysicalPoint, RecordedFrame};
+use crate::core::{Context, Cull, RenderTarget, Viewport};
+use crate::renderer::{
+    pick, world_to_screen, Geometry, IntersectionResult, RendererError, Vec3, Viewer,
+};
 
 ///
 /// Input for rendering (and whatever else needs it) each frame.
@@ -52,6 +55,103 @@ impl FrameInput {
     pub fn screen(&self) -> RenderTarget {
         RenderTarget::screen(&self.context, self.viewport.width, self.viewport.height)
     }
+
+    ///
+    /// Returns the input of this frame without the graphics context, for example to add it to an [EventRecording](crate::renderer::control::EventRecording).
+    ///
+    pub fn to_recorded_frame(&self) -> RecordedFrame {
+        RecordedFrame {
+            events: self.events.clone(),
+            elapsed_time: self.elapsed_time,
+            accumulated_time: self.accumulated_time,
+            viewport: self.viewport,
+            window_width: self.window_width,
+            window_height: self.window_height,
+            device_pixel_ratio: self.device_pixel_ratio,
+            first_frame: self.first_frame,
+        }
+    }
+
+    ///
+    /// Creates the input of a frame from a recorded frame and the given graphics context, for example to replay an [EventRecording](crate::renderer::control::EventRecording).
+    ///
+    pub fn from_recorded_frame(frame: RecordedFrame, context: &Context) -> Self {
+        Self {
+            events: frame.events,
+            elapsed_time: frame.elapsed_time,
+            accumulated_time: frame.accumulated_time,
+            viewport: frame.viewport,
+            window_width: frame.window_width,
+            window_height: frame.window_height,
+            device_pixel_ratio: frame.device_pixel_ratio,
+            first_frame: frame.first_frame,
+            context: context.clone(),
+        }
+    }
+
+    ///
+    /// Converts the given point in logical pixels, for example from the GUI, to physical pixels as used by [Event]s, [Viewport]s and [pick](crate::renderer::pick).
+    ///
+    pub fn logical_to_physical(&self, point: LogicalPoint) -> PhysicalPoint {
+        point.to_physical(self.device_pixel_ratio, self.viewport.height)
+    }
+
+    ///
+    /// Converts the given point in physical pixels, for example the position of an [Event], to logical pixels as used by the GUI.
+    ///
+    pub fn physical_to_logical(&self, point: PhysicalPoint) -> LogicalPoint {
+        LogicalPoint::from_physical(point, self.device_pixel_ratio, self.viewport.height)
+    }
+
+    ///
+    /// Finds the closest intersection between the given geometries and a ray from the given viewer through the given pixel coordinate in logical pixels,
+    /// for example the position of the mouse reported by the GUI. The pixel is converted to physical pixels using the device pixel ratio, see [pick](crate::renderer::pick).
+    ///
+    pub fn pick(
+        &self,
+        viewer: impl Viewer,
+        pixel: LogicalPoint,
+        geometries: impl IntoIterator<Item = impl Geometry>,
+        culling: Cull,
+    ) -> Result<Option<IntersectionResult>, RendererError> {
+        pick(
+            &self.context,
+            viewer,
+            self.logical_to_physical(pixel),
+            geometries,
+            culling,
+        )
+    }
+
+    ///
+    /// Returns the pixel coordinate in logical pixels of the given world position projected by the given viewer,
+    /// for example to place a GUI label at the position, see [world_to_screen](crate::renderer::world_to_screen).
+    ///
+    pub fn world_to_screen(&self, viewer: &impl Viewer, position: Vec3) -> LogicalPoint {
+        self.physical_to_logical(world_to_screen(viewer, position))
+    }
+
+    ///
+    /// Returns the viewport in physical pixels covering the given rectangle in logical pixels,
+    /// where the position is the top left corner of the rectangle with the same coordinate system as [LogicalPoint].
+    /// This is for example useful for finding the viewport of the area next to a GUI panel.
+    /// The returned viewport is clamped to the window.
+    ///
+    pub fn viewport_from_logical(&self, x: f32, y: f32, width: f32, height: f32) -> Viewport {
+        let to_physical = |value: f32, max: u32| {
+            ((value * self.device_pixel_ratio).round() as i32).clamp(0, max as i32)
+        };
+        let left = to_physical(x, self.viewport.width);
+        let right = to_physical(x + width, self.viewport.width);
+        let top = to_physical(y, self.viewport.height);
+        let bottom = to_physical(y + height, self.viewport.height);
+        Viewport {
+            x: left,
+            y: self.viewport.height as i32 - bottom,
+            width: (right - left).max(0) as u32,
+            height: (bottom - top).max(0) as u32,
+        }
+    }
 }
 
 ///
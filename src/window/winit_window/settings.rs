@@ -1,3 +1,5 @@
+use crate::core::ColorSpace;
+
 /// Selects the level of hardware graphics acceleration.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum HardwareAcceleration {
@@ -13,6 +15,8 @@ pub enum HardwareAcceleration {
 }
 
 /// Settings controlling the behavior of the surface on where to draw, to present it on the screen.
+/// If the requested settings are not supported, the closest supported settings are used instead,
+/// use [Window::surface_settings](super::Window::surface_settings) to get the settings that were actually obtained.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[allow(dead_code)]
 pub struct SurfaceSettings {
@@ -22,11 +26,13 @@ pub struct SurfaceSettings {
     pub vsync: bool,
     /// Sets the number of bits in the depth buffer.
     /// A value of 0 means no depth buffer.
+    /// Must be at most 32.
     /// The default value is 24.
     /// On web, this can only be off (0) or on (>0).
     pub depth_buffer: u8,
     /// Sets the number of bits in the stencil buffer.
     /// A value of 0 means no stencil buffer.
+    /// Must be at most 8.
     /// The default value is 0.
     /// On web, this can only be off (0) or on (>0).
     pub stencil_buffer: u8,
@@ -40,6 +46,13 @@ pub struct SurfaceSettings {
     /// Specify whether or not hardware acceleration is preferred, required, or
     /// off. The default is [HardwareAcceleration::Preferred].
     pub hardware_acceleration: HardwareAcceleration,
+    /// The color space of the surface, which should match the [ColorMapping](crate::renderer::ColorMapping) of the viewer used in the final render pass, see [ColorMapping::for_color_space](crate::renderer::ColorMapping::for_color_space).
+    /// The default is [ColorSpace::Srgb].
+    /// On web, the drawing buffer of the canvas is set to Display P3 if [ColorSpace::DisplayP3] is requested and the browser supports it, otherwise it is sRGB.
+    /// On native, the surface is not changed, since the operating system decides how the colors are presented,
+    /// for example macOS shows the colors in the color space of the display, so request [ColorSpace::DisplayP3] when the display is Display P3.
+    /// [ColorSpace::Rec2020] is not supported.
+    pub color_space: ColorSpace,
 }
 
 impl Default for SurfaceSettings {
@@ -50,6 +63,7 @@ impl Default for SurfaceSettings {
             stencil_buffer: 0,
             multisamples: 4,
             hardware_acceleration: HardwareAcceleration::Preferred,
+            color_space: ColorSpace::Srgb,
         }
     }
 }
@@ -25,6 +25,7 @@ mod inner {
     /// A context used for rendering
     pub struct WindowedContext {
         pub(super) context: Context,
+        surface_settings: SurfaceSettings,
     }
 
     impl WindowedContext {
@@ -66,13 +67,53 @@ mod inner {
                 .get_extension("OES_texture_half_float_linear")
                 .map_err(|e| WindowError::OESTextureFloatNotSupported(format!(": {:?}", e)))?;
 
+            // The color space of the drawing buffer is only changed if the browser supports it, otherwise setting the property has no effect
+            let color_space = if settings.color_space == crate::ColorSpace::Srgb {
+                crate::ColorSpace::Srgb
+            } else {
+                let key = wasm_bindgen::JsValue::from_str("drawingBufferColorSpace");
+                let _ = js_sys::Reflect::set(
+                    &webgl_context,
+                    &key,
+                    &wasm_bindgen::JsValue::from_str("display-p3"),
+                );
+                match js_sys::Reflect::get(&webgl_context, &key)
+                    .ok()
+                    .and_then(|v| v.as_string())
+                {
+                    Some(v) if v == "display-p3" => crate::ColorSpace::DisplayP3,
+                    _ => crate::ColorSpace::Srgb,
+                }
+            };
+
+            let context = Context::from_gl_context(Arc::new(
+                crate::context::Context::from_webgl2_context(webgl_context),
+            ))?;
+            // The browser decides the actual number of bits and samples, so query the default framebuffer
+            let surface_settings = unsafe {
+                use crate::context::HasContext;
+                SurfaceSettings {
+                    depth_buffer: context.get_parameter_i32(crate::context::DEPTH_BITS) as u8,
+                    stencil_buffer: context.get_parameter_i32(crate::context::STENCIL_BITS) as u8,
+                    multisamples: context.get_parameter_i32(crate::context::SAMPLES) as u8,
+                    color_space,
+                    ..settings
+                }
+            };
             Ok(Self {
-                context: Context::from_gl_context(Arc::new(
-                    crate::context::Context::from_webgl2_context(webgl_context),
-                ))?,
+                context,
+                surface_settings,
             })
         }
 
+        ///
+        /// Returns the surface settings that were actually obtained when creating this context,
+        /// which might differ from the requested settings if those were not supported.
+        ///
+        pub fn surface_settings(&self) -> SurfaceSettings {
+            self.surface_settings
+        }
+
         /// Resizes the context
         pub fn resize(&self, _physical_size: winit::dpi::PhysicalSize<u32>) {}
 
@@ -101,6 +142,7 @@ mod inner {
         pub(super) context: Context,
         surface: Surface<WindowSurface>,
         glutin_context: glutin::context::PossiblyCurrentContext,
+        surface_settings: SurfaceSettings,
     }
 
     impl WindowedContext {
@@ -113,6 +155,12 @@ mod inner {
             if settings.multisamples > 0 && !settings.multisamples.is_power_of_two() {
                 Err(WindowError::InvalidNumberOfMSAASamples)?;
             }
+            if settings.depth_buffer > 32 {
+                Err(WindowError::InvalidNumberOfDepthBits)?;
+            }
+            if settings.stencil_buffer > 8 {
+                Err(WindowError::InvalidNumberOfStencilBits)?;
+            }
             use glutin::prelude::*;
             use raw_window_handle::*;
             let raw_display_handle = window.raw_display_handle();
@@ -151,29 +199,43 @@ mod inner {
                 crate::HardwareAcceleration::Preferred => None,
                 crate::HardwareAcceleration::Off => Some(false),
             };
+            // Request the minimum and then pick the supported configuration closest to the requested settings,
+            // prioritizing enough depth bits, then enough stencil bits and then the number of samples closest to the requested.
             let config_template = glutin::config::ConfigTemplateBuilder::new()
                 .prefer_hardware_accelerated(hardware_acceleration)
-                .with_depth_size(settings.depth_buffer);
-            // we don't know if multi sampling option is set. so, check if its more than 0.
-            let config_template = if settings.multisamples > 0 {
-                config_template.with_multisampling(settings.multisamples)
-            } else {
-                config_template
-            };
-            let config_template = config_template
-                .with_stencil_size(settings.stencil_buffer)
+                .with_depth_size(0)
+                .with_stencil_size(0)
                 .compatible_with_native_window(raw_window_handle)
                 .build();
-            // finds all valid configurations supported by this display that match the
-            // config_template this is where we will try to get a "fallback" config if
-            // we are okay with ignoring some native options required by user like multi
-            // sampling, srgb, transparency etc..
             let config = unsafe {
                 gl_display
                     .find_configs(config_template)?
-                    .next()
+                    .min_by_key(|config| {
+                        (
+                            settings.depth_buffer.saturating_sub(config.depth_size()),
+                            settings
+                                .stencil_buffer
+                                .saturating_sub(config.stencil_size()),
+                            settings.multisamples.abs_diff(config.num_samples()),
+                            config.depth_size().saturating_sub(settings.depth_buffer),
+                            config
+                                .stencil_size()
+                                .saturating_sub(settings.stencil_buffer),
+                        )
+                    })
                     .ok_or(WindowError::SurfaceCreationError)?
             };
+            let surface_settings = SurfaceSettings {
+                depth_buffer: config.depth_size(),
+                stencil_buffer: config.stencil_size(),
+                multisamples: config.num_samples(),
+                color_space: if settings.color_space == crate::ColorSpace::Rec2020 {
+                    crate::ColorSpace::Srgb
+                } else {
+                    settings.color_space
+                },
+                ..settings
+            };
 
             let context_attributes =
                 glutin::context::ContextAttributesBuilder::new().build(Some(raw_window_handle));
@@ -192,20 +254,33 @@ mod inner {
             let gl_context = gl_context.make_current(&gl_surface)?;
             gl_surface.set_swap_interval(&gl_context, swap_interval)?;
 
-            Ok(Self {
-                context: Context::from_gl_context(Arc::new(unsafe {
-                    crate::context::Context::from_loader_function(|s| {
-                        let s = std::ffi::CString::new(s)
-                            .expect("failed to construct C string from string for gl proc address");
+            let loader = |s: &str| {
+                let s = std::ffi::CString::new(s)
+                    .expect("failed to construct C string from string for gl proc address");
 
-                        gl_display.get_proc_address(&s)
-                    })
-                }))?,
+                gl_display.get_proc_address(&s)
+            };
+            let mut context = Context::from_gl_context(Arc::new(unsafe {
+                crate::context::Context::from_loader_function(loader)
+            }))?;
+            context.load_clip_control(loader);
+
+            Ok(Self {
+                context,
                 glutin_context: gl_context,
                 surface: gl_surface,
+                surface_settings,
             })
         }
 
+        ///
+        /// Returns the surface settings that were actually obtained when creating this context,
+        /// which might differ from the requested settings if those were not supported.
+        ///
+        pub fn surface_settings(&self) -> SurfaceSettings {
+            self.surface_settings
+        }
+
         /// Resizes the context
         pub fn resize(&self, physical_size: winit::dpi::PhysicalSize<u32>) {
             let width = std::num::NonZeroU32::new(physical_size.width.max(1)).unwrap();